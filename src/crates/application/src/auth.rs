@@ -4,10 +4,15 @@ use crate::command::shared::IdGenerator;
 use crate::error::AppError;
 use domain::user::{User, UserRepository};
 use domain::value::UserId;
+use log::warn;
 
 pub trait PasswordHasher {
     fn hash(&self, plain: &str) -> Result<String, AppError>;
     fn verify(&self, pwd: &str, hashed_pwd: &str) -> Result<(), AppError>;
+    /// 哈希不是当前配置的算法或参数生成的，应在下次拿到明文时重新计算
+    fn needs_rehash(&self, _hashed_pwd: &str) -> bool {
+        false
+    }
 }
 
 /// 密码加密器 trait（用于可逆加密，支持 Subsonic token 认证）
@@ -69,10 +74,25 @@ impl AuthService {
             .find_by_username(username)
             .await?
             .ok_or_else(|| AppError::AuthError("invalid username".to_string()))?;
+        user.is_active()?;
         self.hasher.verify(pwd, &user.password)?;
+        if self.hasher.needs_rehash(&user.password) {
+            // 升级失败不影响本次登录，下次登录再试
+            if let Err(e) = self.rehash_password(user.clone(), pwd).await {
+                warn!("Failed to rehash password of {}: {}", user.username, e);
+            }
+        }
         self.token_svc.issue(&UserClaims::from(&user))
     }
 
+    async fn rehash_password(&self, mut user: User, pwd: &str) -> Result<(), AppError> {
+        let hashed_pwd = self.hasher.hash(pwd)?;
+        let encrypted_password = user.encrypted_password.clone();
+        user.change_password(&hashed_pwd, &encrypted_password)?;
+        self.user_repo.save(&user).await?;
+        Ok(())
+    }
+
     /// Authenticate with token, returns refreshed token
     pub async fn authenticate(&self, token: &str) -> Result<String, AppError> {
         let claims = self.token_svc.verify(token)?;
@@ -81,6 +101,7 @@ impl AuthService {
             .find_by_username(&claims.user_name)
             .await?
             .ok_or_else(|| AppError::AuthError("invalid username".to_string()))?;
+        user.is_active()?;
         self.token_svc.issue(&UserClaims::from(&user))
     }
 
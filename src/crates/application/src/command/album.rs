@@ -1,4 +1,4 @@
-use crate::command::shared::IdGenerator;
+use crate::command::shared::{retry_on_conflict, IdGenerator};
 use crate::context::AppContext;
 use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope};
@@ -9,6 +9,15 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct CreateAlbumCmd {
     pub name: String,
+    /// 从专辑名中提取的版本标注
+    pub edition: Option<String>,
+    /// 歌曲的发行年份和日期，合并进专辑的年份范围
+    pub year: Option<i32>,
+    pub original_year: Option<i32>,
+    pub release_date: Option<String>,
+    pub original_date: Option<String>,
+    /// 歌曲的内容分级为 explicit 时，专辑也标记为 explicit
+    pub explicit: bool,
 }
 
 #[derive(Debug)]
@@ -22,12 +31,19 @@ pub trait AlbumNameNormalizer: Send + Sync {
     fn normalize(&self, album_name: &String) -> String;
 }
 
+/// 按版本区分专辑时使用的 sort_name，版本已规范化
+pub fn edition_sort_name(sort_name: &str, edition: &str) -> String {
+    format!("{} ({})", sort_name, edition)
+}
+
 #[derive(Clone)]
 pub struct AlbumService<B: EventBus> {
     id_generator: Arc<dyn IdGenerator>,
     album_repository: Arc<dyn AlbumRepository>,
     album_name_normalizer: Arc<dyn AlbumNameNormalizer>,
     event_bus: Arc<B>,
+    /// 同名专辑的不同版本是否作为不同的专辑
+    separate_editions: bool,
 }
 
 impl<B: EventBus> AlbumService<B> {
@@ -42,17 +58,62 @@ impl<B: EventBus> AlbumService<B> {
             album_repository,
             album_name_normalizer,
             event_bus,
+            separate_editions: false,
         }
     }
 
+    pub fn with_separate_editions(mut self, separate_editions: bool) -> Self {
+        self.separate_editions = separate_editions;
+        self
+    }
+
+    /// 已有同名专辑时合并发行信息，同一专辑的歌曲并发解析时冲突在最新版本上重新合并
     pub async fn create_album(
         &self,
         context: &AppContext,
         cmd: CreateAlbumCmd,
     ) -> Result<Album, AppError> {
-        let sort_name = self.album_name_normalizer.normalize(&cmd.name);
+        retry_on_conflict("Album", cmd.name.clone(), || {
+            self.try_create_album(context, &cmd)
+        })
+        .await
+    }
+
+    async fn try_create_album(
+        &self,
+        context: &AppContext,
+        cmd: &CreateAlbumCmd,
+    ) -> Result<Album, AppError> {
+        let edition = cmd
+            .edition
+            .as_ref()
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty());
+        let mut sort_name = self.album_name_normalizer.normalize(&cmd.name);
+        if let (Some(edition), true) = (&edition, self.separate_editions) {
+            let edition = self.album_name_normalizer.normalize(edition);
+            sort_name = edition_sort_name(&sort_name, &edition);
+        }
         let album = self.album_repository.find_by_sort_name(&sort_name).await?;
-        if let Some(album) = album {
+        if let Some(mut album) = album {
+            let mut changed = album.add_release(
+                cmd.year,
+                cmd.original_year,
+                cmd.release_date.as_deref(),
+                cmd.original_date.as_deref(),
+            );
+            // 合并版本时，专辑记录第一次遇到的版本
+            if album.edition.is_none() && edition.is_some() {
+                album.edition = edition;
+                changed = true;
+            }
+            if cmd.explicit && !album.explicit {
+                album.explicit = true;
+                changed = true;
+            }
+            if changed {
+                album = self.album_repository.save(album).await?;
+            }
             let event_kind = AlbumEventKind::Found(AlbumFound {
                 album_id: album.id.clone(),
                 name: album.name.clone(),
@@ -75,7 +136,15 @@ impl<B: EventBus> AlbumService<B> {
             return Ok(album);
         }
         let album_id = self.id_generator.next_id().await?;
-        let mut album = Album::new(album_id.into(), cmd.name, sort_name);
+        let mut album = Album::new(album_id.into(), cmd.name.clone(), sort_name);
+        album.edition = edition;
+        album.explicit = cmd.explicit;
+        album.add_release(
+            cmd.year,
+            cmd.original_year,
+            cmd.release_date.as_deref(),
+            cmd.original_date.as_deref(),
+        );
         let events = album.take_events();
         let album = self.album_repository.save(album).await?;
         for event in events {
@@ -91,7 +160,15 @@ impl<B: EventBus> AlbumService<B> {
         Ok(album)
     }
 
+    /// 绑定只增加流派和参与者，冲突时重新加载后重放
     pub async fn bind(&self, context: &AppContext, cmd: BindCmd) -> Result<(), AppError> {
+        retry_on_conflict("Album", cmd.album_id.to_string(), || {
+            self.try_bind(context, &cmd)
+        })
+        .await
+    }
+
+    async fn try_bind(&self, context: &AppContext, cmd: &BindCmd) -> Result<(), AppError> {
         let mut album = self
             .album_repository
             .by_id(cmd.album_id.clone())
@@ -101,16 +178,16 @@ impl<B: EventBus> AlbumService<B> {
             })?;
 
         // 绑定流派
-        for genre_id in cmd.genre_ids {
-            album.bind_to_genre(genre_id)?;
+        for genre_id in &cmd.genre_ids {
+            album.bind_to_genre(genre_id.clone())?;
         }
 
         // 绑定艺术家
-        for (artist_id, role, sub_role) in cmd.artists {
+        for (artist_id, role, sub_role) in &cmd.artists {
             let participant = domain::value::Participant {
-                artist_id,
-                role,
-                sub_role,
+                artist_id: artist_id.clone(),
+                role: role.clone(),
+                sub_role: sub_role.clone(),
                 work_id: cmd.album_id.as_i64(),
                 work_type: domain::value::ParticipantWorkType::Album,
             };
@@ -133,4 +210,48 @@ impl<B: EventBus> AlbumService<B> {
         }
         Ok(())
     }
+
+    /// 删除已经没有歌曲的专辑。先解除参与者和流派，流派和艺术家的统计随事件更新
+    pub async fn remove_orphaned(
+        &self,
+        context: &AppContext,
+        album_id: &AlbumId,
+    ) -> Result<(), AppError> {
+        retry_on_conflict("Album", album_id.to_string(), || {
+            self.try_remove_orphaned(context, album_id)
+        })
+        .await
+    }
+
+    async fn try_remove_orphaned(
+        &self,
+        context: &AppContext,
+        album_id: &AlbumId,
+    ) -> Result<(), AppError> {
+        // 同一专辑的多首歌同时改绑时可能已被删除
+        let Some(mut album) = self.album_repository.by_id(album_id.clone()).await? else {
+            return Ok(());
+        };
+        for participant in album.participants.clone() {
+            album.remove_participant(&participant)?;
+        }
+        for genre_id in album.genres.clone() {
+            album.unbind_from_genre(genre_id)?;
+        }
+
+        let events = album.take_events();
+        let album = self.album_repository.save(album).await?;
+        for event in events {
+            let envelope = EventEnvelope::new(
+                album.id.as_i64(),
+                album.version,
+                event,
+                context.correlation_id.clone(),
+                context.event_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+        self.album_repository.delete(album_id.clone()).await?;
+        Ok(())
+    }
 }
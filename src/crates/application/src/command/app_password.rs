@@ -0,0 +1,221 @@
+use crate::auth::PasswordEncryptor;
+use crate::command::shared::IdGenerator;
+use crate::error::AppError;
+use chrono::Utc;
+use domain::app_password::{AppPassword, AppPasswordError, AppPasswordRepository};
+use domain::value::UserId;
+use log::warn;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 新建的应用密码，明文只在创建时返回一次
+#[derive(Debug)]
+pub struct CreatedAppPassword {
+    pub app_password: AppPassword,
+    pub password: String,
+}
+
+pub struct AppPasswordService {
+    app_password_repository: Arc<dyn AppPasswordRepository>,
+    password_encryptor: Arc<dyn PasswordEncryptor>,
+    id_generator: Arc<dyn IdGenerator>,
+}
+
+impl AppPasswordService {
+    pub fn new(
+        app_password_repository: Arc<dyn AppPasswordRepository>,
+        password_encryptor: Arc<dyn PasswordEncryptor>,
+        id_generator: Arc<dyn IdGenerator>,
+    ) -> Self {
+        Self {
+            app_password_repository,
+            password_encryptor,
+            id_generator,
+        }
+    }
+
+    pub async fn list(&self, user_id: &UserId) -> Result<Vec<AppPassword>, AppError> {
+        Ok(self.app_password_repository.find_by_user(user_id).await?)
+    }
+
+    pub async fn create(
+        &self,
+        user_id: UserId,
+        label: &str,
+    ) -> Result<CreatedAppPassword, AppError> {
+        // 只用十六进制字符，避免客户端对 p 参数的编码问题
+        let password = Uuid::new_v4().simple().to_string();
+        let encrypted_password = self.password_encryptor.encrypt(&password)?;
+        let id = self.id_generator.next_id().await?;
+        let app_password = AppPassword::new(id, user_id, label, encrypted_password)?;
+        self.app_password_repository.save(&app_password).await?;
+        Ok(CreatedAppPassword {
+            app_password,
+            password,
+        })
+    }
+
+    pub async fn revoke(&self, user_id: &UserId, id: i64) -> Result<(), AppError> {
+        if self.app_password_repository.delete(user_id, id).await? {
+            Ok(())
+        } else {
+            Err(AppError::AggregateNotFound(
+                "AppPassword".to_string(),
+                id.to_string(),
+            ))
+        }
+    }
+
+    /// 返回明文满足 matches 的应用密码并记录使用时间。
+    /// matches 由调用方按认证方式决定（直接比较或比较 md5 token）
+    pub async fn authenticate(
+        &self,
+        user_id: &UserId,
+        matches: impl Fn(&str) -> bool,
+    ) -> Result<Option<AppPassword>, AppError> {
+        let app_passwords = self.app_password_repository.find_by_user(user_id).await?;
+        for mut app_password in app_passwords {
+            let password = match self
+                .password_encryptor
+                .decrypt(&app_password.encrypted_password)
+            {
+                Ok(password) => password,
+                Err(e) => {
+                    warn!("Failed to decrypt app password {}: {}", app_password.id, e);
+                    continue;
+                }
+            };
+            if !matches(&password) {
+                continue;
+            }
+            let now = Utc::now().naive_utc();
+            match self
+                .app_password_repository
+                .mark_used(app_password.id, now)
+                .await
+            {
+                Ok(()) => app_password.last_used_at = Some(now),
+                Err(e) => warn!(
+                    "Failed to record use of app password {}: {}",
+                    app_password.id, e
+                ),
+            }
+            return Ok(Some(app_password));
+        }
+        Ok(None)
+    }
+}
+
+impl From<AppPasswordError> for AppError {
+    fn from(e: AppPasswordError) -> Self {
+        match e {
+            AppPasswordError::EmptyLabel | AppPasswordError::LabelTooLong(_) => {
+                AppError::InvalidInput(e.to_string())
+            }
+            AppPasswordError::DbErr(msg) => {
+                AppError::RepositoryError("AppPassword".to_string(), msg)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::SequentialIdGenerator;
+    use chrono::NaiveDateTime;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryAppPasswordRepository {
+        rows: Mutex<Vec<AppPassword>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AppPasswordRepository for MemoryAppPasswordRepository {
+        async fn find_by_user(
+            &self,
+            user_id: &UserId,
+        ) -> Result<Vec<AppPassword>, AppPasswordError> {
+            let rows = self.rows.lock().unwrap();
+            Ok(rows
+                .iter()
+                .filter(|p| &p.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn save(&self, app_password: &AppPassword) -> Result<(), AppPasswordError> {
+            self.rows.lock().unwrap().push(app_password.clone());
+            Ok(())
+        }
+
+        async fn delete(&self, user_id: &UserId, id: i64) -> Result<bool, AppPasswordError> {
+            let mut rows = self.rows.lock().unwrap();
+            let before = rows.len();
+            rows.retain(|p| !(p.id == id && &p.user_id == user_id));
+            Ok(rows.len() < before)
+        }
+
+        async fn mark_used(&self, id: i64, at: NaiveDateTime) -> Result<(), AppPasswordError> {
+            let mut rows = self.rows.lock().unwrap();
+            if let Some(p) = rows.iter_mut().find(|p| p.id == id) {
+                p.last_used_at = Some(at);
+            }
+            Ok(())
+        }
+    }
+
+    /// 反转字符串，足以验证存储的不是明文
+    struct ReversingEncryptor;
+
+    impl PasswordEncryptor for ReversingEncryptor {
+        fn encrypt(&self, plain_password: &str) -> Result<String, AppError> {
+            Ok(plain_password.chars().rev().collect())
+        }
+
+        fn decrypt(&self, encrypted_password: &str) -> Result<String, AppError> {
+            Ok(encrypted_password.chars().rev().collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn authenticates_until_revoked() {
+        let repository = Arc::new(MemoryAppPasswordRepository::default());
+        let service = AppPasswordService::new(
+            repository.clone(),
+            Arc::new(ReversingEncryptor),
+            Arc::new(SequentialIdGenerator::new(1)),
+        );
+        let alice = UserId::from(1);
+        let bob = UserId::from(2);
+
+        let phone = service.create(alice.clone(), " phone ").await.unwrap();
+        let desktop = service.create(alice.clone(), "desktop").await.unwrap();
+        assert_eq!(phone.app_password.label, "phone");
+        assert_ne!(phone.app_password.encrypted_password, phone.password);
+        assert!(service.create(alice.clone(), "  ").await.is_err());
+
+        let found = service
+            .authenticate(&alice, |p| p == desktop.password)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, desktop.app_password.id);
+        assert!(found.last_used_at.is_some());
+        assert!(service
+            .authenticate(&bob, |p| p == desktop.password)
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(service.revoke(&bob, phone.app_password.id).await.is_err());
+        service.revoke(&alice, phone.app_password.id).await.unwrap();
+        assert!(service
+            .authenticate(&alice, |p| p == phone.password)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(service.list(&alice).await.unwrap().len(), 1);
+    }
+}
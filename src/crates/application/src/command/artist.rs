@@ -1,8 +1,8 @@
-use crate::command::shared::IdGenerator;
+use crate::command::shared::{retry_on_conflict, IdGenerator};
 use crate::context::AppContext;
 use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope};
-use domain::artist::{Artist, ArtistEvent, ArtistFound, ArtistRepository};
+use domain::artist::{Artist, ArtistAliasRepository, ArtistEvent, ArtistFound, ArtistRepository};
 use domain::value::{ArtistId, GenreId};
 use log::info;
 use std::sync::Arc;
@@ -21,6 +21,11 @@ pub struct BindCmd {
 
 pub trait ArtistNameNormalizer: Send + Sync {
     fn normalize(&self, artist_name: &String) -> String;
+    /// 去掉冠词后的小写名称，用于匹配索引分组
+    fn index_name(&self, artist_name: &str) -> String;
+    /// 索引首字母（大写 ASCII）：跳过开头的标点，带重音的拉丁字母去掉重音；
+    /// 其他文字在 `romanize` 为 true 时取罗马字首字母，否则返回 None
+    fn index_initial(&self, index_name: &str, romanize: bool) -> Option<char>;
 }
 
 #[derive(Clone)]
@@ -29,6 +34,8 @@ pub struct ArtistService<B: EventBus> {
     artist_repository: Arc<dyn ArtistRepository>,
     artist_name_normalizer: Arc<dyn ArtistNameNormalizer>,
     event_bus: Arc<B>,
+    /// 未配置时不解析别名
+    alias_repository: Option<Arc<dyn ArtistAliasRepository>>,
 }
 
 impl<B: EventBus> ArtistService<B> {
@@ -43,16 +50,39 @@ impl<B: EventBus> ArtistService<B> {
             artist_repository,
             artist_name_normalizer,
             event_bus,
+            alias_repository: None,
         }
     }
 
+    pub fn with_alias_repository(
+        mut self,
+        alias_repository: Arc<dyn ArtistAliasRepository>,
+    ) -> Self {
+        self.alias_repository = Some(alias_repository);
+        self
+    }
+
+    /// 名称命中别名时返回别名指向的艺术家，别名优先于同名艺术家
+    async fn find_by_alias(&self, sort_name: &str) -> Result<Option<Artist>, AppError> {
+        let Some(alias_repository) = &self.alias_repository else {
+            return Ok(None);
+        };
+        let Some(alias) = alias_repository.find_by_sort_name(sort_name).await? else {
+            return Ok(None);
+        };
+        Ok(self.artist_repository.by_id(alias.artist_id).await?)
+    }
+
     pub async fn create_artist(
         &self,
         context: &AppContext,
         cmd: CreateArtistCmd,
     ) -> Result<Artist, AppError> {
         let sort_name = self.artist_name_normalizer.normalize(&cmd.name);
-        let artist = self.artist_repository.find_by_sort_name(&sort_name).await?;
+        let artist = match self.find_by_alias(&sort_name).await? {
+            Some(artist) => Some(artist),
+            None => self.artist_repository.find_by_sort_name(&sort_name).await?,
+        };
         if let Some(artist) = artist {
             let event = ArtistEvent::Found(ArtistFound {
                 artist_id: artist.id.clone(),
@@ -88,7 +118,15 @@ impl<B: EventBus> ArtistService<B> {
         Ok(artist)
     }
 
+    /// 绑定只增加流派，冲突时重新加载后重放
     pub async fn bind(&self, context: &AppContext, cmd: BindCmd) -> Result<(), AppError> {
+        retry_on_conflict("Artist", cmd.artist_id.to_string(), || {
+            self.try_bind(context, &cmd)
+        })
+        .await
+    }
+
+    async fn try_bind(&self, context: &AppContext, cmd: &BindCmd) -> Result<(), AppError> {
         let mut artist = self
             .artist_repository
             .by_id(cmd.artist_id.clone())
@@ -96,8 +134,8 @@ impl<B: EventBus> ArtistService<B> {
             .ok_or_else(|| {
                 AppError::AggregateNotFound("Artist".to_string(), cmd.artist_id.to_string())
             })?;
-        for genre_id in cmd.genre_ids {
-            artist.bind_to_genre(genre_id)?;
+        for genre_id in &cmd.genre_ids {
+            artist.bind_to_genre(genre_id.clone())?;
         }
         let events = artist.take_events();
         let artist = self.artist_repository.save(artist).await?;
@@ -0,0 +1,118 @@
+use crate::command::artist::ArtistNameNormalizer;
+use crate::command::merge::{MergeArtistsCmd, MergeService};
+use crate::context::AppContext;
+use crate::error::AppError;
+use crate::event::event_bus::EventBus;
+use domain::artist::{Artist, ArtistAlias, ArtistAliasRepository, ArtistRepository};
+use domain::value::ArtistId;
+use log::info;
+use std::sync::Arc;
+
+/// 重新关联结果
+#[derive(Debug, Default, Clone)]
+pub struct RelinkReport {
+    /// 按别名合并到规范艺术家的艺术家名称
+    pub merged: Vec<String>,
+}
+
+/// 艺术家别名维护
+///
+/// 别名只影响之后的扫描；已按别名建出的艺术家需要通过 relink 合并到规范艺术家
+pub struct ArtistAliasService<B: EventBus> {
+    artist_repository: Arc<dyn ArtistRepository>,
+    alias_repository: Arc<dyn ArtistAliasRepository>,
+    artist_name_normalizer: Arc<dyn ArtistNameNormalizer>,
+    merge_service: MergeService<B>,
+}
+
+impl<B: EventBus> ArtistAliasService<B> {
+    pub fn new(
+        artist_repository: Arc<dyn ArtistRepository>,
+        alias_repository: Arc<dyn ArtistAliasRepository>,
+        artist_name_normalizer: Arc<dyn ArtistNameNormalizer>,
+        merge_service: MergeService<B>,
+    ) -> Self {
+        Self {
+            artist_repository,
+            alias_repository,
+            artist_name_normalizer,
+            merge_service,
+        }
+    }
+
+    pub async fn list_aliases(&self, artist_id: &ArtistId) -> Result<Vec<ArtistAlias>, AppError> {
+        self.load_artist(artist_id).await?;
+        Ok(self.alias_repository.find_by_artist(artist_id).await?)
+    }
+
+    pub async fn add_alias(
+        &self,
+        artist_id: &ArtistId,
+        name: &str,
+    ) -> Result<ArtistAlias, AppError> {
+        let artist = self.load_artist(artist_id).await?;
+        let sort_name = self
+            .artist_name_normalizer
+            .normalize(&name.trim().to_string());
+        if sort_name == artist.sort_name {
+            return Err(AppError::InvalidInput(
+                "Alias matches the artist name".to_string(),
+            ));
+        }
+        let alias = ArtistAlias::new(artist.id, name, sort_name)?;
+        self.alias_repository.save(alias.clone()).await?;
+        Ok(alias)
+    }
+
+    pub async fn remove_alias(&self, artist_id: &ArtistId, name: &str) -> Result<(), AppError> {
+        let sort_name = self
+            .artist_name_normalizer
+            .normalize(&name.trim().to_string());
+        match self.alias_repository.find_by_sort_name(&sort_name).await? {
+            Some(alias) if &alias.artist_id == artist_id => {
+                Ok(self.alias_repository.delete(&sort_name).await?)
+            }
+            _ => Err(AppError::AggregateNotFound(
+                "ArtistAlias".to_string(),
+                name.to_string(),
+            )),
+        }
+    }
+
+    /// 将名称命中该艺术家别名的其他艺术家合并进来
+    pub async fn relink(
+        &self,
+        context: &AppContext,
+        artist_id: &ArtistId,
+    ) -> Result<RelinkReport, AppError> {
+        let artist = self.load_artist(artist_id).await?;
+        let mut report = RelinkReport::default();
+        for alias in self.alias_repository.find_by_artist(artist_id).await? {
+            let Some(source) = self
+                .artist_repository
+                .find_by_sort_name(&alias.sort_name)
+                .await?
+            else {
+                continue;
+            };
+            if source.id == artist.id {
+                continue;
+            }
+            let cmd = MergeArtistsCmd {
+                source_id: source.id.clone(),
+                target_id: artist.id.clone(),
+            };
+            self.merge_service.merge_artists(context, cmd).await?;
+            info!("Artist '{}' relinked to '{}'", source.name, artist.name);
+            report.merged.push(source.name);
+        }
+        Ok(report)
+    }
+
+    async fn load_artist(&self, artist_id: &ArtistId) -> Result<Artist, AppError> {
+        self.artist_repository
+            .by_id(artist_id.clone())
+            .await?
+            .ok_or_else(|| AppError::AggregateNotFound("Artist".to_string(), artist_id.to_string()))
+    }
+}
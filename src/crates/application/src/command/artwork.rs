@@ -0,0 +1,197 @@
+use crate::error::AppError;
+use async_trait::async_trait;
+use domain::cover_art::CoverFormat;
+use domain::value::FileMeta;
+use image::{DynamicImage, ImageFormat};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+
+/// 扫描时生成的嵌入封面缩略图的最大边长
+pub const THUMBNAIL_SIZE: u32 = 300;
+
+/// BlurHash 的横纵分量数，4x3 对方形封面足够
+const BLURHASH_COMPONENTS: (u32, u32) = (4, 3);
+
+/// 扫描时从音频文件提取的嵌入封面
+#[derive(Debug, Clone)]
+pub struct EmbeddedArtwork {
+    /// 原图尺寸和格式
+    pub width: i32,
+    pub height: i32,
+    pub format: Option<CoverFormat>,
+    /// 缩略图的本地路径
+    pub thumbnail_path: String,
+    /// 图片加载前的占位
+    pub blurhash: Option<String>,
+    pub dominant_color: Option<String>,
+}
+
+/// 缩略图存储（封面缓存目录）
+#[async_trait]
+pub trait ThumbnailStore: Send + Sync {
+    /// 保存 WebP 缩略图，返回本地路径；相同 key 会覆盖
+    async fn save(&self, key: &str, data: Vec<u8>) -> Result<String, AppError>;
+}
+
+/// 解码嵌入封面并写入缩略图，文件路径和修改时间决定缩略图的 key，
+/// 文件变化后生成新的缩略图
+pub async fn extract_embedded_artwork(
+    store: &dyn ThumbnailStore,
+    file: &FileMeta,
+    picture: Vec<u8>,
+) -> Result<EmbeddedArtwork, AppError> {
+    let rendered = tokio::task::spawn_blocking(move || render_thumbnail(&picture))
+        .await
+        .map_err(|e| AppError::UnknownError(e.to_string()))?
+        .map_err(AppError::UnknownError)?;
+
+    let mut hasher = DefaultHasher::new();
+    file.path.protocol.hash(&mut hasher);
+    file.path.path.hash(&mut hasher);
+    file.mtime.hash(&mut hasher);
+    let key = format!("{:016x}", hasher.finish());
+
+    let thumbnail_path = store.save(&key, rendered.data).await?;
+    Ok(EmbeddedArtwork {
+        width: rendered.width as i32,
+        height: rendered.height as i32,
+        format: rendered.format,
+        thumbnail_path,
+        blurhash: rendered.blurhash,
+        dominant_color: rendered.dominant_color,
+    })
+}
+
+struct RenderedThumbnail {
+    width: u32,
+    height: u32,
+    format: Option<CoverFormat>,
+    blurhash: Option<String>,
+    dominant_color: Option<String>,
+    data: Vec<u8>,
+}
+
+fn render_thumbnail(picture: &[u8]) -> Result<RenderedThumbnail, String> {
+    let format = image::guess_format(picture).ok().and_then(cover_format);
+    let img = image::load_from_memory(picture)
+        .map_err(|e| format!("Failed to load embedded cover: {}", e))?;
+    let (width, height) = (img.width(), img.height());
+    // thumbnail 保持宽高比，小图不放大
+    let thumbnail = if width > THUMBNAIL_SIZE || height > THUMBNAIL_SIZE {
+        img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    } else {
+        img
+    };
+    let mut buffer = Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut buffer, ImageFormat::WebP)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    // 占位只需要轮廓和色调，在小图上计算
+    let sample = thumbnail.thumbnail(32, 32);
+    Ok(RenderedThumbnail {
+        width,
+        height,
+        format,
+        blurhash: blurhash(&sample),
+        dominant_color: dominant_color(&sample),
+        data: buffer.into_inner(),
+    })
+}
+
+fn blurhash(img: &DynamicImage) -> Option<String> {
+    let rgba = img.to_rgba8();
+    let (x, y) = BLURHASH_COMPONENTS;
+    blurhash::encode(x, y, rgba.width(), rgba.height(), rgba.as_raw()).ok()
+}
+
+/// 按每通道 4 位量化取出现最多的颜色，返回该颜色桶的平均值
+fn dominant_color(img: &DynamicImage) -> Option<String> {
+    let mut buckets: HashMap<(u8, u8, u8), (u32, [u32; 3])> = HashMap::new();
+    for pixel in img.to_rgba8().pixels() {
+        let [r, g, b, a] = pixel.0;
+        // 透明像素不参与统计
+        if a < 128 {
+            continue;
+        }
+        let (count, sum) = buckets.entry((r >> 4, g >> 4, b >> 4)).or_default();
+        *count += 1;
+        sum[0] += r as u32;
+        sum[1] += g as u32;
+        sum[2] += b as u32;
+    }
+    let (count, sum) = buckets.into_values().max_by_key(|(count, _)| *count)?;
+    Some(format!(
+        "#{:02x}{:02x}{:02x}",
+        sum[0] / count,
+        sum[1] / count,
+        sum[2] / count
+    ))
+}
+
+fn cover_format(format: ImageFormat) -> Option<CoverFormat> {
+    match format {
+        ImageFormat::Jpeg => Some(CoverFormat::Jpeg),
+        ImageFormat::Png => Some(CoverFormat::Png),
+        ImageFormat::WebP => Some(CoverFormat::WebP),
+        ImageFormat::Gif => Some(CoverFormat::Gif),
+        ImageFormat::Bmp => Some(CoverFormat::Bmp),
+        ImageFormat::Tiff => Some(CoverFormat::Tiff),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        encode_png(DynamicImage::ImageRgb8(RgbImage::new(width, height)))
+    }
+
+    fn encode_png(img: DynamicImage) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        img.write_to(&mut buffer, ImageFormat::Png).unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn thumbnail_keeps_original_dimensions_and_aspect_ratio() {
+        let rendered = render_thumbnail(&png(600, 400)).unwrap();
+        assert_eq!((rendered.width, rendered.height), (600, 400));
+        assert_eq!(rendered.format, Some(CoverFormat::Png));
+
+        let thumbnail = image::load_from_memory(&rendered.data).unwrap();
+        assert_eq!(
+            image::guess_format(&rendered.data).unwrap(),
+            ImageFormat::WebP
+        );
+        assert_eq!((thumbnail.width(), thumbnail.height()), (300, 200));
+    }
+
+    #[test]
+    fn small_covers_are_not_upscaled() {
+        let rendered = render_thumbnail(&png(120, 120)).unwrap();
+        let thumbnail = image::load_from_memory(&rendered.data).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (120, 120));
+    }
+
+    #[test]
+    fn placeholder_uses_most_common_color() {
+        // 左侧三分之二为红色，右侧为蓝色
+        let img = RgbImage::from_fn(90, 90, |x, _| {
+            if x < 60 {
+                Rgb([200, 16, 32])
+            } else {
+                Rgb([0, 0, 255])
+            }
+        });
+        let rendered = render_thumbnail(&encode_png(DynamicImage::ImageRgb8(img))).unwrap();
+        assert_eq!(rendered.dominant_color.as_deref(), Some("#c81020"));
+        let blurhash = rendered.blurhash.unwrap();
+        // 4x3 分量的 BlurHash 长度固定为 4 + 2 * 12 = 28
+        assert_eq!(blurhash.len(), 28);
+    }
+}
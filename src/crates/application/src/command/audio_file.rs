@@ -1,8 +1,8 @@
-use crate::command::shared::IdGenerator;
+use crate::command::shared::{retry_on_conflict, IdGenerator};
 use crate::context::AppContext;
 use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope};
-use domain::audio_file::{AudioFile, AudioFileRepository};
+use domain::audio_file::{AudioFile, AudioFileRepository, ReparsedFile};
 use domain::value::{
     AlbumId, ArtistId, AudioFileId, AudioMetadata, FileMeta, GenreId, LibraryId, Participant,
     ParticipantRole, ParticipantSubRole, ParticipantWorkType,
@@ -58,11 +58,19 @@ impl<B: EventBus> AudioFileService<B> {
         }
     }
 
+    /// 已入库的文件重新解析时原地更新，ID 和绑定保持不变
     pub async fn create_audio_file(
         &self,
         context: &AppContext,
         cmd: CreateAudioFileCmd,
     ) -> Result<AudioFile, AppError> {
+        if let Some(audio_file) = self
+            .audio_file_repository
+            .find_by_path(&cmd.filemeta.path)
+            .await?
+        {
+            return self.refresh_audio_file(context, audio_file, cmd).await;
+        }
         let mut audio_file = AudioFile::new(
             self.id_generator.next_id().await?.into(),
             cmd.library_id.clone(),
@@ -98,7 +106,65 @@ impl<B: EventBus> AudioFileService<B> {
         Ok(audio_file)
     }
 
-    pub async fn bind(&self, context: &AppContext, cmd: BindCmd) -> Result<(), AppError> {
+    async fn refresh_audio_file(
+        &self,
+        context: &AppContext,
+        mut audio_file: AudioFile,
+        cmd: CreateAudioFileCmd,
+    ) -> Result<AudioFile, AppError> {
+        audio_file.refresh(ReparsedFile {
+            size: cmd.filemeta.size,
+            hash: cmd.filemeta.hash.clone(),
+            duration: cmd.audio_metadata.duration,
+            bit_rate: cmd.audio_metadata.bit_rate,
+            sample_rate: cmd.audio_metadata.sample_rate,
+            channels: cmd.audio_metadata.channels,
+            has_cover_art: cmd.audio_metadata.picture.is_some(),
+            meta: cmd.audio_metadata.into(),
+        })?;
+
+        let events = audio_file.take_events();
+        let audio_file = self.audio_file_repository.save(audio_file).await?;
+        for event in events {
+            let envelope = EventEnvelope::new(
+                audio_file.id.as_i64(),
+                audio_file.version,
+                event,
+                context.correlation_id.clone(),
+                context.causation_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+        Ok(audio_file)
+    }
+
+    /// 专辑下是否还有歌曲
+    pub async fn album_has_files(&self, album_id: &AlbumId) -> Result<bool, AppError> {
+        Ok(!self
+            .audio_file_repository
+            .find_by_album(album_id)
+            .await?
+            .is_empty())
+    }
+
+    /// 绑定专辑、流派和艺术家。文件已属于另一张专辑时改绑，返回原来的专辑。
+    /// 冲突时重新加载后重放
+    pub async fn bind(
+        &self,
+        context: &AppContext,
+        cmd: BindCmd,
+    ) -> Result<Option<AlbumId>, AppError> {
+        retry_on_conflict("AudioFile", cmd.audio_file_id.to_string(), || {
+            self.try_bind(context, &cmd)
+        })
+        .await
+    }
+
+    async fn try_bind(
+        &self,
+        context: &AppContext,
+        cmd: &BindCmd,
+    ) -> Result<Option<AlbumId>, AppError> {
         let mut audio_file = self
             .audio_file_repository
             .find_by_id(&cmd.audio_file_id)
@@ -110,25 +176,24 @@ impl<B: EventBus> AudioFileService<B> {
         })?;
         */
 
-        // 绑定流派
-        for genre_id in cmd.genre_ids {
-            audio_file.bind_to_genre(genre_id)?;
-        }
-
-        // 绑定艺术家
-        for (artist_id, role, sub_role) in cmd.artists {
-            let participant = Participant::new(
-                artist_id,
-                role,
-                sub_role,
-                cmd.audio_file_id.as_i64(),
-                ParticipantWorkType::Artist,
-            );
-            audio_file.add_participant(participant)?;
-        }
+        // 绑定流派和艺术家，重新解析后标签中已经没有的解除绑定
+        let participants: Vec<Participant> = cmd
+            .artists
+            .iter()
+            .map(|(artist_id, role, sub_role)| {
+                Participant::new(
+                    artist_id.clone(),
+                    role.clone(),
+                    sub_role.clone(),
+                    cmd.audio_file_id.as_i64(),
+                    ParticipantWorkType::Artist,
+                )
+            })
+            .collect();
+        audio_file.sync_bindings(&cmd.genre_ids, &participants)?;
 
         // 绑定专辑
-        audio_file.bind_to_album(cmd.album_id)?;
+        let detached = audio_file.move_to_album(cmd.album_id.clone())?;
 
         // 保存并发布事件
         let events = audio_file.take_events();
@@ -144,6 +209,6 @@ impl<B: EventBus> AudioFileService<B> {
             );
             self.event_bus.publish(envelope).await?;
         }
-        Ok(())
+        Ok(detached)
     }
 }
@@ -0,0 +1,26 @@
+use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+/// 某个用户某一天的 stream 流量
+#[derive(Debug, Clone)]
+pub struct BandwidthUsage {
+    pub user_id: i64,
+    pub user_name: String,
+    pub day: NaiveDate,
+    pub bytes: i64,
+    pub streams: i32,
+}
+
+#[async_trait]
+pub trait BandwidthUsageStore: Send + Sync {
+    /// 累加一次 stream 发送的字节数
+    async fn record(&self, user_id: i64, day: NaiveDate, bytes: i64) -> Result<(), AppError>;
+    /// [from, to] 日期范围（含两端）内的流量，user_id 为空时返回所有用户
+    async fn usage(
+        &self,
+        user_id: Option<i64>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<BandwidthUsage>, AppError>;
+}
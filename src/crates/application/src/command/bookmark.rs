@@ -0,0 +1,142 @@
+use crate::error::AppError;
+use domain::audio_file::AudioFileRepository;
+use domain::bookmark::{Bookmark, BookmarkError, BookmarkRepository};
+use domain::library::{LibraryKind, LibraryRepository};
+use domain::value::{AudioFileId, LibraryId, UserId};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 创建书签，已有书签时移动到新位置
+#[derive(Debug)]
+pub struct SaveBookmarkCmd {
+    pub user_id: UserId,
+    pub audio_file_id: AudioFileId,
+    /// 毫秒
+    pub position: i64,
+    /// None 时保留原备注
+    pub comment: Option<String>,
+    /// 客户端名称
+    pub changed_by: String,
+}
+
+/// 有声书库的 ID
+pub(crate) async fn audiobook_library_ids(
+    library_repository: &dyn LibraryRepository,
+) -> Result<HashSet<LibraryId>, AppError> {
+    Ok(library_repository
+        .find_all()
+        .await?
+        .into_iter()
+        .filter(|library| library.kind == LibraryKind::Audiobook)
+        .map(|library| library.id)
+        .collect())
+}
+
+pub struct BookmarkAppService {
+    bookmark_repository: Arc<dyn BookmarkRepository>,
+    audio_file_repository: Arc<dyn AudioFileRepository>,
+    library_repository: Arc<dyn LibraryRepository>,
+}
+
+impl BookmarkAppService {
+    pub fn new(
+        bookmark_repository: Arc<dyn BookmarkRepository>,
+        audio_file_repository: Arc<dyn AudioFileRepository>,
+        library_repository: Arc<dyn LibraryRepository>,
+    ) -> Self {
+        Self {
+            bookmark_repository,
+            audio_file_repository,
+            library_repository,
+        }
+    }
+
+    pub async fn save_bookmark(&self, cmd: SaveBookmarkCmd) -> Result<Bookmark, AppError> {
+        if self
+            .audio_file_repository
+            .find_by_id(&cmd.audio_file_id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::AggregateNotFound(
+                "AudioFile".to_string(),
+                cmd.audio_file_id.to_string(),
+            ));
+        }
+        let bookmark = match self
+            .bookmark_repository
+            .find(&cmd.user_id, &cmd.audio_file_id)
+            .await?
+        {
+            Some(mut bookmark) => {
+                bookmark.update(cmd.position, cmd.comment, cmd.changed_by)?;
+                bookmark
+            }
+            None => Bookmark::new(
+                cmd.user_id,
+                cmd.audio_file_id,
+                cmd.position,
+                cmd.comment.unwrap_or_default(),
+                cmd.changed_by,
+            )?,
+        };
+        self.bookmark_repository.save(&bookmark).await?;
+        Ok(bookmark)
+    }
+
+    pub async fn delete_bookmark(
+        &self,
+        user_id: &UserId,
+        audio_file_id: &AudioFileId,
+    ) -> Result<(), AppError> {
+        if self
+            .bookmark_repository
+            .delete(user_id, audio_file_id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(AppError::AggregateNotFound(
+                "Bookmark".to_string(),
+                audio_file_id.to_string(),
+            ))
+        }
+    }
+
+    /// 保存播放队列时记下有声书的播放位置，其他库的歌曲不处理
+    ///
+    /// 返回是否更新了书签
+    pub async fn record_progress(
+        &self,
+        user_id: &UserId,
+        audio_file_id: &AudioFileId,
+        position: i64,
+        changed_by: &str,
+    ) -> Result<bool, AppError> {
+        let Some(audio_file) = self.audio_file_repository.find_by_id(audio_file_id).await? else {
+            return Ok(false);
+        };
+        let audiobooks = audiobook_library_ids(self.library_repository.as_ref()).await?;
+        if !audiobooks.contains(&audio_file.library_id) {
+            return Ok(false);
+        }
+        self.save_bookmark(SaveBookmarkCmd {
+            user_id: user_id.clone(),
+            audio_file_id: audio_file_id.clone(),
+            position: position.max(0),
+            comment: None,
+            changed_by: changed_by.to_string(),
+        })
+        .await?;
+        Ok(true)
+    }
+}
+
+impl From<BookmarkError> for AppError {
+    fn from(e: BookmarkError) -> Self {
+        match e {
+            BookmarkError::InvalidPosition(_) => AppError::InvalidInput(e.to_string()),
+            BookmarkError::DbErr(msg) => AppError::RepositoryError("Bookmark".to_string(), msg),
+        }
+    }
+}
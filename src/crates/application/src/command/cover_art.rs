@@ -1,3 +1,4 @@
+use crate::command::artwork::EmbeddedArtwork;
 use crate::command::shared::IdGenerator;
 use crate::context::AppContext;
 use crate::error::AppError;
@@ -12,6 +13,7 @@ use std::sync::Arc;
 pub struct CreateCoverArtCmd {
     pub file_meta: FileMeta,
     pub source: CoverSourceType,
+    pub artwork: Option<EmbeddedArtwork>,
 }
 
 pub struct BindCmd {
@@ -59,15 +61,19 @@ impl<B: EventBus> CoverArtService<B> {
     ) -> Result<(), AppError> {
         let new_id = self.id_generator.next_id().await?;
 
+        let artwork = cmd.artwork;
         let dto = CoverArtDTO {
             audio_file_id: None,
             album_id: None,
             path: cmd.file_meta.path.clone(),
-            width: None,
-            height: None,
-            format: None,
+            width: artwork.as_ref().map(|a| a.width),
+            height: artwork.as_ref().map(|a| a.height),
+            format: artwork.as_ref().and_then(|a| a.format.clone()),
             file_size: cmd.file_meta.size,
             source: cmd.source,
+            blurhash: artwork.as_ref().and_then(|a| a.blurhash.clone()),
+            dominant_color: artwork.as_ref().and_then(|a| a.dominant_color.clone()),
+            thumbnail_path: artwork.map(|a| a.thumbnail_path),
         };
         let mut co_art = CoverArt::from_dto(new_id.into(), dto)?;
         co_art = self.cover_art_repository.save(co_art).await?;
@@ -0,0 +1,222 @@
+use crate::command::media_parse::{StorageClient, StorageClientFactory};
+use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::library::{LibraryRepository, ScanStatus};
+use domain::value::{LibraryId, MediaPath};
+use futures::stream::{self, StreamExt};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 同时检查的文件数，SMB 等远程存储每次检查都是一次网络往返
+const CHECK_CONCURRENCY: usize = 32;
+
+/// 待检查的音频文件
+#[derive(Debug, Clone)]
+pub struct AudioFileLocation {
+    pub id: i64,
+    pub library_id: LibraryId,
+    pub path: MediaPath,
+    pub title: String,
+    pub missing_at: Option<NaiveDateTime>,
+}
+
+#[async_trait]
+pub trait AudioFileAvailabilityStore: Send + Sync {
+    /// `library_id` 为 None 时返回所有库的文件
+    async fn list(
+        &self,
+        library_id: Option<&LibraryId>,
+    ) -> Result<Vec<AudioFileLocation>, AppError>;
+    /// `missing_at` 为 None 表示文件已恢复
+    async fn set_missing_at(
+        &self,
+        ids: &[i64],
+        missing_at: Option<NaiveDateTime>,
+    ) -> Result<(), AppError>;
+    /// 库中位于 dirs 下的文件标记为存储不可用（已标记的保留原时间），其余文件取消标记；
+    /// dirs 为空时取消整个库的标记
+    async fn set_unavailable_dirs(
+        &self,
+        library_id: &LibraryId,
+        dirs: &[String],
+        unavailable_at: NaiveDateTime,
+    ) -> Result<(), AppError>;
+}
+
+#[derive(Debug)]
+pub struct CheckFilesCmd {
+    pub library_id: Option<LibraryId>,
+    /// 把缺失的文件标记为不可用（不再出现在列表中），并取消已恢复文件的标记
+    pub mark_unavailable: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MissingFile {
+    pub id: i64,
+    pub library_id: LibraryId,
+    pub path: MediaPath,
+    pub title: String,
+    /// 之前的检查已标记为不可用的时间
+    pub missing_since: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FileCheckReport {
+    pub checked: usize,
+    pub missing: Vec<MissingFile>,
+    /// 之前标记为不可用、现在又存在的文件数
+    pub restored: usize,
+    /// 存储无法访问而跳过的文件数，不算作缺失
+    pub skipped: usize,
+    pub marked: bool,
+}
+
+/// 检查 audio_file 中的路径在存储中是否仍然存在
+pub struct FileCheckService {
+    availability_store: Arc<dyn AudioFileAvailabilityStore>,
+    library_repository: Arc<dyn LibraryRepository>,
+    storage_client_factory: Arc<dyn StorageClientFactory>,
+}
+
+impl FileCheckService {
+    pub fn new(
+        availability_store: Arc<dyn AudioFileAvailabilityStore>,
+        library_repository: Arc<dyn LibraryRepository>,
+        storage_client_factory: Arc<dyn StorageClientFactory>,
+    ) -> Self {
+        Self {
+            availability_store,
+            library_repository,
+            storage_client_factory,
+        }
+    }
+
+    pub async fn check(&self, cmd: CheckFilesCmd) -> Result<FileCheckReport, AppError> {
+        let libraries = self.library_repository.find_all().await?;
+        if let Some(library_id) = &cmd.library_id {
+            if !libraries.iter().any(|library| &library.id == library_id) {
+                return Err(AppError::AggregateNotFound(
+                    "Library".to_string(),
+                    library_id.to_string(),
+                ));
+            }
+        }
+        // 每个库一个客户端，远程库的连接和凭据在库内共用
+        let mut clients: HashMap<LibraryId, Arc<dyn StorageClient>> = HashMap::new();
+        for library in &libraries {
+            match self.storage_client_factory.create(&library.path).await {
+                Ok(client) => {
+                    clients.insert(library.id.clone(), client);
+                }
+                Err(e) => warn!("Failed to open storage of library {}: {}", library.name, e),
+            }
+        }
+
+        let files = self
+            .availability_store
+            .list(cmd.library_id.as_ref())
+            .await?;
+        let results: Vec<(AudioFileLocation, Option<bool>)> = stream::iter(files)
+            .map(|file| {
+                let client = clients.get(&file.library_id).cloned();
+                async move {
+                    let exists = match client {
+                        Some(client) => match client.exists(&file.path).await {
+                            Ok(exists) => Some(exists),
+                            Err(e) => {
+                                warn!("Failed to check {}: {}", file.path.path, e);
+                                None
+                            }
+                        },
+                        None => None,
+                    };
+                    (file, exists)
+                }
+            })
+            .buffer_unordered(CHECK_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut report = FileCheckReport {
+            marked: cmd.mark_unavailable,
+            ..Default::default()
+        };
+        let mut newly_missing = Vec::new();
+        let mut restored = Vec::new();
+        for (file, exists) in results {
+            match exists {
+                None => report.skipped += 1,
+                Some(true) => {
+                    report.checked += 1;
+                    if file.missing_at.is_some() {
+                        restored.push(file.id);
+                    }
+                }
+                Some(false) => {
+                    report.checked += 1;
+                    if file.missing_at.is_none() {
+                        newly_missing.push(file.id);
+                    }
+                    report.missing.push(MissingFile {
+                        id: file.id,
+                        library_id: file.library_id,
+                        path: file.path,
+                        title: file.title,
+                        missing_since: file.missing_at,
+                    });
+                }
+            }
+        }
+        report.restored = restored.len();
+        report.missing.sort_by(|a, b| a.path.path.cmp(&b.path.path));
+
+        if cmd.mark_unavailable {
+            let now = chrono::Utc::now().naive_utc();
+            if !newly_missing.is_empty() {
+                self.availability_store
+                    .set_missing_at(&newly_missing, Some(now))
+                    .await?;
+            }
+            if !restored.is_empty() {
+                self.availability_store
+                    .set_missing_at(&restored, None)
+                    .await?;
+            }
+        }
+        Ok(report)
+    }
+
+    /// 存储无法访问的库的根目录能够重新列出时恢复可用，返回恢复的库，调用方应随后扫描这些库
+    pub async fn restore_libraries(&self) -> Result<Vec<LibraryId>, AppError> {
+        let mut restored = Vec::new();
+        for summary in self.library_repository.find_all().await? {
+            // 扫描结束时会自行更新可用状态
+            if summary.unavailable_since.is_none() || summary.scan_status == ScanStatus::Scanning {
+                continue;
+            }
+            let reachable = match self.storage_client_factory.create(&summary.path).await {
+                Ok(client) => client.list(&summary.path).await.is_ok(),
+                Err(_) => false,
+            };
+            if !reachable {
+                continue;
+            }
+            // find_all 不加载文件列表，保存前需要完整加载
+            let Some(mut library) = self.library_repository.find_by_id(&summary.id).await? else {
+                continue;
+            };
+            if !library.mark_available() {
+                continue;
+            }
+            self.library_repository.save(&library).await?;
+            self.availability_store
+                .set_unavailable_dirs(&library.id, &[], chrono::Utc::now().naive_utc())
+                .await?;
+            info!("Storage of library {} is reachable again", library.name);
+            restored.push(library.id);
+        }
+        Ok(restored)
+    }
+}
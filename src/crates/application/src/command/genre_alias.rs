@@ -0,0 +1,193 @@
+use crate::command::shared::IdGenerator;
+use crate::context::AppContext;
+use crate::error::AppError;
+use crate::event::event_bus::{EventBus, EventEnvelope};
+use domain::album::AlbumRepository;
+use domain::audio_file::{AudioFile, AudioFileRepository};
+use domain::genre::{Genre, GenreAlias, GenreAliasRepository, GenreName, GenreRepository};
+use domain::value::{AlbumId, GenreId};
+use log::info;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct SetGenreParentCmd {
+    pub genre_id: GenreId,
+    /// None 表示取消父流派
+    pub parent_id: Option<GenreId>,
+}
+
+/// 重新归一化结果
+#[derive(Debug, Default, Clone)]
+pub struct RenormalizeReport {
+    /// 被归并的别名流派数
+    pub genres: usize,
+    pub audio_files: usize,
+    pub albums: usize,
+}
+
+/// 流派别名与层级维护
+///
+/// 别名变更只影响之后的扫描，已入库的数据需要通过 renormalize 迁移到规范流派
+pub struct GenreAliasService<B: EventBus> {
+    id_generator: Arc<dyn IdGenerator>,
+    genre_repository: Arc<dyn GenreRepository>,
+    alias_repository: Arc<dyn GenreAliasRepository>,
+    audio_file_repository: Arc<dyn AudioFileRepository>,
+    album_repository: Arc<dyn AlbumRepository>,
+    event_bus: Arc<B>,
+}
+
+impl<B: EventBus> GenreAliasService<B> {
+    pub fn new(
+        id_generator: Arc<dyn IdGenerator>,
+        genre_repository: Arc<dyn GenreRepository>,
+        alias_repository: Arc<dyn GenreAliasRepository>,
+        audio_file_repository: Arc<dyn AudioFileRepository>,
+        album_repository: Arc<dyn AlbumRepository>,
+        event_bus: Arc<B>,
+    ) -> Self {
+        Self {
+            id_generator,
+            genre_repository,
+            alias_repository,
+            audio_file_repository,
+            album_repository,
+            event_bus,
+        }
+    }
+
+    pub async fn list_aliases(&self) -> Result<Vec<GenreAlias>, AppError> {
+        Ok(self.alias_repository.find_all().await?)
+    }
+
+    pub async fn add_alias(&self, alias: &str, genre_name: &str) -> Result<GenreAlias, AppError> {
+        let alias = GenreAlias::new(alias, genre_name)?;
+        self.alias_repository.save(alias.clone()).await?;
+        Ok(alias)
+    }
+
+    pub async fn remove_alias(&self, alias: &str) -> Result<(), AppError> {
+        Ok(self.alias_repository.delete(alias).await?)
+    }
+
+    pub async fn list_genres(&self) -> Result<Vec<Genre>, AppError> {
+        Ok(self.genre_repository.find_all().await?)
+    }
+
+    pub async fn set_parent(&self, cmd: SetGenreParentCmd) -> Result<Genre, AppError> {
+        let mut genre = self.find_genre(&cmd.genre_id).await?;
+        // 沿父链向上检查，避免形成环
+        let mut cursor = cmd.parent_id.clone();
+        while let Some(parent_id) = cursor {
+            if parent_id == genre.id {
+                return Err(AppError::InvalidInput(
+                    "Genre hierarchy cannot contain cycles".to_string(),
+                ));
+            }
+            cursor = self.find_genre(&parent_id).await?.parent_id;
+        }
+        genre.set_parent(cmd.parent_id)?;
+        Ok(self.genre_repository.save(genre).await?)
+    }
+
+    /// 将名称命中别名的流派上的歌曲和专辑迁移到规范流派
+    ///
+    /// 艺术家的流派没有解绑操作，在下次扫描时更新
+    pub async fn renormalize(&self, context: &AppContext) -> Result<RenormalizeReport, AppError> {
+        let aliases: HashMap<String, String> = self
+            .alias_repository
+            .find_all()
+            .await?
+            .into_iter()
+            .map(|a| (a.alias, a.genre_name))
+            .collect();
+        let mut report = RenormalizeReport::default();
+
+        for genre in self.genre_repository.find_all().await? {
+            let Some(canonical) = aliases.get(&genre.name().to_lowercase()) else {
+                continue;
+            };
+            if canonical == &genre.name() {
+                continue;
+            }
+            let target = self.find_or_create(canonical).await?;
+
+            let mut album_ids: HashSet<AlbumId> = HashSet::new();
+            for mut audio_file in self.audio_file_repository.find_by_genre(&genre.id).await? {
+                audio_file.unbind_from_genre(genre.id.clone())?;
+                audio_file.bind_to_genre(target.id.clone())?;
+                if let Some(album_id) = &audio_file.album {
+                    album_ids.insert(album_id.clone());
+                }
+                self.save_audio_file(context, audio_file).await?;
+                report.audio_files += 1;
+            }
+
+            for album_id in album_ids {
+                let Some(mut album) = self.album_repository.by_id(album_id).await? else {
+                    continue;
+                };
+                if !album.genres.contains(&genre.id) {
+                    continue;
+                }
+                album.unbind_from_genre(genre.id.clone())?;
+                album.bind_to_genre(target.id.clone())?;
+                let events = album.take_events();
+                let album = self.album_repository.save(album).await?;
+                for event in events {
+                    let envelope = EventEnvelope::new(
+                        album.id.as_i64(),
+                        album.version,
+                        event,
+                        context.correlation_id.clone(),
+                        context.event_id.clone(),
+                    );
+                    self.event_bus.publish(envelope).await?;
+                }
+                report.albums += 1;
+            }
+            info!("Genre '{}' renormalized to '{}'", genre.name(), canonical);
+            report.genres += 1;
+        }
+        Ok(report)
+    }
+
+    async fn find_genre(&self, genre_id: &GenreId) -> Result<Genre, AppError> {
+        self.genre_repository
+            .find_by_id(genre_id.clone())
+            .await?
+            .ok_or_else(|| AppError::AggregateNotFound("Genre".to_string(), genre_id.to_string()))
+    }
+
+    async fn find_or_create(&self, name: &str) -> Result<Genre, AppError> {
+        let name = GenreName::new(name.to_string())?;
+        if let Some(genre) = self.genre_repository.find_by_name(&name).await? {
+            return Ok(genre);
+        }
+        let mut genre = Genre::new(self.id_generator.next_id().await?.into(), name)?;
+        // Created 事件会触发扫描绑定流程，这里直接丢弃
+        genre.take_events();
+        Ok(self.genre_repository.save(genre).await?)
+    }
+
+    async fn save_audio_file(
+        &self,
+        context: &AppContext,
+        mut audio_file: AudioFile,
+    ) -> Result<(), AppError> {
+        let events = audio_file.take_events();
+        let audio_file = self.audio_file_repository.save(audio_file).await?;
+        for event in events {
+            let envelope = EventEnvelope::new(
+                audio_file.id.as_i64(),
+                audio_file.version,
+                event,
+                context.correlation_id.clone(),
+                context.event_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+        Ok(())
+    }
+}
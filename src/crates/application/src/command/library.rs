@@ -1,16 +1,22 @@
-use super::shared::IdGenerator;
+use super::file_check::AudioFileAvailabilityStore;
+use super::shared::{Clock, IdGenerator};
 use crate::context::AppContext;
 use crate::error::AppError;
 use crate::event::event_bus::{CorrelationId, EventBus, EventEnvelope};
 use async_trait::async_trait;
-use domain::library::{LibraryEvent, LibraryItem, LibraryRepository};
+use chrono::NaiveDateTime;
+use domain::library::{
+    Library, LibraryError, LibraryEvent, LibraryItem, LibraryRepository, ScanFastForward,
+    ScanFilter, ScanStatus,
+};
 use domain::value::LibraryId;
-use domain::value::{FileMeta, FileType};
-use log::{error, info};
+use domain::value::{FileMeta, FileType, MediaPath};
+use log::{error, info, warn};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tokio::time::Instant;
 // 存储后端错误
 #[derive(Error, Debug)]
@@ -19,6 +25,9 @@ pub enum ScanError {
     IoError(String),
     #[error("No more items to scan")]
     NoMoreItems,
+    /// 目录无法读取（如网络存储离线），路径与扫描产出的文件路径形式相同
+    #[error("Directory unavailable: {0}")]
+    DirectoryUnavailable(String),
     #[error("Other error: {0}")]
     OtherError(String),
 }
@@ -30,24 +39,60 @@ pub trait FileTypeDetector: Send + Sync {
 
 #[async_trait]
 pub trait ScannerFactory: Send + Sync {
-    async fn create(&self, protocol: &str) -> Result<Arc<dyn Scanner>, ScanError>;
+    /// 按库的根路径创建扫描器，远程存储据此选择凭据
+    async fn create(&self, root: &MediaPath) -> Result<Arc<dyn Scanner>, ScanError>;
 }
 
 type FileMetaResult = Result<FileMeta, ScanError>;
 
 // 存储后端基础设施trait
+/// 同一目录树多次扫描时应按相同顺序产出文件，中断的扫描据此从检查点继续
+///
+/// 被 filter 排除的目录不再进入，被排除的文件不产出
 #[async_trait]
 pub trait Scanner: Send + Sync {
-    async fn scan(&self, root: &str) -> Result<mpsc::Receiver<FileMetaResult>, ScanError>;
+    async fn scan(
+        &self,
+        root: &str,
+        filter: &ScanFilter,
+    ) -> Result<mpsc::Receiver<FileMetaResult>, ScanError>;
 }
 
 pub trait ScanFactory: Send + Sync {
     fn create(&self, uri: &str) -> Result<Arc<dyn Scanner>, ScanError>;
 }
 
+/// 每扫描多少个文件保存一次检查点
+const CHECKPOINT_INTERVAL: u64 = 500;
+
+/// 扫描进度检查点：最后一个已处理的文件及其所在目录
+#[derive(Debug, Clone)]
+pub struct ScanCheckpoint {
+    pub library_id: LibraryId,
+    pub last_dir: String,
+    pub last_path: String,
+    pub scanned_files: i64,
+    pub updated_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait ScanCheckpointStore: Send + Sync {
+    async fn find(&self, library_id: &LibraryId) -> anyhow::Result<Option<ScanCheckpoint>>;
+    async fn save(&self, checkpoint: &ScanCheckpoint) -> anyhow::Result<()>;
+    async fn delete(&self, library_id: &LibraryId) -> anyhow::Result<()>;
+}
+
 pub struct ScanLibraryCmd {
     pub library_id: LibraryId,
     pub is_full_scan: bool,
+    /// 忽略检查点，中断的扫描也从头开始
+    pub force: bool,
+}
+
+/// 把库目录中新写入的文件加入库，例如用户上传的文件
+pub struct AddLibraryFilesCmd {
+    pub library_id: LibraryId,
+    pub files: Vec<FileMeta>,
 }
 
 pub struct LibraryCommandService<T, B> {
@@ -56,6 +101,9 @@ pub struct LibraryCommandService<T, B> {
     file_type_detector: Arc<dyn FileTypeDetector>,
     event_bus: Arc<B>,
     id_generator: Arc<dyn IdGenerator>,
+    checkpoint_store: Arc<dyn ScanCheckpointStore>,
+    availability_store: Arc<dyn AudioFileAvailabilityStore>,
+    clock: Arc<dyn Clock>,
 }
 
 impl<T, B> LibraryCommandService<T, B>
@@ -69,6 +117,9 @@ where
         file_type_detector: Arc<dyn FileTypeDetector>,
         event_bus: Arc<B>,
         id_generator: Arc<dyn IdGenerator>,
+        checkpoint_store: Arc<dyn ScanCheckpointStore>,
+        availability_store: Arc<dyn AudioFileAvailabilityStore>,
+        clock: Arc<dyn Clock>,
     ) -> Self {
         Self {
             library_repo: library_repository,
@@ -76,13 +127,46 @@ where
             file_type_detector: file_type_detector.clone(),
             event_bus: event_bus.clone(),
             id_generator: id_generator.clone(),
+            checkpoint_store,
+            availability_store,
+            clock,
         }
     }
+
+    /// 服务启动时调用：上次停止时仍在扫描的库标记为中断，并从检查点继续扫描
+    pub async fn resume_interrupted_scans(&self, context: &AppContext) -> Result<(), AppError> {
+        for summary in self.library_repo.find_all().await? {
+            if summary.scan_status != ScanStatus::Scanning {
+                continue;
+            }
+            // find_all 不加载文件列表，保存前需要完整加载
+            let Some(mut library) = self.library_repo.find_by_id(&summary.id).await? else {
+                continue;
+            };
+            library.interrupt_scan();
+            self.library_repo.save(&library).await?;
+            if !library.enabled {
+                continue;
+            }
+            info!("Resuming interrupted scan of library {}", library.name);
+            let cmd = ScanLibraryCmd {
+                library_id: library.id.clone(),
+                is_full_scan: false,
+                force: false,
+            };
+            if let Err(e) = self.scan_library(context, cmd).await {
+                error!("Failed to resume scan of library {}: {}", library.name, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// 扫描在后台任务中进行，返回的句柄可用于等待扫描结束
     pub async fn scan_library(
         &self,
         context: &AppContext,
         cmd: ScanLibraryCmd,
-    ) -> Result<(), AppError> {
+    ) -> Result<JoinHandle<()>, AppError> {
         info!("Scan library: {}", cmd.library_id);
         let library_id = cmd.library_id;
         let mut library =
@@ -93,14 +177,38 @@ where
                     "Library".to_string(),
                     library_id.to_string(),
                 ))?;
-        library.start_scan(cmd.is_full_scan)?;
+        let checkpoint = if library.scan_status == ScanStatus::Interrupted && !cmd.force {
+            self.checkpoint_store
+                .find(&library_id)
+                .await
+                .map_err(checkpoint_error)?
+        } else {
+            None
+        };
+        match &checkpoint {
+            Some(checkpoint) => {
+                info!(
+                    "Resume scan of library {} after {} ({} files scanned)",
+                    library_id, checkpoint.last_path, checkpoint.scanned_files
+                );
+                library.resume_scan()?;
+            }
+            None => {
+                library.start_scan(cmd.is_full_scan)?;
+                self.checkpoint_store
+                    .delete(&library_id)
+                    .await
+                    .map_err(checkpoint_error)?;
+            }
+        }
         self.library_repo.save(&library).await?;
         for event in library.take_events() {
             let envelope = EventEnvelope::<LibraryEvent>::new_with_domain_event(
                 event,
                 CorrelationId::new(),
                 context.event_id.clone(),
-            );
+            )
+            .with_timestamp(self.clock.now());
             self.event_bus.publish(envelope).await?;
         }
 
@@ -109,10 +217,13 @@ where
         let library_repo = Arc::clone(&self.library_repo);
         let id_generator = Arc::clone(&self.id_generator);
         let file_type_detector = Arc::clone(&self.file_type_detector);
+        let checkpoint_store = Arc::clone(&self.checkpoint_store);
+        let availability_store = Arc::clone(&self.availability_store);
+        let clock = Arc::clone(&self.clock);
         let context = context.clone();
-        tokio::spawn(async move {
+        let handle = tokio::spawn(async move {
             let scanner = scanner_factory
-                .create(&library.path.protocol)
+                .create(&library.path)
                 .await
                 .or_else(|e| {
                     error!("Failed to create scanner: {}", e);
@@ -121,15 +232,27 @@ where
             if let Ok(scanner) = scanner {
                 let start_time = Instant::now();
                 info!("Scanner created: {}", library.path.path);
-                if let Ok(mut receiver) = scanner.scan(&library.path.path).await {
+                if let Ok(mut receiver) =
+                    scanner.scan(&library.path.path, &library.scan_filter).await
+                {
                     let mut scan_err = None;
-                    let mut scanned_count = 0u64;
+                    let mut scanned_count = checkpoint
+                        .as_ref()
+                        .map_or(0, |c| c.scanned_files.max(0) as u64);
+                    let mut fast_forward = ScanFastForward::new(checkpoint.map(|c| c.last_path));
                     let mut last_log_time = Instant::now();
                     let mut last_log_count = 0u64;
+                    // 无法读取的目录中已有的文件保留，不当作删除
+                    let mut unavailable_dirs: Vec<String> = Vec::new();
 
                     while let Some(result) = receiver.recv().await {
                         match result {
                             Ok(file) => {
+                                if fast_forward.skip(&mut library, &file.path.path) {
+                                    continue;
+                                }
+                                let last_dir = file.dir_path.path.clone();
+                                let last_path = file.path.path.clone();
                                 let item_id = id_generator.next_id().await.unwrap();
                                 let file_type = file_type_detector.detect(&file.suffix);
                                 let library_item = LibraryItem::new(
@@ -147,7 +270,8 @@ where
                                             event,
                                             CorrelationId::new(),
                                             context.event_id.clone(),
-                                        );
+                                        )
+                                        .with_timestamp(clock.now());
                                     if let Err(e) = event_bus.publish(envelope).await {
                                         error!("Failed to publish event: {}", e);
                                     }
@@ -155,6 +279,20 @@ where
 
                                 scanned_count += 1;
 
+                                if scanned_count % CHECKPOINT_INTERVAL == 0 {
+                                    save_checkpoint(
+                                        library_repo.as_ref(),
+                                        event_bus.as_ref(),
+                                        checkpoint_store.as_ref(),
+                                        &library,
+                                        last_dir,
+                                        last_path,
+                                        scanned_count,
+                                        clock.now().naive_utc(),
+                                    )
+                                    .await;
+                                }
+
                                 // 每1000个文件打印一次进度日志
                                 if scanned_count % 1000 == 0 {
                                     let elapsed = last_log_time.elapsed();
@@ -176,6 +314,11 @@ where
                                     last_log_count = scanned_count;
                                 }
                             }
+                            Err(ScanError::DirectoryUnavailable(dir)) => {
+                                let kept = library.keep_items_under(&dir);
+                                warn!("Directory {} is unavailable, keeping {} files", dir, kept);
+                                unavailable_dirs.push(dir);
+                            }
                             Err(e) => {
                                 error!("Failed to scan library: {}", e);
                                 scan_err = Some(e);
@@ -183,21 +326,41 @@ where
                             }
                         }
                     }
-                    if let Some(_e) = scan_err {
+                    let root = library.path.path.trim_end_matches('/');
+                    let root_unavailable = unavailable_dirs
+                        .iter()
+                        .any(|dir| dir.trim_end_matches('/') == root);
+                    let now = clock.now().naive_utc();
+                    if scan_err.is_some() || root_unavailable {
                         library.abort_scan();
                     } else {
-                        library.finish_scan();
+                        library.finish_scan(now);
+                    }
+                    if root_unavailable {
+                        library.mark_unavailable(now);
+                    } else if library.mark_available() {
+                        info!("Storage of library {} is reachable again", library.name);
                     }
 
                     if let Err(e) = library_repo.save(&library).await {
                         error!("Failed to save library: {}", e);
                     }
+                    if let Err(e) = checkpoint_store.delete(&library_id).await {
+                        error!("Failed to delete scan checkpoint: {}", e);
+                    }
+                    if let Err(e) = availability_store
+                        .set_unavailable_dirs(&library_id, &unavailable_dirs, now)
+                        .await
+                    {
+                        error!("Failed to update file availability: {}", e);
+                    }
                     for event in library.take_events() {
                         let envelope = EventEnvelope::<LibraryEvent>::new_with_domain_event(
                             event,
                             CorrelationId::new(),
                             context.event_id.clone(),
-                        );
+                        )
+                        .with_timestamp(clock.now());
                         if let Err(e) = event_bus.publish(envelope).await {
                             error!("Failed to publish event: {}", e);
                         }
@@ -217,12 +380,147 @@ where
                         "Failed to create storage backend for library {}",
                         library_id
                     );
+                    end_scan_unavailable(
+                        library_repo.as_ref(),
+                        availability_store.as_ref(),
+                        event_bus.as_ref(),
+                        clock.as_ref(),
+                        &context,
+                        &mut library,
+                    )
+                    .await;
                 }
             } else {
                 error!("Failed to create scanner for library {}", library_id);
+                end_scan_unavailable(
+                    library_repo.as_ref(),
+                    availability_store.as_ref(),
+                    event_bus.as_ref(),
+                    clock.as_ref(),
+                    &context,
+                    &mut library,
+                )
+                .await;
             }
         });
 
+        Ok(handle)
+    }
+
+    /// 不等待下一次扫描，直接发布 FileAdded 让文件立即入库。
+    /// 扫描进行中时拒绝，避免与扫描任务同时保存库
+    pub async fn add_files(
+        &self,
+        context: &AppContext,
+        cmd: AddLibraryFilesCmd,
+    ) -> Result<(), AppError> {
+        let mut library = self
+            .library_repo
+            .find_by_id(&cmd.library_id)
+            .await?
+            .ok_or(AppError::AggregateNotFound(
+                "Library".to_string(),
+                cmd.library_id.to_string(),
+            ))?;
+        if !library.enabled {
+            return Err(LibraryError::Disabled.into());
+        }
+        if library.scan_status == ScanStatus::Scanning {
+            return Err(LibraryError::ScanningInProgress.into());
+        }
+        for file in cmd.files {
+            let item_id = self.id_generator.next_id().await?;
+            let file_type = self.file_type_detector.detect(&file.suffix);
+            library.add_item(LibraryItem::new(
+                item_id.into(),
+                library.id.clone(),
+                file,
+                file_type,
+            ));
+        }
+        self.library_repo.save(&library).await?;
+        for event in library.take_events() {
+            let envelope = EventEnvelope::<LibraryEvent>::new_with_domain_event(
+                event,
+                CorrelationId::new(),
+                context.event_id.clone(),
+            )
+            .with_timestamp(self.clock.now());
+            self.event_bus.publish(envelope).await?;
+        }
         Ok(())
     }
 }
+
+/// 先等待已发布的 FileAdded 等事件处理完，再保存库（持久化已加入的文件）并记录检查点；
+/// 否则中断后从检查点恢复时，检查点之前尚未处理完的文件会被跳过而不再重新发布
+async fn save_checkpoint<T, B>(
+    library_repo: &T,
+    event_bus: &B,
+    checkpoint_store: &dyn ScanCheckpointStore,
+    library: &Library,
+    last_dir: String,
+    last_path: String,
+    scanned_count: u64,
+    now: NaiveDateTime,
+) where
+    T: LibraryRepository,
+    B: EventBus,
+{
+    event_bus.drain().await;
+    if let Err(e) = library_repo.save(library).await {
+        error!("Failed to save library at checkpoint: {}", e);
+        return;
+    }
+    let checkpoint = ScanCheckpoint {
+        library_id: library.id.clone(),
+        last_dir,
+        last_path,
+        scanned_files: scanned_count as i64,
+        updated_at: now,
+    };
+    if let Err(e) = checkpoint_store.save(&checkpoint).await {
+        error!("Failed to save scan checkpoint: {}", e);
+    }
+}
+
+/// 存储无法访问时结束扫描并保留所有文件，库和其中的文件标记为不可用
+async fn end_scan_unavailable<T, B>(
+    library_repo: &T,
+    availability_store: &dyn AudioFileAvailabilityStore,
+    event_bus: &B,
+    clock: &dyn Clock,
+    context: &AppContext,
+    library: &mut Library,
+) where
+    T: LibraryRepository,
+    B: EventBus,
+{
+    let now = clock.now().naive_utc();
+    library.abort_scan();
+    library.mark_unavailable(now);
+    if let Err(e) = library_repo.save(library).await {
+        error!("Failed to save library: {}", e);
+    }
+    if let Err(e) = availability_store
+        .set_unavailable_dirs(&library.id, std::slice::from_ref(&library.path.path), now)
+        .await
+    {
+        error!("Failed to update file availability: {}", e);
+    }
+    for event in library.take_events() {
+        let envelope = EventEnvelope::<LibraryEvent>::new_with_domain_event(
+            event,
+            CorrelationId::new(),
+            context.event_id.clone(),
+        )
+        .with_timestamp(clock.now());
+        if let Err(e) = event_bus.publish(envelope).await {
+            error!("Failed to publish event: {}", e);
+        }
+    }
+}
+
+fn checkpoint_error(e: anyhow::Error) -> AppError {
+    AppError::RepositoryError("ScanCheckpoint".to_string(), e.to_string())
+}
@@ -0,0 +1,230 @@
+use crate::command::media_parse::StorageClientFactory;
+use crate::command::shared::IdGenerator;
+use crate::error::AppError;
+use domain::library::{
+    Library, LibraryCredentials, LibraryCredentialsRepository, LibraryError, LibraryKind,
+    LibraryRepository,
+};
+use domain::value::{LibraryId, MediaPath};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct CreateLibraryCmd {
+    pub name: String,
+    pub path: MediaPath,
+    pub enabled: bool,
+    /// 定时扫描间隔（分钟），0 或 None 表示使用系统默认间隔
+    pub scan_interval_minutes: Option<i32>,
+    pub kind: LibraryKind,
+    /// 非空时只扫描匹配的文件
+    pub include_patterns: Vec<String>,
+    /// 扫描时跳过的文件和目录，如 `@eaDir`、`*.partial`
+    pub exclude_patterns: Vec<String>,
+    /// 是否扫描以 `.` 开头的文件和目录
+    pub scan_hidden: bool,
+    /// 存储无法访问时是否从浏览和搜索结果中隐藏库中的歌曲
+    pub hide_unavailable: bool,
+}
+
+/// 更新库配置，None 表示保持原值
+#[derive(Debug)]
+pub struct UpdateLibraryCmd {
+    pub library_id: LibraryId,
+    pub name: Option<String>,
+    pub path: Option<MediaPath>,
+    pub enabled: Option<bool>,
+    /// 0 表示改用系统默认间隔
+    pub scan_interval_minutes: Option<i32>,
+    pub kind: Option<LibraryKind>,
+    pub include_patterns: Option<Vec<String>>,
+    pub exclude_patterns: Option<Vec<String>>,
+    pub scan_hidden: Option<bool>,
+    pub hide_unavailable: Option<bool>,
+}
+
+/// 设置或轮换远程库的访问凭据
+#[derive(Debug)]
+pub struct SetCredentialsCmd {
+    pub library_id: LibraryId,
+    pub username: String,
+    pub password: String,
+    pub domain: Option<String>,
+}
+
+/// 连接测试结果
+#[derive(Debug, Clone)]
+pub struct ConnectionReport {
+    pub reachable: bool,
+    /// 根目录下的条目数
+    pub entries: usize,
+    pub error: Option<String>,
+}
+
+/// 音乐库（音乐文件夹）的注册与维护
+pub struct LibraryAdminService {
+    library_repo: Arc<dyn LibraryRepository>,
+    credentials_repo: Arc<dyn LibraryCredentialsRepository>,
+    storage_client_factory: Arc<dyn StorageClientFactory>,
+    id_generator: Arc<dyn IdGenerator>,
+}
+
+impl LibraryAdminService {
+    pub fn new(
+        library_repo: Arc<dyn LibraryRepository>,
+        credentials_repo: Arc<dyn LibraryCredentialsRepository>,
+        storage_client_factory: Arc<dyn StorageClientFactory>,
+        id_generator: Arc<dyn IdGenerator>,
+    ) -> Self {
+        Self {
+            library_repo,
+            credentials_repo,
+            storage_client_factory,
+            id_generator,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Library>, AppError> {
+        Ok(self.library_repo.find_all().await?)
+    }
+
+    /// 查询单个库的配置，不加载 items
+    pub async fn get(&self, library_id: &LibraryId) -> Result<Library, AppError> {
+        self.library_repo
+            .find_all()
+            .await?
+            .into_iter()
+            .find(|library| &library.id == library_id)
+            .ok_or_else(|| not_found(library_id))
+    }
+
+    /// 加载完整聚合用于修改
+    async fn load(&self, library_id: &LibraryId) -> Result<Library, AppError> {
+        self.library_repo
+            .find_by_id(library_id)
+            .await?
+            .ok_or_else(|| not_found(library_id))
+    }
+
+    pub async fn create(&self, cmd: CreateLibraryCmd) -> Result<Library, AppError> {
+        self.validate_path(&cmd.path).await?;
+        let id = LibraryId::from(self.id_generator.next_id().await?);
+        let mut library = Library::new(id.clone(), String::new(), cmd.path);
+        library.rename(&cmd.name)?;
+        library.set_enabled(cmd.enabled);
+        library.set_scan_interval(cmd.scan_interval_minutes.unwrap_or(0));
+        library.set_kind(cmd.kind);
+        library.set_include_patterns(cmd.include_patterns);
+        library.set_exclude_patterns(cmd.exclude_patterns);
+        library.set_scan_hidden(cmd.scan_hidden);
+        library.set_hide_unavailable(cmd.hide_unavailable);
+        self.library_repo.save(&library).await?;
+        self.get(&id).await
+    }
+
+    pub async fn update(&self, cmd: UpdateLibraryCmd) -> Result<Library, AppError> {
+        let mut library = self.load(&cmd.library_id).await?;
+        if let Some(name) = &cmd.name {
+            library.rename(name)?;
+        }
+        if let Some(path) = cmd.path {
+            if path != library.path {
+                self.validate_path(&path).await?;
+                library.relocate(path)?;
+            }
+        }
+        if let Some(enabled) = cmd.enabled {
+            library.set_enabled(enabled);
+        }
+        if let Some(minutes) = cmd.scan_interval_minutes {
+            library.set_scan_interval(minutes);
+        }
+        if let Some(kind) = cmd.kind {
+            library.set_kind(kind);
+        }
+        if let Some(patterns) = cmd.include_patterns {
+            library.set_include_patterns(patterns);
+        }
+        if let Some(patterns) = cmd.exclude_patterns {
+            library.set_exclude_patterns(patterns);
+        }
+        if let Some(scan_hidden) = cmd.scan_hidden {
+            library.set_scan_hidden(scan_hidden);
+        }
+        if let Some(hide_unavailable) = cmd.hide_unavailable {
+            library.set_hide_unavailable(hide_unavailable);
+        }
+        self.library_repo.save(&library).await?;
+        self.get(&cmd.library_id).await
+    }
+
+    /// 只允许删除没有已索引文件的库，已有内容的库应停用
+    pub async fn delete(&self, library_id: &LibraryId) -> Result<(), AppError> {
+        let library = self.load(library_id).await?;
+        if !library.items.is_empty() {
+            return Err(LibraryError::NotEmpty.into());
+        }
+        Ok(self.library_repo.delete(library_id).await?)
+    }
+
+    pub async fn get_credentials(
+        &self,
+        library_id: &LibraryId,
+    ) -> Result<Option<LibraryCredentials>, AppError> {
+        self.get(library_id).await?;
+        Ok(self.credentials_repo.find_by_library(library_id).await?)
+    }
+
+    /// 新凭据直接覆盖旧凭据，下一次访问存储时生效
+    pub async fn set_credentials(
+        &self,
+        cmd: SetCredentialsCmd,
+    ) -> Result<LibraryCredentials, AppError> {
+        self.get(&cmd.library_id).await?;
+        let credentials = LibraryCredentials::new(
+            cmd.library_id,
+            &cmd.username,
+            &cmd.password,
+            cmd.domain.as_deref(),
+        )?;
+        self.credentials_repo.save(&credentials).await?;
+        Ok(credentials)
+    }
+
+    pub async fn clear_credentials(&self, library_id: &LibraryId) -> Result<(), AppError> {
+        self.get(library_id).await?;
+        Ok(self.credentials_repo.delete(library_id).await?)
+    }
+
+    /// 尝试列出根目录，检查存储是否可达
+    pub async fn test_connection(&self, path: &MediaPath) -> Result<ConnectionReport, AppError> {
+        self.validate_path(path).await?;
+        let client = self.storage_client_factory.create(path).await?;
+        let report = match client.list(path).await {
+            Ok(entries) => ConnectionReport {
+                reachable: true,
+                entries: entries.len(),
+                error: None,
+            },
+            Err(e) => ConnectionReport {
+                reachable: false,
+                entries: 0,
+                error: Some(e.to_string()),
+            },
+        };
+        Ok(report)
+    }
+
+    /// 协议必须有对应的存储客户端，路径格式由客户端校验
+    async fn validate_path(&self, path: &MediaPath) -> Result<(), AppError> {
+        let client = self
+            .storage_client_factory
+            .create(path)
+            .await
+            .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+        client.validate_path(path)
+    }
+}
+
+fn not_found(library_id: &LibraryId) -> AppError {
+    AppError::AggregateNotFound("Library".to_string(), library_id.to_string())
+}
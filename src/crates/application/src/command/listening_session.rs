@@ -0,0 +1,459 @@
+use crate::command::shared::Clock;
+use crate::error::AppError;
+use crate::event::push::{ServerEvent, ServerEventHub};
+use chrono::{DateTime, Duration, Utc};
+use domain::value::{AudioFileId, UserId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// 超过这个时间没有任何操作的会话自动结束
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::hours(6);
+/// 每个会话的最多听众数，不含主持人
+pub const MAX_SESSION_GUESTS: usize = 50;
+
+const CODE_LENGTH: usize = 6;
+/// 去掉了容易看错的 0、O、1、I，共 32 个字符
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// 主持人的播放队列和进度
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionPlayback {
+    pub song_ids: Vec<AudioFileId>,
+    pub current_index: Option<usize>,
+    /// 当前歌曲内的位置（毫秒），对应 updated_at 时刻
+    pub position_ms: i64,
+    pub playing: bool,
+}
+
+/// 会话中的用户，用户名在加入时记下，推送时不再查询
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionMember {
+    pub user_id: UserId,
+    pub username: String,
+}
+
+/// 一起听会话：主持人的队列和进度同步给所有听众。
+/// 只保存在内存中，服务器重启后需要重新创建
+#[derive(Debug, Clone)]
+pub struct ListeningSession {
+    /// 加入会话用的邀请码
+    pub code: String,
+    pub host: SessionMember,
+    /// 按加入顺序
+    pub guests: Vec<SessionMember>,
+    /// 主持人授权可以控制播放的听众
+    pub controllers: Vec<UserId>,
+    pub playback: SessionPlayback,
+    /// 播放状态最后一次修改的时间
+    pub updated_at: DateTime<Utc>,
+    /// 最后一次加入、离开或修改的时间，用于空闲超时
+    pub last_active_at: DateTime<Utc>,
+}
+
+impl ListeningSession {
+    pub fn is_host(&self, user_id: &UserId) -> bool {
+        &self.host.user_id == user_id
+    }
+
+    pub fn is_guest(&self, user_id: &UserId) -> bool {
+        self.guests.iter().any(|g| &g.user_id == user_id)
+    }
+
+    pub fn is_member(&self, user_id: &UserId) -> bool {
+        self.is_host(user_id) || self.is_guest(user_id)
+    }
+
+    pub fn can_control(&self, user_id: &UserId) -> bool {
+        self.is_host(user_id) || self.controllers.contains(user_id)
+    }
+
+    /// 主持人和所有听众的 ID
+    pub fn members(&self) -> Vec<UserId> {
+        let mut members = vec![self.host.user_id.clone()];
+        members.extend(self.guests.iter().map(|g| g.user_id.clone()));
+        members
+    }
+
+    /// 播放中时按经过的时间推算 now 时刻的位置
+    pub fn position_at(&self, now: DateTime<Utc>) -> i64 {
+        if !self.playback.playing {
+            return self.playback.position_ms;
+        }
+        let elapsed = (now - self.updated_at).num_milliseconds().max(0);
+        self.playback.position_ms + elapsed
+    }
+}
+
+/// 管理进行中的一起听会话，每次变化通过 ServerEventHub 推送给会话成员
+pub struct ListeningSessionService {
+    sessions: Mutex<HashMap<String, ListeningSession>>,
+    push_hub: ServerEventHub,
+    clock: Arc<dyn Clock>,
+}
+
+impl ListeningSessionService {
+    pub fn new(push_hub: ServerEventHub, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            push_hub,
+            clock,
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// 创建会话。主持人已有的会话会先结束，同一时间只能主持一个
+    pub fn create(
+        &self,
+        host: SessionMember,
+        playback: SessionPlayback,
+    ) -> Result<ListeningSession, AppError> {
+        validate_playback(&playback)?;
+        let now = self.clock.now();
+        let mut sessions = self.lock();
+        let hosted: Vec<String> = sessions
+            .values()
+            .filter(|s| s.host.user_id == host.user_id)
+            .map(|s| s.code.clone())
+            .collect();
+        for code in hosted {
+            if let Some(session) = sessions.remove(&code) {
+                self.publish_ended(&session);
+            }
+        }
+        let code = loop {
+            let code = generate_code();
+            if !sessions.contains_key(&code) {
+                break code;
+            }
+        };
+        let session = ListeningSession {
+            code: code.clone(),
+            host,
+            guests: vec![],
+            controllers: vec![],
+            playback,
+            updated_at: now,
+            last_active_at: now,
+        };
+        sessions.insert(code, session.clone());
+        Ok(session)
+    }
+
+    /// 只有会话成员可以查看
+    pub fn find(&self, code: &str, user_id: &UserId) -> Result<ListeningSession, AppError> {
+        let mut sessions = self.lock();
+        self.prune(&mut sessions);
+        Self::member_session(&mut sessions, code, user_id).cloned()
+    }
+
+    /// 用户参与（主持或加入）的会话
+    pub fn find_by_member(&self, user_id: &UserId) -> Vec<ListeningSession> {
+        let mut sessions = self.lock();
+        self.prune(&mut sessions);
+        sessions
+            .values()
+            .filter(|s| s.is_member(user_id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn join(&self, code: &str, member: SessionMember) -> Result<ListeningSession, AppError> {
+        let mut sessions = self.lock();
+        self.prune(&mut sessions);
+        let session = sessions
+            .get_mut(&normalize_code(code))
+            .ok_or_else(|| not_found(code))?;
+        if !session.is_member(&member.user_id) {
+            if session.guests.len() >= MAX_SESSION_GUESTS {
+                return Err(AppError::InvalidInput(format!(
+                    "Session is full: at most {} guests",
+                    MAX_SESSION_GUESTS
+                )));
+            }
+            session.guests.push(member);
+        }
+        session.last_active_at = self.clock.now();
+        let session = session.clone();
+        self.publish_updated(&session);
+        Ok(session)
+    }
+
+    /// 听众离开会话；主持人离开时会话结束
+    pub fn leave(&self, code: &str, user_id: &UserId) -> Result<(), AppError> {
+        let mut sessions = self.lock();
+        self.prune(&mut sessions);
+        let session = Self::member_session(&mut sessions, code, user_id)?;
+        if session.is_host(user_id) {
+            let code = session.code.clone();
+            if let Some(session) = sessions.remove(&code) {
+                self.publish_ended(&session);
+            }
+            return Ok(());
+        }
+        session.guests.retain(|g| &g.user_id != user_id);
+        session.controllers.retain(|id| id != user_id);
+        session.last_active_at = self.clock.now();
+        let session = session.clone();
+        self.publish_updated(&session);
+        Ok(())
+    }
+
+    /// 只有主持人可以结束会话
+    pub fn end(&self, code: &str, user_id: &UserId) -> Result<(), AppError> {
+        let mut sessions = self.lock();
+        self.prune(&mut sessions);
+        let session = Self::member_session(&mut sessions, code, user_id)?;
+        if !session.is_host(user_id) {
+            return Err(AppError::AuthError(
+                "Only the host can end the session".to_string(),
+            ));
+        }
+        let code = session.code.clone();
+        if let Some(session) = sessions.remove(&code) {
+            self.publish_ended(&session);
+        }
+        Ok(())
+    }
+
+    /// 主持人或被授权的听众修改队列和进度
+    pub fn update_playback(
+        &self,
+        code: &str,
+        user_id: &UserId,
+        playback: SessionPlayback,
+    ) -> Result<ListeningSession, AppError> {
+        validate_playback(&playback)?;
+        let mut sessions = self.lock();
+        self.prune(&mut sessions);
+        let session = Self::member_session(&mut sessions, code, user_id)?;
+        if !session.can_control(user_id) {
+            return Err(AppError::AuthError(
+                "Not allowed to control playback in this session".to_string(),
+            ));
+        }
+        let now = self.clock.now();
+        session.playback = playback;
+        session.updated_at = now;
+        session.last_active_at = now;
+        let session = session.clone();
+        self.publish_updated(&session);
+        Ok(session)
+    }
+
+    /// 主持人设置可以控制播放的听众，替换原来的列表
+    pub fn set_controllers(
+        &self,
+        code: &str,
+        user_id: &UserId,
+        controllers: Vec<UserId>,
+    ) -> Result<ListeningSession, AppError> {
+        let mut sessions = self.lock();
+        self.prune(&mut sessions);
+        let session = Self::member_session(&mut sessions, code, user_id)?;
+        if !session.is_host(user_id) {
+            return Err(AppError::AuthError(
+                "Only the host can delegate playback control".to_string(),
+            ));
+        }
+        if let Some(id) = controllers.iter().find(|id| !session.is_guest(id)) {
+            return Err(AppError::InvalidInput(format!(
+                "User {} has not joined the session",
+                id
+            )));
+        }
+        let mut deduped: Vec<UserId> = Vec::with_capacity(controllers.len());
+        for id in controllers {
+            if !deduped.contains(&id) {
+                deduped.push(id);
+            }
+        }
+        session.controllers = deduped;
+        session.last_active_at = self.clock.now();
+        let session = session.clone();
+        self.publish_updated(&session);
+        Ok(session)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ListeningSession>> {
+        self.sessions.lock().unwrap()
+    }
+
+    /// 非成员看到的和会话不存在一样，避免通过接口猜测邀请码
+    fn member_session<'a>(
+        sessions: &'a mut HashMap<String, ListeningSession>,
+        code: &str,
+        user_id: &UserId,
+    ) -> Result<&'a mut ListeningSession, AppError> {
+        sessions
+            .get_mut(&normalize_code(code))
+            .filter(|s| s.is_member(user_id))
+            .ok_or_else(|| not_found(code))
+    }
+
+    /// 结束空闲超时的会话
+    fn prune(&self, sessions: &mut HashMap<String, ListeningSession>) {
+        let deadline = self.clock.now() - SESSION_IDLE_TIMEOUT;
+        let expired: Vec<String> = sessions
+            .values()
+            .filter(|s| s.last_active_at < deadline)
+            .map(|s| s.code.clone())
+            .collect();
+        for code in expired {
+            if let Some(session) = sessions.remove(&code) {
+                self.publish_ended(&session);
+            }
+        }
+    }
+
+    fn publish_updated(&self, session: &ListeningSession) {
+        self.push_hub
+            .publish(ServerEvent::ListeningSessionUpdated(session.clone()));
+    }
+
+    fn publish_ended(&self, session: &ListeningSession) {
+        self.push_hub.publish(ServerEvent::ListeningSessionEnded {
+            code: session.code.clone(),
+            members: session.members(),
+        });
+    }
+}
+
+fn validate_playback(playback: &SessionPlayback) -> Result<(), AppError> {
+    if playback
+        .current_index
+        .is_some_and(|index| index >= playback.song_ids.len())
+    {
+        return Err(AppError::InvalidInput(
+            "currentIndex is out of range".to_string(),
+        ));
+    }
+    if playback.position_ms < 0 {
+        return Err(AppError::InvalidInput(
+            "positionMs cannot be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn generate_code() -> String {
+    Uuid::new_v4().as_bytes()[..CODE_LENGTH]
+        .iter()
+        .map(|b| CODE_ALPHABET[*b as usize % CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// 邀请码不区分大小写
+fn normalize_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+fn not_found(code: &str) -> AppError {
+    AppError::AggregateNotFound("ListeningSession".to_string(), code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::FakeClock;
+    use chrono::TimeZone;
+
+    fn service() -> (ListeningSessionService, Arc<FakeClock>, ServerEventHub) {
+        let clock = Arc::new(FakeClock::new(
+            Utc.with_ymd_and_hms(2026, 10, 1, 20, 0, 0).unwrap(),
+        ));
+        let hub = ServerEventHub::new(16);
+        (
+            ListeningSessionService::new(hub.clone(), clock.clone()),
+            clock,
+            hub,
+        )
+    }
+
+    fn member(id: i64) -> SessionMember {
+        SessionMember {
+            user_id: UserId::from(id),
+            username: format!("user{}", id),
+        }
+    }
+
+    fn playback(songs: &[i64], current_index: usize, playing: bool) -> SessionPlayback {
+        SessionPlayback {
+            song_ids: songs.iter().map(|id| AudioFileId::from(*id)).collect(),
+            current_index: Some(current_index),
+            position_ms: 1000,
+            playing,
+        }
+    }
+
+    #[test]
+    fn only_controllers_change_playback() {
+        let (service, clock, hub) = service();
+        let mut events = hub.subscribe();
+        let host = UserId::from(1);
+        let guest = UserId::from(2);
+        let stranger = UserId::from(3);
+
+        let session = service
+            .create(member(1), playback(&[10, 11], 0, true))
+            .unwrap();
+        let code = session.code.to_lowercase();
+        assert!(service.find(&code, &guest).is_err());
+        service.join(&code, member(2)).unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            ServerEvent::ListeningSessionUpdated(s) if s.guests == vec![member(2)]
+        ));
+
+        assert!(service
+            .update_playback(&code, &guest, playback(&[10, 11], 1, true))
+            .is_err());
+        assert!(service
+            .set_controllers(&code, &host, vec![stranger.clone()])
+            .is_err());
+        service
+            .set_controllers(&code, &host, vec![guest.clone()])
+            .unwrap();
+        let updated = service
+            .update_playback(&code, &guest, playback(&[10, 11], 1, true))
+            .unwrap();
+        assert_eq!(updated.playback.current_index, Some(1));
+
+        clock.advance(Duration::seconds(30));
+        let found = service.find(&code, &host).unwrap();
+        assert_eq!(found.position_at(service.now()), 31_000);
+        assert!(service
+            .update_playback(&code, &host, playback(&[10], 1, false))
+            .is_err());
+    }
+
+    #[test]
+    fn host_leaving_or_going_idle_ends_the_session() {
+        let (service, clock, hub) = service();
+        let host = UserId::from(1);
+        let guest = UserId::from(2);
+
+        let first = service
+            .create(member(1), SessionPlayback::default())
+            .unwrap();
+        service.join(&first.code, member(2)).unwrap();
+        assert!(service.end(&first.code, &guest).is_err());
+        let mut events = hub.subscribe();
+        service.leave(&first.code, &host).unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            ServerEvent::ListeningSessionEnded { members, .. } if members == vec![host.clone(), guest.clone()]
+        ));
+        assert!(service.join(&first.code, member(2)).is_err());
+
+        let second = service
+            .create(member(1), SessionPlayback::default())
+            .unwrap();
+        clock.advance(SESSION_IDLE_TIMEOUT + Duration::minutes(1));
+        assert!(service.find_by_member(&host).is_empty());
+        assert!(service.join(&second.code, member(2)).is_err());
+    }
+}
@@ -1,22 +1,30 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use log::warn;
+use std::collections::HashSet;
 use std::sync::Arc;
 
-use super::shared::IdGenerator;
+use super::bookmark::audiobook_library_ids;
+use super::shared::{Clock, IdGenerator};
 use crate::context::AppContext;
 use crate::error::AppError;
-use crate::event::event_bus::EventBus;
 use crate::event::event_bus::EventEnvelope;
+use crate::event::outbox::{AnnotationOutbox, PlayerOutbox};
 use domain::album::AlbumRepository;
 use domain::annotation::Kind;
-use domain::annotation::{Annotation, AnnotationRepository};
+use domain::annotation::{Annotation, AnnotationEvent, AnnotationRepository};
 use domain::artist::ArtistRepository;
-use domain::audio_file::{AudioFileError, AudioFileRepository};
+use domain::audio_file::{AudioFile, AudioFileError, AudioFileRepository};
 use domain::event::DomainEvent;
-use domain::player::{Player, PlayerRepository};
+use domain::library::LibraryRepository;
+use domain::player::Player;
 use domain::value::{AlbumId, AnnotationId, ArtistId, AudioFileId, PlayerId, UserId};
 
 #[derive(Debug)]
 pub struct StarItem {
     pub item_id: i64,
+    /// 客户端通过 albumId / artistId 指明的类型，None 时按 ID 推断
+    pub kind: Option<Kind>,
 }
 
 #[derive(Debug)]
@@ -28,6 +36,7 @@ pub struct StarCmd {
 #[derive(Debug)]
 pub struct UnstarItem {
     pub item_id: i64,
+    pub kind: Option<Kind>,
 }
 
 #[derive(Debug)]
@@ -40,9 +49,60 @@ pub struct UnstarCmd {
 pub struct SetRatingCmd {
     pub user_id: UserId,
     pub item_id: i64,
+    pub kind: Option<Kind>,
     pub rating: i32,
 }
 
+/// 从其他服务器导入的注解
+#[derive(Debug)]
+pub struct ImportAnnotationCmd {
+    pub user_id: UserId,
+    pub kind: Kind,
+    pub item_id: i64,
+    /// None 表示未收藏
+    pub starred_at: Option<NaiveDateTime>,
+    pub rating: i32,
+    pub play_count: i32,
+    pub played_at: Option<NaiveDateTime>,
+}
+
+/// 同步工具提交的单个注解，字段为 None 时保持服务器上的值
+#[derive(Debug)]
+pub struct SyncAnnotationItem {
+    pub kind: Kind,
+    pub item_id: i64,
+    pub starred: Option<bool>,
+    pub starred_at: Option<NaiveDateTime>,
+    pub rating: Option<i32>,
+    pub play_count: Option<i32>,
+    pub played_at: Option<NaiveDateTime>,
+    /// 客户端修改该注解的时间，服务器上的注解更新得更晚时跳过
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug)]
+pub struct SyncAnnotationsCmd {
+    pub user_id: UserId,
+    pub items: Vec<SyncAnnotationItem>,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncAnnotationsReport {
+    pub applied: usize,
+    pub unchanged: usize,
+    /// 服务器上的注解比客户端新，未修改
+    pub conflicts: Vec<(Kind, i64)>,
+    /// 条目不存在
+    pub not_found: Vec<(Kind, i64)>,
+}
+
+#[derive(Debug)]
+pub struct ScrobbleItem {
+    pub audio_file_id: AudioFileId,
+    /// 客户端记录的播放时间（UTC），None 表示现在
+    pub played_at: Option<NaiveDateTime>,
+}
+
 #[derive(Debug)]
 pub struct ScrobbleCmd {
     pub player_id: PlayerId,
@@ -51,52 +111,103 @@ pub struct ScrobbleCmd {
     pub ip: String,
     pub user_agent: String,
     pub user_id: UserId,
-    pub audio_file_id: AudioFileId,
+    /// 客户端可以一次提交离线期间排队的多次播放
+    pub items: Vec<ScrobbleItem>,
+    /// false 时只更新正在播放（取最后一首），不计播放次数
     pub submission: bool,
 }
 
-pub struct MediaAnnotationService<B: EventBus> {
+/// 记录带时间的播放提交，客户端重发离线队列时同一次播放只计一次
+#[async_trait]
+pub trait ScrobbleSubmissionStore: Send + Sync {
+    /// 首次记录返回 true，(用户, 歌曲, 时间) 已经记录过时返回 false
+    async fn record(
+        &self,
+        user_id: &UserId,
+        audio_file_id: &AudioFileId,
+        played_at: NaiveDateTime,
+    ) -> Result<bool, AppError>;
+}
+
+pub struct MediaAnnotationService {
     media_annotation_repo: Arc<dyn AnnotationRepository>,
+    /// 注解事件经 outbox 发布，保证与注解一起落库
+    outbox: Arc<dyn AnnotationOutbox>,
     audio_file_repository: Arc<dyn AudioFileRepository>,
     album_repository: Arc<dyn AlbumRepository>,
     artist_repository: Arc<dyn ArtistRepository>,
-    player_repository: Arc<dyn PlayerRepository>,
+    /// 正在播放的事件同样经 outbox 发布
+    player_repository: Arc<dyn PlayerOutbox>,
     id_generator: Arc<dyn IdGenerator>,
-    event_bus: Arc<B>,
+    clock: Arc<dyn Clock>,
+    submission_store: Option<Arc<dyn ScrobbleSubmissionStore>>,
+    library_repository: Option<Arc<dyn LibraryRepository>>,
 }
 
-impl<B: EventBus> MediaAnnotationService<B> {
+impl MediaAnnotationService {
     pub fn new(
         media_annotation_repo: Arc<dyn AnnotationRepository>,
+        outbox: Arc<dyn AnnotationOutbox>,
         audio_file_repository: Arc<dyn AudioFileRepository>,
         album_repository: Arc<dyn AlbumRepository>,
         artist_repository: Arc<dyn ArtistRepository>,
-        player_repository: Arc<dyn PlayerRepository>,
+        player_repository: Arc<dyn PlayerOutbox>,
         id_generator: Arc<dyn IdGenerator>,
-        event_bus: Arc<B>,
+        clock: Arc<dyn Clock>,
     ) -> Self {
         Self {
             media_annotation_repo,
+            outbox,
             audio_file_repository,
             album_repository,
             artist_repository,
             player_repository,
             id_generator,
-            event_bus,
+            clock,
+            submission_store: None,
+            library_repository: None,
         }
     }
+
+    /// 不设置时只在同一次请求内去重
+    pub fn with_submission_store(mut self, store: Arc<dyn ScrobbleSubmissionStore>) -> Self {
+        self.submission_store = Some(store);
+        self
+    }
+
+    /// 设置后有声书库的歌曲不计入播放记录
+    pub fn with_library_repository(mut self, repository: Arc<dyn LibraryRepository>) -> Self {
+        self.library_repository = Some(repository);
+        self
+    }
+
     pub async fn scrobble(&self, ctx: &AppContext, cmd: ScrobbleCmd) -> Result<(), AppError> {
+        // 私密收听：播放次数、播放历史和正在播放都不更新
+        if self.is_private(&cmd).await? {
+            return Ok(());
+        }
         if cmd.submission {
             self.scrobble_submission(ctx, cmd).await
         } else {
             self.scrobble_now_playing(ctx, cmd).await
         }
     }
+    async fn is_private(&self, cmd: &ScrobbleCmd) -> Result<bool, AppError> {
+        let player = self
+            .player_repository
+            .find_by_id(cmd.player_id.clone())
+            .await?;
+        Ok(player.is_some_and(|p| p.user_id == cmd.user_id && !p.scrobble_enabled))
+    }
+
     async fn scrobble_now_playing(
         &self,
         ctx: &AppContext,
         cmd: ScrobbleCmd,
     ) -> Result<(), AppError> {
+        let Some(item) = cmd.items.last() else {
+            return Ok(());
+        };
         let mut player = match self.player_repository.find_by_id(cmd.player_id).await? {
             Some(player) => player,
             None => {
@@ -110,20 +221,24 @@ impl<B: EventBus> MediaAnnotationService<B> {
                 )
             }
         };
-        player.play(cmd.audio_file_id.clone())?;
-        let events = player.pop_events();
-        self.player_repository.save(&mut player).await?;
-        for event in events {
-            let envelope = EventEnvelope::new(
-                event.aggregate_id(),
-                event.version(),
-                event,
-                ctx.correlation_id.clone(),
-                ctx.event_id.clone(),
-            );
-            self.event_bus.publish(envelope).await?;
-        }
-        Ok(())
+        player.play(item.audio_file_id.clone())?;
+        let events = player
+            .pop_events()
+            .into_iter()
+            .map(|event| {
+                EventEnvelope::new(
+                    event.aggregate_id(),
+                    event.version(),
+                    event,
+                    ctx.correlation_id.clone(),
+                    ctx.event_id.clone(),
+                )
+                .with_timestamp(self.clock.now())
+            })
+            .collect();
+        self.player_repository
+            .save_with_events(&mut player, events)
+            .await
     }
 
     async fn scrobble_submission(
@@ -131,99 +246,153 @@ impl<B: EventBus> MediaAnnotationService<B> {
         ctx: &AppContext,
         cmd: ScrobbleCmd,
     ) -> Result<(), AppError> {
-        let mut events = Vec::new();
-        let audio_file = self
-            .audio_file_repository
-            .find_by_id(&cmd.audio_file_id)
-            .await?
-            .ok_or(AppError::AudioFileError(AudioFileError::NotFound(
-                cmd.audio_file_id.clone(),
-            )))?;
-
-        match self
-            .media_annotation_repo
-            .find_by_item(Kind::AudioFile, cmd.audio_file_id.as_i64())
-            .await?
-        {
-            Some(mut annotation) => {
-                annotation.scrobble()?;
-                events.extend(annotation.pop_events());
-                self.media_annotation_repo.save(annotation).await?;
-            }
-            None => {
-                let mut annot = Annotation::new(
-                    AnnotationId::from(cmd.audio_file_id.as_i64()),
-                    cmd.user_id.clone(),
-                    Kind::AudioFile,
-                    cmd.audio_file_id.as_i64(),
-                );
-                annot.scrobble()?;
-                events.extend(annot.pop_events());
-                self.media_annotation_repo.save(annot).await?;
-            }
+        let ctx = ctx.inherit();
+        let single = cmd.items.len() == 1;
+        let now = self.clock.now().naive_utc();
+        let mut seen = HashSet::new();
+        let audiobooks = match &self.library_repository {
+            Some(repository) => audiobook_library_ids(repository.as_ref()).await?,
+            None => HashSet::new(),
         };
-        if let Some(album_id) = audio_file.album {
-            match self
-                .media_annotation_repo
-                .find_by_item(Kind::Album, album_id.as_i64())
+        for item in cmd.items {
+            let Some(audio_file) = self
+                .audio_file_repository
+                .find_by_id(&item.audio_file_id)
                 .await?
-            {
-                Some(mut annotation) => {
-                    annotation.scrobble()?;
-                    events.extend(annotation.pop_events());
-                    self.media_annotation_repo.save(annotation).await?;
-                }
-                None => {
-                    let mut annotation = Annotation::new(
-                        AnnotationId::from(album_id.as_i64()),
-                        cmd.user_id.clone(),
-                        Kind::Album,
-                        album_id.as_i64(),
-                    );
-                    annotation.scrobble()?;
-                    events.extend(annotation.pop_events());
-                    self.media_annotation_repo.save(annotation).await?;
+            else {
+                // 离线队列里的歌曲可能已被删除，不影响同批的其他播放
+                if single {
+                    return Err(AppError::AudioFileError(AudioFileError::NotFound(
+                        item.audio_file_id,
+                    )));
                 }
+                warn!(
+                    "Skipping scrobble of missing audio file {}",
+                    item.audio_file_id
+                );
+                continue;
+            };
+            if audiobooks.contains(&audio_file.library_id) {
+                continue;
             }
-        }
-        for participant in audio_file.participants {
-            match self
-                .media_annotation_repo
-                .find_by_item(Kind::Artist, participant.artist_id.as_i64())
-                .await?
-            {
-                Some(mut annotation) => {
-                    annotation.scrobble()?;
-                    events.extend(annotation.pop_events());
-                    self.media_annotation_repo.save(annotation).await?;
-                }
-                None => {
-                    let mut annotation = Annotation::new(
-                        AnnotationId::from(participant.artist_id.as_i64()),
-                        cmd.user_id.clone(),
-                        Kind::Artist,
-                        participant.artist_id.as_i64(),
-                    );
-                    annotation.scrobble()?;
-                    events.extend(annotation.pop_events());
-                    self.media_annotation_repo.save(annotation).await?;
+            let played_at = match item.played_at {
+                Some(played_at) => {
+                    if !seen.insert((item.audio_file_id.as_i64(), played_at)) {
+                        continue;
+                    }
+                    if let Some(store) = &self.submission_store {
+                        if !store
+                            .record(&cmd.user_id, &item.audio_file_id, played_at)
+                            .await?
+                        {
+                            continue;
+                        }
+                    }
+                    played_at.min(now)
                 }
-            }
+                None => now,
+            };
+            self.scrobble_audio_file(&ctx, &cmd.user_id, &audio_file, played_at)
+                .await?;
         }
-        let ctx = ctx.inherit();
-        for event in events {
-            let envelope = EventEnvelope::new(
-                event.aggregate_id(),
-                event.version(),
-                event,
-                ctx.correlation_id.clone(),
-                ctx.event_id.clone(),
-            );
-            self.event_bus.publish(envelope).await?;
+        Ok(())
+    }
+
+    /// 歌曲、所属专辑和参与艺术家的播放次数一起累加
+    async fn scrobble_audio_file(
+        &self,
+        ctx: &AppContext,
+        user_id: &UserId,
+        audio_file: &AudioFile,
+        played_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        let mut items = vec![(Kind::AudioFile, audio_file.id.as_i64())];
+        if let Some(album_id) = &audio_file.album {
+            items.push((Kind::Album, album_id.as_i64()));
+        }
+        for participant in &audio_file.participants {
+            items.push((Kind::Artist, participant.artist_id.as_i64()));
+        }
+        for (kind, item_id) in items {
+            let mut annotation = self.find_or_new(user_id, kind, item_id).await?;
+            annotation.scrobble(played_at)?;
+            self.save(ctx, annotation).await?;
         }
         Ok(())
     }
 
+    /// 查找用户对条目的注解，不存在时新建
+    async fn find_or_new(
+        &self,
+        user_id: &UserId,
+        kind: Kind,
+        item_id: i64,
+    ) -> Result<Annotation, AppError> {
+        if let Some(annotation) = self
+            .media_annotation_repo
+            .find_by_item(user_id, kind.clone(), item_id)
+            .await?
+        {
+            return Ok(annotation);
+        }
+        let id = self.id_generator.next_id().await?;
+        Ok(Annotation::new(
+            AnnotationId::from(id),
+            user_id.clone(),
+            kind,
+            item_id,
+            self.clock.now().naive_utc(),
+        ))
+    }
+
+    /// 定位 star/rating 的目标注解：客户端指明类型时直接使用，否则按 ID 推断
+    async fn resolve(
+        &self,
+        user_id: &UserId,
+        item_id: i64,
+        kind: Option<Kind>,
+    ) -> Result<Annotation, AppError> {
+        let kind = match kind {
+            Some(kind) => kind,
+            None => {
+                if let Some(annotation) = self
+                    .media_annotation_repo
+                    .find_by_item_id(user_id, item_id)
+                    .await?
+                {
+                    return Ok(annotation);
+                }
+                self.determine_item_kind(item_id).await?.ok_or_else(|| {
+                    AppError::AggregateNotFound(
+                        "Item".to_string(),
+                        format!("id {} not found in audio_file, album or artist", item_id),
+                    )
+                })?
+            }
+        };
+        self.find_or_new(user_id, kind, item_id).await
+    }
+
+    /// 保存注解，并把待发布事件写入同一事务的 outbox
+    async fn save(&self, ctx: &AppContext, mut annotation: Annotation) -> Result<(), AppError> {
+        let now = self.clock.now();
+        let events: Vec<EventEnvelope<AnnotationEvent>> = annotation
+            .pop_events()
+            .into_iter()
+            .map(|event| {
+                EventEnvelope::new(
+                    event.aggregate_id(),
+                    event.version(),
+                    event,
+                    ctx.correlation_id.clone(),
+                    ctx.event_id.clone(),
+                )
+                .with_timestamp(now)
+            })
+            .collect();
+        self.outbox.save_with_events(annotation, events).await
+    }
+
     /// 根据 ID 确定实体类型（AudioFile、Album 或 Artist）
     async fn determine_item_kind(&self, item_id: i64) -> Result<Option<Kind>, AppError> {
         // 先查询 AudioFile
@@ -260,134 +429,130 @@ impl<B: EventBus> MediaAnnotationService<B> {
     }
 
     pub async fn star(&self, ctx: &AppContext, cmd: StarCmd) -> Result<(), AppError> {
-        let mut all_events = Vec::new();
-        for item in cmd.items {
-            // 先查找已存在的 annotation
-            let mut media_annotation = match self
-                .media_annotation_repo
-                .find_by_item_id(item.item_id)
-                .await?
-            {
-                Some(annotation) => annotation,
-                None => {
-                    // 不存在时，需要确定 item 类型
-                    let item_kind =
-                        self.determine_item_kind(item.item_id)
-                            .await?
-                            .ok_or_else(|| {
-                                AppError::AggregateNotFound(
-                                    "Item".to_string(),
-                                    format!(
-                                        "id {} not found in audio_file, album or artist",
-                                        item.item_id
-                                    ),
-                                )
-                            })?;
-
-                    let id = self.id_generator.next_id().await?;
-                    Annotation::new(
-                        AnnotationId::from(id),
-                        cmd.user_id.clone(),
-                        item_kind,
-                        item.item_id,
-                    )
-                }
-            };
-            media_annotation.set_star()?;
-            all_events.extend(media_annotation.pop_events());
-            self.media_annotation_repo.save(media_annotation).await?;
-        }
         let ctx = ctx.inherit();
-        for event in all_events {
-            let envelope = EventEnvelope::new(
-                event.aggregate_id(),
-                event.version(),
-                event,
-                ctx.correlation_id.clone(),
-                ctx.event_id.clone(),
-            );
-            self.event_bus.publish(envelope).await?;
+        for item in cmd.items {
+            let mut media_annotation = self.resolve(&cmd.user_id, item.item_id, item.kind).await?;
+            media_annotation.set_star(self.clock.now().naive_utc())?;
+            self.save(&ctx, media_annotation).await?;
         }
         Ok(())
     }
 
     pub async fn unstar(&self, ctx: &AppContext, cmd: UnstarCmd) -> Result<(), AppError> {
-        let mut all_events = Vec::new();
+        let ctx = ctx.inherit();
         for item in cmd.items {
-            let mut media_annotation = match self
-                .media_annotation_repo
-                .find_by_item_id(item.item_id)
-                .await?
-            {
-                Some(annotation) => annotation,
+            let existing = match item.kind {
+                Some(kind) => {
+                    self.media_annotation_repo
+                        .find_by_item(&cmd.user_id, kind, item.item_id)
+                        .await?
+                }
                 None => {
-                    // unstar 时如果不存在，不需要创建新的 annotation
-                    continue;
+                    self.media_annotation_repo
+                        .find_by_item_id(&cmd.user_id, item.item_id)
+                        .await?
                 }
             };
-            media_annotation.unset_star()?;
-            all_events.extend(media_annotation.pop_events());
-            self.media_annotation_repo.save(media_annotation).await?;
-        }
-        let ctx = ctx.inherit();
-        for event in all_events {
-            let envelope = EventEnvelope::new(
-                event.aggregate_id(),
-                event.version(),
-                event,
-                ctx.correlation_id.clone(),
-                ctx.event_id.clone(),
-            );
-            self.event_bus.publish(envelope).await?;
+            // unstar 时如果不存在，不需要创建新的 annotation
+            let Some(mut media_annotation) = existing else {
+                continue;
+            };
+            media_annotation.unset_star(self.clock.now().naive_utc())?;
+            self.save(&ctx, media_annotation).await?;
         }
         Ok(())
     }
 
     pub async fn set_rating(&self, ctx: &AppContext, cmd: SetRatingCmd) -> Result<(), AppError> {
-        let mut media_annotation = match self
-            .media_annotation_repo
-            .find_by_item_id(cmd.item_id)
-            .await?
-        {
-            Some(annotation) => annotation,
-            None => {
-                // 不存在时，需要确定 item 类型
-                let item_kind =
-                    self.determine_item_kind(cmd.item_id)
-                        .await?
-                        .ok_or_else(|| {
-                            AppError::AggregateNotFound(
-                                "Item".to_string(),
-                                format!(
-                                    "id {} not found in audio_file, album or artist",
-                                    cmd.item_id
-                                ),
-                            )
-                        })?;
-
-                let id = self.id_generator.next_id().await?;
-                Annotation::new(
-                    AnnotationId::from(id),
-                    cmd.user_id.clone(),
-                    item_kind,
-                    cmd.item_id,
-                )
-            }
-        };
+        let mut media_annotation = self.resolve(&cmd.user_id, cmd.item_id, cmd.kind).await?;
         media_annotation.set_rating(cmd.rating)?;
-        let events = media_annotation.pop_events();
-        self.media_annotation_repo.save(media_annotation).await?;
+        self.save(&ctx.inherit(), media_annotation).await
+    }
+
+    /// 批量写入同步工具提交的注解，以 updated_at 较新的一方为准
+    pub async fn sync_annotations(
+        &self,
+        ctx: &AppContext,
+        cmd: SyncAnnotationsCmd,
+    ) -> Result<SyncAnnotationsReport, AppError> {
         let ctx = ctx.inherit();
-        for event in events {
-            let envelope = EventEnvelope::new(
-                event.aggregate_id(),
-                event.version(),
-                event,
-                ctx.correlation_id.clone(),
-                ctx.event_id.clone(),
-            );
-            self.event_bus.publish(envelope).await?;
+        let mut report = SyncAnnotationsReport::default();
+        for item in cmd.items {
+            let existing = self
+                .media_annotation_repo
+                .find_by_item(&cmd.user_id, item.kind.clone(), item.item_id)
+                .await?;
+            let mut annotation = match existing {
+                Some(annotation) => annotation,
+                None => {
+                    if !self.item_exists(&item.kind, item.item_id).await? {
+                        report.not_found.push((item.kind, item.item_id));
+                        continue;
+                    }
+                    let id = self.id_generator.next_id().await?;
+                    Annotation::new(
+                        AnnotationId::from(id),
+                        cmd.user_id.clone(),
+                        item.kind.clone(),
+                        item.item_id,
+                        self.clock.now().naive_utc(),
+                    )
+                }
+            };
+            if let (Some(client), Some(server)) = (item.updated_at, annotation.updated_at) {
+                if server > client {
+                    report.conflicts.push((item.kind, item.item_id));
+                    continue;
+                }
+            }
+            let version = annotation.version;
+            annotation.sync(
+                item.starred,
+                item.starred_at,
+                item.rating,
+                item.play_count,
+                item.played_at,
+                self.clock.now().naive_utc(),
+            )?;
+            if annotation.version == version {
+                report.unchanged += 1;
+                continue;
+            }
+            self.save(&ctx, annotation).await?;
+            report.applied += 1;
         }
-        Ok(())
+        Ok(report)
+    }
+
+    async fn item_exists(&self, kind: &Kind, item_id: i64) -> Result<bool, AppError> {
+        Ok(match kind {
+            Kind::AudioFile => self
+                .audio_file_repository
+                .find_by_id(&AudioFileId::from(item_id))
+                .await?
+                .is_some(),
+            Kind::Album => self
+                .album_repository
+                .by_id(AlbumId::from(item_id))
+                .await?
+                .is_some(),
+            Kind::Artist => self
+                .artist_repository
+                .by_id(ArtistId::from(item_id))
+                .await?
+                .is_some(),
+            Kind::Playlist => false,
+        })
+    }
+
+    /// 合并导入的注解，重复导入同一份数据不会改变结果
+    pub async fn import_annotation(
+        &self,
+        ctx: &AppContext,
+        cmd: ImportAnnotationCmd,
+    ) -> Result<(), AppError> {
+        let mut annotation = self.find_or_new(&cmd.user_id, cmd.kind, cmd.item_id).await?;
+        annotation.import(cmd.starred_at, cmd.rating, cmd.play_count, cmd.played_at)?;
+        self.save(&ctx.inherit(), annotation).await
     }
 }
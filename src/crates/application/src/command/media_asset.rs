@@ -0,0 +1,49 @@
+use crate::command::shared::IdGenerator;
+use crate::error::AppError;
+use domain::media_asset::{MediaAsset, MediaAssetError, MediaAssetKind, MediaAssetRepository};
+use domain::value::{FileMeta, LibraryId, MediaPath};
+use std::sync::Arc;
+
+/// 维护扫描时收录的非音频资源
+#[derive(Clone)]
+pub struct MediaAssetService {
+    media_asset_repository: Arc<dyn MediaAssetRepository>,
+    id_generator: Arc<dyn IdGenerator>,
+}
+
+impl MediaAssetService {
+    pub fn new(
+        media_asset_repository: Arc<dyn MediaAssetRepository>,
+        id_generator: Arc<dyn IdGenerator>,
+    ) -> Self {
+        Self {
+            media_asset_repository,
+            id_generator,
+        }
+    }
+
+    /// 收录资源文件，返回是否收录；音频等其他文件直接忽略
+    pub async fn index(&self, library_id: LibraryId, file: &FileMeta) -> Result<bool, AppError> {
+        if MediaAssetKind::classify(&file.path.path, &file.suffix).is_none() {
+            return Ok(false);
+        }
+        let id = self.id_generator.next_id().await?;
+        let Some(asset) = MediaAsset::from_file(id, library_id, file) else {
+            return Ok(false);
+        };
+        self.media_asset_repository.save(&asset).await?;
+        Ok(true)
+    }
+
+    pub async fn remove(&self, path: &MediaPath) -> Result<(), AppError> {
+        Ok(self.media_asset_repository.delete_by_path(path).await?)
+    }
+}
+
+impl From<MediaAssetError> for AppError {
+    fn from(e: MediaAssetError) -> Self {
+        match e {
+            MediaAssetError::DbErr(msg) => AppError::RepositoryError("MediaAsset".to_string(), msg),
+        }
+    }
+}
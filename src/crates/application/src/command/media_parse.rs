@@ -1,11 +1,19 @@
+use crate::command::artwork::{extract_embedded_artwork, EmbeddedArtwork, ThumbnailStore};
+use crate::command::shared::IdGenerator;
 use crate::context::AppContext;
 use crate::error::AppError;
 use crate::event::event_bus::{CorrelationId, EventBus, EventEnvelope};
-use crate::event::events::{AppEvent, AudioFileParsed, ImageFileParsed};
+use crate::event::events::{AppEvent, AudioFileParseFailed, AudioFileParsed, ImageFileParsed};
 use domain::cover_art::CoverSourceType;
+use domain::scan_error::{ScanError, ScanErrorRepository};
 use domain::value::{AudioMetadata, FileMeta, FileType, LibraryId, MediaPath};
+use log::{info, warn};
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::io::AsyncRead;
+
+/// 顺序读取的文件内容
+pub type MediaReader = Box<dyn AsyncRead + Send + Unpin>;
 
 #[async_trait::async_trait]
 pub trait AudioMetadataReader: Send + Sync {
@@ -19,6 +27,17 @@ pub trait StorageClient: Send + Sync {
     async fn list(&self, path: &MediaPath) -> Result<Vec<FileMeta>, AppError>;
     async fn read(&self, path: &MediaPath) -> Result<Vec<u8>, AppError>;
     async fn exists(&self, path: &MediaPath) -> Result<bool, AppError>;
+    /// 从 offset 开始顺序读取文件，用于播放和转码输入；远程存储应预读后续的数据。
+    /// 默认一次读入整个文件
+    async fn open(&self, path: &MediaPath, offset: u64) -> Result<MediaReader, AppError> {
+        let mut data = self.read(path).await?;
+        let data = data.split_off((offset as usize).min(data.len()));
+        Ok(Box::new(std::io::Cursor::new(data)))
+    }
+    /// 校验路径格式是否适用于该存储，不访问存储本身
+    fn validate_path(&self, _path: &MediaPath) -> Result<(), AppError> {
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -38,6 +57,9 @@ pub struct MediaFileParseService<B: EventBus> {
     event_bus: Arc<B>,
     storage_client_factory: Arc<dyn StorageClientFactory>,
     audio_metadata_reader: Arc<dyn AudioMetadataReader>,
+    scan_error_repository: Arc<dyn ScanErrorRepository>,
+    id_generator: Arc<dyn IdGenerator>,
+    thumbnail_store: Arc<dyn ThumbnailStore>,
 }
 
 impl<B: EventBus> MediaFileParseService<B> {
@@ -45,17 +67,92 @@ impl<B: EventBus> MediaFileParseService<B> {
         event_bus: Arc<B>,
         storage_client_factory: Arc<dyn StorageClientFactory>,
         audio_metadata_reader: Arc<dyn AudioMetadataReader>,
+        scan_error_repository: Arc<dyn ScanErrorRepository>,
+        id_generator: Arc<dyn IdGenerator>,
+        thumbnail_store: Arc<dyn ThumbnailStore>,
     ) -> Self {
         Self {
             event_bus,
             storage_client_factory,
             audio_metadata_reader,
+            scan_error_repository,
+            id_generator,
+            thumbnail_store,
         }
     }
     async fn parse_audio_file(&self, local_path: &PathBuf) -> Result<AudioMetadata, AppError> {
         let metadata = self.audio_metadata_reader.parse(local_path.clone()).await?;
         Ok(metadata)
     }
+
+    /// 提取失败不影响入库，读取封面时回退到音频文件
+    async fn extract_artwork(
+        &self,
+        filemeta: &FileMeta,
+        picture: &[u8],
+    ) -> Option<EmbeddedArtwork> {
+        match extract_embedded_artwork(self.thumbnail_store.as_ref(), filemeta, picture.to_vec())
+            .await
+        {
+            Ok(artwork) => Some(artwork),
+            Err(e) => {
+                warn!(
+                    "Failed to extract embedded cover of {}: {}",
+                    filemeta.path.path, e
+                );
+                None
+            }
+        }
+    }
+
+    async fn find_scan_error(&self, filemeta: &FileMeta) -> Result<Option<ScanError>, AppError> {
+        self.scan_error_repository
+            .find_by_path(&filemeta.path)
+            .await
+            .map_err(|e| AppError::RepositoryError("ScanError".to_string(), e.to_string()))
+    }
+
+    /// 记录解析失败，同一路径重复失败时累加次数
+    async fn record_scan_error(
+        &self,
+        existing: Option<ScanError>,
+        cmd: &ParseMediaFileCmd,
+        error: &AppError,
+    ) -> Result<(), AppError> {
+        let scan_error = match existing {
+            Some(mut scan_error) => {
+                scan_error.record_failure(&cmd.filemeta, error.to_string());
+                scan_error
+            }
+            None => ScanError::new(
+                self.id_generator.next_id().await?,
+                cmd.library_id.clone(),
+                &cmd.filemeta,
+                error.to_string(),
+            ),
+        };
+        self.scan_error_repository
+            .save(scan_error)
+            .await
+            .map_err(|e| AppError::RepositoryError("ScanError".to_string(), e.to_string()))
+    }
+
+    async fn publish_parse_failed(
+        &self,
+        ctx: &AppContext,
+        cmd: &ParseMediaFileCmd,
+        error: String,
+    ) -> Result<(), AppError> {
+        let event = AppEvent::AudioFileParseFailed(AudioFileParseFailed {
+            library_id: cmd.library_id.clone(),
+            file_info: cmd.filemeta.clone(),
+            error,
+        });
+        let envelope = EventEnvelope::new(0, 0, event, CorrelationId::new(), ctx.event_id.clone());
+        self.event_bus.publish(envelope).await?;
+        Ok(())
+    }
+
     pub async fn parse_media_file(
         &self,
         ctx: &AppContext,
@@ -69,17 +166,51 @@ impl<B: EventBus> MediaFileParseService<B> {
         let mut app_events = Vec::new();
         match cmd.file_type {
             FileType::Audio => {
-                let metadata = self.parse_audio_file(&local_path).await?;
+                let scan_error = self.find_scan_error(&cmd.filemeta).await?;
+                if let Some(scan_error) = &scan_error {
+                    if scan_error.is_unchanged(&cmd.filemeta) {
+                        info!(
+                            "Skip quarantined file {}: {}",
+                            cmd.filemeta.path.path, scan_error.error
+                        );
+                        // 跳过的文件同样计入失败数，扫描进度才能走完
+                        return self
+                            .publish_parse_failed(ctx, &cmd, scan_error.error.clone())
+                            .await;
+                    }
+                }
+                let metadata = match self.parse_audio_file(&local_path).await {
+                    Ok(metadata) => {
+                        if let Some(scan_error) = scan_error {
+                            // 文件修复后解析成功，移出隔离列表
+                            if let Err(e) = self.scan_error_repository.delete(scan_error.id).await
+                            {
+                                warn!("Failed to clear scan error: {}", e);
+                            }
+                        }
+                        metadata
+                    }
+                    Err(e) => {
+                        if let Err(err) = self.record_scan_error(scan_error, &cmd, &e).await {
+                            warn!("Failed to record scan error: {}", err);
+                        }
+                        // 通知扫描进度统计失败数，再把错误返回给调用方
+                        self.publish_parse_failed(ctx, &cmd, e.to_string()).await?;
+                        return Err(e);
+                    }
+                };
                 app_events.push(AppEvent::AudioFileParsed(AudioFileParsed {
                     library_id: cmd.library_id.clone(),
                     metadata: metadata.clone(),
                     file_info: cmd.filemeta.clone(),
                 }));
-                if metadata.picture.is_some() {
+                if let Some(picture) = &metadata.picture {
+                    let artwork = self.extract_artwork(&cmd.filemeta, picture).await;
                     app_events.push(AppEvent::ImageFileParsed(ImageFileParsed {
                         library_id: cmd.library_id.clone(),
                         file_info: cmd.filemeta.clone(),
                         source: CoverSourceType::Embedded,
+                        artwork,
                     }));
                 }
             }
@@ -88,6 +219,7 @@ impl<B: EventBus> MediaFileParseService<B> {
                     library_id: cmd.library_id.clone(),
                     file_info: cmd.filemeta.clone(),
                     source: CoverSourceType::External,
+                    artwork: None,
                 }));
             }
             _ => {
@@ -0,0 +1,817 @@
+use crate::command::album::AlbumNameNormalizer;
+use crate::command::artist::ArtistNameNormalizer;
+use crate::command::shared::IdGenerator;
+use crate::context::AppContext;
+use crate::error::AppError;
+use crate::event::event_bus::{EventBus, EventEnvelope};
+use async_trait::async_trait;
+use domain::album::{Album, AlbumRepository};
+use domain::annotation::Kind;
+use domain::artist::{Artist, ArtistRepository};
+use domain::audio_file::{AudioFile, AudioFileRepository};
+use domain::value::{AlbumId, ArtistId, AudioFileId, Participant, ParticipantWorkType};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct MergeAlbumsCmd {
+    /// 被合并（合并后删除）的专辑
+    pub source_id: AlbumId,
+    /// 保留的专辑
+    pub target_id: AlbumId,
+}
+
+#[derive(Debug)]
+pub struct MergeArtistsCmd {
+    pub source_id: ArtistId,
+    pub target_id: ArtistId,
+}
+
+#[derive(Debug)]
+pub struct SplitAlbumCmd {
+    pub album_id: AlbumId,
+    /// 需要拆分出去的歌曲
+    pub audio_file_ids: Vec<AudioFileId>,
+    /// 新专辑名称，已存在同名专辑时移入该专辑
+    pub new_name: String,
+}
+
+#[derive(Debug)]
+pub struct SplitArtistCmd {
+    pub artist_id: ArtistId,
+    pub audio_file_ids: Vec<AudioFileId>,
+    pub new_name: String,
+}
+
+/// 一次合并或拆分产生的全部修改，由 MergeStore 在同一个事务中写入
+#[derive(Default)]
+pub struct MergeChanges {
+    pub audio_files: Vec<AudioFile>,
+    pub albums: Vec<Album>,
+    pub artists: Vec<Artist>,
+    /// (条目类型, 源条目, 目标条目)
+    pub reassigned_annotations: Vec<(Kind, i64, i64)>,
+    pub deleted_albums: Vec<AlbumId>,
+    pub deleted_artists: Vec<ArtistId>,
+}
+
+#[async_trait]
+pub trait MergeStore: Send + Sync {
+    /// 在一个事务中保存聚合、转移注解并删除被合并的条目，
+    /// 返回保存后的聚合，顺序与传入时一致
+    async fn commit(&self, changes: MergeChanges) -> Result<MergeChanges, AppError>;
+}
+
+/// 合并与拆分读取数据使用的仓储
+pub struct MergeRepositories {
+    pub audio_file: Arc<dyn AudioFileRepository>,
+    pub album: Arc<dyn AlbumRepository>,
+    pub artist: Arc<dyn ArtistRepository>,
+}
+
+/// 专辑/艺术家合并与拆分
+///
+/// 修改先在内存中的聚合上完成，再通过 MergeStore 一次提交；
+/// 提交成功后按歌曲、专辑、艺术家的顺序发布领域事件，统计类投影随事件更新
+pub struct MergeService<B: EventBus> {
+    id_generator: Arc<dyn IdGenerator>,
+    audio_file_repository: Arc<dyn AudioFileRepository>,
+    album_repository: Arc<dyn AlbumRepository>,
+    artist_repository: Arc<dyn ArtistRepository>,
+    store: Arc<dyn MergeStore>,
+    album_name_normalizer: Arc<dyn AlbumNameNormalizer>,
+    artist_name_normalizer: Arc<dyn ArtistNameNormalizer>,
+    event_bus: Arc<B>,
+}
+
+impl<B: EventBus> MergeService<B> {
+    pub fn new(
+        id_generator: Arc<dyn IdGenerator>,
+        repositories: MergeRepositories,
+        store: Arc<dyn MergeStore>,
+        album_name_normalizer: Arc<dyn AlbumNameNormalizer>,
+        artist_name_normalizer: Arc<dyn ArtistNameNormalizer>,
+        event_bus: Arc<B>,
+    ) -> Self {
+        Self {
+            id_generator,
+            audio_file_repository: repositories.audio_file,
+            album_repository: repositories.album,
+            artist_repository: repositories.artist,
+            store,
+            album_name_normalizer,
+            artist_name_normalizer,
+            event_bus,
+        }
+    }
+
+    pub async fn merge_albums(
+        &self,
+        context: &AppContext,
+        cmd: MergeAlbumsCmd,
+    ) -> Result<Album, AppError> {
+        if cmd.source_id == cmd.target_id {
+            return Err(AppError::InvalidInput(
+                "Cannot merge an album into itself".to_string(),
+            ));
+        }
+        let mut source = self.load_album(&cmd.source_id).await?;
+        let mut target = self.load_album(&cmd.target_id).await?;
+        let mut changes = MergeChanges::default();
+
+        // 歌曲改绑到目标专辑
+        for mut audio_file in self
+            .audio_file_repository
+            .find_by_album(&cmd.source_id)
+            .await?
+        {
+            audio_file.unbind_from_album()?;
+            audio_file.bind_to_album(cmd.target_id.clone())?;
+            changes.audio_files.push(audio_file);
+        }
+
+        // 专辑参与者与流派
+        for participant in source.participants.clone() {
+            source.remove_participant(&participant)?;
+            target.add_participant(Participant {
+                work_id: cmd.target_id.as_i64(),
+                ..participant
+            })?;
+        }
+        // 源专辑要先解绑流派，否则删除后 genre_stats 的专辑数不会减少
+        for genre_id in source.genres.clone() {
+            target.bind_to_genre(genre_id.clone())?;
+            source.unbind_from_genre(genre_id)?;
+        }
+
+        changes.albums.push(source);
+        changes.albums.push(target);
+        changes.reassigned_annotations.push((
+            Kind::Album,
+            cmd.source_id.as_i64(),
+            cmd.target_id.as_i64(),
+        ));
+        changes.deleted_albums.push(cmd.source_id);
+        last(self.commit(context, changes).await?.albums)
+    }
+
+    pub async fn merge_artists(
+        &self,
+        context: &AppContext,
+        cmd: MergeArtistsCmd,
+    ) -> Result<Artist, AppError> {
+        if cmd.source_id == cmd.target_id {
+            return Err(AppError::InvalidInput(
+                "Cannot merge an artist into itself".to_string(),
+            ));
+        }
+        let source = self.load_artist(&cmd.source_id).await?;
+        let mut target = self.load_artist(&cmd.target_id).await?;
+        let mut changes = MergeChanges::default();
+
+        let audio_files = self
+            .audio_file_repository
+            .find_by_participant(&cmd.source_id)
+            .await?;
+        let album_ids: HashSet<AlbumId> =
+            audio_files.iter().filter_map(|f| f.album.clone()).collect();
+        for audio_file in audio_files {
+            changes.audio_files.push(reassign_audio_file(
+                audio_file,
+                &cmd.source_id,
+                &cmd.target_id,
+            )?);
+        }
+        for album_id in album_ids {
+            let mut album = self.load_album(&album_id).await?;
+            for participant in album.participants.clone() {
+                if participant.artist_id == cmd.source_id {
+                    album.remove_participant(&participant)?;
+                    album.add_participant(Participant {
+                        artist_id: cmd.target_id.clone(),
+                        ..participant
+                    })?;
+                }
+            }
+            changes.albums.push(album);
+        }
+
+        for genre_id in source.genres.clone() {
+            target.bind_to_genre(genre_id)?;
+        }
+        changes.artists.push(target);
+        changes.reassigned_annotations.push((
+            Kind::Artist,
+            cmd.source_id.as_i64(),
+            cmd.target_id.as_i64(),
+        ));
+        changes.deleted_artists.push(cmd.source_id);
+        last(self.commit(context, changes).await?.artists)
+    }
+
+    pub async fn split_album(
+        &self,
+        context: &AppContext,
+        cmd: SplitAlbumCmd,
+    ) -> Result<Album, AppError> {
+        let name = cmd.new_name.trim().to_string();
+        if name.is_empty() || cmd.audio_file_ids.is_empty() {
+            return Err(AppError::InvalidInput(
+                "name and songs are required".to_string(),
+            ));
+        }
+        let mut source = self.load_album(&cmd.album_id).await?;
+        let sort_name = self.album_name_normalizer.normalize(&name);
+        let mut target = match self.album_repository.find_by_sort_name(&sort_name).await? {
+            Some(album) if album.id == source.id => {
+                return Err(AppError::InvalidInput(
+                    "New album name matches the source album".to_string(),
+                ));
+            }
+            Some(album) => album,
+            None => {
+                let mut album =
+                    Album::new(self.id_generator.next_id().await?.into(), name, sort_name);
+                // 手动创建的专辑不走扫描绑定流程，Created 事件只会触发扫描协调器
+                album.take_events();
+                album
+            }
+        };
+
+        let split_ids: HashSet<AudioFileId> = cmd.audio_file_ids.into_iter().collect();
+        let (moving, remaining): (Vec<AudioFile>, Vec<AudioFile>) = self
+            .audio_file_repository
+            .find_by_album(&cmd.album_id)
+            .await?
+            .into_iter()
+            .partition(|f| split_ids.contains(&f.id));
+        if moving.len() != split_ids.len() {
+            return Err(AppError::InvalidInput(
+                "Some songs do not belong to the album".to_string(),
+            ));
+        }
+
+        let mut changes = MergeChanges::default();
+        for mut audio_file in moving {
+            for participant in &audio_file.participants {
+                target.add_participant(Participant {
+                    work_id: target.id.as_i64(),
+                    work_type: ParticipantWorkType::Album,
+                    ..participant.clone()
+                })?;
+            }
+            for genre_id in &audio_file.genres {
+                target.bind_to_genre(genre_id.clone())?;
+            }
+            audio_file.unbind_from_album()?;
+            audio_file.bind_to_album(target.id.clone())?;
+            changes.audio_files.push(audio_file);
+        }
+
+        // 原专辑中已没有对应歌曲的参与者一并移除
+        for participant in source.participants.clone() {
+            let still_present = remaining.iter().any(|f| {
+                f.participants
+                    .iter()
+                    .any(|p| p.artist_id == participant.artist_id && p.role == participant.role)
+            });
+            if !still_present {
+                source.remove_participant(&participant)?;
+            }
+        }
+        for genre_id in source.genres.clone() {
+            if !remaining.iter().any(|f| f.genres.contains(&genre_id)) {
+                source.unbind_from_genre(genre_id)?;
+            }
+        }
+
+        changes.albums.push(source);
+        changes.albums.push(target);
+        last(self.commit(context, changes).await?.albums)
+    }
+
+    pub async fn split_artist(
+        &self,
+        context: &AppContext,
+        cmd: SplitArtistCmd,
+    ) -> Result<Artist, AppError> {
+        let name = cmd.new_name.trim().to_string();
+        if name.is_empty() || cmd.audio_file_ids.is_empty() {
+            return Err(AppError::InvalidInput(
+                "name and songs are required".to_string(),
+            ));
+        }
+        let source = self.load_artist(&cmd.artist_id).await?;
+        let sort_name = self.artist_name_normalizer.normalize(&name);
+        let mut changes = MergeChanges::default();
+        let target = match self.artist_repository.find_by_sort_name(&sort_name).await? {
+            Some(artist) if artist.id == source.id => {
+                return Err(AppError::InvalidInput(
+                    "New artist name matches the source artist".to_string(),
+                ));
+            }
+            Some(artist) => artist,
+            None => {
+                let mut artist =
+                    Artist::new(self.id_generator.next_id().await?.into(), name, sort_name);
+                artist.take_events();
+                changes.artists.push(artist.clone());
+                artist
+            }
+        };
+
+        let split_ids: HashSet<AudioFileId> = cmd.audio_file_ids.into_iter().collect();
+        let moving: Vec<AudioFile> = self
+            .audio_file_repository
+            .find_by_participant(&cmd.artist_id)
+            .await?
+            .into_iter()
+            .filter(|f| split_ids.contains(&f.id))
+            .collect();
+        if moving.len() != split_ids.len() {
+            return Err(AppError::InvalidInput(
+                "Some songs do not belong to the artist".to_string(),
+            ));
+        }
+
+        let album_ids: HashSet<AlbumId> = moving.iter().filter_map(|f| f.album.clone()).collect();
+        for audio_file in moving {
+            changes
+                .audio_files
+                .push(reassign_audio_file(audio_file, &cmd.artist_id, &target.id)?);
+        }
+
+        // 专辑参与者：加入新艺术家，原艺术家在该专辑已无歌曲时移除
+        for album_id in album_ids {
+            let mut album = self.load_album(&album_id).await?;
+            // 仓储中的歌曲还是拆分前的状态，拆出去的歌曲不再算作原艺术家的
+            let still_present = self
+                .audio_file_repository
+                .find_by_album(&album_id)
+                .await?
+                .iter()
+                .filter(|f| !split_ids.contains(&f.id))
+                .any(|f| f.participants.iter().any(|p| p.artist_id == cmd.artist_id));
+            for participant in album.participants.clone() {
+                if participant.artist_id != cmd.artist_id {
+                    continue;
+                }
+                album.add_participant(Participant {
+                    artist_id: target.id.clone(),
+                    ..participant.clone()
+                })?;
+                if !still_present {
+                    album.remove_participant(&participant)?;
+                }
+            }
+            changes.albums.push(album);
+        }
+
+        let created = !changes.artists.is_empty();
+        let saved = self.commit(context, changes).await?;
+        if created {
+            last(saved.artists)
+        } else {
+            Ok(target)
+        }
+    }
+
+    async fn load_album(&self, id: &AlbumId) -> Result<Album, AppError> {
+        self.album_repository
+            .by_id(id.clone())
+            .await?
+            .ok_or_else(|| AppError::AggregateNotFound("Album".to_string(), id.to_string()))
+    }
+
+    async fn load_artist(&self, id: &ArtistId) -> Result<Artist, AppError> {
+        self.artist_repository
+            .by_id(id.clone())
+            .await?
+            .ok_or_else(|| AppError::AggregateNotFound("Artist".to_string(), id.to_string()))
+    }
+
+    /// 取出事件后一次提交所有修改，提交成功后再用保存后的版本号发布事件
+    async fn commit(
+        &self,
+        context: &AppContext,
+        mut changes: MergeChanges,
+    ) -> Result<MergeChanges, AppError> {
+        let audio_file_events: Vec<_> = changes
+            .audio_files
+            .iter_mut()
+            .map(|f| f.take_events())
+            .collect();
+        let album_events: Vec<_> = changes.albums.iter_mut().map(|a| a.take_events()).collect();
+        let artist_events: Vec<_> = changes
+            .artists
+            .iter_mut()
+            .map(|a| a.take_events())
+            .collect();
+
+        let saved = self.store.commit(changes).await?;
+
+        for (audio_file, events) in saved.audio_files.iter().zip(audio_file_events) {
+            self.publish(context, audio_file.id.as_i64(), audio_file.version, events)
+                .await?;
+        }
+        for (album, events) in saved.albums.iter().zip(album_events) {
+            self.publish(context, album.id.as_i64(), album.version, events)
+                .await?;
+        }
+        for (artist, events) in saved.artists.iter().zip(artist_events) {
+            self.publish(context, artist.id.as_i64(), artist.version, events)
+                .await?;
+        }
+        Ok(saved)
+    }
+
+    async fn publish<E: Send + Sync + 'static>(
+        &self,
+        context: &AppContext,
+        aggregate_id: i64,
+        version: i64,
+        events: Vec<E>,
+    ) -> Result<(), AppError> {
+        for event in events {
+            let envelope = EventEnvelope::new(
+                aggregate_id,
+                version,
+                event,
+                context.correlation_id.clone(),
+                context.event_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 将音频文件中 from 艺术家的参与关系改为 to 艺术家
+fn reassign_audio_file(
+    mut audio_file: AudioFile,
+    from: &ArtistId,
+    to: &ArtistId,
+) -> Result<AudioFile, AppError> {
+    let was_primary = audio_file.artist.as_ref() == Some(from);
+    for participant in audio_file.participants.clone() {
+        if &participant.artist_id != from {
+            continue;
+        }
+        audio_file.remove_participant(participant.clone())?;
+        audio_file.add_participant(Participant {
+            artist_id: to.clone(),
+            ..participant
+        })?;
+    }
+    if was_primary {
+        audio_file.artist = Some(to.clone());
+    }
+    Ok(audio_file)
+}
+
+/// 目标聚合总是最后加入 MergeChanges
+fn last<T>(mut saved: Vec<T>) -> Result<T, AppError> {
+    saved
+        .pop()
+        .ok_or_else(|| AppError::UnknownError("Merge store returned no aggregate".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::SequentialIdGenerator;
+    use crate::projector::album_stats::AlbumStatsProjector;
+    use crate::test_support::{
+        participant, song, LowercaseNormalizer, MemoryAlbumRepository, MemoryAlbumStatsRepository,
+        MemoryArtistRepository, MemoryAudioFileRepository, RecordingEventBus,
+    };
+    use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
+    use model::album_stats::{AlbumStatsAdjustment, AlbumStatsRepository};
+    use std::sync::Mutex;
+
+    /// 把修改写回内存仓储；`fail` 为 true 时模拟事务回滚，什么都不写
+    struct MemoryMergeStore {
+        audio_files: Arc<MemoryAudioFileRepository>,
+        albums: Arc<MemoryAlbumRepository>,
+        artists: Arc<MemoryArtistRepository>,
+        annotations: Mutex<Vec<(Kind, i64, i64)>>,
+        commits: Mutex<usize>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl MergeStore for MemoryMergeStore {
+        async fn commit(&self, changes: MergeChanges) -> Result<MergeChanges, AppError> {
+            if self.fail {
+                return Err(AppError::UnknownError("connection lost".to_string()));
+            }
+            *self.commits.lock().unwrap() += 1;
+            for audio_file in &changes.audio_files {
+                self.audio_files.save(audio_file.clone()).await?;
+            }
+            for album in &changes.albums {
+                self.albums.save(album.clone()).await?;
+            }
+            for artist in &changes.artists {
+                self.artists.save(artist.clone()).await?;
+            }
+            self.annotations
+                .lock()
+                .unwrap()
+                .extend(changes.reassigned_annotations.iter().cloned());
+            for album_id in &changes.deleted_albums {
+                self.albums.delete(album_id.clone()).await?;
+            }
+            for artist_id in &changes.deleted_artists {
+                self.artists.delete(artist_id.clone()).await?;
+            }
+            Ok(changes)
+        }
+    }
+
+    struct Fixture {
+        audio_files: Arc<MemoryAudioFileRepository>,
+        albums: Arc<MemoryAlbumRepository>,
+        artists: Arc<MemoryArtistRepository>,
+        store: Arc<MemoryMergeStore>,
+        event_bus: Arc<RecordingEventBus>,
+        service: MergeService<RecordingEventBus>,
+    }
+
+    impl Fixture {
+        fn new(fail: bool) -> Self {
+            let audio_files = Arc::new(MemoryAudioFileRepository::default());
+            let albums = Arc::new(MemoryAlbumRepository::default());
+            let artists = Arc::new(MemoryArtistRepository::default());
+            let store = Arc::new(MemoryMergeStore {
+                audio_files: audio_files.clone(),
+                albums: albums.clone(),
+                artists: artists.clone(),
+                annotations: Mutex::new(Vec::new()),
+                commits: Mutex::new(0),
+                fail,
+            });
+            let event_bus = Arc::new(RecordingEventBus::default());
+            let service = MergeService::new(
+                Arc::new(SequentialIdGenerator::new(1000)),
+                MergeRepositories {
+                    audio_file: audio_files.clone(),
+                    album: albums.clone(),
+                    artist: artists.clone(),
+                },
+                store.clone(),
+                Arc::new(LowercaseNormalizer),
+                Arc::new(LowercaseNormalizer),
+                event_bus.clone(),
+            );
+            Self {
+                audio_files,
+                albums,
+                artists,
+                store,
+                event_bus,
+                service,
+            }
+        }
+
+        fn add_song(&self, id: i64, album_id: i64, artist_ids: &[i64]) {
+            let audio_file = song(id, Some(album_id), artist_ids);
+            self.audio_files
+                .files
+                .lock()
+                .unwrap()
+                .insert(id, audio_file);
+        }
+
+        fn add_album(&self, id: i64, name: &str, artist_ids: &[i64]) {
+            let mut album = Album::new(id.into(), name.to_string(), name.to_lowercase());
+            for artist_id in artist_ids {
+                album
+                    .add_participant(participant(*artist_id, id, ParticipantWorkType::Album))
+                    .unwrap();
+            }
+            album.take_events();
+            self.albums.albums.lock().unwrap().insert(id, album);
+        }
+
+        fn add_artist(&self, id: i64, name: &str) {
+            let mut artist = Artist::new(id.into(), name.to_string(), name.to_lowercase());
+            artist.take_events();
+            self.artists.artists.lock().unwrap().insert(id, artist);
+        }
+
+        fn album_artists(&self, album_id: i64) -> Vec<i64> {
+            let mut ids: Vec<i64> = self
+                .albums
+                .get(album_id)
+                .unwrap()
+                .participants
+                .iter()
+                .map(|p| p.artist_id.as_i64())
+                .collect();
+            ids.sort();
+            ids
+        }
+    }
+
+    #[tokio::test]
+    async fn merge_albums_commits_once_and_removes_source_stats() {
+        let fixture = Fixture::new(false);
+        fixture.add_album(1, "Live", &[100]);
+        fixture.add_album(2, "Live (Remaster)", &[101]);
+        fixture.add_song(11, 1, &[100]);
+        fixture.add_song(12, 1, &[100]);
+        fixture.add_song(13, 2, &[101]);
+
+        let album_stats = Arc::new(MemoryAlbumStatsRepository::default());
+        let projector = AlbumStatsProjector::new(album_stats.clone());
+        for (album_id, songs) in [(1, 2), (2, 1)] {
+            for _ in 0..songs {
+                album_stats
+                    .adjust_stats(AlbumStatsAdjustment {
+                        album_id: AlbumId::from(album_id),
+                        duration_delta: 180,
+                        size_delta: 1000,
+                        song_count_delta: 1,
+                        disk_number: Some(1),
+                        disc_subtitle: None,
+                        year: None,
+                    })
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let target = fixture
+            .service
+            .merge_albums(
+                &AppContext::new(),
+                MergeAlbumsCmd {
+                    source_id: AlbumId::from(1),
+                    target_id: AlbumId::from(2),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(target.id, AlbumId::from(2));
+        assert_eq!(*fixture.store.commits.lock().unwrap(), 1);
+        assert!(fixture.albums.get(1).is_none());
+        assert_eq!(fixture.album_artists(2), vec![100, 101]);
+        for id in [11, 12, 13] {
+            assert_eq!(
+                fixture.audio_files.get(id).unwrap().album,
+                Some(AlbumId::from(2))
+            );
+        }
+        assert_eq!(
+            *fixture.store.annotations.lock().unwrap(),
+            vec![(Kind::Album, 1, 2)]
+        );
+
+        // 事件在提交后发布，投影据此把源专辑的统计清零并删除
+        for envelope in fixture.event_bus.events::<AudioFileEvent>() {
+            match envelope.payload.kind {
+                AudioFileEventKind::BoundToAlbum(_) => projector
+                    .on_audio_file_bound_to_album(&envelope.payload)
+                    .await
+                    .unwrap(),
+                AudioFileEventKind::UnboundFromAlbum(_) => projector
+                    .on_audio_file_unbound_from_album(&envelope.payload)
+                    .await
+                    .unwrap(),
+                _ => {}
+            }
+        }
+        assert_eq!(album_stats.song_count(1), None);
+        assert_eq!(album_stats.song_count(2), Some(3));
+    }
+
+    #[tokio::test]
+    async fn failed_commit_saves_and_publishes_nothing() {
+        let fixture = Fixture::new(true);
+        fixture.add_album(1, "Live", &[100]);
+        fixture.add_album(2, "Live (Remaster)", &[100]);
+        fixture.add_song(11, 1, &[100]);
+
+        let result = fixture
+            .service
+            .merge_albums(
+                &AppContext::new(),
+                MergeAlbumsCmd {
+                    source_id: AlbumId::from(1),
+                    target_id: AlbumId::from(2),
+                },
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert!(fixture.albums.get(1).is_some());
+        assert_eq!(
+            fixture.audio_files.get(11).unwrap().album,
+            Some(AlbumId::from(1))
+        );
+        assert!(fixture.event_bus.is_empty());
+    }
+
+    #[tokio::test]
+    async fn merge_artists_moves_songs_albums_and_annotations() {
+        let fixture = Fixture::new(false);
+        fixture.add_artist(100, "Beatles");
+        fixture.add_artist(101, "The Beatles");
+        fixture.add_album(1, "Help", &[100]);
+        fixture.add_song(11, 1, &[100]);
+
+        let target = fixture
+            .service
+            .merge_artists(
+                &AppContext::new(),
+                MergeArtistsCmd {
+                    source_id: ArtistId::from(100),
+                    target_id: ArtistId::from(101),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(target.id, ArtistId::from(101));
+        assert_eq!(*fixture.store.commits.lock().unwrap(), 1);
+        assert!(fixture.artists.get(100).is_none());
+        assert_eq!(fixture.album_artists(1), vec![101]);
+        let audio_file = fixture.audio_files.get(11).unwrap();
+        assert_eq!(audio_file.artist, Some(ArtistId::from(101)));
+        assert_eq!(
+            *fixture.store.annotations.lock().unwrap(),
+            vec![(Kind::Artist, 100, 101)]
+        );
+    }
+
+    #[tokio::test]
+    async fn split_album_moves_songs_and_their_artists() {
+        let fixture = Fixture::new(false);
+        fixture.add_album(1, "Hits", &[100, 101]);
+        fixture.add_song(11, 1, &[100]);
+        fixture.add_song(12, 1, &[101]);
+
+        let target = fixture
+            .service
+            .split_album(
+                &AppContext::new(),
+                SplitAlbumCmd {
+                    album_id: AlbumId::from(1),
+                    audio_file_ids: vec![AudioFileId::from(12)],
+                    new_name: "Hits Vol. 2".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(target.id, AlbumId::from(1000));
+        assert_eq!(*fixture.store.commits.lock().unwrap(), 1);
+        assert_eq!(fixture.album_artists(1), vec![100]);
+        assert_eq!(fixture.album_artists(1000), vec![101]);
+        assert_eq!(
+            fixture.audio_files.get(12).unwrap().album,
+            Some(AlbumId::from(1000))
+        );
+        assert_eq!(
+            fixture.audio_files.get(11).unwrap().album,
+            Some(AlbumId::from(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn split_artist_creates_the_artist_in_the_same_commit() {
+        let fixture = Fixture::new(false);
+        fixture.add_artist(100, "Prince");
+        fixture.add_album(1, "Hits", &[100]);
+        fixture.add_album(2, "B-Sides", &[100]);
+        fixture.add_song(11, 1, &[100]);
+        fixture.add_song(12, 2, &[100]);
+        fixture.add_song(13, 2, &[100]);
+
+        let target = fixture
+            .service
+            .split_artist(
+                &AppContext::new(),
+                SplitArtistCmd {
+                    artist_id: ArtistId::from(100),
+                    audio_file_ids: vec![AudioFileId::from(11), AudioFileId::from(12)],
+                    new_name: "The Artist".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(target.id, ArtistId::from(1000));
+        assert_eq!(*fixture.store.commits.lock().unwrap(), 1);
+        assert!(fixture.artists.get(1000).is_some());
+        // 专辑 1 的歌曲全部拆走，原艺术家不再是参与者；专辑 2 还有 13
+        assert_eq!(fixture.album_artists(1), vec![1000]);
+        assert_eq!(fixture.album_artists(2), vec![100, 1000]);
+        assert_eq!(
+            fixture.audio_files.get(12).unwrap().artist,
+            Some(ArtistId::from(1000))
+        );
+    }
+}
@@ -0,0 +1,532 @@
+use crate::command::album::{edition_sort_name, AlbumNameNormalizer};
+use crate::command::artist::ArtistNameNormalizer;
+use crate::command::shared::IdGenerator;
+use crate::context::AppContext;
+use crate::error::AppError;
+use crate::event::event_bus::{EventBus, EventEnvelope};
+use domain::album::AlbumRepository;
+use domain::artist::ArtistRepository;
+use domain::audio_file::{AudioFile, AudioFileRepository};
+use domain::metadata_change::{MetadataChange, MetadataChangeRepository, MetadataTarget};
+use domain::value::{AlbumId, ArtistId, AudioFileId, MediaPath, ParticipantRole};
+use log::warn;
+use std::sync::Arc;
+
+/// 需要回写到文件的标签，None 表示不修改
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagUpdate {
+    pub title: Option<String>,
+    pub album: Option<String>,
+    pub artist: Option<String>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub year: Option<i32>,
+}
+
+impl TagUpdate {
+    pub fn is_empty(&self) -> bool {
+        self == &TagUpdate::default()
+    }
+}
+
+/// 将修正后的元数据写回音频文件标签
+#[async_trait::async_trait]
+pub trait AudioTagWriter: Send + Sync {
+    async fn write_tags(&self, path: &MediaPath, tags: &TagUpdate) -> Result<(), AppError>;
+}
+
+#[derive(Debug)]
+pub struct EditSongCmd {
+    pub audio_file_id: AudioFileId,
+    pub title: Option<String>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub disc_subtitle: Option<String>,
+    pub year: Option<i32>,
+    pub changed_by: String,
+}
+
+#[derive(Debug)]
+pub struct EditAlbumCmd {
+    pub album_id: AlbumId,
+    pub name: String,
+    pub changed_by: String,
+}
+
+#[derive(Debug)]
+pub struct EditArtistCmd {
+    pub artist_id: ArtistId,
+    pub name: String,
+    pub changed_by: String,
+}
+
+/// 元数据编辑读写的仓储
+pub struct MetadataRepositories {
+    pub audio_file: Arc<dyn AudioFileRepository>,
+    pub album: Arc<dyn AlbumRepository>,
+    pub artist: Arc<dyn ArtistRepository>,
+    pub change: Arc<dyn MetadataChangeRepository>,
+}
+
+/// 元数据编辑服务：修改数据库中的歌曲/专辑/艺术家信息，记录审计日志，并按需回写标签
+pub struct MetadataEditService<B: EventBus> {
+    id_generator: Arc<dyn IdGenerator>,
+    audio_file_repository: Arc<dyn AudioFileRepository>,
+    album_repository: Arc<dyn AlbumRepository>,
+    artist_repository: Arc<dyn ArtistRepository>,
+    change_repository: Arc<dyn MetadataChangeRepository>,
+    album_name_normalizer: Arc<dyn AlbumNameNormalizer>,
+    artist_name_normalizer: Arc<dyn ArtistNameNormalizer>,
+    event_bus: Arc<B>,
+    tag_writer: Option<Arc<dyn AudioTagWriter>>,
+}
+
+impl<B: EventBus> MetadataEditService<B> {
+    pub fn new(
+        id_generator: Arc<dyn IdGenerator>,
+        repositories: MetadataRepositories,
+        album_name_normalizer: Arc<dyn AlbumNameNormalizer>,
+        artist_name_normalizer: Arc<dyn ArtistNameNormalizer>,
+        event_bus: Arc<B>,
+    ) -> Self {
+        Self {
+            id_generator,
+            audio_file_repository: repositories.audio_file,
+            album_repository: repositories.album,
+            artist_repository: repositories.artist,
+            change_repository: repositories.change,
+            album_name_normalizer,
+            artist_name_normalizer,
+            event_bus,
+            tag_writer: None,
+        }
+    }
+
+    /// 启用标签回写
+    pub fn with_tag_writer(mut self, tag_writer: Arc<dyn AudioTagWriter>) -> Self {
+        self.tag_writer = Some(tag_writer);
+        self
+    }
+
+    pub async fn edit_song(
+        &self,
+        context: &AppContext,
+        cmd: EditSongCmd,
+    ) -> Result<Vec<MetadataChange>, AppError> {
+        let mut audio_file = self
+            .audio_file_repository
+            .find_by_id(&cmd.audio_file_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::AggregateNotFound("AudioFile".to_string(), cmd.audio_file_id.to_string())
+            })?;
+
+        let target_id = audio_file.id.as_i64();
+        let mut meta = audio_file.meta.clone();
+        let mut changes = Vec::new();
+        let mut tags = TagUpdate::default();
+
+        if let Some(title) = cmd.title.map(|t| t.trim().to_string()) {
+            if title.is_empty() {
+                return Err(AppError::InvalidInput("title cannot be empty".to_string()));
+            }
+            if title != meta.title {
+                changes.push(
+                    self.change(
+                        MetadataTarget::Song,
+                        target_id,
+                        "title",
+                        Some(meta.title.clone()),
+                        Some(title.clone()),
+                        &cmd.changed_by,
+                    )
+                    .await?,
+                );
+                tags.title = Some(title.clone());
+                meta.title = title;
+            }
+        }
+        if cmd.track_number.is_some() && cmd.track_number != meta.track_number {
+            changes.push(
+                self.change(
+                    MetadataTarget::Song,
+                    target_id,
+                    "track_number",
+                    meta.track_number.map(|n| n.to_string()),
+                    cmd.track_number.map(|n| n.to_string()),
+                    &cmd.changed_by,
+                )
+                .await?,
+            );
+            tags.track_number = cmd.track_number;
+            meta.track_number = cmd.track_number;
+        }
+        if cmd.disc_number.is_some() && cmd.disc_number != meta.disc_number {
+            changes.push(
+                self.change(
+                    MetadataTarget::Song,
+                    target_id,
+                    "disc_number",
+                    meta.disc_number.map(|n| n.to_string()),
+                    cmd.disc_number.map(|n| n.to_string()),
+                    &cmd.changed_by,
+                )
+                .await?,
+            );
+            tags.disc_number = cmd.disc_number;
+            meta.disc_number = cmd.disc_number;
+        }
+        if let Some(subtitle) = cmd.disc_subtitle {
+            let subtitle = Some(subtitle.trim().to_string()).filter(|s| !s.is_empty());
+            if subtitle != meta.disc_subtitle {
+                changes.push(
+                    self.change(
+                        MetadataTarget::Song,
+                        target_id,
+                        "disc_subtitle",
+                        meta.disc_subtitle.clone(),
+                        subtitle.clone(),
+                        &cmd.changed_by,
+                    )
+                    .await?,
+                );
+                meta.disc_subtitle = subtitle;
+            }
+        }
+        if cmd.year.is_some() && cmd.year != meta.year {
+            changes.push(
+                self.change(
+                    MetadataTarget::Song,
+                    target_id,
+                    "year",
+                    meta.year.map(|n| n.to_string()),
+                    cmd.year.map(|n| n.to_string()),
+                    &cmd.changed_by,
+                )
+                .await?,
+            );
+            tags.year = cmd.year;
+            meta.year = cmd.year;
+        }
+
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        audio_file.edit_metadata(meta)?;
+        // 碟号、年份等变化时产生专辑解绑/绑定事件，专辑统计据此更新
+        let events = audio_file.take_events();
+        let audio_file = self.audio_file_repository.save(audio_file).await?;
+        for event in events {
+            let envelope = EventEnvelope::new(
+                audio_file.id.as_i64(),
+                audio_file.version,
+                event,
+                context.correlation_id.clone(),
+                context.event_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+
+        if self.write_tags(&audio_file, &tags).await {
+            changes.iter_mut().for_each(|c| c.tags_written = true);
+        }
+        self.change_repository
+            .append(changes.clone())
+            .await
+            .map_err(|e| AppError::RepositoryError("MetadataChange".to_string(), e.to_string()))?;
+        Ok(changes)
+    }
+
+    pub async fn edit_album(&self, cmd: EditAlbumCmd) -> Result<Vec<MetadataChange>, AppError> {
+        let name = cmd.name.trim().to_string();
+        let mut album = self
+            .album_repository
+            .by_id(cmd.album_id.clone())
+            .await?
+            .ok_or_else(|| {
+                AppError::AggregateNotFound("Album".to_string(), cmd.album_id.to_string())
+            })?;
+        if name == album.name {
+            return Ok(Vec::new());
+        }
+
+        let mut sort_name = self.album_name_normalizer.normalize(&name);
+        // 按版本区分的专辑改名后仍保留版本
+        if let Some(edition) = &album.edition {
+            let edition = self.album_name_normalizer.normalize(edition);
+            let old_sort_name = self.album_name_normalizer.normalize(&album.name);
+            if album.sort_name == edition_sort_name(&old_sort_name, &edition) {
+                sort_name = edition_sort_name(&sort_name, &edition);
+            }
+        }
+        if let Some(existing) = self.album_repository.find_by_sort_name(&sort_name).await? {
+            if existing.id != album.id {
+                return Err(AppError::InvalidInput(format!(
+                    "Album '{}' already exists, merge the albums instead",
+                    existing.name
+                )));
+            }
+        }
+
+        let old_name = album.name.clone();
+        album.rename(name.clone(), sort_name)?;
+        self.album_repository.save(album).await?;
+
+        let mut change = self
+            .change(
+                MetadataTarget::Album,
+                cmd.album_id.as_i64(),
+                "name",
+                Some(old_name),
+                Some(name.clone()),
+                &cmd.changed_by,
+            )
+            .await?;
+        if self.tag_writer.is_some() {
+            let tags = TagUpdate {
+                album: Some(name),
+                ..Default::default()
+            };
+            let files = self
+                .audio_file_repository
+                .find_by_album(&cmd.album_id)
+                .await?;
+            change.tags_written = self.write_tags_all(&files, &tags).await;
+        }
+        self.change_repository
+            .append(vec![change.clone()])
+            .await
+            .map_err(|e| AppError::RepositoryError("MetadataChange".to_string(), e.to_string()))?;
+        Ok(vec![change])
+    }
+
+    pub async fn edit_artist(&self, cmd: EditArtistCmd) -> Result<Vec<MetadataChange>, AppError> {
+        let name = cmd.name.trim().to_string();
+        let mut artist = self
+            .artist_repository
+            .by_id(cmd.artist_id.clone())
+            .await?
+            .ok_or_else(|| {
+                AppError::AggregateNotFound("Artist".to_string(), cmd.artist_id.to_string())
+            })?;
+        if name == artist.name {
+            return Ok(Vec::new());
+        }
+
+        let sort_name = self.artist_name_normalizer.normalize(&name);
+        if let Some(existing) = self.artist_repository.find_by_sort_name(&sort_name).await? {
+            if existing.id != artist.id {
+                return Err(AppError::InvalidInput(format!(
+                    "Artist '{}' already exists, merge the artists instead",
+                    existing.name
+                )));
+            }
+        }
+
+        let old_name = artist.name.clone();
+        artist.rename(name.clone(), sort_name)?;
+        self.artist_repository.save(artist).await?;
+
+        let mut change = self
+            .change(
+                MetadataTarget::Artist,
+                cmd.artist_id.as_i64(),
+                "name",
+                Some(old_name),
+                Some(name.clone()),
+                &cmd.changed_by,
+            )
+            .await?;
+        if self.tag_writer.is_some() {
+            // 多艺术家的文件无法只替换其中一个名字，只回写单一艺术家的文件
+            let files: Vec<AudioFile> = self
+                .audio_file_repository
+                .find_by_artist(&cmd.artist_id)
+                .await?
+                .into_iter()
+                .filter(|f| {
+                    f.participants
+                        .iter()
+                        .filter(|p| p.role == ParticipantRole::Artist)
+                        .all(|p| p.artist_id == cmd.artist_id)
+                })
+                .collect();
+            let tags = TagUpdate {
+                artist: Some(name),
+                ..Default::default()
+            };
+            change.tags_written = self.write_tags_all(&files, &tags).await;
+        }
+        self.change_repository
+            .append(vec![change.clone()])
+            .await
+            .map_err(|e| AppError::RepositoryError("MetadataChange".to_string(), e.to_string()))?;
+        Ok(vec![change])
+    }
+
+    /// 查询修改记录
+    pub async fn changelog(
+        &self,
+        target: Option<(MetadataTarget, i64)>,
+        limit: u64,
+    ) -> Result<Vec<MetadataChange>, AppError> {
+        self.change_repository
+            .find_recent(target, limit)
+            .await
+            .map_err(|e| AppError::RepositoryError("MetadataChange".to_string(), e.to_string()))
+    }
+
+    async fn change(
+        &self,
+        target: MetadataTarget,
+        target_id: i64,
+        field: &str,
+        old_value: Option<String>,
+        new_value: Option<String>,
+        changed_by: &str,
+    ) -> Result<MetadataChange, AppError> {
+        Ok(MetadataChange::new(
+            self.id_generator.next_id().await?,
+            target,
+            target_id,
+            field,
+            old_value,
+            new_value,
+            changed_by,
+        ))
+    }
+
+    /// 回写单个文件，失败只记录日志（数据库修改已生效）
+    async fn write_tags(&self, audio_file: &AudioFile, tags: &TagUpdate) -> bool {
+        let Some(writer) = &self.tag_writer else {
+            return false;
+        };
+        if tags.is_empty() {
+            return false;
+        }
+        match writer.write_tags(&audio_file.path, tags).await {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Failed to write tags to {}: {}", audio_file.path.path, e);
+                false
+            }
+        }
+    }
+
+    async fn write_tags_all(&self, files: &[AudioFile], tags: &TagUpdate) -> bool {
+        let mut all_written = !files.is_empty();
+        for file in files {
+            all_written &= self.write_tags(file, tags).await;
+        }
+        all_written
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::SequentialIdGenerator;
+    use crate::projector::album_stats::AlbumStatsProjector;
+    use crate::test_support::{
+        song, LowercaseNormalizer, MemoryAlbumRepository, MemoryAlbumStatsRepository,
+        MemoryArtistRepository, MemoryAudioFileRepository, RecordingEventBus,
+    };
+    use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
+    use domain::metadata_change::MetadataChangeError;
+    use model::album_stats::AlbumStatsRepository;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryChangeRepository {
+        changes: Mutex<Vec<MetadataChange>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MetadataChangeRepository for MemoryChangeRepository {
+        async fn append(&self, changes: Vec<MetadataChange>) -> Result<(), MetadataChangeError> {
+            self.changes.lock().unwrap().extend(changes);
+            Ok(())
+        }
+
+        async fn find_recent(
+            &self,
+            _target: Option<(MetadataTarget, i64)>,
+            limit: u64,
+        ) -> Result<Vec<MetadataChange>, MetadataChangeError> {
+            let changes = self.changes.lock().unwrap();
+            Ok(changes.iter().rev().take(limit as usize).cloned().collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn edit_song_updates_album_stats() {
+        let audio_files = Arc::new(MemoryAudioFileRepository::default());
+        audio_files.save(song(11, Some(1), &[100])).await.unwrap();
+        let event_bus = Arc::new(RecordingEventBus::default());
+        let service = MetadataEditService::new(
+            Arc::new(SequentialIdGenerator::new(1000)),
+            MetadataRepositories {
+                audio_file: audio_files.clone(),
+                album: Arc::new(MemoryAlbumRepository::default()),
+                artist: Arc::new(MemoryArtistRepository::default()),
+                change: Arc::new(MemoryChangeRepository::default()),
+            },
+            Arc::new(LowercaseNormalizer),
+            Arc::new(LowercaseNormalizer),
+            event_bus.clone(),
+        );
+
+        // 先按原来的标签计入专辑统计：1 首歌，碟 1
+        let album_stats = Arc::new(MemoryAlbumStatsRepository::default());
+        let projector = AlbumStatsProjector::new(album_stats.clone());
+        let mut bound = song(11, None, &[]);
+        bound.bind_to_album(AlbumId::from(1)).unwrap();
+        for event in bound.take_events() {
+            projector
+                .on_audio_file_bound_to_album(&event)
+                .await
+                .unwrap();
+        }
+
+        let changes = service
+            .edit_song(
+                &AppContext::new(),
+                EditSongCmd {
+                    audio_file_id: AudioFileId::from(11),
+                    title: None,
+                    track_number: None,
+                    disc_number: Some(2),
+                    disc_subtitle: Some("Bonus".to_string()),
+                    year: None,
+                    changed_by: "admin".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(audio_files.get(11).unwrap().meta.disc_number, Some(2));
+
+        for envelope in event_bus.events::<AudioFileEvent>() {
+            match envelope.payload.kind {
+                AudioFileEventKind::BoundToAlbum(_) => projector
+                    .on_audio_file_bound_to_album(&envelope.payload)
+                    .await
+                    .unwrap(),
+                AudioFileEventKind::UnboundFromAlbum(_) => projector
+                    .on_audio_file_unbound_from_album(&envelope.payload)
+                    .await
+                    .unwrap(),
+                _ => panic!("unexpected event {:?}", envelope.payload.kind),
+            }
+        }
+        let stats = album_stats
+            .find_by_album_id(AlbumId::from(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.song_count, 1);
+        assert!(stats.disk_numbers.contains(&2));
+        assert_eq!(stats.disc_titles.get(&2).map(String::as_str), Some("Bonus"));
+    }
+}
@@ -1,13 +1,33 @@
 pub mod album;
+pub mod app_password;
 pub mod artist;
+pub mod artist_alias;
+pub mod artwork;
 pub mod audio_file;
+pub mod bandwidth;
+pub mod bookmark;
 pub mod cover_art;
+pub mod file_check;
 pub mod genre;
+pub mod genre_alias;
 pub mod library;
+pub mod library_admin;
+pub mod listening_session;
 pub mod media_annotation;
+pub mod media_asset;
 pub mod media_parse;
+pub mod merge;
+pub mod metadata_edit;
+pub mod parse_pool;
+pub mod path_migration;
 pub mod play_queue;
+pub mod playback_history;
+pub mod player;
 pub mod playlist;
+pub mod settings;
 pub mod shared;
+pub mod silence_analysis;
+pub mod trash;
 pub mod user;
+pub mod user_note;
 //pub mod media_ingestion;
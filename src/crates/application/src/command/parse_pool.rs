@@ -0,0 +1,162 @@
+use super::media_parse::{MediaFileParseService, ParseMediaFileCmd};
+use crate::context::AppContext;
+use crate::error::AppError;
+use crate::event::event_bus::EventBus;
+use futures::FutureExt;
+use log::{error, warn};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Notify, Semaphore};
+
+/// 解析工作池参数
+#[derive(Debug, Clone)]
+pub struct ParsePoolOptions {
+    /// 并行解析的 worker 数
+    pub workers: usize,
+    /// 每个 worker 的待解析队列长度，队列满时扫描等待
+    pub queue_capacity: usize,
+    /// 按存储协议（local、smb 等）限制同时解析的文件数，未配置的协议不限制
+    pub io_limits: HashMap<String, usize>,
+}
+
+impl Default for ParsePoolOptions {
+    fn default() -> Self {
+        Self {
+            workers: 4,
+            queue_capacity: 256,
+            io_limits: HashMap::new(),
+        }
+    }
+}
+
+struct ParseJob {
+    ctx: AppContext,
+    cmd: ParseMediaFileCmd,
+}
+
+/// 扫描和元数据解析之间的工作池
+///
+/// 同一目录的文件总是交给同一个 worker，按提交顺序解析，
+/// 专辑分组结果不受并行影响；不同目录之间并行解析
+pub struct ParseWorkerPool {
+    lanes: Vec<mpsc::Sender<ParseJob>>,
+    /// 已提交但未解析完的文件数
+    pending: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl ParseWorkerPool {
+    pub fn start<B>(service: MediaFileParseService<B>, options: ParsePoolOptions) -> Self
+    where
+        B: EventBus + 'static,
+    {
+        let service = Arc::new(service);
+        let io_limits: Arc<HashMap<String, Arc<Semaphore>>> = Arc::new(
+            options
+                .io_limits
+                .iter()
+                .map(|(protocol, limit)| (protocol.clone(), Arc::new(Semaphore::new(*limit))))
+                .collect(),
+        );
+        let pending = Arc::new(AtomicUsize::new(0));
+        let idle = Arc::new(Notify::new());
+        let lanes = (0..options.workers.max(1))
+            .map(|_| {
+                let (tx, rx) = mpsc::channel(options.queue_capacity.max(1));
+                tokio::spawn(run_lane(
+                    rx,
+                    service.clone(),
+                    io_limits.clone(),
+                    pending.clone(),
+                    idle.clone(),
+                ));
+                tx
+            })
+            .collect();
+        Self {
+            lanes,
+            pending,
+            idle,
+        }
+    }
+
+    /// 提交待解析文件，所在 worker 的队列满时等待
+    pub async fn submit(&self, ctx: AppContext, cmd: ParseMediaFileCmd) -> Result<(), AppError> {
+        let lane = &self.lanes[self.lane_of(&cmd)];
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        if lane.send(ParseJob { ctx, cmd }).await.is_err() {
+            self.finish_one();
+            return Err(AppError::UnknownError(
+                "Parse worker has stopped".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 已提交但未解析完的文件数
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// 等待所有已提交的文件解析完成
+    pub async fn drain(&self) {
+        loop {
+            let notified = self.idle.notified();
+            if self.pending() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn lane_of(&self, cmd: &ParseMediaFileCmd) -> usize {
+        let dir = &cmd.filemeta.dir_path;
+        let mut hasher = DefaultHasher::new();
+        dir.protocol.hash(&mut hasher);
+        dir.path.hash(&mut hasher);
+        (hasher.finish() % self.lanes.len() as u64) as usize
+    }
+
+    fn finish_one(&self) {
+        finish_one(&self.pending, &self.idle);
+    }
+}
+
+fn finish_one(pending: &AtomicUsize, idle: &Notify) {
+    if pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+        idle.notify_waiters();
+    }
+}
+
+async fn run_lane<B>(
+    mut rx: mpsc::Receiver<ParseJob>,
+    service: Arc<MediaFileParseService<B>>,
+    io_limits: Arc<HashMap<String, Arc<Semaphore>>>,
+    pending: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+) where
+    B: EventBus + 'static,
+{
+    while let Some(job) = rx.recv().await {
+        let path = job.cmd.filemeta.path.path.clone();
+        let permit = match io_limits.get(&job.cmd.filemeta.path.protocol) {
+            Some(limit) => limit.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        // 单个文件解析 panic 不能让整个 worker 退出
+        match AssertUnwindSafe(service.parse_media_file(&job.ctx, job.cmd))
+            .catch_unwind()
+            .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Failed to parse media file {}: {}", path, e),
+            Err(_) => warn!("Parsing media file {} panicked", path),
+        }
+        drop(permit);
+        finish_one(&pending, &idle);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::command::media_parse::StorageClientFactory;
+use crate::error::AppError;
+use async_trait::async_trait;
+use domain::library::{LibraryError, LibraryRepository, ScanStatus};
+use domain::value::{LibraryId, MediaPath};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait LibraryPathStore: Send + Sync {
+    /// 在一个事务中把库的根路径以及库中所有以 from 开头的路径改为以 to 开头，
+    /// 返回各表更新的行数。`dry_run` 为 true 时执行后回滚，只返回行数
+    async fn rewrite_prefix(
+        &self,
+        library_id: &LibraryId,
+        from: &MediaPath,
+        to: &MediaPath,
+        dry_run: bool,
+    ) -> Result<BTreeMap<String, u64>, AppError>;
+}
+
+#[derive(Debug)]
+pub struct MoveLibraryCmd {
+    pub library_id: LibraryId,
+    /// 省略时沿用原协议
+    pub protocol: Option<String>,
+    /// 新的根路径
+    pub path: String,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PathMigrationReport {
+    pub from: MediaPath,
+    pub to: MediaPath,
+    /// 表名 -> 更新的行数
+    pub updated: BTreeMap<String, u64>,
+    pub dry_run: bool,
+}
+
+/// 库的根目录移动后改写已索引的路径，保留 ID、播放次数和收藏，不需要重新扫描
+pub struct PathMigrationService {
+    library_repository: Arc<dyn LibraryRepository>,
+    storage_client_factory: Arc<dyn StorageClientFactory>,
+    path_store: Arc<dyn LibraryPathStore>,
+}
+
+impl PathMigrationService {
+    pub fn new(
+        library_repository: Arc<dyn LibraryRepository>,
+        storage_client_factory: Arc<dyn StorageClientFactory>,
+        path_store: Arc<dyn LibraryPathStore>,
+    ) -> Self {
+        Self {
+            library_repository,
+            storage_client_factory,
+            path_store,
+        }
+    }
+
+    pub async fn move_library(&self, cmd: MoveLibraryCmd) -> Result<PathMigrationReport, AppError> {
+        let library = self
+            .library_repository
+            .find_all()
+            .await?
+            .into_iter()
+            .find(|library| library.id == cmd.library_id)
+            .ok_or_else(|| {
+                AppError::AggregateNotFound("Library".to_string(), cmd.library_id.to_string())
+            })?;
+        if library.scan_status == ScanStatus::Scanning {
+            return Err(LibraryError::ScanningInProgress.into());
+        }
+
+        let from = normalize_root(&library.path)?;
+        let to = normalize_root(&MediaPath {
+            protocol: cmd
+                .protocol
+                .unwrap_or_else(|| library.path.protocol.clone()),
+            path: cmd.path,
+        })?;
+        if from == to {
+            return Err(AppError::InvalidInput(
+                "New path is the same as the current path".to_string(),
+            ));
+        }
+        let client = self
+            .storage_client_factory
+            .create(&to)
+            .await
+            .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+        client.validate_path(&to)?;
+        // 新路径必须已经可以访问，否则改写后所有文件都会在下次扫描时被当作删除
+        if let Err(e) = client.list(&to).await {
+            return Err(AppError::InvalidInput(format!(
+                "New path is not reachable: {}",
+                e
+            )));
+        }
+
+        let updated = self
+            .path_store
+            .rewrite_prefix(&cmd.library_id, &from, &to, cmd.dry_run)
+            .await?;
+        Ok(PathMigrationReport {
+            from,
+            to,
+            updated,
+            dry_run: cmd.dry_run,
+        })
+    }
+}
+
+/// 去掉结尾的 `/`，按目录边界匹配前缀
+fn normalize_root(path: &MediaPath) -> Result<MediaPath, AppError> {
+    let root = path.path.trim().trim_end_matches('/');
+    if root.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Library root cannot be empty or the filesystem root".to_string(),
+        ));
+    }
+    Ok(MediaPath {
+        protocol: path.protocol.clone(),
+        path: root.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(path: &str) -> MediaPath {
+        MediaPath {
+            protocol: "local".to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn roots_are_compared_without_trailing_slash() {
+        assert_eq!(
+            normalize_root(&local("/srv/music/")).unwrap(),
+            local("/srv/music")
+        );
+        assert_eq!(
+            normalize_root(&local(" /mnt/music ")).unwrap(),
+            local("/mnt/music")
+        );
+        assert!(normalize_root(&local("/")).is_err());
+        assert!(normalize_root(&local("")).is_err());
+    }
+}
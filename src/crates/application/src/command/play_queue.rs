@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use super::settings::PlayQueueMode;
 use super::shared::IdGenerator;
 use crate::error::AppError;
 use domain::play_queue::{PlayQueue, PlayQueueError, PlayQueueRepository};
@@ -13,26 +14,44 @@ pub struct SavePlayQueueCmd {
     pub song_ids: Vec<i64>,
     /// Current playing song ID
     pub current_id: Option<i64>,
+    /// Index of the current song; takes precedence over current_id, which
+    /// is ambiguous when the queue contains the same song more than once
+    pub current_index: Option<usize>,
     /// Position in milliseconds within the currently playing song
     pub position: i64,
     /// Client name
     pub changed_by: String,
+    /// Client unique id, used when queues are kept per device
+    pub client_id: Option<String>,
+    /// When the client made this change (milliseconds); defaults to now
+    pub changed_at: Option<i64>,
+}
+
+/// Queue key for the given policy: empty for the shared queue
+pub fn queue_client_id(mode: PlayQueueMode, client_id: Option<&str>) -> String {
+    match (mode, client_id) {
+        (PlayQueueMode::PerDevice, Some(id)) => id.to_string(),
+        _ => String::new(),
+    }
 }
 
 /// Play queue application service
 pub struct PlayQueueAppService {
     play_queue_repository: Arc<dyn PlayQueueRepository>,
     id_generator: Arc<dyn IdGenerator>,
+    mode: PlayQueueMode,
 }
 
 impl PlayQueueAppService {
     pub fn new(
         play_queue_repository: Arc<dyn PlayQueueRepository>,
         id_generator: Arc<dyn IdGenerator>,
+        mode: PlayQueueMode,
     ) -> Self {
         Self {
             play_queue_repository,
             id_generator,
+            mode,
         }
     }
 
@@ -40,25 +59,37 @@ impl PlayQueueAppService {
     ///
     /// If song_ids is empty, clears the play queue for the user.
     /// Otherwise, saves the play queue state.
+    ///
+    /// Changes older than the stored queue are ignored (last writer wins),
+    /// so a slow request from one device cannot overwrite a newer state.
     pub async fn save_play_queue(&self, cmd: SavePlayQueueCmd) -> Result<(), AppError> {
         let user_id = UserId::from(cmd.user_id);
+        let client_id = queue_client_id(self.mode, cmd.client_id.as_deref());
+        let changed_at = cmd
+            .changed_at
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+
+        let existing = self
+            .play_queue_repository
+            .find_by_user_and_client(user_id.clone(), &client_id)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        if existing
+            .as_ref()
+            .is_some_and(|pq| pq.updated_at > changed_at)
+        {
+            return Ok(());
+        }
 
         // If no songs, clear the queue
         if cmd.song_ids.is_empty() {
             self.play_queue_repository
-                .delete_by_user_id(user_id)
+                .delete_by_user_and_client(user_id, &client_id)
                 .await
                 .map_err(|e| AppError::UnknownError(e.to_string()))?;
             return Ok(());
         }
 
-        // Find existing play queue or create new one
-        let existing = self
-            .play_queue_repository
-            .find_by_user_id(user_id.clone())
-            .await
-            .map_err(|e| AppError::UnknownError(e.to_string()))?;
-
         let play_queue_id = match existing {
             Some(pq) => pq.id,
             None => PlayQueueId::from(self.id_generator.next_id().await?),
@@ -74,11 +105,16 @@ impl PlayQueueAppService {
         let mut play_queue = PlayQueue::from_saved_state(
             play_queue_id,
             user_id,
+            client_id,
             items,
             current,
             cmd.position,
             cmd.changed_by,
         );
+        if cmd.current_index.is_some() {
+            play_queue.set_current_index(cmd.current_index);
+        }
+        play_queue.updated_at = changed_at;
 
         // Save
         self.play_queue_repository
@@ -0,0 +1,10 @@
+use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+#[async_trait]
+pub trait PlaybackHistoryStore: Send + Sync {
+    /// 把 scrobbled_at 早于 before 的播放汇总进播放次数统计，再删除这些记录，返回删除的行数。
+    /// 统计行已由 scrobble 事件投影，这里按较大值合并，只补上投影之前的播放
+    async fn prune(&self, before: NaiveDateTime) -> Result<u64, AppError>;
+}
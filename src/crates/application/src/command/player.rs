@@ -0,0 +1,133 @@
+use crate::error::AppError;
+use domain::player::{Player, PlayerRepository};
+use domain::value::{PlayerId, UserId};
+use std::sync::Arc;
+
+/// 开关播放器的播放记录，关闭即私密收听
+#[derive(Debug)]
+pub struct SetScrobbleCmd {
+    /// 当前会话的播放器，不存在时以这些客户端信息创建
+    pub player_id: PlayerId,
+    pub user_id: UserId,
+    pub client: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub enabled: bool,
+}
+
+pub struct PlayerService {
+    player_repository: Arc<dyn PlayerRepository + Send + Sync>,
+}
+
+impl PlayerService {
+    pub fn new(player_repository: Arc<dyn PlayerRepository + Send + Sync>) -> Self {
+        Self { player_repository }
+    }
+
+    /// 只返回属于该用户的播放器
+    pub async fn find(
+        &self,
+        player_id: PlayerId,
+        user_id: &UserId,
+    ) -> Result<Option<Player>, AppError> {
+        let player = self.player_repository.find_by_id(player_id).await?;
+        Ok(player.filter(|p| &p.user_id == user_id))
+    }
+
+    pub async fn set_scrobble_enabled(&self, cmd: SetScrobbleCmd) -> Result<Player, AppError> {
+        let mut player = match self
+            .player_repository
+            .find_by_id(cmd.player_id.clone())
+            .await?
+        {
+            Some(player) if player.user_id == cmd.user_id => player,
+            Some(_) => {
+                return Err(AppError::AggregateNotFound(
+                    "Player".to_string(),
+                    cmd.player_id.to_string(),
+                ))
+            }
+            None => Player::new(
+                cmd.player_id,
+                cmd.user_id,
+                cmd.user_agent,
+                cmd.client,
+                cmd.ip,
+            ),
+        };
+        if cmd.enabled {
+            player.enable_scrobble();
+        } else {
+            player.disable_scrobble();
+        }
+        self.player_repository.save(&mut player).await?;
+        Ok(player)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use domain::player::PlayerError;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryPlayerRepository {
+        players: Mutex<HashMap<i64, Player>>,
+    }
+
+    #[async_trait]
+    impl PlayerRepository for MemoryPlayerRepository {
+        async fn find_by_id(&self, id: PlayerId) -> Result<Option<Player>, PlayerError> {
+            Ok(self.players.lock().unwrap().get(&id.as_i64()).cloned())
+        }
+
+        async fn save(&self, player: &mut Player) -> Result<(), PlayerError> {
+            self.players
+                .lock()
+                .unwrap()
+                .insert(player.id.as_i64(), player.clone());
+            Ok(())
+        }
+
+        async fn delete(&self, id: PlayerId) -> Result<(), PlayerError> {
+            self.players.lock().unwrap().remove(&id.as_i64());
+            Ok(())
+        }
+    }
+
+    fn cmd(user_id: i64, enabled: bool) -> SetScrobbleCmd {
+        SetScrobbleCmd {
+            player_id: PlayerId::from(42),
+            user_id: UserId::from(user_id),
+            client: "web".to_string(),
+            ip: "127.0.0.1".to_string(),
+            user_agent: "test".to_string(),
+            enabled,
+        }
+    }
+
+    #[tokio::test]
+    async fn creates_the_session_player_and_rejects_other_users() {
+        let repository = Arc::new(MemoryPlayerRepository::default());
+        let service = PlayerService::new(repository.clone());
+
+        let player = service.set_scrobble_enabled(cmd(1, false)).await.unwrap();
+        assert_eq!(player.id, PlayerId::from(42));
+        assert!(!player.scrobble_enabled);
+        let found = service
+            .find(PlayerId::from(42), &UserId::from(1))
+            .await
+            .unwrap();
+        assert!(found.is_some_and(|p| !p.scrobble_enabled));
+
+        assert!(service.set_scrobble_enabled(cmd(2, true)).await.is_err());
+        assert!(service
+            .find(PlayerId::from(42), &UserId::from(2))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
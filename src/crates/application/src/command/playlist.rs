@@ -1,15 +1,23 @@
 use std::sync::Arc;
 
-use super::shared::IdGenerator;
+use super::shared::{retry_on_conflict, IdGenerator, MAX_SAVE_ATTEMPTS};
 use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use domain::playlist::{Owner, Playlist, PlaylistError, PlaylistRepository};
 use domain::value::{PlaylistId, UserId};
+use image::ImageFormat;
+use log::warn;
+
+/// 上传封面的大小上限
+pub const MAX_PLAYLIST_COVER_SIZE: usize = 10 * 1024 * 1024;
 
 /// 创建或更新播放列表命令
 #[derive(Debug)]
 pub struct CreatePlaylistCmd {
     pub playlist_id: Option<i64>,
     pub name: Option<String>,
+    /// 当前用户；更新已有播放列表时作为操作者
     pub owner_id: i64,
     pub owner_name: String,
     pub song_ids: Vec<i64>,
@@ -19,6 +27,8 @@ pub struct CreatePlaylistCmd {
 #[derive(Debug)]
 pub struct UpdatePlaylistCmd {
     pub playlist_id: i64,
+    /// 操作用户
+    pub user_id: i64,
     pub name: Option<String>,
     pub comment: Option<String>,
     pub public: Option<bool>,
@@ -26,106 +36,262 @@ pub struct UpdatePlaylistCmd {
     pub song_indexes_to_remove: Vec<usize>,
 }
 
+/// 设置播放列表封面命令
+pub struct SetPlaylistCoverCmd {
+    pub playlist_id: i64,
+    /// 操作用户，只有所有者可以修改封面
+    pub user_id: i64,
+    pub data: Vec<u8>,
+}
+
+/// 批量添加歌曲命令
+#[derive(Debug)]
+pub struct AddPlaylistEntriesCmd {
+    pub playlist_id: i64,
+    pub user_id: i64,
+    pub song_ids: Vec<i64>,
+    /// 插入位置，为空时追加到末尾
+    pub index: Option<usize>,
+}
+
+/// 移动条目命令
+#[derive(Debug)]
+pub struct MovePlaylistEntryCmd {
+    pub playlist_id: i64,
+    pub user_id: i64,
+    pub entry_id: i64,
+    pub index: usize,
+}
+
+/// 设置协作者命令
+#[derive(Debug)]
+pub struct SetPlaylistCollaboratorsCmd {
+    pub playlist_id: i64,
+    /// 操作用户，必须是所有者
+    pub user_id: i64,
+    pub collaborator_ids: Vec<i64>,
+}
+
+/// 设置标签命令
+#[derive(Debug)]
+pub struct SetPlaylistTagsCmd {
+    pub playlist_id: i64,
+    /// 操作用户，必须是所有者
+    pub user_id: i64,
+    pub tags: Vec<String>,
+}
+
+/// 播放列表封面存储
+#[async_trait]
+pub trait PlaylistCoverStore: Send + Sync {
+    /// 保存封面，返回本地路径；同一播放列表同一格式会覆盖
+    async fn save(
+        &self,
+        playlist_id: i64,
+        extension: &str,
+        data: Vec<u8>,
+    ) -> Result<String, AppError>;
+    async fn remove(&self, path: &str) -> Result<(), AppError>;
+}
+
 /// 播放列表应用服务
 pub struct PlaylistAppService {
     playlist_repository: Arc<dyn PlaylistRepository>,
     id_generator: Arc<dyn IdGenerator>,
+    cover_store: Arc<dyn PlaylistCoverStore>,
 }
 
 impl PlaylistAppService {
     pub fn new(
         playlist_repository: Arc<dyn PlaylistRepository>,
         id_generator: Arc<dyn IdGenerator>,
+        cover_store: Arc<dyn PlaylistCoverStore>,
     ) -> Self {
         Self {
             playlist_repository,
             id_generator,
+            cover_store,
         }
     }
 
     /// 创建或更新播放列表
+    ///
+    /// 带 playlistId 时按 songId 的顺序重建条目（OpenSubsonic 语义），
+    /// 未提供 songId 则保留原有条目。
     pub async fn create_playlist(&self, cmd: CreatePlaylistCmd) -> Result<Playlist, AppError> {
-        let mut playlist = if let Some(playlist_id) = cmd.playlist_id {
-            // 更新现有播放列表
-            let mut playlist = self
-                .playlist_repository
-                .find_by_id(PlaylistId::from(playlist_id))
-                .await
-                .map_err(|e| AppError::UnknownError(e.to_string()))?
-                .ok_or_else(|| {
-                    AppError::AggregateNotFound(
-                        "Playlist".to_string(),
-                        format!("id {} not found", playlist_id),
-                    )
-                })?;
+        let Some(playlist_id) = cmd.playlist_id else {
+            return self.create_new_playlist(cmd).await;
+        };
 
-            if let Some(name) = cmd.name {
-                playlist.update_name(&name);
+        // 整体替换与加载时的状态无关，冲突时可以在最新版本上重放
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.replace_playlist_entries(playlist_id, &cmd).await {
+                Err(AppError::ConcurrentModification(..)) if attempt < MAX_SAVE_ATTEMPTS => {}
+                result => return result,
             }
+        }
+    }
 
-            playlist
-        } else {
-            // 创建新播放列表
-            let name = cmd.name.ok_or_else(|| {
-                AppError::InvalidInput("name is required when creating a playlist".to_string())
-            })?;
-
-            let playlist_id = PlaylistId::from(self.id_generator.next_id().await?);
-            let owner = Owner {
-                id: UserId::from(cmd.owner_id),
-                name: cmd.owner_name,
-            };
+    async fn create_new_playlist(&self, cmd: CreatePlaylistCmd) -> Result<Playlist, AppError> {
+        let name = cmd.name.ok_or_else(|| {
+            AppError::InvalidInput("name is required when creating a playlist".to_string())
+        })?;
 
-            Playlist::new(playlist_id, &name, owner, None, false)
+        let playlist_id = PlaylistId::from(self.id_generator.next_id().await?);
+        let owner_id = UserId::from(cmd.owner_id);
+        let owner = Owner {
+            id: owner_id.clone(),
+            name: cmd.owner_name,
         };
+        let mut playlist = Playlist::new(playlist_id, &name, owner, None, false);
 
-        // 添加歌曲
         for song_id in cmd.song_ids {
             let entry_id = self.id_generator.next_id().await?;
-            playlist.add_entry(entry_id, song_id);
+            playlist.add_entry(entry_id, song_id, Some(owner_id.clone()));
         }
 
-        // 保存
-        self.playlist_repository
-            .save(&mut playlist)
-            .await
-            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        self.playlist_repository.save(&mut playlist).await?;
+        Ok(playlist)
+    }
 
+    async fn replace_playlist_entries(
+        &self,
+        playlist_id: i64,
+        cmd: &CreatePlaylistCmd,
+    ) -> Result<Playlist, AppError> {
+        let user_id = UserId::from(cmd.owner_id);
+        let mut playlist = self.find_playlist(playlist_id).await?;
+        if !playlist.can_edit_entries(&user_id) {
+            return Err(AppError::AuthError(
+                "not allowed to edit this playlist".to_string(),
+            ));
+        }
+
+        if let Some(name) = cmd.name.as_deref().filter(|name| *name != playlist.name) {
+            if !playlist.is_owner(&user_id) {
+                return Err(AppError::AuthError(
+                    "only the owner can rename the playlist".to_string(),
+                ));
+            }
+            playlist.update_name(name);
+        }
+
+        if !cmd.song_ids.is_empty() {
+            let mut entry_ids = Vec::with_capacity(cmd.song_ids.len());
+            for _ in &cmd.song_ids {
+                entry_ids.push(self.id_generator.next_id().await?);
+            }
+            playlist.replace_entries(&cmd.song_ids, &mut entry_ids.into_iter(), Some(user_id))?;
+        }
+
+        self.playlist_repository.save(&mut playlist).await?;
         Ok(playlist)
     }
 
-    /// 删除播放列表
-    pub async fn delete_playlist(&self, playlist_id: i64) -> Result<(), AppError> {
-        self.playlist_repository
-            .delete(PlaylistId::from(playlist_id))
-            .await
-            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+    /// 删除播放列表，只有所有者可以删除。播放列表移入最近删除，保留期内可以恢复
+    pub async fn delete_playlist(&self, playlist_id: i64, user_id: i64) -> Result<(), AppError> {
+        retry_on_conflict("Playlist", playlist_id.to_string(), || async {
+            let mut playlist = self.find_playlist(playlist_id).await?;
+            if !playlist.is_owner(&UserId::from(user_id)) {
+                return Err(AppError::AuthError(
+                    "only the owner can delete the playlist".to_string(),
+                ));
+            }
+            playlist.delete();
+            self.playlist_repository.save(&mut playlist).await?;
+            Ok(())
+        })
+        .await
+    }
 
-        Ok(())
+    /// 从最近删除中恢复，只有所有者可以恢复
+    pub async fn restore_playlist(
+        &self,
+        playlist_id: i64,
+        user_id: i64,
+    ) -> Result<Playlist, AppError> {
+        retry_on_conflict("Playlist", playlist_id.to_string(), || async {
+            let mut playlist = self
+                .playlist_repository
+                .find_by_id(PlaylistId::from(playlist_id))
+                .await?
+                .filter(|playlist| {
+                    playlist.is_deleted() && playlist.is_owner(&UserId::from(user_id))
+                })
+                .ok_or_else(|| {
+                    AppError::AggregateNotFound(
+                        "Playlist".to_string(),
+                        format!("id {} is not in recently deleted", playlist_id),
+                    )
+                })?;
+            playlist.restore();
+            self.playlist_repository.save(&mut playlist).await?;
+            Ok(playlist)
+        })
+        .await
+    }
+
+    /// 永久删除在 before 之前删除的播放列表，返回删除的数量
+    pub async fn purge_deleted(&self, before: NaiveDateTime) -> Result<usize, AppError> {
+        let playlists = self.playlist_repository.find_deleted_before(before).await?;
+        for playlist in &playlists {
+            self.playlist_repository.delete(playlist.id.clone()).await?;
+            if let Some(path) = &playlist.cover_path {
+                self.remove_cover_file(path).await;
+            }
+        }
+        Ok(playlists.len())
     }
 
     /// 更新播放列表
+    ///
+    /// 名称、备注和公开状态只有所有者可以修改；协作者只能增删公开播放列表的条目。
     pub async fn update_playlist(&self, cmd: UpdatePlaylistCmd) -> Result<(), AppError> {
-        let mut playlist = self
-            .playlist_repository
-            .find_by_id(PlaylistId::from(cmd.playlist_id))
-            .await
-            .map_err(|e| AppError::UnknownError(e.to_string()))?
-            .ok_or_else(|| {
-                AppError::AggregateNotFound(
-                    "Playlist".to_string(),
-                    format!("id {} not found", cmd.playlist_id),
-                )
-            })?;
+        // 按索引删除依赖调用方看到的顺序，不能在新版本上重放
+        let max_attempts = if cmd.song_indexes_to_remove.is_empty() {
+            MAX_SAVE_ATTEMPTS
+        } else {
+            1
+        };
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.apply_update(&cmd).await {
+                Err(AppError::ConcurrentModification(..)) if attempt < max_attempts => {}
+                result => return result,
+            }
+        }
+    }
+
+    async fn apply_update(&self, cmd: &UpdatePlaylistCmd) -> Result<(), AppError> {
+        let user_id = UserId::from(cmd.user_id);
+        let mut playlist = self.find_playlist(cmd.playlist_id).await?;
+
+        let edits_details = cmd.name.is_some() || cmd.comment.is_some() || cmd.public.is_some();
+        if edits_details && !playlist.is_owner(&user_id) {
+            return Err(AppError::AuthError(
+                "only the owner can change playlist details".to_string(),
+            ));
+        }
+        let edits_entries =
+            !cmd.song_ids_to_add.is_empty() || !cmd.song_indexes_to_remove.is_empty();
+        if edits_entries && !playlist.can_edit_entries(&user_id) {
+            return Err(AppError::AuthError(
+                "not allowed to edit this playlist".to_string(),
+            ));
+        }
 
         // 更新名称
-        if let Some(name) = cmd.name {
-            playlist.update_name(&name);
+        if let Some(name) = &cmd.name {
+            playlist.update_name(name);
         }
 
         // 更新备注
-        if let Some(comment) = cmd.comment {
-            playlist.update_comment(Some(&comment));
+        if let Some(comment) = &cmd.comment {
+            playlist.update_comment(Some(comment));
         }
 
         // 更新公开状态
@@ -133,29 +299,251 @@ impl PlaylistAppService {
             playlist.set_public(public);
         }
 
-        // 按索引删除歌曲（从大到小排序，避免索引偏移问题）
-        let mut indexes_to_remove = cmd.song_indexes_to_remove;
-        indexes_to_remove.sort_by(|a, b| b.cmp(a));
-        for index in indexes_to_remove {
-            if index < playlist.entries.len() {
-                playlist.entries.remove(index);
-            }
+        // 按索引删除歌曲
+        if !cmd.song_indexes_to_remove.is_empty() {
+            playlist.remove_entries_at(&cmd.song_indexes_to_remove);
         }
 
         // 添加歌曲
-        for song_id in cmd.song_ids_to_add {
+        for &song_id in &cmd.song_ids_to_add {
             let entry_id = self.id_generator.next_id().await?;
-            playlist.add_entry(entry_id, song_id);
+            playlist.add_entry(entry_id, song_id, Some(user_id.clone()));
         }
 
-        // 保存
-        self.playlist_repository
-            .save(&mut playlist)
+        self.playlist_repository.save(&mut playlist).await?;
+        Ok(())
+    }
+
+    /// 批量添加歌曲
+    pub async fn add_entries(&self, cmd: AddPlaylistEntriesCmd) -> Result<(), AppError> {
+        let mut entry_ids = Vec::with_capacity(cmd.song_ids.len());
+        for _ in &cmd.song_ids {
+            entry_ids.push(self.id_generator.next_id().await?);
+        }
+        let items: Vec<(i64, i64)> = entry_ids.into_iter().zip(cmd.song_ids).collect();
+        let added_by = UserId::from(cmd.user_id);
+
+        self.edit_entries(cmd.playlist_id, cmd.user_id, |playlist| {
+            playlist.insert_entries(cmd.index, items.clone(), Some(added_by.clone()));
+            Ok(())
+        })
+        .await
+    }
+
+    /// 批量删除条目，返回删除的数量
+    pub async fn remove_entries(
+        &self,
+        playlist_id: i64,
+        user_id: i64,
+        entry_ids: Vec<i64>,
+    ) -> Result<usize, AppError> {
+        self.edit_entries(playlist_id, user_id, |playlist| {
+            Ok(playlist.remove_entries(&entry_ids))
+        })
+        .await
+    }
+
+    /// 移动单个条目
+    pub async fn move_entry(&self, cmd: MovePlaylistEntryCmd) -> Result<(), AppError> {
+        self.edit_entries(cmd.playlist_id, cmd.user_id, |playlist| {
+            playlist.move_entry(cmd.entry_id, cmd.index)
+        })
+        .await
+    }
+
+    /// 删除重复歌曲，返回删除的数量
+    pub async fn deduplicate_entries(
+        &self,
+        playlist_id: i64,
+        user_id: i64,
+    ) -> Result<usize, AppError> {
+        self.edit_entries(playlist_id, user_id, |playlist| Ok(playlist.deduplicate()))
             .await
-            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+    }
+
+    /// 按给定顺序保存条目（如客户端随机排序后保存）
+    pub async fn reorder_entries(
+        &self,
+        playlist_id: i64,
+        user_id: i64,
+        entry_ids: Vec<i64>,
+    ) -> Result<(), AppError> {
+        self.edit_entries(playlist_id, user_id, |playlist| {
+            playlist.reorder(&entry_ids)
+        })
+        .await
+    }
+
+    /// 加载、修改并保存条目；操作都以条目 ID 定位，冲突时在最新版本上重放
+    async fn edit_entries<T, F>(
+        &self,
+        playlist_id: i64,
+        user_id: i64,
+        mut edit: F,
+    ) -> Result<T, AppError>
+    where
+        F: FnMut(&mut Playlist) -> Result<T, PlaylistError> + Send,
+        T: Send,
+    {
+        let user_id = UserId::from(user_id);
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut playlist = self.find_playlist(playlist_id).await?;
+            if !playlist.can_edit_entries(&user_id) {
+                return Err(AppError::AuthError(
+                    "not allowed to edit this playlist".to_string(),
+                ));
+            }
+            let output = edit(&mut playlist)?;
+            match self.playlist_repository.save(&mut playlist).await {
+                Ok(()) => return Ok(output),
+                Err(PlaylistError::VersionConflict(_)) if attempt < MAX_SAVE_ATTEMPTS => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// 设置协作者，只有所有者可以修改
+    pub async fn set_collaborators(
+        &self,
+        cmd: SetPlaylistCollaboratorsCmd,
+    ) -> Result<(), AppError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut playlist = self.find_playlist(cmd.playlist_id).await?;
+            if !playlist.is_owner(&UserId::from(cmd.user_id)) {
+                return Err(AppError::AuthError(
+                    "only the owner can change collaborators".to_string(),
+                ));
+            }
+            playlist.set_collaborators(
+                cmd.collaborator_ids
+                    .iter()
+                    .map(|&id| UserId::from(id))
+                    .collect(),
+            );
+            match self.playlist_repository.save(&mut playlist).await {
+                Err(PlaylistError::VersionConflict(_)) if attempt < MAX_SAVE_ATTEMPTS => {}
+                result => return result.map_err(AppError::from),
+            }
+        }
+    }
 
+    /// 设置标签，只有所有者可以修改
+    pub async fn set_tags(&self, cmd: SetPlaylistTagsCmd) -> Result<Playlist, AppError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut playlist = self.find_playlist(cmd.playlist_id).await?;
+            if !playlist.is_owner(&UserId::from(cmd.user_id)) {
+                return Err(AppError::AuthError(
+                    "only the owner can change tags".to_string(),
+                ));
+            }
+            playlist.set_tags(cmd.tags.clone())?;
+            match self.playlist_repository.save(&mut playlist).await {
+                Err(PlaylistError::VersionConflict(_)) if attempt < MAX_SAVE_ATTEMPTS => {}
+                result => return result.map(|_| playlist).map_err(AppError::from),
+            }
+        }
+    }
+
+    /// 上传自定义封面，替换原有封面
+    pub async fn set_playlist_cover(&self, cmd: SetPlaylistCoverCmd) -> Result<(), AppError> {
+        if cmd.data.len() > MAX_PLAYLIST_COVER_SIZE {
+            return Err(AppError::InvalidCoverArtFormat(format!(
+                "cover exceeds {} bytes",
+                MAX_PLAYLIST_COVER_SIZE
+            )));
+        }
+        let extension = cover_extension(&cmd.data)?;
+        let mut playlist = self.owned_playlist(cmd.playlist_id, cmd.user_id).await?;
+
+        // 确认图片可以完整解码，避免保存损坏的文件
+        let data = tokio::task::spawn_blocking(move || {
+            image::load_from_memory(&cmd.data)
+                .map(|_| cmd.data)
+                .map_err(|e| AppError::InvalidCoverArtFormat(e.to_string()))
+        })
+        .await
+        .map_err(|e| AppError::UnknownError(e.to_string()))??;
+
+        let path = self
+            .cover_store
+            .save(cmd.playlist_id, extension, data)
+            .await?;
+        let previous = playlist.set_cover(path.clone());
+        self.playlist_repository.save(&mut playlist).await?;
+
+        if let Some(previous) = previous.filter(|p| *p != path) {
+            self.remove_cover_file(&previous).await;
+        }
         Ok(())
     }
+
+    /// 删除自定义封面，之后使用自动生成的封面
+    pub async fn remove_playlist_cover(
+        &self,
+        playlist_id: i64,
+        user_id: i64,
+    ) -> Result<(), AppError> {
+        let mut playlist = self.owned_playlist(playlist_id, user_id).await?;
+        let Some(previous) = playlist.clear_cover() else {
+            return Ok(());
+        };
+        self.playlist_repository.save(&mut playlist).await?;
+        self.remove_cover_file(&previous).await;
+        Ok(())
+    }
+
+    async fn owned_playlist(&self, playlist_id: i64, user_id: i64) -> Result<Playlist, AppError> {
+        let playlist = self.find_playlist(playlist_id).await?;
+        if !playlist.is_owner(&UserId::from(user_id)) {
+            return Err(AppError::AuthError(
+                "only the owner can change the playlist cover".to_string(),
+            ));
+        }
+        Ok(playlist)
+    }
+
+    /// 最近删除中的播放列表视为不存在
+    async fn find_playlist(&self, playlist_id: i64) -> Result<Playlist, AppError> {
+        self.playlist_repository
+            .find_by_id(PlaylistId::from(playlist_id))
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?
+            .filter(|playlist| !playlist.is_deleted())
+            .ok_or_else(|| {
+                AppError::AggregateNotFound(
+                    "Playlist".to_string(),
+                    format!("id {} not found", playlist_id),
+                )
+            })
+    }
+
+    /// 封面文件删除失败不影响播放列表本身
+    async fn remove_cover_file(&self, path: &str) {
+        if let Err(e) = self.cover_store.remove(path).await {
+            warn!("Failed to remove playlist cover {}: {}", path, e);
+        }
+    }
+}
+
+/// 只接受浏览器可直接显示的格式
+fn cover_extension(data: &[u8]) -> Result<&'static str, AppError> {
+    match image::guess_format(data) {
+        Ok(ImageFormat::Jpeg) => Ok("jpg"),
+        Ok(ImageFormat::Png) => Ok("png"),
+        Ok(ImageFormat::WebP) => Ok("webp"),
+        Ok(ImageFormat::Gif) => Ok("gif"),
+        Ok(format) => Err(AppError::InvalidCoverArtFormat(format!(
+            "unsupported image format: {:?}",
+            format
+        ))),
+        Err(e) => Err(AppError::InvalidCoverArtFormat(e.to_string())),
+    }
 }
 
 impl From<PlaylistError> for AppError {
@@ -169,7 +557,230 @@ impl From<PlaylistError> for AppError {
             PlaylistError::EntryNotFound(id) => {
                 AppError::AggregateNotFound("PlaylistEntry".to_string(), id.to_string())
             }
+            PlaylistError::PermissionDenied(msg) => AppError::AuthError(msg),
+            PlaylistError::VersionConflict(id) => {
+                AppError::ConcurrentModification("Playlist".to_string(), id.to_string())
+            }
             PlaylistError::OtherErr(msg) => AppError::UnknownError(msg),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::SequentialIdGenerator;
+    use chrono::{Duration, Utc};
+    use domain::playlist::Owner;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// 保存时检查版本；conflicts 大于 0 时下一次保存先返回版本冲突
+    #[derive(Default)]
+    struct MemoryPlaylistRepository {
+        playlists: Mutex<HashMap<i64, Playlist>>,
+        conflicts: AtomicUsize,
+    }
+
+    impl MemoryPlaylistRepository {
+        fn get(&self, id: i64) -> Option<Playlist> {
+            self.playlists.lock().unwrap().get(&id).cloned()
+        }
+    }
+
+    #[async_trait]
+    impl PlaylistRepository for MemoryPlaylistRepository {
+        async fn find_by_id(&self, id: PlaylistId) -> Result<Option<Playlist>, PlaylistError> {
+            Ok(self.get(id.as_i64()))
+        }
+
+        async fn save(&self, playlist: &mut Playlist) -> Result<(), PlaylistError> {
+            let id = playlist.id.as_i64();
+            let stored_version = self.get(id).map_or(0, |p| p.version);
+            if self.conflicts.load(Ordering::SeqCst) > 0 {
+                self.conflicts.fetch_sub(1, Ordering::SeqCst);
+                return Err(PlaylistError::VersionConflict(id));
+            }
+            if playlist.version != stored_version {
+                return Err(PlaylistError::VersionConflict(id));
+            }
+            playlist.version += 1;
+            self.playlists.lock().unwrap().insert(id, playlist.clone());
+            Ok(())
+        }
+
+        async fn delete(&self, id: PlaylistId) -> Result<(), PlaylistError> {
+            self.playlists.lock().unwrap().remove(&id.as_i64());
+            Ok(())
+        }
+
+        async fn find_deleted_before(
+            &self,
+            before: NaiveDateTime,
+        ) -> Result<Vec<Playlist>, PlaylistError> {
+            Ok(self
+                .playlists
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|p| p.deleted_at.is_some_and(|at| at < before))
+                .cloned()
+                .collect())
+        }
+
+        async fn truncate(&self) -> Result<(), PlaylistError> {
+            self.playlists.lock().unwrap().clear();
+            Ok(())
+        }
+
+        async fn find_by_owner_id(&self, owner_id: UserId) -> Result<Vec<Playlist>, PlaylistError> {
+            Ok(self
+                .playlists
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|p| p.owner.id == owner_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingCoverStore {
+        removed: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl PlaylistCoverStore for RecordingCoverStore {
+        async fn save(
+            &self,
+            playlist_id: i64,
+            extension: &str,
+            _data: Vec<u8>,
+        ) -> Result<String, AppError> {
+            Ok(format!("/covers/{}.{}", playlist_id, extension))
+        }
+
+        async fn remove(&self, path: &str) -> Result<(), AppError> {
+            self.removed.lock().unwrap().push(path.to_string());
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        repository: Arc<MemoryPlaylistRepository>,
+        covers: Arc<RecordingCoverStore>,
+        service: PlaylistAppService,
+    }
+
+    /// 用户 1 拥有播放列表 10（带封面）和 11
+    async fn fixture() -> Fixture {
+        let repository = Arc::new(MemoryPlaylistRepository::default());
+        let covers = Arc::new(RecordingCoverStore::default());
+        for id in [10, 11] {
+            let owner = Owner {
+                id: UserId::from(1),
+                name: "alice".to_string(),
+            };
+            let mut playlist = Playlist::new(PlaylistId::from(id), "Mix", owner, None, false);
+            playlist.add_entry(id * 100, 1000, None);
+            if id == 10 {
+                playlist.set_cover("/covers/10.jpg".to_string());
+            }
+            repository.save(&mut playlist).await.unwrap();
+        }
+        let service = PlaylistAppService::new(
+            repository.clone(),
+            Arc::new(SequentialIdGenerator::new(1)),
+            covers.clone(),
+        );
+        Fixture {
+            repository,
+            covers,
+            service,
+        }
+    }
+
+    #[tokio::test]
+    async fn deleted_playlists_can_be_restored_by_the_owner_only() {
+        let f = fixture().await;
+        assert!(matches!(
+            f.service.delete_playlist(10, 2).await,
+            Err(AppError::AuthError(_))
+        ));
+
+        f.service.delete_playlist(10, 1).await.unwrap();
+        assert!(f.repository.get(10).unwrap().is_deleted());
+        // 最近删除中的播放列表不能再删除或编辑
+        assert!(matches!(
+            f.service.delete_playlist(10, 1).await,
+            Err(AppError::AggregateNotFound(..))
+        ));
+        assert!(matches!(
+            f.service.restore_playlist(10, 2).await,
+            Err(AppError::AggregateNotFound(..))
+        ));
+        assert!(matches!(
+            f.service.restore_playlist(11, 1).await,
+            Err(AppError::AggregateNotFound(..))
+        ));
+
+        let restored = f.service.restore_playlist(10, 1).await.unwrap();
+        assert!(!restored.is_deleted());
+        assert_eq!(restored.song_count(), 1);
+        assert_eq!(restored.cover_path.as_deref(), Some("/covers/10.jpg"));
+        assert!(f.covers.removed.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn delete_retries_version_conflicts() {
+        let f = fixture().await;
+        f.repository.conflicts.store(1, Ordering::SeqCst);
+        f.service.delete_playlist(10, 1).await.unwrap();
+        assert!(f.repository.get(10).unwrap().is_deleted());
+
+        f.repository
+            .conflicts
+            .store(MAX_SAVE_ATTEMPTS, Ordering::SeqCst);
+        assert!(matches!(
+            f.service.restore_playlist(10, 1).await,
+            Err(AppError::ConcurrentModification(..))
+        ));
+        assert!(f.repository.get(10).unwrap().is_deleted());
+    }
+
+    #[tokio::test]
+    async fn purge_removes_only_expired_playlists_and_their_covers() {
+        let f = fixture().await;
+        f.service.delete_playlist(10, 1).await.unwrap();
+        f.service.delete_playlist(11, 1).await.unwrap();
+        let now = Utc::now().naive_utc();
+        f.repository
+            .playlists
+            .lock()
+            .unwrap()
+            .get_mut(&10)
+            .unwrap()
+            .deleted_at = Some(now - Duration::days(31));
+
+        let purged = f
+            .service
+            .purge_deleted(now - Duration::days(30))
+            .await
+            .unwrap();
+
+        assert_eq!(purged, 1);
+        assert!(f.repository.get(10).is_none());
+        assert!(f.repository.get(11).unwrap().is_deleted());
+        assert_eq!(
+            *f.covers.removed.lock().unwrap(),
+            vec!["/covers/10.jpg".to_string()]
+        );
+        // 已永久删除的播放列表不能再恢复
+        assert!(matches!(
+            f.service.restore_playlist(10, 1).await,
+            Err(AppError::AggregateNotFound(..))
+        ));
+    }
+}
@@ -0,0 +1,383 @@
+use crate::error::AppError;
+use crate::shared::SystemConfigStore;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 可在运行时修改的系统设置项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettingKey {
+    IgnoredArticles,
+    CoverArtWildcards,
+    /// 库未单独配置定时扫描时使用的间隔（分钟）
+    ScanIntervalMinutes,
+    LastFmApiKey,
+    LastFmSecret,
+    SpotifyClientId,
+    SpotifyClientSecret,
+    PlayQueueMode,
+}
+
+impl SettingKey {
+    pub const ALL: [SettingKey; 8] = [
+        SettingKey::IgnoredArticles,
+        SettingKey::CoverArtWildcards,
+        SettingKey::ScanIntervalMinutes,
+        SettingKey::LastFmApiKey,
+        SettingKey::LastFmSecret,
+        SettingKey::SpotifyClientId,
+        SettingKey::SpotifyClientSecret,
+        SettingKey::PlayQueueMode,
+    ];
+
+    /// 接口中使用的名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            SettingKey::IgnoredArticles => "ignoredArticles",
+            SettingKey::CoverArtWildcards => "coverArtWildcards",
+            SettingKey::ScanIntervalMinutes => "scanIntervalMinutes",
+            SettingKey::LastFmApiKey => "lastFmApiKey",
+            SettingKey::LastFmSecret => "lastFmSecret",
+            SettingKey::SpotifyClientId => "spotifyClientId",
+            SettingKey::SpotifyClientSecret => "spotifyClientSecret",
+            SettingKey::PlayQueueMode => "playQueueMode",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|key| key.name() == name)
+    }
+
+    /// 密钥类设置只写不读
+    pub fn is_secret(&self) -> bool {
+        matches!(
+            self,
+            SettingKey::LastFmSecret | SettingKey::SpotifyClientSecret
+        )
+    }
+
+    fn store_key(&self) -> &'static str {
+        match self {
+            SettingKey::IgnoredArticles => "settings.ignored_articles",
+            SettingKey::CoverArtWildcards => "settings.cover_art_wildcards",
+            SettingKey::ScanIntervalMinutes => "settings.scan_interval_minutes",
+            SettingKey::LastFmApiKey => "settings.lastfm_api_key",
+            SettingKey::LastFmSecret => "settings.lastfm_secret",
+            SettingKey::SpotifyClientId => "settings.spotify_client_id",
+            SettingKey::SpotifyClientSecret => "settings.spotify_client_secret",
+            SettingKey::PlayQueueMode => "settings.play_queue_mode",
+        }
+    }
+}
+
+/// 播放队列同步策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlayQueueMode {
+    /// 所有设备共用一个队列
+    #[default]
+    Shared,
+    /// 按客户端唯一 ID 为每个设备单独保存
+    PerDevice,
+}
+
+impl PlayQueueMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayQueueMode::Shared => "shared",
+            PlayQueueMode::PerDevice => "perDevice",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "shared" => Some(PlayQueueMode::Shared),
+            "perDevice" => Some(PlayQueueMode::PerDevice),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingValue {
+    List(Vec<String>),
+    Number(i64),
+    Text(String),
+}
+
+/// 已保存的设置，None 表示沿用配置文件中的值
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Settings {
+    pub ignored_articles: Option<Vec<String>>,
+    pub cover_art_wildcards: Option<Vec<String>>,
+    pub scan_interval_minutes: Option<i32>,
+    pub lastfm_api_key: Option<String>,
+    pub lastfm_secret: Option<String>,
+    pub spotify_client_id: Option<String>,
+    pub spotify_client_secret: Option<String>,
+    pub play_queue_mode: Option<PlayQueueMode>,
+}
+
+impl Settings {
+    /// 是否保存了该项（即覆盖了配置文件）
+    pub fn is_set(&self, key: SettingKey) -> bool {
+        self.encode(key).is_some()
+    }
+
+    fn apply(&mut self, key: SettingKey, value: Option<SettingValue>) -> Result<(), AppError> {
+        let invalid =
+            |expected: &str| AppError::InvalidInput(format!("{} must be {}", key.name(), expected));
+        match key {
+            SettingKey::IgnoredArticles | SettingKey::CoverArtWildcards => {
+                let list = match value {
+                    None => None,
+                    Some(SettingValue::List(list)) => Some(list),
+                    // 兼容 Subsonic 的空格分隔写法
+                    Some(SettingValue::Text(text)) => {
+                        Some(text.split_whitespace().map(str::to_string).collect())
+                    }
+                    Some(_) => return Err(invalid("a list of strings")),
+                };
+                let list = list.map(|list| {
+                    list.into_iter()
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect::<Vec<_>>()
+                });
+                if key == SettingKey::IgnoredArticles {
+                    self.ignored_articles = list;
+                } else {
+                    if list.as_ref().is_some_and(|l| l.is_empty()) {
+                        return Err(invalid("a non-empty list"));
+                    }
+                    self.cover_art_wildcards = list;
+                }
+            }
+            SettingKey::ScanIntervalMinutes => {
+                self.scan_interval_minutes = match value {
+                    None => None,
+                    Some(SettingValue::Number(n)) if (0..=i32::MAX as i64).contains(&n) => {
+                        Some(n as i32)
+                    }
+                    Some(_) => return Err(invalid("a non-negative number")),
+                };
+            }
+            SettingKey::PlayQueueMode => {
+                self.play_queue_mode = match value {
+                    None => None,
+                    Some(SettingValue::Text(text)) => match PlayQueueMode::from_name(text.trim()) {
+                        Some(mode) => Some(mode),
+                        None => return Err(invalid("\"shared\" or \"perDevice\"")),
+                    },
+                    Some(_) => return Err(invalid("a string")),
+                };
+            }
+            _ => {
+                let text = match value {
+                    None => None,
+                    Some(SettingValue::Text(text)) => {
+                        Some(text.trim().to_string()).filter(|t| !t.is_empty())
+                    }
+                    Some(_) => return Err(invalid("a string")),
+                };
+                *self.text_mut(key) = text;
+            }
+        }
+        Ok(())
+    }
+
+    fn text_mut(&mut self, key: SettingKey) -> &mut Option<String> {
+        match key {
+            SettingKey::LastFmApiKey => &mut self.lastfm_api_key,
+            SettingKey::LastFmSecret => &mut self.lastfm_secret,
+            SettingKey::SpotifyClientId => &mut self.spotify_client_id,
+            _ => &mut self.spotify_client_secret,
+        }
+    }
+
+    fn encode(&self, key: SettingKey) -> Option<String> {
+        match key {
+            SettingKey::IgnoredArticles => self.ignored_articles.as_ref().map(|l| l.join("\n")),
+            SettingKey::CoverArtWildcards => {
+                self.cover_art_wildcards.as_ref().map(|l| l.join("\n"))
+            }
+            SettingKey::ScanIntervalMinutes => self.scan_interval_minutes.map(|n| n.to_string()),
+            SettingKey::LastFmApiKey => self.lastfm_api_key.clone(),
+            SettingKey::LastFmSecret => self.lastfm_secret.clone(),
+            SettingKey::SpotifyClientId => self.spotify_client_id.clone(),
+            SettingKey::SpotifyClientSecret => self.spotify_client_secret.clone(),
+            SettingKey::PlayQueueMode => self.play_queue_mode.map(|m| m.name().to_string()),
+        }
+    }
+
+    fn decode(&mut self, key: SettingKey, raw: String) {
+        let value = match key {
+            SettingKey::IgnoredArticles | SettingKey::CoverArtWildcards => {
+                SettingValue::List(raw.lines().map(str::to_string).collect())
+            }
+            SettingKey::ScanIntervalMinutes => match raw.parse() {
+                Ok(n) => SettingValue::Number(n),
+                Err(_) => {
+                    log::warn!("Invalid {} value: {}", key.store_key(), raw);
+                    return;
+                }
+            },
+            _ => SettingValue::Text(raw),
+        };
+        if let Err(e) = self.apply(key, Some(value)) {
+            log::warn!("Ignoring stored {}: {}", key.store_key(), e);
+        }
+    }
+}
+
+/// 设置变更通知，依赖设置的组件据此刷新而无需重启
+pub trait SettingsListener: Send + Sync {
+    fn settings_changed(&self, settings: &Settings);
+}
+
+pub struct SettingsService {
+    store: Arc<dyn SystemConfigStore>,
+    listeners: Vec<Arc<dyn SettingsListener>>,
+}
+
+impl SettingsService {
+    pub fn new(
+        store: Arc<dyn SystemConfigStore>,
+        listeners: Vec<Arc<dyn SettingsListener>>,
+    ) -> Self {
+        Self { store, listeners }
+    }
+
+    pub async fn load(&self) -> Result<Settings, AppError> {
+        let mut settings = Settings::default();
+        for key in SettingKey::ALL {
+            if let Some(raw) = self
+                .store
+                .get_string(key.store_key())
+                .await
+                .map_err(store_error)?
+            {
+                settings.decode(key, raw);
+            }
+        }
+        Ok(settings)
+    }
+
+    /// 校验全部变更后再写入，值为 None 的项恢复为配置文件中的值
+    pub async fn update(
+        &self,
+        changes: HashMap<SettingKey, Option<SettingValue>>,
+    ) -> Result<Settings, AppError> {
+        let mut settings = self.load().await?;
+        for (key, value) in &changes {
+            settings.apply(*key, value.clone())?;
+        }
+        for key in changes.keys() {
+            match settings.encode(*key) {
+                Some(raw) => self.store.set_string(key.store_key(), &raw).await,
+                None => self.store.delete(key.store_key()).await,
+            }
+            .map_err(store_error)?;
+        }
+        self.notify(&settings);
+        Ok(settings)
+    }
+
+    /// 启动时加载已保存的设置并分发给监听者
+    pub async fn reload(&self) -> Result<Settings, AppError> {
+        let settings = self.load().await?;
+        self.notify(&settings);
+        Ok(settings)
+    }
+
+    fn notify(&self, settings: &Settings) {
+        for listener in &self.listeners {
+            listener.settings_changed(settings);
+        }
+    }
+}
+
+fn store_error(e: anyhow::Error) -> AppError {
+    AppError::RepositoryError("SystemConfig".to_string(), e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_settings_round_trip_through_store_encoding() {
+        let mut settings = Settings::default();
+        settings
+            .apply(
+                SettingKey::IgnoredArticles,
+                Some(SettingValue::Text("The  El La".to_string())),
+            )
+            .unwrap();
+        let raw = settings.encode(SettingKey::IgnoredArticles).unwrap();
+
+        let mut decoded = Settings::default();
+        decoded.decode(SettingKey::IgnoredArticles, raw);
+        assert_eq!(
+            decoded.ignored_articles,
+            Some(vec!["The".to_string(), "El".to_string(), "La".to_string()])
+        );
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_kind() {
+        let mut settings = Settings::default();
+        assert!(settings
+            .apply(
+                SettingKey::ScanIntervalMinutes,
+                Some(SettingValue::Number(-5))
+            )
+            .is_err());
+        assert!(settings
+            .apply(
+                SettingKey::CoverArtWildcards,
+                Some(SettingValue::List(vec![" ".to_string()]))
+            )
+            .is_err());
+        assert!(settings
+            .apply(SettingKey::LastFmApiKey, Some(SettingValue::Number(1)))
+            .is_err());
+    }
+
+    #[test]
+    fn blank_text_clears_the_setting() {
+        let mut settings = Settings {
+            lastfm_api_key: Some("key".to_string()),
+            ..Default::default()
+        };
+        settings
+            .apply(
+                SettingKey::LastFmApiKey,
+                Some(SettingValue::Text("  ".to_string())),
+            )
+            .unwrap();
+        assert_eq!(settings.lastfm_api_key, None);
+    }
+
+    #[test]
+    fn play_queue_mode_accepts_known_names_only() {
+        let mut settings = Settings::default();
+        settings
+            .apply(
+                SettingKey::PlayQueueMode,
+                Some(SettingValue::Text("perDevice".to_string())),
+            )
+            .unwrap();
+        assert_eq!(settings.play_queue_mode, Some(PlayQueueMode::PerDevice));
+
+        let raw = settings.encode(SettingKey::PlayQueueMode).unwrap();
+        let mut decoded = Settings::default();
+        decoded.decode(SettingKey::PlayQueueMode, raw);
+        assert_eq!(decoded.play_queue_mode, Some(PlayQueueMode::PerDevice));
+
+        assert!(settings
+            .apply(
+                SettingKey::PlayQueueMode,
+                Some(SettingValue::Text("device".to_string()))
+            )
+            .is_err());
+    }
+}
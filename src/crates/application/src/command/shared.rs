@@ -1,4 +1,31 @@
 use crate::error::AppError;
+use chrono::{DateTime, Duration, Utc};
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+
+/// 并发修改冲突时的最大尝试次数
+pub const MAX_SAVE_ATTEMPTS: usize = 3;
+
+/// 保存遇到版本冲突时重新执行 op，op 每次都要重新加载聚合再应用修改。
+/// 只适用于可以在最新版本上重放的修改，用尽次数后返回 ConcurrentModification
+pub async fn retry_on_conflict<T, F, Fut>(kind: &str, id: String, mut op: F) -> Result<T, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match op().await {
+            Err(e) if e.is_conflict() && attempt < MAX_SAVE_ATTEMPTS => {}
+            Err(e) if e.is_conflict() => {
+                return Err(AppError::ConcurrentModification(kind.to_string(), id))
+            }
+            result => return result,
+        }
+    }
+}
 
 /// 通用ID生成器接口，所有需要生成唯一ID的领域服务都可以使用此接口
 #[async_trait::async_trait]
@@ -10,3 +37,115 @@ pub trait IdGenerator: Send + Sync {
     /// business_key 可以是不同的业务类型标识，如 "genre"、"album" 等
     async fn next_id_with_business(&self, business_key: &str) -> Result<i64, AppError>;
 }
+
+/// 当前时间的来源。服务通过它取时间再传给聚合，测试中替换为 FakeClock
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 系统时间
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 只在调用 set / advance 时变化的时钟
+#[derive(Debug)]
+pub struct FakeClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FakeClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// 从指定值开始依次递增的 ID，业务标识不影响结果
+#[derive(Debug)]
+pub struct SequentialIdGenerator {
+    next: AtomicI64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new(first: i64) -> Self {
+        Self {
+            next: AtomicI64::new(first),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl IdGenerator for SequentialIdGenerator {
+    async fn next_id(&self) -> Result<i64, AppError> {
+        Ok(self.next.fetch_add(1, Ordering::Relaxed))
+    }
+
+    async fn next_id_with_business(&self, _business_key: &str) -> Result<i64, AppError> {
+        self.next_id().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::album::AlbumError;
+    use std::sync::atomic::AtomicUsize;
+
+    #[tokio::test]
+    async fn retries_conflicts_until_the_limit() {
+        let calls = AtomicUsize::new(0);
+        let result = retry_on_conflict("Album", "1".to_string(), || async {
+            match calls.fetch_add(1, Ordering::Relaxed) {
+                0 => Err(AppError::AlbumError(AlbumError::VersionConflictErr(2))),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 1);
+
+        calls.store(0, Ordering::Relaxed);
+        let result: Result<(), AppError> = retry_on_conflict("Album", "1".to_string(), || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err(AppError::AlbumError(AlbumError::VersionConflictErr(2)))
+        })
+        .await;
+        assert!(matches!(
+            result,
+            Err(AppError::ConcurrentModification(kind, id)) if kind == "Album" && id == "1"
+        ));
+        assert_eq!(calls.load(Ordering::Relaxed), MAX_SAVE_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_retried() {
+        let calls = AtomicUsize::new(0);
+        let result: Result<(), AppError> = retry_on_conflict("Album", "1".to_string(), || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err(AppError::InvalidInput("bad".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+}
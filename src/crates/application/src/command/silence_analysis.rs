@@ -0,0 +1,321 @@
+use crate::command::shared::Clock;
+use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::value::{AudibleRange, LibraryId, MediaPath};
+use futures::stream::{self, StreamExt};
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 每批读取的待分析歌曲数
+const BATCH_SIZE: u64 = 100;
+/// 同时运行的 ffmpeg 进程数，分析需要解码整首歌，占用 CPU
+const ANALYSIS_CONCURRENCY: usize = 2;
+/// 静音距离开头或结尾不超过这么多毫秒就算作首尾静音
+const EDGE_TOLERANCE_MS: i64 = 100;
+
+/// 检测到的一段静音，end 为 None 表示一直持续到文件结尾
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SilenceInterval {
+    pub start: i64,
+    pub end: Option<i64>,
+}
+
+/// 一个文件的检测结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SilenceScan {
+    /// 解码得到的时长（毫秒），无法获得时使用库中记录的时长
+    pub duration: Option<i64>,
+    pub intervals: Vec<SilenceInterval>,
+}
+
+#[async_trait]
+pub trait SilenceDetector: Send + Sync {
+    async fn detect(&self, path: &MediaPath) -> Result<SilenceScan, AppError>;
+}
+
+/// 需要分析的歌曲：从未分析过，或分析后文件又被更新
+#[derive(Debug, Clone)]
+pub struct PendingTrack {
+    pub id: i64,
+    pub path: MediaPath,
+    /// 毫秒
+    pub duration: i64,
+}
+
+#[async_trait]
+pub trait AudibleRangeStore: Send + Sync {
+    /// `library_id` 为 None 时返回所有库的歌曲
+    async fn pending(
+        &self,
+        library_id: Option<&LibraryId>,
+        limit: u64,
+    ) -> Result<Vec<PendingTrack>, AppError>;
+    /// range 为 None 表示无法分析或整首静音，在文件更新前不再重试
+    async fn save(
+        &self,
+        id: i64,
+        range: Option<AudibleRange>,
+        analyzed_at: NaiveDateTime,
+    ) -> Result<(), AppError>;
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SilenceAnalysisReport {
+    pub analyzed: usize,
+    /// 无法读取或解码的文件数
+    pub failed: usize,
+}
+
+/// 在后台用 ffmpeg 找出每首歌首尾静音的位置，同一时间只运行一次
+pub struct SilenceAnalysisService {
+    store: Arc<dyn AudibleRangeStore>,
+    detector: Arc<dyn SilenceDetector>,
+    clock: Arc<dyn Clock>,
+    running: AtomicBool,
+    last_report: Mutex<Option<SilenceAnalysisReport>>,
+}
+
+impl SilenceAnalysisService {
+    pub fn new(
+        store: Arc<dyn AudibleRangeStore>,
+        detector: Arc<dyn SilenceDetector>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            store,
+            detector,
+            clock,
+            running: AtomicBool::new(false),
+            last_report: Mutex::new(None),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// 上一次完成的分析结果
+    pub fn last_report(&self) -> Option<SilenceAnalysisReport> {
+        self.last_report.lock().unwrap().clone()
+    }
+
+    /// 分析所有待处理的歌曲。已经在运行时返回 None，新歌会被正在运行的分析读到
+    pub async fn run(
+        &self,
+        library_id: Option<LibraryId>,
+    ) -> Result<Option<SilenceAnalysisReport>, AppError> {
+        if self.running.swap(true, Ordering::AcqRel) {
+            return Ok(None);
+        }
+        let result = self.analyze_pending(library_id.as_ref()).await;
+        self.running.store(false, Ordering::Release);
+        let report = result?;
+        info!(
+            "Silence analysis finished: {} analyzed, {} failed",
+            report.analyzed, report.failed
+        );
+        *self.last_report.lock().unwrap() = Some(report.clone());
+        Ok(Some(report))
+    }
+
+    async fn analyze_pending(
+        &self,
+        library_id: Option<&LibraryId>,
+    ) -> Result<SilenceAnalysisReport, AppError> {
+        let mut report = SilenceAnalysisReport::default();
+        loop {
+            let tracks = self.store.pending(library_id, BATCH_SIZE).await?;
+            if tracks.is_empty() {
+                return Ok(report);
+            }
+            let results: Vec<(PendingTrack, Result<SilenceScan, AppError>)> = stream::iter(tracks)
+                .map(|track| async move {
+                    let scan = self.detector.detect(&track.path).await;
+                    (track, scan)
+                })
+                .buffer_unordered(ANALYSIS_CONCURRENCY)
+                .collect()
+                .await;
+            // 每条都会写入分析时间，下一批不会再读到这些歌曲
+            let analyzed_at = self.clock.now().naive_utc();
+            for (track, scan) in results {
+                let range = match scan {
+                    Ok(scan) => {
+                        report.analyzed += 1;
+                        let duration = scan.duration.unwrap_or(track.duration);
+                        audible_range(&scan.intervals, duration)
+                    }
+                    Err(e) => {
+                        warn!("Failed to analyze silence of {}: {}", track.path.path, e);
+                        report.failed += 1;
+                        None
+                    }
+                };
+                self.store.save(track.id, range, analyzed_at).await?;
+            }
+        }
+    }
+}
+
+/// 开头的静音结束处到结尾的静音开始处；整首都是静音时返回 None
+pub fn audible_range(intervals: &[SilenceInterval], duration: i64) -> Option<AudibleRange> {
+    let leading = intervals
+        .iter()
+        .find(|interval| interval.start <= EDGE_TOLERANCE_MS);
+    let start = match leading {
+        Some(SilenceInterval { end: None, .. }) => return None,
+        Some(SilenceInterval { end: Some(end), .. }) => *end,
+        None => 0,
+    };
+    let trailing = intervals.iter().rev().find(|interval| {
+        interval
+            .end
+            .is_none_or(|end| end >= duration - EDGE_TOLERANCE_MS)
+    });
+    let end = match trailing {
+        Some(interval) if interval.start > start => interval.start,
+        _ => duration,
+    };
+    (start < end).then_some(AudibleRange { start, end })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::FakeClock;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn silence(start: i64, end: Option<i64>) -> SilenceInterval {
+        SilenceInterval { start, end }
+    }
+
+    #[test]
+    fn trims_leading_and_trailing_silence_only() {
+        let intervals = [
+            silence(0, Some(1200)),
+            silence(90_000, Some(92_000)),
+            silence(181_000, None),
+        ];
+        assert_eq!(
+            audible_range(&intervals, 183_000),
+            Some(AudibleRange {
+                start: 1200,
+                end: 181_000
+            })
+        );
+        // 结尾的静音一直到文件结束，但 ffmpeg 也给出了结束位置
+        let intervals = [silence(181_000, Some(182_950))];
+        assert_eq!(
+            audible_range(&intervals, 183_000),
+            Some(AudibleRange {
+                start: 0,
+                end: 181_000
+            })
+        );
+        assert_eq!(
+            audible_range(&[silence(90_000, Some(92_000))], 183_000),
+            Some(AudibleRange {
+                start: 0,
+                end: 183_000
+            })
+        );
+        assert_eq!(audible_range(&[silence(0, None)], 183_000), None);
+    }
+
+    #[derive(Default)]
+    struct MemoryAudibleRangeStore {
+        pending: Mutex<Vec<PendingTrack>>,
+        saved: Mutex<HashMap<i64, Option<AudibleRange>>>,
+    }
+
+    #[async_trait]
+    impl AudibleRangeStore for MemoryAudibleRangeStore {
+        async fn pending(
+            &self,
+            _library_id: Option<&LibraryId>,
+            limit: u64,
+        ) -> Result<Vec<PendingTrack>, AppError> {
+            let saved = self.saved.lock().unwrap();
+            Ok(self
+                .pending
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|track| !saved.contains_key(&track.id))
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn save(
+            &self,
+            id: i64,
+            range: Option<AudibleRange>,
+            _analyzed_at: NaiveDateTime,
+        ) -> Result<(), AppError> {
+            self.saved.lock().unwrap().insert(id, range);
+            Ok(())
+        }
+    }
+
+    /// 路径为 "broken" 的文件无法解码，其余文件开头有 500 毫秒静音
+    struct FixedDetector;
+
+    #[async_trait]
+    impl SilenceDetector for FixedDetector {
+        async fn detect(&self, path: &MediaPath) -> Result<SilenceScan, AppError> {
+            if path.path == "broken" {
+                return Err(AppError::UnknownError("invalid data".to_string()));
+            }
+            Ok(SilenceScan {
+                duration: None,
+                intervals: vec![silence(0, Some(500))],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn records_failures_so_they_are_not_retried() {
+        let store = Arc::new(MemoryAudibleRangeStore::default());
+        *store.pending.lock().unwrap() = (1..=3)
+            .map(|id| PendingTrack {
+                id,
+                path: MediaPath {
+                    protocol: "local".to_string(),
+                    path: if id == 2 { "broken" } else { "ok" }.to_string(),
+                },
+                duration: 60_000,
+            })
+            .collect();
+        let service = SilenceAnalysisService::new(
+            store.clone(),
+            Arc::new(FixedDetector),
+            Arc::new(FakeClock::new(
+                Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap(),
+            )),
+        );
+
+        let report = service.run(None).await.unwrap().unwrap();
+        assert_eq!(
+            report,
+            SilenceAnalysisReport {
+                analyzed: 2,
+                failed: 1
+            }
+        );
+        let saved = store.saved.lock().unwrap();
+        assert_eq!(
+            saved[&1],
+            Some(AudibleRange {
+                start: 500,
+                end: 60_000
+            })
+        );
+        assert_eq!(saved[&2], None);
+        assert!(!service.is_running());
+        assert_eq!(service.last_report(), Some(report));
+    }
+}
@@ -0,0 +1,687 @@
+use crate::command::library::AddLibraryFilesCmd;
+use crate::command::merge::{MergeChanges, MergeStore};
+use crate::command::shared::IdGenerator;
+use crate::context::AppContext;
+use crate::error::AppError;
+use crate::event::event_bus::{EventBus, EventEnvelope};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::album::{Album, AlbumRepository};
+use domain::annotation::Kind;
+use domain::audio_file::{AudioFile, AudioFileRepository};
+use domain::library::{Library, LibraryError, LibraryEvent, LibraryRepository, ScanStatus};
+use domain::value::{AlbumId, AudioFileId, FileMeta, LibraryId, MediaPath};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashKind {
+    Song,
+    Album,
+}
+
+impl TrashKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrashKind::Song => "song",
+            TrashKind::Album => "album",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "song" => Some(TrashKind::Song),
+            "album" => Some(TrashKind::Album),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrashedFile {
+    pub library_id: LibraryId,
+    /// 删除前在库中的位置，恢复时移回这里
+    pub path: MediaPath,
+    /// 删除前的歌曲 ID，重新入库后注解从这里迁移；早期的条目没有记录
+    pub audio_file_id: Option<AudioFileId>,
+}
+
+/// 回收站中的一次删除：一首歌或一张专辑的全部歌曲
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    pub id: i64,
+    pub kind: TrashKind,
+    pub name: String,
+    pub files: Vec<TrashedFile>,
+    pub deleted_by: String,
+    pub deleted_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait TrashStore: Send + Sync {
+    /// 把条目中的文件移入回收站；中途失败时已移动的文件会被移回
+    async fn put(&self, entry: &TrashEntry) -> Result<(), AppError>;
+    /// 按删除时间倒序
+    async fn list(&self) -> Result<Vec<TrashEntry>, AppError>;
+    async fn find(&self, id: i64) -> Result<Option<TrashEntry>, AppError>;
+    /// 把文件移回原位置并删除条目，返回与 `entry.files` 顺序一致的文件信息。
+    /// 记录了歌曲 ID 的文件会留下恢复记录，直到重新入库时被 `take_restored` 取走
+    async fn restore(&self, entry: &TrashEntry) -> Result<Vec<FileMeta>, AppError>;
+    /// 取出并删除该路径的恢复记录，返回删除前的歌曲 ID
+    async fn take_restored(&self, path: &MediaPath) -> Result<Option<AudioFileId>, AppError>;
+    /// 永久删除条目及其文件
+    async fn remove(&self, id: i64) -> Result<(), AppError>;
+}
+
+pub struct TrashSongCmd {
+    pub audio_file_id: AudioFileId,
+    pub deleted_by: String,
+}
+
+pub struct TrashAlbumCmd {
+    pub album_id: AlbumId,
+    pub deleted_by: String,
+}
+
+/// 回收站：删除时文件移入回收站目录，并通过聚合事件清理专辑、艺术家、流派等投影；
+/// 恢复时文件移回原位置，作为新文件重新入库，收藏、评分和播放次数随后迁移到新歌曲
+pub struct TrashService<B: EventBus> {
+    id_generator: Arc<dyn IdGenerator>,
+    trash_store: Arc<dyn TrashStore>,
+    library_repository: Arc<dyn LibraryRepository>,
+    audio_file_repository: Arc<dyn AudioFileRepository>,
+    album_repository: Arc<dyn AlbumRepository>,
+    merge_store: Arc<dyn MergeStore>,
+    event_bus: Arc<B>,
+}
+
+impl<B: EventBus> TrashService<B> {
+    pub fn new(
+        id_generator: Arc<dyn IdGenerator>,
+        trash_store: Arc<dyn TrashStore>,
+        library_repository: Arc<dyn LibraryRepository>,
+        audio_file_repository: Arc<dyn AudioFileRepository>,
+        album_repository: Arc<dyn AlbumRepository>,
+        merge_store: Arc<dyn MergeStore>,
+        event_bus: Arc<B>,
+    ) -> Self {
+        Self {
+            id_generator,
+            trash_store,
+            library_repository,
+            audio_file_repository,
+            album_repository,
+            merge_store,
+            event_bus,
+        }
+    }
+
+    pub async fn trash_song(
+        &self,
+        context: &AppContext,
+        cmd: TrashSongCmd,
+    ) -> Result<TrashEntry, AppError> {
+        let audio_file = self
+            .audio_file_repository
+            .find_by_id(&cmd.audio_file_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::AggregateNotFound("AudioFile".to_string(), cmd.audio_file_id.to_string())
+            })?;
+        let name = audio_file.meta.title.clone();
+        self.trash(
+            context,
+            TrashKind::Song,
+            name,
+            vec![audio_file],
+            cmd.deleted_by,
+        )
+        .await
+    }
+
+    /// 专辑的全部歌曲移入回收站后删除专辑本身
+    pub async fn trash_album(
+        &self,
+        context: &AppContext,
+        cmd: TrashAlbumCmd,
+    ) -> Result<TrashEntry, AppError> {
+        let mut album = self
+            .album_repository
+            .by_id(cmd.album_id.clone())
+            .await?
+            .ok_or_else(|| {
+                AppError::AggregateNotFound("Album".to_string(), cmd.album_id.to_string())
+            })?;
+        let audio_files = self
+            .audio_file_repository
+            .find_by_album(&cmd.album_id)
+            .await?;
+        if audio_files.is_empty() {
+            return Err(AppError::InvalidInput("Album has no songs".to_string()));
+        }
+        let entry = self
+            .trash(
+                context,
+                TrashKind::Album,
+                album.name.clone(),
+                audio_files,
+                cmd.deleted_by,
+            )
+            .await?;
+
+        for participant in album.participants.clone() {
+            album.remove_participant(&participant)?;
+        }
+        for genre_id in album.genres.clone() {
+            album.unbind_from_genre(genre_id)?;
+        }
+        self.save_album(context, album).await?;
+        self.album_repository.delete(cmd.album_id).await?;
+        Ok(entry)
+    }
+
+    pub async fn list(&self) -> Result<Vec<TrashEntry>, AppError> {
+        self.trash_store.list().await
+    }
+
+    /// 文件移回原位置后返回入库命令，由调用方交给 `LibraryCommandService::add_files`
+    pub async fn restore(&self, id: i64) -> Result<Vec<AddLibraryFilesCmd>, AppError> {
+        let entry = self.load_entry(id).await?;
+        for library_id in library_ids(&entry.files) {
+            self.load_library(&library_id).await?;
+        }
+        let files = self.trash_store.restore(&entry).await?;
+        let mut cmds: Vec<AddLibraryFilesCmd> = Vec::new();
+        for (trashed, file) in entry.files.iter().zip(files) {
+            match cmds
+                .iter_mut()
+                .find(|cmd| cmd.library_id == trashed.library_id)
+            {
+                Some(cmd) => cmd.files.push(file),
+                None => cmds.push(AddLibraryFilesCmd {
+                    library_id: trashed.library_id.clone(),
+                    files: vec![file],
+                }),
+            }
+        }
+        Ok(cmds)
+    }
+
+    /// 恢复的文件重新入库后，把删除前歌曲上的注解迁移到新歌曲。
+    /// 不是从回收站恢复的文件返回 false
+    pub async fn carry_over_annotations(
+        &self,
+        path: &MediaPath,
+        audio_file_id: &AudioFileId,
+    ) -> Result<bool, AppError> {
+        let Some(original_id) = self.trash_store.take_restored(path).await? else {
+            return Ok(false);
+        };
+        self.merge_store
+            .commit(MergeChanges {
+                reassigned_annotations: vec![(
+                    Kind::AudioFile,
+                    original_id.as_i64(),
+                    audio_file_id.as_i64(),
+                )],
+                ..MergeChanges::default()
+            })
+            .await?;
+        Ok(true)
+    }
+
+    /// 永久删除
+    pub async fn purge(&self, id: i64) -> Result<(), AppError> {
+        self.load_entry(id).await?;
+        self.trash_store.remove(id).await
+    }
+
+    /// 永久删除早于 `before` 的条目，返回删除数量
+    pub async fn purge_expired(&self, before: NaiveDateTime) -> Result<usize, AppError> {
+        let mut purged = 0;
+        for entry in self.trash_store.list().await? {
+            if entry.deleted_at < before {
+                self.trash_store.remove(entry.id).await?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    /// 先移动文件再修改库和歌曲，文件移动失败时数据库保持不变
+    async fn trash(
+        &self,
+        context: &AppContext,
+        kind: TrashKind,
+        name: String,
+        audio_files: Vec<AudioFile>,
+        deleted_by: String,
+    ) -> Result<TrashEntry, AppError> {
+        let files: Vec<TrashedFile> = audio_files
+            .iter()
+            .map(|audio_file| TrashedFile {
+                library_id: audio_file.library_id.clone(),
+                path: audio_file.path.clone(),
+                audio_file_id: Some(audio_file.id.clone()),
+            })
+            .collect();
+        let mut libraries = HashMap::new();
+        for library_id in library_ids(&files) {
+            let library = self.load_library(&library_id).await?;
+            libraries.insert(library_id, library);
+        }
+
+        let entry = TrashEntry {
+            id: self.id_generator.next_id().await?,
+            kind,
+            name,
+            files,
+            deleted_by,
+            deleted_at: chrono::Utc::now().naive_utc(),
+        };
+        self.trash_store.put(&entry).await?;
+
+        for file in &entry.files {
+            if let Some(library) = libraries.get_mut(&file.library_id) {
+                library.remove_item(&file.path.path);
+            }
+        }
+        for (_, library) in libraries {
+            self.save_library(context, library).await?;
+        }
+        for audio_file in audio_files {
+            self.delete_audio_file(context, audio_file).await?;
+        }
+        Ok(entry)
+    }
+
+    /// 依次解除专辑、参与者和流派绑定，投影随各自的事件更新
+    async fn delete_audio_file(
+        &self,
+        context: &AppContext,
+        mut audio_file: AudioFile,
+    ) -> Result<(), AppError> {
+        if audio_file.album.is_some() {
+            audio_file.unbind_from_album()?;
+        }
+        for participant in audio_file.participants.clone() {
+            audio_file.remove_participant(participant)?;
+        }
+        for genre_id in audio_file.genres.clone() {
+            audio_file.unbind_from_genre(genre_id)?;
+        }
+        audio_file.delete()?;
+
+        let id = audio_file.id.clone();
+        let events = audio_file.take_events();
+        let audio_file = self.audio_file_repository.save(audio_file).await?;
+        self.audio_file_repository.delete(&id).await?;
+        for event in events {
+            let envelope = EventEnvelope::new(
+                audio_file.id.as_i64(),
+                audio_file.version,
+                event,
+                context.correlation_id.clone(),
+                context.event_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+        Ok(())
+    }
+
+    /// 与扫描任务同时保存库会互相覆盖 items，扫描期间拒绝删除和恢复
+    async fn load_library(&self, id: &LibraryId) -> Result<Library, AppError> {
+        let library = self
+            .library_repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::AggregateNotFound("Library".to_string(), id.to_string()))?;
+        if !library.path.is_local() {
+            return Err(AppError::InvalidInput(
+                "Only files in local libraries can be moved to the trash".to_string(),
+            ));
+        }
+        if library.scan_status == ScanStatus::Scanning {
+            return Err(LibraryError::ScanningInProgress.into());
+        }
+        Ok(library)
+    }
+
+    async fn load_entry(&self, id: i64) -> Result<TrashEntry, AppError> {
+        self.trash_store
+            .find(id)
+            .await?
+            .ok_or_else(|| AppError::AggregateNotFound("TrashEntry".to_string(), id.to_string()))
+    }
+
+    async fn save_library(
+        &self,
+        context: &AppContext,
+        mut library: Library,
+    ) -> Result<(), AppError> {
+        self.library_repository.save(&library).await?;
+        for event in library.take_events() {
+            let envelope = EventEnvelope::<LibraryEvent>::new_with_domain_event(
+                event,
+                context.correlation_id.clone(),
+                context.event_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+        Ok(())
+    }
+
+    async fn save_album(&self, context: &AppContext, mut album: Album) -> Result<(), AppError> {
+        let events = album.take_events();
+        let album = self.album_repository.save(album).await?;
+        for event in events {
+            let envelope = EventEnvelope::new(
+                album.id.as_i64(),
+                album.version,
+                event,
+                context.correlation_id.clone(),
+                context.event_id.clone(),
+            );
+            self.event_bus.publish(envelope).await?;
+        }
+        Ok(())
+    }
+}
+
+fn library_ids(files: &[TrashedFile]) -> Vec<LibraryId> {
+    let mut ids: Vec<LibraryId> = Vec::new();
+    for file in files {
+        if !ids.contains(&file.library_id) {
+            ids.push(file.library_id.clone());
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::SequentialIdGenerator;
+    use crate::test_support::{
+        song, MemoryAlbumRepository, MemoryAudioFileRepository, RecordingEventBus,
+    };
+    use chrono::{Duration, Utc};
+    use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
+    use domain::library::LibraryItem;
+    use domain::value::{FileType, LibraryItemId};
+    use std::sync::Mutex;
+
+    fn file_meta(path: &MediaPath) -> FileMeta {
+        let now = Utc::now().naive_utc();
+        FileMeta::new(
+            path.clone(),
+            path.parent_path(),
+            1000,
+            "flac".to_string(),
+            now,
+            now,
+            now,
+            None,
+        )
+    }
+
+    #[derive(Default)]
+    struct MemoryLibraryRepository {
+        libraries: Mutex<HashMap<LibraryId, Library>>,
+    }
+
+    #[async_trait]
+    impl LibraryRepository for MemoryLibraryRepository {
+        async fn save(&self, library: &Library) -> Result<(), LibraryError> {
+            self.libraries
+                .lock()
+                .unwrap()
+                .insert(library.id.clone(), library.clone());
+            Ok(())
+        }
+
+        async fn find_by_id(&self, id: &LibraryId) -> Result<Option<Library>, LibraryError> {
+            Ok(self.libraries.lock().unwrap().get(id).cloned())
+        }
+
+        async fn find_all(&self) -> Result<Vec<Library>, LibraryError> {
+            Ok(self.libraries.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn delete(&self, id: &LibraryId) -> Result<(), LibraryError> {
+            self.libraries.lock().unwrap().remove(id);
+            Ok(())
+        }
+    }
+
+    /// 与文件系统实现一样，恢复时为记录了歌曲 ID 的文件留下恢复记录
+    #[derive(Default)]
+    struct MemoryTrashStore {
+        entries: Mutex<HashMap<i64, TrashEntry>>,
+        restored: Mutex<HashMap<String, AudioFileId>>,
+    }
+
+    #[async_trait]
+    impl TrashStore for MemoryTrashStore {
+        async fn put(&self, entry: &TrashEntry) -> Result<(), AppError> {
+            self.entries.lock().unwrap().insert(entry.id, entry.clone());
+            Ok(())
+        }
+
+        async fn list(&self) -> Result<Vec<TrashEntry>, AppError> {
+            let mut entries: Vec<TrashEntry> =
+                self.entries.lock().unwrap().values().cloned().collect();
+            entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+            Ok(entries)
+        }
+
+        async fn find(&self, id: i64) -> Result<Option<TrashEntry>, AppError> {
+            Ok(self.entries.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn restore(&self, entry: &TrashEntry) -> Result<Vec<FileMeta>, AppError> {
+            let mut restored = self.restored.lock().unwrap();
+            for file in &entry.files {
+                if let Some(id) = &file.audio_file_id {
+                    restored.insert(file.path.path.clone(), id.clone());
+                }
+            }
+            self.entries.lock().unwrap().remove(&entry.id);
+            Ok(entry.files.iter().map(|f| file_meta(&f.path)).collect())
+        }
+
+        async fn take_restored(&self, path: &MediaPath) -> Result<Option<AudioFileId>, AppError> {
+            Ok(self.restored.lock().unwrap().remove(&path.path))
+        }
+
+        async fn remove(&self, id: i64) -> Result<(), AppError> {
+            self.entries.lock().unwrap().remove(&id);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingMergeStore {
+        annotations: Mutex<Vec<(Kind, i64, i64)>>,
+    }
+
+    #[async_trait]
+    impl MergeStore for RecordingMergeStore {
+        async fn commit(&self, changes: MergeChanges) -> Result<MergeChanges, AppError> {
+            self.annotations
+                .lock()
+                .unwrap()
+                .extend(changes.reassigned_annotations.iter().cloned());
+            Ok(changes)
+        }
+    }
+
+    struct Fixture {
+        libraries: Arc<MemoryLibraryRepository>,
+        audio_files: Arc<MemoryAudioFileRepository>,
+        trash_store: Arc<MemoryTrashStore>,
+        merge_store: Arc<RecordingMergeStore>,
+        event_bus: Arc<RecordingEventBus>,
+        service: TrashService<RecordingEventBus>,
+    }
+
+    /// 库 1 中有歌曲 1 和 2
+    async fn fixture() -> Fixture {
+        let libraries = Arc::new(MemoryLibraryRepository::default());
+        let audio_files = Arc::new(MemoryAudioFileRepository::default());
+        let mut library = Library::new(
+            LibraryId::from(1),
+            "Music".to_string(),
+            MediaPath::new("local".to_string(), "/music".to_string()),
+        );
+        for id in [1, 2] {
+            let audio_file = song(id, None, &[]);
+            library.add_item(LibraryItem::new(
+                LibraryItemId::from(id),
+                library.id.clone(),
+                file_meta(&audio_file.path),
+                FileType::Audio,
+            ));
+            audio_files.save(audio_file).await.unwrap();
+        }
+        library.take_events();
+        libraries.save(&library).await.unwrap();
+
+        let trash_store = Arc::new(MemoryTrashStore::default());
+        let merge_store = Arc::new(RecordingMergeStore::default());
+        let event_bus = Arc::new(RecordingEventBus::default());
+        let service = TrashService::new(
+            Arc::new(SequentialIdGenerator::new(100)),
+            trash_store.clone(),
+            libraries.clone(),
+            audio_files.clone(),
+            Arc::new(MemoryAlbumRepository::default()),
+            merge_store.clone(),
+            event_bus.clone(),
+        );
+        Fixture {
+            libraries,
+            audio_files,
+            trash_store,
+            merge_store,
+            event_bus,
+            service,
+        }
+    }
+
+    fn trash_song_cmd(id: i64) -> TrashSongCmd {
+        TrashSongCmd {
+            audio_file_id: AudioFileId::from(id),
+            deleted_by: "admin".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn trashed_songs_are_removed_from_the_library() {
+        let f = fixture().await;
+        let entry = f
+            .service
+            .trash_song(&AppContext::new(), trash_song_cmd(1))
+            .await
+            .unwrap();
+
+        assert_eq!(entry.kind, TrashKind::Song);
+        assert_eq!(entry.files[0].audio_file_id, Some(AudioFileId::from(1)));
+        assert!(f.audio_files.get(1).is_none());
+        assert!(f.audio_files.get(2).is_some());
+        let library = f.libraries.libraries.lock().unwrap()[&LibraryId::from(1)].clone();
+        assert!(!library.items.contains_key("/music/1.flac"));
+        assert!(matches!(
+            f.event_bus.events::<LibraryEvent>()[..],
+            [EventEnvelope {
+                payload: LibraryEvent::FileRemoved(_),
+                ..
+            }]
+        ));
+        assert!(f
+            .event_bus
+            .events::<AudioFileEvent>()
+            .iter()
+            .any(|e| matches!(e.payload.kind, AudioFileEventKind::Deleted(_))));
+    }
+
+    #[tokio::test]
+    async fn restored_songs_keep_their_annotations() {
+        let f = fixture().await;
+        let entry = f
+            .service
+            .trash_song(&AppContext::new(), trash_song_cmd(1))
+            .await
+            .unwrap();
+
+        let cmds = f.service.restore(entry.id).await.unwrap();
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(cmds[0].library_id, LibraryId::from(1));
+        assert_eq!(cmds[0].files[0].path.path, "/music/1.flac");
+        assert!(f.service.list().await.unwrap().is_empty());
+
+        // 重新入库得到新的歌曲 ID，注解从原 ID 迁移过去，且只迁移一次
+        let path = MediaPath::new("local".to_string(), "/music/1.flac".to_string());
+        let new_id = AudioFileId::from(200);
+        assert!(f
+            .service
+            .carry_over_annotations(&path, &new_id)
+            .await
+            .unwrap());
+        assert!(!f
+            .service
+            .carry_over_annotations(&path, &new_id)
+            .await
+            .unwrap());
+        assert_eq!(
+            *f.merge_store.annotations.lock().unwrap(),
+            vec![(Kind::AudioFile, 1, 200)]
+        );
+
+        // 不是从回收站恢复的新文件不迁移
+        let other = MediaPath::new("local".to_string(), "/music/3.flac".to_string());
+        assert!(!f
+            .service
+            .carry_over_annotations(&other, &AudioFileId::from(201))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn purge_removes_entries_for_good() {
+        let f = fixture().await;
+        let context = AppContext::new();
+        let first = f
+            .service
+            .trash_song(&context, trash_song_cmd(1))
+            .await
+            .unwrap();
+        let second = f
+            .service
+            .trash_song(&context, trash_song_cmd(2))
+            .await
+            .unwrap();
+
+        f.service.purge(first.id).await.unwrap();
+        assert!(matches!(
+            f.service.restore(first.id).await,
+            Err(AppError::AggregateNotFound(..))
+        ));
+        assert!(matches!(
+            f.service.purge(first.id).await,
+            Err(AppError::AggregateNotFound(..))
+        ));
+
+        f.trash_store
+            .entries
+            .lock()
+            .unwrap()
+            .get_mut(&second.id)
+            .unwrap()
+            .deleted_at -= Duration::days(31);
+        let before = Utc::now().naive_utc() - Duration::days(30);
+        assert_eq!(f.service.purge_expired(before).await.unwrap(), 1);
+        assert!(f.service.list().await.unwrap().is_empty());
+        assert!(f.merge_store.annotations.lock().unwrap().is_empty());
+    }
+}
@@ -1,15 +1,20 @@
 use crate::error::AppError;
-use domain::user::{User, UserRepository};
+use domain::user::{User, UserError, UserPreferenceRepository, UserRepository, UserRoles};
 use domain::value::UserId;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// 创建用户命令
 pub struct CreateUserCmd {
     pub username: String,
-    pub password: String,            // 已经哈希过的密码 (bcrypt)
+    pub password: String,            // 已经哈希过的密码 (Argon2id 或 bcrypt)
     pub encrypted_password: String,  // AES-256-GCM 加密的原始密码，用于 Subsonic token 认证
     pub email: String,
     pub is_admin: bool,
+    pub roles: UserRoles,
+    pub max_bit_rate: Option<i32>,       // 0 或 None 表示不限制
+    pub download_quota_mb: Option<i64>,  // 0 或 None 表示不限制
+    pub hide_explicit: bool,
 }
 
 /// 更新用户命令
@@ -19,6 +24,42 @@ pub struct UpdateUserCmd {
     pub encrypted_password: Option<String>,  // 加密的原始密码，None 表示不修改
     pub email: Option<String>,
     pub is_admin: Option<bool>,
+    pub roles: UserRolesPatch,
+    pub max_bit_rate: Option<i32>,           // None 表示不修改，0 表示取消限制
+    pub download_quota_mb: Option<i64>,      // None 表示不修改，0 表示取消限制
+    pub hide_explicit: Option<bool>,         // None 表示不修改
+}
+
+/// 角色的部分更新，None 表示保持原值
+#[derive(Debug, Default, Clone)]
+pub struct UserRolesPatch {
+    pub settings: Option<bool>,
+    pub stream: Option<bool>,
+    pub download: Option<bool>,
+    pub upload: Option<bool>,
+    pub playlist: Option<bool>,
+    pub cover_art: Option<bool>,
+    pub comment: Option<bool>,
+    pub podcast: Option<bool>,
+    pub jukebox: Option<bool>,
+    pub share: Option<bool>,
+}
+
+impl UserRolesPatch {
+    pub fn apply(&self, roles: UserRoles) -> UserRoles {
+        UserRoles {
+            settings: self.settings.unwrap_or(roles.settings),
+            stream: self.stream.unwrap_or(roles.stream),
+            download: self.download.unwrap_or(roles.download),
+            upload: self.upload.unwrap_or(roles.upload),
+            playlist: self.playlist.unwrap_or(roles.playlist),
+            cover_art: self.cover_art.unwrap_or(roles.cover_art),
+            comment: self.comment.unwrap_or(roles.comment),
+            podcast: self.podcast.unwrap_or(roles.podcast),
+            jukebox: self.jukebox.unwrap_or(roles.jukebox),
+            share: self.share.unwrap_or(roles.share),
+        }
+    }
 }
 
 /// 删除用户命令
@@ -29,27 +70,86 @@ pub struct DeleteUserCmd {
 /// 修改密码命令
 pub struct ChangePasswordCmd {
     pub username: String,
-    pub password: String,            // 已经哈希过的密码 (bcrypt)
+    pub password: String,            // 已经哈希过的密码 (Argon2id 或 bcrypt)
     pub encrypted_password: String,  // AES-256-GCM 加密的原始密码，用于 Subsonic token 认证
 }
 
 /// 用户应用服务
 pub struct UserAppService {
     user_repo: Arc<dyn UserRepository>,
+    preference_repo: Arc<dyn UserPreferenceRepository>,
     id_generator: Arc<dyn crate::command::shared::IdGenerator>,
 }
 
 impl UserAppService {
     pub fn new(
         user_repo: Arc<dyn UserRepository>,
+        preference_repo: Arc<dyn UserPreferenceRepository>,
         id_generator: Arc<dyn crate::command::shared::IdGenerator>,
     ) -> Self {
         Self {
             user_repo,
+            preference_repo,
             id_generator,
         }
     }
 
+    /// 按用户名查找用户
+    pub async fn find_user(&self, username: &str) -> Result<User, AppError> {
+        match self.user_repo.find_by_username(username).await {
+            Ok(Some(user)) => Ok(user),
+            // 仓储在用户不存在时返回 InvalidUserOrPassword
+            Ok(None) | Err(UserError::InvalidUserOrPassword(_)) => Err(AppError::AggregateNotFound(
+                "User".to_string(),
+                username.to_string(),
+            )),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 分页列出用户，同时返回用户总数
+    pub async fn list_users(&self, offset: u64, limit: u64) -> Result<(Vec<User>, u64), AppError> {
+        let users = self.user_repo.find_page(offset, limit).await?;
+        let total = self.user_repo.count().await?;
+        Ok((users, total))
+    }
+
+    /// 启用或禁用用户，禁用后无法登录和访问 Subsonic 接口
+    pub async fn set_enabled(&self, username: &str, enabled: bool) -> Result<User, AppError> {
+        let mut user = self.find_user(username).await?;
+        if enabled {
+            user.enable()?;
+        } else {
+            user.disable()?;
+        }
+        self.user_repo.save(&user).await?;
+        Ok(user)
+    }
+
+    pub async fn get_preferences(&self, username: &str) -> Result<HashMap<String, String>, AppError> {
+        let user = self.find_user(username).await?;
+        Ok(self.preference_repo.find_all(user.id).await?)
+    }
+
+    /// 合并更新偏好设置，值为 None 的键会被删除
+    pub async fn update_preferences(
+        &self,
+        username: &str,
+        changes: HashMap<String, Option<String>>,
+    ) -> Result<HashMap<String, String>, AppError> {
+        let user = self.find_user(username).await?;
+        for (key, value) in changes {
+            if key.trim().is_empty() {
+                return Err(AppError::InvalidInput("Preference key cannot be empty".to_string()));
+            }
+            match value {
+                Some(value) => self.preference_repo.save(user.id.clone(), &key, &value).await?,
+                None => self.preference_repo.delete(user.id.clone(), &key).await?,
+            }
+        }
+        Ok(self.preference_repo.find_all(user.id).await?)
+    }
+
     /// 创建新用户
     pub async fn create_user(&self, cmd: CreateUserCmd) -> Result<(), AppError> {
         // 检查用户名是否已存在
@@ -65,7 +165,7 @@ impl UserAppService {
         let user_id = UserId::from(self.id_generator.next_id().await?);
 
         // 创建用户领域对象
-        let user = User::new(
+        let mut user = User::new(
             user_id,
             &cmd.username,
             None, // name 默认与 username 相同
@@ -75,6 +175,9 @@ impl UserAppService {
             &cmd.encrypted_password,
         )
         .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        user.set_roles(cmd.roles)
+            .set_limits(cmd.max_bit_rate, cmd.download_quota_mb)
+            .set_hide_explicit(cmd.hide_explicit);
 
         // 保存用户
         self.user_repo.save(&user).await?;
@@ -111,6 +214,14 @@ impl UserAppService {
             user.is_admin = is_admin;
         }
 
+        // 更新角色和限制
+        let roles = cmd.roles.apply(user.roles);
+        user.set_roles(roles)
+            .set_limits(cmd.max_bit_rate, cmd.download_quota_mb);
+        if let Some(hide_explicit) = cmd.hide_explicit {
+            user.set_hide_explicit(hide_explicit);
+        }
+
         // 保存用户
         self.user_repo.save(&user).await?;
 
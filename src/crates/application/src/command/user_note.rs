@@ -0,0 +1,148 @@
+use crate::error::AppError;
+use domain::album::AlbumRepository;
+use domain::annotation::Kind;
+use domain::artist::ArtistRepository;
+use domain::audio_file::AudioFileRepository;
+use domain::playlist::PlaylistRepository;
+use domain::user_note::{UserNote, UserNoteError, UserNoteRepository};
+use domain::value::{AlbumId, ArtistId, AudioFileId, PlaylistId, UserId};
+use std::sync::Arc;
+
+/// 创建或修改备注
+#[derive(Debug)]
+pub struct SaveUserNoteCmd {
+    pub user_id: UserId,
+    pub kind: Kind,
+    pub item_id: i64,
+    pub note: String,
+}
+
+pub struct UserNoteService {
+    user_note_repository: Arc<dyn UserNoteRepository>,
+    audio_file_repository: Arc<dyn AudioFileRepository>,
+    album_repository: Arc<dyn AlbumRepository>,
+    artist_repository: Arc<dyn ArtistRepository>,
+    playlist_repository: Arc<dyn PlaylistRepository>,
+}
+
+impl UserNoteService {
+    pub fn new(
+        user_note_repository: Arc<dyn UserNoteRepository>,
+        audio_file_repository: Arc<dyn AudioFileRepository>,
+        album_repository: Arc<dyn AlbumRepository>,
+        artist_repository: Arc<dyn ArtistRepository>,
+        playlist_repository: Arc<dyn PlaylistRepository>,
+    ) -> Self {
+        Self {
+            user_note_repository,
+            audio_file_repository,
+            album_repository,
+            artist_repository,
+            playlist_repository,
+        }
+    }
+
+    pub async fn find_note(
+        &self,
+        user_id: &UserId,
+        kind: &Kind,
+        item_id: i64,
+    ) -> Result<Option<UserNote>, AppError> {
+        Ok(self
+            .user_note_repository
+            .find(user_id, kind, item_id)
+            .await?)
+    }
+
+    pub async fn save_note(&self, cmd: SaveUserNoteCmd) -> Result<UserNote, AppError> {
+        if !self
+            .item_visible(&cmd.user_id, &cmd.kind, cmd.item_id)
+            .await?
+        {
+            return Err(AppError::AggregateNotFound(
+                cmd.kind.name().to_string(),
+                cmd.item_id.to_string(),
+            ));
+        }
+        let note = match self
+            .user_note_repository
+            .find(&cmd.user_id, &cmd.kind, cmd.item_id)
+            .await?
+        {
+            Some(mut note) => {
+                note.update(&cmd.note)?;
+                note
+            }
+            None => UserNote::new(cmd.user_id, cmd.kind, cmd.item_id, &cmd.note)?,
+        };
+        self.user_note_repository.save(&note).await?;
+        Ok(note)
+    }
+
+    pub async fn delete_note(
+        &self,
+        user_id: &UserId,
+        kind: &Kind,
+        item_id: i64,
+    ) -> Result<(), AppError> {
+        if self
+            .user_note_repository
+            .delete(user_id, kind, item_id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(AppError::AggregateNotFound(
+                "UserNote".to_string(),
+                item_id.to_string(),
+            ))
+        }
+    }
+
+    /// 条目存在，播放列表还需要对该用户可见
+    async fn item_visible(
+        &self,
+        user_id: &UserId,
+        kind: &Kind,
+        item_id: i64,
+    ) -> Result<bool, AppError> {
+        Ok(match kind {
+            Kind::AudioFile => self
+                .audio_file_repository
+                .find_by_id(&AudioFileId::from(item_id))
+                .await?
+                .is_some(),
+            Kind::Album => self
+                .album_repository
+                .by_id(AlbumId::from(item_id))
+                .await?
+                .is_some(),
+            Kind::Artist => self
+                .artist_repository
+                .by_id(ArtistId::from(item_id))
+                .await?
+                .is_some(),
+            Kind::Playlist => self
+                .playlist_repository
+                .find_by_id(PlaylistId::from(item_id))
+                .await?
+                .is_some_and(|playlist| {
+                    !playlist.is_deleted()
+                        && (playlist.public
+                            || playlist.is_owner(user_id)
+                            || playlist.collaborators.contains(user_id))
+                }),
+        })
+    }
+}
+
+impl From<UserNoteError> for AppError {
+    fn from(e: UserNoteError) -> Self {
+        match e {
+            UserNoteError::Empty | UserNoteError::TooLong(_) => {
+                AppError::InvalidInput(e.to_string())
+            }
+            UserNoteError::DbErr(msg) => AppError::RepositoryError("UserNote".to_string(), msg),
+        }
+    }
+}
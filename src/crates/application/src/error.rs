@@ -40,6 +40,8 @@ pub enum AppError {
     PlayerError(#[from] PlayerError),
     #[error("Aggregate not found: {0}: {1}")]
     AggregateNotFound(String, String),
+    #[error("Concurrent modification: {0}: {1}")]
+    ConcurrentModification(String, String),
 
     #[error("Parse audio metadata error: {0}")]
     ParseAudioMetadataError(String),
@@ -53,9 +55,30 @@ pub enum AppError {
     #[error("Projection error: {0}")]
     ProjectionError(String),
 
+    /// 请求的功能还没有实现
+    #[error("Not supported: {0}")]
+    NotSupported(String),
+
     #[error("Model error: {0}")]
     ModelError(#[from] ModelError),
 
     #[error("Unknown error: {0}")]
     UnknownError(String),
 }
+
+impl AppError {
+    /// 保存时聚合的版本已被其他写入更新，重新加载后可以重试
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self,
+            AppError::ConcurrentModification(..)
+                | AppError::AudioFileError(AudioFileError::VersionConflict(_))
+                | AppError::AlbumError(AlbumError::VersionConflictErr(_))
+                | AppError::ArtistError(ArtistError::VersionConflict(_))
+                | AppError::GenreError(GenreError::VersionConflictErr(_))
+                | AppError::UserError(UserError::VersionConflictErr(_))
+                | AppError::CoverArtError(CoverArtError::VersionConflict { .. })
+                | AppError::PlayerError(PlayerError::VersionConflict { .. })
+        )
+    }
+}
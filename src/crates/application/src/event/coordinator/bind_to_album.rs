@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::command::album::{AlbumService, BindCmd};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{CorrelationId, EventBus, EventEnvelope, Handler};
 use crate::event::events::AppEvent;
 use domain::album::{AlbumEvent, AlbumEventKind};
@@ -216,7 +217,7 @@ impl<B: EventBus> BindToAlbumCoordinator<B> {
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<ArtistEvent> for BindToAlbumCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<ArtistEvent>) {
+    async fn handle(&self, event: &EventEnvelope<ArtistEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             ArtistEvent::Found(found) => {
@@ -227,12 +228,13 @@ impl<B: EventBus> Handler<ArtistEvent> for BindToAlbumCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<GenreEvent> for BindToAlbumCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<GenreEvent>) {
+    async fn handle(&self, event: &EventEnvelope<GenreEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             GenreEvent::Created(created) => {
@@ -242,12 +244,13 @@ impl<B: EventBus> Handler<GenreEvent> for BindToAlbumCoordinator<B> {
                 self.on_genre_available(&ctx, &found.genre_id).await;
             }
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AlbumEvent> for BindToAlbumCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<AlbumEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AlbumEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload.kind {
             AlbumEventKind::Created(created) => {
@@ -259,12 +262,13 @@ impl<B: EventBus> Handler<AlbumEvent> for BindToAlbumCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for BindToAlbumCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             AppEvent::AudioFileParsed(audio_file_parsed) => {
@@ -272,5 +276,6 @@ impl<B: EventBus> Handler<AppEvent> for BindToAlbumCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
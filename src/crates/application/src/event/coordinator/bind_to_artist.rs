@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::command::artist::{ArtistService, BindCmd};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{CorrelationId, EventBus, EventEnvelope, EventId, Handler};
 use crate::event::events::AppEvent;
 use domain::artist::ArtistEvent;
@@ -156,7 +157,7 @@ impl<B: EventBus> BindToArtistCoordinator<B> {
 }
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<ArtistEvent> for BindToArtistCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<ArtistEvent>) {
+    async fn handle(&self, event: &EventEnvelope<ArtistEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             ArtistEvent::Found(found) => {
@@ -167,12 +168,13 @@ impl<B: EventBus> Handler<ArtistEvent> for BindToArtistCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<GenreEvent> for BindToArtistCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<GenreEvent>) {
+    async fn handle(&self, event: &EventEnvelope<GenreEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             GenreEvent::Created(created) => {
@@ -182,12 +184,13 @@ impl<B: EventBus> Handler<GenreEvent> for BindToArtistCoordinator<B> {
                 self.on_genre_available(&ctx, &found.genre_id).await;
             }
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for BindToArtistCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             AppEvent::AudioFileParsed(audio_file_parsed) => {
@@ -195,5 +198,6 @@ impl<B: EventBus> Handler<AppEvent> for BindToArtistCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
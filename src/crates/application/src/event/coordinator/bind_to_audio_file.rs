@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
+use crate::command::album::AlbumService;
 use crate::command::audio_file::{AudioFileService, BindCmd};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{CorrelationId, EventBus, EventEnvelope, Handler};
 use crate::event::events::AppEvent;
 use domain::album::{AlbumEvent, AlbumEventKind};
@@ -9,13 +11,15 @@ use domain::artist::ArtistEvent;
 use domain::audio_file::AudioFileEvent;
 use domain::genre::GenreEvent;
 use domain::value::{AlbumId, ArtistId, AudioFileId, GenreId, ParticipantRole, ParticipantSubRole};
-use log::error;
+use log::{error, info};
 use std::collections::HashMap;
 use tokio::sync::Mutex;
 
 #[derive(Clone)]
 pub struct BindToAudioFileCoordinator<B: EventBus> {
     audio_file_service: AudioFileService<B>,
+    // 歌曲改绑后清理没有歌曲的专辑
+    album_service: AlbumService<B>,
     // caches to correlate events by media path
     pending_artists_by_correlation_id: Arc<Mutex<HashMap<CorrelationId, Vec<ArtistId>>>>,
     pending_genres_by_correlation_id: Arc<Mutex<HashMap<CorrelationId, Vec<GenreId>>>>,
@@ -28,9 +32,10 @@ pub struct BindToAudioFileCoordinator<B: EventBus> {
 }
 
 impl<B: EventBus> BindToAudioFileCoordinator<B> {
-    pub fn new(audio_file_service: AudioFileService<B>) -> Self {
+    pub fn new(audio_file_service: AudioFileService<B>, album_service: AlbumService<B>) -> Self {
         Self {
             audio_file_service,
+            album_service,
             pending_artists_by_correlation_id: Arc::new(Mutex::new(HashMap::new())),
             pending_genres_by_correlation_id: Arc::new(Mutex::new(HashMap::new())),
             pending_album_by_correlation_id: Arc::new(Mutex::new(HashMap::new())),
@@ -245,15 +250,29 @@ impl<B: EventBus> BindToAudioFileCoordinator<B> {
 
         let ctx = ctx.inherit();
 
-        if let Err(e) = self.audio_file_service.bind(&ctx, cmd).await {
-            error!("Failed to bind: {}", e);
+        match self.audio_file_service.bind(&ctx, cmd).await {
+            Ok(Some(detached)) => self.remove_if_orphaned(&ctx, &detached).await,
+            Ok(None) => {}
+            Err(e) => error!("Failed to bind: {}", e),
+        }
+    }
+
+    /// 重新解析后专辑标签变化，歌曲已改绑到新专辑，原专辑没有其他歌曲时删除
+    async fn remove_if_orphaned(&self, ctx: &AppContext, album_id: &AlbumId) {
+        match self.audio_file_service.album_has_files(album_id).await {
+            Ok(true) => {}
+            Ok(false) => match self.album_service.remove_orphaned(ctx, album_id).await {
+                Ok(()) => info!("Removed album {} after its last song moved", album_id),
+                Err(e) => error!("Failed to remove orphaned album {}: {}", album_id, e),
+            },
+            Err(e) => error!("Failed to count songs of album {}: {}", album_id, e),
         }
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<ArtistEvent> for BindToAudioFileCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<ArtistEvent>) {
+    async fn handle(&self, event: &EventEnvelope<ArtistEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             ArtistEvent::Found(found) => {
@@ -264,12 +283,13 @@ impl<B: EventBus> Handler<ArtistEvent> for BindToAudioFileCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<GenreEvent> for BindToAudioFileCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<GenreEvent>) {
+    async fn handle(&self, event: &EventEnvelope<GenreEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             GenreEvent::Created(created) => {
@@ -279,12 +299,13 @@ impl<B: EventBus> Handler<GenreEvent> for BindToAudioFileCoordinator<B> {
                 self.on_genre_available(&ctx, &found.genre_id).await;
             }
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AlbumEvent> for BindToAudioFileCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<AlbumEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AlbumEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload.kind {
             AlbumEventKind::Created(_) => {
@@ -295,26 +316,33 @@ impl<B: EventBus> Handler<AlbumEvent> for BindToAudioFileCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AudioFileEvent> for BindToAudioFileCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload.kind {
             domain::audio_file::AudioFileEventKind::Created(created) => {
                 self.on_audio_file_available(&ctx, &created.audio_file_id)
                     .await;
             }
+            // 已入库的文件重新解析，按新的标签重新绑定
+            domain::audio_file::AudioFileEventKind::Updated(updated) => {
+                self.on_audio_file_available(&ctx, &updated.audio_file_id)
+                    .await;
+            }
             _ => {}
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for BindToAudioFileCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload {
             AppEvent::AudioFileParsed(audio_file_parsed) => {
@@ -322,5 +350,6 @@ impl<B: EventBus> Handler<AppEvent> for BindToAudioFileCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
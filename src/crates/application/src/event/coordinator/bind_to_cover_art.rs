@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::command::cover_art::{BindCmd, CoverArtService};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{CorrelationId, EventBus, EventEnvelope, Handler};
 
 use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
@@ -70,12 +71,12 @@ impl<B: EventBus + Send + Sync> BindToCoverArtCoordinator<B> {
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AudioFileEvent> for BindToCoverArtCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload.kind {
             AudioFileEventKind::Created(evt) => {
                 if !evt.has_cover_art {
-                    return;
+                    return Ok(());
                 }
                 // cache audio by correlation id
                 {
@@ -87,19 +88,20 @@ impl<B: EventBus> Handler<AudioFileEvent> for BindToCoverArtCoordinator<B> {
                 } // 释放锁
                 self.check_and_bind(&ctx).await;
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<CoverArtEvent> for BindToCoverArtCoordinator<B> {
-    async fn handle(&self, event: &EventEnvelope<CoverArtEvent>) {
+    async fn handle(&self, event: &EventEnvelope<CoverArtEvent>) -> Result<(), AppError> {
         let ctx = AppContext::from(event);
         match &event.payload.kind {
             CoverArtEventKind::Created(created) => {
                 if created.source != CoverSourceType::Embedded {
-                    return;
+                    return Ok(());
                 }
                 // cache cover by correlation id
                 {
@@ -110,5 +112,6 @@ impl<B: EventBus> Handler<CoverArtEvent> for BindToCoverArtCoordinator<B> {
             }
             _ => {}
         }
+        Ok(())
     }
 }
@@ -55,7 +55,8 @@ pub async fn register_coordinators<B: EventBus + Clone + 'static>(
     );
 
     // 创建协调器
-    let bind_to_audio_file_coordinator = BindToAudioFileCoordinator::new(audio_file_service);
+    let bind_to_audio_file_coordinator =
+        BindToAudioFileCoordinator::new(audio_file_service, album_service.clone());
     let bind_to_artist_coordinator = BindToArtistCoordinator::new(artist_service);
     let bind_to_album_coordinator = BindToAlbumCoordinator::new(album_service);
     let bind_to_cover_art_coordinator = BindToCoverArtCoordinator::new(cover_art_service);
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+
+/// 重试耗尽后仍处理失败的事件（死信）
+///
+/// 事件本身不做持久化，记录只保存定位问题所需的元数据
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub id: String,
+    pub event_id: String,
+    pub correlation_id: String,
+    /// 事件的 Rust 类型名
+    pub event_type: String,
+    /// 处理失败的 handler 类型名
+    pub handler: String,
+    pub aggregate_id: i64,
+    pub version: i64,
+    pub error: String,
+    /// 累计处理次数（含首次）
+    pub attempts: i32,
+    pub occurred_at: NaiveDateTime,
+    pub failed_at: NaiveDateTime,
+}
+
+impl DeadLetter {
+    /// 重新投递仍然失败时刷新错误信息
+    pub fn record_failure(&mut self, error: impl Into<String>) {
+        self.error = error.into();
+        self.attempts += 1;
+        self.failed_at = Utc::now().naive_utc();
+    }
+}
+
+#[async_trait]
+pub trait DeadLetterRepository: Send + Sync {
+    async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<DeadLetter>>;
+    /// 最近失败的在前
+    async fn find_all(&self) -> anyhow::Result<Vec<DeadLetter>>;
+    async fn save(&self, dead_letter: &DeadLetter) -> anyhow::Result<()>;
+    async fn delete(&self, id: &str) -> anyhow::Result<()>;
+}
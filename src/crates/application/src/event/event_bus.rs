@@ -63,6 +63,11 @@ impl<T> EventEnvelope<T> {
         self.causation_id = causation_id;
         self
     }
+    /// 使用注入的时钟代替创建时的系统时间
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
@@ -74,6 +79,20 @@ impl EventId {
     }
 }
 
+impl std::fmt::Display for EventId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for EventId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct CorrelationId(Uuid);
 
@@ -83,16 +102,40 @@ impl CorrelationId {
     }
 }
 
+impl std::fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for CorrelationId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
 /// 强类型 Handler
+///
+/// 返回错误时事件总线会重试，重试耗尽后进入死信队列；
+/// 不值得重试的失败（如文件本身无法解析）应自行记录并返回 Ok
 #[async_trait]
 pub trait Handler<E>: Send + Sync {
-    async fn handle(&self, event: &EventEnvelope<E>);
+    async fn handle(&self, event: &EventEnvelope<E>) -> Result<(), AppError>;
+
+    /// 死信记录中使用的处理器名称
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// 类型擦除 Handler，用 Any 做事件擦除
 #[async_trait]
 pub trait ErasedHandler: Send + Sync {
-    async fn handle_erased(&self, event: &(dyn Any + Send + Sync));
+    async fn handle_erased(&self, event: &(dyn Any + Send + Sync)) -> Result<(), AppError>;
+
+    fn name(&self) -> &'static str;
 }
 
 /// 事件总线抽象
@@ -105,4 +148,7 @@ pub trait EventBus: Send + Sync {
     async fn subscribe<E>(&mut self, handler: Arc<dyn Handler<E>>)
     where
         E: Send + Sync + 'static;
+
+    /// 等待已发布事件的处理器执行完；同步分发的实现无需等待
+    async fn drain(&self) {}
 }
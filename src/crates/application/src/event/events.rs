@@ -1,3 +1,4 @@
+use crate::command::artwork::EmbeddedArtwork;
 use domain::cover_art::CoverSourceType;
 use domain::value::{AudioMetadata, FileMeta, LibraryId};
 
@@ -11,9 +12,19 @@ pub struct ImageFileParsed {
     pub library_id: LibraryId,
     pub file_info: FileMeta,
     pub source: CoverSourceType,
+    /// 嵌入封面在扫描时生成的缩略图，提取失败或外部图片时为空
+    pub artwork: Option<EmbeddedArtwork>,
+}
+
+/// 音频文件元数据解析失败，文件不会入库
+pub struct AudioFileParseFailed {
+    pub library_id: LibraryId,
+    pub file_info: FileMeta,
+    pub error: String,
 }
 
 pub enum AppEvent {
     AudioFileParsed(AudioFileParsed),
     ImageFileParsed(ImageFileParsed),
+    AudioFileParseFailed(AudioFileParseFailed),
 }
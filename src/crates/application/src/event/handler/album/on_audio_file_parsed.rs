@@ -1,8 +1,8 @@
 use crate::command::album::{AlbumService, CreateAlbumCmd};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
 use crate::event::events::AppEvent;
-use log::error;
 
 #[derive(Clone)]
 pub struct AlbumOnAudioFileParsedHandler<B: EventBus> {
@@ -17,19 +17,24 @@ impl<B: EventBus> AlbumOnAudioFileParsedHandler<B> {
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for AlbumOnAudioFileParsedHandler<B> {
-    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match evt {
             AppEvent::AudioFileParsed(evt) => {
                 let ctx = AppContext::from(envelope);
                 let cmd = CreateAlbumCmd {
                     name: evt.metadata.album.clone(),
+                    edition: evt.metadata.album_version.clone(),
+                    year: evt.metadata.year,
+                    original_year: evt.metadata.original_year,
+                    release_date: evt.metadata.release_date.clone(),
+                    original_date: evt.metadata.original_date.clone(),
+                    explicit: evt.metadata.explicit,
                 };
-                if let Err(e) = self.album_service.create_album(&ctx, cmd).await {
-                    error!("Failed to create album, error:{}", e);
-                }
+                self.album_service.create_album(&ctx, cmd).await?;
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
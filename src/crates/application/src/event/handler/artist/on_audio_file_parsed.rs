@@ -1,8 +1,8 @@
 use crate::command::artist::{ArtistService, CreateArtistCmd};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
 use crate::event::events::AppEvent;
-use log::error;
 
 #[derive(Clone)]
 pub struct ArtistOnAudioFileParsedHandler<B: EventBus> {
@@ -17,7 +17,7 @@ impl<B: EventBus> ArtistOnAudioFileParsedHandler<B> {
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for ArtistOnAudioFileParsedHandler<B> {
-    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match evt {
             AppEvent::AudioFileParsed(evt) => {
@@ -26,12 +26,11 @@ impl<B: EventBus> Handler<AppEvent> for ArtistOnAudioFileParsedHandler<B> {
                     let cmd = CreateArtistCmd {
                         name: participant.name.clone(),
                     };
-                    if let Err(e) = self.artist_service.create_artist(&ctx, cmd).await {
-                        error!("Failed to create artist, error:{}", e);
-                    }
+                    self.artist_service.create_artist(&ctx, cmd).await?;
                 }
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
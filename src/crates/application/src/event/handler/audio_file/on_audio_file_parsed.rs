@@ -1,8 +1,8 @@
 use crate::command::audio_file::{AudioFileService, CreateAudioFileCmd};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
 use crate::event::events::AppEvent;
-use log::error;
 
 #[derive(Clone)]
 pub struct AudioFileOnAudioFileParsedHandler<B: EventBus> {
@@ -17,7 +17,7 @@ impl<B: EventBus> AudioFileOnAudioFileParsedHandler<B> {
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for AudioFileOnAudioFileParsedHandler<B> {
-    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match evt {
             AppEvent::AudioFileParsed(evt) => {
@@ -27,11 +27,10 @@ impl<B: EventBus> Handler<AppEvent> for AudioFileOnAudioFileParsedHandler<B> {
                     audio_metadata: evt.metadata.clone(),
                     library_id: evt.library_id.clone(),
                 };
-                if let Err(e) = self.audio_file_service.create_audio_file(&ctx, cmd).await {
-                    error!("Failed to create audio file, error:{}", e);
-                }
+                self.audio_file_service.create_audio_file(&ctx, cmd).await?;
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
@@ -1,8 +1,8 @@
 use crate::command::cover_art::{CoverArtService, CreateCoverArtCmd};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
 use crate::event::events::AppEvent;
-use log::error;
 
 #[derive(Clone)]
 pub struct CoverArtOnImageFileParsedHandler<B: EventBus> {
@@ -17,7 +17,7 @@ impl<B: EventBus> CoverArtOnImageFileParsedHandler<B> {
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for CoverArtOnImageFileParsedHandler<B> {
-    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match evt {
             AppEvent::ImageFileParsed(evt) => {
@@ -25,12 +25,12 @@ impl<B: EventBus> Handler<AppEvent> for CoverArtOnImageFileParsedHandler<B> {
                 let cmd = CreateCoverArtCmd {
                     file_meta: evt.file_info.clone(),
                     source: evt.source.clone(),
+                    artwork: evt.artwork.clone(),
                 };
-                if let Err(e) = self.cover_art_service.create_cover_art(&ctx, cmd).await {
-                    error!("Failed to create cover art, error:{}", e);
-                }
+                self.cover_art_service.create_cover_art(&ctx, cmd).await?;
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
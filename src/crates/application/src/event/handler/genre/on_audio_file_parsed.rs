@@ -1,8 +1,8 @@
 use crate::command::genre::{CreateGenreCmd, GenreService};
 use crate::context::AppContext;
+use crate::error::AppError;
 use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
 use crate::event::events::AppEvent;
-use log::error;
 
 #[derive(Clone)]
 pub struct GenreOnAudioFileParsedHandler<B: EventBus> {
@@ -17,7 +17,7 @@ impl<B: EventBus> GenreOnAudioFileParsedHandler<B> {
 
 #[async_trait::async_trait]
 impl<B: EventBus> Handler<AppEvent> for GenreOnAudioFileParsedHandler<B> {
-    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match evt {
             AppEvent::AudioFileParsed(evt) => {
@@ -26,12 +26,11 @@ impl<B: EventBus> Handler<AppEvent> for GenreOnAudioFileParsedHandler<B> {
                     let cmd = CreateGenreCmd {
                         name: genre.to_string(),
                     };
-                    if let Err(e) = self.genre_service.create_genre(&ctx, cmd).await {
-                        error!("Failed to create genre, error:{}", e);
-                    }
+                    self.genre_service.create_genre(&ctx, cmd).await?;
                 }
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
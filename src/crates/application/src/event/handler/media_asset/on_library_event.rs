@@ -0,0 +1,48 @@
+use crate::command::media_asset::MediaAssetService;
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use domain::library::LibraryEvent;
+use domain::value::{FileMeta, FileType};
+
+/// 扫描到的小册子和图片收录为资源，文件变化时更新，移除时一并删除
+#[derive(Clone)]
+pub struct MediaAssetOnLibraryEventHandler {
+    media_asset_service: MediaAssetService,
+}
+
+impl MediaAssetOnLibraryEventHandler {
+    pub fn new(media_asset_service: MediaAssetService) -> Self {
+        Self {
+            media_asset_service,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<LibraryEvent> for MediaAssetOnLibraryEventHandler {
+    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) -> Result<(), AppError> {
+        match &envelope.payload {
+            LibraryEvent::FileAdded(evt)
+                if matches!(evt.item.file_type, FileType::Image | FileType::Document) =>
+            {
+                let file: FileMeta = evt.item.clone().into();
+                self.media_asset_service
+                    .index(evt.library_id.clone(), &file)
+                    .await?;
+            }
+            LibraryEvent::FileUpdated(evt)
+                if matches!(evt.item.file_type, FileType::Image | FileType::Document) =>
+            {
+                let file: FileMeta = evt.item.clone().into();
+                self.media_asset_service
+                    .index(evt.library_id.clone(), &file)
+                    .await?;
+            }
+            LibraryEvent::FileRemoved(evt) => {
+                self.media_asset_service.remove(&evt.path).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use super::on_library_event::MediaAssetOnLibraryEventHandler;
+use crate::command::media_asset::MediaAssetService;
+use crate::event::event_bus::EventBus;
+use domain::library::LibraryEvent;
+use std::sync::Arc;
+
+pub async fn register_handlers<B: EventBus + Clone + 'static>(
+    bus: &mut B,
+    media_asset_service: MediaAssetService,
+) {
+    let handler = MediaAssetOnLibraryEventHandler::new(media_asset_service);
+    bus.subscribe::<LibraryEvent>(Arc::new(handler)).await;
+}
@@ -3,5 +3,9 @@ pub mod artist;
 pub mod audio_file;
 pub mod cover_art;
 pub mod genre;
+pub mod media_asset;
 pub mod on_library_file_added;
 pub mod projector;
+pub mod push;
+pub mod silence_analysis;
+pub mod trash;
@@ -1,25 +1,28 @@
-use crate::command::media_parse::{MediaFileParseService, ParseMediaFileCmd};
+use crate::command::media_parse::ParseMediaFileCmd;
+use crate::command::parse_pool::ParseWorkerPool;
 use crate::context::AppContext;
-use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
 use domain::library::LibraryEvent;
-use log::error;
+use domain::value::FileType;
+use std::sync::Arc;
 
+/// 新文件交给解析工作池，扫描不再等待单个文件解析完成。
+/// 修改过的音频文件重新解析，标签的变化由绑定协调器处理
 #[derive(Clone)]
-pub struct OnLibraryFileAddedHandler<B: EventBus> {
-    media_file_parse_service: MediaFileParseService<B>,
+pub struct OnLibraryFileAddedHandler {
+    parse_pool: Arc<ParseWorkerPool>,
 }
 
-impl<B: EventBus> OnLibraryFileAddedHandler<B> {
-    pub fn new(media_file_parse_service: MediaFileParseService<B>) -> Self {
-        Self {
-            media_file_parse_service,
-        }
+impl OnLibraryFileAddedHandler {
+    pub fn new(parse_pool: Arc<ParseWorkerPool>) -> Self {
+        Self { parse_pool }
     }
 }
 
 #[async_trait::async_trait]
-impl<B: EventBus> Handler<LibraryEvent> for OnLibraryFileAddedHandler<B> {
-    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) {
+impl Handler<LibraryEvent> for OnLibraryFileAddedHandler {
+    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match evt {
             LibraryEvent::FileAdded(evt) => {
@@ -29,15 +32,20 @@ impl<B: EventBus> Handler<LibraryEvent> for OnLibraryFileAddedHandler<B> {
                     library_id: evt.library_id.clone(),
                     file_type: evt.item.file_type.clone(),
                 };
-                if let Err(e) = self
-                    .media_file_parse_service
-                    .parse_media_file(&ctx, cmd)
-                    .await
-                {
-                    error!("Failed to parse media file, error:{}", e);
-                }
+                // 解析失败由工作池记录，这里只在工作池停止时返回错误
+                self.parse_pool.submit(ctx, cmd).await?;
+            }
+            LibraryEvent::FileUpdated(evt) if evt.item.file_type == FileType::Audio => {
+                let ctx = AppContext::from(envelope);
+                let cmd = ParseMediaFileCmd {
+                    filemeta: evt.item.clone().into(),
+                    library_id: evt.library_id.clone(),
+                    file_type: evt.item.file_type.clone(),
+                };
+                self.parse_pool.submit(ctx, cmd).await?;
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::event::event_bus::{EventEnvelope, Handler};
 use crate::projector::album_stats::AlbumStatsProjector;
 use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
@@ -17,7 +18,7 @@ impl AlbumStatsHandler {
 
 #[async_trait::async_trait]
 impl Handler<AudioFileEvent> for AlbumStatsHandler {
-    async fn handle(&self, event_envelope: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, event_envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         match &event_envelope.payload.kind {
             AudioFileEventKind::BoundToAlbum(_) => {
                 if let Err(e) = self
@@ -45,5 +46,6 @@ impl Handler<AudioFileEvent> for AlbumStatsHandler {
                 debug!("Audio file event received, no action needed for album stat");
             }
         }
+        Ok(())
     }
 }
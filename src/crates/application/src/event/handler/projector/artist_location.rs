@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::event::event_bus::{EventEnvelope, Handler};
 use crate::projector::artist_location::ArtistLocationProjector;
 use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
@@ -15,7 +16,7 @@ impl ArtistLocationHandler {
 
 #[async_trait::async_trait]
 impl Handler<AudioFileEvent> for ArtistLocationHandler {
-    async fn handle(&self, event: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, event: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         match &event.payload.kind {
             AudioFileEventKind::ParticipantAdded(_) => {
                 if let Err(e) = self
@@ -39,5 +40,6 @@ impl Handler<AudioFileEvent> for ArtistLocationHandler {
                 debug!("Audio file event received, no action needed for artist location");
             }
         }
+        Ok(())
     }
 }
@@ -1,5 +1,6 @@
 use crate::commands::cover_art::{CoverArtService, CreateCoverArtCmd};
 use crate::commands::event::{EventEnvelope, Handler};
+use crate::error::AppError;
 use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
 use domain::library::FileType;
 use domain::library::LibraryEvent;
@@ -17,7 +18,7 @@ impl CoverArtHandler {
 
 #[async_trait::async_trait]
 impl Handler<AudioFileEvent> for CoverArtHandler {
-    async fn handle(&self, envelope: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match &evt.kind {
             AudioFileEventKind::Created(evt) => {
@@ -28,23 +29,24 @@ impl Handler<AudioFileEvent> for CoverArtHandler {
                 };
                 if let Err(e) = self.cover_art_service.create_cover_art(cmd).await {
                     error!("Failed to create cover art, error:{}", e);
-                    return;
+                    return Ok(());
                 }
-                return;
+                return Ok(());
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl Handler<LibraryEvent> for CoverArtHandler {
-    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) -> Result<(), AppError> {
         let evt = &envelope.payload;
         match evt {
             LibraryEvent::FileAdded(evt) => {
                 if evt.item.file_type != FileType::Image {
-                    return;
+                    return Ok(());
                 }
                 let cmd = CreateCoverArtCmd {
                     media_path: evt.item.path.clone(),
@@ -52,11 +54,12 @@ impl Handler<LibraryEvent> for CoverArtHandler {
                 };
                 if let Err(e) = self.cover_art_service.create_cover_art(cmd).await {
                     error!("Failed to create cover art, error:{}", e);
-                    return;
+                    return Ok(());
                 }
-                return;
+                return Ok(());
             }
-            _ => return,
+            _ => return Ok(()),
         }
+        Ok(())
     }
 }
@@ -0,0 +1,31 @@
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use crate::projector::format_stats::FormatStatsProjector;
+use domain::audio_file::AudioFileEvent;
+use log::error;
+
+pub struct FormatStatsHandler {
+    format_stats_projector: FormatStatsProjector,
+}
+
+impl FormatStatsHandler {
+    pub fn new(format_stats_projector: FormatStatsProjector) -> Self {
+        Self {
+            format_stats_projector,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<AudioFileEvent> for FormatStatsHandler {
+    async fn handle(&self, event_envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
+        if let Err(e) = self
+            .format_stats_projector
+            .on_audio_file_event(&event_envelope.payload)
+            .await
+        {
+            error!("Failed to update format stats: {}", e);
+        }
+        Ok(())
+    }
+}
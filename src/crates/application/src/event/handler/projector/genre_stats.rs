@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::event::event_bus::{EventEnvelope, Handler};
 use crate::projector::genre_stats::GenreStatsProjector;
 use domain::album::{AlbumEvent, AlbumEventKind};
@@ -18,7 +19,7 @@ impl GenreStatsHandler {
 
 #[async_trait::async_trait]
 impl Handler<AudioFileEvent> for GenreStatsHandler {
-    async fn handle(&self, event_envelope: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, event_envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         match &event_envelope.payload.kind {
             AudioFileEventKind::GenreAdded(_) => {
                 if let Err(e) = self
@@ -29,7 +30,7 @@ impl Handler<AudioFileEvent> for GenreStatsHandler {
                     error!("Failed to handle audio file bound to genre event: {}", e);
                 }
             }
-            AudioFileEventKind::GenreRemoved(_) => {
+            AudioFileEventKind::GenreRemoved(_) | AudioFileEventKind::UnboundFromGenre(_) => {
                 if let Err(e) = self
                     .genre_stats_projector
                     .on_audio_file_unbound_from_genre(&event_envelope.payload)
@@ -46,12 +47,13 @@ impl Handler<AudioFileEvent> for GenreStatsHandler {
                 debug!("Audio file event received, no action needed for genre stats");
             }
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl Handler<AlbumEvent> for GenreStatsHandler {
-    async fn handle(&self, event_envelope: &EventEnvelope<AlbumEvent>) {
+    async fn handle(&self, event_envelope: &EventEnvelope<AlbumEvent>) -> Result<(), AppError> {
         match &event_envelope.payload.kind {
             AlbumEventKind::BoundToGenre(_) => {
                 if let Err(e) = self
@@ -76,5 +78,6 @@ impl Handler<AlbumEvent> for GenreStatsHandler {
                 debug!("Album event received, no action needed for genre stats");
             }
         }
+        Ok(())
     }
 }
@@ -0,0 +1,51 @@
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use crate::projector::listening_report::ListeningReportProjector;
+use domain::annotation::AnnotationEvent;
+use domain::value::AudioFileId;
+use log::error;
+
+pub struct ListeningReportHandler {
+    listening_report_projector: ListeningReportProjector,
+}
+
+impl ListeningReportHandler {
+    pub fn new(listening_report_projector: ListeningReportProjector) -> Self {
+        Self {
+            listening_report_projector,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<AnnotationEvent> for ListeningReportHandler {
+    async fn handle(
+        &self,
+        event_envelope: &EventEnvelope<AnnotationEvent>,
+    ) -> Result<(), AppError> {
+        let AnnotationEvent::ItemScrobbled {
+            user_id,
+            item_id,
+            item_type,
+            played_at,
+            ..
+        } = &event_envelope.payload
+        else {
+            return Ok(());
+        };
+        if item_type != "audio_file" {
+            return Ok(());
+        }
+        if let Err(e) = self
+            .listening_report_projector
+            .on_scrobble(user_id.clone(), AudioFileId::from(*item_id), *played_at)
+            .await
+        {
+            error!(
+                "Failed to handle scrobble event for listening report: {}",
+                e
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use crate::event::push::{ServerEvent, ServerEventHub};
+use crate::projector::listening_streak::ListeningStreakProjector;
+use domain::annotation::AnnotationEvent;
+use log::error;
+
+/// 更新连续收听天数，新达成的里程碑推送给在线客户端
+pub struct ListeningStreakHandler {
+    listening_streak_projector: ListeningStreakProjector,
+    hub: ServerEventHub,
+}
+
+impl ListeningStreakHandler {
+    pub fn new(listening_streak_projector: ListeningStreakProjector, hub: ServerEventHub) -> Self {
+        Self {
+            listening_streak_projector,
+            hub,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<AnnotationEvent> for ListeningStreakHandler {
+    async fn handle(
+        &self,
+        event_envelope: &EventEnvelope<AnnotationEvent>,
+    ) -> Result<(), AppError> {
+        let AnnotationEvent::ItemScrobbled {
+            user_id,
+            item_id,
+            item_type,
+            played_at,
+            ..
+        } = &event_envelope.payload
+        else {
+            return Ok(());
+        };
+        match self
+            .listening_streak_projector
+            .on_scrobble(user_id.clone(), item_type, *item_id, *played_at)
+            .await
+        {
+            Ok(reached) => {
+                for milestone in reached {
+                    self.hub.publish(ServerEvent::MilestoneReached(milestone));
+                }
+            }
+            Err(e) => error!(
+                "Failed to handle scrobble event for listening streak: {}",
+                e
+            ),
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,15 @@
 pub mod album_location;
 pub mod album_stats;
 pub mod artist_location;
+pub mod format_stats;
 pub mod genre_stats;
+pub mod listening_report;
+pub mod listening_streak;
 pub mod participant_stats;
 pub mod playback_history;
+pub mod play_stats;
 pub mod scan_status;
+pub mod star_stats;
 
 pub mod registry;
 pub use registry::register_handlers;
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::event::event_bus::{EventEnvelope, Handler};
 use crate::projector::participant_stats::ParticipantStatsProjector;
 use domain::album::{AlbumEvent, AlbumEventKind};
@@ -18,7 +19,7 @@ impl ParticipantStatsHandler {
 
 #[async_trait::async_trait]
 impl Handler<AudioFileEvent> for ParticipantStatsHandler {
-    async fn handle(&self, event_envelope: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, event_envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         match &event_envelope.payload.kind {
             AudioFileEventKind::ParticipantAdded(_) => {
                 if let Err(e) = self
@@ -46,12 +47,13 @@ impl Handler<AudioFileEvent> for ParticipantStatsHandler {
                 debug!("Audio file event received, no action needed for artist stat");
             }
         }
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl Handler<AlbumEvent> for ParticipantStatsHandler {
-    async fn handle(&self, event_envelope: &EventEnvelope<AlbumEvent>) {
+    async fn handle(&self, event_envelope: &EventEnvelope<AlbumEvent>) -> Result<(), AppError> {
         match &event_envelope.payload.kind {
             AlbumEventKind::ParticipantAdded(_) => {
                 if let Err(e) = self
@@ -76,5 +78,6 @@ impl Handler<AlbumEvent> for ParticipantStatsHandler {
                 debug!("Album event received, no action needed for participant stats");
             }
         }
+        Ok(())
     }
 }
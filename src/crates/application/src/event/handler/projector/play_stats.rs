@@ -0,0 +1,44 @@
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use crate::projector::play_stats::PlayStatsProjector;
+use domain::annotation::AnnotationEvent;
+use log::error;
+
+pub struct PlayStatsHandler {
+    play_stats_projector: PlayStatsProjector,
+}
+
+impl PlayStatsHandler {
+    pub fn new(play_stats_projector: PlayStatsProjector) -> Self {
+        Self {
+            play_stats_projector,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<AnnotationEvent> for PlayStatsHandler {
+    async fn handle(
+        &self,
+        event_envelope: &EventEnvelope<AnnotationEvent>,
+    ) -> Result<(), AppError> {
+        let AnnotationEvent::ItemScrobbled {
+            user_id,
+            item_id,
+            item_type,
+            played_at,
+            ..
+        } = &event_envelope.payload
+        else {
+            return Ok(());
+        };
+        if let Err(e) = self
+            .play_stats_projector
+            .on_scrobble(user_id.clone(), item_type, *item_id, *played_at)
+            .await
+        {
+            error!("Failed to handle scrobble event for play stats: {}", e);
+        }
+        Ok(())
+    }
+}
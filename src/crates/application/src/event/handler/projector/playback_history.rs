@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::event::event_bus::{EventEnvelope, Handler};
 use crate::projector::playback_history::PlaybackHistoryProjector;
 use async_trait::async_trait;
@@ -21,7 +22,7 @@ impl PlaybackHistoryEventHandler {
 
 #[async_trait]
 impl Handler<AnnotationEvent> for PlaybackHistoryEventHandler {
-    async fn handle(&self, envelope: &EventEnvelope<AnnotationEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AnnotationEvent>) -> Result<(), AppError> {
         match &envelope.payload {
             AnnotationEvent::ItemScrobbled {
                 annotation_id: _,
@@ -29,13 +30,14 @@ impl Handler<AnnotationEvent> for PlaybackHistoryEventHandler {
                 item_id,
                 item_type,
                 user_id,
+                played_at,
             } => {
                 if item_type != "audio_file" {
-                    return;
+                    return Ok(());
                 }
                 if let Err(e) = self
                     .projector
-                    .on_scrobble(AudioFileId::from(*item_id), user_id.clone())
+                    .on_scrobble(AudioFileId::from(*item_id), user_id.clone(), *played_at)
                     .await
                 {
                     error!("Error projecting player event: {}", e);
@@ -43,5 +45,6 @@ impl Handler<AnnotationEvent> for PlaybackHistoryEventHandler {
             }
             _ => {}
         }
+        Ok(())
     }
 }
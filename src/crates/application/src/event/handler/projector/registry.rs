@@ -1,25 +1,45 @@
 use super::album_location::AlbumLocationHandler;
 use super::album_stats::AlbumStatsHandler;
 use super::artist_location::ArtistLocationHandler;
+use super::format_stats::FormatStatsHandler;
 use super::genre_stats::GenreStatsHandler;
+use super::listening_report::ListeningReportHandler;
+use super::listening_streak::ListeningStreakHandler;
 use super::participant_stats::ParticipantStatsHandler;
+use super::play_stats::PlayStatsHandler;
 use super::playback_history::PlaybackHistoryEventHandler;
-use super::scan_status::{ScanLifecycleEventHandler, ScanStatusEventHandler};
-use crate::command::shared::IdGenerator;
+use super::scan_status::{
+    ScanLifecycleEventHandler, ScanParseEventHandler, ScanStatusEventHandler,
+};
+use super::star_stats::StarStatsHandler;
+use crate::command::shared::{Clock, IdGenerator};
 use crate::event::event_bus::EventBus;
+use crate::event::processed_event::{Idempotent, ProcessedEventLedger};
+use crate::event::push::ServerEventHub;
 use crate::projector::album_location::AlbumLocationProjector;
 use crate::projector::album_stats::AlbumStatsProjector;
 use crate::projector::artist_location::ArtistLocationProjector;
+use crate::projector::format_stats::FormatStatsProjector;
 use crate::projector::genre_stats::GenreStatsProjector;
+use crate::projector::listening_report::ListeningReportProjector;
+use crate::projector::listening_streak::ListeningStreakProjector;
 use crate::projector::participant_stats::ParticipantStatsProjector;
+use crate::projector::play_stats::PlayStatsProjector;
 use crate::projector::scan_status::ScanStatusProjectorImpl;
+use crate::projector::star_stats::StarStatsProjector;
+use domain::audio_file::AudioFileRepository;
 use model::album_location::AlbumLocationRepository;
 use model::album_stats::AlbumStatsRepository;
 use model::artist_location::ArtistLocationRepository;
+use model::format_stats::FormatStatsRepository;
 use model::genre::GenreStatsRepository;
+use model::listening_report::ListeningClockRepository;
+use model::listening_streak::ListeningStreakRepository;
 use model::participant_stats::ParticipantStatsRepository;
+use model::play_stats::PlayStatsRepository;
 use model::playback_history::PlaybackHistoryRepository;
 use model::scan_status::ScanStatusRepository;
+use model::star_stats::StarStatsRepository;
 use std::sync::Arc;
 
 pub async fn register_handlers<B: EventBus + Clone + 'static>(
@@ -28,12 +48,24 @@ pub async fn register_handlers<B: EventBus + Clone + 'static>(
     album_location_repository: Arc<dyn AlbumLocationRepository>,
     album_stats_repository: Arc<dyn AlbumStatsRepository>,
     artist_location_repository: Arc<dyn ArtistLocationRepository>,
+    format_stats_repository: Arc<dyn FormatStatsRepository>,
     genre_stats_repository: Arc<dyn GenreStatsRepository>,
     participant_stats_repository: Arc<dyn ParticipantStatsRepository>,
     playback_history_repository: Arc<dyn PlaybackHistoryRepository + Send + Sync>,
     scan_status_repository: Arc<dyn ScanStatusRepository + Send + Sync>,
+    star_stats_repository: Arc<dyn StarStatsRepository>,
+    play_stats_repository: Arc<dyn PlayStatsRepository>,
+    listening_clock_repository: Arc<dyn ListeningClockRepository>,
+    listening_streak_repository: Arc<dyn ListeningStreakRepository>,
+    audio_file_repository: Arc<dyn AudioFileRepository>,
     // 服务依赖
     id_generator: Arc<dyn IdGenerator>,
+    clock: Arc<dyn Clock>,
+    // 写数据库的投影使用持久化的处理记录；写 memtable 的投影使用进程内的记录，
+    // 否则记录会先于投影落盘，进程退出时丢失的投影再也不会补回
+    processed_events: Arc<dyn ProcessedEventLedger>,
+    buffered_processed_events: Arc<dyn ProcessedEventLedger>,
+    push_hub: ServerEventHub,
 ) {
     // 创建投影器
     let album_location_projector =
@@ -47,22 +79,74 @@ pub async fn register_handlers<B: EventBus + Clone + 'static>(
 
     let genre_stats_projector_audio = GenreStatsProjector::new(genre_stats_repository.clone());
     let genre_stats_projector_album = GenreStatsProjector::new(genre_stats_repository);
-    let scan_status_projector = Arc::new(ScanStatusProjectorImpl::new(scan_status_repository));
+    let scan_status_projector =
+        Arc::new(ScanStatusProjectorImpl::new(scan_status_repository, clock));
 
     // 创建处理器
     let album_location_handler = AlbumLocationHandler::new(album_location_projector);
-    let album_stats_handler = AlbumStatsHandler::new(album_stats_projector);
+    // 累加型的投影重复处理会重复计数，按事件 id 去重
+    let album_stats_handler = Idempotent::new(
+        Arc::new(AlbumStatsHandler::new(album_stats_projector)),
+        buffered_processed_events.clone(),
+    );
 
     let artist_location_handler = ArtistLocationHandler::new(artist_location_projector);
 
-    let participant_stats_handler =
-        Arc::new(ParticipantStatsHandler::new(participant_stats_projector));
+    let participant_stats_handler = Arc::new(Idempotent::new(
+        Arc::new(ParticipantStatsHandler::new(participant_stats_projector)),
+        buffered_processed_events.clone(),
+    ));
 
-    let genre_stats_handler_audio = GenreStatsHandler::new(genre_stats_projector_audio);
-    let genre_stats_handler_album = GenreStatsHandler::new(genre_stats_projector_album);
+    let format_stats_handler = Idempotent::new(
+        Arc::new(FormatStatsHandler::new(FormatStatsProjector::new(
+            format_stats_repository,
+        ))),
+        buffered_processed_events.clone(),
+    );
+    let genre_stats_handler_audio = Idempotent::new(
+        Arc::new(GenreStatsHandler::new(genre_stats_projector_audio)),
+        buffered_processed_events.clone(),
+    );
+    let genre_stats_handler_album = Idempotent::new(
+        Arc::new(GenreStatsHandler::new(genre_stats_projector_album)),
+        buffered_processed_events,
+    );
     let scan_status_handler = ScanStatusEventHandler::new(scan_status_projector.clone());
-    let scan_lifecycle_handler = ScanLifecycleEventHandler::new(scan_status_projector);
-    let playback_history_handler = PlaybackHistoryEventHandler::new(playback_history_repository);
+    let scan_lifecycle_handler = ScanLifecycleEventHandler::new(scan_status_projector.clone());
+    let scan_parse_handler = ScanParseEventHandler::new(scan_status_projector);
+    let playback_history_handler = Idempotent::new(
+        Arc::new(PlaybackHistoryEventHandler::new(
+            playback_history_repository,
+        )),
+        processed_events.clone(),
+    );
+    let star_stats_handler = Idempotent::new(
+        Arc::new(StarStatsHandler::new(StarStatsProjector::new(
+            star_stats_repository,
+        ))),
+        processed_events.clone(),
+    );
+    let play_stats_handler = Idempotent::new(
+        Arc::new(PlayStatsHandler::new(PlayStatsProjector::new(
+            play_stats_repository.clone(),
+        ))),
+        processed_events.clone(),
+    );
+    let listening_report_handler = Idempotent::new(
+        Arc::new(ListeningReportHandler::new(ListeningReportProjector::new(
+            audio_file_repository,
+            listening_clock_repository,
+            play_stats_repository,
+        ))),
+        processed_events.clone(),
+    );
+    let listening_streak_handler = Idempotent::new(
+        Arc::new(ListeningStreakHandler::new(
+            ListeningStreakProjector::new(listening_streak_repository),
+            push_hub,
+        )),
+        processed_events,
+    );
 
     // 注册处理器到事件总线
     bus.subscribe::<domain::audio_file::AudioFileEvent>(Arc::new(album_location_handler))
@@ -79,6 +163,8 @@ pub async fn register_handlers<B: EventBus + Clone + 'static>(
         .await;
     bus.subscribe::<domain::album::AlbumEvent>(participant_stats_handler.clone())
         .await;
+    bus.subscribe::<domain::audio_file::AudioFileEvent>(Arc::new(format_stats_handler))
+        .await;
     bus.subscribe::<domain::audio_file::AudioFileEvent>(Arc::new(genre_stats_handler_audio))
         .await;
     bus.subscribe::<domain::album::AlbumEvent>(Arc::new(genre_stats_handler_album))
@@ -88,6 +174,16 @@ pub async fn register_handlers<B: EventBus + Clone + 'static>(
         .await;
     bus.subscribe::<domain::library::LibraryEvent>(Arc::new(scan_lifecycle_handler))
         .await;
+    bus.subscribe::<crate::event::events::AppEvent>(Arc::new(scan_parse_handler))
+        .await;
     bus.subscribe::<domain::annotation::AnnotationEvent>(Arc::new(playback_history_handler))
         .await;
+    bus.subscribe::<domain::annotation::AnnotationEvent>(Arc::new(star_stats_handler))
+        .await;
+    bus.subscribe::<domain::annotation::AnnotationEvent>(Arc::new(play_stats_handler))
+        .await;
+    bus.subscribe::<domain::annotation::AnnotationEvent>(Arc::new(listening_report_handler))
+        .await;
+    bus.subscribe::<domain::annotation::AnnotationEvent>(Arc::new(listening_streak_handler))
+        .await;
 }
@@ -1,4 +1,6 @@
+use crate::error::AppError;
 use crate::event::event_bus::{EventEnvelope, Handler};
+use crate::event::events::AppEvent;
 use crate::projector::scan_status::ScanStatusProjector;
 use domain::audio_file::AudioFileEvent;
 use domain::library::LibraryEvent;
@@ -18,10 +20,11 @@ impl ScanStatusEventHandler {
 
 #[async_trait::async_trait]
 impl Handler<AudioFileEvent> for ScanStatusEventHandler {
-    async fn handle(&self, envelope: &EventEnvelope<AudioFileEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
         if let Err(e) = self.projector.on_audio_file_event(&envelope.payload).await {
             error!("Error projecting audio file event: {}", e);
         }
+        Ok(())
     }
 }
 
@@ -38,7 +41,7 @@ impl ScanLifecycleEventHandler {
 
 #[async_trait::async_trait]
 impl Handler<LibraryEvent> for ScanLifecycleEventHandler {
-    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) {
+    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) -> Result<(), AppError> {
         match &envelope.payload {
             LibraryEvent::ScanStarted(evt) => {
                 if let Err(e) = self.projector.on_scan_started(evt).await {
@@ -50,7 +53,50 @@ impl Handler<LibraryEvent> for ScanLifecycleEventHandler {
                     error!("Failed to handle scan ended event: {}", e);
                 }
             }
+            LibraryEvent::FileAdded(evt) => {
+                if let Err(e) = self.projector.on_file_added(evt).await {
+                    error!("Failed to handle file added event: {}", e);
+                }
+            }
+            LibraryEvent::FileUpdated(evt) => {
+                if let Err(e) = self.projector.on_file_updated(evt).await {
+                    error!("Failed to handle file updated event: {}", e);
+                }
+            }
             _ => {}
         }
+        Ok(())
+    }
+}
+
+/// ScanParseEventHandler 元数据解析结果事件处理器
+pub struct ScanParseEventHandler {
+    projector: Arc<dyn ScanStatusProjector + Send + Sync>,
+}
+
+impl ScanParseEventHandler {
+    pub fn new(projector: Arc<dyn ScanStatusProjector + Send + Sync>) -> Self {
+        Self { projector }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<AppEvent> for ScanParseEventHandler {
+    async fn handle(&self, envelope: &EventEnvelope<AppEvent>) -> Result<(), AppError> {
+        let result = match &envelope.payload {
+            AppEvent::AudioFileParsed(evt) => {
+                self.projector.on_audio_file_parsed(&evt.library_id).await
+            }
+            AppEvent::AudioFileParseFailed(evt) => {
+                self.projector
+                    .on_audio_file_parse_failed(&evt.library_id)
+                    .await
+            }
+            _ => return Ok(()),
+        };
+        if let Err(e) = result {
+            error!("Failed to handle parse event: {}", e);
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,37 @@
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use crate::projector::star_stats::StarStatsProjector;
+use domain::annotation::AnnotationEvent;
+use log::error;
+
+pub struct StarStatsHandler {
+    star_stats_projector: StarStatsProjector,
+}
+
+impl StarStatsHandler {
+    pub fn new(star_stats_projector: StarStatsProjector) -> Self {
+        Self {
+            star_stats_projector,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<AnnotationEvent> for StarStatsHandler {
+    async fn handle(
+        &self,
+        event_envelope: &EventEnvelope<AnnotationEvent>,
+    ) -> Result<(), AppError> {
+        let payload = &event_envelope.payload;
+        if !matches!(
+            payload,
+            AnnotationEvent::ItemStarred { .. } | AnnotationEvent::ItemUnstarred { .. }
+        ) {
+            return Ok(());
+        }
+        if let Err(e) = self.star_stats_projector.on_star_changed(payload).await {
+            error!("Failed to handle star changed event: {}", e);
+        }
+        Ok(())
+    }
+}
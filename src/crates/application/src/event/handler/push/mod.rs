@@ -0,0 +1,4 @@
+pub mod server_event;
+
+pub mod registry;
+pub use registry::register_handlers;
@@ -0,0 +1,18 @@
+use super::server_event::ServerEventHandler;
+use crate::event::event_bus::EventBus;
+use crate::event::push::ServerEventHub;
+use model::scan_status::ScanStatusRepository;
+use std::sync::Arc;
+
+pub async fn register_handlers<B: EventBus + Clone + 'static>(
+    bus: &mut B,
+    hub: ServerEventHub,
+    scan_status_repository: Arc<dyn ScanStatusRepository + Send + Sync>,
+) {
+    let handler = Arc::new(ServerEventHandler::new(hub, scan_status_repository));
+    bus.subscribe::<domain::library::LibraryEvent>(handler.clone())
+        .await;
+    bus.subscribe::<domain::audio_file::AudioFileEvent>(handler.clone())
+        .await;
+    bus.subscribe::<domain::player::PlayerEvent>(handler).await;
+}
@@ -0,0 +1,117 @@
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use crate::event::push::{ServerEvent, ServerEventHub};
+use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
+use domain::library::LibraryEvent;
+use domain::player::{PlayerEvent, PlayerEventKind};
+use domain::value::LibraryId;
+use log::warn;
+use model::scan_status::ScanStatusRepository;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 同一个库的扫描进度最多每隔这么久推送一次
+const SCAN_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 把内部事件总线上的扫描、播放事件转发到 ServerEventHub
+pub struct ServerEventHandler {
+    hub: ServerEventHub,
+    scan_status_repository: Arc<dyn ScanStatusRepository + Send + Sync>,
+    last_progress: Mutex<HashMap<LibraryId, Instant>>,
+}
+
+impl ServerEventHandler {
+    pub fn new(
+        hub: ServerEventHub,
+        scan_status_repository: Arc<dyn ScanStatusRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            hub,
+            scan_status_repository,
+            last_progress: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn should_push_progress(&self, library_id: &LibraryId) -> bool {
+        let mut last_progress = self.last_progress.lock().unwrap();
+        let now = Instant::now();
+        match last_progress.get(library_id) {
+            Some(last) if now.duration_since(*last) < SCAN_PROGRESS_INTERVAL => false,
+            _ => {
+                last_progress.insert(library_id.clone(), now);
+                true
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<LibraryEvent> for ServerEventHandler {
+    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) -> Result<(), AppError> {
+        match &envelope.payload {
+            LibraryEvent::ScanStarted(evt) => self.hub.publish(ServerEvent::ScanStarted {
+                library_id: evt.library_id.clone(),
+            }),
+            LibraryEvent::ScanEnded(evt) => {
+                self.last_progress.lock().unwrap().remove(&evt.library_id);
+                self.hub.publish(ServerEvent::ScanEnded {
+                    library_id: evt.library_id.clone(),
+                })
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<AudioFileEvent> for ServerEventHandler {
+    async fn handle(&self, envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
+        let AudioFileEventKind::Created(created) = &envelope.payload.kind else {
+            return Ok(());
+        };
+        if !self.should_push_progress(&created.library_id) {
+            return Ok(());
+        }
+        match self
+            .scan_status_repository
+            .get_scan_status(&created.library_id)
+            .await
+        {
+            Ok(Some(status)) => self.hub.publish(ServerEvent::ScanProgress {
+                library_id: status.library_id,
+                processed_files: status.processed_files,
+                total_files: status.total_files,
+            }),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to read scan status for push: {}", e),
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<PlayerEvent> for ServerEventHandler {
+    async fn handle(&self, envelope: &EventEnvelope<PlayerEvent>) -> Result<(), AppError> {
+        let event = &envelope.payload;
+        match &event.kind {
+            PlayerEventKind::PlaybackStarted {
+                user_id,
+                audio_file_id,
+            } => self.hub.publish(ServerEvent::NowPlaying {
+                player_id: event.player_id.clone(),
+                user_id: user_id.clone(),
+                audio_file_id: audio_file_id.clone(),
+            }),
+            PlayerEventKind::PlaybackStopped { audio_file_id } => {
+                self.hub.publish(ServerEvent::PlaybackStopped {
+                    player_id: event.player_id.clone(),
+                    audio_file_id: audio_file_id.clone(),
+                })
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod on_library_event;
+
+pub mod registry;
+pub use registry::register_handlers;
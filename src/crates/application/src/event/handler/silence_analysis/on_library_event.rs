@@ -0,0 +1,36 @@
+use crate::command::silence_analysis::SilenceAnalysisService;
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, Handler};
+use domain::library::LibraryEvent;
+use log::warn;
+use std::sync::Arc;
+
+/// 扫描结束后在后台分析新增和变化歌曲的首尾静音，不阻塞事件处理
+#[derive(Clone)]
+pub struct SilenceAnalysisOnLibraryEventHandler {
+    silence_analysis_service: Arc<SilenceAnalysisService>,
+}
+
+impl SilenceAnalysisOnLibraryEventHandler {
+    pub fn new(silence_analysis_service: Arc<SilenceAnalysisService>) -> Self {
+        Self {
+            silence_analysis_service,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<LibraryEvent> for SilenceAnalysisOnLibraryEventHandler {
+    async fn handle(&self, envelope: &EventEnvelope<LibraryEvent>) -> Result<(), AppError> {
+        if let LibraryEvent::ScanEnded(evt) = &envelope.payload {
+            let service = self.silence_analysis_service.clone();
+            let library_id = evt.library_id.clone();
+            tokio::spawn(async move {
+                if let Err(e) = service.run(Some(library_id)).await {
+                    warn!("Silence analysis failed: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use super::on_library_event::SilenceAnalysisOnLibraryEventHandler;
+use crate::command::silence_analysis::SilenceAnalysisService;
+use crate::event::event_bus::EventBus;
+use domain::library::LibraryEvent;
+use std::sync::Arc;
+
+pub async fn register_handlers<B: EventBus + Clone + 'static>(
+    bus: &mut B,
+    silence_analysis_service: Arc<SilenceAnalysisService>,
+) {
+    let handler = SilenceAnalysisOnLibraryEventHandler::new(silence_analysis_service);
+    bus.subscribe::<LibraryEvent>(Arc::new(handler)).await;
+}
@@ -0,0 +1,4 @@
+pub mod on_audio_file_created;
+
+pub mod registry;
+pub use registry::register_handlers;
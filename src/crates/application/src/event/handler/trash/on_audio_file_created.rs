@@ -0,0 +1,28 @@
+use crate::command::trash::TrashService;
+use crate::error::AppError;
+use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
+use domain::audio_file::{AudioFileEvent, AudioFileEventKind};
+use std::sync::Arc;
+
+/// 从回收站恢复的文件重新入库后迁移删除前的注解
+pub struct TrashOnAudioFileCreatedHandler<B: EventBus> {
+    trash_service: Arc<TrashService<B>>,
+}
+
+impl<B: EventBus> TrashOnAudioFileCreatedHandler<B> {
+    pub fn new(trash_service: Arc<TrashService<B>>) -> Self {
+        Self { trash_service }
+    }
+}
+
+#[async_trait::async_trait]
+impl<B: EventBus + 'static> Handler<AudioFileEvent> for TrashOnAudioFileCreatedHandler<B> {
+    async fn handle(&self, envelope: &EventEnvelope<AudioFileEvent>) -> Result<(), AppError> {
+        if let AudioFileEventKind::Created(evt) = &envelope.payload.kind {
+            self.trash_service
+                .carry_over_annotations(&evt.path, &evt.audio_file_id)
+                .await?;
+        }
+        Ok(())
+    }
+}
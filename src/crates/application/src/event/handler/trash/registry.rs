@@ -0,0 +1,13 @@
+use super::on_audio_file_created::TrashOnAudioFileCreatedHandler;
+use crate::command::trash::TrashService;
+use crate::event::event_bus::EventBus;
+use domain::audio_file::AudioFileEvent;
+use std::sync::Arc;
+
+pub async fn register_handlers<B: EventBus + Clone + 'static>(
+    bus: &mut B,
+    trash_service: Arc<TrashService<B>>,
+) {
+    let handler = TrashOnAudioFileCreatedHandler::new(trash_service);
+    bus.subscribe::<AudioFileEvent>(Arc::new(handler)).await;
+}
@@ -1,4 +1,8 @@
 pub mod coordinator;
+pub mod dead_letter;
 pub mod event_bus;
 pub mod events;
 pub mod handler;
+pub mod outbox;
+pub mod processed_event;
+pub mod push;
@@ -0,0 +1,64 @@
+//! 事务 outbox：注解事件和正在播放的播放器事件与聚合在同一事务中写入，由 relay 发布。
+//!
+//! 扫描、合并、回收站、流派等其他命令服务仍在写库后直接发布事件，进程在两者之间退出时
+//! 事件会丢失，需要重新扫描或重建统计来恢复。
+
+use crate::error::AppError;
+use crate::event::event_bus::EventEnvelope;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::annotation::{Annotation, AnnotationEvent};
+use domain::player::{Player, PlayerEvent, PlayerRepository};
+
+/// 与聚合在同一事务中写入、等待发布到事件总线的事件
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    /// 即事件 id，重新发布时保持不变
+    pub id: String,
+    /// 决定 payload 的解码方式
+    pub event_type: String,
+    pub aggregate_id: i64,
+    pub version: i64,
+    /// JSON 编码的事件
+    pub payload: String,
+    pub correlation_id: String,
+    pub causation_id: String,
+    pub occurred_at: NaiveDateTime,
+    /// 发布失败的次数
+    pub attempts: i32,
+    pub last_error: Option<String>,
+}
+
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// 尚未投递且失败次数小于 max_attempts 的消息，按写入顺序
+    async fn find_pending(
+        &self,
+        max_attempts: i32,
+        limit: u64,
+    ) -> anyhow::Result<Vec<OutboxMessage>>;
+    async fn mark_delivered(&self, id: &str) -> anyhow::Result<()>;
+    async fn record_failure(&self, id: &str, error: &str) -> anyhow::Result<()>;
+    /// 清理投递时间早于 before 的消息，返回删除条数
+    async fn purge_delivered(&self, before: NaiveDateTime) -> anyhow::Result<u64>;
+}
+
+/// 注解和它产生的事件一起提交，事件由 outbox relay 发布
+#[async_trait]
+pub trait AnnotationOutbox: Send + Sync {
+    async fn save_with_events(
+        &self,
+        annotation: Annotation,
+        events: Vec<EventEnvelope<AnnotationEvent>>,
+    ) -> Result<(), AppError>;
+}
+
+/// 播放器状态和正在播放的事件一起提交，事件由 outbox relay 发布
+#[async_trait]
+pub trait PlayerOutbox: PlayerRepository + Send + Sync {
+    async fn save_with_events(
+        &self,
+        player: &mut Player,
+        events: Vec<EventEnvelope<PlayerEvent>>,
+    ) -> Result<(), AppError>;
+}
@@ -0,0 +1,145 @@
+use crate::error::AppError;
+use crate::event::event_bus::{EventEnvelope, EventId, Handler};
+use async_trait::async_trait;
+use log::{debug, warn};
+use std::sync::Arc;
+
+/// 记录 handler 已处理过的事件，重试、重放时据此跳过
+#[async_trait]
+pub trait ProcessedEventLedger: Send + Sync {
+    /// 标记为已处理，已经标记过时返回 false
+    async fn try_mark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<bool>;
+    /// 处理失败时撤销标记，让重试可以再次处理
+    async fn unmark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<()>;
+}
+
+/// 按事件 id 去重的 handler 包装，用于累加型的投影
+pub struct Idempotent<H> {
+    inner: Arc<H>,
+    ledger: Arc<dyn ProcessedEventLedger>,
+}
+
+impl<H> Idempotent<H> {
+    pub fn new(inner: Arc<H>, ledger: Arc<dyn ProcessedEventLedger>) -> Self {
+        Self { inner, ledger }
+    }
+}
+
+#[async_trait]
+impl<E, H> Handler<E> for Idempotent<H>
+where
+    E: Send + Sync + 'static,
+    H: Handler<E> + 'static,
+{
+    async fn handle(&self, event: &EventEnvelope<E>) -> Result<(), AppError> {
+        let name = self.inner.name();
+        let first_time =
+            self.ledger.try_mark(name, &event.id).await.map_err(|e| {
+                AppError::RepositoryError("ProcessedEvent".to_string(), e.to_string())
+            })?;
+        if !first_time {
+            debug!("Event {} already processed by {}, skipped", event.id, name);
+            return Ok(());
+        }
+        if let Err(e) = self.inner.handle(event).await {
+            if let Err(unmark_err) = self.ledger.unmark(name, &event.id).await {
+                warn!(
+                    "Failed to unmark event {} for {}: {}",
+                    event.id, name, unmark_err
+                );
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::event_bus::CorrelationId;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryLedger {
+        marked: Mutex<HashSet<(String, EventId)>>,
+    }
+
+    #[async_trait]
+    impl ProcessedEventLedger for MemoryLedger {
+        async fn try_mark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<bool> {
+            Ok(self
+                .marked
+                .lock()
+                .unwrap()
+                .insert((handler.to_string(), event_id.clone())))
+        }
+
+        async fn unmark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<()> {
+            self.marked
+                .lock()
+                .unwrap()
+                .remove(&(handler.to_string(), event_id.clone()));
+            Ok(())
+        }
+    }
+
+    /// 前 failures 次处理失败，之后成功
+    struct Counter {
+        calls: AtomicUsize,
+        failures: usize,
+    }
+
+    #[async_trait]
+    impl Handler<i32> for Counter {
+        async fn handle(&self, _event: &EventEnvelope<i32>) -> Result<(), AppError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                return Err(AppError::UnknownError("boom".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    fn envelope() -> EventEnvelope<i32> {
+        EventEnvelope::new(1, 1, 7, CorrelationId::new(), EventId::new())
+    }
+
+    #[tokio::test]
+    async fn skips_events_already_processed() {
+        let counter = Arc::new(Counter {
+            calls: AtomicUsize::new(0),
+            failures: 0,
+        });
+        let handler = Idempotent::new(counter.clone(), Arc::new(MemoryLedger::default()));
+        let event = envelope();
+
+        handler.handle(&event).await.unwrap();
+        handler.handle(&event).await.unwrap();
+        handler.handle(&envelope()).await.unwrap();
+
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_attempts_can_be_retried() {
+        let counter = Arc::new(Counter {
+            calls: AtomicUsize::new(0),
+            failures: 1,
+        });
+        let handler = Idempotent::new(counter.clone(), Arc::new(MemoryLedger::default()));
+        let event = envelope();
+
+        assert!(handler.handle(&event).await.is_err());
+        handler.handle(&event).await.unwrap();
+        handler.handle(&event).await.unwrap();
+
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::command::listening_session::ListeningSession;
+use domain::value::{AudioFileId, LibraryId, PlayerId, PlaylistId, UserId};
+use model::listening_streak::ListeningMilestone;
+use tokio::sync::broadcast;
+
+/// 推送给在线客户端的服务器事件
+#[derive(Debug, Clone)]
+pub enum ServerEvent {
+    ScanStarted {
+        library_id: LibraryId,
+    },
+    ScanProgress {
+        library_id: LibraryId,
+        processed_files: i64,
+        total_files: i64,
+    },
+    ScanEnded {
+        library_id: LibraryId,
+    },
+    NowPlaying {
+        player_id: PlayerId,
+        user_id: UserId,
+        audio_file_id: AudioFileId,
+    },
+    PlaybackStopped {
+        player_id: PlayerId,
+        audio_file_id: AudioFileId,
+    },
+    /// 只推送给 user_id 对应的用户
+    PlaylistUpdated {
+        playlist_id: PlaylistId,
+        user_id: UserId,
+        deleted: bool,
+    },
+    /// 只推送给达成里程碑的用户，并且需要客户端订阅
+    MilestoneReached(ListeningMilestone),
+    /// 只推送给会话的主持人和听众
+    ListeningSessionUpdated(ListeningSession),
+    ListeningSessionEnded {
+        code: String,
+        members: Vec<UserId>,
+    },
+}
+
+/// 服务器事件广播中心，每个连接持有一个接收端
+///
+/// 接收端跟不上时会丢弃最旧的事件，客户端应在收到 lagged 提示后重新拉取状态
+#[derive(Clone)]
+pub struct ServerEventHub {
+    sender: broadcast::Sender<ServerEvent>,
+}
+
+impl ServerEventHub {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 没有在线客户端时直接丢弃
+    pub fn publish(&self, event: ServerEvent) {
+        let _ = self.sender.send(event);
+    }
+}
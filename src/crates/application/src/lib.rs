@@ -6,6 +6,8 @@ pub mod event;
 pub mod projector;
 pub mod query;
 pub mod shared;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
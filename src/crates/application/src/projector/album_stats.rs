@@ -28,6 +28,7 @@ impl AlbumStatsProjector {
                 size_delta: evt_kind.size,
                 song_count_delta: 1,
                 disk_number: evt_kind.disc_number,
+                disc_subtitle: evt_kind.disc_subtitle.clone(),
                 year: evt_kind.year,
             };
 
@@ -50,12 +51,27 @@ impl AlbumStatsProjector {
                 size_delta: -evt_kind.size,
                 song_count_delta: -1,
                 disk_number: None, // Don't remove disk numbers on unbind
+                disc_subtitle: None,
                 year: None,        // Don't change year on unbind
             };
 
             self.album_stats_repository
                 .adjust_stats(adjustment)
                 .await?;
+
+            // 专辑已没有歌曲（例如被合并或拆空）时删除统计，避免留下孤立的行
+            let album_id = evt_kind.album_id.clone();
+            if let Some(stats) = self
+                .album_stats_repository
+                .find_by_album_id(album_id.clone())
+                .await?
+            {
+                if stats.song_count <= 0 {
+                    self.album_stats_repository
+                        .delete_by_album_id(album_id)
+                        .await?;
+                }
+            }
         }
         Ok(())
     }
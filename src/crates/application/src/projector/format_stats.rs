@@ -0,0 +1,130 @@
+use crate::error::AppError;
+use domain::audio_file::{AudioFileEvent, AudioFileEventKind, AudioFormat};
+use model::format_stats::{bit_rate_bucket, FormatStats, FormatStatsRepository};
+use std::sync::Arc;
+
+/// FormatStatsProjector 根据音频文件的创建、删除和格式变化维护格式分布
+pub struct FormatStatsProjector {
+    format_stats_repository: Arc<dyn FormatStatsRepository>,
+}
+
+impl FormatStatsProjector {
+    pub fn new(format_stats_repository: Arc<dyn FormatStatsRepository>) -> Self {
+        Self {
+            format_stats_repository,
+        }
+    }
+
+    pub async fn on_audio_file_event(&self, event: &AudioFileEvent) -> Result<(), AppError> {
+        match &event.kind {
+            AudioFileEventKind::Created(evt) => self.adjust(&evt.format, 1).await,
+            AudioFileEventKind::Deleted(evt) => self.adjust(&evt.format, -1).await,
+            AudioFileEventKind::Updated(evt) => {
+                if let Some(previous) = &evt.previous_format {
+                    self.adjust(previous, -1).await?;
+                    self.adjust(&evt.format, 1).await?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn adjust(&self, format: &AudioFormat, sign: i64) -> Result<(), AppError> {
+        let entry = FormatStats {
+            suffix: format.suffix.to_lowercase(),
+            bit_rate_bucket: bit_rate_bucket(format.bit_rate),
+            sample_rate: format.sample_rate,
+            song_count: sign,
+            total_size: sign * format.size,
+        };
+        self.format_stats_repository.adjust_stats(entry).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::audio_file::{AudioFileCreated, AudioFileDeleted, AudioFileUpdated};
+    use domain::value::{AudioFileId, LibraryId, MediaPath};
+    use model::ModelError;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryFormatStatsRepository {
+        rows: Mutex<HashMap<(String, i32, i32), (i64, i64)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl FormatStatsRepository for MemoryFormatStatsRepository {
+        async fn adjust_stats(&self, entry: FormatStats) -> Result<(), ModelError> {
+            let mut rows = self.rows.lock().unwrap();
+            let row = rows
+                .entry((entry.suffix, entry.bit_rate_bucket, entry.sample_rate))
+                .or_default();
+            row.0 += entry.song_count;
+            row.1 += entry.total_size;
+            Ok(())
+        }
+    }
+
+    fn format(suffix: &str, bit_rate: i32, size: i64) -> AudioFormat {
+        AudioFormat {
+            suffix: suffix.to_string(),
+            bit_rate,
+            sample_rate: 44100,
+            size,
+        }
+    }
+
+    fn event(kind: AudioFileEventKind) -> AudioFileEvent {
+        AudioFileEvent {
+            audio_file_id: AudioFileId::from(1),
+            version: 1,
+            kind,
+        }
+    }
+
+    fn created(format: AudioFormat) -> AudioFileEvent {
+        event(AudioFileEventKind::Created(AudioFileCreated {
+            library_id: LibraryId::from(1),
+            audio_file_id: AudioFileId::from(1),
+            path: MediaPath {
+                protocol: "local".to_string(),
+                path: "/music/a.mp3".to_string(),
+            },
+            has_cover_art: false,
+            format,
+        }))
+    }
+
+    #[tokio::test]
+    async fn tracks_files_by_suffix_bucket_and_sample_rate() {
+        let repository = Arc::new(MemoryFormatStatsRepository::default());
+        let projector = FormatStatsProjector::new(repository.clone());
+
+        for e in [
+            created(format("MP3", 320, 10)),
+            created(format("mp3", 256, 8)),
+            created(format("flac", 912, 30)),
+            event(AudioFileEventKind::Updated(AudioFileUpdated {
+                library_id: LibraryId::from(1),
+                audio_file_id: AudioFileId::from(2),
+                previous_format: Some(format("mp3", 256, 8)),
+                format: format("mp3", 320, 8),
+            })),
+            event(AudioFileEventKind::Deleted(AudioFileDeleted {
+                format: format("flac", 912, 30),
+            })),
+        ] {
+            projector.on_audio_file_event(&e).await.unwrap();
+        }
+
+        let rows = repository.rows.lock().unwrap();
+        assert_eq!(rows[&("mp3".to_string(), 320, 44100)], (2, 18));
+        assert_eq!(rows[&("mp3".to_string(), 256, 44100)], (0, 0));
+        assert_eq!(rows[&("flac".to_string(), 500, 44100)], (0, 0));
+    }
+}
@@ -40,9 +40,14 @@ impl GenreStatsProjector {
         &self,
         event: &AudioFileEvent,
     ) -> Result<(), AppError> {
-        if let AudioFileEventKind::GenreRemoved(evt_kind) = &event.kind {
+        let genre_id = match &event.kind {
+            AudioFileEventKind::GenreRemoved(evt_kind) => Some(&evt_kind.genre_id),
+            AudioFileEventKind::UnboundFromGenre(evt_kind) => Some(&evt_kind.genre_id),
+            _ => None,
+        };
+        if let Some(genre_id) = genre_id {
             let entry = GenreStats {
-                genre_id: evt_kind.genre_id.clone(),
+                genre_id: genre_id.clone(),
                 song_count: -1, // Decrement by 1
                 album_count: 0,
             };
@@ -0,0 +1,74 @@
+use crate::error::AppError;
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use domain::audio_file::AudioFileRepository;
+use domain::value::{AudioFileId, UserId};
+use model::listening_report::{ListeningClockEntry, ListeningClockRepository};
+use model::play_stats::{PlayCountEntry, PlayStatsRepository, RollupPeriod};
+use std::sync::Arc;
+
+/// ListeningReportProjector 处理歌曲的 scrobble 事件，维护年度报告所需的增量数据：
+/// 收听时钟（次数与时长）以及按流派的播放次数汇总
+pub struct ListeningReportProjector {
+    audio_file_repository: Arc<dyn AudioFileRepository>,
+    listening_clock_repository: Arc<dyn ListeningClockRepository>,
+    play_stats_repository: Arc<dyn PlayStatsRepository>,
+}
+
+impl ListeningReportProjector {
+    pub fn new(
+        audio_file_repository: Arc<dyn AudioFileRepository>,
+        listening_clock_repository: Arc<dyn ListeningClockRepository>,
+        play_stats_repository: Arc<dyn PlayStatsRepository>,
+    ) -> Self {
+        Self {
+            audio_file_repository,
+            listening_clock_repository,
+            play_stats_repository,
+        }
+    }
+
+    pub async fn on_scrobble(
+        &self,
+        user_id: UserId,
+        audio_file_id: AudioFileId,
+        played_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        let Some(audio_file) = self
+            .audio_file_repository
+            .find_by_id(&audio_file_id)
+            .await?
+        else {
+            return Ok(());
+        };
+        let played_at = super::local_time(played_at);
+        self.listening_clock_repository
+            .record(ListeningClockEntry {
+                user_id: user_id.clone(),
+                year: played_at.year(),
+                weekday: played_at.weekday().num_days_from_monday() as i16,
+                hour: played_at.hour() as i16,
+                play_seconds: audio_file.duration.max(0),
+            })
+            .await?;
+
+        let mut genres = audio_file.genres.clone();
+        if genres.is_empty() {
+            genres.extend(audio_file.genre.clone());
+        }
+        let played_on = played_at.date();
+        for genre_id in genres {
+            for period in RollupPeriod::ALL {
+                self.play_stats_repository
+                    .increment(PlayCountEntry {
+                        period,
+                        period_start: period.start_of(played_on),
+                        user_id: user_id.clone(),
+                        item_kind: "genre".to_string(),
+                        item_id: genre_id.as_i64(),
+                    })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
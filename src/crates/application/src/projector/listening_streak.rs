@@ -0,0 +1,205 @@
+use crate::error::AppError;
+use chrono::NaiveDateTime;
+use domain::value::UserId;
+use model::listening_streak::{
+    ListeningMilestone, ListeningStreak, ListeningStreakRepository, MilestoneKind, PLAY_MILESTONES,
+    STREAK_MILESTONES,
+};
+use std::sync::Arc;
+
+/// ListeningStreakProjector 根据 scrobble 事件维护连续收听天数和里程碑
+pub struct ListeningStreakProjector {
+    listening_streak_repository: Arc<dyn ListeningStreakRepository>,
+}
+
+impl ListeningStreakProjector {
+    pub fn new(listening_streak_repository: Arc<dyn ListeningStreakRepository>) -> Self {
+        Self {
+            listening_streak_repository,
+        }
+    }
+
+    /// 歌曲的 scrobble 计入连续天数和总播放次数，艺术家的 scrobble 用于判断第一次播放。
+    /// 返回这次新达成的里程碑
+    pub async fn on_scrobble(
+        &self,
+        user_id: UserId,
+        item_type: &str,
+        item_id: i64,
+        played_at: NaiveDateTime,
+    ) -> Result<Vec<ListeningMilestone>, AppError> {
+        let mut candidates = Vec::new();
+        match item_type {
+            "audio_file" => {
+                let mut streak = self
+                    .listening_streak_repository
+                    .find(&user_id)
+                    .await?
+                    .unwrap_or_else(|| ListeningStreak::new(user_id.clone()));
+                let extended = streak.record_play(super::local_time(played_at).date());
+                self.listening_streak_repository.save(&streak).await?;
+
+                if PLAY_MILESTONES.contains(&streak.total_plays) {
+                    candidates.push((MilestoneKind::Plays, streak.total_plays));
+                }
+                let days = i64::from(streak.current_streak);
+                if extended && STREAK_MILESTONES.contains(&days) {
+                    candidates.push((MilestoneKind::Streak, days));
+                }
+            }
+            "artist" => candidates.push((MilestoneKind::FirstArtistPlay, item_id)),
+            _ => {}
+        }
+
+        let mut reached = Vec::new();
+        for (kind, value) in candidates {
+            let milestone = ListeningMilestone {
+                user_id: user_id.clone(),
+                kind,
+                value,
+                achieved_at: played_at,
+            };
+            if self
+                .listening_streak_repository
+                .add_milestone(&milestone)
+                .await?
+            {
+                reached.push(milestone);
+            }
+        }
+        Ok(reached)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, NaiveDate, TimeZone};
+    use model::ModelError;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryListeningStreakRepository {
+        streaks: Mutex<HashMap<i64, ListeningStreak>>,
+        milestones: Mutex<Vec<ListeningMilestone>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ListeningStreakRepository for MemoryListeningStreakRepository {
+        async fn find(&self, user_id: &UserId) -> Result<Option<ListeningStreak>, ModelError> {
+            Ok(self.streaks.lock().unwrap().get(&user_id.as_i64()).cloned())
+        }
+
+        async fn save(&self, streak: &ListeningStreak) -> Result<(), ModelError> {
+            self.streaks
+                .lock()
+                .unwrap()
+                .insert(streak.user_id.as_i64(), streak.clone());
+            Ok(())
+        }
+
+        async fn add_milestone(&self, milestone: &ListeningMilestone) -> Result<bool, ModelError> {
+            let mut milestones = self.milestones.lock().unwrap();
+            if milestones.iter().any(|m| {
+                m.user_id == milestone.user_id
+                    && m.kind == milestone.kind
+                    && m.value == milestone.value
+            }) {
+                return Ok(false);
+            }
+            milestones.push(milestone.clone());
+            Ok(true)
+        }
+    }
+
+    /// 本地时间中午对应的 UTC 时间，避免跨日
+    fn noon(day: u32) -> NaiveDateTime {
+        Local
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2026, 3, day)
+                    .unwrap()
+                    .and_hms_opt(12, 0, 0)
+                    .unwrap(),
+            )
+            .unwrap()
+            .naive_utc()
+    }
+
+    #[tokio::test]
+    async fn counts_consecutive_days_and_resets_after_a_gap() {
+        let repository = Arc::new(MemoryListeningStreakRepository::default());
+        let projector = ListeningStreakProjector::new(repository.clone());
+        let user_id = UserId::from(1);
+
+        for day in [1, 1, 2, 3, 5, 6] {
+            projector
+                .on_scrobble(user_id.clone(), "audio_file", 10, noon(day))
+                .await
+                .unwrap();
+        }
+        // 离线提交的早期播放不影响连续天数
+        projector
+            .on_scrobble(user_id.clone(), "audio_file", 10, noon(4))
+            .await
+            .unwrap();
+
+        let streak = repository.find(&user_id).await.unwrap().unwrap();
+        assert_eq!(streak.current_streak, 2);
+        assert_eq!(streak.longest_streak, 3);
+        assert_eq!(streak.total_plays, 7);
+        assert_eq!(streak.last_played_on, NaiveDate::from_ymd_opt(2026, 3, 6));
+        assert_eq!(
+            streak.current_on(NaiveDate::from_ymd_opt(2026, 3, 7).unwrap()),
+            2
+        );
+        assert_eq!(
+            streak.current_on(NaiveDate::from_ymd_opt(2026, 3, 8).unwrap()),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_each_milestone_once() {
+        let repository = Arc::new(MemoryListeningStreakRepository::default());
+        let projector = ListeningStreakProjector::new(repository.clone());
+        let user_id = UserId::from(1);
+
+        let mut reached = Vec::new();
+        for day in 1..=7 {
+            reached.extend(
+                projector
+                    .on_scrobble(user_id.clone(), "audio_file", 10, noon(day))
+                    .await
+                    .unwrap(),
+            );
+        }
+        for _ in 0..2 {
+            reached.extend(
+                projector
+                    .on_scrobble(user_id.clone(), "artist", 42, noon(7))
+                    .await
+                    .unwrap(),
+            );
+        }
+        for _ in 7..100 {
+            reached.extend(
+                projector
+                    .on_scrobble(user_id.clone(), "audio_file", 10, noon(7))
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let reached: Vec<(MilestoneKind, i64)> =
+            reached.iter().map(|m| (m.kind, m.value)).collect();
+        assert_eq!(
+            reached,
+            [
+                (MilestoneKind::Streak, 7),
+                (MilestoneKind::FirstArtistPlay, 42),
+                (MilestoneKind::Plays, 100),
+            ]
+        );
+    }
+}
@@ -1,7 +1,21 @@
 pub mod album_location;
 pub mod album_stats;
 pub mod artist_location;
+pub mod format_stats;
 pub mod genre_stats;
+pub mod listening_report;
+pub mod listening_streak;
 pub mod participant_stats;
 pub mod playback_history;
+pub mod play_stats;
 pub mod scan_status;
+pub mod star_stats;
+
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+
+/// 事件中的时间是 UTC，播放统计按服务器本地时间归档
+pub(crate) fn local_time(utc: NaiveDateTime) -> NaiveDateTime {
+    Utc.from_utc_datetime(&utc)
+        .with_timezone(&Local)
+        .naive_local()
+}
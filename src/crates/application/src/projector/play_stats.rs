@@ -0,0 +1,41 @@
+use crate::error::AppError;
+use chrono::NaiveDateTime;
+use domain::value::UserId;
+use model::play_stats::{PlayCountEntry, PlayStatsRepository, RollupPeriod};
+use std::sync::Arc;
+
+/// PlayStatsProjector 处理 scrobble 事件，按日/周/月汇总各条目的播放次数
+pub struct PlayStatsProjector {
+    play_stats_repository: Arc<dyn PlayStatsRepository>,
+}
+
+impl PlayStatsProjector {
+    pub fn new(play_stats_repository: Arc<dyn PlayStatsRepository>) -> Self {
+        Self {
+            play_stats_repository,
+        }
+    }
+
+    pub async fn on_scrobble(
+        &self,
+        user_id: UserId,
+        item_kind: &str,
+        item_id: i64,
+        played_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        // 与播放历史一致，按服务器本地日期归档
+        let played_on = super::local_time(played_at).date();
+        for period in RollupPeriod::ALL {
+            self.play_stats_repository
+                .increment(PlayCountEntry {
+                    period,
+                    period_start: period.start_of(played_on),
+                    user_id: user_id.clone(),
+                    item_kind: item_kind.to_string(),
+                    item_id,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+}
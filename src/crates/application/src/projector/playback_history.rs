@@ -1,5 +1,5 @@
 use crate::error::AppError;
-use chrono::Local;
+use chrono::NaiveDateTime;
 use domain::value::{AudioFileId, UserId};
 use model::playback_history::{PlaybackHistoryEntry, PlaybackHistoryRepository};
 use std::sync::Arc;
@@ -14,11 +14,16 @@ impl PlaybackHistoryProjector {
     }
 
     /// 处理播放开始事件
-    pub async fn on_scrobble(&self, item_id: AudioFileId, user_id: UserId) -> Result<(), AppError> {
+    pub async fn on_scrobble(
+        &self,
+        item_id: AudioFileId,
+        user_id: UserId,
+        played_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
         let entry = PlaybackHistoryEntry {
             user_id: user_id.clone(),
             audio_file_id: item_id.clone(),
-            scrobbled_at: Local::now().naive_local(),
+            scrobbled_at: super::local_time(played_at),
         };
         self.repository.save(&entry).await?;
         Ok(())
@@ -1,7 +1,10 @@
+use crate::command::shared::Clock;
 use crate::error::AppError;
+use chrono::{Local, NaiveDateTime};
 use domain::audio_file::AudioFileEvent;
 use domain::audio_file::AudioFileEventKind;
-use domain::library::{ScanEnded, ScanStarted};
+use domain::library::{FileAdded, FileUpdated, ScanEnded, ScanStarted};
+use domain::value::{FileType, LibraryId};
 use model::scan_status::{ScanStatus, ScanStatusRepository};
 use std::sync::Arc;
 
@@ -11,16 +14,49 @@ pub trait ScanStatusProjector: Send + Sync {
     async fn on_audio_file_event(&self, event: &AudioFileEvent) -> Result<(), AppError>;
     async fn on_scan_started(&self, event: &ScanStarted) -> Result<(), AppError>;
     async fn on_scan_ended(&self, event: &ScanEnded) -> Result<(), AppError>;
+    async fn on_file_added(&self, event: &FileAdded) -> Result<(), AppError>;
+    /// 修改过的音频文件会重新解析，与新文件一样计入进度
+    async fn on_file_updated(&self, event: &FileUpdated) -> Result<(), AppError>;
+    async fn on_audio_file_parsed(&self, library_id: &LibraryId) -> Result<(), AppError>;
+    async fn on_audio_file_parse_failed(&self, library_id: &LibraryId) -> Result<(), AppError>;
 }
 
 /// ScanStatusProjectorImpl 扫描状态投影器实现
 pub struct ScanStatusProjectorImpl {
     repository: Arc<dyn ScanStatusRepository + Send + Sync>,
+    clock: Arc<dyn Clock>,
 }
 
 impl ScanStatusProjectorImpl {
-    pub fn new(repository: Arc<dyn ScanStatusRepository + Send + Sync>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<dyn ScanStatusRepository + Send + Sync>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self { repository, clock }
+    }
+
+    /// 扫描状态中的时间是本地时间
+    fn now(&self) -> NaiveDateTime {
+        self.clock.now().with_timezone(&Local).naive_local()
+    }
+
+    /// 读取库的扫描状态并更新，不存在时先初始化
+    async fn update<F>(&self, library_id: &LibraryId, f: F) -> Result<(), AppError>
+    where
+        F: FnOnce(&mut ScanStatus, NaiveDateTime) + Send,
+    {
+        let now = self.now();
+        let mut status = match self.repository.get_scan_status(library_id).await {
+            Ok(Some(status)) => status,
+            _ => {
+                let mut status = ScanStatus::new(library_id.clone());
+                status.start_scanning(0, now);
+                status
+            }
+        };
+        f(&mut status, now);
+        self.repository.save(&status).await?;
+        Ok(())
     }
 }
 
@@ -30,18 +66,17 @@ impl ScanStatusProjector for ScanStatusProjectorImpl {
         match &event.kind {
             AudioFileEventKind::Created(created) => {
                 // 文件创建时增加处理计数
-                if let Ok(Some(mut status)) =
-                    self.repository.get_scan_status(&created.library_id).await
-                {
-                    status.increment_processed();
-                    self.repository.save(&status).await?;
-                } else {
-                    // Initialize if missing
-                    let mut status = ScanStatus::new(created.library_id.clone());
-                    status.start_scanning(0);
-                    status.increment_processed();
-                    self.repository.save(&status).await?;
-                }
+                self.update(&created.library_id, |status, now| {
+                    status.increment_processed(now)
+                })
+                .await?;
+            }
+            AudioFileEventKind::Updated(updated) => {
+                // 重新解析的文件更新后同样计为已处理
+                self.update(&updated.library_id, |status, now| {
+                    status.increment_processed(now)
+                })
+                .await?;
             }
             _ => {}
         }
@@ -50,16 +85,199 @@ impl ScanStatusProjector for ScanStatusProjectorImpl {
 
     async fn on_scan_started(&self, event: &ScanStarted) -> Result<(), AppError> {
         let mut status = ScanStatus::new(event.library_id.clone());
-        status.start_scanning(0); // 初始化为0，后续会根据实际文件数量更新
+        status.start_scanning(0, self.now()); // 初始化为0，遍历过程中累加
         self.repository.save(&status).await?;
         Ok(())
     }
 
     async fn on_scan_ended(&self, event: &ScanEnded) -> Result<(), AppError> {
+        // ScanEnded 只表示文件遍历结束，解析和投影可能仍在进行
         if let Ok(Some(mut status)) = self.repository.get_scan_status(&event.library_id).await {
-            status.finish_scanning();
+            status.finish_enumeration(self.now());
             self.repository.save(&status).await?;
         }
         Ok(())
     }
+
+    async fn on_file_added(&self, event: &FileAdded) -> Result<(), AppError> {
+        if event.item.file_type != FileType::Audio {
+            return Ok(());
+        }
+        self.update(&event.library_id, |status, now| {
+            status.increment_enumerated(now)
+        })
+        .await
+    }
+
+    async fn on_file_updated(&self, event: &FileUpdated) -> Result<(), AppError> {
+        if event.item.file_type != FileType::Audio {
+            return Ok(());
+        }
+        self.update(&event.library_id, |status, now| {
+            status.increment_enumerated(now)
+        })
+        .await
+    }
+
+    async fn on_audio_file_parsed(&self, library_id: &LibraryId) -> Result<(), AppError> {
+        self.update(library_id, |status, now| status.increment_parsed(now))
+            .await
+    }
+
+    async fn on_audio_file_parse_failed(&self, library_id: &LibraryId) -> Result<(), AppError> {
+        self.update(library_id, |status, now| status.increment_error(now))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shared::FakeClock;
+    use chrono::{Duration, TimeZone, Utc};
+    use domain::audio_file::{AudioFileUpdated, AudioFormat};
+    use domain::library::LibraryItem;
+    use domain::value::{AudioFileId, FileMeta, LibraryItemId, MediaPath};
+    use model::ModelError;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryScanStatusRepository {
+        statuses: Mutex<HashMap<LibraryId, ScanStatus>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ScanStatusRepository for MemoryScanStatusRepository {
+        async fn get_scan_status(
+            &self,
+            library_id: &LibraryId,
+        ) -> Result<Option<ScanStatus>, ModelError> {
+            Ok(self.statuses.lock().unwrap().get(library_id).cloned())
+        }
+
+        async fn get_all_scan_statuses(
+            &self,
+        ) -> Result<HashMap<LibraryId, ScanStatus>, ModelError> {
+            Ok(self.statuses.lock().unwrap().clone())
+        }
+
+        async fn save(&self, status: &ScanStatus) -> Result<(), ModelError> {
+            self.statuses
+                .lock()
+                .unwrap()
+                .insert(status.library_id.clone(), status.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn records_scan_times_from_clock() {
+        let repository = Arc::new(MemoryScanStatusRepository::default());
+        let clock = Arc::new(FakeClock::new(
+            Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+        ));
+        let projector = ScanStatusProjectorImpl::new(repository.clone(), clock.clone());
+        let library_id = LibraryId::from(1);
+
+        projector
+            .on_scan_started(&ScanStarted {
+                library_id: library_id.clone(),
+                version: 1,
+            })
+            .await
+            .unwrap();
+        clock.advance(Duration::minutes(5));
+        projector
+            .on_scan_ended(&ScanEnded {
+                library_id: library_id.clone(),
+                version: 2,
+            })
+            .await
+            .unwrap();
+
+        let status = repository
+            .get_scan_status(&library_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!status.scanning);
+        assert_eq!(
+            status.finished_at.unwrap() - status.started_at.unwrap(),
+            Duration::minutes(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_reparsed_files_in_progress() {
+        let repository = Arc::new(MemoryScanStatusRepository::default());
+        let clock = Arc::new(FakeClock::new(
+            Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+        ));
+        let projector = ScanStatusProjectorImpl::new(repository.clone(), clock);
+        let library_id = LibraryId::from(1);
+        let now = Utc::now().naive_utc();
+        let path = MediaPath {
+            protocol: "local".to_string(),
+            path: "/music/a.mp3".to_string(),
+        };
+        let file = FileMeta {
+            path: path.clone(),
+            dir_path: path,
+            size: 10,
+            suffix: "mp3".to_string(),
+            mtime: now,
+            atime: now,
+            ctime: now,
+            hash: None,
+        };
+        let format = AudioFormat {
+            suffix: "mp3".to_string(),
+            bit_rate: 320,
+            sample_rate: 44100,
+            size: 10,
+        };
+
+        projector
+            .on_scan_started(&ScanStarted {
+                library_id: library_id.clone(),
+                version: 1,
+            })
+            .await
+            .unwrap();
+        projector
+            .on_file_updated(&FileUpdated {
+                library_id: library_id.clone(),
+                version: 2,
+                item: LibraryItem::new(
+                    LibraryItemId::from(1),
+                    library_id.clone(),
+                    file,
+                    FileType::Audio,
+                ),
+            })
+            .await
+            .unwrap();
+        projector
+            .on_audio_file_event(&AudioFileEvent {
+                audio_file_id: AudioFileId::from(1),
+                version: 2,
+                kind: AudioFileEventKind::Updated(AudioFileUpdated {
+                    library_id: library_id.clone(),
+                    audio_file_id: AudioFileId::from(1),
+                    previous_format: None,
+                    format,
+                }),
+            })
+            .await
+            .unwrap();
+
+        let status = repository
+            .get_scan_status(&library_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.enumerated_files, 1);
+        assert_eq!(status.processed_files, 1);
+    }
 }
@@ -0,0 +1,37 @@
+use crate::error::AppError;
+use domain::annotation::AnnotationEvent;
+use model::star_stats::{StarStats, StarStatsRepository};
+use std::sync::Arc;
+
+/// StarStatsProjector 处理收藏/取消收藏事件，统计各条目被收藏的人数
+pub struct StarStatsProjector {
+    star_stats_repository: Arc<dyn StarStatsRepository>,
+}
+
+impl StarStatsProjector {
+    pub fn new(star_stats_repository: Arc<dyn StarStatsRepository>) -> Self {
+        Self {
+            star_stats_repository,
+        }
+    }
+
+    pub async fn on_star_changed(&self, event: &AnnotationEvent) -> Result<(), AppError> {
+        let (item_id, item_type, delta) = match event {
+            AnnotationEvent::ItemStarred {
+                item_id, item_type, ..
+            } => (*item_id, item_type, 1),
+            AnnotationEvent::ItemUnstarred {
+                item_id, item_type, ..
+            } => (*item_id, item_type, -1),
+            _ => return Ok(()),
+        };
+        self.star_stats_repository
+            .adjust_stats(StarStats {
+                item_kind: item_type.clone(),
+                item_id,
+                star_count: delta,
+            })
+            .await?;
+        Ok(())
+    }
+}
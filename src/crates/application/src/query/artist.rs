@@ -3,15 +3,17 @@ use super::dto::artist::{ArtistIndex, ArtistIndexWithTokens, ArtistWithToken};
 use super::dto::cover_art;
 use super::shared::CoverArtTokenService;
 use super::QueryError;
+use crate::command::artist::ArtistNameNormalizer;
 use lazy_static::lazy_static;
-use log::info;
 use model::artist::Artist;
-use pinyin::ToPinyin;
 use regex::Regex;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-type IndexGroups = HashMap<String, String>;
+/// (小写前缀, 分组名)，按前缀长度降序排列，保证最长前缀优先匹配
+type IndexGroups = Vec<(String, String)>;
+
+const OTHER_INDEX: &str = "#";
 
 lazy_static! {
     static ref INDEX_GROUPS_RX: Regex = Regex::new(r"(.+)\((.+)\)").unwrap();
@@ -20,6 +22,9 @@ lazy_static! {
 pub struct ArtistIndexRule {
     index_groups: IndexGroups,
     prefer_sort_tags: bool,
+    normalizer: Arc<dyn ArtistNameNormalizer>,
+    /// 非拉丁文字（中日韩等）按罗马字首字母分组，否则归入 "#"
+    romanize: bool,
 }
 
 pub struct ArtistService<T>
@@ -33,26 +38,87 @@ where
 
 impl ArtistIndexRule {
     fn parse_index_groups(specs: &str) -> IndexGroups {
-        let mut parsed = HashMap::new();
+        let mut parsed: HashMap<String, String> = HashMap::new();
         for g in specs.split_whitespace() {
             if let Some(caps) = INDEX_GROUPS_RX.captures(g) {
                 let group = caps.get(1).unwrap().as_str();
                 let chars = caps.get(2).unwrap().as_str();
                 for c in chars.chars() {
-                    parsed.insert(c.to_string(), group.to_string());
+                    parsed
+                        .entry(c.to_lowercase().to_string())
+                        .or_insert_with(|| group.to_string());
                 }
             } else {
                 // 如果正则表达式不匹配，将该组作为键和值都添加
-                parsed.insert(g.to_string(), g.to_string());
+                parsed
+                    .entry(g.to_lowercase())
+                    .or_insert_with(|| g.to_string());
             }
         }
-        parsed
+        let mut groups: IndexGroups = parsed.into_iter().collect();
+        groups.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        groups
     }
-    pub fn new(specs: &str, prefer_sort_tags: bool) -> Self {
+
+    pub fn new(
+        specs: &str,
+        prefer_sort_tags: bool,
+        normalizer: Arc<dyn ArtistNameNormalizer>,
+        romanize: bool,
+    ) -> Self {
         Self {
             index_groups: Self::parse_index_groups(specs),
             prefer_sort_tags,
+            normalizer,
+            romanize,
+        }
+    }
+
+    /// 艺术家所属的索引分组：先按配置的前缀匹配去掉冠词后的名称，
+    /// 再按首字母匹配，都不匹配时归入 "#"
+    pub fn index_key(&self, artist: &Artist) -> String {
+        let source = if self.prefer_sort_tags && !artist.sort_name.is_empty() {
+            &artist.sort_name
+        } else {
+            &artist.name
+        };
+        let name = self.normalizer.index_name(source);
+        if let Some((_, group)) = self
+            .index_groups
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix.as_str()))
+        {
+            return group.clone();
+        }
+        self.normalizer
+            .index_initial(&name, self.romanize)
+            .and_then(|initial| {
+                let key = initial.to_ascii_lowercase().to_string();
+                self.index_groups
+                    .iter()
+                    .find(|(prefix, _)| *prefix == key)
+                    .map(|(_, group)| group.clone())
+            })
+            .unwrap_or_else(|| OTHER_INDEX.to_string())
+    }
+
+    /// 按索引分组，分组按 ID 排序，组内按规范化后的名称排序
+    fn group(&self, artists: Vec<Artist>) -> Vec<(String, Vec<Artist>)> {
+        let mut index: HashMap<String, Vec<(String, Artist)>> = HashMap::new();
+        for artist in artists {
+            let key = self.index_key(&artist);
+            let order = self.normalizer.normalize(&artist.name);
+            index.entry(key).or_default().push((order, artist));
         }
+        let mut groups: Vec<(String, Vec<Artist>)> = index
+            .into_iter()
+            .map(|(id, mut artists)| {
+                artists.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.id.cmp(&b.1.id)));
+                (id, artists.into_iter().map(|(_, artist)| artist).collect())
+            })
+            .collect();
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        groups
     }
 }
 
@@ -80,23 +146,13 @@ where
         }
     }
     pub async fn get_indexes(&self) -> Result<Vec<ArtistIndex>, QueryError> {
-        let dao = self.artist_dao.clone();
-        let artists = dao.get_all().await?;
-        // group by index key
-        let mut index = HashMap::new();
-        for artist in artists {
-            let key = self.get_index_key(&artist);
-            index.entry(key).or_insert_with(Vec::new).push(artist);
-        }
-
-        // convert to Vec and sort
-        let mut result: Vec<ArtistIndex> = index
+        let artists = self.artist_dao.get_all().await?;
+        Ok(self
+            .index_rule
+            .group(artists)
             .into_iter()
             .map(|(id, artists)| ArtistIndex { id, artists })
-            .collect();
-
-        result.sort_by(|a, b| a.id.cmp(&b.id));
-        Ok(result)
+            .collect())
     }
 
     /// 获取索引列表（带 token）
@@ -105,98 +161,38 @@ where
             .token_service
             .as_ref()
             .ok_or_else(|| QueryError::InvalidInput("Token service not available".to_string()))?;
-
-        let dao = self.artist_dao.clone();
-        let artists = dao.get_all().await?;
-
-        // group by index key
-        let mut index = HashMap::new();
-        for artist in artists {
-            let key = self.get_index_key(&artist);
-            index.entry(key).or_insert_with(Vec::new).push(artist);
-        }
-
-        // convert to Vec with tokens and sort
-        let mut result: Vec<ArtistIndexWithTokens> = index
-            .into_iter()
-            .map(|(id, artists)| {
-                let artists_with_tokens: Vec<ArtistWithToken> = artists
-                    .into_iter()
-                    .map(|artist| {
-                        let cover_art_id = cover_art::artist_cover_art_id(artist.id);
-                        let cover_art_token = token_service
-                            .issue_cover_art_token(cover_art_id.clone())
-                            .unwrap_or_default();
-                        ArtistWithToken {
-                            artist,
-                            cover_art_id,
-                            cover_art_token,
-                        }
-                    })
-                    .collect();
-                ArtistIndexWithTokens {
-                    id,
-                    artists: artists_with_tokens,
-                }
-            })
-            .collect();
-
-        result.sort_by(|a, b| a.id.cmp(&b.id));
-        Ok(result)
+        let artists = self.artist_dao.get_all().await?;
+        Ok(self.with_tokens(token_service.as_ref(), artists))
     }
-    pub async fn get_artists(&self) -> Result<Vec<ArtistIndex>, QueryError> {
-        info!("xx get_artists");
-        let dao = self.artist_dao.clone();
-        let artists = dao.get_all().await?;
-        info!("xx get_artists artists: {}", artists.len());
-        // group by index key
-        let mut index = HashMap::new();
-        for artist in artists {
-            let key = self.get_index_key(&artist);
-            index.entry(key).or_insert_with(Vec::new).push(artist);
-        }
 
-        // convert to Vec and sort
-        let mut result: Vec<ArtistIndex> = index
-            .into_iter()
-            .map(|(id, artists)| ArtistIndex { id, artists })
-            .collect();
-
-        result.sort_by(|a, b| a.id.cmp(&b.id));
-        Ok(result)
+    pub async fn get_artists(&self) -> Result<Vec<ArtistIndex>, QueryError> {
+        self.get_indexes().await
     }
 
     /// 获取艺术家列表（带 token）
     pub async fn get_artists_with_tokens(&self) -> Result<Vec<ArtistIndexWithTokens>, QueryError> {
-        let token_service = self
-            .token_service
-            .as_ref()
-            .ok_or_else(|| QueryError::InvalidInput("Token service not available".to_string()))?;
-
-        let dao = self.artist_dao.clone();
-        let artists = dao.get_all().await?;
-
-        // group by index key
-        let mut index = HashMap::new();
-        for artist in artists {
-            let key = self.get_index_key(&artist);
-            index.entry(key).or_insert_with(Vec::new).push(artist);
-        }
+        self.get_indexes_with_tokens().await
+    }
 
-        // convert to Vec with tokens and sort
-        let mut result: Vec<ArtistIndexWithTokens> = index
+    fn with_tokens(
+        &self,
+        token_service: &dyn CoverArtTokenService,
+        artists: Vec<Artist>,
+    ) -> Vec<ArtistIndexWithTokens> {
+        self.index_rule
+            .group(artists)
             .into_iter()
             .map(|(id, artists)| {
                 let artists_with_tokens: Vec<ArtistWithToken> = artists
                     .into_iter()
                     .map(|artist| {
-                        let artist_cover_id = cover_art::artist_cover_art_id(artist.id);
+                        let cover_art_id = cover_art::artist_cover_art_id(artist.id);
                         let cover_art_token = token_service
-                            .issue_cover_art_token(artist_cover_id.clone())
+                            .issue_cover_art_token(cover_art_id.clone())
                             .unwrap_or_default();
                         ArtistWithToken {
                             artist,
-                            cover_art_id: artist_cover_id,
+                            cover_art_id,
                             cover_art_token,
                         }
                     })
@@ -206,41 +202,6 @@ where
                     artists: artists_with_tokens,
                 }
             })
-            .collect();
-
-        result.sort_by(|a, b| a.id.cmp(&b.id));
-        Ok(result)
-    }
-    fn get_index_key(&self, artist: &Artist) -> String {
-        let source = if self.index_rule.prefer_sort_tags && artist.sort_name.len() > 0 {
-            &artist.sort_name
-        } else {
-            &artist.order_name
-        };
-
-        let name = source.to_lowercase();
-
-        // 先检查非中文部分
-        for (k, v) in &self.index_rule.index_groups {
-            if name.starts_with(&k.to_lowercase()) {
-                return v.clone();
-            }
-        }
-
-        // 如果没有匹配的非中文索引，再检查中文部分
-        if name.chars().any(|c| c >= '\u{4e00}' && c <= '\u{9fff}') {
-            if let Some(first_char) = name.chars().next() {
-                if let Some(pinyin) = first_char.to_pinyin() {
-                    // 使用不带声调的拼音，取首字母
-                    let pinyin_str = pinyin.plain();
-                    if let Some(first_pinyin_char) = pinyin_str.chars().next() {
-                        let result = first_pinyin_char.to_uppercase().to_string();
-                        return result;
-                    }
-                }
-            }
-        }
-
-        "#".to_string()
+            .collect()
     }
 }
@@ -1,12 +1,24 @@
 use crate::query::QueryError;
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use model::album::{Album, AlbumInfo};
+use model::annotation::UserAnnotation;
 use model::artist::{Artist, ArtistInfo};
 use model::audio_file::AudioFile;
+use model::bookmark::Bookmark;
+use model::feed::FeedItem;
 use model::genre::Genre;
+use model::library_stats::{FormatCount, LibraryTotals, MonthlyAdditions, ValueCount};
+use model::listening_report::ListeningClockCell;
+use model::listening_streak::{AchievedMilestone, ListeningStreak};
+use model::media_asset::MediaAsset;
 use model::music_folder::MusicFolder;
 use model::play_queue::PlayQueue;
+use model::playback_history::PlaybackHistoryItem;
+use model::play_stats::{ChartEntry, RollupPeriod};
 use model::playlist::{Playlist, PlaylistSummary};
+use model::user_note::UserNote;
+use std::collections::HashMap;
 
 #[async_trait]
 pub trait MusicFolderDao {
@@ -17,6 +29,8 @@ pub trait MusicFolderDao {
 #[async_trait]
 pub trait ArtistDao {
     async fn get_by_id(&self, id: i64) -> Result<Option<Artist>, QueryError>;
+    /// 按 ID 批量查询，不存在的 ID 被忽略，结果不保证与输入顺序一致
+    async fn get_by_ids(&self, ids: &[i64]) -> Result<Vec<Artist>, QueryError>;
     async fn get_all(&self) -> Result<Vec<Artist>, QueryError>;
     async fn get_artist_info(&self, artist_id: i64) -> Result<Option<ArtistInfo>, QueryError>;
     /// 根据 sort_name 查询艺术家（优先匹配 sort_name，如果没有则匹配 name）
@@ -49,6 +63,8 @@ pub trait GenreDao {
 #[async_trait]
 pub trait AlbumDao {
     async fn get_by_id(&self, id: i64) -> Result<Option<Album>, QueryError>;
+    /// 按 ID 批量查询，不存在的 ID 被忽略，结果不保证与输入顺序一致
+    async fn get_by_ids(&self, ids: &[i64]) -> Result<Vec<Album>, QueryError>;
     async fn get_by_artist_id(&self, artist_id: i64) -> Result<Vec<Album>, QueryError>;
     async fn get_all(&self) -> Result<Vec<Album>, QueryError>;
     async fn get_album_info(&self, album_id: i64) -> Result<Option<AlbumInfo>, QueryError>;
@@ -81,6 +97,12 @@ pub trait AlbumDao {
     ) -> Result<(Vec<Album>, i64), QueryError>;
     /// 查询所有已收藏的专辑列表（无分页，按用户 ID 过滤）
     async fn get_starred(&self, user_id: i64) -> Result<Vec<Album>, QueryError>;
+    /// 按收藏人数降序的专辑列表
+    async fn get_by_most_starred(
+        &self,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<Album>, i64), QueryError>;
     /// 查询高评分专辑列表（按评分降序）
     async fn get_by_rating(&self, offset: i32, limit: i32)
         -> Result<(Vec<Album>, i64), QueryError>;
@@ -91,6 +113,12 @@ pub trait AlbumDao {
         offset: i32,
         limit: i32,
     ) -> Result<(Vec<Album>, i64), QueryError>;
+    /// 按原始发行年份排序的专辑列表，没有原始年份的按发行年份
+    async fn get_by_original_year(
+        &self,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<Album>, i64), QueryError>;
     /// 根据年份范围查询专辑列表
     async fn get_by_year(
         &self,
@@ -111,7 +139,11 @@ pub trait AlbumDao {
 #[async_trait]
 pub trait AudioFileDao {
     async fn get_by_id(&self, id: i64) -> Result<Option<AudioFile>, QueryError>;
+    /// 按 ID 批量查询，不存在的 ID 被忽略，结果不保证与输入顺序一致
+    async fn get_by_ids(&self, ids: &[i64]) -> Result<Vec<AudioFile>, QueryError>;
     async fn get_by_album_id(&self, album_id: i64) -> Result<Vec<AudioFile>, QueryError>;
+    /// 一次查询多个专辑的歌曲
+    async fn get_by_album_ids(&self, album_ids: &[i64]) -> Result<Vec<AudioFile>, QueryError>;
     async fn get_by_artist_id(&self, artist_id: i64) -> Result<Vec<AudioFile>, QueryError>;
     async fn get_all(&self) -> Result<Vec<AudioFile>, QueryError>;
     /// 根据艺术家 ID 查询 top songs（按播放次数排序）
@@ -154,18 +186,187 @@ pub trait AudioFileDao {
     async fn get_recently_played(&self, limit: i32) -> Result<Vec<AudioFile>, QueryError>;
 }
 
+#[async_trait]
+pub trait BookmarkDao {
+    /// 用户的全部书签，最近更新的在前
+    async fn get_by_user(&self, user_id: i64) -> Result<Vec<Bookmark>, QueryError>;
+    /// 用户在已启用的有声书库中的书签，最近更新的在前
+    async fn get_audiobook_bookmarks(&self, user_id: i64) -> Result<Vec<Bookmark>, QueryError>;
+}
+
+#[async_trait]
+pub trait MediaAssetDao {
+    async fn get_by_id(&self, id: i64) -> Result<Option<MediaAsset>, QueryError>;
+    /// 专辑所在目录及其子目录中的资源，按路径排序
+    async fn get_by_album(&self, album_id: i64) -> Result<Vec<MediaAsset>, QueryError>;
+    /// 艺术家专辑目录及其上一级目录中的艺术家图片和标志，上一级目录的排在前面
+    async fn get_by_artist(&self, artist_id: i64) -> Result<Vec<MediaAsset>, QueryError>;
+}
+
 #[async_trait]
 pub trait PlaylistDao {
     /// 根据 ID 获取播放列表（包含歌曲详情）
     async fn get_by_id(&self, id: i64) -> Result<Option<Playlist>, QueryError>;
     /// 根据所有者 ID 获取播放列表列表（基本信息）
     async fn get_by_owner_id(&self, owner_id: i64) -> Result<Vec<PlaylistSummary>, QueryError>;
+    /// 用户自己的播放列表以及作为协作者的公开播放列表
+    async fn get_editable_by(&self, user_id: i64) -> Result<Vec<PlaylistSummary>, QueryError>;
+    /// 用户最近删除的播放列表，最近删除的在前
+    async fn get_deleted_by_owner(&self, owner_id: i64)
+        -> Result<Vec<PlaylistSummary>, QueryError>;
+}
+
+#[async_trait]
+pub trait ChartDao {
+    /// 按播放次数排序的条目，统计 period_start 落在 [from, to) 的汇总行；user_id 为空时统计全服
+    async fn get_top_items(
+        &self,
+        item_kind: &str,
+        period: RollupPeriod,
+        from: NaiveDate,
+        to: NaiveDate,
+        user_id: Option<i64>,
+        limit: i32,
+    ) -> Result<Vec<ChartEntry>, QueryError>;
+}
+
+#[async_trait]
+pub trait ListeningReportDao {
+    /// 用户某年的收听时钟，只返回有播放记录的格子
+    async fn get_listening_clock(
+        &self,
+        user_id: i64,
+        year: i32,
+    ) -> Result<Vec<ListeningClockCell>, QueryError>;
+}
+
+#[async_trait]
+pub trait ListeningStreakDao {
+    async fn get_streak(&self, user_id: i64) -> Result<Option<ListeningStreak>, QueryError>;
+    /// 最近达成的在前
+    async fn get_milestones(
+        &self,
+        user_id: i64,
+        limit: i32,
+    ) -> Result<Vec<AchievedMilestone>, QueryError>;
+}
+
+#[async_trait]
+pub trait AnnotationDao {
+    /// 用户在 since 之后更新过的歌曲、专辑和艺术家注解（包括已取消收藏的），按 updated_at 升序
+    async fn get_by_user(
+        &self,
+        user_id: i64,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<UserAnnotation>, QueryError>;
+}
+
+#[async_trait]
+pub trait UserNoteDao {
+    /// 用户的备注，最近修改的在前，同时返回总数；item_kind 为 None 时包含所有类型
+    async fn get_by_user(
+        &self,
+        user_id: i64,
+        item_kind: Option<&str>,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<UserNote>, i64), QueryError>;
+    /// 批量查询用户对一组条目的备注，没有备注的条目不在结果中
+    async fn get_notes(
+        &self,
+        user_id: i64,
+        item_kind: &str,
+        item_ids: &[i64],
+    ) -> Result<HashMap<i64, String>, QueryError>;
+}
+
+#[async_trait]
+pub trait PlaybackHistoryDao {
+    /// 用户的播放记录，最近的在前，同时返回总数
+    async fn get_by_user(
+        &self,
+        user_id: i64,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<PlaybackHistoryItem>, i64), QueryError>;
+}
+
+#[async_trait]
+pub trait FeedDao {
+    /// token 大于 since 的新增专辑，按 token 升序
+    async fn get_added_albums(&self, since: i64, limit: i32) -> Result<Vec<FeedItem>, QueryError>;
+    /// token 大于 since 的新增歌曲，按 token 升序
+    async fn get_added_songs(&self, since: i64, limit: i32) -> Result<Vec<FeedItem>, QueryError>;
+    /// 用户在 since 之后播放过的专辑（去重，token 为最近一次播放），按 token 升序
+    async fn get_played_albums(
+        &self,
+        user_id: i64,
+        since: i64,
+        limit: i32,
+    ) -> Result<Vec<FeedItem>, QueryError>;
+    /// 用户在 since 之后播放过的歌曲（去重，token 为最近一次播放），按 token 升序
+    async fn get_played_songs(
+        &self,
+        user_id: i64,
+        since: i64,
+        limit: i32,
+    ) -> Result<Vec<FeedItem>, QueryError>;
+}
+
+/// 首页推荐，均返回按推荐顺序排列的专辑 ID
+#[async_trait]
+pub trait HomeDao {
+    /// 用户最近播放的专辑
+    async fn get_recently_played(&self, user_id: i64, limit: i32) -> Result<Vec<i64>, QueryError>;
+    /// added_since 之后加入、属于用户常听流派且还没听过的专辑，最新的在前
+    async fn get_new_in_played_genres(
+        &self,
+        user_id: i64,
+        added_since: NaiveDateTime,
+        limit: i32,
+    ) -> Result<Vec<i64>, QueryError>;
+    /// 听过但 played_before 之后没有再播放的专辑，播放次数多的在前
+    async fn get_not_played_since(
+        &self,
+        user_id: i64,
+        played_before: NaiveDateTime,
+        limit: i32,
+    ) -> Result<Vec<i64>, QueryError>;
+    /// 与用户常听艺术家流派相近、但用户没听过的艺术家，每位取播放最多的一张专辑
+    async fn get_similar_artist_picks(
+        &self,
+        user_id: i64,
+        limit: i32,
+    ) -> Result<Vec<i64>, QueryError>;
+}
+
+#[async_trait]
+pub trait LibraryStatsDao {
+    async fn get_totals(&self) -> Result<LibraryTotals, QueryError>;
+    /// 按扩展名（小写）统计，歌曲多的在前
+    async fn count_by_format(&self) -> Result<Vec<FormatCount>, QueryError>;
+    /// 按比特率（kbps）统计，升序；value 可以是分段下限
+    async fn count_by_bit_rate(&self) -> Result<Vec<ValueCount>, QueryError>;
+    /// 按采样率（Hz）统计，升序
+    async fn count_by_sample_rate(&self) -> Result<Vec<ValueCount>, QueryError>;
+    /// since 当月及之后每月新加入的歌曲和专辑，只返回有新增的月份，升序
+    async fn get_monthly_additions(
+        &self,
+        since: NaiveDate,
+    ) -> Result<Vec<MonthlyAdditions>, QueryError>;
 }
 
 #[async_trait]
 pub trait PlayQueueDao {
     /// 根据用户 ID 获取播放队列（包含歌曲详情）
-    async fn get_by_user_id(&self, user_id: i64, username: &str) -> Result<Option<PlayQueue>, QueryError>;
+    ///
+    /// 优先返回 `client_id` 对应的队列，没有时返回该用户最近更新的队列
+    async fn get_by_user_id(
+        &self,
+        user_id: i64,
+        client_id: &str,
+        username: &str,
+    ) -> Result<Option<PlayQueue>, QueryError>;
 }
 
 use chrono::NaiveDateTime;
@@ -181,6 +382,15 @@ pub struct CoverArtInfo {
     pub has_embedded: bool,
 }
 
+/// 播放列表封面信息
+#[derive(Debug, Clone)]
+pub struct PlaylistCoverInfo {
+    /// 用户上传的封面路径
+    pub cover_path: Option<String>,
+    /// 播放列表更新时间（用于缓存）
+    pub updated_at: NaiveDateTime,
+}
+
 /// 位置信息（用于查找封面）
 #[derive(Debug, Clone)]
 pub struct LocationInfo {
@@ -203,6 +413,8 @@ pub struct CoverArtPathWithSource {
     pub path: String,
     /// 来源类型：embedded 或 external
     pub source: String,
+    /// 嵌入封面的缩略图路径
+    pub thumbnail_path: Option<String>,
 }
 
 #[async_trait]
@@ -225,6 +437,12 @@ pub trait CoverArtDao {
     /// 获取播放列表第一首歌的封面信息
     async fn get_playlist_cover_info(&self, playlist_id: i64) -> Result<Option<CoverArtInfo>, QueryError>;
     
+    /// 获取播放列表的自定义封面和更新时间
+    async fn get_playlist_cover(&self, playlist_id: i64) -> Result<Option<PlaylistCoverInfo>, QueryError>;
+    
+    /// 获取播放列表中前 limit 张不同专辑的封面（按专辑首次出现的位置排序）
+    async fn get_playlist_album_covers(&self, playlist_id: i64, limit: i32) -> Result<Vec<CoverArtPathWithSource>, QueryError>;
+    
     /// 根据专辑 ID 获取所有封面路径（按路径排序，用于广度优先搜索）
     async fn get_cover_art_paths_by_album(&self, album_id: i64) -> Result<Vec<CoverArtPath>, QueryError>;
     
@@ -240,6 +458,9 @@ pub trait CoverArtDao {
     /// 根据艺术家 ID 获取关联的封面路径（从 cover_art 表查找 artist_id）
     async fn get_cover_art_by_artist(&self, artist_id: i64) -> Result<Option<CoverArtPath>, QueryError>;
     
+    /// 获取艺术家目录中收录的 artist.* 图片（media_asset 表）
+    async fn get_artist_image_asset(&self, artist_id: i64) -> Result<Option<CoverArtPath>, QueryError>;
+    
     /// 获取艺术家的第一个专辑 ID（按专辑名排序）
     async fn get_first_album_id_by_artist(&self, artist_id: i64) -> Result<Option<i64>, QueryError>;
     
@@ -31,7 +31,7 @@ impl GetAlbum {
         let album = match album {
             Some(album) => album,
             None => {
-                return Err(QueryError::InvalidInput(format!(
+                return Err(QueryError::NotFound(format!(
                     "Album not found: {}",
                     album_id
                 )));
@@ -29,7 +29,7 @@ impl GetAlbumInfo {
         let album_info = match album_info {
             Some(info) => info,
             None => {
-                return Err(QueryError::InvalidInput(format!(
+                return Err(QueryError::NotFound(format!(
                     "Album not found: {}",
                     album_id
                 )));
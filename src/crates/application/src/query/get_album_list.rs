@@ -48,6 +48,10 @@ impl GetAlbumList {
             "frequent" => self.album_dao.get_by_frequent(offset, limit).await,
             "starred" => self.album_dao.get_by_starred(user_id, offset, limit).await,
             "highest" => self.album_dao.get_by_rating(offset, limit).await,
+            // 扩展类型：按原始发行年份排序
+            "byOriginalYear" => self.album_dao.get_by_original_year(offset, limit).await,
+            // 扩展类型：按收藏人数排序
+            "mostStarred" => self.album_dao.get_by_most_starred(offset, limit).await,
             "byGenre" => {
                 let genre = genre.ok_or_else(|| {
                     QueryError::InvalidInput(
@@ -71,7 +75,7 @@ impl GetAlbumList {
                     .get_by_year(from_year, to_year, offset, limit)
                     .await
             }
-            _ => Err(QueryError::InvalidInput(format!(
+            _ => Err(QueryError::NotSupported(format!(
                 "type '{}' not implemented",
                 typ
             ))),
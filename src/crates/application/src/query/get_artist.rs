@@ -37,7 +37,7 @@ impl GetArtist {
         let artist = match artist {
             Some(artist) => artist,
             None => {
-                return Err(QueryError::InvalidInput(format!(
+                return Err(QueryError::NotFound(format!(
                     "Artist not found: {}",
                     artist_id
                 )));
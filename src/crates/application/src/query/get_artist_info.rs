@@ -29,7 +29,7 @@ impl GetArtistInfo {
         let artist_info = match artist_info {
             Some(info) => info,
             None => {
-                return Err(QueryError::InvalidInput(format!(
+                return Err(QueryError::NotFound(format!(
                     "Artist not found: {}",
                     artist_id
                 )));
@@ -0,0 +1,111 @@
+use crate::query::dao::{AudioFileDao, BookmarkDao};
+use crate::query::QueryError;
+use model::bookmark::{AudiobookProgress, Bookmark};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct GetBookmarks {
+    bookmark_dao: Arc<dyn BookmarkDao + Send + Sync>,
+    audio_file_dao: Arc<dyn AudioFileDao + Send + Sync>,
+}
+
+impl GetBookmarks {
+    pub fn new(
+        bookmark_dao: Arc<dyn BookmarkDao + Send + Sync>,
+        audio_file_dao: Arc<dyn AudioFileDao + Send + Sync>,
+    ) -> Self {
+        Self {
+            bookmark_dao,
+            audio_file_dao,
+        }
+    }
+
+    pub async fn handle(&self, user_id: i64) -> Result<Vec<Bookmark>, QueryError> {
+        self.bookmark_dao.get_by_user(user_id).await
+    }
+
+    /// 正在收听的有声书，按最近收听排序
+    ///
+    /// 每本书取最近更新的书签作为进度，已听完的书不返回
+    pub async fn in_progress_audiobooks(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<AudiobookProgress>, QueryError> {
+        let mut seen = HashSet::new();
+        let latest: Vec<Bookmark> = self
+            .bookmark_dao
+            .get_audiobook_bookmarks(user_id)
+            .await?
+            .into_iter()
+            .filter(|bookmark| seen.insert(bookmark.entry.album_id))
+            .collect();
+
+        let album_ids: Vec<i64> = latest.iter().map(|b| b.entry.album_id).collect();
+        let mut chapters: HashMap<i64, Vec<(i32, i32, i64, i64)>> = HashMap::new();
+        for song in self.audio_file_dao.get_by_album_ids(&album_ids).await? {
+            chapters.entry(song.album_id).or_default().push((
+                song.disc_number,
+                song.track_number,
+                song.id,
+                song.duration,
+            ));
+        }
+
+        let mut books = Vec::new();
+        for bookmark in latest {
+            let mut album = chapters
+                .remove(&bookmark.entry.album_id)
+                .unwrap_or_default();
+            album.sort();
+            let durations: Vec<(i64, i64)> = album.iter().map(|c| (c.2, c.3)).collect();
+            let Some(elapsed) = elapsed_seconds(&durations, bookmark.entry.id, bookmark.position)
+            else {
+                continue;
+            };
+            let duration: i64 = durations.iter().map(|(_, d)| d).sum();
+            let remaining = (duration - elapsed).max(0);
+            if remaining == 0 {
+                continue;
+            }
+            books.push(AudiobookProgress {
+                album_id: bookmark.entry.album_id,
+                album: bookmark.entry.album.clone(),
+                artist: bookmark.entry.artist.name.clone(),
+                position: bookmark.position,
+                chapter_count: durations.len() as i32,
+                duration,
+                elapsed,
+                remaining,
+                updated_at: bookmark.updated_at,
+                current: bookmark.entry,
+            });
+        }
+        Ok(books)
+    }
+}
+
+/// 书签之前各章节的时长加上章节内位置（秒）
+///
+/// durations 为按播放顺序排列的 (歌曲 ID, 时长秒数)，书签歌曲不在其中时返回 None
+fn elapsed_seconds(durations: &[(i64, i64)], current_id: i64, position_ms: i64) -> Option<i64> {
+    let index = durations.iter().position(|(id, _)| *id == current_id)?;
+    let before: i64 = durations[..index].iter().map(|(_, d)| d).sum();
+    let within = (position_ms / 1000).clamp(0, durations[index].1);
+    Some(before + within)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed_counts_previous_chapters() {
+        let chapters = [(1, 600), (2, 900), (3, 1200)];
+        assert_eq!(elapsed_seconds(&chapters, 1, 30_000), Some(30));
+        assert_eq!(elapsed_seconds(&chapters, 3, 61_500), Some(1561));
+        // 位置超出章节时长时按章节结束计算
+        assert_eq!(elapsed_seconds(&chapters, 2, 5_000_000), Some(1500));
+        assert_eq!(elapsed_seconds(&chapters, 4, 0), None);
+    }
+}
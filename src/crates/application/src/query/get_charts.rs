@@ -0,0 +1,116 @@
+use crate::query::dao::ChartDao;
+use crate::query::QueryError;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use model::play_stats::{ChartEntry, RollupPeriod};
+use std::sync::Arc;
+
+/// 排行榜类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    Artists,
+    Albums,
+    Songs,
+    Genres,
+}
+
+impl ChartKind {
+    /// 对应汇总表中的 item_kind
+    pub fn item_kind(&self) -> &'static str {
+        match self {
+            ChartKind::Artists => "artist",
+            ChartKind::Albums => "album",
+            ChartKind::Songs => "audio_file",
+            ChartKind::Genres => "genre",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GetCharts {
+    dao: Arc<dyn ChartDao + Send + Sync>,
+}
+
+impl GetCharts {
+    pub fn new(dao: Arc<dyn ChartDao + Send + Sync>) -> Self {
+        Self { dao }
+    }
+
+    /// 查询 [from, to] 日期范围（含两端）内的排行榜，user_id 为空时为全服排行
+    pub async fn handle(
+        &self,
+        kind: ChartKind,
+        from: NaiveDate,
+        to: NaiveDate,
+        user_id: Option<i64>,
+        limit: i32,
+    ) -> Result<Vec<ChartEntry>, QueryError> {
+        if from > to {
+            return Err(QueryError::InvalidParameter(format!(
+                "from ({}) must not be after to ({})",
+                from, to
+            )));
+        }
+        let end = to + Duration::days(1);
+        self.dao
+            .get_top_items(
+                kind.item_kind(),
+                rollup_period_for(from, end),
+                from,
+                end,
+                user_id,
+                limit,
+            )
+            .await
+    }
+}
+
+/// 选取能完整覆盖 [from, end) 的最粗粒度，范围没有对齐周/月边界时退回按日汇总
+fn rollup_period_for(from: NaiveDate, end: NaiveDate) -> RollupPeriod {
+    if from.day() == 1 && end.day() == 1 {
+        RollupPeriod::Month
+    } else if from.weekday() == Weekday::Mon && end.weekday() == Weekday::Mon {
+        RollupPeriod::Week
+    } else {
+        RollupPeriod::Day
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_rollup_period_for_month_range() {
+        // 2025-01-01 .. 2025-03-31
+        assert_eq!(
+            rollup_period_for(date(2025, 1, 1), date(2025, 4, 1)),
+            RollupPeriod::Month
+        );
+    }
+
+    #[test]
+    fn test_rollup_period_for_week_range() {
+        // 2025-03-03 是周一
+        assert_eq!(
+            rollup_period_for(date(2025, 3, 3), date(2025, 3, 17)),
+            RollupPeriod::Week
+        );
+    }
+
+    #[test]
+    fn test_rollup_period_for_unaligned_range() {
+        assert_eq!(
+            rollup_period_for(date(2025, 3, 5), date(2025, 3, 20)),
+            RollupPeriod::Day
+        );
+        // 起点对齐月初但终点不对齐
+        assert_eq!(
+            rollup_period_for(date(2025, 3, 1), date(2025, 3, 15)),
+            RollupPeriod::Day
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::query::config::CoverArtConfig;
-use crate::query::dao::{CoverArtDao, CoverArtPath};
+use crate::command::artwork::THUMBNAIL_SIZE;
+use crate::query::dao::{CoverArtDao, CoverArtPath, CoverArtPathWithSource, PlaylistCoverInfo};
 use crate::query::dto::artwork_id::{ArtworkId, ArtworkKind};
 use crate::query::QueryError;
 use async_trait::async_trait;
@@ -62,15 +63,78 @@ pub trait CoverArtCache: Send + Sync {
     async fn invalidate(&self, cache_key: &str);
 }
 
+/// 缩放后图片的输出格式，原图始终按原格式返回
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverOutputFormat {
+    /// 带透明通道的图片改用 PNG
+    Jpeg,
+    WebP,
+    Avif,
+}
+
+impl CoverOutputFormat {
+    /// 优先使用 format 参数，否则按 Accept 头选择客户端支持的最优格式
+    pub fn negotiate(format: Option<&str>, accept: Option<&str>) -> Self {
+        if let Some(format) = format {
+            match format.trim().to_lowercase().as_str() {
+                "webp" => return Self::WebP,
+                "avif" => return Self::Avif,
+                "jpg" | "jpeg" => return Self::Jpeg,
+                _ => {}
+            }
+        }
+        let accepted: Vec<&str> = accept
+            .unwrap_or("")
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';').map(str::trim);
+                let media_type = parts.next()?;
+                // q=0 表示明确不接受
+                let rejected = parts.any(|p| p.replace(' ', "") == "q=0");
+                (!rejected).then_some(media_type)
+            })
+            .collect();
+        if accepted.contains(&"image/avif") {
+            Self::Avif
+        } else if accepted.contains(&"image/webp") {
+            Self::WebP
+        } else {
+            Self::Jpeg
+        }
+    }
+
+    /// 附加在缓存键后，JPEG 沿用原来的缓存键
+    fn cache_suffix(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "",
+            Self::WebP => ".webp",
+            Self::Avif => ".avif",
+        }
+    }
+}
+
+/// 带尺寸和输出格式的缓存键
+fn sized_cache_key(base_cache_key: &str, size: Option<u32>, format: CoverOutputFormat) -> String {
+    match size {
+        Some(s) if s > 0 => format!("{}-{}{}", base_cache_key, s, format.cache_suffix()),
+        _ => base_cache_key.to_string(),
+    }
+}
+
 /// 封面来源信息（用于延迟加载）
 #[derive(Debug, Clone)]
 pub enum CoverSource {
-    /// 嵌入封面（从音频文件中提取）
-    Embedded { protocol: String, path: String },
+    /// 嵌入封面（从音频文件中提取），thumbnail 为扫描时生成的缩略图
+    Embedded { protocol: String, path: String, thumbnail: Option<String> },
     /// 外部封面文件
     External { protocol: String, path: String },
+    /// 由多张封面拼成的 2x2 图片（播放列表自动封面），读取时合成
+    Mosaic { tiles: Vec<CoverSource> },
 }
 
+/// 拼图封面每格的边长
+const MOSAIC_TILE_SIZE: u32 = THUMBNAIL_SIZE;
+
 // ============================================================================
 // 责任链模式：封面解析器
 // ============================================================================
@@ -105,11 +169,7 @@ impl CoverResolver for AudioFileCoverArtResolver {
             .await
             .ok()??;
         
-        if cover.source == "embedded" {
-            Some(CoverSource::Embedded { protocol: cover.protocol, path: cover.path })
-        } else {
-            Some(CoverSource::External { protocol: cover.protocol, path: cover.path })
-        }
+        Some(cover_source(&cover))
     }
 }
 
@@ -175,12 +235,7 @@ impl CoverResolver for AlbumAudioFileCoverResolver {
             a_is_embedded.cmp(&b_is_embedded)
         });
         
-        let cover = &covers[0];
-        if cover.source == "embedded" {
-            Some(CoverSource::Embedded { protocol: cover.protocol.clone(), path: cover.path.clone() })
-        } else {
-            Some(CoverSource::External { protocol: cover.protocol.clone(), path: cover.path.clone() })
-        }
+        Some(cover_source(&covers[0]))
     }
 }
 
@@ -205,6 +260,23 @@ impl CoverResolver for ArtistDirectCoverResolver {
     }
 }
 
+/// 艺术家图片解析器（扫描收录的 artist.jpg 等文件）
+struct ArtistImageAssetResolver {
+    artist_id: i64,
+}
+
+#[async_trait]
+impl CoverResolver for ArtistImageAssetResolver {
+    async fn resolve(&self, ctx: &ResolveContext<'_>) -> Option<CoverSource> {
+        ctx.dao
+            .get_artist_image_asset(self.artist_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|c| CoverSource::External { protocol: c.protocol, path: c.path })
+    }
+}
+
 /// 艺术家专辑封面解析器（复用专辑解析链）
 struct ArtistAlbumCoverResolver {
     artist_id: i64,
@@ -246,12 +318,7 @@ impl CoverResolver for ArtistAudioFileCoverResolver {
             a_is_embedded.cmp(&b_is_embedded)
         });
         
-        let cover = &covers[0];
-        if cover.source == "embedded" {
-            Some(CoverSource::Embedded { protocol: cover.protocol.clone(), path: cover.path.clone() })
-        } else {
-            Some(CoverSource::External { protocol: cover.protocol.clone(), path: cover.path.clone() })
-        }
+        Some(cover_source(&covers[0]))
     }
 }
 
@@ -259,6 +326,40 @@ impl CoverResolver for ArtistAudioFileCoverResolver {
 // Playlist 解析器
 // ============================================================================
 
+/// 播放列表自定义封面解析器（用户上传）
+struct PlaylistCustomCoverResolver {
+    cover_path: Option<String>,
+}
+
+#[async_trait]
+impl CoverResolver for PlaylistCustomCoverResolver {
+    async fn resolve(&self, _ctx: &ResolveContext<'_>) -> Option<CoverSource> {
+        self.cover_path
+            .as_ref()
+            .map(|path| CoverSource::External { protocol: "local".to_string(), path: path.clone() })
+    }
+}
+
+/// 播放列表拼图封面解析器（前四张不同专辑的封面）
+struct PlaylistMosaicResolver {
+    playlist_id: i64,
+}
+
+#[async_trait]
+impl CoverResolver for PlaylistMosaicResolver {
+    async fn resolve(&self, ctx: &ResolveContext<'_>) -> Option<CoverSource> {
+        let covers = ctx.dao
+            .get_playlist_album_covers(self.playlist_id, 4)
+            .await
+            .ok()?;
+        // 不足四张专辑时使用第一首歌的封面
+        if covers.len() < 4 {
+            return None;
+        }
+        Some(CoverSource::Mosaic { tiles: covers.iter().map(cover_source).collect() })
+    }
+}
+
 /// 播放列表嵌入封面解析器
 struct PlaylistEmbeddedResolver {
     path: String,
@@ -270,7 +371,7 @@ impl CoverResolver for PlaylistEmbeddedResolver {
     async fn resolve(&self, _ctx: &ResolveContext<'_>) -> Option<CoverSource> {
         if self.has_embedded {
             let (protocol, path) = parse_media_path(&self.path);
-            Some(CoverSource::Embedded { protocol, path })
+            Some(CoverSource::Embedded { protocol, path, thumbnail: None })
         } else {
             None
         }
@@ -298,6 +399,31 @@ impl CoverResolver for PlaylistPatternResolver {
     }
 }
 
+/// cover_art 记录对应的封面来源
+fn cover_source(cover: &CoverArtPathWithSource) -> CoverSource {
+    if cover.source == "embedded" {
+        CoverSource::Embedded {
+            protocol: cover.protocol.clone(),
+            path: cover.path.clone(),
+            thumbnail: cover.thumbnail_path.clone(),
+        }
+    } else {
+        CoverSource::External { protocol: cover.protocol.clone(), path: cover.path.clone() }
+    }
+}
+
+/// 请求尺寸不超过缩略图时，嵌入封面改读缩略图
+fn thumbnail_source(source: &CoverSource, size: Option<u32>) -> Option<CoverSource> {
+    match (source, size) {
+        (CoverSource::Embedded { thumbnail: Some(thumbnail), .. }, Some(s))
+            if s > 0 && s <= THUMBNAIL_SIZE =>
+        {
+            Some(CoverSource::External { protocol: "local".to_string(), path: thumbnail.clone() })
+        }
+        _ => None,
+    }
+}
+
 // ============================================================================
 // 责任链执行器
 // ============================================================================
@@ -347,20 +473,21 @@ impl GetCoverArt {
     /// 2. 检查带 size 的缓存 -> 命中直接返回
     /// 3. 检查原图缓存 -> 命中则缩放后返回（并缓存缩放结果）
     /// 4. 从磁盘读取 -> 同时缓存原图和缩放后的图片
-    pub async fn get_or_placeholder(&self, artwork_id_str: &str, size: Option<u32>) -> Result<CoverArtData, QueryError> {
+    pub async fn get_or_placeholder(
+        &self,
+        artwork_id_str: &str,
+        size: Option<u32>,
+        format: CoverOutputFormat,
+    ) -> Result<CoverArtData, QueryError> {
         let artwork_id = ArtworkId::parse(artwork_id_str)
             .map_err(|e| QueryError::InvalidInput(e.to_string()))?;
 
         // 1. 查询对象的 ID 和 last_modified 构建 cache_key
         let (base_cache_key, last_modified) = self.get_cache_key(&artwork_id).await?;
         
-        // 构建带 size 的 cache_key
+        // 构建带 size 和格式的 cache_key
         let needs_resize = matches!(size, Some(s) if s > 0);
-        let sized_cache_key = if needs_resize {
-            format!("{}-{}", base_cache_key, size.unwrap())
-        } else {
-            base_cache_key.clone()
-        };
+        let sized_cache_key = sized_cache_key(&base_cache_key, size, format);
 
         // 2. 先查带 size 的缓存
         if let Some(cached) = self.cover_art_cache.get(&sized_cache_key).await {
@@ -375,18 +502,34 @@ impl GetCoverArt {
             if let Some(cached) = self.cover_art_cache.get(&base_cache_key).await {
                 log::debug!("Cache hit for original cover art: {}", base_cache_key);
                 // 缩放并缓存
-                return self.resize_and_cache(cached, size.unwrap(), &sized_cache_key, last_modified).await;
+                return self.resize_and_cache(cached, size.unwrap(), format, &sized_cache_key, last_modified).await;
             }
             log::debug!("Cache miss for original cover art: {}", base_cache_key);
         }
 
         // 4. 缓存未命中，执行解析链获取封面来源
         let source = self.resolve_cover_source(&artwork_id).await?;
-        
+
+        // 嵌入封面优先读取扫描时生成的缩略图，避免读取音频文件
+        if let Some(thumbnail) = source.as_ref().and_then(|src| thumbnail_source(src, size)) {
+            match self.cover_art_reader.read(&thumbnail).await {
+                Ok(result) => {
+                    let data = CoverArtData {
+                        data: result.data,
+                        mime_type: result.mime_type,
+                        cache_key: sized_cache_key.clone(),
+                        last_modified,
+                    };
+                    return self.resize_and_cache(data, size.unwrap(), format, &sized_cache_key, last_modified).await;
+                }
+                Err(e) => log::warn!("Failed to read cover thumbnail, falling back to audio file: {}", e),
+            }
+        }
+
         // 5. 从磁盘读取原图
         let original_data = match &source {
             Some(src) => {
-                match self.cover_art_reader.read(src).await {
+                match self.read_source(src).await {
                     Ok(result) => Some((result.data, result.mime_type)),
                     Err(e) => {
                         log::warn!("Failed to read cover: {}", e);
@@ -412,16 +555,42 @@ impl GetCoverArt {
             
             // 如果需要缩放，缩放并缓存
             if needs_resize {
-                return self.resize_and_cache(original, size.unwrap(), &sized_cache_key, last_modified).await;
+                return self.resize_and_cache(original, size.unwrap(), format, &sized_cache_key, last_modified).await;
             }
             
             Ok(original)
         } else {
             // 7. 返回占位图（支持缩放）
-            Ok(self.get_placeholder(&artwork_id.kind, size))
+            Ok(self.get_placeholder(&artwork_id.kind, size, format))
         }
     }
     
+    /// 响应的 ETag（即 cache_key），只查询更新时间，不读取图片；
+    /// 用于在生成图片前处理条件请求，回退到占位图时与实际响应不同
+    pub async fn etag(
+        &self,
+        artwork_id_str: &str,
+        size: Option<u32>,
+        format: CoverOutputFormat,
+    ) -> Result<String, QueryError> {
+        let artwork_id = ArtworkId::parse(artwork_id_str)
+            .map_err(|e| QueryError::InvalidInput(e.to_string()))?;
+        let (base_cache_key, _) = self.get_cache_key(&artwork_id).await?;
+        Ok(sized_cache_key(&base_cache_key, size, format))
+    }
+
+    /// 与 get_or_placeholder 相同，但没有封面时返回 None 而不是占位图
+    pub async fn get(
+        &self,
+        artwork_id_str: &str,
+        size: Option<u32>,
+        format: CoverOutputFormat,
+    ) -> Result<Option<CoverArtData>, QueryError> {
+        let cover = self.get_or_placeholder(artwork_id_str, size, format).await?;
+        // 占位图的缓存键都以 placeholder- 开头
+        Ok(Some(cover).filter(|cover| !cover.cache_key.starts_with("placeholder-")))
+    }
+
     /// 获取 cache_key（仅查询 ID 和 last_modified）
     async fn get_cache_key(&self, artwork_id: &ArtworkId) -> Result<(String, i64), QueryError> {
         match artwork_id.kind {
@@ -450,9 +619,10 @@ impl GetCoverArt {
                 Ok((format!("ar-{}-{}", artwork_id.id, last_modified), last_modified))
             }
             ArtworkKind::Playlist => {
-                match self.cover_art_dao.get_playlist_cover_info(artwork_id.id).await? {
-                    Some(info) => {
-                        let last_modified = info.updated_at.and_utc().timestamp_millis();
+                // 播放列表的更新时间涵盖条目和自定义封面的变化
+                match self.cover_art_dao.get_playlist_cover(artwork_id.id).await? {
+                    Some(PlaylistCoverInfo { updated_at, .. }) => {
+                        let last_modified = updated_at.and_utc().timestamp_millis();
                         Ok((format!("pl-{}-{}", artwork_id.id, last_modified), last_modified))
                     }
                     None => Ok((format!("pl-{}-0", artwork_id.id), 0)),
@@ -461,15 +631,34 @@ impl GetCoverArt {
         }
     }
     
+    /// 读取封面来源，拼图封面先读取各格再合成
+    async fn read_source(&self, source: &CoverSource) -> Result<CoverArtBytes, QueryError> {
+        let CoverSource::Mosaic { tiles } = source else {
+            return self.cover_art_reader.read(source).await;
+        };
+        let reads = tiles.iter().map(|tile| async move {
+            // 嵌入封面优先读取缩略图
+            let tile = thumbnail_source(tile, Some(MOSAIC_TILE_SIZE)).unwrap_or_else(|| tile.clone());
+            self.cover_art_reader.read(&tile).await.map(|bytes| bytes.data)
+        });
+        let tiles = futures::future::try_join_all(reads).await?;
+        let data = tokio::task::spawn_blocking(move || compose_mosaic(&tiles))
+            .await
+            .map_err(|e| QueryError::ExecutionError(e.to_string()))?
+            .map_err(QueryError::ExecutionError)?;
+        Ok(CoverArtBytes { data: Bytes::from(data), mime_type: "image/jpeg".to_string() })
+    }
+
     /// 缩放图片并缓存结果
     async fn resize_and_cache(
         &self,
         original: CoverArtData,
         size: u32,
+        format: CoverOutputFormat,
         sized_cache_key: &str,
         last_modified: i64,
     ) -> Result<CoverArtData, QueryError> {
-        match resize_image(&original.data, size, &original.mime_type) {
+        match resize_image(&original.data, size, &original.mime_type, format) {
             Ok((resized_data, mime_type)) => {
                 let resized = CoverArtData {
                     data: resized_data,
@@ -493,7 +682,12 @@ impl GetCoverArt {
     /// 获取占位图（支持缩放）
     /// - Artist 使用艺术家占位图
     /// - 其他使用专辑占位图
-    fn get_placeholder(&self, kind: &ArtworkKind, size: Option<u32>) -> CoverArtData {
+    fn get_placeholder(
+        &self,
+        kind: &ArtworkKind,
+        size: Option<u32>,
+        format: CoverOutputFormat,
+    ) -> CoverArtData {
         let (placeholder_path, base_cache_key) = match kind {
             ArtworkKind::Artist => (
                 self.cover_art_config.artist_placeholder_path(),
@@ -505,11 +699,7 @@ impl GetCoverArt {
             ),
         };
         
-        // 构建带 size 的 cache_key
-        let cache_key = match size {
-            Some(s) if s > 0 => format!("{}-{}", base_cache_key, s),
-            _ => base_cache_key.to_string(),
-        };
+        let cache_key = sized_cache_key(base_cache_key, size, format);
         
         // 尝试从文件读取占位图
         if let Some(path) = placeholder_path {
@@ -527,7 +717,7 @@ impl GetCoverArt {
                 // 如果需要缩放
                 if let Some(s) = size {
                     if s > 0 {
-                        match resize_image(&data, s, mime_type) {
+                        match resize_image(&data, s, mime_type, format) {
                             Ok((resized_data, resized_mime)) => {
                                 return CoverArtData {
                                     data: resized_data,
@@ -589,6 +779,7 @@ impl GetCoverArt {
             ArtworkKind::Artist => {
                 let resolvers: Vec<Box<dyn CoverResolver>> = vec![
                     Box::new(ArtistDirectCoverResolver { artist_id: artwork_id.id }),
+                    Box::new(ArtistImageAssetResolver { artist_id: artwork_id.id }),
                     Box::new(ArtistAlbumCoverResolver { artist_id: artwork_id.id }),
                     Box::new(ArtistAudioFileCoverResolver { artist_id: artwork_id.id }),
                 ];
@@ -596,20 +787,23 @@ impl GetCoverArt {
             }
             ArtworkKind::Playlist => {
                 // Playlist 需要额外信息来构建解析器
-                let info = self.cover_art_dao.get_playlist_cover_info(artwork_id.id).await?;
-                match info {
-                    Some(info) => {
-                        let resolvers: Vec<Box<dyn CoverResolver>> = vec![
-                            Box::new(PlaylistEmbeddedResolver {
-                                path: info.path.clone(),
-                                has_embedded: info.has_embedded,
-                            }),
-                            Box::new(PlaylistPatternResolver { path: info.path }),
-                        ];
-                        Ok(run_resolver_chain(&resolvers, &ctx).await)
-                    }
-                    None => Ok(None),
+                let cover_path = self
+                    .cover_art_dao
+                    .get_playlist_cover(artwork_id.id)
+                    .await?
+                    .and_then(|cover| cover.cover_path);
+                let mut resolvers: Vec<Box<dyn CoverResolver>> = vec![
+                    Box::new(PlaylistCustomCoverResolver { cover_path }),
+                    Box::new(PlaylistMosaicResolver { playlist_id: artwork_id.id }),
+                ];
+                if let Some(info) = self.cover_art_dao.get_playlist_cover_info(artwork_id.id).await? {
+                    resolvers.push(Box::new(PlaylistEmbeddedResolver {
+                        path: info.path.clone(),
+                        has_embedded: info.has_embedded,
+                    }));
+                    resolvers.push(Box::new(PlaylistPatternResolver { path: info.path }));
                 }
+                Ok(run_resolver_chain(&resolvers, &ctx).await)
             }
         }
     }
@@ -755,7 +949,12 @@ fn get_wildcard_priority(filename: &str, wildcards: &[String]) -> usize {
 
 /// 缩放图片到指定尺寸（保持宽高比）
 /// 返回缩放后的图片数据和 MIME 类型
-fn resize_image(data: &Bytes, size: u32, original_mime: &str) -> Result<(Bytes, String), String> {
+fn resize_image(
+    data: &Bytes,
+    size: u32,
+    original_mime: &str,
+    format: CoverOutputFormat,
+) -> Result<(Bytes, String), String> {
     // 加载图片
     let img = image::load_from_memory(data)
         .map_err(|e| format!("Failed to load image: {}", e))?;
@@ -779,12 +978,36 @@ fn resize_image(data: &Bytes, size: u32, original_mime: &str) -> Result<(Bytes,
     // 使用 Lanczos3 算法缩放（高质量）
     let resized = img.resize(new_width, new_height, image::imageops::FilterType::Lanczos3);
     
-    // 编码为 JPEG（通用且压缩率好）
+    // JPEG 不支持透明通道，带透明的图片保留为 PNG
+    let (image_format, mime_type) = match format {
+        CoverOutputFormat::WebP => (ImageFormat::WebP, "image/webp"),
+        CoverOutputFormat::Avif => (ImageFormat::Avif, "image/avif"),
+        CoverOutputFormat::Jpeg if resized.color().has_alpha() => (ImageFormat::Png, "image/png"),
+        CoverOutputFormat::Jpeg => (ImageFormat::Jpeg, "image/jpeg"),
+    };
     let mut buffer = Cursor::new(Vec::new());
-    resized.write_to(&mut buffer, ImageFormat::Jpeg)
+    resized.write_to(&mut buffer, image_format)
         .map_err(|e| format!("Failed to encode image: {}", e))?;
     
-    Ok((Bytes::from(buffer.into_inner()), "image/jpeg".to_string()))
+    Ok((Bytes::from(buffer.into_inner()), mime_type.to_string()))
+}
+
+/// 把最多四张封面裁成正方形后拼成 2x2 的 JPEG
+fn compose_mosaic(tiles: &[Bytes]) -> Result<Vec<u8>, String> {
+    let mut canvas = image::RgbImage::new(MOSAIC_TILE_SIZE * 2, MOSAIC_TILE_SIZE * 2);
+    for (i, tile) in tiles.iter().take(4).enumerate() {
+        let img = image::load_from_memory(tile)
+            .map_err(|e| format!("Failed to load mosaic tile: {}", e))?
+            .resize_to_fill(MOSAIC_TILE_SIZE, MOSAIC_TILE_SIZE, image::imageops::FilterType::Lanczos3)
+            .to_rgb8();
+        let x = (i as u32 % 2) * MOSAIC_TILE_SIZE;
+        let y = (i as u32 / 2) * MOSAIC_TILE_SIZE;
+        image::imageops::replace(&mut canvas, &img, x as i64, y as i64);
+    }
+    let mut buffer = Cursor::new(Vec::new());
+    canvas.write_to(&mut buffer, ImageFormat::Jpeg)
+        .map_err(|e| format!("Failed to encode mosaic: {}", e))?;
+    Ok(buffer.into_inner())
 }
 
 #[cfg(test)]
@@ -884,4 +1107,97 @@ mod tests {
         assert!(best.is_some());
         assert_eq!(best.unwrap().path, "/music/album/cover.png");
     }
+
+    #[test]
+    fn test_thumbnail_source() {
+        let embedded = CoverSource::Embedded {
+            protocol: "local".to_string(),
+            path: "/music/a.flac".to_string(),
+            thumbnail: Some("/data/cover_thumbnails/a.webp".to_string()),
+        };
+        match thumbnail_source(&embedded, Some(150)) {
+            Some(CoverSource::External { path, .. }) => {
+                assert_eq!(path, "/data/cover_thumbnails/a.webp")
+            }
+            other => panic!("unexpected source: {:?}", other),
+        }
+        // 原图或超过缩略图尺寸时仍读取音频文件
+        assert!(thumbnail_source(&embedded, None).is_none());
+        assert!(thumbnail_source(&embedded, Some(THUMBNAIL_SIZE + 1)).is_none());
+
+        let without_thumbnail = CoverSource::Embedded {
+            protocol: "local".to_string(),
+            path: "/music/a.flac".to_string(),
+            thumbnail: None,
+        };
+        assert!(thumbnail_source(&without_thumbnail, Some(150)).is_none());
+    }
+
+    #[test]
+    fn test_negotiate_output_format() {
+        use CoverOutputFormat::*;
+        assert_eq!(CoverOutputFormat::negotiate(None, None), Jpeg);
+        assert_eq!(
+            CoverOutputFormat::negotiate(None, Some("image/avif,image/webp,image/*,*/*;q=0.8")),
+            Avif
+        );
+        assert_eq!(CoverOutputFormat::negotiate(None, Some("image/webp, */*")), WebP);
+        assert_eq!(CoverOutputFormat::negotiate(None, Some("image/avif;q=0, image/webp")), WebP);
+        // format 参数优先于 Accept
+        assert_eq!(CoverOutputFormat::negotiate(Some("jpeg"), Some("image/avif")), Jpeg);
+        assert_eq!(CoverOutputFormat::negotiate(Some("WEBP"), None), WebP);
+        assert_eq!(CoverOutputFormat::negotiate(Some("bogus"), Some("image/webp")), WebP);
+    }
+
+    #[test]
+    fn test_sized_cache_key_keeps_jpeg_keys() {
+        assert_eq!(sized_cache_key("al-1-100", Some(300), CoverOutputFormat::Jpeg), "al-1-100-300");
+        assert_eq!(sized_cache_key("al-1-100", Some(300), CoverOutputFormat::WebP), "al-1-100-300.webp");
+        assert_eq!(sized_cache_key("al-1-100", None, CoverOutputFormat::Avif), "al-1-100");
+    }
+
+    #[test]
+    fn test_resize_keeps_png_for_transparency() {
+        use image::{DynamicImage, RgbaImage};
+        let mut buffer = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::new(64, 64))
+            .write_to(&mut buffer, ImageFormat::Png)
+            .unwrap();
+        let data = Bytes::from(buffer.into_inner());
+
+        let (_, mime) = resize_image(&data, 32, "image/png", CoverOutputFormat::Jpeg).unwrap();
+        assert_eq!(mime, "image/png");
+        let (resized, mime) = resize_image(&data, 32, "image/png", CoverOutputFormat::WebP).unwrap();
+        assert_eq!(mime, "image/webp");
+        assert_eq!(image::load_from_memory(&resized).unwrap().width(), 32);
+    }
+
+    #[test]
+    fn test_compose_mosaic_places_tiles_in_grid() {
+        let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]];
+        let tiles: Vec<Bytes> = colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| {
+                // 不同尺寸和比例的封面都会被裁成正方形
+                let img = image::RgbImage::from_pixel(100 + i as u32 * 50, 100, image::Rgb(*color));
+                let mut buffer = Cursor::new(Vec::new());
+                img.write_to(&mut buffer, ImageFormat::Png).unwrap();
+                Bytes::from(buffer.into_inner())
+            })
+            .collect();
+
+        let mosaic = image::load_from_memory(&compose_mosaic(&tiles).unwrap()).unwrap().to_rgb8();
+        assert_eq!(mosaic.dimensions(), (MOSAIC_TILE_SIZE * 2, MOSAIC_TILE_SIZE * 2));
+        let center = MOSAIC_TILE_SIZE / 2;
+        for (i, color) in colors.iter().enumerate() {
+            let x = (i as u32 % 2) * MOSAIC_TILE_SIZE + center;
+            let y = (i as u32 / 2) * MOSAIC_TILE_SIZE + center;
+            let pixel = mosaic.get_pixel(x, y).0;
+            // JPEG 有损，允许少量偏差
+            for c in 0..3 {
+                assert!((pixel[c] as i32 - color[c] as i32).abs() < 8, "tile {} = {:?}", i, pixel);
+            }
+        }
+    }
 }
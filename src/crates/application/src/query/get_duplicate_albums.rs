@@ -0,0 +1,183 @@
+use crate::query::dao::{AlbumDao, AudioFileDao};
+use crate::query::shared::normalize_title;
+use crate::query::QueryError;
+use model::album::Album;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// 默认的最低歌曲重合比例
+pub const DEFAULT_MIN_OVERLAP: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct DuplicateAlbum {
+    pub id: i64,
+    pub name: String,
+    pub artist_name: String,
+    pub song_count: usize,
+}
+
+/// 疑似重复的两张专辑，建议将 source 合并到 target
+#[derive(Debug, Clone)]
+pub struct DuplicateAlbumPair {
+    pub source: DuplicateAlbum,
+    pub target: DuplicateAlbum,
+    /// 两张专辑中标题相同的歌曲数
+    pub shared_songs: usize,
+    /// shared_songs 占歌曲较少一方的比例
+    pub overlap: f64,
+}
+
+#[derive(Clone)]
+pub struct GetDuplicateAlbums {
+    album_dao: Arc<dyn AlbumDao + Send + Sync>,
+    audio_file_dao: Arc<dyn AudioFileDao + Send + Sync>,
+}
+
+impl GetDuplicateAlbums {
+    pub fn new(
+        album_dao: Arc<dyn AlbumDao + Send + Sync>,
+        audio_file_dao: Arc<dyn AudioFileDao + Send + Sync>,
+    ) -> Self {
+        Self {
+            album_dao,
+            audio_file_dao,
+        }
+    }
+
+    /// 名称规范化后相同、歌曲重合比例不低于 min_overlap 的专辑对，重合多的在前
+    pub async fn handle(&self, min_overlap: f64) -> Result<Vec<DuplicateAlbumPair>, QueryError> {
+        let albums = self.album_dao.get_all().await?;
+        let mut name_counts: HashMap<String, usize> = HashMap::new();
+        for album in &albums {
+            *name_counts.entry(normalize_title(&album.name)).or_default() += 1;
+        }
+        // 只读取有同名专辑的歌曲
+        let candidates: Vec<Album> = albums
+            .into_iter()
+            .filter(|album| name_counts[&normalize_title(&album.name)] > 1)
+            .collect();
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let album_ids: Vec<i64> = candidates.iter().map(|album| album.id).collect();
+        let mut titles: HashMap<i64, HashSet<String>> = HashMap::new();
+        for song in self.audio_file_dao.get_by_album_ids(&album_ids).await? {
+            titles
+                .entry(song.album_id)
+                .or_default()
+                .insert(normalize_title(&song.title));
+        }
+        let albums = candidates
+            .into_iter()
+            .map(|album| {
+                let titles = titles.remove(&album.id).unwrap_or_default();
+                let album = DuplicateAlbum {
+                    id: album.id,
+                    name: album.name,
+                    artist_name: album.artist.name,
+                    song_count: titles.len(),
+                };
+                (album, titles)
+            })
+            .collect();
+        Ok(find_pairs(albums, min_overlap))
+    }
+}
+
+fn find_pairs(
+    albums: Vec<(DuplicateAlbum, HashSet<String>)>,
+    min_overlap: f64,
+) -> Vec<DuplicateAlbumPair> {
+    let mut groups: HashMap<String, Vec<(DuplicateAlbum, HashSet<String>)>> = HashMap::new();
+    for (album, titles) in albums {
+        groups
+            .entry(normalize_title(&album.name))
+            .or_default()
+            .push((album, titles));
+    }
+
+    let mut pairs = Vec::new();
+    for group in groups.into_values() {
+        for (i, (a, a_titles)) in group.iter().enumerate() {
+            for (b, b_titles) in &group[i + 1..] {
+                let smaller = a_titles.len().min(b_titles.len());
+                let shared_songs = a_titles.intersection(b_titles).count();
+                if shared_songs == 0 {
+                    continue;
+                }
+                let overlap = shared_songs as f64 / smaller as f64;
+                if overlap < min_overlap {
+                    continue;
+                }
+                // 保留歌曲较多的一张，相同时保留先入库（ID 较小）的一张
+                let (source, target) = match (a.song_count, -a.id).cmp(&(b.song_count, -b.id)) {
+                    std::cmp::Ordering::Less => (a, b),
+                    _ => (b, a),
+                };
+                pairs.push(DuplicateAlbumPair {
+                    source: source.clone(),
+                    target: target.clone(),
+                    shared_songs,
+                    overlap,
+                });
+            }
+        }
+    }
+    pairs.sort_by(|a, b| {
+        b.overlap
+            .total_cmp(&a.overlap)
+            .then(b.shared_songs.cmp(&a.shared_songs))
+            .then(a.target.id.cmp(&b.target.id))
+            .then(a.source.id.cmp(&b.source.id))
+    });
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album(id: i64, name: &str, titles: &[&str]) -> (DuplicateAlbum, HashSet<String>) {
+        let titles: HashSet<String> = titles.iter().map(|t| normalize_title(t)).collect();
+        let album = DuplicateAlbum {
+            id,
+            name: name.to_string(),
+            artist_name: "Radiohead".to_string(),
+            song_count: titles.len(),
+        };
+        (album, titles)
+    }
+
+    #[test]
+    fn pairs_albums_with_same_name_and_shared_songs() {
+        let albums = vec![
+            album(1, "OK Computer", &["Airbag", "Paranoid Android"]),
+            album(
+                2,
+                "OK Computer (Remastered)",
+                &["Airbag (Remastered)", "Paranoid Android", "Lucky"],
+            ),
+            // 同名但歌曲不同
+            album(3, "Ok computer", &["Intro"]),
+            album(4, "Kid A", &["Idioteque"]),
+        ];
+        let pairs = find_pairs(albums, DEFAULT_MIN_OVERLAP);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].source.id, 1);
+        assert_eq!(pairs[0].target.id, 2);
+        assert_eq!(pairs[0].shared_songs, 2);
+        assert_eq!(pairs[0].overlap, 1.0);
+    }
+
+    #[test]
+    fn keeps_older_album_when_song_counts_match() {
+        let albums = vec![
+            album(7, "Amnesiac", &["Knives Out", "Pyramid Song"]),
+            album(5, "Amnesiac", &["Knives Out", "Dollars and Cents"]),
+        ];
+        let pairs = find_pairs(albums.clone(), DEFAULT_MIN_OVERLAP);
+        assert_eq!((pairs[0].source.id, pairs[0].target.id), (7, 5));
+        assert!(find_pairs(albums, 0.8).is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::query::dao::FeedDao;
+use crate::query::QueryError;
+use model::feed::FeedItem;
+use std::sync::Arc;
+
+/// 增量同步的数据源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feed {
+    AddedAlbums,
+    AddedSongs,
+    PlayedAlbums,
+    PlayedSongs,
+}
+
+/// 一页同步结果，客户端用 next_token 继续拉取，has_more 为 false 时表示已追上
+#[derive(Debug, Clone)]
+pub struct FeedPage {
+    pub items: Vec<FeedItem>,
+    pub next_token: i64,
+    pub has_more: bool,
+}
+
+#[derive(Clone)]
+pub struct GetFeed {
+    dao: Arc<dyn FeedDao + Send + Sync>,
+}
+
+impl GetFeed {
+    pub fn new(dao: Arc<dyn FeedDao + Send + Sync>) -> Self {
+        Self { dao }
+    }
+
+    pub async fn handle(
+        &self,
+        feed: Feed,
+        user_id: i64,
+        since: i64,
+        limit: i32,
+    ) -> Result<FeedPage, QueryError> {
+        let limit = limit.clamp(1, 500);
+        // 多取一条用于判断是否还有下一页
+        let fetch = limit + 1;
+        let items = match feed {
+            Feed::AddedAlbums => self.dao.get_added_albums(since, fetch).await?,
+            Feed::AddedSongs => self.dao.get_added_songs(since, fetch).await?,
+            Feed::PlayedAlbums => self.dao.get_played_albums(user_id, since, fetch).await?,
+            Feed::PlayedSongs => self.dao.get_played_songs(user_id, since, fetch).await?,
+        };
+        Ok(paginate(items, since, limit as usize))
+    }
+}
+
+fn paginate(mut items: Vec<FeedItem>, since: i64, limit: usize) -> FeedPage {
+    let has_more = items.len() > limit;
+    items.truncate(limit);
+    let next_token = items.last().map(|item| item.token).unwrap_or(since);
+    FeedPage {
+        items,
+        next_token,
+        has_more,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn item(token: i64) -> FeedItem {
+        FeedItem {
+            token,
+            item_id: token,
+            name: format!("item {}", token),
+            album_id: None,
+            artist_id: None,
+            at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_paginate_with_more_items() {
+        let page = paginate(vec![item(11), item(12), item(13)], 10, 2);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.next_token, 12);
+        assert!(page.has_more);
+    }
+
+    #[test]
+    fn test_paginate_last_page() {
+        let page = paginate(vec![item(11)], 10, 2);
+        assert_eq!(page.next_token, 11);
+        assert!(!page.has_more);
+    }
+
+    #[test]
+    fn test_paginate_empty_keeps_since() {
+        let page = paginate(Vec::new(), 42, 2);
+        assert!(page.items.is_empty());
+        assert_eq!(page.next_token, 42);
+        assert!(!page.has_more);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::query::dao::{AlbumDao, HomeDao};
+use crate::query::QueryError;
+use chrono::{Duration, Utc};
+use model::album::Album;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// 每行缓存的专辑数，请求的数量不能超过它
+pub const MAX_ROW_SIZE: i32 = 50;
+/// 最近多少天加入的专辑算作新专辑
+const NEW_ALBUM_DAYS: i64 = 90;
+/// 多少天没有播放的专辑可以重温
+const REDISCOVER_AFTER_DAYS: i64 = 182;
+
+/// 首页的推荐行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HomeRowKind {
+    /// 最近播放
+    RecentlyPlayed,
+    /// 常听流派中新加入的专辑
+    NewForYou,
+    /// 半年以上没听的专辑
+    Rediscover,
+    /// 相似艺术家的热门专辑
+    SimilarArtists,
+}
+
+impl HomeRowKind {
+    pub const ALL: [HomeRowKind; 4] = [
+        HomeRowKind::RecentlyPlayed,
+        HomeRowKind::NewForYou,
+        HomeRowKind::Rediscover,
+        HomeRowKind::SimilarArtists,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HomeRowKind::RecentlyPlayed => "recentlyPlayed",
+            HomeRowKind::NewForYou => "newForYou",
+            HomeRowKind::Rediscover => "rediscover",
+            HomeRowKind::SimilarArtists => "similarArtists",
+        }
+    }
+
+    /// 最近播放变化快，只做短时间缓存；其余几行计算较重且变化慢
+    fn ttl(&self) -> std::time::Duration {
+        let secs = match self {
+            HomeRowKind::RecentlyPlayed => 60,
+            HomeRowKind::NewForYou => 3600,
+            HomeRowKind::Rediscover | HomeRowKind::SimilarArtists => 6 * 3600,
+        };
+        std::time::Duration::from_secs(secs)
+    }
+}
+
+#[derive(Debug)]
+pub struct HomeRow {
+    pub kind: HomeRowKind,
+    pub albums: Vec<Album>,
+}
+
+/// 计算时间和推荐的专辑 ID
+type CachedRow = (Instant, Vec<i64>);
+
+/// 按用户和行缓存推荐的专辑 ID，专辑详情（包括播放次数、收藏）每次重新读取
+#[derive(Debug, Default)]
+pub struct HomeRowCache {
+    rows: Mutex<HashMap<(i64, HomeRowKind), CachedRow>>,
+}
+
+impl HomeRowCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get(&self, user_id: i64, kind: HomeRowKind) -> Option<Vec<i64>> {
+        let rows = self.rows.lock().unwrap();
+        rows.get(&(user_id, kind))
+            .filter(|(at, _)| at.elapsed() < kind.ttl())
+            .map(|(_, ids)| ids.clone())
+    }
+
+    fn put(&self, user_id: i64, kind: HomeRowKind, ids: Vec<i64>) {
+        let mut rows = self.rows.lock().unwrap();
+        // 顺便清理过期的行，缓存大小不随历史用户数增长
+        rows.retain(|(_, kind), (at, _)| at.elapsed() < kind.ttl());
+        rows.insert((user_id, kind), (Instant::now(), ids));
+    }
+
+    /// 清除用户的缓存，下次请求重新计算
+    pub fn invalidate(&self, user_id: i64) {
+        self.rows
+            .lock()
+            .unwrap()
+            .retain(|(cached_user, _), _| *cached_user != user_id);
+    }
+}
+
+#[derive(Clone)]
+pub struct GetHome {
+    home_dao: Arc<dyn HomeDao + Send + Sync>,
+    album_dao: Arc<dyn AlbumDao + Send + Sync>,
+    cache: Arc<HomeRowCache>,
+}
+
+impl GetHome {
+    pub fn new(
+        home_dao: Arc<dyn HomeDao + Send + Sync>,
+        album_dao: Arc<dyn AlbumDao + Send + Sync>,
+        cache: Arc<HomeRowCache>,
+    ) -> Self {
+        Self {
+            home_dao,
+            album_dao,
+            cache,
+        }
+    }
+
+    /// 依次返回各推荐行，没有内容的行不返回
+    pub async fn handle(&self, user_id: i64, limit: i32) -> Result<Vec<HomeRow>, QueryError> {
+        let limit = limit.clamp(1, MAX_ROW_SIZE) as usize;
+        let mut rows = Vec::new();
+        for kind in HomeRowKind::ALL {
+            let mut ids = match self.cache.get(user_id, kind) {
+                Some(ids) => ids,
+                None => {
+                    let ids = self.compute(user_id, kind).await?;
+                    self.cache.put(user_id, kind, ids.clone());
+                    ids
+                }
+            };
+            ids.truncate(limit);
+            if ids.is_empty() {
+                continue;
+            }
+            let albums = self.album_dao.get_by_ids(&ids).await?;
+            let albums = order_by_ids(&ids, albums);
+            if !albums.is_empty() {
+                rows.push(HomeRow { kind, albums });
+            }
+        }
+        Ok(rows)
+    }
+
+    async fn compute(&self, user_id: i64, kind: HomeRowKind) -> Result<Vec<i64>, QueryError> {
+        let now = Utc::now().naive_utc();
+        match kind {
+            HomeRowKind::RecentlyPlayed => {
+                self.home_dao
+                    .get_recently_played(user_id, MAX_ROW_SIZE)
+                    .await
+            }
+            HomeRowKind::NewForYou => {
+                self.home_dao
+                    .get_new_in_played_genres(
+                        user_id,
+                        now - Duration::days(NEW_ALBUM_DAYS),
+                        MAX_ROW_SIZE,
+                    )
+                    .await
+            }
+            HomeRowKind::Rediscover => {
+                self.home_dao
+                    .get_not_played_since(
+                        user_id,
+                        now - Duration::days(REDISCOVER_AFTER_DAYS),
+                        MAX_ROW_SIZE,
+                    )
+                    .await
+            }
+            HomeRowKind::SimilarArtists => {
+                self.home_dao
+                    .get_similar_artist_picks(user_id, MAX_ROW_SIZE)
+                    .await
+            }
+        }
+    }
+}
+
+/// 按推荐顺序排列，缓存之后被删除的专辑跳过
+fn order_by_ids(ids: &[i64], albums: Vec<Album>) -> Vec<Album> {
+    let mut by_id: HashMap<i64, Album> =
+        albums.into_iter().map(|album| (album.id, album)).collect();
+    ids.iter().filter_map(|id| by_id.remove(id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_rows_are_recomputed() {
+        let cache = HomeRowCache::new();
+        cache.put(1, HomeRowKind::Rediscover, vec![3, 1, 2]);
+        assert_eq!(cache.get(1, HomeRowKind::Rediscover), Some(vec![3, 1, 2]));
+        assert_eq!(cache.get(2, HomeRowKind::Rediscover), None);
+
+        let stale = Instant::now() - HomeRowKind::RecentlyPlayed.ttl();
+        cache
+            .rows
+            .lock()
+            .unwrap()
+            .insert((1, HomeRowKind::RecentlyPlayed), (stale, vec![4]));
+        assert_eq!(cache.get(1, HomeRowKind::RecentlyPlayed), None);
+
+        cache.invalidate(1);
+        assert_eq!(cache.get(1, HomeRowKind::Rediscover), None);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::query::dao::LibraryStatsDao;
+use crate::query::QueryError;
+use chrono::{Datelike, Months, NaiveDate};
+use model::format_stats::BIT_RATE_BUCKETS;
+use model::library_stats::{FormatCount, LibraryTotals, MonthlyAdditions, ValueCount};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 汇总查询涉及全库，短时间内的重复请求（如仪表盘刷新）直接使用缓存
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+pub const DEFAULT_GROWTH_MONTHS: u32 = 12;
+pub const MAX_GROWTH_MONTHS: u32 = 120;
+
+/// 一个比特率分段，包含 min，不包含 max
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitRateBucket {
+    pub min: i32,
+    pub max: Option<i32>,
+    pub songs: i64,
+}
+
+/// 某个月的新增数量，以及到该月末为止加入、目前仍在库中的累计数量
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrowthPoint {
+    pub month: NaiveDate,
+    pub songs_added: i64,
+    pub albums_added: i64,
+    pub total_songs: i64,
+    pub total_albums: i64,
+}
+
+/// 管理仪表盘使用的媒体库统计
+#[derive(Debug, Clone)]
+pub struct LibraryStats {
+    pub totals: LibraryTotals,
+    pub formats: Vec<FormatCount>,
+    pub bit_rates: Vec<BitRateBucket>,
+    pub sample_rates: Vec<ValueCount>,
+    /// 从最早的月份到当月，没有新增的月份也包含在内
+    pub growth: Vec<GrowthPoint>,
+}
+
+/// 按统计起始月份缓存结果
+#[derive(Debug, Default)]
+pub struct LibraryStatsCache {
+    entries: Mutex<HashMap<NaiveDate, (Instant, LibraryStats)>>,
+}
+
+impl LibraryStatsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get(&self, since: NaiveDate) -> Option<LibraryStats> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&since)
+            .filter(|(at, _)| at.elapsed() < CACHE_TTL)
+            .map(|(_, stats)| stats.clone())
+    }
+
+    fn put(&self, since: NaiveDate, stats: LibraryStats) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (at, _)| at.elapsed() < CACHE_TTL);
+        entries.insert(since, (Instant::now(), stats));
+    }
+
+    /// 清除缓存，下次请求重新统计
+    pub fn invalidate(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[derive(Clone)]
+pub struct GetLibraryStats {
+    dao: Arc<dyn LibraryStatsDao + Send + Sync>,
+    cache: Arc<LibraryStatsCache>,
+}
+
+impl GetLibraryStats {
+    pub fn new(dao: Arc<dyn LibraryStatsDao + Send + Sync>, cache: Arc<LibraryStatsCache>) -> Self {
+        Self { dao, cache }
+    }
+
+    /// 统计整个媒体库，growth 覆盖 today 所在月及之前共 months 个月
+    pub async fn handle(&self, today: NaiveDate, months: u32) -> Result<LibraryStats, QueryError> {
+        if months == 0 || months > MAX_GROWTH_MONTHS {
+            return Err(QueryError::InvalidParameter(format!(
+                "months must be between 1 and {}",
+                MAX_GROWTH_MONTHS
+            )));
+        }
+        let current = first_of_month(today);
+        let since = current - Months::new(months - 1);
+        if let Some(stats) = self.cache.get(since) {
+            return Ok(stats);
+        }
+
+        let totals = self.dao.get_totals().await?;
+        let formats = self.dao.count_by_format().await?;
+        let bit_rates = bucket_bit_rates(&self.dao.count_by_bit_rate().await?);
+        let sample_rates = self.dao.count_by_sample_rate().await?;
+        let additions = self.dao.get_monthly_additions(since).await?;
+        let growth = growth_points(&totals, &additions, since, current);
+
+        let stats = LibraryStats {
+            totals,
+            formats,
+            bit_rates,
+            sample_rates,
+            growth,
+        };
+        self.cache.put(since, stats.clone());
+        Ok(stats)
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn bucket_bit_rates(counts: &[ValueCount]) -> Vec<BitRateBucket> {
+    let mut buckets: Vec<BitRateBucket> = BIT_RATE_BUCKETS
+        .iter()
+        .enumerate()
+        .map(|(i, &min)| BitRateBucket {
+            min,
+            max: BIT_RATE_BUCKETS.get(i + 1).copied(),
+            songs: 0,
+        })
+        .collect();
+    for count in counts {
+        let index = BIT_RATE_BUCKETS
+            .iter()
+            .rposition(|&min| count.value >= min)
+            .unwrap_or(0);
+        buckets[index].songs += count.songs;
+    }
+    buckets
+}
+
+/// 累计数量从当前总数倒推：某月末的累计 = 总数 - 之后各月的新增
+fn growth_points(
+    totals: &LibraryTotals,
+    additions: &[MonthlyAdditions],
+    since: NaiveDate,
+    current: NaiveDate,
+) -> Vec<GrowthPoint> {
+    let by_month: HashMap<NaiveDate, &MonthlyAdditions> = additions
+        .iter()
+        .map(|a| (first_of_month(a.month), a))
+        .collect();
+    let mut points = Vec::new();
+    let mut month = since;
+    while month <= current {
+        let added = by_month.get(&month);
+        points.push(GrowthPoint {
+            month,
+            songs_added: added.map_or(0, |a| a.songs),
+            albums_added: added.map_or(0, |a| a.albums),
+            total_songs: 0,
+            total_albums: 0,
+        });
+        month = month + Months::new(1);
+    }
+
+    let mut total_songs = totals.songs;
+    let mut total_albums = totals.albums;
+    for point in points.iter_mut().rev() {
+        point.total_songs = total_songs.max(0);
+        point.total_albums = total_albums.max(0);
+        total_songs -= point.songs_added;
+        total_albums -= point.albums_added;
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn count(value: i32, songs: i64) -> ValueCount {
+        ValueCount { value, songs }
+    }
+
+    #[test]
+    fn bit_rates_fall_into_half_open_buckets() {
+        let counts = [
+            count(96, 1),
+            count(128, 2),
+            count(320, 4),
+            count(912, 3),
+            count(1411, 5),
+        ];
+        let buckets = bucket_bit_rates(&counts);
+        let songs: Vec<(i32, Option<i32>, i64)> =
+            buckets.iter().map(|b| (b.min, b.max, b.songs)).collect();
+        assert_eq!(
+            songs,
+            [
+                (0, Some(128), 1),
+                (128, Some(192), 2),
+                (192, Some(256), 0),
+                (256, Some(320), 0),
+                (320, Some(500), 4),
+                (500, Some(1000), 3),
+                (1000, None, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn growth_fills_empty_months_and_accumulates_backwards() {
+        let totals = LibraryTotals {
+            songs: 30,
+            albums: 4,
+            ..Default::default()
+        };
+        let additions = [
+            MonthlyAdditions {
+                month: date(2026, 8, 1),
+                songs: 10,
+                albums: 1,
+            },
+            MonthlyAdditions {
+                month: date(2026, 10, 1),
+                songs: 5,
+                albums: 1,
+            },
+        ];
+        let points = growth_points(&totals, &additions, date(2026, 7, 1), date(2026, 10, 1));
+        let summary: Vec<(u32, i64, i64, i64)> = points
+            .iter()
+            .map(|p| {
+                (
+                    p.month.month(),
+                    p.songs_added,
+                    p.total_songs,
+                    p.total_albums,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [(7, 0, 15, 2), (8, 10, 25, 3), (9, 0, 25, 3), (10, 5, 30, 4)]
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use crate::query::dao::{ChartDao, ListeningReportDao};
+use crate::query::get_charts::ChartKind;
+use crate::query::QueryError;
+use chrono::NaiveDate;
+use model::listening_report::ListeningClockCell;
+use model::play_stats::{ChartEntry, RollupPeriod};
+use std::sync::Arc;
+
+/// 用户年度收听报告
+#[derive(Debug, Clone)]
+pub struct ListeningReport {
+    pub year: i32,
+    pub total_plays: i64,
+    pub total_minutes: i64,
+    pub top_artists: Vec<ChartEntry>,
+    pub top_albums: Vec<ChartEntry>,
+    pub top_songs: Vec<ChartEntry>,
+    pub top_genres: Vec<ChartEntry>,
+    /// 收听时钟热力图：7 行（周一到周日）x 24 列（小时）的播放次数
+    pub clock: Vec<Vec<i64>>,
+}
+
+#[derive(Clone)]
+pub struct GetListeningReport {
+    report_dao: Arc<dyn ListeningReportDao + Send + Sync>,
+    chart_dao: Arc<dyn ChartDao + Send + Sync>,
+}
+
+impl GetListeningReport {
+    pub fn new(
+        report_dao: Arc<dyn ListeningReportDao + Send + Sync>,
+        chart_dao: Arc<dyn ChartDao + Send + Sync>,
+    ) -> Self {
+        Self {
+            report_dao,
+            chart_dao,
+        }
+    }
+
+    /// 报告直接读取增量维护的收听时钟和按月汇总的播放次数，不扫描播放历史
+    pub async fn handle(
+        &self,
+        user_id: i64,
+        year: i32,
+        limit: i32,
+    ) -> Result<ListeningReport, QueryError> {
+        let (from, to) = match (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        ) {
+            (Some(from), Some(to)) => (from, to),
+            _ => {
+                return Err(QueryError::InvalidParameter(format!(
+                    "Invalid year: {}",
+                    year
+                )))
+            }
+        };
+
+        let cells = self.report_dao.get_listening_clock(user_id, year).await?;
+        let total_plays = cells.iter().map(|c| c.play_count).sum();
+        let total_seconds: i64 = cells.iter().map(|c| c.play_seconds).sum();
+
+        let top = |kind: ChartKind| {
+            self.chart_dao.get_top_items(
+                kind.item_kind(),
+                RollupPeriod::Month,
+                from,
+                to,
+                Some(user_id),
+                limit,
+            )
+        };
+        Ok(ListeningReport {
+            year,
+            total_plays,
+            total_minutes: total_seconds / 60,
+            top_artists: top(ChartKind::Artists).await?,
+            top_albums: top(ChartKind::Albums).await?,
+            top_songs: top(ChartKind::Songs).await?,
+            top_genres: top(ChartKind::Genres).await?,
+            clock: build_clock(&cells),
+        })
+    }
+}
+
+/// 把稀疏的格子展开成完整的 7 x 24 矩阵
+fn build_clock(cells: &[ListeningClockCell]) -> Vec<Vec<i64>> {
+    let mut clock = vec![vec![0; 24]; 7];
+    for cell in cells {
+        if let Some(slot) = clock
+            .get_mut(cell.weekday as usize)
+            .and_then(|row| row.get_mut(cell.hour as usize))
+        {
+            *slot += cell.play_count;
+        }
+    }
+    clock
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(weekday: i16, hour: i16, play_count: i64) -> ListeningClockCell {
+        ListeningClockCell {
+            weekday,
+            hour,
+            play_count,
+            play_seconds: play_count * 180,
+        }
+    }
+
+    #[test]
+    fn test_build_clock() {
+        let clock = build_clock(&[cell(0, 8, 3), cell(6, 23, 1)]);
+        assert_eq!(clock.len(), 7);
+        assert!(clock.iter().all(|row| row.len() == 24));
+        assert_eq!(clock[0][8], 3);
+        assert_eq!(clock[6][23], 1);
+        assert_eq!(clock.iter().flatten().sum::<i64>(), 4);
+    }
+
+    #[test]
+    fn test_build_clock_ignores_out_of_range_cells() {
+        let clock = build_clock(&[cell(7, 0, 5), cell(0, 24, 5)]);
+        assert_eq!(clock.iter().flatten().sum::<i64>(), 0);
+    }
+}
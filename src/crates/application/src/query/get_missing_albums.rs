@@ -0,0 +1,195 @@
+use crate::query::dao::{AlbumDao, ArtistDao};
+use crate::query::shared::{normalize_title, DiscographyCache, DiscographySource};
+use crate::query::QueryError;
+use chrono::{Duration, NaiveDateTime, Utc};
+use model::discography::{ArtistDiscography, ReleaseGroup};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 艺术家在 MusicBrainz 中有、库中没有的专辑
+#[derive(Debug, Clone)]
+pub struct MissingAlbums {
+    pub artist_id: i64,
+    pub mbz_artist_id: Option<String>,
+    /// 参与比较的发行组数
+    pub release_group_count: usize,
+    /// 按首发日期排序
+    pub missing: Vec<ReleaseGroup>,
+    pub fetched_at: NaiveDateTime,
+}
+
+#[derive(Clone)]
+pub struct GetMissingAlbums {
+    artist_dao: Arc<dyn ArtistDao + Send + Sync>,
+    album_dao: Arc<dyn AlbumDao + Send + Sync>,
+    cache: Arc<dyn DiscographyCache>,
+    source: Arc<dyn DiscographySource>,
+    /// 参与比较的发行组主类型（小写），如 album、ep
+    release_types: Vec<String>,
+    cache_ttl: Duration,
+}
+
+impl GetMissingAlbums {
+    pub fn new(
+        artist_dao: Arc<dyn ArtistDao + Send + Sync>,
+        album_dao: Arc<dyn AlbumDao + Send + Sync>,
+        cache: Arc<dyn DiscographyCache>,
+        source: Arc<dyn DiscographySource>,
+        release_types: Vec<String>,
+        cache_ttl: Duration,
+    ) -> Self {
+        Self {
+            artist_dao,
+            album_dao,
+            cache,
+            source,
+            release_types,
+            cache_ttl,
+        }
+    }
+
+    /// 缓存过期或 refresh 为 true 时重新从 MusicBrainz 获取
+    pub async fn handle(&self, artist_id: i64, refresh: bool) -> Result<MissingAlbums, QueryError> {
+        let artist = self
+            .artist_dao
+            .get_by_id(artist_id)
+            .await?
+            .ok_or_else(|| QueryError::NotFound(format!("Artist not found: {}", artist_id)))?;
+
+        let now = Utc::now().naive_utc();
+        let cached = match refresh {
+            true => None,
+            false => self
+                .cache
+                .get(artist_id)
+                .await?
+                .filter(|cached| now - cached.fetched_at < self.cache_ttl),
+        };
+        let discography = match cached {
+            Some(cached) => cached,
+            None => {
+                let discography = self
+                    .fetch(artist_id, artist.mbz_artist_id, &artist.name)
+                    .await?;
+                self.cache.save(&discography).await?;
+                discography
+            }
+        };
+
+        let albums: Vec<String> = self
+            .album_dao
+            .get_by_artist_id(artist_id)
+            .await?
+            .into_iter()
+            .map(|album| album.name)
+            .collect();
+        let candidates: Vec<ReleaseGroup> = discography
+            .release_groups
+            .into_iter()
+            .filter(|group| self.is_candidate(group))
+            .collect();
+        let release_group_count = candidates.len();
+        Ok(MissingAlbums {
+            artist_id,
+            mbz_artist_id: discography.mbz_artist_id,
+            release_group_count,
+            missing: missing_release_groups(candidates, &albums),
+            fetched_at: discography.fetched_at,
+        })
+    }
+
+    async fn fetch(
+        &self,
+        artist_id: i64,
+        mbz_artist_id: Option<String>,
+        name: &str,
+    ) -> Result<ArtistDiscography, QueryError> {
+        let external = |e: anyhow::Error| {
+            QueryError::ExecutionError(format!("MusicBrainz lookup failed: {:#}", e))
+        };
+        let mbz_artist_id = match mbz_artist_id {
+            Some(id) => Some(id),
+            None => self.source.find_artist(name).await.map_err(external)?,
+        };
+        let release_groups = match &mbz_artist_id {
+            Some(id) => self.source.release_groups(id).await.map_err(external)?,
+            None => Vec::new(),
+        };
+        Ok(ArtistDiscography {
+            artist_id,
+            mbz_artist_id,
+            release_groups,
+            fetched_at: Utc::now().naive_utc(),
+        })
+    }
+
+    /// 合辑、现场等有次要类型的发行组不参与比较
+    fn is_candidate(&self, group: &ReleaseGroup) -> bool {
+        group.secondary_types.is_empty()
+            && group.primary_type.as_deref().is_some_and(|kind| {
+                self.release_types
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(kind))
+            })
+    }
+}
+
+/// 按规范化后的标题比较
+fn missing_release_groups(groups: Vec<ReleaseGroup>, album_names: &[String]) -> Vec<ReleaseGroup> {
+    let owned: HashSet<String> = album_names
+        .iter()
+        .map(|name| normalize_title(name))
+        .collect();
+    let mut missing: Vec<ReleaseGroup> = groups
+        .into_iter()
+        .filter(|group| !owned.contains(&normalize_title(&group.title)))
+        .collect();
+    // 没有日期的排在最后
+    missing.sort_by_key(|group| {
+        (
+            group.first_release_date.is_none(),
+            group.first_release_date.clone(),
+            group.title.clone(),
+        )
+    });
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(title: &str, date: Option<&str>) -> ReleaseGroup {
+        ReleaseGroup {
+            mbz_id: title.to_string(),
+            title: title.to_string(),
+            primary_type: Some("Album".to_string()),
+            secondary_types: Vec::new(),
+            first_release_date: date.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn ignores_case_punctuation_and_edition_suffixes() {
+        assert_eq!(normalize_title("OK Computer (Remastered)"), "okcomputer");
+        assert_eq!(normalize_title("Kid A [Deluxe Edition]"), "kida");
+        assert_eq!(normalize_title("Hail to the Thief"), "hailtothethief");
+        assert_eq!(normalize_title("(What's the Story)"), "(what's the story)");
+    }
+
+    #[test]
+    fn lists_unowned_groups_by_release_date() {
+        let groups = vec![
+            group("Amnesiac", Some("2001-06-04")),
+            group("Kid A", Some("2000-10-02")),
+            group("Unreleased Demos", None),
+            group("Pablo Honey", Some("1993-02-22")),
+        ];
+        let owned = vec!["KID A (Deluxe)".to_string()];
+        let titles: Vec<String> = missing_release_groups(groups, &owned)
+            .into_iter()
+            .map(|group| group.title)
+            .collect();
+        assert_eq!(titles, ["Pablo Honey", "Amnesiac", "Unreleased Demos"]);
+    }
+}
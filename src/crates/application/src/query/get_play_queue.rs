@@ -14,12 +14,15 @@ impl GetPlayQueue {
         Self { play_queue_dao }
     }
 
-    /// 根据用户 ID 获取播放队列（包含歌曲详情）
+    /// 根据用户 ID 获取播放队列（包含歌曲详情），`client_id` 为空表示共享队列
     pub async fn get_by_user_id(
         &self,
         user_id: i64,
+        client_id: &str,
         username: &str,
     ) -> Result<Option<PlayQueue>, QueryError> {
-        self.play_queue_dao.get_by_user_id(user_id, username).await
+        self.play_queue_dao
+            .get_by_user_id(user_id, client_id, username)
+            .await
     }
 }
@@ -1,8 +1,26 @@
 use crate::query::dao::PlaylistDao;
 use crate::query::QueryError;
 use model::playlist::{Playlist, PlaylistSummary};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+/// 播放列表列表的排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaylistOrder {
+    /// 最近修改的在前
+    #[default]
+    Changed,
+    /// 按名称，不区分大小写
+    Name,
+}
+
+/// 某个标签下的播放列表数量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistTagCount {
+    pub tag: String,
+    pub playlists: usize,
+}
+
 /// 获取播放列表查询服务
 #[derive(Clone)]
 pub struct GetPlaylist {
@@ -19,11 +37,135 @@ impl GetPlaylist {
         self.playlist_dao
             .get_by_id(playlist_id)
             .await?
-            .ok_or_else(|| QueryError::InvalidInput(format!("Playlist not found: {}", playlist_id)))
+            .ok_or_else(|| QueryError::NotFound(format!("Playlist not found: {}", playlist_id)))
     }
 
     /// 根据用户 ID 获取播放列表列表（基本信息）
     pub async fn get_by_owner_id(&self, owner_id: i64) -> Result<Vec<PlaylistSummary>, QueryError> {
         self.playlist_dao.get_by_owner_id(owner_id).await
     }
+
+    /// 获取用户可以编辑的播放列表（自己的和协作的）
+    pub async fn get_editable_by(&self, user_id: i64) -> Result<Vec<PlaylistSummary>, QueryError> {
+        self.playlist_dao.get_editable_by(user_id).await
+    }
+
+    /// 用户可以编辑的播放列表，tag 不为空时只返回带该标签的
+    pub async fn list_editable(
+        &self,
+        user_id: i64,
+        tag: Option<&str>,
+        order: PlaylistOrder,
+    ) -> Result<Vec<PlaylistSummary>, QueryError> {
+        let mut playlists = self.playlist_dao.get_editable_by(user_id).await?;
+        if let Some(tag) = tag {
+            playlists.retain(|playlist| has_tag(playlist, tag));
+        }
+        sort_playlists(&mut playlists, order);
+        Ok(playlists)
+    }
+
+    /// 用户可以编辑的播放列表中用到的标签，按名称排序
+    /// 最近删除的播放列表，最近删除的在前
+    pub async fn get_deleted(&self, owner_id: i64) -> Result<Vec<PlaylistSummary>, QueryError> {
+        self.playlist_dao.get_deleted_by_owner(owner_id).await
+    }
+
+    pub async fn get_tags(&self, user_id: i64) -> Result<Vec<PlaylistTagCount>, QueryError> {
+        let playlists = self.playlist_dao.get_editable_by(user_id).await?;
+        Ok(count_tags(&playlists))
+    }
+}
+
+/// 标签比较不区分大小写
+pub fn has_tag(playlist: &PlaylistSummary, tag: &str) -> bool {
+    let tag = tag.trim().to_lowercase();
+    playlist.tags.iter().any(|t| t.to_lowercase() == tag)
+}
+
+fn sort_playlists(playlists: &mut [PlaylistSummary], order: PlaylistOrder) {
+    match order {
+        PlaylistOrder::Changed => {
+            playlists.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(a.id.cmp(&b.id)))
+        }
+        PlaylistOrder::Name => playlists.sort_by_cached_key(|p| (p.name.to_lowercase(), p.id)),
+    }
+}
+
+/// 不同播放列表中大小写不同的同一标签合并计数，显示第一次出现时的写法
+fn count_tags(playlists: &[PlaylistSummary]) -> Vec<PlaylistTagCount> {
+    let mut counts: BTreeMap<String, PlaylistTagCount> = BTreeMap::new();
+    for tag in playlists.iter().flat_map(|p| p.tags.iter()) {
+        counts
+            .entry(tag.to_lowercase())
+            .or_insert_with(|| PlaylistTagCount {
+                tag: tag.clone(),
+                playlists: 0,
+            })
+            .playlists += 1;
+    }
+    counts.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(id: i64, name: &str, updated_at: i64, tags: &[&str]) -> PlaylistSummary {
+        PlaylistSummary {
+            id,
+            name: name.to_string(),
+            comment: None,
+            duration: 0,
+            song_count: 0,
+            owner_name: "alice".to_string(),
+            owner_id: 1,
+            public: false,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created_at: 0,
+            updated_at,
+            deleted_at: None,
+        }
+    }
+
+    fn names(playlists: &[PlaylistSummary]) -> Vec<&str> {
+        playlists.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sorts_by_name_or_recent_change() {
+        let mut playlists = vec![
+            summary(1, "road trip", 30, &[]),
+            summary(2, "Ambient", 10, &[]),
+            summary(3, "Workout", 20, &[]),
+        ];
+        sort_playlists(&mut playlists, PlaylistOrder::Name);
+        assert_eq!(names(&playlists), ["Ambient", "road trip", "Workout"]);
+        sort_playlists(&mut playlists, PlaylistOrder::Changed);
+        assert_eq!(names(&playlists), ["road trip", "Workout", "Ambient"]);
+    }
+
+    #[test]
+    fn tags_match_and_count_case_insensitively() {
+        let playlists = vec![
+            summary(1, "a", 0, &["Chill", "Work"]),
+            summary(2, "b", 0, &["chill"]),
+            summary(3, "c", 0, &[]),
+        ];
+        assert!(has_tag(&playlists[1], " CHILL "));
+        assert!(!has_tag(&playlists[2], "chill"));
+        assert_eq!(
+            count_tags(&playlists),
+            [
+                PlaylistTagCount {
+                    tag: "Chill".to_string(),
+                    playlists: 2,
+                },
+                PlaylistTagCount {
+                    tag: "Work".to_string(),
+                    playlists: 1,
+                },
+            ]
+        );
+    }
 }
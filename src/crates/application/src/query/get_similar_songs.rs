@@ -31,7 +31,7 @@ impl GetSimilarSongs {
             .map_err(|e| QueryError::ExecutionError(e.to_string()))?;
 
         if input_artist.is_none() {
-            return Err(QueryError::InvalidInput(format!(
+            return Err(QueryError::NotFound(format!(
                 "Artist not found: {}",
                 artist_id
             )));
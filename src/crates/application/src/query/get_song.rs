@@ -22,7 +22,7 @@ impl GetSong {
 
         match audio_file {
             Some(audio_file) => Ok(audio_file),
-            None => Err(QueryError::InvalidInput(format!(
+            None => Err(QueryError::NotFound(format!(
                 "Song not found: {}",
                 song_id
             ))),
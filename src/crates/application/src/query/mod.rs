@@ -10,8 +10,16 @@ pub mod get_album_list;
 pub mod get_artist;
 pub mod get_artist_info;
 pub mod get_artist_list;
+pub mod get_bookmarks;
+pub mod get_charts;
 pub mod get_cover_art;
+pub mod get_feed;
+pub mod get_duplicate_albums;
 pub mod get_genres;
+pub mod get_home;
+pub mod get_library_stats;
+pub mod get_listening_report;
+pub mod get_missing_albums;
 pub mod get_music_folders;
 pub mod get_play_queue;
 pub mod get_playlist;
@@ -34,6 +42,9 @@ pub enum QueryError {
     InvalidParameter(String),
     #[error("Not found: {0}")]
     NotFound(String),
+    /// 请求的功能或类型还没有实现
+    #[error("Not supported: {0}")]
+    NotSupported(String),
     #[error("Execution error: {0}")]
     ExecutionError(String),
     #[error("Database error: {0}")]
@@ -1,4 +1,6 @@
 use crate::error::AppError;
+use crate::query::QueryError;
+use model::discography::{ArtistDiscography, ReleaseGroup};
 
 /// Token 生成服务 trait（用于生成封面图片访问 token）
 /// 应用服务层通过此 trait 生成 token，不依赖具体实现
@@ -6,3 +8,37 @@ pub trait CoverArtTokenService: Send + Sync {
     /// 为指定的封面图片 ID 生成访问 token
     fn issue_cover_art_token(&self, cover_art_id: String) -> Result<String, AppError>;
 }
+
+/// 外部作品目录（MusicBrainz），用于比较库中缺少的专辑
+#[async_trait::async_trait]
+pub trait DiscographySource: Send + Sync {
+    /// 按名称查找艺术家，返回 MusicBrainz 艺术家 ID
+    async fn find_artist(&self, name: &str) -> anyhow::Result<Option<String>>;
+    async fn release_groups(&self, mbz_artist_id: &str) -> anyhow::Result<Vec<ReleaseGroup>>;
+}
+
+/// 作品目录的缓存
+#[async_trait::async_trait]
+pub trait DiscographyCache: Send + Sync {
+    async fn get(&self, artist_id: i64) -> Result<Option<ArtistDiscography>, QueryError>;
+    async fn save(&self, discography: &ArtistDiscography) -> Result<(), QueryError>;
+}
+
+/// 用于比较专辑、歌曲标题：忽略大小写、标点和括号中的版本说明（Deluxe Edition、Remastered 等）
+pub fn normalize_title(title: &str) -> String {
+    let mut normalized = String::new();
+    let mut depth = 0usize;
+    for c in title.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            c if depth == 0 && c.is_alphanumeric() => normalized.extend(c.to_lowercase()),
+            _ => {}
+        }
+    }
+    // 整个标题都在括号中时按原标题比较
+    if normalized.is_empty() {
+        return title.to_lowercase();
+    }
+    normalized
+}
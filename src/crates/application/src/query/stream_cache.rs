@@ -25,6 +25,10 @@ pub trait StreamCacheConfig: Send + Sync {
     fn default_bit_rate(&self) -> i32;
     /// 是否为无损格式
     fn is_lossless(&self, format: &str) -> bool;
+    /// 转码为该格式时是否在输出中写入标签和封面
+    fn embeds_metadata(&self, _format: &str) -> bool {
+        false
+    }
 }
 
 /// 流媒体缓存 trait
@@ -1,21 +1,31 @@
+use crate::command::media_parse::{MediaReader, StorageClientFactory};
 use crate::query::dao::AudioFileDao;
+use crate::query::dto::cover_art::audio_file_cover_art_id;
+use crate::query::get_cover_art::{CoverOutputFormat, GetCoverArt};
 use crate::query::stream_cache::{
     generate_cache_key, generate_raw_cache_key, StreamCache, StreamCacheConfig, StreamCacheData,
 };
 use crate::query::QueryError;
 use bytes::Bytes;
-use domain::transcoding::{TranscodingError, TranscodingStreamer};
+use domain::transcoding::{StreamTags, TranscodingError, TranscodingStreamer};
+use domain::value::MediaPath;
 use futures::Stream;
 use std::pin::Pin;
 use std::sync::Arc;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
+/// 写入转码输出的封面尺寸
+const EMBEDDED_COVER_SIZE: u32 = 500;
+
 /// 流媒体信息（用于 stream API）
 #[derive(Debug, Clone)]
 pub struct StreamInfo {
+    pub id: i64,
     /// 文件路径
     pub path: String,
+    /// 文件在存储中的位置，不在本地的文件通过存储客户端读取
+    pub source: MediaPath,
     /// 文件大小（字节）
     pub size: i64,
     /// 文件后缀（如 mp3, flac）
@@ -26,9 +36,15 @@ pub struct StreamInfo {
     pub duration: i64,
     /// MIME 类型
     pub content_type: String,
+    /// 转码时写入输出的标签，不含封面
+    pub tags: StreamTags,
 }
 
 impl StreamInfo {
+    pub fn is_local(&self) -> bool {
+        matches!(self.source.protocol.as_str(), "local" | "")
+    }
+
     /// 根据文件后缀获取 MIME 类型
     pub fn mime_type_from_suffix(suffix: &str) -> String {
         match suffix.to_lowercase().as_str() {
@@ -62,6 +78,8 @@ pub struct StreamRequest {
     pub time_offset: Option<i32>,
     /// 是否估算 Content-Length
     pub estimate_content_length: bool,
+    /// 用户或播放器的比特率上限（kbps），源文件超过时强制转码，raw 和无损格式也不例外
+    pub bit_rate_cap: Option<i32>,
 }
 
 /// 转码决策结果
@@ -79,6 +97,8 @@ pub struct TranscodeDecision {
     pub cache_key: String,
     /// 估算的输出大小（字节），用于设置 Content-Length
     pub estimated_size: Option<u64>,
+    /// 在转码输出中写入标签和封面
+    pub embed_metadata: bool,
 }
 
 /// 流媒体响应数据
@@ -100,6 +120,8 @@ pub struct StreamMedia {
     cache: Option<Arc<dyn StreamCache + Send + Sync>>,
     config: Option<Arc<dyn StreamCacheConfig + Send + Sync>>,
     transcoder: Option<Arc<dyn TranscodingStreamer + Send + Sync>>,
+    storage: Option<Arc<dyn StorageClientFactory>>,
+    cover_art: Option<Arc<GetCoverArt>>,
 }
 
 impl StreamMedia {
@@ -109,6 +131,8 @@ impl StreamMedia {
             cache: None,
             config: None,
             transcoder: None,
+            storage: None,
+            cover_art: None,
         }
     }
 
@@ -127,6 +151,17 @@ impl StreamMedia {
         self
     }
 
+    pub fn with_storage(mut self, storage: Arc<dyn StorageClientFactory>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// 用于在转码输出中写入封面，未设置时只写标签
+    pub fn with_cover_art(mut self, cover_art: Arc<GetCoverArt>) -> Self {
+        self.cover_art = Some(cover_art);
+        self
+    }
+
     /// 获取流媒体信息
     pub async fn get_stream_info(&self, request: &StreamRequest) -> Result<StreamInfo, QueryError> {
         let audio_file = self
@@ -142,16 +177,43 @@ impl StreamMedia {
         } else {
             audio_file.path.clone()
         };
+        let source = MediaPath {
+            protocol: audio_file
+                .path
+                .split_once("://")
+                .map_or("local", |(protocol, _)| protocol)
+                .to_string(),
+            path: audio_file.path.clone(),
+        };
 
         let content_type = StreamInfo::mime_type_from_suffix(&audio_file.suffix);
+        let tags = StreamTags {
+            title: audio_file.title,
+            artist: audio_file.artist.name,
+            album: audio_file.album,
+            album_artist: audio_file
+                .album_artists
+                .into_iter()
+                .next()
+                .map(|artist| artist.name)
+                .unwrap_or_default(),
+            track_number: Some(audio_file.track_number),
+            disc_number: Some(audio_file.disc_number),
+            year: audio_file.year,
+            genre: audio_file.genre.map(|genre| genre.name),
+            cover: None,
+        };
 
         Ok(StreamInfo {
+            id: audio_file.id,
             path,
+            source,
             size: audio_file.size,
             suffix: audio_file.suffix,
             bit_rate: audio_file.bit_rate,
             duration: audio_file.duration,
             content_type,
+            tags,
         })
     }
 
@@ -162,10 +224,14 @@ impl StreamMedia {
         info: &StreamInfo,
     ) -> TranscodeDecision {
         let config = self.config.as_ref();
+        // 源文件超过上限时必须转码
+        let cap = request
+            .bit_rate_cap
+            .filter(|&cap| cap > 0 && info.bit_rate > cap);
 
         // 如果请求 raw 格式，不转码
         if let Some(ref format) = request.format {
-            if format.eq_ignore_ascii_case("raw") {
+            if format.eq_ignore_ascii_case("raw") && cap.is_none() {
                 log::debug!(
                     "[Transcode] id={} raw format requested, skipping transcode (source: {}, {}kbps)",
                     request.id, info.suffix, info.bit_rate
@@ -177,6 +243,7 @@ impl StreamMedia {
                     content_type: info.content_type.clone(),
                     cache_key: generate_raw_cache_key(request.id, &info.suffix),
                     estimated_size: Some(info.size as u64),
+                    embed_metadata: false,
                 };
             }
         }
@@ -189,7 +256,7 @@ impl StreamMedia {
             .map(|f| f.eq_ignore_ascii_case("auto"))
             .unwrap_or(true); // 未指定也视为 auto
 
-        let target_format = if is_auto_or_empty {
+        let mut target_format = if is_auto_or_empty {
             // 使用服务器默认格式，如果没有配置则使用源格式
             config
                 .map(|c| c.default_format())
@@ -198,6 +265,19 @@ impl StreamMedia {
             // 使用请求指定的格式
             request.format.clone().unwrap()
         };
+        // 超过上限时 raw 或无损目标无法限制比特率，改用有损格式
+        if cap.is_some() {
+            let lossy = |format: &str| {
+                !format.eq_ignore_ascii_case("raw")
+                    && !config.map(|c| c.is_lossless(format)).unwrap_or(false)
+            };
+            if !lossy(&target_format) {
+                target_format = config
+                    .map(|c| c.default_format())
+                    .filter(|format| lossy(format))
+                    .unwrap_or_else(|| "mp3".to_string());
+            }
+        }
 
         // 检查源文件和目标格式的无损属性
         let source_is_lossless = config
@@ -221,6 +301,7 @@ impl StreamMedia {
                 content_type: info.content_type.clone(),
                 cache_key: generate_raw_cache_key(request.id, &info.suffix),
                 estimated_size: Some(info.size as u64),
+                embed_metadata: false,
             };
         }
 
@@ -244,6 +325,7 @@ impl StreamMedia {
                     })
                 })
                 .unwrap_or(info.bit_rate)
+                .min(cap.unwrap_or(i32::MAX))
         };
 
         // 判断是否需要转码
@@ -258,8 +340,18 @@ impl StreamMedia {
             info.content_type.clone()
         };
 
+        let embed_metadata = needs_transcoding
+            && config
+                .map(|c| c.embeds_metadata(&target_format))
+                .unwrap_or(false);
         let cache_key = if needs_transcoding {
-            generate_cache_key(request.id, &target_format, target_bit_rate)
+            let key = generate_cache_key(request.id, &target_format, target_bit_rate);
+            // 写入标签的输出与不写的分开缓存，修改配置后不会返回旧的输出
+            if embed_metadata {
+                format!("{}_tagged", key)
+            } else {
+                key
+            }
         } else {
             generate_raw_cache_key(request.id, &info.suffix)
         };
@@ -293,6 +385,7 @@ impl StreamMedia {
             content_type,
             cache_key,
             estimated_size,
+            embed_metadata,
         }
     }
 
@@ -358,16 +451,45 @@ impl StreamMedia {
         })
     }
 
-    /// 读取原始文件
-    async fn read_raw_file(&self, info: &StreamInfo) -> Result<(Bytes, String), QueryError> {
-        let mut file = File::open(&info.path)
+    /// 从 offset 开始读取不在本地的源文件，远程存储会预读后续数据
+    pub async fn open_source(
+        &self,
+        info: &StreamInfo,
+        offset: u64,
+    ) -> Result<MediaReader, QueryError> {
+        let storage = self.storage.as_ref().ok_or_else(|| {
+            QueryError::ExecutionError(format!(
+                "Storage not configured for {}",
+                info.source.protocol
+            ))
+        })?;
+        let client = storage
+            .create(&info.source)
+            .await
+            .map_err(|e| QueryError::ExecutionError(e.to_string()))?;
+        client
+            .open(&info.source, offset)
             .await
-            .map_err(|e| QueryError::ExecutionError(format!("Failed to open file: {}", e)))?;
+            .map_err(|e| QueryError::ExecutionError(format!("Failed to open file: {}", e)))
+    }
 
+    /// 读取原始文件
+    async fn read_raw_file(&self, info: &StreamInfo) -> Result<(Bytes, String), QueryError> {
         let mut buffer = Vec::with_capacity(info.size as usize);
-        file.read_to_end(&mut buffer)
-            .await
-            .map_err(|e| QueryError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        if info.is_local() {
+            let mut file = File::open(&info.path)
+                .await
+                .map_err(|e| QueryError::ExecutionError(format!("Failed to open file: {}", e)))?;
+            file.read_to_end(&mut buffer)
+                .await
+                .map_err(|e| QueryError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        } else {
+            self.open_source(info, 0)
+                .await?
+                .read_to_end(&mut buffer)
+                .await
+                .map_err(|e| QueryError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        }
 
         Ok((Bytes::from(buffer), info.content_type.clone()))
     }
@@ -393,18 +515,7 @@ impl StreamMedia {
         let start_time = std::time::Instant::now();
 
         // 使用 TranscodingStreamer 的 create_stream 并收集所有数据
-        let mut stream = transcoder
-            .create_stream(
-                info.path.clone(),
-                decision.target_format.clone(),
-                decision.target_bit_rate,
-                std::collections::HashMap::new(),
-            )
-            .await
-            .map_err(|e: TranscodingError| {
-                log::error!("[Transcode] Failed to create stream: {}", e);
-                QueryError::ExecutionError(e.to_string())
-            })?;
+        let mut stream = self.start_transcoder(transcoder, info, decision).await?;
 
         // 收集流数据
         use futures::StreamExt;
@@ -460,18 +571,7 @@ impl StreamMedia {
             decision.target_bit_rate
         );
 
-        let stream = transcoder
-            .create_stream(
-                info.path.clone(),
-                decision.target_format.clone(),
-                decision.target_bit_rate,
-                std::collections::HashMap::new(),
-            )
-            .await
-            .map_err(|e: TranscodingError| {
-                log::error!("[Transcode] Failed to create stream: {}", e);
-                QueryError::ExecutionError(e.to_string())
-            })?;
+        let stream = self.start_transcoder(transcoder, info, decision).await?;
 
         Ok(TranscodeStream {
             inner: stream,
@@ -485,6 +585,65 @@ impl StreamMedia {
         })
     }
 
+    /// 本地文件由转码器直接打开，其余文件通过存储客户端读取后传给转码器
+    async fn start_transcoder(
+        &self,
+        transcoder: &Arc<dyn TranscodingStreamer + Send + Sync>,
+        info: &StreamInfo,
+        decision: &TranscodeDecision,
+    ) -> Result<TranscoderOutput, QueryError> {
+        let tags = if decision.embed_metadata {
+            Some(self.stream_tags(info).await)
+        } else {
+            None
+        };
+        let result = if info.is_local() {
+            transcoder
+                .create_stream(
+                    info.path.clone(),
+                    decision.target_format.clone(),
+                    decision.target_bit_rate,
+                    std::collections::HashMap::new(),
+                    tags,
+                )
+                .await
+        } else {
+            transcoder
+                .create_stream_from_reader(
+                    self.open_source(info, 0).await?,
+                    decision.target_format.clone(),
+                    decision.target_bit_rate,
+                    std::collections::HashMap::new(),
+                    tags,
+                )
+                .await
+        };
+        result.map_err(|e: TranscodingError| {
+            log::error!("[Transcode] Failed to create stream: {}", e);
+            QueryError::ExecutionError(e.to_string())
+        })
+    }
+
+    /// 歌曲的标签和解析到的封面，封面读取失败时只写标签
+    async fn stream_tags(&self, info: &StreamInfo) -> StreamTags {
+        let mut tags = info.tags.clone();
+        if let Some(cover_art) = &self.cover_art {
+            let artwork_id = audio_file_cover_art_id(info.id);
+            match cover_art
+                .get(
+                    &artwork_id,
+                    Some(EMBEDDED_COVER_SIZE),
+                    CoverOutputFormat::Jpeg,
+                )
+                .await
+            {
+                Ok(cover) => tags.cover = cover.map(|cover| cover.data.to_vec()),
+                Err(e) => log::warn!("[Transcode] Failed to load cover for {}: {}", info.id, e),
+            }
+        }
+        tags
+    }
+
     /// 检查缓存并返回缓存数据（如果有）
     pub async fn get_cached_data(&self, cache_key: &str) -> Option<StreamData> {
         if let Some(ref cache) = self.cache {
@@ -506,9 +665,11 @@ impl StreamMedia {
     }
 }
 
+type TranscoderOutput = Box<dyn Stream<Item = Result<Vec<u8>, TranscodingError>> + Unpin + Send>;
+
 /// 转码流包装器，支持边转码边返回，同时收集数据用于缓存
 pub struct TranscodeStream {
-    inner: Box<dyn Stream<Item = Result<Vec<u8>, TranscodingError>> + Unpin + Send>,
+    inner: TranscoderOutput,
     pub content_type: String,
     cache_key: String,
     collected_data: Vec<u8>,
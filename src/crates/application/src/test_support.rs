@@ -0,0 +1,363 @@
+//! 服务测试共用的内存仓储与事件总线
+
+use crate::command::album::AlbumNameNormalizer;
+use crate::command::artist::ArtistNameNormalizer;
+use crate::error::AppError;
+use crate::event::event_bus::{EventBus, EventEnvelope, Handler};
+use async_trait::async_trait;
+use domain::album::{Album, AlbumError, AlbumRepository};
+use domain::artist::{Artist, ArtistError, ArtistRepository};
+use domain::audio_file::{AudioFile, AudioFileError, AudioFileMeta, AudioFileRepository};
+use domain::value::{
+    AlbumId, ArtistId, AudioFileId, GenreId, LibraryId, MediaPath, Participant, ParticipantRole,
+    ParticipantWorkType, ReplayGain,
+};
+use model::album_stats::{AlbumStats, AlbumStatsAdjustment, AlbumStatsRepository};
+use model::ModelError;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 标签只有标题的歌曲，时长 180 秒、大小 1000 字节，已取出创建事件
+pub(crate) fn song(id: i64, album_id: Option<i64>, artist_ids: &[i64]) -> AudioFile {
+    let meta = AudioFileMeta {
+        title: format!("Song {}", id),
+        track_number: Some(1),
+        disc_number: Some(1),
+        disc_subtitle: None,
+        year: None,
+        date: None,
+        original_year: None,
+        original_date: None,
+        release_year: None,
+        release_date: None,
+        compilation: false,
+        bpm: None,
+        comment: None,
+        mbz_track_id: None,
+        replay_gain: ReplayGain::default(),
+        explicit: false,
+        work: None,
+        movement_name: None,
+        movement_number: None,
+        movement_count: None,
+        chapters: Vec::new(),
+    };
+    let mut audio_file = AudioFile::new(
+        AudioFileId::from(id),
+        LibraryId::from(1),
+        MediaPath::new("local".to_string(), format!("/music/{}.flac", id)),
+        1000,
+        "flac".to_string(),
+        None,
+        180,
+        900,
+        16,
+        44100,
+        2,
+        false,
+        meta,
+    );
+    if let Some(album_id) = album_id {
+        audio_file.bind_to_album(AlbumId::from(album_id)).unwrap();
+    }
+    for artist_id in artist_ids {
+        audio_file
+            .add_participant(participant(*artist_id, id, ParticipantWorkType::Artist))
+            .unwrap();
+    }
+    audio_file.take_events();
+    audio_file
+}
+
+pub(crate) fn participant(
+    artist_id: i64,
+    work_id: i64,
+    work_type: ParticipantWorkType,
+) -> Participant {
+    Participant::new(
+        ArtistId::from(artist_id),
+        ParticipantRole::Artist,
+        None,
+        work_id,
+        work_type,
+    )
+}
+
+/// 名称转为小写作为 sort_name
+pub(crate) struct LowercaseNormalizer;
+
+impl AlbumNameNormalizer for LowercaseNormalizer {
+    fn normalize(&self, album_name: &String) -> String {
+        album_name.to_lowercase()
+    }
+}
+
+impl ArtistNameNormalizer for LowercaseNormalizer {
+    fn normalize(&self, artist_name: &String) -> String {
+        artist_name.to_lowercase()
+    }
+
+    fn index_name(&self, artist_name: &str) -> String {
+        artist_name.to_lowercase()
+    }
+
+    fn index_initial(&self, index_name: &str, _romanize: bool) -> Option<char> {
+        index_name.chars().next()
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MemoryAudioFileRepository {
+    pub files: Mutex<HashMap<i64, AudioFile>>,
+}
+
+impl MemoryAudioFileRepository {
+    pub fn get(&self, id: i64) -> Option<AudioFile> {
+        self.files.lock().unwrap().get(&id).cloned()
+    }
+
+    fn filter(&self, predicate: impl Fn(&AudioFile) -> bool) -> Vec<AudioFile> {
+        let mut files: Vec<AudioFile> = self
+            .files
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|f| predicate(f))
+            .cloned()
+            .collect();
+        files.sort_by_key(|f| f.id.as_i64());
+        files
+    }
+}
+
+#[async_trait]
+impl AudioFileRepository for MemoryAudioFileRepository {
+    async fn save(&self, audio_file: AudioFile) -> Result<AudioFile, AudioFileError> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(audio_file.id.as_i64(), audio_file.clone());
+        Ok(audio_file)
+    }
+
+    async fn find_by_id(&self, id: &AudioFileId) -> Result<Option<AudioFile>, AudioFileError> {
+        Ok(self.get(id.as_i64()))
+    }
+
+    async fn find_by_path(&self, path: &MediaPath) -> Result<Option<AudioFile>, AudioFileError> {
+        Ok(self.filter(|f| &f.path == path).into_iter().next())
+    }
+
+    async fn find_by_album(&self, album_id: &AlbumId) -> Result<Vec<AudioFile>, AudioFileError> {
+        Ok(self.filter(|f| f.album.as_ref() == Some(album_id)))
+    }
+
+    async fn find_by_artist(&self, artist_id: &ArtistId) -> Result<Vec<AudioFile>, AudioFileError> {
+        Ok(self.filter(|f| f.artist.as_ref() == Some(artist_id)))
+    }
+
+    async fn find_by_participant(
+        &self,
+        artist_id: &ArtistId,
+    ) -> Result<Vec<AudioFile>, AudioFileError> {
+        Ok(self.filter(|f| f.participants.iter().any(|p| &p.artist_id == artist_id)))
+    }
+
+    async fn find_by_genre(&self, genre_id: &GenreId) -> Result<Vec<AudioFile>, AudioFileError> {
+        Ok(self.filter(|f| f.genres.contains(genre_id)))
+    }
+
+    async fn delete(&self, id: &AudioFileId) -> Result<(), AudioFileError> {
+        self.files.lock().unwrap().remove(&id.as_i64());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MemoryAlbumRepository {
+    pub albums: Mutex<HashMap<i64, Album>>,
+}
+
+impl MemoryAlbumRepository {
+    pub fn get(&self, id: i64) -> Option<Album> {
+        self.albums.lock().unwrap().get(&id).cloned()
+    }
+}
+
+#[async_trait]
+impl AlbumRepository for MemoryAlbumRepository {
+    async fn find_by_sort_name(&self, sort_name: &String) -> Result<Option<Album>, AlbumError> {
+        Ok(self
+            .albums
+            .lock()
+            .unwrap()
+            .values()
+            .find(|a| &a.sort_name == sort_name)
+            .cloned())
+    }
+
+    async fn by_id(&self, album_id: AlbumId) -> Result<Option<Album>, AlbumError> {
+        Ok(self.get(album_id.as_i64()))
+    }
+
+    async fn save(&self, album: Album) -> Result<Album, AlbumError> {
+        self.albums
+            .lock()
+            .unwrap()
+            .insert(album.id.as_i64(), album.clone());
+        Ok(album)
+    }
+
+    async fn delete(&self, album_id: AlbumId) -> Result<(), AlbumError> {
+        self.albums.lock().unwrap().remove(&album_id.as_i64());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MemoryArtistRepository {
+    pub artists: Mutex<HashMap<i64, Artist>>,
+}
+
+impl MemoryArtistRepository {
+    pub fn get(&self, id: i64) -> Option<Artist> {
+        self.artists.lock().unwrap().get(&id).cloned()
+    }
+}
+
+#[async_trait]
+impl ArtistRepository for MemoryArtistRepository {
+    async fn find_by_sort_name(&self, sort_name: &String) -> Result<Option<Artist>, ArtistError> {
+        Ok(self
+            .artists
+            .lock()
+            .unwrap()
+            .values()
+            .find(|a| &a.sort_name == sort_name)
+            .cloned())
+    }
+
+    async fn save(&self, artist: Artist) -> Result<Artist, ArtistError> {
+        self.artists
+            .lock()
+            .unwrap()
+            .insert(artist.id.as_i64(), artist.clone());
+        Ok(artist)
+    }
+
+    async fn delete(&self, artist_id: ArtistId) -> Result<(), ArtistError> {
+        self.artists.lock().unwrap().remove(&artist_id.as_i64());
+        Ok(())
+    }
+
+    async fn by_id(&self, id: ArtistId) -> Result<Option<Artist>, ArtistError> {
+        Ok(self.get(id.as_i64()))
+    }
+}
+
+/// 与数据库实现一样按增量更新，没有记录时从 0 开始
+#[derive(Default)]
+pub(crate) struct MemoryAlbumStatsRepository {
+    pub stats: Mutex<HashMap<i64, AlbumStats>>,
+}
+
+impl MemoryAlbumStatsRepository {
+    pub fn song_count(&self, album_id: i64) -> Option<i32> {
+        self.stats
+            .lock()
+            .unwrap()
+            .get(&album_id)
+            .map(|s| s.song_count)
+    }
+}
+
+#[async_trait]
+impl AlbumStatsRepository for MemoryAlbumStatsRepository {
+    async fn adjust_stats(&self, adjustment: AlbumStatsAdjustment) -> Result<(), ModelError> {
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats
+            .entry(adjustment.album_id.as_i64())
+            .or_insert_with(|| AlbumStats {
+                album_id: adjustment.album_id.clone(),
+                duration: 0,
+                size: 0,
+                song_count: 0,
+                disk_numbers: Vec::new(),
+                disc_titles: HashMap::new(),
+                year: None,
+            });
+        entry.duration += adjustment.duration_delta;
+        entry.size += adjustment.size_delta;
+        entry.song_count += adjustment.song_count_delta;
+        if let Some(disk_number) = adjustment.disk_number {
+            if !entry.disk_numbers.contains(&disk_number) {
+                entry.disk_numbers.push(disk_number);
+            }
+            if let Some(subtitle) = adjustment.disc_subtitle {
+                entry.disc_titles.insert(disk_number, subtitle);
+            }
+        }
+        if entry.year.is_none() {
+            entry.year = adjustment.year;
+        }
+        Ok(())
+    }
+
+    async fn find_by_album_id(&self, album_id: AlbumId) -> Result<Option<AlbumStats>, ModelError> {
+        Ok(self.stats.lock().unwrap().get(&album_id.as_i64()).cloned())
+    }
+
+    async fn save(&self, album_stats: AlbumStats) -> Result<(), ModelError> {
+        self.stats
+            .lock()
+            .unwrap()
+            .insert(album_stats.album_id.as_i64(), album_stats);
+        Ok(())
+    }
+
+    async fn delete_by_album_id(&self, album_id: AlbumId) -> Result<(), ModelError> {
+        self.stats.lock().unwrap().remove(&album_id.as_i64());
+        Ok(())
+    }
+}
+
+/// 记录发布的事件，不分发给处理器
+#[derive(Default)]
+pub(crate) struct RecordingEventBus {
+    published: Mutex<Vec<Box<dyn Any + Send + Sync>>>,
+}
+
+impl RecordingEventBus {
+    /// 按发布顺序返回某一类事件
+    pub fn events<E: Clone + 'static>(&self) -> Vec<EventEnvelope<E>> {
+        self.published
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|e| e.downcast_ref::<EventEnvelope<E>>())
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.published.lock().unwrap().is_empty()
+    }
+}
+
+#[async_trait]
+impl EventBus for RecordingEventBus {
+    async fn publish<E>(&self, event: EventEnvelope<E>) -> Result<(), AppError>
+    where
+        E: Send + Sync + 'static,
+    {
+        self.published.lock().unwrap().push(Box::new(event));
+        Ok(())
+    }
+
+    async fn subscribe<E>(&mut self, _handler: Arc<dyn Handler<E>>)
+    where
+        E: Send + Sync + 'static,
+    {
+    }
+}
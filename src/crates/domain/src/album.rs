@@ -132,6 +132,10 @@ pub struct Album {
     pub compilation: bool,
     /// 专辑目录编号
     pub catalog_num: Option<String>,
+    /// 发行版本，如 "Deluxe"、"2019 Remaster"
+    pub edition: Option<String>,
+    /// 包含内容分级为 explicit 的歌曲
+    pub explicit: bool,
 
     pub description: Option<String>,
     /// 版本号
@@ -162,6 +166,8 @@ impl Album {
             releases: None,
             compilation: false,
             catalog_num: None,
+            edition: None,
+            explicit: false,
             description: None,
             version: 0,
             pending_events: Vec::new(),
@@ -222,6 +228,110 @@ impl Album {
         Ok(())
     }
 
+    pub fn unbind_from_genre(&mut self, genre_id: GenreId) -> Result<(), AlbumError> {
+        if !self.genres.contains(&genre_id) {
+            return Ok(());
+        }
+        self.genres.retain(|g| g != &genre_id);
+        if self.genre.as_ref() == Some(&genre_id) {
+            self.genre = self.genres.first().cloned();
+        }
+        self.version += 1;
+        self.pending_events.push(AlbumEvent {
+            album_id: self.id.clone(),
+            version: self.version,
+            kind: AlbumEventKind::UnboundFromGenre(AlbumUnboundFromGenre {
+                name: self.name.clone(),
+                sort_name: self.sort_name.clone(),
+                genre_id,
+            }),
+        });
+        Ok(())
+    }
+
+    pub fn remove_participant(&mut self, participant: &Participant) -> Result<(), AlbumError> {
+        if !self.participants.iter().any(|p| p == participant) {
+            return Ok(());
+        }
+        self.participants.retain(|p| p != participant);
+        // 主艺术家被移除时，改用剩余的第一个参与者
+        if self.artist.as_ref() == Some(&participant.artist_id)
+            && !self
+                .participants
+                .iter()
+                .any(|p| p.artist_id == participant.artist_id)
+        {
+            self.artist = self.participants.first().map(|p| p.artist_id.clone());
+        }
+        self.version += 1;
+        self.pending_events.push(AlbumEvent {
+            album_id: self.id.clone(),
+            version: self.version,
+            kind: AlbumEventKind::ParticipantRemoved(AlbumParticipantRemoved {
+                name: self.name.clone(),
+                sort_name: self.sort_name.clone(),
+                participant: participant.clone(),
+                all_participants: self.participants.clone(),
+            }),
+        });
+        Ok(())
+    }
+
+    /// 把一首歌曲的发行信息合并进专辑：扩展年份范围，原始发行日期取最早的，
+    /// 发行日期取第一次遇到的。返回专辑是否有变化
+    pub fn add_release(
+        &mut self,
+        year: Option<i32>,
+        original_year: Option<i32>,
+        release_date: Option<&str>,
+        original_date: Option<&str>,
+    ) -> bool {
+        let before = (
+            self.min_year,
+            self.max_year,
+            self.min_original_year,
+            self.max_original_year,
+            self.release_date.clone(),
+            self.original_date.clone(),
+        );
+        if let Some(year) = year.filter(|y| *y > 0) {
+            self.min_year = Some(self.min_year.map_or(year, |y| y.min(year)));
+            self.max_year = Some(self.max_year.map_or(year, |y| y.max(year)));
+        }
+        if let Some(year) = original_year.filter(|y| *y > 0) {
+            self.min_original_year = Some(self.min_original_year.map_or(year, |y| y.min(year)));
+            self.max_original_year = Some(self.max_original_year.map_or(year, |y| y.max(year)));
+        }
+        if self.release_date.is_none() {
+            self.release_date = release_date.map(str::to_string);
+        }
+        // YYYY[-MM[-DD]] 按字符串比较即按时间先后
+        if let Some(date) = original_date {
+            if self.original_date.as_deref().is_none_or(|d| date < d) {
+                self.original_date = Some(date.to_string());
+            }
+        }
+        before
+            != (
+                self.min_year,
+                self.max_year,
+                self.min_original_year,
+                self.max_original_year,
+                self.release_date.clone(),
+                self.original_date.clone(),
+            )
+    }
+
+    /// 修改专辑名称，sort_name 由调用方按规范化规则生成
+    pub fn rename(&mut self, name: String, sort_name: String) -> Result<(), AlbumError> {
+        if name.trim().is_empty() {
+            return Err(AlbumError::MissingParameter("name".to_string()));
+        }
+        self.name = name;
+        self.sort_name = sort_name;
+        Ok(())
+    }
+
     // 从事件队列中拉取所有事件
     pub fn take_events(&mut self) -> Vec<AlbumEvent> {
         std::mem::take(&mut self.pending_events)
@@ -1,7 +1,7 @@
 use crate::event::DomainEvent;
 use crate::value::{AnnotationId, UserId};
 use async_trait::async_trait;
-use chrono::{NaiveDateTime, Utc};
+use chrono::NaiveDateTime;
 use std::{fmt, str::FromStr};
 use thiserror::Error;
 
@@ -104,6 +104,8 @@ pub enum AnnotationEvent {
         user_id: UserId,
         item_id: i64,
         item_type: String,
+        /// 播放时间（UTC），离线提交时是客户端记录的时间
+        played_at: NaiveDateTime,
     },
 }
 
@@ -139,12 +141,19 @@ pub struct Annotation {
     pub played_count: i32,
     pub played_at: NaiveDateTime,
     pub version: i64,
+    /// 上次保存的时间，尚未保存时为 None
+    pub updated_at: Option<NaiveDateTime>,
     pub pending_events: Vec<AnnotationEvent>,
 }
 
 impl Annotation {
-    pub fn new(id: AnnotationId, user_id: UserId, item_kind: Kind, item_id: i64) -> Self {
-        let now = Utc::now().naive_utc();
+    pub fn new(
+        id: AnnotationId,
+        user_id: UserId,
+        item_kind: Kind,
+        item_id: i64,
+        now: NaiveDateTime,
+    ) -> Self {
         Self {
             id,
             user_id,
@@ -156,14 +165,15 @@ impl Annotation {
             played_count: 0,
             played_at: now,
             version: 0,
+            updated_at: None,
             pending_events: Vec::new(),
         }
     }
 
-    pub fn set_star(&mut self) -> Result<(), AnnotationError> {
+    pub fn set_star(&mut self, now: NaiveDateTime) -> Result<(), AnnotationError> {
         if !self.starred {
             self.starred = true;
-            self.starred_at = Utc::now().naive_utc();
+            self.starred_at = now;
             self.version += 1;
             self.pending_events.push(AnnotationEvent::ItemStarred {
                 annotation_id: self.id.clone(),
@@ -176,10 +186,10 @@ impl Annotation {
         Ok(())
     }
 
-    pub fn unset_star(&mut self) -> Result<(), AnnotationError> {
+    pub fn unset_star(&mut self, now: NaiveDateTime) -> Result<(), AnnotationError> {
         if self.starred {
             self.starred = false;
-            self.starred_at = Utc::now().naive_utc();
+            self.starred_at = now;
             self.version += 1;
             self.pending_events.push(AnnotationEvent::ItemUnstarred {
                 annotation_id: self.id.clone(),
@@ -193,7 +203,7 @@ impl Annotation {
     }
 
     pub fn set_rating(&mut self, rating: i32) -> Result<(), AnnotationError> {
-        if rating > 5 {
+        if !(0..=5).contains(&rating) {
             return Err(AnnotationError::ValidationError(
                 "Rating must be between 0 and 5".to_string(),
             ));
@@ -214,9 +224,12 @@ impl Annotation {
         Ok(())
     }
 
-    pub fn scrobble(&mut self) -> Result<(), AnnotationError> {
+    pub fn scrobble(&mut self, played_at: NaiveDateTime) -> Result<(), AnnotationError> {
+        // 离线提交的播放可能早于已记录的最近一次播放
+        if self.played_count == 0 || played_at > self.played_at {
+            self.played_at = played_at;
+        }
         self.played_count += 1;
-        self.played_at = Utc::now().naive_utc();
         self.version += 1;
         self.pending_events.push(AnnotationEvent::ItemScrobbled {
             annotation_id: self.id.clone(),
@@ -224,10 +237,66 @@ impl Annotation {
             user_id: self.user_id.clone(),
             item_id: self.item_id,
             item_type: self.item_kind.name().to_string(),
+            played_at,
         });
         Ok(())
     }
 
+    /// 导入其他服务器的收藏、评分和播放次数，已有的收藏和评分保持不变。
+    /// 播放次数只取较大值且不产生 ItemScrobbled，否则播放历史会把它们记成导入时刻的播放
+    pub fn import(
+        &mut self,
+        starred_at: Option<NaiveDateTime>,
+        rating: i32,
+        played_count: i32,
+        played_at: Option<NaiveDateTime>,
+    ) -> Result<(), AnnotationError> {
+        if let Some(starred_at) = starred_at {
+            if !self.starred {
+                self.set_star(starred_at)?;
+            }
+        }
+        if rating > 0 && self.rating == 0 {
+            self.set_rating(rating)?;
+        }
+        if played_count > self.played_count {
+            match played_at {
+                Some(played_at) if self.played_count == 0 || played_at > self.played_at => {
+                    self.played_at = played_at;
+                }
+                _ => {}
+            }
+            self.played_count = played_count;
+            self.version += 1;
+        }
+        Ok(())
+    }
+
+    /// 按同步工具提交的状态设置收藏和评分，None 表示不修改。
+    /// 与导入一样，播放次数只取较大值，不产生 ItemScrobbled
+    pub fn sync(
+        &mut self,
+        starred: Option<bool>,
+        starred_at: Option<NaiveDateTime>,
+        rating: Option<i32>,
+        played_count: Option<i32>,
+        played_at: Option<NaiveDateTime>,
+        now: NaiveDateTime,
+    ) -> Result<(), AnnotationError> {
+        match starred {
+            Some(true) if !self.starred => self.set_star(starred_at.unwrap_or(now))?,
+            Some(false) if self.starred => self.unset_star(now)?,
+            _ => {}
+        }
+        if let Some(rating) = rating {
+            self.set_rating(rating)?;
+        }
+        if let Some(played_count) = played_count {
+            self.import(None, 0, played_count, played_at)?;
+        }
+        Ok(())
+    }
+
     // 从事件队列中拉取所有事件
     pub fn pop_events(&mut self) -> Vec<AnnotationEvent> {
         std::mem::take(&mut self.pending_events)
@@ -237,14 +306,27 @@ impl Annotation {
 // 仓储接口
 #[async_trait]
 pub trait AnnotationRepository: Send + Sync {
+    /// 注解按用户隔离，查询时必须带上用户
     async fn find_by_item(
         &self,
+        user_id: &UserId,
         item_kind: Kind,
         item_id: i64,
     ) -> Result<Option<Annotation>, AnnotationError>;
-    async fn find_by_item_id(&self, item_id: i64) -> Result<Option<Annotation>, AnnotationError>;
+    async fn find_by_item_id(
+        &self,
+        user_id: &UserId,
+        item_id: i64,
+    ) -> Result<Option<Annotation>, AnnotationError>;
     async fn save(&self, annotation: Annotation) -> Result<(), AnnotationError>;
     async fn find_by_user_id(&self, user_id: UserId) -> Result<Vec<Annotation>, AnnotationError>;
     async fn delete_all(&self) -> Result<(), AnnotationError>;
     async fn delete(&self, annotation: Annotation) -> Result<(), AnnotationError>;
+    /// 将某个条目的注解合并到另一个条目（用于合并重复的专辑/艺术家）
+    async fn reassign_item(
+        &self,
+        item_kind: Kind,
+        from_item_id: i64,
+        to_item_id: i64,
+    ) -> Result<(), AnnotationError>;
 }
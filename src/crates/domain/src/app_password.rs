@@ -0,0 +1,69 @@
+use crate::value::UserId;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use thiserror::Error;
+
+/// 标签的最大字符数
+pub const MAX_LABEL_LENGTH: usize = 64;
+
+#[derive(Error, Debug)]
+pub enum AppPasswordError {
+    #[error("Label cannot be empty")]
+    EmptyLabel,
+    #[error("Label is too long: {0} characters, at most {MAX_LABEL_LENGTH}")]
+    LabelTooLong(usize),
+    #[error("{0}")]
+    DbErr(String),
+}
+
+/// 为某个客户端单独生成的密码，可用于 Subsonic 的 p 和 t+s 认证，
+/// 与主密码互不影响，可以逐个吊销
+#[derive(Debug, Clone)]
+pub struct AppPassword {
+    pub id: i64,
+    pub user_id: UserId,
+    pub label: String,
+    /// 可逆加密后的密码，t+s 认证需要明文
+    pub encrypted_password: String,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+impl AppPassword {
+    pub fn new(
+        id: i64,
+        user_id: UserId,
+        label: &str,
+        encrypted_password: String,
+    ) -> Result<Self, AppPasswordError> {
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(AppPasswordError::EmptyLabel);
+        }
+        let length = label.chars().count();
+        if length > MAX_LABEL_LENGTH {
+            return Err(AppPasswordError::LabelTooLong(length));
+        }
+        Ok(Self {
+            id,
+            user_id,
+            label: label.to_string(),
+            encrypted_password,
+            created_at: Utc::now().naive_utc(),
+            last_used_at: None,
+        })
+    }
+}
+
+#[async_trait]
+pub trait AppPasswordRepository: Send + Sync {
+    /// 按创建时间排列
+    async fn find_by_user(&self, user_id: &UserId) -> Result<Vec<AppPassword>, AppPasswordError>;
+
+    async fn save(&self, app_password: &AppPassword) -> Result<(), AppPasswordError>;
+
+    /// 返回是否删除了密码，只能删除属于该用户的
+    async fn delete(&self, user_id: &UserId, id: i64) -> Result<bool, AppPasswordError>;
+
+    async fn mark_used(&self, id: i64, at: NaiveDateTime) -> Result<(), AppPasswordError>;
+}
@@ -143,6 +143,16 @@ impl Artist {
         Ok(())
     }
 
+    /// 修改艺术家名称，sort_name 由调用方按规范化规则生成
+    pub fn rename(&mut self, name: String, sort_name: String) -> Result<(), ArtistError> {
+        if name.trim().is_empty() {
+            return Err(ArtistError::OtherErr("Artist name cannot be empty".to_string()));
+        }
+        self.name = name;
+        self.sort_name = sort_name;
+        Ok(())
+    }
+
     pub fn take_events(&mut self) -> Vec<ArtistEvent> {
         std::mem::take(&mut self.pending_events)
     }
@@ -155,3 +165,40 @@ pub trait ArtistRepository: Send + Sync {
     async fn delete(&self, artist_id: ArtistId) -> Result<(), ArtistError>;
     async fn by_id(&self, id: ArtistId) -> Result<Option<Artist>, ArtistError>;
 }
+
+/// 艺术家别名，如 "Sheena Ringo" 之于 "椎名林檎"
+#[derive(Debug, Clone)]
+pub struct ArtistAlias {
+    /// 按艺术家名称规则规范化后的别名，用于匹配
+    pub sort_name: String,
+    pub name: String,
+    pub artist_id: ArtistId,
+}
+
+impl ArtistAlias {
+    pub fn new(artist_id: ArtistId, name: &str, sort_name: String) -> Result<Self, ArtistError> {
+        let name = name.trim();
+        if name.is_empty() || sort_name.is_empty() {
+            return Err(ArtistError::OtherErr(
+                "Artist alias cannot be empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            sort_name,
+            name: name.to_string(),
+            artist_id,
+        })
+    }
+}
+
+#[async_trait]
+pub trait ArtistAliasRepository: Send + Sync {
+    async fn find_by_sort_name(&self, sort_name: &str) -> Result<Option<ArtistAlias>, ArtistError>;
+
+    async fn find_by_artist(&self, artist_id: &ArtistId) -> Result<Vec<ArtistAlias>, ArtistError>;
+
+    /// 新增别名，别名已属于其他艺术家时改为指向当前艺术家
+    async fn save(&self, alias: ArtistAlias) -> Result<(), ArtistError>;
+
+    async fn delete(&self, sort_name: &str) -> Result<(), ArtistError>;
+}
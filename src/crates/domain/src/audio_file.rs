@@ -1,7 +1,7 @@
 use crate::event::DomainEvent;
 use crate::value::{
-    AlbumId, ArtistId, AudioFileId, AudioMetadata, AudioQuality, FileMeta, GenreId, LibraryId,
-    MediaPath, Participant,
+    AlbumId, ArtistId, AudioFileId, AudioMetadata, AudioQuality, Chapter, FileMeta, GenreId,
+    LibraryId, MediaPath, Participant, ReplayGain,
 };
 use async_trait::async_trait;
 use chrono::{NaiveDateTime, Utc};
@@ -14,6 +14,7 @@ pub struct AudioFileMeta {
     // 曲目信息
     pub track_number: Option<i32>,
     pub disc_number: Option<i32>,
+    pub disc_subtitle: Option<String>, // 碟片副标题
 
     // 发行相关
     pub year: Option<i32>,          // 普通标签里的年份
@@ -26,6 +27,19 @@ pub struct AudioFileMeta {
 
     // 节奏信息
     pub bpm: Option<i32>, // 每分钟节拍数
+
+    pub comment: Option<String>,
+    pub mbz_track_id: Option<String>,
+    pub replay_gain: ReplayGain,
+    pub explicit: bool, // 内容分级为 explicit
+
+    // 作品与乐章
+    pub work: Option<String>,
+    pub movement_name: Option<String>,
+    pub movement_number: Option<i32>,
+    pub movement_count: Option<i32>,
+
+    pub chapters: Vec<Chapter>,
 }
 
 impl From<AudioMetadata> for AudioFileMeta {
@@ -33,19 +47,42 @@ impl From<AudioMetadata> for AudioFileMeta {
         Self {
             title: meta.title,
             track_number: meta.track_number,
-            disc_number: None,
+            disc_number: meta.disc_number,
+            disc_subtitle: meta.disc_subtitle,
             year: meta.year,
             date: None,
-            original_year: None,
+            original_year: meta.original_year,
             original_date: None,
             release_year: None,
             release_date: None,
             compilation: false,
-            bpm: None,
+            bpm: meta.bpm,
+            comment: meta.comment,
+            mbz_track_id: meta.mbz_track_id,
+            replay_gain: meta.replay_gain,
+            explicit: meta.explicit,
+            work: meta.work,
+            movement_name: meta.movement_name,
+            movement_number: meta.movement_number,
+            movement_count: meta.movement_count,
+            chapters: meta.chapters,
         }
     }
 }
 
+/// 重新解析已入库文件得到的属性和标签
+#[derive(Debug, Clone)]
+pub struct ReparsedFile {
+    pub size: i64,
+    pub hash: Option<String>,
+    pub duration: i64,
+    pub bit_rate: i32,
+    pub sample_rate: i32,
+    pub channels: i32,
+    pub has_cover_art: bool,
+    pub meta: AudioFileMeta,
+}
+
 /// AudioFile AudioFile聚合根，代表一个音频媒体文件
 #[derive(Debug, Clone)]
 pub struct AudioFile {
@@ -174,6 +211,7 @@ impl AudioFile {
                 year: self.meta.year,
                 track_number: self.meta.track_number,
                 disc_number: self.meta.disc_number,
+                disc_subtitle: self.meta.disc_subtitle.clone(),
             }),
         });
         Ok(())
@@ -203,6 +241,19 @@ impl AudioFile {
         Ok(())
     }
 
+    /// move_to_album 改绑到另一张专辑，解绑和绑定在同一次保存中完成；返回原来的专辑
+    pub fn move_to_album(&mut self, album_id: AlbumId) -> Result<Option<AlbumId>, AudioFileError> {
+        if self.album.as_ref() == Some(&album_id) {
+            return Ok(None);
+        }
+        let previous = self.album.clone();
+        if previous.is_some() {
+            self.unbind_from_album()?;
+        }
+        self.bind_to_album(album_id)?;
+        Ok(previous)
+    }
+
     pub fn add_participant(&mut self, participant: Participant) -> Result<(), AudioFileError> {
         if self.artist.is_none() {
             self.artist = Some(participant.artist_id.clone());
@@ -232,6 +283,15 @@ impl AudioFile {
         }
 
         self.participants.retain(|p| p != &participant);
+        // 主艺术家被移除时，改用剩余的第一个参与者
+        if self.artist.as_ref() == Some(&participant.artist_id)
+            && !self
+                .participants
+                .iter()
+                .any(|p| p.artist_id == participant.artist_id)
+        {
+            self.artist = self.participants.first().map(|p| p.artist_id.clone());
+        }
         self.updated_at = Utc::now().naive_utc();
         self.events.push(AudioFileEvent {
             audio_file_id: self.id.clone(),
@@ -277,6 +337,9 @@ impl AudioFile {
         }
 
         self.genres.retain(|g| g != &genre_id);
+        if self.genre.as_ref() == Some(&genre_id) {
+            self.genre = self.genres.first().cloned();
+        }
         self.updated_at = Utc::now().naive_utc();
         self.events.push(AudioFileEvent {
             audio_file_id: self.id.clone(),
@@ -295,7 +358,95 @@ impl AudioFile {
     pub fn update_metadata(&mut self, new_meta: AudioFileMeta) {
         self.meta = new_meta;
         self.updated_at = Utc::now().naive_utc();
-        self.add_updated_event();
+        self.add_updated_event(None);
+    }
+
+    /// edit_metadata 手动编辑标签。不产生 Updated 事件，以免触发从文件重新解析；
+    /// 碟号、碟片副标题或年份变化时解绑再绑回原专辑，专辑统计按新值重新计入
+    pub fn edit_metadata(&mut self, new_meta: AudioFileMeta) -> Result<(), AudioFileError> {
+        let album = self.album.clone().filter(|_| {
+            self.meta.disc_number != new_meta.disc_number
+                || self.meta.disc_subtitle != new_meta.disc_subtitle
+                || self.meta.year != new_meta.year
+        });
+        if album.is_some() {
+            self.unbind_from_album()?;
+        }
+        self.meta = new_meta;
+        self.updated_at = Utc::now().naive_utc();
+        if let Some(album) = album {
+            self.bind_to_album(album)?;
+        }
+        Ok(())
+    }
+
+    /// refresh 文件重新解析后更新属性和标签。大小或时长变化时先解绑再绑回原专辑，
+    /// 专辑统计按新值重新计入
+    pub fn refresh(&mut self, file: ReparsedFile) -> Result<(), AudioFileError> {
+        let album = self
+            .album
+            .clone()
+            .filter(|_| self.size != file.size || self.duration != file.duration);
+        if album.is_some() {
+            self.unbind_from_album()?;
+        }
+        let previous_format = self.format();
+        self.size = file.size;
+        self.hash = file.hash;
+        self.duration = file.duration;
+        self.bit_rate = file.bit_rate;
+        self.sample_rate = file.sample_rate;
+        self.channels = file.channels;
+        self.has_cover_art = file.has_cover_art;
+        self.meta = file.meta;
+        self.updated_at = Utc::now().naive_utc();
+        if let Some(album) = album {
+            self.bind_to_album(album)?;
+        }
+        let previous_format = Some(previous_format).filter(|f| *f != self.format());
+        self.add_updated_event(previous_format);
+        Ok(())
+    }
+
+    /// sync_bindings 按重新解析的标签绑定流派和参与者，标签中已经没有的解除绑定
+    pub fn sync_bindings(
+        &mut self,
+        genre_ids: &[GenreId],
+        participants: &[Participant],
+    ) -> Result<(), AudioFileError> {
+        let stale_genres: Vec<GenreId> = self
+            .genres
+            .iter()
+            .filter(|genre_id| !genre_ids.contains(genre_id))
+            .cloned()
+            .collect();
+        for genre_id in stale_genres {
+            self.unbind_from_genre(genre_id)?;
+        }
+        for genre_id in genre_ids {
+            self.bind_to_genre(genre_id.clone())?;
+        }
+
+        let stale_participants: Vec<Participant> = self
+            .participants
+            .iter()
+            .filter(|participant| !participants.contains(participant))
+            .cloned()
+            .collect();
+        for participant in stale_participants {
+            self.remove_participant(participant)?;
+        }
+        for participant in participants {
+            self.add_participant(participant.clone())?;
+        }
+        // 与首次绑定一致，主流派和主艺术家取标签中的第一个
+        if let Some(genre_id) = genre_ids.first() {
+            self.genre = Some(genre_id.clone());
+        }
+        if let Some(participant) = participants.first() {
+            self.artist = Some(participant.artist_id.clone());
+        }
+        Ok(())
     }
 
     /// update_technical_info 更新技术信息
@@ -307,13 +458,25 @@ impl AudioFile {
         sample_rate: i32,
         channels: i32,
     ) {
+        let previous_format = self.format();
         self.duration = duration;
         self.bit_rate = bit_rate;
         self.bit_depth = bit_depth;
         self.sample_rate = sample_rate;
         self.channels = channels;
         self.updated_at = Utc::now().naive_utc();
-        self.add_updated_event();
+        let previous_format = Some(previous_format).filter(|f| *f != self.format());
+        self.add_updated_event(previous_format);
+    }
+
+    /// format 格式分布统计使用的属性
+    pub fn format(&self) -> AudioFormat {
+        AudioFormat {
+            suffix: self.suffix.clone(),
+            bit_rate: self.bit_rate,
+            sample_rate: self.sample_rate,
+            size: self.size,
+        }
     }
 
     /// quality 获取音频质量等级
@@ -399,16 +562,20 @@ impl AudioFile {
                 audio_file_id: self.id.clone(),
                 path: self.path.clone(),
                 has_cover_art: self.has_cover_art,
+                format: self.format(),
             }),
         });
     }
 
-    fn add_updated_event(&mut self) {
+    fn add_updated_event(&mut self, previous_format: Option<AudioFormat>) {
         self.events.push(AudioFileEvent {
             audio_file_id: self.id.clone(),
             version: self.version,
             kind: AudioFileEventKind::Updated(AudioFileUpdated {
+                library_id: self.library_id.clone(),
                 audio_file_id: self.id.clone(),
+                previous_format,
+                format: self.format(),
             }),
         });
     }
@@ -417,17 +584,29 @@ impl AudioFile {
         self.events.push(AudioFileEvent {
             audio_file_id: self.id.clone(),
             version: self.version,
-            kind: AudioFileEventKind::Deleted(AudioFileDeleted {}),
+            kind: AudioFileEventKind::Deleted(AudioFileDeleted {
+                format: self.format(),
+            }),
         });
     }
 }
 
+/// 文件格式相关的属性，事件中携带以便投影不必回查文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFormat {
+    pub suffix: String,
+    pub bit_rate: i32,
+    pub sample_rate: i32,
+    pub size: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct AudioFileCreated {
     pub library_id: LibraryId,
     pub audio_file_id: AudioFileId,
     pub path: MediaPath,
     pub has_cover_art: bool,
+    pub format: AudioFormat,
 }
 
 #[derive(Debug, Clone)]
@@ -440,6 +619,7 @@ pub struct AudioFileBoundToAlbum {
     pub year: Option<i32>,
     pub track_number: Option<i32>,
     pub disc_number: Option<i32>,
+    pub disc_subtitle: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -478,7 +658,11 @@ pub struct AudioFileGenreRemoved {
 }
 #[derive(Debug, Clone)]
 pub struct AudioFileUpdated {
+    pub library_id: LibraryId,
     pub audio_file_id: AudioFileId,
+    /// 格式属性发生变化时为变化前的值
+    pub previous_format: Option<AudioFormat>,
+    pub format: AudioFormat,
 }
 #[derive(Debug, Clone)]
 pub struct AudioFileUnboundFromAlbum {
@@ -500,7 +684,9 @@ pub struct AudioFileUnboundFromGenre {
 }
 
 #[derive(Debug, Clone)]
-pub struct AudioFileDeleted {}
+pub struct AudioFileDeleted {
+    pub format: AudioFormat,
+}
 
 #[derive(Debug, Clone)]
 pub struct AudioFileEvent {
@@ -546,6 +732,21 @@ pub trait AudioFileRepository: Send + Sync {
     /// find_by_path 根据路径加载音频文件
     async fn find_by_path(&self, path: &MediaPath) -> Result<Option<AudioFile>, AudioFileError>;
 
+    /// find_by_album 加载专辑下的所有音频文件
+    async fn find_by_album(&self, album_id: &AlbumId) -> Result<Vec<AudioFile>, AudioFileError>;
+
+    /// find_by_artist 加载主艺术家为指定艺术家的所有音频文件
+    async fn find_by_artist(&self, artist_id: &ArtistId) -> Result<Vec<AudioFile>, AudioFileError>;
+
+    /// find_by_participant 加载指定艺术家参与（任意角色）的所有音频文件
+    async fn find_by_participant(
+        &self,
+        artist_id: &ArtistId,
+    ) -> Result<Vec<AudioFile>, AudioFileError>;
+
+    /// find_by_genre 加载绑定了指定流派的所有音频文件
+    async fn find_by_genre(&self, genre_id: &GenreId) -> Result<Vec<AudioFile>, AudioFileError>;
+
     /// delete 删除音频文件
     async fn delete(&self, id: &AudioFileId) -> Result<(), AudioFileError>;
 }
@@ -585,4 +786,138 @@ pub enum AudioFileError {
     InvalidMetadata(String),
     #[error("Participant not found: {0}")]
     ParticipantNotFound(Participant),
+    #[error("Version conflict: {0}")]
+    VersionConflict(i64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::{ParticipantRole, ParticipantWorkType};
+
+    fn meta() -> AudioFileMeta {
+        AudioFileMeta {
+            title: "Song".to_string(),
+            track_number: Some(1),
+            disc_number: Some(1),
+            disc_subtitle: None,
+            year: None,
+            date: None,
+            original_year: None,
+            original_date: None,
+            release_year: None,
+            release_date: None,
+            compilation: false,
+            bpm: None,
+            comment: None,
+            mbz_track_id: None,
+            replay_gain: ReplayGain::default(),
+            explicit: false,
+            work: None,
+            movement_name: None,
+            movement_number: None,
+            movement_count: None,
+            chapters: Vec::new(),
+        }
+    }
+
+    fn artist(id: i64) -> Participant {
+        Participant::new(
+            ArtistId::from(id),
+            ParticipantRole::Artist,
+            None,
+            1,
+            ParticipantWorkType::Artist,
+        )
+    }
+
+    fn audio_file() -> AudioFile {
+        AudioFile::new(
+            AudioFileId::from(1),
+            LibraryId::from(1),
+            MediaPath::new("local".to_string(), "/music/song.flac".to_string()),
+            1024,
+            "flac".to_string(),
+            None,
+            180,
+            900,
+            16,
+            44100,
+            2,
+            false,
+            meta(),
+        )
+    }
+
+    #[test]
+    fn sync_bindings_removes_tags_that_changed() {
+        let mut audio_file = audio_file();
+        audio_file
+            .sync_bindings(&[GenreId::from(1)], &[artist(10), artist(11)])
+            .unwrap();
+        audio_file.take_events();
+
+        // 艺术家标签从 10 改为 12，流派改为 2
+        audio_file
+            .sync_bindings(&[GenreId::from(2)], &[artist(12), artist(11)])
+            .unwrap();
+
+        assert_eq!(audio_file.participants, vec![artist(11), artist(12)]);
+        assert_eq!(audio_file.artist, Some(ArtistId::from(12)));
+        assert_eq!(audio_file.genres, vec![GenreId::from(2)]);
+        assert_eq!(audio_file.genre, Some(GenreId::from(2)));
+        let kinds: Vec<&AudioFileEventKind> =
+            audio_file.events.iter().map(|event| &event.kind).collect();
+        assert!(matches!(
+            kinds.as_slice(),
+            [
+                AudioFileEventKind::UnboundFromGenre(genre),
+                AudioFileEventKind::GenreAdded(_),
+                AudioFileEventKind::ParticipantRemoved(removed),
+                AudioFileEventKind::ParticipantAdded(added),
+            ] if genre.genre_id == GenreId::from(1)
+                && removed.participant == artist(10)
+                && added.participant == artist(12)
+        ));
+    }
+
+    #[test]
+    fn sync_bindings_without_changes_emits_nothing() {
+        let mut audio_file = audio_file();
+        audio_file
+            .sync_bindings(&[GenreId::from(1)], &[artist(10)])
+            .unwrap();
+        audio_file.take_events();
+
+        audio_file
+            .sync_bindings(&[GenreId::from(1)], &[artist(10)])
+            .unwrap();
+        assert!(audio_file.take_events().is_empty());
+    }
+
+    #[test]
+    fn edit_metadata_rebinds_album_only_when_album_fields_change() {
+        let mut audio_file = audio_file();
+        audio_file.bind_to_album(AlbumId::from(5)).unwrap();
+        audio_file.take_events();
+
+        let mut edited = meta();
+        edited.title = "Renamed".to_string();
+        audio_file.edit_metadata(edited.clone()).unwrap();
+        assert!(audio_file.take_events().is_empty());
+
+        edited.disc_number = Some(2);
+        audio_file.edit_metadata(edited).unwrap();
+        let events = audio_file.take_events();
+        assert!(matches!(
+            &events[0].kind,
+            AudioFileEventKind::UnboundFromAlbum(e) if e.disc_number == Some(1)
+        ));
+        assert!(matches!(
+            &events[1].kind,
+            AudioFileEventKind::BoundToAlbum(e) if e.disc_number == Some(2)
+        ));
+        assert_eq!(events.len(), 2);
+        assert_eq!(audio_file.album, Some(AlbumId::from(5)));
+    }
 }
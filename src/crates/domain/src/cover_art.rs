@@ -99,10 +99,13 @@ pub struct CoverArt {
     pub path: MediaPath, // 文件路径
     pub width: Option<i32>,
     pub height: Option<i32>,
-    pub format: Option<CoverFormat>, // 图片格式
-    pub file_size: i64,              // 文件大小（字节）
-    pub source: CoverSourceType,     // 原始来源信息
-    pub version: i64,                // 版本号（乐观锁）
+    pub format: Option<CoverFormat>,    // 图片格式
+    pub file_size: i64,                 // 文件大小（字节）
+    pub source: CoverSourceType,        // 原始来源信息
+    pub thumbnail_path: Option<String>, // 扫描时生成的缩略图（嵌入封面）
+    pub blurhash: Option<String>,       // 图片加载前的模糊占位
+    pub dominant_color: Option<String>, // 主色调，#rrggbb
+    pub version: i64,                   // 版本号（乐观锁）
     pending_events: Vec<CoverArtEvent>,
 }
 
@@ -115,6 +118,9 @@ pub struct CoverArtDTO {
     pub format: Option<CoverFormat>,
     pub file_size: i64,
     pub source: CoverSourceType,
+    pub thumbnail_path: Option<String>,
+    pub blurhash: Option<String>,
+    pub dominant_color: Option<String>,
 }
 
 // 领域事件
@@ -210,6 +216,9 @@ impl CoverArt {
             format: dto.format,
             file_size: dto.file_size,
             source: dto.source,
+            thumbnail_path: dto.thumbnail_path,
+            blurhash: dto.blurhash,
+            dominant_color: dto.dominant_color,
             version: 0,
             pending_events: vec![created_evt],
         })
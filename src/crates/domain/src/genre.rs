@@ -80,6 +80,8 @@ impl GenreName {
 pub struct Genre {
     pub id: GenreId,
     pub name: GenreName,
+    /// 父流派（可选），如 "Alternative Rock" -> "Rock"
+    pub parent_id: Option<GenreId>,
     pub version: i64,
     pending_events: Vec<GenreEvent>,
 }
@@ -89,6 +91,7 @@ impl Genre {
         let mut genre = Self {
             id,
             name: genre_name,
+            parent_id: None,
             version: 0,
             pending_events: Vec::new(),
         };
@@ -102,6 +105,21 @@ impl Genre {
         Self { version, ..self }
     }
 
+    pub fn with_parent(self, parent_id: Option<GenreId>) -> Self {
+        Self { parent_id, ..self }
+    }
+
+    /// 设置父流派，None 表示取消
+    pub fn set_parent(&mut self, parent_id: Option<GenreId>) -> Result<(), GenreError> {
+        if parent_id.as_ref() == Some(&self.id) {
+            return Err(GenreError::ValidationErr(
+                "流派不能以自身作为父流派".to_string(),
+            ));
+        }
+        self.parent_id = parent_id;
+        Ok(())
+    }
+
     pub fn name(&self) -> String {
         self.name.value()
     }
@@ -122,7 +140,43 @@ pub trait GenreRepository: Send + Sync {
 
     async fn find_by_name(&self, genre_name: &GenreName) -> Result<Option<Genre>, GenreError>;
 
+    /// 全部流派，用于别名重新归一化和层级维护
+    async fn find_all(&self) -> Result<Vec<Genre>, GenreError>;
+
     async fn save(&self, mut genre: Genre) -> Result<Genre, GenreError>;
 
     async fn delete(&self, genre_id: GenreId) -> Result<(), GenreError>;
 }
+
+// 流派别名：把不同写法归并到同一个规范流派名
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenreAlias {
+    /// 小写别名，如 "alt rock"
+    pub alias: String,
+    /// 规范流派名，如 "Alternative"
+    pub genre_name: String,
+}
+
+impl GenreAlias {
+    pub fn new(alias: &str, genre_name: &str) -> Result<Self, GenreError> {
+        let alias = alias.trim().to_lowercase();
+        if alias.is_empty() {
+            return Err(GenreError::ValidationErr("流派别名不能为空".to_string()));
+        }
+        let genre_name = GenreName::new(genre_name.trim().to_string())?;
+        Ok(Self {
+            alias,
+            genre_name: genre_name.value(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+pub trait GenreAliasRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<GenreAlias>, GenreError>;
+
+    /// 新增或覆盖别名
+    async fn save(&self, alias: GenreAlias) -> Result<(), GenreError>;
+
+    async fn delete(&self, alias: &str) -> Result<(), GenreError>;
+}
@@ -1,16 +1,21 @@
 pub mod album;
+pub mod app_password;
 pub mod annotation;
 pub mod artist;
 pub mod audio_file;
 pub mod value;
-// pub mod bookmark;
+pub mod bookmark;
 pub mod cover_art;
 pub mod genre;
 pub mod library;
+pub mod media_asset;
+pub mod metadata_change;
 // pub mod lyrics;
 pub mod event;
 pub mod play_queue;
 pub mod player;
 pub mod playlist;
+pub mod scan_error;
 pub mod transcoding;
 pub mod user;
+pub mod user_note;
@@ -11,12 +11,78 @@ use super::value::FileMeta;
 pub trait LibraryRepository: Send + Sync {
     async fn save(&self, library: &Library) -> Result<(), LibraryError>;
     async fn find_by_id(&self, id: &LibraryId) -> Result<Option<Library>, LibraryError>;
+    /// 列出所有库，不加载 items，结果不能直接 save
+    async fn find_all(&self) -> Result<Vec<Library>, LibraryError>;
+    async fn delete(&self, id: &LibraryId) -> Result<(), LibraryError>;
+}
+
+/// 远程存储（SMB 等）的访问凭据，密码明文只存在于内存中
+#[derive(Clone)]
+pub struct LibraryCredentials {
+    pub library_id: LibraryId,
+    pub username: String,
+    pub password: String,
+    /// SMB 域/工作组
+    pub domain: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl LibraryCredentials {
+    pub fn new(
+        library_id: LibraryId,
+        username: &str,
+        password: &str,
+        domain: Option<&str>,
+    ) -> Result<Self, LibraryError> {
+        if username.trim().is_empty() {
+            return Err(LibraryError::InvalidCredentials(
+                "username cannot be empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            library_id,
+            username: username.trim().to_string(),
+            password: password.to_string(),
+            domain: domain
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            updated_at: Utc::now().naive_utc(),
+        })
+    }
+}
+
+impl std::fmt::Debug for LibraryCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LibraryCredentials")
+            .field("library_id", &self.library_id)
+            .field("username", &self.username)
+            .field("password", &"***")
+            .field("domain", &self.domain)
+            .finish()
+    }
+}
+
+#[async_trait]
+pub trait LibraryCredentialsRepository: Send + Sync {
+    async fn find_by_library(
+        &self,
+        library_id: &LibraryId,
+    ) -> Result<Option<LibraryCredentials>, LibraryError>;
+    /// 查找根路径包含该路径的库的凭据，多个库匹配时取根路径最长的
+    async fn find_for_path(
+        &self,
+        path: &MediaPath,
+    ) -> Result<Option<LibraryCredentials>, LibraryError>;
+    async fn save(&self, credentials: &LibraryCredentials) -> Result<(), LibraryError>;
+    async fn delete(&self, library_id: &LibraryId) -> Result<(), LibraryError>;
 }
 
 #[derive(Error, Debug)]
 pub enum LibraryError {
     #[error("Library is currently being scanned")]
     ScanningInProgress,
+    #[error("Library has no interrupted scan to resume")]
+    NoInterruptedScan,
     #[error("Library not found")]
     NotFound,
     #[error("Database error: {0}")]
@@ -25,6 +91,14 @@ pub enum LibraryError {
     IoError(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
+    #[error("Invalid name: {0}")]
+    InvalidName(String),
+    #[error("Library is disabled")]
+    Disabled,
+    #[error("Library still contains indexed files")]
+    NotEmpty,
+    #[error("Invalid credentials: {0}")]
+    InvalidCredentials(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -32,6 +106,8 @@ pub enum LibraryError {
 pub enum ScanStatus {
     Idle = 1,
     Scanning = 2,
+    /// 扫描过程中服务停止，可从检查点继续
+    Interrupted = 3,
 }
 
 impl From<ScanStatus> for i32 {
@@ -46,11 +122,128 @@ impl TryFrom<i32> for ScanStatus {
         match value {
             1 => Ok(ScanStatus::Idle),
             2 => Ok(ScanStatus::Scanning),
+            3 => Ok(ScanStatus::Interrupted),
             _ => Err(format!("invalid value:{}", value)),
         }
     }
 }
 
+/// 库的内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LibraryKind {
+    #[default]
+    Music,
+    /// 有声书：按书签续播，不参与随机播放和播放记录
+    Audiobook,
+}
+
+impl LibraryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LibraryKind::Music => "music",
+            LibraryKind::Audiobook => "audiobook",
+        }
+    }
+}
+
+impl TryFrom<&str> for LibraryKind {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "music" => Ok(LibraryKind::Music),
+            "audiobook" | "audiobooks" => Ok(LibraryKind::Audiobook),
+            _ => Err(format!("invalid library kind:{}", value)),
+        }
+    }
+}
+
+/// 扫描时的文件过滤规则，由存储客户端在遍历目录时应用
+///
+/// 模式支持 `*`（不跨目录）、`**`（可跨目录）和 `?`，不区分大小写；
+/// 不含 `/` 的模式匹配文件或目录名，含 `/` 的模式匹配相对库根目录的路径
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScanFilter {
+    /// 非空时只收录匹配其中之一的文件，不影响目录
+    pub include: Vec<String>,
+    /// 匹配的文件被跳过，匹配的目录整个不再进入
+    pub exclude: Vec<String>,
+    /// 是否扫描以 `.` 开头的文件和目录
+    pub scan_hidden: bool,
+}
+
+impl ScanFilter {
+    /// relative 为相对库根目录、以 `/` 分隔的路径
+    pub fn allows_dir(&self, relative: &str) -> bool {
+        let name = entry_name(relative);
+        (self.scan_hidden || !name.starts_with('.'))
+            && !self.exclude.iter().any(|p| pattern_matches(p, relative))
+    }
+
+    pub fn allows_file(&self, relative: &str) -> bool {
+        self.allows_dir(relative)
+            && (self.include.is_empty()
+                || self.include.iter().any(|p| pattern_matches(p, relative)))
+    }
+}
+
+fn entry_name(relative: &str) -> &str {
+    relative.rsplit('/').next().unwrap_or(relative)
+}
+
+fn pattern_matches(pattern: &str, relative: &str) -> bool {
+    let relative = relative.trim_start_matches('/');
+    let (pattern, text) = match pattern.strip_prefix('/') {
+        Some(anchored) => (anchored, relative),
+        None if pattern.contains('/') => (pattern, relative),
+        None => (pattern, entry_name(relative)),
+    };
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    glob_match(&pattern, &text)
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            // `**/` 也匹配零层目录
+            if rest.first() == Some(&'/') && glob_match(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if glob_match(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => {
+            matches!(text.first(), Some(c) if *c != '/') && glob_match(&pattern[1..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// 去掉首尾空白、空模式和重复模式
+fn normalize_patterns(patterns: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().to_string();
+        if !pattern.is_empty() && !normalized.contains(&pattern) {
+            normalized.push(pattern);
+        }
+    }
+    normalized
+}
+
 #[derive(Debug, Clone)]
 pub enum LibraryEvent {
     FileAdded(FileAdded),
@@ -210,6 +403,16 @@ pub struct Library {
     pub scan_status: ScanStatus,
     pub version: i64,
     pub last_scan_at: NaiveDateTime,
+    /// 停用的库不参与扫描，也不在音乐文件夹中展示
+    pub enabled: bool,
+    /// 定时扫描间隔（分钟），None 表示使用系统默认间隔
+    pub scan_interval_minutes: Option<i32>,
+    pub kind: LibraryKind,
+    pub scan_filter: ScanFilter,
+    /// 存储无法访问的起始时间，期间扫描不删除文件
+    pub unavailable_since: Option<NaiveDateTime>,
+    /// 存储无法访问的文件是否从浏览和搜索结果中隐藏
+    pub hide_unavailable: bool,
     pub pending_events: Vec<LibraryEvent>,
 }
 
@@ -223,11 +426,98 @@ impl Library {
             scan_status: ScanStatus::Idle,
             version: 0,
             last_scan_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            enabled: true,
+            scan_interval_minutes: None,
+            kind: LibraryKind::Music,
+            scan_filter: ScanFilter::default(),
+            unavailable_since: None,
+            hide_unavailable: false,
             pending_events: Vec::new(),
         }
     }
 
+    pub fn rename(&mut self, name: &str) -> Result<(), LibraryError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(LibraryError::InvalidName("name cannot be empty".to_string()));
+        }
+        self.name = name.to_string();
+        Ok(())
+    }
+
+    /// 修改根路径，旧路径下的文件会在下次扫描时被移除
+    pub fn relocate(&mut self, path: MediaPath) -> Result<(), LibraryError> {
+        if self.scan_status == ScanStatus::Scanning {
+            return Err(LibraryError::ScanningInProgress);
+        }
+        if path.path.trim().is_empty() {
+            return Err(LibraryError::InvalidPath("path cannot be empty".to_string()));
+        }
+        self.path = path;
+        // 旧路径的扫描进度不再适用
+        if self.scan_status == ScanStatus::Interrupted {
+            self.scan_status = ScanStatus::Idle;
+        }
+        Ok(())
+    }
+
+    /// 已经处于不可用状态时保留最早的时间
+    pub fn mark_unavailable(&mut self, now: NaiveDateTime) {
+        self.unavailable_since.get_or_insert(now);
+    }
+
+    /// 存储恢复访问，返回之前是否不可用
+    pub fn mark_available(&mut self) -> bool {
+        self.unavailable_since.take().is_some()
+    }
+
+    pub fn set_hide_unavailable(&mut self, hide_unavailable: bool) {
+        self.hide_unavailable = hide_unavailable;
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn set_kind(&mut self, kind: LibraryKind) {
+        self.kind = kind;
+    }
+
+    /// 过滤规则在下次扫描时生效，不再匹配的已索引文件随之移除
+    pub fn set_include_patterns(&mut self, patterns: Vec<String>) {
+        self.scan_filter.include = normalize_patterns(patterns);
+    }
+
+    pub fn set_exclude_patterns(&mut self, patterns: Vec<String>) {
+        self.scan_filter.exclude = normalize_patterns(patterns);
+    }
+
+    pub fn set_scan_hidden(&mut self, scan_hidden: bool) {
+        self.scan_filter.scan_hidden = scan_hidden;
+    }
+
+    /// 0 或负数表示改用系统默认间隔
+    pub fn set_scan_interval(&mut self, minutes: i32) {
+        self.scan_interval_minutes = Some(minutes).filter(|m| *m > 0);
+    }
+
+    /// 是否到了定时扫描的时间，库未设置间隔时使用默认间隔，两者都没有则只手动扫描
+    pub fn is_scan_due(&self, now: NaiveDateTime, default_interval_minutes: Option<i32>) -> bool {
+        let Some(minutes) = self
+            .scan_interval_minutes
+            .or(default_interval_minutes.filter(|m| *m > 0))
+        else {
+            return false;
+        };
+        self.enabled
+            && self.scan_status == ScanStatus::Idle
+            && now - self.last_scan_at >= chrono::Duration::minutes(minutes as i64)
+    }
+
     pub fn start_scan(&mut self, full_scan: bool) -> Result<(), LibraryError> {
+        if !self.enabled {
+            return Err(LibraryError::Disabled);
+        }
         if self.scan_status == ScanStatus::Scanning {
             return Err(LibraryError::ScanningInProgress);
         }
@@ -247,10 +537,80 @@ impl Library {
         Ok(())
     }
 
-    pub fn finish_scan(&mut self) {
+    /// 服务启动时，把上次未结束的扫描标记为中断
+    pub fn interrupt_scan(&mut self) {
+        if self.scan_status == ScanStatus::Scanning {
+            self.scan_status = ScanStatus::Interrupted;
+        }
+    }
+
+    /// 继续中断的扫描，检查点之前的文件由调用方通过 keep_item 标记
+    pub fn resume_scan(&mut self) -> Result<(), LibraryError> {
+        if !self.enabled {
+            return Err(LibraryError::Disabled);
+        }
+        if self.scan_status != ScanStatus::Interrupted {
+            return Err(LibraryError::NoInterruptedScan);
+        }
+        self.scan_status = ScanStatus::Scanning;
+        self.items.iter_mut().for_each(|(_, item)| {
+            item.state = LibraryItemState::Deleted;
+            self.version += 1;
+        });
+        self.pending_events
+            .push(LibraryEvent::ScanStarted(ScanStarted {
+                library_id: self.id.clone(),
+                version: self.version,
+            }));
+        Ok(())
+    }
+
+    /// 标记文件仍然存在，不产生事件；文件不在库中时返回 false
+    pub fn keep_item(&mut self, path: &str) -> bool {
+        match self.items.get_mut(path) {
+            Some(item) => {
+                item.state = LibraryItemState::Origin;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 目录无法读取时保留其中已有的文件，返回保留的文件数
+    pub fn keep_items_under(&mut self, dir: &str) -> usize {
+        let prefix = format!("{}/", dir.trim_end_matches('/'));
+        let mut kept = 0;
+        for (path, item) in self.items.iter_mut() {
+            if path == dir || path.starts_with(&prefix) {
+                item.state = LibraryItemState::Origin;
+                kept += 1;
+            }
+        }
+        kept
+    }
+
+    /// 文件被移出库目录（如移入回收站）时调用，产生 FileRemoved；文件不在库中时返回 false
+    pub fn remove_item(&mut self, path: &str) -> bool {
+        if self.items.remove(path).is_none() {
+            return false;
+        }
+        self.pending_events
+            .push(LibraryEvent::FileRemoved(FileRemoved {
+                library_id: self.id.clone(),
+                version: self.version,
+                path: MediaPath {
+                    protocol: self.path.protocol.clone(),
+                    path: path.to_string(),
+                },
+            }));
+        self.version += 1;
+        true
+    }
+
+    pub fn finish_scan(&mut self, now: NaiveDateTime) {
         if self.scan_status != ScanStatus::Idle {
             self.scan_status = ScanStatus::Idle;
-            self.last_scan_at = Utc::now().naive_utc();
+            self.last_scan_at = now;
         }
         let mut items_to_remove = Vec::new();
         self.items
@@ -290,19 +650,21 @@ impl Library {
         }
     }
     pub fn add_item(&mut self, item: LibraryItem) {
-        if let Some(item) = self.items.get_mut(&item.path.path) {
-            if item.mtime != item.mtime || item.atime != item.atime {
-                item.state = LibraryItemState::Updated;
-                item.mtime = item.mtime;
-                item.atime = item.atime;
+        if let Some(existing) = self.items.get_mut(&item.path.path) {
+            // 播放会改变访问时间，只按修改时间和大小判断文件是否变化
+            existing.atime = item.atime;
+            if existing.mtime != item.mtime || existing.size != item.size {
+                existing.state = LibraryItemState::Updated;
+                existing.mtime = item.mtime;
+                existing.size = item.size;
                 self.pending_events
                     .push(LibraryEvent::FileUpdated(FileUpdated {
                         library_id: self.id.clone(),
                         version: self.version,
-                        item: item.clone(),
+                        item: existing.clone(),
                     }));
             } else {
-                item.state = LibraryItemState::Origin;
+                existing.state = LibraryItemState::Origin;
             }
         } else {
             self.items.insert(item.path.path.clone(), item.clone());
@@ -319,3 +681,238 @@ impl Library {
         std::mem::take(&mut self.pending_events)
     }
 }
+
+/// 从检查点继续扫描时跳过上次已处理的文件
+///
+/// 检查点之前已在库中的文件只标记为仍然存在；检查点文件已被删除时会一直快进到扫描结束，
+/// 不在库中的新文件仍正常加入
+#[derive(Debug, Clone, Default)]
+pub struct ScanFastForward {
+    last_path: Option<String>,
+}
+
+impl ScanFastForward {
+    /// last_path 为 None 时不跳过任何文件
+    pub fn new(last_path: Option<String>) -> Self {
+        Self { last_path }
+    }
+
+    /// 文件上次已处理过时返回 true，调用方不再加入该文件
+    pub fn skip(&mut self, library: &mut Library, path: &str) -> bool {
+        let Some(last_path) = &self.last_path else {
+            return false;
+        };
+        if path == last_path {
+            self.last_path = None;
+        }
+        library.keep_item(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(secs: i64) -> NaiveDateTime {
+        DateTime::from_timestamp(secs, 0).unwrap().naive_utc()
+    }
+
+    fn item(id: i64, path: &str) -> LibraryItem {
+        LibraryItem {
+            id: LibraryItemId::from(id),
+            library_id: LibraryId::from(1),
+            path: MediaPath::new("local".to_string(), path.to_string()),
+            size: 1024,
+            suffix: "flac".to_string(),
+            mtime: time(100),
+            atime: time(100),
+            state: LibraryItemState::Origin,
+            file_type: FileType::Audio,
+        }
+    }
+
+    fn library(paths: &[&str]) -> Library {
+        let mut library = Library::new(
+            LibraryId::from(1),
+            "Music".to_string(),
+            MediaPath::new("local".to_string(), "/music".to_string()),
+        );
+        for (i, path) in paths.iter().enumerate() {
+            library
+                .items
+                .insert(path.to_string(), item(i as i64 + 1, path));
+        }
+        library
+    }
+
+    fn removed_paths(events: &[LibraryEvent]) -> Vec<String> {
+        let mut paths: Vec<String> = events
+            .iter()
+            .filter_map(|event| match event {
+                LibraryEvent::FileRemoved(removed) => Some(removed.path.path.clone()),
+                _ => None,
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn unavailable_since_keeps_the_first_time() {
+        let mut library = library(&[]);
+        assert!(!library.mark_available());
+
+        library.mark_unavailable(time(10));
+        library.mark_unavailable(time(20));
+        assert_eq!(library.unavailable_since, Some(time(10)));
+
+        assert!(library.mark_available());
+        assert_eq!(library.unavailable_since, None);
+        assert!(!library.mark_available());
+    }
+
+    #[test]
+    fn files_under_unavailable_dir_survive_the_scan() {
+        let mut library = library(&["/music/nas/a.flac", "/music/nas2/b.flac", "/music/c.flac"]);
+        library.start_scan(false).unwrap();
+        library.take_events();
+
+        // 前缀相同的兄弟目录不受影响
+        assert_eq!(library.keep_items_under("/music/nas/"), 1);
+        library.finish_scan(time(200));
+
+        assert_eq!(
+            removed_paths(&library.take_events()),
+            vec!["/music/c.flac", "/music/nas2/b.flac"]
+        );
+        assert!(library.items.contains_key("/music/nas/a.flac"));
+        assert_eq!(library.items.len(), 1);
+    }
+
+    fn added_paths(events: &[LibraryEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                LibraryEvent::FileAdded(added) => Some(added.item.path.path.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn interrupted(paths: &[&str]) -> Library {
+        let mut library = library(paths);
+        library.start_scan(false).unwrap();
+        library.interrupt_scan();
+        library.take_events();
+        library
+    }
+
+    /// 按扫描顺序送入文件，跳过快进中的文件，其余加入库
+    fn rescan(library: &mut Library, checkpoint: Option<&str>, paths: &[&str]) {
+        let mut fast_forward = ScanFastForward::new(checkpoint.map(str::to_string));
+        for (i, path) in paths.iter().enumerate() {
+            if !fast_forward.skip(library, path) {
+                library.add_item(item(100 + i as i64, path));
+            }
+        }
+        library.finish_scan(time(200));
+    }
+
+    #[test]
+    fn resume_scan_requires_an_interrupted_scan() {
+        let mut library = library(&["/music/a.flac"]);
+        assert!(matches!(
+            library.resume_scan(),
+            Err(LibraryError::NoInterruptedScan)
+        ));
+
+        library.start_scan(false).unwrap();
+        assert!(matches!(
+            library.resume_scan(),
+            Err(LibraryError::NoInterruptedScan)
+        ));
+
+        library.interrupt_scan();
+        assert_eq!(library.scan_status, ScanStatus::Interrupted);
+        library.take_events();
+        library.resume_scan().unwrap();
+        assert_eq!(library.scan_status, ScanStatus::Scanning);
+        assert!(matches!(
+            library.take_events().as_slice(),
+            [LibraryEvent::ScanStarted(_)]
+        ));
+
+        let mut disabled = interrupted(&[]);
+        disabled.enabled = false;
+        assert!(matches!(
+            disabled.resume_scan(),
+            Err(LibraryError::Disabled)
+        ));
+    }
+
+    #[test]
+    fn kept_items_survive_a_resumed_scan() {
+        let mut library = interrupted(&["/music/a.flac", "/music/b.flac"]);
+        library.resume_scan().unwrap();
+
+        assert!(library.keep_item("/music/a.flac"));
+        assert!(!library.keep_item("/music/new.flac"));
+        library.finish_scan(time(200));
+
+        let events = library.take_events();
+        assert_eq!(removed_paths(&events), vec!["/music/b.flac"]);
+        assert!(added_paths(&events).is_empty());
+        assert_eq!(library.items.len(), 1);
+    }
+
+    #[test]
+    fn fast_forward_skips_files_up_to_the_checkpoint() {
+        let mut library = interrupted(&["/music/a.flac", "/music/b.flac", "/music/c.flac"]);
+        library.resume_scan().unwrap();
+        library.take_events();
+
+        rescan(
+            &mut library,
+            Some("/music/b.flac"),
+            &[
+                "/music/a.flac",
+                "/music/b.flac",
+                "/music/c.flac",
+                "/music/d.flac",
+            ],
+        );
+
+        let events = library.take_events();
+        assert_eq!(added_paths(&events), vec!["/music/d.flac"]);
+        assert!(removed_paths(&events).is_empty());
+        // 检查点之后已在库中的文件照常检查，没有变化时保留原来的条目
+        assert_eq!(library.items["/music/c.flac"].id, LibraryItemId::from(3));
+        assert_eq!(library.items.len(), 4);
+    }
+
+    #[test]
+    fn fast_forward_past_a_deleted_checkpoint_still_adds_new_files() {
+        let mut library = interrupted(&["/music/a.flac", "/music/b.flac", "/music/c.flac"]);
+        library.resume_scan().unwrap();
+        library.take_events();
+
+        // 检查点文件 b 已被删除，快进一直持续到扫描结束
+        rescan(
+            &mut library,
+            Some("/music/b.flac"),
+            &["/music/a.flac", "/music/c.flac", "/music/d.flac"],
+        );
+
+        let events = library.take_events();
+        assert_eq!(added_paths(&events), vec!["/music/d.flac"]);
+        assert_eq!(removed_paths(&events), vec!["/music/b.flac"]);
+        assert_eq!(library.items.len(), 3);
+    }
+
+    #[test]
+    fn no_checkpoint_skips_nothing() {
+        let mut fast_forward = ScanFastForward::new(None);
+        let mut library = library(&["/music/a.flac"]);
+        assert!(!fast_forward.skip(&mut library, "/music/a.flac"));
+    }
+}
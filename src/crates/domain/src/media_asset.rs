@@ -0,0 +1,93 @@
+use crate::value::{FileMeta, LibraryId, MediaPath};
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MediaAssetError {
+    #[error("{0}")]
+    DbErr(String),
+}
+
+/// 库中的非音频资源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaAssetKind {
+    /// 数字小册子（PDF）
+    Booklet,
+    /// artist.* 艺术家图片
+    ArtistImage,
+    /// logo.* 艺术家或厂牌标志
+    Logo,
+    /// 其他图片，如封底、扫描页
+    Image,
+}
+
+impl MediaAssetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaAssetKind::Booklet => "booklet",
+            MediaAssetKind::ArtistImage => "artist_image",
+            MediaAssetKind::Logo => "logo",
+            MediaAssetKind::Image => "image",
+        }
+    }
+
+    /// 按文件名判断资源类型，不是资源的文件返回 None
+    pub fn classify(path: &str, suffix: &str) -> Option<Self> {
+        let suffix = suffix.to_lowercase();
+        if suffix == "pdf" {
+            return Some(MediaAssetKind::Booklet);
+        }
+        if !matches!(suffix.as_str(), "jpg" | "jpeg" | "png" | "gif" | "webp") {
+            return None;
+        }
+        let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let stem = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem)
+            .to_lowercase();
+        match stem.as_str() {
+            "artist" => Some(MediaAssetKind::ArtistImage),
+            "logo" => Some(MediaAssetKind::Logo),
+            _ => Some(MediaAssetKind::Image),
+        }
+    }
+}
+
+/// 扫描时收录的小册子、艺术家图片等文件，按所在目录关联到专辑和艺术家
+#[derive(Debug, Clone)]
+pub struct MediaAsset {
+    pub id: i64,
+    pub library_id: LibraryId,
+    pub path: MediaPath,
+    /// 所在目录，与 album_location 的目录取法一致
+    pub dir_path: String,
+    pub kind: MediaAssetKind,
+    pub size: i64,
+    pub mtime: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl MediaAsset {
+    /// 文件不是资源时返回 None
+    pub fn from_file(id: i64, library_id: LibraryId, file: &FileMeta) -> Option<Self> {
+        let kind = MediaAssetKind::classify(&file.path.path, &file.suffix)?;
+        Some(Self {
+            id,
+            library_id,
+            path: file.path.clone(),
+            dir_path: file.path.parent_path().path,
+            kind,
+            size: file.size,
+            mtime: file.mtime,
+            created_at: Utc::now().naive_utc(),
+        })
+    }
+}
+
+#[async_trait]
+pub trait MediaAssetRepository: Send + Sync {
+    /// 同一路径已存在时更新类型、大小和修改时间
+    async fn save(&self, asset: &MediaAsset) -> Result<(), MediaAssetError>;
+    async fn delete_by_path(&self, path: &MediaPath) -> Result<(), MediaAssetError>;
+}
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MetadataChangeError {
+    #[error("Invalid target type: {0}")]
+    InvalidTarget(String),
+    #[error("{0}")]
+    DbErr(String),
+}
+
+/// 元数据修改的目标类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataTarget {
+    Song,
+    Album,
+    Artist,
+}
+
+impl MetadataTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataTarget::Song => "song",
+            MetadataTarget::Album => "album",
+            MetadataTarget::Artist => "artist",
+        }
+    }
+}
+
+impl TryFrom<&str> for MetadataTarget {
+    type Error = MetadataChangeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "song" => Ok(MetadataTarget::Song),
+            "album" => Ok(MetadataTarget::Album),
+            "artist" => Ok(MetadataTarget::Artist),
+            other => Err(MetadataChangeError::InvalidTarget(other.to_string())),
+        }
+    }
+}
+
+/// 一条元数据修改记录（审计用，只追加不修改）
+#[derive(Debug, Clone)]
+pub struct MetadataChange {
+    pub id: i64,
+    pub target: MetadataTarget,
+    pub target_id: i64,
+    /// 修改的字段名，如 title / album / artist
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// 操作人用户名
+    pub changed_by: String,
+    /// 是否已回写到文件标签
+    pub tags_written: bool,
+    pub created_at: NaiveDateTime,
+}
+
+impl MetadataChange {
+    pub fn new(
+        id: i64,
+        target: MetadataTarget,
+        target_id: i64,
+        field: impl Into<String>,
+        old_value: Option<String>,
+        new_value: Option<String>,
+        changed_by: impl Into<String>,
+    ) -> Self {
+        Self {
+            id,
+            target,
+            target_id,
+            field: field.into(),
+            old_value,
+            new_value,
+            changed_by: changed_by.into(),
+            tags_written: false,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait MetadataChangeRepository: Send + Sync {
+    /// 追加修改记录
+    async fn append(&self, changes: Vec<MetadataChange>) -> Result<(), MetadataChangeError>;
+
+    /// 按时间倒序查询修改记录，可按目标过滤
+    async fn find_recent(
+        &self,
+        target: Option<(MetadataTarget, i64)>,
+        limit: u64,
+    ) -> Result<Vec<MetadataChange>, MetadataChangeError>;
+}
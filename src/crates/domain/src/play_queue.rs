@@ -25,6 +25,9 @@ pub struct PlayQueue {
     pub position: i64,
     /// Client name that last changed this queue
     pub changed_by: String,
+    /// Device the queue belongs to; empty for the queue shared by all devices
+    pub client_id: String,
+    /// Last change time in milliseconds, used for last-writer-wins
     pub updated_at: i64,
     pub pending_events: Vec<PlayQueueDomainEvent>,
 }
@@ -39,7 +42,8 @@ impl PlayQueue {
             current_index: None,
             position: 0,
             changed_by,
-            updated_at: chrono::Utc::now().timestamp(),
+            client_id: String::new(),
+            updated_at: chrono::Utc::now().timestamp_millis(),
             pending_events: vec![],
         }
     }
@@ -48,6 +52,7 @@ impl PlayQueue {
     pub fn from_saved_state(
         id: PlayQueueId,
         user_id: UserId,
+        client_id: String,
         items: Vec<AudioFileId>,
         current: Option<AudioFileId>,
         position: i64,
@@ -62,7 +67,8 @@ impl PlayQueue {
             current_index,
             position,
             changed_by,
-            updated_at: chrono::Utc::now().timestamp(),
+            client_id,
+            updated_at: chrono::Utc::now().timestamp_millis(),
             pending_events: vec![],
         }
     }
@@ -236,8 +242,17 @@ fn adjust_index_after_move(current_index: usize, old_index: usize, new_index: us
 #[async_trait]
 pub trait PlayQueueRepository {
     async fn find_by_id(&self, id: PlayQueueId) -> Result<Option<PlayQueue>, PlayQueueError>;
-    async fn find_by_user_id(&self, user_id: UserId) -> Result<Option<PlayQueue>, PlayQueueError>;
+    /// `client_id` is empty for the shared queue
+    async fn find_by_user_and_client(
+        &self,
+        user_id: UserId,
+        client_id: &str,
+    ) -> Result<Option<PlayQueue>, PlayQueueError>;
     async fn save(&self, play_queue: &mut PlayQueue) -> Result<(), PlayQueueError>;
     async fn delete(&self, id: PlayQueueId) -> Result<(), PlayQueueError>;
-    async fn delete_by_user_id(&self, user_id: UserId) -> Result<(), PlayQueueError>;
+    async fn delete_by_user_and_client(
+        &self,
+        user_id: UserId,
+        client_id: &str,
+    ) -> Result<(), PlayQueueError>;
 }
@@ -74,6 +74,7 @@ pub struct Player {
     pub transcoding_id: String,
     pub max_bit_rate: i32,
     pub report_real_path: bool,
+    /// false means private listening: streaming works but plays are not recorded.
     pub scrobble_enabled: bool,
     pub version: i32,
     pub last_op_time: NaiveDateTime,
@@ -107,7 +108,7 @@ impl Player {
             transcoding_id: String::new(),
             max_bit_rate: 0,
             report_real_path: false,
-            scrobble_enabled: false,
+            scrobble_enabled: true,
             state: PlayerState::Stopped,
             current_item: None,
             play_queue_id: None,
@@ -1,8 +1,17 @@
 use crate::value::{PlaylistId, UserId};
 use async_trait::async_trait;
 use chrono::{NaiveDateTime, Utc};
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
+/// 条目位置的默认间隔，插入和移动时只改写受影响的条目
+pub const POSITION_GAP: i32 = 1024;
+
+/// 每个播放列表的标签数上限
+pub const MAX_PLAYLIST_TAGS: usize = 20;
+/// 单个标签的最大字符数
+pub const MAX_TAG_LENGTH: usize = 50;
+
 /// 播放列表领域错误
 #[derive(Error, Debug)]
 pub enum PlaylistError {
@@ -14,6 +23,10 @@ pub enum PlaylistError {
     ValidationErr(String),
     #[error("Entry not found: {0}")]
     EntryNotFound(i64),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("Playlist {0} was modified concurrently")]
+    VersionConflict(i64),
     #[error("{0}")]
     OtherErr(String),
 }
@@ -42,16 +55,25 @@ pub struct PlaylistEntry {
     pub audio_file_id: i64,
     pub position: i32,
     pub added_at: NaiveDateTime,
+    /// 添加该条目的用户，旧数据为空
+    pub added_by: Option<UserId>,
 }
 
 impl PlaylistEntry {
-    fn new(id: i64, playlist_id: PlaylistId, audio_file_id: i64, position: i32) -> Self {
+    fn new(
+        id: i64,
+        playlist_id: PlaylistId,
+        audio_file_id: i64,
+        position: i32,
+        added_by: Option<UserId>,
+    ) -> Self {
         Self {
             id,
             playlist_id,
             audio_file_id,
             position,
             added_at: Utc::now().naive_utc(),
+            added_by,
         }
     }
 }
@@ -65,10 +87,18 @@ pub struct Playlist {
     pub owner: Owner,
     pub public: bool,
     pub entries: Vec<PlaylistEntry>,
+    /// 协作者，仅在播放列表公开时可以编辑条目
+    pub collaborators: Vec<UserId>,
+    /// 所有者用来分类的标签
+    pub tags: Vec<String>,
+    /// 用户上传的封面（本地路径），为空时自动生成
+    pub cover_path: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// 乐观锁版本，由仓储在保存时递增
     pub version: i64,
-    pub deleted: bool,
+    /// 移入最近删除的时间，保留期过后永久删除
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 impl Playlist {
@@ -88,19 +118,129 @@ impl Playlist {
             owner,
             public,
             entries: Vec::new(),
+            collaborators: Vec::new(),
+            tags: Vec::new(),
+            cover_path: None,
             created_at: now,
             updated_at: now,
             version: 0,
-            deleted: false,
+            deleted_at: None,
         }
     }
 
-    /// 添加条目
-    pub fn add_entry(&mut self, entry_id: i64, audio_file_id: i64) {
-        let position = self.entries.len() as i32;
-        let entry = PlaylistEntry::new(entry_id, self.id.clone(), audio_file_id, position);
+    /// 是否为所有者
+    pub fn is_owner(&self, user_id: &UserId) -> bool {
+        self.owner.id == *user_id
+    }
+
+    /// 所有者可以编辑条目；协作者只能编辑公开的播放列表
+    pub fn can_edit_entries(&self, user_id: &UserId) -> bool {
+        self.is_owner(user_id) || (self.public && self.collaborators.contains(user_id))
+    }
+
+    /// 在末尾添加条目
+    pub fn add_entry(&mut self, entry_id: i64, audio_file_id: i64, added_by: Option<UserId>) {
+        let entry = PlaylistEntry::new(entry_id, self.id.clone(), audio_file_id, 0, added_by);
         self.entries.push(entry);
+        self.place(self.entries.len() - 1, 1);
+        self.touch();
+    }
+
+    /// 在指定索引处批量插入条目（`(条目 ID, 歌曲 ID)`），索引为空或越界时追加到末尾
+    pub fn insert_entries(
+        &mut self,
+        index: Option<usize>,
+        items: Vec<(i64, i64)>,
+        added_by: Option<UserId>,
+    ) {
+        if items.is_empty() {
+            return;
+        }
+        let index = index.unwrap_or(self.entries.len()).min(self.entries.len());
+        let count = items.len();
+        let entries: Vec<PlaylistEntry> = items
+            .into_iter()
+            .map(|(entry_id, audio_file_id)| {
+                PlaylistEntry::new(
+                    entry_id,
+                    self.id.clone(),
+                    audio_file_id,
+                    0,
+                    added_by.clone(),
+                )
+            })
+            .collect();
+        self.entries.splice(index..index, entries);
+        self.place(index, count);
+        self.touch();
+    }
+
+    /// 将条目移动到指定索引，越界时移到末尾
+    pub fn move_entry(&mut self, entry_id: i64, index: usize) -> Result<(), PlaylistError> {
+        let from = self
+            .entries
+            .iter()
+            .position(|e| e.id == entry_id)
+            .ok_or(PlaylistError::EntryNotFound(entry_id))?;
+
+        let entry = self.entries.remove(from);
+        let index = index.min(self.entries.len());
+        self.entries.insert(index, entry);
+        if index != from {
+            self.place(index, 1);
+            self.touch();
+        }
+        Ok(())
+    }
+
+    /// 批量删除条目，返回实际删除的数量；已不存在的条目忽略
+    pub fn remove_entries(&mut self, entry_ids: &[i64]) -> usize {
+        let ids: HashSet<i64> = entry_ids.iter().copied().collect();
+        let before = self.entries.len();
+        self.entries.retain(|e| !ids.contains(&e.id));
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.touch();
+        }
+        removed
+    }
+
+    /// 删除重复的歌曲，保留最早出现的条目，返回删除的数量
+    pub fn deduplicate(&mut self) -> usize {
+        let mut seen = HashSet::new();
+        let before = self.entries.len();
+        self.entries.retain(|e| seen.insert(e.audio_file_id));
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.touch();
+        }
+        removed
+    }
+
+    /// 按给定的条目 ID 顺序重排，必须恰好包含当前所有条目
+    pub fn reorder(&mut self, entry_ids: &[i64]) -> Result<(), PlaylistError> {
+        if entry_ids.len() != self.entries.len() {
+            return Err(PlaylistError::ValidationErr(format!(
+                "expected {} entries, got {}",
+                self.entries.len(),
+                entry_ids.len()
+            )));
+        }
+
+        let mut by_id: HashMap<i64, &PlaylistEntry> =
+            self.entries.iter().map(|e| (e.id, e)).collect();
+        let mut entries = Vec::with_capacity(entry_ids.len());
+        for id in entry_ids {
+            let entry = by_id.remove(id).ok_or_else(|| {
+                PlaylistError::ValidationErr(format!("unknown or duplicated entry: {}", id))
+            })?;
+            entries.push(entry.clone());
+        }
+
+        self.entries = entries;
+        self.rebalance();
         self.touch();
+        Ok(())
     }
 
     /// 删除条目
@@ -116,6 +256,104 @@ impl Playlist {
         Ok(())
     }
 
+    /// 按索引删除条目，越界的索引忽略
+    pub fn remove_entries_at(&mut self, indexes: &[usize]) {
+        let mut indexes = indexes.to_vec();
+        indexes.sort_unstable_by(|a, b| b.cmp(a));
+        indexes.dedup();
+        for index in indexes {
+            if index < self.entries.len() {
+                self.entries.remove(index);
+            }
+        }
+        self.touch();
+    }
+
+    /// 按给定歌曲顺序重建条目
+    ///
+    /// 已有的相同歌曲复用原条目，保留添加者和添加时间；
+    /// 新歌曲依次使用 `new_entry_ids` 中的 ID。
+    pub fn replace_entries(
+        &mut self,
+        audio_file_ids: &[i64],
+        new_entry_ids: &mut impl Iterator<Item = i64>,
+        added_by: Option<UserId>,
+    ) -> Result<(), PlaylistError> {
+        let mut remaining: Vec<Option<PlaylistEntry>> = std::mem::take(&mut self.entries)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut entries = Vec::with_capacity(audio_file_ids.len());
+
+        for &audio_file_id in audio_file_ids {
+            let reused = remaining
+                .iter_mut()
+                .find(|e| matches!(e, Some(e) if e.audio_file_id == audio_file_id))
+                .and_then(Option::take);
+            let entry = match reused {
+                Some(entry) => entry,
+                None => {
+                    let entry_id = new_entry_ids.next().ok_or_else(|| {
+                        PlaylistError::OtherErr("not enough entry ids".to_string())
+                    })?;
+                    PlaylistEntry::new(
+                        entry_id,
+                        self.id.clone(),
+                        audio_file_id,
+                        0,
+                        added_by.clone(),
+                    )
+                }
+            };
+            entries.push(entry);
+        }
+
+        self.entries = entries;
+        self.rebalance();
+        self.touch();
+        Ok(())
+    }
+
+    /// 设置协作者，忽略所有者本人和重复项
+    pub fn set_collaborators(&mut self, collaborators: Vec<UserId>) {
+        let mut unique: Vec<UserId> = Vec::with_capacity(collaborators.len());
+        for user_id in collaborators {
+            if user_id != self.owner.id && !unique.contains(&user_id) {
+                unique.push(user_id);
+            }
+        }
+        self.collaborators = unique;
+        self.touch();
+    }
+
+    /// 设置标签：去掉首尾空白，忽略空标签和大小写不同的重复项
+    pub fn set_tags(&mut self, tags: Vec<String>) -> Result<(), PlaylistError> {
+        let mut unique: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = tag.trim();
+            let key = tag.to_lowercase();
+            if tag.is_empty() || unique.iter().any(|t| t.to_lowercase() == key) {
+                continue;
+            }
+            if tag.chars().count() > MAX_TAG_LENGTH {
+                return Err(PlaylistError::ValidationErr(format!(
+                    "tag is longer than {} characters: {}",
+                    MAX_TAG_LENGTH, tag
+                )));
+            }
+            unique.push(tag.to_string());
+        }
+        if unique.len() > MAX_PLAYLIST_TAGS {
+            return Err(PlaylistError::ValidationErr(format!(
+                "at most {} tags per playlist",
+                MAX_PLAYLIST_TAGS
+            )));
+        }
+        self.tags = unique;
+        self.touch();
+        Ok(())
+    }
+
     /// 更新名称
     pub fn update_name(&mut self, name: &str) {
         self.name = name.to_string();
@@ -134,15 +372,40 @@ impl Playlist {
         self.touch();
     }
 
-    /// 标记删除
-    pub fn delete(&mut self) {
-        self.deleted = true;
+    /// 设置自定义封面，返回被替换的封面路径
+    pub fn set_cover(&mut self, path: String) -> Option<String> {
+        let previous = self.cover_path.replace(path);
         self.touch();
+        previous
+    }
+
+    /// 清除自定义封面，返回原封面路径
+    pub fn clear_cover(&mut self) -> Option<String> {
+        let previous = self.cover_path.take();
+        if previous.is_some() {
+            self.touch();
+        }
+        previous
+    }
+
+    /// 移入最近删除，条目、协作者和封面都保留以便恢复
+    pub fn delete(&mut self) {
+        if self.deleted_at.is_none() {
+            self.deleted_at = Some(Utc::now().naive_utc());
+            self.touch();
+        }
+    }
+
+    /// 从最近删除中恢复
+    pub fn restore(&mut self) {
+        if self.deleted_at.take().is_some() {
+            self.touch();
+        }
     }
 
     /// 是否已删除
     pub fn is_deleted(&self) -> bool {
-        self.deleted
+        self.deleted_at.is_some()
     }
 
     /// 获取活跃条目数量
@@ -155,9 +418,37 @@ impl Playlist {
         self.entries.iter()
     }
 
+    /// 为 `entries[start..start + count]` 分配位置
+    ///
+    /// 在前后邻居之间均匀取值；没有足够间隔时整体重新编号。
+    fn place(&mut self, start: usize, count: usize) {
+        let lower = match start {
+            0 => 0,
+            _ => self.entries[start - 1].position as i64,
+        };
+        let step = match self.entries.get(start + count) {
+            None => POSITION_GAP as i64,
+            Some(next) => (next.position as i64 - lower) / (count as i64 + 1),
+        };
+        let last = lower + step * count as i64;
+        if step < 1 || last > i32::MAX as i64 {
+            self.rebalance();
+            return;
+        }
+        for (i, entry) in self.entries[start..start + count].iter_mut().enumerate() {
+            entry.position = (lower + step * (i as i64 + 1)) as i32;
+        }
+    }
+
+    /// 按当前顺序以固定间隔重新编号
+    fn rebalance(&mut self) {
+        for (i, entry) in self.entries.iter_mut().enumerate() {
+            entry.position = (i as i32 + 1).saturating_mul(POSITION_GAP);
+        }
+    }
+
     fn touch(&mut self) {
         self.updated_at = Utc::now().naive_utc();
-        self.version += 1;
     }
 }
 
@@ -167,15 +458,66 @@ pub trait PlaylistRepository: Send + Sync {
     /// 根据 ID 查找
     async fn find_by_id(&self, id: PlaylistId) -> Result<Option<Playlist>, PlaylistError>;
 
-    /// 保存播放列表
+    /// 保存播放列表，版本与加载时不一致返回 `VersionConflict`
     async fn save(&self, playlist: &mut Playlist) -> Result<(), PlaylistError>;
 
-    /// 删除播放列表
+    /// 永久删除播放列表
     async fn delete(&self, id: PlaylistId) -> Result<(), PlaylistError>;
 
+    /// 在 before 之前移入最近删除的播放列表，不加载条目
+    async fn find_deleted_before(
+        &self,
+        before: NaiveDateTime,
+    ) -> Result<Vec<Playlist>, PlaylistError>;
+
     /// 清空所有数据
     async fn truncate(&self) -> Result<(), PlaylistError>;
 
     /// 根据所有者 ID 查找
     async fn find_by_owner_id(&self, owner_id: UserId) -> Result<Vec<Playlist>, PlaylistError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist() -> Playlist {
+        let owner = Owner {
+            id: UserId::from(1),
+            name: "alice".to_string(),
+        };
+        let mut playlist = Playlist::new(PlaylistId::from(10), "Road trip", owner, None, true);
+        playlist.add_entry(100, 1000, None);
+        playlist.add_entry(101, 1001, None);
+        playlist.set_collaborators(vec![UserId::from(2)]);
+        playlist.set_cover("/covers/10.jpg".to_string());
+        playlist
+    }
+
+    #[test]
+    fn delete_keeps_the_contents_for_restore() {
+        let mut playlist = playlist();
+        playlist.delete();
+        assert!(playlist.is_deleted());
+        let deleted_at = playlist.deleted_at;
+
+        // 重复删除不会推迟保留期
+        playlist.delete();
+        assert_eq!(playlist.deleted_at, deleted_at);
+
+        playlist.restore();
+        assert!(!playlist.is_deleted());
+        assert_eq!(playlist.song_count(), 2);
+        assert_eq!(playlist.collaborators, vec![UserId::from(2)]);
+        assert_eq!(playlist.cover_path.as_deref(), Some("/covers/10.jpg"));
+    }
+
+    #[test]
+    fn restore_of_a_live_playlist_changes_nothing() {
+        let mut playlist = playlist();
+        let updated_at = playlist.updated_at;
+        playlist.restore();
+        assert!(!playlist.is_deleted());
+        assert_eq!(playlist.updated_at, updated_at);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::value::{FileMeta, LibraryId, MediaPath};
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScanErrorError {
+    #[error("{0}")]
+    DbErr(String),
+}
+
+/// 元数据解析失败的文件（隔离列表）
+///
+/// 文件大小和修改时间不变时，后续扫描直接跳过
+#[derive(Debug, Clone)]
+pub struct ScanError {
+    pub id: i64,
+    pub library_id: LibraryId,
+    pub path: MediaPath,
+    pub suffix: String,
+    pub size: i64,
+    pub mtime: NaiveDateTime,
+    pub error: String,
+    /// 累计失败次数
+    pub attempts: i32,
+    pub failed_at: NaiveDateTime,
+}
+
+impl ScanError {
+    pub fn new(id: i64, library_id: LibraryId, file: &FileMeta, error: impl Into<String>) -> Self {
+        Self {
+            id,
+            library_id,
+            path: file.path.clone(),
+            suffix: file.suffix.clone(),
+            size: file.size,
+            mtime: file.mtime,
+            error: error.into(),
+            attempts: 1,
+            failed_at: Utc::now().naive_utc(),
+        }
+    }
+
+    /// 文件自上次失败后没有变化
+    pub fn is_unchanged(&self, file: &FileMeta) -> bool {
+        self.size == file.size && self.mtime == file.mtime
+    }
+
+    /// 再次失败时刷新文件信息和错误
+    pub fn record_failure(&mut self, file: &FileMeta, error: impl Into<String>) {
+        self.size = file.size;
+        self.mtime = file.mtime;
+        self.suffix = file.suffix.clone();
+        self.error = error.into();
+        self.attempts += 1;
+        self.failed_at = Utc::now().naive_utc();
+    }
+
+    /// 还原为解析命令需要的文件信息
+    pub fn file_meta(&self) -> FileMeta {
+        FileMeta::new(
+            self.path.clone(),
+            self.path.clone(),
+            self.size,
+            self.suffix.clone(),
+            self.mtime,
+            self.mtime,
+            self.mtime,
+            None,
+        )
+    }
+}
+
+#[async_trait]
+pub trait ScanErrorRepository: Send + Sync {
+    async fn find_by_id(&self, id: i64) -> Result<Option<ScanError>, ScanErrorError>;
+    async fn find_by_path(&self, path: &MediaPath) -> Result<Option<ScanError>, ScanErrorError>;
+    async fn find_all(&self) -> Result<Vec<ScanError>, ScanErrorError>;
+    async fn save(&self, scan_error: ScanError) -> Result<(), ScanErrorError>;
+    async fn delete(&self, id: i64) -> Result<(), ScanErrorError>;
+}
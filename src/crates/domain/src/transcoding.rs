@@ -1,6 +1,7 @@
 use futures::stream::Stream;
 use std::collections::HashMap;
 use thiserror::Error;
+use tokio::io::AsyncRead;
 
 use crate::value::TranscodingId;
 
@@ -87,14 +88,44 @@ impl Transcoding {
     }
 }
 
+/// 写入转码输出的标签和封面，供显示流元数据的客户端使用
+#[derive(Debug, Clone, Default)]
+pub struct StreamTags {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub album_artist: String,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub year: Option<i32>,
+    pub genre: Option<String>,
+    /// 封面图片（JPEG 或 PNG）
+    pub cover: Option<Vec<u8>>,
+}
+
 #[async_trait::async_trait]
 pub trait TranscodingStreamer: Send + Sync {
+    /// tags 不为 None 时写入输出，目标格式不支持时忽略
     async fn create_stream(
         &self,
         input_path: String,
         output_format: String,
         bit_rate: i32,
         additional_params: HashMap<String, String>,
+        tags: Option<StreamTags>,
+    ) -> Result<
+        Box<dyn Stream<Item = Result<Vec<u8>, TranscodingError>> + Unpin + Send>,
+        TranscodingError,
+    >;
+
+    /// 源文件不在本地时（如 SMB）从 input 读取，其余参数与 create_stream 相同
+    async fn create_stream_from_reader(
+        &self,
+        input: Box<dyn AsyncRead + Send + Unpin>,
+        output_format: String,
+        bit_rate: i32,
+        additional_params: HashMap<String, String>,
+        tags: Option<StreamTags>,
     ) -> Result<
         Box<dyn Stream<Item = Result<Vec<u8>, TranscodingError>> + Unpin + Send>,
         TranscodingError,
@@ -1,6 +1,7 @@
 use super::value::UserId;
 use async_trait::async_trait;
 use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use std::collections::HashMap;
 use thiserror::Error;
 
 // 用户事件定义
@@ -25,6 +26,8 @@ pub enum UserError {
     UserNotFound(String),
     #[error("user is deleted")]
     UserDeleted,
+    #[error("user is disabled")]
+    UserDisabled,
     #[error("version conflict: {0}")]
     VersionConflictErr(i64),
     #[error("{0}")]
@@ -41,6 +44,7 @@ pub enum UserStatus {
     Active = 1,
     New = 2,
     Deleted = 3,
+    Disabled = 4,
 }
 
 impl From<UserStatus> for i32 {
@@ -56,11 +60,82 @@ impl TryFrom<i32> for UserStatus {
             1 => Ok(UserStatus::Active),
             2 => Ok(UserStatus::New),
             3 => Ok(UserStatus::Deleted),
+            4 => Ok(UserStatus::Disabled),
             _ => Err(format!("invalid value:{}", value)),
         }
     }
 }
 
+/// 用户角色（对应 Subsonic 的各项 role）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserRoles {
+    pub settings: bool,
+    pub stream: bool,
+    pub download: bool,
+    pub upload: bool,
+    pub playlist: bool,
+    pub cover_art: bool,
+    pub comment: bool,
+    pub podcast: bool,
+    pub jukebox: bool,
+    pub share: bool,
+}
+
+impl Default for UserRoles {
+    /// 与 Subsonic createUser 的默认值一致：只允许修改设置和播放
+    fn default() -> Self {
+        Self {
+            settings: true,
+            stream: true,
+            download: false,
+            upload: false,
+            playlist: false,
+            cover_art: false,
+            comment: false,
+            podcast: false,
+            jukebox: false,
+            share: false,
+        }
+    }
+}
+
+impl UserRoles {
+    /// 按位存储，顺序固定，新增角色只能追加
+    pub fn to_bits(&self) -> i32 {
+        [
+            self.settings,
+            self.stream,
+            self.download,
+            self.upload,
+            self.playlist,
+            self.cover_art,
+            self.comment,
+            self.podcast,
+            self.jukebox,
+            self.share,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, on)| if *on { bits | (1 << i) } else { bits })
+    }
+
+    pub fn from_bits(bits: i32) -> Self {
+        let on = |i: i32| bits & (1 << i) != 0;
+        Self {
+            settings: on(0),
+            stream: on(1),
+            download: on(2),
+            upload: on(3),
+            playlist: on(4),
+            cover_art: on(5),
+            comment: on(6),
+            podcast: on(7),
+            jukebox: on(8),
+            share: on(9),
+        }
+    }
+}
+
 /// 用户聚合根
 ///
 /// 用户是系统中的核心聚合根，代表有权访问系统的个体。
@@ -73,12 +148,16 @@ pub struct User {
     pub name: String,                     // 用户昵称/显示名称
     pub email: String,                    // 用户电子邮件地址
     pub is_admin: bool,                   // 用户是否为管理员
-    pub password: String,                 // 加密后的密码 (bcrypt)
+    pub password: String,                 // 登录密码的哈希 (Argon2id 或 bcrypt)
     pub encrypted_password: String,       // AES-256-GCM 加密的原始密码，用于 Subsonic token 认证
     pub last_login_at: NaiveDateTime,     // 最后登录时间
     pub last_access_at: NaiveDateTime,    // 最后访问时间
     pub last_op_time: NaiveDateTime,      // 新增: 表示command的时间
     pub status: UserStatus,               // 用户状态
+    pub roles: UserRoles,                 // 用户角色
+    pub max_bit_rate: Option<i32>,        // 最大比特率 (kbps)，None 表示不限制
+    pub download_quota_mb: Option<i64>,   // 每日下载配额 (MB)，None 表示不限制
+    pub hide_explicit: bool,              // 家长控制：浏览、搜索和随机歌曲中隐藏 explicit 内容
     pub version: i64,                     // 当前版本，用于乐观锁
     pub pending_events: Vec<UserEvent>,   // 用户事件列表
 }
@@ -111,6 +190,10 @@ impl User {
             last_access_at: DateTime::<Utc>::from_timestamp(0, 0).unwrap().naive_utc(),
             last_op_time: Local::now().naive_utc(),
             status: UserStatus::New,
+            roles: UserRoles::default(),
+            max_bit_rate: None,
+            download_quota_mb: None,
+            hide_explicit: false,
             version: 0,
             pending_events: Vec::new(),
         })
@@ -135,10 +218,57 @@ impl User {
     }
 
     pub fn is_active(&self) -> Result<(), UserError> {
+        match self.status {
+            UserStatus::Deleted => Err(UserError::UserDeleted),
+            UserStatus::Disabled => Err(UserError::UserDisabled),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn disable(&mut self) -> Result<&mut Self, UserError> {
+        if self.status == UserStatus::Deleted {
+            return Err(UserError::UserDeleted);
+        }
+        self.status = UserStatus::Disabled;
+        Ok(self)
+    }
+
+    pub fn enable(&mut self) -> Result<&mut Self, UserError> {
         if self.status == UserStatus::Deleted {
             return Err(UserError::UserDeleted);
         }
-        Ok(())
+        self.status = UserStatus::Active;
+        Ok(self)
+    }
+
+    pub fn set_roles(&mut self, roles: UserRoles) -> &mut Self {
+        self.roles = roles;
+        self
+    }
+
+    /// 设置比特率和下载配额，0 或负数表示不限制
+    pub fn set_limits(&mut self, max_bit_rate: Option<i32>, download_quota_mb: Option<i64>) -> &mut Self {
+        if let Some(max_bit_rate) = max_bit_rate {
+            self.max_bit_rate = Some(max_bit_rate).filter(|v| *v > 0);
+        }
+        if let Some(download_quota_mb) = download_quota_mb {
+            self.download_quota_mb = Some(download_quota_mb).filter(|v| *v > 0);
+        }
+        self
+    }
+
+    pub fn set_hide_explicit(&mut self, hide_explicit: bool) -> &mut Self {
+        self.hide_explicit = hide_explicit;
+        self
+    }
+
+    /// 按用户的比特率上限收紧客户端请求的比特率
+    pub fn limit_bit_rate(&self, requested: Option<i32>) -> Option<i32> {
+        match (self.max_bit_rate, requested.filter(|v| *v > 0)) {
+            (Some(limit), Some(requested)) => Some(requested.min(limit)),
+            (Some(limit), None) => Some(limit),
+            (None, requested) => requested,
+        }
     }
 
     // 从事件队列中拉取所有事件
@@ -167,6 +297,19 @@ pub trait UserRepository: Send + Sync {
 
     /// 删除用户
     async fn delete<'a>(&'a self, username: &'a str) -> Result<(), UserError>;
+
+    /// 按用户名排序分页查询
+    async fn find_page(&self, offset: u64, limit: u64) -> Result<Vec<User>, UserError>;
+}
+
+/// 用户偏好设置仓储（键值对，值由客户端自行解释）
+#[async_trait]
+pub trait UserPreferenceRepository: Send + Sync {
+    async fn find_all(&self, user_id: UserId) -> Result<HashMap<String, String>, UserError>;
+
+    async fn save(&self, user_id: UserId, key: &str, value: &str) -> Result<(), UserError>;
+
+    async fn delete(&self, user_id: UserId, key: &str) -> Result<(), UserError>;
 }
 
 /*
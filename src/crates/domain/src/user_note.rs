@@ -0,0 +1,88 @@
+use crate::annotation::Kind;
+use crate::value::UserId;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use thiserror::Error;
+
+/// 备注的最大字符数
+pub const MAX_NOTE_LENGTH: usize = 4000;
+
+#[derive(Error, Debug)]
+pub enum UserNoteError {
+    #[error("Note cannot be empty")]
+    Empty,
+    #[error("Note is too long: {0} characters, at most {MAX_NOTE_LENGTH}")]
+    TooLong(usize),
+    #[error("{0}")]
+    DbErr(String),
+}
+
+/// 用户对歌曲、专辑、艺术家或播放列表的个人备注，只有本人可见
+#[derive(Debug, Clone)]
+pub struct UserNote {
+    pub user_id: UserId,
+    pub kind: Kind,
+    pub item_id: i64,
+    pub note: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl UserNote {
+    pub fn new(
+        user_id: UserId,
+        kind: Kind,
+        item_id: i64,
+        note: &str,
+    ) -> Result<Self, UserNoteError> {
+        let note = validate(note)?;
+        let now = Utc::now().naive_utc();
+        Ok(Self {
+            user_id,
+            kind,
+            item_id,
+            note,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    pub fn update(&mut self, note: &str) -> Result<(), UserNoteError> {
+        self.note = validate(note)?;
+        self.updated_at = Utc::now().naive_utc();
+        Ok(())
+    }
+}
+
+/// 去掉首尾空白，删除备注应使用 delete 而不是保存空内容
+fn validate(note: &str) -> Result<String, UserNoteError> {
+    let note = note.trim();
+    if note.is_empty() {
+        return Err(UserNoteError::Empty);
+    }
+    let length = note.chars().count();
+    if length > MAX_NOTE_LENGTH {
+        return Err(UserNoteError::TooLong(length));
+    }
+    Ok(note.to_string())
+}
+
+#[async_trait]
+pub trait UserNoteRepository: Send + Sync {
+    async fn find(
+        &self,
+        user_id: &UserId,
+        kind: &Kind,
+        item_id: i64,
+    ) -> Result<Option<UserNote>, UserNoteError>;
+
+    async fn save(&self, note: &UserNote) -> Result<(), UserNoteError>;
+
+    /// 返回是否删除了备注
+    async fn delete(
+        &self,
+        user_id: &UserId,
+        kind: &Kind,
+        item_id: i64,
+    ) -> Result<bool, UserNoteError>;
+}
@@ -125,13 +125,19 @@ impl FileMeta {
 pub struct AudioMetadata {
     // 专辑相关信息
     pub album: String, // 专辑
+    pub album_version: Option<String>, // 专辑版本（如 "Deluxe"、"2019 Remaster"）
     pub participants: Vec<ParticipantMeta>,
     pub genres: Vec<String>,       // 流派
     pub track_number: Option<i32>, // 在专辑中的曲目编号
+    pub disc_number: Option<i32>,  // 碟片编号
+    pub disc_subtitle: Option<String>, // 碟片副标题（如 "Live"）
     pub title: String,             // 歌曲标题
 
     // 发行信息
-    pub year: Option<i32>, // 发行年份
+    pub year: Option<i32>,             // 发行年份
+    pub release_date: Option<String>,  // 本版本的发行日期（YYYY[-MM[-DD]]）
+    pub original_year: Option<i32>,    // 原始发行年份（再版时早于 year）
+    pub original_date: Option<String>, // 原始发行日期（YYYY[-MM[-DD]]）
 
     // 音频技术信息
     pub duration: i64,            // 音频时长（秒）
@@ -140,6 +146,31 @@ pub struct AudioMetadata {
     pub channels: i32,            // 声道数
     pub picture: Option<Vec<u8>>, // 封面图片
     pub lyrics: Option<String>,   // 歌词内容
+
+    // 附加标签
+    pub bpm: Option<i32>,             // 每分钟节拍数
+    pub comment: Option<String>,      // 注释
+    pub mbz_track_id: Option<String>, // MusicBrainz 录音 ID
+    pub replay_gain: ReplayGain,
+    pub explicit: bool, // 内容分级（ITUNESADVISORY）标记为不适宜未成年人
+
+    // 古典音乐的作品与乐章
+    pub work: Option<String>, // 作品，如 "Symphony No. 5 in C minor, Op. 67"
+    pub movement_name: Option<String>, // 乐章名，如 "Allegro con brio"
+    pub movement_number: Option<i32>, // 乐章序号
+    pub movement_count: Option<i32>, // 作品的乐章总数
+
+    // 有声书、DJ 混音等长文件的章节，按起始位置排序
+    pub chapters: Vec<Chapter>,
+}
+
+/// ReplayGain 标签，增益单位为 dB
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayGain {
+    pub track_gain: Option<f64>,
+    pub track_peak: Option<f64>,
+    pub album_gain: Option<f64>,
+    pub album_peak: Option<f64>,
 }
 
 impl Default for AudioMetadata {
@@ -148,18 +179,49 @@ impl Default for AudioMetadata {
             title: String::new(),
             participants: Vec::new(),
             album: String::new(),
+            album_version: None,
             genres: Vec::new(),
             track_number: None,
+            disc_number: None,
+            disc_subtitle: None,
             year: None,
+            release_date: None,
+            original_year: None,
+            original_date: None,
             duration: 0,
             bit_rate: 0,
             sample_rate: 0,
             channels: 0,
             picture: None,
             lyrics: None,
+            bpm: None,
+            comment: None,
+            mbz_track_id: None,
+            replay_gain: ReplayGain::default(),
+            explicit: false,
+            work: None,
+            movement_name: None,
+            movement_number: None,
+            movement_count: None,
+            chapters: Vec::new(),
         }
     }
 }
+
+/// 文件内的章节标记，位置单位为毫秒
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// 去掉首尾静音后有声音的部分，位置单位为毫秒，客户端据此安排交叉淡入淡出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudibleRange {
+    pub start: i64,
+    pub end: i64,
+}
 #[derive(Debug, Clone, PartialEq)]
 pub enum AudioQuality {
     Lossless,
@@ -179,6 +241,8 @@ pub enum FileType {
     Audio,
     Image,
     Nfo,
+    /// PDF 小册子等文档
+    Document,
     Other,
 }
 
@@ -188,6 +252,7 @@ impl From<FileType> for String {
             FileType::Audio => "audio".to_string(),
             FileType::Image => "image".to_string(),
             FileType::Nfo => "nfo".to_string(),
+            FileType::Document => "document".to_string(),
             FileType::Other => "other".to_string(),
         }
     }
@@ -200,6 +265,7 @@ impl TryFrom<String> for FileType {
             "audio" => Ok(FileType::Audio),
             "image" => Ok(FileType::Image),
             "nfo" => Ok(FileType::Nfo),
+            "document" => Ok(FileType::Document),
             "other" => Ok(FileType::Other),
             _ => Err(format!("invalid value:{}", value)),
         }
@@ -291,6 +357,12 @@ pub enum ParticipantRole {
     AlbumArtist,
     Artist,
     Performer,
+    Composer,
+    Lyricist,
+    Conductor,
+    Arranger,
+    Producer,
+    Remixer,
 }
 
 impl Display for ParticipantRole {
@@ -305,6 +377,12 @@ impl From<String> for ParticipantRole {
             "AlbumArtist" => ParticipantRole::AlbumArtist,
             "Artist" => ParticipantRole::Artist,
             "Performer" => ParticipantRole::Performer,
+            "Composer" => ParticipantRole::Composer,
+            "Lyricist" => ParticipantRole::Lyricist,
+            "Conductor" => ParticipantRole::Conductor,
+            "Arranger" => ParticipantRole::Arranger,
+            "Producer" => ParticipantRole::Producer,
+            "Remixer" => ParticipantRole::Remixer,
             _ => ParticipantRole::AlbumArtist, // Default fallback
         }
     }
@@ -316,6 +394,12 @@ impl From<&str> for ParticipantRole {
             "AlbumArtist" => ParticipantRole::AlbumArtist,
             "Artist" => ParticipantRole::Artist,
             "Performer" => ParticipantRole::Performer,
+            "Composer" => ParticipantRole::Composer,
+            "Lyricist" => ParticipantRole::Lyricist,
+            "Conductor" => ParticipantRole::Conductor,
+            "Arranger" => ParticipantRole::Arranger,
+            "Producer" => ParticipantRole::Producer,
+            "Remixer" => ParticipantRole::Remixer,
             _ => ParticipantRole::AlbumArtist, // Default fallback
         }
     }
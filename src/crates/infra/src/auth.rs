@@ -1,5 +1,9 @@
+use crate::config::{PasswordAlgorithm, PasswordHashingConfig};
 use application::auth::{TokenService, UserClaims};
 use application::error::AppError;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, SaltString};
+use argon2::{Argon2, Params, PasswordVerifier};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use bcrypt::hash as bcrypt_hash;
 use bcrypt::verify as bcrypt_verify;
@@ -53,6 +57,100 @@ impl application::auth::PasswordHasher for BcryptPasswordHasher {
     }
 }
 
+/// 按配置的算法生成哈希，验证时根据哈希前缀识别 Argon2id 或 bcrypt，
+/// 旧算法或参数变化后的哈希由 needs_rehash 报告，登录成功时重新计算
+#[derive(Debug, Clone)]
+pub struct ConfiguredPasswordHasher {
+    config: PasswordHashingConfig,
+}
+
+impl ConfiguredPasswordHasher {
+    pub fn new(config: PasswordHashingConfig) -> Self {
+        Self { config }
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>, AppError> {
+        let params = Params::new(
+            self.config.argon2_memory_kib,
+            self.config.argon2_iterations,
+            self.config.argon2_parallelism,
+            None,
+        )
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+        Ok(Argon2::new(
+            argon2::Algorithm::Argon2id,
+            argon2::Version::V0x13,
+            params,
+        ))
+    }
+}
+
+fn is_argon2(hashed_pwd: &str) -> bool {
+    hashed_pwd.starts_with("$argon2")
+}
+
+/// `$2b$<cost>$...`
+fn bcrypt_cost(hashed_pwd: &str) -> Option<u32> {
+    hashed_pwd.split('$').nth(2)?.parse().ok()
+}
+
+impl application::auth::PasswordHasher for ConfiguredPasswordHasher {
+    fn hash(&self, plain: &str) -> Result<String, AppError> {
+        match self.config.algorithm {
+            PasswordAlgorithm::Argon2id => {
+                let salt = SaltString::generate(&mut OsRng);
+                argon2::PasswordHasher::hash_password(&self.argon2()?, plain.as_bytes(), &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|e| AppError::AuthError(e.to_string()))
+            }
+            PasswordAlgorithm::Bcrypt => bcrypt_hash(plain, self.config.bcrypt_cost)
+                .map_err(|e| AppError::AuthError(e.to_string())),
+        }
+    }
+
+    fn verify(&self, pwd: &str, hashed_pwd: &str) -> Result<(), AppError> {
+        let valid = if is_argon2(hashed_pwd) {
+            // 参数取自哈希本身，修改配置不影响已有哈希的验证
+            PasswordHash::new(hashed_pwd).is_ok_and(|hash| {
+                Argon2::default()
+                    .verify_password(pwd.as_bytes(), &hash)
+                    .is_ok()
+            })
+        } else {
+            bcrypt_verify(pwd, hashed_pwd).unwrap_or(false)
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(AppError::AuthError("invalid password".to_string()))
+        }
+    }
+
+    fn needs_rehash(&self, hashed_pwd: &str) -> bool {
+        match self.config.algorithm {
+            PasswordAlgorithm::Argon2id => {
+                let Ok(hash) = PasswordHash::new(hashed_pwd) else {
+                    return true;
+                };
+                if hash.algorithm != argon2::ARGON2ID_IDENT {
+                    return true;
+                }
+                match Params::try_from(&hash) {
+                    Ok(params) => {
+                        params.m_cost() != self.config.argon2_memory_kib
+                            || params.t_cost() != self.config.argon2_iterations
+                            || params.p_cost() != self.config.argon2_parallelism
+                    }
+                    Err(_) => true,
+                }
+            }
+            PasswordAlgorithm::Bcrypt => {
+                is_argon2(hashed_pwd) || bcrypt_cost(hashed_pwd) != Some(self.config.bcrypt_cost)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct JwtTokenService {
     jwt_secret: String,
@@ -250,6 +348,69 @@ impl JwtTokenService {
     }
 }
 
+impl JwtTokenService {
+    fn stream_signature(
+        &self,
+        media_id: &str,
+        user_id: i64,
+        exp: i64,
+    ) -> Result<HmacSha256, AppError> {
+        let mut mac = HmacSha256::new_from_slice(self.jwt_secret.as_bytes())
+            .map_err(|e| AppError::AuthError(format!("Invalid key: {}", e)))?;
+        mac.update(format!("stream|{}|{}|{}", media_id, user_id, exp).as_bytes());
+        Ok(mac)
+    }
+
+    /// 签名媒体 URL 的 token，绑定媒体 ID、用户和过期时间
+    /// 格式: user_id.exp.base64(hmac 前 16 字节)，媒体 ID 由 URL 中的 id 参数提供
+    pub fn issue_stream_token(
+        &self,
+        media_id: &str,
+        user_id: i64,
+        exp: i64,
+    ) -> Result<String, AppError> {
+        let signature = self
+            .stream_signature(media_id, user_id, exp)?
+            .finalize()
+            .into_bytes();
+        Ok(format!(
+            "{}.{}.{}",
+            user_id,
+            exp,
+            URL_SAFE_NO_PAD.encode(&signature[..16])
+        ))
+    }
+
+    /// 验证签名媒体 URL 的 token，返回签发对象的用户 ID
+    pub fn verify_stream_token(&self, token: &str, media_id: &str) -> Result<i64, AppError> {
+        let mut parts = token.splitn(3, '.');
+        let (Some(user_id), Some(exp), Some(signature)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(AppError::AuthError("Invalid token format".to_string()));
+        };
+        let user_id: i64 = user_id
+            .parse()
+            .map_err(|_| AppError::AuthError("Invalid token format".to_string()))?;
+        let exp: i64 = exp
+            .parse()
+            .map_err(|_| AppError::AuthError("Invalid token format".to_string()))?;
+        if exp < Utc::now().timestamp() {
+            return Err(AppError::AuthError("Token expired".to_string()));
+        }
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| AppError::AuthError("Invalid signature".to_string()))?;
+        if signature.len() != 16 {
+            return Err(AppError::AuthError("Invalid signature".to_string()));
+        }
+        self.stream_signature(media_id, user_id, exp)?
+            .verify_truncated_left(&signature)
+            .map_err(|_| AppError::AuthError("Invalid signature".to_string()))?;
+        Ok(user_id)
+    }
+}
+
 // 实现 CoverArtTokenService trait，让 JwtTokenService 可以直接用于应用服务层
 impl application::query::shared::CoverArtTokenService for JwtTokenService {
     fn issue_cover_art_token(&self, cover_art_id: String) -> Result<String, AppError> {
@@ -257,3 +418,70 @@ impl application::query::shared::CoverArtTokenService for JwtTokenService {
         self.issue_cover_art_token_short(cover_art_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use application::auth::PasswordHasher;
+
+    fn hasher(algorithm: PasswordAlgorithm) -> ConfiguredPasswordHasher {
+        ConfiguredPasswordHasher::new(PasswordHashingConfig {
+            algorithm,
+            bcrypt_cost: 4,
+            argon2_memory_kib: 64,
+            argon2_iterations: 1,
+            argon2_parallelism: 1,
+        })
+    }
+
+    #[test]
+    fn verifies_both_algorithms_and_rehashes_bcrypt() {
+        let argon2 = hasher(PasswordAlgorithm::Argon2id);
+        let bcrypt = hasher(PasswordAlgorithm::Bcrypt);
+        let old = bcrypt.hash("secret").unwrap();
+        let new = argon2.hash("secret").unwrap();
+        assert!(new.starts_with("$argon2id$"));
+
+        for hashed in [&old, &new] {
+            assert!(argon2.verify("secret", hashed).is_ok());
+            assert!(argon2.verify("wrong", hashed).is_err());
+        }
+        assert!(argon2.needs_rehash(&old));
+        assert!(!argon2.needs_rehash(&new));
+        assert!(bcrypt.needs_rehash(&new));
+        assert!(!bcrypt.needs_rehash(&old));
+    }
+
+    #[test]
+    fn changed_parameters_require_rehash() {
+        let hashed = hasher(PasswordAlgorithm::Argon2id).hash("secret").unwrap();
+        let stronger = ConfiguredPasswordHasher::new(PasswordHashingConfig {
+            argon2_iterations: 2,
+            ..hasher(PasswordAlgorithm::Argon2id).config
+        });
+        assert!(stronger.verify("secret", &hashed).is_ok());
+        assert!(stronger.needs_rehash(&hashed));
+    }
+
+    #[test]
+    fn stream_token_is_bound_to_media_and_user() {
+        let service = JwtTokenService::new("secret", 3600);
+        let exp = Utc::now().timestamp() + 60;
+        let token = service.issue_stream_token("42", 7, exp).unwrap();
+
+        assert_eq!(service.verify_stream_token(&token, "42").unwrap(), 7);
+        assert!(service.verify_stream_token(&token, "43").is_err());
+        let forged = token.replacen("7.", "8.", 1);
+        assert!(service.verify_stream_token(&forged, "42").is_err());
+        let other_key = JwtTokenService::new("other", 3600);
+        assert!(other_key.verify_stream_token(&token, "42").is_err());
+    }
+
+    #[test]
+    fn expired_stream_token_is_rejected() {
+        let service = JwtTokenService::new("secret", 3600);
+        let exp = Utc::now().timestamp() - 1;
+        let token = service.issue_stream_token("42", 7, exp).unwrap();
+        assert!(service.verify_stream_token(&token, "42").is_err());
+    }
+}
@@ -1,14 +1,22 @@
 use crate::auth::AuthConfig;
+use crate::normalize::SharedArticles;
+use crate::storage::read_ahead::ReadAheadOptions;
+use application::command::settings::{Settings, SettingsListener};
 use config::{Config, Environment, File};
 use domain::cover_art::CoverSourceType;
 use dotenvy::dotenv;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::error::Error;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::fmt;
+use std::net::IpAddr;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use std::sync::RwLock;
+use std::time::Duration;
 
 /// 艺术家占位图文件名
 const PLACE_HOLDER_ARTIST_ART: &str = "artist-placeholder.webp";
@@ -47,10 +55,17 @@ struct RawConfig {
     auto_login_username: String,
     jwt_expire_secs: i64,
     jwt_secret_key: String,
+    /// 签名媒体 URL 的有效期（秒）
+    stream_token_expire_secs: i64,
     password_encryption_key: String,
+    /// bcrypt 的 cost，password_hashing.algorithm 为 bcrypt 时使用
     salt_cost: i32,
+    /// 登录密码的哈希算法和参数
+    password_hashing: RawPasswordHashingConfig,
     ignoredarticles: String,
     indexgroups: String,
+    /// 中日韩等非拉丁文字的艺术家按罗马字首字母分组，关闭时归入 "#"
+    index_romanize: bool,
     database_url: String,
     cover_art_source_priority: HashMap<String, f32>,
     base_url: String,
@@ -64,6 +79,42 @@ struct RawConfig {
     server: RawServerConfig,
     /// 转码配置
     transcoding: RawTranscodingConfig,
+    /// 元数据规则配置
+    metadata: RawMetadataConfig,
+    /// 缓冲仓库（入库写入）配置
+    ingest: RawIngestConfig,
+    /// 扫描解析配置
+    scan: RawScanConfig,
+    /// DLNA/UPnP 媒体服务器配置
+    dlna: RawDlnaConfig,
+    /// GraphQL 接口配置
+    graphql: RawGraphqlConfig,
+    /// 登录和 Subsonic 认证的限流配置
+    rate_limit: RawRateLimitConfig,
+    /// HTTPS 配置
+    tls: RawTlsConfig,
+    /// 用户上传配置
+    upload: RawUploadConfig,
+    /// 回收站配置
+    trash: RawTrashConfig,
+    /// 播放历史配置
+    playback_history: RawPlaybackHistoryConfig,
+    /// 备份与恢复配置
+    backup: RawBackupConfig,
+    /// 每个请求的数据库查询预算
+    query_budget: RawQueryBudgetConfig,
+    /// 存储读取配置
+    storage: RawStorageConfig,
+    /// 跨域请求配置
+    cors: RawCorsConfig,
+    /// 安全响应头配置
+    security_headers: RawSecurityHeadersConfig,
+    /// MusicBrainz 作品目录配置
+    musicbrainz: RawMusicBrainzConfig,
+    /// 日志级别（off/error/warn/info/debug/trace）
+    log_level: String,
+    /// 雪花 ID 节点号（0-1023），多实例部署时每个实例必须不同；未配置时由主机名派生
+    node_id: Option<i64>,
 }
 
 /// 音乐库配置（原始配置）
@@ -119,6 +170,8 @@ struct RawTranscodingConfig {
     chunk_size: usize,
     /// 无损格式列表（这些格式在请求时会被转码）
     lossless_formats: Vec<String>,
+    /// 转码输出中写入标签和封面的目标格式（mp3、aac）
+    embed_metadata_formats: Vec<String>,
 }
 
 impl Default for RawTranscodingConfig {
@@ -139,10 +192,591 @@ impl Default for RawTranscodingConfig {
                 "dff".to_string(),
                 "wv".to_string(),
             ],
+            embed_metadata_formats: Vec::new(),
         }
     }
 }
 
+/// 元数据规则配置（原始配置）
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct RawMetadataConfig {
+    /// 自定义规则文件路径（TOML）
+    rules_file: String,
+    /// 规则文件变更检查间隔（秒），0 表示不热加载
+    reload_interval_secs: u64,
+    /// 编辑元数据时是否回写文件标签
+    write_tags: bool,
+    /// 同名专辑的不同版本（Deluxe、Remaster 等）是否作为不同的专辑
+    separate_editions: bool,
+}
+
+impl Default for RawMetadataConfig {
+    fn default() -> Self {
+        Self {
+            rules_file: "metadata_rules.toml".to_string(),
+            reload_interval_secs: 30,
+            write_tags: false,
+            separate_editions: false,
+        }
+    }
+}
+
+/// 单个缓冲仓库的参数（原始配置），未配置的项沿用上一级
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawBufferConfig {
+    /// memtable 容量，达到后触发 flush
+    cache_capacity: Option<usize>,
+    /// 同时写库的 flush 任务数（统计类仓库不使用）
+    concurrency: Option<usize>,
+    /// 未达到容量时的 flush 间隔（秒）
+    flush_timeout_secs: Option<u64>,
+    /// 允许同时等待 flush 的 memtable 数，超过后写入等待
+    max_pending_flushes: Option<usize>,
+}
+
+impl RawBufferConfig {
+    fn or(&self, fallback: &RawBufferConfig) -> RawBufferConfig {
+        RawBufferConfig {
+            cache_capacity: self.cache_capacity.or(fallback.cache_capacity),
+            concurrency: self.concurrency.or(fallback.concurrency),
+            flush_timeout_secs: self.flush_timeout_secs.or(fallback.flush_timeout_secs),
+            max_pending_flushes: self.max_pending_flushes.or(fallback.max_pending_flushes),
+        }
+    }
+}
+
+/// 入库配置（原始配置）：[ingest] 下的参数作用于所有缓冲仓库，
+/// [ingest.<仓库>] 中的参数覆盖单个仓库
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawIngestConfig {
+    cache_capacity: Option<usize>,
+    concurrency: Option<usize>,
+    flush_timeout_secs: Option<u64>,
+    max_pending_flushes: Option<usize>,
+    album: RawBufferConfig,
+    artist: RawBufferConfig,
+    genre: RawBufferConfig,
+    audio_file: RawBufferConfig,
+    cover_art: RawBufferConfig,
+    album_stats: RawBufferConfig,
+    genre_stats: RawBufferConfig,
+    participant_stats: RawBufferConfig,
+    format_stats: RawBufferConfig,
+}
+
+impl RawIngestConfig {
+    fn defaults(&self) -> RawBufferConfig {
+        RawBufferConfig {
+            cache_capacity: self.cache_capacity,
+            concurrency: self.concurrency,
+            flush_timeout_secs: self.flush_timeout_secs,
+            max_pending_flushes: self.max_pending_flushes,
+        }
+    }
+
+    fn repository(&self, repo: BufferedRepository) -> &RawBufferConfig {
+        match repo {
+            BufferedRepository::Album => &self.album,
+            BufferedRepository::Artist => &self.artist,
+            BufferedRepository::Genre => &self.genre,
+            BufferedRepository::AudioFile => &self.audio_file,
+            BufferedRepository::CoverArt => &self.cover_art,
+            BufferedRepository::AlbumStats => &self.album_stats,
+            BufferedRepository::GenreStats => &self.genre_stats,
+            BufferedRepository::ParticipantStats => &self.participant_stats,
+            BufferedRepository::FormatStats => &self.format_stats,
+        }
+    }
+
+    /// 合并为每个仓库的最终参数：仓库配置 > [ingest] 配置 > 内置默认值
+    fn resolve(&self) -> IngestConfig {
+        let defaults = self.defaults();
+        let repositories = BufferedRepository::ALL
+            .into_iter()
+            .map(|repo| {
+                let builtin = repo.default_config();
+                let raw = self.repository(repo).or(&defaults);
+                let config = BufferConfig {
+                    cache_capacity: raw.cache_capacity.unwrap_or(builtin.cache_capacity),
+                    concurrency: raw.concurrency.unwrap_or(builtin.concurrency),
+                    flush_timeout: raw
+                        .flush_timeout_secs
+                        .map(Duration::from_secs)
+                        .unwrap_or(builtin.flush_timeout),
+                    max_pending_flushes: raw
+                        .max_pending_flushes
+                        .unwrap_or(builtin.max_pending_flushes),
+                };
+                (repo, config)
+            })
+            .collect();
+        IngestConfig { repositories }
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |key: String, message: &str| {
+            Err(ConfigError::Invalid {
+                key,
+                message: message.to_string(),
+            })
+        };
+        let defaults = self.defaults();
+        let sections = std::iter::once(("ingest".to_string(), &defaults)).chain(
+            BufferedRepository::ALL
+                .into_iter()
+                .map(|repo| (format!("ingest.{}", repo.key()), self.repository(repo))),
+        );
+        for (section, raw) in sections {
+            if raw
+                .cache_capacity
+                .is_some_and(|n| !(1..=MAX_BUFFER_CAPACITY).contains(&n))
+            {
+                return invalid(
+                    format!("{}.cache_capacity", section),
+                    "must be between 1 and 100000",
+                );
+            }
+            if raw
+                .concurrency
+                .is_some_and(|n| !(1..=MAX_BUFFER_CONCURRENCY).contains(&n))
+            {
+                return invalid(
+                    format!("{}.concurrency", section),
+                    "must be between 1 and 64",
+                );
+            }
+            if raw
+                .flush_timeout_secs
+                .is_some_and(|n| !(1..=MAX_FLUSH_TIMEOUT_SECS).contains(&n))
+            {
+                return invalid(
+                    format!("{}.flush_timeout_secs", section),
+                    "must be between 1 and 3600",
+                );
+            }
+            if raw
+                .max_pending_flushes
+                .is_some_and(|n| !(1..=MAX_PENDING_FLUSHES).contains(&n))
+            {
+                return invalid(
+                    format!("{}.max_pending_flushes", section),
+                    "must be between 1 and 16",
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 扫描解析配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawScanConfig {
+    /// 并行解析的 worker 数
+    parse_workers: usize,
+    /// 每个 worker 的待解析队列长度
+    queue_capacity: usize,
+    /// 按存储协议限制同时解析的文件数
+    io_limits: HashMap<String, usize>,
+    /// 扫描结束后用 ffmpeg 分析新歌的首尾静音，供客户端交叉淡入淡出
+    analyze_silence: bool,
+}
+
+impl Default for RawScanConfig {
+    fn default() -> Self {
+        Self {
+            parse_workers: 4,
+            queue_capacity: 256,
+            io_limits: HashMap::new(),
+            analyze_silence: false,
+        }
+    }
+}
+
+/// DLNA 配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawDlnaConfig {
+    /// 是否在局域网内通过 SSDP 广播媒体服务器
+    enabled: bool,
+    /// 电视、功放等设备上显示的服务器名称
+    friendly_name: String,
+    /// 重复发送 ssdp:alive 通知的间隔（秒）
+    advertise_interval_secs: u64,
+}
+
+impl Default for RawDlnaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            friendly_name: "Rhythm".to_string(),
+            advertise_interval_secs: 900,
+        }
+    }
+}
+
+/// GraphQL 配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawGraphqlConfig {
+    /// 是否开放 /graphql 接口
+    enabled: bool,
+    /// 查询允许的最大嵌套深度
+    max_depth: usize,
+    /// 查询允许的最大复杂度（每个字段计 1）
+    max_complexity: usize,
+}
+
+impl Default for RawGraphqlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_depth: 10,
+            max_complexity: 2000,
+        }
+    }
+}
+
+/// 认证限流配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawRateLimitConfig {
+    enabled: bool,
+    /// 每个 IP 的令牌桶容量
+    ip_burst: u32,
+    /// 每个 IP 每分钟补充的令牌数
+    ip_refill_per_minute: u32,
+    /// 每个用户名的令牌桶容量
+    user_burst: u32,
+    /// 每个用户名每分钟补充的令牌数
+    user_refill_per_minute: u32,
+    /// 统计窗口内连续失败多少次后锁定
+    max_failures: u32,
+    /// 失败次数的统计窗口（秒）
+    failure_window_secs: u64,
+    /// 锁定时长（秒）
+    lockout_secs: u64,
+}
+
+impl Default for RawRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ip_burst: 120,
+            ip_refill_per_minute: 600,
+            user_burst: 120,
+            user_refill_per_minute: 600,
+            max_failures: 5,
+            failure_window_secs: 300,
+            lockout_secs: 900,
+        }
+    }
+}
+
+/// 用户上传配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawUploadConfig {
+    enabled: bool,
+    /// 上传文件写入的音乐库，必须是本地库
+    library_id: Option<i64>,
+    /// 库根目录下的上传目录，每个用户一个子目录
+    directory: String,
+    /// 单个文件的大小上限（MB）
+    max_file_size_mb: u64,
+}
+
+impl Default for RawUploadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            library_id: None,
+            directory: "uploads".to_string(),
+            max_file_size_mb: 1024,
+        }
+    }
+}
+
+/// 回收站配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawTrashConfig {
+    /// 被删除的歌曲和专辑文件移入的目录
+    directory: String,
+    /// 保留天数，过期后永久删除，同样用于最近删除的播放列表；0 表示不自动清理
+    retention_days: u32,
+}
+
+impl Default for RawTrashConfig {
+    fn default() -> Self {
+        Self {
+            directory: "./data/trash".to_string(),
+            retention_days: 30,
+        }
+    }
+}
+
+/// 密码哈希配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawPasswordHashingConfig {
+    /// 新密码使用的算法：argon2id 或 bcrypt。其他算法的旧哈希在登录成功时重新计算
+    algorithm: String,
+    /// Argon2id 内存开销（KiB）
+    argon2_memory_kib: u32,
+    /// Argon2id 迭代次数
+    argon2_iterations: u32,
+    /// Argon2id 并行度
+    argon2_parallelism: u32,
+}
+
+impl Default for RawPasswordHashingConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: "argon2id".to_string(),
+            argon2_memory_kib: 19 * 1024,
+            argon2_iterations: 2,
+            argon2_parallelism: 1,
+        }
+    }
+}
+
+/// 播放历史配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+struct RawPlaybackHistoryConfig {
+    /// 保留天数，更早的记录汇总进播放统计后删除；0 表示永久保留
+    retention_days: u32,
+}
+
+/// 备份与恢复配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawBackupConfig {
+    /// 上传恢复的备份文件大小上限（MB）
+    max_restore_size_mb: u64,
+}
+
+impl Default for RawBackupConfig {
+    fn default() -> Self {
+        Self {
+            max_restore_size_mb: 4096,
+        }
+    }
+}
+
+/// 数据库查询预算（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawQueryBudgetConfig {
+    /// 单个请求的查询次数上限，超过时记录警告；0 表示不检查
+    max_queries: u64,
+    /// 单个请求的数据库总耗时上限（毫秒）；0 表示不检查
+    max_db_ms: u64,
+    /// 单条查询超过该耗时（毫秒）时记录慢查询；0 表示不记录
+    slow_query_ms: u64,
+}
+
+impl Default for RawQueryBudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_queries: 50,
+            max_db_ms: 1000,
+            slow_query_ms: 200,
+        }
+    }
+}
+
+/// 存储读取配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawStorageConfig {
+    /// 远程存储（SMB）播放和转码时每次读取的大小（KB）
+    read_ahead_chunk_kb: usize,
+    /// 提前读好的块数
+    read_ahead_chunks: usize,
+}
+
+impl Default for RawStorageConfig {
+    fn default() -> Self {
+        let read_ahead = ReadAheadOptions::default();
+        Self {
+            read_ahead_chunk_kb: read_ahead.chunk_size / 1024,
+            read_ahead_chunks: read_ahead.lookahead,
+        }
+    }
+}
+
+/// MusicBrainz 配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawMusicBrainzConfig {
+    /// 是否开放缺失专辑接口（需要访问 musicbrainz.org）
+    enabled: bool,
+    base_url: String,
+    /// MusicBrainz 要求 User-Agent 带有应用名称和联系方式
+    user_agent: String,
+    /// 发行组列表的缓存天数
+    cache_ttl_days: u32,
+    /// 参与比较的发行组类型
+    release_types: Vec<String>,
+}
+
+impl Default for RawMusicBrainzConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: "https://musicbrainz.org".to_string(),
+            user_agent: concat!(
+                "rhythm/",
+                env!("CARGO_PKG_VERSION"),
+                " ( https://github.com/netscane/rhythm )"
+            )
+            .to_string(),
+            cache_ttl_days: 30,
+            release_types: vec!["album".to_string(), "ep".to_string()],
+        }
+    }
+}
+
+/// HTTPS 配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawTlsConfig {
+    /// 启用后所有 TCP 监听地址都使用 HTTPS，Unix 套接字不受影响
+    enabled: bool,
+    /// PEM 格式的证书链
+    cert_path: String,
+    /// PEM 格式的私钥
+    key_path: String,
+    /// 检查证书文件变化的间隔（秒），0 表示不检查
+    reload_interval_secs: u64,
+    /// 在这些地址上监听 HTTP，并重定向到 HTTPS
+    redirect_http_listen: Vec<String>,
+    /// 配置域名后通过 ACME 自动申请证书，不再使用 cert_path 和 key_path
+    acme: RawAcmeConfig,
+}
+
+impl Default for RawTlsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cert_path: "".to_string(),
+            key_path: "".to_string(),
+            reload_interval_secs: 3600,
+            redirect_http_listen: Vec::new(),
+            acme: RawAcmeConfig::default(),
+        }
+    }
+}
+
+/// ACME 证书配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawAcmeConfig {
+    /// 申请证书的域名
+    domains: Vec<String>,
+    /// 联系邮箱
+    contact: Vec<String>,
+    /// 账户和证书的缓存目录
+    cache_dir: String,
+    /// false 时使用 Let's Encrypt 测试环境
+    production: bool,
+}
+
+impl Default for RawAcmeConfig {
+    fn default() -> Self {
+        Self {
+            domains: Vec::new(),
+            contact: Vec::new(),
+            cache_dir: "./data/acme".to_string(),
+            production: false,
+        }
+    }
+}
+
+/// 跨域请求配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawCorsConfig {
+    /// 允许的来源（如 https://music.example.com），为空时允许任意来源
+    allowed_origins: Vec<String>,
+    /// 允许的请求头，为空时允许任意请求头
+    allowed_headers: Vec<String>,
+    /// 是否允许携带 Cookie 和认证信息，需要同时配置 allowed_origins
+    allow_credentials: bool,
+    /// 预检请求结果的缓存时间（秒）
+    max_age_secs: usize,
+}
+
+impl Default for RawCorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_headers: Vec::new(),
+            allow_credentials: false,
+            max_age_secs: 3600,
+        }
+    }
+}
+
+/// 安全响应头配置（原始配置）
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+struct RawSecurityHeadersConfig {
+    /// 预设：off、development 或 production，下面的选项覆盖预设中的值
+    preset: String,
+    /// 是否发送 Strict-Transport-Security
+    hsts: Option<bool>,
+    hsts_max_age_secs: u64,
+    /// 是否发送 X-Content-Type-Options: nosniff
+    content_type_options: Option<bool>,
+    /// Web UI 页面的 Content-Security-Policy，设为空字符串时不发送
+    ui_content_security_policy: Option<String>,
+}
+
+impl Default for RawSecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            preset: "development".to_string(),
+            hsts: None,
+            hsts_max_age_secs: 31_536_000,
+            content_type_options: None,
+            ui_content_security_policy: None,
+        }
+    }
+}
+
+const SECURITY_HEADER_PRESETS: [&str; 3] = ["off", "development", "production"];
+
+/// 开发环境下 UI 可能连接其他服务器，并由开发工具注入脚本
+const DEVELOPMENT_UI_CSP: &str =
+    "default-src 'self'; script-src 'self' 'unsafe-inline' 'unsafe-eval'; \
+     style-src 'self' 'unsafe-inline'; img-src * data: blob:; media-src * blob:; connect-src *";
+
+const PRODUCTION_UI_CSP: &str =
+    "default-src 'self'; script-src 'self'; style-src 'self' 'unsafe-inline'; \
+     img-src 'self' data: blob:; media-src 'self' blob:; connect-src 'self'; \
+     frame-ancestors 'none'; base-uri 'self'; form-action 'self'";
+
+/// 只接受 scheme://host[:port] 形式，不带路径
+fn is_valid_origin(origin: &str) -> bool {
+    let Some((scheme, host)) = origin.split_once("://") else {
+        return false;
+    };
+    matches!(scheme, "http" | "https")
+        && !host.is_empty()
+        && !host.contains(['/', '?', '#', ' ', '*'])
+}
+
+const MAX_BUFFER_CAPACITY: usize = 100_000;
+const MAX_BUFFER_CONCURRENCY: usize = 64;
+const MAX_FLUSH_TIMEOUT_SECS: u64 = 3600;
+const MAX_PENDING_FLUSHES: usize = 16;
+
 /// 服务器配置（原始配置）
 #[derive(Debug, Deserialize)]
 #[serde(default)]
@@ -151,126 +785,1113 @@ struct RawServerConfig {
     host: String,
     /// 监听端口
     port: u16,
-    /// UI 静态文件目录路径
+    /// 是否提供 Web UI，只需要 API 时可关闭
+    ui_enabled: bool,
+    /// UI 静态文件目录路径，为空或目录不存在时使用内嵌的 UI（需启用 embedded-ui 编译）
     ui_path: String,
     /// UI 挂载的 URL 路径
     ui_base_path: String,
+    /// 关闭时等待请求和后台写入完成的最长时间（秒）
+    shutdown_timeout_secs: u64,
+    /// 额外的监听地址（host:port 或 unix:/path），配置后不再使用 host 和 port
+    listen: Vec<String>,
+    /// 受信任的反向代理（IP 或 CIDR），只有来自这些地址的 X-Forwarded-For 才会被采用
+    trusted_proxies: Vec<String>,
+}
+
+impl Default for RawServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 5533,
+            ui_enabled: true,
+            ui_path: "ui/dist".to_string(),
+            ui_base_path: "/app".to_string(),
+            shutdown_timeout_secs: 30,
+            listen: Vec::new(),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+impl RawServerConfig {
+    fn listen_addresses(&self) -> Vec<ListenAddress> {
+        self.listen
+            .iter()
+            .filter_map(|address| address.parse().ok())
+            .collect()
+    }
+
+    fn trusted_proxies(&self) -> Vec<IpNetwork> {
+        self.trusted_proxies
+            .iter()
+            .filter_map(|network| network.parse().ok())
+            .collect()
+    }
+}
+
+impl Default for RawConfig {
+    fn default() -> Self {
+        Self {
+            auto_login_username: "".to_string(),
+            jwt_expire_secs: 3600,
+            salt_cost: 10,
+            password_hashing: RawPasswordHashingConfig::default(),
+            jwt_secret_key: "secret".to_string(),
+            stream_token_expire_secs: 21600,
+            password_encryption_key: "default_password_encryption_key".to_string(),
+            ignoredarticles: "The El La Los Las Le Les Os As O A".to_string(),
+            indexgroups: "A B C D E F G H I J K L M N O P Q R S T U V W X-Z(XYZ) [Unknown]([)"
+                .to_string(),
+            index_romanize: true,
+            database_url: "".to_string(),
+            cover_art_source_priority: HashMap::from([
+                ("embedded".to_string(), 15.0),
+                ("external".to_string(), 10.0),
+                ("downloaded".to_string(), 5.0),
+                ("generated".to_string(), 2.0),
+                ("manual".to_string(), 8.0),
+            ]),
+            base_url: "http://192.168.2.31:5533".to_string(),
+            cover_art_wildcards: vec![
+                "cover.*".to_string(),
+                "folder.*".to_string(),
+                "front.*".to_string(),
+                "album.*".to_string(),
+                "albumart.*".to_string(),
+                "*".to_string(),
+            ],
+            music_folders: vec![],
+            cache: RawCacheConfig::default(),
+            server: RawServerConfig::default(),
+            transcoding: RawTranscodingConfig::default(),
+            metadata: RawMetadataConfig::default(),
+            ingest: RawIngestConfig::default(),
+            scan: RawScanConfig::default(),
+            dlna: RawDlnaConfig::default(),
+            graphql: RawGraphqlConfig::default(),
+            rate_limit: RawRateLimitConfig::default(),
+            tls: RawTlsConfig::default(),
+            upload: RawUploadConfig::default(),
+            trash: RawTrashConfig::default(),
+            playback_history: RawPlaybackHistoryConfig::default(),
+            backup: RawBackupConfig::default(),
+            query_budget: RawQueryBudgetConfig::default(),
+            storage: RawStorageConfig::default(),
+            cors: RawCorsConfig::default(),
+            security_headers: RawSecurityHeadersConfig::default(),
+            musicbrainz: RawMusicBrainzConfig::default(),
+            log_level: std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            node_id: None,
+        }
+    }
+}
+
+impl RawConfig {
+    /// 校验取值范围，错误信息中带上出错的配置键
+    fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |key: &str, message: &str| {
+            Err(ConfigError::Invalid {
+                key: key.to_string(),
+                message: message.to_string(),
+            })
+        };
+        if self.database_url.trim().is_empty() {
+            return invalid("database_url", "must not be empty");
+        }
+        if self.server.port == 0 {
+            return invalid("server.port", "must be between 1 and 65535");
+        }
+        let ui_base_path = &self.server.ui_base_path;
+        if !ui_base_path.starts_with('/') || ui_base_path.len() < 2 || ui_base_path.ends_with('/') {
+            return invalid(
+                "server.ui_base_path",
+                "must start with / and must not be / or end with /",
+            );
+        }
+        for (i, address) in self.server.listen.iter().enumerate() {
+            if let Err(message) = address.parse::<ListenAddress>() {
+                return invalid(&format!("server.listen[{}]", i), &message);
+            }
+        }
+        for (i, network) in self.server.trusted_proxies.iter().enumerate() {
+            if let Err(message) = network.parse::<IpNetwork>() {
+                return invalid(&format!("server.trusted_proxies[{}]", i), &message);
+            }
+        }
+        if self.transcoding.default_bit_rate <= 0 {
+            return invalid("transcoding.default_bit_rate", "must be positive");
+        }
+        if self.transcoding.chunk_size == 0 {
+            return invalid("transcoding.chunk_size", "must be positive");
+        }
+        for (i, format) in self.transcoding.embed_metadata_formats.iter().enumerate() {
+            if !EMBED_METADATA_FORMATS.contains(&format.to_lowercase().as_str()) {
+                return invalid(
+                    &format!("transcoding.embed_metadata_formats[{}]", i),
+                    "must be one of mp3, aac, m4a",
+                );
+            }
+        }
+        if self.stream_token_expire_secs <= 0 {
+            return invalid("stream_token_expire_secs", "must be positive");
+        }
+        if self.salt_cost < 4 || self.salt_cost > 31 {
+            return invalid("salt_cost", "must be between 4 and 31");
+        }
+        if let Err(message) = self.password_hashing.algorithm.parse::<PasswordAlgorithm>() {
+            return invalid("password_hashing.algorithm", &message);
+        }
+        if self.password_hashing.argon2_iterations == 0 {
+            return invalid("password_hashing.argon2_iterations", "must be positive");
+        }
+        if !(1..=255).contains(&self.password_hashing.argon2_parallelism) {
+            return invalid(
+                "password_hashing.argon2_parallelism",
+                "must be between 1 and 255",
+            );
+        }
+        if self.password_hashing.argon2_memory_kib < 8 * self.password_hashing.argon2_parallelism {
+            return invalid(
+                "password_hashing.argon2_memory_kib",
+                "must be at least 8 times argon2_parallelism",
+            );
+        }
+        if self.log_level.parse::<log::LevelFilter>().is_err() {
+            return invalid(
+                "log_level",
+                "must be one of off, error, warn, info, debug, trace",
+            );
+        }
+        if self.node_id.is_some_and(|id| !(0..=1023).contains(&id)) {
+            return invalid("node_id", "must be between 0 and 1023");
+        }
+        self.ingest.validate()?;
+        if !(1..=64).contains(&self.scan.parse_workers) {
+            return invalid("scan.parse_workers", "must be between 1 and 64");
+        }
+        if !(1..=MAX_BUFFER_CAPACITY).contains(&self.scan.queue_capacity) {
+            return invalid("scan.queue_capacity", "must be between 1 and 100000");
+        }
+        for (protocol, limit) in &self.scan.io_limits {
+            if *limit == 0 {
+                return invalid(&format!("scan.io_limits.{}", protocol), "must be positive");
+            }
+        }
+        if !(60..=3600).contains(&self.dlna.advertise_interval_secs) {
+            return invalid(
+                "dlna.advertise_interval_secs",
+                "must be between 60 and 3600",
+            );
+        }
+        if self.dlna.enabled && self.dlna.friendly_name.trim().is_empty() {
+            return invalid("dlna.friendly_name", "must not be empty");
+        }
+        if self.musicbrainz.enabled && self.musicbrainz.user_agent.trim().is_empty() {
+            return invalid("musicbrainz.user_agent", "must not be empty");
+        }
+        if !(1..=64).contains(&self.graphql.max_depth) {
+            return invalid("graphql.max_depth", "must be between 1 and 64");
+        }
+        if self.graphql.max_complexity == 0 {
+            return invalid("graphql.max_complexity", "must be positive");
+        }
+        let rate_limit = &self.rate_limit;
+        for (field, value) in [
+            ("rate_limit.ip_burst", rate_limit.ip_burst),
+            ("rate_limit.ip_refill_per_minute", rate_limit.ip_refill_per_minute),
+            ("rate_limit.user_burst", rate_limit.user_burst),
+            ("rate_limit.user_refill_per_minute", rate_limit.user_refill_per_minute),
+            ("rate_limit.max_failures", rate_limit.max_failures),
+        ] {
+            if value == 0 {
+                return invalid(field, "must be positive");
+            }
+        }
+        if rate_limit.failure_window_secs == 0 {
+            return invalid("rate_limit.failure_window_secs", "must be positive");
+        }
+        if rate_limit.lockout_secs == 0 {
+            return invalid("rate_limit.lockout_secs", "must be positive");
+        }
+        let tls = &self.tls;
+        let has_cert_files = !tls.cert_path.is_empty() || !tls.key_path.is_empty();
+        if tls.acme.domains.is_empty() {
+            if tls.enabled && (tls.cert_path.is_empty() || tls.key_path.is_empty()) {
+                return invalid(
+                    "tls.cert_path",
+                    "cert_path and key_path are required unless tls.acme.domains is set",
+                );
+            }
+        } else if has_cert_files {
+            return invalid("tls.acme.domains", "cannot be combined with cert_path/key_path");
+        }
+        if tls.acme.domains.iter().any(|domain| domain.trim().is_empty()) {
+            return invalid("tls.acme.domains", "must not contain empty domains");
+        }
+        if tls.acme.contact.iter().any(|email| !email.contains('@')) {
+            return invalid("tls.acme.contact", "must be email addresses");
+        }
+        for (i, address) in tls.redirect_http_listen.iter().enumerate() {
+            if !matches!(address.parse::<ListenAddress>(), Ok(ListenAddress::Tcp(_))) {
+                return invalid(
+                    &format!("tls.redirect_http_listen[{}]", i),
+                    "must be host:port",
+                );
+            }
+        }
+        let upload = &self.upload;
+        if upload.enabled && upload.library_id.is_none() {
+            return invalid("upload.library_id", "is required when upload is enabled");
+        }
+        let directory = Path::new(&upload.directory);
+        if upload.directory.trim().is_empty()
+            || !directory
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return invalid(
+                "upload.directory",
+                "must be a relative path inside the library without ..",
+            );
+        }
+        if upload.max_file_size_mb == 0 {
+            return invalid("upload.max_file_size_mb", "must be positive");
+        }
+        if self.trash.directory.trim().is_empty() {
+            return invalid("trash.directory", "must not be empty");
+        }
+        if self.backup.max_restore_size_mb == 0 {
+            return invalid("backup.max_restore_size_mb", "must be positive");
+        }
+        if self.storage.read_ahead_chunk_kb == 0 {
+            return invalid("storage.read_ahead_chunk_kb", "must be positive");
+        }
+        if self.storage.read_ahead_chunks == 0 {
+            return invalid("storage.read_ahead_chunks", "must be positive");
+        }
+        let cors = &self.cors;
+        for (i, origin) in cors.allowed_origins.iter().enumerate() {
+            if !is_valid_origin(origin) {
+                return invalid(
+                    &format!("cors.allowed_origins[{}]", i),
+                    "must be scheme://host[:port] without a path",
+                );
+            }
+        }
+        for (i, header) in cors.allowed_headers.iter().enumerate() {
+            if header.is_empty()
+                || !header
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            {
+                return invalid(
+                    &format!("cors.allowed_headers[{}]", i),
+                    "must be a header name",
+                );
+            }
+        }
+        // 任意来源都能带上用户的 Cookie 等同于关闭同源保护
+        if cors.allow_credentials && cors.allowed_origins.is_empty() {
+            return invalid(
+                "cors.allow_credentials",
+                "requires cors.allowed_origins to be set",
+            );
+        }
+        let security_headers = &self.security_headers;
+        if !SECURITY_HEADER_PRESETS.contains(&security_headers.preset.as_str()) {
+            return invalid(
+                "security_headers.preset",
+                "must be off, development or production",
+            );
+        }
+        if security_headers.hsts_max_age_secs == 0 {
+            return invalid("security_headers.hsts_max_age_secs", "must be positive");
+        }
+        for (i, folder) in self.music_folders.iter().enumerate() {
+            if !matches!(folder.protocol.as_str(), "local" | "smb") {
+                return invalid(
+                    &format!("music_folders[{}].protocol", i),
+                    "must be local or smb",
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("invalid value for `{key}`: {message}")]
+    Invalid { key: String, message: String },
+    #[error("failed to load config: {0}")]
+    Source(#[from] config::ConfigError),
+    #[error("invalid command line argument: {0}")]
+    Argument(String),
+}
+
+/// 配置来源，优先级：命令行 > 环境变量（APP__ 前缀）> 配置文件 > 默认值
+#[derive(Debug, Clone)]
+pub struct ConfigSources {
+    /// 配置文件路径（不带扩展名时按支持的格式查找）
+    pub file: String,
+    /// 命令行覆盖的配置项，键使用点号分隔，如 server.port
+    pub overrides: Vec<(String, String)>,
+}
+
+impl Default for ConfigSources {
+    fn default() -> Self {
+        Self {
+            file: "config".to_string(),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl ConfigSources {
+    /// 解析命令行参数：--config <path>、--set key=value，以及常用项的简写
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, ConfigError> {
+        let mut sources = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let key = match flag.as_str() {
+                "--config" => None,
+                "--set" => None,
+                "--host" => Some("server.host"),
+                "--port" => Some("server.port"),
+                "--database-url" => Some("database_url"),
+                "--log-level" => Some("log_level"),
+                _ => return Err(ConfigError::Argument(arg)),
+            };
+            let value = match inline.or_else(|| args.next()) {
+                Some(value) => value,
+                None => return Err(ConfigError::Argument(format!("{} requires a value", flag))),
+            };
+            match (flag.as_str(), key) {
+                ("--config", _) => sources.file = value,
+                ("--set", _) => {
+                    let Some((key, value)) = value.split_once('=') else {
+                        return Err(ConfigError::Argument(format!(
+                            "--set expects key=value, got {}",
+                            value
+                        )));
+                    };
+                    sources
+                        .overrides
+                        .push((key.trim().to_string(), value.to_string()));
+                }
+                (_, Some(key)) => sources.overrides.push((key.to_string(), value)),
+                _ => unreachable!(),
+            }
+        }
+        Ok(sources)
+    }
+
+    fn build(&self) -> Result<RawConfig, ConfigError> {
+        let mut builder = Config::builder()
+            .add_source(File::with_name(&self.file).required(false))
+            .add_source(Environment::with_prefix("APP").separator("__"));
+        for (key, value) in &self.overrides {
+            builder = builder.set_override(key.as_str(), value.as_str())?;
+        }
+        let raw: RawConfig = builder.build()?.try_deserialize()?; // serde 自动填充默认值
+        raw.validate()?;
+        Ok(raw)
+    }
+}
+
+/// 重新加载配置的结果
+#[derive(Debug, Clone, Default)]
+pub struct ReloadReport {
+    /// 已立即生效的配置段
+    pub applied: Vec<&'static str>,
+    /// 已修改但需要重启才能生效的配置项
+    pub restart_required: Vec<&'static str>,
+}
+
+/// 缓存配置
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// 缓存数据目录
+    pub data_dir: String,
+    /// 缓存过期时间（秒）
+    pub ttl_secs: u64,
+}
+
+impl CacheConfig {
+    /// 获取封面缓存目录路径
+    pub fn cover_art_cache_path(&self) -> std::path::PathBuf {
+        std::path::PathBuf::from(&self.data_dir).join("cover_art")
+    }
+
+    /// 扫描时生成的嵌入封面缩略图目录
+    pub fn cover_thumbnail_path(&self) -> std::path::PathBuf {
+        std::path::PathBuf::from(&self.data_dir).join("cover_thumbnails")
+    }
+
+    /// 用户上传的播放列表封面目录
+    pub fn playlist_cover_path(&self) -> std::path::PathBuf {
+        std::path::PathBuf::from(&self.data_dir).join("playlist_covers")
+    }
+
+    /// 获取音乐文件缓存目录路径（预留给后续使用）
+    pub fn music_cache_path(&self) -> std::path::PathBuf {
+        std::path::PathBuf::from(&self.data_dir).join("music")
+    }
+}
+
+/// 服务器配置
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// 监听地址
+    pub host: String,
+    /// 监听端口
+    pub port: u16,
+    pub ui_enabled: bool,
+    /// UI 静态文件目录路径
+    pub ui_path: String,
+    /// UI 挂载的 URL 路径
+    pub ui_base_path: String,
+    /// 关闭时等待请求和后台写入完成的最长时间（秒）
+    pub shutdown_timeout_secs: u64,
+    /// 为空时监听 host:port
+    pub listen: Vec<ListenAddress>,
+    pub trusted_proxies: Vec<IpNetwork>,
+}
+
+impl ServerConfig {
+    /// 实际监听的地址
+    pub fn listen_addresses(&self) -> Vec<ListenAddress> {
+        if !self.listen.is_empty() {
+            return self.listen.clone();
+        }
+        let host = match self.host.parse::<IpAddr>() {
+            Ok(IpAddr::V6(v6)) => format!("[{}]", v6),
+            _ => self.host.clone(),
+        };
+        vec![ListenAddress::Tcp(format!("{}:{}", host, self.port))]
+    }
+
+    pub fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies
+            .iter()
+            .any(|network| network.contains(ip))
+    }
+}
+
+/// 监听地址
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddress {
+    /// host:port，IPv6 地址需要加方括号，如 [::]:5533
+    Tcp(String),
+    /// unix:/path/to/socket
+    Unix(PathBuf),
+}
+
+impl FromStr for ListenAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(path) = s.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err("unix socket path must not be empty".to_string());
+            }
+            return Ok(ListenAddress::Unix(PathBuf::from(path)));
+        }
+        let Some((host, port)) = s.rsplit_once(':') else {
+            return Err(format!("expected host:port or unix:/path, got {}", s));
+        };
+        if host.is_empty() || !matches!(port.parse::<u16>(), Ok(port) if port > 0) {
+            return Err(format!("expected host:port or unix:/path, got {}", s));
+        }
+        // 未加方括号的 IPv6 地址无法区分端口
+        if host.contains(':') && !(host.starts_with('[') && host.ends_with(']')) {
+            return Err(format!("IPv6 address must be enclosed in brackets: {}", s));
+        }
+        Ok(ListenAddress::Tcp(s.to_string()))
+    }
+}
+
+impl fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenAddress::Tcp(address) => write!(f, "{}", address),
+            ListenAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// IP 网段，单个地址按 /32 或 /128 处理；IPv4 映射的 IPv6 地址按 IPv4 匹配
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.trim().split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s.trim(), None),
+        };
+        let addr = addr
+            .parse::<IpAddr>()
+            .map_err(|_| format!("invalid IP address: {}", addr))?
+            .to_canonical();
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("invalid prefix length: {}", len))?,
+            None => max_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// 转码配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscodingConfig {
+    /// FFmpeg 可执行文件路径
+    pub ffmpeg_path: String,
+    /// 默认目标格式（当客户端未指定时使用）
+    pub default_format: String,
+    /// 默认比特率（kbps）
+    pub default_bit_rate: i32,
+    /// 是否启用转码缓存
+    pub cache_enabled: bool,
+    /// 转码缓存过期时间（秒）
+    pub cache_ttl_secs: u64,
+    /// 流式传输块大小（字节）
+    pub chunk_size: usize,
+    /// 无损格式列表
+    pub lossless_formats: Vec<String>,
+    /// 转码输出中写入标签和封面的目标格式
+    pub embed_metadata_formats: Vec<String>,
+}
+
+/// 能写入 ID3 标签的转码格式，aac 输出不带封面
+const EMBED_METADATA_FORMATS: [&str; 3] = ["mp3", "aac", "m4a"];
+
+impl TranscodingConfig {
+    /// 检查格式是否为无损格式
+    pub fn is_lossless(&self, format: &str) -> bool {
+        self.lossless_formats
+            .iter()
+            .any(|f| f.eq_ignore_ascii_case(format))
+    }
+
+    /// 转码为该格式时是否写入标签和封面
+    pub fn embeds_metadata(&self, format: &str) -> bool {
+        self.embed_metadata_formats
+            .iter()
+            .any(|f| f.eq_ignore_ascii_case(format))
+    }
+
+    /// 获取转码缓存目录路径
+    pub fn cache_path(&self, cache_data_dir: &str) -> std::path::PathBuf {
+        std::path::PathBuf::from(cache_data_dir).join("transcoding")
+    }
+}
+
+/// 缓冲仓库
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BufferedRepository {
+    Album,
+    Artist,
+    Genre,
+    AudioFile,
+    CoverArt,
+    AlbumStats,
+    GenreStats,
+    ParticipantStats,
+    FormatStats,
+}
+
+impl BufferedRepository {
+    pub const ALL: [BufferedRepository; 9] = [
+        BufferedRepository::Album,
+        BufferedRepository::Artist,
+        BufferedRepository::Genre,
+        BufferedRepository::AudioFile,
+        BufferedRepository::CoverArt,
+        BufferedRepository::AlbumStats,
+        BufferedRepository::GenreStats,
+        BufferedRepository::ParticipantStats,
+        BufferedRepository::FormatStats,
+    ];
+
+    /// 配置文件中 [ingest.<key>] 使用的名称
+    pub fn key(&self) -> &'static str {
+        match self {
+            BufferedRepository::Album => "album",
+            BufferedRepository::Artist => "artist",
+            BufferedRepository::Genre => "genre",
+            BufferedRepository::AudioFile => "audio_file",
+            BufferedRepository::CoverArt => "cover_art",
+            BufferedRepository::AlbumStats => "album_stats",
+            BufferedRepository::GenreStats => "genre_stats",
+            BufferedRepository::ParticipantStats => "participant_stats",
+            BufferedRepository::FormatStats => "format_stats",
+        }
+    }
+
+    /// 内置默认值：写入量大的仓库容量和并发更高，统计类仓库 flush 间隔更长
+    fn default_config(&self) -> BufferConfig {
+        let (cache_capacity, concurrency, flush_timeout_secs) = match self {
+            BufferedRepository::Album => (100, 3, 5),
+            BufferedRepository::Artist => (100, 5, 5),
+            BufferedRepository::Genre => (50, 3, 5),
+            BufferedRepository::AudioFile => (1000, 10, 5),
+            BufferedRepository::CoverArt => (100, 3, 5),
+            BufferedRepository::AlbumStats => (1000, 1, 30),
+            BufferedRepository::GenreStats => (100, 1, 30),
+            BufferedRepository::ParticipantStats => (2000, 1, 30),
+            BufferedRepository::FormatStats => (100, 1, 30),
+        };
+        BufferConfig {
+            cache_capacity,
+            concurrency,
+            flush_timeout: Duration::from_secs(flush_timeout_secs),
+            max_pending_flushes: 1,
+        }
+    }
+}
+
+/// 缓冲仓库参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferConfig {
+    /// memtable 容量
+    pub cache_capacity: usize,
+    /// 并发 flush 数
+    pub concurrency: usize,
+    /// 即使未达到容量也 flush 的间隔
+    pub flush_timeout: Duration,
+    /// 待 flush 的 memtable 上限
+    pub max_pending_flushes: usize,
+}
+
+/// 入库配置，包含每个缓冲仓库的最终参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngestConfig {
+    repositories: HashMap<BufferedRepository, BufferConfig>,
+}
+
+impl IngestConfig {
+    pub fn get(&self, repo: BufferedRepository) -> BufferConfig {
+        self.repositories
+            .get(&repo)
+            .copied()
+            .unwrap_or_else(|| repo.default_config())
+    }
+}
+
+/// 扫描解析配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanConfig {
+    /// 并行解析的 worker 数
+    pub parse_workers: usize,
+    /// 每个 worker 的待解析队列长度
+    pub queue_capacity: usize,
+    /// 按存储协议限制同时解析的文件数
+    pub io_limits: HashMap<String, usize>,
+    /// 扫描结束后自动分析首尾静音
+    pub analyze_silence: bool,
+}
+
+impl From<RawScanConfig> for ScanConfig {
+    fn from(raw: RawScanConfig) -> Self {
+        Self {
+            parse_workers: raw.parse_workers,
+            queue_capacity: raw.queue_capacity,
+            io_limits: raw.io_limits,
+            analyze_silence: raw.analyze_silence,
+        }
+    }
+}
+
+/// DLNA/UPnP 媒体服务器配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct DlnaConfig {
+    /// 是否在局域网内广播媒体服务器
+    pub enabled: bool,
+    /// 设备上显示的服务器名称
+    pub friendly_name: String,
+    /// ssdp:alive 通知间隔（秒），广播的有效期为其两倍
+    pub advertise_interval_secs: u64,
+}
+
+impl From<RawDlnaConfig> for DlnaConfig {
+    fn from(raw: RawDlnaConfig) -> Self {
+        Self {
+            enabled: raw.enabled,
+            friendly_name: raw.friendly_name,
+            advertise_interval_secs: raw.advertise_interval_secs,
+        }
+    }
+}
+
+/// MusicBrainz 作品目录配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct MusicBrainzConfig {
+    pub enabled: bool,
+    pub base_url: String,
+    pub user_agent: String,
+    pub cache_ttl: chrono::Duration,
+    /// 小写的发行组主类型
+    pub release_types: Vec<String>,
+}
+
+impl From<RawMusicBrainzConfig> for MusicBrainzConfig {
+    fn from(raw: RawMusicBrainzConfig) -> Self {
+        Self {
+            enabled: raw.enabled,
+            base_url: raw.base_url.trim_end_matches('/').to_string(),
+            user_agent: raw.user_agent,
+            cache_ttl: chrono::Duration::days(raw.cache_ttl_days as i64),
+            release_types: raw
+                .release_types
+                .iter()
+                .map(|kind| kind.to_lowercase())
+                .collect(),
+        }
+    }
+}
+
+/// GraphQL 接口配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphqlConfig {
+    pub enabled: bool,
+    pub max_depth: usize,
+    pub max_complexity: usize,
+}
+
+impl From<RawGraphqlConfig> for GraphqlConfig {
+    fn from(raw: RawGraphqlConfig) -> Self {
+        Self {
+            enabled: raw.enabled,
+            max_depth: raw.max_depth,
+            max_complexity: raw.max_complexity,
+        }
+    }
+}
+
+/// 认证限流配置：IP 和用户名各一个令牌桶，连续失败后临时锁定
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub ip_burst: u32,
+    pub ip_refill_per_minute: u32,
+    pub user_burst: u32,
+    pub user_refill_per_minute: u32,
+    pub max_failures: u32,
+    pub failure_window: Duration,
+    pub lockout: Duration,
+}
+
+impl From<RawRateLimitConfig> for RateLimitConfig {
+    fn from(raw: RawRateLimitConfig) -> Self {
+        Self {
+            enabled: raw.enabled,
+            ip_burst: raw.ip_burst,
+            ip_refill_per_minute: raw.ip_refill_per_minute,
+            user_burst: raw.user_burst,
+            user_refill_per_minute: raw.user_refill_per_minute,
+            max_failures: raw.max_failures,
+            failure_window: Duration::from_secs(raw.failure_window_secs),
+            lockout: Duration::from_secs(raw.lockout_secs),
+        }
+    }
+}
+
+/// HTTPS 配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsConfig {
+    pub enabled: bool,
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// None 表示不检查证书文件变化
+    pub reload_interval: Option<Duration>,
+    pub redirect_http_listen: Vec<ListenAddress>,
+    /// 未配置域名时为 None
+    pub acme: Option<AcmeConfig>,
+}
+
+/// ACME 证书配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcmeConfig {
+    pub domains: Vec<String>,
+    pub contact: Vec<String>,
+    pub cache_dir: PathBuf,
+    pub production: bool,
+}
+
+impl From<RawTlsConfig> for TlsConfig {
+    fn from(raw: RawTlsConfig) -> Self {
+        let acme = (!raw.acme.domains.is_empty()).then(|| AcmeConfig {
+            domains: raw.acme.domains,
+            contact: raw.acme.contact,
+            cache_dir: PathBuf::from(raw.acme.cache_dir),
+            production: raw.acme.production,
+        });
+        Self {
+            enabled: raw.enabled,
+            cert_path: PathBuf::from(raw.cert_path),
+            key_path: PathBuf::from(raw.key_path),
+            reload_interval: (raw.reload_interval_secs > 0)
+                .then(|| Duration::from_secs(raw.reload_interval_secs)),
+            redirect_http_listen: raw
+                .redirect_http_listen
+                .iter()
+                .filter_map(|address| address.parse().ok())
+                .collect(),
+            acme,
+        }
+    }
+}
+
+/// 用户上传配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadConfig {
+    pub enabled: bool,
+    pub library_id: Option<i64>,
+    /// 相对于库根目录
+    pub directory: PathBuf,
+    /// 单个文件的大小上限（字节）
+    pub max_file_size: u64,
+}
+
+impl From<RawUploadConfig> for UploadConfig {
+    fn from(raw: RawUploadConfig) -> Self {
+        Self {
+            enabled: raw.enabled,
+            library_id: raw.library_id,
+            directory: PathBuf::from(raw.directory),
+            max_file_size: raw.max_file_size_mb * 1024 * 1024,
+        }
+    }
+}
+
+/// 回收站配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashConfig {
+    pub directory: PathBuf,
+    /// None 表示不自动清理
+    pub retention: Option<chrono::Duration>,
+}
+
+impl From<RawTrashConfig> for TrashConfig {
+    fn from(raw: RawTrashConfig) -> Self {
+        Self {
+            directory: PathBuf::from(raw.directory),
+            retention: (raw.retention_days > 0)
+                .then(|| chrono::Duration::days(raw.retention_days as i64)),
+        }
+    }
+}
+
+/// 新密码使用的哈希算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordAlgorithm {
+    Argon2id,
+    Bcrypt,
+}
+
+impl FromStr for PasswordAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "argon2id" => Ok(PasswordAlgorithm::Argon2id),
+            "bcrypt" => Ok(PasswordAlgorithm::Bcrypt),
+            _ => Err("must be one of argon2id, bcrypt".to_string()),
+        }
+    }
+}
+
+/// 密码哈希配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordHashingConfig {
+    pub algorithm: PasswordAlgorithm,
+    pub bcrypt_cost: u32,
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+}
+
+impl PasswordHashingConfig {
+    fn new(raw: &RawPasswordHashingConfig, salt_cost: i32) -> Self {
+        Self {
+            algorithm: raw.algorithm.parse().unwrap_or(PasswordAlgorithm::Argon2id),
+            bcrypt_cost: salt_cost as u32,
+            argon2_memory_kib: raw.argon2_memory_kib,
+            argon2_iterations: raw.argon2_iterations,
+            argon2_parallelism: raw.argon2_parallelism,
+        }
+    }
+}
+
+/// 播放历史配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackHistoryConfig {
+    /// None 表示永久保留
+    pub retention: Option<chrono::Duration>,
 }
 
-impl Default for RawServerConfig {
-    fn default() -> Self {
+impl From<RawPlaybackHistoryConfig> for PlaybackHistoryConfig {
+    fn from(raw: RawPlaybackHistoryConfig) -> Self {
         Self {
-            host: "0.0.0.0".to_string(),
-            port: 5533,
-            ui_path: "ui/dist".to_string(),
-            ui_base_path: "/app".to_string(),
+            retention: (raw.retention_days > 0)
+                .then(|| chrono::Duration::days(raw.retention_days as i64)),
         }
     }
 }
 
-impl Default for RawConfig {
-    fn default() -> Self {
+/// 备份与恢复配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupConfig {
+    /// 上传恢复的备份文件大小上限（字节）
+    pub max_restore_size: u64,
+}
+
+impl From<RawBackupConfig> for BackupConfig {
+    fn from(raw: RawBackupConfig) -> Self {
         Self {
-            auto_login_username: "".to_string(),
-            jwt_expire_secs: 3600,
-            salt_cost: 10,
-            jwt_secret_key: "secret".to_string(),
-            password_encryption_key: "default_password_encryption_key".to_string(),
-            ignoredarticles: "The El La Los Las Le Les Os As O A".to_string(),
-            indexgroups: "A B C D E F G H I J K L M N O P Q R S T U V W X-Z(XYZ) [Unknown]([)"
-                .to_string(),
-            database_url: "".to_string(),
-            cover_art_source_priority: HashMap::from([
-                ("embedded".to_string(), 15.0),
-                ("external".to_string(), 10.0),
-                ("downloaded".to_string(), 5.0),
-                ("generated".to_string(), 2.0),
-                ("manual".to_string(), 8.0),
-            ]),
-            base_url: "http://192.168.2.31:5533".to_string(),
-            cover_art_wildcards: vec![
-                "cover.*".to_string(),
-                "folder.*".to_string(),
-                "front.*".to_string(),
-                "album.*".to_string(),
-                "albumart.*".to_string(),
-                "*".to_string(),
-            ],
-            music_folders: vec![],
-            cache: RawCacheConfig::default(),
-            server: RawServerConfig::default(),
-            transcoding: RawTranscodingConfig::default(),
+            max_restore_size: raw.max_restore_size_mb * 1024 * 1024,
         }
     }
 }
 
-/// 缓存配置
-#[derive(Debug, Clone)]
-pub struct CacheConfig {
-    /// 缓存数据目录
-    pub data_dir: String,
-    /// 缓存过期时间（秒）
-    pub ttl_secs: u64,
+/// 数据库查询预算配置，None 表示不检查
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryBudgetConfig {
+    pub max_queries: Option<u64>,
+    pub max_db_time: Option<Duration>,
+    pub slow_query: Option<Duration>,
 }
 
-impl CacheConfig {
-    /// 获取封面缓存目录路径
-    pub fn cover_art_cache_path(&self) -> std::path::PathBuf {
-        std::path::PathBuf::from(&self.data_dir).join("cover_art")
+impl From<RawQueryBudgetConfig> for QueryBudgetConfig {
+    fn from(raw: RawQueryBudgetConfig) -> Self {
+        Self {
+            max_queries: (raw.max_queries > 0).then_some(raw.max_queries),
+            max_db_time: (raw.max_db_ms > 0).then(|| Duration::from_millis(raw.max_db_ms)),
+            slow_query: (raw.slow_query_ms > 0).then(|| Duration::from_millis(raw.slow_query_ms)),
+        }
     }
+}
 
-    /// 获取音乐文件缓存目录路径（预留给后续使用）
-    pub fn music_cache_path(&self) -> std::path::PathBuf {
-        std::path::PathBuf::from(&self.data_dir).join("music")
-    }
+/// 存储读取配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageConfig {
+    pub read_ahead: ReadAheadOptions,
 }
 
-/// 服务器配置
-#[derive(Debug, Clone)]
-pub struct ServerConfig {
-    /// 监听地址
-    pub host: String,
-    /// 监听端口
-    pub port: u16,
-    /// UI 静态文件目录路径
-    pub ui_path: String,
-    /// UI 挂载的 URL 路径
-    pub ui_base_path: String,
+impl From<RawStorageConfig> for StorageConfig {
+    fn from(raw: RawStorageConfig) -> Self {
+        Self {
+            read_ahead: ReadAheadOptions {
+                chunk_size: raw.read_ahead_chunk_kb * 1024,
+                lookahead: raw.read_ahead_chunks,
+            },
+        }
+    }
 }
 
-/// 转码配置
-#[derive(Debug, Clone)]
-pub struct TranscodingConfig {
-    /// FFmpeg 可执行文件路径
-    pub ffmpeg_path: String,
-    /// 默认目标格式（当客户端未指定时使用）
-    pub default_format: String,
-    /// 默认比特率（kbps）
-    pub default_bit_rate: i32,
-    /// 是否启用转码缓存
-    pub cache_enabled: bool,
-    /// 转码缓存过期时间（秒）
-    pub cache_ttl_secs: u64,
-    /// 流式传输块大小（字节）
-    pub chunk_size: usize,
-    /// 无损格式列表
-    pub lossless_formats: Vec<String>,
+/// 跨域请求配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorsConfig {
+    /// 为空时允许任意来源
+    pub allowed_origins: Vec<String>,
+    /// 为空时允许任意请求头
+    pub allowed_headers: Vec<String>,
+    pub allow_credentials: bool,
+    pub max_age_secs: usize,
 }
 
-impl TranscodingConfig {
-    /// 检查格式是否为无损格式
-    pub fn is_lossless(&self, format: &str) -> bool {
-        self.lossless_formats
-            .iter()
-            .any(|f| f.eq_ignore_ascii_case(format))
+impl From<RawCorsConfig> for CorsConfig {
+    fn from(raw: RawCorsConfig) -> Self {
+        Self {
+            allowed_origins: raw.allowed_origins,
+            allowed_headers: raw.allowed_headers,
+            allow_credentials: raw.allow_credentials,
+            max_age_secs: raw.max_age_secs,
+        }
     }
+}
 
-    /// 获取转码缓存目录路径
-    pub fn cache_path(&self, cache_data_dir: &str) -> std::path::PathBuf {
-        std::path::PathBuf::from(cache_data_dir).join("transcoding")
+/// 安全响应头配置，已按预设展开
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityHeadersConfig {
+    /// Strict-Transport-Security 的值，None 表示不发送
+    pub hsts: Option<String>,
+    pub content_type_options: bool,
+    /// 只用于 Web UI 页面
+    pub ui_content_security_policy: Option<String>,
+}
+
+impl From<RawSecurityHeadersConfig> for SecurityHeadersConfig {
+    fn from(raw: RawSecurityHeadersConfig) -> Self {
+        // (hsts, nosniff, csp)
+        let (hsts, content_type_options, csp) = match raw.preset.as_str() {
+            "production" => (true, true, Some(PRODUCTION_UI_CSP)),
+            "development" => (false, true, Some(DEVELOPMENT_UI_CSP)),
+            _ => (false, false, None),
+        };
+        let csp = match raw.ui_content_security_policy {
+            Some(csp) => Some(csp).filter(|csp| !csp.trim().is_empty()),
+            None => csp.map(str::to_string),
+        };
+        Self {
+            hsts: raw
+                .hsts
+                .unwrap_or(hsts)
+                .then(|| format!("max-age={}; includeSubDomains", raw.hsts_max_age_secs)),
+            content_type_options: raw.content_type_options.unwrap_or(content_type_options),
+            ui_content_security_policy: csp,
+        }
     }
 }
 
+/// 元数据规则配置
+#[derive(Debug, Clone)]
+pub struct MetadataConfig {
+    /// 自定义规则文件路径（TOML）
+    pub rules_file: String,
+    /// 规则文件变更检查间隔（秒），0 表示不热加载
+    pub reload_interval_secs: u64,
+    /// 编辑元数据时是否回写文件标签
+    pub write_tags: bool,
+    /// 同名专辑的不同版本是否作为不同的专辑
+    pub separate_editions: bool,
+}
+
 /// 音乐库配置
 #[derive(Debug, Clone)]
 pub struct MusicFolderConfig {
@@ -287,22 +1908,59 @@ pub struct AppConfigImpl {
     pub auto_login_username: Arc<RwLock<String>>,
     pub jwt_expire_secs: Arc<AtomicU64>,
     pub salt_cost: Arc<AtomicU64>,
+    pub password_hashing: Arc<RwLock<PasswordHashingConfig>>,
     pub jwt_secret_key: Arc<RwLock<String>>,
+    pub stream_token_expire_secs: Arc<AtomicU64>,
     pub password_encryption_key: Arc<RwLock<String>>,
     pub ignoredarticles: Arc<RwLock<String>>,
+    /// 生效中的冠词列表，管理设置覆盖配置文件时更新
+    pub ignored_articles: SharedArticles,
     pub indexgroups: Arc<RwLock<String>>,
+    pub index_romanize: Arc<AtomicBool>,
     pub database_url: Arc<RwLock<String>>,
     pub cover_art_source_priority: Arc<RwLock<HashMap<CoverSourceType, f32>>>,
     pub base_url: Arc<RwLock<String>>,
     pub cover_art_wildcards: Arc<RwLock<Vec<String>>>,
+    /// 配置文件中的封面通配符，管理设置被清除时恢复
+    pub default_cover_art_wildcards: Arc<Vec<String>>,
+    /// 管理接口保存的系统设置
+    pub settings: Arc<RwLock<Settings>>,
     pub music_folders: Arc<RwLock<Vec<MusicFolderConfig>>>,
     pub cache: Arc<RwLock<CacheConfig>>,
     pub server: Arc<RwLock<ServerConfig>>,
     pub transcoding: Arc<RwLock<TranscodingConfig>>,
+    pub metadata: Arc<RwLock<MetadataConfig>>,
+    /// 缓冲仓库在启动时创建，修改后需要重启
+    pub ingest: Arc<IngestConfig>,
+    /// 解析工作池在启动时创建，修改后需要重启
+    pub scan: Arc<ScanConfig>,
+    /// SSDP 广播在启动时开始，修改后需要重启
+    pub dlna: Arc<DlnaConfig>,
+    /// 客户端在启动时创建，修改后需要重启
+    pub musicbrainz: Arc<MusicBrainzConfig>,
+    /// Schema 在启动时构建，修改后需要重启
+    pub graphql: Arc<GraphqlConfig>,
+    /// 限流状态在启动时创建，修改后需要重启
+    pub rate_limit: Arc<RateLimitConfig>,
+    /// 监听方式在启动时确定，修改后需要重启；证书文件内容变化会自动重新加载
+    pub tls: Arc<TlsConfig>,
+    pub upload: Arc<RwLock<UploadConfig>>,
+    pub trash: Arc<RwLock<TrashConfig>>,
+    pub playback_history: Arc<RwLock<PlaybackHistoryConfig>>,
+    pub backup: Arc<RwLock<BackupConfig>>,
+    pub query_budget: Arc<RwLock<QueryBudgetConfig>>,
+    pub storage: Arc<RwLock<StorageConfig>>,
+    /// Cors 中间件在创建 App 时构建，修改后需要重启
+    pub cors: Arc<CorsConfig>,
+    pub security_headers: Arc<RwLock<SecurityHeadersConfig>>,
+    pub log_level: Arc<RwLock<log::LevelFilter>>,
+    pub node_id: Option<i64>,
+    /// 启动时使用的配置来源，重新加载时沿用
+    pub sources: Arc<ConfigSources>,
 }
 
 impl AppConfigImpl {
-    fn new(data: RawConfig) -> Self {
+    fn new(data: RawConfig, sources: ConfigSources) -> Self {
         let cover_art_source_priority = data
             .cover_art_source_priority
             .iter()
@@ -312,11 +1970,17 @@ impl AppConfigImpl {
             data_dir: data.cache.data_dir,
             ttl_secs: data.cache.ttl_secs,
         };
+        let listen = data.server.listen_addresses();
+        let trusted_proxies = data.server.trusted_proxies();
         let server_config = ServerConfig {
             host: data.server.host,
             port: data.server.port,
+            ui_enabled: data.server.ui_enabled,
             ui_path: data.server.ui_path,
             ui_base_path: data.server.ui_base_path,
+            shutdown_timeout_secs: data.server.shutdown_timeout_secs,
+            listen,
+            trusted_proxies,
         };
         let transcoding_config = TranscodingConfig {
             ffmpeg_path: data.transcoding.ffmpeg_path,
@@ -326,6 +1990,13 @@ impl AppConfigImpl {
             cache_ttl_secs: data.transcoding.cache_ttl_secs,
             chunk_size: data.transcoding.chunk_size,
             lossless_formats: data.transcoding.lossless_formats,
+            embed_metadata_formats: data.transcoding.embed_metadata_formats,
+        };
+        let metadata_config = MetadataConfig {
+            rules_file: data.metadata.rules_file,
+            reload_interval_secs: data.metadata.reload_interval_secs,
+            write_tags: data.metadata.write_tags,
+            separate_editions: data.metadata.separate_editions,
         };
         let music_folders_config: Vec<MusicFolderConfig> = data
             .music_folders
@@ -340,18 +2011,50 @@ impl AppConfigImpl {
             auto_login_username: Arc::new(RwLock::new(data.auto_login_username)),
             jwt_expire_secs: Arc::new(AtomicU64::new(data.jwt_expire_secs as u64)),
             salt_cost: Arc::new(AtomicU64::new(data.salt_cost as u64)),
+            password_hashing: Arc::new(RwLock::new(PasswordHashingConfig::new(
+                &data.password_hashing,
+                data.salt_cost,
+            ))),
             jwt_secret_key: Arc::new(RwLock::new(data.jwt_secret_key)),
+            stream_token_expire_secs: Arc::new(AtomicU64::new(
+                data.stream_token_expire_secs as u64,
+            )),
             password_encryption_key: Arc::new(RwLock::new(data.password_encryption_key)),
+            ignored_articles: SharedArticles::new(&split_articles(&data.ignoredarticles)),
             ignoredarticles: Arc::new(RwLock::new(data.ignoredarticles)),
             indexgroups: Arc::new(RwLock::new(data.indexgroups)),
+            index_romanize: Arc::new(AtomicBool::new(data.index_romanize)),
             database_url: Arc::new(RwLock::new(data.database_url)),
             cover_art_source_priority: Arc::new(RwLock::new(cover_art_source_priority)),
             base_url: Arc::new(RwLock::new(data.base_url)),
+            default_cover_art_wildcards: Arc::new(data.cover_art_wildcards.clone()),
             cover_art_wildcards: Arc::new(RwLock::new(data.cover_art_wildcards)),
+            settings: Arc::new(RwLock::new(Settings::default())),
             music_folders: Arc::new(RwLock::new(music_folders_config)),
             cache: Arc::new(RwLock::new(cache_config)),
             server: Arc::new(RwLock::new(server_config)),
             transcoding: Arc::new(RwLock::new(transcoding_config)),
+            metadata: Arc::new(RwLock::new(metadata_config)),
+            ingest: Arc::new(data.ingest.resolve()),
+            scan: Arc::new(data.scan.into()),
+            dlna: Arc::new(data.dlna.into()),
+            musicbrainz: Arc::new(data.musicbrainz.into()),
+            graphql: Arc::new(data.graphql.into()),
+            rate_limit: Arc::new(data.rate_limit.into()),
+            tls: Arc::new(data.tls.into()),
+            upload: Arc::new(RwLock::new(data.upload.into())),
+            trash: Arc::new(RwLock::new(data.trash.into())),
+            playback_history: Arc::new(RwLock::new(data.playback_history.into())),
+            backup: Arc::new(RwLock::new(data.backup.into())),
+            query_budget: Arc::new(RwLock::new(data.query_budget.into())),
+            storage: Arc::new(RwLock::new(data.storage.into())),
+            cors: Arc::new(data.cors.into()),
+            security_headers: Arc::new(RwLock::new(data.security_headers.into())),
+            log_level: Arc::new(RwLock::new(
+                data.log_level.parse().unwrap_or(log::LevelFilter::Info),
+            )),
+            node_id: data.node_id,
+            sources: Arc::new(sources),
         }
     }
 
@@ -380,17 +2083,247 @@ impl AppConfigImpl {
         cfg_val.clone()
     }
 
+    pub fn metadata(&self) -> MetadataConfig {
+        let cfg_val = self.metadata.read().unwrap();
+        cfg_val.clone()
+    }
+
+    pub fn ingest(&self) -> IngestConfig {
+        self.ingest.as_ref().clone()
+    }
+
+    pub fn scan(&self) -> ScanConfig {
+        self.scan.as_ref().clone()
+    }
+
+    pub fn dlna(&self) -> DlnaConfig {
+        self.dlna.as_ref().clone()
+    }
+
+    pub fn musicbrainz(&self) -> MusicBrainzConfig {
+        self.musicbrainz.as_ref().clone()
+    }
+
+    pub fn graphql(&self) -> GraphqlConfig {
+        self.graphql.as_ref().clone()
+    }
+
+    pub fn rate_limit(&self) -> RateLimitConfig {
+        self.rate_limit.as_ref().clone()
+    }
+
+    pub fn tls(&self) -> TlsConfig {
+        self.tls.as_ref().clone()
+    }
+
+    pub fn upload(&self) -> UploadConfig {
+        self.upload.read().unwrap().clone()
+    }
+
+    pub fn trash(&self) -> TrashConfig {
+        self.trash.read().unwrap().clone()
+    }
+
+    pub fn playback_history(&self) -> PlaybackHistoryConfig {
+        self.playback_history.read().unwrap().clone()
+    }
+
+    pub fn backup(&self) -> BackupConfig {
+        self.backup.read().unwrap().clone()
+    }
+
+    pub fn password_hashing(&self) -> PasswordHashingConfig {
+        *self.password_hashing.read().unwrap()
+    }
+
+    pub fn query_budget(&self) -> QueryBudgetConfig {
+        *self.query_budget.read().unwrap()
+    }
+
+    pub fn storage(&self) -> StorageConfig {
+        *self.storage.read().unwrap()
+    }
+
+    pub fn cors(&self) -> CorsConfig {
+        self.cors.as_ref().clone()
+    }
+
+    pub fn security_headers(&self) -> SecurityHeadersConfig {
+        self.security_headers.read().unwrap().clone()
+    }
+
+    /// 雪花 ID 节点号，未配置时由主机名派生
+    pub fn node_id(&self) -> i64 {
+        self.node_id
+            .unwrap_or_else(crate::id_generator::SnowflakeIdGenerator::node_id_from_hostname)
+    }
+
+    pub fn log_level(&self) -> log::LevelFilter {
+        *self.log_level.read().unwrap()
+    }
+
+    /// 从配置文件、环境变量和命令行参数加载配置
     pub fn load() -> Result<AppConfigImpl, Box<dyn Error>> {
+        let sources = ConfigSources::from_args(std::env::args().skip(1))?;
+        Ok(Self::load_from(sources)?)
+    }
+
+    pub fn load_from(sources: ConfigSources) -> Result<AppConfigImpl, ConfigError> {
+        dotenv().ok();
+        let raw = sources.build()?;
+        Ok(AppConfigImpl::new(raw, sources))
+    }
+
+    /// 重新读取所有配置来源，缓存过期时间、转码和日志级别立即生效，
+    /// 其余变更只报告为需要重启。校验失败时保持原配置不变
+    pub fn reload(&self) -> Result<ReloadReport, ConfigError> {
         dotenv().ok();
+        let raw = self.sources.build()?;
+        let mut report = ReloadReport::default();
+
+        {
+            let mut cache = self.cache.write().unwrap();
+            if cache.ttl_secs != raw.cache.ttl_secs {
+                cache.ttl_secs = raw.cache.ttl_secs;
+                report.applied.push("cache");
+            }
+            if cache.data_dir != raw.cache.data_dir {
+                report.restart_required.push("cache.data_dir");
+            }
+        }
+
+        let transcoding = TranscodingConfig {
+            ffmpeg_path: raw.transcoding.ffmpeg_path,
+            default_format: raw.transcoding.default_format,
+            default_bit_rate: raw.transcoding.default_bit_rate,
+            cache_enabled: raw.transcoding.cache_enabled,
+            cache_ttl_secs: raw.transcoding.cache_ttl_secs,
+            chunk_size: raw.transcoding.chunk_size,
+            lossless_formats: raw.transcoding.lossless_formats,
+            embed_metadata_formats: raw.transcoding.embed_metadata_formats,
+        };
+        {
+            let mut current = self.transcoding.write().unwrap();
+            // ffmpeg 进程参数在启动时固定
+            if current.ffmpeg_path != transcoding.ffmpeg_path {
+                report.restart_required.push("transcoding.ffmpeg_path");
+            }
+            if current.chunk_size != transcoding.chunk_size {
+                report.restart_required.push("transcoding.chunk_size");
+            }
+            if *current != transcoding {
+                *current = transcoding;
+                report.applied.push("transcoding");
+            }
+        }
+
+        if self.stream_token_expire_secs() != raw.stream_token_expire_secs {
+            self.stream_token_expire_secs
+                .store(raw.stream_token_expire_secs as u64, Ordering::SeqCst);
+            report.applied.push("stream_token_expire_secs");
+        }
+
+        if self.indexgroups() != raw.indexgroups {
+            *self.indexgroups.write().unwrap() = raw.indexgroups;
+            report.applied.push("indexgroups");
+        }
+        if self.index_romanize() != raw.index_romanize {
+            self.index_romanize
+                .store(raw.index_romanize, Ordering::SeqCst);
+            report.applied.push("index_romanize");
+        }
 
-        let config = Config::builder()
-            .add_source(File::with_name("config").required(false))
-            .add_source(Environment::with_prefix("APP").separator("__"))
-            .build()?;
+        let level = raw.log_level.parse().unwrap_or(log::LevelFilter::Info);
+        if self.log_level() != level {
+            *self.log_level.write().unwrap() = level;
+            log::set_max_level(level);
+            report.applied.push("log_level");
+        }
 
-        let raw: RawConfig = config.try_deserialize()?; // serde 自动填充默认值
-        let app_config = AppConfigImpl::new(raw);
-        Ok(app_config)
+        let server = self.server();
+        if server.host != raw.server.host
+            || server.port != raw.server.port
+            || server.listen != raw.server.listen_addresses()
+            || server.ui_enabled != raw.server.ui_enabled
+            || server.ui_path != raw.server.ui_path
+            || server.ui_base_path != raw.server.ui_base_path
+        {
+            report.restart_required.push("server");
+        }
+        let trusted_proxies = raw.server.trusted_proxies();
+        if server.trusted_proxies != trusted_proxies {
+            self.server.write().unwrap().trusted_proxies = trusted_proxies;
+            report.applied.push("server.trusted_proxies");
+        }
+        if self.database_url() != raw.database_url {
+            report.restart_required.push("database_url");
+        }
+        if *self.ingest != raw.ingest.resolve() {
+            report.restart_required.push("ingest");
+        }
+        if *self.scan != ScanConfig::from(raw.scan) {
+            report.restart_required.push("scan");
+        }
+        if *self.dlna != DlnaConfig::from(raw.dlna) {
+            report.restart_required.push("dlna");
+        }
+        if *self.musicbrainz != MusicBrainzConfig::from(raw.musicbrainz) {
+            report.restart_required.push("musicbrainz");
+        }
+        if *self.graphql != GraphqlConfig::from(raw.graphql) {
+            report.restart_required.push("graphql");
+        }
+        if *self.rate_limit != RateLimitConfig::from(raw.rate_limit) {
+            report.restart_required.push("rate_limit");
+        }
+        if *self.tls != TlsConfig::from(raw.tls) {
+            report.restart_required.push("tls");
+        }
+        let upload = UploadConfig::from(raw.upload);
+        if self.upload() != upload {
+            *self.upload.write().unwrap() = upload;
+            report.applied.push("upload");
+        }
+        let trash = TrashConfig::from(raw.trash);
+        if self.trash() != trash {
+            *self.trash.write().unwrap() = trash;
+            report.applied.push("trash");
+        }
+        let password_hashing = PasswordHashingConfig::new(&raw.password_hashing, raw.salt_cost);
+        if self.password_hashing() != password_hashing {
+            self.salt_cost.store(raw.salt_cost as u64, Ordering::SeqCst);
+            *self.password_hashing.write().unwrap() = password_hashing;
+            report.applied.push("password_hashing");
+        }
+        let playback_history = PlaybackHistoryConfig::from(raw.playback_history);
+        if self.playback_history() != playback_history {
+            *self.playback_history.write().unwrap() = playback_history;
+            report.applied.push("playback_history");
+        }
+        let backup = BackupConfig::from(raw.backup);
+        if self.backup() != backup {
+            *self.backup.write().unwrap() = backup;
+            report.applied.push("backup");
+        }
+        let query_budget = QueryBudgetConfig::from(raw.query_budget);
+        if self.query_budget() != query_budget {
+            *self.query_budget.write().unwrap() = query_budget;
+            report.applied.push("query_budget");
+        }
+        let storage = StorageConfig::from(raw.storage);
+        if self.storage() != storage {
+            *self.storage.write().unwrap() = storage;
+            report.applied.push("storage");
+        }
+        if *self.cors != CorsConfig::from(raw.cors) {
+            report.restart_required.push("cors");
+        }
+        let security_headers = SecurityHeadersConfig::from(raw.security_headers);
+        if self.security_headers() != security_headers {
+            *self.security_headers.write().unwrap() = security_headers;
+            report.applied.push("security_headers");
+        }
+        Ok(report)
     }
 
     pub fn indexgroups(&self) -> String {
@@ -398,20 +2331,17 @@ impl AppConfigImpl {
         (*cfg_val).clone()
     }
 
-    pub fn ignored_articles(&self) -> &[String] {
-        static CACHED_ARTICLES: OnceLock<Vec<String>> = OnceLock::new();
+    pub fn index_romanize(&self) -> bool {
+        self.index_romanize.load(Ordering::SeqCst)
+    }
 
-        let articles_string = {
-            let cfg_val = self.ignoredarticles.read().unwrap();
-            cfg_val.clone()
-        };
+    pub fn ignored_articles(&self) -> Vec<String> {
+        self.ignored_articles.get()
+    }
 
-        CACHED_ARTICLES.get_or_init(|| {
-            articles_string
-                .split_whitespace()
-                .map(|s| s.to_string())
-                .collect()
-        })
+    pub fn settings(&self) -> Settings {
+        let cfg_val = self.settings.read().unwrap();
+        cfg_val.clone()
     }
     pub fn database_url(&self) -> String {
         let cfg_val = self.database_url.read().unwrap();
@@ -427,6 +2357,10 @@ impl AppConfigImpl {
         let cfg_val = self.password_encryption_key.read().unwrap();
         (*cfg_val).clone()
     }
+
+    pub fn stream_token_expire_secs(&self) -> i64 {
+        self.stream_token_expire_secs.load(Ordering::SeqCst) as i64
+    }
 }
 
 impl AuthConfig for AppConfigImpl {
@@ -450,6 +2384,26 @@ impl AuthConfig for AppConfigImpl {
     }
 }
 
+fn split_articles(articles: &str) -> Vec<String> {
+    articles.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+/// 管理设置覆盖配置文件中的对应项，清除后恢复配置文件的值
+impl SettingsListener for AppConfigImpl {
+    fn settings_changed(&self, settings: &Settings) {
+        let articles = settings
+            .ignored_articles
+            .clone()
+            .unwrap_or_else(|| split_articles(&self.ignoredarticles.read().unwrap()));
+        self.ignored_articles.set(articles);
+        *self.cover_art_wildcards.write().unwrap() = settings
+            .cover_art_wildcards
+            .clone()
+            .unwrap_or_else(|| self.default_cover_art_wildcards.to_vec());
+        *self.settings.write().unwrap() = settings.clone();
+    }
+}
+
 impl application::query::config::CoverArtConfig for AppConfigImpl {
     fn cover_art_wildcards(&self) -> Vec<String> {
         self.cover_art_wildcards()
@@ -463,3 +2417,304 @@ impl application::query::config::CoverArtConfig for AppConfigImpl {
         find_resource_file(PLACE_HOLDER_ALBUM_ART)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_config_path_and_overrides() {
+        let sources = ConfigSources::from_args(args(&[
+            "--config",
+            "/etc/rhythm/config",
+            "--port=8080",
+            "--set",
+            "cache.ttl_secs=60",
+        ]))
+        .unwrap();
+        assert_eq!(sources.file, "/etc/rhythm/config");
+        assert_eq!(
+            sources.overrides,
+            vec![
+                ("server.port".to_string(), "8080".to_string()),
+                ("cache.ttl_secs".to_string(), "60".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_flags_and_missing_values() {
+        assert!(ConfigSources::from_args(args(&["--verbose"])).is_err());
+        assert!(ConfigSources::from_args(args(&["--port"])).is_err());
+        assert!(ConfigSources::from_args(args(&["--set", "cache.ttl_secs"])).is_err());
+    }
+
+    #[test]
+    fn validation_error_names_the_key() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            log_level: "loud".to_string(),
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`log_level`"));
+    }
+
+    #[test]
+    fn repository_buffer_settings_override_ingest_defaults() {
+        let ingest = RawIngestConfig {
+            flush_timeout_secs: Some(10),
+            audio_file: RawBufferConfig {
+                cache_capacity: Some(5000),
+                flush_timeout_secs: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .resolve();
+
+        let audio_file = ingest.get(BufferedRepository::AudioFile);
+        assert_eq!(audio_file.cache_capacity, 5000);
+        assert_eq!(audio_file.concurrency, 10);
+        assert_eq!(audio_file.flush_timeout, Duration::from_secs(2));
+
+        let genre = ingest.get(BufferedRepository::Genre);
+        assert_eq!(genre.cache_capacity, 50);
+        assert_eq!(genre.flush_timeout, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn rejects_out_of_range_buffer_settings() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            ingest: RawIngestConfig {
+                artist: RawBufferConfig {
+                    concurrency: Some(0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`ingest.artist.concurrency`"));
+    }
+
+    #[test]
+    fn rejects_zero_scan_io_limit() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            scan: RawScanConfig {
+                io_limits: HashMap::from([("smb".to_string(), 0)]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`scan.io_limits.smb`"));
+    }
+
+    #[test]
+    fn rejects_out_of_range_dlna_advertise_interval() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            dlna: RawDlnaConfig {
+                advertise_interval_secs: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`dlna.advertise_interval_secs`"));
+    }
+
+    #[test]
+    fn rejects_zero_graphql_depth() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            graphql: RawGraphqlConfig {
+                max_depth: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`graphql.max_depth`"));
+    }
+
+    #[test]
+    fn parses_listen_addresses() {
+        assert_eq!(
+            "[::]:5533".parse::<ListenAddress>(),
+            Ok(ListenAddress::Tcp("[::]:5533".to_string()))
+        );
+        assert_eq!(
+            "unix:/run/rhythm.sock".parse::<ListenAddress>(),
+            Ok(ListenAddress::Unix(PathBuf::from("/run/rhythm.sock")))
+        );
+        assert!("::1:5533".parse::<ListenAddress>().is_err());
+        assert!("localhost".parse::<ListenAddress>().is_err());
+    }
+
+    #[test]
+    fn matches_trusted_proxy_networks() {
+        let v4: IpNetwork = "10.0.0.0/8".parse().unwrap();
+        assert!(v4.contains("10.1.2.3".parse().unwrap()));
+        assert!(v4.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!v4.contains("11.0.0.1".parse().unwrap()));
+
+        let v6: IpNetwork = "fd00::/8".parse().unwrap();
+        assert!(v6.contains("fd12::1".parse().unwrap()));
+        assert!(!v6.contains("fe80::1".parse().unwrap()));
+
+        let single: IpNetwork = "127.0.0.1".parse().unwrap();
+        assert!(single.contains("127.0.0.1".parse().unwrap()));
+        assert!(!single.contains("127.0.0.2".parse().unwrap()));
+
+        let any: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("203.0.113.9".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<IpNetwork>().is_err());
+    }
+
+    #[test]
+    fn cors_credentials_require_explicit_origins() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            cors: RawCorsConfig {
+                allow_credentials: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`cors.allow_credentials`"));
+
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            cors: RawCorsConfig {
+                allowed_origins: vec!["https://music.example.com/app".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`cors.allowed_origins[0]`"));
+    }
+
+    #[test]
+    fn security_header_overrides_apply_on_top_of_preset() {
+        let production = SecurityHeadersConfig::from(RawSecurityHeadersConfig {
+            preset: "production".to_string(),
+            ui_content_security_policy: Some("".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            production.hsts.as_deref(),
+            Some("max-age=31536000; includeSubDomains")
+        );
+        assert!(production.content_type_options);
+        assert_eq!(production.ui_content_security_policy, None);
+
+        let off = SecurityHeadersConfig::from(RawSecurityHeadersConfig {
+            preset: "off".to_string(),
+            content_type_options: Some(true),
+            ..Default::default()
+        });
+        assert_eq!(off.hsts, None);
+        assert!(off.content_type_options);
+        assert_eq!(off.ui_content_security_policy, None);
+
+        let development = SecurityHeadersConfig::from(RawSecurityHeadersConfig::default());
+        assert_eq!(development.hsts, None);
+        assert!(development.ui_content_security_policy.is_some());
+    }
+
+    #[test]
+    fn rejects_invalid_trusted_proxy() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            server: RawServerConfig {
+                trusted_proxies: vec!["not-an-ip".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`server.trusted_proxies[0]`"));
+    }
+
+    #[test]
+    fn requires_certificate_files_without_acme() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            tls: RawTlsConfig {
+                enabled: true,
+                cert_path: "/etc/rhythm/cert.pem".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`tls.cert_path`"));
+
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            tls: RawTlsConfig {
+                enabled: true,
+                acme: RawAcmeConfig {
+                    domains: vec!["music.example.com".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            log_level: "info".to_string(),
+            ..Default::default()
+        };
+        assert!(raw.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_zero_lockout() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            rate_limit: RawRateLimitConfig {
+                lockout_secs: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`rate_limit.lockout_secs`"));
+    }
+
+    #[test]
+    fn validates_upload_settings() {
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            upload: RawUploadConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`upload.library_id`"));
+
+        let raw = RawConfig {
+            database_url: "postgres://localhost/rhythm".to_string(),
+            upload: RawUploadConfig {
+                enabled: true,
+                library_id: Some(1),
+                directory: "../outside".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = raw.validate().unwrap_err();
+        assert!(err.to_string().contains("`upload.directory`"));
+    }
+}
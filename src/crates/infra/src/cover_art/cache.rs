@@ -4,6 +4,7 @@ use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use sled::Db;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// 缓存条目（存储在 sled 中）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,8 +21,8 @@ struct CacheEntry {
 pub struct CoverArtCacheImpl {
     /// sled 数据库实例
     db: Db,
-    /// 缓存过期时间（秒）
-    ttl_secs: u64,
+    /// 缓存过期时间（秒），配置重载时可修改
+    ttl_secs: AtomicU64,
 }
 
 impl CoverArtCacheImpl {
@@ -33,7 +34,10 @@ impl CoverArtCacheImpl {
     pub fn new(db_path: PathBuf, ttl_secs: u64) -> Result<Self, sled::Error> {
         let db = sled::open(db_path)?;
 
-        Ok(Self { db, ttl_secs })
+        Ok(Self {
+            db,
+            ttl_secs: AtomicU64::new(ttl_secs),
+        })
     }
 
     /// 使用默认配置创建缓存（7 天过期）
@@ -44,7 +48,12 @@ impl CoverArtCacheImpl {
     /// 检查缓存是否过期
     fn is_expired(&self, created_at: i64) -> bool {
         let now = chrono::Utc::now().timestamp();
-        (now - created_at) > self.ttl_secs as i64
+        (now - created_at) > self.ttl_secs.load(Ordering::Relaxed) as i64
+    }
+
+    /// 修改过期时间，对已缓存的条目同样生效
+    pub fn set_ttl_secs(&self, ttl_secs: u64) {
+        self.ttl_secs.store(ttl_secs, Ordering::Relaxed);
     }
 
     /// 从 sled 加载缓存
@@ -85,6 +94,11 @@ impl CoverArtCacheImpl {
         Ok(())
     }
 
+    /// 清空所有条目，数据恢复后缓存键可能指向不同的内容
+    pub fn clear(&self) -> Result<(), sled::Error> {
+        self.db.clear()
+    }
+
     /// 从 sled 删除缓存
     fn remove_from_db(&self, cache_key: &str) {
         let _ = self.db.remove(cache_key.as_bytes());
@@ -162,6 +176,26 @@ mod tests {
         assert!(cache.get("invalidate-test").await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_cache_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = CoverArtCacheImpl::new(temp_dir.path().to_path_buf(), 3600).unwrap();
+
+        for key in ["clear-a", "clear-b"] {
+            let data = CoverArtData {
+                data: Bytes::from_static(&[1]),
+                mime_type: "image/png".to_string(),
+                cache_key: key.to_string(),
+                last_modified: 0,
+            };
+            cache.put(key, data).await;
+        }
+
+        cache.clear().unwrap();
+        assert!(cache.get("clear-a").await.is_none());
+        assert!(cache.get("clear-b").await.is_none());
+    }
+
     #[tokio::test]
     async fn test_cache_survives_restart() {
         let temp_dir = TempDir::new().unwrap();
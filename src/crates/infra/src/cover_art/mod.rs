@@ -1,5 +1,9 @@
 mod cache;
+mod playlist_cover;
 mod reader;
+mod thumbnail;
 
 pub use cache::CoverArtCacheImpl;
+pub use playlist_cover::FsPlaylistCoverStore;
 pub use reader::CoverArtReaderImpl;
+pub use thumbnail::FsThumbnailStore;
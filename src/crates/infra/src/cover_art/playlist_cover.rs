@@ -0,0 +1,68 @@
+use application::command::playlist::PlaylistCoverStore;
+use application::error::AppError;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// 播放列表封面保存在本地目录，文件名为 `<playlist_id>.<ext>`
+pub struct FsPlaylistCoverStore {
+    dir: PathBuf,
+}
+
+impl FsPlaylistCoverStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl PlaylistCoverStore for FsPlaylistCoverStore {
+    async fn save(
+        &self,
+        playlist_id: i64,
+        extension: &str,
+        data: Vec<u8>,
+    ) -> Result<String, AppError> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        let name = format!("{}.{}", playlist_id, extension);
+        let path = self.dir.join(&name);
+        let tmp = self.dir.join(format!("{}.tmp", name));
+        tokio::fs::write(&tmp, data)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    async fn remove(&self, path: &str) -> Result<(), AppError> {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::UnknownError(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn saves_and_removes_cover() {
+        let dir = TempDir::new().unwrap();
+        let store = FsPlaylistCoverStore::new(dir.path().join("playlists"));
+
+        let path = store.save(42, "png", vec![1, 2, 3]).await.unwrap();
+        assert!(path.ends_with("42.png"));
+        assert_eq!(std::fs::read(&path).unwrap(), vec![1, 2, 3]);
+
+        store.remove(&path).await.unwrap();
+        assert!(!std::path::Path::new(&path).exists());
+        // 重复删除不报错
+        store.remove(&path).await.unwrap();
+    }
+}
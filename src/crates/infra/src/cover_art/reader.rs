@@ -29,11 +29,15 @@ impl CoverArtReader for CoverArtReaderImpl {
                 let mime_type = guess_mime_type_from_path(path);
                 Ok(CoverArtBytes { data, mime_type })
             }
-            CoverSource::Embedded { protocol, path } => {
+            CoverSource::Embedded { protocol, path, .. } => {
                 let data = self.read_embedded(protocol, path).await?;
                 let mime_type = guess_mime_type_from_data(&data);
                 Ok(CoverArtBytes { data, mime_type })
             }
+            // 拼图由 GetCoverArt 读取各格后合成
+            CoverSource::Mosaic { .. } => Err(QueryError::InvalidInput(
+                "Mosaic cover must be read tile by tile".to_string(),
+            )),
         }
     }
 }
@@ -0,0 +1,52 @@
+use application::command::artwork::ThumbnailStore;
+use application::error::AppError;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// 缩略图保存在本地目录，文件名为 `<key>.webp`
+pub struct FsThumbnailStore {
+    dir: PathBuf,
+}
+
+impl FsThumbnailStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl ThumbnailStore for FsThumbnailStore {
+    async fn save(&self, key: &str, data: Vec<u8>) -> Result<String, AppError> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        let path = self.dir.join(format!("{}.webp", key));
+        // 先写临时文件再改名，读取方不会看到写了一半的文件
+        let tmp = self.dir.join(format!("{}.webp.tmp", key));
+        tokio::fs::write(&tmp, data)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        Ok(path.to_string_lossy().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn saves_thumbnail_under_key() {
+        let dir = TempDir::new().unwrap();
+        let store = FsThumbnailStore::new(dir.path().join("thumbs"));
+
+        let path = store.save("abc", vec![1, 2, 3]).await.unwrap();
+        store.save("abc", vec![4, 5]).await.unwrap();
+
+        assert!(path.ends_with("abc.webp"));
+        assert_eq!(std::fs::read(&path).unwrap(), vec![4, 5]);
+    }
+}
@@ -0,0 +1,222 @@
+use application::error::AppError;
+use application::event::dead_letter::{DeadLetter, DeadLetterRepository};
+use application::event::event_bus::ErasedHandler;
+use chrono::{DateTime, Utc};
+use futures::FutureExt;
+use log::{error, warn};
+use std::any::Any;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 事件处理失败后的重试策略（指数退避）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最多处理次数（含首次）
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 attempt 次失败后等待的时间
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// 投递失败事件时需要记录的信封元数据
+#[derive(Debug, Clone)]
+pub(crate) struct EnvelopeMeta {
+    pub event_id: String,
+    pub correlation_id: String,
+    pub event_type: &'static str,
+    pub aggregate_id: i64,
+    pub version: i64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 本进程内仍可重新投递的事件
+struct PendingDelivery {
+    handler: Arc<dyn ErasedHandler>,
+    event: Arc<dyn Any + Send + Sync>,
+}
+
+/// 重新投递的结果
+#[derive(Debug, Clone)]
+pub struct RedispatchReport {
+    pub delivered: bool,
+    pub error: Option<String>,
+}
+
+/// 死信队列：记录持久化，事件本身保留在内存中用于重新投递，
+/// 进程重启后只剩记录可供排查
+pub struct DeadLetterQueue {
+    repository: Arc<dyn DeadLetterRepository>,
+    pending: Mutex<HashMap<String, PendingDelivery>>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(repository: Arc<dyn DeadLetterRepository>) -> Self {
+        Self {
+            repository,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<DeadLetter>, AppError> {
+        self.repository.find_all().await.map_err(repository_error)
+    }
+
+    /// 是否还能重新投递（事件仍在内存中）
+    pub fn is_redeliverable(&self, id: &str) -> bool {
+        self.pending.lock().unwrap().contains_key(id)
+    }
+
+    pub async fn remove(&self, id: &str) -> Result<(), AppError> {
+        self.find(id).await?;
+        self.repository.delete(id).await.map_err(repository_error)?;
+        self.pending.lock().unwrap().remove(id);
+        Ok(())
+    }
+
+    /// 把事件重新交给失败的 handler 处理一次，成功后移出死信队列
+    pub async fn redispatch(&self, id: &str) -> Result<RedispatchReport, AppError> {
+        let mut dead_letter = self.find(id).await?;
+        let (handler, event) = {
+            let pending = self.pending.lock().unwrap();
+            match pending.get(id) {
+                Some(p) => (p.handler.clone(), p.event.clone()),
+                None => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Event of dead letter {} is no longer available for redispatch",
+                        id
+                    )))
+                }
+            }
+        };
+        match deliver(handler.as_ref(), event.as_ref()).await {
+            Ok(()) => {
+                self.repository.delete(id).await.map_err(repository_error)?;
+                self.pending.lock().unwrap().remove(id);
+                Ok(RedispatchReport {
+                    delivered: true,
+                    error: None,
+                })
+            }
+            Err(e) => {
+                dead_letter.record_failure(&e);
+                self.repository
+                    .save(&dead_letter)
+                    .await
+                    .map_err(repository_error)?;
+                Ok(RedispatchReport {
+                    delivered: false,
+                    error: Some(e),
+                })
+            }
+        }
+    }
+
+    pub(crate) async fn push(
+        &self,
+        handler: Arc<dyn ErasedHandler>,
+        event: Arc<dyn Any + Send + Sync>,
+        meta: &EnvelopeMeta,
+        error: String,
+        attempts: u32,
+    ) {
+        let dead_letter = DeadLetter {
+            id: uuid::Uuid::new_v4().to_string(),
+            event_id: meta.event_id.clone(),
+            correlation_id: meta.correlation_id.clone(),
+            event_type: meta.event_type.to_string(),
+            handler: handler.name().to_string(),
+            aggregate_id: meta.aggregate_id,
+            version: meta.version,
+            error,
+            attempts: attempts as i32,
+            occurred_at: meta.timestamp.naive_utc(),
+            failed_at: Utc::now().naive_utc(),
+        };
+        if let Err(e) = self.repository.save(&dead_letter).await {
+            error!(
+                "Failed to save dead letter for {} ({}): {}",
+                dead_letter.event_type, dead_letter.handler, e
+            );
+            return;
+        }
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(dead_letter.id, PendingDelivery { handler, event });
+    }
+
+    async fn find(&self, id: &str) -> Result<DeadLetter, AppError> {
+        self.repository
+            .find_by_id(id)
+            .await
+            .map_err(repository_error)?
+            .ok_or_else(|| AppError::AggregateNotFound("DeadLetter".to_string(), id.to_string()))
+    }
+}
+
+/// 调用 handler 一次，handler panic 也视为失败
+pub(crate) async fn deliver(
+    handler: &dyn ErasedHandler,
+    event: &(dyn Any + Send + Sync),
+) -> Result<(), String> {
+    match AssertUnwindSafe(handler.handle_erased(event))
+        .catch_unwind()
+        .await
+    {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            warn!("Event handler {} panicked: {}", handler.name(), message);
+            Err(format!("panicked: {}", message))
+        }
+    }
+}
+
+fn repository_error(e: anyhow::Error) -> AppError {
+    AppError::RepositoryError("DeadLetter".to_string(), e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 6,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+}
@@ -2,11 +2,16 @@ use application::error::AppError;
 use application::event::event_bus::EventEnvelope;
 use application::event::event_bus::{ErasedHandler, EventBus, Handler};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures::future::join_all;
-use std::any::{Any, TypeId};
+use log::{error, warn};
+use std::any::{type_name, Any, TypeId};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, RwLock};
+
+use super::dead_letter::{deliver, DeadLetterQueue, EnvelopeMeta, RetryPolicy};
 
 /// 桥接，将 Handler<E> 擦除为 ErasedHandler
 pub struct HandlerWrapper<E> {
@@ -18,19 +23,89 @@ impl<E> ErasedHandler for HandlerWrapper<E>
 where
     E: Send + Sync + 'static,
 {
-    async fn handle_erased(&self, event: &(dyn Any + Send + Sync)) {
-        if let Some(e) = event.downcast_ref::<EventEnvelope<E>>() {
-            self.inner.handle(e).await;
+    async fn handle_erased(&self, event: &(dyn Any + Send + Sync)) -> Result<(), AppError> {
+        match event.downcast_ref::<EventEnvelope<E>>() {
+            Some(e) => self.inner.handle(e).await,
+            None => Ok(()),
         }
     }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+/// 单个处理器对某类事件的运行统计
+#[derive(Default)]
+struct HandlerCounters {
+    processed: AtomicU64,
+    /// 失败的处理次数（包括之后重试成功的）
+    errors: AtomicU64,
+    /// 重试耗尽的事件数
+    gave_up: AtomicU64,
+    /// 已投递但还没处理完的事件数
+    pending: AtomicUsize,
+    /// 最近处理完的事件的发生时间和处理完成时间
+    last: Mutex<Option<(DateTime<Utc>, DateTime<Utc>)>>,
+}
+
+/// 处理器统计快照
+#[derive(Debug, Clone)]
+pub struct HandlerStats {
+    pub handler: &'static str,
+    pub event_type: &'static str,
+    pub processed: u64,
+    pub errors: u64,
+    pub gave_up: u64,
+    pub backlog: usize,
+    pub last_event_at: Option<DateTime<Utc>>,
+    pub last_processed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+struct Subscription {
+    handler: Arc<dyn ErasedHandler>,
+    event_type: &'static str,
+    counters: Arc<HandlerCounters>,
+}
+
+/// 投递结束（包括重试和死信）时减少处理器的积压数
+struct PendingGuard {
+    counters: Arc<HandlerCounters>,
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.counters.pending.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// 内存事件总线
 #[derive(Clone)]
 pub struct InMemoryEventBus {
-    handlers: Arc<RwLock<HashMap<TypeId, Vec<Arc<dyn ErasedHandler>>>>>,
+    handlers: Arc<RwLock<HashMap<TypeId, Vec<Subscription>>>>,
     /// 是否异步触发处理器（不等待完成）
     fire_and_forget: bool,
+    /// 正在处理中的事件数，关闭时等待归零
+    in_flight: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+    retry: RetryPolicy,
+    /// 重试耗尽的事件进入死信队列，未配置时只记录日志
+    dead_letters: Option<Arc<DeadLetterQueue>>,
+}
+
+/// 事件处理完成（包括异步触发的任务）时减少计数
+struct InFlightGuard {
+    in_flight: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
 }
 
 impl InMemoryEventBus {
@@ -38,6 +113,10 @@ impl InMemoryEventBus {
         Self {
             handlers: Arc::new(RwLock::new(HashMap::new())),
             fire_and_forget: false,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            idle: Arc::new(Notify::new()),
+            retry: RetryPolicy::default(),
+            dead_letters: None,
         }
     }
 
@@ -46,6 +125,75 @@ impl InMemoryEventBus {
         Self {
             handlers: Arc::new(RwLock::new(HashMap::new())),
             fire_and_forget: true,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            idle: Arc::new(Notify::new()),
+            retry: RetryPolicy::default(),
+            dead_letters: None,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_dead_letter_queue(mut self, dead_letters: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letters = Some(dead_letters);
+        self
+    }
+
+    pub fn dead_letters(&self) -> Option<Arc<DeadLetterQueue>> {
+        self.dead_letters.clone()
+    }
+
+    /// 正在处理中的事件数
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// 各处理器的统计，按处理器名称排序
+    pub async fn handler_stats(&self) -> Vec<HandlerStats> {
+        let mut stats: Vec<HandlerStats> = self
+            .handlers
+            .read()
+            .await
+            .values()
+            .flatten()
+            .map(|subscription| {
+                let counters = &subscription.counters;
+                let last = *counters.last.lock().unwrap();
+                HandlerStats {
+                    handler: subscription.handler.name(),
+                    event_type: subscription.event_type,
+                    processed: counters.processed.load(Ordering::Relaxed),
+                    errors: counters.errors.load(Ordering::Relaxed),
+                    gave_up: counters.gave_up.load(Ordering::Relaxed),
+                    backlog: counters.pending.load(Ordering::SeqCst),
+                    last_event_at: last.map(|(event_at, _)| event_at),
+                    last_processed_at: last.map(|(_, processed_at)| processed_at),
+                }
+            })
+            .collect();
+        stats.sort_by_key(|s| (s.handler, s.event_type));
+        stats
+    }
+
+    /// 等待所有正在处理的事件完成，用于关闭前排空事件总线
+    pub async fn drain(&self) {
+        loop {
+            let notified = self.idle.notified();
+            if self.in_flight() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn track(&self) -> InFlightGuard {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard {
+            in_flight: self.in_flight.clone(),
+            idle: self.idle.clone(),
         }
     }
 }
@@ -58,23 +206,43 @@ impl EventBus for InMemoryEventBus {
     {
         let type_id = TypeId::of::<E>();
 
-        let handlers: Option<Vec<Arc<dyn ErasedHandler>>> = {
+        let handlers: Option<Vec<Subscription>> = {
             let guard = self.handlers.read().await;
             guard.get(&type_id).cloned()
         };
 
         if let Some(list) = handlers {
+            let guard = self.track();
+            let meta = Arc::new(EnvelopeMeta {
+                event_id: event.id.to_string(),
+                correlation_id: event.correlation_id.to_string(),
+                event_type: type_name::<E>(),
+                aggregate_id: event.aggregate_id,
+                version: event.version,
+                timestamp: event.timestamp,
+            });
+            let event: Arc<dyn Any + Send + Sync> = Arc::new(event);
+            let futures = list.into_iter().map(|subscription| {
+                subscription.counters.pending.fetch_add(1, Ordering::SeqCst);
+                dispatch(
+                    subscription,
+                    event.clone(),
+                    meta.clone(),
+                    self.retry,
+                    self.dead_letters.clone(),
+                )
+            });
             if self.fire_and_forget {
                 // 异步触发：spawn单个任务处理所有handlers
-                let event_arc = Arc::new(event);
+                let futures: Vec<_> = futures.collect();
                 tokio::spawn(async move {
-                    let futures = list.iter().map(|h| h.handle_erased(event_arc.as_ref()));
+                    let _guard = guard;
                     join_all(futures).await;
                 });
             } else {
                 // 同步等待所有处理器完成
-                let futures = list.iter().map(|h| h.handle_erased(&event));
                 join_all(futures).await;
+                drop(guard);
             }
         }
         Ok(())
@@ -91,6 +259,125 @@ impl EventBus for InMemoryEventBus {
             .await
             .entry(TypeId::of::<E>())
             .or_default()
-            .push(wrapper);
+            .push(Subscription {
+                handler: wrapper,
+                event_type: type_name::<E>(),
+                counters: Arc::new(HandlerCounters::default()),
+            });
+    }
+
+    async fn drain(&self) {
+        InMemoryEventBus::drain(self).await;
+    }
+}
+
+/// 按重试策略投递给单个 handler，重试耗尽后进入死信队列
+async fn dispatch(
+    subscription: Subscription,
+    event: Arc<dyn Any + Send + Sync>,
+    meta: Arc<EnvelopeMeta>,
+    retry: RetryPolicy,
+    dead_letters: Option<Arc<DeadLetterQueue>>,
+) {
+    let Subscription {
+        handler, counters, ..
+    } = subscription;
+    let _pending = PendingGuard {
+        counters: counters.clone(),
+    };
+    let max_attempts = retry.max_attempts.max(1);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let err = match deliver(handler.as_ref(), event.as_ref()).await {
+            Ok(()) => {
+                counters.processed.fetch_add(1, Ordering::Relaxed);
+                *counters.last.lock().unwrap() = Some((meta.timestamp, Utc::now()));
+                return;
+            }
+            Err(e) => e,
+        };
+        counters.errors.fetch_add(1, Ordering::Relaxed);
+        if attempt < max_attempts {
+            let backoff = retry.backoff(attempt);
+            warn!(
+                "Handler {} failed on {} {} (attempt {}/{}), retrying in {:?}: {}",
+                handler.name(),
+                meta.event_type,
+                meta.event_id,
+                attempt,
+                max_attempts,
+                backoff,
+                err
+            );
+            tokio::time::sleep(backoff).await;
+            continue;
+        }
+        error!(
+            "Handler {} gave up on {} {} after {} attempts: {}",
+            handler.name(),
+            meta.event_type,
+            meta.event_id,
+            attempt,
+            err
+        );
+        counters.gave_up.fetch_add(1, Ordering::Relaxed);
+        if let Some(dead_letters) = dead_letters {
+            dead_letters.push(handler, event, &meta, err, attempt).await;
+        }
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use application::event::event_bus::{CorrelationId, EventId};
+    use std::time::Duration;
+
+    /// 前 failures 次处理失败
+    struct Flaky {
+        failures: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Handler<u32> for Flaky {
+        async fn handle(&self, _event: &EventEnvelope<u32>) -> Result<(), AppError> {
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err(AppError::UnknownError("flaky".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn handler_stats_count_retries_and_give_ups() {
+        let mut bus = InMemoryEventBus::new().with_retry_policy(RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        });
+        bus.subscribe::<u32>(Arc::new(Flaky {
+            failures: AtomicUsize::new(3),
+        }))
+        .await;
+
+        // 第一个事件两次都失败后放弃，第二个事件重试一次后成功
+        let first = EventEnvelope::new(1, 1, 1u32, CorrelationId::new(), EventId::new());
+        let second = EventEnvelope::new(1, 2, 2u32, CorrelationId::new(), EventId::new());
+        let timestamp = second.timestamp;
+        bus.publish(first).await.unwrap();
+        bus.publish(second).await.unwrap();
+
+        let stats = bus.handler_stats().await;
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].event_type, "u32");
+        assert_eq!(stats[0].processed, 1);
+        assert_eq!(stats[0].errors, 3);
+        assert_eq!(stats[0].gave_up, 1);
+        assert_eq!(stats[0].backlog, 0);
+        assert_eq!(stats[0].last_event_at, Some(timestamp));
     }
 }
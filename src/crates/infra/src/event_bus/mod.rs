@@ -1,4 +1,6 @@
+pub mod dead_letter;
 pub mod in_memory;
+pub mod outbox;
 use application::error::AppError;
 use chrono::{DateTime, Utc};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -0,0 +1,557 @@
+use super::in_memory::InMemoryEventBus;
+use application::error::AppError;
+use application::event::event_bus::{EventBus, EventEnvelope};
+use application::event::outbox::{OutboxMessage, OutboxRepository};
+use chrono::{NaiveDateTime, Utc};
+use domain::annotation::AnnotationEvent;
+use domain::player::{PlaybackMode, PlayerEvent, PlayerEventKind};
+use domain::value::{AnnotationId, AudioFileId, PlayQueueId, PlayerId, UserId};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// 注解事件在 outbox 中的类型标识
+pub const ANNOTATION_EVENT: &str = "annotation";
+/// 播放器（正在播放）事件在 outbox 中的类型标识
+pub const PLAYER_EVENT: &str = "player";
+
+/// 超过该失败次数的消息不再尝试（通常是无法解码）
+const MAX_ATTEMPTS: i32 = 5;
+const BATCH_SIZE: u64 = 200;
+/// 已投递消息的保留时间
+const RETENTION_DAYS: i64 = 7;
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// AnnotationEvent 的持久化格式
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum AnnotationEventRecord {
+    ItemStarred {
+        annotation_id: i64,
+        version: i64,
+        user_id: i64,
+        item_id: i64,
+        item_type: String,
+    },
+    ItemUnstarred {
+        annotation_id: i64,
+        version: i64,
+        user_id: i64,
+        item_id: i64,
+        item_type: String,
+    },
+    ItemRated {
+        annotation_id: i64,
+        version: i64,
+        user_id: i64,
+        item_id: i64,
+        item_type: String,
+        rating: i32,
+    },
+    ItemScrobbled {
+        annotation_id: i64,
+        version: i64,
+        user_id: i64,
+        item_id: i64,
+        item_type: String,
+        /// 早期版本写入的消息没有该字段
+        #[serde(default)]
+        played_at: Option<NaiveDateTime>,
+    },
+}
+
+impl From<&AnnotationEvent> for AnnotationEventRecord {
+    fn from(event: &AnnotationEvent) -> Self {
+        match event.clone() {
+            AnnotationEvent::ItemStarred {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+            } => Self::ItemStarred {
+                annotation_id: annotation_id.as_i64(),
+                version,
+                user_id: user_id.as_i64(),
+                item_id,
+                item_type,
+            },
+            AnnotationEvent::ItemUnstarred {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+            } => Self::ItemUnstarred {
+                annotation_id: annotation_id.as_i64(),
+                version,
+                user_id: user_id.as_i64(),
+                item_id,
+                item_type,
+            },
+            AnnotationEvent::ItemRated {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+                rating,
+            } => Self::ItemRated {
+                annotation_id: annotation_id.as_i64(),
+                version,
+                user_id: user_id.as_i64(),
+                item_id,
+                item_type,
+                rating,
+            },
+            AnnotationEvent::ItemScrobbled {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+                played_at,
+            } => Self::ItemScrobbled {
+                annotation_id: annotation_id.as_i64(),
+                version,
+                user_id: user_id.as_i64(),
+                item_id,
+                item_type,
+                played_at: Some(played_at),
+            },
+        }
+    }
+}
+
+impl From<AnnotationEventRecord> for AnnotationEvent {
+    fn from(record: AnnotationEventRecord) -> Self {
+        match record {
+            AnnotationEventRecord::ItemStarred {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+            } => Self::ItemStarred {
+                annotation_id: AnnotationId::from(annotation_id),
+                version,
+                user_id: UserId::from(user_id),
+                item_id,
+                item_type,
+            },
+            AnnotationEventRecord::ItemUnstarred {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+            } => Self::ItemUnstarred {
+                annotation_id: AnnotationId::from(annotation_id),
+                version,
+                user_id: UserId::from(user_id),
+                item_id,
+                item_type,
+            },
+            AnnotationEventRecord::ItemRated {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+                rating,
+            } => Self::ItemRated {
+                annotation_id: AnnotationId::from(annotation_id),
+                version,
+                user_id: UserId::from(user_id),
+                item_id,
+                item_type,
+                rating,
+            },
+            AnnotationEventRecord::ItemScrobbled {
+                annotation_id,
+                version,
+                user_id,
+                item_id,
+                item_type,
+                played_at,
+            } => Self::ItemScrobbled {
+                annotation_id: AnnotationId::from(annotation_id),
+                version,
+                user_id: UserId::from(user_id),
+                item_id,
+                item_type,
+                played_at: played_at.unwrap_or_else(|| Utc::now().naive_utc()),
+            },
+        }
+    }
+}
+
+/// PlayerEvent 的持久化格式
+#[derive(Debug, Serialize, Deserialize)]
+struct PlayerEventRecord {
+    player_id: i64,
+    version: i64,
+    kind: PlayerEventKindRecord,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum PlayerEventKindRecord {
+    PlayQueueChanged { play_queue_id: i64 },
+    PlaybackStarted { user_id: i64, audio_file_id: i64 },
+    PlaybackPaused,
+    PlaybackResumed,
+    PlaybackStopped { audio_file_id: i64 },
+    PlaybackModeChanged { mode: PlaybackModeRecord },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum PlaybackModeRecord {
+    Sequential,
+    Shuffle,
+    RepeatOne,
+    RepeatAll,
+}
+
+impl From<&PlayerEvent> for PlayerEventRecord {
+    fn from(event: &PlayerEvent) -> Self {
+        let kind = match &event.kind {
+            PlayerEventKind::PlayQueueChanged { play_queue_id } => {
+                PlayerEventKindRecord::PlayQueueChanged {
+                    play_queue_id: play_queue_id.as_i64(),
+                }
+            }
+            PlayerEventKind::PlaybackStarted {
+                user_id,
+                audio_file_id,
+            } => PlayerEventKindRecord::PlaybackStarted {
+                user_id: user_id.as_i64(),
+                audio_file_id: audio_file_id.as_i64(),
+            },
+            PlayerEventKind::PlaybackPaused => PlayerEventKindRecord::PlaybackPaused,
+            PlayerEventKind::PlaybackResumed => PlayerEventKindRecord::PlaybackResumed,
+            PlayerEventKind::PlaybackStopped { audio_file_id } => {
+                PlayerEventKindRecord::PlaybackStopped {
+                    audio_file_id: audio_file_id.as_i64(),
+                }
+            }
+            PlayerEventKind::PlaybackModeChanged { mode } => {
+                PlayerEventKindRecord::PlaybackModeChanged {
+                    mode: match mode {
+                        PlaybackMode::Sequential => PlaybackModeRecord::Sequential,
+                        PlaybackMode::Shuffle => PlaybackModeRecord::Shuffle,
+                        PlaybackMode::RepeatOne => PlaybackModeRecord::RepeatOne,
+                        PlaybackMode::RepeatAll => PlaybackModeRecord::RepeatAll,
+                    },
+                }
+            }
+        };
+        Self {
+            player_id: event.player_id.as_i64(),
+            version: event.version,
+            kind,
+        }
+    }
+}
+
+impl From<PlayerEventRecord> for PlayerEvent {
+    fn from(record: PlayerEventRecord) -> Self {
+        let kind = match record.kind {
+            PlayerEventKindRecord::PlayQueueChanged { play_queue_id } => {
+                PlayerEventKind::PlayQueueChanged {
+                    play_queue_id: PlayQueueId::from(play_queue_id),
+                }
+            }
+            PlayerEventKindRecord::PlaybackStarted {
+                user_id,
+                audio_file_id,
+            } => PlayerEventKind::PlaybackStarted {
+                user_id: UserId::from(user_id),
+                audio_file_id: AudioFileId::from(audio_file_id),
+            },
+            PlayerEventKindRecord::PlaybackPaused => PlayerEventKind::PlaybackPaused,
+            PlayerEventKindRecord::PlaybackResumed => PlayerEventKind::PlaybackResumed,
+            PlayerEventKindRecord::PlaybackStopped { audio_file_id } => {
+                PlayerEventKind::PlaybackStopped {
+                    audio_file_id: AudioFileId::from(audio_file_id),
+                }
+            }
+            PlayerEventKindRecord::PlaybackModeChanged { mode } => {
+                PlayerEventKind::PlaybackModeChanged {
+                    mode: match mode {
+                        PlaybackModeRecord::Sequential => PlaybackMode::Sequential,
+                        PlaybackModeRecord::Shuffle => PlaybackMode::Shuffle,
+                        PlaybackModeRecord::RepeatOne => PlaybackMode::RepeatOne,
+                        PlaybackModeRecord::RepeatAll => PlaybackMode::RepeatAll,
+                    },
+                }
+            }
+        };
+        Self {
+            player_id: PlayerId::from(record.player_id),
+            version: record.version,
+            kind,
+        }
+    }
+}
+
+/// 把注解事件编码为 outbox 消息
+pub fn encode_annotation_event(
+    envelope: &EventEnvelope<AnnotationEvent>,
+) -> Result<OutboxMessage, AppError> {
+    encode(
+        envelope,
+        ANNOTATION_EVENT,
+        &AnnotationEventRecord::from(&envelope.payload),
+    )
+}
+
+/// 把播放器事件编码为 outbox 消息
+pub fn encode_player_event(
+    envelope: &EventEnvelope<PlayerEvent>,
+) -> Result<OutboxMessage, AppError> {
+    encode(
+        envelope,
+        PLAYER_EVENT,
+        &PlayerEventRecord::from(&envelope.payload),
+    )
+}
+
+fn encode<E, T: Serialize>(
+    envelope: &EventEnvelope<E>,
+    event_type: &str,
+    record: &T,
+) -> Result<OutboxMessage, AppError> {
+    let payload =
+        serde_json::to_string(record).map_err(|e| AppError::UnknownError(e.to_string()))?;
+    Ok(OutboxMessage {
+        id: envelope.id.to_string(),
+        event_type: event_type.to_string(),
+        aggregate_id: envelope.aggregate_id,
+        version: envelope.version,
+        payload,
+        correlation_id: envelope.correlation_id.to_string(),
+        causation_id: envelope.causation_id.to_string(),
+        occurred_at: envelope.timestamp.naive_utc(),
+        attempts: 0,
+        last_error: None,
+    })
+}
+
+fn decode<T, E>(message: &OutboxMessage) -> Result<EventEnvelope<E>, String>
+where
+    T: for<'de> Deserialize<'de>,
+    E: From<T>,
+{
+    let record: T = serde_json::from_str(&message.payload).map_err(|e| e.to_string())?;
+    Ok(EventEnvelope {
+        id: message
+            .id
+            .parse()
+            .map_err(|e| format!("invalid event id: {}", e))?,
+        aggregate_id: message.aggregate_id,
+        version: message.version,
+        timestamp: message.occurred_at.and_utc(),
+        payload: E::from(record),
+        correlation_id: message
+            .correlation_id
+            .parse()
+            .map_err(|e| format!("invalid correlation id: {}", e))?,
+        causation_id: message
+            .causation_id
+            .parse()
+            .map_err(|e| format!("invalid causation id: {}", e))?,
+    })
+}
+
+/// 把 outbox 中未投递的事件发布到事件总线，发布后标记为已投递
+///
+/// 投递语义为至少一次：发布后标记失败的消息会被再次发布
+pub struct OutboxRelay {
+    repository: Arc<dyn OutboxRepository>,
+    event_bus: InMemoryEventBus,
+    /// 定时任务和关闭时的最后一轮不能同时发布同一批消息
+    running: Mutex<()>,
+}
+
+impl OutboxRelay {
+    pub fn new(repository: Arc<dyn OutboxRepository>, event_bus: InMemoryEventBus) -> Self {
+        Self {
+            repository,
+            event_bus,
+            running: Mutex::new(()),
+        }
+    }
+
+    /// 按固定间隔轮询 outbox，并定期清理已投递的消息
+    pub fn start(self: &Arc<Self>, interval: Duration) {
+        let relay = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut last_purge = Instant::now();
+            loop {
+                ticker.tick().await;
+                relay.relay_pending().await;
+                if last_purge.elapsed() >= PURGE_INTERVAL {
+                    last_purge = Instant::now();
+                    relay.purge().await;
+                }
+            }
+        });
+    }
+
+    /// 发布当前所有待投递的消息，返回成功发布的条数
+    pub async fn relay_pending(&self) -> usize {
+        let _running = self.running.lock().await;
+        let mut relayed = 0;
+        loop {
+            let messages = match self.repository.find_pending(MAX_ATTEMPTS, BATCH_SIZE).await {
+                Ok(messages) => messages,
+                Err(e) => {
+                    error!("Failed to load outbox messages: {}", e);
+                    return relayed;
+                }
+            };
+            let batch_len = messages.len() as u64;
+            let mut failed = 0;
+            for message in messages {
+                match self.publish(&message).await {
+                    Ok(()) => {
+                        relayed += 1;
+                        if let Err(e) = self.repository.mark_delivered(&message.id).await {
+                            error!(
+                                "Failed to mark outbox message {} delivered: {}",
+                                message.id, e
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        warn!(
+                            "Failed to relay outbox message {} ({}): {}",
+                            message.id, message.event_type, e
+                        );
+                        if let Err(e) = self.repository.record_failure(&message.id, &e).await {
+                            error!("Failed to record outbox failure {}: {}", message.id, e);
+                        }
+                    }
+                }
+            }
+            // 整批失败时交给下一轮，避免在同一批消息上空转
+            if batch_len < BATCH_SIZE || failed as u64 == batch_len {
+                return relayed;
+            }
+        }
+    }
+
+    async fn publish(&self, message: &OutboxMessage) -> Result<(), String> {
+        match message.event_type.as_str() {
+            ANNOTATION_EVENT => {
+                let envelope = decode::<AnnotationEventRecord, AnnotationEvent>(message)?;
+                self.event_bus
+                    .publish(envelope)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            PLAYER_EVENT => {
+                let envelope = decode::<PlayerEventRecord, PlayerEvent>(message)?;
+                self.event_bus
+                    .publish(envelope)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            other => Err(format!("unknown event type: {}", other)),
+        }
+    }
+
+    async fn purge(&self) {
+        let before = (Utc::now() - chrono::Duration::days(RETENTION_DAYS)).naive_utc();
+        match self.repository.purge_delivered(before).await {
+            Ok(0) => {}
+            Ok(n) => info!("Purged {} delivered outbox messages", n),
+            Err(e) => error!("Failed to purge outbox: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use application::event::event_bus::{CorrelationId, EventId};
+
+    #[test]
+    fn annotation_event_survives_the_outbox_round_trip() {
+        let envelope = EventEnvelope::new(
+            42,
+            3,
+            AnnotationEvent::ItemRated {
+                annotation_id: AnnotationId::from(42),
+                version: 3,
+                user_id: UserId::from(7),
+                item_id: 1001,
+                item_type: "AudioFile".to_string(),
+                rating: 4,
+            },
+            CorrelationId::new(),
+            EventId::new(),
+        );
+        let message = encode_annotation_event(&envelope).unwrap();
+        let decoded = decode::<AnnotationEventRecord, AnnotationEvent>(&message).unwrap();
+
+        assert_eq!(decoded.id, envelope.id);
+        assert_eq!(decoded.correlation_id, envelope.correlation_id);
+        assert_eq!(decoded.causation_id, envelope.causation_id);
+        match decoded.payload {
+            AnnotationEvent::ItemRated {
+                annotation_id,
+                user_id,
+                item_id,
+                rating,
+                ..
+            } => {
+                assert_eq!(annotation_id.as_i64(), 42);
+                assert_eq!(user_id.as_i64(), 7);
+                assert_eq!(item_id, 1001);
+                assert_eq!(rating, 4);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn player_event_survives_the_outbox_round_trip() {
+        let envelope = EventEnvelope::new(
+            9,
+            2,
+            PlayerEvent {
+                player_id: PlayerId::from(9),
+                version: 2,
+                kind: PlayerEventKind::PlaybackStarted {
+                    user_id: UserId::from(7),
+                    audio_file_id: AudioFileId::from(1001),
+                },
+            },
+            CorrelationId::new(),
+            EventId::new(),
+        );
+        let message = encode_player_event(&envelope).unwrap();
+        assert_eq!(message.event_type, PLAYER_EVENT);
+        let decoded = decode::<PlayerEventRecord, PlayerEvent>(&message).unwrap();
+
+        assert_eq!(decoded.id, envelope.id);
+        assert_eq!(decoded.payload.player_id.as_i64(), 9);
+        assert_eq!(decoded.payload.version, 2);
+        match decoded.payload.kind {
+            PlayerEventKind::PlaybackStarted {
+                user_id,
+                audio_file_id,
+            } => {
+                assert_eq!(user_id.as_i64(), 7);
+                assert_eq!(audio_file_id.as_i64(), 1001);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+}
@@ -29,6 +29,7 @@ impl FileTypeDetector for DefaultFileTypeDetector {
             | "psd" | "raw" | "cr2" | "nef" | "arw" | "dng" => FileType::Image,
             // NFO file extension
             "nfo" => FileType::Nfo,
+            "pdf" => FileType::Document,
             // Default to Other for unknown extensions
             _ => FileType::Other,
         }
@@ -71,12 +72,19 @@ mod tests {
         assert_eq!(detector.detect("NFO"), FileType::Nfo);
     }
 
+    #[test]
+    fn test_document_detection() {
+        let detector = DefaultFileTypeDetector::new();
+
+        assert_eq!(detector.detect("pdf"), FileType::Document);
+        assert_eq!(detector.detect("PDF"), FileType::Document);
+    }
+
     #[test]
     fn test_other_detection() {
         let detector = DefaultFileTypeDetector::new();
 
         assert_eq!(detector.detect("txt"), FileType::Other);
-        assert_eq!(detector.detect("pdf"), FileType::Other);
         assert_eq!(detector.detect("doc"), FileType::Other);
         assert_eq!(detector.detect(""), FileType::Other);
     }
@@ -2,6 +2,7 @@ use application::command::shared::IdGenerator;
 use application::error::AppError;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
@@ -20,14 +21,15 @@ pub struct SnowflakeIdGenerator {
     last_timestamp: Arc<Mutex<i64>>,
     sequence: Arc<Mutex<i64>>,
     business_map: Arc<Mutex<HashMap<String, (i64, i64)>>>, // 业务类型 -> (上一个时间戳, 序列号)
+    clock_regressed: Arc<AtomicBool>,
 }
 
 impl SnowflakeIdGenerator {
     /// 创建新的雪花算法ID生成器
     pub fn new(node_id: i64) -> Result<Self, AppError> {
-        if node_id > MAX_NODE_ID {
+        if !(0..=MAX_NODE_ID).contains(&node_id) {
             return Err(AppError::UnknownError(format!(
-                "节点ID不能超过{}",
+                "节点ID必须在0到{}之间",
                 MAX_NODE_ID
             )));
         }
@@ -37,9 +39,25 @@ impl SnowflakeIdGenerator {
             last_timestamp: Arc::new(Mutex::new(0)),
             sequence: Arc::new(Mutex::new(0)),
             business_map: Arc::new(Mutex::new(HashMap::new())),
+            clock_regressed: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// 由主机名派生节点ID，未配置节点ID时使用。多实例部署时仍建议显式配置以避免冲突
+    pub fn node_id_from_hostname() -> i64 {
+        let hostname = std::env::var("HOSTNAME")
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| "localhost".to_string());
+        // FNV-1a，保证同一主机名在不同版本间得到相同结果
+        let hash = hostname.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        (hash % (MAX_NODE_ID as u64 + 1)) as i64
+    }
+
     /// 获取当前时间戳（毫秒）
     fn get_timestamp() -> Result<i64, AppError> {
         SystemTime::now()
@@ -53,6 +71,34 @@ impl SnowflakeIdGenerator {
         ((timestamp - EPOCH) << TIMESTAMP_SHIFT) | (node_id << NODE_ID_SHIFT) | sequence
     }
 
+    /// 计算下一个 (时间戳, 序列号)，返回 None 表示需要等待下一毫秒
+    ///
+    /// 时钟回拨时沿用上一个时间戳继续递增序列号，序列号用尽则借用下一毫秒，
+    /// 保证 ID 单调递增而不是拒绝生成
+    fn advance(&self, now: i64, last_timestamp: i64, sequence: i64) -> Option<(i64, i64)> {
+        if now < last_timestamp {
+            if !self.clock_regressed.swap(true, Ordering::Relaxed) {
+                log::warn!(
+                    "System clock moved backwards by {}ms, continuing from last timestamp",
+                    last_timestamp - now
+                );
+            }
+        } else {
+            self.clock_regressed.store(false, Ordering::Relaxed);
+        }
+        if now > last_timestamp {
+            return Some((now, 0));
+        }
+        let sequence = (sequence + 1) & MAX_SEQUENCE;
+        if sequence != 0 {
+            Some((last_timestamp, sequence))
+        } else if now < last_timestamp {
+            Some((last_timestamp + 1, 0))
+        } else {
+            None
+        }
+    }
+
     /// 等待下一个毫秒
     async fn wait_next_millis(&self, last_timestamp: i64) -> Result<i64, AppError> {
         let mut timestamp = Self::get_timestamp()?;
@@ -70,49 +116,31 @@ impl IdGenerator for SnowflakeIdGenerator {
         let mut last_timestamp = self.last_timestamp.lock().await;
         let mut sequence = self.sequence.lock().await;
 
-        let mut timestamp = Self::get_timestamp()?;
-
-        if timestamp < *last_timestamp {
-            return Err(AppError::UnknownError(
-                "系统时钟回拨，拒绝生成ID".to_string(),
-            ));
-        }
-
-        if timestamp == *last_timestamp {
-            *sequence = (*sequence + 1) & MAX_SEQUENCE;
-            if *sequence == 0 {
-                timestamp = self.wait_next_millis(*last_timestamp).await?;
-            }
-        } else {
-            *sequence = 0;
-        }
+        let now = Self::get_timestamp()?;
+        let (timestamp, next_sequence) = match self.advance(now, *last_timestamp, *sequence) {
+            Some(next) => next,
+            None => (self.wait_next_millis(*last_timestamp).await?, 0),
+        };
 
         *last_timestamp = timestamp;
-        Ok(self.generate_id(timestamp, self.node_id, *sequence))
+        *sequence = next_sequence;
+        Ok(self.generate_id(timestamp, self.node_id, next_sequence))
     }
 
     async fn next_id_with_business(&self, business_key: &str) -> Result<i64, AppError> {
         let mut business_map = self.business_map.lock().await;
-        let mut timestamp = Self::get_timestamp()?;
-        let (mut last_timestamp, mut sequence) =
-            business_map.get(business_key).copied().unwrap_or((0, 0));
+        let now = Self::get_timestamp()?;
+        let (last_timestamp, sequence) = business_map.get(business_key).copied().unwrap_or((0, 0));
 
-        if timestamp < last_timestamp {
-            return Err(AppError::UnknownError(
-                "系统时钟回拨，拒绝生成ID".to_string(),
-            ));
-        }
-
-        if timestamp == last_timestamp {
-            sequence = (sequence + 1) & MAX_SEQUENCE;
-            if sequence == 0 {
+        let (timestamp, sequence) = match self.advance(now, last_timestamp, sequence) {
+            Some(next) => next,
+            None => {
                 drop(business_map); // 释放锁，避免在异步等待过程中持有锁
-                timestamp = self.wait_next_millis(last_timestamp).await?;
+                let timestamp = self.wait_next_millis(last_timestamp).await?;
                 business_map = self.business_map.lock().await;
+                (timestamp, 0)
             }
-        } else {
-            sequence = 0;
-        }
+        };
 
         business_map.insert(business_key.to_string(), (timestamp, sequence));
         Ok(self.generate_id(timestamp, self.node_id, sequence))
@@ -164,4 +192,28 @@ mod tests {
             genre_ids.insert(id);
         }
     }
+
+    #[test]
+    fn test_clock_regression_keeps_ids_increasing() {
+        let generator = SnowflakeIdGenerator::new(1).unwrap();
+        let last = 1_700_000_000_000;
+
+        // 时钟回拨时沿用上一个时间戳
+        assert_eq!(generator.advance(last - 500, last, 3), Some((last, 4)));
+        // 回拨期间序列号用尽，借用下一毫秒而不是等待
+        assert_eq!(
+            generator.advance(last - 500, last, MAX_SEQUENCE),
+            Some((last + 1, 0))
+        );
+        // 同一毫秒序列号用尽需要等待
+        assert_eq!(generator.advance(last, last, MAX_SEQUENCE), None);
+        assert_eq!(generator.advance(last + 1, last, 7), Some((last + 1, 0)));
+    }
+
+    #[test]
+    fn test_node_id_validation() {
+        assert!(SnowflakeIdGenerator::new(-1).is_err());
+        assert!(SnowflakeIdGenerator::new(MAX_NODE_ID + 1).is_err());
+        assert!((0..=MAX_NODE_ID).contains(&SnowflakeIdGenerator::node_id_from_hostname()));
+    }
 }
@@ -0,0 +1,159 @@
+use super::ImportError;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 导出文件中的一条注解。条目用路径和标签描述而不是 ID，重新扫描或换库后仍能对应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationRecord {
+    pub username: String,
+    /// song、album 或 artist
+    pub kind: String,
+    /// 歌曲的文件路径，专辑和艺术家为空
+    #[serde(default)]
+    pub path: String,
+    /// 歌曲标题，专辑和艺术家为空
+    #[serde(default)]
+    pub title: String,
+    /// 歌曲所在专辑或专辑名
+    #[serde(default)]
+    pub album: String,
+    /// 歌曲艺术家、专辑艺术家或艺术家名
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub starred_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub rating: i32,
+    #[serde(default)]
+    pub play_count: i32,
+    #[serde(default)]
+    pub played_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationFormat {
+    /// 带表头的 CSV，列与 AnnotationRecord 的字段一致
+    Csv,
+    /// AnnotationRecord 数组
+    Json,
+}
+
+impl FromStr for AnnotationFormat {
+    type Err = ImportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(AnnotationFormat::Csv),
+            "json" => Ok(AnnotationFormat::Json),
+            _ => Err(ImportError::Source(format!("Unknown format: {}", s))),
+        }
+    }
+}
+
+impl AnnotationFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AnnotationFormat::Csv => "csv",
+            AnnotationFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            AnnotationFormat::Csv => "text/csv; charset=utf-8",
+            AnnotationFormat::Json => "application/json",
+        }
+    }
+
+    pub fn read(&self, bytes: &[u8]) -> Result<Vec<AnnotationRecord>, ImportError> {
+        match self {
+            AnnotationFormat::Csv => csv::Reader::from_reader(bytes)
+                .deserialize()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| ImportError::Source(e.to_string())),
+            AnnotationFormat::Json => {
+                serde_json::from_slice(bytes).map_err(|e| ImportError::Source(e.to_string()))
+            }
+        }
+    }
+
+    pub fn write(&self, records: &[AnnotationRecord]) -> Result<Vec<u8>, ImportError> {
+        match self {
+            AnnotationFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                for record in records {
+                    writer
+                        .serialize(record)
+                        .map_err(|e| ImportError::Source(e.to_string()))?;
+                }
+                writer
+                    .into_inner()
+                    .map_err(|e| ImportError::Source(e.to_string()))
+            }
+            AnnotationFormat::Json => {
+                serde_json::to_vec(records).map_err(|e| ImportError::Source(e.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<AnnotationRecord> {
+        let at = NaiveDateTime::parse_from_str("2024-05-01 08:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        vec![
+            AnnotationRecord {
+                username: "alice".to_string(),
+                kind: "song".to_string(),
+                path: "local:///music/Queen/A Night at the Opera/11 Bohemian Rhapsody, Pt. 1.flac"
+                    .to_string(),
+                title: "Bohemian \"Rhapsody\"".to_string(),
+                album: "A Night at the Opera".to_string(),
+                artist: "Queen".to_string(),
+                starred: true,
+                starred_at: Some(at),
+                rating: 5,
+                play_count: 12,
+                played_at: Some(at),
+            },
+            AnnotationRecord {
+                username: "alice".to_string(),
+                kind: "artist".to_string(),
+                path: String::new(),
+                title: String::new(),
+                album: String::new(),
+                artist: "Björk".to_string(),
+                starred: false,
+                starred_at: None,
+                rating: 4,
+                play_count: 0,
+                played_at: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_and_json_round_trip() {
+        for format in [AnnotationFormat::Csv, AnnotationFormat::Json] {
+            let bytes = format.write(&records()).unwrap();
+            assert_eq!(format.read(&bytes).unwrap(), records(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn csv_columns_may_be_omitted() {
+        let csv = "username,kind,artist,rating\nbob,artist,Queen,3\n";
+        let read = AnnotationFormat::Csv.read(csv.as_bytes()).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].artist, "Queen");
+        assert_eq!(read[0].rating, 3);
+        assert!(!read[0].starred);
+        assert_eq!(read[0].played_at, None);
+    }
+}
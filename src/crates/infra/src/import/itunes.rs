@@ -0,0 +1,275 @@
+use super::ImportError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// iTunes 的评分为 0-100，每 20 分一颗星
+const RATING_PER_STAR: i64 = 20;
+
+#[derive(Debug, Clone)]
+pub struct ItunesTrack {
+    /// 本地文件路径，流媒体曲目为 None
+    pub path: Option<String>,
+    pub title: String,
+    pub album: String,
+    pub artist: String,
+    pub album_artist: String,
+    pub play_count: i32,
+    pub played_at: Option<NaiveDateTime>,
+    /// 1-5，0 表示未评分或由专辑评分推算
+    pub rating: i32,
+    /// 1-5，0 表示未评分或由歌曲评分推算
+    pub album_rating: i32,
+    pub loved: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ItunesPlaylist {
+    pub name: String,
+    pub track_ids: Vec<i64>,
+}
+
+/// `iTunes Music Library.xml`（或“音乐”应用导出的资料库 XML）
+#[derive(Debug, Clone)]
+pub struct ItunesLibrary {
+    pub tracks: HashMap<i64, ItunesTrack>,
+    /// 只包含普通播放列表，不含资料库本身、内置分类、智能播放列表和文件夹
+    pub playlists: Vec<ItunesPlaylist>,
+}
+
+#[derive(Deserialize)]
+struct RawLibrary {
+    #[serde(rename = "Tracks", default)]
+    tracks: HashMap<String, RawTrack>,
+    #[serde(rename = "Playlists", default)]
+    playlists: Vec<RawPlaylist>,
+}
+
+#[derive(Deserialize)]
+struct RawTrack {
+    #[serde(rename = "Track ID")]
+    id: i64,
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "Artist", default)]
+    artist: String,
+    #[serde(rename = "Album Artist")]
+    album_artist: Option<String>,
+    #[serde(rename = "Album", default)]
+    album: String,
+    #[serde(rename = "Location")]
+    location: Option<String>,
+    #[serde(rename = "Play Count", default)]
+    play_count: i64,
+    #[serde(rename = "Play Date UTC")]
+    play_date: Option<plist::Date>,
+    #[serde(rename = "Rating", default)]
+    rating: i64,
+    #[serde(rename = "Rating Computed", default)]
+    rating_computed: bool,
+    #[serde(rename = "Album Rating", default)]
+    album_rating: i64,
+    #[serde(rename = "Album Rating Computed", default)]
+    album_rating_computed: bool,
+    #[serde(rename = "Loved", default)]
+    loved: bool,
+    /// 新版“音乐”应用用“喜爱”代替了“Loved”
+    #[serde(rename = "Favorited", default)]
+    favorited: bool,
+}
+
+#[derive(Deserialize)]
+struct RawPlaylist {
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "Master", default)]
+    master: bool,
+    #[serde(rename = "Distinguished Kind")]
+    distinguished_kind: Option<i64>,
+    #[serde(rename = "Smart Info")]
+    smart_info: Option<plist::Value>,
+    #[serde(rename = "Folder", default)]
+    folder: bool,
+    #[serde(rename = "Playlist Items", default)]
+    items: Vec<RawPlaylistItem>,
+}
+
+#[derive(Deserialize)]
+struct RawPlaylistItem {
+    #[serde(rename = "Track ID")]
+    track_id: i64,
+}
+
+impl ItunesLibrary {
+    pub fn open(path: &Path) -> Result<Self, ImportError> {
+        let raw: RawLibrary = plist::from_file(path)?;
+        Ok(Self::from_raw(raw))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, ImportError> {
+        let raw: RawLibrary = plist::from_bytes(bytes)?;
+        Ok(Self::from_raw(raw))
+    }
+
+    fn from_raw(raw: RawLibrary) -> Self {
+        let tracks = raw
+            .tracks
+            .into_values()
+            .map(|track| {
+                let imported = ItunesTrack {
+                    path: track.location.as_deref().and_then(location_to_path),
+                    title: track.name,
+                    album: track.album,
+                    album_artist: track.album_artist.unwrap_or_else(|| track.artist.clone()),
+                    artist: track.artist,
+                    play_count: track.play_count.clamp(0, i32::MAX as i64) as i32,
+                    played_at: track
+                        .play_date
+                        .map(|date| DateTime::<Utc>::from(SystemTime::from(date)).naive_utc()),
+                    rating: stars(track.rating, track.rating_computed),
+                    album_rating: stars(track.album_rating, track.album_rating_computed),
+                    loved: track.loved || track.favorited,
+                };
+                (track.id, imported)
+            })
+            .collect();
+        let playlists = raw
+            .playlists
+            .into_iter()
+            .filter(|playlist| {
+                !playlist.master
+                    && playlist.distinguished_kind.is_none()
+                    && playlist.smart_info.is_none()
+                    && !playlist.folder
+            })
+            .map(|playlist| ItunesPlaylist {
+                name: playlist.name,
+                track_ids: playlist.items.iter().map(|item| item.track_id).collect(),
+            })
+            .collect();
+        Self { tracks, playlists }
+    }
+}
+
+/// 推算出的评分不是用户设置的，忽略
+fn stars(rating: i64, computed: bool) -> i32 {
+    if computed {
+        return 0;
+    }
+    ((rating + RATING_PER_STAR / 2) / RATING_PER_STAR).clamp(0, 5) as i32
+}
+
+/// `file://localhost/Users/me/Music/a%20b.mp3` 或 `file://localhost/C:/Music/a.mp3`
+fn location_to_path(location: &str) -> Option<String> {
+    let rest = location.strip_prefix("file://")?;
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    let path = percent_decode_str(rest).decode_utf8().ok()?;
+    let bytes = path.as_bytes();
+    if bytes.len() > 2 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+        return Some(path[1..].to_string());
+    }
+    Some(path.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple Computer//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Major Version</key><integer>1</integer>
+	<key>Tracks</key>
+	<dict>
+		<key>101</key>
+		<dict>
+			<key>Track ID</key><integer>101</integer>
+			<key>Name</key><string>Army of Me</string>
+			<key>Artist</key><string>Björk</string>
+			<key>Album</key><string>Post</string>
+			<key>Play Count</key><integer>12</integer>
+			<key>Play Date UTC</key><date>2020-05-01T10:00:00Z</date>
+			<key>Rating</key><integer>80</integer>
+			<key>Album Rating</key><integer>60</integer>
+			<key>Album Rating Computed</key><true/>
+			<key>Loved</key><true/>
+			<key>Location</key><string>file://localhost/Users/me/Music/Bj%C3%B6rk/Post/01%20Army%20of%20Me.m4a</string>
+		</dict>
+		<key>102</key>
+		<dict>
+			<key>Track ID</key><integer>102</integer>
+			<key>Name</key><string>Hyperballad</string>
+			<key>Artist</key><string>Björk</string>
+			<key>Album</key><string>Post</string>
+		</dict>
+	</dict>
+	<key>Playlists</key>
+	<array>
+		<dict>
+			<key>Name</key><string>Library</string>
+			<key>Master</key><true/>
+			<key>Playlist Items</key>
+			<array>
+				<dict><key>Track ID</key><integer>101</integer></dict>
+				<dict><key>Track ID</key><integer>102</integer></dict>
+			</array>
+		</dict>
+		<dict>
+			<key>Name</key><string>Music</string>
+			<key>Distinguished Kind</key><integer>4</integer>
+		</dict>
+		<dict>
+			<key>Name</key><string>Favourites</string>
+			<key>Playlist Items</key>
+			<array>
+				<dict><key>Track ID</key><integer>102</integer></dict>
+				<dict><key>Track ID</key><integer>101</integer></dict>
+			</array>
+		</dict>
+	</array>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn parses_tracks_and_regular_playlists() {
+        let library = ItunesLibrary::parse(LIBRARY.as_bytes()).unwrap();
+        assert_eq!(library.tracks.len(), 2);
+
+        let track = &library.tracks[&101];
+        assert_eq!(
+            track.path.as_deref(),
+            Some("/Users/me/Music/Björk/Post/01 Army of Me.m4a")
+        );
+        assert_eq!(track.album_artist, "Björk");
+        assert_eq!(track.play_count, 12);
+        assert_eq!(
+            track.played_at.map(|t| t.to_string()),
+            Some("2020-05-01 10:00:00".to_string())
+        );
+        assert_eq!(track.rating, 4);
+        assert_eq!(track.album_rating, 0);
+        assert!(track.loved);
+
+        let track = &library.tracks[&102];
+        assert_eq!(track.path, None);
+        assert_eq!(track.play_count, 0);
+        assert!(!track.loved);
+
+        assert_eq!(library.playlists.len(), 1);
+        assert_eq!(library.playlists[0].name, "Favourites");
+        assert_eq!(library.playlists[0].track_ids, vec![102, 101]);
+    }
+
+    #[test]
+    fn converts_windows_locations() {
+        assert_eq!(
+            location_to_path("file://localhost/C:/Users/me/Music/a%20b.mp3").as_deref(),
+            Some("C:/Users/me/Music/a b.mp3")
+        );
+        assert_eq!(location_to_path("http://example.com/stream"), None);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use unidecode::unidecode;
+
+/// 用路径末尾几级目录匹配，兼容挂载点不同的情况（艺术家/专辑/文件）
+const PATH_TAIL_COMPONENTS: usize = 3;
+
+/// 本库中的歌曲
+#[derive(Debug, Clone)]
+pub struct CatalogSong {
+    pub id: i64,
+    /// `protocol://path` 或不带协议的路径
+    pub path: String,
+    pub title: String,
+    pub album: String,
+    pub artist: String,
+}
+
+/// 匹配方式，用于统计报告
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchKind {
+    Path,
+    PathTail,
+    Tags,
+}
+
+/// 同一个键对应多个条目时视为无法匹配，避免导入到错误的歌曲上
+struct UniqueIndex<K>(HashMap<K, Option<i64>>);
+
+impl<K: Eq + Hash> UniqueIndex<K> {
+    fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    fn insert(&mut self, key: K, id: i64) {
+        self.0
+            .entry(key)
+            .and_modify(|existing| {
+                if *existing != Some(id) {
+                    *existing = None;
+                }
+            })
+            .or_insert(Some(id));
+    }
+
+    fn get(&self, key: &K) -> Option<i64> {
+        self.0.get(key).copied().flatten()
+    }
+}
+
+/// 把其他服务器的歌曲、专辑和艺术家对应到本库：
+/// 先比较完整路径（可按前缀改写），再比较路径末尾，最后比较规范化后的艺术家/专辑/标题
+pub struct CatalogMatcher {
+    path_maps: Vec<(String, String)>,
+    by_path: UniqueIndex<String>,
+    by_tail: UniqueIndex<String>,
+    by_tags: UniqueIndex<(String, String, String)>,
+    by_artist_title: UniqueIndex<(String, String)>,
+    albums: UniqueIndex<(String, String)>,
+    artists: UniqueIndex<String>,
+}
+
+impl CatalogMatcher {
+    /// `path_maps` 为 (原路径前缀, 本库路径前缀)
+    pub fn new(path_maps: Vec<(String, String)>) -> Self {
+        Self {
+            path_maps,
+            by_path: UniqueIndex::new(),
+            by_tail: UniqueIndex::new(),
+            by_tags: UniqueIndex::new(),
+            by_artist_title: UniqueIndex::new(),
+            albums: UniqueIndex::new(),
+            artists: UniqueIndex::new(),
+        }
+    }
+
+    pub fn add_song(&mut self, song: CatalogSong) {
+        let path = strip_protocol(&song.path);
+        self.by_path.insert(path.to_string(), song.id);
+        if let Some(tail) = path_tail(path) {
+            self.by_tail.insert(tail, song.id);
+        }
+        let artist = normalize(&song.artist);
+        let title = normalize(&song.title);
+        self.by_tags.insert(
+            (artist.clone(), normalize(&song.album), title.clone()),
+            song.id,
+        );
+        self.by_artist_title.insert((artist, title), song.id);
+    }
+
+    pub fn add_album(&mut self, id: i64, name: &str, artist: &str) {
+        self.albums.insert((normalize(artist), normalize(name)), id);
+    }
+
+    pub fn add_artist(&mut self, id: i64, name: &str) {
+        self.artists.insert(normalize(name), id);
+    }
+
+    pub fn match_song(
+        &self,
+        path: &str,
+        title: &str,
+        album: &str,
+        artist: &str,
+    ) -> Option<(i64, MatchKind)> {
+        self.match_song_by_path(path)
+            .or_else(|| self.match_song_by_tags(title, album, artist))
+    }
+
+    /// 只比较完整路径和路径末尾
+    pub fn match_song_by_path(&self, path: &str) -> Option<(i64, MatchKind)> {
+        let path = self.map_path(path);
+        if let Some(id) = self.by_path.get(&path) {
+            return Some((id, MatchKind::Path));
+        }
+        path_tail(&path)
+            .and_then(|tail| self.by_tail.get(&tail))
+            .map(|id| (id, MatchKind::PathTail))
+    }
+
+    /// 只比较规范化后的艺术家/专辑/标题
+    pub fn match_song_by_tags(
+        &self,
+        title: &str,
+        album: &str,
+        artist: &str,
+    ) -> Option<(i64, MatchKind)> {
+        let artist = normalize(artist);
+        let title = normalize(title);
+        self.by_tags
+            .get(&(artist.clone(), normalize(album), title.clone()))
+            .or_else(|| self.by_artist_title.get(&(artist, title)))
+            .map(|id| (id, MatchKind::Tags))
+    }
+
+    pub fn match_album(&self, name: &str, artist: &str) -> Option<i64> {
+        self.albums.get(&(normalize(artist), normalize(name)))
+    }
+
+    pub fn match_artist(&self, name: &str) -> Option<i64> {
+        self.artists.get(&normalize(name))
+    }
+
+    fn map_path(&self, path: &str) -> String {
+        let path = strip_protocol(path);
+        for (from, to) in &self.path_maps {
+            if let Some(rest) = path.strip_prefix(from.as_str()) {
+                return format!("{}{}", to, rest);
+            }
+        }
+        path.to_string()
+    }
+}
+
+fn strip_protocol(path: &str) -> &str {
+    match path.split_once("://") {
+        Some((_, rest)) => rest,
+        None => path,
+    }
+}
+
+/// 忽略大小写和重音，兼容不同文件系统的 Unicode 规范化形式
+fn path_tail(path: &str) -> Option<String> {
+    let components: Vec<&str> = path
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty())
+        .collect();
+    if components.len() < PATH_TAIL_COMPONENTS {
+        return None;
+    }
+    let tail = components[components.len() - PATH_TAIL_COMPONENTS..].join("/");
+    Some(unidecode(&tail).to_lowercase())
+}
+
+/// 去掉重音、大小写、括号内容（版本说明等）和标点
+fn normalize(value: &str) -> String {
+    let mut depth = 0usize;
+    let mut stripped = String::with_capacity(value.len());
+    for c in unidecode(value).chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            c if depth == 0 && c.is_alphanumeric() => stripped.extend(c.to_lowercase()),
+            _ => {}
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(id: i64, path: &str, title: &str, album: &str, artist: &str) -> CatalogSong {
+        CatalogSong {
+            id,
+            path: path.to_string(),
+            title: title.to_string(),
+            album: album.to_string(),
+            artist: artist.to_string(),
+        }
+    }
+
+    fn matcher() -> CatalogMatcher {
+        let mut matcher =
+            CatalogMatcher::new(vec![("/data/music/".to_string(), "/music/".to_string())]);
+        matcher.add_song(song(
+            1,
+            "local:///music/Queen/A Night at the Opera/11 Bohemian Rhapsody.flac",
+            "Bohemian Rhapsody",
+            "A Night at the Opera",
+            "Queen",
+        ));
+        matcher.add_song(song(
+            2,
+            "local:///music/Björk/Post/01 Army of Me.flac",
+            "Army of Me",
+            "Post",
+            "Björk",
+        ));
+        matcher
+    }
+
+    #[test]
+    fn matches_by_mapped_path() {
+        let found = matcher().match_song(
+            "/data/music/Queen/A Night at the Opera/11 Bohemian Rhapsody.flac",
+            "",
+            "",
+            "",
+        );
+        assert_eq!(found, Some((1, MatchKind::Path)));
+    }
+
+    #[test]
+    fn matches_by_path_tail() {
+        let found = matcher().match_song("/mnt/nas/bjork/post/01 army of me.flac", "", "", "");
+        assert_eq!(found, Some((2, MatchKind::PathTail)));
+    }
+
+    #[test]
+    fn matches_by_normalized_tags() {
+        let matcher = matcher();
+        let found = matcher.match_song(
+            "/other/file.mp3",
+            "Bohemian Rhapsody (Remastered 2011)",
+            "A Night At The Opera",
+            "queen",
+        );
+        assert_eq!(found, Some((1, MatchKind::Tags)));
+        let found = matcher.match_song("x.mp3", "Army of Me", "Greatest Hits", "Bjork");
+        assert_eq!(found, Some((2, MatchKind::Tags)));
+    }
+
+    #[test]
+    fn strategies_can_be_used_alone() {
+        let matcher = matcher();
+        assert_eq!(matcher.match_song_by_path("/other/file.mp3"), None);
+        assert_eq!(
+            matcher.match_song_by_tags("Army of Me", "Post", "Björk"),
+            Some((2, MatchKind::Tags))
+        );
+        assert_eq!(matcher.match_song_by_tags("", "", ""), None);
+    }
+
+    #[test]
+    fn ambiguous_keys_do_not_match() {
+        let mut matcher = matcher();
+        matcher.add_song(song(3, "/live/a.flac", "Army of Me", "Live", "Björk"));
+        assert_eq!(
+            matcher.match_song("x.mp3", "Army of Me", "Other", "Björk"),
+            None
+        );
+        assert_eq!(
+            matcher.match_song("x.mp3", "Army of Me", "Live", "Björk"),
+            Some((3, MatchKind::Tags))
+        );
+    }
+}
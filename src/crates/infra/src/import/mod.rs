@@ -0,0 +1,25 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+mod annotation_file;
+mod itunes;
+mod matcher;
+mod navidrome;
+
+pub use annotation_file::{AnnotationFormat, AnnotationRecord};
+pub use itunes::{ItunesLibrary, ItunesPlaylist, ItunesTrack};
+pub use matcher::{CatalogMatcher, CatalogSong, MatchKind};
+pub use navidrome::{
+    NavidromeAnnotation, NavidromeDb, NavidromeItem, NavidromePlaylist, NavidromeSong,
+    NavidromeUser, DEFAULT_NAVIDROME_KEY,
+};
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Database error: {0}")]
+    Db(#[from] DbErr),
+    #[error("Invalid property list: {0}")]
+    Plist(#[from] plist::Error),
+    #[error("Invalid import source: {0}")]
+    Source(String),
+}
@@ -0,0 +1,320 @@
+use super::ImportError;
+use crate::crypto::Aes256GcmEncryptor;
+use application::auth::PasswordEncryptor;
+use chrono::{DateTime, NaiveDateTime};
+use log::warn;
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, DbBackend, FromQueryResult, Statement,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 未配置 `PasswordEncryptionKey` 时 Navidrome 使用的密钥
+pub const DEFAULT_NAVIDROME_KEY: &str = "just for obfuscation";
+
+#[derive(Debug, Clone)]
+pub struct NavidromeUser {
+    pub id: String,
+    pub username: String,
+    pub name: String,
+    pub email: String,
+    pub is_admin: bool,
+    /// 密钥不匹配时无法解密，为 None
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NavidromeSong {
+    pub path: String,
+    pub title: String,
+    pub album: String,
+    pub artist: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NavidromePlaylist {
+    pub name: String,
+    pub comment: String,
+    pub public: bool,
+    pub owner_id: String,
+    pub tracks: Vec<NavidromeSong>,
+}
+
+#[derive(Debug, Clone)]
+pub enum NavidromeItem {
+    Song(NavidromeSong),
+    Album { name: String, artist: String },
+    Artist { name: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct NavidromeAnnotation {
+    pub user_id: String,
+    pub item: NavidromeItem,
+    pub play_count: i32,
+    pub played_at: Option<NaiveDateTime>,
+    pub rating: i32,
+    pub starred_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct UserRow {
+    id: String,
+    user_name: String,
+    name: Option<String>,
+    email: Option<String>,
+    password: Option<String>,
+    is_admin: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct TrackRow {
+    playlist_id: String,
+    path: String,
+    title: String,
+    album: String,
+    artist: String,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AnnotationRow {
+    user_id: String,
+    item_type: String,
+    play_count: i64,
+    play_date: Option<String>,
+    rating: i64,
+    starred: i64,
+    starred_at: Option<String>,
+    song_path: Option<String>,
+    song_title: Option<String>,
+    song_album: Option<String>,
+    song_artist: Option<String>,
+    album_name: Option<String>,
+    album_artist: Option<String>,
+    artist_name: Option<String>,
+}
+
+const USERS_SQL: &str = r#"
+SELECT id, user_name, name, email, password, CAST(is_admin AS INTEGER) AS is_admin
+FROM user
+ORDER BY created_at
+"#;
+
+const TRACKS_SQL: &str = r#"
+SELECT pt.playlist_id, m.path, m.title, m.album, m.artist
+FROM playlist_tracks pt
+JOIN media_file m ON m.id = pt.media_file_id
+ORDER BY pt.playlist_id, pt.id
+"#;
+
+const ANNOTATIONS_SQL: &str = r#"
+SELECT a.user_id, a.item_type,
+       COALESCE(a.play_count, 0) AS play_count, a.play_date,
+       COALESCE(a.rating, 0) AS rating,
+       CAST(COALESCE(a.starred, 0) AS INTEGER) AS starred, a.starred_at,
+       m.path AS song_path, m.title AS song_title, m.album AS song_album, m.artist AS song_artist,
+       al.name AS album_name, al.album_artist AS album_artist,
+       ar.name AS artist_name
+FROM annotation a
+LEFT JOIN media_file m ON a.item_type = 'media_file' AND m.id = a.item_id
+LEFT JOIN album al ON a.item_type = 'album' AND al.id = a.item_id
+LEFT JOIN artist ar ON a.item_type = 'artist' AND ar.id = a.item_id
+"#;
+
+/// 只读打开 Navidrome 的 SQLite 数据库
+pub struct NavidromeDb {
+    db: DatabaseConnection,
+    encryptor: Aes256GcmEncryptor,
+}
+
+impl NavidromeDb {
+    /// `key` 为 Navidrome 的 `PasswordEncryptionKey`，用于解密用户密码
+    pub async fn open(path: &Path, key: &str) -> Result<Self, ImportError> {
+        if !path.is_file() {
+            return Err(ImportError::Source(format!(
+                "{} is not a file",
+                path.display()
+            )));
+        }
+        let db = Database::connect(format!("sqlite://{}?mode=ro", path.display())).await?;
+        let encryptor =
+            Aes256GcmEncryptor::new(key).map_err(|e| ImportError::Source(e.to_string()))?;
+        Ok(Self { db, encryptor })
+    }
+
+    pub async fn users(&self) -> Result<Vec<NavidromeUser>, ImportError> {
+        let rows = UserRow::find_by_statement(Statement::from_string(DbBackend::Sqlite, USERS_SQL))
+            .all(&self.db)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let password = row.password.filter(|p| !p.is_empty()).and_then(|p| {
+                    match self.encryptor.decrypt(&p) {
+                        Ok(password) => Some(password),
+                        Err(e) => {
+                            warn!("Failed to decrypt password of {}: {}", row.user_name, e);
+                            None
+                        }
+                    }
+                });
+                NavidromeUser {
+                    id: row.id,
+                    name: row.name.unwrap_or_default(),
+                    email: row.email.unwrap_or_default(),
+                    username: row.user_name,
+                    is_admin: row.is_admin != 0,
+                    password,
+                }
+            })
+            .collect())
+    }
+
+    /// 智能播放列表（带 rules）的内容由规则生成，不导入
+    pub async fn playlists(&self) -> Result<Vec<NavidromePlaylist>, ImportError> {
+        let rows = self
+            .db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT * FROM playlist ORDER BY created_at",
+            ))
+            .await?;
+        let mut tracks: HashMap<String, Vec<NavidromeSong>> = HashMap::new();
+        for row in
+            TrackRow::find_by_statement(Statement::from_string(DbBackend::Sqlite, TRACKS_SQL))
+                .all(&self.db)
+                .await?
+        {
+            tracks
+                .entry(row.playlist_id)
+                .or_default()
+                .push(NavidromeSong {
+                    path: row.path,
+                    title: row.title,
+                    album: row.album,
+                    artist: row.artist,
+                });
+        }
+
+        let mut playlists = Vec::new();
+        for row in rows {
+            // 旧版本没有 rules 列
+            let rules: Option<String> = row.try_get("", "rules").ok().flatten();
+            if rules.is_some_and(|rules| !rules.trim().is_empty() && rules != "null") {
+                continue;
+            }
+            let id: String = row.try_get("", "id")?;
+            playlists.push(NavidromePlaylist {
+                name: row.try_get("", "name")?,
+                comment: row
+                    .try_get::<Option<String>>("", "comment")?
+                    .unwrap_or_default(),
+                public: row.try_get::<Option<bool>>("", "public")?.unwrap_or(false),
+                owner_id: row.try_get("", "owner_id")?,
+                tracks: tracks.remove(&id).unwrap_or_default(),
+            });
+        }
+        Ok(playlists)
+    }
+
+    /// 忽略目标已被删除或没有任何数据的标注
+    pub async fn annotations(&self) -> Result<Vec<NavidromeAnnotation>, ImportError> {
+        let rows = AnnotationRow::find_by_statement(Statement::from_string(
+            DbBackend::Sqlite,
+            ANNOTATIONS_SQL,
+        ))
+        .all(&self.db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter(|row| row.play_count > 0 || row.rating > 0 || row.starred != 0)
+            .filter_map(|row| {
+                let item = match row.item_type.as_str() {
+                    "media_file" => NavidromeItem::Song(NavidromeSong {
+                        path: row.song_path?,
+                        title: row.song_title.unwrap_or_default(),
+                        album: row.song_album.unwrap_or_default(),
+                        artist: row.song_artist.unwrap_or_default(),
+                    }),
+                    "album" => NavidromeItem::Album {
+                        name: row.album_name?,
+                        artist: row.album_artist.unwrap_or_default(),
+                    },
+                    "artist" => NavidromeItem::Artist {
+                        name: row.artist_name?,
+                    },
+                    _ => return None,
+                };
+                let starred_at = match row.starred {
+                    0 => None,
+                    // 旧版本星标没有记录时间
+                    _ => Some(
+                        row.starred_at
+                            .as_deref()
+                            .and_then(parse_time)
+                            .unwrap_or_else(|| chrono::Utc::now().naive_utc()),
+                    ),
+                };
+                Some(NavidromeAnnotation {
+                    user_id: row.user_id,
+                    item,
+                    play_count: row.play_count.clamp(0, i32::MAX as i64) as i32,
+                    played_at: row.play_date.as_deref().and_then(parse_time),
+                    rating: row.rating.clamp(0, 5) as i32,
+                    starred_at,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Navidrome 以 Go 的 `time.Time` 字符串保存时间，如 `2024-01-02 03:04:05.123456789+00:00`
+fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.naive_utc());
+    }
+    // `time.Time.String()` 在偏移后附带时区缩写，如 `+0000 UTC`
+    let value = match value.rsplit_once(' ') {
+        Some((rest, zone)) if zone.chars().all(|c| c.is_ascii_alphabetic()) => rest,
+        _ => value,
+    };
+    for format in ["%Y-%m-%d %H:%M:%S%.f%:z", "%Y-%m-%d %H:%M:%S%.f %z"] {
+        if let Ok(time) = DateTime::parse_from_str(value, format) {
+            return Some(time.naive_utc());
+        }
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn time(h: u32, m: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_opt(h, m, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_navidrome_times() {
+        assert_eq!(
+            parse_time("2024-01-02 03:04:05.123456789+00:00"),
+            Some(time(3, 4, 5) + chrono::Duration::nanoseconds(123_456_789))
+        );
+        assert_eq!(parse_time("2024-01-02T03:04:05Z"), Some(time(3, 4, 5)));
+        assert_eq!(parse_time("2024-01-02 11:04:05+08:00"), Some(time(3, 4, 5)));
+        assert_eq!(parse_time("2024-01-02 03:04:05"), Some(time(3, 4, 5)));
+        assert_eq!(
+            parse_time("2024-01-02 03:04:05.5 +0000 UTC"),
+            Some(time(3, 4, 5) + chrono::Duration::milliseconds(500))
+        );
+        assert_eq!(parse_time(""), None);
+        assert_eq!(parse_time("not a time"), None);
+    }
+}
@@ -21,7 +21,11 @@ pub mod auth;
 pub mod normalize;
 
 pub mod cover_art;
-pub use cover_art::{CoverArtCacheImpl, CoverArtReaderImpl};
+pub use cover_art::{CoverArtCacheImpl, CoverArtReaderImpl, FsPlaylistCoverStore, FsThumbnailStore};
 
 pub mod crypto;
 pub use crypto::Aes256GcmEncryptor;
+
+pub mod import;
+
+pub mod musicbrainz;
@@ -1,29 +1,37 @@
+use super::chapters::read_chapters;
+use super::container_tags::read_container_tag;
+use super::rule_config::ReloadableRuleEngine;
 use super::rule_engine::{MetadataRuleEngine, RuleContext};
 use application::command::media_parse::AudioMetadataReader;
 use application::error::AppError;
-use domain::value::AudioMetadata;
-use id3::Tag;
+use domain::value::{AudioMetadata, ParticipantMeta, ParticipantRole, ReplayGain};
+use id3::{Tag, TagLike};
 use std::path::PathBuf;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AudioMetadataReaderImpl {
-    rule_engine: Arc<MetadataRuleEngine>,
+    rule_engine: Arc<ReloadableRuleEngine>,
 }
 
 impl AudioMetadataReaderImpl {
     pub fn new() -> Self {
         Self {
-            rule_engine: Arc::new(MetadataRuleEngine::with_default_rules()),
+            rule_engine: Arc::new(ReloadableRuleEngine::default()),
         }
     }
 
     /// 使用自定义规则引擎创建
     pub fn with_rule_engine(rule_engine: MetadataRuleEngine) -> Self {
         Self {
-            rule_engine: Arc::new(rule_engine),
+            rule_engine: Arc::new(ReloadableRuleEngine::new(rule_engine)),
         }
     }
+
+    /// 使用可热加载的规则引擎创建
+    pub fn with_reloadable_rule_engine(rule_engine: Arc<ReloadableRuleEngine>) -> Self {
+        Self { rule_engine }
+    }
 }
 
 #[async_trait::async_trait]
@@ -49,7 +57,14 @@ impl AudioMetadataReader for AudioMetadataReaderImpl {
         let title = tag.title().unwrap_or_default();
         let artist_raw = tag.artist().unwrap_or_default();
         let album = tag.album().unwrap_or_default();
-        let genre = tag.genre().unwrap_or_default();
+        // ID3v2.4 的多值流派（TCON 中以 \0 分隔）完整保留，交给流派分割规则
+        let genre = id3_tag
+            .as_ref()
+            .and_then(|tag| tag.get("TCON"))
+            .and_then(|frame| frame.content().text())
+            .filter(|genre| genre.contains('\0'))
+            .map(|genre| genre.to_string())
+            .unwrap_or_else(|| tag.genre().unwrap_or_default());
 
         let year = tag.year();
         let track_num = tag.track();
@@ -57,6 +72,16 @@ impl AudioMetadataReader for AudioMetadataReaderImpl {
             .as_ref()
             .and_then(|tag| tag.lyrics().next().map(|l| l.text.clone()));
 
+        // MusicBrainz 多值艺术家标签（TXXX:ARTISTS，多个值以 \0 分隔）
+        let artists_tag: Vec<String> = id3_tag
+            .as_ref()
+            .and_then(|tag| {
+                tag.extended_texts()
+                    .find(|t| t.description.eq_ignore_ascii_case("ARTISTS"))
+                    .map(|t| t.value.split('\0').map(|v| v.to_string()).collect())
+            })
+            .unwrap_or_default();
+
         // 使用规则引擎处理元数据
         let mut ctx = RuleContext::new(
             title,
@@ -65,17 +90,113 @@ impl AudioMetadataReader for AudioMetadataReaderImpl {
             genre,
             year.and_then(|y| if y > 0 { Some(y as i32) } else { None }),
             track_num.and_then(|n| if n > 0 { Some(n as i32) } else { None }),
-        );
+        )
+        .with_raw_artists(artists_tag);
+
+        // 乐章序号交给规则引擎，用于可选的乐章标题前缀
+        let work = id3_tag.as_ref().map(work_tags).unwrap_or_default();
+        if let Some(number) = work.movement_number {
+            ctx.extra
+                .insert("movement_number".to_string(), number.to_string());
+        }
+
+        self.rule_engine.engine().execute(&mut ctx);
+
+        // 碟片信息：优先使用标签（TPOS/TSST，FLAC、Ogg 和 MP4 为 DISCSUBTITLE），
+        // 其次使用规则引擎从专辑名中提取的结果
+        let disc_number = id3_tag
+            .as_ref()
+            .and_then(|tag| tag.disc())
+            .map(|n| n as i32)
+            .filter(|n| *n > 0)
+            .or_else(|| ctx.extra.get("disc_number").and_then(|n| n.parse().ok()));
+        let disc_subtitle = id3_tag
+            .as_ref()
+            .and_then(|tag| tag.get("TSST"))
+            .and_then(|frame| frame.content().text())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .or_else(|| read_container_tag(path.as_path(), "DISCSUBTITLE"))
+            .or_else(|| ctx.extra.get("disc_subtitle").cloned());
+
+        let bpm = id3_tag
+            .as_ref()
+            .and_then(|tag| tag.get("TBPM"))
+            .and_then(|frame| frame.content().text())
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .map(|bpm| bpm.round() as i32)
+            .filter(|bpm| *bpm > 0);
+        let comment = id3_tag
+            .as_ref()
+            .and_then(|tag| tag.comments().next().map(|c| c.text.clone()))
+            .or_else(|| tag.comment())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let txxx = |names: &[&str]| -> Option<String> {
+            id3_tag.as_ref().and_then(|tag| {
+                tag.extended_texts()
+                    .find(|t| names.iter().any(|n| t.description.eq_ignore_ascii_case(n)))
+                    .map(|t| t.value.trim().to_string())
+                    .filter(|v| !v.is_empty())
+            })
+        };
+        let mbz_track_id = txxx(&["MusicBrainz Release Track Id", "MusicBrainz Track Id"]);
+        // 发行日期：TDRL/TDRC；原始发行日期：TDOR，ID3v2.3 为 TORY 或 Picard 写入的 TXXX
+        let release_date = id3_tag
+            .as_ref()
+            .and_then(|tag| tag.date_released().or_else(|| tag.date_recorded()))
+            .map(|ts| format_timestamp(&ts));
+        let original_date = id3_tag
+            .as_ref()
+            .and_then(|tag| tag.original_date_released())
+            .map(|ts| format_timestamp(&ts))
+            .or_else(|| {
+                id3_tag
+                    .as_ref()
+                    .and_then(|tag| tag.get("TORY"))
+                    .and_then(|frame| frame.content().text())
+                    .map(|s| s.to_string())
+            })
+            .or_else(|| txxx(&["ORIGINALDATE", "ORIGINALYEAR"]))
+            .and_then(|date| parse_date(&date));
+        let original_year = original_date.as_deref().and_then(date_year);
+        let replay_gain = ReplayGain {
+            track_gain: txxx(&["REPLAYGAIN_TRACK_GAIN"]).and_then(|v| parse_replay_gain(&v)),
+            track_peak: txxx(&["REPLAYGAIN_TRACK_PEAK"]).and_then(|v| parse_replay_gain(&v)),
+            album_gain: txxx(&["REPLAYGAIN_ALBUM_GAIN"]).and_then(|v| parse_replay_gain(&v)),
+            album_peak: txxx(&["REPLAYGAIN_ALBUM_PEAK"]).and_then(|v| parse_replay_gain(&v)),
+        };
+        let explicit = txxx(&["ITUNESADVISORY", "EXPLICIT"]).is_some_and(|v| is_explicit(&v));
 
-        self.rule_engine.execute(&mut ctx);
+        // 作曲、指挥等角色排在艺术家之后，歌曲的主艺术家仍取第一个艺术家
+        let mut participants = ctx.artists;
+        if let Some(tag) = id3_tag.as_ref() {
+            for participant in role_participants(tag) {
+                if !participants.contains(&participant) {
+                    participants.push(participant);
+                }
+            }
+        }
+
+        let chapters = read_chapters(
+            path.as_path(),
+            id3_tag.as_ref(),
+            properties.length() as i64 * 1000,
+        );
 
         Ok(AudioMetadata {
             title: ctx.title,
-            participants: ctx.artists,
+            participants,
             album: ctx.album,
+            album_version: ctx.extra.get("album_version").cloned(),
             genres: ctx.genres,
             track_number: ctx.track_number,
+            disc_number,
+            disc_subtitle,
             year: ctx.year,
+            release_date,
+            original_year,
+            original_date,
             duration: properties.length() as i64,
             bit_rate: properties.bitrate() as i32,
             sample_rate: properties.samplerate() as i32,
@@ -84,6 +205,16 @@ impl AudioMetadataReader for AudioMetadataReaderImpl {
                 .as_ref()
                 .and_then(|tag| tag.pictures().next().map(|p| p.data.clone())),
             lyrics,
+            bpm,
+            comment,
+            mbz_track_id,
+            replay_gain,
+            explicit,
+            work: work.work,
+            movement_name: work.movement_name,
+            movement_number: work.movement_number,
+            movement_count: work.movement_count,
+            chapters,
         })
     }
     async fn get_picture(&self, path: PathBuf) -> Result<Vec<u8>, AppError> {
@@ -95,9 +226,274 @@ impl AudioMetadataReader for AudioMetadataReaderImpl {
     }
 }
 
+/// 角色标签：ID3 文本帧、TXXX 和 TIPL（角色\0名字 成对出现）
+fn role_participants(tag: &Tag) -> Vec<ParticipantMeta> {
+    const FRAMES: [(&str, ParticipantRole); 4] = [
+        ("TCOM", ParticipantRole::Composer),
+        ("TEXT", ParticipantRole::Lyricist),
+        ("TPE3", ParticipantRole::Conductor),
+        ("TPE4", ParticipantRole::Remixer),
+    ];
+    const TXXX: [(&str, ParticipantRole); 2] = [
+        ("ARRANGER", ParticipantRole::Arranger),
+        ("PRODUCER", ParticipantRole::Producer),
+    ];
+
+    let mut names: Vec<(ParticipantRole, String)> = Vec::new();
+    for (id, role) in FRAMES {
+        if let Some(text) = tag.get(id).and_then(|frame| frame.content().text()) {
+            names.extend(split_names(text).into_iter().map(|n| (role.clone(), n)));
+        }
+    }
+    for text in tag.extended_texts() {
+        if let Some((_, role)) = TXXX
+            .iter()
+            .find(|(name, _)| text.description.eq_ignore_ascii_case(name))
+        {
+            names.extend(
+                split_names(&text.value)
+                    .into_iter()
+                    .map(|n| (role.clone(), n)),
+            );
+        }
+    }
+    if let Some(text) = tag.get("TIPL").and_then(|frame| frame.content().text()) {
+        let values: Vec<&str> = text.split('\0').collect();
+        for pair in values.chunks_exact(2) {
+            let role = match pair[0].trim().to_lowercase().as_str() {
+                "arranger" => ParticipantRole::Arranger,
+                "producer" => ParticipantRole::Producer,
+                _ => continue,
+            };
+            names.extend(split_names(pair[1]).into_iter().map(|n| (role.clone(), n)));
+        }
+    }
+
+    let mut participants: Vec<ParticipantMeta> = Vec::new();
+    for (role, name) in names {
+        let participant = ParticipantMeta {
+            role,
+            sub_role: None,
+            name,
+        };
+        if !participants.contains(&participant) {
+            participants.push(participant);
+        }
+    }
+    participants
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct WorkTags {
+    work: Option<String>,
+    movement_name: Option<String>,
+    movement_number: Option<i32>,
+    movement_count: Option<i32>,
+}
+
+/// 作品与乐章：iTunes 写入 MVNM/MVIN，Picard 另写 TXXX:WORK 等；
+/// TIT1 通常是分组，只有存在乐章信息时才作为作品名
+fn work_tags(tag: &Tag) -> WorkTags {
+    let text = |id: &str| {
+        tag.get(id)
+            .and_then(|frame| frame.content().text())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let txxx = |name: &str| {
+        tag.extended_texts()
+            .find(|t| t.description.eq_ignore_ascii_case(name))
+            .map(|t| t.value.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let (number, count) = text("MVIN")
+        .or_else(|| txxx("MOVEMENT"))
+        .map(|v| parse_position(&v))
+        .unwrap_or_default();
+    let movement_count = count.or_else(|| txxx("MOVEMENTTOTAL").and_then(|v| v.parse().ok()));
+    let movement_name = text("MVNM").or_else(|| txxx("MOVEMENTNAME"));
+    let has_movement = number.is_some() || movement_name.is_some();
+    WorkTags {
+        work: txxx("WORK").or_else(|| text("TIT1").filter(|_| has_movement)),
+        movement_name,
+        movement_number: number,
+        movement_count: movement_count.filter(|n| *n > 0),
+    }
+}
+
+/// 解析 "2/4" 或 "2" 形式的序号
+fn parse_position(value: &str) -> (Option<i32>, Option<i32>) {
+    let mut parts = value.splitn(2, '/');
+    let number = parts
+        .next()
+        .and_then(|n| n.trim().parse::<i32>().ok())
+        .filter(|n| *n > 0);
+    let total = parts
+        .next()
+        .and_then(|n| n.trim().parse::<i32>().ok())
+        .filter(|n| *n > 0);
+    (number, total)
+}
+
+/// 多值标签以 \0 或 ; 分隔；不按 / 拆分，避免拆开 "AC/DC" 这样的名字
+fn split_names(value: &str) -> Vec<String> {
+    value
+        .split(['\0', ';'])
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn format_timestamp(ts: &id3::Timestamp) -> String {
+    match (ts.month, ts.day) {
+        (Some(month), Some(day)) => format!("{:04}-{:02}-{:02}", ts.year, month, day),
+        (Some(month), None) => format!("{:04}-{:02}", ts.year, month),
+        _ => format!("{:04}", ts.year),
+    }
+}
+
+/// 把 "1969"、"1969-09"、"1969-09-26T..." 规范为 YYYY[-MM[-DD]]，无法识别时返回 None
+fn parse_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    let mut parts = value.get(..10).unwrap_or(value).split('-');
+    let year = parts.next().filter(|y| y.len() == 4)?.parse::<i32>().ok()?;
+    let month = parts.next().and_then(|m| m.parse::<u32>().ok());
+    let day = parts.next().and_then(|d| d.parse::<u32>().ok());
+    Some(match (month, day) {
+        (Some(month @ 1..=12), Some(day @ 1..=31)) => {
+            format!("{:04}-{:02}-{:02}", year, month, day)
+        }
+        (Some(month @ 1..=12), _) => format!("{:04}-{:02}", year, month),
+        _ => format!("{:04}", year),
+    })
+}
+
+fn date_year(date: &str) -> Option<i32> {
+    date.get(..4)?.parse().ok().filter(|year| *year > 0)
+}
+
+/// 解析 "-6.54 dB" / "0.988553" 形式的 ReplayGain 值
+fn parse_replay_gain(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .unwrap_or(value);
+    number.trim().parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// iTunes 内容分级：0 无分级，1 或 4 为 explicit，2 为 clean
+fn is_explicit(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "1" | "4" | "explicit" | "e" | "true" | "yes"
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn parses_replay_gain_values() {
+        assert_eq!(parse_replay_gain("-6.54 dB"), Some(-6.54));
+        assert_eq!(parse_replay_gain("+1.20dB"), Some(1.2));
+        assert_eq!(parse_replay_gain("0.988553"), Some(0.988553));
+        assert_eq!(parse_replay_gain("n/a"), None);
+    }
+
+    #[test]
+    fn parses_content_advisory() {
+        assert!(is_explicit("1"));
+        assert!(is_explicit(" 4 "));
+        assert!(is_explicit("Explicit"));
+        assert!(!is_explicit("2"));
+        assert!(!is_explicit("0"));
+        assert!(!is_explicit("clean"));
+    }
+
+    #[test]
+    fn normalizes_release_dates() {
+        assert_eq!(parse_date("1969"), Some("1969".to_string()));
+        assert_eq!(
+            parse_date("1969-09-26T00:00:00"),
+            Some("1969-09-26".to_string())
+        );
+        assert_eq!(parse_date("1969-9"), Some("1969-09".to_string()));
+        assert_eq!(parse_date("1969-13-01"), Some("1969".to_string()));
+        assert_eq!(parse_date("69"), None);
+        assert_eq!(date_year("2019-06"), Some(2019));
+    }
+
+    #[test]
+    fn extracts_role_participants() {
+        let mut tag = Tag::new();
+        tag.set_text("TCOM", "Johann Sebastian Bach");
+        tag.set_text("TPE3", "Karl Richter; Helmut Winschermann");
+        tag.set_text("TIPL", "producer\0Hans Ritter\0engineer\0Heinz Wildhagen");
+        tag.add_frame(id3::frame::ExtendedText {
+            description: "Arranger".to_string(),
+            value: "AC/DC".to_string(),
+        });
+
+        let participants = role_participants(&tag);
+        let names: Vec<(ParticipantRole, &str)> = participants
+            .iter()
+            .map(|p| (p.role.clone(), p.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (ParticipantRole::Composer, "Johann Sebastian Bach"),
+                (ParticipantRole::Conductor, "Karl Richter"),
+                (ParticipantRole::Conductor, "Helmut Winschermann"),
+                (ParticipantRole::Arranger, "AC/DC"),
+                (ParticipantRole::Producer, "Hans Ritter"),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_work_and_movement() {
+        let mut tag = Tag::new();
+        tag.set_text("TIT1", "Symphony No. 5 in C minor, Op. 67");
+        tag.set_text("MVNM", "Andante con moto");
+        tag.set_text("MVIN", "2/4");
+        assert_eq!(
+            work_tags(&tag),
+            WorkTags {
+                work: Some("Symphony No. 5 in C minor, Op. 67".to_string()),
+                movement_name: Some("Andante con moto".to_string()),
+                movement_number: Some(2),
+                movement_count: Some(4),
+            }
+        );
+
+        // 没有乐章信息时 TIT1 只是分组
+        let mut tag = Tag::new();
+        tag.set_text("TIT1", "Workout");
+        assert_eq!(work_tags(&tag), WorkTags::default());
+
+        let mut tag = Tag::new();
+        for (description, value) in [
+            ("WORK", "Goldberg Variations"),
+            ("MOVEMENT", "3"),
+            ("MOVEMENTTOTAL", "32"),
+        ] {
+            tag.add_frame(id3::frame::ExtendedText {
+                description: description.to_string(),
+                value: value.to_string(),
+            });
+        }
+        let work = work_tags(&tag);
+        assert_eq!(work.work.as_deref(), Some("Goldberg Variations"));
+        assert_eq!(
+            (work.movement_number, work.movement_count),
+            (Some(3), Some(32))
+        );
+    }
     use std::time::Instant;
     use walkdir::WalkDir;
 
@@ -0,0 +1,753 @@
+//! 章节标记：MP3 的 ID3 CHAP 帧、M4B/M4A 的章节轨道（或 Nero chpl）、MKA 的 Chapters 元素
+
+use domain::value::Chapter;
+use id3::Tag;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 读取文件中的章节，没有章节或格式不支持时返回空列表
+///
+/// duration 为文件时长（毫秒），用于补齐最后一章的结束位置
+pub fn read_chapters(path: &Path, id3_tag: Option<&Tag>, duration: i64) -> Vec<Chapter> {
+    let mut chapters = id3_tag.map(id3_chapters).unwrap_or_default();
+    if chapters.is_empty() {
+        chapters = read_container_chapters(path).unwrap_or_else(|e| {
+            log::warn!("Failed to read chapters from {:?}: {}", path, e);
+            Vec::new()
+        });
+    }
+    normalize(chapters, duration)
+}
+
+/// 按文件头判断容器格式，临时文件没有扩展名
+fn read_container_chapters(path: &Path) -> io::Result<Vec<Chapter>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 8];
+    if reader.read(&mut magic)? < magic.len() {
+        return Ok(Vec::new());
+    }
+    reader.seek(SeekFrom::Start(0))?;
+    if &magic[4..8] == b"ftyp" {
+        mp4_chapters(&mut reader)
+    } else if magic[..4] == [0x1A, 0x45, 0xDF, 0xA3] {
+        matroska_chapters(&mut reader)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// 按起始位置排序，补齐缺失的结束位置和标题
+fn normalize(mut chapters: Vec<Chapter>, duration: i64) -> Vec<Chapter> {
+    chapters.retain(|c| c.start >= 0 && (duration <= 0 || c.start < duration));
+    chapters.sort_by_key(|c| c.start);
+    chapters.dedup_by_key(|c| c.start);
+    let starts: Vec<i64> = chapters.iter().map(|c| c.start).collect();
+    for (i, chapter) in chapters.iter_mut().enumerate() {
+        let next = starts
+            .get(i + 1)
+            .copied()
+            .or(Some(duration).filter(|d| *d > 0));
+        match next {
+            Some(next) if chapter.end <= chapter.start || chapter.end > next => chapter.end = next,
+            None if chapter.end < chapter.start => chapter.end = chapter.start,
+            _ => {}
+        }
+        chapter.title = chapter.title.trim().to_string();
+        if chapter.title.is_empty() {
+            chapter.title = format!("Chapter {}", i + 1);
+        }
+    }
+    chapters
+}
+
+fn id3_chapters(tag: &Tag) -> Vec<Chapter> {
+    tag.chapters()
+        .map(|chap| Chapter {
+            title: chap
+                .frames
+                .iter()
+                .find(|frame| frame.id() == "TIT2")
+                .and_then(|frame| frame.content().text())
+                .unwrap_or_default()
+                .to_string(),
+            start: chap.start_time as i64,
+            end: chap.end_time as i64,
+        })
+        .collect()
+}
+
+// ============================================================================
+// MP4
+// ============================================================================
+
+pub(super) struct Mp4Box {
+    pub(super) kind: [u8; 4],
+    /// 内容起始位置（不含头部）
+    pub(super) start: u64,
+    pub(super) end: u64,
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_be_bytes(buf))
+}
+
+/// 列出 [start, end) 范围内的子 box
+pub(super) fn mp4_children<R: Read + Seek>(
+    r: &mut R,
+    start: u64,
+    end: u64,
+) -> io::Result<Vec<Mp4Box>> {
+    let mut boxes = Vec::new();
+    let mut pos = start;
+    while pos + 8 <= end {
+        r.seek(SeekFrom::Start(pos))?;
+        let size = read_u32(r)? as u64;
+        let mut kind = [0u8; 4];
+        r.read_exact(&mut kind)?;
+        let (size, header) = match size {
+            0 => (end - pos, 8),
+            1 if pos + 16 <= end => (read_u64(r)?, 16),
+            // 64 位大小的头部超出了父 box，文件被截断
+            1 => break,
+            size => (size, 8),
+        };
+        if size < header {
+            break;
+        }
+        let box_end = pos.saturating_add(size).min(end);
+        boxes.push(Mp4Box {
+            kind,
+            start: pos + header,
+            end: box_end,
+        });
+        pos = box_end;
+    }
+    Ok(boxes)
+}
+
+fn mp4_child<R: Read + Seek>(
+    r: &mut R,
+    parent: &Mp4Box,
+    kind: &[u8; 4],
+) -> io::Result<Option<Mp4Box>> {
+    Ok(mp4_children(r, parent.start, parent.end)?
+        .into_iter()
+        .find(|b| &b.kind == kind))
+}
+
+/// 按路径查找子 box，如 [b"mdia", b"minf", b"stbl"]
+pub(super) fn mp4_find<R: Read + Seek>(
+    r: &mut R,
+    parent: &Mp4Box,
+    path: &[&[u8; 4]],
+) -> io::Result<Option<Mp4Box>> {
+    let mut current = None;
+    for kind in path {
+        let next = mp4_child(r, current.as_ref().unwrap_or(parent), kind)?;
+        match next {
+            Some(b) => current = Some(b),
+            None => return Ok(None),
+        }
+    }
+    Ok(current)
+}
+
+pub(super) fn mp4_content<R: Read + Seek>(r: &mut R, b: &Mp4Box) -> io::Result<Vec<u8>> {
+    // 章节相关的 box 都很小，限制大小避免异常文件占用内存
+    let len = b.end.saturating_sub(b.start).min(16 * 1024 * 1024) as usize;
+    let mut buf = vec![0u8; len];
+    r.seek(SeekFrom::Start(b.start))?;
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn be_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn be_u64(buf: &[u8], offset: usize) -> Option<u64> {
+    buf.get(offset..offset + 8)
+        .map(|b| u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
+
+/// 优先使用 QuickTime 章节轨道（iTunes、Audible），其次是 Nero 的 chpl
+fn mp4_chapters<R: Read + Seek>(r: &mut R) -> io::Result<Vec<Chapter>> {
+    let file_end = r.seek(SeekFrom::End(0))?;
+    let Some(moov) = mp4_children(r, 0, file_end)?
+        .into_iter()
+        .find(|b| &b.kind == b"moov")
+    else {
+        return Ok(Vec::new());
+    };
+    let chapters = mp4_chapter_track(r, &moov)?;
+    if !chapters.is_empty() {
+        return Ok(chapters);
+    }
+    match mp4_find(r, &moov, &[b"udta", b"chpl"])? {
+        Some(chpl) => Ok(parse_chpl(&mp4_content(r, &chpl)?)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// chpl：起始时间以 100 纳秒为单位，后跟一字节长度的标题
+fn parse_chpl(buf: &[u8]) -> Vec<Chapter> {
+    let mut offset = if buf.first().copied().unwrap_or(0) > 0 {
+        8
+    } else {
+        4
+    };
+    let Some(&count) = buf.get(offset) else {
+        return Vec::new();
+    };
+    offset += 1;
+    let mut chapters = Vec::new();
+    for _ in 0..count {
+        let (Some(start), Some(&len)) = (be_u64(buf, offset), buf.get(offset + 8)) else {
+            break;
+        };
+        let title_start = offset + 9;
+        let Some(title) = buf.get(title_start..title_start + len as usize) else {
+            break;
+        };
+        chapters.push(Chapter {
+            title: String::from_utf8_lossy(title).to_string(),
+            start: (start / 10_000) as i64,
+            end: 0,
+        });
+        offset = title_start + len as usize;
+    }
+    chapters
+}
+
+/// 音频轨道通过 tref/chap 引用的文本轨道，每个 sample 是一个章节标题
+fn mp4_chapter_track<R: Read + Seek>(r: &mut R, moov: &Mp4Box) -> io::Result<Vec<Chapter>> {
+    let traks: Vec<Mp4Box> = mp4_children(r, moov.start, moov.end)?
+        .into_iter()
+        .filter(|b| &b.kind == b"trak")
+        .collect();
+    let mut chapter_ids = Vec::new();
+    for trak in &traks {
+        if let Some(chap) = mp4_find(r, trak, &[b"tref", b"chap"])? {
+            let buf = mp4_content(r, &chap)?;
+            chapter_ids.extend((0..buf.len() / 4).filter_map(|i| be_u32(&buf, i * 4)));
+        }
+    }
+    if chapter_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    for trak in &traks {
+        let Some(tkhd) = mp4_child(r, trak, b"tkhd")? else {
+            continue;
+        };
+        let tkhd = mp4_content(r, &tkhd)?;
+        let id_offset = if tkhd.first().copied() == Some(1) {
+            20
+        } else {
+            12
+        };
+        match be_u32(&tkhd, id_offset) {
+            Some(id) if chapter_ids.contains(&id) => return mp4_text_samples(r, trak),
+            _ => {}
+        }
+    }
+    Ok(Vec::new())
+}
+
+fn mp4_text_samples<R: Read + Seek>(r: &mut R, trak: &Mp4Box) -> io::Result<Vec<Chapter>> {
+    let Some(mdhd) = mp4_find(r, trak, &[b"mdia", b"mdhd"])? else {
+        return Ok(Vec::new());
+    };
+    let mdhd = mp4_content(r, &mdhd)?;
+    let timescale_offset = if mdhd.first().copied() == Some(1) {
+        20
+    } else {
+        12
+    };
+    let timescale = be_u32(&mdhd, timescale_offset).unwrap_or(0) as u64;
+    let Some(stbl) = mp4_find(r, trak, &[b"mdia", b"minf", b"stbl"])? else {
+        return Ok(Vec::new());
+    };
+    if timescale == 0 {
+        return Ok(Vec::new());
+    }
+    let mut tables = std::collections::HashMap::new();
+    for b in mp4_children(r, stbl.start, stbl.end)? {
+        if matches!(&b.kind, b"stts" | b"stsz" | b"stsc" | b"stco" | b"co64") {
+            tables.insert(b.kind, mp4_content(r, &b)?);
+        }
+    }
+    let empty = Vec::new();
+    let table = |kind: &[u8; 4]| tables.get(kind).unwrap_or(&empty);
+
+    // 每个 sample 的起始时间
+    let stts = table(b"stts");
+    let mut starts = Vec::new();
+    let mut time = 0u64;
+    for i in 0..be_u32(stts, 4).unwrap_or(0) as usize {
+        let (Some(count), Some(delta)) = (be_u32(stts, 8 + i * 8), be_u32(stts, 12 + i * 8)) else {
+            break;
+        };
+        for _ in 0..count.min(10_000) {
+            starts.push(time);
+            time += delta as u64;
+        }
+    }
+
+    // 每个 sample 的大小
+    let stsz = table(b"stsz");
+    let fixed_size = be_u32(stsz, 4).unwrap_or(0);
+    let sample_count = be_u32(stsz, 8).unwrap_or(0) as usize;
+    let sizes: Vec<u32> = (0..sample_count.min(starts.len()))
+        .map(|i| {
+            if fixed_size > 0 {
+                fixed_size
+            } else {
+                be_u32(stsz, 12 + i * 4).unwrap_or(0)
+            }
+        })
+        .collect();
+
+    // chunk 偏移量与每个 chunk 的 sample 数
+    let chunk_offsets: Vec<u64> = if let Some(co64) = tables.get(b"co64") {
+        (0..be_u32(co64, 4).unwrap_or(0) as usize)
+            .map_while(|i| be_u64(co64, 8 + i * 8))
+            .collect()
+    } else {
+        let stco = table(b"stco");
+        (0..be_u32(stco, 4).unwrap_or(0) as usize)
+            .map_while(|i| be_u32(stco, 8 + i * 4).map(|o| o as u64))
+            .collect()
+    };
+    let stsc = table(b"stsc");
+    let stsc_entries: Vec<(u32, u32)> = (0..be_u32(stsc, 4).unwrap_or(0) as usize)
+        .map_while(|i| Some((be_u32(stsc, 8 + i * 12)?, be_u32(stsc, 12 + i * 12)?)))
+        .collect();
+
+    let mut chapters = Vec::new();
+    let mut sample = 0usize;
+    for (chunk_index, chunk_offset) in chunk_offsets.iter().enumerate() {
+        let per_chunk = stsc_entries
+            .iter()
+            .rev()
+            .find(|(first, _)| *first as usize <= chunk_index + 1)
+            .map(|(_, n)| *n)
+            .unwrap_or(1);
+        let mut offset = *chunk_offset;
+        for _ in 0..per_chunk {
+            let Some(&size) = sizes.get(sample) else {
+                break;
+            };
+            let title = read_text_sample(r, offset, size)?;
+            chapters.push(Chapter {
+                title,
+                start: (starts[sample] * 1000 / timescale) as i64,
+                end: 0,
+            });
+            offset += size as u64;
+            sample += 1;
+        }
+    }
+    Ok(chapters)
+}
+
+/// 文本 sample：两字节长度后跟 UTF-8 或带 BOM 的 UTF-16 文本
+fn read_text_sample<R: Read + Seek>(r: &mut R, offset: u64, size: u32) -> io::Result<String> {
+    if size < 2 {
+        return Ok(String::new());
+    }
+    let mut buf = vec![0u8; size.min(64 * 1024) as usize];
+    r.seek(SeekFrom::Start(offset))?;
+    r.read_exact(&mut buf)?;
+    let len = (u16::from_be_bytes([buf[0], buf[1]]) as usize).min(buf.len() - 2);
+    let text = &buf[2..2 + len];
+    if text.starts_with(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = text[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        return Ok(String::from_utf16_lossy(&units));
+    }
+    Ok(String::from_utf8_lossy(text).to_string())
+}
+
+// ============================================================================
+// Matroska
+// ============================================================================
+
+const EBML_SEGMENT: u64 = 0x18538067;
+const EBML_SEEK_HEAD: u64 = 0x114D9B74;
+const EBML_SEEK: u64 = 0x4DBB;
+const EBML_SEEK_ID: u64 = 0x53AB;
+const EBML_SEEK_POSITION: u64 = 0x53AC;
+const EBML_CLUSTER: u64 = 0x1F43B675;
+const EBML_CHAPTERS: u64 = 0x1043A770;
+const EBML_EDITION_ENTRY: u64 = 0x45B9;
+const EBML_CHAPTER_ATOM: u64 = 0xB6;
+const EBML_CHAPTER_TIME_START: u64 = 0x91;
+const EBML_CHAPTER_TIME_END: u64 = 0x92;
+const EBML_CHAPTER_FLAG_HIDDEN: u64 = 0x98;
+const EBML_CHAPTER_DISPLAY: u64 = 0x80;
+const EBML_CHAP_STRING: u64 = 0x85;
+
+/// 长度未知时的 size
+const EBML_UNKNOWN_SIZE: u64 = u64::MAX;
+
+/// 读取变长整数，返回 (值, 字节数)；keep_marker 为 true 时保留长度标记（元素 ID）
+fn read_vint<R: Read>(r: &mut R, keep_marker: bool) -> io::Result<(u64, usize)> {
+    let mut first = [0u8; 1];
+    r.read_exact(&mut first)?;
+    let len = first[0].leading_zeros() as usize + 1;
+    if len > 8 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid EBML vint",
+        ));
+    }
+    let mut value = if keep_marker {
+        first[0] as u64
+    } else {
+        (first[0] as u64) & (0xFF >> len)
+    };
+    let mut all_ones = value == (0xFF >> len);
+    for _ in 1..len {
+        let mut b = [0u8; 1];
+        r.read_exact(&mut b)?;
+        value = (value << 8) | b[0] as u64;
+        all_ones &= b[0] == 0xFF;
+    }
+    if !keep_marker && all_ones {
+        return Ok((EBML_UNKNOWN_SIZE, len));
+    }
+    Ok((value, len))
+}
+
+/// 元素头，返回 (ID, 内容大小, 头部字节数)
+fn read_element_header<R: Read>(r: &mut R) -> io::Result<(u64, u64, usize)> {
+    let (id, id_len) = read_vint(r, true)?;
+    let (size, size_len) = read_vint(r, false)?;
+    Ok((id, size, id_len + size_len))
+}
+
+/// 内存中的 EBML 元素列表 (ID, 内容)
+fn ebml_children(buf: &[u8]) -> Vec<(u64, &[u8])> {
+    let mut children = Vec::new();
+    let mut cursor = io::Cursor::new(buf);
+    while (cursor.position() as usize) < buf.len() {
+        let Ok((id, size, _)) = read_element_header(&mut cursor) else {
+            break;
+        };
+        let start = cursor.position() as usize;
+        let end = start
+            .saturating_add(size.min(buf.len() as u64) as usize)
+            .min(buf.len());
+        children.push((id, &buf[start..end]));
+        cursor.set_position(end as u64);
+    }
+    children
+}
+
+fn ebml_uint(buf: &[u8]) -> u64 {
+    buf.iter().take(8).fold(0, |acc, b| (acc << 8) | *b as u64)
+}
+
+fn matroska_chapters<R: Read + Seek>(r: &mut R) -> io::Result<Vec<Chapter>> {
+    let file_end = r.seek(SeekFrom::End(0))?;
+    r.seek(SeekFrom::Start(0))?;
+    // 跳过 EBML 头，找到 Segment
+    let (_, header_size, _) = read_element_header(r)?;
+    r.seek(SeekFrom::Current(header_size as i64))?;
+    let (id, segment_size, _) = read_element_header(r)?;
+    if id != EBML_SEGMENT {
+        return Ok(Vec::new());
+    }
+    let segment_start = r.stream_position()?;
+    let segment_end = if segment_size == EBML_UNKNOWN_SIZE {
+        file_end
+    } else {
+        (segment_start + segment_size).min(file_end)
+    };
+
+    let mut chapters_position = None;
+    let mut pos = segment_start;
+    while pos < segment_end {
+        r.seek(SeekFrom::Start(pos))?;
+        let Ok((id, size, header)) = read_element_header(r) else {
+            break;
+        };
+        let content_start = pos + header as u64;
+        match id {
+            EBML_CHAPTERS => return read_chapters_element(r, content_start, size),
+            EBML_SEEK_HEAD => {
+                let buf = read_element_content(r, content_start, size)?;
+                chapters_position = chapters_position.or_else(|| {
+                    ebml_children(&buf)
+                        .into_iter()
+                        .filter(|(id, _)| *id == EBML_SEEK)
+                        .find_map(|(_, seek)| {
+                            let entries = ebml_children(seek);
+                            let target = entries.iter().find(|(id, _)| *id == EBML_SEEK_ID)?;
+                            let position =
+                                entries.iter().find(|(id, _)| *id == EBML_SEEK_POSITION)?;
+                            (ebml_uint(target.1) == EBML_CHAPTERS)
+                                .then(|| segment_start + ebml_uint(position.1))
+                        })
+                });
+            }
+            // 音频数据开始后只能通过 SeekHead 定位章节
+            EBML_CLUSTER => {
+                let Some(position) = chapters_position else {
+                    break;
+                };
+                r.seek(SeekFrom::Start(position))?;
+                let (id, size, header) = read_element_header(r)?;
+                if id == EBML_CHAPTERS {
+                    return read_chapters_element(r, position + header as u64, size);
+                }
+                break;
+            }
+            _ => {}
+        }
+        if size == EBML_UNKNOWN_SIZE {
+            break;
+        }
+        pos = content_start + size;
+    }
+    Ok(Vec::new())
+}
+
+fn read_element_content<R: Read + Seek>(r: &mut R, start: u64, size: u64) -> io::Result<Vec<u8>> {
+    if size == EBML_UNKNOWN_SIZE || size > 16 * 1024 * 1024 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "EBML element too large",
+        ));
+    }
+    let mut buf = vec![0u8; size as usize];
+    r.seek(SeekFrom::Start(start))?;
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// 只读取第一个版本（EditionEntry）的顶层章节，忽略隐藏章节
+fn read_chapters_element<R: Read + Seek>(
+    r: &mut R,
+    start: u64,
+    size: u64,
+) -> io::Result<Vec<Chapter>> {
+    let buf = read_element_content(r, start, size)?;
+    Ok(parse_chapters_element(&buf))
+}
+
+fn parse_chapters_element(buf: &[u8]) -> Vec<Chapter> {
+    let Some((_, edition)) = ebml_children(buf)
+        .into_iter()
+        .find(|(id, _)| *id == EBML_EDITION_ENTRY)
+    else {
+        return Vec::new();
+    };
+    ebml_children(edition)
+        .into_iter()
+        .filter(|(id, _)| *id == EBML_CHAPTER_ATOM)
+        .filter_map(|(_, atom)| {
+            let fields = ebml_children(atom);
+            let field = |id: u64| fields.iter().find(|(i, _)| *i == id).map(|(_, v)| *v);
+            if field(EBML_CHAPTER_FLAG_HIDDEN).map(ebml_uint) == Some(1) {
+                return None;
+            }
+            let title = field(EBML_CHAPTER_DISPLAY)
+                .and_then(|display| {
+                    ebml_children(display)
+                        .into_iter()
+                        .find(|(id, _)| *id == EBML_CHAP_STRING)
+                        .map(|(_, s)| String::from_utf8_lossy(s).to_string())
+                })
+                .unwrap_or_default();
+            // 时间单位为纳秒
+            Some(Chapter {
+                title,
+                start: (field(EBML_CHAPTER_TIME_START).map(ebml_uint)? / 1_000_000) as i64,
+                end: field(EBML_CHAPTER_TIME_END)
+                    .map(|v| (ebml_uint(v) / 1_000_000) as i64)
+                    .unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn chapter(title: &str, start: i64, end: i64) -> Chapter {
+        Chapter {
+            title: title.to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn fills_missing_end_and_title() {
+        let chapters = normalize(
+            vec![
+                chapter("Two", 60_000, 0),
+                chapter("", 0, 0),
+                chapter("Late", 900_000, 0),
+            ],
+            300_000,
+        );
+        assert_eq!(
+            chapters,
+            vec![
+                chapter("Chapter 1", 0, 60_000),
+                chapter("Two", 60_000, 300_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_truncated_64_bit_box_header() {
+        // 64 位大小的 chpl，头部需要 16 字节，但父 box 在 12 字节处结束
+        let mut data = vec![0, 0, 0, 1];
+        data.extend_from_slice(b"chpl");
+        data.extend_from_slice(&1000u64.to_be_bytes());
+        let mut cursor = Cursor::new(data.clone());
+        assert!(mp4_children(&mut cursor, 0, 12).unwrap().is_empty());
+
+        let boxes = mp4_children(&mut cursor, 0, data.len() as u64).unwrap();
+        assert_eq!(boxes.len(), 1);
+        assert_eq!((boxes[0].start, boxes[0].end), (16, 16));
+        assert!(mp4_content(&mut cursor, &boxes[0]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reads_id3_chap_frames() {
+        let mut tag = Tag::new();
+        for (i, title) in ["Intro", "Main"].iter().enumerate() {
+            tag.add_frame(id3::frame::Chapter {
+                element_id: format!("ch{}", i),
+                start_time: i as u32 * 5_000,
+                end_time: (i as u32 + 1) * 5_000,
+                start_offset: u32::MAX,
+                end_offset: u32::MAX,
+                frames: vec![id3::Frame::text("TIT2", *title)],
+            });
+        }
+        assert_eq!(
+            id3_chapters(&tag),
+            vec![chapter("Intro", 0, 5_000), chapter("Main", 5_000, 10_000)]
+        );
+    }
+
+    fn mp4_box(kind: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        let mut buf = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+        buf.extend_from_slice(kind);
+        buf.extend_from_slice(content);
+        buf
+    }
+
+    #[test]
+    fn reads_nero_chpl() {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+        for (start, title) in [(0u64, "Opening"), (1_250_000_000, "Part Two")] {
+            chpl.extend_from_slice(&start.to_be_bytes());
+            chpl.push(title.len() as u8);
+            chpl.extend_from_slice(title.as_bytes());
+        }
+        let mut file = mp4_box(b"ftyp", b"M4B \0\0\0\0");
+        file.extend(mp4_box(
+            b"moov",
+            &mp4_box(b"udta", &mp4_box(b"chpl", &chpl)),
+        ));
+        let chapters = mp4_chapters(&mut Cursor::new(file)).unwrap();
+        assert_eq!(
+            chapters,
+            vec![chapter("Opening", 0, 0), chapter("Part Two", 125_000, 0)]
+        );
+    }
+
+    #[test]
+    fn reads_quicktime_chapter_track() {
+        let full_box = |kind: &[u8; 4], fields: &[u32]| {
+            let mut content = vec![0u8; 4];
+            fields
+                .iter()
+                .for_each(|f| content.extend_from_slice(&f.to_be_bytes()));
+            mp4_box(kind, &content)
+        };
+        let mut samples = Vec::new();
+        for title in ["Prologue", "Chapter One"] {
+            samples.extend_from_slice(&(title.len() as u16).to_be_bytes());
+            samples.extend_from_slice(title.as_bytes());
+        }
+        let ftyp = mp4_box(b"ftyp", b"M4A \0\0\0\0");
+        let mdat = mp4_box(b"mdat", &samples);
+        let sample_offset = (ftyp.len() + 8) as u32;
+
+        let audio = [
+            full_box(b"tkhd", &[0, 0, 1]),
+            mp4_box(b"tref", &mp4_box(b"chap", &2u32.to_be_bytes())),
+        ]
+        .concat();
+        let stbl = [
+            full_box(b"stts", &[1, 2, 90_000]),
+            full_box(b"stsz", &[0, 2, 10, 13]),
+            full_box(b"stsc", &[1, 1, 2, 1]),
+            full_box(b"stco", &[1, sample_offset]),
+        ]
+        .concat();
+        let mdia = [
+            full_box(b"mdhd", &[0, 0, 1000]),
+            mp4_box(b"minf", &mp4_box(b"stbl", &stbl)),
+        ]
+        .concat();
+        let text = [full_box(b"tkhd", &[0, 0, 2]), mp4_box(b"mdia", &mdia)].concat();
+        let moov = [mp4_box(b"trak", &audio), mp4_box(b"trak", &text)].concat();
+
+        let file = [ftyp, mdat, mp4_box(b"moov", &moov)].concat();
+        assert_eq!(
+            mp4_chapters(&mut Cursor::new(file)).unwrap(),
+            vec![chapter("Prologue", 0, 0), chapter("Chapter One", 90_000, 0)]
+        );
+    }
+
+    #[test]
+    fn reads_matroska_chapters() {
+        fn element(id: &[u8], content: &[u8]) -> Vec<u8> {
+            let mut buf = id.to_vec();
+            buf.push(0x80 | content.len() as u8);
+            buf.extend_from_slice(content);
+            buf
+        }
+        let atom = |title: &str, start_ns: u64, hidden: bool| {
+            let mut content = element(&[0x91], &start_ns.to_be_bytes());
+            if hidden {
+                content.extend(element(&[0x98], &[1]));
+            }
+            content.extend(element(&[0x80], &element(&[0x85], title.as_bytes())));
+            element(&[0xB6], &content)
+        };
+        let mut edition = atom("First", 0, false);
+        edition.extend(atom("Hidden", 1_000_000_000, true));
+        edition.extend(atom("Second", 90_000_000_000, false));
+        let chapters = element(&[0x10, 0x43, 0xA7, 0x70], &element(&[0x45, 0xB9], &edition));
+
+        let mut file = element(&[0x1A, 0x45, 0xDF, 0xA3], &[]);
+        file.extend(element(&[0x18, 0x53, 0x80, 0x67], &chapters));
+        assert_eq!(
+            matroska_chapters(&mut Cursor::new(file)).unwrap(),
+            vec![chapter("First", 0, 0), chapter("Second", 90_000, 0)]
+        );
+    }
+}
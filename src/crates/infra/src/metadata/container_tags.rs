@@ -0,0 +1,327 @@
+//! ID3 以外的文本标签：FLAC 与 Ogg（Vorbis、Opus）的 Vorbis comment、MP4 的 iTunes 自定义标签（----）
+
+use super::chapters::{mp4_children, mp4_content, mp4_find, Mp4Box};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 注释块可能包含封面，限制大小避免异常文件占用内存
+const MAX_COMMENT_LEN: usize = 16 * 1024 * 1024;
+
+/// 读取名为 name 的标签（不区分大小写），没有该标签或格式不支持时返回 None
+pub fn read_container_tag(path: &Path, name: &str) -> Option<String> {
+    File::open(path)
+        .and_then(|file| read_tag(&mut BufReader::new(file), name))
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to read {} from {:?}: {}", name, path, e);
+            None
+        })
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 按文件头判断容器格式，临时文件没有扩展名
+fn read_tag<R: Read + Seek>(r: &mut R, name: &str) -> io::Result<Option<String>> {
+    let mut magic = [0u8; 8];
+    if r.read(&mut magic)? < magic.len() {
+        return Ok(None);
+    }
+    r.seek(SeekFrom::Start(0))?;
+    if &magic[..4] == b"fLaC" {
+        flac_tag(r, name)
+    } else if &magic[..4] == b"OggS" {
+        ogg_tag(r, name)
+    } else if &magic[4..8] == b"ftyp" {
+        mp4_tag(r, name)
+    } else {
+        Ok(None)
+    }
+}
+
+fn le_u32(buf: &[u8], offset: usize) -> Option<usize> {
+    buf.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+}
+
+/// Vorbis comment：厂商字符串后是 `NAME=value` 列表，长度均为小端 u32
+fn vorbis_comment(buf: &[u8], name: &str) -> Option<String> {
+    let mut offset = 4 + le_u32(buf, 0)?;
+    let count = le_u32(buf, offset)?;
+    offset += 4;
+    for _ in 0..count {
+        let len = le_u32(buf, offset)?;
+        let entry = buf.get(offset + 4..offset + 4 + len)?;
+        offset += 4 + len;
+        let entry = String::from_utf8_lossy(entry);
+        if let Some((key, value)) = entry.split_once('=') {
+            if key.eq_ignore_ascii_case(name) {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
+
+// ============================================================================
+// FLAC
+// ============================================================================
+
+const FLAC_VORBIS_COMMENT: u8 = 4;
+
+/// 元数据块头：最高位表示最后一块，低 7 位为类型，后跟 3 字节长度
+fn flac_tag<R: Read + Seek>(r: &mut R, name: &str) -> io::Result<Option<String>> {
+    r.seek(SeekFrom::Start(4))?;
+    loop {
+        let mut header = [0u8; 4];
+        r.read_exact(&mut header)?;
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        if header[0] & 0x7F == FLAC_VORBIS_COMMENT {
+            let mut buf = vec![0u8; len.min(MAX_COMMENT_LEN)];
+            r.read_exact(&mut buf)?;
+            return Ok(vorbis_comment(&buf, name));
+        }
+        if header[0] & 0x80 != 0 {
+            return Ok(None);
+        }
+        r.seek(SeekFrom::Current(len as i64))?;
+    }
+}
+
+// ============================================================================
+// Ogg
+// ============================================================================
+
+/// 注释在第二个包中，Vorbis 以 `\x03vorbis` 开头，Opus 以 `OpusTags` 开头
+fn ogg_tag<R: Read + Seek>(r: &mut R, name: &str) -> io::Result<Option<String>> {
+    let packet = ogg_packet(r, 1)?;
+    let comment = if packet.starts_with(b"\x03vorbis") {
+        &packet[7..]
+    } else if packet.starts_with(b"OpusTags") {
+        &packet[8..]
+    } else {
+        return Ok(None);
+    };
+    Ok(vorbis_comment(comment, name))
+}
+
+/// 按页拼接第 index 个包，只处理单个逻辑流；分段长度小于 255 时包结束
+fn ogg_packet<R: Read>(r: &mut R, index: usize) -> io::Result<Vec<u8>> {
+    let mut current = 0;
+    let mut packet = Vec::new();
+    loop {
+        let mut header = [0u8; 27];
+        r.read_exact(&mut header)?;
+        if &header[..4] != b"OggS" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid Ogg page",
+            ));
+        }
+        let mut lacing = vec![0u8; header[26] as usize];
+        r.read_exact(&mut lacing)?;
+        for len in lacing {
+            let mut segment = vec![0u8; len as usize];
+            r.read_exact(&mut segment)?;
+            if current == index {
+                if packet.len() + segment.len() > MAX_COMMENT_LEN {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Ogg comment packet too large",
+                    ));
+                }
+                packet.extend_from_slice(&segment);
+            }
+            if len < 255 {
+                if current == index {
+                    return Ok(packet);
+                }
+                current += 1;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// MP4
+// ============================================================================
+
+/// moov/udta/meta/ilst 中的 `----` 标签，由 mean、name 和 data 三个子 box 组成
+fn mp4_tag<R: Read + Seek>(r: &mut R, name: &str) -> io::Result<Option<String>> {
+    let file_end = r.seek(SeekFrom::End(0))?;
+    let Some(moov) = mp4_children(r, 0, file_end)?
+        .into_iter()
+        .find(|b| &b.kind == b"moov")
+    else {
+        return Ok(None);
+    };
+    let Some(meta) = mp4_find(r, &moov, &[b"udta", b"meta"])? else {
+        return Ok(None);
+    };
+    // ISO 的 meta 是带版本和标志的 full box，QuickTime 的没有
+    let mut probe = [0u8; 8];
+    r.seek(SeekFrom::Start(meta.start))?;
+    r.read_exact(&mut probe)?;
+    let children_start = if &probe[4..8] == b"hdlr" {
+        meta.start
+    } else {
+        meta.start + 4
+    };
+    let Some(ilst) = mp4_children(r, children_start, meta.end)?
+        .into_iter()
+        .find(|b| &b.kind == b"ilst")
+    else {
+        return Ok(None);
+    };
+    for item in mp4_children(r, ilst.start, ilst.end)? {
+        if &item.kind != b"----" {
+            continue;
+        }
+        let mut item_name = None;
+        let mut value = None;
+        for field in mp4_children(r, item.start, item.end)? {
+            match &field.kind {
+                // name 与 data 的内容前有 4 字节版本和标志，data 还有 4 字节语言
+                b"name" => item_name = Some(mp4_text(r, &field, 4)?),
+                b"data" => value = value.or(Some(mp4_text(r, &field, 8)?)),
+                _ => {}
+            }
+        }
+        if item_name.is_some_and(|n| n.eq_ignore_ascii_case(name)) {
+            return Ok(value);
+        }
+    }
+    Ok(None)
+}
+
+fn mp4_text<R: Read + Seek>(r: &mut R, b: &Mp4Box, skip: usize) -> io::Result<String> {
+    let content = mp4_content(r, b)?;
+    Ok(String::from_utf8_lossy(content.get(skip..).unwrap_or_default()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn comment(entries: &[&str]) -> Vec<u8> {
+        let vendor = b"reference libFLAC 1.4.3";
+        let mut buf = (vendor.len() as u32).to_le_bytes().to_vec();
+        buf.extend_from_slice(vendor);
+        buf.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for entry in entries {
+            buf.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+            buf.extend_from_slice(entry.as_bytes());
+        }
+        buf
+    }
+
+    fn flac_block(kind: u8, last: bool, content: &[u8]) -> Vec<u8> {
+        let len = (content.len() as u32).to_be_bytes();
+        let mut buf = vec![kind | if last { 0x80 } else { 0 }, len[1], len[2], len[3]];
+        buf.extend_from_slice(content);
+        buf
+    }
+
+    #[test]
+    fn reads_flac_vorbis_comment() {
+        let mut file = b"fLaC".to_vec();
+        file.extend(flac_block(0, false, &[0u8; 34]));
+        file.extend(flac_block(
+            FLAC_VORBIS_COMMENT,
+            true,
+            &comment(&["TITLE=Intro", "discsubtitle=Live"]),
+        ));
+        assert_eq!(
+            read_tag(&mut Cursor::new(file.clone()), "DISCSUBTITLE").unwrap(),
+            Some("Live".to_string())
+        );
+        assert_eq!(read_tag(&mut Cursor::new(file), "ALBUM").unwrap(), None);
+    }
+
+    /// 把包按 255 字节分段写入一页
+    fn ogg_page(packets: &[Vec<u8>]) -> Vec<u8> {
+        let mut lacing = Vec::new();
+        for packet in packets {
+            lacing.extend(std::iter::repeat_n(255u8, packet.len() / 255));
+            lacing.push((packet.len() % 255) as u8);
+        }
+        let mut page = b"OggS".to_vec();
+        page.extend_from_slice(&[0u8; 22]);
+        page.push(lacing.len() as u8);
+        page.extend(lacing);
+        packets.iter().for_each(|p| page.extend_from_slice(p));
+        page
+    }
+
+    #[test]
+    fn reads_ogg_vorbis_and_opus_comments() {
+        // 注释包超过 255 字节，需要拼接多个分段
+        let long_title = format!("TITLE={}", "x".repeat(300));
+        let entries = [long_title.as_str(), "DISCSUBTITLE=Disc Two"];
+
+        let mut vorbis = b"\x03vorbis".to_vec();
+        vorbis.extend(comment(&entries));
+        let file = [
+            ogg_page(&[b"\x01vorbis\0\0\0\0".to_vec()]),
+            ogg_page(&[vorbis, b"\x05vorbis".to_vec()]),
+        ]
+        .concat();
+        assert_eq!(
+            read_tag(&mut Cursor::new(file), "DISCSUBTITLE").unwrap(),
+            Some("Disc Two".to_string())
+        );
+
+        let mut opus = b"OpusTags".to_vec();
+        opus.extend(comment(&entries));
+        let file = [ogg_page(&[b"OpusHead\x01\x02".to_vec()]), ogg_page(&[opus])].concat();
+        assert_eq!(
+            read_tag(&mut Cursor::new(file), "DISCSUBTITLE").unwrap(),
+            Some("Disc Two".to_string())
+        );
+    }
+
+    fn mp4_box(kind: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        let mut buf = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+        buf.extend_from_slice(kind);
+        buf.extend_from_slice(content);
+        buf
+    }
+
+    fn freeform(name: &str, value: &str) -> Vec<u8> {
+        let full = |kind: &[u8; 4], prefix: &[u8], text: &str| {
+            mp4_box(kind, &[prefix, text.as_bytes()].concat())
+        };
+        mp4_box(
+            b"----",
+            &[
+                full(b"mean", &[0; 4], "com.apple.iTunes"),
+                full(b"name", &[0; 4], name),
+                full(b"data", &[0, 0, 0, 1, 0, 0, 0, 0], value),
+            ]
+            .concat(),
+        )
+    }
+
+    #[test]
+    fn reads_mp4_freeform_tag() {
+        let ilst = mp4_box(
+            b"ilst",
+            &[
+                freeform("MusicBrainz Album Id", "b1"),
+                freeform("DISCSUBTITLE", "Acoustic"),
+            ]
+            .concat(),
+        );
+        let hdlr = mp4_box(b"hdlr", &[0u8; 25]);
+        let meta = mp4_box(b"meta", &[&[0u8; 4][..], &hdlr, &ilst].concat());
+        let file = [
+            mp4_box(b"ftyp", b"M4A \0\0\0\0"),
+            mp4_box(b"moov", &mp4_box(b"udta", &meta)),
+        ]
+        .concat();
+        assert_eq!(
+            read_tag(&mut Cursor::new(file), "discsubtitle").unwrap(),
+            Some("Acoustic".to_string())
+        );
+    }
+}
@@ -1,2 +1,7 @@
 pub mod audio_metadata_reader;
+pub mod chapters;
+pub mod container_tags;
+pub mod rule_config;
 pub mod rule_engine;
+pub mod silence_detector;
+pub mod tag_writer;
@@ -0,0 +1,419 @@
+use super::rule_engine::{
+    AlbumCleanupRule, ArtistFeatExtractRule, ArtistRoleExtractRule, ArtistSplitRule,
+    ArtistsTagRule, FeatArtistExtractRule, GenreNormalizeRule, GenreSplitRule, MetadataRule,
+    MetadataRuleEngine, MovementTitleRule, PriorityOverrideRule, ProtectedArtists,
+    RegexReplaceRule, RuleField, TitleCleanupRule, TrackNumberCleanupRule, YearExtractRule,
+};
+use config::{Config, File, FileFormat};
+use log::{error, info};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// 自定义正则替换规则配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaceRuleConfig {
+    /// 规则名称
+    pub name: String,
+    /// 作用字段：title / album / artist / genre
+    pub field: String,
+    /// 匹配的正则表达式
+    pub pattern: String,
+    /// 替换内容，支持 $1 等捕获组引用
+    #[serde(default)]
+    pub replacement: String,
+    /// 优先级（数字越小越先执行）
+    #[serde(default = "default_replace_priority")]
+    pub priority: i32,
+    /// 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_replace_priority() -> i32 {
+    15
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 元数据规则配置（metadata_rules.toml）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MetadataRulesConfig {
+    /// 艺术家分隔符（为空时使用内置列表）
+    pub artist_separators: Vec<String>,
+    /// 不参与分割的艺术家名称，如 "AC/DC"
+    pub protected_artists: Vec<String>,
+    /// 追加的专辑名水印正则
+    pub watermark_patterns: Vec<String>,
+    /// 追加的流派映射（key 不区分大小写）
+    pub genre_mappings: HashMap<String, String>,
+    /// 禁用的内置规则名称
+    pub disabled_rules: Vec<String>,
+    /// 覆盖内置规则优先级
+    pub rule_priorities: HashMap<String, i32>,
+    /// 自定义正则替换规则
+    pub replace_rules: Vec<ReplaceRuleConfig>,
+    /// 标题前加乐章序号（古典音乐）
+    pub movement_titles: bool,
+}
+
+impl MetadataRulesConfig {
+    /// 从 TOML 文件加载，文件不存在时返回默认配置
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let config = Config::builder()
+            .add_source(File::new(&path.to_string_lossy(), FileFormat::Toml))
+            .build()
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        config
+            .try_deserialize()
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// 根据配置构建规则引擎（内置规则 + 自定义规则）
+    pub fn build_engine(&self) -> Result<MetadataRuleEngine, String> {
+        let watermarks = self
+            .watermark_patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("Invalid watermark pattern '{}': {}", p, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let protected = ProtectedArtists::new(self.protected_artists.clone())?;
+        let artist_split = if self.artist_separators.is_empty() {
+            ArtistSplitRule::new()
+        } else {
+            ArtistSplitRule::with_separators(self.artist_separators.clone())
+        }
+        .with_protected(protected.clone());
+
+        let genre_normalize = if self.genre_mappings.is_empty() {
+            GenreNormalizeRule::new()
+        } else {
+            let mut mappings = GenreNormalizeRule::default_mappings();
+            for (k, v) in &self.genre_mappings {
+                mappings.insert(k.to_lowercase(), v.clone());
+            }
+            GenreNormalizeRule::with_mappings(mappings)
+        };
+
+        let builtin: Vec<Arc<dyn MetadataRule>> = vec![
+            Arc::new(TitleCleanupRule::new()),
+            Arc::new(AlbumCleanupRule::new().with_extra_watermarks(watermarks)),
+            Arc::new(ArtistsTagRule::new()),
+            Arc::new(ArtistRoleExtractRule::new().with_protected(protected)),
+            Arc::new(ArtistFeatExtractRule::new()),
+            Arc::new(artist_split),
+            Arc::new(GenreSplitRule::new()),
+            Arc::new(genre_normalize),
+            Arc::new(YearExtractRule::new()),
+            Arc::new(FeatArtistExtractRule::new()),
+            Arc::new(TrackNumberCleanupRule::new()),
+        ];
+
+        let mut engine = MetadataRuleEngine::new();
+        for rule in builtin {
+            if self.disabled_rules.iter().any(|n| n == rule.name()) {
+                continue;
+            }
+            match self.rule_priorities.get(rule.name()) {
+                Some(priority) => engine.add_rule(Arc::new(PriorityOverrideRule::new(rule, *priority))),
+                None => engine.add_rule(rule),
+            }
+        }
+
+        if self.movement_titles {
+            engine.add_rule(Arc::new(MovementTitleRule::new()));
+        }
+
+        for rule in self.replace_rules.iter().filter(|r| r.enabled) {
+            let field = RuleField::try_from(rule.field.as_str())?;
+            let pattern = Regex::new(&rule.pattern)
+                .map_err(|e| format!("Invalid pattern in rule '{}': {}", rule.name, e))?;
+            engine.add_rule(Arc::new(RegexReplaceRule::new(
+                rule.name.clone(),
+                field,
+                pattern,
+                rule.replacement.clone(),
+                rule.priority,
+            )));
+        }
+
+        engine.sort_rules();
+        Ok(engine)
+    }
+}
+
+/// 可热加载的规则引擎：持有当前引擎，规则文件变化时原子替换
+pub struct ReloadableRuleEngine {
+    path: Option<PathBuf>,
+    engine: RwLock<Arc<MetadataRuleEngine>>,
+    modified: RwLock<Option<SystemTime>>,
+    /// 通过管理接口维护的受保护艺术家（与规则文件中的名单合并）
+    protected_artists: RwLock<Vec<String>>,
+    /// 数据库中的流派别名（覆盖规则文件中的同名映射）
+    genre_aliases: RwLock<HashMap<String, String>>,
+}
+
+impl ReloadableRuleEngine {
+    /// 使用固定的规则引擎（不关联规则文件）
+    pub fn new(engine: MetadataRuleEngine) -> Self {
+        Self {
+            path: None,
+            engine: RwLock::new(Arc::new(engine)),
+            modified: RwLock::new(None),
+            protected_artists: RwLock::new(Vec::new()),
+            genre_aliases: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 从规则文件加载，加载失败时回退到默认规则
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        let holder = Self {
+            path: Some(path.into()),
+            engine: RwLock::new(Arc::new(MetadataRuleEngine::with_default_rules())),
+            modified: RwLock::new(None),
+            protected_artists: RwLock::new(Vec::new()),
+            genre_aliases: RwLock::new(HashMap::new()),
+        };
+        if let Err(e) = holder.reload() {
+            error!("Failed to load metadata rules, using default rules: {}", e);
+        }
+        holder
+    }
+
+    /// 获取当前规则引擎快照
+    pub fn engine(&self) -> Arc<MetadataRuleEngine> {
+        self.engine.read().unwrap().clone()
+    }
+
+    /// 当前通过管理接口维护的受保护艺术家
+    pub fn protected_artists(&self) -> Vec<String> {
+        self.protected_artists.read().unwrap().clone()
+    }
+
+    /// 用新的名单构建一次引擎但不替换，保存名单前用来校验
+    pub fn validate_protected_artists(&self, names: &[String]) -> Result<(), String> {
+        self.build(names.to_vec(), self.genre_aliases()).map(|_| ())
+    }
+
+    /// 替换受保护艺术家名单并重建引擎；构建失败时保留原名单和引擎
+    pub fn set_protected_artists(&self, names: Vec<String>) -> Result<(), String> {
+        let engine = self.build(names.clone(), self.genre_aliases())?;
+        *self.protected_artists.write().unwrap() = names;
+        *self.engine.write().unwrap() = Arc::new(engine);
+        Ok(())
+    }
+
+    /// 当前数据库中的流派别名
+    pub fn genre_aliases(&self) -> HashMap<String, String> {
+        self.genre_aliases.read().unwrap().clone()
+    }
+
+    /// 替换流派别名并重建引擎；构建失败时保留原别名和引擎
+    pub fn set_genre_aliases(&self, aliases: HashMap<String, String>) -> Result<(), String> {
+        let engine = self.build(self.protected_artists(), aliases.clone())?;
+        *self.genre_aliases.write().unwrap() = aliases;
+        *self.engine.write().unwrap() = Arc::new(engine);
+        Ok(())
+    }
+
+    /// 合并管理接口维护的名单和别名
+    fn with_overrides(&self, mut config: MetadataRulesConfig) -> MetadataRulesConfig {
+        config.protected_artists.extend(self.protected_artists());
+        config.genre_mappings.extend(self.genre_aliases());
+        config
+    }
+
+    /// 读取规则文件（没有时使用默认规则），合并给定的名单和别名后构建引擎
+    fn build(
+        &self,
+        protected_artists: Vec<String>,
+        genre_aliases: HashMap<String, String>,
+    ) -> Result<MetadataRuleEngine, String> {
+        let mut config = match &self.path {
+            Some(path) => MetadataRulesConfig::load(path)?,
+            None => MetadataRulesConfig::default(),
+        };
+        config.protected_artists.extend(protected_artists);
+        config.genre_mappings.extend(genre_aliases);
+        config.build_engine()
+    }
+
+    /// 重新读取规则文件并替换引擎；解析失败时保留旧引擎
+    pub fn reload(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let config = self.with_overrides(MetadataRulesConfig::load(path)?);
+        let engine = config.build_engine()?;
+        info!(
+            "Metadata rules loaded from {} ({} rules)",
+            path.display(),
+            engine.rules().len()
+        );
+        *self.engine.write().unwrap() = Arc::new(engine);
+        *self.modified.write().unwrap() = modified;
+        Ok(())
+    }
+
+    /// 启动后台任务，定期检查规则文件修改时间并热加载
+    pub fn start_watcher(self: &Arc<Self>, interval: Duration) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let holder = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                if modified == *holder.modified.read().unwrap() {
+                    continue;
+                }
+                if let Err(e) = holder.reload() {
+                    error!("Failed to reload metadata rules: {}", e);
+                    // 记录修改时间，避免对同一份错误文件反复报错
+                    *holder.modified.write().unwrap() = modified;
+                }
+            }
+        });
+    }
+}
+
+impl Default for ReloadableRuleEngine {
+    fn default() -> Self {
+        Self::new(MetadataRuleEngine::with_default_rules())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::rule_engine::RuleContext;
+
+    fn ctx(title: &str, artist: &str, album: &str, genre: &str) -> RuleContext {
+        RuleContext::new(
+            title.to_string(),
+            artist.to_string(),
+            album.to_string(),
+            genre.to_string(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_build_engine_with_custom_rules() {
+        let config = MetadataRulesConfig {
+            artist_separators: vec![";".to_string()],
+            watermark_patterns: vec![r"(?i)\[FLAC\]\s*".to_string()],
+            genre_mappings: HashMap::from([("j-pop".to_string(), "J-Pop".to_string())]),
+            replace_rules: vec![ReplaceRuleConfig {
+                name: "strip_bonus".to_string(),
+                field: "title".to_string(),
+                pattern: r"(?i)\s*\(bonus track\)".to_string(),
+                replacement: String::new(),
+                priority: 11,
+                enabled: true,
+            }],
+            ..Default::default()
+        };
+        let engine = config.build_engine().unwrap();
+
+        let mut c = ctx("Song (Bonus Track)", "A & B;C", "[FLAC] Album", "j-pop");
+        engine.execute(&mut c);
+
+        assert_eq!(c.title, "Song");
+        assert_eq!(c.album, "Album");
+        let names: Vec<&str> = c.artists.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["A & B", "C"]);
+        assert_eq!(c.genres, vec!["J-Pop".to_string()]);
+    }
+
+    #[test]
+    fn test_disabled_rules_and_priorities() {
+        let config = MetadataRulesConfig {
+            disabled_rules: vec!["year_extract".to_string()],
+            rule_priorities: HashMap::from([("track_number_cleanup".to_string(), 1)]),
+            ..Default::default()
+        };
+        let engine = config.build_engine().unwrap();
+        let names: Vec<&str> = engine.rules().iter().map(|r| r.name()).collect();
+
+        assert!(!names.contains(&"year_extract"));
+        assert_eq!(names.first(), Some(&"track_number_cleanup"));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let config = MetadataRulesConfig {
+            replace_rules: vec![ReplaceRuleConfig {
+                name: "broken".to_string(),
+                field: "album".to_string(),
+                pattern: "(".to_string(),
+                replacement: String::new(),
+                priority: 15,
+                enabled: true,
+            }],
+            ..Default::default()
+        };
+        assert!(config.build_engine().is_err());
+    }
+
+    #[test]
+    fn test_set_protected_artists_rebuilds_engine() {
+        let holder = ReloadableRuleEngine::default();
+        holder
+            .set_protected_artists(vec!["Earth, Wind & Fire".to_string()])
+            .unwrap();
+
+        let mut c = ctx("Song", "Earth, Wind & Fire", "Album", "Funk");
+        holder.engine().execute(&mut c);
+
+        assert_eq!(c.artists.len(), 1);
+        assert_eq!(c.artists[0].name, "Earth, Wind & Fire");
+    }
+
+    #[test]
+    fn test_invalid_protected_artists_keep_the_current_engine() {
+        let holder = ReloadableRuleEngine::default();
+        holder
+            .set_protected_artists(vec!["AC/DC".to_string()])
+            .unwrap();
+        let engine = holder.engine();
+
+        let invalid = vec!["AC/DC".to_string(), "Bad\u{1}Name".to_string()];
+        assert!(holder.validate_protected_artists(&invalid).is_err());
+        assert!(holder.set_protected_artists(invalid).is_err());
+
+        assert_eq!(holder.protected_artists(), vec!["AC/DC".to_string()]);
+        assert!(Arc::ptr_eq(&engine, &holder.engine()));
+    }
+
+    #[test]
+    fn test_set_genre_aliases_overrides_defaults() {
+        let holder = ReloadableRuleEngine::default();
+        holder
+            .set_genre_aliases(HashMap::from([
+                ("alternative".to_string(), "Alternative Rock".to_string()),
+                ("Alt Rock".to_string(), "Alternative Rock".to_string()),
+                ("alternative rock".to_string(), "Alternative Rock".to_string()),
+            ]))
+            .unwrap();
+
+        for genre in ["Alt Rock", "alternative", "Alternative Rock"] {
+            let mut c = ctx("Song", "Artist", "Album", genre);
+            holder.engine().execute(&mut c);
+            assert_eq!(c.genres, vec!["Alternative Rock".to_string()]);
+        }
+    }
+}
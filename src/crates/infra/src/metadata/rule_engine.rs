@@ -13,6 +13,8 @@ pub struct RuleContext {
     pub raw_genre: String,
     pub raw_year: Option<i32>,
     pub raw_track_number: Option<i32>,
+    /// 多值艺术家标签（MusicBrainz ARTISTS），非空时跳过艺术家分割
+    pub raw_artists: Vec<String>,
 
     /// 处理后的数据
     pub title: String,
@@ -42,6 +44,7 @@ impl RuleContext {
             raw_genre: genre.clone(),
             raw_year: year,
             raw_track_number: track_number,
+            raw_artists: Vec::new(),
             title,
             artists: Vec::new(),
             album,
@@ -51,6 +54,29 @@ impl RuleContext {
             extra: HashMap::new(),
         }
     }
+
+    /// 设置多值艺术家标签
+    pub fn with_raw_artists(mut self, artists: Vec<String>) -> Self {
+        self.raw_artists = artists
+            .into_iter()
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .collect();
+        self
+    }
+}
+
+/// 单条规则的执行记录
+#[derive(Debug, Clone)]
+pub struct RuleTrace {
+    /// 规则名称
+    pub rule: String,
+    /// 规则优先级
+    pub priority: i32,
+    /// 是否满足 should_apply 并被执行
+    pub applied: bool,
+    /// 规则执行后的上下文
+    pub context: RuleContext,
 }
 
 /// 规则 trait，所有元数据处理规则都需要实现
@@ -93,6 +119,11 @@ impl MetadataRuleEngine {
         self.rules.sort_by_key(|r| r.priority());
     }
 
+    /// 当前已注册的规则（按执行顺序）
+    pub fn rules(&self) -> &[Arc<dyn MetadataRule>] {
+        &self.rules
+    }
+
     /// 执行所有规则
     pub fn execute(&self, ctx: &mut RuleContext) {
         for rule in &self.rules {
@@ -102,6 +133,24 @@ impl MetadataRuleEngine {
         }
     }
 
+    /// 执行所有规则并记录每条规则执行后的上下文快照（用于调试规则）
+    pub fn execute_traced(&self, ctx: &mut RuleContext) -> Vec<RuleTrace> {
+        let mut traces = Vec::with_capacity(self.rules.len());
+        for rule in &self.rules {
+            let applied = rule.should_apply(ctx);
+            if applied {
+                rule.apply(ctx);
+            }
+            traces.push(RuleTrace {
+                rule: rule.name().to_string(),
+                priority: rule.priority(),
+                applied,
+                context: ctx.clone(),
+            });
+        }
+        traces
+    }
+
     /// 创建默认规则引擎（包含所有内置规则）
     pub fn with_default_rules() -> Self {
         let mut engine = Self::new();
@@ -109,6 +158,7 @@ impl MetadataRuleEngine {
         // 添加内置规则（按执行顺序）
         engine.add_rule(Arc::new(TitleCleanupRule::new()));
         engine.add_rule(Arc::new(AlbumCleanupRule::new()));       // 专辑名清理
+        engine.add_rule(Arc::new(ArtistsTagRule::new()));        // 多值艺术家标签优先
         engine.add_rule(Arc::new(ArtistRoleExtractRule::new())); // 先提取角色标注
         engine.add_rule(Arc::new(ArtistFeatExtractRule::new())); // 提取 feat 艺术家
         engine.add_rule(Arc::new(ArtistSplitRule::new()));       // 再分割艺术家
@@ -191,6 +241,8 @@ pub struct AlbumCleanupRule {
     watermark_patterns: Vec<Regex>,
     /// Disc 信息模式
     disc_pattern: Regex,
+    /// 带副标题的 Disc 信息模式
+    disc_subtitle_pattern: Regex,
     /// 版本信息模式（保留在 extra 中）
     version_pattern: Regex,
 }
@@ -207,14 +259,27 @@ impl AlbumCleanupRule {
             ],
             // 匹配 [Disc 1], (Disc 2), Disc 3 等
             disc_pattern: Regex::new(r"(?i)[\[\(]?\s*Disc\s*(\d+)\s*[\]\)]?").unwrap(),
-            // 匹配版本信息 [香港版], [港台版], [日本版], [Remaster] 等
+            // 匹配 [Disc 1: Live], (Disc 2 - Acoustic) 等
+            disc_subtitle_pattern: Regex::new(
+                r"(?i)[\[\(]\s*Disc\s*(\d+)\s*[:：\-]\s*([^\]\)]+?)\s*[\]\)]",
+            )
+            .unwrap(),
+            // 匹配版本信息 [香港版], [港台版], [日本版], [Remaster], (2019 Remaster) 等
             version_pattern: Regex::new(
-                r"[\[\(]\s*(香港版|港台版|台湾版|日本版|韩国版|国语版|粤语版|精装版|豪华版|限量版|纪念版|Remaster|Remastered|Deluxe|Special Edition|Limited Edition)\s*[\]\)]"
+                r"(?i)[\[\(]\s*(香港版|港台版|台湾版|日本版|韩国版|国语版|粤语版|精装版|豪华版|限量版|纪念版|(?:\d{4}\s+)?Remaster(?:ed)?(?:\s+Edition)?|Deluxe(?:\s+Edition)?|Special Edition|Limited Edition|(?:\d+\w*\s+)?Anniversary Edition)\s*[\]\)]"
             ).unwrap(),
         }
     }
 }
 
+impl AlbumCleanupRule {
+    /// 在内置水印之外追加自定义水印模式
+    pub fn with_extra_watermarks(mut self, patterns: Vec<Regex>) -> Self {
+        self.watermark_patterns.extend(patterns);
+        self
+    }
+}
+
 impl MetadataRule for AlbumCleanupRule {
     fn name(&self) -> &str {
         "album_cleanup"
@@ -232,8 +297,16 @@ impl MetadataRule for AlbumCleanupRule {
             album = pattern.replace_all(&album, "").to_string();
         }
 
-        // 2. 提取并移除 Disc 信息
-        if let Some(caps) = self.disc_pattern.captures(&album) {
+        // 2. 提取并移除 Disc 信息（含副标题）
+        if let Some(caps) = self.disc_subtitle_pattern.captures(&album) {
+            if let (Some(disc_num), Some(subtitle)) = (caps.get(1), caps.get(2)) {
+                ctx.extra
+                    .insert("disc_number".to_string(), disc_num.as_str().to_string());
+                ctx.extra
+                    .insert("disc_subtitle".to_string(), subtitle.as_str().to_string());
+            }
+            album = self.disc_subtitle_pattern.replace_all(&album, "").to_string();
+        } else if let Some(caps) = self.disc_pattern.captures(&album) {
             if let Some(disc_num) = caps.get(1) {
                 ctx.extra
                     .insert("disc_number".to_string(), disc_num.as_str().to_string());
@@ -272,17 +345,131 @@ impl MetadataRule for AlbumCleanupRule {
     }
 }
 
+/// 不允许被分割的艺术家名单，如 "AC/DC"、"Earth, Wind & Fire"
+#[derive(Clone, Default)]
+pub struct ProtectedArtists {
+    /// (不区分大小写的匹配模式, 规范名称)，按名称长度降序
+    entries: Vec<(Regex, String)>,
+}
+
+impl ProtectedArtists {
+    const MARK: char = '\u{1}';
+
+    /// 名称含控制字符时返回错误
+    pub fn new(names: Vec<String>) -> Result<Self, String> {
+        let mut names: Vec<String> = names
+            .into_iter()
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .collect();
+        // 长名称优先，避免 "Earth, Wind & Fire" 被 "Earth" 之类的短名称截断
+        names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+        names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        let entries = names
+            .into_iter()
+            .map(|n| {
+                if n.chars().any(char::is_control) {
+                    return Err(format!("Invalid protected artist '{}'", n.escape_debug()));
+                }
+                Regex::new(&Self::pattern(&n))
+                    .map(|re| (re, n.clone()))
+                    .map_err(|e| format!("Invalid protected artist '{}': {}", n, e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { entries })
+    }
+
+    /// 不区分大小写，名称以字母数字开头或结尾时要求该侧是单词边界，
+    /// 避免 "AC" 匹配到 "Jack" 中间
+    fn pattern(name: &str) -> String {
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let boundary = |c: Option<char>| if is_word(c) { r"\b" } else { "" };
+        format!(
+            "(?i){}{}{}",
+            boundary(name.chars().next()),
+            regex::escape(name),
+            boundary(name.chars().last())
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 将受保护名称替换为不含分隔符的占位符
+    fn mask(&self, value: &str) -> String {
+        let mut masked = value.to_string();
+        for (idx, (re, _)) in self.entries.iter().enumerate() {
+            let placeholder = format!("{}{}{}", Self::MARK, idx, Self::MARK);
+            masked = re.replace_all(&masked, placeholder.as_str()).to_string();
+        }
+        masked
+    }
+
+    /// 将占位符还原为规范名称
+    fn unmask(&self, value: &str) -> String {
+        if !value.contains(Self::MARK) {
+            return value.to_string();
+        }
+        let mut restored = value.to_string();
+        for (idx, (_, name)) in self.entries.iter().enumerate() {
+            let placeholder = format!("{}{}{}", Self::MARK, idx, Self::MARK);
+            restored = restored.replace(&placeholder, name);
+        }
+        restored
+    }
+}
+
+/// 多值艺术家标签规则：存在 MusicBrainz ARTISTS 标签时直接使用，不再分割
+pub struct ArtistsTagRule;
+
+impl ArtistsTagRule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl MetadataRule for ArtistsTagRule {
+    fn name(&self) -> &str {
+        "artists_tag"
+    }
+
+    fn priority(&self) -> i32 {
+        17 // 在角色提取和分割之前
+    }
+
+    fn should_apply(&self, ctx: &RuleContext) -> bool {
+        !ctx.raw_artists.is_empty()
+    }
+
+    fn apply(&self, ctx: &mut RuleContext) {
+        let mut seen = std::collections::HashSet::new();
+        ctx.artists = ctx
+            .raw_artists
+            .iter()
+            .filter(|name| seen.insert(name.to_lowercase()))
+            .map(|name| ParticipantMeta {
+                role: ParticipantRole::Artist,
+                sub_role: None,
+                name: name.clone(),
+            })
+            .collect();
+    }
+}
+
 /// 艺术家分割规则：将艺术家字符串按分隔符分割
 pub struct ArtistSplitRule {
     /// 分隔符列表（按优先级排序）
-    separators: Vec<&'static str>,
+    separators: Vec<String>,
+    /// 不参与分割的艺术家名单
+    protected: ProtectedArtists,
 }
 
 impl ArtistSplitRule {
     pub fn new() -> Self {
         Self {
             // 分隔符按优先级排序，先处理明确的分隔符
-            separators: vec![
+            separators: [
                 // 中英文逗号
                 ",",
                 "，",
@@ -297,22 +484,35 @@ impl ArtistSplitRule {
                 " vs ",
                 " vs. ",
                 " and ",
-            ],
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            protected: ProtectedArtists::default(),
         }
     }
 
-    pub fn with_separators(separators: Vec<&'static str>) -> Self {
-        Self { separators }
+    pub fn with_separators<S: Into<String>>(separators: Vec<S>) -> Self {
+        Self {
+            separators: separators.into_iter().map(Into::into).collect(),
+            protected: ProtectedArtists::default(),
+        }
+    }
+
+    /// 设置不参与分割的艺术家名单
+    pub fn with_protected(mut self, protected: ProtectedArtists) -> Self {
+        self.protected = protected;
+        self
     }
 
     fn split_artists(&self, artist_string: &str) -> Vec<String> {
-        let mut result = vec![artist_string.to_string()];
+        let mut result = vec![self.protected.mask(artist_string)];
 
         for separator in &self.separators {
             let mut new_result = Vec::new();
             for artist in result {
-                if artist.contains(separator) {
-                    for part in artist.split(separator) {
+                if artist.contains(separator.as_str()) {
+                    for part in artist.split(separator.as_str()) {
                         let trimmed = part.trim();
                         if !trimmed.is_empty() {
                             new_result.push(trimmed.to_string());
@@ -324,6 +524,7 @@ impl ArtistSplitRule {
             }
             result = new_result;
         }
+        let mut result: Vec<String> = result.iter().map(|a| self.protected.unmask(a)).collect();
 
         // 去重（保持顺序，基于小写比较）
         let mut seen = std::collections::HashSet::new();
@@ -342,6 +543,10 @@ impl MetadataRule for ArtistSplitRule {
         22
     }
 
+    fn should_apply(&self, ctx: &RuleContext) -> bool {
+        ctx.raw_artists.is_empty()
+    }
+
     fn apply(&self, ctx: &mut RuleContext) {
         // 如果已经有艺术家了（可能由前置规则处理），则对每个艺术家再次分割
         if !ctx.artists.is_empty() {
@@ -381,6 +586,8 @@ pub struct ArtistRoleExtractRule {
     role_pattern: Regex,
     /// 角色名称到 SubRole 的映射
     role_mappings: HashMap<String, ParticipantSubRole>,
+    /// 不参与逗号分割的艺术家名单
+    protected: ProtectedArtists,
 }
 
 impl ArtistRoleExtractRule {
@@ -402,9 +609,16 @@ impl ArtistRoleExtractRule {
             // 匹配 "Name (Role)" 或 "Name [Role]" 格式
             role_pattern: Regex::new(r"^(.+?)\s*[\(\[]([\w\s]+)[\)\]]$").unwrap(),
             role_mappings,
+            protected: ProtectedArtists::default(),
         }
     }
 
+    /// 设置不参与逗号分割的艺术家名单
+    pub fn with_protected(mut self, protected: ProtectedArtists) -> Self {
+        self.protected = protected;
+        self
+    }
+
     fn extract_role(&self, artist_str: &str) -> (String, Option<ParticipantSubRole>) {
         if let Some(caps) = self.role_pattern.captures(artist_str) {
             let name = caps.get(1).map(|m| m.as_str().trim()).unwrap_or(artist_str);
@@ -428,18 +642,23 @@ impl MetadataRule for ArtistRoleExtractRule {
         18 // 在分割之前执行
     }
 
+    fn should_apply(&self, ctx: &RuleContext) -> bool {
+        ctx.raw_artists.is_empty()
+    }
+
     fn apply(&self, ctx: &mut RuleContext) {
         // 处理原始艺术家字符串，先按逗号分割，然后提取角色
-        let parts: Vec<&str> = ctx.raw_artist.split(&[',', '，'][..]).collect();
+        let masked = self.protected.mask(&ctx.raw_artist);
+        let parts: Vec<&str> = masked.split(&[',', '，'][..]).collect();
         let mut artists = Vec::new();
 
         for part in parts {
-            let trimmed = part.trim();
+            let trimmed = self.protected.unmask(part.trim());
             if trimmed.is_empty() {
                 continue;
             }
 
-            let (name, sub_role) = self.extract_role(trimmed);
+            let (name, sub_role) = self.extract_role(&trimmed);
             artists.push(ParticipantMeta {
                 role: ParticipantRole::Artist,
                 sub_role,
@@ -533,6 +752,10 @@ impl MetadataRule for ArtistFeatExtractRule {
         19 // 在角色提取之后，分割之前
     }
 
+    fn should_apply(&self, ctx: &RuleContext) -> bool {
+        ctx.raw_artists.is_empty()
+    }
+
     fn apply(&self, ctx: &mut RuleContext) {
         // 检查原始艺术家字符串是否包含 feat 模式
         for pattern in &self.feat_patterns {
@@ -577,7 +800,8 @@ pub struct GenreSplitRule {
 impl GenreSplitRule {
     pub fn new() -> Self {
         Self {
-            separators: vec![',', ';', '/', '|'],
+            // \0 是 ID3v2.4 多值标签的分隔符
+            separators: vec![',', ';', '/', '|', '\0'],
         }
     }
 }
@@ -618,6 +842,13 @@ pub struct GenreNormalizeRule {
 
 impl GenreNormalizeRule {
     pub fn new() -> Self {
+        Self {
+            mappings: Self::default_mappings(),
+        }
+    }
+
+    /// 内置的流派别名映射
+    pub fn default_mappings() -> HashMap<String, String> {
         let mut mappings = HashMap::new();
 
         // 常见流派别名映射
@@ -649,7 +880,7 @@ impl GenreNormalizeRule {
         mappings.insert("trip hop".to_string(), "Trip-Hop".to_string());
         mappings.insert("triphop".to_string(), "Trip-Hop".to_string());
 
-        Self { mappings }
+        mappings
     }
 
     pub fn with_mappings(mappings: HashMap<String, String>) -> Self {
@@ -691,6 +922,9 @@ impl MetadataRule for GenreNormalizeRule {
                     })
             })
             .collect();
+        // 不同写法规范化后可能相同，只保留第一个
+        let mut seen = std::collections::HashSet::new();
+        ctx.genres.retain(|g| seen.insert(g.to_lowercase()));
     }
 }
 
@@ -841,6 +1075,194 @@ impl MetadataRule for TrackNumberCleanupRule {
     }
 }
 
+/// 乐章标题规则：有乐章序号时以罗马数字作为标题前缀，如 "II. Andante"
+///
+/// 默认不启用，需在规则文件中打开 movement_titles
+pub struct MovementTitleRule;
+
+impl MovementTitleRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn roman(mut number: i32) -> String {
+        const NUMERALS: [(i32, &str); 13] = [
+            (1000, "M"),
+            (900, "CM"),
+            (500, "D"),
+            (400, "CD"),
+            (100, "C"),
+            (90, "XC"),
+            (50, "L"),
+            (40, "XL"),
+            (10, "X"),
+            (9, "IX"),
+            (5, "V"),
+            (4, "IV"),
+            (1, "I"),
+        ];
+        let mut result = String::new();
+        for (value, numeral) in NUMERALS {
+            while number >= value {
+                result.push_str(numeral);
+                number -= value;
+            }
+        }
+        result
+    }
+}
+
+impl MetadataRule for MovementTitleRule {
+    fn name(&self) -> &str {
+        "movement_title"
+    }
+
+    fn priority(&self) -> i32 {
+        60
+    }
+
+    fn apply(&self, ctx: &mut RuleContext) {
+        let Some(number) = ctx
+            .extra
+            .get("movement_number")
+            .and_then(|n| n.parse::<i32>().ok())
+            .filter(|n| *n > 0 && *n < 4000)
+        else {
+            return;
+        };
+        let prefix = format!("{}. ", Self::roman(number));
+        if ctx.title.is_empty() || ctx.title.starts_with(&prefix) {
+            return;
+        }
+        ctx.title = format!("{}{}", prefix, ctx.title);
+    }
+}
+
+/// 自定义规则作用的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleField {
+    Title,
+    Album,
+    Artist,
+    Genre,
+}
+
+impl TryFrom<&str> for RuleField {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "title" => Ok(RuleField::Title),
+            "album" => Ok(RuleField::Album),
+            "artist" => Ok(RuleField::Artist),
+            "genre" => Ok(RuleField::Genre),
+            _ => Err(format!("invalid rule field:{}", value)),
+        }
+    }
+}
+
+/// 正则替换规则：由管理员在配置中定义，对指定字段做查找替换
+pub struct RegexReplaceRule {
+    name: String,
+    field: RuleField,
+    pattern: Regex,
+    replacement: String,
+    priority: i32,
+}
+
+impl RegexReplaceRule {
+    pub fn new(
+        name: String,
+        field: RuleField,
+        pattern: Regex,
+        replacement: String,
+        priority: i32,
+    ) -> Self {
+        Self {
+            name,
+            field,
+            pattern,
+            replacement,
+            priority,
+        }
+    }
+
+    fn replace(&self, value: &str) -> String {
+        self.pattern
+            .replace_all(value, self.replacement.as_str())
+            .trim()
+            .to_string()
+    }
+}
+
+impl MetadataRule for RegexReplaceRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn apply(&self, ctx: &mut RuleContext) {
+        match self.field {
+            RuleField::Title => ctx.title = self.replace(&ctx.title),
+            RuleField::Album => ctx.album = self.replace(&ctx.album),
+            RuleField::Artist => {
+                // 分割前作用于原始字符串，分割后作用于每个艺术家
+                if ctx.artists.is_empty() {
+                    ctx.raw_artist = self.replace(&ctx.raw_artist);
+                } else {
+                    for artist in ctx.artists.iter_mut() {
+                        artist.name = self.replace(&artist.name);
+                    }
+                    ctx.artists.retain(|a| !a.name.is_empty());
+                }
+            }
+            RuleField::Genre => {
+                // 分割前作用于原始字符串，分割后作用于每个流派
+                if ctx.genres.is_empty() {
+                    ctx.raw_genre = self.replace(&ctx.raw_genre);
+                } else {
+                    for genre in ctx.genres.iter_mut() {
+                        *genre = self.replace(genre);
+                    }
+                    ctx.genres.retain(|g| !g.is_empty());
+                }
+            }
+        }
+    }
+}
+
+/// 覆盖内置规则优先级的包装
+pub struct PriorityOverrideRule {
+    inner: Arc<dyn MetadataRule>,
+    priority: i32,
+}
+
+impl PriorityOverrideRule {
+    pub fn new(inner: Arc<dyn MetadataRule>, priority: i32) -> Self {
+        Self { inner, priority }
+    }
+}
+
+impl MetadataRule for PriorityOverrideRule {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn apply(&self, ctx: &mut RuleContext) {
+        self.inner.apply(ctx)
+    }
+
+    fn should_apply(&self, ctx: &RuleContext) -> bool {
+        self.inner.should_apply(ctx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -979,6 +1401,23 @@ mod tests {
         assert_eq!(ctx.genres[1], "R&B");
     }
 
+    #[test]
+    fn test_multi_valued_genre_tag() {
+        let engine = MetadataRuleEngine::with_default_rules();
+        let mut ctx = RuleContext::new(
+            "Test".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            "Rock\0hip hop\0rock".to_string(),
+            None,
+            None,
+        );
+
+        engine.execute(&mut ctx);
+
+        assert_eq!(ctx.genres, vec!["Rock".to_string(), "Hip-Hop".to_string()]);
+    }
+
     #[test]
     fn test_year_extract_rule() {
         let rule = YearExtractRule::new();
@@ -996,6 +1435,30 @@ mod tests {
         assert_eq!(ctx.year, Some(2019));
     }
 
+    #[test]
+    fn test_movement_title_rule() {
+        let rule = MovementTitleRule::new();
+        let mut ctx = RuleContext::new(
+            "Allegro con brio".to_string(),
+            "Artist".to_string(),
+            "Symphony No. 5".to_string(),
+            "Classical".to_string(),
+            None,
+            Some(1),
+        );
+        rule.apply(&mut ctx);
+        assert_eq!(ctx.title, "Allegro con brio");
+
+        ctx.extra
+            .insert("movement_number".to_string(), "4".to_string());
+        rule.apply(&mut ctx);
+        assert_eq!(ctx.title, "IV. Allegro con brio");
+
+        // 重复执行不会叠加前缀
+        rule.apply(&mut ctx);
+        assert_eq!(ctx.title, "IV. Allegro con brio");
+    }
+
     #[test]
     fn test_full_engine() {
         let engine = MetadataRuleEngine::with_default_rules();
@@ -1092,6 +1555,26 @@ mod tests {
         assert_eq!(ctx.extra.get("album_version"), Some(&"港台版".to_string()));
     }
 
+    #[test]
+    fn test_album_cleanup_remaster_year() {
+        let engine = MetadataRuleEngine::with_default_rules();
+
+        let mut ctx = RuleContext::new(
+            "Test".to_string(),
+            "Artist".to_string(),
+            "Abbey Road (2019 Remaster)".to_string(),
+            "Rock".to_string(),
+            None,
+            None,
+        );
+        engine.execute(&mut ctx);
+        assert_eq!(ctx.album, "Abbey Road");
+        assert_eq!(
+            ctx.extra.get("album_version"),
+            Some(&"2019 Remaster".to_string())
+        );
+    }
+
     #[test]
     fn test_album_cleanup_watermark() {
         let engine = MetadataRuleEngine::with_default_rules();
@@ -1129,4 +1612,127 @@ mod tests {
         assert_eq!(ctx.album, "经典名曲");
         assert_eq!(ctx.extra.get("disc_number"), Some(&"2".to_string()));
     }
+
+    #[test]
+    fn test_album_cleanup_disc_subtitle() {
+        let engine = MetadataRuleEngine::with_default_rules();
+
+        // 测试: Greatest Hits (Disc 2: Live)
+        let mut ctx = RuleContext::new(
+            "Test".to_string(),
+            "Artist".to_string(),
+            "Greatest Hits (Disc 2: Live)".to_string(),
+            "Pop".to_string(),
+            None,
+            None,
+        );
+        engine.execute(&mut ctx);
+        assert_eq!(ctx.album, "Greatest Hits");
+        assert_eq!(ctx.extra.get("disc_number"), Some(&"2".to_string()));
+        assert_eq!(ctx.extra.get("disc_subtitle"), Some(&"Live".to_string()));
+    }
+
+    #[test]
+    fn test_execute_traced_records_each_rule() {
+        let engine = MetadataRuleEngine::with_default_rules();
+        let mut ctx = RuleContext::new(
+            "Song".to_string(),
+            "A, B".to_string(),
+            "Album (1999)".to_string(),
+            "Pop".to_string(),
+            None,
+            None,
+        );
+        let traces = engine.execute_traced(&mut ctx);
+
+        assert_eq!(traces.len(), engine.rules().len());
+        let split = traces.iter().find(|t| t.rule == "artist_split").unwrap();
+        assert_eq!(split.context.artists.len(), 2);
+        let year = traces.iter().find(|t| t.rule == "year_extract").unwrap();
+        assert!(year.applied);
+        assert_eq!(traces.last().unwrap().context.year, Some(1999));
+    }
+
+    #[test]
+    fn test_protected_artists_are_not_split() {
+        let protected = ProtectedArtists::new(vec![
+            "AC/DC".to_string(),
+            "Simon & Garfunkel".to_string(),
+            "Earth, Wind & Fire".to_string(),
+        ])
+        .unwrap();
+        let mut engine = MetadataRuleEngine::new();
+        engine.add_rule(Arc::new(
+            ArtistRoleExtractRule::new().with_protected(protected.clone()),
+        ));
+        engine.add_rule(Arc::new(ArtistSplitRule::new().with_protected(protected)));
+        engine.sort_rules();
+
+        let mut ctx = RuleContext::new(
+            "Test".to_string(),
+            "ac/dc, Earth, Wind & Fire & Simon & Garfunkel".to_string(),
+            "Album".to_string(),
+            "Rock".to_string(),
+            None,
+            None,
+        );
+        engine.execute(&mut ctx);
+
+        let names: Vec<&str> = ctx.artists.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["AC/DC", "Earth, Wind & Fire", "Simon & Garfunkel"]);
+    }
+
+    #[test]
+    fn test_artists_tag_bypasses_split() {
+        let engine = MetadataRuleEngine::with_default_rules();
+        let mut ctx = RuleContext::new(
+            "Test".to_string(),
+            "Simon & Garfunkel".to_string(),
+            "Album".to_string(),
+            "Folk".to_string(),
+            None,
+            None,
+        )
+        .with_raw_artists(vec!["Simon & Garfunkel".to_string()]);
+        engine.execute(&mut ctx);
+
+        assert_eq!(ctx.artists.len(), 1);
+        assert_eq!(ctx.artists[0].name, "Simon & Garfunkel");
+    }
+
+    #[test]
+    fn test_protected_artists_match_whole_words_only() {
+        let protected = ProtectedArtists::new(vec![
+            "AC".to_string(),
+            "Earth".to_string(),
+            "+44".to_string(),
+            "Sunn O)))".to_string(),
+        ])
+        .unwrap();
+
+        let unmasked = |value: &str| {
+            let masked = protected.mask(value);
+            assert_eq!(protected.unmask(&masked), value);
+            !masked.contains(ProtectedArtists::MARK)
+        };
+        assert!(unmasked("Jack & Diane"));
+        assert!(unmasked("Earthling, Moon"));
+        assert!(unmasked("Macy, Gray"));
+        assert_eq!(
+            protected.mask("ac & Jack"),
+            format!("{m}3{m} & Jack", m = ProtectedArtists::MARK)
+        );
+        // 以符号开头或结尾的名称只在字母数字一侧检查边界
+        assert!(!unmasked("+44, Blink"));
+        assert!(!unmasked("Sunn O))) & Boris"));
+        assert!(!unmasked("(Earth)"));
+    }
+
+    #[test]
+    fn test_protected_artists_reject_control_characters() {
+        assert!(ProtectedArtists::new(vec!["Bad\u{1}Name".to_string()]).is_err());
+        assert!(ProtectedArtists::new(vec!["  ".to_string()])
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -0,0 +1,157 @@
+use application::command::silence_analysis::{SilenceDetector, SilenceInterval, SilenceScan};
+use application::error::AppError;
+use async_trait::async_trait;
+use domain::value::MediaPath;
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// 低于该音量视为静音
+const NOISE_THRESHOLD: &str = "-60dB";
+/// 至少持续这么久才算一段静音（秒）
+const MIN_SILENCE_SECONDS: &str = "0.5";
+
+/// 使用 ffmpeg 的 silencedetect 滤镜解码整首歌，从 stderr 中读取静音区间
+pub struct FfmpegSilenceDetector {
+    ffmpeg_path: String,
+}
+
+impl FfmpegSilenceDetector {
+    pub fn new(ffmpeg_path: String) -> Self {
+        Self { ffmpeg_path }
+    }
+
+    fn build_arguments(input: &str) -> Vec<String> {
+        vec![
+            "-hide_banner".into(),
+            "-nostats".into(),
+            "-i".into(),
+            input.to_string(),
+            "-vn".into(),
+            "-af".into(),
+            format!(
+                "silencedetect=noise={}:d={}",
+                NOISE_THRESHOLD, MIN_SILENCE_SECONDS
+            ),
+            "-f".into(),
+            "null".into(),
+            "-".into(),
+        ]
+    }
+
+    /// 解析 `silence_start: 12.3`、`silence_end: 14.5 | silence_duration: 2.2`
+    /// 以及 `Duration: 00:03:05.12` 这样的日志行
+    fn parse_output(stderr: &str) -> SilenceScan {
+        let mut scan = SilenceScan::default();
+        for line in stderr.lines() {
+            if let Some(start) = value_after(line, "silence_start:") {
+                scan.intervals.push(SilenceInterval {
+                    start: seconds_to_ms(start),
+                    end: None,
+                });
+            } else if let Some(end) = value_after(line, "silence_end:") {
+                if let Some(last) = scan.intervals.last_mut().filter(|i| i.end.is_none()) {
+                    last.end = Some(seconds_to_ms(end));
+                }
+            } else if scan.duration.is_none() {
+                scan.duration = value_after(line, "Duration:").and_then(parse_timestamp);
+            }
+        }
+        scan
+    }
+}
+
+fn value_after<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let rest = &line[line.find(key)? + key.len()..];
+    rest.split(|c: char| c.is_whitespace() || c == ',' || c == '|')
+        .find(|s| !s.is_empty())
+}
+
+fn seconds_to_ms(value: &str) -> i64 {
+    value
+        .parse::<f64>()
+        .map(|s| (s.max(0.0) * 1000.0).round() as i64)
+        .unwrap_or(0)
+}
+
+/// HH:MM:SS.xx 转为毫秒，`N/A` 返回 None
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let mut parts = value.splitn(3, ':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(((hours * 3600.0 + minutes * 60.0 + seconds) * 1000.0).round() as i64)
+}
+
+#[async_trait]
+impl SilenceDetector for FfmpegSilenceDetector {
+    async fn detect(&self, path: &MediaPath) -> Result<SilenceScan, AppError> {
+        if !matches!(path.protocol.as_str(), "local" | "") {
+            return Err(AppError::NotSupported(format!(
+                "Silence analysis is not supported for protocol: {}",
+                path.protocol
+            )));
+        }
+        let args = Self::build_arguments(&path.path);
+        log::debug!(
+            "[FFmpeg] Silence detect command: {} {}",
+            self.ffmpeg_path,
+            args.join(" ")
+        );
+
+        let result = Command::new(&self.ffmpeg_path)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| AppError::UnknownError(format!("Failed to execute FFmpeg: {}", e)))?;
+
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        if !result.status.success() {
+            return Err(AppError::UnknownError(format!(
+                "FFmpeg failed to analyze silence: {}",
+                stderr.lines().last().unwrap_or_default()
+            )));
+        }
+        Ok(Self::parse_output(&stderr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output() {
+        let stderr = "\
+Input #0, flac, from '/music/a/01 Song.flac':
+  Duration: 00:03:05.12, start: 0.000000, bitrate: 912 kb/s
+[silencedetect @ 0x55d0] silence_start: 0
+[silencedetect @ 0x55d0] silence_end: 1.2034 | silence_duration: 1.2034
+[silencedetect @ 0x55d0] silence_start: 90.5
+[silencedetect @ 0x55d0] silence_end: 92 | silence_duration: 1.5
+[silencedetect @ 0x55d0] silence_start: 181.25
+size=N/A time=00:03:05.12 bitrate=N/A speed= 412x";
+        let scan = FfmpegSilenceDetector::parse_output(stderr);
+
+        assert_eq!(scan.duration, Some(185_120));
+        assert_eq!(
+            scan.intervals,
+            vec![
+                SilenceInterval {
+                    start: 0,
+                    end: Some(1203)
+                },
+                SilenceInterval {
+                    start: 90_500,
+                    end: Some(92_000)
+                },
+                SilenceInterval {
+                    start: 181_250,
+                    end: None
+                },
+            ]
+        );
+    }
+}
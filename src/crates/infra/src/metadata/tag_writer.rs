@@ -0,0 +1,142 @@
+use application::command::metadata_edit::{AudioTagWriter, TagUpdate};
+use application::error::AppError;
+use async_trait::async_trait;
+use domain::value::MediaPath;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// 使用 ffmpeg 回写标签：复制音频流到临时文件并覆盖元数据，成功后替换原文件
+pub struct FfmpegTagWriter {
+    ffmpeg_path: String,
+}
+
+impl FfmpegTagWriter {
+    pub fn new(ffmpeg_path: String) -> Self {
+        Self { ffmpeg_path }
+    }
+
+    fn temp_path(path: &Path) -> PathBuf {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // 保留原扩展名，ffmpeg 需要根据扩展名选择封装格式
+        path.with_file_name(format!(".rhythm-tags-{}", file_name))
+    }
+
+    fn build_arguments(input: &Path, output: &Path, tags: &TagUpdate) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "-y".into(),
+            "-v".into(),
+            "error".into(),
+            "-i".into(),
+            input.to_string_lossy().to_string(),
+            "-map".into(),
+            "0".into(),
+            "-c".into(),
+            "copy".into(),
+            "-map_metadata".into(),
+            "0".into(),
+        ];
+        let mut push = |key: &str, value: String| {
+            args.push("-metadata".into());
+            args.push(format!("{}={}", key, value));
+        };
+        if let Some(title) = &tags.title {
+            push("title", title.clone());
+        }
+        if let Some(album) = &tags.album {
+            push("album", album.clone());
+        }
+        if let Some(artist) = &tags.artist {
+            push("artist", artist.clone());
+        }
+        if let Some(track) = tags.track_number {
+            push("track", track.to_string());
+        }
+        if let Some(disc) = tags.disc_number {
+            push("disc", disc.to_string());
+        }
+        if let Some(year) = tags.year {
+            push("date", year.to_string());
+        }
+        let is_mp3 = input
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("mp3"));
+        if is_mp3 {
+            args.push("-id3v2_version".into());
+            args.push("3".into());
+        }
+        args.push(output.to_string_lossy().to_string());
+        args
+    }
+}
+
+#[async_trait]
+impl AudioTagWriter for FfmpegTagWriter {
+    async fn write_tags(&self, path: &MediaPath, tags: &TagUpdate) -> Result<(), AppError> {
+        if !matches!(path.protocol.as_str(), "local" | "") {
+            return Err(AppError::InvalidInput(format!(
+                "Tag writing is not supported for protocol: {}",
+                path.protocol
+            )));
+        }
+        let input = PathBuf::from(&path.path);
+        let output = Self::temp_path(&input);
+        let args = Self::build_arguments(&input, &output, tags);
+        log::debug!(
+            "[FFmpeg] Tag write command: {} {}",
+            self.ffmpeg_path,
+            args.join(" ")
+        );
+
+        let result = Command::new(&self.ffmpeg_path)
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| AppError::UnknownError(format!("Failed to execute FFmpeg: {}", e)))?;
+
+        if !result.status.success() {
+            let _ = tokio::fs::remove_file(&output).await;
+            return Err(AppError::UnknownError(format!(
+                "FFmpeg failed to write tags: {}",
+                String::from_utf8_lossy(&result.stderr)
+            )));
+        }
+
+        tokio::fs::rename(&output, &input)
+            .await
+            .map_err(|e| AppError::UnknownError(format!("Failed to replace file: {}", e)))?;
+        log::info!("Tags written to {}", path.path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_arguments() {
+        let input = PathBuf::from("/music/a/01 Song.mp3");
+        let output = FfmpegTagWriter::temp_path(&input);
+        assert_eq!(output, PathBuf::from("/music/a/.rhythm-tags-01 Song.mp3"));
+
+        let tags = TagUpdate {
+            title: Some("Song".to_string()),
+            track_number: Some(1),
+            ..Default::default()
+        };
+        let args = FfmpegTagWriter::build_arguments(&input, &output, &tags);
+        let joined = args.join(" ");
+
+        assert!(joined.contains("-metadata title=Song"));
+        assert!(joined.contains("-metadata track=1"));
+        assert!(!joined.contains("album="));
+        assert!(joined.contains("-id3v2_version 3"));
+        assert_eq!(args.last().unwrap(), "/music/a/.rhythm-tags-01 Song.mp3");
+    }
+}
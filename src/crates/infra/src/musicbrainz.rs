@@ -0,0 +1,234 @@
+use crate::config::MusicBrainzConfig;
+use application::query::shared::DiscographySource;
+use async_trait::async_trait;
+use model::discography::ReleaseGroup;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// MusicBrainz 要求每秒最多一次请求
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// 浏览接口每页的最大条数
+const PAGE_SIZE: usize = 100;
+/// 发行组过多的艺术家（古典作曲家等）只取前面的部分
+const MAX_RELEASE_GROUPS: usize = 2000;
+/// 按名称搜索艺术家时接受的最低匹配分数
+const MIN_ARTIST_SCORE: u32 = 90;
+
+#[derive(Debug, Deserialize)]
+struct ArtistSearch {
+    #[serde(default)]
+    artists: Vec<ArtistHit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtistHit {
+    id: String,
+    name: String,
+    #[serde(default)]
+    score: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseGroupPage {
+    #[serde(rename = "release-group-count", default)]
+    count: usize,
+    #[serde(rename = "release-groups", default)]
+    release_groups: Vec<ReleaseGroupItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseGroupItem {
+    id: String,
+    title: String,
+    #[serde(rename = "primary-type")]
+    primary_type: Option<String>,
+    #[serde(rename = "secondary-types", default)]
+    secondary_types: Vec<String>,
+    #[serde(rename = "first-release-date")]
+    first_release_date: Option<String>,
+}
+
+impl From<ReleaseGroupItem> for ReleaseGroup {
+    fn from(item: ReleaseGroupItem) -> Self {
+        Self {
+            mbz_id: item.id,
+            title: item.title,
+            primary_type: item.primary_type,
+            secondary_types: item.secondary_types,
+            // 未知日期返回空字符串
+            first_release_date: item.first_release_date.filter(|date| !date.is_empty()),
+        }
+    }
+}
+
+/// 同名艺术家较多，只接受名称完全相同且分数足够高的结果
+fn pick_artist(search: ArtistSearch, name: &str) -> Option<String> {
+    let name = name.to_lowercase();
+    search
+        .artists
+        .into_iter()
+        .find(|hit| hit.score >= MIN_ARTIST_SCORE && hit.name.to_lowercase() == name)
+        .map(|hit| hit.id)
+}
+
+/// Lucene 查询语法中的特殊字符需要转义
+fn escape_query(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// MusicBrainz Web Service v2 客户端，所有请求串行并保持间隔
+pub struct MusicBrainzClient {
+    http: reqwest::Client,
+    base_url: String,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl MusicBrainzClient {
+    pub fn new(config: &MusicBrainzConfig) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .user_agent(config.user_agent.as_str())
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            http,
+            base_url: config.base_url.clone(),
+            last_request: Mutex::new(None),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        query: &[(&str, String)],
+    ) -> anyhow::Result<T> {
+        // 持有锁直到发出请求，并发的查询依次等待
+        let mut last_request = self.last_request.lock().await;
+        if let Some(at) = *last_request {
+            let wait = REQUEST_INTERVAL.saturating_sub(at.elapsed());
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+        }
+        *last_request = Some(Instant::now());
+        let rsp = self
+            .http
+            .get(format!("{}/ws/2/{}", self.base_url, resource))
+            .query(query)
+            .query(&[("fmt", "json")])
+            .send()
+            .await?
+            .error_for_status()?;
+        drop(last_request);
+        Ok(rsp.json().await?)
+    }
+}
+
+#[async_trait]
+impl DiscographySource for MusicBrainzClient {
+    async fn find_artist(&self, name: &str) -> anyhow::Result<Option<String>> {
+        let query = format!("artist:\"{}\"", escape_query(name));
+        let search: ArtistSearch = self
+            .get("artist", &[("query", query), ("limit", "10".to_string())])
+            .await?;
+        Ok(pick_artist(search, name))
+    }
+
+    async fn release_groups(&self, mbz_artist_id: &str) -> anyhow::Result<Vec<ReleaseGroup>> {
+        let mut release_groups = Vec::new();
+        loop {
+            let page: ReleaseGroupPage = self
+                .get(
+                    "release-group",
+                    &[
+                        ("artist", mbz_artist_id.to_string()),
+                        ("limit", PAGE_SIZE.to_string()),
+                        ("offset", release_groups.len().to_string()),
+                    ],
+                )
+                .await?;
+            let fetched = page.release_groups.len();
+            release_groups.extend(page.release_groups.into_iter().map(ReleaseGroup::from));
+            if fetched == 0
+                || release_groups.len() >= page.count
+                || release_groups.len() >= MAX_RELEASE_GROUPS
+            {
+                return Ok(release_groups);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_release_group_page() {
+        let json = r#"{
+            "release-group-count": 2,
+            "release-group-offset": 0,
+            "release-groups": [
+                {
+                    "id": "b1392450-e666-3926-a536-22c65f834433",
+                    "title": "OK Computer",
+                    "primary-type": "Album",
+                    "secondary-types": [],
+                    "first-release-date": "1997-05-21"
+                },
+                {
+                    "id": "c0d6dc9c-7bcf-4ad5-a0c9-e6a5e8d3bd0f",
+                    "title": "I Might Be Wrong",
+                    "primary-type": "Album",
+                    "secondary-types": ["Live"],
+                    "first-release-date": ""
+                }
+            ]
+        }"#;
+        let page: ReleaseGroupPage = serde_json::from_str(json).unwrap();
+        assert_eq!(page.count, 2);
+        let groups: Vec<ReleaseGroup> = page
+            .release_groups
+            .into_iter()
+            .map(ReleaseGroup::from)
+            .collect();
+        assert_eq!(groups[0].title, "OK Computer");
+        assert_eq!(groups[0].first_release_date.as_deref(), Some("1997-05-21"));
+        assert_eq!(groups[1].secondary_types, ["Live"]);
+        assert_eq!(groups[1].first_release_date, None);
+    }
+
+    #[test]
+    fn picks_exact_name_with_high_score() {
+        let json = r#"{
+            "artists": [
+                {"id": "tribute", "name": "Radiohead Tribute Band", "score": 95},
+                {"id": "a74b1b7f", "name": "Radiohead", "score": 100}
+            ]
+        }"#;
+        let search: ArtistSearch = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            pick_artist(search, "radiohead"),
+            Some("a74b1b7f".to_string())
+        );
+
+        let search: ArtistSearch =
+            serde_json::from_str(r#"{"artists": [{"id": "x", "name": "Radiohead", "score": 40}]}"#)
+                .unwrap();
+        assert_eq!(pick_artist(search, "Radiohead"), None);
+    }
+
+    #[test]
+    fn escapes_quotes_in_search_terms() {
+        assert_eq!(escape_query(r#"The "Band""#), r#"The \"Band\""#);
+    }
+}
@@ -1,9 +1,10 @@
-use unidecode::unidecode;
+use unidecode::{unidecode, unidecode_char};
 
 use application::command::album::AlbumNameNormalizer;
 use application::command::artist::ArtistNameNormalizer;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 static UTF8_TO_ASCII: Lazy<HashMap<char, char>> = Lazy::new(|| {
     let mut map = HashMap::new();
@@ -50,36 +51,110 @@ pub fn sanitize_no_article(original_value: &str, articles: &[String]) -> String
     clear(without_article.trim().to_lowercase().as_str())
 }
 
+/// 可在运行时替换的冠词列表，共享同一实例的规范化器立即使用新值
+#[derive(Debug, Clone, Default)]
+pub struct SharedArticles(Arc<RwLock<Vec<String>>>);
+
+impl SharedArticles {
+    pub fn new(articles: &[String]) -> Self {
+        Self(Arc::new(RwLock::new(articles.to_vec())))
+    }
+
+    pub fn get(&self) -> Vec<String> {
+        self.0.read().unwrap().clone()
+    }
+
+    pub fn set(&self, articles: Vec<String>) {
+        *self.0.write().unwrap() = articles;
+    }
+}
+
 pub struct ArtistNameNormalizerImpl {
-    ignored_articles: Vec<String>,
+    ignored_articles: SharedArticles,
 }
 
 impl ArtistNameNormalizerImpl {
     pub fn new(ignored_articles: &[String]) -> Self {
-        Self {
-            ignored_articles: ignored_articles.to_vec(),
-        }
+        Self::with_shared(SharedArticles::new(ignored_articles))
+    }
+
+    pub fn with_shared(ignored_articles: SharedArticles) -> Self {
+        Self { ignored_articles }
     }
 }
 impl ArtistNameNormalizer for ArtistNameNormalizerImpl {
     fn normalize(&self, name: &String) -> String {
-        sanitize_no_article(name, &self.ignored_articles)
+        sanitize_no_article(name, &self.ignored_articles.0.read().unwrap())
+    }
+
+    fn index_name(&self, artist_name: &str) -> String {
+        let name = artist_name.trim().to_lowercase();
+        for article in self.ignored_articles.0.read().unwrap().iter() {
+            let prefix = format!("{} ", article.to_lowercase());
+            if let Some(rest) = name.strip_prefix(&prefix) {
+                return rest.trim_start().to_string();
+            }
+        }
+        name
+    }
+
+    fn index_initial(&self, index_name: &str, romanize: bool) -> Option<char> {
+        let c = index_name.chars().find(|c| c.is_alphanumeric())?;
+        if c.is_ascii() {
+            return Some(c.to_ascii_uppercase());
+        }
+        if !romanize && !is_latin(c) {
+            return None;
+        }
+        unidecode_char(c)
+            .chars()
+            .find(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
     }
 }
 
+/// Latin-1 补充、扩展 A/B 和附加扩展区的字母
+fn is_latin(c: char) -> bool {
+    matches!(c, '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}')
+}
+
 pub struct AlbumNameNormalizerImpl {
-    ignored_articles: Vec<String>,
+    ignored_articles: SharedArticles,
 }
 
 impl AlbumNameNormalizerImpl {
     pub fn new(ignored_articles: &[String]) -> Self {
-        Self {
-            ignored_articles: ignored_articles.to_vec(),
-        }
+        Self::with_shared(SharedArticles::new(ignored_articles))
+    }
+
+    pub fn with_shared(ignored_articles: SharedArticles) -> Self {
+        Self { ignored_articles }
     }
 }
 impl AlbumNameNormalizer for AlbumNameNormalizerImpl {
     fn normalize(&self, name: &String) -> String {
-        sanitize_no_article(name, &self.ignored_articles)
+        sanitize_no_article(name, &self.ignored_articles.0.read().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_initial_honors_articles_and_scripts() {
+        let normalizer = ArtistNameNormalizerImpl::new(&["The".to_string(), "Les".to_string()]);
+        let initial = |name: &str, romanize: bool| {
+            normalizer.index_initial(&normalizer.index_name(name), romanize)
+        };
+        assert_eq!(normalizer.index_name("THE Beatles"), "beatles");
+        assert_eq!(initial("The Beatles", true), Some('B'));
+        assert_eq!(initial("Les Rita Mitsouko", true), Some('R'));
+        assert_eq!(initial("Édith Piaf", false), Some('E'));
+        assert_eq!(initial("...And You Will Know Us", false), Some('A'));
+        assert_eq!(initial("周杰伦", true), Some('Z'));
+        assert_eq!(initial("周杰伦", false), None);
+        assert_eq!(initial("あいみょん", true), Some('A'));
+        assert_eq!(initial("!!!", true), None);
     }
 }
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use domain::audio_file::{AudioFile, AudioFileError, AudioFileRepository};
-use domain::value::{AudioFileId, MediaPath};
+use domain::value::{AlbumId, ArtistId, AudioFileId, GenreId, MediaPath};
 use futures::stream::{self, StreamExt};
 use log::{error, info, warn};
 use lru::LruCache;
@@ -14,6 +14,7 @@ use tokio::sync::{RwLock, Semaphore};
 // 导入 memtable 模块
 use super::super::memtable::{
     IndexValue, IndexMatch, Memtable, MemtableContext, MemtablePersister, MemtableValue,
+    ManagedMemtable,
 };
 
 #[derive(Clone)]
@@ -145,6 +146,26 @@ where
         }
     }
 
+    async fn find_by_album(&self, album_id: &AlbumId) -> Result<Vec<AudioFile>, AudioFileError> {
+        // 列表查询不走缓存
+        self.inner.find_by_album(album_id).await
+    }
+
+    async fn find_by_artist(&self, artist_id: &ArtistId) -> Result<Vec<AudioFile>, AudioFileError> {
+        self.inner.find_by_artist(artist_id).await
+    }
+
+    async fn find_by_participant(
+        &self,
+        artist_id: &ArtistId,
+    ) -> Result<Vec<AudioFile>, AudioFileError> {
+        self.inner.find_by_participant(artist_id).await
+    }
+
+    async fn find_by_genre(&self, genre_id: &GenreId) -> Result<Vec<AudioFile>, AudioFileError> {
+        self.inner.find_by_genre(genre_id).await
+    }
+
     async fn delete(&self, id: &AudioFileId) -> Result<(), AudioFileError> {
         let id_i64 = id.as_i64();
 
@@ -343,6 +364,7 @@ where
         cache_capacity: usize,
         concurrency: usize,
         flush_timeout: Duration,
+        max_pending_flushes: usize,
     ) -> Arc<Self> {
         let memtable_size_threshold = cache_capacity.max(100);
 
@@ -361,6 +383,7 @@ where
             memtable_size_threshold,
             persister,
             flush_timeout,
+            max_pending_flushes,
         ));
 
         // 启动自动 flush 定时器（由 MemtableContext 自己管理）
@@ -396,6 +419,11 @@ where
     ///     Err(e) => eprintln!("Shutdown error: {}", e),
     /// }
     /// ```
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
     pub async fn shutdown_gracefully(
         &self,
         wait_duration: Duration,
@@ -477,6 +505,26 @@ where
         self.inner.find_by_path(path).await
     }
 
+    async fn find_by_album(&self, album_id: &AlbumId) -> Result<Vec<AudioFile>, AudioFileError> {
+        // memtable 没有专辑索引，只返回已落库的数据
+        self.inner.find_by_album(album_id).await
+    }
+
+    async fn find_by_artist(&self, artist_id: &ArtistId) -> Result<Vec<AudioFile>, AudioFileError> {
+        self.inner.find_by_artist(artist_id).await
+    }
+
+    async fn find_by_participant(
+        &self,
+        artist_id: &ArtistId,
+    ) -> Result<Vec<AudioFile>, AudioFileError> {
+        self.inner.find_by_participant(artist_id).await
+    }
+
+    async fn find_by_genre(&self, genre_id: &GenreId) -> Result<Vec<AudioFile>, AudioFileError> {
+        self.inner.find_by_genre(genre_id).await
+    }
+
     async fn delete(&self, id: &AudioFileId) -> Result<(), AudioFileError> {
         let id_i64 = id.as_i64();
 
@@ -14,6 +14,7 @@ use tokio::sync::{RwLock, Semaphore};
 // 导入通用 memtable 模块
 use super::super::memtable::{
     IndexValue, IndexMatch, Memtable, MemtableContext, MemtablePersister, MemtableValue,
+    ManagedMemtable,
 };
 
 #[derive(Clone)]
@@ -293,6 +294,7 @@ where
         cache_capacity: usize,
         concurrency: usize,
         flush_timeout: Duration,
+        max_pending_flushes: usize,
     ) -> Arc<Self> {
         let memtable_size_threshold = cache_capacity.max(100);
 
@@ -310,6 +312,7 @@ where
             memtable_size_threshold,
             persister,
             flush_timeout,
+            max_pending_flushes,
         ));
 
         memtable_context.start_auto_flush_timer();
@@ -320,6 +323,11 @@ where
         })
     }
 
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
     pub async fn shutdown_gracefully(
         &self,
         wait_duration: Duration,
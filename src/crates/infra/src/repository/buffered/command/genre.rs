@@ -14,6 +14,7 @@ use tokio::sync::{RwLock, Semaphore};
 // 导入通用 memtable 模块
 use super::super::memtable::{
     IndexValue, IndexMatch, Memtable, MemtableContext, MemtablePersister, MemtableValue,
+    ManagedMemtable,
 };
 
 #[derive(Clone)]
@@ -158,6 +159,11 @@ where
         }
     }
 
+    async fn find_all(&self) -> Result<Vec<Genre>, GenreError> {
+        // 列表查询不走缓存
+        self.inner.find_all().await
+    }
+
     async fn delete(&self, genre_id: GenreId) -> Result<(), GenreError> {
         let id_i64 = genre_id.as_i64();
 
@@ -350,6 +356,7 @@ where
         cache_capacity: usize,
         concurrency: usize,
         flush_timeout: Duration,
+        max_pending_flushes: usize,
     ) -> Arc<Self> {
         let memtable_size_threshold = cache_capacity.max(100);
 
@@ -367,6 +374,7 @@ where
             memtable_size_threshold,
             persister,
             flush_timeout,
+            max_pending_flushes,
         ));
 
         memtable_context.start_auto_flush_timer();
@@ -377,6 +385,11 @@ where
         })
     }
 
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
     pub async fn shutdown_gracefully(
         &self,
         wait_duration: Duration,
@@ -452,6 +465,11 @@ where
         self.inner.find_by_name(genre_name).await
     }
 
+    async fn find_all(&self) -> Result<Vec<Genre>, GenreError> {
+        // memtable 中的流派只在落库后可见
+        self.inner.find_all().await
+    }
+
     async fn delete(&self, genre_id: GenreId) -> Result<(), GenreError> {
         let id_i64 = genre_id.as_i64();
 
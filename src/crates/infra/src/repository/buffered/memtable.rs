@@ -1,11 +1,11 @@
 use async_trait::async_trait;
 use log::{error, info, warn};
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::{oneshot, RwLock, Semaphore};
 
 // 旋转原因枚举
 #[derive(Debug, Clone, Copy)]
@@ -486,16 +486,53 @@ pub trait MemtablePersister<K: MemtableKey, V: MemtableValue<K>>:
 // 2. 控制大小阈值触发的旋转
 // 3. 控制超时触发的旋转（自动 flush）
 // 4. 协调数据持久化
-// 5. 通过 oneshot channel 实现背压控制，防止 OOM
+// 5. 限制待 flush 的 immutable memtable 数量实现背压控制，防止 OOM
 pub struct MemtableContext<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> {
     name: String,
     active_memtable: Arc<RwLock<Memtable<K, V>>>,
-    immutable_memtable: Arc<RwLock<Option<Arc<RwLock<Memtable<K, V>>>>>>,
+    // 等待 flush 的 memtable，按旋转顺序排列（越靠后越新）
+    immutable_memtables: Arc<RwLock<Vec<Arc<RwLock<Memtable<K, V>>>>>>,
     active_size: Arc<AtomicUsize>,
     active_threshold_size: usize,
     persister: Arc<P>,
     rotate_time: Arc<RwLock<Option<Instant>>>,
     flush_timeout: Duration,
+    // 每个待 flush 的 memtable 占用一个许可，许可用完时旋转需要等待
+    pending_flushes: Arc<Semaphore>,
+    max_pending_flushes: usize,
+    // 最近一次 flush 的完成信号，下一次 flush 等待它
+    flush_chain: Arc<std::sync::Mutex<Option<oneshot::Receiver<()>>>>,
+    counters: Arc<MemtableCounters>,
+}
+
+// 运行计数，用于指标采集
+#[derive(Default)]
+struct MemtableCounters {
+    rotations: AtomicU64,
+    flushes: AtomicU64,
+    flush_errors: AtomicU64,
+    backpressure_waits: AtomicU64,
+    last_flush_micros: AtomicU64,
+    total_flush_micros: AtomicU64,
+}
+
+/// memtable 运行指标快照
+#[derive(Debug, Clone)]
+pub struct MemtableMetrics {
+    pub name: String,
+    /// active memtable 中的条数
+    pub active_size: usize,
+    pub threshold_size: usize,
+    /// 正在等待或进行 flush 的 memtable 数
+    pub pending_flushes: usize,
+    pub max_pending_flushes: usize,
+    pub rotations: u64,
+    pub flushes: u64,
+    pub flush_errors: u64,
+    /// 因待 flush 数达到上限而等待的旋转次数
+    pub backpressure_waits: u64,
+    pub last_flush_duration: Duration,
+    pub total_flush_duration: Duration,
 }
 
 impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableContext<K, V, P> {
@@ -506,16 +543,22 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
         threshold_size: usize,
         persister: Arc<P>,
         flush_timeout: Duration,
+        max_pending_flushes: usize,
     ) -> Self {
+        let max_pending_flushes = max_pending_flushes.max(1);
         Self {
             name,
             active_memtable: memtable,
-            immutable_memtable: Arc::new(RwLock::new(None)),
+            immutable_memtables: Arc::new(RwLock::new(Vec::new())),
             active_size: size,
             active_threshold_size: threshold_size,
             persister,
             rotate_time: Arc::new(RwLock::new(Some(Instant::now()))),
             flush_timeout,
+            pending_flushes: Arc::new(Semaphore::new(max_pending_flushes)),
+            max_pending_flushes,
+            flush_chain: Arc::new(std::sync::Mutex::new(None)),
+            counters: Arc::new(MemtableCounters::default()),
         }
     }
 
@@ -585,12 +628,46 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
         Self {
             name: self.name.clone(),
             active_memtable: self.active_memtable.clone(),
-            immutable_memtable: self.immutable_memtable.clone(),
+            immutable_memtables: self.immutable_memtables.clone(),
             active_size: self.active_size.clone(),
             active_threshold_size: self.active_threshold_size,
             persister: self.persister.clone(),
             rotate_time: self.rotate_time.clone(),
             flush_timeout: self.flush_timeout,
+            pending_flushes: self.pending_flushes.clone(),
+            max_pending_flushes: self.max_pending_flushes,
+            flush_chain: self.flush_chain.clone(),
+            counters: self.counters.clone(),
+        }
+    }
+
+    // 当前的运行指标
+    pub fn metrics(&self) -> MemtableMetrics {
+        let counters = &self.counters;
+        MemtableMetrics {
+            name: self.name.clone(),
+            active_size: self.size(),
+            threshold_size: self.active_threshold_size,
+            pending_flushes: self.max_pending_flushes - self.pending_flushes.available_permits(),
+            max_pending_flushes: self.max_pending_flushes,
+            rotations: counters.rotations.load(Ordering::Relaxed),
+            flushes: counters.flushes.load(Ordering::Relaxed),
+            flush_errors: counters.flush_errors.load(Ordering::Relaxed),
+            backpressure_waits: counters.backpressure_waits.load(Ordering::Relaxed),
+            last_flush_duration: Duration::from_micros(
+                counters.last_flush_micros.load(Ordering::Relaxed),
+            ),
+            total_flush_duration: Duration::from_micros(
+                counters.total_flush_micros.load(Ordering::Relaxed),
+            ),
+        }
+    }
+
+    // 等待所有进行中的 flush 完成
+    async fn wait_pending_flushes(&self) {
+        let permits = self.max_pending_flushes as u32;
+        if let Ok(permit) = self.pending_flushes.acquire_many(permits).await {
+            drop(permit);
         }
     }
 
@@ -691,11 +768,13 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
         }
         drop(memtable);
 
-        // 如果 active memtable 中没有找到，再从 immutable memtable 查询
-        let immutable = self.immutable_memtable.read().await;
-        if let Some(ref immutable_memtable) = *immutable {
+        // 如果 active memtable 中没有找到，再从 immutable memtable 查询（新的优先）
+        let immutables = self.immutable_memtables.read().await;
+        for immutable_memtable in immutables.iter().rev() {
             let memtable = immutable_memtable.read().await;
-            return memtable.get(key);
+            if let Some(value) = memtable.get(key) {
+                return Some(value);
+            }
         }
         None
     }
@@ -709,11 +788,13 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
         }
         drop(memtable);
 
-        // 如果 active memtable 中没有找到，再从 immutable memtable 查询
-        let immutable = self.immutable_memtable.read().await;
-        if let Some(ref immutable_memtable) = *immutable {
+        // 如果 active memtable 中没有找到，再从 immutable memtable 查询（新的优先）
+        let immutables = self.immutable_memtables.read().await;
+        for immutable_memtable in immutables.iter().rev() {
             let memtable = immutable_memtable.read().await;
-            return memtable.get_by_index(index_name, index_value);
+            if let Some(value) = memtable.get_by_index(index_name, index_value.clone()) {
+                return Some(value);
+            }
         }
         None
     }
@@ -728,8 +809,8 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
         drop(memtable);
 
         // 从 immutable memtable 查询
-        let immutable = self.immutable_memtable.read().await;
-        if let Some(ref immutable_memtable) = *immutable {
+        let immutables = self.immutable_memtables.read().await;
+        for immutable_memtable in immutables.iter().rev() {
             let memtable = immutable_memtable.read().await;
             results.extend(memtable.find_by_prefix(index_name, prefix));
         }
@@ -812,6 +893,8 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
         let size = self.active_size.load(Ordering::Relaxed);
 
         if size == 0 {
+            // 之前旋转出的 memtable 可能仍在 flush
+            self.wait_pending_flushes().await;
             info!(
                 "[{}] Memtable is empty, no need to flush during shutdown",
                 self.name
@@ -826,7 +909,9 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
 
         // 强制旋转 active memtable（即使没有达到阈值）
         let active_state = ActiveState::new(Arc::new(self.clone_without_generic()));
-        if let Some((_, flushed_size)) = active_state.rotate(RotateReason::Shutdown).await {
+        let rotated = active_state.rotate(RotateReason::Shutdown).await;
+        self.wait_pending_flushes().await;
+        if let Some((_, flushed_size)) = rotated {
             info!(
                 "[{}] Graceful shutdown: successfully flushed {} items",
                 self.name, flushed_size
@@ -842,6 +927,66 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> MemtableCo
     }
 }
 
+/// 缓冲仓库对外暴露的 memtable 句柄，用于指标采集和关闭时 flush
+#[async_trait]
+pub trait ManagedMemtable: Send + Sync {
+    fn name(&self) -> &str;
+    fn metrics(&self) -> MemtableMetrics;
+    /// 强制 flush 并等待写入完成，返回写入条数
+    async fn flush_on_shutdown(&self) -> Option<usize>;
+}
+
+#[async_trait]
+impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> ManagedMemtable
+    for MemtableContext<K, V, P>
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn metrics(&self) -> MemtableMetrics {
+        MemtableContext::metrics(self)
+    }
+
+    async fn flush_on_shutdown(&self) -> Option<usize> {
+        self.shutdown_gracefully().await
+    }
+}
+
+/// 收集所有缓冲仓库的 memtable
+#[derive(Clone, Default)]
+pub struct MemtableRegistry {
+    contexts: Arc<std::sync::Mutex<Vec<Arc<dyn ManagedMemtable>>>>,
+}
+
+impl MemtableRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, context: Arc<dyn ManagedMemtable>) {
+        self.contexts.lock().unwrap().push(context);
+    }
+
+    pub fn metrics(&self) -> Vec<MemtableMetrics> {
+        let contexts = self.contexts.lock().unwrap();
+        contexts.iter().map(|context| context.metrics()).collect()
+    }
+
+    /// 依次 flush 所有已注册的 memtable，返回写入的总条数
+    pub async fn flush_all(&self) -> usize {
+        let contexts = self.contexts.lock().unwrap().clone();
+        let mut total = 0;
+        for context in contexts {
+            if let Some(count) = context.flush_on_shutdown().await {
+                info!("[{}] Flushed {} items on shutdown", context.name(), count);
+                total += count;
+            }
+        }
+        total
+    }
+}
+
 // Active 状态：当前可写的 memtable
 pub struct ActiveState<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> {
     context: Arc<MemtableContext<K, V, P>>,
@@ -855,13 +1000,36 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> ActiveStat
     // 旋转 memtable：将当前的 active memtable 变为 immutable，创建新的 active
     // 返回旧的 memtable 和大小
     //
-    // 关键改进：使用 oneshot channel 确保每次 rotate 的 flush 完成后才能继续
-    // 这防止了 immutable memtables 堆积导致的 OOM
+    // 背压：每个待 flush 的 immutable memtable 占用一个许可，达到上限时
+    // 旋转会等待最早的 flush 完成，防止 immutable memtables 堆积导致 OOM。
+    // flush 按旋转顺序依次执行，保证同一个 key 的新值不会被旧值覆盖
     pub async fn rotate(&self, reason: RotateReason) -> Option<(Memtable<K, V>, usize)> {
         let start_time = Instant::now();
+        let name = &self.context.name;
+
+        // ✅ 在获取 active_memtable 写锁之前等待许可，等待期间读写不受影响
+        let permit = match self.context.pending_flushes.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                self.context
+                    .counters
+                    .backpressure_waits
+                    .fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "[{}] {} memtables pending flush, waiting before rotation (reason: {})",
+                    name, self.context.max_pending_flushes, reason
+                );
+                self.context
+                    .pending_flushes
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .ok()?
+            }
+        };
+
         let mut memtable = self.context.memtable().write().await;
         let old_size = memtable.len();
-        let name = &self.context.name;
 
         if old_size == 0 {
             // 如果 memtable 为空，不需要旋转
@@ -881,25 +1049,27 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> ActiveStat
         let new_memtable = Memtable::<K, V>::new();
         // 替换 memtable（move，不 clone）
         let old_memtable = std::mem::replace(&mut *memtable, new_memtable);
+        let immutable_memtable = Arc::new(RwLock::new(old_memtable.clone()));
 
-        // ⚠️ 关键：先克隆 old_memtable，稍后再设置 immutable_memtable
-        let old_memtable_for_immutable = old_memtable.clone();
+        // 持有写锁时接入 flush 链，保证 flush 顺序与旋转顺序一致
+        let (done_tx, done_rx) = oneshot::channel();
+        let previous_flush = self.context.flush_chain.lock().unwrap().replace(done_rx);
 
         // 重置大小计数器
         self.context.size_ref().store(0, Ordering::Relaxed);
 
         // ✅ 显式释放 active_memtable 写锁，允许新数据写入新的 active memtable
         drop(memtable);
-
-        // ✅ 关键改进：为这次 rotate 创建专属的 oneshot channel
-        // 避免多个 rotate 共享 receiver 导致的信号混乱和死锁
-        let (tx, rx) = oneshot::channel();
+        self.context
+            .counters
+            .rotations
+            .fetch_add(1, Ordering::Relaxed);
 
         // ✅ 在释放 active_memtable 锁之后，再获取其他锁，避免死锁
         // 将旧的 memtable 存储为 immutable memtable，供查询使用
         {
-            let mut immutable = self.context.immutable_memtable.write().await;
-            *immutable = Some(Arc::new(RwLock::new(old_memtable_for_immutable)));
+            let mut immutables = self.context.immutable_memtables.write().await;
+            immutables.push(immutable_memtable.clone());
         }
 
         // 重置旋转时间，开始新的超时周期
@@ -910,53 +1080,47 @@ impl<K: MemtableKey, V: MemtableValue<K>, P: MemtablePersister<K, V>> ActiveStat
             *rotate_time = Some(Instant::now());
         } // 锁在这里立即释放
 
-        // 在后台异步 flush immutable memtable
+        // 在后台异步 flush immutable memtable，flush 完成后归还许可
         let context = self.context.clone_without_generic();
-        let memtable_to_flush = old_memtable.clone();
-        let name_clone = name.clone();
         tokio::spawn(async move {
-            if let Err(e) = context.flush_immutable(&memtable_to_flush).await {
-                error!("[{}] Failed to flush memtable: {}", name_clone, e);
+            let _permit = permit;
+            if let Some(previous) = previous_flush {
+                // 上一次 flush 异常退出时 sender 被 drop，同样继续
+                let _ = previous.await;
+            }
+            let flush_start = Instant::now();
+            let result = {
+                let memtable = immutable_memtable.read().await;
+                context.flush_immutable(&memtable).await
+            };
+            let elapsed = flush_start.elapsed().as_micros() as u64;
+            let counters = &context.counters;
+            counters.flushes.fetch_add(1, Ordering::Relaxed);
+            counters.last_flush_micros.store(elapsed, Ordering::Relaxed);
+            counters
+                .total_flush_micros
+                .fetch_add(elapsed, Ordering::Relaxed);
+            if let Err(e) = result {
+                counters.flush_errors.fetch_add(1, Ordering::Relaxed);
+                error!("[{}] Failed to flush memtable: {}", context.name, e);
             }
 
-            // flush 完成后清除 immutable memtable
+            // flush 完成后移除对应的 immutable memtable
             {
-                let mut immutable = context.immutable_memtable.write().await;
-                *immutable = None;
+                let mut immutables = context.immutable_memtables.write().await;
+                immutables.retain(|m| !Arc::ptr_eq(m, &immutable_memtable));
             }
 
-            // flush 完成后发送信号给这次 rotate 专属的 receiver
-            // 如果 receiver 已经 dropped（不太可能），这里会失败但不影响正确性
-            let _ = tx.send(());
+            info!(
+                "[{}] Memtable rotation completed (reason: {}, size: {} items, elapsed: {:?})",
+                context.name,
+                reason,
+                old_size,
+                start_time.elapsed()
+            );
+            let _ = done_tx.send(());
         });
 
-        // 等待 flush 完成信号（背压控制点）
-        // ⚠️ 关键：此时所有锁都已释放，不会阻塞其他操作
-        // 优点：
-        // 1. 每次 rotate 有独立的 channel，不会信号混乱
-        // 2. 如果 flush 很快，这里几乎不阻塞
-        // 3. 如果 flush 很慢，这里会阻塞，防止连续 rotate 导致 OOM
-        // 4. 阻塞期间，新数据仍然可以写入新的 active memtable（写锁已释放）
-        // 5. 其他需要读取 rotate_time 的操作不会被阻塞（如 auto-flush timer）
-        match rx.await {
-            Ok(()) => {
-                info!(
-                    "[{}] Memtable rotation completed (reason: {}, size: {} items, elapsed: {:?})",
-                    name,
-                    reason,
-                    old_size,
-                    start_time.elapsed()
-                );
-            }
-            Err(_) => {
-                // Sender dropped，说明 flush 任务异常退出
-                warn!(
-                    "[{}] Memtable rotation failed - flush task terminated unexpectedly (reason: {}, size: {} items)",
-                    name, reason, old_size
-                );
-            }
-        }
-
         Some((old_memtable, old_size))
     }
 }
@@ -3,6 +3,7 @@ use domain::value::AlbumId;
 use log::info;
 use model::album_stats::{AlbumStats, AlbumStatsAdjustment, AlbumStatsRepository};
 use model::ModelError;
+use std::collections::HashMap;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use std::time::Duration;
@@ -10,6 +11,7 @@ use tokio::sync::RwLock;
 
 use super::super::memtable::{
     IndexValue, IndexMatch, Memtable, MemtableContext, MemtablePersister, MemtableValue,
+    ManagedMemtable,
 };
 
 #[derive(Clone)]
@@ -91,7 +93,12 @@ impl<R> BufferedAlbumStatsRepository<R>
 where
     R: AlbumStatsRepository + Send + Sync + 'static,
 {
-    pub fn new(inner: R, cache_capacity: usize, flush_timeout: Duration) -> Arc<Self> {
+    pub fn new(
+        inner: R,
+        cache_capacity: usize,
+        flush_timeout: Duration,
+        max_pending_flushes: usize,
+    ) -> Arc<Self> {
         let memtable_size_threshold = cache_capacity.max(100);
 
         // 创建 persister
@@ -109,6 +116,7 @@ where
             memtable_size_threshold,
             persister,
             flush_timeout,
+            max_pending_flushes,
         ));
 
         // 启动自动 flush 定时器
@@ -120,6 +128,11 @@ where
         })
     }
 
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
     pub async fn shutdown_gracefully(
         &self,
         wait_duration: Duration,
@@ -156,14 +169,21 @@ where
 {
     async fn adjust_stats(&self, adjustment: AlbumStatsAdjustment) -> Result<(), ModelError> {
         let id_i64 = adjustment.album_id.as_i64();
+        // memtable 中没有时以数据库中的统计为基准，否则减量会从 0 开始并在 flush 时覆盖数据库
+        let persisted = match self.memtable_context.get(&id_i64).await {
+            Some(_) => None,
+            None => {
+                self.inner
+                    .find_by_album_id(adjustment.album_id.clone())
+                    .await?
+            }
+        };
 
         // 使用 update_or_insert 保证原子性，避免竞态条件
         self.memtable_context
             .update_or_insert(id_i64, |current| {
-                let new_stats = match current {
-                    Some(existing) => {
-                        let mut stats = existing.0.clone();
-
+                let new_stats = match current.map(|existing| existing.0.clone()).or(persisted) {
+                    Some(mut stats) => {
                         // Apply deltas
                         stats.duration += adjustment.duration_delta;
                         stats.size += adjustment.size_delta;
@@ -176,6 +196,13 @@ where
                             }
                         }
 
+                        // Record disc subtitle for the disk_number
+                        if let (Some(disk_num), Some(subtitle)) =
+                            (adjustment.disk_number, adjustment.disc_subtitle.as_ref())
+                        {
+                            stats.disc_titles.insert(disk_num, subtitle.clone());
+                        }
+
                         // Set year if provided and not already set
                         if let Some(year_val) = adjustment.year {
                             if stats.year.is_none() || stats.year == Some(0) {
@@ -197,6 +224,12 @@ where
                             } else {
                                 vec![]
                             },
+                            disc_titles: match (adjustment.disk_number, &adjustment.disc_subtitle) {
+                                (Some(disk_num), Some(subtitle)) => {
+                                    HashMap::from([(disk_num, subtitle.clone())])
+                                }
+                                _ => HashMap::new(),
+                            },
                             year: adjustment.year,
                         }
                     }
@@ -9,6 +9,7 @@ use tokio::sync::RwLock;
 
 use super::super::memtable::{
     IndexMatch, IndexValue, Memtable, MemtableContext, MemtablePersister, MemtableValue,
+    ManagedMemtable,
 };
 
 // 复合主键: (artist_id, location_protocol, location_path)
@@ -126,7 +127,12 @@ impl<R> BufferedArtistLocationRepository<R>
 where
     R: ArtistLocationRepository + Send + Sync + 'static,
 {
-    pub fn new(inner: R, cache_capacity: usize, flush_timeout: Duration) -> Arc<Self> {
+    pub fn new(
+        inner: R,
+        cache_capacity: usize,
+        flush_timeout: Duration,
+        max_pending_flushes: usize,
+    ) -> Arc<Self> {
         let memtable_size_threshold = cache_capacity.max(100);
 
         // 创建 persister
@@ -146,6 +152,7 @@ where
             memtable_size_threshold,
             persister,
             flush_timeout,
+            max_pending_flushes,
         ));
 
         // 启动自动 flush 定时器
@@ -157,6 +164,11 @@ where
         })
     }
 
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
     pub async fn shutdown_gracefully(
         &self,
         wait_duration: Duration,
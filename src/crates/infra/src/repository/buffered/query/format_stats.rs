@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use log::info;
+use model::format_stats::{FormatStats, FormatStatsRepository};
+use model::ModelError;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use super::super::memtable::{
+    IndexMatch, IndexValue, ManagedMemtable, Memtable, MemtableContext, MemtablePersister,
+    MemtableValue,
+};
+
+// 复合键：bit_rate_bucket + sample_rate + suffix
+fn make_composite_key(entry: &FormatStats) -> String {
+    format!(
+        "{}:{}:{}",
+        entry.bit_rate_bucket, entry.sample_rate, entry.suffix
+    )
+}
+
+#[derive(Clone)]
+struct FormatStatsWrapper(FormatStats);
+
+impl MemtableValue<String> for FormatStatsWrapper {
+    fn get_key(&self) -> String {
+        make_composite_key(&self.0)
+    }
+
+    fn get_indexes(&self) -> Vec<(&str, IndexValue, IndexMatch)> {
+        vec![]
+    }
+
+    fn get_index(&self, _index_name: &str) -> IndexValue {
+        panic!("No indexes defined for FormatStats")
+    }
+}
+
+pub struct FormatStatsPersister<R>
+where
+    R: FormatStatsRepository + Send + Sync + 'static,
+{
+    inner: Arc<R>,
+}
+
+impl<R> Clone for FormatStatsPersister<R>
+where
+    R: FormatStatsRepository + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<R> FormatStatsPersister<R>
+where
+    R: FormatStatsRepository + Send + Sync + 'static,
+{
+    pub fn new(inner: Arc<R>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl<R> MemtablePersister<String, FormatStatsWrapper> for FormatStatsPersister<R>
+where
+    R: FormatStatsRepository + Send + Sync + 'static,
+{
+    async fn persist(&self, _key: String, value: Arc<FormatStatsWrapper>) -> Result<(), String> {
+        // memtable 中累积的是增量
+        self.inner
+            .adjust_stats(value.0.clone())
+            .await
+            .map_err(|e| format!("Failed to adjust format stats: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, _key: String) -> Result<(), String> {
+        // 统计只累加增量，不会删除键
+        Ok(())
+    }
+}
+
+pub struct BufferedFormatStatsRepository<R>
+where
+    R: FormatStatsRepository + Send + Sync + 'static,
+{
+    memtable_context: Arc<MemtableContext<String, FormatStatsWrapper, FormatStatsPersister<R>>>,
+    inner: Arc<R>,
+}
+
+impl<R> BufferedFormatStatsRepository<R>
+where
+    R: FormatStatsRepository + Send + Sync + 'static,
+{
+    pub fn new(
+        inner: R,
+        cache_capacity: usize,
+        flush_timeout: Duration,
+        max_pending_flushes: usize,
+    ) -> Arc<Self> {
+        let memtable_size_threshold = cache_capacity.max(100);
+
+        let inner_arc = Arc::new(inner);
+        let persister = Arc::new(FormatStatsPersister::new(inner_arc.clone()));
+
+        let active_memtable = Arc::new(RwLock::new(Memtable::<String, FormatStatsWrapper>::new()));
+        let active_size = Arc::new(AtomicUsize::new(0));
+
+        let memtable_context = Arc::new(MemtableContext::new(
+            "FormatStats".to_string(),
+            active_memtable.clone(),
+            active_size.clone(),
+            memtable_size_threshold,
+            persister,
+            flush_timeout,
+            max_pending_flushes,
+        ));
+
+        memtable_context.start_auto_flush_timer();
+
+        Arc::new(Self {
+            memtable_context,
+            inner: inner_arc,
+        })
+    }
+
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
+    pub async fn shutdown_gracefully(
+        &self,
+        wait_duration: Duration,
+    ) -> Result<Option<usize>, String> {
+        info!("Starting graceful shutdown of BufferedFormatStatsRepository");
+
+        let Some(count) = self.memtable_context.shutdown_gracefully().await else {
+            info!("No data to flush during shutdown");
+            return Ok(None);
+        };
+        info!(
+            "Triggered flush of {} items, waiting for async tasks to complete",
+            count
+        );
+
+        tokio::time::sleep(wait_duration).await;
+        Ok(Some(count))
+    }
+}
+
+#[async_trait]
+impl<R> FormatStatsRepository for BufferedFormatStatsRepository<R>
+where
+    R: FormatStatsRepository + Send + Sync + 'static,
+{
+    async fn adjust_stats(&self, entry: FormatStats) -> Result<(), ModelError> {
+        let key = make_composite_key(&entry);
+
+        self.memtable_context
+            .update_or_insert(key, |current| {
+                let (song_count, total_size) = match current {
+                    Some(existing) => (
+                        existing.0.song_count + entry.song_count,
+                        existing.0.total_size + entry.total_size,
+                    ),
+                    None => (entry.song_count, entry.total_size),
+                };
+
+                Arc::new(FormatStatsWrapper(FormatStats {
+                    song_count,
+                    total_size,
+                    ..entry.clone()
+                }))
+            })
+            .await
+            .map_err(|e| ModelError::DbErr(format!("Failed to update memtable: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -9,7 +9,8 @@ use std::time::Duration;
 use tokio::sync::RwLock;
 
 use super::super::memtable::{
-    IndexMatch, IndexValue, Memtable, MemtableContext, MemtablePersister, MemtableValue,
+    IndexMatch, IndexValue, ManagedMemtable, Memtable, MemtableContext, MemtablePersister,
+    MemtableValue,
 };
 
 #[derive(Clone)]
@@ -104,7 +105,12 @@ impl<R> BufferedGenreStatsRepository<R>
 where
     R: GenreStatsRepository + Send + Sync + 'static,
 {
-    pub fn new(inner: R, cache_capacity: usize, flush_timeout: Duration) -> Arc<Self> {
+    pub fn new(
+        inner: R,
+        cache_capacity: usize,
+        flush_timeout: Duration,
+        max_pending_flushes: usize,
+    ) -> Arc<Self> {
         let memtable_size_threshold = cache_capacity.max(100);
 
         // 创建 persister
@@ -122,6 +128,7 @@ where
             memtable_size_threshold,
             persister,
             flush_timeout,
+            max_pending_flushes,
         ));
 
         // 启动自动 flush 定时器
@@ -133,6 +140,11 @@ where
         })
     }
 
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
     pub async fn shutdown_gracefully(
         &self,
         wait_duration: Duration,
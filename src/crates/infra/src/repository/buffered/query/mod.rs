@@ -1,5 +1,6 @@
 pub mod album_stats;
 pub mod artist_location;
 pub mod album_location;
+pub mod format_stats;
 pub mod genre_stats;
 pub mod participant_stats;
@@ -10,6 +10,7 @@ use tokio::sync::RwLock;
 
 use super::super::memtable::{
     IndexValue, IndexMatch, Memtable, MemtableContext, MemtablePersister, MemtableValue,
+    ManagedMemtable,
 };
 
 // 使用复合键：artist_id + role 的字符串表示
@@ -95,6 +96,12 @@ where
             "AlbumArtist" => ParticipantRole::AlbumArtist,
             "Artist" => ParticipantRole::Artist,
             "Performer" => ParticipantRole::Performer,
+            "Composer" => ParticipantRole::Composer,
+            "Lyricist" => ParticipantRole::Lyricist,
+            "Conductor" => ParticipantRole::Conductor,
+            "Arranger" => ParticipantRole::Arranger,
+            "Producer" => ParticipantRole::Producer,
+            "Remixer" => ParticipantRole::Remixer,
             _ => return Err(format!("Unknown role: {}", parts[1])),
         };
 
@@ -129,7 +136,12 @@ impl<R> BufferedParticipantStatsRepository<R>
 where
     R: ParticipantStatsRepository + Send + Sync + 'static,
 {
-    pub fn new(inner: R, cache_capacity: usize, flush_timeout: Duration) -> Arc<Self> {
+    pub fn new(
+        inner: R,
+        cache_capacity: usize,
+        flush_timeout: Duration,
+        max_pending_flushes: usize,
+    ) -> Arc<Self> {
         let memtable_size_threshold = cache_capacity.max(100);
 
         // 创建 persister
@@ -147,6 +159,7 @@ where
             memtable_size_threshold,
             persister,
             flush_timeout,
+            max_pending_flushes,
         ));
 
         // 启动自动 flush 定时器
@@ -158,6 +171,11 @@ where
         })
     }
 
+    /// 供指标采集和关闭流程使用
+    pub fn memtable_handle(&self) -> Arc<dyn ManagedMemtable> {
+        self.memtable_context.clone()
+    }
+
     pub async fn shutdown_gracefully(
         &self,
         wait_duration: Duration,
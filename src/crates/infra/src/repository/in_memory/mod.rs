@@ -1 +1,2 @@
+pub mod processed_event;
 pub mod scan_status;
@@ -0,0 +1,80 @@
+use application::event::event_bus::EventId;
+use application::event::processed_event::ProcessedEventLedger;
+use async_trait::async_trait;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+
+/// 只保存在进程内的处理记录，给写入 memtable 的投影使用。
+///
+/// 记录与尚未 flush 的投影一起随进程丢失，重启后重新投递的事件会再计入一次；
+/// 写数据库的记录则会比投影先落盘，丢失的投影不会再补回。
+pub struct InMemoryProcessedEventLedger {
+    capacity: usize,
+    state: Mutex<LedgerState>,
+}
+
+#[derive(Default)]
+struct LedgerState {
+    marked: HashSet<(String, EventId)>,
+    /// 标记顺序，超出容量时先淘汰最早的记录
+    order: VecDeque<(String, EventId)>,
+}
+
+impl InMemoryProcessedEventLedger {
+    /// capacity 需要覆盖重试和死信重放的时间窗口内处理的事件数
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(LedgerState::default()),
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessedEventLedger for InMemoryProcessedEventLedger {
+    async fn try_mark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<bool> {
+        let key = (handler.to_string(), event_id.clone());
+        let mut state = self.state.lock().unwrap();
+        if !state.marked.insert(key.clone()) {
+            return Ok(false);
+        }
+        state.order.push_back(key);
+        while state.order.len() > self.capacity {
+            if let Some(oldest) = state.order.pop_front() {
+                state.marked.remove(&oldest);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn unmark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<()> {
+        let key = (handler.to_string(), event_id.clone());
+        let mut state = self.state.lock().unwrap();
+        if state.marked.remove(&key) {
+            state.order.retain(|k| k != &key);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn evicts_the_oldest_marks_beyond_capacity() {
+        let ledger = InMemoryProcessedEventLedger::new(2);
+        let (first, second, third) = (EventId::new(), EventId::new(), EventId::new());
+
+        assert!(ledger.try_mark("stats", &first).await.unwrap());
+        assert!(!ledger.try_mark("stats", &first).await.unwrap());
+        assert!(ledger.try_mark("other", &first).await.unwrap());
+        assert!(ledger.try_mark("stats", &second).await.unwrap());
+        // ("stats", first) 已被淘汰
+        assert!(ledger.try_mark("stats", &first).await.unwrap());
+
+        ledger.unmark("stats", &first).await.unwrap();
+        assert!(ledger.try_mark("stats", &first).await.unwrap());
+        assert!(ledger.try_mark("stats", &third).await.unwrap());
+    }
+}
@@ -0,0 +1,393 @@
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use sea_orm::{
+    AccessMode, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr,
+    IsolationLevel, Statement, StreamTrait, TransactionTrait, Value,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use thiserror::Error;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// 备份文件格式版本，行结构变化时递增
+const FORMAT_VERSION: u32 = 1;
+/// 迁移记录表不备份，恢复前要求两边的迁移版本一致
+const MIGRATION_TABLE: &str = "seaql_migrations";
+/// 恢复时每条 INSERT 写入的行数
+const RESTORE_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Database error: {0}")]
+    Db(#[from] DbErr),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid backup line {line}: {message}")]
+    Format { line: u64, message: String },
+    #[error("Backup was taken at migration {backup}, database is at {current}; run the same version first")]
+    VersionMismatch { backup: String, current: String },
+}
+
+/// 备份文件每行一个 JSON 对象：首行 header，中间每行一条记录，末行 end 用于检测截断
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum BackupLine {
+    #[serde(rename_all = "camelCase")]
+    Header {
+        format: u32,
+        created_at: NaiveDateTime,
+        migration: String,
+    },
+    Row {
+        table: String,
+        row: serde_json::Value,
+    },
+    End {
+        rows: u64,
+    },
+}
+
+/// 恢复结果：各表写入的行数
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub created_at: Option<NaiveDateTime>,
+    pub rows: u64,
+    pub tables: BTreeMap<String, u64>,
+}
+
+/// 逻辑备份：在可重复读的只读事务中按外键依赖顺序导出所有表，恢复时在单个事务中清空后重新写入
+pub struct PostgresBackup {
+    db: DatabaseConnection,
+}
+
+impl PostgresBackup {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 导出 JSONL，返回导出的行数
+    pub async fn export<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<u64, BackupError> {
+        let txn = self
+            .db
+            .begin_with_config(
+                Some(IsolationLevel::RepeatableRead),
+                Some(AccessMode::ReadOnly),
+            )
+            .await?;
+        let header = BackupLine::Header {
+            format: FORMAT_VERSION,
+            created_at: chrono::Utc::now().naive_utc(),
+            migration: current_migration(&txn).await?,
+        };
+        write_line(writer, &header).await?;
+
+        let mut rows = 0;
+        for table in table_order(&txn).await? {
+            let sql = format!(
+                "SELECT row_to_json(t)::text AS row FROM {} t",
+                quote(&table)
+            );
+            let mut stream = txn
+                .stream(Statement::from_string(DbBackend::Postgres, sql))
+                .await?;
+            while let Some(result) = stream.next().await {
+                let json: String = result?.try_get("", "row")?;
+                let line = BackupLine::Row {
+                    table: table.clone(),
+                    row: serde_json::from_str(&json).map_err(std::io::Error::from)?,
+                };
+                write_line(writer, &line).await?;
+                rows += 1;
+            }
+        }
+        write_line(writer, &BackupLine::End { rows }).await?;
+        writer.flush().await?;
+        txn.commit().await?;
+        Ok(rows)
+    }
+
+    /// 用备份替换所有表的数据，任何一行出错都会回滚
+    pub async fn restore<R: AsyncBufRead + Unpin>(
+        &self,
+        reader: R,
+    ) -> Result<RestoreReport, BackupError> {
+        let txn = self.db.begin().await?;
+        let mut lines = reader.lines();
+        let mut line_no = 0u64;
+        let mut report = RestoreReport::default();
+        let mut tables: HashSet<String> = HashSet::new();
+        let mut batch = RowBatch::default();
+        let mut finished = false;
+
+        while let Some(line) = lines.next_line().await? {
+            line_no += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let format_error = |message: String| BackupError::Format {
+                line: line_no,
+                message,
+            };
+            if finished {
+                return Err(format_error("data after end marker".to_string()));
+            }
+            let parsed: BackupLine =
+                serde_json::from_str(&line).map_err(|e| format_error(e.to_string()))?;
+            match parsed {
+                BackupLine::Header {
+                    format,
+                    created_at,
+                    migration,
+                } => {
+                    if line_no != 1 {
+                        return Err(format_error("unexpected header".to_string()));
+                    }
+                    if format != FORMAT_VERSION {
+                        return Err(format_error(format!("unsupported format {}", format)));
+                    }
+                    let current = current_migration(&txn).await?;
+                    if migration != current {
+                        return Err(BackupError::VersionMismatch {
+                            backup: migration,
+                            current,
+                        });
+                    }
+                    tables = table_order(&txn).await?.into_iter().collect();
+                    truncate_all(&txn, &tables).await?;
+                    report.created_at = Some(created_at);
+                }
+                BackupLine::Row { table, row } => {
+                    if report.created_at.is_none() {
+                        return Err(format_error("missing header".to_string()));
+                    }
+                    if !tables.contains(&table) {
+                        return Err(format_error(format!("unknown table {}", table)));
+                    }
+                    if batch.table != table || batch.rows.len() >= RESTORE_BATCH_SIZE {
+                        batch.flush(&txn).await?;
+                        batch.table = table.clone();
+                    }
+                    batch.rows.push(row);
+                    *report.tables.entry(table).or_default() += 1;
+                    report.rows += 1;
+                }
+                BackupLine::End { rows } => {
+                    if report.created_at.is_none() {
+                        return Err(format_error("missing header".to_string()));
+                    }
+                    if rows != report.rows {
+                        return Err(format_error(format!(
+                            "expected {} rows, found {}",
+                            rows, report.rows
+                        )));
+                    }
+                    finished = true;
+                }
+            }
+        }
+        if !finished {
+            return Err(BackupError::Format {
+                line: line_no,
+                message: "backup is truncated".to_string(),
+            });
+        }
+        batch.flush(&txn).await?;
+        reset_sequences(&txn).await?;
+        txn.commit().await?;
+        Ok(report)
+    }
+}
+
+#[derive(Default)]
+struct RowBatch {
+    table: String,
+    rows: Vec<serde_json::Value>,
+}
+
+impl RowBatch {
+    /// json_populate_recordset 按列名映射，列顺序不同也能恢复
+    async fn flush(&mut self, txn: &DatabaseTransaction) -> Result<(), BackupError> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let rows = serde_json::Value::Array(std::mem::take(&mut self.rows));
+        let sql = format!(
+            "INSERT INTO {table} SELECT * FROM json_populate_recordset(NULL::{table}, $1::json)",
+            table = quote(&self.table)
+        );
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [Value::from(rows.to_string())],
+        ))
+        .await?;
+        Ok(())
+    }
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    line: &BackupLine,
+) -> Result<(), BackupError> {
+    let mut bytes = serde_json::to_vec(line).map_err(std::io::Error::from)?;
+    bytes.push(b'\n');
+    writer.write_all(&bytes).await?;
+    Ok(())
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+async fn current_migration(db: &impl ConnectionTrait) -> Result<String, BackupError> {
+    let sql = format!(
+        "SELECT version FROM {} ORDER BY version DESC LIMIT 1",
+        MIGRATION_TABLE
+    );
+    let row = db
+        .query_one(Statement::from_string(DbBackend::Postgres, sql))
+        .await?;
+    Ok(match row {
+        Some(row) => row.try_get("", "version")?,
+        None => String::new(),
+    })
+}
+
+/// 所有业务表，被引用的表排在前面
+async fn table_order(db: &impl ConnectionTrait) -> Result<Vec<String>, BackupError> {
+    let tables = db
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT table_name::text AS name FROM information_schema.tables \
+             WHERE table_schema = 'public' AND table_type = 'BASE TABLE' AND table_name <> $1",
+            [MIGRATION_TABLE.into()],
+        ))
+        .await?
+        .into_iter()
+        .map(|row| row.try_get::<String>("", "name"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let references = db
+        .query_all(Statement::from_string(
+            DbBackend::Postgres,
+            "SELECT c.relname::text AS child, p.relname::text AS parent FROM pg_constraint k \
+             JOIN pg_class c ON c.oid = k.conrelid \
+             JOIN pg_class p ON p.oid = k.confrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE k.contype = 'f' AND n.nspname = 'public'",
+        ))
+        .await?
+        .into_iter()
+        .map(|row| Ok((row.try_get("", "child")?, row.try_get("", "parent")?)))
+        .collect::<Result<Vec<(String, String)>, DbErr>>()?;
+    Ok(dependency_order(tables, &references))
+}
+
+/// 按 (子表, 父表) 的引用关系排序，循环引用的表按名称追加在最后
+fn dependency_order(tables: Vec<String>, references: &[(String, String)]) -> Vec<String> {
+    let mut pending: BTreeMap<String, BTreeSet<&str>> = tables
+        .into_iter()
+        .map(|table| (table, BTreeSet::new()))
+        .collect();
+    for (child, parent) in references {
+        if child != parent && pending.contains_key(parent.as_str()) {
+            if let Some(parents) = pending.get_mut(child.as_str()) {
+                parents.insert(parent.as_str());
+            }
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(pending.len());
+    loop {
+        let ready: Vec<String> = pending
+            .iter()
+            .filter(|(_, parents)| parents.is_empty())
+            .map(|(table, _)| table.clone())
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        for table in &ready {
+            pending.remove(table);
+        }
+        for parents in pending.values_mut() {
+            for table in &ready {
+                parents.remove(table.as_str());
+            }
+        }
+        ordered.extend(ready);
+    }
+    ordered.extend(pending.into_keys());
+    ordered
+}
+
+async fn truncate_all(
+    txn: &DatabaseTransaction,
+    tables: &HashSet<String>,
+) -> Result<(), BackupError> {
+    if tables.is_empty() {
+        return Ok(());
+    }
+    let names: Vec<String> = tables.iter().map(|table| quote(table)).collect();
+    let sql = format!("TRUNCATE {} RESTART IDENTITY CASCADE", names.join(", "));
+    txn.execute(Statement::from_string(DbBackend::Postgres, sql))
+        .await?;
+    Ok(())
+}
+
+/// 自增列的序列从恢复后的最大值继续
+async fn reset_sequences(txn: &DatabaseTransaction) -> Result<(), BackupError> {
+    let columns = txn
+        .query_all(Statement::from_string(
+            DbBackend::Postgres,
+            "SELECT table_name::text AS table_name, column_name::text AS column_name \
+             FROM information_schema.columns \
+             WHERE table_schema = 'public' AND column_default LIKE 'nextval(%'",
+        ))
+        .await?;
+    for row in columns {
+        let table: String = row.try_get("", "table_name")?;
+        let column: String = row.try_get("", "column_name")?;
+        let sql = format!(
+            "SELECT setval(pg_get_serial_sequence($1, $2), \
+             COALESCE((SELECT MAX({column}) FROM {table}), 0) + 1, false)",
+            column = quote(&column),
+            table = quote(&table)
+        );
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [quote(&table).into(), column.into()],
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn orders_referenced_tables_first() {
+        let references = vec![
+            ("playlist_entry".to_string(), "playlist".to_string()),
+            ("playlist".to_string(), "user".to_string()),
+            ("user".to_string(), "user".to_string()),
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "a".to_string()),
+        ];
+        let ordered = dependency_order(
+            names(&["playlist_entry", "a", "playlist", "b", "user", "album"]),
+            &references,
+        );
+        assert_eq!(
+            ordered,
+            names(&["album", "user", "playlist", "playlist_entry", "a", "b"])
+        );
+    }
+}
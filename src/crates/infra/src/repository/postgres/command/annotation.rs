@@ -1,6 +1,13 @@
 use super::db_data::{annotation, annotation::ActiveModel, annotation::Entity, annotation::Model};
+use super::event_outbox::insert_messages;
+use crate::event_bus::outbox::encode_annotation_event;
+use application::error::AppError;
+use application::event::event_bus::EventEnvelope;
+use application::event::outbox::AnnotationOutbox;
 use async_trait::async_trait;
-use domain::annotation::{Annotation, AnnotationError, AnnotationRepository, Kind};
+use domain::annotation::{
+    Annotation, AnnotationError, AnnotationEvent, AnnotationRepository, Kind,
+};
 use domain::value::UserId;
 use sea_orm::*;
 
@@ -19,10 +26,12 @@ impl AnnotationRepositoryImpl {
 impl AnnotationRepository for AnnotationRepositoryImpl {
     async fn find_by_item(
         &self,
+        user_id: &UserId,
         item_kind: Kind,
         item_id: i64,
     ) -> Result<Option<Annotation>, AnnotationError> {
         let result_row: Option<Model> = Entity::find()
+            .filter(annotation::Column::UserId.eq(user_id.as_i64()))
             .filter(annotation::Column::ItemId.eq(item_id))
             .filter(annotation::Column::ItemKind.eq(item_kind.to_string()))
             .one(&self.db)
@@ -35,8 +44,13 @@ impl AnnotationRepository for AnnotationRepositoryImpl {
         }
     }
 
-    async fn find_by_item_id(&self, item_id: i64) -> Result<Option<Annotation>, AnnotationError> {
+    async fn find_by_item_id(
+        &self,
+        user_id: &UserId,
+        item_id: i64,
+    ) -> Result<Option<Annotation>, AnnotationError> {
         let result_row: Option<Model> = Entity::find()
+            .filter(annotation::Column::UserId.eq(user_id.as_i64()))
             .filter(annotation::Column::ItemId.eq(item_id))
             .one(&self.db)
             .await
@@ -49,58 +63,13 @@ impl AnnotationRepository for AnnotationRepositoryImpl {
     }
 
     async fn save(&self, annotation: Annotation) -> Result<(), AnnotationError> {
-        self.db
-            .transaction::<_, (), AnnotationError>(move |txn| {
-                let annotation = annotation.clone();
-                Box::pin(async move {
-                    // 先查询记录是否存在
-                    let existing: Option<Model> = Entity::find_by_id(annotation.id.as_i64())
-                        .one(txn)
-                        .await
-                        .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
-
-                    match existing {
-                        None => {
-                            // 记录不存在，执行插入
-                            let active_model: ActiveModel = annotation.into();
-                            active_model
-                                .insert(txn)
-                                .await
-                                .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
-                        }
-                        Some(existing_model) => {
-                            // 记录存在，检查版本号
-                            if annotation.version <= existing_model.version {
-                                return Err(AnnotationError::InvalidOperation(
-                                    "版本号必须大于当前版本号".to_string(),
-                                ));
-                            }
-
-                            // 执行更新，使用乐观锁
-                            let mut update_model: ActiveModel = annotation.clone().into();
-                            update_model.created_at = NotSet;
-                            let update_condition = Condition::all()
-                                .add(annotation::Column::Id.eq(annotation.id.as_i64()))
-                                .add(annotation::Column::Version.eq(existing_model.version));
-
-                            let result = Entity::update_many()
-                                .set(update_model)
-                                .filter(update_condition)
-                                .exec(txn)
-                                .await
-                                .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
-
-                            // 验证乐观锁
-                            if result.rows_affected == 0 {
-                                return Err(AnnotationError::InvalidOperation(
-                                    "版本号冲突，数据已被其他事务修改".to_string(),
-                                ));
-                            }
-                        }
-                    }
-                    Ok(())
-                })
-            })
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+        save_annotation(&txn, annotation).await?;
+        txn.commit()
             .await
             .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
         Ok(())
@@ -123,6 +92,24 @@ impl AnnotationRepository for AnnotationRepositoryImpl {
             .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
         Ok(())
     }
+    async fn reassign_item(
+        &self,
+        item_kind: Kind,
+        from_item_id: i64,
+        to_item_id: i64,
+    ) -> Result<(), AnnotationError> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+        reassign_annotations(&txn, item_kind, from_item_id, to_item_id).await?;
+        txn.commit()
+            .await
+            .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
     async fn delete_all(&self) -> Result<(), AnnotationError> {
         let txn = self
             .db
@@ -141,3 +128,131 @@ impl AnnotationRepository for AnnotationRepositoryImpl {
         Ok(())
     }
 }
+
+#[async_trait]
+impl AnnotationOutbox for AnnotationRepositoryImpl {
+    async fn save_with_events(
+        &self,
+        annotation: Annotation,
+        events: Vec<EventEnvelope<AnnotationEvent>>,
+    ) -> Result<(), AppError> {
+        let messages = events
+            .iter()
+            .map(encode_annotation_event)
+            .collect::<Result<Vec<_>, _>>()?;
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+        save_annotation(&txn, annotation).await?;
+        insert_messages(&txn, messages)
+            .await
+            .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+        txn.commit()
+            .await
+            .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// 在给定连接（通常是事务）中保存注解，带乐观锁
+async fn save_annotation<C: ConnectionTrait>(
+    conn: &C,
+    annotation: Annotation,
+) -> Result<(), AnnotationError> {
+    // 先查询记录是否存在
+    let existing: Option<Model> = Entity::find_by_id(annotation.id.as_i64())
+        .one(conn)
+        .await
+        .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+
+    match existing {
+        None => {
+            // 记录不存在，执行插入
+            let active_model: ActiveModel = annotation.into();
+            active_model
+                .insert(conn)
+                .await
+                .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+        }
+        Some(existing_model) => {
+            // 记录存在，检查版本号
+            if annotation.version <= existing_model.version {
+                return Err(AnnotationError::InvalidOperation(
+                    "版本号必须大于当前版本号".to_string(),
+                ));
+            }
+
+            // 执行更新，使用乐观锁
+            let mut update_model: ActiveModel = annotation.clone().into();
+            update_model.created_at = NotSet;
+            let update_condition = Condition::all()
+                .add(annotation::Column::Id.eq(annotation.id.as_i64()))
+                .add(annotation::Column::Version.eq(existing_model.version));
+
+            let result = Entity::update_many()
+                .set(update_model)
+                .filter(update_condition)
+                .exec(conn)
+                .await
+                .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+
+            // 验证乐观锁
+            if result.rows_affected == 0 {
+                return Err(AnnotationError::InvalidOperation(
+                    "版本号冲突，数据已被其他事务修改".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 把条目的注解转到另一个条目，同一用户两边都有时合并
+pub(crate) async fn reassign_annotations<C: ConnectionTrait>(
+    conn: &C,
+    item_kind: Kind,
+    from_item_id: i64,
+    to_item_id: i64,
+) -> Result<(), AnnotationError> {
+    let kind = item_kind.to_string();
+
+    // 同一用户两边都有注解时合并到目标条目
+    let merge_sql = r#"
+        UPDATE annotation t SET
+            rating = GREATEST(t.rating, s.rating),
+            starred = t.starred OR s.starred,
+            starred_at = GREATEST(t.starred_at, s.starred_at),
+            played_count = t.played_count + s.played_count,
+            played_at = GREATEST(t.played_at, s.played_at),
+            version = t.version + 1,
+            updated_at = NOW()
+        FROM annotation s
+        WHERE s.user_id = t.user_id
+          AND s.item_kind = $1 AND s.item_id = $2
+          AND t.item_kind = $1 AND t.item_id = $3
+    "#;
+    let delete_sql = r#"
+        DELETE FROM annotation s
+        WHERE s.item_kind = $1 AND s.item_id = $2
+          AND EXISTS (
+              SELECT 1 FROM annotation t
+              WHERE t.user_id = s.user_id AND t.item_kind = $1 AND t.item_id = $3
+          )
+    "#;
+    let move_sql = r#"
+        UPDATE annotation SET item_id = $3, version = version + 1, updated_at = NOW()
+        WHERE item_kind = $1 AND item_id = $2
+    "#;
+    for sql in [merge_sql, delete_sql, move_sql] {
+        conn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [kind.clone().into(), from_item_id.into(), to_item_id.into()],
+        ))
+        .await
+        .map_err(|e| AnnotationError::DbErr(e.to_string()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use super::db_data::app_password;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::app_password::{AppPassword, AppPasswordError, AppPasswordRepository};
+use domain::value::UserId;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct AppPasswordRepositoryImpl {
+    db: DbConn,
+}
+
+impl AppPasswordRepositoryImpl {
+    pub fn new(db: DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AppPasswordRepository for AppPasswordRepositoryImpl {
+    async fn find_by_user(&self, user_id: &UserId) -> Result<Vec<AppPassword>, AppPasswordError> {
+        let rows = app_password::Entity::find()
+            .filter(app_password::Column::UserId.eq(user_id.as_i64()))
+            .order_by_asc(app_password::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| AppPasswordError::DbErr(e.to_string()))?;
+        Ok(rows.into_iter().map(|m| m.into()).collect())
+    }
+
+    async fn save(&self, value: &AppPassword) -> Result<(), AppPasswordError> {
+        let active_model: app_password::ActiveModel = value.into();
+        app_password::Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::column(app_password::Column::Id)
+                    .update_columns([
+                        app_password::Column::Label,
+                        app_password::Column::LastUsedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| AppPasswordError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &UserId, id: i64) -> Result<bool, AppPasswordError> {
+        let result = app_password::Entity::delete_many()
+            .filter(app_password::Column::Id.eq(id))
+            .filter(app_password::Column::UserId.eq(user_id.as_i64()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| AppPasswordError::DbErr(e.to_string()))?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn mark_used(&self, id: i64, at: NaiveDateTime) -> Result<(), AppPasswordError> {
+        app_password::Entity::update_many()
+            .col_expr(app_password::Column::LastUsedAt, Expr::value(at))
+            .filter(app_password::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| AppPasswordError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -2,12 +2,13 @@ use super::db_data::{
     artist,
     artist::Entity,
     artist::Model,
+    artist_alias,
     participant::{self, Entity as ParticipantEntity, Model as ParticipantModel},
 };
 use application::command::shared::IdGenerator;
 use async_trait::async_trait;
 use chrono::Utc;
-use domain::artist::{Artist, ArtistError, ArtistRepository};
+use domain::artist::{Artist, ArtistAlias, ArtistAliasRepository, ArtistError, ArtistRepository};
 use domain::value::{ArtistId, Participant};
 use sea_orm::sea_query::Value;
 use sea_orm::*;
@@ -43,92 +44,12 @@ impl ArtistRepository for ArtistRepositoryImpl {
         }
     }
 
-    async fn save(&self, mut artist: Artist) -> Result<Artist, ArtistError> {
-        artist.version += 1;
-        let now = Utc::now().naive_utc();
-
-        // Use INSERT ... ON CONFLICT to upsert artist by id
-        // id is generated by snowflake and remains constant
-        // create_time is set on insert, update_time is updated on conflict
-        let sql = String::from(
-            "INSERT INTO artist \
-             (id, version, name, genre_id, genre_ids, sort_name, create_time, update_time) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
-             ON CONFLICT (id) \
-             DO UPDATE SET \
-               version = EXCLUDED.version, \
-               name = EXCLUDED.name, \
-               genre_id = EXCLUDED.genre_id, \
-               genre_ids = EXCLUDED.genre_ids, \
-               sort_name = EXCLUDED.sort_name, \
-               update_time = EXCLUDED.update_time \
-             WHERE artist.version < EXCLUDED.version",
-        );
-
-        let mut params: Vec<Value> = Vec::with_capacity(8);
-        params.push(Value::BigInt(Some(artist.id.as_i64())));
-        params.push(Value::BigInt(Some(artist.version)));
-        params.push(Value::String(Some(Box::new(artist.name.clone()))));
-        params.push(Value::BigInt(Some(
-            artist.genre.as_ref().map(|g| g.as_i64()).unwrap_or(0),
-        )));
-        params.push(Value::Array(
-            sea_orm::sea_query::ArrayType::BigInt,
-            Some(Box::new(
-                artist
-                    .genres
-                    .iter()
-                    .map(|g| Value::BigInt(Some(g.as_i64())))
-                    .collect(),
-            )),
-        ));
-        params.push(Value::String(Some(Box::new(artist.sort_name.clone()))));
-        params.push(Value::ChronoDateTime(Some(Box::new(now))));
-        params.push(Value::ChronoDateTime(Some(Box::new(now))));
-
-        let stmt = Statement::from_sql_and_values(DbBackend::Postgres, sql, params);
-        let result = self
-            .db
-            .execute(stmt)
-            .await
-            .map_err(|e| ArtistError::DbErr(e.to_string()))?;
-
-        // Check if update was skipped due to version conflict
-        if result.rows_affected() == 0 {
-            // Version conflict - get the current version using a simple SELECT query by id
-            let version_sql = "SELECT version FROM artist WHERE id = $1";
-            let version_params = vec![Value::BigInt(Some(artist.id.as_i64()))];
-            let version_stmt = Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                version_sql.to_string(),
-                version_params,
-            );
-            let version_result = self
-                .db
-                .query_one(version_stmt)
-                .await
-                .map_err(|e| ArtistError::DbErr(e.to_string()))?;
-
-            if let Some(version_row) = version_result {
-                let current_version: i64 = version_row
-                    .try_get("", "version")
-                    .map_err(|e| ArtistError::DbErr(format!("Failed to get version: {}", e)))?;
-                return Err(ArtistError::VersionConflict(current_version));
-            }
-            return Err(ArtistError::DbErr(
-                "Failed to insert or update artist".to_string(),
-            ));
-        }
-
-        Ok(artist)
+    async fn save(&self, artist: Artist) -> Result<Artist, ArtistError> {
+        save_artist(&self.db, artist).await
     }
 
     async fn delete(&self, artist_id: ArtistId) -> Result<(), ArtistError> {
-        Entity::delete_by_id(Into::<i64>::into(artist_id))
-            .exec(&self.db)
-            .await
-            .map_err(|e| ArtistError::DbErr(e.to_string()))?;
-        Ok(())
+        delete_artist(&self.db, artist_id).await
     }
 
     async fn by_id(&self, id: ArtistId) -> Result<Option<Artist>, ArtistError> {
@@ -147,6 +68,99 @@ impl ArtistRepository for ArtistRepositoryImpl {
     }
 }
 
+/// 保存艺术家，带乐观锁
+pub(crate) async fn save_artist<C: ConnectionTrait>(
+    conn: &C,
+    mut artist: Artist,
+) -> Result<Artist, ArtistError> {
+    artist.version += 1;
+    let now = Utc::now().naive_utc();
+
+    // Use INSERT ... ON CONFLICT to upsert artist by id
+    // id is generated by snowflake and remains constant
+    // create_time is set on insert, update_time is updated on conflict
+    let sql = String::from(
+        "INSERT INTO artist \
+         (id, version, name, genre_id, genre_ids, sort_name, create_time, update_time) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+         ON CONFLICT (id) \
+         DO UPDATE SET \
+           version = EXCLUDED.version, \
+           name = EXCLUDED.name, \
+           genre_id = EXCLUDED.genre_id, \
+           genre_ids = EXCLUDED.genre_ids, \
+           sort_name = EXCLUDED.sort_name, \
+           update_time = EXCLUDED.update_time \
+         WHERE artist.version < EXCLUDED.version",
+    );
+
+    let mut params: Vec<Value> = Vec::with_capacity(8);
+    params.push(Value::BigInt(Some(artist.id.as_i64())));
+    params.push(Value::BigInt(Some(artist.version)));
+    params.push(Value::String(Some(Box::new(artist.name.clone()))));
+    params.push(Value::BigInt(Some(
+        artist.genre.as_ref().map(|g| g.as_i64()).unwrap_or(0),
+    )));
+    params.push(Value::Array(
+        sea_orm::sea_query::ArrayType::BigInt,
+        Some(Box::new(
+            artist
+                .genres
+                .iter()
+                .map(|g| Value::BigInt(Some(g.as_i64())))
+                .collect(),
+        )),
+    ));
+    params.push(Value::String(Some(Box::new(artist.sort_name.clone()))));
+    params.push(Value::ChronoDateTime(Some(Box::new(now))));
+    params.push(Value::ChronoDateTime(Some(Box::new(now))));
+
+    let stmt = Statement::from_sql_and_values(DbBackend::Postgres, sql, params);
+    let result = conn
+        .execute(stmt)
+        .await
+        .map_err(|e| ArtistError::DbErr(e.to_string()))?;
+
+    // Check if update was skipped due to version conflict
+    if result.rows_affected() == 0 {
+        // Version conflict - get the current version using a simple SELECT query by id
+        let version_sql = "SELECT version FROM artist WHERE id = $1";
+        let version_params = vec![Value::BigInt(Some(artist.id.as_i64()))];
+        let version_stmt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            version_sql.to_string(),
+            version_params,
+        );
+        let version_result = conn
+            .query_one(version_stmt)
+            .await
+            .map_err(|e| ArtistError::DbErr(e.to_string()))?;
+
+        if let Some(version_row) = version_result {
+            let current_version: i64 = version_row
+                .try_get("", "version")
+                .map_err(|e| ArtistError::DbErr(format!("Failed to get version: {}", e)))?;
+            return Err(ArtistError::VersionConflict(current_version));
+        }
+        return Err(ArtistError::DbErr(
+            "Failed to insert or update artist".to_string(),
+        ));
+    }
+
+    Ok(artist)
+}
+
+pub(crate) async fn delete_artist<C: ConnectionTrait>(
+    conn: &C,
+    artist_id: ArtistId,
+) -> Result<(), ArtistError> {
+    Entity::delete_by_id(Into::<i64>::into(artist_id))
+        .exec(conn)
+        .await
+        .map_err(|e| ArtistError::DbErr(e.to_string()))?;
+    Ok(())
+}
+
 impl ArtistRepositoryImpl {
     /// Load participants for a specific artist
     pub async fn load_artist_participants(
@@ -279,3 +293,57 @@ impl ArtistRepositoryImpl {
         Ok(())
     }
 }
+
+#[derive(Clone)]
+pub struct ArtistAliasRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ArtistAliasRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ArtistAliasRepository for ArtistAliasRepositoryImpl {
+    async fn find_by_sort_name(&self, sort_name: &str) -> Result<Option<ArtistAlias>, ArtistError> {
+        let row = artist_alias::Entity::find_by_id(sort_name.to_string())
+            .one(&self.db)
+            .await
+            .map_err(|e| ArtistError::DbErr(e.to_string()))?;
+        Ok(row.map(|m| m.into()))
+    }
+
+    async fn find_by_artist(&self, artist_id: &ArtistId) -> Result<Vec<ArtistAlias>, ArtistError> {
+        let rows = artist_alias::Entity::find()
+            .filter(artist_alias::Column::ArtistId.eq(artist_id.as_i64()))
+            .order_by_asc(artist_alias::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| ArtistError::DbErr(e.to_string()))?;
+        Ok(rows.into_iter().map(|m| m.into()).collect())
+    }
+
+    async fn save(&self, alias: ArtistAlias) -> Result<(), ArtistError> {
+        let active_model: artist_alias::ActiveModel = alias.into();
+        artist_alias::Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::column(artist_alias::Column::SortName)
+                    .update_columns([artist_alias::Column::Name, artist_alias::Column::ArtistId])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| ArtistError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, sort_name: &str) -> Result<(), ArtistError> {
+        artist_alias::Entity::delete_by_id(sort_name.to_string())
+            .exec(&self.db)
+            .await
+            .map_err(|e| ArtistError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use application::query::shared::DiscographyCache;
+use application::query::QueryError;
+use async_trait::async_trait;
+use model::discography::{ArtistDiscography, ReleaseGroup};
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct ArtistDiscographyStoreImpl {
+    db: DatabaseConnection,
+}
+
+impl ArtistDiscographyStoreImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct DiscographyRow {
+    artist_id: i64,
+    mbz_artist_id: Option<String>,
+    release_groups: serde_json::Value,
+    fetched_at: chrono::NaiveDateTime,
+}
+
+#[async_trait]
+impl DiscographyCache for ArtistDiscographyStoreImpl {
+    async fn get(&self, artist_id: i64) -> Result<Option<ArtistDiscography>, QueryError> {
+        let sql = r#"
+            SELECT artist_id, mbz_artist_id, release_groups, fetched_at
+            FROM artist_discography
+            WHERE artist_id = $1
+        "#;
+        let row = DiscographyRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [artist_id.into()],
+        ))
+        .one(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(row.map(|row| ArtistDiscography {
+            artist_id: row.artist_id,
+            mbz_artist_id: row.mbz_artist_id,
+            // 无法解析的旧数据视为空，下次刷新时覆盖
+            release_groups: serde_json::from_value::<Vec<ReleaseGroup>>(row.release_groups)
+                .unwrap_or_default(),
+            fetched_at: row.fetched_at,
+        }))
+    }
+
+    async fn save(&self, discography: &ArtistDiscography) -> Result<(), QueryError> {
+        let release_groups = serde_json::to_value(&discography.release_groups)
+            .map_err(|e| QueryError::ExecutionError(e.to_string()))?;
+        let sql = r#"
+            INSERT INTO artist_discography (artist_id, mbz_artist_id, release_groups, fetched_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (artist_id) DO UPDATE SET
+                mbz_artist_id = EXCLUDED.mbz_artist_id,
+                release_groups = EXCLUDED.release_groups,
+                fetched_at = EXCLUDED.fetched_at
+        "#;
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [
+                    discography.artist_id.into(),
+                    discography.mbz_artist_id.clone().into(),
+                    Value::Json(Some(Box::new(release_groups))),
+                    discography.fetched_at.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use application::command::silence_analysis::{AudibleRangeStore, PendingTrack};
+use application::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::value::{AudibleRange, LibraryId, MediaPath};
+use sea_orm::{ConnectionTrait, DbBackend, FromQueryResult, Statement, Value};
+
+/// 直接读写 audio_file 的静音分析结果，不经过 AudioFile 聚合，不产生事件
+pub struct AudibleRangeStoreImpl {
+    db: sea_orm::DbConn,
+}
+
+impl AudibleRangeStoreImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct PendingRow {
+    id: i64,
+    path_protocol: String,
+    path_path: String,
+    duration: i64,
+}
+
+fn db_error(e: sea_orm::DbErr) -> AppError {
+    AppError::RepositoryError("audio_file".to_string(), e.to_string())
+}
+
+#[async_trait]
+impl AudibleRangeStore for AudibleRangeStoreImpl {
+    async fn pending(
+        &self,
+        library_id: Option<&LibraryId>,
+        limit: u64,
+    ) -> Result<Vec<PendingTrack>, AppError> {
+        // 缺失和存储不可用的文件无法读取，等恢复后再分析
+        let (filter, mut values): (&str, Vec<Value>) = match library_id {
+            Some(library_id) => ("AND library_id = $2", vec![library_id.as_i64().into()]),
+            None => ("", vec![]),
+        };
+        values.insert(0, (limit as i64).into());
+        let sql = format!(
+            "SELECT id, path_protocol, path_path, duration FROM audio_file \
+             WHERE (silence_analyzed_at IS NULL OR silence_analyzed_at < updated_at) \
+             AND missing_at IS NULL AND unavailable_at IS NULL {} \
+             ORDER BY id LIMIT $1",
+            filter
+        );
+        let rows = PendingRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await
+        .map_err(db_error)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| PendingTrack {
+                id: row.id,
+                path: MediaPath {
+                    protocol: row.path_protocol,
+                    path: row.path_path,
+                },
+                duration: row.duration * 1000,
+            })
+            .collect())
+    }
+
+    async fn save(
+        &self,
+        id: i64,
+        range: Option<AudibleRange>,
+        analyzed_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "UPDATE audio_file SET audible_start_ms = $1, audible_end_ms = $2, \
+                 silence_analyzed_at = $3 WHERE id = $4",
+                vec![
+                    range.map(|r| r.start).into(),
+                    range.map(|r| r.end).into(),
+                    analyzed_at.into(),
+                    id.into(),
+                ],
+            ))
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use application::command::file_check::{AudioFileAvailabilityStore, AudioFileLocation};
+use application::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::value::{LibraryId, MediaPath};
+use sea_orm::{ConnectionTrait, DbBackend, FromQueryResult, Statement, Value};
+
+/// 直接读写 audio_file.missing_at 和 unavailable_at，不经过 AudioFile 聚合，不产生事件
+pub struct AudioFileAvailabilityStoreImpl {
+    db: sea_orm::DbConn,
+}
+
+impl AudioFileAvailabilityStoreImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct LocationRow {
+    id: i64,
+    library_id: i64,
+    path_protocol: String,
+    path_path: String,
+    title: String,
+    missing_at: Option<NaiveDateTime>,
+}
+
+fn db_error(e: sea_orm::DbErr) -> AppError {
+    AppError::RepositoryError("audio_file".to_string(), e.to_string())
+}
+
+#[async_trait]
+impl AudioFileAvailabilityStore for AudioFileAvailabilityStoreImpl {
+    async fn list(
+        &self,
+        library_id: Option<&LibraryId>,
+    ) -> Result<Vec<AudioFileLocation>, AppError> {
+        let (sql, values): (&str, Vec<Value>) = match library_id {
+            Some(library_id) => (
+                "SELECT id, library_id, path_protocol, path_path, title, missing_at \
+                 FROM audio_file WHERE library_id = $1 ORDER BY id",
+                vec![library_id.as_i64().into()],
+            ),
+            None => (
+                "SELECT id, library_id, path_protocol, path_path, title, missing_at \
+                 FROM audio_file ORDER BY id",
+                vec![],
+            ),
+        };
+        let rows = LocationRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await
+        .map_err(db_error)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| AudioFileLocation {
+                id: row.id,
+                library_id: row.library_id.into(),
+                path: MediaPath {
+                    protocol: row.path_protocol,
+                    path: row.path_path,
+                },
+                title: row.title,
+                missing_at: row.missing_at,
+            })
+            .collect())
+    }
+
+    async fn set_missing_at(
+        &self,
+        ids: &[i64],
+        missing_at: Option<NaiveDateTime>,
+    ) -> Result<(), AppError> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "UPDATE audio_file SET missing_at = $1 WHERE id = ANY($2)",
+                vec![missing_at.into(), ids.to_vec().into()],
+            ))
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn set_unavailable_dirs(
+        &self,
+        library_id: &LibraryId,
+        dirs: &[String],
+        unavailable_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        // 按目录边界匹配，`/mnt/music` 不匹配 `/mnt/music2`
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "WITH files AS ( \
+                     SELECT id, EXISTS ( \
+                         SELECT 1 FROM unnest($3::text[]) AS d(dir) \
+                         WHERE path_path = d.dir \
+                            OR left(path_path, char_length(d.dir) + 1) = d.dir || '/' \
+                     ) AS unavailable \
+                     FROM audio_file WHERE library_id = $1 \
+                 ) \
+                 UPDATE audio_file af \
+                 SET unavailable_at = CASE WHEN f.unavailable \
+                     THEN COALESCE(af.unavailable_at, $2) ELSE NULL END \
+                 FROM files f \
+                 WHERE af.id = f.id AND (f.unavailable OR af.unavailable_at IS NOT NULL)",
+                vec![
+                    library_id.as_i64().into(),
+                    unavailable_at.into(),
+                    dirs.iter()
+                        .map(|dir| dir.trim_end_matches('/').to_string())
+                        .collect::<Vec<_>>()
+                        .into(),
+                ],
+            ))
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
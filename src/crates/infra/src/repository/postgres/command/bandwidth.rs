@@ -0,0 +1,83 @@
+use application::command::bandwidth::{BandwidthUsage, BandwidthUsageStore};
+use application::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sea_orm::{ConnectionTrait, DbBackend, FromQueryResult, Statement, Value};
+
+/// user_bandwidth 表，按用户和日期累加
+pub struct BandwidthUsageStoreImpl {
+    db: sea_orm::DbConn,
+}
+
+impl BandwidthUsageStoreImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct UsageRow {
+    user_id: i64,
+    user_name: String,
+    day: NaiveDate,
+    bytes: i64,
+    streams: i32,
+}
+
+fn db_error(e: sea_orm::DbErr) -> AppError {
+    AppError::RepositoryError("user_bandwidth".to_string(), e.to_string())
+}
+
+#[async_trait]
+impl BandwidthUsageStore for BandwidthUsageStoreImpl {
+    async fn record(&self, user_id: i64, day: NaiveDate, bytes: i64) -> Result<(), AppError> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "INSERT INTO user_bandwidth (user_id, day, bytes, streams) VALUES ($1, $2, $3, 1) \
+                 ON CONFLICT (user_id, day) DO UPDATE SET \
+                 bytes = user_bandwidth.bytes + EXCLUDED.bytes, \
+                 streams = user_bandwidth.streams + 1",
+                vec![user_id.into(), day.into(), bytes.into()],
+            ))
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn usage(
+        &self,
+        user_id: Option<i64>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<BandwidthUsage>, AppError> {
+        let mut sql = "SELECT b.user_id, u.username AS user_name, b.day, b.bytes, b.streams \
+                       FROM user_bandwidth b JOIN \"user\" u ON u.id = b.user_id \
+                       WHERE b.day BETWEEN $1 AND $2"
+            .to_string();
+        let mut values: Vec<Value> = vec![from.into(), to.into()];
+        if let Some(user_id) = user_id {
+            sql.push_str(" AND b.user_id = $3");
+            values.push(user_id.into());
+        }
+        sql.push_str(" ORDER BY b.day, u.username");
+        let rows = UsageRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await
+        .map_err(db_error)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| BandwidthUsage {
+                user_id: row.user_id,
+                user_name: row.user_name,
+                day: row.day,
+                bytes: row.bytes,
+                streams: row.streams,
+            })
+            .collect())
+    }
+}
@@ -1 +1,65 @@
- 
\ No newline at end of file
+use super::db_data::bookmark;
+use async_trait::async_trait;
+use domain::bookmark::{Bookmark, BookmarkError, BookmarkRepository};
+use domain::value::{AudioFileId, UserId};
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct BookmarkRepositoryImpl {
+    db: DbConn,
+}
+
+impl BookmarkRepositoryImpl {
+    pub fn new(db: DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl BookmarkRepository for BookmarkRepositoryImpl {
+    async fn find(
+        &self,
+        user_id: &UserId,
+        audio_file_id: &AudioFileId,
+    ) -> Result<Option<Bookmark>, BookmarkError> {
+        let row = bookmark::Entity::find_by_id((user_id.as_i64(), audio_file_id.as_i64()))
+            .one(&self.db)
+            .await
+            .map_err(|e| BookmarkError::DbErr(e.to_string()))?;
+        Ok(row.map(|m| m.into()))
+    }
+
+    async fn save(&self, value: &Bookmark) -> Result<(), BookmarkError> {
+        let active_model: bookmark::ActiveModel = value.into();
+        bookmark::Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    bookmark::Column::UserId,
+                    bookmark::Column::AudioFileId,
+                ])
+                .update_columns([
+                    bookmark::Column::Position,
+                    bookmark::Column::Comment,
+                    bookmark::Column::ChangedBy,
+                    bookmark::Column::UpdatedAt,
+                ])
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| BookmarkError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        user_id: &UserId,
+        audio_file_id: &AudioFileId,
+    ) -> Result<bool, BookmarkError> {
+        let result = bookmark::Entity::delete_by_id((user_id.as_i64(), audio_file_id.as_i64()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| BookmarkError::DbErr(e.to_string()))?;
+        Ok(result.rows_affected > 0)
+    }
+}
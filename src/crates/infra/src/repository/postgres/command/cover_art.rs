@@ -34,8 +34,9 @@ impl CoverArtRepository for CoverArtRepositoryImpl {
         let sql = String::from(
             "INSERT INTO cover_art \
              (id, version, audio_file_id, album_id, path_protocol, path_path, width, height, \
-              format, file_size, source, created_at, updated_at) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) \
+              format, file_size, source, created_at, updated_at, thumbnail_path, \
+              blurhash, dominant_color) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16) \
              ON CONFLICT (id) \
              DO UPDATE SET \
                version = EXCLUDED.version, \
@@ -48,11 +49,14 @@ impl CoverArtRepository for CoverArtRepositoryImpl {
                format = EXCLUDED.format, \
                file_size = EXCLUDED.file_size, \
                source = EXCLUDED.source, \
-               updated_at = EXCLUDED.updated_at \
+               updated_at = EXCLUDED.updated_at, \
+               thumbnail_path = EXCLUDED.thumbnail_path, \
+               blurhash = EXCLUDED.blurhash, \
+               dominant_color = EXCLUDED.dominant_color \
              WHERE cover_art.version < EXCLUDED.version",
         );
 
-        let mut params: Vec<Value> = Vec::with_capacity(13);
+        let mut params: Vec<Value> = Vec::with_capacity(16);
         params.push(Value::BigInt(Some(cover_art.id.clone().into())));
         params.push(Value::BigInt(Some(cover_art_version)));
         params.push(Value::BigInt(
@@ -70,6 +74,15 @@ impl CoverArtRepository for CoverArtRepositoryImpl {
         params.push(Value::String(Some(Box::new(cover_art.source.to_string()))));
         params.push(Value::ChronoDateTime(Some(Box::new(now.clone()))));
         params.push(Value::ChronoDateTime(Some(Box::new(now.clone()))));
+        params.push(Value::String(
+            cover_art.thumbnail_path.as_ref().map(|p| Box::new(p.clone())),
+        ));
+        params.push(Value::String(
+            cover_art.blurhash.as_ref().map(|h| Box::new(h.clone())),
+        ));
+        params.push(Value::String(
+            cover_art.dominant_color.as_ref().map(|c| Box::new(c.clone())),
+        ));
 
         let stmt = Statement::from_sql_and_values(DbBackend::Postgres, sql, params);
         let result = self
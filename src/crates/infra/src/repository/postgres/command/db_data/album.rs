@@ -44,6 +44,8 @@ pub struct Model {
     pub compilation: bool,
     pub sort_name: String,
     pub catalog_num: Option<String>,
+    pub edition: Option<String>,
+    pub explicit: bool,
 
     pub description: Option<String>,
 
@@ -121,6 +123,8 @@ impl From<Model> for agg::Album {
         album.compilation = model.compilation;
         album.sort_name = sort_name;
         album.catalog_num = catalog_num;
+        album.edition = model.edition;
+        album.explicit = model.explicit;
         album.description = model.description;
         album.version = model.version;
 
@@ -160,6 +164,8 @@ impl From<&agg::Album> for ActiveModel {
             compilation: Set(album.compilation),
             sort_name: Set(album.sort_name.clone()),
             catalog_num: Set(album.catalog_num.clone()),
+            edition: Set(album.edition.clone()),
+            explicit: Set(album.explicit),
             description: Set(album.description.clone()),
             create_time: Set(now),
             update_time: Set(now),
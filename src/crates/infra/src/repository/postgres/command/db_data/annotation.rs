@@ -106,6 +106,7 @@ impl Into<Annotation> for Model {
             played_count: self.played_count,
             played_at: self.played_at,
             version: self.version,
+            updated_at: Some(self.updated_at),
             pending_events: Vec::new(),
         }
     }
@@ -0,0 +1,49 @@
+//! `SeaORM` Entity for app_password table
+
+use domain::app_password::AppPassword;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "app_password")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub user_id: i64,
+    pub label: String,
+    #[sea_orm(column_type = "Text")]
+    pub encrypted_password: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for AppPassword {
+    fn from(model: Model) -> Self {
+        AppPassword {
+            id: model.id,
+            user_id: model.user_id.into(),
+            label: model.label,
+            encrypted_password: model.encrypted_password,
+            created_at: model.created_at,
+            last_used_at: model.last_used_at,
+        }
+    }
+}
+
+impl From<&AppPassword> for ActiveModel {
+    fn from(value: &AppPassword) -> Self {
+        Self {
+            id: Set(value.id),
+            user_id: Set(value.user_id.as_i64()),
+            label: Set(value.label.clone()),
+            encrypted_password: Set(value.encrypted_password.clone()),
+            created_at: Set(value.created_at),
+            last_used_at: Set(value.last_used_at),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! `SeaORM` Entity for artist_alias table
+
+use domain::artist::ArtistAlias;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "artist_alias")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub sort_name: String,
+    pub name: String,
+    pub artist_id: i64,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for ArtistAlias {
+    fn from(model: Model) -> Self {
+        ArtistAlias {
+            sort_name: model.sort_name,
+            name: model.name,
+            artist_id: model.artist_id.into(),
+        }
+    }
+}
+
+impl From<ArtistAlias> for ActiveModel {
+    fn from(value: ArtistAlias) -> Self {
+        Self {
+            sort_name: Set(value.sort_name),
+            name: Set(value.name),
+            artist_id: Set(value.artist_id.as_i64()),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
 use domain::audio_file::{AudioFile, AudioFileMeta};
-use domain::value::{AlbumId, ArtistId, AudioFileId, GenreId, LibraryId, MediaPath};
+use domain::value::{
+    AlbumId, ArtistId, AudioFileId, Chapter, GenreId, LibraryId, MediaPath, ReplayGain,
+};
 use sea_orm::entity::prelude::*;
 use sea_orm::ActiveValue::{NotSet, Set};
 use serde::{Deserialize, Serialize};
@@ -35,6 +37,7 @@ pub struct Model {
     pub title: String,
     pub track_number: Option<i32>,
     pub disc_number: Option<i32>,
+    pub disc_subtitle: Option<String>,
     pub year: Option<i32>,
     pub date: Option<i32>,
     pub original_year: Option<i32>,
@@ -43,6 +46,19 @@ pub struct Model {
     pub release_date: Option<i32>,
     pub compilation: bool,
     pub bpm: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub comment: Option<String>,
+    pub mbz_track_id: Option<String>,
+    pub rg_track_gain: Option<f64>,
+    pub rg_track_peak: Option<f64>,
+    pub rg_album_gain: Option<f64>,
+    pub rg_album_peak: Option<f64>,
+    pub explicit: bool,
+    pub work: Option<String>,
+    pub movement_name: Option<String>,
+    pub movement_number: Option<i32>,
+    pub movement_count: Option<i32>,
+    pub chapters: Json,
 
     // Timestamps
     pub created_at: chrono::NaiveDateTime,
@@ -68,6 +84,34 @@ impl RelationTrait for Relation {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+/// 章节以 [{"title", "start", "end"}] 的形式保存
+pub fn chapters_to_json(chapters: &[Chapter]) -> Json {
+    Json::Array(
+        chapters
+            .iter()
+            .map(|c| serde_json::json!({ "title": c.title, "start": c.start, "end": c.end }))
+            .collect(),
+    )
+}
+
+pub fn chapters_from_json(value: &Json) -> Vec<Chapter> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(Chapter {
+                        title: item.get("title")?.as_str()?.to_string(),
+                        start: item.get("start")?.as_i64()?,
+                        end: item.get("end")?.as_i64()?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Convert domain AudioFile to database ActiveModel
 impl From<AudioFile> for ActiveModel {
     fn from(audio_file: AudioFile) -> Self {
@@ -92,6 +136,7 @@ impl From<AudioFile> for ActiveModel {
             title: Set(audio_file.meta.title),
             track_number: Set(audio_file.meta.track_number),
             disc_number: Set(audio_file.meta.disc_number),
+            disc_subtitle: Set(audio_file.meta.disc_subtitle),
             year: Set(audio_file.meta.year),
             date: Set(audio_file.meta.date),
             original_year: Set(audio_file.meta.original_year),
@@ -100,6 +145,18 @@ impl From<AudioFile> for ActiveModel {
             release_date: Set(audio_file.meta.release_date),
             compilation: Set(audio_file.meta.compilation),
             bpm: Set(audio_file.meta.bpm),
+            comment: Set(audio_file.meta.comment),
+            mbz_track_id: Set(audio_file.meta.mbz_track_id),
+            rg_track_gain: Set(audio_file.meta.replay_gain.track_gain),
+            rg_track_peak: Set(audio_file.meta.replay_gain.track_peak),
+            rg_album_gain: Set(audio_file.meta.replay_gain.album_gain),
+            rg_album_peak: Set(audio_file.meta.replay_gain.album_peak),
+            explicit: Set(audio_file.meta.explicit),
+            work: Set(audio_file.meta.work),
+            movement_name: Set(audio_file.meta.movement_name),
+            movement_number: Set(audio_file.meta.movement_number),
+            movement_count: Set(audio_file.meta.movement_count),
+            chapters: Set(chapters_to_json(&audio_file.meta.chapters)),
             created_at: Set(audio_file.created_at),
             updated_at: Set(audio_file.updated_at),
             version: Set(audio_file.version),
@@ -114,6 +171,7 @@ impl From<Model> for AudioFile {
             title: model.title,
             track_number: model.track_number,
             disc_number: model.disc_number,
+            disc_subtitle: model.disc_subtitle,
             year: model.year,
             date: model.date,
             original_year: model.original_year,
@@ -122,6 +180,20 @@ impl From<Model> for AudioFile {
             release_date: model.release_date,
             compilation: model.compilation,
             bpm: model.bpm,
+            comment: model.comment,
+            mbz_track_id: model.mbz_track_id,
+            replay_gain: ReplayGain {
+                track_gain: model.rg_track_gain,
+                track_peak: model.rg_track_peak,
+                album_gain: model.rg_album_gain,
+                album_peak: model.rg_album_peak,
+            },
+            explicit: model.explicit,
+            work: model.work,
+            movement_name: model.movement_name,
+            movement_number: model.movement_number,
+            movement_count: model.movement_count,
+            chapters: chapters_from_json(&model.chapters),
         };
 
         Self {
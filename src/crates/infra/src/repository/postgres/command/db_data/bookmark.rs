@@ -1,92 +1,52 @@
-use super::song;
-use domain::bookmark::{Bookmark, EntityStatus};
-use sea_orm::{
-    entity::prelude::*,
-    ActiveValue::{NotSet, Set, Unchanged},
-};
+//! `SeaORM` Entity for bookmark table
+
+use domain::bookmark::Bookmark;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "bookmark")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
-    #[sea_orm(column_type = "BigInteger")]
-    pub id: i64,
-    #[sea_orm(column_type = "BigInteger")]
-    pub song_id: i64,
-    pub comment: String,
+    pub user_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub audio_file_id: i64,
     pub position: i64,
-    #[sea_orm(column_type = "BigInteger")]
-    pub changed_by: i64,
-    pub created_at: DateTime,
-    pub updated_at: DateTime,
-    #[sea_orm(column_type = "BigInteger")]
-    pub version: i64,
-}
-
-#[derive(Copy, Clone, Debug, EnumIter)]
-pub enum Relation {
-    Song,
-}
-
-impl RelationTrait for Relation {
-    fn def(&self) -> RelationDef {
-        match self {
-            Self::Song => Entity::belongs_to(song::Entity)
-                .from(Column::SongId)
-                .to(song::Column::Id)
-                .into(),
-        }
-    }
+    pub comment: String,
+    pub changed_by: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
 }
 
-impl Related<song::Entity> for Entity {
-    fn to() -> RelationDef {
-        Relation::Song.def()
-    }
-}
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
 
-impl From<Bookmark> for ActiveModel {
-    fn from(value: Bookmark) -> Self {
-        let now = chrono::Local::now().naive_utc();
-        Self {
-            id: if value.status == EntityStatus::New {
-                Set(value.id)
-            } else {
-                Unchanged(value.id)
-            },
-            song_id: Set(value.song_id),
-            comment: Set(value.comment),
-            position: Set(value.position),
-            changed_by: Set(value.changed_by),
-            created_at: if value.status == EntityStatus::New {
-                Set(now)
-            } else {
-                NotSet
-            },
-            updated_at: Set(now),
-            version: if value.status == EntityStatus::New {
-                Set(1)
-            } else {
-                Set(value.version)
-            },
+impl From<Model> for Bookmark {
+    fn from(model: Model) -> Self {
+        Bookmark {
+            user_id: model.user_id.into(),
+            audio_file_id: model.audio_file_id.into(),
+            position: model.position,
+            comment: model.comment,
+            changed_by: model.changed_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
         }
     }
 }
 
-impl From<Model> for Bookmark {
-    fn from(model: Model) -> Self {
+impl From<&Bookmark> for ActiveModel {
+    fn from(value: &Bookmark) -> Self {
         Self {
-            id: model.id,
-            song_id: model.song_id,
-            comment: model.comment,
-            position: model.position,
-            changed_by: model.changed_by,
-            version: model.version,
-            status: EntityStatus::Active,
-            old_version: model.version,
+            user_id: Set(value.user_id.as_i64()),
+            audio_file_id: Set(value.audio_file_id.as_i64()),
+            position: Set(value.position),
+            comment: Set(value.comment.clone()),
+            changed_by: Set(value.changed_by.clone()),
+            created_at: Set(value.created_at),
+            updated_at: Set(value.updated_at),
         }
     }
 }
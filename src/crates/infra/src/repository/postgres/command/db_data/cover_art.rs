@@ -28,6 +28,9 @@ pub struct Model {
     pub format: Option<String>, // CoverFormat as string
     pub file_size: i64,
     pub source: String, // CoverSourceType as string
+    pub thumbnail_path: Option<String>,
+    pub blurhash: Option<String>,
+    pub dominant_color: Option<String>,
 
     // Timestamps
     pub created_at: NaiveDateTime,
@@ -65,6 +68,9 @@ impl From<CoverArt> for ActiveModel {
             format: Set(cover_art.format.map(|f| f.to_string())),
             file_size: Set(cover_art.file_size),
             source: Set(cover_art.source.to_string()),
+            thumbnail_path: Set(cover_art.thumbnail_path),
+            blurhash: Set(cover_art.blurhash),
+            dominant_color: Set(cover_art.dominant_color),
             updated_at: Set(now),
             created_at: Set(now),
         }
@@ -109,6 +115,9 @@ impl From<Model> for CoverArt {
             format,
             file_size: model.file_size,
             source: source.clone(),
+            thumbnail_path: model.thumbnail_path,
+            blurhash: model.blurhash,
+            dominant_color: model.dominant_color,
         };
 
         let mut cover = CoverArt::from_dto(CoverArtId::from(model.id), dto).unwrap_or_else(|_| {
@@ -124,6 +133,9 @@ impl From<Model> for CoverArt {
                     format: None,
                     file_size: 1,
                     source,
+                    thumbnail_path: None,
+                    blurhash: None,
+                    dominant_color: None,
                 },
             )
             .expect("Failed to create CoverArt with minimal data")
@@ -0,0 +1,64 @@
+//! `SeaORM` Entity for event_dead_letter table
+
+use application::event::dead_letter::DeadLetter;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "event_dead_letter")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub event_id: String,
+    pub correlation_id: String,
+    pub event_type: String,
+    pub handler: String,
+    pub aggregate_id: i64,
+    pub version: i64,
+    #[sea_orm(column_type = "Text")]
+    pub error: String,
+    pub attempts: i32,
+    pub occurred_at: chrono::NaiveDateTime,
+    pub failed_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<&DeadLetter> for ActiveModel {
+    fn from(value: &DeadLetter) -> Self {
+        ActiveModel {
+            id: Set(value.id.clone()),
+            event_id: Set(value.event_id.clone()),
+            correlation_id: Set(value.correlation_id.clone()),
+            event_type: Set(value.event_type.clone()),
+            handler: Set(value.handler.clone()),
+            aggregate_id: Set(value.aggregate_id),
+            version: Set(value.version),
+            error: Set(value.error.clone()),
+            attempts: Set(value.attempts),
+            occurred_at: Set(value.occurred_at),
+            failed_at: Set(value.failed_at),
+        }
+    }
+}
+
+impl From<Model> for DeadLetter {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            event_id: model.event_id,
+            correlation_id: model.correlation_id,
+            event_type: model.event_type,
+            handler: model.handler,
+            aggregate_id: model.aggregate_id,
+            version: model.version,
+            error: model.error,
+            attempts: model.attempts,
+            occurred_at: model.occurred_at,
+            failed_at: model.failed_at,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! `SeaORM` Entity for event_outbox table
+
+use application::event::outbox::OutboxMessage;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "event_outbox")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub event_type: String,
+    pub aggregate_id: i64,
+    pub version: i64,
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+    pub correlation_id: String,
+    pub causation_id: String,
+    pub occurred_at: chrono::NaiveDateTime,
+    pub attempts: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_error: Option<String>,
+    pub delivered_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<OutboxMessage> for ActiveModel {
+    fn from(value: OutboxMessage) -> Self {
+        ActiveModel {
+            id: Set(value.id),
+            event_type: Set(value.event_type),
+            aggregate_id: Set(value.aggregate_id),
+            version: Set(value.version),
+            payload: Set(value.payload),
+            correlation_id: Set(value.correlation_id),
+            causation_id: Set(value.causation_id),
+            occurred_at: Set(value.occurred_at),
+            attempts: Set(value.attempts),
+            last_error: Set(value.last_error),
+            delivered_at: Set(None),
+        }
+    }
+}
+
+impl From<Model> for OutboxMessage {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            event_type: model.event_type,
+            aggregate_id: model.aggregate_id,
+            version: model.version,
+            payload: model.payload,
+            correlation_id: model.correlation_id,
+            causation_id: model.causation_id,
+            occurred_at: model.occurred_at,
+            attempts: model.attempts,
+            last_error: model.last_error,
+        }
+    }
+}
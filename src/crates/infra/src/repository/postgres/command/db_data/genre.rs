@@ -13,6 +13,8 @@ pub struct Model {
     pub id: i64,
     #[sea_orm(unique)]
     pub name: String,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub parent_id: Option<i64>,
     #[sea_orm(column_type = "BigInteger")]
     pub version: i64,
 }
@@ -45,6 +47,7 @@ impl From<Model> for agg::Genre {
         agg::Genre::new(GenreId::from(model.id), genre_name.clone())
             .unwrap()
             .with_version(model.version)
+            .with_parent(model.parent_id.map(GenreId::from))
     }
 }
 
@@ -53,6 +56,7 @@ impl From<agg::Genre> for ActiveModel {
         Self {
             id: Set(genre.id.into()),
             name: Set(genre.name.value()),
+            parent_id: Set(genre.parent_id.map(|id| id.as_i64())),
             version: Set(genre.version),
         }
     }
@@ -0,0 +1,38 @@
+//! `SeaORM` Entity for genre_alias table
+
+use domain::genre::GenreAlias;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "genre_alias")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub alias: String,
+    pub genre_name: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for GenreAlias {
+    fn from(model: Model) -> Self {
+        GenreAlias {
+            alias: model.alias,
+            genre_name: model.genre_name,
+        }
+    }
+}
+
+impl From<GenreAlias> for ActiveModel {
+    fn from(value: GenreAlias) -> Self {
+        Self {
+            alias: Set(value.alias),
+            genre_name: Set(value.genre_name),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
-use domain::library::{Library, ScanStatus};
+use domain::library::{Library, LibraryKind, ScanFilter, ScanStatus};
 use sea_orm::{entity::prelude::*, ActiveValue::Set};
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +17,14 @@ pub struct Model {
     pub last_scan_at: chrono::NaiveDateTime,
     #[sea_orm(column_type = "BigInteger")]
     pub version: i64,
+    pub enabled: bool,
+    pub scan_interval_minutes: Option<i32>,
+    pub kind: String,
+    pub include_patterns: Json,
+    pub exclude_patterns: Json,
+    pub scan_hidden: bool,
+    pub unavailable_since: Option<chrono::NaiveDateTime>,
+    pub hide_unavailable: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -34,6 +42,16 @@ impl From<Library> for Model {
             scan_status: library.scan_status.into(),
             last_scan_at: library.last_scan_at,
             version: library.version,
+            enabled: library.enabled,
+            scan_interval_minutes: library.scan_interval_minutes,
+            kind: library.kind.as_str().to_string(),
+            include_patterns: serde_json::to_value(&library.scan_filter.include)
+                .unwrap_or_default(),
+            exclude_patterns: serde_json::to_value(&library.scan_filter.exclude)
+                .unwrap_or_default(),
+            scan_hidden: library.scan_filter.scan_hidden,
+            unavailable_since: library.unavailable_since,
+            hide_unavailable: library.hide_unavailable,
         }
     }
 }
@@ -51,6 +69,16 @@ impl From<Model> for Library {
         library.scan_status = model.scan_status.try_into().unwrap_or(ScanStatus::Idle);
         library.last_scan_at = model.last_scan_at;
         library.version = model.version;
+        library.enabled = model.enabled;
+        library.scan_interval_minutes = model.scan_interval_minutes;
+        library.kind = LibraryKind::try_from(model.kind.as_str()).unwrap_or_default();
+        library.scan_filter = ScanFilter {
+            include: serde_json::from_value(model.include_patterns).unwrap_or_default(),
+            exclude: serde_json::from_value(model.exclude_patterns).unwrap_or_default(),
+            scan_hidden: model.scan_hidden,
+        };
+        library.unavailable_since = model.unavailable_since;
+        library.hide_unavailable = model.hide_unavailable;
 
         library
     }
@@ -66,6 +94,18 @@ impl From<Library> for ActiveModel {
             scan_status: Set(library.scan_status.into()),
             last_scan_at: Set(library.last_scan_at),
             version: Set(library.version),
+            enabled: Set(library.enabled),
+            scan_interval_minutes: Set(library.scan_interval_minutes),
+            kind: Set(library.kind.as_str().to_string()),
+            include_patterns: Set(
+                serde_json::to_value(&library.scan_filter.include).unwrap_or_default()
+            ),
+            exclude_patterns: Set(
+                serde_json::to_value(&library.scan_filter.exclude).unwrap_or_default()
+            ),
+            scan_hidden: Set(library.scan_filter.scan_hidden),
+            unavailable_since: Set(library.unavailable_since),
+            hide_unavailable: Set(library.hide_unavailable),
         }
     }
 }
@@ -0,0 +1,41 @@
+//! `SeaORM` Entity for media_asset table
+
+use domain::media_asset::MediaAsset;
+use sea_orm::{entity::prelude::*, ActiveValue::Set, NotSet};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "media_asset")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub library_id: i64,
+    pub path_protocol: String,
+    pub path_path: String,
+    pub dir_path: String,
+    pub kind: String,
+    pub size: i64,
+    pub mtime: chrono::NaiveDateTime,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<&MediaAsset> for ActiveModel {
+    fn from(value: &MediaAsset) -> Self {
+        ActiveModel {
+            id: Set(value.id),
+            library_id: Set(value.library_id.as_i64()),
+            path_protocol: Set(value.path.protocol.clone()),
+            path_path: Set(value.path.path.clone()),
+            dir_path: Set(value.dir_path.clone()),
+            kind: Set(value.kind.as_str().to_string()),
+            size: Set(value.size),
+            mtime: Set(value.mtime),
+            created_at: NotSet,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! `SeaORM` Entity for metadata_change_log table
+
+use domain::metadata_change::{MetadataChange, MetadataTarget};
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "metadata_change_log")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub target_type: String,
+    pub target_id: i64,
+    pub field: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub old_value: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub new_value: Option<String>,
+    pub changed_by: String,
+    pub tags_written: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<&MetadataChange> for ActiveModel {
+    fn from(value: &MetadataChange) -> Self {
+        ActiveModel {
+            id: Set(value.id),
+            target_type: Set(value.target.as_str().to_string()),
+            target_id: Set(value.target_id),
+            field: Set(value.field.clone()),
+            old_value: Set(value.old_value.clone()),
+            new_value: Set(value.new_value.clone()),
+            changed_by: Set(value.changed_by.clone()),
+            tags_written: Set(value.tags_written),
+            created_at: Set(value.created_at),
+        }
+    }
+}
+
+impl TryFrom<Model> for MetadataChange {
+    type Error = domain::metadata_change::MetadataChangeError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            target: MetadataTarget::try_from(model.target_type.as_str())?,
+            target_id: model.target_id,
+            field: model.field,
+            old_value: model.old_value,
+            new_value: model.new_value,
+            changed_by: model.changed_by,
+            tags_written: model.tags_written,
+            created_at: model.created_at,
+        })
+    }
+}
@@ -3,21 +3,37 @@
 pub mod prelude;
 
 pub mod album;
+pub mod app_password;
 //pub mod album_genre;
 pub mod annotation;
 pub mod artist;
+pub mod artist_alias;
 //pub mod artist_genre;
 pub mod audio_file;
+pub mod bookmark;
 pub mod cover_art;
+pub mod dead_letter;
+pub mod event_outbox;
 pub mod genre;
+pub mod genre_alias;
 pub mod library;
+pub mod library_credentials;
 pub mod library_item;
+pub mod media_asset;
+pub mod metadata_change_log;
 pub mod participant;
 pub mod play_queue;
 pub mod play_queue_item;
 pub mod player;
 pub mod playlist;
+pub mod playlist_collaborator;
 pub mod playlist_entry;
+pub mod playlist_tag;
+pub mod processed_event;
+pub mod scan_checkpoint;
+pub mod scan_error;
 pub mod system_config;
 pub mod transcoding;
 pub mod user;
+pub mod user_note;
+pub mod user_preference;
@@ -14,11 +14,15 @@ pub struct Model {
     /// Current playing audio file id
     #[sea_orm(column_type = "BigInteger", nullable)]
     pub current_id: Option<i64>,
+    /// Index of the current song, unambiguous when the queue has duplicates
+    pub current_index: Option<i32>,
     /// Position in milliseconds within the currently playing song
     #[sea_orm(column_type = "BigInteger")]
     pub position: i64,
     /// Client name that last changed this queue
     pub changed_by: String,
+    /// Client unique id; empty for the shared queue
+    pub client_id: String,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
@@ -53,23 +57,38 @@ impl From<&PlayQueue> for ActiveModel {
             pq.items.get(idx).map(|id| id.as_i64())
         });
         let now = chrono::Utc::now().naive_utc();
+        let updated_at = chrono::DateTime::from_timestamp_millis(pq.updated_at)
+            .map(|dt| dt.naive_utc())
+            .unwrap_or(now);
         Self {
             id: Set(pq.id.as_i64()),
             user_id: Set(pq.user_id.as_i64()),
             current_id: Set(current_id),
+            current_index: Set(pq.current_index.map(|idx| idx as i32)),
             position: Set(pq.position),
             changed_by: Set(pq.changed_by.clone()),
+            client_id: Set(pq.client_id.clone()),
             created_at: Set(now),
-            updated_at: Set(now),
+            updated_at: Set(updated_at),
         }
     }
 }
 
 impl Model {
     pub fn into_play_queue(self, items: Vec<AudioFileId>) -> PlayQueue {
-        let current_index = self.current_id.and_then(|cid| {
-            items.iter().position(|id| id.as_i64() == cid)
-        });
+        // 优先使用保存的索引，旧数据或索引与歌曲不一致时按 current_id 查找
+        let current_index = self
+            .current_index
+            .map(|idx| idx as usize)
+            .filter(|&idx| {
+                items
+                    .get(idx)
+                    .is_some_and(|id| self.current_id.is_none_or(|cid| id.as_i64() == cid))
+            })
+            .or_else(|| {
+                self.current_id
+                    .and_then(|cid| items.iter().position(|id| id.as_i64() == cid))
+            });
         PlayQueue {
             id: PlayQueueId::from(self.id),
             name: String::new(),
@@ -78,7 +97,8 @@ impl Model {
             current_index,
             position: self.position,
             changed_by: self.changed_by,
-            updated_at: self.updated_at.and_utc().timestamp(),
+            client_id: self.client_id,
+            updated_at: self.updated_at.and_utc().timestamp_millis(),
             pending_events: vec![],
         }
     }
@@ -17,8 +17,10 @@ pub struct Model {
     pub public: bool,
     #[sea_orm(column_type = "BigInteger")]
     pub version: i64,
+    pub cover_path: Option<String>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub deleted_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -55,8 +57,10 @@ impl From<&Playlist> for ActiveModel {
             owner_name: Set(playlist.owner.name.clone()),
             public: Set(playlist.public),
             version: Set(playlist.version),
+            cover_path: Set(playlist.cover_path.clone()),
             created_at: Set(playlist.created_at),
             updated_at: Set(playlist.updated_at),
+            deleted_at: Set(playlist.deleted_at),
         }
     }
 }
@@ -73,10 +77,13 @@ impl From<Model> for Playlist {
             },
             public: model.public,
             entries: Vec::new(),
+            collaborators: Vec::new(),
+            tags: Vec::new(),
+            cover_path: model.cover_path,
             created_at: model.created_at,
             updated_at: model.updated_at,
             version: model.version,
-            deleted: false,
+            deleted_at: model.deleted_at,
         }
     }
 }
@@ -1,6 +1,6 @@
 use chrono::Utc;
 use domain::playlist::PlaylistEntry;
-use domain::value::PlaylistId;
+use domain::value::{PlaylistId, UserId};
 use sea_orm::{
     entity::prelude::*,
     ActiveModelBehavior,
@@ -19,6 +19,8 @@ pub struct Model {
     pub audio_file_id: i64,
     pub position: i32,
     pub added_at: DateTime,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub added_by: Option<i64>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
@@ -56,6 +58,7 @@ impl From<&PlaylistEntry> for ActiveModel {
             audio_file_id: Set(entry.audio_file_id),
             position: Set(entry.position),
             added_at: Set(entry.added_at),
+            added_by: Set(entry.added_by.as_ref().map(|id| id.as_i64())),
             created_at: Set(now),
             updated_at: Set(now),
         }
@@ -70,6 +73,7 @@ impl From<Model> for PlaylistEntry {
             audio_file_id: model.audio_file_id,
             position: model.position,
             added_at: model.added_at,
+            added_by: model.added_by.map(UserId::from),
         }
     }
 }
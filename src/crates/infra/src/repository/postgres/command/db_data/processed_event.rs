@@ -0,0 +1,19 @@
+//! `SeaORM` Entity for processed_event table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "processed_event")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub handler: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub event_id: String,
+    pub processed_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
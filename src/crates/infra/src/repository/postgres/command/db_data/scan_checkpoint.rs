@@ -0,0 +1,48 @@
+//! `SeaORM` Entity for scan_checkpoint table
+
+use application::command::library::ScanCheckpoint;
+use domain::value::LibraryId;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "scan_checkpoint")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub library_id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub last_dir: String,
+    #[sea_orm(column_type = "Text")]
+    pub last_path: String,
+    pub scanned_files: i64,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for ScanCheckpoint {
+    fn from(model: Model) -> Self {
+        Self {
+            library_id: LibraryId::from(model.library_id),
+            last_dir: model.last_dir,
+            last_path: model.last_path,
+            scanned_files: model.scanned_files,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl From<&ScanCheckpoint> for ActiveModel {
+    fn from(checkpoint: &ScanCheckpoint) -> Self {
+        Self {
+            library_id: Set(checkpoint.library_id.as_i64()),
+            last_dir: Set(checkpoint.last_dir.clone()),
+            last_path: Set(checkpoint.last_path.clone()),
+            scanned_files: Set(checkpoint.scanned_files),
+            updated_at: Set(checkpoint.updated_at),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! `SeaORM` Entity for scan_error table
+
+use domain::scan_error::ScanError;
+use domain::value::{LibraryId, MediaPath};
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "scan_error")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub library_id: i64,
+    pub path_protocol: String,
+    pub path_path: String,
+    pub suffix: String,
+    pub size: i64,
+    pub mtime: chrono::NaiveDateTime,
+    #[sea_orm(column_type = "Text")]
+    pub error: String,
+    pub attempts: i32,
+    pub failed_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<ScanError> for ActiveModel {
+    fn from(value: ScanError) -> Self {
+        ActiveModel {
+            id: Set(value.id),
+            library_id: Set(value.library_id.as_i64()),
+            path_protocol: Set(value.path.protocol),
+            path_path: Set(value.path.path),
+            suffix: Set(value.suffix),
+            size: Set(value.size),
+            mtime: Set(value.mtime),
+            error: Set(value.error),
+            attempts: Set(value.attempts),
+            failed_at: Set(value.failed_at),
+        }
+    }
+}
+
+impl From<Model> for ScanError {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            library_id: LibraryId::from(model.library_id),
+            path: MediaPath {
+                protocol: model.path_protocol,
+                path: model.path_path,
+            },
+            suffix: model.suffix,
+            size: model.size,
+            mtime: model.mtime,
+            error: model.error,
+            attempts: model.attempts,
+            failed_at: model.failed_at,
+        }
+    }
+}
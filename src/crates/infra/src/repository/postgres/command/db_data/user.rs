@@ -1,6 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
-use domain::user::{User, UserStatus};
+use domain::user::{User, UserRoles, UserStatus};
 use sea_orm::entity::prelude::*;
 use sea_orm::ActiveValue::{NotSet, Set};
 use serde::{Deserialize, Serialize};
@@ -19,6 +19,10 @@ pub struct Model {
     pub encrypted_password: String,
     pub is_admin: bool,
     pub status: i32,
+    pub roles: i32,
+    pub max_bit_rate: Option<i32>,
+    pub download_quota_mb: Option<i64>,
+    pub hide_explicit: bool,
     pub last_login_at: chrono::NaiveDateTime,
     pub last_access_at: chrono::NaiveDateTime,
     pub last_op_time: chrono::NaiveDateTime,
@@ -55,6 +59,10 @@ impl From<User> for ActiveModel {
             encrypted_password: Set(user.encrypted_password.clone()),
             is_admin: Set(user.is_admin),
             status: Set(user.status.into()),
+            roles: Set(user.roles.to_bits()),
+            max_bit_rate: Set(user.max_bit_rate),
+            download_quota_mb: Set(user.download_quota_mb),
+            hide_explicit: Set(user.hide_explicit),
             last_login_at: Set(user.last_login_at),
             last_access_at: Set(user.last_access_at),
             last_op_time: Set(user.last_op_time),
@@ -79,6 +87,10 @@ impl From<Model> for User {
             last_access_at: model.last_access_at,
             last_op_time: model.last_op_time,
             status: UserStatus::try_from(model.status).unwrap_or(UserStatus::Active),
+            roles: UserRoles::from_bits(model.roles),
+            max_bit_rate: model.max_bit_rate,
+            download_quota_mb: model.download_quota_mb,
+            hide_explicit: model.hide_explicit,
             version: model.version,
             pending_events: Vec::new(), // Events are not persisted in the database
         }
@@ -0,0 +1,51 @@
+//! `SeaORM` Entity for user_note table
+
+use domain::user_note::UserNote;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "user_note")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_kind: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub note: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for UserNote {
+    fn from(model: Model) -> Self {
+        UserNote {
+            user_id: model.user_id.into(),
+            kind: model.item_kind.parse().unwrap_or_default(),
+            item_id: model.item_id,
+            note: model.note,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl From<&UserNote> for ActiveModel {
+    fn from(value: &UserNote) -> Self {
+        Self {
+            user_id: Set(value.user_id.as_i64()),
+            item_kind: Set(value.kind.name().to_string()),
+            item_id: Set(value.item_id),
+            note: Set(value.note.clone()),
+            created_at: Set(value.created_at),
+            updated_at: Set(value.updated_at),
+        }
+    }
+}
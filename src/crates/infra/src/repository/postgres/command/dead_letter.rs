@@ -0,0 +1,49 @@
+use super::db_data::dead_letter::{ActiveModel, Column, Entity, Model};
+use application::event::dead_letter::{DeadLetter, DeadLetterRepository};
+use async_trait::async_trait;
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct DeadLetterRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl DeadLetterRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl DeadLetterRepository for DeadLetterRepositoryImpl {
+    async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<DeadLetter>> {
+        let row = Entity::find_by_id(id.to_string()).one(&self.db).await?;
+        Ok(row.map(DeadLetter::from))
+    }
+
+    async fn find_all(&self) -> anyhow::Result<Vec<DeadLetter>> {
+        let rows: Vec<Model> = Entity::find()
+            .order_by_desc(Column::FailedAt)
+            .all(&self.db)
+            .await?;
+        Ok(rows.into_iter().map(DeadLetter::from).collect())
+    }
+
+    async fn save(&self, dead_letter: &DeadLetter) -> anyhow::Result<()> {
+        let active_model: ActiveModel = dead_letter.into();
+        Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::column(Column::Id)
+                    .update_columns([Column::Error, Column::Attempts, Column::FailedAt])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> anyhow::Result<()> {
+        Entity::delete_by_id(id.to_string()).exec(&self.db).await?;
+        Ok(())
+    }
+}
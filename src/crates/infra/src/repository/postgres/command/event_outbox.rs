@@ -0,0 +1,75 @@
+use super::db_data::event_outbox::{ActiveModel, Column, Entity, Model};
+use application::event::outbox::{OutboxMessage, OutboxRepository};
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct OutboxRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl OutboxRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+/// 在调用方的事务中写入 outbox
+pub(crate) async fn insert_messages<C: ConnectionTrait>(
+    conn: &C,
+    messages: Vec<OutboxMessage>,
+) -> Result<(), DbErr> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+    let models: Vec<ActiveModel> = messages.into_iter().map(ActiveModel::from).collect();
+    Entity::insert_many(models).exec(conn).await?;
+    Ok(())
+}
+
+#[async_trait]
+impl OutboxRepository for OutboxRepositoryImpl {
+    async fn find_pending(
+        &self,
+        max_attempts: i32,
+        limit: u64,
+    ) -> anyhow::Result<Vec<OutboxMessage>> {
+        let rows: Vec<Model> = Entity::find()
+            .filter(Column::DeliveredAt.is_null())
+            .filter(Column::Attempts.lt(max_attempts))
+            .order_by_asc(Column::OccurredAt)
+            .order_by_asc(Column::Version)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+        Ok(rows.into_iter().map(OutboxMessage::from).collect())
+    }
+
+    async fn mark_delivered(&self, id: &str) -> anyhow::Result<()> {
+        Entity::update_many()
+            .col_expr(Column::DeliveredAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_failure(&self, id: &str, error: &str) -> anyhow::Result<()> {
+        Entity::update_many()
+            .col_expr(Column::Attempts, Expr::col(Column::Attempts).add(1))
+            .col_expr(Column::LastError, Expr::value(error.to_string()))
+            .filter(Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn purge_delivered(&self, before: NaiveDateTime) -> anyhow::Result<u64> {
+        let result = Entity::delete_many()
+            .filter(Column::DeliveredAt.lt(before))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
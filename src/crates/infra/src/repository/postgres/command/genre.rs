@@ -1,6 +1,8 @@
-use super::db_data::{genre, genre::ActiveModel, genre::Entity, genre::Model};
+use super::db_data::{genre, genre::ActiveModel, genre::Entity, genre::Model, genre_alias};
 use async_trait::async_trait;
-use domain::genre::{Genre, GenreError, GenreName, GenreRepository};
+use domain::genre::{
+    Genre, GenreAlias, GenreAliasRepository, GenreError, GenreName, GenreRepository,
+};
 use domain::value::GenreId;
 use sea_orm::entity::prelude::*;
 use sea_orm::*;
@@ -35,6 +37,15 @@ impl GenreRepository for GenreRepositoryImpl {
         Ok(row.map(|m| m.into()))
     }
 
+    async fn find_all(&self) -> Result<Vec<Genre>, GenreError> {
+        let rows: Vec<Model> = Entity::find()
+            .order_by_asc(genre::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| GenreError::DbErr(e.to_string()))?;
+        Ok(rows.into_iter().map(|m| m.into()).collect())
+    }
+
     async fn save(&self, mut genre_agg: Genre) -> Result<Genre, GenreError> {
         genre_agg.version += 1;
         let genre_agg_cloned = genre_agg.clone();
@@ -81,3 +92,48 @@ impl GenreRepository for GenreRepositoryImpl {
         Ok(())
     }
 }
+
+#[derive(Clone)]
+pub struct GenreAliasRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl GenreAliasRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl GenreAliasRepository for GenreAliasRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<GenreAlias>, GenreError> {
+        let rows = genre_alias::Entity::find()
+            .order_by_asc(genre_alias::Column::Alias)
+            .all(&self.db)
+            .await
+            .map_err(|e| GenreError::DbErr(e.to_string()))?;
+        Ok(rows.into_iter().map(|m| m.into()).collect())
+    }
+
+    async fn save(&self, alias: GenreAlias) -> Result<(), GenreError> {
+        let active_model: genre_alias::ActiveModel = alias.into();
+        genre_alias::Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::column(genre_alias::Column::Alias)
+                    .update_column(genre_alias::Column::GenreName)
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| GenreError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, alias: &str) -> Result<(), GenreError> {
+        genre_alias::Entity::delete_by_id(alias.to_lowercase())
+            .exec(&self.db)
+            .await
+            .map_err(|e| GenreError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -1,11 +1,16 @@
+use crate::crypto::Aes256GcmEncryptor;
+use application::auth::PasswordEncryptor;
 use async_trait::async_trait;
-use domain::library::{Library, LibraryError, LibraryItem, LibraryRepository};
-use domain::value::LibraryId;
+use domain::library::{
+    Library, LibraryCredentials, LibraryCredentialsRepository, LibraryError, LibraryItem,
+    LibraryRepository,
+};
+use domain::value::{LibraryId, MediaPath};
 use sea_orm::*;
 use std::collections::HashSet;
 
 use super::db_data::{
-    library::ActiveModel, library::Column as LibraryColumn, library::Entity as LibraryEntity,
+    library::ActiveModel, library_credentials, library::Column as LibraryColumn, library::Entity as LibraryEntity,
     library_item::ActiveModel as ItemActiveModel, library_item::Column as ItemColumn,
     library_item::Entity as ItemEntity,
 };
@@ -67,21 +72,33 @@ impl LibraryRepository for LibraryRepositoryImpl {
                         .map(|(_, item)| item.clone().into())
                         .collect();
 
-                    // 更新库基本信息(带版本控制)
+                    // 更新库基本信息(带版本控制)，新建的库直接插入
                     let active_model: ActiveModel = library.clone().into();
-                    let update_condition = Condition::all()
-                        .add(LibraryColumn::Id.eq(library.id.as_i64()))
-                        .add(LibraryColumn::Version.lt(library.version + 1));
-
-                    let result = LibraryEntity::update_many()
-                        .set(active_model)
-                        .filter(update_condition)
-                        .exec(txn)
+                    let exists = LibraryEntity::find_by_id(library.id.as_i64())
+                        .one(txn)
                         .await
-                        .map_err(|e| LibraryError::DbError(e.to_string()))?;
+                        .map_err(|e| LibraryError::DbError(e.to_string()))?
+                        .is_some();
+                    if exists {
+                        let update_condition = Condition::all()
+                            .add(LibraryColumn::Id.eq(library.id.as_i64()))
+                            .add(LibraryColumn::Version.lt(library.version + 1));
 
-                    if result.rows_affected == 0 {
-                        return Err(LibraryError::DbError("版本号冲突".to_string()));
+                        let result = LibraryEntity::update_many()
+                            .set(active_model)
+                            .filter(update_condition)
+                            .exec(txn)
+                            .await
+                            .map_err(|e| LibraryError::DbError(e.to_string()))?;
+
+                        if result.rows_affected == 0 {
+                            return Err(LibraryError::DbError("版本号冲突".to_string()));
+                        }
+                    } else {
+                        LibraryEntity::insert(active_model)
+                            .exec(txn)
+                            .await
+                            .map_err(|e| LibraryError::DbError(e.to_string()))?;
                     }
 
                     // 批量删除不再需要的 items
@@ -151,4 +168,142 @@ impl LibraryRepository for LibraryRepositoryImpl {
 
         Ok(Some(library))
     }
+
+    async fn find_all(&self) -> Result<Vec<Library>, LibraryError> {
+        let models = LibraryEntity::find()
+            .order_by_asc(LibraryColumn::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| LibraryError::DbError(e.to_string()))?;
+        Ok(models.into_iter().map(Library::from).collect())
+    }
+
+    async fn delete(&self, id: &LibraryId) -> Result<(), LibraryError> {
+        let id = id.as_i64();
+        self.db
+            .transaction::<_, _, LibraryError>(|txn| {
+                Box::pin(async move {
+                    ItemEntity::delete_many()
+                        .filter(ItemColumn::LibraryId.eq(id))
+                        .exec(txn)
+                        .await
+                        .map_err(|e| LibraryError::DbError(e.to_string()))?;
+                    library_credentials::Entity::delete_by_id(id)
+                        .exec(txn)
+                        .await
+                        .map_err(|e| LibraryError::DbError(e.to_string()))?;
+                    let result = LibraryEntity::delete_by_id(id)
+                        .exec(txn)
+                        .await
+                        .map_err(|e| LibraryError::DbError(e.to_string()))?;
+                    if result.rows_affected == 0 {
+                        return Err(LibraryError::NotFound);
+                    }
+                    Ok(())
+                })
+            })
+            .await
+            .map_err(|e| match e {
+                TransactionError::Transaction(e) => e,
+                e => LibraryError::DbError(e.to_string()),
+            })
+    }
+}
+
+/// 库存储凭据仓储，密码在落库前加密、读取后解密
+#[derive(Clone)]
+pub struct LibraryCredentialsRepositoryImpl {
+    db: sea_orm::DbConn,
+    encryptor: Aes256GcmEncryptor,
+}
+
+impl LibraryCredentialsRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn, encryptor: Aes256GcmEncryptor) -> Self {
+        Self { db, encryptor }
+    }
+
+    fn decrypt(&self, model: library_credentials::Model) -> Result<LibraryCredentials, LibraryError> {
+        let password = self
+            .encryptor
+            .decrypt(&model.encrypted_password)
+            .map_err(|e| LibraryError::InvalidCredentials(e.to_string()))?;
+        Ok(LibraryCredentials {
+            library_id: LibraryId::from(model.library_id),
+            username: model.username,
+            password,
+            domain: model.domain,
+            updated_at: model.updated_at,
+        })
+    }
+}
+
+#[async_trait]
+impl LibraryCredentialsRepository for LibraryCredentialsRepositoryImpl {
+    async fn find_by_library(
+        &self,
+        library_id: &LibraryId,
+    ) -> Result<Option<LibraryCredentials>, LibraryError> {
+        let model = library_credentials::Entity::find_by_id(library_id.as_i64())
+            .one(&self.db)
+            .await
+            .map_err(|e| LibraryError::DbError(e.to_string()))?;
+        model.map(|m| self.decrypt(m)).transpose()
+    }
+
+    async fn find_for_path(
+        &self,
+        path: &MediaPath,
+    ) -> Result<Option<LibraryCredentials>, LibraryError> {
+        let model = library_credentials::Model::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"select c.library_id, c.username, c.encrypted_password, c.domain, c.updated_at
+               from library_credentials c
+               join library l on l.id = c.library_id
+               where l.path_protocol = $1 and starts_with($2, l.path_path)
+               order by length(l.path_path) desc
+               limit 1"#,
+            vec![path.protocol.clone().into(), path.path.clone().into()],
+        ))
+        .one(&self.db)
+        .await
+        .map_err(|e| LibraryError::DbError(e.to_string()))?;
+        model.map(|m| self.decrypt(m)).transpose()
+    }
+
+    async fn save(&self, credentials: &LibraryCredentials) -> Result<(), LibraryError> {
+        let encrypted_password = self
+            .encryptor
+            .encrypt(&credentials.password)
+            .map_err(|e| LibraryError::InvalidCredentials(e.to_string()))?;
+        let active_model = library_credentials::ActiveModel {
+            library_id: Set(credentials.library_id.as_i64()),
+            username: Set(credentials.username.clone()),
+            encrypted_password: Set(encrypted_password),
+            domain: Set(credentials.domain.clone()),
+            updated_at: Set(credentials.updated_at),
+        };
+        library_credentials::Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::column(library_credentials::Column::LibraryId)
+                    .update_columns([
+                        library_credentials::Column::Username,
+                        library_credentials::Column::EncryptedPassword,
+                        library_credentials::Column::Domain,
+                        library_credentials::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| LibraryError::DbError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, library_id: &LibraryId) -> Result<(), LibraryError> {
+        library_credentials::Entity::delete_by_id(library_id.as_i64())
+            .exec(&self.db)
+            .await
+            .map_err(|e| LibraryError::DbError(e.to_string()))?;
+        Ok(())
+    }
 }
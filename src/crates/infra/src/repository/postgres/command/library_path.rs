@@ -0,0 +1,217 @@
+use application::command::path_migration::LibraryPathStore;
+use application::error::AppError;
+use async_trait::async_trait;
+use domain::value::{LibraryId, MediaPath};
+use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait, Value};
+use std::collections::BTreeMap;
+
+/// 保存路径的表和列
+struct PathColumns {
+    table: &'static str,
+    /// 没有协议列的表不修改协议
+    protocol: Option<&'static str>,
+    /// 第一列用于判断是否在库的根目录下
+    paths: &'static [&'static str],
+    /// 按库过滤的列，没有该列的表（专辑、封面、位置统计）只按路径前缀匹配
+    library: Option<&'static str>,
+}
+
+const PATH_COLUMNS: &[PathColumns] = &[
+    PathColumns {
+        table: "library",
+        protocol: Some("path_protocol"),
+        paths: &["path_path"],
+        library: Some("id"),
+    },
+    PathColumns {
+        table: "library_item",
+        protocol: Some("path_protocol"),
+        paths: &["path_path"],
+        library: Some("library_id"),
+    },
+    PathColumns {
+        table: "audio_file",
+        protocol: Some("path_protocol"),
+        paths: &["path_path"],
+        library: Some("library_id"),
+    },
+    PathColumns {
+        table: "media_asset",
+        protocol: Some("path_protocol"),
+        paths: &["path_path", "dir_path"],
+        library: Some("library_id"),
+    },
+    PathColumns {
+        table: "scan_error",
+        protocol: Some("path_protocol"),
+        paths: &["path_path"],
+        library: Some("library_id"),
+    },
+    // 中断的扫描在移动后可以继续
+    PathColumns {
+        table: "scan_checkpoint",
+        protocol: None,
+        paths: &["last_dir", "last_path"],
+        library: Some("library_id"),
+    },
+    PathColumns {
+        table: "album",
+        protocol: Some("path_protocol"),
+        paths: &["path_path"],
+        library: None,
+    },
+    PathColumns {
+        table: "cover_art",
+        protocol: Some("path_protocol"),
+        paths: &["path_path"],
+        library: None,
+    },
+    PathColumns {
+        table: "album_location",
+        protocol: Some("location_protocol"),
+        paths: &["location_path"],
+        library: None,
+    },
+    PathColumns {
+        table: "artist_location",
+        protocol: Some("location_protocol"),
+        paths: &["location_path"],
+        library: None,
+    },
+];
+
+/// 按目录边界匹配前缀，`/mnt/music` 不匹配 `/mnt/music2`
+fn under_root(column: &str, from: &str) -> String {
+    format!(
+        "({column} = {from} OR left({column}, char_length({from}) + 1) = {from} || '/')",
+        column = column,
+        from = from
+    )
+}
+
+fn rewrite_statement(
+    columns: &PathColumns,
+    library_id: &LibraryId,
+    from: &MediaPath,
+    to: &MediaPath,
+) -> Statement {
+    let mut values: Vec<Value> = vec![from.path.clone().into(), to.path.clone().into()];
+    let mut assignments: Vec<String> = columns
+        .paths
+        .iter()
+        .map(|column| {
+            format!(
+                "{column} = CASE WHEN {matches} THEN $2 || substr({column}, char_length($1) + 1) \
+                 ELSE {column} END",
+                column = column,
+                matches = under_root(column, "$1")
+            )
+        })
+        .collect();
+    let mut conditions = vec![under_root(columns.paths[0], "$1")];
+    if let Some(protocol) = columns.protocol {
+        values.push(from.protocol.clone().into());
+        conditions.push(format!("{} = ${}", protocol, values.len()));
+        values.push(to.protocol.clone().into());
+        assignments.push(format!("{} = ${}", protocol, values.len()));
+    }
+    if let Some(library) = columns.library {
+        values.push(library_id.as_i64().into());
+        conditions.push(format!("{} = ${}", library, values.len()));
+    }
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        format!(
+            "UPDATE {} SET {} WHERE {}",
+            columns.table,
+            assignments.join(", "),
+            conditions.join(" AND ")
+        ),
+        values,
+    )
+}
+
+/// 直接改写各表中的路径，不经过聚合，不产生事件
+pub struct LibraryPathStoreImpl {
+    db: sea_orm::DbConn,
+}
+
+impl LibraryPathStoreImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+fn db_error(e: sea_orm::DbErr) -> AppError {
+    AppError::RepositoryError("library".to_string(), e.to_string())
+}
+
+#[async_trait]
+impl LibraryPathStore for LibraryPathStoreImpl {
+    async fn rewrite_prefix(
+        &self,
+        library_id: &LibraryId,
+        from: &MediaPath,
+        to: &MediaPath,
+        dry_run: bool,
+    ) -> Result<BTreeMap<String, u64>, AppError> {
+        let txn = self.db.begin().await.map_err(db_error)?;
+        let mut updated = BTreeMap::new();
+        for columns in PATH_COLUMNS {
+            let result = txn
+                .execute(rewrite_statement(columns, library_id, from, to))
+                .await
+                .map_err(db_error)?;
+            updated.insert(columns.table.to_string(), result.rows_affected());
+        }
+        if dry_run {
+            txn.rollback().await.map_err(db_error)?;
+        } else {
+            txn.commit().await.map_err(db_error)?;
+        }
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(path: &str) -> MediaPath {
+        MediaPath {
+            protocol: "local".to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn scopes_updates_by_protocol_and_library() {
+        let library_id = LibraryId::from(7);
+        let from = local("/mnt/music");
+        let to = local("/srv/music");
+
+        let audio_file = PATH_COLUMNS
+            .iter()
+            .find(|columns| columns.table == "audio_file")
+            .unwrap();
+        let statement = rewrite_statement(audio_file, &library_id, &from, &to);
+        assert_eq!(
+            statement.sql,
+            "UPDATE audio_file SET path_path = CASE WHEN (path_path = $1 OR \
+             left(path_path, char_length($1) + 1) = $1 || '/') \
+             THEN $2 || substr(path_path, char_length($1) + 1) ELSE path_path END, \
+             path_protocol = $4 \
+             WHERE (path_path = $1 OR left(path_path, char_length($1) + 1) = $1 || '/') \
+             AND path_protocol = $3 AND library_id = $5"
+        );
+        assert_eq!(statement.values.unwrap().0.len(), 5);
+
+        let checkpoint = PATH_COLUMNS
+            .iter()
+            .find(|columns| columns.table == "scan_checkpoint")
+            .unwrap();
+        let statement = rewrite_statement(checkpoint, &library_id, &from, &to);
+        assert!(statement.sql.ends_with("AND library_id = $3"));
+        assert_eq!(statement.values.unwrap().0.len(), 3);
+    }
+}
@@ -0,0 +1,49 @@
+use super::db_data::media_asset::{ActiveModel, Column, Entity};
+use async_trait::async_trait;
+use domain::media_asset::{MediaAsset, MediaAssetError, MediaAssetRepository};
+use domain::value::MediaPath;
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct MediaAssetRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl MediaAssetRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MediaAssetRepository for MediaAssetRepositoryImpl {
+    async fn save(&self, asset: &MediaAsset) -> Result<(), MediaAssetError> {
+        let active_model: ActiveModel = asset.into();
+        Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::columns([Column::PathProtocol, Column::PathPath])
+                    .update_columns([
+                        Column::LibraryId,
+                        Column::DirPath,
+                        Column::Kind,
+                        Column::Size,
+                        Column::Mtime,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| MediaAssetError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete_by_path(&self, path: &MediaPath) -> Result<(), MediaAssetError> {
+        Entity::delete_many()
+            .filter(Column::PathProtocol.eq(path.protocol.clone()))
+            .filter(Column::PathPath.eq(path.path.clone()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| MediaAssetError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use super::album::{delete_album, save_album};
+use super::annotation::reassign_annotations;
+use super::artist::{delete_artist, save_artist};
+use super::audio_file::save_audio_file;
+use application::command::merge::{MergeChanges, MergeStore};
+use application::error::AppError;
+use async_trait::async_trait;
+use sea_orm::TransactionTrait;
+
+/// 合并与拆分的修改在一个事务中写入，任何一步失败都会整体回滚
+pub struct MergeStoreImpl {
+    db: sea_orm::DbConn,
+}
+
+impl MergeStoreImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+fn db_error(e: sea_orm::DbErr) -> AppError {
+    AppError::RepositoryError("merge".to_string(), e.to_string())
+}
+
+#[async_trait]
+impl MergeStore for MergeStoreImpl {
+    async fn commit(&self, changes: MergeChanges) -> Result<MergeChanges, AppError> {
+        let txn = self.db.begin().await.map_err(db_error)?;
+        let mut saved = MergeChanges::default();
+        for audio_file in changes.audio_files {
+            saved
+                .audio_files
+                .push(save_audio_file(&txn, audio_file).await?);
+        }
+        for album in changes.albums {
+            saved.albums.push(save_album(&txn, album).await?);
+        }
+        for artist in changes.artists {
+            saved.artists.push(save_artist(&txn, artist).await?);
+        }
+        for (kind, from, to) in changes.reassigned_annotations {
+            reassign_annotations(&txn, kind.clone(), from, to).await?;
+            saved.reassigned_annotations.push((kind, from, to));
+        }
+        for album_id in changes.deleted_albums {
+            delete_album(&txn, album_id.clone()).await?;
+            saved.deleted_albums.push(album_id);
+        }
+        for artist_id in changes.deleted_artists {
+            delete_artist(&txn, artist_id.clone()).await?;
+            saved.deleted_artists.push(artist_id);
+        }
+        txn.commit().await.map_err(db_error)?;
+        Ok(saved)
+    }
+}
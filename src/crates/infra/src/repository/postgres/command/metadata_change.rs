@@ -0,0 +1,53 @@
+use super::db_data::metadata_change_log::{ActiveModel, Column, Entity};
+use async_trait::async_trait;
+use domain::metadata_change::{
+    MetadataChange, MetadataChangeError, MetadataChangeRepository, MetadataTarget,
+};
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct MetadataChangeRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl MetadataChangeRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MetadataChangeRepository for MetadataChangeRepositoryImpl {
+    async fn append(&self, changes: Vec<MetadataChange>) -> Result<(), MetadataChangeError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let models: Vec<ActiveModel> = changes.iter().map(ActiveModel::from).collect();
+        Entity::insert_many(models)
+            .exec(&self.db)
+            .await
+            .map_err(|e| MetadataChangeError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn find_recent(
+        &self,
+        target: Option<(MetadataTarget, i64)>,
+        limit: u64,
+    ) -> Result<Vec<MetadataChange>, MetadataChangeError> {
+        let mut query = Entity::find();
+        if let Some((target, target_id)) = target {
+            query = query
+                .filter(Column::TargetType.eq(target.as_str()))
+                .filter(Column::TargetId.eq(target_id));
+        }
+        let rows = query
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| MetadataChangeError::DbErr(e.to_string()))?;
+        rows.into_iter().map(MetadataChange::try_from).collect()
+    }
+}
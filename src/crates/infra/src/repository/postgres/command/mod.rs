@@ -1,15 +1,32 @@
 pub mod album;
+pub mod app_password;
 pub mod annotation;
 pub mod artist;
+pub mod artist_discography;
+pub mod audible_range;
 pub mod audio_file;
-//pub mod bookmark;
+pub mod audio_file_availability;
+pub mod bandwidth;
+pub mod dead_letter;
+pub mod event_outbox;
+pub mod bookmark;
 pub mod genre;
 pub mod library;
+pub mod library_path;
+pub mod media_asset;
+pub mod merge;
+pub mod metadata_change;
 pub mod play_queue;
+pub mod playback_history;
 pub mod player;
 pub mod playlist;
+pub mod processed_event;
+pub mod scan_checkpoint;
+pub mod scan_error;
+pub mod scrobble_submission;
 pub mod transcoding;
 pub mod cover_art;
 pub mod db_data;
 pub mod system_config;
 pub mod user;
+pub mod user_note;
@@ -46,9 +46,14 @@ impl PlayQueueRepository for PlayQueueRepositoryImpl {
         }
     }
 
-    async fn find_by_user_id(&self, user_id: UserId) -> Result<Option<PlayQueue>, PlayQueueError> {
+    async fn find_by_user_and_client(
+        &self,
+        user_id: UserId,
+        client_id: &str,
+    ) -> Result<Option<PlayQueue>, PlayQueueError> {
         let result: Option<Model> = Entity::find()
             .filter(play_queue::Column::UserId.eq(user_id.as_i64()))
+            .filter(play_queue::Column::ClientId.eq(client_id))
             .one(&self.db)
             .await
             .map_err(|e| PlayQueueError::DbErr(e.to_string()))?;
@@ -158,16 +163,21 @@ impl PlayQueueRepository for PlayQueueRepositoryImpl {
         Ok(())
     }
 
-    async fn delete_by_user_id(&self, user_id: UserId) -> Result<(), PlayQueueError> {
+    async fn delete_by_user_and_client(
+        &self,
+        user_id: UserId,
+        client_id: &str,
+    ) -> Result<(), PlayQueueError> {
         let txn = self
             .db
             .begin()
             .await
             .map_err(|e| PlayQueueError::DbErr(e.to_string()))?;
 
-        // Find the play queue for this user
+        // Find the play queue for this user and device
         let result: Option<Model> = Entity::find()
             .filter(play_queue::Column::UserId.eq(user_id.as_i64()))
+            .filter(play_queue::Column::ClientId.eq(client_id))
             .one(&txn)
             .await
             .map_err(|e| PlayQueueError::DbErr(e.to_string()))?;
@@ -0,0 +1,61 @@
+use application::command::playback_history::PlaybackHistoryStore;
+use application::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait};
+
+/// 按日/周/月把待删除的播放记录汇总进 play_count_rollup，周从周一开始，与 RollupPeriod 一致
+const ROLLUP_SQL: &str = r#"
+INSERT INTO play_count_rollup (period, period_start, user_id, item_kind, item_id, play_count)
+SELECT p.period,
+       CASE p.period
+           WHEN 'day' THEN ph.scrobbled_at::date
+           WHEN 'week' THEN date_trunc('week', ph.scrobbled_at)::date
+           ELSE date_trunc('month', ph.scrobbled_at)::date
+       END AS period_start,
+       ph.user_id, 'audio_file', ph.audio_file_id, COUNT(*)::int
+FROM playback_history ph
+CROSS JOIN (VALUES ('day'), ('week'), ('month')) AS p(period)
+WHERE ph.scrobbled_at < $1
+GROUP BY 1, 2, ph.user_id, ph.audio_file_id
+ON CONFLICT (period, period_start, user_id, item_kind, item_id)
+DO UPDATE SET play_count = GREATEST(play_count_rollup.play_count, EXCLUDED.play_count)
+"#;
+
+pub struct PlaybackHistoryStoreImpl {
+    db: sea_orm::DbConn,
+}
+
+impl PlaybackHistoryStoreImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+fn db_error(e: sea_orm::DbErr) -> AppError {
+    AppError::RepositoryError("playback_history".to_string(), e.to_string())
+}
+
+#[async_trait]
+impl PlaybackHistoryStore for PlaybackHistoryStoreImpl {
+    async fn prune(&self, before: NaiveDateTime) -> Result<u64, AppError> {
+        let txn = self.db.begin().await.map_err(db_error)?;
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            ROLLUP_SQL,
+            vec![before.into()],
+        ))
+        .await
+        .map_err(db_error)?;
+        let result = txn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "DELETE FROM playback_history WHERE scrobbled_at < $1",
+                vec![before.into()],
+            ))
+            .await
+            .map_err(db_error)?;
+        txn.commit().await.map_err(db_error)?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,6 +1,11 @@
 use super::db_data::{player, player::ActiveModel, player::Entity, player::Model};
+use super::event_outbox::insert_messages;
+use crate::event_bus::outbox::encode_player_event;
+use application::error::AppError;
+use application::event::event_bus::EventEnvelope;
+use application::event::outbox::PlayerOutbox;
 use async_trait::async_trait;
-use domain::player::{Player, PlayerError, PlayerRepository};
+use domain::player::{Player, PlayerError, PlayerEvent, PlayerRepository};
 use domain::value::PlayerId;
 use sea_orm::*;
 
@@ -30,39 +35,7 @@ impl PlayerRepository for PlayerRepositoryImpl {
     }
 
     async fn save(&self, player: &mut Player) -> Result<(), PlayerError> {
-        let mut active_model: ActiveModel = player.clone().into();
-        let existing = player::Entity::find_by_id(player.id.as_i64())
-            .one(&self.db)
-            .await
-            .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
-        if let Some(existing_model) = existing {
-            let existing_version = existing_model.version;
-            active_model.version = Set(existing_version + 1);
-            active_model.created_at = NotSet;
-            let update_condition = Condition::all()
-                .add(player::Column::Id.eq(player.id.as_i64()))
-                .add(player::Column::Version.lt(existing_version + 1));
-            let result = Entity::update_many()
-                .set(active_model)
-                .filter(update_condition)
-                .exec(&self.db)
-                .await
-                .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
-            if result.rows_affected == 0 {
-                return Err(PlayerError::VersionConflict {
-                    expected: player.version,
-                    actual: existing_version,
-                });
-            }
-            return Ok(());
-        } else {
-            active_model.version = Set(1);
-            Entity::insert(active_model)
-                .exec(&self.db)
-                .await
-                .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
-            return Ok(());
-        }
+        save_player(&self.db, player).await
     }
 
     async fn delete(&self, id: PlayerId) -> Result<(), PlayerError> {
@@ -73,3 +46,66 @@ impl PlayerRepository for PlayerRepositoryImpl {
         Ok(())
     }
 }
+
+#[async_trait]
+impl PlayerOutbox for PlayerRepositoryImpl {
+    async fn save_with_events(
+        &self,
+        player: &mut Player,
+        events: Vec<EventEnvelope<PlayerEvent>>,
+    ) -> Result<(), AppError> {
+        let messages = events
+            .iter()
+            .map(encode_player_event)
+            .collect::<Result<Vec<_>, _>>()?;
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
+        save_player(&txn, player).await?;
+        insert_messages(&txn, messages)
+            .await
+            .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
+        txn.commit()
+            .await
+            .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// 在给定连接（通常是事务）中保存播放器，带乐观锁
+async fn save_player<C: ConnectionTrait>(conn: &C, player: &Player) -> Result<(), PlayerError> {
+    let mut active_model: ActiveModel = player.clone().into();
+    let existing = player::Entity::find_by_id(player.id.as_i64())
+        .one(conn)
+        .await
+        .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
+    if let Some(existing_model) = existing {
+        let existing_version = existing_model.version;
+        active_model.version = Set(existing_version + 1);
+        active_model.created_at = NotSet;
+        let update_condition = Condition::all()
+            .add(player::Column::Id.eq(player.id.as_i64()))
+            .add(player::Column::Version.lt(existing_version + 1));
+        let result = Entity::update_many()
+            .set(active_model)
+            .filter(update_condition)
+            .exec(conn)
+            .await
+            .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
+        if result.rows_affected == 0 {
+            return Err(PlayerError::VersionConflict {
+                expected: player.version,
+                actual: existing_version,
+            });
+        }
+    } else {
+        active_model.version = Set(1);
+        Entity::insert(active_model)
+            .exec(conn)
+            .await
+            .map_err(|e| PlayerError::OtherErr(e.to_string()))?;
+    }
+    Ok(())
+}
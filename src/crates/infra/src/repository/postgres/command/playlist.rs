@@ -1,12 +1,18 @@
 use super::db_data::{
     playlist::{self, ActiveModel, Entity, Model},
+    playlist_collaborator::{
+        self, ActiveModel as CollaboratorActiveModel, Entity as CollaboratorEntity,
+    },
     playlist_entry::{self, ActiveModel as EntryActiveModel, Entity as EntryEntity, Model as EntryModel},
+    playlist_tag::{self, ActiveModel as TagActiveModel, Entity as TagEntity},
 };
 use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
 use domain::playlist::{Playlist, PlaylistEntry, PlaylistError, PlaylistRepository};
 use domain::value::{PlaylistId, UserId};
+use sea_orm::sea_query::Expr;
 use sea_orm::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone)]
 pub struct PlaylistRepositoryImpl {
@@ -28,6 +34,108 @@ impl PlaylistRepositoryImpl {
 
         Ok(entries.into_iter().map(|m| m.into()).collect())
     }
+
+    async fn load_collaborators(&self, playlist_id: i64) -> Result<Vec<UserId>, PlaylistError> {
+        let rows = CollaboratorEntity::find()
+            .filter(playlist_collaborator::Column::PlaylistId.eq(playlist_id))
+            .order_by_asc(playlist_collaborator::Column::AddedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|m| UserId::from(m.user_id)).collect())
+    }
+
+    async fn load_tags<C: ConnectionTrait>(
+        conn: &C,
+        playlist_id: i64,
+    ) -> Result<Vec<String>, PlaylistError> {
+        let rows = TagEntity::find()
+            .filter(playlist_tag::Column::PlaylistId.eq(playlist_id))
+            .order_by_asc(playlist_tag::Column::Position)
+            .all(conn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|m| m.tag).collect())
+    }
+
+    /// 标签很少，有变化时整体重写
+    async fn save_tags<C: ConnectionTrait>(
+        txn: &C,
+        playlist_id: i64,
+        tags: &[String],
+    ) -> Result<(), PlaylistError> {
+        if Self::load_tags(txn, playlist_id).await? == tags {
+            return Ok(());
+        }
+        TagEntity::delete_many()
+            .filter(playlist_tag::Column::PlaylistId.eq(playlist_id))
+            .exec(txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+
+        let to_insert: Vec<TagActiveModel> = tags
+            .iter()
+            .enumerate()
+            .map(|(i, tag)| TagActiveModel {
+                playlist_id: Set(playlist_id),
+                tag: Set(tag.clone()),
+                position: Set(i as i32),
+            })
+            .collect();
+        if !to_insert.is_empty() {
+            TagEntity::insert_many(to_insert)
+                .exec(txn)
+                .await
+                .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    async fn save_collaborators<C: ConnectionTrait>(
+        txn: &C,
+        playlist_id: i64,
+        collaborators: &[UserId],
+    ) -> Result<(), PlaylistError> {
+        let existing: HashSet<i64> = CollaboratorEntity::find()
+            .filter(playlist_collaborator::Column::PlaylistId.eq(playlist_id))
+            .all(txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?
+            .into_iter()
+            .map(|m| m.user_id)
+            .collect();
+        let wanted: HashSet<i64> = collaborators.iter().map(|id| id.as_i64()).collect();
+
+        let to_delete: Vec<i64> = existing.difference(&wanted).copied().collect();
+        if !to_delete.is_empty() {
+            CollaboratorEntity::delete_many()
+                .filter(playlist_collaborator::Column::PlaylistId.eq(playlist_id))
+                .filter(playlist_collaborator::Column::UserId.is_in(to_delete))
+                .exec(txn)
+                .await
+                .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        }
+
+        let now = Utc::now().naive_utc();
+        let to_insert: Vec<CollaboratorActiveModel> = collaborators
+            .iter()
+            .filter(|id| !existing.contains(&id.as_i64()))
+            .map(|id| CollaboratorActiveModel {
+                playlist_id: Set(playlist_id),
+                user_id: Set(id.as_i64()),
+                added_at: Set(now),
+            })
+            .collect();
+        if !to_insert.is_empty() {
+            CollaboratorEntity::insert_many(to_insert)
+                .exec(txn)
+                .await
+                .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -42,6 +150,8 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
             Some(model) => {
                 let mut playlist: Playlist = model.into();
                 playlist.entries = self.load_entries(id.as_i64()).await?;
+                playlist.collaborators = self.load_collaborators(id.as_i64()).await?;
+                playlist.tags = Self::load_tags(&self.db, id.as_i64()).await?;
                 Ok(Some(playlist))
             }
             None => Ok(None),
@@ -57,83 +167,103 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
 
         let playlist_id = playlist.id.as_i64();
 
-        if playlist.is_deleted() {
-            // 删除条目
-            EntryEntity::delete_many()
-                .filter(playlist_entry::Column::PlaylistId.eq(playlist_id))
-                .exec(&txn)
-                .await
-                .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        // 检查是否存在
+        let exists = Entity::find_by_id(playlist_id)
+            .one(&txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?
+            .is_some();
+
+        let mut active_model: ActiveModel = (&*playlist).into();
 
-            // 删除播放列表
-            Entity::delete_by_id(playlist_id)
+        if exists {
+            // 更新，版本与加载时不一致说明已被其他请求修改
+            active_model.version = Set(playlist.version + 1);
+            let result = Entity::update_many()
+                .set(active_model)
+                .filter(playlist::Column::Id.eq(playlist_id))
+                .filter(playlist::Column::Version.eq(playlist.version))
                 .exec(&txn)
                 .await
                 .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+            if result.rows_affected == 0 {
+                return Err(PlaylistError::VersionConflict(playlist_id));
+            }
         } else {
-            // 检查是否存在
-            let exists = Entity::find_by_id(playlist_id)
-                .one(&txn)
+            // 插入
+            active_model
+                .insert(&txn)
                 .await
-                .map_err(|e| PlaylistError::DbErr(e.to_string()))?
-                .is_some();
+                .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        }
 
-            let active_model: ActiveModel = (&*playlist).into();
+        // 加载现有条目 ID
+        let existing_entries: Vec<EntryModel> = EntryEntity::find()
+            .filter(playlist_entry::Column::PlaylistId.eq(playlist_id))
+            .all(&txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        let existing_ids: HashSet<i64> = existing_entries.iter().map(|e| e.id).collect();
+        let existing_positions: HashMap<i64, i32> = existing_entries
+            .iter()
+            .map(|e| (e.id, e.position))
+            .collect();
 
-            if exists {
-                // 更新
-                active_model
-                    .update(&txn)
-                    .await
-                    .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
-            } else {
-                // 插入
-                active_model
-                    .insert(&txn)
-                    .await
-                    .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
-            }
+        // 计算新条目 ID
+        let new_ids: HashSet<i64> = playlist.entries.iter().map(|e| e.id).collect();
 
-            // 加载现有条目 ID
-            let existing_entries: Vec<EntryModel> = EntryEntity::find()
-                .filter(playlist_entry::Column::PlaylistId.eq(playlist_id))
-                .all(&txn)
+        // 删除缺失的条目
+        let to_delete: Vec<i64> = existing_ids.difference(&new_ids).copied().collect();
+        if !to_delete.is_empty() {
+            EntryEntity::delete_many()
+                .filter(playlist_entry::Column::Id.is_in(to_delete))
+                .exec(&txn)
                 .await
                 .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
-            let existing_ids: HashSet<i64> = existing_entries.iter().map(|e| e.id).collect();
-
-            // 计算新条目 ID
-            let new_ids: HashSet<i64> = playlist.entries.iter().map(|e| e.id).collect();
+        }
 
-            // 删除缺失的条目
-            let to_delete: Vec<i64> = existing_ids.difference(&new_ids).copied().collect();
-            if !to_delete.is_empty() {
-                EntryEntity::delete_many()
-                    .filter(playlist_entry::Column::Id.is_in(to_delete))
-                    .exec(&txn)
-                    .await
-                    .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
-            }
+        // 插入新条目
+        let to_insert: Vec<EntryActiveModel> = playlist
+            .entries
+            .iter()
+            .filter(|e| !existing_ids.contains(&e.id))
+            .map(|e| e.into())
+            .collect();
+        if !to_insert.is_empty() {
+            EntryEntity::insert_many(to_insert)
+                .exec(&txn)
+                .await
+                .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        }
 
-            // 插入新条目
-            let to_insert: Vec<EntryActiveModel> = playlist
-                .entries
-                .iter()
-                .filter(|e| !existing_ids.contains(&e.id))
-                .map(|e| e.into())
-                .collect();
-            if !to_insert.is_empty() {
-                EntryEntity::insert_many(to_insert)
+        // 更新位置变化的已有条目
+        let now = Utc::now().naive_utc();
+        for entry in &playlist.entries {
+            let moved = existing_positions
+                .get(&entry.id)
+                .is_some_and(|&position| position != entry.position);
+            if moved {
+                EntryEntity::update_many()
+                    .col_expr(
+                        playlist_entry::Column::Position,
+                        Expr::value(entry.position),
+                    )
+                    .col_expr(playlist_entry::Column::UpdatedAt, Expr::value(now))
+                    .filter(playlist_entry::Column::Id.eq(entry.id))
                     .exec(&txn)
                     .await
                     .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
             }
         }
 
+        Self::save_collaborators(&txn, playlist_id, &playlist.collaborators).await?;
+        Self::save_tags(&txn, playlist_id, &playlist.tags).await?;
+
         txn.commit()
             .await
             .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
 
+        playlist.version += 1;
         Ok(())
     }
 
@@ -144,12 +274,22 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
             .await
             .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
 
-        // 删除条目
+        // 删除条目、协作者和标签
         EntryEntity::delete_many()
             .filter(playlist_entry::Column::PlaylistId.eq(id.as_i64()))
             .exec(&txn)
             .await
             .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        CollaboratorEntity::delete_many()
+            .filter(playlist_collaborator::Column::PlaylistId.eq(id.as_i64()))
+            .exec(&txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+        TagEntity::delete_many()
+            .filter(playlist_tag::Column::PlaylistId.eq(id.as_i64()))
+            .exec(&txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
 
         // 删除播放列表
         Entity::delete_by_id(id.as_i64())
@@ -164,6 +304,19 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
         Ok(())
     }
 
+    async fn find_deleted_before(
+        &self,
+        before: NaiveDateTime,
+    ) -> Result<Vec<Playlist>, PlaylistError> {
+        let playlists: Vec<Model> = Entity::find()
+            .filter(playlist::Column::DeletedAt.lt(before))
+            .all(&self.db)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+
+        Ok(playlists.into_iter().map(|m| m.into()).collect())
+    }
+
     async fn truncate(&self) -> Result<(), PlaylistError> {
         let txn = self
             .db
@@ -176,6 +329,16 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
             .await
             .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
 
+        CollaboratorEntity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+
+        TagEntity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(|e| PlaylistError::DbErr(e.to_string()))?;
+
         Entity::delete_many()
             .exec(&txn)
             .await
@@ -200,6 +363,8 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
             let playlist_id = model.id;
             let mut playlist: Playlist = model.into();
             playlist.entries = self.load_entries(playlist_id).await?;
+            playlist.collaborators = self.load_collaborators(playlist_id).await?;
+            playlist.tags = Self::load_tags(&self.db, playlist_id).await?;
             result.push(playlist);
         }
 
@@ -0,0 +1,76 @@
+use super::db_data::processed_event::{Column, Entity};
+use application::event::event_bus::EventId;
+use application::event::processed_event::ProcessedEventLedger;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct ProcessedEventRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ProcessedEventRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+
+    /// 删除 retention_days 天前的记录，只需覆盖重试和重放的时间窗口
+    pub async fn purge(&self, retention_days: i64) -> Result<u64, DbErr> {
+        let before = (Utc::now() - chrono::Duration::days(retention_days)).naive_utc();
+        let result = Entity::delete_many()
+            .filter(Column::ProcessedAt.lt(before))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 定期清理过期的处理记录
+    pub fn start_purger(self: &Arc<Self>, retention_days: i64, interval: Duration) {
+        let repo = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match repo.purge(retention_days).await {
+                    Ok(0) => {}
+                    Ok(n) => info!("Purged {} processed event records", n),
+                    Err(e) => error!("Failed to purge processed events: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl ProcessedEventLedger for ProcessedEventRepositoryImpl {
+    async fn try_mark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<bool> {
+        let result = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO processed_event (handler, event_id, processed_at)
+                   VALUES ($1, $2, $3)
+                   ON CONFLICT (handler, event_id) DO NOTHING"#,
+                [
+                    handler.into(),
+                    event_id.to_string().into(),
+                    Utc::now().naive_utc().into(),
+                ],
+            ))
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unmark(&self, handler: &str, event_id: &EventId) -> anyhow::Result<()> {
+        Entity::delete_many()
+            .filter(Column::Handler.eq(handler))
+            .filter(Column::EventId.eq(event_id.to_string()))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use super::db_data::scan_checkpoint::{ActiveModel, Column, Entity};
+use application::command::library::{ScanCheckpoint, ScanCheckpointStore};
+use async_trait::async_trait;
+use domain::value::LibraryId;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct ScanCheckpointRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ScanCheckpointRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ScanCheckpointStore for ScanCheckpointRepositoryImpl {
+    async fn find(&self, library_id: &LibraryId) -> anyhow::Result<Option<ScanCheckpoint>> {
+        let model = Entity::find_by_id(library_id.as_i64())
+            .one(&self.db)
+            .await?;
+        Ok(model.map(ScanCheckpoint::from))
+    }
+
+    async fn save(&self, checkpoint: &ScanCheckpoint) -> anyhow::Result<()> {
+        Entity::insert(ActiveModel::from(checkpoint))
+            .on_conflict(
+                OnConflict::column(Column::LibraryId)
+                    .update_columns([
+                        Column::LastDir,
+                        Column::LastPath,
+                        Column::ScannedFiles,
+                        Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, library_id: &LibraryId) -> anyhow::Result<()> {
+        Entity::delete_by_id(library_id.as_i64())
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use super::db_data::scan_error::{ActiveModel, Column, Entity, Model};
+use async_trait::async_trait;
+use domain::scan_error::{ScanError, ScanErrorError, ScanErrorRepository};
+use domain::value::MediaPath;
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct ScanErrorRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ScanErrorRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ScanErrorRepository for ScanErrorRepositoryImpl {
+    async fn find_by_id(&self, id: i64) -> Result<Option<ScanError>, ScanErrorError> {
+        let row = Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| ScanErrorError::DbErr(e.to_string()))?;
+        Ok(row.map(ScanError::from))
+    }
+
+    async fn find_by_path(&self, path: &MediaPath) -> Result<Option<ScanError>, ScanErrorError> {
+        let row = Entity::find()
+            .filter(Column::PathProtocol.eq(path.protocol.clone()))
+            .filter(Column::PathPath.eq(path.path.clone()))
+            .one(&self.db)
+            .await
+            .map_err(|e| ScanErrorError::DbErr(e.to_string()))?;
+        Ok(row.map(ScanError::from))
+    }
+
+    async fn find_all(&self) -> Result<Vec<ScanError>, ScanErrorError> {
+        let rows: Vec<Model> = Entity::find()
+            .order_by_desc(Column::FailedAt)
+            .order_by_desc(Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| ScanErrorError::DbErr(e.to_string()))?;
+        Ok(rows.into_iter().map(ScanError::from).collect())
+    }
+
+    async fn save(&self, scan_error: ScanError) -> Result<(), ScanErrorError> {
+        let active_model: ActiveModel = scan_error.into();
+        Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::column(Column::Id)
+                    .update_columns([
+                        Column::Suffix,
+                        Column::Size,
+                        Column::Mtime,
+                        Column::Error,
+                        Column::Attempts,
+                        Column::FailedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| ScanErrorError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<(), ScanErrorError> {
+        Entity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| ScanErrorError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use application::command::media_annotation::ScrobbleSubmissionStore;
+use application::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::value::{AudioFileId, UserId};
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+/// scrobble_submission 表，主键为 (user_id, audio_file_id, played_at)
+pub struct ScrobbleSubmissionStoreImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ScrobbleSubmissionStoreImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ScrobbleSubmissionStore for ScrobbleSubmissionStoreImpl {
+    async fn record(
+        &self,
+        user_id: &UserId,
+        audio_file_id: &AudioFileId,
+        played_at: NaiveDateTime,
+    ) -> Result<bool, AppError> {
+        let result = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "INSERT INTO scrobble_submission (user_id, audio_file_id, played_at) \
+                 VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+                vec![
+                    user_id.as_i64().into(),
+                    audio_file_id.as_i64().into(),
+                    played_at.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| {
+                AppError::RepositoryError("scrobble_submission".to_string(), e.to_string())
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -43,4 +43,9 @@ impl SystemConfigStore for SystemConfigStoreImpl {
 
         Ok(())
     }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        Entity::delete_by_id(key.to_string()).exec(&self.db).await?;
+        Ok(())
+    }
 }
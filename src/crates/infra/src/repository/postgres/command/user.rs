@@ -1,8 +1,10 @@
 use super::db_data::user::{self, ActiveModel, Column, Entity, Model};
+use super::db_data::user_preference;
 use async_trait::async_trait;
-use domain::user::{User, UserError};
+use domain::user::{User, UserError, UserPreferenceRepository};
 use domain::value::UserId;
 use sea_orm::*;
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct UserRepositoryImpl {
@@ -86,6 +88,17 @@ impl domain::user::UserRepository for UserRepositoryImpl {
 
         Ok(())
     }
+
+    async fn find_page(&self, offset: u64, limit: u64) -> Result<Vec<User>, UserError> {
+        let models: Vec<Model> = Entity::find()
+            .order_by_asc(Column::Username)
+            .offset(offset)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| UserError::DbErr(e.to_string()))?;
+        Ok(models.into_iter().map(User::from).collect())
+    }
 }
 
 impl UserRepositoryImpl {
@@ -104,3 +117,61 @@ impl UserRepositoryImpl {
         Ok(result.map(|model| model.into()))
     }
 }
+
+#[derive(Clone)]
+pub struct UserPreferenceRepositoryImpl {
+    db: DatabaseConnection,
+}
+
+impl UserPreferenceRepositoryImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl UserPreferenceRepository for UserPreferenceRepositoryImpl {
+    async fn find_all(&self, user_id: UserId) -> Result<HashMap<String, String>, UserError> {
+        let rows = user_preference::Entity::find()
+            .filter(user_preference::Column::UserId.eq(user_id.as_i64()))
+            .all(&self.db)
+            .await
+            .map_err(|e| UserError::DbErr(e.to_string()))?;
+        Ok(rows.into_iter().map(|row| (row.key, row.value)).collect())
+    }
+
+    async fn save(&self, user_id: UserId, key: &str, value: &str) -> Result<(), UserError> {
+        let active_model = user_preference::ActiveModel {
+            user_id: Set(user_id.as_i64()),
+            key: Set(key.to_string()),
+            value: Set(value.to_string()),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        };
+        user_preference::Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    user_preference::Column::UserId,
+                    user_preference::Column::Key,
+                ])
+                .update_columns([
+                    user_preference::Column::Value,
+                    user_preference::Column::UpdatedAt,
+                ])
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| UserError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: UserId, key: &str) -> Result<(), UserError> {
+        user_preference::Entity::delete_many()
+            .filter(user_preference::Column::UserId.eq(user_id.as_i64()))
+            .filter(user_preference::Column::Key.eq(key))
+            .exec(&self.db)
+            .await
+            .map_err(|e| UserError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use super::db_data::user_note;
+use async_trait::async_trait;
+use domain::annotation::Kind;
+use domain::user_note::{UserNote, UserNoteError, UserNoteRepository};
+use domain::value::UserId;
+use sea_orm::*;
+
+#[derive(Clone)]
+pub struct UserNoteRepositoryImpl {
+    db: DbConn,
+}
+
+impl UserNoteRepositoryImpl {
+    pub fn new(db: DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl UserNoteRepository for UserNoteRepositoryImpl {
+    async fn find(
+        &self,
+        user_id: &UserId,
+        kind: &Kind,
+        item_id: i64,
+    ) -> Result<Option<UserNote>, UserNoteError> {
+        let row =
+            user_note::Entity::find_by_id((user_id.as_i64(), kind.name().to_string(), item_id))
+                .one(&self.db)
+                .await
+                .map_err(|e| UserNoteError::DbErr(e.to_string()))?;
+        Ok(row.map(|m| m.into()))
+    }
+
+    async fn save(&self, value: &UserNote) -> Result<(), UserNoteError> {
+        let active_model: user_note::ActiveModel = value.into();
+        user_note::Entity::insert(active_model)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    user_note::Column::UserId,
+                    user_note::Column::ItemKind,
+                    user_note::Column::ItemId,
+                ])
+                .update_columns([user_note::Column::Note, user_note::Column::UpdatedAt])
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| UserNoteError::DbErr(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        user_id: &UserId,
+        kind: &Kind,
+        item_id: i64,
+    ) -> Result<bool, UserNoteError> {
+        let result =
+            user_note::Entity::delete_by_id((user_id.as_i64(), kind.name().to_string(), item_id))
+                .exec(&self.db)
+                .await
+                .map_err(|e| UserNoteError::DbErr(e.to_string()))?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -1,2 +1,4 @@
+pub mod backup;
 pub mod command;
 pub mod query;
+pub mod query_metrics;
@@ -4,16 +4,51 @@ use application::query::dao::AlbumDao;
 use application::query::QueryError;
 use async_trait::async_trait;
 use model::album::{Album, AlbumInfo, Discs};
-use model::shared::{Annotation, ArtistSummary, Contributor, GenreSummary};
+use model::shared::{Annotation, ArtistSummary, Contributor, CoverPlaceholder, GenreSummary};
 use sea_orm::*;
 
 pub struct AlbumDaoImpl {
     db: DatabaseConnection,
+    hide_explicit: bool,
+    user_id: Option<i64>,
 }
 
 impl AlbumDaoImpl {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            hide_explicit: false,
+            user_id: None,
+        }
+    }
+
+    /// 播放次数、评分和收藏只取该用户的注解；未指定用户时这些字段为空
+    pub fn with_user(mut self, user_id: Option<i64>) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    /// 列表和搜索中不返回 explicit 专辑，按 ID 查询不受影响
+    pub fn with_hide_explicit(mut self, hide_explicit: bool) -> Self {
+        self.hide_explicit = hide_explicit;
+        self
+    }
+
+    fn hides_explicit(&self, filter: &AlbumQueryFilter) -> bool {
+        self.hide_explicit
+            && !matches!(
+                filter,
+                AlbumQueryFilter::ById(_) | AlbumQueryFilter::ByIds(_)
+            )
+    }
+}
+
+/// 追加隐藏 explicit 专辑的条件
+fn hide_explicit_albums(where_clause: String, hide: bool) -> String {
+    match (hide, where_clause.is_empty()) {
+        (false, _) => where_clause,
+        (true, true) => "WHERE NOT al.explicit".to_string(),
+        (true, false) => format!("{} AND NOT al.explicit", where_clause),
     }
 }
 
@@ -25,6 +60,11 @@ struct AlbumBase {
     pub sort_name: String,
     pub order_name: String,
     pub compilation: bool,
+    pub edition: Option<String>,
+    pub explicit: bool,
+    pub min_original_year: Option<i32>,
+    pub original_date: Option<String>,
+    pub release_date: Option<String>,
     pub create_time: chrono::NaiveDateTime,
     pub update_time: chrono::NaiveDateTime,
     pub artist_id: i64,
@@ -33,6 +73,7 @@ struct AlbumBase {
     pub song_count: i32,
     pub duration: i64,
     pub disk_numbers: Vec<i32>,
+    pub disc_titles: Option<serde_json::Value>,
     pub year: i32,
     pub played_count: Option<i32>,
     pub played_at: Option<chrono::NaiveDateTime>,
@@ -61,13 +102,23 @@ struct SecondaryGenreRow {
     pub genre_name: String,
 }
 
+/// 封面占位数据
+#[derive(Debug, Clone, FromQueryResult)]
+struct CoverPlaceholderRow {
+    pub item_id: i64,
+    pub blurhash: String,
+    pub dominant_color: Option<String>,
+}
+
 /// 查询过滤器
 #[derive(Debug, Clone)]
 enum AlbumQueryFilter {
     ById(i64),
+    ByIds(Vec<i64>),
     ByArtistId(i64),
     ByStarred(i64), // user_id
-    ByGenre(String),
+    /// 专辑的任一流派匹配（genre_ids 包含）
+    ByGenreId(i64),
     ByYearRange(i32, i32),
     All,
 }
@@ -85,6 +136,10 @@ enum AlbumQueryOrderBy {
     ByStarred,
     ByRating,
     ByYear,
+    /// 原始发行年份，没有时使用发行年份
+    ByOriginalYear,
+    /// 收藏人数，取自 star_stats
+    ByMostStarred,
 }
 
 /// 查询选项
@@ -109,7 +164,11 @@ impl Default for AlbumQueryOptions {
 
 impl AlbumDaoImpl {
     /// 第一步：构建基础查询 SQL
-    fn build_base_query_sql(options: &AlbumQueryOptions) -> (String, Vec<Value>) {
+    fn build_base_query_sql(
+        options: &AlbumQueryOptions,
+        hide_explicit: bool,
+        user_id: Option<i64>,
+    ) -> (String, Vec<Value>) {
         let mut values: Vec<Value> = Vec::new();
         let mut param_index = 1;
 
@@ -123,7 +182,12 @@ impl AlbumDaoImpl {
                 param_index += 1;
                 format!("JOIN annotation an ON al.id = an.item_id AND an.item_kind = 'album' AND an.user_id = $1 AND an.starred = true")
             }
-            _ => "LEFT JOIN annotation an ON al.id = an.item_id AND an.item_kind = 'album'".to_string(),
+            _ => {
+                // 没有用户时绑定 NULL，不匹配任何注解
+                values.push(user_id.into());
+                param_index += 1;
+                "LEFT JOIN annotation an ON al.id = an.item_id AND an.item_kind = 'album' AND an.user_id = $1".to_string()
+            }
         };
 
         // 构建 WHERE 条件
@@ -133,6 +197,11 @@ impl AlbumDaoImpl {
                 param_index += 1;
                 format!("WHERE al.id = ${}", param_index - 1)
             }
+            AlbumQueryFilter::ByIds(ids) => {
+                values.push(ids.clone().into());
+                param_index += 1;
+                format!("WHERE al.id = ANY(${})", param_index - 1)
+            }
             AlbumQueryFilter::ByArtistId(id) => {
                 values.push((*id).into());
                 param_index += 1;
@@ -142,10 +211,10 @@ impl AlbumDaoImpl {
                 // user_id 已在 JOIN 条件中使用
                 String::new()
             }
-            AlbumQueryFilter::ByGenre(genre) => {
-                values.push(genre.clone().into());
+            AlbumQueryFilter::ByGenreId(genre_id) => {
+                values.push((*genre_id).into());
                 param_index += 1;
-                format!("WHERE lower(g.name) = lower(${})", param_index - 1)
+                format!("WHERE al.genre_ids @> ARRAY[${}]::bigint[]", param_index - 1)
             }
             AlbumQueryFilter::ByYearRange(from, to) => {
                 values.push((*from).into());
@@ -155,6 +224,7 @@ impl AlbumDaoImpl {
             }
             AlbumQueryFilter::All => String::new(),
         };
+        let where_clause = hide_explicit_albums(where_clause, hide_explicit);
 
         // 额外的 JOIN
         let extra_joins = if needs_artist_filter {
@@ -178,6 +248,10 @@ impl AlbumDaoImpl {
             AlbumQueryOrderBy::ByStarred => "ORDER BY starred_at DESC NULLS LAST",
             AlbumQueryOrderBy::ByRating => "ORDER BY COALESCE(rating, 0) DESC",
             AlbumQueryOrderBy::ByYear => "ORDER BY year, sort_name",
+            AlbumQueryOrderBy::ByOriginalYear => {
+                "ORDER BY COALESCE(min_original_year, NULLIF(year, 0)) NULLS LAST, sort_name"
+            }
+            AlbumQueryOrderBy::ByMostStarred => "ORDER BY star_count DESC, sort_name",
         };
 
         // LIMIT & OFFSET
@@ -196,14 +270,17 @@ impl AlbumDaoImpl {
             r#"SELECT * FROM (
                 SELECT DISTINCT ON (al.id)
                     al.id, al.name, al.sort_name, al.sort_name as order_name,
-                    al.compilation, al.create_time, al.update_time,
+                    al.compilation, al.edition, al.explicit, al.create_time, al.update_time,
+                    al.min_original_year, al.original_date, al.release_date,
                     COALESCE(ar.id, 0) as artist_id, COALESCE(ar.name, '') as artist_name,
-                    als.size, als.song_count, als.duration, als.disk_numbers, als.year,
+                    als.size, als.song_count, als.duration, als.disk_numbers, als.disc_titles, als.year,
                     an.played_count, an.played_at, an.rating, an.starred, an.starred_at,
-                    COALESCE(al.genre_id, 0) as genre_id, COALESCE(g.name, '') as genre_name
+                    COALESCE(al.genre_id, 0) as genre_id, COALESCE(g.name, '') as genre_name,
+                    COALESCE(ss.star_count, 0) as star_count
                 FROM album al
                 JOIN album_stats als ON al.id = als.album_id
                 {annotation_join}
+                LEFT JOIN star_stats ss ON ss.item_kind = 'album' AND ss.item_id = al.id
                 LEFT JOIN artist ar ON al.artist_id = ar.id
                 LEFT JOIN genre g ON al.genre_id = g.id{extra_joins}
                 {where_clause}
@@ -257,7 +334,9 @@ impl AlbumDaoImpl {
         for row in rows {
             let contributors = result.entry(row.album_id).or_default();
             // 去重
-            if !contributors.iter().any(|c| c.artist_id == row.artist_id && c.role == row.role) {
+            if !contributors.iter().any(|c| {
+                c.artist_id == row.artist_id && c.role == row.role && c.sub_role == row.sub_role
+            }) {
                 contributors.push(Contributor {
                     artist_id: row.artist_id,
                     artist_name: row.artist_name,
@@ -315,11 +394,59 @@ impl AlbumDaoImpl {
         Ok(result)
     }
 
+    /// 第四步：批量查询封面占位（取专辑第一首音轨的封面）
+    async fn query_cover_placeholders(
+        &self,
+        album_ids: &[i64],
+    ) -> Result<HashMap<i64, CoverPlaceholder>, QueryError> {
+        if album_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let placeholders: Vec<String> = (1..=album_ids.len())
+            .map(|i| format!("${}", i))
+            .collect();
+        let sql = format!(
+            r#"SELECT DISTINCT ON (af.album_id)
+                   af.album_id as item_id, ca.blurhash, ca.dominant_color
+               FROM cover_art ca
+               JOIN audio_file af ON ca.audio_file_id = af.id
+               WHERE af.album_id IN ({}) AND ca.blurhash IS NOT NULL
+               ORDER BY af.album_id, af.disc_number NULLS LAST, af.track_number NULLS LAST"#,
+            placeholders.join(", ")
+        );
+
+        let values: Vec<Value> = album_ids.iter().map(|id| (*id).into()).collect();
+        let rows: Vec<CoverPlaceholderRow> =
+            CoverPlaceholderRow::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &sql,
+                values,
+            ))
+            .all(&self.db)
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.item_id,
+                    CoverPlaceholder {
+                        blurhash: row.blurhash,
+                        dominant_color: row.dominant_color,
+                    },
+                )
+            })
+            .collect())
+    }
+
     /// 组装最终结果
     fn assemble_albums(
         base_albums: Vec<AlbumBase>,
         contributors: HashMap<i64, Vec<Contributor>>,
         secondary_genres: HashMap<i64, Vec<GenreSummary>>,
+        mut cover_placeholders: HashMap<i64, CoverPlaceholder>,
     ) -> Vec<Album> {
         base_albums
             .into_iter()
@@ -327,10 +454,16 @@ impl AlbumDaoImpl {
                 let album_contributors = contributors.get(&base.id).cloned().unwrap_or_default();
                 let genres = secondary_genres.get(&base.id).cloned().unwrap_or_default();
 
-                // 构建 disc_numbers 到 Discs
+                // 构建 disc_numbers 到 Discs，优先使用标签中的碟片副标题
+                let disc_titles: Discs = base
+                    .disc_titles
+                    .and_then(|v| serde_json::from_value(v).ok())
+                    .unwrap_or_default();
                 let mut discs: Discs = HashMap::new();
                 for disk_num in base.disk_numbers.iter() {
-                    if *disk_num == 0 {
+                    if let Some(title) = disc_titles.get(disk_num).filter(|t| !t.is_empty()) {
+                        discs.insert(*disk_num, title.clone());
+                    } else if *disk_num == 0 {
                         discs.insert(0, "#".to_string());
                     } else {
                         discs.insert(*disk_num, format!("Disc {}", *disk_num));
@@ -345,6 +478,11 @@ impl AlbumDaoImpl {
                     duration: base.duration,
                     year: if base.year != 0 { Some(base.year) } else { None },
                     compilation: base.compilation,
+                    edition: base.edition,
+                    explicit: base.explicit,
+                    original_year: base.min_original_year,
+                    original_date: base.original_date,
+                    release_date: base.release_date,
                     size: base.size,
                     discs,
                     sort_name: base.sort_name,
@@ -370,6 +508,7 @@ impl AlbumDaoImpl {
                         name: base.artist_name,
                     },
                     contributors: album_contributors,
+                    cover_placeholder: cover_placeholders.remove(&base.id),
                     created_at: base.create_time,
                     updated_at: base.update_time,
                 }
@@ -380,7 +519,11 @@ impl AlbumDaoImpl {
     /// 执行完整的三步查询
     async fn query_albums(&self, options: AlbumQueryOptions) -> Result<Vec<Album>, QueryError> {
         // 第一步：查询基础数据
-        let (sql, values) = Self::build_base_query_sql(&options);
+        let (sql, values) = Self::build_base_query_sql(
+            &options,
+            self.hides_explicit(&options.filter),
+            self.user_id,
+        );
         let base_albums: Vec<AlbumBase> =
             AlbumBase::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::Postgres,
@@ -398,14 +541,20 @@ impl AlbumDaoImpl {
         // 收集所有 album_id
         let ids: Vec<i64> = base_albums.iter().map(|a| a.id).collect();
 
-        // 第二步 & 第三步：并行查询 contributors 和 secondary_genres
-        let (contributors, secondary_genres) = tokio::try_join!(
+        // 第二步到第四步：并行查询 contributors、secondary_genres 和封面占位
+        let (contributors, secondary_genres, cover_placeholders) = tokio::try_join!(
             self.query_contributors(&ids),
-            self.query_secondary_genres(&ids)
+            self.query_secondary_genres(&ids),
+            self.query_cover_placeholders(&ids)
         )?;
 
         // 组装结果
-        Ok(Self::assemble_albums(base_albums, contributors, secondary_genres))
+        Ok(Self::assemble_albums(
+            base_albums,
+            contributors,
+            secondary_genres,
+            cover_placeholders,
+        ))
     }
 
     /// 带总数的查询（用于分页）
@@ -418,6 +567,10 @@ impl AlbumDaoImpl {
             AlbumQueryFilter::ById(id) => {
                 format!("WHERE al.id = {}", id)
             }
+            AlbumQueryFilter::ByIds(ids) => {
+                let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                format!("WHERE al.id IN ({})", ids.join(", "))
+            }
             AlbumQueryFilter::ByArtistId(id) => {
                 format!(
                     "WHERE EXISTS (SELECT 1 FROM participant p WHERE p.work_id = al.id AND p.work_type = 'Album' AND p.artist_id = {})",
@@ -427,14 +580,15 @@ impl AlbumDaoImpl {
             AlbumQueryFilter::ByStarred(user_id) => {
                 format!("WHERE an.starred = true AND an.user_id = {}", user_id)
             }
-            AlbumQueryFilter::ByGenre(genre) => {
-                format!("WHERE lower(g.name) = lower('{}')", genre.replace('\'', "''"))
+            AlbumQueryFilter::ByGenreId(genre_id) => {
+                format!("WHERE al.genre_ids @> ARRAY[{}]::bigint[]", genre_id)
             }
             AlbumQueryFilter::ByYearRange(from, to) => {
                 format!("WHERE als.year >= {} AND als.year <= {}", from, to)
             }
             AlbumQueryFilter::All => String::new(),
         };
+        let count_where = hide_explicit_albums(count_where, self.hides_explicit(&options.filter));
 
         let count_sql = format!(
             r#"SELECT COUNT(DISTINCT al.id) as total
@@ -473,6 +627,17 @@ impl AlbumDao for AlbumDaoImpl {
         Ok(results.into_iter().next())
     }
 
+    async fn get_by_ids(&self, ids: &[i64]) -> Result<Vec<Album>, QueryError> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let options = AlbumQueryOptions {
+            filter: AlbumQueryFilter::ByIds(ids.to_vec()),
+            ..Default::default()
+        };
+        self.query_albums(options).await
+    }
+
     async fn get_by_artist_id(&self, artist_id: i64) -> Result<Vec<Album>, QueryError> {
         let options = AlbumQueryOptions {
             filter: AlbumQueryFilter::ByArtistId(artist_id),
@@ -602,6 +767,20 @@ impl AlbumDao for AlbumDaoImpl {
         self.query_albums(options).await
     }
 
+    async fn get_by_most_starred(
+        &self,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<Album>, i64), QueryError> {
+        let options = AlbumQueryOptions {
+            filter: AlbumQueryFilter::All,
+            order_by: AlbumQueryOrderBy::ByMostStarred,
+            limit: Some(limit),
+            offset: Some(offset),
+        };
+        self.query_albums_with_count(options).await
+    }
+
     async fn get_by_rating(&self, offset: i32, limit: i32) -> Result<(Vec<Album>, i64), QueryError> {
         let options = AlbumQueryOptions {
             filter: AlbumQueryFilter::All,
@@ -612,19 +791,65 @@ impl AlbumDao for AlbumDaoImpl {
         self.query_albums_with_count(options).await
     }
 
+    async fn get_by_original_year(
+        &self,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<Album>, i64), QueryError> {
+        let options = AlbumQueryOptions {
+            filter: AlbumQueryFilter::All,
+            order_by: AlbumQueryOrderBy::ByOriginalYear,
+            limit: Some(limit),
+            offset: Some(offset),
+        };
+        self.query_albums_with_count(options).await
+    }
+
     async fn get_by_genre(
         &self,
         genre: &str,
         offset: i32,
         limit: i32,
     ) -> Result<(Vec<Album>, i64), QueryError> {
+        // 总数取自 genre_stats（由投影维护），分页时不需要再 COUNT 一遍 album 表
+        let row = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT g.id, GREATEST(COALESCE(gs.album_count, 0), 0)::bigint AS album_count
+                   FROM genre g
+                   LEFT JOIN genre_stats gs ON gs.genre_id = g.id
+                   WHERE lower(g.name) = lower($1)
+                   ORDER BY g.id
+                   LIMIT 1"#,
+                vec![genre.into()],
+            ))
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+        let Some(row) = row else {
+            return Ok((Vec::new(), 0));
+        };
+        let genre_id: i64 = row
+            .try_get_by_index(0)
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+        let total: i64 = row
+            .try_get_by_index(1)
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+        if total == 0 {
+            return Ok((Vec::new(), 0));
+        }
+
         let options = AlbumQueryOptions {
-            filter: AlbumQueryFilter::ByGenre(genre.to_string()),
+            filter: AlbumQueryFilter::ByGenreId(genre_id),
             order_by: AlbumQueryOrderBy::ByName,
             limit: Some(limit),
             offset: Some(offset),
         };
-        self.query_albums_with_count(options).await
+        // genre_stats 的总数包含 explicit 专辑，隐藏时需要重新计数
+        if self.hide_explicit {
+            return self.query_albums_with_count(options).await;
+        }
+        Ok((self.query_albums(options).await?, total))
     }
 
     async fn get_by_year(
@@ -651,31 +876,45 @@ impl AlbumDao for AlbumDaoImpl {
     ) -> Result<Vec<Album>, QueryError> {
         // 第一步：搜索匹配的 album 基础信息
         // 使用子查询解决 DISTINCT ON 与 ORDER BY 冲突
-        let sql = r#"SELECT * FROM (
+        let explicit_condition = if self.hide_explicit {
+            " AND NOT al.explicit"
+        } else {
+            ""
+        };
+        let sql = format!(
+            r#"SELECT * FROM (
                 SELECT DISTINCT ON (al.id)
                     al.id, al.name, al.sort_name, al.sort_name as order_name,
-                    al.compilation, al.create_time, al.update_time,
+                    al.compilation, al.edition, al.explicit, al.create_time, al.update_time,
+                    al.min_original_year, al.original_date, al.release_date,
                     COALESCE(ar.id, 0) as artist_id, COALESCE(ar.name, '') as artist_name,
-                    als.size, als.song_count, als.duration, als.disk_numbers, als.year,
+                    als.size, als.song_count, als.duration, als.disk_numbers, als.disc_titles, als.year,
                     an.played_count, an.played_at, an.rating, an.starred, an.starred_at,
                     COALESCE(al.genre_id, 0) as genre_id, COALESCE(g.name, '') as genre_name
                 FROM album al
                 JOIN album_stats als ON al.id = als.album_id
-                LEFT JOIN annotation an ON al.id = an.item_id AND an.item_kind = 'album'
+                LEFT JOIN annotation an ON al.id = an.item_id AND an.item_kind = 'album' AND an.user_id = $4
                 LEFT JOIN artist ar ON al.artist_id = ar.id
                 LEFT JOIN genre g ON al.genre_id = g.id
-                WHERE lower(al.name) LIKE lower($1) OR lower(al.sort_name) LIKE lower($1)
+                WHERE (lower(al.name) LIKE lower($1) OR lower(al.sort_name) LIKE lower($1)){}
                 ORDER BY al.id
             ) AS sub
             ORDER BY sort_name
-            LIMIT $2 OFFSET $3"#;
+            LIMIT $2 OFFSET $3"#,
+            explicit_condition
+        );
 
         let search_pattern = format!("%{}%", query);
         let base_albums: Vec<AlbumBase> =
             AlbumBase::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 sql,
-                vec![search_pattern.into(), limit.into(), offset.into()],
+                vec![
+                    search_pattern.into(),
+                    limit.into(),
+                    offset.into(),
+                    self.user_id.into(),
+                ],
             ))
             .all(&self.db)
             .await
@@ -688,13 +927,63 @@ impl AlbumDao for AlbumDaoImpl {
         // 收集所有 album_id
         let ids: Vec<i64> = base_albums.iter().map(|a| a.id).collect();
 
-        // 第二步 & 第三步：并行查询 contributors 和 secondary_genres
-        let (contributors, secondary_genres) = tokio::try_join!(
+        // 第二步到第四步：并行查询 contributors、secondary_genres 和封面占位
+        let (contributors, secondary_genres, cover_placeholders) = tokio::try_join!(
             self.query_contributors(&ids),
-            self.query_secondary_genres(&ids)
+            self.query_secondary_genres(&ids),
+            self.query_cover_placeholders(&ids)
         )?;
 
         // 组装结果
-        Ok(Self::assemble_albums(base_albums, contributors, secondary_genres))
+        Ok(Self::assemble_albums(
+            base_albums,
+            contributors,
+            secondary_genres,
+            cover_placeholders,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(filter: AlbumQueryFilter) -> AlbumQueryOptions {
+        AlbumQueryOptions {
+            filter,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn annotations_are_joined_for_the_requesting_user_only() {
+        let (sql, alice) = AlbumDaoImpl::build_base_query_sql(
+            &options(AlbumQueryFilter::ByGenreId(7)),
+            false,
+            Some(1),
+        );
+        let (_, bob) = AlbumDaoImpl::build_base_query_sql(
+            &options(AlbumQueryFilter::ByGenreId(7)),
+            false,
+            Some(2),
+        );
+        assert!(sql.contains("an.item_kind = 'album' AND an.user_id = $1"));
+        assert!(sql.contains("WHERE al.genre_ids @> ARRAY[$2]::bigint[]"));
+        assert_eq!(alice[0], Value::from(Some(1i64)));
+        assert_eq!(bob[0], Value::from(Some(2i64)));
+
+        // 未登录时绑定 NULL，不会借用其他用户的收藏和评分
+        let (_, anonymous) =
+            AlbumDaoImpl::build_base_query_sql(&options(AlbumQueryFilter::All), false, None);
+        assert_eq!(anonymous, vec![Value::from(None::<i64>)]);
+
+        // 收藏列表使用过滤条件中的用户
+        let (sql, values) = AlbumDaoImpl::build_base_query_sql(
+            &options(AlbumQueryFilter::ByStarred(2)),
+            false,
+            Some(1),
+        );
+        assert!(sql.contains("an.user_id = $1 AND an.starred = true"));
+        assert_eq!(values, vec![Value::from(2i64)]);
     }
 }
@@ -32,6 +32,11 @@ impl AlbumStatsRepository for MysqlAlbumStatsRepository {
             vec![]
         };
         
+        let initial_disc_titles = match (adjustment.disk_number, &adjustment.disc_subtitle) {
+            (Some(disk_num), Some(subtitle)) => serde_json::json!({ disk_num.to_string(): subtitle }),
+            _ => serde_json::json!({}),
+        };
+
         let initial_year = adjustment.year.unwrap_or(0);
         
         // Build the INSERT portion
@@ -41,6 +46,7 @@ impl AlbumStatsRepository for MysqlAlbumStatsRepository {
             size: Set(adjustment.size_delta),
             song_count: Set(adjustment.song_count_delta),
             disk_numbers: Set(initial_disk_numbers.clone()),
+            disc_titles: Set(initial_disc_titles.clone()),
             year: Set(initial_year),
         };
         
@@ -74,6 +80,18 @@ impl AlbumStatsRepository for MysqlAlbumStatsRepository {
             .to_owned();
         }
         
+        // Handle disc_titles update (merge subtitle into the jsonb object)
+        if let (Some(disk_num), Some(subtitle)) = (adjustment.disk_number, &adjustment.disc_subtitle) {
+            on_conflict = on_conflict.value(
+                stat_db::Column::DiscTitles,
+                Expr::cust_with_values(
+                    "COALESCE(disc_titles, '{}'::jsonb) || jsonb_build_object($1::text, $2::text)",
+                    vec![disk_num.to_string(), subtitle.clone()]
+                ),
+            )
+            .to_owned();
+        }
+
         // Handle year update (only set if current is 0 or NULL)
         if let Some(year_val) = adjustment.year {
             on_conflict = on_conflict.value(
@@ -117,6 +135,7 @@ impl AlbumStatsRepository for MysqlAlbumStatsRepository {
                 size: Set(album_stats.size),
                 song_count: Set(album_stats.song_count),
                 disk_numbers: Set(album_stats.disk_numbers.clone()),
+                disc_titles: Set(serde_json::to_value(&album_stats.disc_titles).unwrap_or_default()),
                 year: Set(album_stats.year.unwrap_or(0)),
             })
             .exec(&self.db)
@@ -134,6 +153,7 @@ impl AlbumStatsRepository for MysqlAlbumStatsRepository {
                     size: Set(album_stats.size),
                     song_count: Set(album_stats.song_count),
                     disk_numbers: Set(album_stats.disk_numbers.clone()),
+                    disc_titles: Set(serde_json::to_value(&album_stats.disc_titles).unwrap_or_default()),
                     year: Set(album_stats.year.unwrap_or(0)),
                 };
 
@@ -0,0 +1,68 @@
+use application::query::dao::AnnotationDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use model::annotation::UserAnnotation;
+use sea_orm::*;
+
+pub struct AnnotationDaoImpl {
+    db: sea_orm::DbConn,
+}
+
+impl AnnotationDaoImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AnnotationRow {
+    item_kind: String,
+    item_id: i64,
+    starred: bool,
+    starred_at: NaiveDateTime,
+    rating: i32,
+    played_count: i32,
+    played_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<AnnotationRow> for UserAnnotation {
+    fn from(row: AnnotationRow) -> Self {
+        Self {
+            item_kind: row.item_kind,
+            item_id: row.item_id,
+            starred: row.starred,
+            starred_at: row.starred_at,
+            rating: row.rating,
+            play_count: row.played_count,
+            played_at: row.played_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[async_trait]
+impl AnnotationDao for AnnotationDaoImpl {
+    async fn get_by_user(
+        &self,
+        user_id: i64,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<UserAnnotation>, QueryError> {
+        let rows = AnnotationRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT item_kind, item_id, starred, starred_at, rating, played_count,
+                      played_at, updated_at
+               FROM annotation
+               WHERE user_id = $1
+                 AND item_kind IN ('audio_file', 'album', 'artist')
+                 AND ($2::timestamp IS NULL OR updated_at > $2)
+               ORDER BY updated_at, id"#,
+            vec![user_id.into(), since.into()],
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
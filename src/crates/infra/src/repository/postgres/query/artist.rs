@@ -8,11 +8,18 @@ use sea_orm::*;
 
 pub struct ArtistDaoImpl {
     db: DatabaseConnection,
+    user_id: Option<i64>,
 }
 
 impl ArtistDaoImpl {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, user_id: None }
+    }
+
+    /// 播放次数、评分和收藏只取该用户的注解；未指定用户时这些字段为空
+    pub fn with_user(mut self, user_id: Option<i64>) -> Self {
+        self.user_id = user_id;
+        self
     }
 }
 
@@ -49,6 +56,7 @@ struct RoleStatsRow {
 #[derive(Debug, Clone)]
 enum ArtistQueryFilter {
     ById(i64),
+    ByIds(Vec<i64>),
     ByStarred(i64), // user_id
     All,
 }
@@ -85,7 +93,10 @@ impl Default for ArtistQueryOptions {
 
 impl ArtistDaoImpl {
     /// 第一步：查询 artist 基础信息（只返回有 'Artist' role 的艺术家）
-    fn build_base_query_sql(options: &ArtistQueryOptions) -> (String, Vec<Value>) {
+    fn build_base_query_sql(
+        options: &ArtistQueryOptions,
+        user_id: Option<i64>,
+    ) -> (String, Vec<Value>) {
         let mut values: Vec<Value> = Vec::new();
         let mut param_index = 1;
 
@@ -96,7 +107,12 @@ impl ArtistDaoImpl {
                 param_index += 1;
                 format!("JOIN annotation an ON ar.id = an.item_id AND an.item_kind = 'artist' AND an.user_id = $1 AND an.starred = true")
             }
-            _ => "LEFT JOIN annotation an ON ar.id = an.item_id AND an.item_kind = 'artist'".to_string(),
+            _ => {
+                // 没有用户时绑定 NULL，不匹配任何注解
+                values.push(user_id.into());
+                param_index += 1;
+                "LEFT JOIN annotation an ON ar.id = an.item_id AND an.item_kind = 'artist' AND an.user_id = $1".to_string()
+            }
         };
 
         // 构建 WHERE 条件
@@ -106,6 +122,11 @@ impl ArtistDaoImpl {
                 param_index += 1;
                 format!("WHERE ar.id = ${} AND ps.role = 'Artist'", param_index - 1)
             }
+            ArtistQueryFilter::ByIds(ids) => {
+                values.push(ids.clone().into());
+                param_index += 1;
+                format!("WHERE ar.id = ANY(${}) AND ps.role = 'Artist'", param_index - 1)
+            }
             ArtistQueryFilter::ByStarred(_) => {
                 // user_id 已在 JOIN 条件中使用
                 "WHERE ps.role = 'Artist'".to_string()
@@ -241,7 +262,7 @@ impl ArtistDaoImpl {
         options: ArtistQueryOptions,
     ) -> Result<Vec<Artist>, QueryError> {
         // 第一步：查询基础数据
-        let (sql, values) = Self::build_base_query_sql(&options);
+        let (sql, values) = Self::build_base_query_sql(&options, self.user_id);
         let base_artists: Vec<ArtistBase> =
             ArtistBase::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::Postgres,
@@ -278,6 +299,17 @@ impl ArtistDao for ArtistDaoImpl {
         Ok(results.into_iter().next())
     }
 
+    async fn get_by_ids(&self, ids: &[i64]) -> Result<Vec<Artist>, QueryError> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let options = ArtistQueryOptions {
+            filter: ArtistQueryFilter::ByIds(ids.to_vec()),
+            ..Default::default()
+        };
+        self.query_artists(options).await
+    }
+
     async fn get_all(&self) -> Result<Vec<Artist>, QueryError> {
         let options = ArtistQueryOptions::default();
         self.query_artists(options).await
@@ -294,7 +326,7 @@ impl ArtistDao for ArtistDaoImpl {
                     ar.update_time as updated_at
                 FROM artist ar
                 JOIN participant_stats ps ON ar.id = ps.artist_id
-                LEFT JOIN annotation an ON ar.id = an.item_id AND an.item_kind = 'artist'
+                LEFT JOIN annotation an ON ar.id = an.item_id AND an.item_kind = 'artist' AND an.user_id = $2
                 WHERE ps.role = 'Artist' AND (lower(ar.sort_name) = lower($1) OR lower(ar.name) = lower($1))
                 ORDER BY ar.id
             ) AS sub
@@ -305,7 +337,7 @@ impl ArtistDao for ArtistDaoImpl {
             ArtistBase::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 sql,
-                vec![artist_name.into()],
+                vec![artist_name.into(), self.user_id.into()],
             ))
             .all(&self.db)
             .await
@@ -440,7 +472,7 @@ impl ArtistDao for ArtistDaoImpl {
                     ar.update_time as updated_at
                 FROM artist ar
                 JOIN participant_stats ps ON ar.id = ps.artist_id
-                LEFT JOIN annotation an ON ar.id = an.item_id AND an.item_kind = 'artist'
+                LEFT JOIN annotation an ON ar.id = an.item_id AND an.item_kind = 'artist' AND an.user_id = $4
                 WHERE ps.role = 'Artist' AND (lower(ar.name) LIKE lower($1) OR lower(ar.sort_name) LIKE lower($1))
                 ORDER BY ar.id
             ) AS sub
@@ -452,7 +484,12 @@ impl ArtistDao for ArtistDaoImpl {
             ArtistBase::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 sql,
-                vec![search_pattern.into(), limit.into(), offset.into()],
+                vec![
+                    search_pattern.into(),
+                    limit.into(),
+                    offset.into(),
+                    self.user_id.into(),
+                ],
             ))
             .all(&self.db)
             .await
@@ -1,19 +1,43 @@
 use std::collections::HashMap;
 
+use crate::repository::postgres::command::db_data::audio_file::chapters_from_json;
 use application::query::dao::AudioFileDao;
 use application::query::QueryError;
 use async_trait::async_trait;
+use domain::value::{AudibleRange, ReplayGain};
 use model::audio_file::AudioFile;
-use model::shared::{Annotation, ArtistSummary, Contributor, GenreSummary};
+use model::shared::{Annotation, ArtistSummary, Contributor, CoverPlaceholder, GenreSummary};
 use sea_orm::*;
 
+/// 库设置了隐藏时，列表和搜索中不显示存储无法访问的歌曲
+const HIDE_UNAVAILABLE: &str = "(af.unavailable_at IS NULL OR NOT EXISTS \
+     (SELECT 1 FROM library l WHERE l.id = af.library_id AND l.hide_unavailable))";
+
 pub struct AudioFileDaoImpl {
     db: DatabaseConnection,
+    hide_explicit: bool,
+    user_id: Option<i64>,
 }
 
 impl AudioFileDaoImpl {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            hide_explicit: false,
+            user_id: None,
+        }
+    }
+
+    /// 播放次数、评分和收藏只取该用户的注解；未指定用户时这些字段为空
+    pub fn with_user(mut self, user_id: Option<i64>) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    /// 列表、搜索和随机播放中不返回 explicit 歌曲，按 ID 查询不受影响
+    pub fn with_hide_explicit(mut self, hide_explicit: bool) -> Self {
+        self.hide_explicit = hide_explicit;
+        self
     }
 }
 
@@ -21,11 +45,19 @@ impl AudioFileDaoImpl {
 #[derive(Debug, Clone)]
 enum AudioFileQueryFilter {
     ById(i64),
+    ByIds(Vec<i64>),
     ByArtistId(i64),
     ByAlbumId(i64),
+    ByAlbumIds(Vec<i64>),
     ByGenre(String),
     ByYearRange(i32, i32),
     ByStarred(i64), // user_id
+    /// 排除有声书库的歌曲
+    NotAudiobook,
+    /// 排除存储无法访问的歌曲
+    Available,
+    /// 排除内容分级为 explicit 的歌曲
+    NotExplicit,
     #[allow(dead_code)]
     All,
 }
@@ -71,6 +103,9 @@ struct AudioFileBase {
     pub create_time: chrono::NaiveDateTime,
     pub update_time: chrono::NaiveDateTime,
     pub year: Option<i32>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub disc_subtitle: Option<String>,
     pub size: i64,
     pub duration: i64,
     pub bit_rate: i32,
@@ -80,6 +115,20 @@ struct AudioFileBase {
     pub channel_count: Option<i32>,
     pub sample_rate: Option<i32>,
     pub has_cover_art: bool,
+    pub comment: Option<String>,
+    pub mbz_track_id: Option<String>,
+    pub rg_track_gain: Option<f64>,
+    pub rg_track_peak: Option<f64>,
+    pub rg_album_gain: Option<f64>,
+    pub rg_album_peak: Option<f64>,
+    pub work: Option<String>,
+    pub movement_name: Option<String>,
+    pub movement_number: Option<i32>,
+    pub movement_count: Option<i32>,
+    pub chapters: serde_json::Value,
+    pub explicit: bool,
+    pub audible_start_ms: Option<i64>,
+    pub audible_end_ms: Option<i64>,
     pub album_id: i64,
     pub album_name: String,
     pub artist_id: i64,
@@ -111,9 +160,20 @@ struct SecondaryGenreRow {
     pub genre_name: String,
 }
 
+/// 封面占位数据
+#[derive(Debug, Clone, FromQueryResult)]
+struct CoverPlaceholderRow {
+    pub item_id: i64,
+    pub blurhash: String,
+    pub dominant_color: Option<String>,
+}
+
 impl AudioFileDaoImpl {
     /// 第一步：查询音频文件基础信息（不含一对多关系）
-    fn build_base_query_sql(options: &AudioFileQueryOptions) -> (String, Vec<Value>) {
+    fn build_base_query_sql(
+        options: &AudioFileQueryOptions,
+        user_id: Option<i64>,
+    ) -> (String, Vec<Value>) {
         let mut values: Vec<Value> = Vec::new();
         let mut param_index = 1;
 
@@ -141,11 +201,25 @@ impl AudioFileDaoImpl {
             param_index += 1;
             format!("JOIN annotation an ON af.id = an.item_id AND an.item_kind = 'audio_file' AND an.user_id = $1 AND an.starred = true")
         } else {
-            "LEFT JOIN annotation an ON af.id = an.item_id AND an.item_kind = 'audio_file'".to_string()
+            // 没有用户时绑定 NULL，不匹配任何注解
+            values.push(user_id.into());
+            param_index += 1;
+            "LEFT JOIN annotation an ON af.id = an.item_id AND an.item_kind = 'audio_file' AND an.user_id = $1".to_string()
         };
 
         // 构建 WHERE 条件（跳过 ByStarred，因为已在 JOIN 中处理）
         let mut where_parts = Vec::new();
+        // 按 ID 查询（播放列表、队列、播放）时保留被标记为缺失的文件，列表中不显示
+        let by_ids = options.filters.iter().any(|f| {
+            matches!(
+                f,
+                AudioFileQueryFilter::ById(_) | AudioFileQueryFilter::ByIds(_)
+            )
+        });
+        if !by_ids {
+            where_parts.push("af.missing_at IS NULL".to_string());
+            where_parts.push(HIDE_UNAVAILABLE.to_string());
+        }
         for filter in &options.filters {
             match filter {
                 AudioFileQueryFilter::ById(id) => {
@@ -163,9 +237,20 @@ impl AudioFileDaoImpl {
                     values.push((*id).into());
                     param_index += 1;
                 }
+                AudioFileQueryFilter::ByIds(ids) => {
+                    where_parts.push(format!("af.id = ANY(${})", param_index));
+                    values.push(ids.clone().into());
+                    param_index += 1;
+                }
+                AudioFileQueryFilter::ByAlbumIds(ids) => {
+                    where_parts.push(format!("af.album_id = ANY(${})", param_index));
+                    values.push(ids.clone().into());
+                    param_index += 1;
+                }
                 AudioFileQueryFilter::ByGenre(genre) => {
+                    // 匹配歌曲的任一流派，不只是主流派
                     where_parts.push(format!(
-                        "EXISTS (SELECT 1 FROM genre g WHERE g.id = af.genre_id AND lower(g.name) = lower(${}))",
+                        "af.genre_ids && ARRAY(SELECT g.id FROM genre g WHERE lower(g.name) = lower(${}))",
                         param_index
                     ));
                     values.push(genre.clone().into());
@@ -184,6 +269,18 @@ impl AudioFileDaoImpl {
                 AudioFileQueryFilter::ByStarred(_) => {
                     // 已在 JOIN 条件中处理，跳过
                 }
+                AudioFileQueryFilter::NotAudiobook => {
+                    where_parts.push(
+                        "NOT EXISTS (SELECT 1 FROM library l WHERE l.id = af.library_id AND l.kind = 'audiobook')"
+                            .to_string(),
+                    );
+                }
+                AudioFileQueryFilter::Available => {
+                    where_parts.push("af.unavailable_at IS NULL".to_string());
+                }
+                AudioFileQueryFilter::NotExplicit => {
+                    where_parts.push("NOT af.explicit".to_string());
+                }
                 AudioFileQueryFilter::All => {}
             }
         }
@@ -231,9 +328,14 @@ impl AudioFileDaoImpl {
                 SELECT DISTINCT ON (af.id)
                     af.id, af.title as name, af.title as sort_name, af.title as order_name,
                     af.compilation, af.created_at as create_time, af.updated_at as update_time,
-                    af.year, af.size, CAST(af.duration AS bigint) as duration, af.bit_rate, af.suffix,
+                    af.year, af.track_number, af.disc_number, af.disc_subtitle,
+                    af.size, CAST(af.duration AS bigint) as duration, af.bit_rate, af.suffix,
                     (af.path_protocol || '://' || af.path_path) as path,
                     af.bpm, af.channels as channel_count, af.sample_rate, af.has_cover_art,
+                    af.comment, af.mbz_track_id,
+                    af.rg_track_gain, af.rg_track_peak, af.rg_album_gain, af.rg_album_peak,
+                    af.work, af.movement_name, af.movement_number, af.movement_count,
+                    af.chapters, af.explicit, af.audible_start_ms, af.audible_end_ms,
                     COALESCE(al.id, 0) as album_id, COALESCE(al.name, '') as album_name,
                     COALESCE(ar.id, 0) as artist_id, COALESCE(ar.name, '') as artist_name,
                     COALESCE(af.genre_id, 0) as genre_id, COALESCE(g.name, '') as genre_name,
@@ -350,11 +452,58 @@ impl AudioFileDaoImpl {
         Ok(result)
     }
 
+    /// 第四步：批量查询封面占位（音频文件自身的封面）
+    async fn query_cover_placeholders(
+        &self,
+        audio_file_ids: &[i64],
+    ) -> Result<HashMap<i64, CoverPlaceholder>, QueryError> {
+        if audio_file_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let placeholders: Vec<String> = (1..=audio_file_ids.len())
+            .map(|i| format!("${}", i))
+            .collect();
+        let sql = format!(
+            r#"SELECT DISTINCT ON (ca.audio_file_id)
+                   ca.audio_file_id as item_id, ca.blurhash, ca.dominant_color
+               FROM cover_art ca
+               WHERE ca.audio_file_id IN ({}) AND ca.blurhash IS NOT NULL
+               ORDER BY ca.audio_file_id"#,
+            placeholders.join(", ")
+        );
+
+        let values: Vec<Value> = audio_file_ids.iter().map(|id| (*id).into()).collect();
+        let rows: Vec<CoverPlaceholderRow> =
+            CoverPlaceholderRow::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &sql,
+                values,
+            ))
+            .all(&self.db)
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.item_id,
+                    CoverPlaceholder {
+                        blurhash: row.blurhash,
+                        dominant_color: row.dominant_color,
+                    },
+                )
+            })
+            .collect())
+    }
+
     /// 组装最终结果
     fn assemble_audio_files(
         base_files: Vec<AudioFileBase>,
         participants: HashMap<i64, Vec<Contributor>>,
         secondary_genres: HashMap<i64, Vec<GenreSummary>>,
+        mut cover_placeholders: HashMap<i64, CoverPlaceholder>,
     ) -> Vec<AudioFile> {
         base_files
             .into_iter()
@@ -372,17 +521,36 @@ impl AudioFileDaoImpl {
                     album_artists: Vec::new(),
                     album_id: base.album_id,
                     has_cover_art: base.has_cover_art,
-                    track_number: 0,
-                    disc_number: 0,
-                    disc_subtitle: String::new(),
+                    track_number: base.track_number.unwrap_or(0),
+                    disc_number: base.disc_number.unwrap_or(0),
+                    disc_subtitle: base.disc_subtitle.unwrap_or_default(),
                     year: base.year,
                     size: base.size,
                     suffix: base.suffix,
                     duration: base.duration,
                     bit_rate: base.bit_rate,
                     channels: base.channel_count.unwrap_or(0),
+                    sample_rate: base.sample_rate.unwrap_or(0),
                     order_title: base.order_name.clone(),
                     bpm: base.bpm.unwrap_or(0),
+                    comment: base.comment,
+                    mbz_track_id: base.mbz_track_id,
+                    replay_gain: ReplayGain {
+                        track_gain: base.rg_track_gain,
+                        track_peak: base.rg_track_peak,
+                        album_gain: base.rg_album_gain,
+                        album_peak: base.rg_album_peak,
+                    },
+                    explicit: base.explicit,
+                    work: base.work,
+                    movement_name: base.movement_name,
+                    movement_number: base.movement_number,
+                    movement_count: base.movement_count,
+                    chapters: chapters_from_json(&base.chapters),
+                    audible_range: base
+                        .audible_start_ms
+                        .zip(base.audible_end_ms)
+                        .map(|(start, end)| AudibleRange { start, end }),
                     name: base.name,
                     song_count: 1,
                     compilation: base.compilation,
@@ -409,6 +577,7 @@ impl AudioFileDaoImpl {
                         name: base.artist_name,
                     },
                     contributors,
+                    cover_placeholder: cover_placeholders.remove(&base.id),
                     created_at: base.create_time,
                     updated_at: base.update_time,
                 }
@@ -419,10 +588,20 @@ impl AudioFileDaoImpl {
     /// 执行完整的三步查询
     async fn query_audio_files(
         &self,
-        options: AudioFileQueryOptions,
+        mut options: AudioFileQueryOptions,
     ) -> Result<Vec<AudioFile>, QueryError> {
+        let by_ids = options.filters.iter().any(|f| {
+            matches!(
+                f,
+                AudioFileQueryFilter::ById(_) | AudioFileQueryFilter::ByIds(_)
+            )
+        });
+        if self.hide_explicit && !by_ids {
+            options.filters.push(AudioFileQueryFilter::NotExplicit);
+        }
+
         // 第一步：查询基础数据
-        let (sql, values) = Self::build_base_query_sql(&options);
+        let (sql, values) = Self::build_base_query_sql(&options, self.user_id);
         let base_files: Vec<AudioFileBase> =
             AudioFileBase::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::Postgres,
@@ -440,10 +619,11 @@ impl AudioFileDaoImpl {
         // 收集所有 audio_file_id
         let ids: Vec<i64> = base_files.iter().map(|f| f.id).collect();
 
-        // 第二步 & 第三步：并行查询 participants 和 secondary_genres
-        let (participants, secondary_genres) = tokio::try_join!(
+        // 第二步到第四步：并行查询 participants、secondary_genres 和封面占位
+        let (participants, secondary_genres, cover_placeholders) = tokio::try_join!(
             self.query_participants(&ids),
-            self.query_secondary_genres(&ids)
+            self.query_secondary_genres(&ids),
+            self.query_cover_placeholders(&ids)
         )?;
 
         // 组装结果
@@ -451,6 +631,7 @@ impl AudioFileDaoImpl {
             base_files,
             participants,
             secondary_genres,
+            cover_placeholders,
         ))
     }
 }
@@ -474,6 +655,28 @@ impl AudioFileDao for AudioFileDaoImpl {
         self.query_audio_files(options).await
     }
 
+    async fn get_by_ids(&self, ids: &[i64]) -> Result<Vec<AudioFile>, QueryError> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let options = AudioFileQueryOptions {
+            filters: vec![AudioFileQueryFilter::ByIds(ids.to_vec())],
+            ..Default::default()
+        };
+        self.query_audio_files(options).await
+    }
+
+    async fn get_by_album_ids(&self, album_ids: &[i64]) -> Result<Vec<AudioFile>, QueryError> {
+        if album_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let options = AudioFileQueryOptions {
+            filters: vec![AudioFileQueryFilter::ByAlbumIds(album_ids.to_vec())],
+            ..Default::default()
+        };
+        self.query_audio_files(options).await
+    }
+
     async fn get_by_artist_id(&self, artist_id: i64) -> Result<Vec<AudioFile>, QueryError> {
         let options = AudioFileQueryOptions {
             filters: vec![AudioFileQueryFilter::ByArtistId(artist_id)],
@@ -508,7 +711,11 @@ impl AudioFileDao for AudioFileDaoImpl {
         to_year: Option<i32>,
         limit: i32,
     ) -> Result<Vec<AudioFile>, QueryError> {
-        let mut filters = Vec::new();
+        // 随机播放时无法访问的歌曲总是排除，不论库是否设置隐藏
+        let mut filters = vec![
+            AudioFileQueryFilter::NotAudiobook,
+            AudioFileQueryFilter::Available,
+        ];
         if let Some(g) = genre {
             filters.push(AudioFileQueryFilter::ByGenre(g.to_string()));
         }
@@ -583,7 +790,13 @@ impl AudioFileDao for AudioFileDaoImpl {
         limit: i32,
     ) -> Result<(Vec<AudioFile>, i64), QueryError> {
         // 构建搜索条件
-        let mut where_parts = Vec::new();
+        let mut where_parts = vec![
+            "af.missing_at IS NULL".to_string(),
+            HIDE_UNAVAILABLE.to_string(),
+        ];
+        if self.hide_explicit {
+            where_parts.push("NOT af.explicit".to_string());
+        }
         let mut values: Vec<Value> = Vec::new();
         let mut param_index = 1;
 
@@ -666,15 +879,21 @@ impl AudioFileDao for AudioFileDaoImpl {
         let mut query_values = values;
         query_values.push(limit.into());
         query_values.push(offset.into());
+        query_values.push(self.user_id.into());
 
         let base_sql = format!(
             r#"SELECT * FROM (
                 SELECT DISTINCT ON (af.id)
                     af.id, af.title as name, af.title as sort_name, af.title as order_name,
                     af.compilation, af.created_at as create_time, af.updated_at as update_time,
-                    af.year, af.size, CAST(af.duration AS bigint) as duration, af.bit_rate, af.suffix,
+                    af.year, af.track_number, af.disc_number, af.disc_subtitle,
+                    af.size, CAST(af.duration AS bigint) as duration, af.bit_rate, af.suffix,
                     (af.path_protocol || '://' || af.path_path) as path,
                     af.bpm, af.channels as channel_count, af.sample_rate, af.has_cover_art,
+                    af.comment, af.mbz_track_id,
+                    af.rg_track_gain, af.rg_track_peak, af.rg_album_gain, af.rg_album_peak,
+                    af.work, af.movement_name, af.movement_number, af.movement_count,
+                    af.chapters, af.explicit, af.audible_start_ms, af.audible_end_ms,
                     COALESCE(al.id, 0) as album_id, COALESCE(al.name, '') as album_name,
                     COALESCE(ar.id, 0) as artist_id, COALESCE(ar.name, '') as artist_name,
                     COALESCE(af.genre_id, 0) as genre_id, COALESCE(g.name, '') as genre_name,
@@ -683,13 +902,16 @@ impl AudioFileDao for AudioFileDaoImpl {
                 LEFT JOIN album al ON af.album_id = al.id
                 LEFT JOIN artist ar ON af.artist_id = ar.id
                 LEFT JOIN genre g ON af.genre_id = g.id
-                LEFT JOIN annotation an ON af.id = an.item_id AND an.item_kind = 'audio_file'
+                LEFT JOIN annotation an ON af.id = an.item_id AND an.item_kind = 'audio_file' AND an.user_id = ${}
                 {}
                 ORDER BY af.id
             ) AS sub
             ORDER BY name
             LIMIT ${} OFFSET ${}"#,
-            where_clause, param_index, param_index + 1
+            param_index + 2,
+            where_clause,
+            param_index,
+            param_index + 1
         );
 
         let base_files: Vec<AudioFileBase> =
@@ -708,13 +930,19 @@ impl AudioFileDao for AudioFileDaoImpl {
 
         // 3. 批量查询关联数据
         let ids: Vec<i64> = base_files.iter().map(|f| f.id).collect();
-        let (participants, secondary_genres) = tokio::try_join!(
+        let (participants, secondary_genres, cover_placeholders) = tokio::try_join!(
             self.query_participants(&ids),
-            self.query_secondary_genres(&ids)
+            self.query_secondary_genres(&ids),
+            self.query_cover_placeholders(&ids)
         )?;
 
         // 4. 组装结果
-        let audio_files = Self::assemble_audio_files(base_files, participants, secondary_genres);
+        let audio_files = Self::assemble_audio_files(
+            base_files,
+            participants,
+            secondary_genres,
+            cover_placeholders,
+        );
         Ok((audio_files, total))
     }
 }
@@ -0,0 +1,86 @@
+use super::audio_file::AudioFileDaoImpl;
+use application::query::dao::{AudioFileDao, BookmarkDao};
+use application::query::QueryError;
+use async_trait::async_trait;
+use model::bookmark::Bookmark;
+use sea_orm::*;
+use std::collections::HashMap;
+
+pub struct BookmarkDaoImpl {
+    db: DatabaseConnection,
+}
+
+impl BookmarkDaoImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 查询书签行后批量加载歌曲，已删除歌曲的书签被忽略
+    async fn query(&self, sql: &str, user_id: i64) -> Result<Vec<Bookmark>, QueryError> {
+        let rows: Vec<BookmarkRow> = BookmarkRow::find_by_statement(
+            Statement::from_sql_and_values(DbBackend::Postgres, sql, vec![user_id.into()]),
+        )
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::ExecutionError(e.to_string()))?;
+
+        let ids: Vec<i64> = rows.iter().map(|row| row.audio_file_id).collect();
+        let mut songs: HashMap<i64, _> = AudioFileDaoImpl::new(self.db.clone())
+            .get_by_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|song| (song.id, song))
+            .collect();
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                songs.remove(&row.audio_file_id).map(|entry| Bookmark {
+                    entry,
+                    position: row.position,
+                    comment: row.comment,
+                    changed_by: row.changed_by,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                })
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct BookmarkRow {
+    pub audio_file_id: i64,
+    pub position: i64,
+    pub comment: String,
+    pub changed_by: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[async_trait]
+impl BookmarkDao for BookmarkDaoImpl {
+    async fn get_by_user(&self, user_id: i64) -> Result<Vec<Bookmark>, QueryError> {
+        self.query(
+            r#"SELECT b.audio_file_id, b.position, b.comment, b.changed_by, b.created_at, b.updated_at
+               FROM bookmark b
+               WHERE b.user_id = $1
+               ORDER BY b.updated_at DESC"#,
+            user_id,
+        )
+        .await
+    }
+
+    async fn get_audiobook_bookmarks(&self, user_id: i64) -> Result<Vec<Bookmark>, QueryError> {
+        self.query(
+            r#"SELECT b.audio_file_id, b.position, b.comment, b.changed_by, b.created_at, b.updated_at
+               FROM bookmark b
+               JOIN audio_file af ON af.id = b.audio_file_id
+               JOIN library l ON l.id = af.library_id
+               WHERE b.user_id = $1 AND l.kind = 'audiobook' AND l.enabled
+               ORDER BY b.updated_at DESC"#,
+            user_id,
+        )
+        .await
+    }
+}
@@ -1,5 +1,7 @@
+use super::media_asset::MediaAssetDaoImpl;
 use application::query::dao::{
-    CoverArtDao, CoverArtInfo, CoverArtPath, CoverArtPathWithSource, LocationInfo,
+    CoverArtDao, CoverArtInfo, CoverArtPath, CoverArtPathWithSource, LocationInfo, MediaAssetDao,
+    PlaylistCoverInfo,
 };
 use application::query::QueryError;
 use async_trait::async_trait;
@@ -198,10 +200,11 @@ impl CoverArtDao for CoverArtDaoImpl {
         &self,
         playlist_id: i64,
     ) -> Result<Option<CoverArtInfo>, QueryError> {
-        // Get the first song in the playlist
+        // Get the first song in the playlist; 最近删除中的播放列表没有封面
         let sql = r#"
             SELECT af.path_protocol, af.path_path, af.has_cover_art, af.updated_at
             FROM playlist_entry pe
+            JOIN playlist p ON p.id = pe.playlist_id AND p.deleted_at IS NULL
             JOIN audio_file af ON pe.audio_file_id = af.id
             WHERE pe.playlist_id = $1
             ORDER BY pe.position ASC
@@ -222,6 +225,94 @@ impl CoverArtDao for CoverArtDaoImpl {
         }))
     }
 
+    async fn get_playlist_cover(
+        &self,
+        playlist_id: i64,
+    ) -> Result<Option<PlaylistCoverInfo>, QueryError> {
+        #[derive(Debug, Clone, FromQueryResult)]
+        struct PlaylistCoverRow {
+            pub cover_path: Option<String>,
+            pub updated_at: chrono::NaiveDateTime,
+        }
+
+        let sql = r#"
+            SELECT cover_path, updated_at
+            FROM playlist
+            WHERE id = $1 AND deleted_at IS NULL
+        "#;
+
+        let row: Option<PlaylistCoverRow> = PlaylistCoverRow::find_by_statement(
+            Statement::from_sql_and_values(DbBackend::Postgres, sql, [playlist_id.into()]),
+        )
+        .one(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+
+        Ok(row.map(|r| PlaylistCoverInfo {
+            cover_path: r.cover_path,
+            updated_at: r.updated_at,
+        }))
+    }
+
+    async fn get_playlist_album_covers(
+        &self,
+        playlist_id: i64,
+        limit: i32,
+    ) -> Result<Vec<CoverArtPathWithSource>, QueryError> {
+        #[derive(Debug, Clone, FromQueryResult)]
+        struct CoverArtPathWithSourceRow {
+            pub path_protocol: String,
+            pub path_path: String,
+            pub source: String,
+            pub thumbnail_path: Option<String>,
+        }
+
+        // 每张专辑取一个封面（优先 external），没有封面的专辑跳过
+        let sql = r#"
+            WITH albums AS (
+                SELECT af.album_id, MIN(pe.position) AS first_position
+                FROM playlist_entry pe
+                JOIN playlist p ON p.id = pe.playlist_id AND p.deleted_at IS NULL
+                JOIN audio_file af ON pe.audio_file_id = af.id
+                WHERE pe.playlist_id = $1 AND af.album_id IS NOT NULL
+                GROUP BY af.album_id
+            )
+            SELECT c.path_protocol, c.path_path, c.source, c.thumbnail_path
+            FROM albums a
+            CROSS JOIN LATERAL (
+                SELECT ca.path_protocol, ca.path_path, ca.source, ca.thumbnail_path
+                FROM cover_art ca
+                JOIN audio_file af ON ca.audio_file_id = af.id
+                WHERE af.album_id = a.album_id
+                ORDER BY ca.source = 'embedded', af.disc_number NULLS LAST, af.track_number NULLS LAST
+                LIMIT 1
+            ) c
+            ORDER BY a.first_position
+            LIMIT $2
+        "#;
+
+        let rows: Vec<CoverArtPathWithSourceRow> = CoverArtPathWithSourceRow::find_by_statement(
+            Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [playlist_id.into(), limit.into()],
+            ),
+        )
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| CoverArtPathWithSource {
+                protocol: r.path_protocol,
+                path: r.path_path,
+                source: r.source,
+                thumbnail_path: r.thumbnail_path,
+            })
+            .collect())
+    }
+
     async fn get_cover_art_paths_by_album(
         &self,
         album_id: i64,
@@ -291,10 +382,11 @@ impl CoverArtDao for CoverArtDaoImpl {
             pub path_protocol: String,
             pub path_path: String,
             pub source: String,
+            pub thumbnail_path: Option<String>,
         }
 
         let sql = r#"
-            SELECT path_protocol, path_path, source
+            SELECT path_protocol, path_path, source, thumbnail_path
             FROM cover_art
             WHERE audio_file_id = $1
             LIMIT 1
@@ -311,6 +403,7 @@ impl CoverArtDao for CoverArtDaoImpl {
             protocol: r.path_protocol,
             path: r.path_path,
             source: r.source,
+            thumbnail_path: r.thumbnail_path,
         }))
     }
 
@@ -323,11 +416,12 @@ impl CoverArtDao for CoverArtDaoImpl {
             pub path_protocol: String,
             pub path_path: String,
             pub source: String,
+            pub thumbnail_path: Option<String>,
         }
 
         // 查询专辑音频文件的所有封面
         let sql = r#"
-            SELECT ca.path_protocol, ca.path_path, ca.source
+            SELECT ca.path_protocol, ca.path_path, ca.source, ca.thumbnail_path
             FROM cover_art ca
             JOIN audio_file af ON ca.audio_file_id = af.id
             WHERE af.album_id = $1
@@ -347,6 +441,7 @@ impl CoverArtDao for CoverArtDaoImpl {
                 protocol: r.path_protocol,
                 path: r.path_path,
                 source: r.source,
+                thumbnail_path: r.thumbnail_path,
             })
             .collect())
     }
@@ -375,6 +470,22 @@ impl CoverArtDao for CoverArtDaoImpl {
         }))
     }
 
+    async fn get_artist_image_asset(
+        &self,
+        artist_id: i64,
+    ) -> Result<Option<CoverArtPath>, QueryError> {
+        let assets = MediaAssetDaoImpl::new(self.db.clone())
+            .get_by_artist(artist_id)
+            .await?;
+        Ok(assets
+            .into_iter()
+            .find(|asset| asset.kind == "artist_image")
+            .map(|asset| CoverArtPath {
+                protocol: asset.protocol,
+                path: asset.path,
+            }))
+    }
+
     async fn get_first_album_id_by_artist(
         &self,
         artist_id: i64,
@@ -411,11 +522,12 @@ impl CoverArtDao for CoverArtDaoImpl {
             pub path_protocol: String,
             pub path_path: String,
             pub source: String,
+            pub thumbnail_path: Option<String>,
         }
 
         // 查询艺术家音频文件的所有封面
         let sql = r#"
-            SELECT ca.path_protocol, ca.path_path, ca.source
+            SELECT ca.path_protocol, ca.path_path, ca.source, ca.thumbnail_path
             FROM cover_art ca
             JOIN audio_file af ON ca.audio_file_id = af.id
             JOIN album a ON af.album_id = a.id
@@ -435,6 +547,7 @@ impl CoverArtDao for CoverArtDaoImpl {
                 protocol: r.path_protocol,
                 path: r.path_path,
                 source: r.source,
+                thumbnail_path: r.thumbnail_path,
             })
             .collect())
     }
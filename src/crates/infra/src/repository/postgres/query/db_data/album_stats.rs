@@ -25,6 +25,9 @@ pub struct Model {
 
     pub disk_numbers: Vec<i32>,
 
+    #[sea_orm(column_type = "JsonBinary")]
+    pub disc_titles: Json,
+
     pub year: i32,
 }
 
@@ -54,6 +57,7 @@ impl From<Model> for AlbumStats {
             size: model.size,
             song_count: model.song_count,
             disk_numbers: model.disk_numbers,
+            disc_titles: serde_json::from_value(model.disc_titles).unwrap_or_default(),
             year: if model.year != 0 {
                 Some(model.year)
             } else {
@@ -71,6 +75,7 @@ impl From<&AlbumStats> for ActiveModel {
             size: Set(album_stats.size),
             song_count: Set(album_stats.song_count),
             disk_numbers: Set(album_stats.disk_numbers.clone()),
+            disc_titles: Set(serde_json::to_value(&album_stats.disc_titles).unwrap_or_default()),
             year: Set(album_stats.year.unwrap_or(0)),
         }
     }
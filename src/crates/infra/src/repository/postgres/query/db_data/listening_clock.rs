@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, DeriveEntityModel, Default)]
+#[sea_orm(table_name = "listening_clock")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(column_type = "BigInteger")]
+    pub user_id: i64,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub year: i32,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub weekday: i16,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hour: i16,
+
+    pub play_count: i32,
+    pub play_seconds: i64,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations defined")
+    }
+}
+
+impl From<Model> for model::listening_report::ListeningClockCell {
+    fn from(model: Model) -> Self {
+        Self {
+            weekday: model.weekday,
+            hour: model.hour,
+            play_count: model.play_count as i64,
+            play_seconds: model.play_seconds,
+        }
+    }
+}
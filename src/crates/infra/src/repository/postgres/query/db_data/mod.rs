@@ -6,6 +6,9 @@ pub mod artist_location;
 pub mod audio_file;
 pub mod genre;
 pub mod genre_stats;
+pub mod listening_clock;
 pub mod music_folder;
 pub mod participant_stats;
 pub mod playback_history;
+pub mod play_count_rollup;
+pub mod star_stats;
@@ -0,0 +1,54 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, DeriveEntityModel, Default)]
+#[sea_orm(table_name = "play_count_rollup")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub period: String,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub period_start: chrono::NaiveDate,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(column_type = "BigInteger")]
+    pub user_id: i64,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_kind: String,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(column_type = "BigInteger")]
+    pub item_id: i64,
+
+    pub play_count: i32,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations defined")
+    }
+}
+
+/// 排行榜查询结果
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct ChartRow {
+    pub item_id: i64,
+    pub name: String,
+    pub play_count: i64,
+}
+
+impl From<ChartRow> for model::play_stats::ChartEntry {
+    fn from(row: ChartRow) -> Self {
+        Self {
+            item_id: row.item_id,
+            name: row.name,
+            play_count: row.play_count,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use model::star_stats::StarStats;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, DeriveEntityModel, Default)]
+#[sea_orm(table_name = "star_stats")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_kind: String,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(column_type = "BigInteger")]
+    pub item_id: i64,
+
+    pub star_count: i32,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations defined")
+    }
+}
+
+impl From<Model> for StarStats {
+    fn from(model: Model) -> Self {
+        StarStats {
+            item_kind: model.item_kind,
+            item_id: model.item_id,
+            star_count: model.star_count,
+        }
+    }
+}
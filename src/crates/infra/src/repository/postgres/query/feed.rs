@@ -0,0 +1,120 @@
+use application::query::dao::FeedDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use model::feed::FeedItem;
+use sea_orm::*;
+
+pub struct FeedDaoImpl {
+    db: sea_orm::DbConn,
+}
+
+impl FeedDaoImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+
+    async fn query(&self, sql: &str, values: Vec<Value>) -> Result<Vec<FeedItem>, QueryError> {
+        let rows: Vec<FeedRow> = FeedRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct FeedRow {
+    pub token: i64,
+    pub item_id: i64,
+    pub name: String,
+    pub album_id: Option<i64>,
+    pub artist_id: Option<i64>,
+    pub at: chrono::NaiveDateTime,
+}
+
+impl From<FeedRow> for FeedItem {
+    fn from(row: FeedRow) -> Self {
+        Self {
+            token: row.token,
+            item_id: row.item_id,
+            name: row.name,
+            album_id: row.album_id,
+            artist_id: row.artist_id,
+            at: row.at,
+        }
+    }
+}
+
+#[async_trait]
+impl FeedDao for FeedDaoImpl {
+    async fn get_added_albums(&self, since: i64, limit: i32) -> Result<Vec<FeedItem>, QueryError> {
+        self.query(
+            r#"SELECT a.id AS token, a.id AS item_id, a.name, a.id AS album_id,
+                      a.artist_id, a.create_time AS at
+               FROM album a
+               WHERE a.id > $1
+               ORDER BY a.id
+               LIMIT $2"#,
+            vec![since.into(), limit.into()],
+        )
+        .await
+    }
+
+    async fn get_added_songs(&self, since: i64, limit: i32) -> Result<Vec<FeedItem>, QueryError> {
+        self.query(
+            r#"SELECT af.id AS token, af.id AS item_id, af.title AS name, af.album_id,
+                      af.artist_id, af.created_at AS at
+               FROM audio_file af
+               WHERE af.id > $1
+               ORDER BY af.id
+               LIMIT $2"#,
+            vec![since.into(), limit.into()],
+        )
+        .await
+    }
+
+    async fn get_played_albums(
+        &self,
+        user_id: i64,
+        since: i64,
+        limit: i32,
+    ) -> Result<Vec<FeedItem>, QueryError> {
+        self.query(
+            r#"SELECT MAX(ph.id) AS token, a.id AS item_id, a.name, a.id AS album_id,
+                      a.artist_id, MAX(ph.scrobbled_at) AS at
+               FROM playback_history ph
+               JOIN audio_file af ON af.id = ph.audio_file_id
+               JOIN album a ON a.id = af.album_id
+               WHERE ph.user_id = $1 AND ph.id > $2
+               GROUP BY a.id, a.name, a.artist_id
+               ORDER BY token
+               LIMIT $3"#,
+            vec![user_id.into(), since.into(), limit.into()],
+        )
+        .await
+    }
+
+    async fn get_played_songs(
+        &self,
+        user_id: i64,
+        since: i64,
+        limit: i32,
+    ) -> Result<Vec<FeedItem>, QueryError> {
+        self.query(
+            r#"SELECT MAX(ph.id) AS token, af.id AS item_id, af.title AS name, af.album_id,
+                      af.artist_id, MAX(ph.scrobbled_at) AS at
+               FROM playback_history ph
+               JOIN audio_file af ON af.id = ph.audio_file_id
+               WHERE ph.user_id = $1 AND ph.id > $2
+               GROUP BY af.id, af.title, af.album_id, af.artist_id
+               ORDER BY token
+               LIMIT $3"#,
+            vec![user_id.into(), since.into(), limit.into()],
+        )
+        .await
+    }
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use model::format_stats::{FormatStats, FormatStatsRepository};
+use model::ModelError;
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+#[derive(Clone)]
+pub struct FormatStatsRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl FormatStatsRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl FormatStatsRepository for FormatStatsRepositoryImpl {
+    async fn adjust_stats(&self, entry: FormatStats) -> Result<(), ModelError> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO format_stats (suffix, bit_rate_bucket, sample_rate, song_count, total_size)
+                   VALUES ($1, $2, $3, $4, $5)
+                   ON CONFLICT (suffix, bit_rate_bucket, sample_rate) DO UPDATE SET
+                     song_count = format_stats.song_count + EXCLUDED.song_count,
+                     total_size = format_stats.total_size + EXCLUDED.total_size"#,
+                vec![
+                    entry.suffix.into(),
+                    entry.bit_rate_bucket.into(),
+                    entry.sample_rate.into(),
+                    entry.song_count.into(),
+                    entry.total_size.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| ModelError::ProjectionError(e.to_string()))?;
+        Ok(())
+    }
+}
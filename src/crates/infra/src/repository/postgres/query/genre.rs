@@ -87,7 +87,12 @@ impl GenreDao for GenreDaoImpl {
         let rows: Vec<db_genre::GenreModel> =
             db_genre::GenreModel::find_by_statement(Statement::from_string(
                 DbBackend::Postgres,
-                r#"select genre.name, genre_stats.song_count, genre_stats.album_count from genre_stats join genre on genre_stats.genre_id = genre.id;
+                r#"select genre.name,
+                       greatest(genre_stats.song_count, 0) as song_count,
+                       greatest(genre_stats.album_count, 0) as album_count
+                   from genre_stats join genre on genre_stats.genre_id = genre.id
+                   where genre_stats.song_count > 0 or genre_stats.album_count > 0
+                   order by lower(genre.name);
             "#,
             ))
             .all(&self.db)
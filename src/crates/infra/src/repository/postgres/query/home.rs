@@ -0,0 +1,178 @@
+use application::query::dao::HomeDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::*;
+
+/// 计算流派和相似艺术家时参考的常听艺术家、流派数
+const TOP_ARTISTS: i32 = 10;
+const TOP_GENRES: i32 = 5;
+/// 参与排序的相似艺术家数
+const SIMILAR_ARTISTS: i32 = 30;
+
+pub struct HomeDaoImpl {
+    db: DatabaseConnection,
+}
+
+impl HomeDaoImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn ids(&self, sql: &str, values: Vec<Value>) -> Result<Vec<i64>, QueryError> {
+        let rows: Vec<IdRow> = IdRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct IdRow {
+    id: i64,
+}
+
+#[async_trait]
+impl HomeDao for HomeDaoImpl {
+    async fn get_recently_played(&self, user_id: i64, limit: i32) -> Result<Vec<i64>, QueryError> {
+        self.ids(
+            r#"SELECT al.id
+               FROM annotation an
+               JOIN album al ON al.id = an.item_id
+               WHERE an.user_id = $1 AND an.item_kind = 'album' AND an.played_at IS NOT NULL
+               ORDER BY an.played_at DESC
+               LIMIT $2"#,
+            vec![user_id.into(), limit.into()],
+        )
+        .await
+    }
+
+    async fn get_new_in_played_genres(
+        &self,
+        user_id: i64,
+        added_since: NaiveDateTime,
+        limit: i32,
+    ) -> Result<Vec<i64>, QueryError> {
+        self.ids(
+            r#"WITH user_genres AS (
+                   SELECT g.genre_id
+                   FROM annotation an
+                   JOIN album al ON al.id = an.item_id
+                   CROSS JOIN LATERAL unnest(al.genre_ids) AS g(genre_id)
+                   WHERE an.user_id = $1 AND an.item_kind = 'album' AND an.played_count > 0
+                   GROUP BY g.genre_id
+                   ORDER BY SUM(an.played_count) DESC
+                   LIMIT $4
+               )
+               SELECT al.id
+               FROM album al
+               WHERE al.create_time >= $2
+                 AND al.genre_ids && ARRAY(SELECT genre_id FROM user_genres)
+                 AND NOT EXISTS (
+                     SELECT 1 FROM annotation an
+                     WHERE an.user_id = $1 AND an.item_kind = 'album'
+                       AND an.item_id = al.id AND an.played_count > 0
+                 )
+               ORDER BY al.create_time DESC, al.id DESC
+               LIMIT $3"#,
+            vec![
+                user_id.into(),
+                added_since.into(),
+                limit.into(),
+                TOP_GENRES.into(),
+            ],
+        )
+        .await
+    }
+
+    async fn get_not_played_since(
+        &self,
+        user_id: i64,
+        played_before: NaiveDateTime,
+        limit: i32,
+    ) -> Result<Vec<i64>, QueryError> {
+        self.ids(
+            r#"SELECT al.id
+               FROM annotation an
+               JOIN album al ON al.id = an.item_id
+               WHERE an.user_id = $1 AND an.item_kind = 'album'
+                 AND an.played_count > 0 AND an.played_at < $2
+               ORDER BY an.played_count DESC, an.played_at DESC
+               LIMIT $3"#,
+            vec![user_id.into(), played_before.into(), limit.into()],
+        )
+        .await
+    }
+
+    async fn get_similar_artist_picks(
+        &self,
+        user_id: i64,
+        limit: i32,
+    ) -> Result<Vec<i64>, QueryError> {
+        // 没有外部相似度数据，按与常听艺术家共有的流派数判断相似
+        self.ids(
+            r#"WITH played AS (
+                   SELECT al.artist_id, al.genre_ids, an.played_count
+                   FROM annotation an
+                   JOIN album al ON al.id = an.item_id
+                   WHERE an.user_id = $1 AND an.item_kind = 'album' AND an.played_count > 0
+               ),
+               top_artists AS (
+                   SELECT artist_id
+                   FROM played
+                   GROUP BY artist_id
+                   ORDER BY SUM(played_count) DESC
+                   LIMIT $3
+               ),
+               top_genres AS (
+                   SELECT g.genre_id
+                   FROM played p
+                   JOIN top_artists t ON t.artist_id = p.artist_id
+                   CROSS JOIN LATERAL unnest(p.genre_ids) AS g(genre_id)
+                   GROUP BY g.genre_id
+                   ORDER BY SUM(p.played_count) DESC
+                   LIMIT $4
+               ),
+               similar AS (
+                   SELECT al.artist_id, COUNT(DISTINCT g.genre_id) AS shared
+                   FROM album al
+                   CROSS JOIN LATERAL unnest(al.genre_ids) AS g(genre_id)
+                   WHERE g.genre_id IN (SELECT genre_id FROM top_genres)
+                     AND NOT EXISTS (SELECT 1 FROM played p WHERE p.artist_id = al.artist_id)
+                   GROUP BY al.artist_id
+                   ORDER BY shared DESC
+                   LIMIT $5
+               ),
+               picks AS (
+                   SELECT DISTINCT ON (al.artist_id)
+                          al.id, s.shared, COALESCE(pc.plays, 0) AS plays
+                   FROM album al
+                   JOIN similar s ON s.artist_id = al.artist_id
+                   LEFT JOIN (
+                       SELECT item_id, SUM(played_count) AS plays
+                       FROM annotation
+                       WHERE item_kind = 'album'
+                       GROUP BY item_id
+                   ) pc ON pc.item_id = al.id
+                   ORDER BY al.artist_id, plays DESC, al.id
+               )
+               SELECT id
+               FROM picks
+               ORDER BY shared DESC, plays DESC, id
+               LIMIT $2"#,
+            vec![
+                user_id.into(),
+                limit.into(),
+                TOP_ARTISTS.into(),
+                TOP_GENRES.into(),
+                SIMILAR_ARTISTS.into(),
+            ],
+        )
+        .await
+    }
+}
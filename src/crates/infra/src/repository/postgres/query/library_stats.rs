@@ -0,0 +1,176 @@
+use application::query::dao::LibraryStatsDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use model::library_stats::{FormatCount, LibraryTotals, MonthlyAdditions, ValueCount};
+use sea_orm::*;
+
+pub struct LibraryStatsDaoImpl {
+    db: DatabaseConnection,
+}
+
+impl LibraryStatsDaoImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn values(&self, sql: &str) -> Result<Vec<ValueCount>, QueryError> {
+        let rows: Vec<ValueRow> =
+            ValueRow::find_by_statement(Statement::from_string(DbBackend::Postgres, sql))
+                .all(&self.db)
+                .await
+                .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| ValueCount {
+                value: row.value,
+                songs: row.songs,
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct TotalsRow {
+    songs: i64,
+    albums: i64,
+    artists: i64,
+    genres: i64,
+    duration: i64,
+    size: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct FormatRow {
+    suffix: String,
+    songs: i64,
+    size: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ValueRow {
+    value: i32,
+    songs: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct MonthRow {
+    month: NaiveDate,
+    songs: i64,
+    albums: i64,
+}
+
+#[async_trait]
+impl LibraryStatsDao for LibraryStatsDaoImpl {
+    async fn get_totals(&self) -> Result<LibraryTotals, QueryError> {
+        // 歌曲、时长和大小按专辑汇总，与专辑列表显示的数字一致
+        let row = TotalsRow::find_by_statement(Statement::from_string(
+            DbBackend::Postgres,
+            r#"SELECT
+                 COALESCE(SUM(s.song_count), 0)::bigint AS songs,
+                 COUNT(*)::bigint AS albums,
+                 (SELECT COUNT(DISTINCT artist_id) FROM participant_stats
+                  WHERE song_count > 0)::bigint AS artists,
+                 (SELECT COUNT(*) FROM genre_stats WHERE song_count > 0)::bigint AS genres,
+                 COALESCE(SUM(s.duration), 0)::bigint AS duration,
+                 COALESCE(SUM(s.size), 0)::bigint AS size
+               FROM album_stats s
+               WHERE s.song_count > 0"#,
+        ))
+        .one(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(row
+            .map(|row| LibraryTotals {
+                songs: row.songs,
+                albums: row.albums,
+                artists: row.artists,
+                genres: row.genres,
+                duration: row.duration,
+                size: row.size,
+            })
+            .unwrap_or_default())
+    }
+
+    async fn count_by_format(&self) -> Result<Vec<FormatCount>, QueryError> {
+        let rows: Vec<FormatRow> = FormatRow::find_by_statement(Statement::from_string(
+            DbBackend::Postgres,
+            r#"SELECT suffix, SUM(song_count)::bigint AS songs,
+                      SUM(total_size)::bigint AS size
+               FROM format_stats
+               GROUP BY suffix
+               HAVING SUM(song_count) > 0
+               ORDER BY songs DESC, suffix"#,
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| FormatCount {
+                suffix: row.suffix,
+                songs: row.songs,
+                size: row.size,
+            })
+            .collect())
+    }
+
+    async fn count_by_bit_rate(&self) -> Result<Vec<ValueCount>, QueryError> {
+        self.values(
+            r#"SELECT bit_rate_bucket AS value, SUM(song_count)::bigint AS songs
+               FROM format_stats
+               GROUP BY bit_rate_bucket
+               ORDER BY bit_rate_bucket"#,
+        )
+        .await
+    }
+
+    async fn count_by_sample_rate(&self) -> Result<Vec<ValueCount>, QueryError> {
+        self.values(
+            r#"SELECT sample_rate AS value, SUM(song_count)::bigint AS songs
+               FROM format_stats
+               GROUP BY sample_rate
+               HAVING SUM(song_count) > 0
+               ORDER BY sample_rate"#,
+        )
+        .await
+    }
+
+    async fn get_monthly_additions(
+        &self,
+        since: NaiveDate,
+    ) -> Result<Vec<MonthlyAdditions>, QueryError> {
+        let rows: Vec<MonthRow> = MonthRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"WITH songs AS (
+                 SELECT date_trunc('month', created_at)::date AS month, COUNT(*)::bigint AS n
+                 FROM audio_file
+                 WHERE created_at >= $1
+                 GROUP BY 1
+               ), albums AS (
+                 SELECT date_trunc('month', create_time)::date AS month, COUNT(*)::bigint AS n
+                 FROM album
+                 WHERE create_time >= $1
+                 GROUP BY 1
+               )
+               SELECT COALESCE(s.month, a.month) AS month,
+                      COALESCE(s.n, 0) AS songs,
+                      COALESCE(a.n, 0) AS albums
+               FROM songs s
+               FULL OUTER JOIN albums a ON a.month = s.month
+               ORDER BY month"#,
+            vec![since.and_hms_opt(0, 0, 0).unwrap_or_default().into()],
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| MonthlyAdditions {
+                month: row.month,
+                songs: row.songs,
+                albums: row.albums,
+            })
+            .collect())
+    }
+}
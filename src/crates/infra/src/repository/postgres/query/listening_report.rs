@@ -0,0 +1,93 @@
+use super::db_data::listening_clock::{self, ActiveModel, Entity};
+use application::query::dao::ListeningReportDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use model::listening_report::{ListeningClockCell, ListeningClockEntry, ListeningClockRepository};
+use model::ModelError;
+use sea_orm::entity::prelude::*;
+use sea_orm::QueryOrder;
+
+#[derive(Clone)]
+pub struct ListeningClockRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ListeningClockRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ListeningClockRepository for ListeningClockRepositoryImpl {
+    async fn record(&self, entry: ListeningClockEntry) -> Result<(), ModelError> {
+        use sea_orm::sea_query::{Expr, OnConflict};
+
+        let play_seconds_val = entry.play_seconds;
+        let active_model = ActiveModel {
+            user_id: sea_orm::Set(entry.user_id.as_i64()),
+            year: sea_orm::Set(entry.year),
+            weekday: sea_orm::Set(entry.weekday),
+            hour: sea_orm::Set(entry.hour),
+            play_count: sea_orm::Set(1),
+            play_seconds: sea_orm::Set(play_seconds_val),
+        };
+
+        Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([
+                    listening_clock::Column::UserId,
+                    listening_clock::Column::Year,
+                    listening_clock::Column::Weekday,
+                    listening_clock::Column::Hour,
+                ])
+                .value(
+                    listening_clock::Column::PlayCount,
+                    Expr::col((listening_clock::Entity, listening_clock::Column::PlayCount)).add(1),
+                )
+                .value(
+                    listening_clock::Column::PlaySeconds,
+                    Expr::col((
+                        listening_clock::Entity,
+                        listening_clock::Column::PlaySeconds,
+                    ))
+                    .add(play_seconds_val),
+                )
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| ModelError::ProjectionError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+pub struct ListeningReportDaoImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ListeningReportDaoImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ListeningReportDao for ListeningReportDaoImpl {
+    async fn get_listening_clock(
+        &self,
+        user_id: i64,
+        year: i32,
+    ) -> Result<Vec<ListeningClockCell>, QueryError> {
+        let rows = Entity::find()
+            .filter(listening_clock::Column::UserId.eq(user_id))
+            .filter(listening_clock::Column::Year.eq(year))
+            .order_by_asc(listening_clock::Column::Weekday)
+            .order_by_asc(listening_clock::Column::Hour)
+            .all(&self.db)
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,174 @@
+use application::query::dao::ListeningStreakDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use domain::value::UserId;
+use model::listening_streak::{
+    AchievedMilestone, ListeningMilestone, ListeningStreak, ListeningStreakRepository,
+    MilestoneKind,
+};
+use model::ModelError;
+use sea_orm::*;
+
+#[derive(Debug, FromQueryResult)]
+struct StreakRow {
+    user_id: i64,
+    current_streak: i32,
+    longest_streak: i32,
+    last_played_on: Option<NaiveDate>,
+    total_plays: i64,
+}
+
+impl From<StreakRow> for ListeningStreak {
+    fn from(row: StreakRow) -> Self {
+        ListeningStreak {
+            user_id: UserId::from(row.user_id),
+            current_streak: row.current_streak,
+            longest_streak: row.longest_streak,
+            last_played_on: row.last_played_on,
+            total_plays: row.total_plays,
+        }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct MilestoneRow {
+    user_id: i64,
+    kind: String,
+    value: i64,
+    achieved_at: NaiveDateTime,
+    name: Option<String>,
+}
+
+fn find_streak_statement(user_id: i64) -> Statement {
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"SELECT user_id, current_streak, longest_streak, last_played_on, total_plays
+           FROM listening_streak
+           WHERE user_id = $1"#,
+        vec![user_id.into()],
+    )
+}
+
+#[derive(Clone)]
+pub struct ListeningStreakRepositoryImpl {
+    db: DatabaseConnection,
+}
+
+impl ListeningStreakRepositoryImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ListeningStreakRepository for ListeningStreakRepositoryImpl {
+    async fn find(&self, user_id: &UserId) -> Result<Option<ListeningStreak>, ModelError> {
+        let row = StreakRow::find_by_statement(find_streak_statement(user_id.as_i64()))
+            .one(&self.db)
+            .await
+            .map_err(|e| ModelError::ProjectionError(e.to_string()))?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn save(&self, streak: &ListeningStreak) -> Result<(), ModelError> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO listening_streak
+                     (user_id, current_streak, longest_streak, last_played_on, total_plays)
+                   VALUES ($1, $2, $3, $4, $5)
+                   ON CONFLICT (user_id) DO UPDATE SET
+                     current_streak = EXCLUDED.current_streak,
+                     longest_streak = EXCLUDED.longest_streak,
+                     last_played_on = EXCLUDED.last_played_on,
+                     total_plays = EXCLUDED.total_plays"#,
+                vec![
+                    streak.user_id.as_i64().into(),
+                    streak.current_streak.into(),
+                    streak.longest_streak.into(),
+                    streak.last_played_on.into(),
+                    streak.total_plays.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| ModelError::ProjectionError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn add_milestone(&self, milestone: &ListeningMilestone) -> Result<bool, ModelError> {
+        let result = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO listening_milestone (user_id, kind, value, achieved_at)
+                   VALUES ($1, $2, $3, $4)
+                   ON CONFLICT DO NOTHING"#,
+                vec![
+                    milestone.user_id.as_i64().into(),
+                    milestone.kind.name().into(),
+                    milestone.value.into(),
+                    milestone.achieved_at.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| ModelError::ProjectionError(e.to_string()))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+pub struct ListeningStreakDaoImpl {
+    db: DatabaseConnection,
+}
+
+impl ListeningStreakDaoImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ListeningStreakDao for ListeningStreakDaoImpl {
+    async fn get_streak(&self, user_id: i64) -> Result<Option<ListeningStreak>, QueryError> {
+        let row = StreakRow::find_by_statement(find_streak_statement(user_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn get_milestones(
+        &self,
+        user_id: i64,
+        limit: i32,
+    ) -> Result<Vec<AchievedMilestone>, QueryError> {
+        let rows: Vec<MilestoneRow> =
+            MilestoneRow::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT m.user_id, m.kind, m.value, m.achieved_at, ar.name
+                   FROM listening_milestone m
+                   LEFT JOIN artist ar ON m.kind = 'first_artist_play' AND ar.id = m.value
+                   WHERE m.user_id = $1
+                   ORDER BY m.achieved_at DESC, m.kind, m.value
+                   LIMIT $2"#,
+                vec![user_id.into(), limit.into()],
+            ))
+            .all(&self.db)
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(AchievedMilestone {
+                    milestone: ListeningMilestone {
+                        user_id: UserId::from(row.user_id),
+                        kind: MilestoneKind::parse(&row.kind)?,
+                        value: row.value,
+                        achieved_at: row.achieved_at,
+                    },
+                    name: row.name,
+                })
+            })
+            .collect())
+    }
+}
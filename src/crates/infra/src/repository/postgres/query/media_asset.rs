@@ -0,0 +1,100 @@
+use application::query::dao::MediaAssetDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use model::media_asset::MediaAsset;
+use sea_orm::*;
+
+pub struct MediaAssetDaoImpl {
+    db: DatabaseConnection,
+}
+
+impl MediaAssetDaoImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn query(&self, sql: &str, id: i64) -> Result<Vec<MediaAsset>, QueryError> {
+        let rows: Vec<MediaAssetRow> = MediaAssetRow::find_by_statement(
+            Statement::from_sql_and_values(DbBackend::Postgres, sql, [id.into()]),
+        )
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows.into_iter().map(MediaAsset::from).collect())
+    }
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct MediaAssetRow {
+    pub id: i64,
+    pub kind: String,
+    pub path_protocol: String,
+    pub path_path: String,
+    pub size: i64,
+    pub mtime: chrono::NaiveDateTime,
+}
+
+impl From<MediaAssetRow> for MediaAsset {
+    fn from(row: MediaAssetRow) -> Self {
+        Self {
+            id: row.id,
+            kind: row.kind,
+            protocol: row.path_protocol,
+            path: row.path_path,
+            size: row.size,
+            mtime: row.mtime,
+        }
+    }
+}
+
+#[async_trait]
+impl MediaAssetDao for MediaAssetDaoImpl {
+    async fn get_by_id(&self, id: i64) -> Result<Option<MediaAsset>, QueryError> {
+        let sql = r#"
+            SELECT id, kind, path_protocol, path_path, size, mtime
+            FROM media_asset
+            WHERE id = $1
+        "#;
+        Ok(self.query(sql, id).await?.into_iter().next())
+    }
+
+    async fn get_by_album(&self, album_id: i64) -> Result<Vec<MediaAsset>, QueryError> {
+        // 分碟目录（CD1、Disc 2）的上一级目录也属于专辑
+        let sql = r#"
+            SELECT ma.id, ma.kind, ma.path_protocol, ma.path_path, ma.size, ma.mtime
+            FROM media_asset ma
+            JOIN album_location al ON al.location_protocol = ma.path_protocol
+            WHERE al.album_id = $1 AND al.total > 0
+              AND (
+                ma.dir_path = al.location_path
+                OR ma.dir_path LIKE al.location_path || '/%'
+                OR (
+                  al.location_path ~* '/(cd|disc|disk)\s*[0-9]+$'
+                  AND ma.dir_path = regexp_replace(al.location_path, '/[^/]*$', '')
+                )
+              )
+            GROUP BY ma.id
+            ORDER BY ma.path_path ASC
+        "#;
+        self.query(sql, album_id).await
+    }
+
+    async fn get_by_artist(&self, artist_id: i64) -> Result<Vec<MediaAsset>, QueryError> {
+        let sql = r#"
+            SELECT ma.id, ma.kind, ma.path_protocol, ma.path_path, ma.size, ma.mtime,
+                   MIN(CASE WHEN ma.dir_path = al.location_path THEN 1 ELSE 0 END) AS depth
+            FROM media_asset ma
+            JOIN album_location al ON al.location_protocol = ma.path_protocol
+            JOIN album a ON a.id = al.album_id
+            WHERE a.album_artist_id = $1 AND al.total > 0
+              AND ma.kind IN ('artist_image', 'logo')
+              AND (
+                ma.dir_path = al.location_path
+                OR ma.dir_path = regexp_replace(al.location_path, '/[^/]*$', '')
+              )
+            GROUP BY ma.id
+            ORDER BY depth ASC, ma.path_path ASC
+        "#;
+        self.query(sql, artist_id).await
+    }
+}
@@ -1,14 +1,26 @@
 pub mod album;
 pub mod album_location;
 pub mod album_stats;
+pub mod annotation;
 pub mod artist;
 pub mod artist_location;
 pub mod audio_file;
+pub mod bookmark;
 pub mod cover_art;
 pub mod db_data;
+pub mod feed;
+pub mod format_stats;
 pub mod genre;
+pub mod home;
+pub mod library_stats;
+pub mod listening_report;
+pub mod listening_streak;
+pub mod media_asset;
 pub mod music_folder;
 pub mod participant_stats;
 pub mod play_queue;
 pub mod playback_history;
+pub mod play_stats;
 pub mod playlist;
+pub mod star_stats;
+pub mod user_note;
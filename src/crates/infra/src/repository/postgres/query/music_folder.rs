@@ -33,7 +33,7 @@ impl MusicFolderDao for MusicFolderDaoImpl {
         let folders: Vec<db_music_folder::MusicFolderModel> =
             db_music_folder::MusicFolderModel::find_by_statement(Statement::from_string(
                 DbBackend::Postgres,
-                r#"select id, name, last_scan_at from library;
+                r#"select id, name, last_scan_at from library where enabled order by id;
                 "#,
             ))
             .all(&self.db)
@@ -19,6 +19,7 @@ impl PlayQueueDaoImpl {
 struct PlayQueueRow {
     pub id: i64,
     pub current_id: Option<i64>,
+    pub current_index: Option<i32>,
     pub position: i64,
     pub changed_by: String,
     pub updated_at: i64,
@@ -55,6 +56,7 @@ impl PlayQueueDao for PlayQueueDaoImpl {
     async fn get_by_user_id(
         &self,
         user_id: i64,
+        client_id: &str,
         username: &str,
     ) -> Result<Option<PlayQueue>, QueryError> {
         // Get play queue basic info
@@ -65,13 +67,16 @@ impl PlayQueueDao for PlayQueueDaoImpl {
                 SELECT 
                     pq.id,
                     pq.current_id,
+                    pq.current_index,
                     pq.position,
                     pq.changed_by,
                     EXTRACT(EPOCH FROM pq.updated_at)::bigint as updated_at
                 FROM play_queue pq
                 WHERE pq.user_id = $1
+                ORDER BY (pq.client_id = $2) DESC, pq.updated_at DESC
+                LIMIT 1
                 "#,
-                vec![user_id.into()],
+                vec![user_id.into(), client_id.into()],
             ),
         )
         .one(&self.db)
@@ -115,11 +120,11 @@ impl PlayQueueDao for PlayQueueDaoImpl {
                 LEFT JOIN album al ON af.album_id = al.id
                 LEFT JOIN artist ar ON af.artist_id = ar.id
                 LEFT JOIN genre g ON af.genre_id = g.id
-                LEFT JOIN annotation ann ON ann.item_id = af.id AND ann.item_kind = 'audio_file'
+                LEFT JOIN annotation ann ON ann.item_id = af.id AND ann.item_kind = 'audio_file' AND ann.user_id = $2
                 WHERE pqi.play_queue_id = $1
                 ORDER BY pqi.position
                 "#,
-                vec![queue_row.id.into()],
+                vec![queue_row.id.into(), user_id.into()],
             ),
         )
         .all(&self.db)
@@ -158,8 +163,22 @@ impl PlayQueueDao for PlayQueueDaoImpl {
             .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
             .unwrap_or_default();
 
+        // 旧数据没有保存索引，按 current_id 在队列中的位置补齐
+        let current_index = queue_row
+            .current_index
+            .filter(|&idx| idx >= 0 && (idx as usize) < entries.len())
+            .or_else(|| {
+                queue_row.current_id.and_then(|cid| {
+                    entries.iter().position(|e| e.id == cid).map(|idx| idx as i32)
+                })
+            });
+        let current_id = current_index
+            .map(|idx| entries[idx as usize].id)
+            .or(queue_row.current_id);
+
         Ok(Some(PlayQueue {
-            current_id: queue_row.current_id,
+            current_id,
+            current_index,
             position: queue_row.position,
             username: username.to_string(),
             changed_by: queue_row.changed_by,
@@ -0,0 +1,136 @@
+use super::db_data::play_count_rollup::{self, ActiveModel, ChartRow, Entity};
+use application::query::dao::ChartDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use model::play_stats::{ChartEntry, PlayCountEntry, PlayStatsRepository, RollupPeriod};
+use model::ModelError;
+use sea_orm::entity::prelude::*;
+use sea_orm::{DbBackend, FromQueryResult, Statement};
+
+#[derive(Clone)]
+pub struct PlayStatsRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl PlayStatsRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PlayStatsRepository for PlayStatsRepositoryImpl {
+    async fn increment(&self, entry: PlayCountEntry) -> Result<(), ModelError> {
+        use sea_orm::sea_query::{Expr, OnConflict};
+
+        let active_model = ActiveModel {
+            period: sea_orm::Set(entry.period.as_str().to_string()),
+            period_start: sea_orm::Set(entry.period_start),
+            user_id: sea_orm::Set(entry.user_id.as_i64()),
+            item_kind: sea_orm::Set(entry.item_kind),
+            item_id: sea_orm::Set(entry.item_id),
+            play_count: sea_orm::Set(1),
+        };
+
+        Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([
+                    play_count_rollup::Column::Period,
+                    play_count_rollup::Column::PeriodStart,
+                    play_count_rollup::Column::UserId,
+                    play_count_rollup::Column::ItemKind,
+                    play_count_rollup::Column::ItemId,
+                ])
+                .value(
+                    play_count_rollup::Column::PlayCount,
+                    Expr::col((
+                        play_count_rollup::Entity,
+                        play_count_rollup::Column::PlayCount,
+                    ))
+                    .add(1),
+                )
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| ModelError::ProjectionError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+pub struct ChartDaoImpl {
+    db: sea_orm::DbConn,
+}
+
+impl ChartDaoImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+/// 各条目类型对应的名称表和名称列
+fn name_source(item_kind: &str) -> Result<(&'static str, &'static str), QueryError> {
+    match item_kind {
+        "audio_file" => Ok(("audio_file", "title")),
+        "album" => Ok(("album", "name")),
+        "artist" => Ok(("artist", "name")),
+        "genre" => Ok(("genre", "name")),
+        other => Err(QueryError::InvalidParameter(format!(
+            "Unsupported chart item kind: {}",
+            other
+        ))),
+    }
+}
+
+#[async_trait]
+impl ChartDao for ChartDaoImpl {
+    async fn get_top_items(
+        &self,
+        item_kind: &str,
+        period: RollupPeriod,
+        from: NaiveDate,
+        to: NaiveDate,
+        user_id: Option<i64>,
+        limit: i32,
+    ) -> Result<Vec<ChartEntry>, QueryError> {
+        let (table, name_column) = name_source(item_kind)?;
+        let mut values: Vec<Value> = vec![
+            period.as_str().into(),
+            item_kind.into(),
+            from.into(),
+            to.into(),
+            limit.into(),
+        ];
+        let user_filter = match user_id {
+            Some(user_id) => {
+                values.push(user_id.into());
+                "AND r.user_id = $6"
+            }
+            None => "",
+        };
+        // 已删除的条目不再出现在排行榜中
+        let sql = format!(
+            r#"SELECT r.item_id, t.{name_column} AS name, SUM(r.play_count)::BIGINT AS play_count
+               FROM play_count_rollup r
+               JOIN {table} t ON t.id = r.item_id
+               WHERE r.period = $1 AND r.item_kind = $2
+                 AND r.period_start >= $3 AND r.period_start < $4
+                 {user_filter}
+               GROUP BY r.item_id, t.{name_column}
+               ORDER BY play_count DESC, r.item_id
+               LIMIT $5"#
+        );
+
+        let rows: Vec<ChartRow> = ChartRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &sql,
+            values,
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
@@ -1,7 +1,12 @@
 use super::db_data::playback_history as db;
+use application::query::dao::PlaybackHistoryDao;
+use application::query::QueryError;
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use domain::value::UserId;
-use model::playback_history::{PlaybackHistoryEntry, PlaybackHistoryRepository};
+use model::playback_history::{
+    PlaybackHistoryEntry, PlaybackHistoryItem, PlaybackHistoryRepository,
+};
 use model::ModelError;
 use sea_orm::*;
 
@@ -40,3 +45,87 @@ impl PlaybackHistoryRepository for PlaybackHistoryRepositoryImpl {
         Ok(())
     }
 }
+
+pub struct PlaybackHistoryDaoImpl {
+    db: DatabaseConnection,
+}
+
+impl PlaybackHistoryDaoImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct HistoryRow {
+    id: i64,
+    audio_file_id: i64,
+    title: String,
+    album_id: Option<i64>,
+    album: Option<String>,
+    artist_id: Option<i64>,
+    artist: Option<String>,
+    duration: i64,
+    scrobbled_at: NaiveDateTime,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CountRow {
+    total: i64,
+}
+
+impl From<HistoryRow> for PlaybackHistoryItem {
+    fn from(row: HistoryRow) -> Self {
+        Self {
+            id: row.id,
+            audio_file_id: row.audio_file_id,
+            title: row.title,
+            album_id: row.album_id,
+            album: row.album,
+            artist_id: row.artist_id,
+            artist: row.artist,
+            duration: row.duration,
+            scrobbled_at: row.scrobbled_at,
+        }
+    }
+}
+
+#[async_trait]
+impl PlaybackHistoryDao for PlaybackHistoryDaoImpl {
+    async fn get_by_user(
+        &self,
+        user_id: i64,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<PlaybackHistoryItem>, i64), QueryError> {
+        let total = CountRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT COUNT(*) AS total FROM playback_history WHERE user_id = $1",
+            vec![user_id.into()],
+        ))
+        .one(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?
+        .map(|row| row.total)
+        .unwrap_or(0);
+        // 歌曲被删除后记录仍然保留，只是没有歌曲信息
+        let rows = HistoryRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT ph.id, ph.audio_file_id, COALESCE(af.title, '') AS title,
+                      af.album_id, al.name AS album, af.artist_id, ar.name AS artist,
+                      COALESCE(af.duration, 0) AS duration, ph.scrobbled_at
+               FROM playback_history ph
+               LEFT JOIN audio_file af ON af.id = ph.audio_file_id
+               LEFT JOIN album al ON al.id = af.album_id
+               LEFT JOIN artist ar ON ar.id = af.artist_id
+               WHERE ph.user_id = $1
+               ORDER BY ph.scrobbled_at DESC, ph.id DESC
+               OFFSET $2 LIMIT $3"#,
+            vec![user_id.into(), offset.into(), limit.into()],
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok((rows.into_iter().map(Into::into).collect(), total))
+    }
+}
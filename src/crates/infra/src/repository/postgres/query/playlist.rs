@@ -6,11 +6,18 @@ use sea_orm::*;
 
 pub struct PlaylistDaoImpl {
     db: DatabaseConnection,
+    user_id: Option<i64>,
 }
 
 impl PlaylistDaoImpl {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, user_id: None }
+    }
+
+    /// 歌曲的播放次数、评分和收藏只取该用户的注解；未指定用户时这些字段为空
+    pub fn with_user(mut self, user_id: Option<i64>) -> Self {
+        self.user_id = user_id;
+        self
     }
 }
 
@@ -24,8 +31,10 @@ struct PlaylistRow {
     pub public: i32,
     pub created_at: i64,
     pub updated_at: i64,
+    pub deleted_at: Option<i64>,
     pub song_count: i64,
     pub duration: i64,
+    pub tags: Vec<String>,
 }
 
 impl From<PlaylistRow> for PlaylistSummary {
@@ -43,8 +52,10 @@ impl From<PlaylistRow> for PlaylistSummary {
             owner_id: row.owner_id,
             owner_name: row.owner_name,
             public: row.public == 1,
+            tags: row.tags,
             created_at: row.created_at,
             updated_at: row.updated_at,
+            deleted_at: row.deleted_at,
         }
     }
 }
@@ -52,6 +63,7 @@ impl From<PlaylistRow> for PlaylistSummary {
 #[derive(Debug, Clone, FromQueryResult)]
 struct PlaylistEntryRow {
     pub entry_id: i64,
+    pub added_by: Option<String>,
     // AudioFile fields
     pub id: i64,
     pub title: String,
@@ -76,6 +88,11 @@ struct PlaylistEntryRow {
     pub created_at: i64,
 }
 
+#[derive(Debug, Clone, FromQueryResult)]
+struct CollaboratorRow {
+    pub username: String,
+}
+
 #[async_trait]
 impl PlaylistDao for PlaylistDaoImpl {
     async fn get_by_id(&self, id: i64) -> Result<Option<Playlist>, QueryError> {
@@ -90,12 +107,17 @@ impl PlaylistDao for PlaylistDaoImpl {
                     CASE WHEN p.public THEN 1 ELSE 0 END as public,
                     EXTRACT(EPOCH FROM p.created_at)::bigint as created_at,
                     EXTRACT(EPOCH FROM p.updated_at)::bigint as updated_at,
+                    EXTRACT(EPOCH FROM p.deleted_at)::bigint as deleted_at,
                     COUNT(pe.id) as song_count,
-                    COALESCE(SUM(af.duration), 0)::bigint as duration
+                    COALESCE(SUM(af.duration), 0)::bigint as duration,
+                    COALESCE((
+                        SELECT array_agg(pt.tag ORDER BY pt.position)
+                        FROM playlist_tag pt WHERE pt.playlist_id = p.id
+                    ), ARRAY[]::varchar[]) as tags
                 FROM playlist p
                 LEFT JOIN playlist_entry pe ON p.id = pe.playlist_id
                 LEFT JOIN audio_file af ON pe.audio_file_id = af.id
-                WHERE p.id = $1
+                WHERE p.id = $1 AND p.deleted_at IS NULL
                 GROUP BY p.id
                 "#,
                 vec![id.into()],
@@ -117,6 +139,7 @@ impl PlaylistDao for PlaylistDaoImpl {
                 r#"
                 SELECT 
                     pe.id as entry_id,
+                    u.username as added_by,
                     af.id,
                     af.title,
                     af.album_id,
@@ -143,11 +166,12 @@ impl PlaylistDao for PlaylistDaoImpl {
                 LEFT JOIN album al ON af.album_id = al.id
                 LEFT JOIN artist ar ON af.artist_id = ar.id
                 LEFT JOIN genre g ON af.genre_id = g.id
-                LEFT JOIN annotation ann ON ann.item_id = af.id AND ann.item_kind = 'audio_file'
+                LEFT JOIN annotation ann ON ann.item_id = af.id AND ann.item_kind = 'audio_file' AND ann.user_id = $2
+                LEFT JOIN "user" u ON pe.added_by = u.id
                 WHERE pe.playlist_id = $1
-                ORDER BY pe.added_at
+                ORDER BY pe.position, pe.added_at
                 "#,
-                vec![id.into()],
+                vec![id.into(), self.user_id.into()],
             ),
         )
         .all(&self.db)
@@ -158,6 +182,7 @@ impl PlaylistDao for PlaylistDaoImpl {
             .into_iter()
             .map(|row| PlaylistTrack {
                 id: row.entry_id,
+                added_by: row.added_by,
                 audio_file: PlaylistAudioFile {
                     id: row.id,
                     title: row.title,
@@ -184,6 +209,22 @@ impl PlaylistDao for PlaylistDaoImpl {
             })
             .collect();
 
+        let collaborators: Vec<CollaboratorRow> =
+            CollaboratorRow::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT u.username
+                FROM playlist_collaborator pc
+                JOIN "user" u ON pc.user_id = u.id
+                WHERE pc.playlist_id = $1
+                ORDER BY pc.added_at
+                "#,
+                vec![id.into()],
+            ))
+            .all(&self.db)
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?;
+
         Ok(Some(Playlist {
             id: playlist_row.id,
             name: playlist_row.name,
@@ -197,6 +238,8 @@ impl PlaylistDao for PlaylistDaoImpl {
             owner_id: playlist_row.owner_id,
             owner_name: playlist_row.owner_name,
             public: playlist_row.public == 1,
+            collaborators: collaborators.into_iter().map(|c| c.username).collect(),
+            tags: playlist_row.tags,
             tracks,
             created_at: playlist_row.created_at,
             updated_at: playlist_row.updated_at,
@@ -213,15 +256,96 @@ impl PlaylistDao for PlaylistDaoImpl {
                 CASE WHEN p.public THEN 1 ELSE 0 END as public,
                 EXTRACT(EPOCH FROM p.created_at)::bigint as created_at,
                 EXTRACT(EPOCH FROM p.updated_at)::bigint as updated_at,
+                EXTRACT(EPOCH FROM p.deleted_at)::bigint as deleted_at,
+                COUNT(pe.id) as song_count,
+                COALESCE(SUM(af.duration), 0)::bigint as duration,
+                COALESCE((
+                    SELECT array_agg(pt.tag ORDER BY pt.position)
+                    FROM playlist_tag pt WHERE pt.playlist_id = p.id
+                ), ARRAY[]::varchar[]) as tags
+            FROM playlist p
+            LEFT JOIN playlist_entry pe ON p.id = pe.playlist_id
+            LEFT JOIN audio_file af ON pe.audio_file_id = af.id
+            WHERE p.owner_id = $1 AND p.deleted_at IS NULL
+            GROUP BY p.id
+            ORDER BY p.updated_at DESC
+            "#,
+            vec![owner_id.into()],
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|row| row.into()).collect())
+    }
+
+    async fn get_editable_by(&self, user_id: i64) -> Result<Vec<PlaylistSummary>, QueryError> {
+        let rows: Vec<PlaylistRow> = PlaylistRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT 
+                p.id, p.name, COALESCE(p.comment, '') as comment, 
+                p.owner_id, p.owner_name, 
+                CASE WHEN p.public THEN 1 ELSE 0 END as public,
+                EXTRACT(EPOCH FROM p.created_at)::bigint as created_at,
+                EXTRACT(EPOCH FROM p.updated_at)::bigint as updated_at,
+                EXTRACT(EPOCH FROM p.deleted_at)::bigint as deleted_at,
                 COUNT(pe.id) as song_count,
-                COALESCE(SUM(af.duration), 0)::bigint as duration
+                COALESCE(SUM(af.duration), 0)::bigint as duration,
+                COALESCE((
+                    SELECT array_agg(pt.tag ORDER BY pt.position)
+                    FROM playlist_tag pt WHERE pt.playlist_id = p.id
+                ), ARRAY[]::varchar[]) as tags
             FROM playlist p
             LEFT JOIN playlist_entry pe ON p.id = pe.playlist_id
             LEFT JOIN audio_file af ON pe.audio_file_id = af.id
-            WHERE p.owner_id = $1
+            WHERE p.deleted_at IS NULL
+              AND (
+                  p.owner_id = $1
+                  OR (p.public AND EXISTS (
+                      SELECT 1 FROM playlist_collaborator pc
+                      WHERE pc.playlist_id = p.id AND pc.user_id = $1
+                  ))
+              )
             GROUP BY p.id
             ORDER BY p.updated_at DESC
             "#,
+            vec![user_id.into()],
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|row| row.into()).collect())
+    }
+
+    async fn get_deleted_by_owner(
+        &self,
+        owner_id: i64,
+    ) -> Result<Vec<PlaylistSummary>, QueryError> {
+        let rows: Vec<PlaylistRow> = PlaylistRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                p.id, p.name, COALESCE(p.comment, '') as comment,
+                p.owner_id, p.owner_name,
+                CASE WHEN p.public THEN 1 ELSE 0 END as public,
+                EXTRACT(EPOCH FROM p.created_at)::bigint as created_at,
+                EXTRACT(EPOCH FROM p.updated_at)::bigint as updated_at,
+                EXTRACT(EPOCH FROM p.deleted_at)::bigint as deleted_at,
+                COUNT(pe.id) as song_count,
+                COALESCE(SUM(af.duration), 0)::bigint as duration,
+                COALESCE((
+                    SELECT array_agg(pt.tag ORDER BY pt.position)
+                    FROM playlist_tag pt WHERE pt.playlist_id = p.id
+                ), ARRAY[]::varchar[]) as tags
+            FROM playlist p
+            LEFT JOIN playlist_entry pe ON p.id = pe.playlist_id
+            LEFT JOIN audio_file af ON pe.audio_file_id = af.id
+            WHERE p.owner_id = $1 AND p.deleted_at IS NOT NULL
+            GROUP BY p.id
+            ORDER BY p.deleted_at DESC
+            "#,
             vec![owner_id.into()],
         ))
         .all(&self.db)
@@ -0,0 +1,49 @@
+use super::db_data::{star_stats, star_stats::ActiveModel, star_stats::Entity};
+use async_trait::async_trait;
+use model::star_stats::{StarStats, StarStatsRepository};
+use model::ModelError;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone)]
+pub struct StarStatsRepositoryImpl {
+    db: sea_orm::DbConn,
+}
+
+impl StarStatsRepositoryImpl {
+    pub fn new(db: sea_orm::DbConn) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl StarStatsRepository for StarStatsRepositoryImpl {
+    async fn adjust_stats(&self, entry: StarStats) -> Result<(), ModelError> {
+        use sea_orm::sea_query::{Expr, OnConflict};
+
+        let star_count_val = entry.star_count;
+        let active_model = ActiveModel {
+            item_kind: sea_orm::Set(entry.item_kind),
+            item_id: sea_orm::Set(entry.item_id),
+            // 首次插入时不会出现负数
+            star_count: sea_orm::Set(star_count_val.max(0)),
+        };
+
+        Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([star_stats::Column::ItemKind, star_stats::Column::ItemId])
+                    .value(
+                        star_stats::Column::StarCount,
+                        Expr::cust_with_values(
+                            "GREATEST(star_stats.star_count + $1, 0)",
+                            [star_count_val],
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| ModelError::ProjectionError(e.to_string()))?;
+
+        Ok(())
+    }
+}
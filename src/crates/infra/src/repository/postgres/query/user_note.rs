@@ -0,0 +1,115 @@
+use application::query::dao::UserNoteDao;
+use application::query::QueryError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use model::user_note::UserNote;
+use sea_orm::*;
+use std::collections::HashMap;
+
+pub struct UserNoteDaoImpl {
+    db: DatabaseConnection,
+}
+
+impl UserNoteDaoImpl {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct UserNoteRow {
+    item_kind: String,
+    item_id: i64,
+    note: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<UserNoteRow> for UserNote {
+    fn from(row: UserNoteRow) -> Self {
+        Self {
+            item_kind: row.item_kind,
+            item_id: row.item_id,
+            note: row.note,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct NoteTextRow {
+    item_id: i64,
+    note: String,
+}
+
+#[async_trait]
+impl UserNoteDao for UserNoteDaoImpl {
+    async fn get_by_user(
+        &self,
+        user_id: i64,
+        item_kind: Option<&str>,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<UserNote>, i64), QueryError> {
+        let total = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT COUNT(*) FROM user_note
+                   WHERE user_id = $1 AND ($2::text IS NULL OR item_kind = $2)"#,
+                vec![user_id.into(), item_kind.map(str::to_string).into()],
+            ))
+            .await
+            .map_err(|e| QueryError::DbError(e.to_string()))?
+            .and_then(|row| row.try_get_by_index::<i64>(0).ok())
+            .unwrap_or(0);
+
+        let rows = UserNoteRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT item_kind, item_id, note, created_at, updated_at
+               FROM user_note
+               WHERE user_id = $1 AND ($2::text IS NULL OR item_kind = $2)
+               ORDER BY updated_at DESC, item_kind, item_id
+               LIMIT $3 OFFSET $4"#,
+            vec![
+                user_id.into(),
+                item_kind.map(str::to_string).into(),
+                limit.into(),
+                offset.into(),
+            ],
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok((rows.into_iter().map(UserNote::from).collect(), total))
+    }
+
+    async fn get_notes(
+        &self,
+        user_id: i64,
+        item_kind: &str,
+        item_ids: &[i64],
+    ) -> Result<HashMap<i64, String>, QueryError> {
+        if item_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = NoteTextRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT item_id, note FROM user_note
+               WHERE user_id = $1 AND item_kind = $2 AND item_id = ANY($3)"#,
+            vec![
+                user_id.into(),
+                item_kind.to_string().into(),
+                item_ids.to_vec().into(),
+            ],
+        ))
+        .all(&self.db)
+        .await
+        .map_err(|e| QueryError::DbError(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.item_id, row.note))
+            .collect())
+    }
+}
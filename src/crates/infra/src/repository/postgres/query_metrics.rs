@@ -0,0 +1,243 @@
+use crate::config::{AppConfigImpl, QueryBudgetConfig};
+use log::warn;
+use sea_orm::metric::Info;
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 单个请求最多分别统计的语句数，更多的语句只计入总数
+const MAX_TRACKED_STATEMENTS: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_QUERIES: Arc<RequestQueries>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct StatementStats {
+    count: u64,
+    max: Duration,
+}
+
+#[derive(Debug, Default)]
+struct RequestQueriesInner {
+    count: u64,
+    total: Duration,
+    statements: HashMap<String, StatementStats>,
+}
+
+/// 单个请求内执行的查询
+#[derive(Debug, Default)]
+pub struct RequestQueries {
+    inner: Mutex<RequestQueriesInner>,
+}
+
+impl RequestQueries {
+    fn record(&self, sql: &str, elapsed: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.count += 1;
+        inner.total += elapsed;
+        if let Some(stats) = inner.statements.get_mut(sql) {
+            stats.count += 1;
+            stats.max = stats.max.max(elapsed);
+        } else if inner.statements.len() < MAX_TRACKED_STATEMENTS {
+            let stats = StatementStats {
+                count: 1,
+                max: elapsed,
+            };
+            inner.statements.insert(sql.to_string(), stats);
+        }
+    }
+
+    pub fn summary(&self) -> RequestQuerySummary {
+        let inner = self.inner.lock().unwrap();
+        let most_repeated = inner
+            .statements
+            .iter()
+            .max_by_key(|(sql, stats)| (stats.count, std::cmp::Reverse(sql.as_str())))
+            .map(|(sql, stats)| (sql.clone(), stats.count));
+        let slowest = inner
+            .statements
+            .iter()
+            .max_by_key(|(sql, stats)| (stats.max, std::cmp::Reverse(sql.as_str())))
+            .map(|(sql, stats)| (sql.clone(), stats.max));
+        RequestQuerySummary {
+            count: inner.count,
+            total: inner.total,
+            most_repeated,
+            slowest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RequestQuerySummary {
+    pub count: u64,
+    /// 查询总耗时
+    pub total: Duration,
+    /// 执行次数最多的语句及次数，N+1 查询通常表现为同一语句重复执行
+    pub most_repeated: Option<(String, u64)>,
+    /// 单次耗时最长的语句及耗时
+    pub slowest: Option<(String, Duration)>,
+}
+
+impl RequestQuerySummary {
+    pub fn exceeds(&self, budget: &QueryBudgetConfig) -> bool {
+        budget.max_queries.is_some_and(|max| self.count > max)
+            || budget.max_db_time.is_some_and(|max| self.total > max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryMetricsSnapshot {
+    pub queries: u64,
+    pub failed: u64,
+    pub total_time: Duration,
+    pub slow_queries: u64,
+    /// 统计过的请求数
+    pub requests: u64,
+    /// 请求内执行的查询数，其余为后台任务和事件处理的查询
+    pub request_queries: u64,
+    pub over_budget_requests: u64,
+    /// 单个请求的最多查询次数
+    pub max_request_queries: u64,
+}
+
+/// 数据库查询的累计指标
+#[derive(Debug, Default)]
+pub struct QueryMetrics {
+    queries: AtomicU64,
+    failed: AtomicU64,
+    total_micros: AtomicU64,
+    slow_queries: AtomicU64,
+    requests: AtomicU64,
+    request_queries: AtomicU64,
+    over_budget_requests: AtomicU64,
+    max_request_queries: AtomicU64,
+}
+
+impl QueryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在连接上安装查询回调，安装之后克隆出的连接共享同一份统计
+    pub fn install(self: &Arc<Self>, db: &mut DatabaseConnection, app_cfg: AppConfigImpl) {
+        let metrics = self.clone();
+        db.set_metric_callback(move |info: &Info<'_>| {
+            let slow_query = app_cfg.query_budget().slow_query;
+            metrics.record_query(&info.statement.sql, info.elapsed, info.failed, slow_query);
+        });
+    }
+
+    fn record_query(
+        &self,
+        sql: &str,
+        elapsed: Duration,
+        failed: bool,
+        slow_query: Option<Duration>,
+    ) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        self.total_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        if failed {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        if slow_query.is_some_and(|threshold| elapsed >= threshold) {
+            self.slow_queries.fetch_add(1, Ordering::Relaxed);
+            warn!("Slow query ({} ms): {}", elapsed.as_millis(), sql);
+        }
+        // 不在请求范围内（后台任务、事件处理）的查询只计入累计指标
+        let _ = REQUEST_QUERIES.try_with(|queries| queries.record(sql, elapsed));
+    }
+
+    /// 执行 future 并统计期间的查询，future 内 spawn 出去的任务不计入
+    pub async fn track<F: Future>(&self, future: F) -> (F::Output, RequestQuerySummary) {
+        let queries = Arc::new(RequestQueries::default());
+        let output = REQUEST_QUERIES.scope(queries.clone(), future).await;
+        let summary = queries.summary();
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.request_queries
+            .fetch_add(summary.count, Ordering::Relaxed);
+        self.max_request_queries
+            .fetch_max(summary.count, Ordering::Relaxed);
+        (output, summary)
+    }
+
+    pub fn record_over_budget(&self) {
+        self.over_budget_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> QueryMetricsSnapshot {
+        QueryMetricsSnapshot {
+            queries: self.queries.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            total_time: Duration::from_micros(self.total_micros.load(Ordering::Relaxed)),
+            slow_queries: self.slow_queries.load(Ordering::Relaxed),
+            requests: self.requests.load(Ordering::Relaxed),
+            request_queries: self.request_queries.load(Ordering::Relaxed),
+            over_budget_requests: self.over_budget_requests.load(Ordering::Relaxed),
+            max_request_queries: self.max_request_queries.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BY_ID: &str = "SELECT * FROM album WHERE id = $1";
+    const LIST: &str = "SELECT * FROM album ORDER BY name";
+
+    #[tokio::test]
+    async fn tracks_queries_within_request_scope() {
+        let metrics = QueryMetrics::new();
+        let ((), summary) = metrics
+            .track(async {
+                metrics.record_query(LIST, Duration::from_millis(30), false, None);
+                for _ in 0..3 {
+                    metrics.record_query(BY_ID, Duration::from_millis(2), false, None);
+                }
+            })
+            .await;
+        // 请求范围外的查询只计入累计指标
+        metrics.record_query(BY_ID, Duration::from_millis(500), true, None);
+
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.total, Duration::from_millis(36));
+        assert_eq!(summary.most_repeated, Some((BY_ID.to_string(), 3)));
+        assert_eq!(
+            summary.slowest,
+            Some((LIST.to_string(), Duration::from_millis(30)))
+        );
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.queries, 5);
+        assert_eq!(snapshot.failed, 1);
+        assert_eq!(snapshot.requests, 1);
+        assert_eq!(snapshot.request_queries, 4);
+        assert_eq!(snapshot.max_request_queries, 4);
+    }
+
+    #[test]
+    fn budget_checks_count_and_time() {
+        let summary = RequestQuerySummary {
+            count: 60,
+            total: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let budget = QueryBudgetConfig {
+            max_queries: Some(50),
+            max_db_time: Some(Duration::from_secs(1)),
+            slow_query: None,
+        };
+        assert!(summary.exceeds(&budget));
+        let unlimited = QueryBudgetConfig {
+            max_queries: None,
+            ..budget
+        };
+        assert!(!summary.exceeds(&unlimited));
+    }
+}
@@ -1,18 +1,58 @@
 use application::command::library::{ScanError, Scanner, ScannerFactory};
 use application::command::media_parse::{StorageClient, StorageClientFactory};
 use async_trait::async_trait;
+use domain::library::LibraryCredentialsRepository;
 use domain::value::MediaPath;
+use log::warn;
 use std::sync::Arc;
 
 use super::local::LocalStorageClient;
+use super::read_ahead::ReadAheadOptions;
 use super::smb::SmbStorageClient;
 
-#[derive(Debug, Clone)]
-pub struct StorageClientFactoryImpl;
+#[derive(Clone, Default)]
+pub struct StorageClientFactoryImpl {
+    credentials_repository: Option<Arc<dyn LibraryCredentialsRepository>>,
+    read_ahead: ReadAheadOptions,
+}
 
 impl StorageClientFactoryImpl {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// 创建远程存储客户端时按路径所属的库加载凭据
+    pub fn with_credentials(credentials_repository: Arc<dyn LibraryCredentialsRepository>) -> Self {
+        Self {
+            credentials_repository: Some(credentials_repository),
+            ..Self::default()
+        }
+    }
+
+    /// 远程存储顺序读取时的预读参数
+    pub fn with_read_ahead(mut self, read_ahead: ReadAheadOptions) -> Self {
+        self.read_ahead = read_ahead;
+        self
+    }
+
+    async fn smb_client(&self, path: &MediaPath) -> SmbStorageClient {
+        self.smb_client_with_credentials(path)
+            .await
+            .with_read_ahead(self.read_ahead)
+    }
+
+    async fn smb_client_with_credentials(&self, path: &MediaPath) -> SmbStorageClient {
+        let Some(repo) = &self.credentials_repository else {
+            return SmbStorageClient::new();
+        };
+        match repo.find_for_path(path).await {
+            Ok(Some(credentials)) => SmbStorageClient::with_credentials(credentials),
+            Ok(None) => SmbStorageClient::new(),
+            Err(e) => {
+                warn!("Failed to load credentials for {}: {}", path.path, e);
+                SmbStorageClient::new()
+            }
+        }
     }
 }
 
@@ -24,7 +64,7 @@ impl StorageClientFactory for StorageClientFactoryImpl {
     ) -> Result<Arc<dyn StorageClient>, application::error::AppError> {
         match path.protocol.as_str() {
             "local" | "" => Ok(Arc::new(LocalStorageClient::new())),
-            "smb" => Ok(Arc::new(SmbStorageClient::new())),
+            "smb" => Ok(Arc::new(self.smb_client(path).await)),
             p => Err(application::error::AppError::UnknownError(format!(
                 "Unsupported storage protocol: {}",
                 p
@@ -35,11 +75,11 @@ impl StorageClientFactory for StorageClientFactoryImpl {
 
 #[async_trait]
 impl ScannerFactory for StorageClientFactoryImpl {
-    async fn create(&self, protocol: &str) -> Result<Arc<dyn Scanner>, ScanError> {
-        match protocol {
+    async fn create(&self, root: &MediaPath) -> Result<Arc<dyn Scanner>, ScanError> {
+        match root.protocol.as_str() {
             "local" | "" => Ok(Arc::new(LocalStorageClient::new())),
-            "smb" => Ok(Arc::new(SmbStorageClient::new())),
-            _ => Err(ScanError::OtherError(format!(
+            "smb" => Ok(Arc::new(self.smb_client(root).await)),
+            protocol => Err(ScanError::OtherError(format!(
                 "Unsupported scanner protocol: {}",
                 protocol
             ))),
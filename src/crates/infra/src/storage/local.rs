@@ -1,11 +1,14 @@
 use application::command::library::{ScanError, Scanner};
-use application::command::media_parse::StorageClient;
+use application::command::media_parse::{MediaReader, StorageClient};
 use application::error::AppError;
 use async_trait::async_trait;
+use domain::library::ScanFilter;
 use domain::value::{FileMeta, MediaPath};
 use std::fs;
+use std::io::SeekFrom;
 use std::path::Path;
 use std::path::PathBuf;
+use tokio::io::AsyncSeekExt;
 use tokio::sync::mpsc;
 use walkdir::WalkDir;
 
@@ -27,39 +30,7 @@ impl StorageClient for LocalStorageClient {
             let meta = entry
                 .metadata()
                 .map_err(|e| AppError::UnknownError(e.to_string()))?;
-            let p = entry.path();
-            items.push(FileMeta::new(
-                MediaPath {
-                    protocol: "local".to_string(),
-                    path: p.to_string_lossy().to_string(),
-                },
-                MediaPath {
-                    protocol: "local".to_string(),
-                    path: p
-                        .parent()
-                        .and_then(|pp| pp.to_str())
-                        .unwrap_or("")
-                        .to_string(),
-                },
-                meta.len() as i64,
-                p.extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("")
-                    .to_string(),
-                chrono::DateTime::<chrono::Utc>::from(
-                    meta.modified().unwrap_or(std::time::SystemTime::now()),
-                )
-                .naive_utc(),
-                chrono::DateTime::<chrono::Utc>::from(
-                    meta.accessed().unwrap_or(std::time::SystemTime::now()),
-                )
-                .naive_utc(),
-                chrono::DateTime::<chrono::Utc>::from(
-                    meta.created().unwrap_or(std::time::SystemTime::now()),
-                )
-                .naive_utc(),
-                None,
-            ));
+            items.push(file_meta(&entry.path(), &meta));
         }
         Ok(items)
     }
@@ -75,6 +46,27 @@ impl StorageClient for LocalStorageClient {
     async fn get_local_path(&self, path: &MediaPath) -> Result<PathBuf, AppError> {
         Ok(Path::new(&path.path).to_path_buf())
     }
+
+    /// 本地文件由操作系统预读，不需要额外的缓冲
+    async fn open(&self, path: &MediaPath, offset: u64) -> Result<MediaReader, AppError> {
+        let mut file = tokio::fs::File::open(&path.path)
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        Ok(Box::new(file))
+    }
+
+    fn validate_path(&self, path: &MediaPath) -> Result<(), AppError> {
+        if !Path::new(&path.path).is_absolute() {
+            return Err(AppError::InvalidInput(format!(
+                "Local path must be absolute: {}",
+                path.path
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -82,49 +74,41 @@ impl Scanner for LocalStorageClient {
     async fn scan(
         &self,
         root: &str,
+        filter: &ScanFilter,
     ) -> Result<mpsc::Receiver<Result<FileMeta, ScanError>>, ScanError> {
         let (tx, rx) = mpsc::channel(64);
-        let root = root.to_string();
+        let root = PathBuf::from(root);
+        let filter = filter.clone();
         tokio::spawn(async move {
-            for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+            // 按文件名排序遍历，保证每次扫描顺序一致
+            for entry in WalkDir::new(&root)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|entry| {
+                    let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+                    let relative = relative.to_string_lossy();
+                    if entry.depth() == 0 {
+                        true
+                    } else if entry.file_type().is_dir() {
+                        filter.allows_dir(&relative)
+                    } else {
+                        filter.allows_file(&relative)
+                    }
+                })
+            {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        // 目录无法读取（如网络挂载断开）时通知调用方保留其中的文件
+                        let dir = e.path().unwrap_or(&root).to_string_lossy().to_string();
+                        let _ = tx.send(Err(ScanError::DirectoryUnavailable(dir))).await;
+                        continue;
+                    }
+                };
                 if entry.file_type().is_file() {
-                    let p = entry.path().to_path_buf();
                     match entry.metadata() {
                         Ok(meta) => {
-                            let _ = tx
-                                .send(Ok(FileMeta::new(
-                                    MediaPath {
-                                        protocol: "local".to_string(),
-                                        path: p.to_string_lossy().to_string(),
-                                    },
-                                    MediaPath {
-                                        protocol: "local".to_string(),
-                                        path: p
-                                            .parent()
-                                            .and_then(|pp| pp.to_str())
-                                            .unwrap_or("")
-                                            .to_string(),
-                                    },
-                                    meta.len() as i64,
-                                    p.extension()
-                                        .and_then(|e| e.to_str())
-                                        .unwrap_or("")
-                                        .to_string(),
-                                    chrono::DateTime::<chrono::Utc>::from(
-                                        meta.modified().unwrap_or(std::time::SystemTime::now()),
-                                    )
-                                    .naive_utc(),
-                                    chrono::DateTime::<chrono::Utc>::from(
-                                        meta.accessed().unwrap_or(std::time::SystemTime::now()),
-                                    )
-                                    .naive_utc(),
-                                    chrono::DateTime::<chrono::Utc>::from(
-                                        meta.created().unwrap_or(std::time::SystemTime::now()),
-                                    )
-                                    .naive_utc(),
-                                    None,
-                                )))
-                                .await;
+                            let _ = tx.send(Ok(file_meta(entry.path(), &meta))).await;
                         }
                         Err(_) => {}
                     }
@@ -135,6 +119,37 @@ impl Scanner for LocalStorageClient {
     }
 }
 
+/// 本地文件的元数据，与扫描时产出的一致
+pub fn file_meta(path: &Path, meta: &fs::Metadata) -> FileMeta {
+    let time = |time: std::io::Result<std::time::SystemTime>| {
+        chrono::DateTime::<chrono::Utc>::from(time.unwrap_or(std::time::SystemTime::now()))
+            .naive_utc()
+    };
+    FileMeta::new(
+        MediaPath {
+            protocol: "local".to_string(),
+            path: path.to_string_lossy().to_string(),
+        },
+        MediaPath {
+            protocol: "local".to_string(),
+            path: path
+                .parent()
+                .and_then(|parent| parent.to_str())
+                .unwrap_or("")
+                .to_string(),
+        },
+        meta.len() as i64,
+        path.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_string(),
+        time(meta.modified()),
+        time(meta.accessed()),
+        time(meta.created()),
+        None,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,7 +163,7 @@ mod tests {
         let backend = LocalStorageClient::new();
 
         let mut receiver = backend
-            .scan(temp_dir.path().to_str().unwrap())
+            .scan(temp_dir.path().to_str().unwrap(), &ScanFilter::default())
             .await
             .unwrap();
         let mut files = Vec::new();
@@ -176,7 +191,7 @@ mod tests {
         writeln!(sub_file, "sub content").unwrap();
 
         let mut receiver = backend
-            .scan(temp_dir.path().to_str().unwrap())
+            .scan(temp_dir.path().to_str().unwrap(), &ScanFilter::default())
             .await
             .unwrap();
         let mut files = Vec::new();
@@ -206,13 +221,52 @@ mod tests {
     async fn test_scan_invalid_path() {
         let backend = LocalStorageClient::new();
 
-        let mut receiver = backend.scan("/non_existent_path_12345").await.unwrap();
+        let mut receiver = backend
+            .scan("/non_existent_path_12345", &ScanFilter::default())
+            .await
+            .unwrap();
         let mut files = Vec::new();
         while let Some(result) = receiver.recv().await {
             files.push(result);
         }
-        // 对于不存在的路径，walkdir 可能返回空结果或错误
-        // 这里只验证不会 panic
-        assert!(true);
+        // 根目录无法读取时报告目录不可用，扫描据此保留已有文件
+        assert_eq!(files.len(), 1);
+        assert!(matches!(
+            &files[0],
+            Err(ScanError::DirectoryUnavailable(dir)) if dir == "/non_existent_path_12345"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_scan_skips_excluded_and_hidden() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for dir in ["album", "album/@eaDir", ".stversions"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "album/01.flac",
+            "album/02.flac.partial",
+            "album/@eaDir/01.flac",
+            ".stversions/01.flac",
+            "album/.hidden.flac",
+        ] {
+            File::create(root.join(file)).unwrap();
+        }
+
+        let filter = ScanFilter {
+            exclude: vec!["@eaDir".to_string(), "*.partial".to_string()],
+            ..Default::default()
+        };
+        let mut receiver = LocalStorageClient::new()
+            .scan(root.to_str().unwrap(), &filter)
+            .await
+            .unwrap();
+        let mut paths = Vec::new();
+        while let Some(result) = receiver.recv().await {
+            paths.push(result.unwrap().path.path);
+        }
+        let expected = root.join("album/01.flac").to_string_lossy().to_string();
+        assert_eq!(paths, vec![expected]);
     }
 }
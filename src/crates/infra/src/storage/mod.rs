@@ -1,7 +1,10 @@
 pub mod factory;
 pub mod local;
+pub mod read_ahead;
 pub mod smb;
+pub mod trash;
 
 pub use factory::StorageClientFactoryImpl;
 pub use local::LocalStorageClient;
 pub use smb::SmbStorageClient;
+pub use trash::FsTrashStore;
@@ -0,0 +1,205 @@
+use bytes::Bytes;
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+/// 远程存储顺序读取的预读参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadAheadOptions {
+    /// 每次从存储读取的字节数
+    pub chunk_size: usize,
+    /// 读取方之前最多提前读好的块数
+    pub lookahead: usize,
+}
+
+impl Default for ReadAheadOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 256 * 1024,
+            lookahead: 4,
+        }
+    }
+}
+
+/// 所有预读的累计统计，读取方需要下一块时已经读好的算作命中
+#[derive(Debug)]
+struct ReadAheadStats {
+    chunks: AtomicU64,
+    hits: AtomicU64,
+    bytes: AtomicU64,
+}
+
+static STATS: ReadAheadStats = ReadAheadStats {
+    chunks: AtomicU64::new(0),
+    hits: AtomicU64::new(0),
+    bytes: AtomicU64::new(0),
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadAheadSnapshot {
+    pub chunks: u64,
+    pub hits: u64,
+    pub bytes: u64,
+}
+
+impl ReadAheadSnapshot {
+    pub fn hit_ratio(&self) -> f64 {
+        if self.chunks == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.chunks as f64
+        }
+    }
+}
+
+pub fn stats() -> ReadAheadSnapshot {
+    ReadAheadSnapshot {
+        chunks: STATS.chunks.load(Ordering::Relaxed),
+        hits: STATS.hits.load(Ordering::Relaxed),
+        bytes: STATS.bytes.load(Ordering::Relaxed),
+    }
+}
+
+/// 创建一对预读通道：存储一侧在阻塞线程中调用 `ChunkSender::pump`，读取方使用 `ReadAheadReader`
+pub fn channel(options: ReadAheadOptions) -> (ChunkSender, ReadAheadReader) {
+    let (tx, rx) = mpsc::channel(options.lookahead.max(1));
+    let sender = ChunkSender {
+        tx,
+        chunk_size: options.chunk_size.max(4096),
+    };
+    let reader = ReadAheadReader {
+        rx,
+        current: Bytes::new(),
+        waited: false,
+    };
+    (sender, reader)
+}
+
+pub struct ChunkSender {
+    tx: mpsc::Sender<io::Result<Bytes>>,
+    chunk_size: usize,
+}
+
+impl ChunkSender {
+    /// 按块读取 source 直到文件结束、出错或读取方关闭。会阻塞，只能在阻塞线程中调用
+    pub fn pump(&self, source: &mut dyn Read) {
+        loop {
+            let mut chunk = vec![0u8; self.chunk_size];
+            let mut filled = 0;
+            // 读满一块再发送，远程存储单次读取可能只返回一部分
+            while filled < chunk.len() {
+                match source.read(&mut chunk[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        let _ = self.tx.blocking_send(Err(e));
+                        return;
+                    }
+                }
+            }
+            if filled == 0 {
+                return;
+            }
+            chunk.truncate(filled);
+            if self.tx.blocking_send(Ok(Bytes::from(chunk))).is_err() || filled < self.chunk_size {
+                return;
+            }
+        }
+    }
+}
+
+pub struct ReadAheadReader {
+    rx: mpsc::Receiver<io::Result<Bytes>>,
+    current: Bytes,
+    /// 当前等待的块在需要时还没有读好
+    waited: bool,
+}
+
+impl AsyncRead for ReadAheadReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.current.is_empty() {
+            let chunk = match this.rx.try_recv() {
+                Ok(chunk) => Some(chunk),
+                Err(TryRecvError::Disconnected) => None,
+                Err(TryRecvError::Empty) => {
+                    this.waited = true;
+                    ready!(this.rx.poll_recv(cx))
+                }
+            };
+            match chunk {
+                // 文件结束
+                None => return Poll::Ready(Ok(())),
+                Some(Err(e)) => return Poll::Ready(Err(e)),
+                Some(Ok(chunk)) => {
+                    STATS.chunks.fetch_add(1, Ordering::Relaxed);
+                    STATS.bytes.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                    if !this.waited {
+                        STATS.hits.fetch_add(1, Ordering::Relaxed);
+                    }
+                    this.waited = false;
+                    this.current = chunk;
+                }
+            }
+        }
+        let n = buf.remaining().min(this.current.len());
+        buf.put_slice(&this.current.split_to(n));
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn reads_source_in_chunks_ahead_of_reader() {
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        let options = ReadAheadOptions {
+            chunk_size: 4096,
+            lookahead: 8,
+        };
+        let (sender, mut reader) = channel(options);
+        let source = data.clone();
+        tokio::task::spawn_blocking(move || sender.pump(&mut Cursor::new(source)))
+            .await
+            .unwrap();
+
+        let before = stats();
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).await.unwrap();
+        assert_eq!(read, data);
+        // 5 块在读取前都已读好
+        let after = stats();
+        assert!(after.chunks - before.chunks >= 5);
+        assert!(after.hits - before.hits >= 5);
+    }
+
+    #[tokio::test]
+    async fn stops_reading_when_reader_is_dropped() {
+        let (sender, reader) = channel(ReadAheadOptions {
+            chunk_size: 4096,
+            lookahead: 1,
+        });
+        drop(reader);
+        let source = vec![0u8; 1024 * 1024];
+        let mut cursor = Cursor::new(source);
+        let position = tokio::task::spawn_blocking(move || {
+            sender.pump(&mut cursor);
+            cursor.position()
+        })
+        .await
+        .unwrap();
+        assert_eq!(position, 4096);
+    }
+}
@@ -1,22 +1,41 @@
 use application::command::library::{ScanError, Scanner};
-use application::command::media_parse::StorageClient;
+use application::command::media_parse::{MediaReader, StorageClient};
 use application::error::AppError;
+use domain::library::{LibraryCredentials, ScanFilter};
 use domain::value::{FileMeta, MediaPath};
 use pavao::{SmbClient, SmbCredentials, SmbDirentType, SmbOpenOptions, SmbOptions};
 use std::collections::VecDeque;
 use std::env;
-use std::io::Write;
+use std::io::{Seek, SeekFrom, Write};
 use std::path::Path;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
+
+use super::read_ahead::{self, ReadAheadOptions};
 
 #[derive(Clone, Default)]
-pub struct SmbStorageClient;
+pub struct SmbStorageClient {
+    credentials: Option<LibraryCredentials>,
+    read_ahead: ReadAheadOptions,
+}
 
 impl SmbStorageClient {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// 使用库配置的凭据，未配置时回退到 SMB_USERNAME / SMB_PASSWORD 环境变量
+    pub fn with_credentials(credentials: LibraryCredentials) -> Self {
+        Self {
+            credentials: Some(credentials),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_read_ahead(mut self, read_ahead: ReadAheadOptions) -> Self {
+        self.read_ahead = read_ahead;
+        self
     }
 
     fn parse_smb_url(&self, url: &str) -> Result<(String, String, String), AppError> {
@@ -37,17 +56,23 @@ impl SmbStorageClient {
     }
 
     fn create_client(&self, server: &str, share: &str) -> Result<SmbClient, AppError> {
-        let username = env::var("SMB_USERNAME").unwrap_or_default();
-        let password = env::var("SMB_PASSWORD").unwrap_or_default();
-        SmbClient::new(
-            SmbCredentials::default()
-                .server(server)
-                .share(share)
-                .username(&username)
-                .password(&password),
-            SmbOptions::default(),
-        )
-        .map_err(|e| AppError::UnknownError(format!("Failed to create SMB client: {}", e)))
+        let credentials = SmbCredentials::default().server(server).share(share);
+        let credentials = match &self.credentials {
+            Some(c) => {
+                let credentials = credentials.username(&c.username).password(&c.password);
+                match &c.domain {
+                    Some(domain) => credentials.workgroup(domain),
+                    None => credentials,
+                }
+            }
+            None => {
+                let username = env::var("SMB_USERNAME").unwrap_or_default();
+                let password = env::var("SMB_PASSWORD").unwrap_or_default();
+                credentials.username(&username).password(&password)
+            }
+        };
+        SmbClient::new(credentials, SmbOptions::default())
+            .map_err(|e| AppError::UnknownError(format!("Failed to create SMB client: {}", e)))
     }
 
     fn share_path(&self, share: &str, remote_path: &str) -> String {
@@ -125,6 +150,46 @@ impl StorageClient for SmbStorageClient {
         Ok(buf)
     }
 
+    /// 在阻塞线程中按块读取，提前读好的块在播放到之前就已经在内存中，
+    /// 避免每次小块读取都等待一次网络往返
+    async fn open(&self, path: &MediaPath, offset: u64) -> Result<MediaReader, AppError> {
+        let (server, share, remote_path) = self.parse_smb_url(&path.path)?;
+        let full_path = self.share_path(&share, &remote_path);
+        let storage = self.clone();
+        let (sender, reader) = read_ahead::channel(self.read_ahead);
+        let (opened_tx, opened_rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let client = match storage.create_client(&server, &share) {
+                Ok(client) => client,
+                Err(e) => {
+                    let _ = opened_tx.send(Err(e));
+                    return;
+                }
+            };
+            let file = client
+                .open_with(&full_path, SmbOpenOptions::default().read(true))
+                .map_err(|e| AppError::UnknownError(e.to_string()))
+                .and_then(|mut file| {
+                    file.seek(SeekFrom::Start(offset))
+                        .map_err(|e| AppError::UnknownError(e.to_string()))?;
+                    Ok(file)
+                });
+            match file {
+                Ok(mut file) => {
+                    let _ = opened_tx.send(Ok(()));
+                    sender.pump(&mut file);
+                }
+                Err(e) => {
+                    let _ = opened_tx.send(Err(e));
+                }
+            }
+        });
+        opened_rx
+            .await
+            .map_err(|_| AppError::UnknownError("SMB reader stopped".to_string()))??;
+        Ok(Box::new(reader))
+    }
+
     async fn exists(&self, path: &MediaPath) -> Result<bool, AppError> {
         let path = path.path.clone();
         let (server, share, remote_path) = self.parse_smb_url(&path)?;
@@ -143,6 +208,19 @@ impl StorageClient for SmbStorageClient {
         })?;
         Ok(tmp.path().to_path_buf())
     }
+
+    fn validate_path(&self, path: &MediaPath) -> Result<(), AppError> {
+        let (server, share, _) = self
+            .parse_smb_url(&path.path)
+            .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+        if server.is_empty() || share.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "SMB path must look like smb://server/share[/path]: {}",
+                path.path
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -150,8 +228,10 @@ impl Scanner for SmbStorageClient {
     async fn scan(
         &self,
         root: &str,
+        filter: &ScanFilter,
     ) -> Result<mpsc::Receiver<Result<FileMeta, ScanError>>, ScanError> {
         let protocol = "smb".to_string();
+        let filter = filter.clone();
         let path = root.to_string();
         let (server, share, remote_path) = self
             .parse_smb_url(&path)
@@ -173,10 +253,20 @@ impl Scanner for SmbStorageClient {
                     format!("/{}/{}", share_clone, current)
                 };
 
-                let entries = match client.list_dir(&full_dir) {
+                let mut entries = match client.list_dir(&full_dir) {
                     Ok(e) => e,
-                    Err(_) => continue,
+                    Err(_) => {
+                        let dir = format!("smb://{}/{}/{}", server, share_clone, current);
+                        let _ = tx
+                            .send(Err(ScanError::DirectoryUnavailable(
+                                dir.trim_end_matches('/').to_string(),
+                            )))
+                            .await;
+                        continue;
+                    }
                 };
+                // 按名称排序，保证每次扫描顺序一致
+                entries.sort_by(|a, b| a.name().cmp(b.name()));
 
                 for entry in entries {
                     let name = entry.name();
@@ -186,9 +276,18 @@ impl Scanner for SmbStorageClient {
                         format!("{}/{}", current, name)
                     };
                     let child_full = format!("/{}/{}", share_clone, child_remote);
+                    let relative = child_remote
+                        .strip_prefix(remote_path.as_str())
+                        .unwrap_or(&child_remote)
+                        .trim_start_matches('/');
 
                     if entry.get_type() == SmbDirentType::Dir {
-                        queue.push_back(child_remote);
+                        if filter.allows_dir(relative) {
+                            queue.push_back(child_remote);
+                        }
+                        continue;
+                    }
+                    if !filter.allows_file(relative) {
                         continue;
                     }
 
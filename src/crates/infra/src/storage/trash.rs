@@ -0,0 +1,365 @@
+use crate::storage::local::file_meta;
+use application::command::trash::{TrashEntry, TrashKind, TrashStore, TrashedFile};
+use application::error::AppError;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use domain::value::{AudioFileId, FileMeta, MediaPath};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "entry.json";
+/// 恢复后等待重新入库的文件，每个文件一条 `<歌曲 ID>.json`
+const RESTORED: &str = "restored";
+
+/// 回收站目录：每个条目一个子目录 `<id>/`，包含 `entry.json` 和 `files/<序号>_<文件名>`
+pub struct FsTrashStore {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    id: i64,
+    kind: String,
+    name: String,
+    files: Vec<ManifestFile>,
+    deleted_by: String,
+    deleted_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestFile {
+    library_id: i64,
+    protocol: String,
+    path: String,
+    stored_name: String,
+    #[serde(default)]
+    audio_file_id: Option<i64>,
+}
+
+/// 恢复记录：文件移回的位置，与重新入库时的路径一致
+#[derive(Serialize, Deserialize)]
+struct RestoredFile {
+    protocol: String,
+    path: String,
+}
+
+impl Manifest {
+    fn into_entry(self) -> Option<TrashEntry> {
+        Some(TrashEntry {
+            id: self.id,
+            kind: TrashKind::parse(&self.kind)?,
+            name: self.name,
+            files: self
+                .files
+                .into_iter()
+                .map(|file| TrashedFile {
+                    library_id: file.library_id.into(),
+                    path: MediaPath {
+                        protocol: file.protocol,
+                        path: file.path,
+                    },
+                    audio_file_id: file.audio_file_id.map(AudioFileId::from),
+                })
+                .collect(),
+            deleted_by: self.deleted_by,
+            deleted_at: self.deleted_at,
+        })
+    }
+}
+
+fn io_error(e: std::io::Error) -> AppError {
+    AppError::UnknownError(e.to_string())
+}
+
+impl FsTrashStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn entry_dir(&self, id: i64) -> PathBuf {
+        self.dir.join(id.to_string())
+    }
+
+    async fn read_manifest(&self, id: i64) -> Result<Option<Manifest>, AppError> {
+        match tokio::fs::read(self.entry_dir(id).join(MANIFEST)).await {
+            Ok(data) => serde_json::from_slice(&data)
+                .map(Some)
+                .map_err(|e| AppError::UnknownError(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    fn stored_path(&self, id: i64, file: &ManifestFile) -> PathBuf {
+        self.entry_dir(id).join("files").join(&file.stored_name)
+    }
+
+    async fn write_restored(&self, audio_file_id: i64, path: &MediaPath) -> Result<(), AppError> {
+        let dir = self.dir.join(RESTORED);
+        tokio::fs::create_dir_all(&dir).await.map_err(io_error)?;
+        let data = serde_json::to_vec(&RestoredFile {
+            protocol: path.protocol.clone(),
+            path: path.path.clone(),
+        })
+        .map_err(|e| AppError::UnknownError(e.to_string()))?;
+        tokio::fs::write(dir.join(format!("{}.json", audio_file_id)), data)
+            .await
+            .map_err(io_error)
+    }
+}
+
+/// 回收站和音乐库可能不在同一个文件系统，改名失败时复制后删除
+async fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+    tokio::fs::copy(from, to).await?;
+    if let Err(e) = tokio::fs::remove_file(from).await {
+        let _ = tokio::fs::remove_file(to).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl TrashStore for FsTrashStore {
+    async fn put(&self, entry: &TrashEntry) -> Result<(), AppError> {
+        let entry_dir = self.entry_dir(entry.id);
+        tokio::fs::create_dir_all(entry_dir.join("files"))
+            .await
+            .map_err(io_error)?;
+        let manifest = Manifest {
+            id: entry.id,
+            kind: entry.kind.as_str().to_string(),
+            name: entry.name.clone(),
+            files: entry
+                .files
+                .iter()
+                .enumerate()
+                .map(|(i, file)| {
+                    let name = Path::new(&file.path.path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    ManifestFile {
+                        library_id: file.library_id.as_i64(),
+                        protocol: file.path.protocol.clone(),
+                        path: file.path.path.clone(),
+                        stored_name: format!("{}_{}", i, name),
+                        audio_file_id: file.audio_file_id.as_ref().map(AudioFileId::as_i64),
+                    }
+                })
+                .collect(),
+            deleted_by: entry.deleted_by.clone(),
+            deleted_at: entry.deleted_at,
+        };
+
+        let mut moved = Vec::new();
+        let mut result = Ok(());
+        for file in &manifest.files {
+            let stored = self.stored_path(entry.id, file);
+            match move_file(Path::new(&file.path), &stored).await {
+                Ok(()) => moved.push((stored, &file.path)),
+                Err(e) => {
+                    result = Err(AppError::UnknownError(format!("{}: {}", file.path, e)));
+                    break;
+                }
+            }
+        }
+        if result.is_ok() {
+            let data = serde_json::to_vec_pretty(&manifest)
+                .map_err(|e| AppError::UnknownError(e.to_string()))?;
+            result = tokio::fs::write(entry_dir.join(MANIFEST), data)
+                .await
+                .map_err(io_error);
+        }
+        if result.is_err() {
+            for (stored, original) in moved {
+                let _ = move_file(&stored, Path::new(original)).await;
+            }
+            let _ = tokio::fs::remove_dir_all(&entry_dir).await;
+        }
+        result
+    }
+
+    async fn list(&self) -> Result<Vec<TrashEntry>, AppError> {
+        let mut dir = match tokio::fs::read_dir(&self.dir).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(e)),
+        };
+        let mut entries = Vec::new();
+        while let Some(child) = dir.next_entry().await.map_err(io_error)? {
+            let Some(id) = child.file_name().to_str().and_then(|n| n.parse().ok()) else {
+                continue;
+            };
+            if let Some(entry) = self.read_manifest(id).await?.and_then(Manifest::into_entry) {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(entries)
+    }
+
+    async fn find(&self, id: i64) -> Result<Option<TrashEntry>, AppError> {
+        Ok(self.read_manifest(id).await?.and_then(Manifest::into_entry))
+    }
+
+    async fn restore(&self, entry: &TrashEntry) -> Result<Vec<FileMeta>, AppError> {
+        let manifest = self.read_manifest(entry.id).await?.ok_or_else(|| {
+            AppError::AggregateNotFound("TrashEntry".to_string(), entry.id.to_string())
+        })?;
+        for file in &manifest.files {
+            if tokio::fs::try_exists(&file.path).await.unwrap_or(true) {
+                return Err(AppError::InvalidInput(format!(
+                    "{} already exists",
+                    file.path
+                )));
+            }
+        }
+
+        let mut files = Vec::new();
+        for file in &manifest.files {
+            let target = PathBuf::from(&file.path);
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+            }
+            move_file(&self.stored_path(entry.id, file), &target)
+                .await
+                .map_err(|e| AppError::UnknownError(format!("{}: {}", file.path, e)))?;
+            let meta = file_meta(
+                &target,
+                &tokio::fs::metadata(&target).await.map_err(io_error)?,
+            );
+            if let Some(audio_file_id) = file.audio_file_id {
+                if let Err(e) = self.write_restored(audio_file_id, &meta.path).await {
+                    // 文件已经移回，记录写不进去只会丢失注解
+                    warn!("Failed to record restored file {}: {}", file.path, e);
+                }
+            }
+            files.push(meta);
+        }
+        self.remove(entry.id).await?;
+        Ok(files)
+    }
+
+    async fn take_restored(&self, path: &MediaPath) -> Result<Option<AudioFileId>, AppError> {
+        let mut dir = match tokio::fs::read_dir(self.dir.join(RESTORED)).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_error(e)),
+        };
+        while let Some(child) = dir.next_entry().await.map_err(io_error)? {
+            let Some(id) = child
+                .file_name()
+                .to_str()
+                .and_then(|n| n.strip_suffix(".json"))
+                .and_then(|n| n.parse::<i64>().ok())
+            else {
+                continue;
+            };
+            let Ok(data) = tokio::fs::read(child.path()).await else {
+                continue;
+            };
+            let Ok(restored) = serde_json::from_slice::<RestoredFile>(&data) else {
+                continue;
+            };
+            if restored.protocol != path.protocol || restored.path != path.path {
+                continue;
+            }
+            // 同一文件的 Created 事件可能被并发处理，删除成功的一方负责迁移
+            return match tokio::fs::remove_file(child.path()).await {
+                Ok(()) => Ok(Some(AudioFileId::from(id))),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(io_error(e)),
+            };
+        }
+        Ok(None)
+    }
+
+    async fn remove(&self, id: i64) -> Result<(), AppError> {
+        match tokio::fs::remove_dir_all(self.entry_dir(id)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn moves_files_to_trash_and_back() {
+        let dir = TempDir::new().unwrap();
+        let song = dir.path().join("music/Artist/Album/01 Song.flac");
+        std::fs::create_dir_all(song.parent().unwrap()).unwrap();
+        std::fs::write(&song, b"audio").unwrap();
+
+        let store = FsTrashStore::new(dir.path().join("trash"));
+        let entry = TrashEntry {
+            id: 7,
+            kind: TrashKind::Song,
+            name: "Song".to_string(),
+            files: vec![TrashedFile {
+                library_id: 1.into(),
+                path: MediaPath {
+                    protocol: "local".to_string(),
+                    path: song.to_string_lossy().to_string(),
+                },
+                audio_file_id: Some(AudioFileId::from(42)),
+            }],
+            deleted_by: "admin".to_string(),
+            deleted_at: chrono::Utc::now().naive_utc(),
+        };
+        store.put(&entry).await.unwrap();
+        assert!(!song.exists());
+        let listed = store.list().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].kind, TrashKind::Song);
+        assert_eq!(listed[0].files[0].path.path, song.to_string_lossy());
+
+        let files = store.restore(&entry).await.unwrap();
+        assert_eq!(std::fs::read(&song).unwrap(), b"audio");
+        assert_eq!(files[0].size, 5);
+        assert!(store.find(7).await.unwrap().is_none());
+        // 恢复记录不会被当作回收站条目列出，只能按重新入库的路径取走一次
+        assert!(store.list().await.unwrap().is_empty());
+        assert_eq!(
+            store.take_restored(&files[0].path).await.unwrap(),
+            Some(AudioFileId::from(42))
+        );
+        assert_eq!(store.take_restored(&files[0].path).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rolls_back_when_a_file_is_missing() {
+        let dir = TempDir::new().unwrap();
+        let song = dir.path().join("a.mp3");
+        std::fs::write(&song, b"audio").unwrap();
+        let file = |path: &Path| TrashedFile {
+            library_id: 1.into(),
+            path: MediaPath {
+                protocol: "local".to_string(),
+                path: path.to_string_lossy().to_string(),
+            },
+            audio_file_id: None,
+        };
+
+        let store = FsTrashStore::new(dir.path().join("trash"));
+        let entry = TrashEntry {
+            id: 8,
+            kind: TrashKind::Album,
+            name: "Album".to_string(),
+            files: vec![file(&song), file(&dir.path().join("missing.mp3"))],
+            deleted_by: "admin".to_string(),
+            deleted_at: chrono::Utc::now().naive_utc(),
+        };
+        assert!(store.put(&entry).await.is_err());
+        assert!(song.exists());
+        assert!(store.list().await.unwrap().is_empty());
+    }
+}
@@ -4,6 +4,7 @@ use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use sled::Db;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// 缓存条目（存储在 sled 中）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,8 +20,8 @@ struct CacheEntry {
 pub struct StreamCacheImpl {
     /// sled 数据库实例
     db: Db,
-    /// 缓存过期时间（秒）
-    ttl_secs: u64,
+    /// 缓存过期时间（秒），配置重载时可修改
+    ttl_secs: AtomicU64,
     /// 最大缓存文件大小（字节），超过此大小的文件不缓存
     max_cache_size: u64,
 }
@@ -36,7 +37,7 @@ impl StreamCacheImpl {
 
         Ok(Self {
             db,
-            ttl_secs,
+            ttl_secs: AtomicU64::new(ttl_secs),
             max_cache_size: 100 * 1024 * 1024, // 100MB
         })
     }
@@ -50,7 +51,12 @@ impl StreamCacheImpl {
     /// 检查缓存是否过期
     fn is_expired(&self, created_at: i64) -> bool {
         let now = chrono::Utc::now().timestamp();
-        (now - created_at) > self.ttl_secs as i64
+        (now - created_at) > self.ttl_secs.load(Ordering::Relaxed) as i64
+    }
+
+    /// 修改过期时间，对已缓存的条目同样生效
+    pub fn set_ttl_secs(&self, ttl_secs: u64) {
+        self.ttl_secs.store(ttl_secs, Ordering::Relaxed);
     }
 
     /// 从 sled 加载缓存
@@ -105,6 +111,11 @@ impl StreamCacheImpl {
         Ok(())
     }
 
+    /// 清空所有已转码的数据
+    pub fn clear(&self) -> Result<(), sled::Error> {
+        self.db.clear()
+    }
+
     /// 从 sled 删除缓存
     fn remove_from_db(&self, cache_key: &str) {
         let _ = self.db.remove(cache_key.as_bytes());
@@ -1,12 +1,14 @@
 use async_trait::async_trait;
 use bytes::Bytes;
-use domain::transcoding::{TranscodingError, TranscodingStreamer};
+use domain::transcoding::{StreamTags, TranscodingError, TranscodingStreamer};
 use futures::Stream;
 use std::collections::HashMap;
+use std::io::Write;
 use std::pin::Pin;
 use std::process::Stdio;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+use tempfile::TempPath;
 use tokio::process::{Child, Command};
 
 pub struct FfmpegStreamer {
@@ -22,13 +24,15 @@ impl FfmpegStreamer {
         }
     }
 
-    /// 构建 FFmpeg 音频转码参数
+    /// 构建 FFmpeg 音频转码参数，cover_path 为写入输出的封面文件
     fn build_audio_arguments(
         &self,
         input_path: &str,
         output_format: &str,
         bit_rate: i32,
         additional_params: &HashMap<String, String>,
+        tags: Option<&StreamTags>,
+        cover_path: Option<&str>,
     ) -> Vec<String> {
         let mut args = vec![
             "-hide_banner".to_string(),
@@ -36,7 +40,6 @@ impl FfmpegStreamer {
             "error".to_string(),
             "-i".to_string(),
             input_path.to_string(),
-            "-vn".to_string(), // 禁用视频
         ];
 
         // 根据输出格式选择编码器
@@ -50,6 +53,31 @@ impl FfmpegStreamer {
             _ => ("libmp3lame", "mp3"), // 默认 mp3
         };
 
+        // 只有 mp3 能带封面（ID3v2 APIC）
+        let cover_path = cover_path.filter(|_| container == "mp3");
+        if let Some(cover_path) = cover_path {
+            args.extend([
+                "-i".to_string(),
+                cover_path.to_string(),
+                "-map".to_string(),
+                "0:a".to_string(),
+                "-map".to_string(),
+                "1:v".to_string(),
+                "-c:v".to_string(),
+                "copy".to_string(),
+                "-disposition:v".to_string(),
+                "attached_pic".to_string(),
+                "-metadata:s:v".to_string(),
+                "comment=Cover (front)".to_string(),
+            ]);
+        } else {
+            args.push("-vn".to_string()); // 禁用视频
+        }
+
+        if let Some(tags) = tags.filter(|_| matches!(container, "mp3" | "adts")) {
+            args.extend(tag_arguments(container, tags));
+        }
+
         args.push("-c:a".to_string());
         args.push(codec.to_string());
 
@@ -90,8 +118,14 @@ impl FfmpegStreamer {
         bit_rate: i32,
         additional_params: &HashMap<String, String>,
     ) -> Result<Bytes, TranscodingError> {
-        let args =
-            self.build_audio_arguments(input_path, output_format, bit_rate, additional_params);
+        let args = self.build_audio_arguments(
+            input_path,
+            output_format,
+            bit_rate,
+            additional_params,
+            None,
+            None,
+        );
 
         log::info!(
             "[FFmpeg] Executing transcode: input={}, format={}, bitrate={}k",
@@ -139,16 +173,51 @@ impl FfmpegStreamer {
     }
 }
 
+/// 用转码后的标签替换源文件的标签
+fn tag_arguments(container: &str, tags: &StreamTags) -> Vec<String> {
+    let mut args = vec!["-map_metadata".to_string(), "-1".to_string()];
+    let number = |n: Option<i32>| n.filter(|n| *n > 0).map(|n| n.to_string());
+    let fields = [
+        ("title", Some(tags.title.clone())),
+        ("artist", Some(tags.artist.clone())),
+        ("album", Some(tags.album.clone())),
+        ("album_artist", Some(tags.album_artist.clone())),
+        ("track", number(tags.track_number)),
+        ("disc", number(tags.disc_number)),
+        ("date", number(tags.year)),
+        ("genre", tags.genre.clone()),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            args.push("-metadata".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+    }
+    match container {
+        // 部分播放器只识别 ID3v2.3
+        "mp3" => args.extend(["-id3v2_version".to_string(), "3".to_string()]),
+        "adts" => args.extend(["-write_id3v2".to_string(), "1".to_string()]),
+        _ => {}
+    }
+    args
+}
+
 /// FFmpeg 输出流
 pub struct FfmpegOutputStream {
     child: Child,
     stdout: Option<tokio::process::ChildStdout>,
     chunk_size: usize,
     buffer: Vec<u8>,
+    /// 写入输出的封面临时文件，进程结束前不能删除
+    _cover: Option<TempPath>,
 }
 
 impl FfmpegOutputStream {
-    fn new(mut child: Child, chunk_size: usize) -> Result<Self, TranscodingError> {
+    fn new(
+        mut child: Child,
+        chunk_size: usize,
+        cover: Option<TempPath>,
+    ) -> Result<Self, TranscodingError> {
         let stdout = child.stdout.take().ok_or_else(|| {
             TranscodingError::ExecutionErr("Failed to capture FFmpeg stdout".to_string())
         })?;
@@ -158,6 +227,7 @@ impl FfmpegOutputStream {
             stdout: Some(stdout),
             chunk_size,
             buffer: vec![0u8; chunk_size],
+            _cover: cover,
         })
     }
 }
@@ -211,12 +281,78 @@ impl TranscodingStreamer for FfmpegStreamer {
         output_format: String,
         bit_rate: i32,
         additional_params: HashMap<String, String>,
+        tags: Option<StreamTags>,
+    ) -> Result<
+        Box<dyn Stream<Item = Result<Vec<u8>, TranscodingError>> + Unpin + Send>,
+        TranscodingError,
+    > {
+        self.spawn_stream(
+            &input_path,
+            None,
+            &output_format,
+            bit_rate,
+            &additional_params,
+            tags,
+        )
+        .await
+    }
+
+    async fn create_stream_from_reader(
+        &self,
+        input: Box<dyn AsyncRead + Send + Unpin>,
+        output_format: String,
+        bit_rate: i32,
+        additional_params: HashMap<String, String>,
+        tags: Option<StreamTags>,
     ) -> Result<
         Box<dyn Stream<Item = Result<Vec<u8>, TranscodingError>> + Unpin + Send>,
         TranscodingError,
     > {
-        let args =
-            self.build_audio_arguments(&input_path, &output_format, bit_rate, &additional_params);
+        self.spawn_stream(
+            "pipe:0",
+            Some(input),
+            &output_format,
+            bit_rate,
+            &additional_params,
+            tags,
+        )
+        .await
+    }
+}
+
+impl FfmpegStreamer {
+    /// input 不为 None 时写入 FFmpeg 的标准输入，input_path 应为 pipe:0
+    async fn spawn_stream(
+        &self,
+        input_path: &str,
+        input: Option<Box<dyn AsyncRead + Send + Unpin>>,
+        output_format: &str,
+        bit_rate: i32,
+        additional_params: &HashMap<String, String>,
+        mut tags: Option<StreamTags>,
+    ) -> Result<
+        Box<dyn Stream<Item = Result<Vec<u8>, TranscodingError>> + Unpin + Send>,
+        TranscodingError,
+    > {
+        // FFmpeg 只能从文件读取封面，写入失败时只写标签
+        let cover = match tags.as_mut().and_then(|tags| tags.cover.take()) {
+            Some(cover) => match write_cover(cover).await {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    log::warn!("[FFmpeg] Failed to write cover for embedding: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let args = self.build_audio_arguments(
+            input_path,
+            output_format,
+            bit_rate,
+            additional_params,
+            tags.as_ref(),
+            cover.as_ref().and_then(|path| path.to_str()),
+        );
 
         log::info!(
             "[FFmpeg] Creating stream: input={}, format={}, bitrate={}k, chunk_size={}",
@@ -224,8 +360,13 @@ impl TranscodingStreamer for FfmpegStreamer {
         );
         log::debug!("[FFmpeg] Stream command: {} {}", self.ffmpeg_path, args.join(" "));
 
-        let child = Command::new(&self.ffmpeg_path)
+        let mut child = Command::new(&self.ffmpeg_path)
             .args(&args)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
@@ -236,7 +377,83 @@ impl TranscodingStreamer for FfmpegStreamer {
 
         log::debug!("[FFmpeg] Process started, pid={:?}", child.id());
 
-        let stream = FfmpegOutputStream::new(child, self.chunk_size)?;
+        if let (Some(mut input), Some(mut stdin)) = (input, child.stdin.take()) {
+            tokio::spawn(async move {
+                // 客户端断开时 FFmpeg 被终止，写入失败属于正常情况
+                if let Err(e) = tokio::io::copy(&mut input, &mut stdin).await {
+                    log::debug!("[FFmpeg] Stopped feeding input: {}", e);
+                }
+            });
+        }
+
+        let stream = FfmpegOutputStream::new(child, self.chunk_size, cover)?;
         Ok(Box::new(stream))
     }
 }
+
+async fn write_cover(cover: Vec<u8>) -> std::io::Result<TempPath> {
+    tokio::task::spawn_blocking(move || {
+        let mut file = tempfile::Builder::new()
+            .prefix("rhythm-cover-")
+            .tempfile()?;
+        file.write_all(&cover)?;
+        Ok(file.into_temp_path())
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeds_tags_and_cover_only_for_mp3() {
+        let streamer = FfmpegStreamer::new("ffmpeg".to_string(), 4096);
+        let tags = StreamTags {
+            title: "Airbag".to_string(),
+            artist: "Radiohead".to_string(),
+            album: "OK Computer".to_string(),
+            track_number: Some(1),
+            year: Some(0),
+            ..Default::default()
+        };
+        let params = HashMap::new();
+
+        let args = streamer.build_audio_arguments(
+            "in.flac",
+            "mp3",
+            192,
+            &params,
+            Some(&tags),
+            Some("cover.jpg"),
+        );
+        let joined = args.join(" ");
+        assert!(joined.contains("-i in.flac -i cover.jpg -map 0:a -map 1:v"));
+        assert!(joined.contains("-metadata title=Airbag -metadata artist=Radiohead"));
+        assert!(joined.contains("-metadata track=1"));
+        assert!(!joined.contains("date="));
+        assert!(!args.contains(&"-vn".to_string()));
+
+        // aac 只写标签，opus 都不写
+        let args = streamer.build_audio_arguments(
+            "in.flac",
+            "aac",
+            192,
+            &params,
+            Some(&tags),
+            Some("cover.jpg"),
+        );
+        assert!(!args.contains(&"cover.jpg".to_string()));
+        assert!(args.contains(&"-write_id3v2".to_string()));
+        let args = streamer.build_audio_arguments(
+            "in.flac",
+            "opus",
+            192,
+            &params,
+            Some(&tags),
+            None,
+        );
+        assert!(!args.contains(&"-map_metadata".to_string()));
+    }
+}
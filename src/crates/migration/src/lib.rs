@@ -11,6 +11,51 @@ mod m20220101_000008_create_player_domain;
 mod m20250202_000001_create_playlist_domain;
 mod m20250203_000001_create_play_queue_domain;
 mod m20250204_000001_create_transcoding_domain;
+mod m20250301_000001_add_disc_subtitles;
+mod m20250302_000001_create_metadata_change_log;
+mod m20250303_000001_create_genre_alias;
+mod m20250304_000001_create_star_stats;
+mod m20250305_000001_create_play_count_rollup;
+mod m20250306_000001_create_listening_clock;
+mod m20250307_000001_create_scan_error;
+mod m20250308_000001_add_user_settings;
+mod m20250309_000001_add_library_settings;
+mod m20250310_000001_create_library_credentials;
+mod m20250311_000001_create_event_dead_letter;
+mod m20250312_000001_create_event_outbox;
+mod m20250313_000001_create_processed_event;
+mod m20250314_000001_create_scan_checkpoint;
+mod m20250315_000001_add_cover_art_thumbnail;
+mod m20250316_000001_add_cover_art_placeholder;
+mod m20250317_000001_add_playlist_cover;
+mod m20250318_000001_add_playlist_collaborator;
+mod m20250319_000001_add_play_queue_client;
+mod m20250320_000001_add_play_queue_current_index;
+mod m20250321_000001_add_audio_file_missing_at;
+mod m20250322_000001_create_user_bandwidth;
+mod m20250323_000001_add_audio_file_tags;
+mod m20250324_000001_add_album_genre_index;
+mod m20250325_000001_create_scrobble_submission;
+mod m20250326_000001_add_album_edition;
+mod m20250327_000001_create_artist_alias;
+mod m20250328_000001_add_audio_file_work;
+mod m20250329_000001_create_bookmark;
+mod m20250330_000001_add_library_kind;
+mod m20250331_000001_add_library_scan_filter;
+mod m20250401_000001_create_media_asset;
+mod m20250402_000001_add_audio_file_chapters;
+mod m20250403_000001_create_artist_discography;
+mod m20250404_000001_add_storage_availability;
+mod m20250405_000001_add_audio_file_genre_index;
+mod m20250406_000001_create_format_stats;
+mod m20250407_000001_create_listening_streak;
+mod m20250408_000001_enable_player_scrobble;
+mod m20250409_000001_add_explicit_content;
+mod m20250410_000001_create_user_note;
+mod m20250411_000001_create_playlist_tag;
+mod m20250412_000001_add_playlist_deleted_at;
+mod m20250413_000001_create_app_password;
+mod m20250414_000001_add_audio_file_audible_range;
 
 pub struct Migrator;
 
@@ -29,6 +74,51 @@ impl MigratorTrait for Migrator {
             Box::new(m20250202_000001_create_playlist_domain::Migration),
             Box::new(m20250203_000001_create_play_queue_domain::Migration),
             Box::new(m20250204_000001_create_transcoding_domain::Migration),
+            Box::new(m20250301_000001_add_disc_subtitles::Migration),
+            Box::new(m20250302_000001_create_metadata_change_log::Migration),
+            Box::new(m20250303_000001_create_genre_alias::Migration),
+            Box::new(m20250304_000001_create_star_stats::Migration),
+            Box::new(m20250305_000001_create_play_count_rollup::Migration),
+            Box::new(m20250306_000001_create_listening_clock::Migration),
+            Box::new(m20250307_000001_create_scan_error::Migration),
+            Box::new(m20250308_000001_add_user_settings::Migration),
+            Box::new(m20250309_000001_add_library_settings::Migration),
+            Box::new(m20250310_000001_create_library_credentials::Migration),
+            Box::new(m20250311_000001_create_event_dead_letter::Migration),
+            Box::new(m20250312_000001_create_event_outbox::Migration),
+            Box::new(m20250313_000001_create_processed_event::Migration),
+            Box::new(m20250314_000001_create_scan_checkpoint::Migration),
+            Box::new(m20250315_000001_add_cover_art_thumbnail::Migration),
+            Box::new(m20250316_000001_add_cover_art_placeholder::Migration),
+            Box::new(m20250317_000001_add_playlist_cover::Migration),
+            Box::new(m20250318_000001_add_playlist_collaborator::Migration),
+            Box::new(m20250319_000001_add_play_queue_client::Migration),
+            Box::new(m20250320_000001_add_play_queue_current_index::Migration),
+            Box::new(m20250321_000001_add_audio_file_missing_at::Migration),
+            Box::new(m20250322_000001_create_user_bandwidth::Migration),
+            Box::new(m20250323_000001_add_audio_file_tags::Migration),
+            Box::new(m20250324_000001_add_album_genre_index::Migration),
+            Box::new(m20250325_000001_create_scrobble_submission::Migration),
+            Box::new(m20250326_000001_add_album_edition::Migration),
+            Box::new(m20250327_000001_create_artist_alias::Migration),
+            Box::new(m20250328_000001_add_audio_file_work::Migration),
+            Box::new(m20250329_000001_create_bookmark::Migration),
+            Box::new(m20250330_000001_add_library_kind::Migration),
+            Box::new(m20250331_000001_add_library_scan_filter::Migration),
+            Box::new(m20250401_000001_create_media_asset::Migration),
+            Box::new(m20250402_000001_add_audio_file_chapters::Migration),
+            Box::new(m20250403_000001_create_artist_discography::Migration),
+            Box::new(m20250404_000001_add_storage_availability::Migration),
+            Box::new(m20250405_000001_add_audio_file_genre_index::Migration),
+            Box::new(m20250406_000001_create_format_stats::Migration),
+            Box::new(m20250407_000001_create_listening_streak::Migration),
+            Box::new(m20250408_000001_enable_player_scrobble::Migration),
+            Box::new(m20250409_000001_add_explicit_content::Migration),
+            Box::new(m20250410_000001_create_user_note::Migration),
+            Box::new(m20250411_000001_create_playlist_tag::Migration),
+            Box::new(m20250412_000001_add_playlist_deleted_at::Migration),
+            Box::new(m20250413_000001_create_app_password::Migration),
+            Box::new(m20250414_000001_add_audio_file_audible_range::Migration),
         ]
     }
 }
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 音频文件的碟片副标题（TSST / DISCSUBTITLE）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .add_column_if_not_exists(ColumnDef::new(AudioFile::DiscSubtitle).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 专辑按碟片编号聚合的副标题 {"1": "Studio", "2": "Live"}
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AlbumStats::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AlbumStats::DiscTitles)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AlbumStats::Table)
+                    .drop_column(AlbumStats::DiscTitles)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .drop_column(AudioFile::DiscSubtitle)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AudioFile {
+    Table,
+    DiscSubtitle,
+}
+
+#[derive(DeriveIden)]
+enum AlbumStats {
+    Table,
+    DiscTitles,
+}
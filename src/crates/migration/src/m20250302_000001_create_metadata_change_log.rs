@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 元数据修改审计日志
+        manager
+            .create_table(
+                Table::create()
+                    .table(MetadataChangeLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MetadataChangeLog::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MetadataChangeLog::TargetType).string().not_null())
+                    .col(
+                        ColumnDef::new(MetadataChangeLog::TargetId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MetadataChangeLog::Field).string().not_null())
+                    .col(ColumnDef::new(MetadataChangeLog::OldValue).text().null())
+                    .col(ColumnDef::new(MetadataChangeLog::NewValue).text().null())
+                    .col(ColumnDef::new(MetadataChangeLog::ChangedBy).string().not_null())
+                    .col(
+                        ColumnDef::new(MetadataChangeLog::TagsWritten)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(MetadataChangeLog::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_metadata_change_log_target")
+                    .table(MetadataChangeLog::Table)
+                    .col(MetadataChangeLog::TargetType)
+                    .col(MetadataChangeLog::TargetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MetadataChangeLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MetadataChangeLog {
+    Table,
+    Id,
+    TargetType,
+    TargetId,
+    Field,
+    OldValue,
+    NewValue,
+    ChangedBy,
+    TagsWritten,
+    CreatedAt,
+}
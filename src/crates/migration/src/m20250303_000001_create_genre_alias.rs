@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 流派别名：小写别名 -> 规范流派名
+        manager
+            .create_table(
+                Table::create()
+                    .table(GenreAlias::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GenreAlias::Alias)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(GenreAlias::GenreName).string().not_null())
+                    .col(
+                        ColumnDef::new(GenreAlias::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 流派层级：可选的父流派
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Genre::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Genre::ParentId).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Genre::Table)
+                    .drop_column(Genre::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(GenreAlias::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GenreAlias {
+    Table,
+    Alias,
+    GenreName,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Genre {
+    Table,
+    ParentId,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 各条目被多少用户收藏（由注解事件投影）
+        manager
+            .create_table(
+                Table::create()
+                    .table(StarStats::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(StarStats::ItemKind).string().not_null())
+                    .col(ColumnDef::new(StarStats::ItemId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(StarStats::StarCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(StarStats::ItemKind)
+                            .col(StarStats::ItemId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 注解按用户 + 条目查找
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_annotation_user_item")
+                    .table(Annotation::Table)
+                    .col(Annotation::UserId)
+                    .col(Annotation::ItemKind)
+                    .col(Annotation::ItemId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_annotation_user_item")
+                    .table(Annotation::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(StarStats::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StarStats {
+    Table,
+    ItemKind,
+    ItemId,
+    StarCount,
+}
+
+#[derive(DeriveIden)]
+enum Annotation {
+    Table,
+    UserId,
+    ItemKind,
+    ItemId,
+}
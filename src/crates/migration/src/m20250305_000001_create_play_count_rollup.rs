@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 按日/周/月汇总的播放次数（由 scrobble 事件投影）
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlayCountRollup::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PlayCountRollup::Period).string().not_null())
+                    .col(
+                        ColumnDef::new(PlayCountRollup::PeriodStart)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PlayCountRollup::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PlayCountRollup::ItemKind)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PlayCountRollup::ItemId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PlayCountRollup::PlayCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(PlayCountRollup::Period)
+                            .col(PlayCountRollup::PeriodStart)
+                            .col(PlayCountRollup::UserId)
+                            .col(PlayCountRollup::ItemKind)
+                            .col(PlayCountRollup::ItemId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 全服排行榜按粒度 + 类型 + 时间范围查询
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_play_count_rollup_kind_period")
+                    .table(PlayCountRollup::Table)
+                    .col(PlayCountRollup::Period)
+                    .col(PlayCountRollup::ItemKind)
+                    .col(PlayCountRollup::PeriodStart)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PlayCountRollup::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlayCountRollup {
+    Table,
+    Period,
+    PeriodStart,
+    UserId,
+    ItemKind,
+    ItemId,
+    PlayCount,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 每个用户每年按星期 + 小时汇总的收听次数和时长（年度报告使用）
+        manager
+            .create_table(
+                Table::create()
+                    .table(ListeningClock::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ListeningClock::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ListeningClock::Year).integer().not_null())
+                    .col(
+                        ColumnDef::new(ListeningClock::Weekday)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ListeningClock::Hour)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ListeningClock::PlayCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ListeningClock::PlaySeconds)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ListeningClock::UserId)
+                            .col(ListeningClock::Year)
+                            .col(ListeningClock::Weekday)
+                            .col(ListeningClock::Hour),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ListeningClock::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ListeningClock {
+    Table,
+    UserId,
+    Year,
+    Weekday,
+    Hour,
+    PlayCount,
+    PlaySeconds,
+}
@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 元数据解析失败的文件，文件未变化时后续扫描跳过
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScanError::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScanError::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ScanError::LibraryId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ScanError::PathProtocol).string().not_null())
+                    .col(ColumnDef::new(ScanError::PathPath).string().not_null())
+                    .col(ColumnDef::new(ScanError::Suffix).string().not_null())
+                    .col(ColumnDef::new(ScanError::Size).big_integer().not_null())
+                    .col(ColumnDef::new(ScanError::Mtime).timestamp().not_null())
+                    .col(ColumnDef::new(ScanError::Error).text().not_null())
+                    .col(
+                        ColumnDef::new(ScanError::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(ScanError::FailedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_scan_error_path")
+                    .table(ScanError::Table)
+                    .col(ScanError::PathProtocol)
+                    .col(ScanError::PathPath)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScanError::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScanError {
+    Table,
+    Id,
+    LibraryId,
+    PathProtocol,
+    PathPath,
+    Suffix,
+    Size,
+    Mtime,
+    Error,
+    Attempts,
+    FailedAt,
+}
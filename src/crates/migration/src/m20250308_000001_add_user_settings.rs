@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 默认角色：settings | stream，与 UserRoles::default 一致
+const DEFAULT_ROLES: i32 = 0b11;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户角色（按位存储）、比特率上限和下载配额
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(User::Roles)
+                            .integer()
+                            .not_null()
+                            .default(DEFAULT_ROLES),
+                    )
+                    .add_column_if_not_exists(ColumnDef::new(User::MaxBitRate).integer().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(User::DownloadQuotaMb).big_integer().null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 用户偏好设置
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserPreference::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserPreference::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserPreference::Key).string().not_null())
+                    .col(ColumnDef::new(UserPreference::Value).text().not_null())
+                    .col(
+                        ColumnDef::new(UserPreference::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(UserPreference::UserId)
+                            .col(UserPreference::Key),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserPreference::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::Roles)
+                    .drop_column(User::MaxBitRate)
+                    .drop_column(User::DownloadQuotaMb)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Roles,
+    MaxBitRate,
+    DownloadQuotaMb,
+}
+
+#[derive(DeriveIden)]
+enum UserPreference {
+    Table,
+    UserId,
+    Key,
+    Value,
+    UpdatedAt,
+}
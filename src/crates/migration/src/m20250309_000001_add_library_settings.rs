@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 库的启用状态和定时扫描间隔
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::ScanIntervalMinutes)
+                            .integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .drop_column(Library::Enabled)
+                    .drop_column(Library::ScanIntervalMinutes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Library {
+    Table,
+    Enabled,
+    ScanIntervalMinutes,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 远程存储凭据，密码使用 AES-256-GCM 加密保存
+        manager
+            .create_table(
+                Table::create()
+                    .table(LibraryCredentials::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LibraryCredentials::LibraryId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LibraryCredentials::Username)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LibraryCredentials::EncryptedPassword)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LibraryCredentials::Domain).string().null())
+                    .col(
+                        ColumnDef::new(LibraryCredentials::UpdatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LibraryCredentials::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LibraryCredentials {
+    Table,
+    LibraryId,
+    Username,
+    EncryptedPassword,
+    Domain,
+    UpdatedAt,
+}
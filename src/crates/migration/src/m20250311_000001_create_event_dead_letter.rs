@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 重试耗尽后仍处理失败的事件
+        manager
+            .create_table(
+                Table::create()
+                    .table(EventDeadLetter::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EventDeadLetter::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(EventDeadLetter::EventId).string().not_null())
+                    .col(
+                        ColumnDef::new(EventDeadLetter::CorrelationId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EventDeadLetter::EventType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EventDeadLetter::Handler).string().not_null())
+                    .col(
+                        ColumnDef::new(EventDeadLetter::AggregateId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EventDeadLetter::Version)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EventDeadLetter::Error).text().not_null())
+                    .col(
+                        ColumnDef::new(EventDeadLetter::Attempts)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EventDeadLetter::OccurredAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EventDeadLetter::FailedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EventDeadLetter::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EventDeadLetter {
+    Table,
+    Id,
+    EventId,
+    CorrelationId,
+    EventType,
+    Handler,
+    AggregateId,
+    Version,
+    Error,
+    Attempts,
+    OccurredAt,
+    FailedAt,
+}
@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 与聚合同事务写入的事件，由 relay 发布到事件总线
+        manager
+            .create_table(
+                Table::create()
+                    .table(EventOutbox::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EventOutbox::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(EventOutbox::EventType).string().not_null())
+                    .col(
+                        ColumnDef::new(EventOutbox::AggregateId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EventOutbox::Version)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EventOutbox::Payload).text().not_null())
+                    .col(
+                        ColumnDef::new(EventOutbox::CorrelationId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EventOutbox::CausationId).string().not_null())
+                    .col(
+                        ColumnDef::new(EventOutbox::OccurredAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EventOutbox::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(EventOutbox::LastError).text().null())
+                    .col(ColumnDef::new(EventOutbox::DeliveredAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_event_outbox_pending")
+                    .table(EventOutbox::Table)
+                    .col(EventOutbox::DeliveredAt)
+                    .col(EventOutbox::OccurredAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EventOutbox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EventOutbox {
+    Table,
+    Id,
+    EventType,
+    AggregateId,
+    Version,
+    Payload,
+    CorrelationId,
+    CausationId,
+    OccurredAt,
+    Attempts,
+    LastError,
+    DeliveredAt,
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 投影 handler 已处理过的事件，重试和重放时用于去重
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProcessedEvent::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ProcessedEvent::Handler).string().not_null())
+                    .col(ColumnDef::new(ProcessedEvent::EventId).string().not_null())
+                    .col(
+                        ColumnDef::new(ProcessedEvent::ProcessedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ProcessedEvent::Handler)
+                            .col(ProcessedEvent::EventId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_processed_event_processed_at")
+                    .table(ProcessedEvent::Table)
+                    .col(ProcessedEvent::ProcessedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProcessedEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProcessedEvent {
+    Table,
+    Handler,
+    EventId,
+    ProcessedAt,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 每个库最近一次扫描的进度，服务重启后从这里继续
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScanCheckpoint::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScanCheckpoint::LibraryId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ScanCheckpoint::LastDir).text().not_null())
+                    .col(ColumnDef::new(ScanCheckpoint::LastPath).text().not_null())
+                    .col(
+                        ColumnDef::new(ScanCheckpoint::ScannedFiles)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ScanCheckpoint::UpdatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScanCheckpoint::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScanCheckpoint {
+    Table,
+    LibraryId,
+    LastDir,
+    LastPath,
+    ScannedFiles,
+    UpdatedAt,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 扫描时生成的嵌入封面缩略图
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CoverArt::Table)
+                    .add_column_if_not_exists(ColumnDef::new(CoverArt::ThumbnailPath).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CoverArt::Table)
+                    .drop_column(CoverArt::ThumbnailPath)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CoverArt {
+    Table,
+    ThumbnailPath,
+}
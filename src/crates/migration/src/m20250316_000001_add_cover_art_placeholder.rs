@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 封面加载前的占位：BlurHash 和主色调
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CoverArt::Table)
+                    .add_column_if_not_exists(ColumnDef::new(CoverArt::Blurhash).string().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(CoverArt::DominantColor).string_len(7).null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CoverArt::Table)
+                    .drop_column(CoverArt::Blurhash)
+                    .drop_column(CoverArt::DominantColor)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CoverArt {
+    Table,
+    Blurhash,
+    DominantColor,
+}
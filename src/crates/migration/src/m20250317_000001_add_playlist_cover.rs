@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户上传的播放列表封面
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Playlist::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Playlist::CoverPath).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Playlist::Table)
+                    .drop_column(Playlist::CoverPath)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Playlist {
+    Table,
+    CoverPath,
+}
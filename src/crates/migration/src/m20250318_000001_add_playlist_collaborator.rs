@@ -0,0 +1,121 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 公开播放列表的协作者，可以增删、排序条目
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlaylistCollaborator::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PlaylistCollaborator::PlaylistId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PlaylistCollaborator::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PlaylistCollaborator::AddedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(PlaylistCollaborator::PlaylistId)
+                            .col(PlaylistCollaborator::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_playlist_collaborator_playlist_id")
+                            .from(
+                                PlaylistCollaborator::Table,
+                                PlaylistCollaborator::PlaylistId,
+                            )
+                            .to(Playlist::Table, Playlist::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_playlist_collaborator_user_id")
+                            .from(PlaylistCollaborator::Table, PlaylistCollaborator::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_playlist_collaborator_user_id")
+                    .table(PlaylistCollaborator::Table)
+                    .col(PlaylistCollaborator::UserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 条目添加者，旧数据为空
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlaylistEntry::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(PlaylistEntry::AddedBy).big_integer().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlaylistEntry::Table)
+                    .drop_column(PlaylistEntry::AddedBy)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PlaylistCollaborator::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlaylistCollaborator {
+    Table,
+    PlaylistId,
+    UserId,
+    AddedAt,
+}
+
+#[derive(DeriveIden)]
+enum PlaylistEntry {
+    Table,
+    AddedBy,
+}
+
+#[derive(DeriveIden)]
+enum Playlist {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
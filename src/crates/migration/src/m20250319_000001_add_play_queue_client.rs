@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 按设备保存的队列以客户端唯一 ID 区分，共享队列为空字符串
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlayQueue::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(PlayQueue::ClientId)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_play_queue_user_id_unique")
+                    .table(PlayQueue::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_play_queue_user_client_unique")
+                    .table(PlayQueue::Table)
+                    .col(PlayQueue::UserId)
+                    .col(PlayQueue::ClientId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 只保留每个用户的共享队列，否则无法恢复唯一索引
+        manager
+            .get_connection()
+            .execute_unprepared("DELETE FROM play_queue WHERE client_id <> ''")
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_play_queue_user_client_unique")
+                    .table(PlayQueue::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_play_queue_user_id_unique")
+                    .table(PlayQueue::Table)
+                    .col(PlayQueue::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlayQueue::Table)
+                    .drop_column(PlayQueue::ClientId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlayQueue {
+    Table,
+    UserId,
+    ClientId,
+}
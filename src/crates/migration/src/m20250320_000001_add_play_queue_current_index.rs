@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 队列中有重复歌曲时 current_id 无法确定位置，同时保存索引
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlayQueue::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(PlayQueue::CurrentIndex).integer().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlayQueue::Table)
+                    .drop_column(PlayQueue::CurrentIndex)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlayQueue {
+    Table,
+    CurrentIndex,
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 文件检查发现文件已不存在时记录时间，列表中不再显示，文件恢复后清空
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::MissingAt).timestamp().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .drop_column(AudioFile::MissingAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AudioFile {
+    Table,
+    MissingAt,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 每个用户每天通过 stream 发送的字节数
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserBandwidth::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserBandwidth::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserBandwidth::Day).date().not_null())
+                    .col(
+                        ColumnDef::new(UserBandwidth::Bytes)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(UserBandwidth::Streams)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(UserBandwidth::UserId)
+                            .col(UserBandwidth::Day),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserBandwidth::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserBandwidth {
+    Table,
+    UserId,
+    Day,
+    Bytes,
+    Streams,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 注释（COMM）、MusicBrainz 录音 ID 和 ReplayGain 标签，供 OpenSubsonic 歌曲字段使用
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .add_column_if_not_exists(ColumnDef::new(AudioFile::Comment).text().null())
+                    .add_column_if_not_exists(ColumnDef::new(AudioFile::MbzTrackId).string().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::RgTrackGain).double().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::RgTrackPeak).double().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::RgAlbumGain).double().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::RgAlbumPeak).double().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .drop_column(AudioFile::Comment)
+                    .drop_column(AudioFile::MbzTrackId)
+                    .drop_column(AudioFile::RgTrackGain)
+                    .drop_column(AudioFile::RgTrackPeak)
+                    .drop_column(AudioFile::RgAlbumGain)
+                    .drop_column(AudioFile::RgAlbumPeak)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AudioFile {
+    Table,
+    Comment,
+    MbzTrackId,
+    RgTrackGain,
+    RgTrackPeak,
+    RgAlbumGain,
+    RgAlbumPeak,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 带时间提交的 scrobble，客户端重发离线队列时用于去重
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScrobbleSubmission::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScrobbleSubmission::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScrobbleSubmission::AudioFileId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScrobbleSubmission::PlayedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ScrobbleSubmission::UserId)
+                            .col(ScrobbleSubmission::AudioFileId)
+                            .col(ScrobbleSubmission::PlayedAt),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScrobbleSubmission::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScrobbleSubmission {
+    Table,
+    UserId,
+    AudioFileId,
+    PlayedAt,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 专辑名中的版本标注（Deluxe、2019 Remaster 等），扫描时由元数据规则提取
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Album::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Album::Edition).string().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Album::Table)
+                    .drop_column(Album::Edition)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Album {
+    Table,
+    Edition,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 艺术家别名：规范化后的别名 -> 艺术家
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArtistAlias::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArtistAlias::SortName)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ArtistAlias::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(ArtistAlias::ArtistId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArtistAlias::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_artist_alias_artist_id")
+                    .table(ArtistAlias::Table)
+                    .col(ArtistAlias::ArtistId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ArtistAlias::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ArtistAlias {
+    Table,
+    SortName,
+    Name,
+    ArtistId,
+    CreatedAt,
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 古典音乐的作品与乐章标签，用于把专辑中的音轨按作品分组
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .add_column_if_not_exists(ColumnDef::new(AudioFile::Work).string().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::MovementName).string().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::MovementNumber).integer().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::MovementCount).integer().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .drop_column(AudioFile::Work)
+                    .drop_column(AudioFile::MovementName)
+                    .drop_column(AudioFile::MovementNumber)
+                    .drop_column(AudioFile::MovementCount)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AudioFile {
+    Table,
+    Work,
+    MovementName,
+    MovementNumber,
+    MovementCount,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 书签：每个用户每首歌一个播放位置
+        manager
+            .create_table(
+                Table::create()
+                    .table(Bookmark::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Bookmark::UserId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(Bookmark::AudioFileId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Bookmark::Position)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Bookmark::Comment)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(Bookmark::ChangedBy)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(Bookmark::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Bookmark::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(Bookmark::UserId)
+                            .col(Bookmark::AudioFileId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Bookmark::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Bookmark {
+    Table,
+    UserId,
+    AudioFileId,
+    Position,
+    Comment,
+    ChangedBy,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 库的内容类型：music / audiobook
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::Kind)
+                            .string()
+                            .not_null()
+                            .default("music"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .drop_column(Library::Kind)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Library {
+    Table,
+    Kind,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 扫描时的包含/排除模式（JSON 字符串数组）以及是否扫描隐藏文件
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::IncludePatterns)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb")),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::ExcludePatterns)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb")),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::ScanHidden)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .drop_column(Library::IncludePatterns)
+                    .drop_column(Library::ExcludePatterns)
+                    .drop_column(Library::ScanHidden)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Library {
+    Table,
+    IncludePatterns,
+    ExcludePatterns,
+    ScanHidden,
+}
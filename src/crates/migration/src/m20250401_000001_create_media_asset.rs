@@ -0,0 +1,90 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 扫描时收录的非音频资源：PDF 小册子、artist.jpg、logo.png 等
+        manager
+            .create_table(
+                Table::create()
+                    .table(MediaAsset::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MediaAsset::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MediaAsset::LibraryId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MediaAsset::PathProtocol).string().not_null())
+                    .col(ColumnDef::new(MediaAsset::PathPath).string().not_null())
+                    .col(ColumnDef::new(MediaAsset::DirPath).string().not_null())
+                    .col(ColumnDef::new(MediaAsset::Kind).string().not_null())
+                    .col(ColumnDef::new(MediaAsset::Size).big_integer().not_null())
+                    .col(ColumnDef::new(MediaAsset::Mtime).timestamp().not_null())
+                    .col(
+                        ColumnDef::new(MediaAsset::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_media_asset_path")
+                    .table(MediaAsset::Table)
+                    .col(MediaAsset::PathProtocol)
+                    .col(MediaAsset::PathPath)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按专辑目录查找资源
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_media_asset_dir")
+                    .table(MediaAsset::Table)
+                    .col(MediaAsset::PathProtocol)
+                    .col(MediaAsset::DirPath)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MediaAsset::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MediaAsset {
+    Table,
+    Id,
+    LibraryId,
+    PathProtocol,
+    PathPath,
+    DirPath,
+    Kind,
+    Size,
+    Mtime,
+    CreatedAt,
+}
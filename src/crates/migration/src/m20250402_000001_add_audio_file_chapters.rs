@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 长文件的章节标记：[{"title": "...", "start": 0, "end": 60000}]，位置单位为毫秒
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::Chapters)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb")),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .drop_column(AudioFile::Chapters)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AudioFile {
+    Table,
+    Chapters,
+}
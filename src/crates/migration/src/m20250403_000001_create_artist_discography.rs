@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 从 MusicBrainz 获取的艺术家发行组列表，用于计算缺失的专辑
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArtistDiscography::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArtistDiscography::ArtistId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // 未在 MusicBrainz 找到艺术家时为空，同样缓存以免重复查询
+                    .col(ColumnDef::new(ArtistDiscography::MbzArtistId).string())
+                    .col(
+                        ColumnDef::new(ArtistDiscography::ReleaseGroups)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb")),
+                    )
+                    .col(
+                        ColumnDef::new(ArtistDiscography::FetchedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ArtistDiscography::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ArtistDiscography {
+    Table,
+    ArtistId,
+    MbzArtistId,
+    ReleaseGroups,
+    FetchedAt,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 存储（如 SMB 共享）无法访问的时间，恢复后清空；扫描不会因此删除其中的文件
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::UnavailableSince).timestamp().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Library::HideUnavailable)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::UnavailableAt).timestamp().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .drop_column(AudioFile::UnavailableAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .drop_column(Library::UnavailableSince)
+                    .drop_column(Library::HideUnavailable)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Library {
+    Table,
+    UnavailableSince,
+    HideUnavailable,
+}
+
+#[derive(DeriveIden)]
+enum AudioFile {
+    Table,
+    UnavailableAt,
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 按扩展名、比特率分段和采样率汇总的歌曲数和大小，由音频文件事件增量维护
+        manager
+            .create_table(
+                Table::create()
+                    .table(FormatStats::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(FormatStats::Suffix).string().not_null())
+                    // 分段下限（kbps），与 model::format_stats::BIT_RATE_BUCKETS 一致
+                    .col(
+                        ColumnDef::new(FormatStats::BitRateBucket)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(FormatStats::SampleRate).integer().not_null())
+                    .col(
+                        ColumnDef::new(FormatStats::SongCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(FormatStats::TotalSize)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(FormatStats::Suffix)
+                            .col(FormatStats::BitRateBucket)
+                            .col(FormatStats::SampleRate),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有的音频文件不会再产生创建事件，从 audio_file 回填
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"INSERT INTO format_stats (suffix, bit_rate_bucket, sample_rate, song_count, total_size)
+                   SELECT LOWER(suffix),
+                          CASE
+                            WHEN bit_rate >= 1000 THEN 1000
+                            WHEN bit_rate >= 500 THEN 500
+                            WHEN bit_rate >= 320 THEN 320
+                            WHEN bit_rate >= 256 THEN 256
+                            WHEN bit_rate >= 192 THEN 192
+                            WHEN bit_rate >= 128 THEN 128
+                            ELSE 0
+                          END,
+                          sample_rate, COUNT(*), COALESCE(SUM(size), 0)
+                   FROM audio_file
+                   GROUP BY 1, 2, 3
+                   ON CONFLICT DO NOTHING"#,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FormatStats::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FormatStats {
+    Table,
+    Suffix,
+    BitRateBucket,
+    SampleRate,
+    SongCount,
+    TotalSize,
+}
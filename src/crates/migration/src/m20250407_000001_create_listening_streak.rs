@@ -0,0 +1,135 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 每个用户的连续收听天数和总播放次数，由 scrobble 事件维护
+        manager
+            .create_table(
+                Table::create()
+                    .table(ListeningStreak::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ListeningStreak::UserId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ListeningStreak::CurrentStreak)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ListeningStreak::LongestStreak)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ListeningStreak::LastPlayedOn).date())
+                    .col(
+                        ColumnDef::new(ListeningStreak::TotalPlays)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已达成的里程碑，同一用户、类型和值只有一条
+        manager
+            .create_table(
+                Table::create()
+                    .table(ListeningMilestone::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ListeningMilestone::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ListeningMilestone::Kind).string().not_null())
+                    .col(
+                        ColumnDef::new(ListeningMilestone::Value)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ListeningMilestone::AchievedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ListeningMilestone::UserId)
+                            .col(ListeningMilestone::Kind)
+                            .col(ListeningMilestone::Value),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 从已有的播放次数回填总次数和已经达成的里程碑，避免升级后重复提示。
+        // 连续天数无法从注解还原，从升级后开始计算
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"INSERT INTO listening_streak (user_id, total_plays)
+               SELECT user_id, SUM(played_count)
+               FROM annotation
+               WHERE item_kind = 'audio_file' AND played_count > 0
+               GROUP BY user_id
+               ON CONFLICT DO NOTHING"#,
+        )
+        .await?;
+        db.execute_unprepared(
+            r#"INSERT INTO listening_milestone (user_id, kind, value, achieved_at)
+               SELECT s.user_id, 'plays', m.value, NOW() AT TIME ZONE 'UTC'
+               FROM listening_streak s
+               JOIN (VALUES (100), (500), (1000), (2500), (5000), (10000), (25000), (50000), (100000))
+                 AS m(value) ON m.value <= s.total_plays
+               ON CONFLICT DO NOTHING"#,
+        )
+        .await?;
+        db.execute_unprepared(
+            r#"INSERT INTO listening_milestone (user_id, kind, value, achieved_at)
+               SELECT user_id, 'first_artist_play', item_id, created_at
+               FROM annotation
+               WHERE item_kind = 'artist' AND played_count > 0
+               ON CONFLICT DO NOTHING"#,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ListeningMilestone::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ListeningStreak::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ListeningStreak {
+    Table,
+    UserId,
+    CurrentStreak,
+    LongestStreak,
+    LastPlayedOn,
+    TotalPlays,
+}
+
+#[derive(DeriveIden)]
+enum ListeningMilestone {
+    Table,
+    UserId,
+    Kind,
+    Value,
+    AchievedAt,
+}
@@ -0,0 +1,21 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // scrobble_enabled 之前没有生效，新建播放器时默认写入 0；
+        // 现在为 0 表示私密收听，已有的播放器都改为正常记录
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE player SET scrobble_enabled = 1")
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
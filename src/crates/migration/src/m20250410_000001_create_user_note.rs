@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 个人备注：每个用户对每个条目一条
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserNote::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserNote::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(UserNote::ItemKind).string().not_null())
+                    .col(ColumnDef::new(UserNote::ItemId).big_integer().not_null())
+                    .col(ColumnDef::new(UserNote::Note).text().not_null())
+                    .col(
+                        ColumnDef::new(UserNote::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(UserNote::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(UserNote::UserId)
+                            .col(UserNote::ItemKind)
+                            .col(UserNote::ItemId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserNote::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserNote {
+    Table,
+    UserId,
+    ItemKind,
+    ItemId,
+    Note,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 播放列表标签，由所有者设置，用于在列表中分类
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlaylistTag::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PlaylistTag::PlaylistId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PlaylistTag::Tag).string().not_null())
+                    .col(
+                        ColumnDef::new(PlaylistTag::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(PlaylistTag::PlaylistId)
+                            .col(PlaylistTag::Tag),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_playlist_tag_playlist_id")
+                            .from(PlaylistTag::Table, PlaylistTag::PlaylistId)
+                            .to(Playlist::Table, Playlist::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_playlist_tag_tag")
+                    .table(PlaylistTag::Table)
+                    .col(PlaylistTag::Tag)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PlaylistTag::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlaylistTag {
+    Table,
+    PlaylistId,
+    Tag,
+    Position,
+}
+
+#[derive(DeriveIden)]
+enum Playlist {
+    Table,
+    Id,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 删除的播放列表先保留一段时间，可以恢复
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Playlist::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Playlist::DeletedAt).timestamp().null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_playlist_deleted_at")
+                    .table(Playlist::Table)
+                    .col(Playlist::DeletedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_playlist_deleted_at")
+                    .table(Playlist::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Playlist::Table)
+                    .drop_column(Playlist::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Playlist {
+    Table,
+    DeletedAt,
+}
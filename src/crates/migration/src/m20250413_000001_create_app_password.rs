@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 应用密码：每个客户端一个，独立于用户主密码
+        manager
+            .create_table(
+                Table::create()
+                    .table(AppPassword::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AppPassword::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AppPassword::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(AppPassword::Label).string().not_null())
+                    .col(
+                        ColumnDef::new(AppPassword::EncryptedPassword)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AppPassword::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(AppPassword::LastUsedAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_app_password_user_id")
+                    .table(AppPassword::Table)
+                    .col(AppPassword::UserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AppPassword::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AppPassword {
+    Table,
+    Id,
+    UserId,
+    Label,
+    EncryptedPassword,
+    CreatedAt,
+    LastUsedAt,
+}
@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 去掉首尾静音后的起止位置（毫秒），由后台静音分析写入；分析时间早于 updated_at 时重新分析
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::AudibleStartMs)
+                            .big_integer()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::AudibleEndMs).big_integer().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(AudioFile::SilenceAnalyzedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AudioFile::Table)
+                    .drop_column(AudioFile::AudibleStartMs)
+                    .drop_column(AudioFile::AudibleEndMs)
+                    .drop_column(AudioFile::SilenceAnalyzedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AudioFile {
+    Table,
+    AudibleStartMs,
+    AudibleEndMs,
+    SilenceAnalyzedAt,
+}
@@ -1,4 +1,4 @@
-use super::shared::{Annotation, ArtistSummary, Contributor, GenreSummary};
+use super::shared::{Annotation, ArtistSummary, Contributor, CoverPlaceholder, GenreSummary};
 use chrono::NaiveDateTime;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -25,8 +25,17 @@ pub struct Album {
     pub song_count: i32,
     pub duration: i64,
     pub year: Option<i32>,
+    /// 原始发行年份（再版专辑早于 year）
+    pub original_year: Option<i32>,
+    /// YYYY[-MM[-DD]]
+    pub original_date: Option<String>,
+    pub release_date: Option<String>,
 
     pub compilation: bool,
+    /// 发行版本，如 "Deluxe"
+    pub edition: Option<String>,
+    /// 包含 explicit 歌曲
+    pub explicit: bool,
     pub size: i64,
     pub discs: Discs,
     pub sort_name: String,
@@ -37,6 +46,7 @@ pub struct Album {
     pub genres: Vec<GenreSummary>,
     pub artist: ArtistSummary,
     pub contributors: Vec<Contributor>,
+    pub cover_placeholder: Option<CoverPlaceholder>,
 
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
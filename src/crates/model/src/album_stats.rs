@@ -1,3 +1,4 @@
+use crate::album::Discs;
 use crate::ModelError;
 use domain::value::AlbumId;
 
@@ -8,6 +9,7 @@ pub struct AlbumStats {
     pub size: i64,
     pub song_count: i32,
     pub disk_numbers: Vec<i32>,
+    pub disc_titles: Discs,
     pub year: Option<i32>,
 }
 
@@ -19,6 +21,7 @@ pub struct AlbumStatsAdjustment {
     pub size_delta: i64,           // Can be positive or negative
     pub song_count_delta: i32,     // Can be positive or negative
     pub disk_number: Option<i32>,  // Disk number to add (if Some)
+    pub disc_subtitle: Option<String>, // Subtitle for disk_number (if Some)
     pub year: Option<i32>,         // Year to set (if Some and not already set)
 }
 
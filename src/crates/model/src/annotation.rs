@@ -0,0 +1,16 @@
+use chrono::NaiveDateTime;
+
+/// 用户对某个条目的收藏、评分和播放次数，供同步工具对账
+#[derive(Debug, Clone)]
+pub struct UserAnnotation {
+    /// audio_file、album 或 artist
+    pub item_kind: String,
+    pub item_id: i64,
+    pub starred: bool,
+    /// 最近一次收藏或取消收藏的时间
+    pub starred_at: NaiveDateTime,
+    pub rating: i32,
+    pub play_count: i32,
+    pub played_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -1,5 +1,6 @@
-use super::shared::{Annotation, ArtistSummary, Contributor, GenreSummary};
+use super::shared::{Annotation, ArtistSummary, Contributor, CoverPlaceholder, GenreSummary};
 use chrono::NaiveDateTime;
+use domain::value::{AudibleRange, Chapter, ReplayGain};
 
 #[derive(Debug)]
 pub struct AudioFile {
@@ -21,8 +22,23 @@ pub struct AudioFile {
     pub duration: i64,
     pub bit_rate: i32,
     pub channels: i32,
+    pub sample_rate: i32,
     pub order_title: String,
     pub bpm: i32,
+    pub comment: Option<String>,
+    pub mbz_track_id: Option<String>,
+    pub replay_gain: ReplayGain,
+    /// 内容分级为 explicit
+    pub explicit: bool,
+    /// 古典音乐的作品与乐章
+    pub work: Option<String>,
+    pub movement_name: Option<String>,
+    pub movement_number: Option<i32>,
+    pub movement_count: Option<i32>,
+    /// 章节标记，没有章节时为空
+    pub chapters: Vec<Chapter>,
+    /// 去掉首尾静音后的范围，尚未分析时为 None
+    pub audible_range: Option<AudibleRange>,
 
     pub name: String,
     pub song_count: i32,
@@ -36,6 +52,7 @@ pub struct AudioFile {
     pub genres: Vec<GenreSummary>,
     pub artist: ArtistSummary,
     pub contributors: Vec<Contributor>,
+    pub cover_placeholder: Option<CoverPlaceholder>,
 
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -1,17 +1,34 @@
-use super::media_file::MediaFile;
-use serde::{Deserialize, Serialize};
+use super::audio_file::AudioFile;
+use chrono::NaiveDateTime;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Bookmarkable {
-    pub bookmark_position: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+/// 用户的书签及对应歌曲
+#[derive(Debug)]
 pub struct Bookmark {
-    pub item: MediaFile,
-    pub comment: String,
+    pub entry: AudioFile,
+    /// 毫秒
     pub position: i64,
+    pub comment: String,
     pub changed_by: String,
-    pub created_at: i64,
-    pub updated_at: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 正在收听的有声书，一张专辑为一本书
+#[derive(Debug)]
+pub struct AudiobookProgress {
+    pub album_id: i64,
+    pub album: String,
+    pub artist: String,
+    /// 书签所在的章节
+    pub current: AudioFile,
+    /// 章节内的位置（毫秒）
+    pub position: i64,
+    pub chapter_count: i32,
+    /// 全书时长（秒）
+    pub duration: i64,
+    /// 已听时长（秒）
+    pub elapsed: i64,
+    /// 剩余时长（秒）
+    pub remaining: i64,
+    pub updated_at: NaiveDateTime,
 }
@@ -0,0 +1,25 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// MusicBrainz 发行组，同一专辑的不同版本属于同一个发行组
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseGroup {
+    pub mbz_id: String,
+    pub title: String,
+    /// Album、EP、Single 等
+    pub primary_type: Option<String>,
+    /// Compilation、Live、Remix 等
+    pub secondary_types: Vec<String>,
+    /// YYYY[-MM[-DD]]
+    pub first_release_date: Option<String>,
+}
+
+/// 缓存的艺术家发行组列表
+#[derive(Debug, Clone)]
+pub struct ArtistDiscography {
+    pub artist_id: i64,
+    /// 未在 MusicBrainz 找到艺术家时为 None
+    pub mbz_artist_id: Option<String>,
+    pub release_groups: Vec<ReleaseGroup>,
+    pub fetched_at: NaiveDateTime,
+}
@@ -0,0 +1,15 @@
+use chrono::NaiveDateTime;
+
+/// 增量同步的条目
+///
+/// token 单调递增：新增条目为雪花 ID，播放条目为播放历史 ID，客户端保存最后一个 token 作为下次同步起点
+#[derive(Debug, Clone)]
+pub struct FeedItem {
+    pub token: i64,
+    pub item_id: i64,
+    pub name: String,
+    pub album_id: Option<i64>,
+    pub artist_id: Option<i64>,
+    /// 加入曲库或最近一次播放的时间
+    pub at: NaiveDateTime,
+}
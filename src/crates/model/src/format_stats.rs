@@ -0,0 +1,32 @@
+use crate::ModelError;
+use async_trait::async_trait;
+
+/// 比特率分段的下限（kbps），最后一段没有上限
+pub const BIT_RATE_BUCKETS: &[i32] = &[0, 128, 192, 256, 320, 500, 1000];
+
+/// 比特率所在分段的下限
+pub fn bit_rate_bucket(bit_rate: i32) -> i32 {
+    BIT_RATE_BUCKETS
+        .iter()
+        .rev()
+        .copied()
+        .find(|&min| bit_rate >= min)
+        .unwrap_or(0)
+}
+
+/// format_stats 的一行，调整时 song_count 和 total_size 为增量
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatStats {
+    /// 小写扩展名
+    pub suffix: String,
+    pub bit_rate_bucket: i32,
+    pub sample_rate: i32,
+    pub song_count: i64,
+    pub total_size: i64,
+}
+
+#[async_trait]
+pub trait FormatStatsRepository: Send + Sync {
+    /// 按 (suffix, bit_rate_bucket, sample_rate) 累加，增量可以为负
+    async fn adjust_stats(&self, entry: FormatStats) -> Result<(), ModelError>;
+}
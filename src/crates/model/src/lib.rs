@@ -1,7 +1,6 @@
 /*
 pub mod artist_info;
 pub mod artwork_id;
-pub mod bookmark;
 pub mod discs;
 pub mod genre;
 pub mod kind;
@@ -17,17 +16,29 @@ pub mod user;
 pub mod album;
 pub mod album_location;
 pub mod album_stats;
+pub mod annotation;
 pub mod artist;
 pub mod artist_location;
 pub mod audio_file;
+pub mod bookmark;
+pub mod discography;
+pub mod feed;
+pub mod format_stats;
 pub mod genre;
+pub mod library_stats;
+pub mod listening_report;
+pub mod listening_streak;
+pub mod media_asset;
 pub mod music_folder;
 pub mod participant_stats;
 pub mod playback_history;
+pub mod play_stats;
 pub mod play_queue;
 pub mod playlist;
 pub mod scan_status;
 pub mod shared;
+pub mod star_stats;
+pub mod user_note;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -0,0 +1,37 @@
+use chrono::NaiveDate;
+
+/// 整个媒体库的汇总，来自 album_stats、genre_stats 和 participant_stats 投影
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LibraryTotals {
+    pub songs: i64,
+    pub albums: i64,
+    pub artists: i64,
+    pub genres: i64,
+    /// 总时长（秒）
+    pub duration: i64,
+    /// 总大小（字节）
+    pub size: i64,
+}
+
+/// 按文件格式（扩展名）统计的歌曲数和大小
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatCount {
+    pub suffix: String,
+    pub songs: i64,
+    pub size: i64,
+}
+
+/// 按比特率或采样率等数值统计的歌曲数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueCount {
+    pub value: i32,
+    pub songs: i64,
+}
+
+/// 某个月新加入的歌曲和专辑数，month 为当月第一天
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyAdditions {
+    pub month: NaiveDate,
+    pub songs: i64,
+    pub albums: i64,
+}
@@ -0,0 +1,28 @@
+use crate::ModelError;
+use async_trait::async_trait;
+use domain::value::UserId;
+
+/// 一次播放计入收听时钟：按年、星期（周一为 0）和小时汇总
+#[derive(Debug, Clone)]
+pub struct ListeningClockEntry {
+    pub user_id: UserId,
+    pub year: i32,
+    pub weekday: i16,
+    pub hour: i16,
+    pub play_seconds: i64,
+}
+
+/// 收听时钟中的一格
+#[derive(Debug, Clone)]
+pub struct ListeningClockCell {
+    pub weekday: i16,
+    pub hour: i16,
+    pub play_count: i64,
+    pub play_seconds: i64,
+}
+
+#[async_trait]
+pub trait ListeningClockRepository: Send + Sync {
+    /// 对应格子的播放次数加一，并累加收听时长
+    async fn record(&self, entry: ListeningClockEntry) -> Result<(), ModelError>;
+}
@@ -0,0 +1,115 @@
+use crate::ModelError;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use domain::value::UserId;
+
+/// 达到这些总播放次数时记为里程碑
+pub const PLAY_MILESTONES: &[i64] = &[100, 500, 1000, 2500, 5000, 10000, 25000, 50000, 100000];
+
+/// 连续收听达到这些天数时记为里程碑
+pub const STREAK_MILESTONES: &[i64] = &[7, 30, 100, 365];
+
+/// 用户的连续收听天数，日期按服务器本地时间计算
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListeningStreak {
+    pub user_id: UserId,
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub last_played_on: Option<NaiveDate>,
+    pub total_plays: i64,
+}
+
+impl ListeningStreak {
+    pub fn new(user_id: UserId) -> Self {
+        Self {
+            user_id,
+            current_streak: 0,
+            longest_streak: 0,
+            last_played_on: None,
+            total_plays: 0,
+        }
+    }
+
+    /// 记一次播放，连续天数增加时返回 true。
+    /// 早于最后播放日的离线播放只计入总次数
+    pub fn record_play(&mut self, played_on: NaiveDate) -> bool {
+        self.total_plays += 1;
+        let extended = match self.last_played_on {
+            Some(last) if played_on <= last => false,
+            Some(last) if played_on == last + Duration::days(1) => {
+                self.current_streak += 1;
+                true
+            }
+            _ => {
+                self.current_streak = 1;
+                true
+            }
+        };
+        if extended {
+            self.last_played_on = Some(played_on);
+            self.longest_streak = self.longest_streak.max(self.current_streak);
+        }
+        extended
+    }
+
+    /// today 这天仍然有效的连续天数：昨天或今天播放过才算没有中断
+    pub fn current_on(&self, today: NaiveDate) -> i32 {
+        match self.last_played_on {
+            Some(last) if last + Duration::days(1) >= today => self.current_streak,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneKind {
+    /// value 为总播放次数
+    Plays,
+    /// value 为连续天数
+    Streak,
+    /// value 为艺术家 ID
+    FirstArtistPlay,
+}
+
+impl MilestoneKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MilestoneKind::Plays => "plays",
+            MilestoneKind::Streak => "streak",
+            MilestoneKind::FirstArtistPlay => "first_artist_play",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "plays" => Some(MilestoneKind::Plays),
+            "streak" => Some(MilestoneKind::Streak),
+            "first_artist_play" => Some(MilestoneKind::FirstArtistPlay),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListeningMilestone {
+    pub user_id: UserId,
+    pub kind: MilestoneKind,
+    pub value: i64,
+    /// UTC
+    pub achieved_at: NaiveDateTime,
+}
+
+/// 查询时附带的名称，目前只有艺术家里程碑有
+#[derive(Debug, Clone)]
+pub struct AchievedMilestone {
+    pub milestone: ListeningMilestone,
+    pub name: Option<String>,
+}
+
+#[async_trait]
+pub trait ListeningStreakRepository: Send + Sync {
+    async fn find(&self, user_id: &UserId) -> Result<Option<ListeningStreak>, ModelError>;
+    async fn save(&self, streak: &ListeningStreak) -> Result<(), ModelError>;
+    /// 同一用户、类型和值的里程碑只记录一次，已存在时返回 false
+    async fn add_milestone(&self, milestone: &ListeningMilestone) -> Result<bool, ModelError>;
+}
@@ -0,0 +1,12 @@
+use chrono::NaiveDateTime;
+
+/// 扫描时收录的非音频资源，kind 为 booklet、artist_image、logo 或 image
+#[derive(Debug, Clone)]
+pub struct MediaAsset {
+    pub id: i64,
+    pub kind: String,
+    pub protocol: String,
+    pub path: String,
+    pub size: i64,
+    pub mtime: NaiveDateTime,
+}
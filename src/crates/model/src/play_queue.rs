@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub struct PlayQueue {
     /// Current playing audio file ID
     pub current_id: Option<i64>,
+    /// Index of the current entry in `entries`
+    pub current_index: Option<i32>,
     /// Position in milliseconds within the currently playing song
     pub position: i64,
     /// Username of the queue owner
@@ -0,0 +1,59 @@
+use crate::ModelError;
+use async_trait::async_trait;
+use chrono::{Datelike, Duration, NaiveDate};
+use domain::value::UserId;
+
+/// 播放次数汇总的时间粒度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollupPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl RollupPeriod {
+    pub const ALL: [RollupPeriod; 3] = [RollupPeriod::Day, RollupPeriod::Week, RollupPeriod::Month];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RollupPeriod::Day => "day",
+            RollupPeriod::Week => "week",
+            RollupPeriod::Month => "month",
+        }
+    }
+
+    /// 日期所在周期的起始日，周从周一开始
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            RollupPeriod::Day => date,
+            RollupPeriod::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            RollupPeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// 一次播放计入的汇总行（item_kind 与注解的条目类型一致，如 audio_file / album / artist）
+#[derive(Debug, Clone)]
+pub struct PlayCountEntry {
+    pub period: RollupPeriod,
+    pub period_start: NaiveDate,
+    pub user_id: UserId,
+    pub item_kind: String,
+    pub item_id: i64,
+}
+
+/// 排行榜条目
+#[derive(Debug, Clone)]
+pub struct ChartEntry {
+    pub item_id: i64,
+    pub name: String,
+    pub play_count: i64,
+}
+
+#[async_trait]
+pub trait PlayStatsRepository: Send + Sync {
+    /// 对应汇总行的播放次数加一
+    async fn increment(&self, entry: PlayCountEntry) -> Result<(), ModelError>;
+}
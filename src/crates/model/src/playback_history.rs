@@ -11,6 +11,21 @@ pub struct PlaybackHistoryEntry {
     pub scrobbled_at: NaiveDateTime,
 }
 
+/// 带歌曲信息的播放记录
+#[derive(Debug, Clone)]
+pub struct PlaybackHistoryItem {
+    pub id: i64,
+    pub audio_file_id: i64,
+    pub title: String,
+    pub album_id: Option<i64>,
+    pub album: Option<String>,
+    pub artist_id: Option<i64>,
+    pub artist: Option<String>,
+    pub duration: i64,
+    /// 服务器本地时间
+    pub scrobbled_at: NaiveDateTime,
+}
+
 #[async_trait]
 pub trait PlaybackHistoryRepository: Send + Sync {
     async fn save(&self, entry: &PlaybackHistoryEntry) -> Result<(), ModelError>;
@@ -11,6 +11,9 @@ pub struct Playlist {
     pub owner_name: String,
     pub owner_id: i64,
     pub public: bool,
+    /// 协作者用户名
+    pub collaborators: Vec<String>,
+    pub tags: Vec<String>,
     pub tracks: Vec<PlaylistTrack>,
     pub created_at: i64,
     pub updated_at: i64,
@@ -27,14 +30,20 @@ pub struct PlaylistSummary {
     pub owner_name: String,
     pub owner_id: i64,
     pub public: bool,
+    /// 所有者设置的标签
+    pub tags: Vec<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    /// 只有最近删除的播放列表才有
+    pub deleted_at: Option<i64>,
 }
 
 /// 播放列表中的歌曲
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaylistTrack {
     pub id: i64,
+    /// 添加该歌曲的用户名
+    pub added_by: Option<String>,
     pub audio_file: PlaylistAudioFile,
 }
 
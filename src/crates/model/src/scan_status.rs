@@ -1,8 +1,31 @@
 use crate::ModelError;
+use chrono::NaiveDateTime;
 use domain::value::LibraryId;
 use std::collections::HashMap;
 
+/// 扫描阶段：遍历文件 -> 解析元数据 -> 投影入库，三者以流水线方式重叠进行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScanPhase {
+    Idle,
+    Enumerating,
+    Parsing,
+    Projecting,
+}
+
+impl ScanPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanPhase::Idle => "idle",
+            ScanPhase::Enumerating => "enumerating",
+            ScanPhase::Parsing => "parsing",
+            ScanPhase::Projecting => "projecting",
+        }
+    }
+}
+
 /// ScanStatus 扫描状态，支持多库扫描
+///
+/// total_files 为遍历到的音频文件数，processed_files 为已投影入库的文件数
 #[derive(Debug, Clone)]
 pub struct ScanStatus {
     pub library_id: LibraryId,
@@ -11,6 +34,13 @@ pub struct ScanStatus {
     pub total_files: i64,
     pub processed_files: i64,
     pub error_count: i64,
+    pub phase: ScanPhase,
+    pub enumerated_files: i64,
+    pub parsed_files: i64,
+    /// 文件遍历是否已结束
+    pub enumeration_done: bool,
+    pub started_at: Option<NaiveDateTime>,
+    pub finished_at: Option<NaiveDateTime>,
 }
 
 impl ScanStatus {
@@ -22,30 +52,98 @@ impl ScanStatus {
             total_files: 0,
             processed_files: 0,
             error_count: 0,
+            phase: ScanPhase::Idle,
+            enumerated_files: 0,
+            parsed_files: 0,
+            enumeration_done: false,
+            started_at: None,
+            finished_at: None,
         }
     }
 
     /// 开始扫描
-    pub fn start_scanning(&mut self, total_files: i64) {
+    pub fn start_scanning(&mut self, total_files: i64, now: NaiveDateTime) {
         self.scanning = true;
         self.total_files = total_files;
         self.processed_files = 0;
         self.error_count = 0;
+        self.phase = ScanPhase::Enumerating;
+        self.enumerated_files = 0;
+        self.parsed_files = 0;
+        self.enumeration_done = false;
+        self.started_at = Some(now);
+        self.finished_at = None;
     }
 
-    /// 完成扫描
-    pub fn finish_scanning(&mut self) {
-        self.scanning = false;
+    /// 强制结束扫描
+    pub fn finish_scanning(&mut self, now: NaiveDateTime) {
+        self.enumeration_done = true;
+        self.set_idle(now);
+    }
+
+    /// 文件遍历结束，之后等待解析和投影追上
+    pub fn finish_enumeration(&mut self, now: NaiveDateTime) {
+        self.enumeration_done = true;
+        self.refresh_phase(now);
+    }
+
+    /// 遍历到一个音频文件
+    pub fn increment_enumerated(&mut self, now: NaiveDateTime) {
+        self.enumerated_files += 1;
+        self.total_files = self.total_files.max(self.enumerated_files);
+        self.refresh_phase(now);
+    }
+
+    /// 增加解析完成计数
+    pub fn increment_parsed(&mut self, now: NaiveDateTime) {
+        self.parsed_files += 1;
+        self.refresh_phase(now);
     }
 
     /// 增加处理文件计数
-    pub fn increment_processed(&mut self) {
+    pub fn increment_processed(&mut self, now: NaiveDateTime) {
         self.processed_files += 1;
+        self.refresh_phase(now);
     }
 
-    /// 增加错误计数
-    pub fn increment_error(&mut self) {
+    /// 增加错误计数（解析失败的文件不会进入投影阶段）
+    pub fn increment_error(&mut self, now: NaiveDateTime) {
         self.error_count += 1;
+        self.refresh_phase(now);
+    }
+
+    /// 预计剩余秒数，按已投影文件的平均速度估算；仍在遍历时总数未知，返回 None
+    pub fn eta_seconds(&self, now: NaiveDateTime) -> Option<i64> {
+        if !self.scanning || !self.enumeration_done || self.processed_files == 0 {
+            return None;
+        }
+        let elapsed = (now - self.started_at?).num_milliseconds().max(1) as f64;
+        let remaining = (self.total_files - self.processed_files - self.error_count).max(0) as f64;
+        Some((remaining * elapsed / self.processed_files as f64 / 1000.0).ceil() as i64)
+    }
+
+    /// 扫描在这次更新后结束时，now 记为结束时间
+    fn refresh_phase(&mut self, now: NaiveDateTime) {
+        if !self.scanning {
+            return;
+        }
+        if !self.enumeration_done {
+            self.phase = ScanPhase::Enumerating;
+        } else if self.parsed_files + self.error_count < self.total_files {
+            self.phase = ScanPhase::Parsing;
+        } else if self.processed_files < self.parsed_files {
+            self.phase = ScanPhase::Projecting;
+        } else {
+            self.set_idle(now);
+        }
+    }
+
+    fn set_idle(&mut self, now: NaiveDateTime) {
+        if self.scanning {
+            self.finished_at = Some(now);
+        }
+        self.scanning = false;
+        self.phase = ScanPhase::Idle;
     }
 
     /// 获取扫描进度百分比
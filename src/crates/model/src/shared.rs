@@ -22,6 +22,13 @@ pub struct GenreSummary {
     pub name: String,
 }
 
+/// 封面加载前的占位
+#[derive(Debug, Clone)]
+pub struct CoverPlaceholder {
+    pub blurhash: String,
+    pub dominant_color: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Contributor {
     pub artist_id: i64,
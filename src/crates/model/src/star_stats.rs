@@ -0,0 +1,16 @@
+use crate::ModelError;
+use async_trait::async_trait;
+
+/// 条目的收藏人数（item_kind 与注解的 Kind 名称一致，如 album / artist）
+#[derive(Debug, Clone)]
+pub struct StarStats {
+    pub item_kind: String,
+    pub item_id: i64,
+    pub star_count: i32,
+}
+
+#[async_trait]
+pub trait StarStatsRepository: Send + Sync {
+    /// star_count 为增量，可正可负
+    async fn adjust_stats(&self, entry: StarStats) -> Result<(), ModelError>;
+}
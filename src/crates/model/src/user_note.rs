@@ -0,0 +1,12 @@
+use chrono::NaiveDateTime;
+
+/// 用户的个人备注
+#[derive(Debug, Clone)]
+pub struct UserNote {
+    /// audio_file、album、artist 或 playlist
+    pub item_kind: String,
+    pub item_id: i64,
+    pub note: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
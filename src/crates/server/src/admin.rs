@@ -0,0 +1,61 @@
+pub mod annotations;
+pub mod artist_alias;
+pub mod backup;
+pub mod config;
+pub mod dead_letter;
+pub mod duplicate;
+pub mod file_check;
+pub mod genre;
+pub mod library;
+pub mod merge;
+pub mod metadata;
+pub mod metadata_edit;
+pub mod metrics;
+pub mod scan_error;
+pub mod settings;
+pub mod silence_analysis;
+pub mod trash;
+pub mod user;
+
+use crate::auth::ErrorResponse;
+use crate::consts;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use application::auth::UserClaims;
+
+/// 注册管理员原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/admin", consts::URL_PATH_NATIVE_API))
+            .configure(annotations::configure_routes)
+            .configure(artist_alias::configure_routes)
+            .configure(backup::configure_routes)
+            .configure(config::configure_routes)
+            .configure(dead_letter::configure_routes)
+            .configure(duplicate::configure_routes)
+            .configure(file_check::configure_routes)
+            .configure(genre::configure_routes)
+            .configure(library::configure_routes)
+            .configure(merge::configure_routes)
+            .configure(metadata::configure_routes)
+            .configure(metadata_edit::configure_routes)
+            .configure(metrics::configure_routes)
+            .configure(scan_error::configure_routes)
+            .configure(settings::configure_routes)
+            .configure(silence_analysis::configure_routes)
+            .configure(trash::configure_routes)
+            .configure(user::configure_routes),
+    );
+}
+
+/// 校验当前 JWT 用户是否为管理员
+pub(crate) fn require_admin(req: &HttpRequest) -> Result<UserClaims, HttpResponse> {
+    match req.extensions().get::<UserClaims>() {
+        Some(claims) if claims.is_admin => Ok(claims.clone()),
+        Some(_) => Err(HttpResponse::Forbidden().json(ErrorResponse {
+            error: "Only administrators can access this resource".to_string(),
+        })),
+        None => Err(HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        })),
+    }
+}
@@ -0,0 +1,130 @@
+use super::require_admin;
+use crate::auth::{bad_request, ErrorResponse};
+use crate::import::{self, MatchStrategy};
+use crate::AppState;
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures::StreamExt;
+use infra::import::{AnnotationFormat, ImportError};
+use log::info;
+use serde::Deserialize;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/annotations/export", web::get().to(export_annotations))
+        .route("/annotations/import", web::post().to(import_annotations));
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// csv 或 json，默认 json
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// csv 或 json，默认 json
+    pub format: Option<String>,
+    /// 歌曲的匹配方式：path 或 metadata，默认 path
+    #[serde(rename = "match")]
+    pub strategy: Option<String>,
+}
+
+fn parse_format(raw: Option<&str>) -> Result<AnnotationFormat, HttpResponse> {
+    raw.unwrap_or("json")
+        .parse()
+        .map_err(|e: ImportError| bad_request(e.to_string()))
+}
+
+fn parse_strategy(raw: Option<&str>) -> Result<MatchStrategy, HttpResponse> {
+    match raw.unwrap_or("path") {
+        "path" => Ok(MatchStrategy::Path),
+        "metadata" => Ok(MatchStrategy::Metadata),
+        other => Err(bad_request(format!("Unknown match strategy: {}", other))),
+    }
+}
+
+/// 下载所有用户的收藏、评分和播放次数
+pub async fn export_annotations(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<ExportQuery>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let format = match parse_format(query.format.as_deref()) {
+        Ok(format) => format,
+        Err(rsp) => return rsp,
+    };
+    let body = match import::export_annotations(&state).await {
+        Ok(records) => {
+            info!("Exported {} annotations", records.len());
+            format.write(&records).map_err(anyhow::Error::from)
+        }
+        Err(e) => Err(e),
+    };
+    match body {
+        Ok(body) => {
+            let filename = format!(
+                "rhythm-annotations-{}.{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                format.extension()
+            );
+            HttpResponse::Ok()
+                .content_type(format.content_type())
+                .insert_header((
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename),
+                ))
+                .body(body)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 上传导出的文件，合并到现有注解中
+pub async fn import_annotations(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<ImportQuery>,
+    mut payload: web::Payload,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let format = match parse_format(query.format.as_deref()) {
+        Ok(format) => format,
+        Err(rsp) => return rsp,
+    };
+    let strategy = match parse_strategy(query.strategy.as_deref()) {
+        Ok(strategy) => strategy,
+        Err(rsp) => return rsp,
+    };
+
+    let mut body = Vec::new();
+    while let Some(chunk) = payload.next().await {
+        match chunk {
+            Ok(chunk) => body.extend_from_slice(&chunk),
+            Err(e) => return bad_request(e.to_string()),
+        }
+    }
+    let records = match format.read(&body) {
+        Ok(records) => records,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    match import::import_annotation_records(&state, records, strategy).await {
+        Ok(report) => {
+            info!(
+                "Imported {} annotations, {} songs unmatched",
+                report.annotations_imported, report.songs_unmatched
+            );
+            HttpResponse::Ok().json(report)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
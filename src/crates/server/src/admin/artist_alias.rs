@@ -0,0 +1,159 @@
+use super::require_admin;
+use crate::auth::{error_response, parse_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::artist_alias::ArtistAliasService;
+use application::context::AppContext;
+use domain::artist::ArtistAlias;
+use infra::event_bus::in_memory::InMemoryEventBus;
+use infra::normalize::ArtistNameNormalizerImpl;
+use infra::repository::postgres::command::artist::{
+    ArtistAliasRepositoryImpl, ArtistRepositoryImpl,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/artists/{id}/aliases", web::get().to(list_aliases))
+        .route("/artists/{id}/aliases", web::post().to(add_alias))
+        .route(
+            "/artists/{id}/aliases/{alias}",
+            web::delete().to(remove_alias),
+        )
+        .route("/artists/{id}/relink", web::post().to(relink));
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AliasRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtistAliasView {
+    pub name: String,
+    pub artist_id: String,
+}
+
+impl From<ArtistAlias> for ArtistAliasView {
+    fn from(value: ArtistAlias) -> Self {
+        Self {
+            name: value.name,
+            artist_id: value.artist_id.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelinkView {
+    /// 被合并进来的艺术家名称
+    pub merged: Vec<String>,
+}
+
+fn alias_service(state: &AppState) -> ArtistAliasService<InMemoryEventBus> {
+    ArtistAliasService::new(
+        Arc::new(ArtistRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(ArtistAliasRepositoryImpl::new(state.db.clone())),
+        Arc::new(ArtistNameNormalizerImpl::new(
+            &state.app_cfg.ignored_articles(),
+        )),
+        super::merge::merge_service(state),
+    )
+}
+
+/// 列出艺术家的别名
+pub async fn list_aliases(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let artist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match alias_service(&state).list_aliases(&artist_id.into()).await {
+        Ok(aliases) => HttpResponse::Ok().json(
+            aliases
+                .into_iter()
+                .map(ArtistAliasView::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 新增别名；别名已属于其他艺术家时改为指向该艺术家
+pub async fn add_alias(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<AliasRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let artist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match alias_service(&state)
+        .add_alias(&artist_id.into(), &body.name)
+        .await
+    {
+        Ok(alias) => HttpResponse::Ok().json(ArtistAliasView::from(alias)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 删除别名，已合并的歌曲不会拆回
+pub async fn remove_alias(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let (artist_id, alias) = path.into_inner();
+    let artist_id = match parse_id(&artist_id) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match alias_service(&state)
+        .remove_alias(&artist_id.into(), &alias)
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 把名称命中别名的已有艺术家合并到该艺术家
+pub async fn relink(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let artist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match alias_service(&state)
+        .relink(&AppContext::new(), &artist_id.into())
+        .await
+    {
+        Ok(report) => HttpResponse::Ok().json(RelinkView {
+            merged: report.merged,
+        }),
+        Err(e) => error_response(e),
+    }
+}
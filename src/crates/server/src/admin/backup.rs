@@ -0,0 +1,215 @@
+use super::require_admin;
+use crate::auth::ErrorResponse;
+use crate::AppState;
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures::StreamExt;
+use infra::repository::postgres::backup::{BackupError, PostgresBackup, RestoreReport};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadBuf};
+
+/// 导出和恢复时管道的缓冲大小
+const PIPE_BUFFER_SIZE: usize = 64 * 1024;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/backup", web::get().to(download_backup))
+        .route("/restore", web::post().to(restore_backup));
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreView {
+    #[serde(flatten)]
+    pub report: RestoreReport,
+    /// 缓冲仓库的内存缓存仍是恢复前的数据，需要重启服务
+    pub restart_required: bool,
+}
+
+/// 恢复后封面和转码缓存的键可能对应不同的数据，全部清空
+pub(crate) fn clear_caches(state: &AppState) {
+    if let Err(e) = state.cover_art_cache.clear() {
+        warn!("Failed to clear cover art cache: {}", e);
+    }
+    if let Err(e) = state.stream_cache.clear() {
+        warn!("Failed to clear stream cache: {}", e);
+    }
+}
+
+/// 以 JSONL 下载一致性快照，边导出边发送
+pub async fn download_backup(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let (mut writer, reader) = tokio::io::duplex(PIPE_BUFFER_SIZE);
+    let backup = PostgresBackup::new(state.db.clone());
+    actix_web::rt::spawn(async move {
+        // 导出失败时响应缺少结束行，恢复时会被识别为截断
+        match backup.export(&mut writer).await {
+            Ok(rows) => info!("Exported backup with {} rows", rows),
+            Err(e) => error!("Failed to export backup: {}", e),
+        }
+    });
+    let body = futures::stream::unfold(reader, |mut reader| async move {
+        let mut buf = vec![0u8; PIPE_BUFFER_SIZE];
+        match reader.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((Ok::<_, std::io::Error>(Bytes::from(buf)), reader))
+            }
+            Err(e) => Some((Err(e), reader)),
+        }
+    });
+    let filename = format!(
+        "rhythm-backup-{}.jsonl",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        ))
+        .streaming(body)
+}
+
+fn too_large(max_size: u64) -> HttpResponse {
+    HttpResponse::PayloadTooLarge().json(ErrorResponse {
+        error: format!("Backup exceeds {} bytes", max_size),
+    })
+}
+
+/// 恢复读取的一端；上传失败时把管道的结束变成错误，已读到结束行也不会提交
+struct UploadReader {
+    inner: DuplexStream,
+    failed: Arc<AtomicBool>,
+}
+
+impl AsyncRead for UploadReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        match Pin::new(&mut this.inner).poll_read(cx, buf) {
+            Poll::Ready(Ok(()))
+                if buf.filled().len() == filled
+                    && buf.remaining() > 0
+                    && this.failed.load(Ordering::Acquire) =>
+            {
+                Poll::Ready(Err(io::Error::other("backup upload failed")))
+            }
+            other => other,
+        }
+    }
+}
+
+/// 把上传的数据写入管道，恢复提前结束时停止写入
+async fn copy_payload(
+    mut payload: web::Payload,
+    mut writer: DuplexStream,
+    max_size: u64,
+    failed: &AtomicBool,
+) -> Result<(), HttpResponse> {
+    let mut size = 0u64;
+    while let Some(chunk) = payload.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                failed.store(true, Ordering::Release);
+                return Err(HttpResponse::BadRequest().json(ErrorResponse {
+                    error: e.to_string(),
+                }));
+            }
+        };
+        size += chunk.len() as u64;
+        if size > max_size {
+            failed.store(true, Ordering::Release);
+            return Err(too_large(max_size));
+        }
+        if writer.write_all(&chunk).await.is_err() {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// 上传备份替换全部数据，扫描进行中时拒绝
+pub async fn restore_backup(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    payload: web::Payload,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match state.scan_repo.get_all_scan_statuses().await {
+        Ok(statuses) if statuses.values().any(|status| status.scanning) => {
+            return HttpResponse::Conflict().json(ErrorResponse {
+                error: "Cannot restore while a library scan is running".to_string(),
+            });
+        }
+        Ok(_) => {}
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: e.to_string(),
+            })
+        }
+    }
+
+    let max_size = state.app_cfg.backup().max_restore_size;
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if content_length.is_some_and(|len| len > max_size) {
+        return too_large(max_size);
+    }
+
+    // 边接收边恢复；上传中断或超出大小时恢复读到的备份缺少结束行，整体回滚
+    let (writer, reader) = tokio::io::duplex(PIPE_BUFFER_SIZE);
+    let failed = Arc::new(AtomicBool::new(false));
+    let reader = UploadReader {
+        inner: reader,
+        failed: failed.clone(),
+    };
+    let backup = PostgresBackup::new(state.db.clone());
+    let (upload, restored) = futures::join!(
+        copy_payload(payload, writer, max_size, &failed),
+        backup.restore(BufReader::new(reader))
+    );
+    if let Err(rsp) = upload {
+        return rsp;
+    }
+    match restored {
+        Ok(report) => {
+            clear_caches(&state);
+            warn!(
+                "Restored backup with {} rows, restart the server to drop cached data",
+                report.rows
+            );
+            HttpResponse::Ok().json(RestoreView {
+                report,
+                restart_required: true,
+            })
+        }
+        Err(e @ (BackupError::Format { .. } | BackupError::VersionMismatch { .. })) => {
+            HttpResponse::BadRequest().json(ErrorResponse {
+                error: e.to_string(),
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
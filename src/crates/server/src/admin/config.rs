@@ -0,0 +1,76 @@
+use super::require_admin;
+use crate::auth::ErrorResponse;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use infra::config::{ConfigError, ReloadReport};
+use log::{error, info, warn};
+use serde::Serialize;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/config/reload", web::post().to(reload_config));
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadView {
+    pub applied: Vec<&'static str>,
+    pub restart_required: Vec<&'static str>,
+}
+
+/// 重新加载配置，并把新的缓存过期时间同步到已创建的缓存
+pub fn reload(state: &AppState) -> Result<ReloadReport, ConfigError> {
+    let report = state.app_cfg.reload()?;
+    state
+        .cover_art_cache
+        .set_ttl_secs(state.app_cfg.cache().ttl_secs);
+    state
+        .stream_cache
+        .set_ttl_secs(state.app_cfg.transcoding().cache_ttl_secs);
+    if !report.restart_required.is_empty() {
+        warn!(
+            "Config changes require a restart: {}",
+            report.restart_required.join(", ")
+        );
+    }
+    Ok(report)
+}
+
+/// 收到 SIGHUP 时重新加载配置
+#[cfg(unix)]
+pub fn start_reload_on_sighup(state: web::Data<AppState>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!("Failed to listen for SIGHUP: {}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match reload(&state) {
+                Ok(report) => info!("Config reloaded, applied: {:?}", report.applied),
+                Err(e) => error!("Config reload failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn start_reload_on_sighup(_state: web::Data<AppState>) {}
+
+pub async fn reload_config(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match reload(&state) {
+        Ok(report) => HttpResponse::Ok().json(ReloadView {
+            applied: report.applied,
+            restart_required: report.restart_required,
+        }),
+        Err(e) => HttpResponse::BadRequest().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,134 @@
+use super::require_admin;
+use crate::auth::{error_response, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::event::dead_letter::DeadLetter;
+use infra::event_bus::dead_letter::DeadLetterQueue;
+use serde::Serialize;
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/dead-letters", web::get().to(list_dead_letters))
+        .route("/dead-letters/{id}", web::delete().to(remove_dead_letter))
+        .route(
+            "/dead-letters/{id}/retry",
+            web::post().to(retry_dead_letter),
+        );
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterView {
+    pub id: String,
+    pub event_id: String,
+    pub correlation_id: String,
+    pub event_type: String,
+    pub handler: String,
+    pub aggregate_id: String,
+    pub version: i64,
+    pub error: String,
+    pub attempts: i32,
+    pub occurred_at: String,
+    pub failed_at: String,
+    /// 事件仍在内存中，可以重新投递（重启后为 false）
+    pub redispatchable: bool,
+}
+
+impl DeadLetterView {
+    fn new(value: DeadLetter, redispatchable: bool) -> Self {
+        Self {
+            id: value.id,
+            event_id: value.event_id,
+            correlation_id: value.correlation_id,
+            event_type: value.event_type,
+            handler: value.handler,
+            aggregate_id: value.aggregate_id.to_string(),
+            version: value.version,
+            error: value.error,
+            attempts: value.attempts,
+            occurred_at: value.occurred_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            failed_at: value.failed_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            redispatchable,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryView {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn dead_letter_queue(state: &AppState) -> Result<Arc<DeadLetterQueue>, HttpResponse> {
+    state.event_bus.dead_letters().ok_or_else(|| {
+        HttpResponse::NotFound().json(ErrorResponse {
+            error: "Dead letter queue is not enabled".to_string(),
+        })
+    })
+}
+
+/// 列出重试耗尽的事件，最近失败的在前
+pub async fn list_dead_letters(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let queue = match dead_letter_queue(&state) {
+        Ok(queue) => queue,
+        Err(rsp) => return rsp,
+    };
+    match queue.list().await {
+        Ok(dead_letters) => HttpResponse::Ok().json(
+            dead_letters
+                .into_iter()
+                .map(|d| {
+                    let redispatchable = queue.is_redeliverable(&d.id);
+                    DeadLetterView::new(d, redispatchable)
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 丢弃死信
+pub async fn remove_dead_letter(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let queue = match dead_letter_queue(&state) {
+        Ok(queue) => queue,
+        Err(rsp) => return rsp,
+    };
+    match queue.remove(&path).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 把事件重新投递给失败的 handler，成功后移出死信队列
+pub async fn retry_dead_letter(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let queue = match dead_letter_queue(&state) {
+        Ok(queue) => queue,
+        Err(rsp) => return rsp,
+    };
+    match queue.redispatch(&path).await {
+        Ok(report) => HttpResponse::Ok().json(RetryView {
+            success: report.delivered,
+            error: report.error,
+        }),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,154 @@
+use super::merge::{merge_service, MergeRequest};
+use super::require_admin;
+use crate::auth::ErrorResponse;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::merge::MergeAlbumsCmd;
+use application::context::AppContext;
+use application::query::get_duplicate_albums::{
+    DuplicateAlbum, DuplicateAlbumPair, GetDuplicateAlbums, DEFAULT_MIN_OVERLAP,
+};
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/albums/duplicates", web::get().to(list_duplicate_albums))
+        .route(
+            "/albums/duplicates/merge",
+            web::post().to(merge_duplicate_albums),
+        );
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DuplicateQuery {
+    /// 0-1，省略时为 0.5
+    pub min_overlap: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergePairsRequest {
+    pub pairs: Vec<MergeRequest>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateAlbumView {
+    pub id: String,
+    pub name: String,
+    pub artist_name: String,
+    pub song_count: usize,
+}
+
+impl From<DuplicateAlbum> for DuplicateAlbumView {
+    fn from(value: DuplicateAlbum) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            artist_name: value.artist_name,
+            song_count: value.song_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePairView {
+    /// 建议合并后删除的专辑
+    pub source: DuplicateAlbumView,
+    pub target: DuplicateAlbumView,
+    pub shared_songs: usize,
+    pub overlap: f64,
+}
+
+impl From<DuplicateAlbumPair> for DuplicatePairView {
+    fn from(value: DuplicateAlbumPair) -> Self {
+        Self {
+            source: value.source.into(),
+            target: value.target.into(),
+            shared_songs: value.shared_songs,
+            overlap: value.overlap,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePairResultView {
+    pub source_id: String,
+    pub target_id: String,
+    pub merged: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 名称相同（忽略大小写、标点和版本说明）且歌曲标题有重合的专辑，通常是重新编辑标签后产生的
+pub async fn list_duplicate_albums(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<DuplicateQuery>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let min_overlap = query.min_overlap.unwrap_or(DEFAULT_MIN_OVERLAP);
+    if !(min_overlap > 0.0 && min_overlap <= 1.0) {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "minOverlap must be greater than 0 and at most 1".to_string(),
+        });
+    }
+    let query = GetDuplicateAlbums::new(
+        Arc::new(AlbumDaoImpl::new(state.db.clone())),
+        Arc::new(AudioFileDaoImpl::new(state.db.clone())),
+    );
+    match query.handle(min_overlap).await {
+        Ok(pairs) => HttpResponse::Ok().json(
+            pairs
+                .into_iter()
+                .map(DuplicatePairView::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 依次合并报告中选中的专辑对，单个失败不影响其余的专辑对
+pub async fn merge_duplicate_albums(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<MergePairsRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let service = merge_service(&state);
+    let context = AppContext::new();
+    let mut results = Vec::with_capacity(body.pairs.len());
+    for pair in &body.pairs {
+        let result = match (pair.source_id.parse::<i64>(), pair.target_id.parse::<i64>()) {
+            (Ok(source_id), Ok(target_id)) => {
+                let cmd = MergeAlbumsCmd {
+                    source_id: source_id.into(),
+                    target_id: target_id.into(),
+                };
+                service
+                    .merge_albums(&context, cmd)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+            _ => Err("Invalid id".to_string()),
+        };
+        results.push(MergePairResultView {
+            source_id: pair.source_id.clone(),
+            target_id: pair.target_id.clone(),
+            merged: result.is_ok(),
+            error: result.err(),
+        });
+    }
+    HttpResponse::Ok().json(results)
+}
@@ -0,0 +1,92 @@
+use super::require_admin;
+use crate::auth::{error_response, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::file_check::{CheckFilesCmd, MissingFile};
+use serde::{Deserialize, Serialize};
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/files/check", web::post().to(check_files));
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CheckFilesRequest {
+    /// 省略时检查所有库
+    pub library_id: Option<String>,
+    pub mark_unavailable: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingFileView {
+    pub id: String,
+    pub library_id: String,
+    pub path: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_since: Option<String>,
+}
+
+impl From<MissingFile> for MissingFileView {
+    fn from(value: MissingFile) -> Self {
+        Self {
+            id: value.id.to_string(),
+            library_id: value.library_id.to_string(),
+            path: value.path.path,
+            title: value.title,
+            missing_since: value
+                .missing_since
+                .map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileCheckView {
+    pub checked: usize,
+    pub missing_count: usize,
+    pub restored: usize,
+    pub skipped: usize,
+    pub marked: bool,
+    pub missing: Vec<MissingFileView>,
+}
+
+/// 检查所有歌曲文件是否仍然存在：/api/admin/files/check。
+/// `markUnavailable` 为 true 时缺失的歌曲不再出现在列表和搜索中，文件恢复后再次检查即可取消
+pub async fn check_files(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: Option<web::Json<CheckFilesRequest>>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let library_id = match body.library_id.as_deref().map(str::parse::<i64>) {
+        None => None,
+        Some(Ok(id)) => Some(id.into()),
+        Some(Err(_)) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: "Invalid libraryId".to_string(),
+            })
+        }
+    };
+    let service = crate::file_check_service(&state);
+    let cmd = CheckFilesCmd {
+        library_id,
+        mark_unavailable: body.mark_unavailable,
+    };
+    match service.check(cmd).await {
+        Ok(report) => HttpResponse::Ok().json(FileCheckView {
+            checked: report.checked,
+            missing_count: report.missing.len(),
+            restored: report.restored,
+            skipped: report.skipped,
+            marked: report.marked,
+            missing: report.missing.into_iter().map(Into::into).collect(),
+        }),
+        Err(e) => error_response(e),
+    }
+}
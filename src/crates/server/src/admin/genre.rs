@@ -0,0 +1,226 @@
+use super::require_admin;
+use crate::auth::{error_response, parse_id, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::genre_alias::{GenreAliasService, SetGenreParentCmd};
+use application::context::AppContext;
+use domain::genre::GenreAliasRepository;
+use infra::event_bus::in_memory::InMemoryEventBus;
+use infra::repository::postgres::command::{
+    album::AlbumRepositoryImpl,
+    audio_file::AudioFileRepositoryImpl,
+    genre::{GenreAliasRepositoryImpl, GenreRepositoryImpl},
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/genres", web::get().to(list_genres))
+        .route("/genres/aliases", web::get().to(list_aliases))
+        .route("/genres/aliases", web::post().to(add_alias))
+        .route("/genres/aliases/{alias}", web::delete().to(remove_alias))
+        .route("/genres/renormalize", web::post().to(renormalize))
+        .route("/genres/{id}/parent", web::put().to(set_parent));
+}
+
+/// 读取数据库中的流派别名（别名 -> 规范名）
+pub async fn load_genre_aliases(repo: &dyn GenreAliasRepository) -> HashMap<String, String> {
+    match repo.find_all().await {
+        Ok(aliases) => aliases
+            .into_iter()
+            .map(|a| (a.alias, a.genre_name))
+            .collect(),
+        Err(e) => {
+            warn!("Failed to load genre aliases: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AliasRequest {
+    pub alias: String,
+    pub genre: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentRequest {
+    /// 为空时取消父流派
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AliasView {
+    pub alias: String,
+    pub genre: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenreView {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenormalizeView {
+    pub genres: usize,
+    pub songs: usize,
+    pub albums: usize,
+}
+
+fn genre_service(state: &AppState) -> GenreAliasService<InMemoryEventBus> {
+    GenreAliasService::new(
+        state.id_generator.clone(),
+        Arc::new(GenreRepositoryImpl::new(state.db.clone())),
+        Arc::new(GenreAliasRepositoryImpl::new(state.db.clone())),
+        Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+        Arc::new(AlbumRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(state.event_bus.clone()),
+    )
+}
+
+/// 别名变更后刷新规则引擎，新的扫描立即生效
+async fn refresh_rule_engine(state: &AppState) -> HttpResponse {
+    let aliases = load_genre_aliases(&GenreAliasRepositoryImpl::new(state.db.clone())).await;
+    if let Err(e) = state.rule_engine.set_genre_aliases(aliases.clone()) {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let mut views: Vec<AliasView> = aliases
+        .into_iter()
+        .map(|(alias, genre)| AliasView { alias, genre })
+        .collect();
+    views.sort_by(|a, b| a.alias.cmp(&b.alias));
+    HttpResponse::Ok().json(views)
+}
+
+/// 列出所有流派及其父流派
+pub async fn list_genres(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match genre_service(&state).list_genres().await {
+        Ok(genres) => HttpResponse::Ok().json(
+            genres
+                .into_iter()
+                .map(|g| GenreView {
+                    id: g.id.to_string(),
+                    name: g.name(),
+                    parent_id: g.parent_id.map(|p| p.to_string()),
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 列出流派别名
+pub async fn list_aliases(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match genre_service(&state).list_aliases().await {
+        Ok(aliases) => HttpResponse::Ok().json(
+            aliases
+                .into_iter()
+                .map(|a| AliasView {
+                    alias: a.alias,
+                    genre: a.genre_name,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 新增或修改流派别名
+pub async fn add_alias(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<AliasRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    if let Err(e) = genre_service(&state)
+        .add_alias(&body.alias, &body.genre)
+        .await
+    {
+        return error_response(e);
+    }
+    refresh_rule_engine(&state).await
+}
+
+/// 删除流派别名
+pub async fn remove_alias(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    if let Err(e) = genre_service(&state).remove_alias(&path).await {
+        return error_response(e);
+    }
+    refresh_rule_engine(&state).await
+}
+
+/// 设置或取消父流派
+pub async fn set_parent(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<ParentRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let genre_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let parent_id = match body.parent_id.as_deref().filter(|s| !s.is_empty()) {
+        Some(raw) => match parse_id(raw) {
+            Ok(id) => Some(id.into()),
+            Err(rsp) => return rsp,
+        },
+        None => None,
+    };
+    let cmd = SetGenreParentCmd {
+        genre_id: genre_id.into(),
+        parent_id,
+    };
+    match genre_service(&state).set_parent(cmd).await {
+        Ok(genre) => HttpResponse::Ok().json(GenreView {
+            id: genre.id.to_string(),
+            name: genre.name(),
+            parent_id: genre.parent_id.map(|p| p.to_string()),
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 按当前别名把已入库的歌曲和专辑迁移到规范流派
+pub async fn renormalize(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match genre_service(&state).renormalize(&AppContext::new()).await {
+        Ok(report) => HttpResponse::Ok().json(RenormalizeView {
+            genres: report.genres,
+            songs: report.audio_files,
+            albums: report.albums,
+        }),
+        Err(e) => error_response(e),
+    }
+}
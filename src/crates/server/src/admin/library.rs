@@ -0,0 +1,570 @@
+use super::require_admin;
+use crate::auth::{error_response, parse_id, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::library_admin::{
+    CreateLibraryCmd, LibraryAdminService, SetCredentialsCmd, UpdateLibraryCmd,
+};
+use application::command::path_migration::{
+    MoveLibraryCmd, PathMigrationReport, PathMigrationService,
+};
+use domain::library::{Library, LibraryCredentials, LibraryKind, ScanStatus};
+use domain::value::MediaPath;
+use infra::repository::postgres::command::library::LibraryRepositoryImpl;
+use infra::repository::postgres::command::library_path::LibraryPathStoreImpl;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/libraries", web::get().to(list_libraries))
+        .route("/libraries", web::post().to(create_library))
+        .route("/libraries/test", web::post().to(test_path))
+        .route("/libraries/{id}", web::get().to(get_library))
+        .route("/libraries/{id}", web::put().to(update_library))
+        .route("/libraries/{id}", web::delete().to(delete_library))
+        .route("/libraries/{id}/test", web::post().to(test_library))
+        .route("/libraries/{id}/move", web::post().to(move_library))
+        .route(
+            "/libraries/{id}/credentials",
+            web::get().to(get_credentials),
+        )
+        .route(
+            "/libraries/{id}/credentials",
+            web::put().to(set_credentials),
+        )
+        .route(
+            "/libraries/{id}/credentials",
+            web::delete().to(clear_credentials),
+        );
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLibraryRequest {
+    pub name: String,
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+    pub path: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub scan_interval_minutes: Option<i32>,
+    /// music（默认）或 audiobook
+    pub kind: Option<String>,
+    /// 非空时只扫描匹配的文件
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+    /// 扫描时跳过的文件和目录，如 `@eaDir`、`*.partial`
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    /// 是否扫描以 `.` 开头的文件和目录，默认跳过
+    #[serde(default)]
+    pub scan_hidden: bool,
+    /// 存储无法访问时从浏览和搜索结果中隐藏库中的歌曲，默认仍然显示
+    #[serde(default)]
+    pub hide_unavailable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLibraryRequest {
+    pub name: Option<String>,
+    /// 与 path 一起修改，单独提供时沿用原协议
+    pub protocol: Option<String>,
+    pub path: Option<String>,
+    pub enabled: Option<bool>,
+    /// 0 表示改用系统默认间隔
+    pub scan_interval_minutes: Option<i32>,
+    pub kind: Option<String>,
+    /// 提供时整体替换，空数组表示清空
+    pub include_patterns: Option<Vec<String>>,
+    pub exclude_patterns: Option<Vec<String>>,
+    pub scan_hidden: Option<bool>,
+    pub hide_unavailable: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestPathRequest {
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveLibraryRequest {
+    /// 省略时沿用原协议
+    pub protocol: Option<String>,
+    pub path: String,
+    /// 只统计会被改写的行数，不修改数据
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CredentialsRequest {
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    pub domain: Option<String>,
+}
+
+fn default_protocol() -> String {
+    "local".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryView {
+    pub id: String,
+    pub name: String,
+    pub protocol: String,
+    pub path: String,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_interval_minutes: Option<i32>,
+    pub kind: String,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    pub scan_hidden: bool,
+    pub hide_unavailable: bool,
+    /// 存储无法访问的起始时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unavailable_since: Option<String>,
+    pub scanning: bool,
+    pub last_scan_at: String,
+}
+
+impl From<Library> for LibraryView {
+    fn from(library: Library) -> Self {
+        Self {
+            id: library.id.to_string(),
+            name: library.name,
+            protocol: library.path.protocol,
+            path: library.path.path,
+            enabled: library.enabled,
+            scan_interval_minutes: library.scan_interval_minutes,
+            kind: library.kind.as_str().to_string(),
+            include_patterns: library.scan_filter.include,
+            exclude_patterns: library.scan_filter.exclude,
+            scan_hidden: library.scan_filter.scan_hidden,
+            hide_unavailable: library.hide_unavailable,
+            unavailable_since: library
+                .unavailable_since
+                .map(|time| time.format("%Y-%m-%dT%H:%M:%S").to_string()),
+            scanning: library.scan_status == ScanStatus::Scanning,
+            last_scan_at: library.last_scan_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionView {
+    pub reachable: bool,
+    pub entries: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 凭据只返回账号信息，密码永不回显
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialsView {
+    pub username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    pub updated_at: String,
+}
+
+impl From<LibraryCredentials> for CredentialsView {
+    fn from(credentials: LibraryCredentials) -> Self {
+        Self {
+            username: credentials.username,
+            domain: credentials.domain,
+            updated_at: credentials
+                .updated_at
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMigrationView {
+    pub from: String,
+    pub to: String,
+    /// 表名 -> 改写的行数
+    pub updated: BTreeMap<String, u64>,
+    pub dry_run: bool,
+}
+
+impl From<PathMigrationReport> for PathMigrationView {
+    fn from(report: PathMigrationReport) -> Self {
+        Self {
+            from: report.from.path,
+            to: report.to.path,
+            updated: report.updated,
+            dry_run: report.dry_run,
+        }
+    }
+}
+
+fn library_service(state: &AppState) -> Result<LibraryAdminService, HttpResponse> {
+    let credentials_repo = crate::library_credentials_repository(state).ok_or_else(|| {
+        HttpResponse::InternalServerError().json(ErrorResponse {
+            error: "Failed to create credentials encryptor".to_string(),
+        })
+    })?;
+    Ok(LibraryAdminService::new(
+        Arc::new(LibraryRepositoryImpl::new(state.db.clone())),
+        credentials_repo,
+        Arc::new(crate::storage_client_factory(state)),
+        state.id_generator.clone(),
+    ))
+}
+
+fn parse_kind(raw: Option<&str>) -> Result<Option<LibraryKind>, HttpResponse> {
+    raw.map(LibraryKind::try_from)
+        .transpose()
+        .map_err(|error| HttpResponse::BadRequest().json(ErrorResponse { error }))
+}
+
+/// 列出所有音乐库（包括停用的）
+pub async fn list_libraries(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    match service.list().await {
+        Ok(libraries) => HttpResponse::Ok().json(
+            libraries
+                .into_iter()
+                .map(LibraryView::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn get_library(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    match service.get(&library_id.into()).await {
+        Ok(library) => HttpResponse::Ok().json(LibraryView::from(library)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 注册新的音乐库，路径格式由对应协议的存储客户端校验
+pub async fn create_library(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<CreateLibraryRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let body = body.into_inner();
+    let kind = match parse_kind(body.kind.as_deref()) {
+        Ok(kind) => kind.unwrap_or_default(),
+        Err(rsp) => return rsp,
+    };
+    let cmd = CreateLibraryCmd {
+        name: body.name,
+        path: MediaPath {
+            protocol: body.protocol,
+            path: body.path,
+        },
+        enabled: body.enabled,
+        scan_interval_minutes: body.scan_interval_minutes,
+        kind,
+        include_patterns: body.include_patterns,
+        exclude_patterns: body.exclude_patterns,
+        scan_hidden: body.scan_hidden,
+        hide_unavailable: body.hide_unavailable,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    match service.create(cmd).await {
+        Ok(library) => HttpResponse::Created().json(LibraryView::from(library)),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn update_library(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<UpdateLibraryRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    let body = body.into_inner();
+    let kind = match parse_kind(body.kind.as_deref()) {
+        Ok(kind) => kind,
+        Err(rsp) => return rsp,
+    };
+    let new_path = match (body.protocol, body.path) {
+        (protocol, Some(path)) => {
+            let protocol = match protocol {
+                Some(protocol) => protocol,
+                None => match service.get(&library_id.into()).await {
+                    Ok(library) => library.path.protocol,
+                    Err(e) => return error_response(e),
+                },
+            };
+            Some(MediaPath { protocol, path })
+        }
+        (Some(_), None) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: "protocol can only be changed together with path".to_string(),
+            })
+        }
+        (None, None) => None,
+    };
+    let cmd = UpdateLibraryCmd {
+        library_id: library_id.into(),
+        name: body.name,
+        path: new_path,
+        enabled: body.enabled,
+        scan_interval_minutes: body.scan_interval_minutes,
+        kind,
+        include_patterns: body.include_patterns,
+        exclude_patterns: body.exclude_patterns,
+        scan_hidden: body.scan_hidden,
+        hide_unavailable: body.hide_unavailable,
+    };
+    match service.update(cmd).await {
+        Ok(library) => HttpResponse::Ok().json(LibraryView::from(library)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 删除音乐库，已有索引文件的库需先停用而不能删除
+pub async fn delete_library(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    match service.delete(&library_id.into()).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn connection_response(service: &LibraryAdminService, path: &MediaPath) -> HttpResponse {
+    match service.test_connection(path).await {
+        Ok(report) => HttpResponse::Ok().json(ConnectionView {
+            reachable: report.reachable,
+            entries: report.entries,
+            error: report.error,
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 注册前测试任意根路径是否可达
+pub async fn test_path(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<TestPathRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let body = body.into_inner();
+    let path = MediaPath {
+        protocol: body.protocol,
+        path: body.path,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    connection_response(&service, &path).await
+}
+
+/// 测试已注册库的根路径是否可达
+pub async fn test_library(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    match service.get(&library_id.into()).await {
+        Ok(library) => connection_response(&service, &library.path).await,
+        Err(e) => error_response(e),
+    }
+}
+
+/// 库的根目录移动后（如 `/mnt/music` -> `/srv/music`）在一个事务中改写所有已索引的路径，
+/// 保留播放次数和收藏，之后清空封面和转码缓存
+pub async fn move_library(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<MoveLibraryRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let body = body.into_inner();
+    let service = PathMigrationService::new(
+        Arc::new(LibraryRepositoryImpl::new(state.db.clone())),
+        Arc::new(crate::storage_client_factory(&state)),
+        Arc::new(LibraryPathStoreImpl::new(state.db.clone())),
+    );
+    let cmd = MoveLibraryCmd {
+        library_id: library_id.into(),
+        protocol: body.protocol,
+        path: body.path,
+        dry_run: body.dry_run,
+    };
+    match service.move_library(cmd).await {
+        Ok(report) => {
+            if !report.dry_run {
+                super::backup::clear_caches(&state);
+            }
+            HttpResponse::Ok().json(PathMigrationView::from(report))
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// 查看库的凭据（不含密码）
+pub async fn get_credentials(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    match service.get_credentials(&library_id.into()).await {
+        Ok(Some(credentials)) => HttpResponse::Ok().json(CredentialsView::from(credentials)),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("No credentials for library {}", library_id),
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 设置或轮换库的凭据
+pub async fn set_credentials(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<CredentialsRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    let body = body.into_inner();
+    let cmd = SetCredentialsCmd {
+        library_id: library_id.into(),
+        username: body.username,
+        password: body.password,
+        domain: body.domain,
+    };
+    match service.set_credentials(cmd).await {
+        Ok(credentials) => HttpResponse::Ok().json(CredentialsView::from(credentials)),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn clear_credentials(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let service = match library_service(&state) {
+        Ok(service) => service,
+        Err(rsp) => return rsp,
+    };
+    match service.clear_credentials(&library_id.into()).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
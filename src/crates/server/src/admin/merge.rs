@@ -0,0 +1,187 @@
+use super::require_admin;
+use crate::auth::{error_response, parse_id, parse_ids};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::merge::{
+    MergeAlbumsCmd, MergeArtistsCmd, MergeRepositories, MergeService, SplitAlbumCmd, SplitArtistCmd,
+};
+use application::context::AppContext;
+use infra::event_bus::in_memory::InMemoryEventBus;
+use infra::normalize::{AlbumNameNormalizerImpl, ArtistNameNormalizerImpl};
+use infra::repository::postgres::command::{
+    album::AlbumRepositoryImpl, artist::ArtistRepositoryImpl, audio_file::AudioFileRepositoryImpl,
+    merge::MergeStoreImpl,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/albums/merge", web::post().to(merge_albums))
+        .route("/albums/{id}/split", web::post().to(split_album))
+        .route("/artists/merge", web::post().to(merge_artists))
+        .route("/artists/{id}/split", web::post().to(split_artist));
+}
+
+/// 合并请求：source 合并到 target 后被删除
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeRequest {
+    pub source_id: String,
+    pub target_id: String,
+}
+
+/// 拆分请求：将指定歌曲移到新名称下
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitRequest {
+    pub name: String,
+    pub song_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeResultView {
+    pub id: String,
+    pub name: String,
+}
+
+pub(super) fn merge_service(state: &AppState) -> MergeService<InMemoryEventBus> {
+    let ignored_articles = state.app_cfg.ignored_articles();
+    MergeService::new(
+        state.id_generator.clone(),
+        MergeRepositories {
+            audio_file: Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+            album: Arc::new(AlbumRepositoryImpl::new(
+                state.db.clone(),
+                state.id_generator.clone(),
+            )),
+            artist: Arc::new(ArtistRepositoryImpl::new(
+                state.db.clone(),
+                state.id_generator.clone(),
+            )),
+        },
+        Arc::new(MergeStoreImpl::new(state.db.clone())),
+        Arc::new(AlbumNameNormalizerImpl::new(&ignored_articles)),
+        Arc::new(ArtistNameNormalizerImpl::new(&ignored_articles)),
+        Arc::new(state.event_bus.clone()),
+    )
+}
+
+/// 合并两张专辑
+pub async fn merge_albums(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<MergeRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let (source_id, target_id) = match (parse_id(&body.source_id), parse_id(&body.target_id)) {
+        (Ok(s), Ok(t)) => (s, t),
+        (Err(rsp), _) | (_, Err(rsp)) => return rsp,
+    };
+    let cmd = MergeAlbumsCmd {
+        source_id: source_id.into(),
+        target_id: target_id.into(),
+    };
+    match merge_service(&state)
+        .merge_albums(&AppContext::new(), cmd)
+        .await
+    {
+        Ok(album) => HttpResponse::Ok().json(MergeResultView {
+            id: album.id.to_string(),
+            name: album.name,
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 合并两个艺术家
+pub async fn merge_artists(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<MergeRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let (source_id, target_id) = match (parse_id(&body.source_id), parse_id(&body.target_id)) {
+        (Ok(s), Ok(t)) => (s, t),
+        (Err(rsp), _) | (_, Err(rsp)) => return rsp,
+    };
+    let cmd = MergeArtistsCmd {
+        source_id: source_id.into(),
+        target_id: target_id.into(),
+    };
+    match merge_service(&state)
+        .merge_artists(&AppContext::new(), cmd)
+        .await
+    {
+        Ok(artist) => HttpResponse::Ok().json(MergeResultView {
+            id: artist.id.to_string(),
+            name: artist.name,
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 将专辑中的部分歌曲拆分到新专辑
+pub async fn split_album(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<SplitRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let (album_id, song_ids) = match (parse_id(&path), parse_ids(&body.song_ids)) {
+        (Ok(a), Ok(s)) => (a, s),
+        (Err(rsp), _) | (_, Err(rsp)) => return rsp,
+    };
+    let cmd = SplitAlbumCmd {
+        album_id: album_id.into(),
+        audio_file_ids: song_ids.into_iter().map(Into::into).collect(),
+        new_name: body.into_inner().name,
+    };
+    match merge_service(&state)
+        .split_album(&AppContext::new(), cmd)
+        .await
+    {
+        Ok(album) => HttpResponse::Ok().json(MergeResultView {
+            id: album.id.to_string(),
+            name: album.name,
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 将艺术家的部分歌曲拆分给另一个艺术家
+pub async fn split_artist(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<SplitRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let (artist_id, song_ids) = match (parse_id(&path), parse_ids(&body.song_ids)) {
+        (Ok(a), Ok(s)) => (a, s),
+        (Err(rsp), _) | (_, Err(rsp)) => return rsp,
+    };
+    let cmd = SplitArtistCmd {
+        artist_id: artist_id.into(),
+        audio_file_ids: song_ids.into_iter().map(Into::into).collect(),
+        new_name: body.into_inner().name,
+    };
+    match merge_service(&state)
+        .split_artist(&AppContext::new(), cmd)
+        .await
+    {
+        Ok(artist) => HttpResponse::Ok().json(MergeResultView {
+            id: artist.id.to_string(),
+            name: artist.name,
+        }),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,273 @@
+use super::require_admin;
+use crate::auth::ErrorResponse;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::shared::SystemConfigStore;
+use infra::metadata::rule_engine::{RuleContext, RuleTrace};
+use infra::repository::postgres::command::system_config::SystemConfigStoreImpl;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/metadata/rules")
+            .route("", web::get().to(list_rules))
+            .route("/dry-run", web::post().to(dry_run))
+            .route("/reload", web::post().to(reload_rules)),
+    );
+    cfg.service(
+        web::scope("/metadata/protected-artists")
+            .route("", web::get().to(list_protected_artists))
+            .route("", web::post().to(add_protected_artist))
+            .route("", web::delete().to(remove_protected_artist)),
+    );
+}
+
+/// 受保护艺术家名单在系统配置中的键（JSON 数组）
+pub const PROTECTED_ARTISTS_KEY: &str = "metadata.protected_artists";
+
+/// 从系统配置读取受保护艺术家名单
+pub async fn load_protected_artists(store: &dyn SystemConfigStore) -> Vec<String> {
+    match store.get_string(PROTECTED_ARTISTS_KEY).await {
+        Ok(Some(raw)) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+            warn!("Invalid {} value: {}", PROTECTED_ARTISTS_KEY, e);
+            Vec::new()
+        }),
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            warn!("Failed to load {}: {}", PROTECTED_ARTISTS_KEY, e);
+            Vec::new()
+        }
+    }
+}
+
+/// 规则试运行请求：原始标签值
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunRequest {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub album: String,
+    #[serde(default)]
+    pub genre: String,
+    pub year: Option<i32>,
+    pub track_number: Option<i32>,
+    /// 多值艺术家标签（ARTISTS）
+    #[serde(default)]
+    pub artists: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProtectedArtistRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantView {
+    pub name: String,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_role: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleContextView {
+    pub title: String,
+    pub artists: Vec<ParticipantView>,
+    pub album: String,
+    pub genres: Vec<String>,
+    pub year: Option<i32>,
+    pub track_number: Option<i32>,
+    pub extra: HashMap<String, String>,
+}
+
+impl From<&RuleContext> for RuleContextView {
+    fn from(ctx: &RuleContext) -> Self {
+        Self {
+            title: ctx.title.clone(),
+            artists: ctx
+                .artists
+                .iter()
+                .map(|a| ParticipantView {
+                    name: a.name.clone(),
+                    role: a.role.to_string(),
+                    sub_role: a.sub_role.as_ref().map(|r| r.to_string()),
+                })
+                .collect(),
+            album: ctx.album.clone(),
+            genres: ctx.genres.clone(),
+            year: ctx.year,
+            track_number: ctx.track_number,
+            extra: ctx.extra.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleStepView {
+    pub rule: String,
+    pub priority: i32,
+    pub applied: bool,
+    pub context: RuleContextView,
+}
+
+impl From<&RuleTrace> for RuleStepView {
+    fn from(trace: &RuleTrace) -> Self {
+        Self {
+            rule: trace.rule.clone(),
+            priority: trace.priority,
+            applied: trace.applied,
+            context: RuleContextView::from(&trace.context),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunResponse {
+    pub input: RuleContextView,
+    pub steps: Vec<RuleStepView>,
+    pub result: RuleContextView,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleView {
+    pub name: String,
+    pub priority: i32,
+}
+
+/// 列出当前生效的规则（按执行顺序）
+pub async fn list_rules(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let engine = state.rule_engine.engine();
+    let rules: Vec<RuleView> = engine
+        .rules()
+        .iter()
+        .map(|r| RuleView {
+            name: r.name().to_string(),
+            priority: r.priority(),
+        })
+        .collect();
+    HttpResponse::Ok().json(rules)
+}
+
+/// 使用当前规则对原始标签做试运行，返回每条规则执行后的上下文
+pub async fn dry_run(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<DryRunRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let body = body.into_inner();
+    let mut ctx = RuleContext::new(
+        body.title,
+        body.artist,
+        body.album,
+        body.genre,
+        body.year,
+        body.track_number,
+    )
+    .with_raw_artists(body.artists);
+    let input = RuleContextView::from(&ctx);
+
+    let traces = state.rule_engine.engine().execute_traced(&mut ctx);
+
+    HttpResponse::Ok().json(DryRunResponse {
+        input,
+        steps: traces.iter().map(RuleStepView::from).collect(),
+        result: RuleContextView::from(&ctx),
+    })
+}
+
+/// 立即重新加载规则文件
+pub async fn reload_rules(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match state.rule_engine.reload() {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().json(ErrorResponse { error: e }),
+    }
+}
+
+/// 列出通过管理接口维护的受保护艺术家
+pub async fn list_protected_artists(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    HttpResponse::Ok().json(state.rule_engine.protected_artists())
+}
+
+/// 添加受保护艺术家
+pub async fn add_protected_artist(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<ProtectedArtistRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let name = body.name.trim().to_string();
+    if name.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "name is required".to_string(),
+        });
+    }
+    let mut names = state.rule_engine.protected_artists();
+    if names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+        return HttpResponse::Ok().json(names);
+    }
+    names.push(name);
+    save_protected_artists(&state, names).await
+}
+
+/// 移除受保护艺术家
+pub async fn remove_protected_artist(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<ProtectedArtistRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let mut names = state.rule_engine.protected_artists();
+    names.retain(|n| !n.eq_ignore_ascii_case(body.name.trim()));
+    save_protected_artists(&state, names).await
+}
+
+/// 先用新名单构建引擎校验，通过后再写入系统配置并替换引擎
+async fn save_protected_artists(state: &AppState, names: Vec<String>) -> HttpResponse {
+    if let Err(e) = state.rule_engine.validate_protected_artists(&names) {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let store = SystemConfigStoreImpl::new(state.db.clone());
+    let raw = match serde_json::to_string(&names) {
+        Ok(raw) => raw,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: e.to_string(),
+            })
+        }
+    };
+    if let Err(e) = store.set_string(PROTECTED_ARTISTS_KEY, &raw).await {
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        });
+    }
+    if let Err(e) = state.rule_engine.set_protected_artists(names.clone()) {
+        return HttpResponse::InternalServerError().json(ErrorResponse { error: e });
+    }
+    HttpResponse::Ok().json(names)
+}
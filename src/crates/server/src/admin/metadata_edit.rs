@@ -0,0 +1,224 @@
+use super::require_admin;
+use crate::auth::{error_response, parse_id, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::metadata_edit::{
+    EditAlbumCmd, EditArtistCmd, EditSongCmd, MetadataEditService, MetadataRepositories,
+};
+use application::context::AppContext;
+use application::error::AppError;
+use domain::metadata_change::{MetadataChange, MetadataTarget};
+use domain::value::{AlbumId, ArtistId, AudioFileId};
+use infra::event_bus::in_memory::InMemoryEventBus;
+use infra::metadata::tag_writer::FfmpegTagWriter;
+use infra::normalize::{AlbumNameNormalizerImpl, ArtistNameNormalizerImpl};
+use infra::repository::postgres::command::{
+    album::AlbumRepositoryImpl, artist::ArtistRepositoryImpl, audio_file::AudioFileRepositoryImpl,
+    metadata_change::MetadataChangeRepositoryImpl,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metadata/songs/{id}", web::patch().to(edit_song))
+        .route("/metadata/albums/{id}", web::patch().to(edit_album))
+        .route("/metadata/artists/{id}", web::patch().to(edit_artist))
+        .route("/metadata/changelog", web::get().to(changelog));
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditSongRequest {
+    pub title: Option<String>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub disc_subtitle: Option<String>,
+    pub year: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangelogQuery {
+    pub target_type: Option<String>,
+    pub target_id: Option<i64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataChangeView {
+    pub id: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: String,
+    pub tags_written: bool,
+    pub created_at: String,
+}
+
+impl From<&MetadataChange> for MetadataChangeView {
+    fn from(change: &MetadataChange) -> Self {
+        Self {
+            id: change.id.to_string(),
+            target_type: change.target.as_str().to_string(),
+            target_id: change.target_id.to_string(),
+            field: change.field.clone(),
+            old_value: change.old_value.clone(),
+            new_value: change.new_value.clone(),
+            changed_by: change.changed_by.clone(),
+            tags_written: change.tags_written,
+            created_at: change.created_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+fn edit_service(state: &AppState) -> MetadataEditService<InMemoryEventBus> {
+    let ignored_articles = state.app_cfg.ignored_articles();
+    let service = MetadataEditService::new(
+        state.id_generator.clone(),
+        MetadataRepositories {
+            audio_file: Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+            album: Arc::new(AlbumRepositoryImpl::new(
+                state.db.clone(),
+                state.id_generator.clone(),
+            )),
+            artist: Arc::new(ArtistRepositoryImpl::new(
+                state.db.clone(),
+                state.id_generator.clone(),
+            )),
+            change: Arc::new(MetadataChangeRepositoryImpl::new(state.db.clone())),
+        },
+        Arc::new(AlbumNameNormalizerImpl::new(&ignored_articles)),
+        Arc::new(ArtistNameNormalizerImpl::new(&ignored_articles)),
+        Arc::new(state.event_bus.clone()),
+    );
+    if state.app_cfg.metadata().write_tags {
+        let ffmpeg_path = state.app_cfg.transcoding().ffmpeg_path;
+        service.with_tag_writer(Arc::new(FfmpegTagWriter::new(ffmpeg_path)))
+    } else {
+        service
+    }
+}
+
+fn changes_response(result: Result<Vec<MetadataChange>, AppError>) -> HttpResponse {
+    match result {
+        Ok(changes) => HttpResponse::Ok().json(
+            changes
+                .iter()
+                .map(MetadataChangeView::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 修改歌曲信息
+pub async fn edit_song(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<EditSongRequest>,
+) -> HttpResponse {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let body = body.into_inner();
+    let cmd = EditSongCmd {
+        audio_file_id: AudioFileId::from(id),
+        title: body.title,
+        track_number: body.track_number,
+        disc_number: body.disc_number,
+        disc_subtitle: body.disc_subtitle,
+        year: body.year,
+        changed_by: claims.user_name,
+    };
+    changes_response(
+        edit_service(&state)
+            .edit_song(&AppContext::new(), cmd)
+            .await,
+    )
+}
+
+/// 修改专辑名称
+pub async fn edit_album(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<RenameRequest>,
+) -> HttpResponse {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let cmd = EditAlbumCmd {
+        album_id: AlbumId::from(id),
+        name: body.into_inner().name,
+        changed_by: claims.user_name,
+    };
+    changes_response(edit_service(&state).edit_album(cmd).await)
+}
+
+/// 修改艺术家名称
+pub async fn edit_artist(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<RenameRequest>,
+) -> HttpResponse {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let cmd = EditArtistCmd {
+        artist_id: ArtistId::from(id),
+        name: body.into_inner().name,
+        changed_by: claims.user_name,
+    };
+    changes_response(edit_service(&state).edit_artist(cmd).await)
+}
+
+/// 查询元数据修改记录
+pub async fn changelog(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<ChangelogQuery>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let target = match (&query.target_type, query.target_id) {
+        (Some(target_type), Some(target_id)) => {
+            match MetadataTarget::try_from(target_type.as_str()) {
+                Ok(target) => Some((target, target_id)),
+                Err(e) => {
+                    return HttpResponse::BadRequest().json(ErrorResponse {
+                        error: e.to_string(),
+                    })
+                }
+            }
+        }
+        _ => None,
+    };
+    let limit = query.limit.unwrap_or(100).min(1000);
+    changes_response(edit_service(&state).changelog(target, limit).await)
+}
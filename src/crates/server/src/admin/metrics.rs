@@ -0,0 +1,209 @@
+use super::require_admin;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use infra::event_bus::in_memory::HandlerStats;
+use infra::repository::buffered::memtable::MemtableMetrics;
+use infra::repository::postgres::query_metrics::QueryMetricsSnapshot;
+use infra::storage::read_ahead::{self, ReadAheadSnapshot};
+use serde::Serialize;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(get_metrics))
+        .route("/metrics/handlers", web::get().to(get_handler_metrics));
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemtableMetricsView {
+    pub name: String,
+    pub active_size: usize,
+    pub threshold_size: usize,
+    pub pending_flushes: usize,
+    pub max_pending_flushes: usize,
+    pub rotations: u64,
+    pub flushes: u64,
+    pub flush_errors: u64,
+    pub backpressure_waits: u64,
+    pub last_flush_ms: u64,
+    /// 平均每次 flush 耗时
+    pub avg_flush_ms: u64,
+}
+
+impl From<MemtableMetrics> for MemtableMetricsView {
+    fn from(m: MemtableMetrics) -> Self {
+        let total_ms = m.total_flush_duration.as_millis() as u64;
+        Self {
+            name: m.name,
+            active_size: m.active_size,
+            threshold_size: m.threshold_size,
+            pending_flushes: m.pending_flushes,
+            max_pending_flushes: m.max_pending_flushes,
+            rotations: m.rotations,
+            flushes: m.flushes,
+            flush_errors: m.flush_errors,
+            backpressure_waits: m.backpressure_waits,
+            last_flush_ms: m.last_flush_duration.as_millis() as u64,
+            avg_flush_ms: total_ms.checked_div(m.flushes).unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryMetricsView {
+    pub queries: u64,
+    pub failed_queries: u64,
+    pub slow_queries: u64,
+    pub total_ms: u64,
+    /// 平均每条查询耗时
+    pub avg_query_ms: f64,
+    pub requests: u64,
+    /// 查询次数或耗时超出预算的请求数
+    pub over_budget_requests: u64,
+    pub avg_queries_per_request: f64,
+    pub max_queries_per_request: u64,
+}
+
+impl From<QueryMetricsSnapshot> for QueryMetricsView {
+    fn from(m: QueryMetricsSnapshot) -> Self {
+        let total_ms = m.total_time.as_secs_f64() * 1000.0;
+        Self {
+            queries: m.queries,
+            failed_queries: m.failed,
+            slow_queries: m.slow_queries,
+            total_ms: total_ms as u64,
+            avg_query_ms: if m.queries > 0 {
+                total_ms / m.queries as f64
+            } else {
+                0.0
+            },
+            requests: m.requests,
+            over_budget_requests: m.over_budget_requests,
+            avg_queries_per_request: if m.requests > 0 {
+                m.request_queries as f64 / m.requests as f64
+            } else {
+                0.0
+            },
+            max_queries_per_request: m.max_request_queries,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadAheadMetricsView {
+    /// 从远程存储播放或转码时读取的块数
+    pub chunks: u64,
+    /// 需要时已经读好的块数
+    pub hits: u64,
+    pub bytes: u64,
+    pub hit_ratio: f64,
+}
+
+impl From<ReadAheadSnapshot> for ReadAheadMetricsView {
+    fn from(s: ReadAheadSnapshot) -> Self {
+        Self {
+            chunks: s.chunks,
+            hits: s.hits,
+            bytes: s.bytes,
+            hit_ratio: s.hit_ratio(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsView {
+    /// 正在处理中的事件数
+    pub events_in_flight: usize,
+    pub memtables: Vec<MemtableMetricsView>,
+    pub database: QueryMetricsView,
+    pub read_ahead: ReadAheadMetricsView,
+}
+
+/// 缓冲仓库、事件总线、数据库查询和远程存储预读的运行指标
+pub async fn get_metrics(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    HttpResponse::Ok().json(MetricsView {
+        events_in_flight: state.event_bus.in_flight(),
+        memtables: state
+            .memtables
+            .metrics()
+            .into_iter()
+            .map(MemtableMetricsView::from)
+            .collect(),
+        database: state.query_metrics.snapshot().into(),
+        read_ahead: read_ahead::stats().into(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandlerStatsView {
+    pub handler: String,
+    pub event_type: String,
+    pub processed: u64,
+    /// 失败的处理次数，包括之后重试成功的
+    pub errors: u64,
+    /// 重试耗尽进入死信队列的事件数
+    pub dead_lettered: u64,
+    /// 已投递但还没处理完的事件数
+    pub backlog: usize,
+    /// 最近处理完的事件的发生时间
+    pub last_event_at: Option<String>,
+    pub last_processed_at: Option<String>,
+    /// 最近一个事件从发生到处理完的毫秒数
+    pub lag_ms: Option<i64>,
+}
+
+impl From<HandlerStats> for HandlerStatsView {
+    fn from(stats: HandlerStats) -> Self {
+        Self {
+            handler: stats.handler.to_string(),
+            event_type: stats.event_type.to_string(),
+            processed: stats.processed,
+            errors: stats.errors,
+            dead_lettered: stats.gave_up,
+            backlog: stats.backlog,
+            last_event_at: stats.last_event_at.map(|at| at.to_rfc3339()),
+            last_processed_at: stats.last_processed_at.map(|at| at.to_rfc3339()),
+            lag_ms: stats
+                .last_event_at
+                .zip(stats.last_processed_at)
+                .map(|(event_at, processed_at)| (processed_at - event_at).num_milliseconds()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandlerMetricsView {
+    pub events_in_flight: usize,
+    /// 等待解析的文件数
+    pub parse_queue: usize,
+    pub handlers: Vec<HandlerStatsView>,
+}
+
+/// 事件处理器的处理量、积压和错误数，用于发现扫描时跟不上的投影
+pub async fn get_handler_metrics(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    HttpResponse::Ok().json(HandlerMetricsView {
+        events_in_flight: state.event_bus.in_flight(),
+        parse_queue: state
+            .parse_pool
+            .as_ref()
+            .map(|pool| pool.pending())
+            .unwrap_or(0),
+        handlers: state
+            .event_bus
+            .handler_stats()
+            .await
+            .into_iter()
+            .map(HandlerStatsView::from)
+            .collect(),
+    })
+}
@@ -0,0 +1,144 @@
+use super::require_admin;
+use crate::auth::{error_response, parse_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::media_parse::ParseMediaFileCmd;
+use application::context::AppContext;
+use application::error::AppError;
+use domain::scan_error::{ScanError, ScanErrorError, ScanErrorRepository};
+use domain::value::FileType;
+use infra::repository::postgres::command::scan_error::ScanErrorRepositoryImpl;
+use serde::Serialize;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/scan-errors", web::get().to(list_scan_errors))
+        .route("/scan-errors/{id}", web::delete().to(remove_scan_error))
+        .route("/scan-errors/{id}/retry", web::post().to(retry_scan_error));
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanErrorView {
+    pub id: String,
+    pub library_id: String,
+    pub path: String,
+    pub size: i64,
+    pub error: String,
+    pub attempts: i32,
+    pub failed_at: String,
+}
+
+impl From<ScanError> for ScanErrorView {
+    fn from(value: ScanError) -> Self {
+        Self {
+            id: value.id.to_string(),
+            library_id: value.library_id.to_string(),
+            path: value.path.path,
+            size: value.size,
+            error: value.error,
+            attempts: value.attempts,
+            failed_at: value.failed_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryView {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn repository_error(e: ScanErrorError) -> HttpResponse {
+    error_response(AppError::RepositoryError(
+        "ScanError".to_string(),
+        e.to_string(),
+    ))
+}
+
+async fn find_scan_error(
+    repo: &ScanErrorRepositoryImpl,
+    raw_id: &str,
+) -> Result<ScanError, HttpResponse> {
+    let id = parse_id(raw_id)?;
+    match repo.find_by_id(id).await {
+        Ok(Some(scan_error)) => Ok(scan_error),
+        Ok(None) => Err(error_response(AppError::AggregateNotFound(
+            "Scan error".to_string(),
+            id.to_string(),
+        ))),
+        Err(e) => Err(repository_error(e)),
+    }
+}
+
+/// 列出解析失败的文件，最近失败的在前
+pub async fn list_scan_errors(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match ScanErrorRepositoryImpl::new(state.db.clone())
+        .find_all()
+        .await
+    {
+        Ok(errors) => HttpResponse::Ok().json(
+            errors
+                .into_iter()
+                .map(ScanErrorView::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => repository_error(e),
+    }
+}
+
+/// 移出隔离列表，下次扫描会重新解析
+pub async fn remove_scan_error(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let repo = ScanErrorRepositoryImpl::new(state.db.clone());
+    let scan_error = match find_scan_error(&repo, &path).await {
+        Ok(scan_error) => scan_error,
+        Err(rsp) => return rsp,
+    };
+    match repo.delete(scan_error.id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => repository_error(e),
+    }
+}
+
+/// 立即重新解析该文件，失败时重新记入隔离列表
+pub async fn retry_scan_error(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let repo = ScanErrorRepositoryImpl::new(state.db.clone());
+    let scan_error = match find_scan_error(&repo, &path).await {
+        Ok(scan_error) => scan_error,
+        Err(rsp) => return rsp,
+    };
+    // 先删除记录，避免文件未变化时被直接跳过
+    if let Err(e) = repo.delete(scan_error.id).await {
+        return repository_error(e);
+    }
+    let cmd = ParseMediaFileCmd {
+        filemeta: scan_error.file_meta(),
+        library_id: scan_error.library_id.clone(),
+        file_type: FileType::Audio,
+    };
+    let result = crate::media_file_parse_service(&state)
+        .parse_media_file(&AppContext::new(), cmd)
+        .await;
+    HttpResponse::Ok().json(RetryView {
+        success: result.is_ok(),
+        error: result.err().map(|e| e.to_string()),
+    })
+}
@@ -0,0 +1,125 @@
+use super::require_admin;
+use crate::auth::{error_response, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::settings::{SettingKey, SettingValue, Settings, SettingsService};
+use infra::repository::postgres::command::system_config::SystemConfigStoreImpl;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/settings", web::get().to(get_settings))
+        .route("/settings", web::put().to(update_settings));
+}
+
+/// 生效中的设置，密钥只返回是否已配置
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsView {
+    pub ignored_articles: Vec<String>,
+    pub cover_art_wildcards: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_interval_minutes: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_fm_api_key: Option<String>,
+    pub last_fm_secret_set: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spotify_client_id: Option<String>,
+    pub spotify_client_secret_set: bool,
+    pub play_queue_mode: &'static str,
+    /// 通过管理接口覆盖了配置文件的项
+    pub overridden: Vec<&'static str>,
+}
+
+impl SettingsView {
+    fn new(state: &AppState, settings: Settings) -> Self {
+        let overridden = SettingKey::ALL
+            .into_iter()
+            .filter(|key| !key.is_secret() && settings.is_set(*key))
+            .map(|key| key.name())
+            .collect();
+        Self {
+            ignored_articles: state.app_cfg.ignored_articles(),
+            cover_art_wildcards: state.app_cfg.cover_art_wildcards(),
+            scan_interval_minutes: settings.scan_interval_minutes,
+            last_fm_api_key: settings.lastfm_api_key,
+            last_fm_secret_set: settings.lastfm_secret.is_some(),
+            spotify_client_id: settings.spotify_client_id,
+            spotify_client_secret_set: settings.spotify_client_secret.is_some(),
+            play_queue_mode: settings.play_queue_mode.unwrap_or_default().name(),
+            overridden,
+        }
+    }
+}
+
+/// 设置服务，变更后同步到进程内的配置
+pub(crate) fn settings_service(state: &AppState) -> SettingsService {
+    SettingsService::new(
+        Arc::new(SystemConfigStoreImpl::new(state.db.clone())),
+        vec![Arc::new(state.app_cfg.clone())],
+    )
+}
+
+fn parse_value(name: &str, value: Value) -> Result<Option<SettingValue>, HttpResponse> {
+    let invalid = || {
+        HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Invalid value for {}", name),
+        })
+    };
+    match value {
+        Value::Null => Ok(None),
+        Value::String(text) => Ok(Some(SettingValue::Text(text))),
+        Value::Number(n) => n
+            .as_i64()
+            .map(|n| Some(SettingValue::Number(n)))
+            .ok_or_else(invalid),
+        Value::Array(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|list| Some(SettingValue::List(list))),
+        _ => Err(invalid()),
+    }
+}
+
+pub async fn get_settings(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match settings_service(&state).load().await {
+        Ok(settings) => HttpResponse::Ok().json(SettingsView::new(&state, settings)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 合并更新设置，值为 null 的项恢复为配置文件中的值
+pub async fn update_settings(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<HashMap<String, Value>>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let mut changes = HashMap::new();
+    for (name, value) in body.into_inner() {
+        let Some(key) = SettingKey::from_name(&name) else {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: format!("Unknown setting: {}", name),
+            });
+        };
+        match parse_value(&name, value) {
+            Ok(value) => changes.insert(key, value),
+            Err(rsp) => return rsp,
+        };
+    }
+    match settings_service(&state).update(changes).await {
+        Ok(settings) => HttpResponse::Ok().json(SettingsView::new(&state, settings)),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,96 @@
+use super::require_admin;
+use crate::auth::ErrorResponse;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::silence_analysis::SilenceAnalysisReport;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/silence-analysis", web::get().to(get_status))
+        .route("/silence-analysis", web::post().to(start_analysis));
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StartAnalysisRequest {
+    /// 省略时分析所有库
+    pub library_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceAnalysisReportView {
+    pub analyzed: usize,
+    pub failed: usize,
+}
+
+impl From<SilenceAnalysisReport> for SilenceAnalysisReportView {
+    fn from(value: SilenceAnalysisReport) -> Self {
+        Self {
+            analyzed: value.analyzed,
+            failed: value.failed,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceAnalysisStatusView {
+    pub running: bool,
+    /// 上一次完成的分析，服务重启后为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_report: Option<SilenceAnalysisReportView>,
+}
+
+fn status_view(state: &AppState) -> SilenceAnalysisStatusView {
+    SilenceAnalysisStatusView {
+        running: state.silence_analysis.is_running(),
+        last_report: state.silence_analysis.last_report().map(Into::into),
+    }
+}
+
+/// 静音分析是否正在运行及上一次的结果：GET /api/admin/silence-analysis
+pub async fn get_status(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    HttpResponse::Ok().json(status_view(&state))
+}
+
+/// 在后台分析尚未分析或文件有变化的歌曲：POST /api/admin/silence-analysis。
+/// 已有分析在运行时返回 409
+pub async fn start_analysis(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: Option<web::Json<StartAnalysisRequest>>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let library_id = match body.library_id.as_deref().map(str::parse::<i64>) {
+        None => None,
+        Some(Ok(id)) => Some(id.into()),
+        Some(Err(_)) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: "Invalid libraryId".to_string(),
+            })
+        }
+    };
+    if state.silence_analysis.is_running() {
+        return HttpResponse::Conflict().json(ErrorResponse {
+            error: "Silence analysis is already running".to_string(),
+        });
+    }
+    let service = state.silence_analysis.clone();
+    tokio::spawn(async move {
+        if let Err(e) = service.run(library_id).await {
+            warn!("Silence analysis failed: {}", e);
+        }
+    });
+    HttpResponse::Accepted().json(SilenceAnalysisStatusView {
+        running: true,
+        last_report: state.silence_analysis.last_report().map(Into::into),
+    })
+}
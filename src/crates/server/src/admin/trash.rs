@@ -0,0 +1,225 @@
+use super::require_admin;
+use crate::auth::{error_response, parse_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::trash::{TrashAlbumCmd, TrashEntry, TrashService, TrashSongCmd};
+use application::context::AppContext;
+use chrono::NaiveDateTime;
+use infra::event_bus::in_memory::InMemoryEventBus;
+use infra::repository::postgres::command::{
+    album::AlbumRepositoryImpl, audio_file::AudioFileRepositoryImpl,
+    library::LibraryRepositoryImpl, merge::MergeStoreImpl,
+};
+use infra::storage::FsTrashStore;
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 过期条目的清理周期
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/songs/{id}", web::delete().to(trash_song))
+        .route("/albums/{id}", web::delete().to(trash_album))
+        .route("/trash", web::get().to(list_trash))
+        .route("/trash/{id}/restore", web::post().to(restore_trash))
+        .route("/trash/{id}", web::delete().to(purge_trash));
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntryView {
+    pub id: String,
+    pub kind: &'static str,
+    pub name: String,
+    pub paths: Vec<String>,
+    pub deleted_by: String,
+    pub deleted_at: NaiveDateTime,
+    /// 超过该时间后永久删除，未配置保留期时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreView {
+    pub restored_files: usize,
+    /// 文件已移回但未能立即入库，会在下一次扫描时入库
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+pub(crate) fn trash_service(state: &AppState) -> TrashService<InMemoryEventBus> {
+    TrashService::new(
+        state.id_generator.clone(),
+        Arc::new(FsTrashStore::new(state.app_cfg.trash().directory)),
+        Arc::new(LibraryRepositoryImpl::new(state.db.clone())),
+        Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+        Arc::new(AlbumRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(MergeStoreImpl::new(state.db.clone())),
+        Arc::new(state.event_bus.clone()),
+    )
+}
+
+fn entry_view(state: &AppState, entry: TrashEntry) -> TrashEntryView {
+    let retention = state.app_cfg.trash().retention;
+    TrashEntryView {
+        id: entry.id.to_string(),
+        kind: entry.kind.as_str(),
+        name: entry.name,
+        paths: entry.files.into_iter().map(|f| f.path.path).collect(),
+        deleted_by: entry.deleted_by,
+        deleted_at: entry.deleted_at,
+        expires_at: retention.map(|retention| entry.deleted_at + retention),
+    }
+}
+
+/// 把歌曲文件移入回收站并从库中移除
+pub async fn trash_song(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let cmd = TrashSongCmd {
+        audio_file_id: id.into(),
+        deleted_by: claims.user_name,
+    };
+    match trash_service(&state)
+        .trash_song(&AppContext::new(), cmd)
+        .await
+    {
+        Ok(entry) => HttpResponse::Ok().json(entry_view(&state, entry)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 把专辑的全部歌曲移入回收站并删除专辑
+pub async fn trash_album(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let cmd = TrashAlbumCmd {
+        album_id: id.into(),
+        deleted_by: claims.user_name,
+    };
+    match trash_service(&state)
+        .trash_album(&AppContext::new(), cmd)
+        .await
+    {
+        Ok(entry) => HttpResponse::Ok().json(entry_view(&state, entry)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 回收站中的条目，最近删除的在前
+pub async fn list_trash(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match trash_service(&state).list().await {
+        Ok(entries) => HttpResponse::Ok().json(
+            entries
+                .into_iter()
+                .map(|entry| entry_view(&state, entry))
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 把文件移回原位置并重新入库；恢复后是新的歌曲，原来的 ID 不再有效
+pub async fn restore_trash(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let cmds = match trash_service(&state).restore(id).await {
+        Ok(cmds) => cmds,
+        Err(e) => return error_response(e),
+    };
+    let restored_files = cmds.iter().map(|cmd| cmd.files.len()).sum();
+    let mut warning = None;
+    let ctx = AppContext::new();
+    for cmd in cmds {
+        if let Err(e) = crate::library_command_service(&state)
+            .add_files(&ctx, cmd)
+            .await
+        {
+            warn!("Failed to add restored files: {}", e);
+            warning = Some(format!(
+                "Files were restored but will be indexed by the next scan: {}",
+                e
+            ));
+        }
+    }
+    HttpResponse::Ok().json(RestoreView {
+        restored_files,
+        warning,
+    })
+}
+
+/// 永久删除回收站中的条目
+pub async fn purge_trash(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match trash_service(&state).purge(id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 定期永久删除超过保留期的条目
+pub fn start_trash_purge(state: web::Data<AppState>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let Some(retention) = state.app_cfg.trash().retention else {
+                continue;
+            };
+            let before = chrono::Utc::now().naive_utc() - retention;
+            match trash_service(&state).purge_expired(before).await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} expired trash entries", purged),
+                Err(e) => warn!("Failed to purge expired trash entries: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,418 @@
+use super::require_admin;
+use crate::auth::{error_response, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::auth::{PasswordEncryptor, PasswordHasher};
+use application::command::user::{
+    CreateUserCmd, DeleteUserCmd, UpdateUserCmd, UserAppService, UserRolesPatch,
+};
+use domain::user::{User, UserRoles, UserStatus};
+use infra::repository::postgres::command::user::{
+    UserPreferenceRepositoryImpl, UserRepositoryImpl,
+};
+use infra::Aes256GcmEncryptor;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 500;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/users", web::get().to(list_users))
+        .route("/users", web::post().to(create_user))
+        .route("/users/{username}", web::get().to(get_user))
+        .route("/users/{username}", web::put().to(update_user))
+        .route("/users/{username}", web::delete().to(delete_user))
+        .route("/users/{username}/disable", web::post().to(disable_user))
+        .route("/users/{username}/enable", web::post().to(enable_user))
+        .route(
+            "/users/{username}/preferences",
+            web::get().to(get_preferences),
+        )
+        .route(
+            "/users/{username}/preferences",
+            web::put().to(update_preferences),
+        );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub offset: Option<u64>,
+    pub size: Option<u64>,
+}
+
+/// 角色开关，创建时缺省取默认角色，更新时缺省保持原值
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolesBody {
+    pub settings: Option<bool>,
+    pub stream: Option<bool>,
+    pub download: Option<bool>,
+    pub upload: Option<bool>,
+    pub playlist: Option<bool>,
+    pub cover_art: Option<bool>,
+    pub comment: Option<bool>,
+    pub podcast: Option<bool>,
+    pub jukebox: Option<bool>,
+    pub share: Option<bool>,
+}
+
+impl From<RolesBody> for UserRolesPatch {
+    fn from(value: RolesBody) -> Self {
+        Self {
+            settings: value.settings,
+            stream: value.stream,
+            download: value.download,
+            upload: value.upload,
+            playlist: value.playlist,
+            cover_art: value.cover_art,
+            comment: value.comment,
+            podcast: value.podcast,
+            jukebox: value.jukebox,
+            share: value.share,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub is_admin: bool,
+    #[serde(default)]
+    pub roles: RolesBody,
+    pub max_bit_rate: Option<i32>,
+    pub download_quota_mb: Option<i64>,
+    /// 家长控制：隐藏 explicit 内容
+    #[serde(default)]
+    pub hide_explicit: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserRequest {
+    pub password: Option<String>,
+    pub email: Option<String>,
+    pub is_admin: Option<bool>,
+    #[serde(default)]
+    pub roles: RolesBody,
+    /// 0 表示取消限制
+    pub max_bit_rate: Option<i32>,
+    /// 0 表示取消限制
+    pub download_quota_mb: Option<i64>,
+    pub hide_explicit: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolesView {
+    pub settings: bool,
+    pub stream: bool,
+    pub download: bool,
+    pub upload: bool,
+    pub playlist: bool,
+    pub cover_art: bool,
+    pub comment: bool,
+    pub podcast: bool,
+    pub jukebox: bool,
+    pub share: bool,
+}
+
+impl From<UserRoles> for RolesView {
+    fn from(value: UserRoles) -> Self {
+        Self {
+            settings: value.settings,
+            stream: value.stream,
+            download: value.download,
+            upload: value.upload,
+            playlist: value.playlist,
+            cover_art: value.cover_art,
+            comment: value.comment,
+            podcast: value.podcast,
+            jukebox: value.jukebox,
+            share: value.share,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserView {
+    pub id: String,
+    pub username: String,
+    pub name: String,
+    pub email: String,
+    pub is_admin: bool,
+    pub disabled: bool,
+    pub roles: RolesView,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bit_rate: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_quota_mb: Option<i64>,
+    pub hide_explicit: bool,
+    pub last_login_at: String,
+}
+
+impl From<User> for UserView {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id.to_string(),
+            username: user.username,
+            name: user.name,
+            email: user.email,
+            is_admin: user.is_admin,
+            disabled: user.status == UserStatus::Disabled,
+            roles: user.roles.into(),
+            max_bit_rate: user.max_bit_rate,
+            download_quota_mb: user.download_quota_mb,
+            hide_explicit: user.hide_explicit,
+            last_login_at: user.last_login_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPageView {
+    pub users: Vec<UserView>,
+    pub total: u64,
+}
+
+fn user_service(state: &AppState) -> UserAppService {
+    UserAppService::new(
+        Arc::new(UserRepositoryImpl::new(state.db.clone())),
+        Arc::new(UserPreferenceRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+    )
+}
+
+/// 生成登录用的哈希和用于 Subsonic token 认证的加密密码
+fn secure_password(state: &AppState, plain: &str) -> Result<(String, String), HttpResponse> {
+    if plain.is_empty() {
+        return Err(HttpResponse::BadRequest().json(ErrorResponse {
+            error: "Password cannot be empty".to_string(),
+        }));
+    }
+    let internal_error =
+        |e: String| HttpResponse::InternalServerError().json(ErrorResponse { error: e });
+    let hashed = crate::password_hasher(state)
+        .hash(plain)
+        .map_err(|e| internal_error(format!("Failed to hash password: {}", e)))?;
+    let encrypted = Aes256GcmEncryptor::new(&state.app_cfg.password_encryption_key())
+        .map_err(|e| internal_error(format!("Failed to create encryptor: {}", e)))?
+        .encrypt(plain)
+        .map_err(|e| internal_error(format!("Failed to encrypt password: {}", e)))?;
+    Ok((hashed, encrypted))
+}
+
+async fn user_response(state: &AppState, username: &str) -> HttpResponse {
+    match user_service(state).find_user(username).await {
+        Ok(user) => HttpResponse::Ok().json(UserView::from(user)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 分页列出用户
+pub async fn list_users(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<PageQuery>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let offset = query.offset.unwrap_or(0);
+    let size = query
+        .size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    match user_service(&state).list_users(offset, size).await {
+        Ok((users, total)) => HttpResponse::Ok().json(UserPageView {
+            users: users.into_iter().map(UserView::from).collect(),
+            total,
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn get_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    user_response(&state, &path).await
+}
+
+/// 创建用户，可同时指定角色和限制
+pub async fn create_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<CreateUserRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let body = body.into_inner();
+    let username = body.username.trim().to_string();
+    if username.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "Username cannot be empty".to_string(),
+        });
+    }
+    let (password, encrypted_password) = match secure_password(&state, &body.password) {
+        Ok(pair) => pair,
+        Err(rsp) => return rsp,
+    };
+    let cmd = CreateUserCmd {
+        username: username.clone(),
+        password,
+        encrypted_password,
+        email: body.email,
+        is_admin: body.is_admin,
+        roles: UserRolesPatch::from(body.roles).apply(UserRoles::default()),
+        max_bit_rate: body.max_bit_rate,
+        download_quota_mb: body.download_quota_mb,
+        hide_explicit: body.hide_explicit,
+    };
+    if let Err(e) = user_service(&state).create_user(cmd).await {
+        return error_response(e);
+    }
+    user_response(&state, &username).await
+}
+
+/// 更新用户资料、角色和限制，未提供的字段保持不变
+pub async fn update_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<UpdateUserRequest>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let body = body.into_inner();
+    let (password, encrypted_password) = match body.password.as_deref() {
+        Some(plain) => match secure_password(&state, plain) {
+            Ok((hashed, encrypted)) => (Some(hashed), Some(encrypted)),
+            Err(rsp) => return rsp,
+        },
+        None => (None, None),
+    };
+    let cmd = UpdateUserCmd {
+        username: path.to_string(),
+        password,
+        encrypted_password,
+        email: body.email,
+        is_admin: body.is_admin,
+        roles: body.roles.into(),
+        max_bit_rate: body.max_bit_rate,
+        download_quota_mb: body.download_quota_mb,
+        hide_explicit: body.hide_explicit,
+    };
+    if let Err(e) = user_service(&state).update_user(cmd).await {
+        return error_response(e);
+    }
+    user_response(&state, &path).await
+}
+
+pub async fn delete_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    if claims.user_name == *path {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "Cannot delete yourself".to_string(),
+        });
+    }
+    let cmd = DeleteUserCmd {
+        username: path.to_string(),
+    };
+    match user_service(&state).delete_user(cmd).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn set_enabled(
+    req: HttpRequest,
+    state: &AppState,
+    username: &str,
+    enabled: bool,
+) -> HttpResponse {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    if !enabled && claims.user_name == username {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "Cannot disable yourself".to_string(),
+        });
+    }
+    match user_service(state).set_enabled(username, enabled).await {
+        Ok(user) => HttpResponse::Ok().json(UserView::from(user)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 禁用用户，禁用后无法登录
+pub async fn disable_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    set_enabled(req, &state, &path, false).await
+}
+
+pub async fn enable_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    set_enabled(req, &state, &path, true).await
+}
+
+pub async fn get_preferences(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match user_service(&state).get_preferences(&path).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 合并更新偏好设置，值为 null 的键会被删除
+pub async fn update_preferences(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<HashMap<String, Option<String>>>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    match user_service(&state)
+        .update_preferences(&path, body.into_inner())
+        .await
+    {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,278 @@
+use crate::auth::{bad_request, current_claims, resolve_user_id, ErrorResponse};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::media_annotation::{
+    MediaAnnotationService, SyncAnnotationItem, SyncAnnotationsCmd,
+};
+use application::context::AppContext;
+use application::error::AppError;
+use application::query::dao::AnnotationDao;
+use chrono::NaiveDateTime;
+use domain::annotation::Kind;
+use infra::repository::postgres::command::album::AlbumRepositoryImpl;
+use infra::repository::postgres::command::annotation::AnnotationRepositoryImpl;
+use infra::repository::postgres::command::artist::ArtistRepositoryImpl;
+use infra::repository::postgres::command::audio_file::AudioFileRepositoryImpl;
+use infra::repository::postgres::command::player::PlayerRepositoryImpl;
+use infra::repository::postgres::query::annotation::AnnotationDaoImpl;
+use model::annotation::UserAnnotation;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 单次批量写入的条目上限
+const MAX_SYNC_ITEMS: usize = 1000;
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// 注册注解同步原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/annotations", consts::URL_PATH_NATIVE_API))
+            .route("", web::get().to(list_annotations))
+            .route("", web::post().to(sync_annotations)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnotationQuery {
+    /// 上次同步返回的 serverTime，为空时返回全部注解
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationView {
+    /// song、album 或 artist
+    pub kind: &'static str,
+    pub id: String,
+    pub starred: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starred_at: Option<NaiveDateTime>,
+    pub rating: i32,
+    pub play_count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub played_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationListView {
+    /// 作为下次同步的 since
+    pub server_time: NaiveDateTime,
+    pub items: Vec<AnnotationView>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncItemRequest {
+    pub kind: String,
+    pub id: String,
+    pub starred: Option<bool>,
+    pub starred_at: Option<NaiveDateTime>,
+    pub rating: Option<i32>,
+    pub play_count: Option<i32>,
+    pub played_at: Option<NaiveDateTime>,
+    /// 客户端修改时间，服务器上的注解更新得更晚时该条目不会被覆盖
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncRequest {
+    pub items: Vec<SyncItemRequest>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ItemRefView {
+    pub kind: &'static str,
+    pub id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReportView {
+    pub applied: usize,
+    pub unchanged: usize,
+    pub conflicts: Vec<ItemRefView>,
+    pub not_found: Vec<ItemRefView>,
+}
+
+fn parse_kind(raw: &str) -> Option<Kind> {
+    match raw {
+        "song" => Some(Kind::AudioFile),
+        "album" => Some(Kind::Album),
+        "artist" => Some(Kind::Artist),
+        _ => None,
+    }
+}
+
+fn kind_name(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::AudioFile => "song",
+        Kind::Album => "album",
+        Kind::Artist => "artist",
+        Kind::Playlist => "playlist",
+    }
+}
+
+fn item_ref((kind, id): (Kind, i64)) -> ItemRefView {
+    ItemRefView {
+        kind: kind_name(&kind),
+        id: id.to_string(),
+    }
+}
+
+impl From<UserAnnotation> for AnnotationView {
+    fn from(value: UserAnnotation) -> Self {
+        let kind = value.item_kind.parse::<Kind>().unwrap_or_default();
+        Self {
+            kind: kind_name(&kind),
+            id: value.item_id.to_string(),
+            starred: value.starred,
+            starred_at: value.starred.then_some(value.starred_at),
+            rating: value.rating,
+            play_count: value.play_count,
+            played_at: (value.play_count > 0).then_some(value.played_at),
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+fn parse_item(item: SyncItemRequest) -> Result<SyncAnnotationItem, HttpResponse> {
+    let kind = parse_kind(&item.kind)
+        .ok_or_else(|| bad_request(format!("Unknown kind: {}", item.kind)))?;
+    let item_id = item
+        .id
+        .parse::<i64>()
+        .map_err(|_| bad_request(format!("Invalid id: {}", item.id)))?;
+    if let Some(rating) = item.rating {
+        if !(0..=5).contains(&rating) {
+            return Err(bad_request(format!(
+                "Invalid rating of {}: {}",
+                item.id, rating
+            )));
+        }
+    }
+    if item.play_count.is_some_and(|count| count < 0) {
+        return Err(bad_request(format!("Invalid playCount of {}", item.id)));
+    }
+    Ok(SyncAnnotationItem {
+        kind,
+        item_id,
+        starred: item.starred,
+        starred_at: item.starred_at,
+        rating: item.rating,
+        play_count: item.play_count,
+        played_at: item.played_at,
+        updated_at: item.updated_at,
+    })
+}
+
+fn media_annotation_service(state: &AppState) -> MediaAnnotationService {
+    let annotation_repo = Arc::new(AnnotationRepositoryImpl::new(state.db.clone()));
+    MediaAnnotationService::new(
+        annotation_repo.clone(),
+        annotation_repo,
+        Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+        Arc::new(AlbumRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(ArtistRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(PlayerRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+        state.clock.clone(),
+    )
+}
+
+/// 当前用户的全部收藏、评分和播放次数：/api/annotations?since=
+///
+/// 带 since 时只返回之后更新过的注解，取消收藏的条目也会返回（starred 为 false）
+pub async fn list_annotations(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<AnnotationQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let since = match query.since.as_deref().filter(|s| !s.is_empty()) {
+        Some(raw) => match NaiveDateTime::parse_from_str(raw, DATETIME_FORMAT) {
+            Ok(since) => Some(since),
+            Err(_) => return bad_request(format!("Invalid since: {}", raw)),
+        },
+        None => None,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    // 先取时间再查询，查询期间的更新会在下次同步时再返回一次
+    let server_time = chrono::Utc::now().naive_utc();
+    match AnnotationDaoImpl::new(state.db.clone())
+        .get_by_user(user_id, since)
+        .await
+    {
+        Ok(items) => HttpResponse::Ok().json(AnnotationListView {
+            server_time,
+            items: items.into_iter().map(Into::into).collect(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 批量写入收藏、评分和播放次数：POST /api/annotations
+///
+/// 省略的字段保持不变，播放次数只会增加；带 updatedAt 的条目在服务器上有更新的修改时跳过
+pub async fn sync_annotations(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<SyncRequest>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let body = body.into_inner();
+    if body.items.len() > MAX_SYNC_ITEMS {
+        return bad_request(format!("At most {} items per request", MAX_SYNC_ITEMS));
+    }
+    let items = match body
+        .items
+        .into_iter()
+        .map(parse_item)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(items) => items,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let cmd = SyncAnnotationsCmd {
+        user_id: user_id.into(),
+        items,
+    };
+    match media_annotation_service(&state)
+        .sync_annotations(&AppContext::new(), cmd)
+        .await
+    {
+        Ok(report) => HttpResponse::Ok().json(SyncReportView {
+            applied: report.applied,
+            unchanged: report.unchanged,
+            conflicts: report.conflicts.into_iter().map(item_ref).collect(),
+            not_found: report.not_found.into_iter().map(item_ref).collect(),
+        }),
+        Err(AppError::AnnotationError(e)) => bad_request(e.to_string()),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,324 @@
+pub mod albums;
+pub mod artists;
+pub mod assets;
+pub mod audiobooks;
+pub mod home;
+pub mod libraries;
+pub mod playlists;
+pub mod songs;
+pub mod users;
+
+use crate::auth::{bad_request, ErrorResponse};
+use crate::consts;
+use actix_web::{web, HttpResponse};
+use application::query::QueryError;
+use model::shared::Contributor;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+
+const DEFAULT_PAGE_LIMIT: i32 = 50;
+const MAX_PAGE_LIMIT: i32 = 500;
+
+/// role 过滤可用的参与者角色
+const ROLES: [&str; 9] = [
+    "artist",
+    "albumartist",
+    "performer",
+    "composer",
+    "lyricist",
+    "conductor",
+    "arranger",
+    "producer",
+    "remixer",
+];
+
+/// 注册 v1 资源路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/v1", consts::URL_PATH_NATIVE_API))
+            .configure(albums::configure_routes)
+            .configure(artists::configure_routes)
+            .configure(assets::configure_routes)
+            .configure(audiobooks::configure_routes)
+            .configure(home::configure_routes)
+            .configure(libraries::configure_routes)
+            .configure(playlists::configure_routes)
+            .configure(songs::configure_routes)
+            .configure(users::configure_routes),
+    );
+}
+
+/// OpenAPI 文档不需要认证，需注册在 JWT scope 之前
+pub fn configure_public_service(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        &format!("{}/v1/openapi.json", consts::URL_PATH_NATIVE_API),
+        web::get().to(get_openapi),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        albums::list_albums,
+        albums::get_album,
+        albums::get_album_works,
+        artists::list_artists,
+        artists::get_artist,
+        artists::get_missing_albums,
+        artists::refresh_missing_albums,
+        songs::list_songs,
+        songs::get_song,
+        songs::get_song_chapters,
+        playlists::list_playlists,
+        playlists::get_playlist,
+        users::list_users,
+        users::get_user,
+        libraries::list_libraries,
+        libraries::get_library,
+        audiobooks::list_in_progress,
+        home::get_home,
+        assets::list_album_assets,
+        assets::list_artist_assets,
+        assets::get_asset_content,
+    ),
+    components(schemas(
+        ErrorResponse,
+        albums::AlbumView,
+        albums::AlbumDetailView,
+        albums::WorkView,
+        artists::ArtistView,
+        artists::ArtistDetailView,
+        artists::ReleaseGroupView,
+        artists::MissingAlbumsView,
+        songs::SongView,
+        songs::ChapterView,
+        songs::AudibleRangeView,
+        ContributorView,
+        playlists::PlaylistView,
+        playlists::PlaylistDetailView,
+        playlists::PlaylistEntryView,
+        users::UserView,
+        libraries::LibraryView,
+        audiobooks::AudiobookProgressView,
+        home::HomeRowView,
+        home::HomeView,
+        assets::MediaAssetView,
+        AlbumPage,
+        ArtistPage,
+        SongPage,
+        PlaylistPage,
+        UserPage,
+        LibraryPage,
+    )),
+    modifiers(&ApiInfo, &BearerAuth),
+    security(("bearer_auth" = [])),
+    tags(
+        (name = "albums"),
+        (name = "artists"),
+        (name = "songs"),
+        (name = "playlists"),
+        (name = "users"),
+        (name = "libraries"),
+        (name = "audiobooks"),
+        (name = "home"),
+        (name = "assets"),
+    )
+)]
+pub struct ApiDoc;
+
+/// 标题和版本跟随应用，而不是 server crate
+struct ApiInfo;
+
+impl Modify for ApiInfo {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi.info.title = consts::APP_NAME.to_string();
+        openapi.info.version = consts::VERSION.to_string();
+    }
+}
+
+/// 与登录接口返回的 JWT 一致，也可以通过 X-ND-Authorization 头传递
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let mut scheme = Http::new(HttpAuthScheme::Bearer);
+        scheme.bearer_format = Some("JWT".to_string());
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme("bearer_auth", SecurityScheme::Http(scheme));
+    }
+}
+
+static OPENAPI: Lazy<utoipa::openapi::OpenApi> = Lazy::new(ApiDoc::openapi);
+
+/// GET /api/v1/openapi.json
+pub async fn get_openapi() -> HttpResponse {
+    HttpResponse::Ok().json(&*OPENAPI)
+}
+
+/// 所有列表接口统一的分页结构
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[aliases(
+    AlbumPage = Page<albums::AlbumView>,
+    ArtistPage = Page<artists::ArtistView>,
+    SongPage = Page<songs::SongView>,
+    PlaylistPage = Page<playlists::PlaylistView>,
+    UserPage = Page<users::UserView>,
+    LibraryPage = Page<libraries::LibraryView>,
+)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 满足条件的总数
+    pub total: i64,
+    pub offset: i32,
+    pub limit: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Paging {
+    pub offset: i32,
+    pub limit: i32,
+}
+
+impl Paging {
+    pub(crate) fn new(offset: Option<i32>, limit: Option<i32>) -> Result<Self, HttpResponse> {
+        let offset = offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(bad_request(format!("Invalid offset: {}", offset)));
+        }
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+        Ok(Self { offset, limit })
+    }
+
+    /// 数据库已经分页，只需包装
+    pub(crate) fn page<T, V: From<T>>(self, items: Vec<T>, total: i64) -> Page<V> {
+        Page {
+            items: items.into_iter().map(V::from).collect(),
+            total,
+            offset: self.offset,
+            limit: self.limit,
+        }
+    }
+
+    /// 在内存中过滤排序后的完整列表，由这里截取当前页
+    pub(crate) fn slice<T, V: From<T>>(self, items: Vec<T>) -> Page<V> {
+        let total = items.len() as i64;
+        let items = items
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(self.limit as usize)
+            .map(V::from)
+            .collect();
+        Page {
+            items,
+            total,
+            offset: self.offset,
+            limit: self.limit,
+        }
+    }
+}
+
+/// 歌曲或专辑的参与者
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributorView {
+    pub artist_id: String,
+    pub artist: String,
+    /// 小写角色名，如 artist、composer、conductor
+    pub role: String,
+    /// 演奏者的乐器等
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_role: Option<String>,
+}
+
+impl From<&Contributor> for ContributorView {
+    fn from(contributor: &Contributor) -> Self {
+        Self {
+            artist_id: contributor.artist_id.to_string(),
+            artist: contributor.artist_name.clone(),
+            role: contributor.role.to_lowercase(),
+            sub_role: contributor.sub_role.clone(),
+        }
+    }
+}
+
+/// 校验 role 参数，返回小写角色名
+pub(crate) fn parse_role(raw: &str) -> Result<String, HttpResponse> {
+    let role = raw.trim().to_lowercase();
+    if !ROLES.contains(&role.as_str()) {
+        return Err(bad_request(format!(
+            "Unknown role: {} (allowed: {})",
+            raw,
+            ROLES.join(", ")
+        )));
+    }
+    Ok(role)
+}
+
+/// 艺术家以指定角色参与
+pub(crate) fn has_role(contributors: &[Contributor], artist_id: i64, role: &str) -> bool {
+    contributors
+        .iter()
+        .any(|c| c.artist_id == artist_id && c.role.to_lowercase() == role)
+}
+
+/// 排序参数：字段名，前缀 `-` 表示降序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Sort<'a> {
+    pub field: &'a str,
+    pub descending: bool,
+}
+
+pub(crate) fn parse_sort<'a>(
+    raw: Option<&'a str>,
+    allowed: &[&str],
+    default: &'a str,
+) -> Result<Sort<'a>, HttpResponse> {
+    let raw = raw.filter(|s| !s.is_empty()).unwrap_or(default);
+    let (field, descending) = match raw.strip_prefix('-') {
+        Some(field) => (field, true),
+        None => (raw, false),
+    };
+    if !allowed.contains(&field) {
+        return Err(bad_request(format!(
+            "Unknown sort field: {} (allowed: {})",
+            field,
+            allowed.join(", ")
+        )));
+    }
+    Ok(Sort { field, descending })
+}
+
+pub(crate) fn not_found(error: String) -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse { error })
+}
+
+pub(crate) fn internal_error(error: String) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ErrorResponse { error })
+}
+
+pub(crate) fn query_error(e: QueryError) -> HttpResponse {
+    match e {
+        QueryError::InvalidInput(e)
+        | QueryError::InvalidParameter(e)
+        | QueryError::NotSupported(e) => bad_request(e),
+        QueryError::NotFound(e) => not_found(e),
+        e => internal_error(e.to_string()),
+    }
+}
+
+/// 与其他原生 API 一致的时间格式
+pub(crate) fn format_datetime(value: chrono::NaiveDateTime) -> String {
+    value.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// 秒级时间戳
+pub(crate) fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| format_datetime(dt.naive_utc()))
+        .unwrap_or_default()
+}
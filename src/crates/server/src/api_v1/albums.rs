@@ -0,0 +1,327 @@
+use super::songs::SongView;
+use super::{
+    format_datetime, has_role, not_found, parse_role, parse_sort, query_error, ContributorView,
+    Page, Paging,
+};
+use crate::auth::{bad_request, current_claims, parse_id, resolve_user_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::query::dao::{AlbumDao, AudioFileDao};
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use model::album::Album;
+use model::audio_file::AudioFile;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// 专辑的排序由查询决定，不支持 `-` 反转
+const SORT_FIELDS: [&str; 8] = [
+    "name",
+    "artist",
+    "newest",
+    "recent",
+    "frequent",
+    "rating",
+    "random",
+    "originalYear",
+];
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/albums", web::get().to(list_albums))
+        .route("/albums/{id}", web::get().to(get_album))
+        .route("/albums/{id}/works", web::get().to(get_album_works));
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct AlbumListQuery {
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+    /// name（默认）、artist、newest、recent、frequent、rating、random 或 originalYear，
+    /// 不能与过滤条件同时使用
+    pub sort: Option<String>,
+    /// 按流派过滤
+    pub genre: Option<String>,
+    /// 按年份范围过滤（含）
+    pub from_year: Option<i32>,
+    pub to_year: Option<i32>,
+    /// 只返回当前用户收藏的专辑
+    pub starred: Option<bool>,
+    /// 按参与的艺术家过滤
+    pub artist_id: Option<String>,
+    /// 与 artistId 一起使用，只返回该艺术家以此角色参与的专辑，如 composer、conductor
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumView {
+    pub id: String,
+    pub name: String,
+    pub artist: String,
+    pub artist_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    /// 原始发行年份，再版专辑早于 year
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    pub song_count: i32,
+    /// 秒
+    pub duration: i64,
+    pub play_count: i32,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<ContributorView>,
+}
+
+impl From<Album> for AlbumView {
+    fn from(album: Album) -> Self {
+        Self {
+            id: album.id.to_string(),
+            name: album.name,
+            artist: album.artist.name,
+            artist_id: album.artist.id.to_string(),
+            year: album.year,
+            original_year: album.original_year,
+            genre: album.genre.map(|genre| genre.name),
+            song_count: album.song_count,
+            duration: album.duration,
+            play_count: album.annotation.play_count,
+            created_at: format_datetime(album.created_at),
+            contributors: album.contributors.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumDetailView {
+    #[serde(flatten)]
+    pub album: AlbumView,
+    pub songs: Vec<SongView>,
+}
+
+/// 专辑中的一部作品；不属于任何作品的歌曲单独成组，name 为空
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkView {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub movement_count: Option<i32>,
+    pub songs: Vec<SongView>,
+}
+
+/// 分页列出专辑，genre、年份范围、starred 和 artistId 每次只能使用一种
+#[utoipa::path(
+    get,
+    path = "/api/v1/albums",
+    tag = "albums",
+    params(AlbumListQuery),
+    responses(
+        (status = 200, description = "Album page", body = super::AlbumPage),
+        (status = 400, description = "Invalid paging, filter or sort", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_albums(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<AlbumListQuery>,
+) -> HttpResponse {
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let by_year = query.from_year.is_some() || query.to_year.is_some();
+    let starred = query.starred.unwrap_or(false);
+    let filters = [
+        query.genre.is_some(),
+        by_year,
+        starred,
+        query.artist_id.is_some(),
+    ]
+    .into_iter()
+    .filter(|on| *on)
+    .count();
+    if filters > 1 {
+        return bad_request(
+            "Only one of genre, fromYear/toYear, starred and artistId can be used".into(),
+        );
+    }
+    if filters == 1 && query.sort.is_some() {
+        return bad_request("sort cannot be combined with filters".into());
+    }
+    let sort = match parse_sort(query.sort.as_deref(), &SORT_FIELDS, "name") {
+        Ok(sort) if sort.descending => {
+            return bad_request("Albums do not support descending sort".into())
+        }
+        Ok(sort) => sort,
+        Err(rsp) => return rsp,
+    };
+
+    let dao = AlbumDaoImpl::new(state.db.clone());
+    if let Some(raw_id) = &query.artist_id {
+        let artist_id = match parse_id(raw_id) {
+            Ok(id) => id,
+            Err(rsp) => return rsp,
+        };
+        let role = match query.role.as_deref().map(parse_role).transpose() {
+            Ok(role) => role,
+            Err(rsp) => return rsp,
+        };
+        return match dao.get_by_artist_id(artist_id).await {
+            Ok(mut albums) => {
+                if let Some(role) = &role {
+                    albums.retain(|album| has_role(&album.contributors, artist_id, role));
+                }
+                let page: Page<AlbumView> = paging.slice(albums);
+                HttpResponse::Ok().json(page)
+            }
+            Err(e) => query_error(e),
+        };
+    }
+    if query.role.is_some() {
+        return bad_request("role requires artistId".into());
+    }
+    let (offset, limit) = (paging.offset, paging.limit);
+    let result = if let Some(genre) = &query.genre {
+        dao.get_by_genre(genre, offset, limit).await
+    } else if by_year {
+        let from = query.from_year.unwrap_or(0);
+        let to = query.to_year.unwrap_or(9999);
+        dao.get_by_year(from, to, offset, limit).await
+    } else if starred {
+        let user_id = match current_claims(&req) {
+            Ok(claims) => match resolve_user_id(&state, &claims).await {
+                Ok(id) => id,
+                Err(rsp) => return rsp,
+            },
+            Err(rsp) => return rsp,
+        };
+        dao.get_by_starred(user_id, offset, limit).await
+    } else {
+        match sort.field {
+            "artist" => dao.get_by_artist(offset, limit).await,
+            "newest" => dao.get_by_newest(offset, limit).await,
+            "recent" => dao.get_by_recent(offset, limit).await,
+            "frequent" => dao.get_by_frequent(offset, limit).await,
+            "rating" => dao.get_by_rating(offset, limit).await,
+            "random" => dao.get_by_random(offset, limit).await,
+            "originalYear" => dao.get_by_original_year(offset, limit).await,
+            _ => dao.get_by_name(offset, limit).await,
+        }
+    };
+    match result {
+        Ok((albums, total)) => {
+            let page: Page<AlbumView> = paging.page(albums, total);
+            HttpResponse::Ok().json(page)
+        }
+        Err(e) => query_error(e),
+    }
+}
+
+/// 专辑详情，包含按碟号和音轨排序的歌曲
+#[utoipa::path(
+    get,
+    path = "/api/v1/albums/{id}",
+    tag = "albums",
+    params(("id" = String, Path, description = "Album id")),
+    responses(
+        (status = 200, description = "Album with songs", body = AlbumDetailView),
+        (status = 404, description = "Album not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_album(state: web::Data<AppState>, path: web::Path<String>) -> HttpResponse {
+    let album_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let album = match AlbumDaoImpl::new(state.db.clone())
+        .get_by_id(album_id)
+        .await
+    {
+        Ok(Some(album)) => album,
+        Ok(None) => return not_found(format!("Album not found: {}", album_id)),
+        Err(e) => return query_error(e),
+    };
+    let mut songs = match AudioFileDaoImpl::new(state.db.clone())
+        .get_by_album_id(album_id)
+        .await
+    {
+        Ok(songs) => songs,
+        Err(e) => return query_error(e),
+    };
+    songs.sort_by_key(|song| (song.disc_number, song.track_number));
+    HttpResponse::Ok().json(AlbumDetailView {
+        album: album.into(),
+        songs: songs.into_iter().map(SongView::from).collect(),
+    })
+}
+
+/// 按作品分组的专辑歌曲，作品按首次出现的位置排列，组内按乐章序号排序
+#[utoipa::path(
+    get,
+    path = "/api/v1/albums/{id}/works",
+    tag = "albums",
+    params(("id" = String, Path, description = "Album id")),
+    responses(
+        (status = 200, description = "Album songs grouped by work", body = Vec<WorkView>),
+        (status = 404, description = "Album not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_album_works(state: web::Data<AppState>, path: web::Path<String>) -> HttpResponse {
+    let album_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match AlbumDaoImpl::new(state.db.clone())
+        .get_by_id(album_id)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found(format!("Album not found: {}", album_id)),
+        Err(e) => return query_error(e),
+    }
+    let mut songs = match AudioFileDaoImpl::new(state.db.clone())
+        .get_by_album_id(album_id)
+        .await
+    {
+        Ok(songs) => songs,
+        Err(e) => return query_error(e),
+    };
+    songs.sort_by_key(|song| (song.disc_number, song.track_number));
+
+    let mut groups: Vec<(Option<String>, Vec<AudioFile>)> = Vec::new();
+    for song in songs {
+        let work = song.work.clone();
+        match groups
+            .iter_mut()
+            .find(|(name, _)| name.is_some() && *name == work)
+        {
+            Some((_, members)) => members.push(song),
+            None => groups.push((work, vec![song])),
+        }
+    }
+
+    let works: Vec<WorkView> = groups
+        .into_iter()
+        .map(|(name, mut members)| {
+            members.sort_by_key(|song| {
+                (
+                    song.movement_number.unwrap_or(i32::MAX),
+                    song.disc_number,
+                    song.track_number,
+                )
+            });
+            WorkView {
+                movement_count: members.iter().find_map(|song| song.movement_count),
+                name,
+                songs: members.into_iter().map(SongView::from).collect(),
+            }
+        })
+        .collect();
+    HttpResponse::Ok().json(works)
+}
@@ -0,0 +1,269 @@
+use super::albums::AlbumView;
+use super::{format_datetime, not_found, parse_sort, query_error, Page, Paging};
+use crate::auth::{parse_id, ErrorResponse};
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+use application::query::dao::{AlbumDao, ArtistDao};
+use application::query::get_missing_albums::{GetMissingAlbums, MissingAlbums};
+use application::query::QueryError;
+use infra::repository::postgres::command::artist_discography::ArtistDiscographyStoreImpl;
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::artist::ArtistDaoImpl;
+use model::artist::Artist;
+use model::discography::ReleaseGroup;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+const SORT_FIELDS: [&str; 3] = ["name", "albumCount", "songCount"];
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/artists", web::get().to(list_artists))
+        .route("/artists/{id}", web::get().to(get_artist))
+        .route(
+            "/artists/{id}/missing-albums",
+            web::get().to(get_missing_albums),
+        )
+        .route(
+            "/artists/{id}/missing-albums/refresh",
+            web::post().to(refresh_missing_albums),
+        );
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ArtistListQuery {
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+    /// name（默认）、albumCount 或 songCount，前缀 `-` 表示降序
+    pub sort: Option<String>,
+    /// 名称包含该字符串（不区分大小写）
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtistView {
+    pub id: String,
+    pub name: String,
+    pub album_count: i32,
+    pub song_count: i32,
+    pub play_count: i32,
+}
+
+impl From<Artist> for ArtistView {
+    fn from(artist: Artist) -> Self {
+        Self {
+            id: artist.id.to_string(),
+            name: artist.name,
+            album_count: artist.album_count,
+            song_count: artist.song_count,
+            play_count: artist.played_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtistDetailView {
+    #[serde(flatten)]
+    pub artist: ArtistView,
+    pub albums: Vec<AlbumView>,
+}
+
+/// 分页列出艺术家
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists",
+    tag = "artists",
+    params(ArtistListQuery),
+    responses(
+        (status = 200, description = "Artist page", body = super::ArtistPage),
+        (status = 400, description = "Invalid paging or sort", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_artists(
+    state: web::Data<AppState>,
+    query: web::Query<ArtistListQuery>,
+) -> HttpResponse {
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let sort = match parse_sort(query.sort.as_deref(), &SORT_FIELDS, "name") {
+        Ok(sort) => sort,
+        Err(rsp) => return rsp,
+    };
+    let mut artists = match ArtistDaoImpl::new(state.db.clone()).get_all().await {
+        Ok(artists) => artists,
+        Err(e) => return query_error(e),
+    };
+    if let Some(q) = query.q.as_deref().map(str::to_lowercase) {
+        artists.retain(|artist| artist.name.to_lowercase().contains(&q));
+    }
+    match sort.field {
+        "albumCount" => artists.sort_by_key(|artist| artist.album_count),
+        "songCount" => artists.sort_by_key(|artist| artist.song_count),
+        _ => artists.sort_by(|a, b| a.order_name.cmp(&b.order_name)),
+    }
+    if sort.descending {
+        artists.reverse();
+    }
+    let page: Page<ArtistView> = paging.slice(artists);
+    HttpResponse::Ok().json(page)
+}
+
+/// 艺术家详情，包含其专辑
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists/{id}",
+    tag = "artists",
+    params(("id" = String, Path, description = "Artist id")),
+    responses(
+        (status = 200, description = "Artist with albums", body = ArtistDetailView),
+        (status = 404, description = "Artist not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_artist(state: web::Data<AppState>, path: web::Path<String>) -> HttpResponse {
+    let artist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let artist = match ArtistDaoImpl::new(state.db.clone())
+        .get_by_id(artist_id)
+        .await
+    {
+        Ok(Some(artist)) => artist,
+        Ok(None) => return not_found(format!("Artist not found: {}", artist_id)),
+        Err(e) => return query_error(e),
+    };
+    let mut albums = match AlbumDaoImpl::new(state.db.clone())
+        .get_by_artist_id(artist_id)
+        .await
+    {
+        Ok(albums) => albums,
+        Err(e) => return query_error(e),
+    };
+    albums.sort_by_key(|album| album.year);
+    HttpResponse::Ok().json(ArtistDetailView {
+        artist: artist.into(),
+        albums: albums.into_iter().map(AlbumView::from).collect(),
+    })
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseGroupView {
+    /// MusicBrainz 发行组 ID
+    pub mbz_id: String,
+    pub title: String,
+    pub primary_type: Option<String>,
+    pub first_release_date: Option<String>,
+}
+
+impl From<ReleaseGroup> for ReleaseGroupView {
+    fn from(group: ReleaseGroup) -> Self {
+        Self {
+            mbz_id: group.mbz_id,
+            title: group.title,
+            primary_type: group.primary_type,
+            first_release_date: group.first_release_date,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingAlbumsView {
+    pub artist_id: String,
+    /// 未在 MusicBrainz 找到艺术家时为空
+    pub mbz_artist_id: Option<String>,
+    /// 参与比较的发行组数
+    pub release_group_count: usize,
+    pub missing: Vec<ReleaseGroupView>,
+    /// 发行组列表的获取时间
+    pub fetched_at: String,
+}
+
+impl From<MissingAlbums> for MissingAlbumsView {
+    fn from(albums: MissingAlbums) -> Self {
+        Self {
+            artist_id: albums.artist_id.to_string(),
+            mbz_artist_id: albums.mbz_artist_id,
+            release_group_count: albums.release_group_count,
+            missing: albums
+                .missing
+                .into_iter()
+                .map(ReleaseGroupView::from)
+                .collect(),
+            fetched_at: format_datetime(albums.fetched_at),
+        }
+    }
+}
+
+async fn missing_albums(state: &AppState, path: &str, refresh: bool) -> HttpResponse {
+    let artist_id = match parse_id(path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let Some(musicbrainz) = state.musicbrainz.clone() else {
+        return not_found("MusicBrainz lookup is disabled".to_string());
+    };
+    let musicbrainz_cfg = state.app_cfg.musicbrainz();
+    let usecase = GetMissingAlbums::new(
+        Arc::new(ArtistDaoImpl::new(state.db.clone())),
+        Arc::new(AlbumDaoImpl::new(state.db.clone())),
+        Arc::new(ArtistDiscographyStoreImpl::new(state.db.clone())),
+        musicbrainz,
+        musicbrainz_cfg.release_types,
+        musicbrainz_cfg.cache_ttl,
+    );
+    match usecase.handle(artist_id, refresh).await {
+        Ok(albums) => HttpResponse::Ok().json(MissingAlbumsView::from(albums)),
+        // 访问 MusicBrainz 失败
+        Err(QueryError::ExecutionError(error)) => {
+            log::warn!("Failed to get missing albums of {}: {}", artist_id, error);
+            HttpResponse::BadGateway().json(ErrorResponse { error })
+        }
+        Err(e) => query_error(e),
+    }
+}
+
+/// MusicBrainz 中有、库中没有的专辑，发行组列表按配置缓存
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists/{id}/missing-albums",
+    tag = "artists",
+    params(("id" = String, Path, description = "Artist id")),
+    responses(
+        (status = 200, description = "Release groups missing from the library", body = MissingAlbumsView),
+        (status = 404, description = "Artist not found or MusicBrainz lookup disabled", body = crate::auth::ErrorResponse),
+        (status = 502, description = "MusicBrainz request failed", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_missing_albums(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    missing_albums(&state, &path, false).await
+}
+
+/// 忽略缓存，重新从 MusicBrainz 获取发行组列表
+#[utoipa::path(
+    post,
+    path = "/api/v1/artists/{id}/missing-albums/refresh",
+    tag = "artists",
+    params(("id" = String, Path, description = "Artist id")),
+    responses(
+        (status = 200, description = "Release groups missing from the library", body = MissingAlbumsView),
+        (status = 404, description = "Artist not found or MusicBrainz lookup disabled", body = crate::auth::ErrorResponse),
+        (status = 502, description = "MusicBrainz request failed", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn refresh_missing_albums(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    missing_albums(&state, &path, true).await
+}
@@ -0,0 +1,196 @@
+use super::{format_datetime, internal_error, not_found, query_error};
+use crate::auth::parse_id;
+use crate::AppState;
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+use application::command::media_parse::StorageClientFactory;
+use application::query::dao::{AlbumDao, ArtistDao, MediaAssetDao};
+use domain::value::MediaPath;
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::artist::ArtistDaoImpl;
+use infra::repository::postgres::query::media_asset::MediaAssetDaoImpl;
+use model::media_asset::MediaAsset;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/albums/{id}/assets", web::get().to(list_album_assets))
+        .route("/artists/{id}/assets", web::get().to(list_artist_assets))
+        .route("/assets/{id}", web::get().to(get_asset_content));
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaAssetView {
+    pub id: String,
+    /// booklet、artist_image、logo 或 image
+    pub kind: String,
+    /// 文件名
+    pub name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub updated_at: String,
+}
+
+impl From<MediaAsset> for MediaAssetView {
+    fn from(asset: MediaAsset) -> Self {
+        Self {
+            id: asset.id.to_string(),
+            content_type: content_type(&asset.path).to_string(),
+            name: file_name(&asset.path).to_string(),
+            kind: asset.kind,
+            size: asset.size,
+            updated_at: format_datetime(asset.mtime),
+        }
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+fn content_type(path: &str) -> &'static str {
+    let suffix = path
+        .rsplit_once('.')
+        .map(|(_, suffix)| suffix.to_lowercase())
+        .unwrap_or_default();
+    match suffix.as_str() {
+        "pdf" => "application/pdf",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+fn to_views(assets: Vec<MediaAsset>) -> HttpResponse {
+    HttpResponse::Ok().json(
+        assets
+            .into_iter()
+            .map(MediaAssetView::from)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// 专辑目录中的小册子和图片，按路径排序
+#[utoipa::path(
+    get,
+    path = "/api/v1/albums/{id}/assets",
+    tag = "assets",
+    params(("id" = String, Path, description = "Album id")),
+    responses(
+        (status = 200, description = "Booklets and images found next to the album", body = [MediaAssetView]),
+        (status = 404, description = "Album not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_album_assets(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let album_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match AlbumDaoImpl::new(state.db.clone())
+        .get_by_id(album_id)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found(format!("Album not found: {}", album_id)),
+        Err(e) => return query_error(e),
+    }
+    match MediaAssetDaoImpl::new(state.db.clone())
+        .get_by_album(album_id)
+        .await
+    {
+        Ok(assets) => to_views(assets),
+        Err(e) => query_error(e),
+    }
+}
+
+/// 艺术家图片和标志，艺术家目录中的排在专辑目录前面
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists/{id}/assets",
+    tag = "assets",
+    params(("id" = String, Path, description = "Artist id")),
+    responses(
+        (status = 200, description = "Artist images and logos", body = [MediaAssetView]),
+        (status = 404, description = "Artist not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_artist_assets(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let artist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match ArtistDaoImpl::new(state.db.clone())
+        .get_by_id(artist_id)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found(format!("Artist not found: {}", artist_id)),
+        Err(e) => return query_error(e),
+    }
+    match MediaAssetDaoImpl::new(state.db.clone())
+        .get_by_artist(artist_id)
+        .await
+    {
+        Ok(assets) => to_views(assets),
+        Err(e) => query_error(e),
+    }
+}
+
+/// 资源文件内容
+#[utoipa::path(
+    get,
+    path = "/api/v1/assets/{id}",
+    tag = "assets",
+    params(("id" = String, Path, description = "Asset id")),
+    responses(
+        (status = 200, description = "Asset file content"),
+        (status = 404, description = "Asset not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_asset_content(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let asset_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let asset = match MediaAssetDaoImpl::new(state.db.clone())
+        .get_by_id(asset_id)
+        .await
+    {
+        Ok(Some(asset)) => asset,
+        Ok(None) => return not_found(format!("Asset not found: {}", asset_id)),
+        Err(e) => return query_error(e),
+    };
+    let media_path = MediaPath::new(asset.protocol.clone(), asset.path.clone());
+    let data = match crate::storage_client_factory(&state)
+        .create(&media_path)
+        .await
+    {
+        Ok(client) => client.read(&media_path).await,
+        Err(e) => Err(e),
+    };
+    match data {
+        Ok(data) => HttpResponse::Ok()
+            .content_type(content_type(&asset.path))
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", file_name(&asset.path)),
+            ))
+            .body(data),
+        Err(e) => {
+            log::warn!("Failed to read asset {}: {}", asset.path, e);
+            internal_error(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use super::songs::SongView;
+use super::{format_datetime, query_error};
+use crate::auth::{current_claims, resolve_user_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::query::get_bookmarks::GetBookmarks;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use infra::repository::postgres::query::bookmark::BookmarkDaoImpl;
+use model::bookmark::AudiobookProgress;
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/audiobooks/in-progress", web::get().to(list_in_progress));
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AudiobookProgressView {
+    pub album_id: String,
+    pub album: String,
+    pub artist: String,
+    /// 书签所在的章节
+    pub current: SongView,
+    /// 章节内的位置（毫秒）
+    pub position: i64,
+    pub chapter_count: i32,
+    /// 全书时长（秒）
+    pub duration: i64,
+    /// 已听时长（秒）
+    pub elapsed: i64,
+    /// 剩余时长（秒）
+    pub remaining: i64,
+    pub updated_at: String,
+}
+
+impl From<AudiobookProgress> for AudiobookProgressView {
+    fn from(book: AudiobookProgress) -> Self {
+        Self {
+            album_id: book.album_id.to_string(),
+            album: book.album,
+            artist: book.artist,
+            current: SongView::from(book.current),
+            position: book.position,
+            chapter_count: book.chapter_count,
+            duration: book.duration,
+            elapsed: book.elapsed,
+            remaining: book.remaining,
+            updated_at: format_datetime(book.updated_at),
+        }
+    }
+}
+
+/// 当前用户正在收听的有声书，最近收听的在前
+#[utoipa::path(
+    get,
+    path = "/api/v1/audiobooks/in-progress",
+    tag = "audiobooks",
+    responses(
+        (status = 200, description = "Audiobooks with remaining time", body = [AudiobookProgressView]),
+    )
+)]
+pub async fn list_in_progress(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let user_id = match current_claims(&req) {
+        Ok(claims) => match resolve_user_id(&state, &claims).await {
+            Ok(id) => id,
+            Err(rsp) => return rsp,
+        },
+        Err(rsp) => return rsp,
+    };
+    let get_bookmarks = GetBookmarks::new(
+        Arc::new(BookmarkDaoImpl::new(state.db.clone())),
+        Arc::new(AudioFileDaoImpl::new(state.db.clone())),
+    );
+    match get_bookmarks.in_progress_audiobooks(user_id).await {
+        Ok(books) => HttpResponse::Ok().json(
+            books
+                .into_iter()
+                .map(AudiobookProgressView::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => query_error(e),
+    }
+}
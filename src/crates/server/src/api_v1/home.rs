@@ -0,0 +1,83 @@
+use super::albums::AlbumView;
+use super::query_error;
+use crate::auth::{current_claims, resolve_user_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::query::get_home::{GetHome, HomeRow};
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::home::HomeDaoImpl;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_ROW_SIZE: i32 = 12;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/home", web::get().to(get_home));
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HomeQuery {
+    /// 每行的专辑数，默认 12，最多 50
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeRowView {
+    /// recentlyPlayed、newForYou、rediscover 或 similarArtists
+    pub id: String,
+    pub albums: Vec<AlbumView>,
+}
+
+impl From<HomeRow> for HomeRowView {
+    fn from(row: HomeRow) -> Self {
+        Self {
+            id: row.kind.as_str().to_string(),
+            albums: row.albums.into_iter().map(AlbumView::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeView {
+    pub rows: Vec<HomeRowView>,
+}
+
+/// 当前用户的首页推荐：最近播放、常听流派的新专辑、很久没听的专辑和相似艺术家的专辑
+#[utoipa::path(
+    get,
+    path = "/api/v1/home",
+    tag = "home",
+    params(HomeQuery),
+    responses(
+        (status = 200, description = "Personalized album rows, empty rows are left out", body = HomeView),
+    )
+)]
+pub async fn get_home(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<HomeQuery>,
+) -> HttpResponse {
+    let user_id = match current_claims(&req) {
+        Ok(claims) => match resolve_user_id(&state, &claims).await {
+            Ok(id) => id,
+            Err(rsp) => return rsp,
+        },
+        Err(rsp) => return rsp,
+    };
+    let get_home = GetHome::new(
+        Arc::new(HomeDaoImpl::new(state.db.clone())),
+        Arc::new(AlbumDaoImpl::new(state.db.clone())),
+        state.home_cache.clone(),
+    );
+    let limit = query.limit.unwrap_or(DEFAULT_ROW_SIZE);
+    match get_home.handle(user_id, limit).await {
+        Ok(rows) => HttpResponse::Ok().json(HomeView {
+            rows: rows.into_iter().map(HomeRowView::from).collect(),
+        }),
+        Err(e) => query_error(e),
+    }
+}
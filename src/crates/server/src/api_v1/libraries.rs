@@ -0,0 +1,149 @@
+use super::{format_datetime, internal_error, not_found, Page, Paging};
+use crate::auth::{current_claims, parse_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use domain::library::{Library, LibraryRepository, ScanStatus};
+use infra::repository::postgres::command::library::LibraryRepositoryImpl;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/libraries", web::get().to(list_libraries))
+        .route("/libraries/{id}", web::get().to(get_library));
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct LibraryListQuery {
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+    /// 同时返回已停用的库，仅管理员可用
+    pub include_disabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryView {
+    pub id: String,
+    pub name: String,
+    /// 存储位置只对管理员返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub enabled: bool,
+    /// music 或 audiobook
+    pub kind: String,
+    pub scanning: bool,
+    pub last_scan_at: String,
+}
+
+impl LibraryView {
+    fn new(library: Library, is_admin: bool) -> Self {
+        let (protocol, path) = if is_admin {
+            (Some(library.path.protocol), Some(library.path.path))
+        } else {
+            (None, None)
+        };
+        Self {
+            id: library.id.to_string(),
+            name: library.name,
+            protocol,
+            path,
+            enabled: library.enabled,
+            kind: library.kind.as_str().to_string(),
+            scanning: library.scan_status == ScanStatus::Scanning,
+            last_scan_at: format_datetime(library.last_scan_at),
+        }
+    }
+}
+
+/// 按名称列出音乐库
+#[utoipa::path(
+    get,
+    path = "/api/v1/libraries",
+    tag = "libraries",
+    params(LibraryListQuery),
+    responses(
+        (status = 200, description = "Library page", body = super::LibraryPage),
+        (status = 400, description = "Invalid paging", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_libraries(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<LibraryListQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let mut libraries = match LibraryRepositoryImpl::new(state.db.clone())
+        .find_all()
+        .await
+    {
+        Ok(libraries) => libraries,
+        Err(e) => return internal_error(e.to_string()),
+    };
+    if !(claims.is_admin && query.include_disabled.unwrap_or(false)) {
+        libraries.retain(|library| library.enabled);
+    }
+    libraries.sort_by_key(|library| library.name.to_lowercase());
+    let page: Page<Library> = paging.slice(libraries);
+    HttpResponse::Ok().json(Page {
+        items: page
+            .items
+            .into_iter()
+            .map(|library| LibraryView::new(library, claims.is_admin))
+            .collect::<Vec<_>>(),
+        total: page.total,
+        offset: page.offset,
+        limit: page.limit,
+    })
+}
+
+/// 停用的库只对管理员可见
+#[utoipa::path(
+    get,
+    path = "/api/v1/libraries/{id}",
+    tag = "libraries",
+    params(("id" = String, Path, description = "Library id")),
+    responses(
+        (status = 200, description = "Library", body = LibraryView),
+        (status = 404, description = "Library not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_library(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let library_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match LibraryRepositoryImpl::new(state.db.clone())
+        .find_all()
+        .await
+    {
+        Ok(libraries) => match libraries
+            .into_iter()
+            .find(|library| library.id.as_i64() == library_id)
+        {
+            Some(library) if library.enabled || claims.is_admin => {
+                HttpResponse::Ok().json(LibraryView::new(library, claims.is_admin))
+            }
+            _ => not_found(format!("Library not found: {}", library_id)),
+        },
+        Err(e) => internal_error(e.to_string()),
+    }
+}
@@ -0,0 +1,227 @@
+use super::{format_timestamp, not_found, parse_sort, query_error, Page, Paging};
+use crate::auth::{current_claims, parse_id, resolve_user_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::query::dao::PlaylistDao;
+use application::query::get_playlist::has_tag;
+use infra::repository::postgres::query::playlist::PlaylistDaoImpl;
+use model::playlist::{PlaylistSummary, PlaylistTrack};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const SORT_FIELDS: [&str; 3] = ["name", "created", "changed"];
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/playlists", web::get().to(list_playlists))
+        .route("/playlists/{id}", web::get().to(get_playlist));
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct PlaylistListQuery {
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+    /// name（默认）、created 或 changed，前缀 `-` 表示降序
+    pub sort: Option<String>,
+    /// 只返回当前用户拥有的播放列表，不包含协作的
+    pub owned: Option<bool>,
+    /// 只返回带该标签的播放列表，不区分大小写
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistView {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub owner: String,
+    pub public: bool,
+    pub tags: Vec<String>,
+    pub song_count: i32,
+    /// 秒
+    pub duration: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<PlaylistSummary> for PlaylistView {
+    fn from(playlist: PlaylistSummary) -> Self {
+        Self {
+            id: playlist.id.to_string(),
+            name: playlist.name,
+            comment: playlist.comment,
+            owner: playlist.owner_name,
+            public: playlist.public,
+            tags: playlist.tags,
+            song_count: playlist.song_count,
+            duration: playlist.duration,
+            created_at: format_timestamp(playlist.created_at),
+            updated_at: format_timestamp(playlist.updated_at),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistEntryView {
+    /// 条目 ID，同一首歌可以出现多次
+    pub id: String,
+    pub song_id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added_by: Option<String>,
+}
+
+impl From<PlaylistTrack> for PlaylistEntryView {
+    fn from(track: PlaylistTrack) -> Self {
+        let song = track.audio_file;
+        Self {
+            id: track.id.to_string(),
+            song_id: song.id.to_string(),
+            title: song.title,
+            album: song.album_name,
+            artist: song.artist_name,
+            duration: song.duration,
+            added_by: track.added_by,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistDetailView {
+    #[serde(flatten)]
+    pub playlist: PlaylistView,
+    pub collaborators: Vec<String>,
+    pub entries: Vec<PlaylistEntryView>,
+}
+
+/// 分页列出当前用户拥有或协作的播放列表
+#[utoipa::path(
+    get,
+    path = "/api/v1/playlists",
+    tag = "playlists",
+    params(PlaylistListQuery),
+    responses(
+        (status = 200, description = "Playlist page", body = super::PlaylistPage),
+        (status = 400, description = "Invalid paging or sort", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_playlists(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<PlaylistListQuery>,
+) -> HttpResponse {
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let sort = match parse_sort(query.sort.as_deref(), &SORT_FIELDS, "name") {
+        Ok(sort) => sort,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match current_claims(&req) {
+        Ok(claims) => match resolve_user_id(&state, &claims).await {
+            Ok(id) => id,
+            Err(rsp) => return rsp,
+        },
+        Err(rsp) => return rsp,
+    };
+    let mut playlists = match PlaylistDaoImpl::new(state.db.clone())
+        .get_editable_by(user_id)
+        .await
+    {
+        Ok(playlists) => playlists,
+        Err(e) => return query_error(e),
+    };
+    if query.owned.unwrap_or(false) {
+        playlists.retain(|playlist| playlist.owner_id == user_id);
+    }
+    if let Some(tag) = query.tag.as_deref().filter(|tag| !tag.trim().is_empty()) {
+        playlists.retain(|playlist| has_tag(playlist, tag));
+    }
+    match sort.field {
+        "created" => playlists.sort_by_key(|playlist| playlist.created_at),
+        "changed" => playlists.sort_by_key(|playlist| playlist.updated_at),
+        _ => playlists.sort_by_key(|playlist| playlist.name.to_lowercase()),
+    }
+    if sort.descending {
+        playlists.reverse();
+    }
+    let page: Page<PlaylistView> = paging.slice(playlists);
+    HttpResponse::Ok().json(page)
+}
+
+/// 播放列表详情，仅所有者、协作者或公开时可见
+#[utoipa::path(
+    get,
+    path = "/api/v1/playlists/{id}",
+    tag = "playlists",
+    params(("id" = String, Path, description = "Playlist id")),
+    responses(
+        (status = 200, description = "Playlist with entries", body = PlaylistDetailView),
+        (status = 404, description = "Playlist not found or not visible", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_playlist(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let playlist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let playlist = match PlaylistDaoImpl::new(state.db.clone())
+        .get_by_id(playlist_id)
+        .await
+    {
+        Ok(Some(playlist)) => playlist,
+        Ok(None) => return not_found(format!("Playlist not found: {}", playlist_id)),
+        Err(e) => return query_error(e),
+    };
+    let visible = playlist.public
+        || playlist.owner_id == user_id
+        || playlist.collaborators.contains(&claims.user_name);
+    // 不可见时与不存在一样返回 404，避免泄露私有播放列表的存在
+    if !visible {
+        return not_found(format!("Playlist not found: {}", playlist_id));
+    }
+    HttpResponse::Ok().json(PlaylistDetailView {
+        playlist: PlaylistView {
+            id: playlist.id.to_string(),
+            name: playlist.name,
+            comment: playlist.comment,
+            owner: playlist.owner_name,
+            public: playlist.public,
+            tags: playlist.tags,
+            song_count: playlist.song_count,
+            duration: playlist.duration,
+            created_at: format_timestamp(playlist.created_at),
+            updated_at: format_timestamp(playlist.updated_at),
+        },
+        collaborators: playlist.collaborators,
+        entries: playlist
+            .tracks
+            .into_iter()
+            .map(PlaylistEntryView::from)
+            .collect(),
+    })
+}
@@ -0,0 +1,301 @@
+use super::{
+    has_role, not_found, parse_role, parse_sort, query_error, ContributorView, Page, Paging, Sort,
+};
+use crate::auth::{bad_request, parse_id};
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+use application::query::dao::AudioFileDao;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use model::audio_file::AudioFile;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const SORT_FIELDS: [&str; 4] = ["track", "title", "duration", "year"];
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/songs", web::get().to(list_songs))
+        .route("/songs/{id}", web::get().to(get_song))
+        .route("/songs/{id}/chapters", web::get().to(get_song_chapters));
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct SongListQuery {
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+    /// 只在按 albumId 或 artistId 过滤时可用：track（默认）、title、duration、year，前缀 `-` 表示降序
+    pub sort: Option<String>,
+    /// 在标题、艺术家和专辑中搜索
+    pub q: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub title: Option<String>,
+    pub album_id: Option<String>,
+    pub artist_id: Option<String>,
+    /// 与 artistId 一起使用，只返回该艺术家以此角色参与的歌曲，如 composer、conductor
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SongView {
+    pub id: String,
+    pub title: String,
+    pub album: String,
+    pub album_id: String,
+    pub artist: String,
+    pub artist_id: String,
+    pub track_number: i32,
+    pub disc_number: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    /// 秒
+    pub duration: i64,
+    pub bit_rate: i32,
+    pub size: i64,
+    pub suffix: String,
+    pub play_count: i32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<ContributorView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub movement_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub movement_number: Option<i32>,
+    /// 有章节时通过 /songs/{id}/chapters 获取
+    #[serde(skip_serializing_if = "is_zero")]
+    pub chapter_count: usize,
+    /// 尚未分析静音时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audible_range: Option<AudibleRangeView>,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterView {
+    /// 从 1 开始
+    pub index: usize,
+    pub title: String,
+    /// 毫秒
+    pub start: i64,
+    /// 毫秒
+    pub end: i64,
+}
+
+/// 去掉首尾静音后有声音的部分，客户端可以在 end 处开始淡出下一首
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AudibleRangeView {
+    /// 毫秒
+    pub start: i64,
+    /// 毫秒
+    pub end: i64,
+}
+
+impl From<AudioFile> for SongView {
+    fn from(song: AudioFile) -> Self {
+        Self {
+            id: song.id.to_string(),
+            title: song.title,
+            album: song.album,
+            album_id: song.album_id.to_string(),
+            artist: song.artist.name,
+            artist_id: song.artist.id.to_string(),
+            track_number: song.track_number,
+            disc_number: song.disc_number,
+            year: song.year,
+            genre: song.genre.map(|genre| genre.name),
+            duration: song.duration,
+            bit_rate: song.bit_rate,
+            size: song.size,
+            suffix: song.suffix,
+            play_count: song.annotation.play_count,
+            contributors: song.contributors.iter().map(Into::into).collect(),
+            movement_name: song.movement_name,
+            movement_number: song.movement_number,
+            chapter_count: song.chapters.len(),
+            audible_range: song.audible_range.map(|range| AudibleRangeView {
+                start: range.start,
+                end: range.end,
+            }),
+        }
+    }
+}
+
+fn sort_songs(songs: &mut [AudioFile], sort: Sort) {
+    match sort.field {
+        "title" => songs.sort_by_key(|song| song.title.to_lowercase()),
+        "duration" => songs.sort_by_key(|song| song.duration),
+        "year" => songs.sort_by_key(|song| song.year),
+        _ => songs.sort_by_key(|song| (song.album_id, song.disc_number, song.track_number)),
+    }
+    if sort.descending {
+        songs.reverse();
+    }
+}
+
+/// 分页列出歌曲
+///
+/// albumId、artistId 过滤返回完整列表并支持排序，其余条件走搜索，按相关度排序
+#[utoipa::path(
+    get,
+    path = "/api/v1/songs",
+    tag = "songs",
+    params(SongListQuery),
+    responses(
+        (status = 200, description = "Song page", body = super::SongPage),
+        (status = 400, description = "Invalid paging, filter or sort", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_songs(
+    state: web::Data<AppState>,
+    query: web::Query<SongListQuery>,
+) -> HttpResponse {
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let dao = AudioFileDaoImpl::new(state.db.clone());
+
+    let by_parent = match (&query.album_id, &query.artist_id) {
+        (Some(_), Some(_)) => {
+            return bad_request("Only one of albumId and artistId can be used".into())
+        }
+        (Some(id), None) => Some((id, true)),
+        (None, Some(id)) => Some((id, false)),
+        (None, None) => None,
+    };
+    let role = match (&query.role, &query.artist_id) {
+        (Some(_), None) => return bad_request("role requires artistId".into()),
+        (Some(raw), Some(_)) => match parse_role(raw) {
+            Ok(role) => Some(role),
+            Err(rsp) => return rsp,
+        },
+        (None, _) => None,
+    };
+    if let Some((raw_id, is_album)) = by_parent {
+        let searching = [&query.q, &query.artist, &query.album, &query.title]
+            .iter()
+            .any(|value| value.is_some());
+        if searching {
+            return bad_request("albumId/artistId cannot be combined with search filters".into());
+        }
+        let sort = match parse_sort(query.sort.as_deref(), &SORT_FIELDS, "track") {
+            Ok(sort) => sort,
+            Err(rsp) => return rsp,
+        };
+        let id = match parse_id(raw_id) {
+            Ok(id) => id,
+            Err(rsp) => return rsp,
+        };
+        let result = if is_album {
+            dao.get_by_album_id(id).await
+        } else {
+            dao.get_by_artist_id(id).await
+        };
+        return match result {
+            Ok(mut songs) => {
+                if let Some(role) = &role {
+                    songs.retain(|song| has_role(&song.contributors, id, role));
+                }
+                sort_songs(&mut songs, sort);
+                let page: Page<SongView> = paging.slice(songs);
+                HttpResponse::Ok().json(page)
+            }
+            Err(e) => query_error(e),
+        };
+    }
+
+    if query.sort.is_some() {
+        return bad_request("sort requires albumId or artistId".into());
+    }
+    match dao
+        .search(
+            query.q.as_deref(),
+            query.artist.as_deref(),
+            query.album.as_deref(),
+            query.title.as_deref(),
+            None,
+            paging.offset,
+            paging.limit,
+        )
+        .await
+    {
+        Ok((songs, total)) => {
+            let page: Page<SongView> = paging.page(songs, total);
+            HttpResponse::Ok().json(page)
+        }
+        Err(e) => query_error(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/songs/{id}",
+    tag = "songs",
+    params(("id" = String, Path, description = "Song id")),
+    responses(
+        (status = 200, description = "Song", body = SongView),
+        (status = 404, description = "Song not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_song(state: web::Data<AppState>, path: web::Path<String>) -> HttpResponse {
+    let song_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match AudioFileDaoImpl::new(state.db.clone())
+        .get_by_id(song_id)
+        .await
+    {
+        Ok(Some(song)) => HttpResponse::Ok().json(SongView::from(song)),
+        Ok(None) => not_found(format!("Song not found: {}", song_id)),
+        Err(e) => query_error(e),
+    }
+}
+
+/// 歌曲内的章节，按起始位置排序
+#[utoipa::path(
+    get,
+    path = "/api/v1/songs/{id}/chapters",
+    tag = "songs",
+    params(("id" = String, Path, description = "Song id")),
+    responses(
+        (status = 200, description = "Chapter markers, empty when the file has none", body = [ChapterView]),
+        (status = 404, description = "Song not found", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_song_chapters(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let song_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match AudioFileDaoImpl::new(state.db.clone())
+        .get_by_id(song_id)
+        .await
+    {
+        Ok(Some(song)) => HttpResponse::Ok().json(
+            song.chapters
+                .into_iter()
+                .enumerate()
+                .map(|(i, chapter)| ChapterView {
+                    index: i + 1,
+                    title: chapter.title,
+                    start: chapter.start,
+                    end: chapter.end,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Ok(None) => not_found(format!("Song not found: {}", song_id)),
+        Err(e) => query_error(e),
+    }
+}
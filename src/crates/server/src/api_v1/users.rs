@@ -0,0 +1,128 @@
+use super::{format_datetime, internal_error, not_found, Page, Paging};
+use crate::auth::{current_claims, parse_id};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use domain::user::{User, UserRepository, UserStatus};
+use infra::repository::postgres::command::user::UserRepositoryImpl;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/users", web::get().to(list_users))
+        .route("/users/{id}", web::get().to(get_user));
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UserListQuery {
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserView {
+    pub id: String,
+    pub username: String,
+    pub name: String,
+    pub email: String,
+    pub is_admin: bool,
+    pub disabled: bool,
+    pub last_login_at: String,
+}
+
+impl From<User> for UserView {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id.to_string(),
+            username: user.username,
+            name: user.name,
+            email: user.email,
+            is_admin: user.is_admin,
+            disabled: user.status == UserStatus::Disabled,
+            last_login_at: format_datetime(user.last_login_at),
+        }
+    }
+}
+
+/// 管理员分页列出所有用户，普通用户只能看到自己，按用户名排序
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    tag = "users",
+    params(UserListQuery),
+    responses(
+        (status = 200, description = "User page", body = super::UserPage),
+        (status = 400, description = "Invalid paging", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn list_users(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<UserListQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let repo = UserRepositoryImpl::new(state.db.clone());
+    if !claims.is_admin {
+        return match repo.find_by_username(&claims.user_name).await {
+            Ok(user) => {
+                let page: Page<UserView> = paging.slice(user.into_iter().collect());
+                HttpResponse::Ok().json(page)
+            }
+            Err(e) => internal_error(e.to_string()),
+        };
+    }
+    let users = repo
+        .find_page(paging.offset as u64, paging.limit as u64)
+        .await;
+    match (users, repo.count().await) {
+        (Ok(users), Ok(total)) => {
+            let page: Page<UserView> = paging.page(users, total as i64);
+            HttpResponse::Ok().json(page)
+        }
+        (Err(e), _) | (_, Err(e)) => internal_error(e.to_string()),
+    }
+}
+
+/// 管理员可以查看任意用户，普通用户只能查看自己
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = String, Path, description = "User id")),
+    responses(
+        (status = 200, description = "User", body = UserView),
+        (status = 404, description = "User not found or not visible", body = crate::auth::ErrorResponse),
+    )
+)]
+pub async fn get_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match UserRepositoryImpl::new(state.db.clone())
+        .find_by_id(user_id.into())
+        .await
+    {
+        Ok(Some(user)) if claims.is_admin || user.username == claims.user_name => {
+            HttpResponse::Ok().json(UserView::from(user))
+        }
+        Ok(_) => not_found(format!("User not found: {}", user_id)),
+        Err(e) => internal_error(e.to_string()),
+    }
+}
@@ -0,0 +1,139 @@
+use crate::auth::{current_claims, error_response, resolve_user_id, ErrorResponse};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::app_password::AppPasswordService;
+use application::error::AppError;
+use chrono::NaiveDateTime;
+use domain::app_password::AppPassword;
+use domain::value::UserId;
+use infra::repository::postgres::command::app_password::AppPasswordRepositoryImpl;
+use infra::Aes256GcmEncryptor;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 注册应用密码原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/app-passwords", consts::URL_PATH_NATIVE_API))
+            .route("", web::get().to(list_app_passwords))
+            .route("", web::post().to(create_app_password))
+            .route("/{id}", web::delete().to(revoke_app_password)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAppPasswordRequest {
+    pub label: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppPasswordView {
+    pub id: String,
+    pub label: String,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+impl From<AppPassword> for AppPasswordView {
+    fn from(value: AppPassword) -> Self {
+        Self {
+            id: value.id.to_string(),
+            label: value.label,
+            created_at: value.created_at,
+            last_used_at: value.last_used_at,
+        }
+    }
+}
+
+/// 创建结果，password 只返回这一次
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedAppPasswordView {
+    #[serde(flatten)]
+    pub app_password: AppPasswordView,
+    pub password: String,
+}
+
+pub(crate) fn app_password_service(state: &AppState) -> Result<AppPasswordService, AppError> {
+    let encryptor = Aes256GcmEncryptor::new(&state.app_cfg.password_encryption_key())?;
+    Ok(AppPasswordService::new(
+        Arc::new(AppPasswordRepositoryImpl::new(state.db.clone())),
+        Arc::new(encryptor),
+        state.id_generator.clone(),
+    ))
+}
+
+async fn current_user_id(req: &HttpRequest, state: &AppState) -> Result<UserId, HttpResponse> {
+    let claims = current_claims(req)?;
+    let user_id = resolve_user_id(state, &claims).await?;
+    Ok(UserId::from(user_id))
+}
+
+/// 当前用户的应用密码，不含密码本身：GET /api/app-passwords
+pub async fn list_app_passwords(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let user_id = match current_user_id(&req, &state).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let result = match app_password_service(&state) {
+        Ok(service) => service.list(&user_id).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(items) => {
+            let items: Vec<AppPasswordView> = items.into_iter().map(Into::into).collect();
+            HttpResponse::Ok().json(items)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// 生成新的应用密码：POST /api/app-passwords，请求体 {"label": "..."}
+pub async fn create_app_password(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<CreateAppPasswordRequest>,
+) -> HttpResponse {
+    let user_id = match current_user_id(&req, &state).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let result = match app_password_service(&state) {
+        Ok(service) => service.create(user_id, &body.label).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(created) => HttpResponse::Created().json(CreatedAppPasswordView {
+            app_password: created.app_password.into(),
+            password: created.password,
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 吊销应用密码：DELETE /api/app-passwords/{id}
+pub async fn revoke_app_password(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let user_id = match current_user_id(&req, &state).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let Ok(id) = path.parse::<i64>() else {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Invalid id: {}", path),
+        });
+    };
+    let result = match app_password_service(&state) {
+        Ok(service) => service.revoke(&user_id, id).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
@@ -1,61 +1,20 @@
-use actix_web::{web, HttpRequest, HttpResponse, Scope};
-use application::auth::AuthService;
-use infra::auth::{AuthConfig, BcryptPasswordHasher, JwtTokenService};
+use actix_web::{middleware::from_fn, web, HttpMessage, HttpRequest, HttpResponse};
+use application::auth::{AuthService, UserClaims};
+use application::error::AppError;
+use domain::artist::ArtistError;
+use domain::genre::GenreError;
+use domain::library::LibraryError;
+use domain::user::{UserError, UserRepository};
+use infra::auth::{AuthConfig, JwtTokenService};
 use infra::repository::postgres::command::user::UserRepositoryImpl;
 use infra::Aes256GcmEncryptor;
-use once_cell::sync::Lazy;
-use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
 
+use crate::middleware::other::RequestUsername;
+use crate::middleware::rate_limit::{self, RateKey};
 use crate::AppState;
 
-/// Rate limiter for login attempts by IP
-struct RateLimiter {
-    attempts: HashMap<String, Vec<Instant>>,
-    max_attempts: usize,
-    window: Duration,
-}
-
-impl RateLimiter {
-    fn new(max_attempts: usize, window_secs: u64) -> Self {
-        Self {
-            attempts: HashMap::new(),
-            max_attempts,
-            window: Duration::from_secs(window_secs),
-        }
-    }
-
-    fn is_allowed(&mut self, ip: &str) -> bool {
-        let now = Instant::now();
-        let attempts = self.attempts.entry(ip.to_string()).or_default();
-
-        // Remove expired attempts
-        attempts.retain(|t| now.duration_since(*t) < self.window);
-
-        if attempts.len() >= self.max_attempts {
-            false
-        } else {
-            attempts.push(now);
-            true
-        }
-    }
-
-    #[allow(dead_code)]
-    fn cleanup(&mut self) {
-        let now = Instant::now();
-        self.attempts.retain(|_, attempts| {
-            attempts.retain(|t| now.duration_since(*t) < self.window);
-            !attempts.is_empty()
-        });
-    }
-}
-
-static LOGIN_LIMITER: Lazy<Mutex<RateLimiter>> =
-    Lazy::new(|| Mutex::new(RateLimiter::new(3, 60))); // 3 attempts per 60 seconds
-
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
     pub username: String,
@@ -67,32 +26,93 @@ pub struct LoginResponse {
     pub token: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
 }
 
-fn get_client_ip(req: &HttpRequest) -> String {
-    // Try X-Forwarded-For header first (for reverse proxy)
-    if let Some(forwarded) = req.headers().get("X-Forwarded-For") {
-        if let Ok(s) = forwarded.to_str() {
-            if let Some(ip) = s.split(',').next() {
-                return ip.trim().to_string();
-            }
-        }
+/// JWT 中间件放入请求扩展的当前用户
+pub(crate) fn current_claims(req: &HttpRequest) -> Result<UserClaims, HttpResponse> {
+    req.extensions()
+        .get::<UserClaims>()
+        .cloned()
+        .ok_or_else(|| {
+            HttpResponse::Unauthorized().json(ErrorResponse {
+                error: "Unauthorized".to_string(),
+            })
+        })
+}
+
+/// JWT 中只有用户名，按用户名查出用户 ID
+pub(crate) async fn resolve_user_id(
+    state: &AppState,
+    claims: &UserClaims,
+) -> Result<i64, HttpResponse> {
+    match UserRepositoryImpl::new(state.db.clone())
+        .find_by_username(&claims.user_name)
+        .await
+    {
+        Ok(Some(user)) => Ok(user.id.as_i64()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "User not found".to_string(),
+        })),
+        Err(e) => Err(HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        })),
     }
+}
+
+pub(crate) fn bad_request(error: String) -> HttpResponse {
+    HttpResponse::BadRequest().json(ErrorResponse { error })
+}
+
+pub(crate) fn parse_id(raw: &str) -> Result<i64, HttpResponse> {
+    raw.parse::<i64>()
+        .map_err(|_| bad_request(format!("Invalid id: {}", raw)))
+}
 
-    // Try X-Real-IP header
-    if let Some(real_ip) = req.headers().get("X-Real-IP") {
-        if let Ok(s) = real_ip.to_str() {
-            return s.trim().to_string();
+pub(crate) fn parse_ids(raw: &[String]) -> Result<Vec<i64>, HttpResponse> {
+    raw.iter().map(|id| parse_id(id)).collect()
+}
+
+/// 原生 API 统一的错误状态码，同一种错误在所有接口返回相同的状态码
+pub(crate) fn error_response(e: AppError) -> HttpResponse {
+    match e {
+        AppError::InvalidInput(e) | AppError::InvalidCoverArtFormat(e) => bad_request(e),
+        AppError::AuthError(e) => HttpResponse::Forbidden().json(ErrorResponse { error: e }),
+        AppError::AggregateNotFound(kind, id) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("{} not found: {}", kind, id),
+        }),
+        AppError::UserError(UserError::UserNotFound(name)) => {
+            HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("User not found: {}", name),
+            })
         }
+        e if e.is_conflict() => HttpResponse::Conflict().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+        AppError::LibraryError(
+            e
+            @ (LibraryError::ScanningInProgress | LibraryError::Disabled | LibraryError::NotEmpty),
+        ) => HttpResponse::Conflict().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+        AppError::LibraryError(
+            e @ (LibraryError::InvalidName(_)
+            | LibraryError::InvalidPath(_)
+            | LibraryError::InvalidCredentials(_)),
+        ) => bad_request(e.to_string()),
+        AppError::GenreError(
+            e @ (GenreError::ValidationErr(_)
+            | GenreError::RelationError(_)
+            | GenreError::OtherErr(_)),
+        ) => bad_request(e.to_string()),
+        AppError::ArtistError(ArtistError::OtherErr(e)) => bad_request(e),
+        AppError::UserError(e @ UserError::UserDeleted) => bad_request(e.to_string()),
+        e => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
     }
-
-    // Fall back to peer address
-    req.peer_addr()
-        .map(|addr| addr.ip().to_string())
-        .unwrap_or_else(|| "unknown".to_string())
 }
 
 pub async fn login(
@@ -100,22 +120,18 @@ pub async fn login(
     state: web::Data<AppState>,
     body: web::Json<LoginRequest>,
 ) -> HttpResponse {
-    let client_ip = get_client_ip(&req);
-
-    // Check rate limit
-    {
-        let mut limiter = LOGIN_LIMITER.lock();
-        if !limiter.is_allowed(&client_ip) {
-            return HttpResponse::TooManyRequests().json(ErrorResponse {
-                error: "Too many login attempts. Please try again later.".to_string(),
-            });
-        }
+    // IP 维度由 rate_limit 中间件处理，用户名在请求体里，这里检查用户维度，
+    // 并交给中间件在响应阶段统计失败次数
+    if let Err(retry_after) = state.auth_limiter.check(&[RateKey::User(&body.username)]) {
+        return rate_limit::too_many_requests(req.path(), retry_after);
     }
+    req.extensions_mut()
+        .insert(RequestUsername(body.username.clone()));
 
     let user_repo: Arc<dyn domain::user::UserRepository> =
         Arc::new(UserRepositoryImpl::new(state.db.clone()));
     let hasher: Arc<dyn application::auth::PasswordHasher> =
-        Arc::new(BcryptPasswordHasher::new(10));
+        Arc::new(crate::password_hasher(&state));
     let encryptor: Arc<dyn application::auth::PasswordEncryptor> = Arc::new(
         Aes256GcmEncryptor::new(&state.app_cfg.password_encryption_key())
             .expect("Failed to create password encryptor"),
@@ -141,6 +157,10 @@ pub async fn login(
     }
 }
 
-pub fn configure_service() -> Scope {
-    web::scope("/auth").route("/login", web::post().to(login))
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/auth")
+            .route("/login", web::post().to(login))
+            .wrap(from_fn(rate_limit::rate_limit)),
+    );
 }
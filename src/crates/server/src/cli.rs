@@ -0,0 +1,655 @@
+use crate::import::{ImportSource, ItunesImportOptions, NavidromeImportOptions};
+use crate::AppState;
+use anyhow::{anyhow, bail, Context};
+use application::auth::{PasswordEncryptor, PasswordHasher};
+use application::command::library::ScanLibraryCmd;
+use application::command::user::{ChangePasswordCmd, CreateUserCmd, UserAppService};
+use application::context::AppContext;
+use application::query::dao::PlaylistDao;
+use chrono::NaiveDateTime;
+use domain::library::LibraryRepository;
+use domain::user::{User, UserRoles, UserStatus};
+use infra::import::DEFAULT_NAVIDROME_KEY;
+use infra::repository::postgres::backup::PostgresBackup;
+use infra::repository::postgres::command::library::LibraryRepositoryImpl;
+use infra::repository::postgres::command::user::{
+    UserPreferenceRepositoryImpl, UserRepositoryImpl,
+};
+use infra::repository::postgres::query::playlist::PlaylistDaoImpl;
+use infra::Aes256GcmEncryptor;
+use migration::{Migrator, MigratorTrait};
+use model::playlist::Playlist;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: rhythm [COMMAND] [OPTIONS]
+
+Commands:
+  serve                                  Start the server (default)
+  scan [--library <id>] [--full] [--force]
+                                         Scan one library, or all enabled libraries, and wait for it to finish
+  user add <username> [--email <email>] [--admin] [--password <password>]
+  user passwd <username> [--password <password>]
+  user disable <username>
+  user enable <username>
+  migrate                                Apply pending database migrations
+  export [--output <file>]               Export users, libraries and playlists as JSON
+  backup [--output <file>]               Write a snapshot of all tables as JSONL
+  restore <file>                         Replace all data with a backup
+  import navidrome <navidrome.db> [--path-map <old>=<new>]... [--navidrome-key <key>]
+                                         Import users, playlists, stars, ratings and play counts
+  import itunes <library.xml> --user <username> [--path-map <old>=<new>]...
+                                         Import ratings, play counts, loved songs and playlists
+  help                                   Show this message
+
+Passwords are read from stdin when --password is omitted.
+
+Options (all commands):
+  --config <file>  --set <key=value>  --host <host>  --port <port>
+  --database-url <url>  --log-level <level>";
+
+/// 扫描任务结束后等待解析和入库完成的上限，只作为兜底
+const SCAN_DRAIN_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+/// 导入结束后等待事件处理完成的上限
+const IMPORT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// 导出时每次读取的用户数
+const EXPORT_PAGE_SIZE: u64 = 500;
+
+/// 命令行子命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Serve,
+    Scan {
+        library_id: Option<i64>,
+        full: bool,
+        force: bool,
+    },
+    UserAdd {
+        username: String,
+        email: String,
+        admin: bool,
+        password: Option<String>,
+    },
+    UserPasswd {
+        username: String,
+        password: Option<String>,
+    },
+    UserSetEnabled {
+        username: String,
+        enabled: bool,
+    },
+    Migrate,
+    Export {
+        output: Option<PathBuf>,
+    },
+    Backup {
+        output: Option<PathBuf>,
+    },
+    Restore {
+        input: PathBuf,
+    },
+    Import(ImportSource),
+    Help,
+}
+
+/// 子命令自己的参数；其余以 `--` 开头的参数都交给配置加载
+#[derive(Default)]
+struct ParsedArgs {
+    positional: Vec<String>,
+    /// 可重复的参数按出现顺序保存
+    values: HashMap<&'static str, Vec<String>>,
+    switches: HashSet<&'static str>,
+    config: Vec<String>,
+}
+
+impl ParsedArgs {
+    fn parse(
+        mut args: impl Iterator<Item = String>,
+        value_flags: &[&'static str],
+        switch_flags: &[&'static str],
+    ) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                parsed.positional.push(arg);
+                continue;
+            }
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            if let Some(flag) = switch_flags.iter().copied().find(|f| *f == flag) {
+                if inline.is_some() {
+                    return Err(format!("{} does not take a value", flag));
+                }
+                parsed.switches.insert(flag);
+            } else if let Some(flag) = value_flags.iter().copied().find(|f| *f == flag) {
+                let value = inline
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} requires a value", flag))?;
+                parsed.values.entry(flag).or_default().push(value);
+            } else {
+                // 配置参数都带值，缺少值时由配置加载报错
+                let needs_value = inline.is_none();
+                parsed.config.push(arg);
+                if needs_value {
+                    parsed.config.extend(args.next());
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// 重复出现时以最后一次为准
+    fn value(&self, flag: &str) -> Option<&String> {
+        self.values.get(flag).and_then(|values| values.last())
+    }
+
+    fn expect_positional(&self, count: usize, usage: &str) -> Result<(), String> {
+        if self.positional.len() != count {
+            return Err(format!("usage: rhythm {}", usage));
+        }
+        Ok(())
+    }
+}
+
+impl Command {
+    /// 解析子命令，返回子命令和剩余的配置参数。没有子命令时等同于 `serve`
+    pub fn parse(args: Vec<String>) -> Result<(Command, Vec<String>), String> {
+        if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+            return Ok((Command::Help, Vec::new()));
+        }
+        let mut args = args.into_iter().peekable();
+        let name = match args.peek() {
+            Some(arg) if !arg.starts_with('-') => args.next(),
+            _ => None,
+        };
+
+        let command = match name.as_deref() {
+            None | Some("serve") => {
+                let parsed = ParsedArgs::parse(args, &[], &[])?;
+                parsed.expect_positional(0, "serve")?;
+                (Command::Serve, parsed.config)
+            }
+            Some("scan") => {
+                let parsed = ParsedArgs::parse(args, &["--library"], &["--full", "--force"])?;
+                parsed.expect_positional(0, "scan [--library <id>] [--full] [--force]")?;
+                let library_id = parsed
+                    .value("--library")
+                    .map(|id| {
+                        id.parse()
+                            .map_err(|_| format!("invalid library id: {}", id))
+                    })
+                    .transpose()?;
+                let command = Command::Scan {
+                    library_id,
+                    full: parsed.switches.contains("--full"),
+                    force: parsed.switches.contains("--force"),
+                };
+                (command, parsed.config)
+            }
+            Some("user") => Self::parse_user(args)?,
+            Some("migrate") => {
+                let parsed = ParsedArgs::parse(args, &[], &[])?;
+                parsed.expect_positional(0, "migrate")?;
+                (Command::Migrate, parsed.config)
+            }
+            Some("export") => {
+                let parsed = ParsedArgs::parse(args, &["--output"], &[])?;
+                parsed.expect_positional(0, "export [--output <file>]")?;
+                let output = parsed.value("--output").map(PathBuf::from);
+                (Command::Export { output }, parsed.config)
+            }
+            Some("backup") => {
+                let parsed = ParsedArgs::parse(args, &["--output"], &[])?;
+                parsed.expect_positional(0, "backup [--output <file>]")?;
+                let output = parsed.value("--output").map(PathBuf::from);
+                (Command::Backup { output }, parsed.config)
+            }
+            Some("restore") => {
+                let parsed = ParsedArgs::parse(args, &[], &[])?;
+                parsed.expect_positional(1, "restore <file>")?;
+                let input = PathBuf::from(&parsed.positional[0]);
+                (Command::Restore { input }, parsed.config)
+            }
+            Some("import") => Self::parse_import(args)?,
+            Some("help") => (Command::Help, Vec::new()),
+            Some(other) => return Err(format!("unknown command: {}", other)),
+        };
+        Ok(command)
+    }
+
+    fn parse_import(args: impl Iterator<Item = String>) -> Result<(Command, Vec<String>), String> {
+        const USAGE: &str = "import <navidrome|itunes> <file> [--path-map <old>=<new>]...";
+        let parsed = ParsedArgs::parse(args, &["--path-map", "--navidrome-key", "--user"], &[])?;
+        parsed.expect_positional(2, USAGE)?;
+        let path_maps = parsed
+            .values
+            .get("--path-map")
+            .into_iter()
+            .flatten()
+            .map(|map| {
+                map.split_once('=')
+                    .map(|(old, new)| (old.to_string(), new.to_string()))
+                    .ok_or_else(|| format!("invalid path map, expected <old>=<new>: {}", map))
+            })
+            .collect::<Result<_, _>>()?;
+        let path = PathBuf::from(&parsed.positional[1]);
+        let source = match parsed.positional[0].as_str() {
+            "navidrome" => ImportSource::Navidrome(NavidromeImportOptions {
+                db_path: path,
+                key: parsed
+                    .value("--navidrome-key")
+                    .cloned()
+                    .unwrap_or_else(|| DEFAULT_NAVIDROME_KEY.to_string()),
+                path_maps,
+            }),
+            "itunes" => ImportSource::Itunes(ItunesImportOptions {
+                library_path: path,
+                username: parsed
+                    .value("--user")
+                    .cloned()
+                    .ok_or("import itunes requires --user <username>")?,
+                path_maps,
+            }),
+            _ => return Err(format!("usage: rhythm {}", USAGE)),
+        };
+        Ok((Command::Import(source), parsed.config))
+    }
+
+    fn parse_user(args: impl Iterator<Item = String>) -> Result<(Command, Vec<String>), String> {
+        let parsed = ParsedArgs::parse(args, &["--email", "--password"], &["--admin"])?;
+        let action = parsed.positional.first().map(String::as_str);
+        let username = parsed.positional.get(1).cloned().unwrap_or_default();
+        let command = match action {
+            Some("add") => {
+                parsed.expect_positional(
+                    2,
+                    "user add <username> [--email <email>] [--admin] [--password <password>]",
+                )?;
+                Command::UserAdd {
+                    username,
+                    email: parsed.value("--email").cloned().unwrap_or_default(),
+                    admin: parsed.switches.contains("--admin"),
+                    password: parsed.value("--password").cloned(),
+                }
+            }
+            Some("passwd") => {
+                parsed.expect_positional(2, "user passwd <username> [--password <password>]")?;
+                Command::UserPasswd {
+                    username,
+                    password: parsed.value("--password").cloned(),
+                }
+            }
+            Some(action @ ("disable" | "enable")) => {
+                parsed.expect_positional(2, &format!("user {} <username>", action))?;
+                Command::UserSetEnabled {
+                    username,
+                    enabled: action == "enable",
+                }
+            }
+            _ => return Err("usage: rhythm user <add|passwd|disable|enable> <username>".into()),
+        };
+        Ok((command, parsed.config))
+    }
+}
+
+/// 执行除 `serve`、`migrate` 以外的子命令，AppState 与服务启动时的初始化方式相同
+pub async fn run(command: Command, state: AppState) -> anyhow::Result<()> {
+    match command {
+        Command::Scan {
+            library_id,
+            full,
+            force,
+        } => scan(state, library_id, full, force).await,
+        Command::UserAdd {
+            username,
+            email,
+            admin,
+            password,
+        } => add_user(&state, username, email, admin, password).await,
+        Command::UserPasswd { username, password } => {
+            change_password(&state, username, password).await
+        }
+        Command::UserSetEnabled { username, enabled } => {
+            user_service(&state).set_enabled(&username, enabled).await?;
+            println!(
+                "User {} {}",
+                username,
+                if enabled { "enabled" } else { "disabled" }
+            );
+            Ok(())
+        }
+        Command::Export { output } => export(&state, output).await,
+        Command::Backup { output } => backup(&state, output).await,
+        Command::Restore { input } => restore(&state, input).await,
+        Command::Import(source) => import(state, source).await,
+        Command::Serve | Command::Migrate | Command::Help => {
+            Err(anyhow!("{:?} is not an admin command", command))
+        }
+    }
+}
+
+/// 执行未应用的迁移。只需要数据库连接，表结构缺失时也能运行
+pub async fn migrate(db: &DatabaseConnection) -> anyhow::Result<()> {
+    let pending = Migrator::get_pending_migrations(db)
+        .await
+        .context("failed to read migration status")?;
+    if pending.is_empty() {
+        println!("Database is up to date");
+        return Ok(());
+    }
+    for migration in &pending {
+        println!("Applying {}", migration.name());
+    }
+    Migrator::up(db, None)
+        .await
+        .context("failed to apply migrations")?;
+    println!("Applied {} migrations", pending.len());
+    Ok(())
+}
+
+async fn scan(
+    mut state: AppState,
+    library_id: Option<i64>,
+    full: bool,
+    force: bool,
+) -> anyhow::Result<()> {
+    crate::setup_event_bus(&mut state).await;
+    let libraries = LibraryRepositoryImpl::new(state.db.clone())
+        .find_all()
+        .await?;
+    let libraries: Vec<_> = match library_id {
+        Some(id) => {
+            let library = libraries
+                .into_iter()
+                .find(|library| library.id.as_i64() == id)
+                .ok_or_else(|| anyhow!("library {} not found", id))?;
+            vec![library]
+        }
+        None => libraries
+            .into_iter()
+            .filter(|library| library.enabled)
+            .collect(),
+    };
+    if libraries.is_empty() {
+        println!("No enabled libraries to scan");
+        return Ok(());
+    }
+
+    let svc = crate::library_command_service(&state);
+    let ctx = AppContext::new();
+    let mut scans = Vec::new();
+    for library in &libraries {
+        println!("Scanning library {} ({})", library.name, library.id);
+        let cmd = ScanLibraryCmd {
+            library_id: library.id.clone(),
+            is_full_scan: full,
+            force,
+        };
+        scans.push(svc.scan_library(&ctx, cmd).await?);
+    }
+    for scan in scans {
+        scan.await?;
+    }
+    // 扫描任务只负责枚举文件，解析和入库还在事件总线中进行
+    crate::shutdown(&state, SCAN_DRAIN_TIMEOUT).await;
+    println!("Scanned {} libraries", libraries.len());
+    Ok(())
+}
+
+pub(crate) fn user_service(state: &AppState) -> UserAppService {
+    UserAppService::new(
+        Arc::new(UserRepositoryImpl::new(state.db.clone())),
+        Arc::new(UserPreferenceRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+    )
+}
+
+/// 未通过 `--password` 指定时从标准输入读取一行，便于通过管道传入
+fn read_password(password: Option<String>) -> anyhow::Result<String> {
+    let password = match password {
+        Some(password) => password,
+        None => {
+            let stdin = io::stdin();
+            if stdin.is_terminal() {
+                eprint!("Password: ");
+                io::stderr().flush()?;
+            }
+            let mut line = String::new();
+            stdin.lock().read_line(&mut line)?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+    if password.is_empty() {
+        bail!("password must not be empty");
+    }
+    Ok(password)
+}
+
+/// 按配置的算法哈希用于登录，AES 加密的原文用于 Subsonic token 认证
+pub(crate) fn protect_password(
+    state: &AppState,
+    password: &str,
+) -> anyhow::Result<(String, String)> {
+    let hashed = crate::password_hasher(state)
+        .hash(password)
+        .context("failed to hash password")?;
+    let encrypted = Aes256GcmEncryptor::new(&state.app_cfg.password_encryption_key())
+        .map_err(|e| anyhow!("failed to create encryptor: {}", e))?
+        .encrypt(password)
+        .map_err(|e| anyhow!("failed to encrypt password: {}", e))?;
+    Ok((hashed, encrypted))
+}
+
+async fn add_user(
+    state: &AppState,
+    username: String,
+    email: String,
+    admin: bool,
+    password: Option<String>,
+) -> anyhow::Result<()> {
+    let password = read_password(password)?;
+    let (password, encrypted_password) = protect_password(state, &password)?;
+    user_service(state)
+        .create_user(CreateUserCmd {
+            username: username.clone(),
+            password,
+            encrypted_password,
+            email,
+            is_admin: admin,
+            roles: UserRoles::default(),
+            max_bit_rate: None,
+            download_quota_mb: None,
+            hide_explicit: false,
+        })
+        .await?;
+    println!(
+        "Created {} {}",
+        if admin { "admin" } else { "user" },
+        username
+    );
+    Ok(())
+}
+
+async fn change_password(
+    state: &AppState,
+    username: String,
+    password: Option<String>,
+) -> anyhow::Result<()> {
+    let password = read_password(password)?;
+    let (password, encrypted_password) = protect_password(state, &password)?;
+    user_service(state)
+        .change_password(ChangePasswordCmd {
+            username: username.clone(),
+            password,
+            encrypted_password,
+        })
+        .await?;
+    println!("Changed password of {}", username);
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportData {
+    exported_at: NaiveDateTime,
+    users: Vec<ExportedUser>,
+    libraries: Vec<ExportedLibrary>,
+    playlists: Vec<Playlist>,
+}
+
+/// 不导出密码，导入后需要重新设置
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedUser {
+    username: String,
+    name: String,
+    email: String,
+    is_admin: bool,
+    enabled: bool,
+    roles: i32,
+    max_bit_rate: Option<i32>,
+    download_quota_mb: Option<i64>,
+    hide_explicit: bool,
+}
+
+impl From<&User> for ExportedUser {
+    fn from(user: &User) -> Self {
+        Self {
+            username: user.username.clone(),
+            name: user.name.clone(),
+            email: user.email.clone(),
+            is_admin: user.is_admin,
+            enabled: user.status != UserStatus::Disabled,
+            roles: user.roles.to_bits(),
+            max_bit_rate: user.max_bit_rate,
+            download_quota_mb: user.download_quota_mb,
+            hide_explicit: user.hide_explicit,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedLibrary {
+    name: String,
+    path: String,
+    enabled: bool,
+    scan_interval_minutes: Option<i32>,
+    kind: String,
+    include_patterns: Vec<String>,
+    exclude_patterns: Vec<String>,
+    scan_hidden: bool,
+    hide_unavailable: bool,
+}
+
+/// 导出用户、音乐库和播放列表（包含歌曲路径），未指定文件时写到标准输出
+async fn export(state: &AppState, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let user_svc = user_service(state);
+    let mut users = Vec::new();
+    loop {
+        let (page, _) = user_svc
+            .list_users(users.len() as u64, EXPORT_PAGE_SIZE)
+            .await?;
+        let done = (page.len() as u64) < EXPORT_PAGE_SIZE;
+        users.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    let playlist_dao = PlaylistDaoImpl::new(state.db.clone());
+    let mut playlists = Vec::new();
+    for user in &users {
+        for summary in playlist_dao.get_by_owner_id(user.id.as_i64()).await? {
+            playlists.extend(playlist_dao.get_by_id(summary.id).await?);
+        }
+    }
+
+    let libraries = LibraryRepositoryImpl::new(state.db.clone())
+        .find_all()
+        .await?
+        .into_iter()
+        .map(|library| ExportedLibrary {
+            name: library.name,
+            path: library.path.path,
+            enabled: library.enabled,
+            scan_interval_minutes: library.scan_interval_minutes,
+            kind: library.kind.as_str().to_string(),
+            include_patterns: library.scan_filter.include,
+            exclude_patterns: library.scan_filter.exclude,
+            scan_hidden: library.scan_filter.scan_hidden,
+            hide_unavailable: library.hide_unavailable,
+        })
+        .collect();
+
+    let data = ExportData {
+        exported_at: chrono::Local::now().naive_local(),
+        users: users.iter().map(ExportedUser::from).collect(),
+        libraries,
+        playlists,
+    };
+    let json = serde_json::to_string_pretty(&data)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, json)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!(
+                "Exported {} users, {} playlists to {}",
+                data.users.len(),
+                data.playlists.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// 一致性快照写到文件或标准输出，服务运行时也可以执行
+async fn backup(state: &AppState, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let backup = PostgresBackup::new(state.db.clone());
+    let rows = match &output {
+        Some(path) => {
+            let mut file = tokio::fs::File::create(path)
+                .await
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            backup.export(&mut file).await?
+        }
+        None => backup.export(&mut tokio::io::stdout()).await?,
+    };
+    eprintln!("Backed up {} rows", rows);
+    Ok(())
+}
+
+/// 恢复前应停止服务，运行中的服务仍持有恢复前的缓存数据
+async fn restore(state: &AppState, input: PathBuf) -> anyhow::Result<()> {
+    let file = tokio::fs::File::open(&input)
+        .await
+        .with_context(|| format!("failed to open {}", input.display()))?;
+    let report = PostgresBackup::new(state.db.clone())
+        .restore(tokio::io::BufReader::new(file))
+        .await?;
+    crate::admin::backup::clear_caches(state);
+    for (table, rows) in &report.tables {
+        println!("{:>10}  {}", rows, table);
+    }
+    println!("Restored {} rows", report.rows);
+    Ok(())
+}
+
+/// 收藏等操作产生的事件需要投递给投影，结束前等待事件处理完
+async fn import(mut state: AppState, source: ImportSource) -> anyhow::Result<()> {
+    crate::setup_event_bus(&mut state).await;
+    let report = crate::import::import(&state, source).await?;
+    crate::shutdown(&state, IMPORT_DRAIN_TIMEOUT).await;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
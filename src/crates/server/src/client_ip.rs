@@ -0,0 +1,70 @@
+use crate::AppState;
+use actix_web::{web, HttpRequest};
+use infra::config::ServerConfig;
+use std::net::{IpAddr, SocketAddr};
+
+/// 客户端 IP
+///
+/// 只有直连地址是受信代理时才采用 X-Forwarded-For / X-Real-IP，否则客户端可以随意伪造。
+/// X-Forwarded-For 从右往左取第一个不受信的地址；IPv4 映射的 IPv6 地址统一转换为 IPv4。
+pub fn client_ip(req: &HttpRequest) -> Option<IpAddr> {
+    let peer = req.peer_addr().map(|addr| addr.ip().to_canonical());
+    let Some(server_cfg) = req
+        .app_data::<web::Data<AppState>>()
+        .map(|state| state.app_cfg.server())
+    else {
+        return peer;
+    };
+    if !is_trusted(&server_cfg, peer) {
+        return peer;
+    }
+
+    if let Some(forwarded) = header_value(req, "X-Forwarded-For") {
+        let mut client = peer;
+        for entry in forwarded.rsplit(',') {
+            let Some(ip) = parse_ip(entry) else {
+                break;
+            };
+            client = Some(ip);
+            if !server_cfg.is_trusted_proxy(ip) {
+                break;
+            }
+        }
+        return client;
+    }
+    header_value(req, "X-Real-IP").and_then(parse_ip).or(peer)
+}
+
+/// 日志、播放器记录和限流使用的字符串形式
+pub fn client_ip_string(req: &HttpRequest) -> String {
+    client_ip(req)
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Unix 套接字连接没有对端地址，只有本机进程能连接，视为受信
+fn is_trusted(server_cfg: &ServerConfig, peer: Option<IpAddr>) -> bool {
+    peer.is_none_or(|ip| server_cfg.is_trusted_proxy(ip))
+}
+
+fn header_value<'a>(req: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    req.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// 支持 `1.2.3.4`、`1.2.3.4:5678`、`::1`、`[::1]:5678` 以及带引号的写法
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+    value
+        .parse::<IpAddr>()
+        .or_else(|_| value.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .or_else(|_| {
+            value
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+        })
+        .ok()
+        .map(|ip| ip.to_canonical())
+}
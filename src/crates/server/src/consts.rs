@@ -8,5 +8,7 @@ pub const DEFAULT_SESSION_TIMEOUT: i64 = 24 * 3600;
 pub const COOKIE_EXPIRY: i64 = 365 * 24 * 3600; // One year
 pub const URL_PATH_NATIVE_API: &str = "/api";
 pub const URL_PATH_SUBSONIC_API: &str = "/rest";
+pub const URL_PATH_DLNA: &str = "/dlna";
+pub const URL_PATH_GRAPHQL: &str = "/graphql";
 pub const URL_PATH_PUBLIC: &str = "/share";
 pub const URL_PATH_PUBLIC_IMAGES: &str = "/share/img";
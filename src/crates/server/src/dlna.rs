@@ -0,0 +1,378 @@
+pub mod content_directory;
+pub mod ssdp;
+
+use crate::consts;
+use crate::subsonic::helper::SubsonicQuery;
+use crate::subsonic::media_retrieval::{self, GetCoverArtQuery, StreamQuery, StreamResponse};
+use crate::AppState;
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderName, HeaderValue},
+    middleware::{from_fn, Next},
+    web, HttpRequest, HttpResponse,
+};
+use application::query::dto::cover_art::album_cover_art_id;
+use std::net::IpAddr;
+
+pub(crate) const DEVICE_TYPE: &str = "urn:schemas-upnp-org:device:MediaServer:1";
+pub(crate) const CONTENT_DIRECTORY_TYPE: &str = "urn:schemas-upnp-org:service:ContentDirectory:1";
+pub(crate) const CONNECTION_MANAGER_TYPE: &str = "urn:schemas-upnp-org:service:ConnectionManager:1";
+
+const XML_CONTENT_TYPE: &str = "text/xml; charset=\"utf-8\"";
+/// 专辑封面缩略图尺寸，DLNA 设备普遍只支持 JPEG
+const ALBUM_ART_SIZE: u32 = 300;
+/// 声明支持按字节范围 seek
+const CONTENT_FEATURES: &str =
+    "DLNA.ORG_OP=01;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=01700000000000000000000000000000";
+
+/// 注册 DLNA 路由（设备不会携带认证信息，需挂在 JWT scope 之外）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(consts::URL_PATH_DLNA)
+            .route("/description.xml", web::get().to(device_description))
+            .route(
+                "/ContentDirectory.xml",
+                web::get().to(content_directory::scpd),
+            )
+            .route(
+                "/ConnectionManager.xml",
+                web::get().to(connection_manager_scpd),
+            )
+            .route(
+                "/control/ContentDirectory",
+                web::post().to(content_directory::control),
+            )
+            .route(
+                "/control/ConnectionManager",
+                web::post().to(connection_manager_control),
+            )
+            .route("/stream/{id}", web::get().to(stream))
+            .route("/stream/{id}", web::head().to(stream))
+            .route("/art/{album_id}", web::get().to(album_art))
+            .wrap(from_fn(local_network_only)),
+    );
+}
+
+/// 未启用 DLNA 或请求不是来自局域网时，当作路由不存在
+async fn local_network_only(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let enabled = req
+        .app_data::<web::Data<AppState>>()
+        .is_some_and(|state| state.app_cfg.dlna().enabled);
+    let local = req
+        .peer_addr()
+        .is_some_and(|addr| is_local_address(addr.ip()));
+    if !enabled || !local {
+        return Err(actix_web::error::ErrorNotFound("Not Found"));
+    }
+    next.call(req).await
+}
+
+/// 私有网段、回环和链路本地地址
+pub(crate) fn is_local_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_private() || v4.is_loopback() || v4.is_link_local(),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_local_address(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                // fc00::/7 唯一本地地址，fe80::/10 链路本地地址
+                v6.is_loopback() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+            }
+        },
+    }
+}
+
+/// 设备 UUID 由节点号派生，重启后保持不变，设备端不会出现重复的服务器
+pub(crate) fn device_uuid(state: &AppState) -> String {
+    let digest = md5::compute(format!(
+        "{}-dlna-{}",
+        consts::APP_NAME,
+        state.app_cfg.node_id()
+    ));
+    let hex = format!("{:x}", digest);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// 设备访问资源时使用的地址前缀，如 http://192.168.1.2:5533/dlna
+pub(crate) fn dlna_base_url(state: &AppState) -> String {
+    format!(
+        "{}{}",
+        state.app_cfg.base_url().trim_end_matches('/'),
+        consts::URL_PATH_DLNA
+    )
+}
+
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 读取 SOAP 请求中的参数值，参数名可能带命名空间前缀
+pub(crate) fn soap_argument(body: &str, name: &str) -> Option<String> {
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let tag = &rest[..end];
+        let tag_name = tag
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        let local_name = tag_name.rsplit(':').next().unwrap_or_default();
+        if local_name != name || tag.starts_with('/') {
+            continue;
+        }
+        if tag.ends_with('/') {
+            return Some(String::new());
+        }
+        let value = &rest[end + 1..];
+        let close = value.find("</")?;
+        return Some(xml_unescape(value[..close].trim()));
+    }
+    None
+}
+
+/// SOAPACTION 头中 # 之后的动作名
+pub(crate) fn soap_action(req: &HttpRequest) -> Option<String> {
+    let value = req.headers().get("SOAPACTION")?.to_str().ok()?;
+    let action = value.trim_matches('"').rsplit('#').next()?;
+    Some(action.to_string())
+}
+
+pub(crate) fn soap_response(service: &str, action: &str, arguments: &str) -> HttpResponse {
+    let body = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" "#,
+            r#"s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">"#,
+            r#"<s:Body><u:{action}Response xmlns:u="{service}">{arguments}</u:{action}Response>"#,
+            r#"</s:Body></s:Envelope>"#
+        ),
+        action = action,
+        service = service,
+        arguments = arguments
+    );
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, XML_CONTENT_TYPE))
+        .body(body)
+}
+
+/// UPnP 错误，如 401 无效动作、701 对象不存在
+pub(crate) fn soap_fault(code: u16, description: &str) -> HttpResponse {
+    let body = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" "#,
+            r#"s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">"#,
+            r#"<s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring>"#,
+            r#"<detail><UPnPError xmlns="urn:schemas-upnp-org:control-1-0">"#,
+            r#"<errorCode>{}</errorCode><errorDescription>{}</errorDescription>"#,
+            r#"</UPnPError></detail></s:Fault></s:Body></s:Envelope>"#
+        ),
+        code,
+        xml_escape(description)
+    );
+    HttpResponse::InternalServerError()
+        .insert_header((header::CONTENT_TYPE, XML_CONTENT_TYPE))
+        .body(body)
+}
+
+fn xml_document(body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, XML_CONTENT_TYPE))
+        .body(body)
+}
+
+/// 设备描述，SSDP 广播中的 LOCATION 指向这里
+async fn device_description(state: web::Data<AppState>) -> HttpResponse {
+    let service = |service_type: &str, name: &str| {
+        format!(
+            concat!(
+                "<service><serviceType>{service_type}</serviceType>",
+                "<serviceId>urn:upnp-org:serviceId:{name}</serviceId>",
+                "<SCPDURL>{base}/{name}.xml</SCPDURL>",
+                "<controlURL>{base}/control/{name}</controlURL>",
+                "<eventSubURL></eventSubURL></service>"
+            ),
+            service_type = service_type,
+            name = name,
+            base = consts::URL_PATH_DLNA
+        )
+    };
+    xml_document(format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<root xmlns="urn:schemas-upnp-org:device-1-0" xmlns:dlna="urn:schemas-dlna-org:device-1-0">"#,
+            "<specVersion><major>1</major><minor>0</minor></specVersion>",
+            "<device><deviceType>{device_type}</deviceType>",
+            "<friendlyName>{friendly_name}</friendlyName>",
+            "<manufacturer>{app}</manufacturer><modelName>{app}</modelName>",
+            "<modelNumber>{version}</modelNumber><UDN>uuid:{uuid}</UDN>",
+            "<dlna:X_DLNADOC>DMS-1.50</dlna:X_DLNADOC>",
+            "<serviceList>{content_directory}{connection_manager}</serviceList>",
+            "</device></root>"
+        ),
+        device_type = DEVICE_TYPE,
+        friendly_name = xml_escape(&state.app_cfg.dlna().friendly_name),
+        app = consts::APP_NAME,
+        version = consts::VERSION,
+        uuid = device_uuid(&state),
+        content_directory = service(CONTENT_DIRECTORY_TYPE, "ContentDirectory"),
+        connection_manager = service(CONNECTION_MANAGER_TYPE, "ConnectionManager"),
+    ))
+}
+
+const CONNECTION_MANAGER_SCPD: &str = concat!(
+    r#"<?xml version="1.0" encoding="utf-8"?>"#,
+    r#"<scpd xmlns="urn:schemas-upnp-org:service-1-0">"#,
+    "<specVersion><major>1</major><minor>0</minor></specVersion>",
+    "<actionList>",
+    "<action><name>GetProtocolInfo</name><argumentList>",
+    "<argument><name>Source</name><direction>out</direction><relatedStateVariable>SourceProtocolInfo</relatedStateVariable></argument>",
+    "<argument><name>Sink</name><direction>out</direction><relatedStateVariable>SinkProtocolInfo</relatedStateVariable></argument>",
+    "</argumentList></action>",
+    "<action><name>GetCurrentConnectionIDs</name><argumentList>",
+    "<argument><name>ConnectionIDs</name><direction>out</direction><relatedStateVariable>CurrentConnectionIDs</relatedStateVariable></argument>",
+    "</argumentList></action>",
+    "<action><name>GetCurrentConnectionInfo</name><argumentList>",
+    "<argument><name>ConnectionID</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_ConnectionID</relatedStateVariable></argument>",
+    "<argument><name>RcsID</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_RcsID</relatedStateVariable></argument>",
+    "<argument><name>AVTransportID</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_AVTransportID</relatedStateVariable></argument>",
+    "<argument><name>ProtocolInfo</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_ProtocolInfo</relatedStateVariable></argument>",
+    "<argument><name>PeerConnectionManager</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_ConnectionManager</relatedStateVariable></argument>",
+    "<argument><name>PeerConnectionID</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_ConnectionID</relatedStateVariable></argument>",
+    "<argument><name>Direction</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_Direction</relatedStateVariable></argument>",
+    "<argument><name>Status</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_ConnectionStatus</relatedStateVariable></argument>",
+    "</argumentList></action>",
+    "</actionList>",
+    "<serviceStateTable>",
+    r#"<stateVariable sendEvents="yes"><name>SourceProtocolInfo</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="yes"><name>SinkProtocolInfo</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="yes"><name>CurrentConnectionIDs</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_ConnectionStatus</name><dataType>string</dataType>"#,
+    "<allowedValueList><allowedValue>OK</allowedValue><allowedValue>ContentFormatMismatch</allowedValue>",
+    "<allowedValue>InsufficientBandwidth</allowedValue><allowedValue>UnreliableChannel</allowedValue>",
+    "<allowedValue>Unknown</allowedValue></allowedValueList></stateVariable>",
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_ConnectionManager</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_Direction</name><dataType>string</dataType>"#,
+    "<allowedValueList><allowedValue>Input</allowedValue><allowedValue>Output</allowedValue></allowedValueList></stateVariable>",
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_ProtocolInfo</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_ConnectionID</name><dataType>i4</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_AVTransportID</name><dataType>i4</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_RcsID</name><dataType>i4</dataType></stateVariable>"#,
+    "</serviceStateTable></scpd>"
+);
+
+async fn connection_manager_scpd() -> HttpResponse {
+    xml_document(CONNECTION_MANAGER_SCPD.to_string())
+}
+
+/// 可以输出的格式，按原始文件输出，不做转码
+const SOURCE_MIME_TYPES: [&str; 8] = [
+    "audio/mpeg",
+    "audio/flac",
+    "audio/ogg",
+    "audio/opus",
+    "audio/mp4",
+    "audio/wav",
+    "audio/x-ms-wma",
+    "audio/aiff",
+];
+
+/// ConnectionManager 只需回答协议信息，媒体服务器不维护连接
+async fn connection_manager_control(req: HttpRequest) -> HttpResponse {
+    let action = soap_action(&req).unwrap_or_default();
+    let arguments = match action.as_str() {
+        "GetProtocolInfo" => {
+            let source = SOURCE_MIME_TYPES
+                .iter()
+                .map(|mime| format!("http-get:*:{}:*", mime))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("<Source>{}</Source><Sink></Sink>", source)
+        }
+        "GetCurrentConnectionIDs" => "<ConnectionIDs>0</ConnectionIDs>".to_string(),
+        "GetCurrentConnectionInfo" => concat!(
+            "<RcsID>-1</RcsID><AVTransportID>-1</AVTransportID><ProtocolInfo></ProtocolInfo>",
+            "<PeerConnectionManager></PeerConnectionManager><PeerConnectionID>-1</PeerConnectionID>",
+            "<Direction>Output</Direction><Status>OK</Status>"
+        )
+        .to_string(),
+        _ => return soap_fault(401, "Invalid Action"),
+    };
+    soap_response(CONNECTION_MANAGER_TYPE, &action, &arguments)
+}
+
+/// 播放地址，复用 Subsonic stream 的缓存和 Range 处理
+async fn stream(
+    state: web::Data<AppState>,
+    path: web::Path<i64>,
+    req: HttpRequest,
+) -> HttpResponse {
+    // 按原始格式输出，与 DIDL 中声明的 MIME 类型一致
+    let query = StreamQuery {
+        id: path.into_inner(),
+        max_bit_rate: None,
+        format: Some("raw".to_string()),
+        time_offset: None,
+        estimate_content_length: None,
+    };
+    match media_retrieval::stream(state, SubsonicQuery(query), req).await {
+        StreamResponse::Binary(mut response) => {
+            let headers = response.headers_mut();
+            headers.insert(
+                HeaderName::from_static("transfermode.dlna.org"),
+                HeaderValue::from_static("Streaming"),
+            );
+            headers.insert(
+                HeaderName::from_static("contentfeatures.dlna.org"),
+                HeaderValue::from_static(CONTENT_FEATURES),
+            );
+            response
+        }
+        StreamResponse::Error(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+async fn album_art(
+    state: web::Data<AppState>,
+    path: web::Path<i64>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let query = GetCoverArtQuery {
+        id: album_cover_art_id(path.into_inner()),
+        size: Some(ALBUM_ART_SIZE),
+        format: Some("jpeg".to_string()),
+    };
+    media_retrieval::get_cover_art(req, state, web::Query(query)).await
+}
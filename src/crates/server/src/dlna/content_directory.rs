@@ -0,0 +1,518 @@
+use super::{
+    dlna_base_url, soap_action, soap_argument, soap_fault, soap_response, xml_document, xml_escape,
+    CONTENT_DIRECTORY_TYPE,
+};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::query::dao::{AlbumDao, ArtistDao, AudioFileDao, GenreDao};
+use application::query::stream_media::StreamInfo;
+use application::query::QueryError;
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::artist::ArtistDaoImpl;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use infra::repository::postgres::query::genre::GenreDaoImpl;
+use model::album::Album;
+use model::audio_file::AudioFile;
+use std::fmt;
+
+/// RequestedCount 为 0 时表示全部，单次最多返回的条目数
+const MAX_BROWSE_COUNT: usize = 500;
+/// 暂不跟踪库的变更，设备按需重新浏览
+const SYSTEM_UPDATE_ID: u32 = 1;
+
+const FOLDER_CLASS: &str = "object.container.storageFolder";
+const ALBUM_CLASS: &str = "object.container.album.musicAlbum";
+const ARTIST_CLASS: &str = "object.container.person.musicArtist";
+const GENRE_CLASS: &str = "object.container.genre.musicGenre";
+const TRACK_CLASS: &str = "object.item.audioItem.musicTrack";
+
+/// ContentDirectory 中的对象 ID
+///
+/// 根目录下按专辑、艺术家、流派分三个入口，最终都落到专辑下的歌曲
+#[derive(Debug, Clone, PartialEq)]
+enum ObjectId {
+    Root,
+    Albums,
+    Artists,
+    Genres,
+    Artist(i64),
+    Genre(String),
+    Album(i64),
+    Track(i64),
+}
+
+impl ObjectId {
+    fn parse(id: &str) -> Option<Self> {
+        match id {
+            "0" => return Some(ObjectId::Root),
+            "albums" => return Some(ObjectId::Albums),
+            "artists" => return Some(ObjectId::Artists),
+            "genres" => return Some(ObjectId::Genres),
+            _ => {}
+        }
+        let (kind, value) = id.split_once('-')?;
+        match kind {
+            "artist" => value.parse().ok().map(ObjectId::Artist),
+            "genre" if !value.is_empty() => Some(ObjectId::Genre(value.to_string())),
+            "album" => value.parse().ok().map(ObjectId::Album),
+            "track" => value.parse().ok().map(ObjectId::Track),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectId::Root => write!(f, "0"),
+            ObjectId::Albums => write!(f, "albums"),
+            ObjectId::Artists => write!(f, "artists"),
+            ObjectId::Genres => write!(f, "genres"),
+            ObjectId::Artist(id) => write!(f, "artist-{}", id),
+            ObjectId::Genre(name) => write!(f, "genre-{}", name),
+            ObjectId::Album(id) => write!(f, "album-{}", id),
+            ObjectId::Track(id) => write!(f, "track-{}", id),
+        }
+    }
+}
+
+/// DIDL-Lite 中的一个条目
+enum DidlObject {
+    Container {
+        id: ObjectId,
+        parent_id: String,
+        title: String,
+        class: &'static str,
+        child_count: Option<i64>,
+        artist: Option<String>,
+        album_art: Option<i64>,
+    },
+    Track(AudioFile),
+}
+
+impl DidlObject {
+    fn folder(id: ObjectId, title: &str) -> Self {
+        let parent_id = match id {
+            ObjectId::Root => "-1".to_string(),
+            _ => ObjectId::Root.to_string(),
+        };
+        DidlObject::Container {
+            id,
+            parent_id,
+            title: title.to_string(),
+            class: FOLDER_CLASS,
+            child_count: None,
+            artist: None,
+            album_art: None,
+        }
+    }
+
+    fn album(album: Album, parent: &ObjectId) -> Self {
+        DidlObject::Container {
+            id: ObjectId::Album(album.id),
+            parent_id: parent.to_string(),
+            title: album.name,
+            class: ALBUM_CLASS,
+            child_count: Some(album.song_count as i64),
+            artist: Some(album.artist.name),
+            album_art: Some(album.id),
+        }
+    }
+
+    fn render(&self, base_url: &str, out: &mut String) {
+        match self {
+            DidlObject::Container {
+                id,
+                parent_id,
+                title,
+                class,
+                child_count,
+                artist,
+                album_art,
+            } => {
+                out.push_str(&format!(
+                    r#"<container id="{}" parentID="{}" restricted="1" searchable="0""#,
+                    xml_escape(&id.to_string()),
+                    xml_escape(parent_id)
+                ));
+                if let Some(count) = child_count {
+                    out.push_str(&format!(r#" childCount="{}""#, count));
+                }
+                out.push('>');
+                out.push_str(&format!("<dc:title>{}</dc:title>", xml_escape(title)));
+                out.push_str(&format!("<upnp:class>{}</upnp:class>", class));
+                if let Some(artist) = artist {
+                    out.push_str(&format!(
+                        "<upnp:artist>{}</upnp:artist>",
+                        xml_escape(artist)
+                    ));
+                }
+                if let Some(album_id) = album_art {
+                    out.push_str(&format!(
+                        "<upnp:albumArtURI>{}/art/{}</upnp:albumArtURI>",
+                        base_url, album_id
+                    ));
+                }
+                out.push_str("</container>");
+            }
+            DidlObject::Track(track) => render_track(track, base_url, out),
+        }
+    }
+}
+
+fn render_track(track: &AudioFile, base_url: &str, out: &mut String) {
+    out.push_str(&format!(
+        r#"<item id="{}" parentID="{}" restricted="1">"#,
+        ObjectId::Track(track.id),
+        ObjectId::Album(track.album_id)
+    ));
+    out.push_str(&format!(
+        "<dc:title>{}</dc:title>",
+        xml_escape(&track.title)
+    ));
+    out.push_str(&format!("<upnp:class>{}</upnp:class>", TRACK_CLASS));
+    let artist = xml_escape(&track.artist.name);
+    out.push_str(&format!(
+        "<dc:creator>{0}</dc:creator><upnp:artist>{0}</upnp:artist>",
+        artist
+    ));
+    out.push_str(&format!(
+        "<upnp:album>{}</upnp:album>",
+        xml_escape(&track.album)
+    ));
+    if let Some(genre) = &track.genre {
+        out.push_str(&format!(
+            "<upnp:genre>{}</upnp:genre>",
+            xml_escape(&genre.name)
+        ));
+    }
+    if track.track_number > 0 {
+        out.push_str(&format!(
+            "<upnp:originalTrackNumber>{}</upnp:originalTrackNumber>",
+            track.track_number
+        ));
+    }
+    if let Some(year) = track.year {
+        out.push_str(&format!("<dc:date>{:04}-01-01</dc:date>", year));
+    }
+    out.push_str(&format!(
+        "<upnp:albumArtURI>{}/art/{}</upnp:albumArtURI>",
+        base_url, track.album_id
+    ));
+    // DIDL 中的 bitrate 单位是字节/秒
+    out.push_str(&format!(
+        r#"<res protocolInfo="http-get:*:{}:*" size="{}" duration="{}" bitrate="{}">{}/stream/{}</res>"#,
+        StreamInfo::mime_type_from_suffix(&track.suffix),
+        track.size,
+        format_duration(track.duration),
+        track.bit_rate.max(0) * 1000 / 8,
+        base_url,
+        track.id
+    ));
+    out.push_str("</item>");
+}
+
+/// 秒数转换为 DIDL 的 H:MM:SS.000 格式
+fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    format!(
+        "{}:{:02}:{:02}.000",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+fn didl(objects: &[DidlObject], base_url: &str) -> String {
+    let mut out = String::from(concat!(
+        r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" "#,
+        r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+        r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/">"#
+    ));
+    for object in objects {
+        object.render(base_url, &mut out);
+    }
+    out.push_str("</DIDL-Lite>");
+    out
+}
+
+/// 一页浏览结果和总条目数
+struct BrowsePage {
+    objects: Vec<DidlObject>,
+    total: usize,
+}
+
+impl BrowsePage {
+    fn slice(objects: Vec<DidlObject>, start: usize, count: usize) -> Self {
+        let total = objects.len();
+        let objects = objects.into_iter().skip(start).take(count).collect();
+        Self { objects, total }
+    }
+}
+
+struct ContentDirectory {
+    album_dao: AlbumDaoImpl,
+    artist_dao: ArtistDaoImpl,
+    audio_file_dao: AudioFileDaoImpl,
+    genre_dao: GenreDaoImpl,
+}
+
+impl ContentDirectory {
+    fn new(state: &AppState) -> Self {
+        Self {
+            album_dao: AlbumDaoImpl::new(state.db.clone()),
+            artist_dao: ArtistDaoImpl::new(state.db.clone()),
+            audio_file_dao: AudioFileDaoImpl::new(state.db.clone()),
+            genre_dao: GenreDaoImpl::new(state.db.clone()),
+        }
+    }
+
+    /// BrowseMetadata：对象本身，不存在时返回 None
+    async fn metadata(&self, id: &ObjectId) -> Result<Option<DidlObject>, QueryError> {
+        let object = match id {
+            ObjectId::Root => Some(DidlObject::folder(ObjectId::Root, "Rhythm")),
+            ObjectId::Albums => Some(DidlObject::folder(ObjectId::Albums, "Albums")),
+            ObjectId::Artists => Some(DidlObject::folder(ObjectId::Artists, "Artists")),
+            ObjectId::Genres => Some(DidlObject::folder(ObjectId::Genres, "Genres")),
+            ObjectId::Artist(artist_id) => {
+                self.artist_dao
+                    .get_by_id(*artist_id)
+                    .await?
+                    .map(|artist| DidlObject::Container {
+                        id: id.clone(),
+                        parent_id: ObjectId::Artists.to_string(),
+                        title: artist.name,
+                        class: ARTIST_CLASS,
+                        child_count: Some(artist.album_count as i64),
+                        artist: None,
+                        album_art: None,
+                    })
+            }
+            ObjectId::Genre(name) => Some(DidlObject::Container {
+                id: id.clone(),
+                parent_id: ObjectId::Genres.to_string(),
+                title: name.clone(),
+                class: GENRE_CLASS,
+                child_count: None,
+                artist: None,
+                album_art: None,
+            }),
+            ObjectId::Album(album_id) => self
+                .album_dao
+                .get_by_id(*album_id)
+                .await?
+                .map(|album| DidlObject::album(album, &ObjectId::Albums)),
+            ObjectId::Track(track_id) => self
+                .audio_file_dao
+                .get_by_id(*track_id)
+                .await?
+                .map(DidlObject::Track),
+        };
+        Ok(object)
+    }
+
+    /// BrowseDirectChildren：分页列出子条目
+    async fn children(
+        &self,
+        id: &ObjectId,
+        start: usize,
+        count: usize,
+    ) -> Result<BrowsePage, QueryError> {
+        let page = match id {
+            ObjectId::Root => BrowsePage::slice(
+                vec![
+                    DidlObject::folder(ObjectId::Albums, "Albums"),
+                    DidlObject::folder(ObjectId::Artists, "Artists"),
+                    DidlObject::folder(ObjectId::Genres, "Genres"),
+                ],
+                start,
+                count,
+            ),
+            ObjectId::Albums => {
+                let (albums, total) = self
+                    .album_dao
+                    .get_by_name(start as i32, count as i32)
+                    .await?;
+                BrowsePage {
+                    objects: albums
+                        .into_iter()
+                        .map(|album| DidlObject::album(album, id))
+                        .collect(),
+                    total: total as usize,
+                }
+            }
+            ObjectId::Artists => {
+                let artists = self.artist_dao.get_all().await?;
+                let objects = artists
+                    .into_iter()
+                    .map(|artist| DidlObject::Container {
+                        id: ObjectId::Artist(artist.id),
+                        parent_id: id.to_string(),
+                        title: artist.name,
+                        class: ARTIST_CLASS,
+                        child_count: Some(artist.album_count as i64),
+                        artist: None,
+                        album_art: None,
+                    })
+                    .collect();
+                BrowsePage::slice(objects, start, count)
+            }
+            ObjectId::Genres => {
+                let genres = self.genre_dao.get_all().await?;
+                let objects = genres
+                    .into_iter()
+                    .map(|genre| DidlObject::Container {
+                        id: ObjectId::Genre(genre.name.clone()),
+                        parent_id: id.to_string(),
+                        title: genre.name,
+                        class: GENRE_CLASS,
+                        child_count: Some(genre.album_count as i64),
+                        artist: None,
+                        album_art: None,
+                    })
+                    .collect();
+                BrowsePage::slice(objects, start, count)
+            }
+            ObjectId::Artist(artist_id) => {
+                let albums = self.album_dao.get_by_artist_id(*artist_id).await?;
+                let objects = albums
+                    .into_iter()
+                    .map(|album| DidlObject::album(album, id))
+                    .collect();
+                BrowsePage::slice(objects, start, count)
+            }
+            ObjectId::Genre(name) => {
+                let (albums, total) = self
+                    .album_dao
+                    .get_by_genre(name, start as i32, count as i32)
+                    .await?;
+                BrowsePage {
+                    objects: albums
+                        .into_iter()
+                        .map(|album| DidlObject::album(album, id))
+                        .collect(),
+                    total: total as usize,
+                }
+            }
+            ObjectId::Album(album_id) => {
+                let tracks = self.audio_file_dao.get_by_album_id(*album_id).await?;
+                let objects = tracks.into_iter().map(DidlObject::Track).collect();
+                BrowsePage::slice(objects, start, count)
+            }
+            ObjectId::Track(_) => BrowsePage {
+                objects: Vec::new(),
+                total: 0,
+            },
+        };
+        Ok(page)
+    }
+}
+
+const CONTENT_DIRECTORY_SCPD: &str = concat!(
+    r#"<?xml version="1.0" encoding="utf-8"?>"#,
+    r#"<scpd xmlns="urn:schemas-upnp-org:service-1-0">"#,
+    "<specVersion><major>1</major><minor>0</minor></specVersion>",
+    "<actionList>",
+    "<action><name>Browse</name><argumentList>",
+    "<argument><name>ObjectID</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_ObjectID</relatedStateVariable></argument>",
+    "<argument><name>BrowseFlag</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_BrowseFlag</relatedStateVariable></argument>",
+    "<argument><name>Filter</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_Filter</relatedStateVariable></argument>",
+    "<argument><name>StartingIndex</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_Index</relatedStateVariable></argument>",
+    "<argument><name>RequestedCount</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_Count</relatedStateVariable></argument>",
+    "<argument><name>SortCriteria</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_SortCriteria</relatedStateVariable></argument>",
+    "<argument><name>Result</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_Result</relatedStateVariable></argument>",
+    "<argument><name>NumberReturned</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_Count</relatedStateVariable></argument>",
+    "<argument><name>TotalMatches</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_Count</relatedStateVariable></argument>",
+    "<argument><name>UpdateID</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_UpdateID</relatedStateVariable></argument>",
+    "</argumentList></action>",
+    "<action><name>GetSearchCapabilities</name><argumentList>",
+    "<argument><name>SearchCaps</name><direction>out</direction><relatedStateVariable>SearchCapabilities</relatedStateVariable></argument>",
+    "</argumentList></action>",
+    "<action><name>GetSortCapabilities</name><argumentList>",
+    "<argument><name>SortCaps</name><direction>out</direction><relatedStateVariable>SortCapabilities</relatedStateVariable></argument>",
+    "</argumentList></action>",
+    "<action><name>GetSystemUpdateID</name><argumentList>",
+    "<argument><name>Id</name><direction>out</direction><relatedStateVariable>SystemUpdateID</relatedStateVariable></argument>",
+    "</argumentList></action>",
+    "</actionList>",
+    "<serviceStateTable>",
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_ObjectID</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_Result</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_BrowseFlag</name><dataType>string</dataType>"#,
+    "<allowedValueList><allowedValue>BrowseMetadata</allowedValue><allowedValue>BrowseDirectChildren</allowedValue></allowedValueList></stateVariable>",
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_Filter</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_SortCriteria</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_Index</name><dataType>ui4</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_Count</name><dataType>ui4</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>A_ARG_TYPE_UpdateID</name><dataType>ui4</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>SearchCapabilities</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="no"><name>SortCapabilities</name><dataType>string</dataType></stateVariable>"#,
+    r#"<stateVariable sendEvents="yes"><name>SystemUpdateID</name><dataType>ui4</dataType></stateVariable>"#,
+    "</serviceStateTable></scpd>"
+);
+
+pub async fn scpd() -> HttpResponse {
+    xml_document(CONTENT_DIRECTORY_SCPD.to_string())
+}
+
+/// ContentDirectory 控制接口（SOAP）
+pub async fn control(state: web::Data<AppState>, req: HttpRequest, body: String) -> HttpResponse {
+    let action = soap_action(&req).unwrap_or_default();
+    let arguments = match action.as_str() {
+        "Browse" => return browse(&state, &body).await,
+        "GetSearchCapabilities" => "<SearchCaps></SearchCaps>".to_string(),
+        "GetSortCapabilities" => "<SortCaps></SortCaps>".to_string(),
+        "GetSystemUpdateID" => format!("<Id>{}</Id>", SYSTEM_UPDATE_ID),
+        _ => return soap_fault(401, "Invalid Action"),
+    };
+    soap_response(CONTENT_DIRECTORY_TYPE, &action, &arguments)
+}
+
+async fn browse(state: &AppState, body: &str) -> HttpResponse {
+    let Some(id) = soap_argument(body, "ObjectID").and_then(|id| ObjectId::parse(&id)) else {
+        return soap_fault(701, "No such object");
+    };
+    let start = soap_argument(body, "StartingIndex")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let count = match soap_argument(body, "RequestedCount").and_then(|v| v.parse::<usize>().ok()) {
+        Some(0) | None => MAX_BROWSE_COUNT,
+        Some(count) => count.min(MAX_BROWSE_COUNT),
+    };
+
+    let directory = ContentDirectory::new(state);
+    let page = match soap_argument(body, "BrowseFlag").as_deref() {
+        Some("BrowseMetadata") => match directory.metadata(&id).await {
+            Ok(Some(object)) => BrowsePage {
+                objects: vec![object],
+                total: 1,
+            },
+            Ok(None) => return soap_fault(701, "No such object"),
+            Err(e) => {
+                log::warn!("DLNA browse metadata of {} failed: {}", id, e);
+                return soap_fault(501, "Action Failed");
+            }
+        },
+        Some("BrowseDirectChildren") => match directory.children(&id, start, count).await {
+            Ok(page) => page,
+            Err(e) => {
+                log::warn!("DLNA browse children of {} failed: {}", id, e);
+                return soap_fault(501, "Action Failed");
+            }
+        },
+        _ => return soap_fault(402, "Invalid Args"),
+    };
+
+    let result = didl(&page.objects, &dlna_base_url(state));
+    let arguments = format!(
+        concat!(
+            "<Result>{}</Result><NumberReturned>{}</NumberReturned>",
+            "<TotalMatches>{}</TotalMatches><UpdateID>{}</UpdateID>"
+        ),
+        xml_escape(&result),
+        page.objects.len(),
+        page.total,
+        SYSTEM_UPDATE_ID
+    );
+    soap_response(CONTENT_DIRECTORY_TYPE, "Browse", &arguments)
+}
@@ -0,0 +1,203 @@
+use super::{
+    device_uuid, dlna_base_url, is_local_address, CONNECTION_MANAGER_TYPE, CONTENT_DIRECTORY_TYPE,
+    DEVICE_TYPE,
+};
+use crate::consts;
+use crate::AppState;
+use actix_web::web;
+use log::{info, warn};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+const SSDP_ADDR: Ipv4Addr = Ipv4Addr::new(239, 255, 255, 250);
+const SSDP_PORT: u16 = 1900;
+/// UPnP 规范建议的组播 TTL
+const MULTICAST_TTL: u32 = 2;
+
+/// 本机广播的内容：根设备、设备本身、设备类型和两个服务
+#[derive(Debug, Clone)]
+struct Advertisement {
+    uuid: String,
+    location: String,
+    max_age: u64,
+}
+
+impl Advertisement {
+    /// (NT/ST, USN) 列表
+    fn targets(&self) -> Vec<(String, String)> {
+        let uuid = format!("uuid:{}", self.uuid);
+        let mut targets = vec![(uuid.clone(), uuid.clone())];
+        for nt in [
+            "upnp:rootdevice",
+            DEVICE_TYPE,
+            CONTENT_DIRECTORY_TYPE,
+            CONNECTION_MANAGER_TYPE,
+        ] {
+            targets.push((nt.to_string(), format!("{}::{}", uuid, nt)));
+        }
+        targets
+    }
+
+    /// M-SEARCH 的 ST 对应的应答目标，ssdp:all 应答全部
+    fn matching(&self, st: &str) -> Vec<(String, String)> {
+        self.targets()
+            .into_iter()
+            .filter(|(nt, _)| st == "ssdp:all" || nt == st)
+            .collect()
+    }
+
+    fn notify_alive(&self, nt: &str, usn: &str) -> String {
+        format!(
+            concat!(
+                "NOTIFY * HTTP/1.1\r\n",
+                "HOST: {}:{}\r\n",
+                "CACHE-CONTROL: max-age={}\r\n",
+                "LOCATION: {}\r\n",
+                "NT: {}\r\n",
+                "NTS: ssdp:alive\r\n",
+                "SERVER: {}\r\n",
+                "USN: {}\r\n\r\n"
+            ),
+            SSDP_ADDR,
+            SSDP_PORT,
+            self.max_age,
+            self.location,
+            nt,
+            server_header(),
+            usn
+        )
+    }
+
+    fn search_response(&self, st: &str, usn: &str) -> String {
+        format!(
+            concat!(
+                "HTTP/1.1 200 OK\r\n",
+                "CACHE-CONTROL: max-age={}\r\n",
+                "DATE: {}\r\n",
+                "EXT:\r\n",
+                "LOCATION: {}\r\n",
+                "SERVER: {}\r\n",
+                "ST: {}\r\n",
+                "USN: {}\r\n\r\n"
+            ),
+            self.max_age,
+            chrono::Utc::now().format("%a, %d %b %Y %H:%M:%S GMT"),
+            self.location,
+            server_header(),
+            st,
+            usn
+        )
+    }
+}
+
+fn server_header() -> String {
+    format!(
+        "{}/1.0 UPnP/1.0 {}/{}",
+        std::env::consts::OS,
+        consts::APP_NAME,
+        consts::VERSION
+    )
+}
+
+/// 解析 M-SEARCH 请求，返回 ST
+fn parse_search(packet: &str) -> Option<String> {
+    let mut lines = packet.lines();
+    if !lines.next()?.starts_with("M-SEARCH") {
+        return None;
+    }
+    let mut st = None;
+    let mut discover = false;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.trim().eq_ignore_ascii_case("ST") {
+            st = Some(value.to_string());
+        } else if name.trim().eq_ignore_ascii_case("MAN") {
+            discover = value.trim_matches('"') == "ssdp:discover";
+        }
+    }
+    st.filter(|_| discover)
+}
+
+async fn bind() -> std::io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, SSDP_PORT)).await?;
+    socket.join_multicast_v4(SSDP_ADDR, Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_ttl_v4(MULTICAST_TTL)?;
+    Ok(socket)
+}
+
+/// 启用 DLNA 时在局域网内广播媒体服务器，并应答设备的 M-SEARCH 搜索
+pub fn start_ssdp(state: web::Data<AppState>) {
+    let cfg = state.app_cfg.dlna();
+    if !cfg.enabled {
+        return;
+    }
+    let interval = Duration::from_secs(cfg.advertise_interval_secs);
+    let advertisement = Advertisement {
+        uuid: device_uuid(&state),
+        location: format!("{}/description.xml", dlna_base_url(&state)),
+        max_age: cfg.advertise_interval_secs * 2,
+    };
+    tokio::spawn(async move {
+        let socket = match bind().await {
+            Ok(socket) => Arc::new(socket),
+            Err(e) => {
+                warn!(
+                    "Failed to bind SSDP port {}, DLNA discovery disabled: {}",
+                    SSDP_PORT, e
+                );
+                return;
+            }
+        };
+        info!(
+            "DLNA media server '{}' advertised at {}",
+            cfg.friendly_name, advertisement.location
+        );
+
+        let notifier = {
+            let socket = socket.clone();
+            let advertisement = advertisement.clone();
+            async move {
+                let group = SocketAddr::V4(SocketAddrV4::new(SSDP_ADDR, SSDP_PORT));
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    for (nt, usn) in advertisement.targets() {
+                        let message = advertisement.notify_alive(&nt, &usn);
+                        if let Err(e) = socket.send_to(message.as_bytes(), group).await {
+                            warn!("Failed to send SSDP notify: {}", e);
+                        }
+                    }
+                }
+            }
+        };
+        tokio::spawn(notifier);
+
+        let mut buf = [0u8; 2048];
+        loop {
+            let (len, peer) = match socket.recv_from(&mut buf).await {
+                Ok(received) => received,
+                Err(e) => {
+                    warn!("Failed to receive SSDP packet: {}", e);
+                    continue;
+                }
+            };
+            if !is_local_address(peer.ip()) {
+                continue;
+            }
+            let Some(st) = parse_search(&String::from_utf8_lossy(&buf[..len])) else {
+                continue;
+            };
+            for (st, usn) in advertisement.matching(&st) {
+                let response = advertisement.search_response(&st, &usn);
+                if let Err(e) = socket.send_to(response.as_bytes(), peer).await {
+                    warn!("Failed to answer SSDP search from {}: {}", peer, e);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,166 @@
+use crate::auth::{current_claims, resolve_user_id};
+use crate::consts;
+use crate::listening_sessions::ListeningSessionView;
+use crate::stats::MilestoneView;
+use crate::AppState;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use application::event::push::ServerEvent;
+use chrono::Utc;
+use domain::value::UserId;
+use futures::stream;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{timeout, Duration};
+
+/// 没有事件时发送注释行，避免代理断开空闲连接
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 注册服务器事件推送路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource(format!("{}/events", consts::URL_PATH_NATIVE_API))
+            .route(web::get().to(event_stream)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    /// 为 true 时推送当前用户的收听里程碑
+    pub milestones: Option<bool>,
+}
+
+/// 转换为 SSE 的事件名和数据，不属于该用户或未订阅的事件返回 None
+fn encode(
+    event: &ServerEvent,
+    user_id: i64,
+    include_milestones: bool,
+) -> Option<(&'static str, serde_json::Value)> {
+    let encoded = match event {
+        ServerEvent::ScanStarted { library_id } => (
+            "scanStarted",
+            json!({ "libraryId": library_id.to_string() }),
+        ),
+        ServerEvent::ScanProgress {
+            library_id,
+            processed_files,
+            total_files,
+        } => (
+            "scanProgress",
+            json!({
+                "libraryId": library_id.to_string(),
+                "processedFiles": processed_files,
+                "totalFiles": total_files,
+            }),
+        ),
+        ServerEvent::ScanEnded { library_id } => {
+            ("scanEnded", json!({ "libraryId": library_id.to_string() }))
+        }
+        ServerEvent::NowPlaying {
+            player_id,
+            user_id,
+            audio_file_id,
+        } => (
+            "nowPlaying",
+            json!({
+                "playerId": player_id.to_string(),
+                "userId": user_id.to_string(),
+                "songId": audio_file_id.to_string(),
+            }),
+        ),
+        ServerEvent::PlaybackStopped {
+            player_id,
+            audio_file_id,
+        } => (
+            "playbackStopped",
+            json!({
+                "playerId": player_id.to_string(),
+                "songId": audio_file_id.to_string(),
+            }),
+        ),
+        ServerEvent::PlaylistUpdated {
+            playlist_id,
+            user_id: owner_id,
+            deleted,
+        } => {
+            if owner_id.as_i64() != user_id {
+                return None;
+            }
+            (
+                "playlistUpdated",
+                json!({ "playlistId": playlist_id.to_string(), "deleted": deleted }),
+            )
+        }
+        ServerEvent::MilestoneReached(milestone) => {
+            if !include_milestones || milestone.user_id.as_i64() != user_id {
+                return None;
+            }
+            (
+                "milestoneReached",
+                serde_json::to_value(MilestoneView::new(milestone, None)).ok()?,
+            )
+        }
+        ServerEvent::ListeningSessionUpdated(session) => {
+            if !session.is_member(&UserId::from(user_id)) {
+                return None;
+            }
+            (
+                "listeningSessionUpdated",
+                serde_json::to_value(ListeningSessionView::new(session, Utc::now())).ok()?,
+            )
+        }
+        ServerEvent::ListeningSessionEnded { code, members } => {
+            if !members.iter().any(|id| id.as_i64() == user_id) {
+                return None;
+            }
+            ("listeningSessionEnded", json!({ "code": code }))
+        }
+    };
+    Some(encoded)
+}
+
+fn sse_frame(name: &str, data: &serde_json::Value) -> web::Bytes {
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", name, data))
+}
+
+/// 服务器事件流：/api/events（text/event-stream），`?milestones=true` 时包含收听里程碑
+pub async fn event_stream(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<EventStreamQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    let include_milestones = query.milestones.unwrap_or(false);
+
+    let receiver = state.push_hub.subscribe();
+    let body = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            let frame = match timeout(KEEPALIVE_INTERVAL, receiver.recv()).await {
+                Err(_) => web::Bytes::from_static(b": keepalive\n\n"),
+                Ok(Ok(event)) => match encode(&event, user_id, include_milestones) {
+                    Some((name, data)) => sse_frame(name, &data),
+                    None => continue,
+                },
+                // 客户端太慢丢了事件，提示其重新拉取状态
+                Ok(Err(RecvError::Lagged(missed))) => {
+                    sse_frame("lagged", &json!({ "missed": missed }))
+                }
+                Ok(Err(RecvError::Closed)) => return None,
+            };
+            return Some((Ok::<_, actix_web::Error>(frame), receiver));
+        }
+    });
+
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "text/event-stream"))
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(body)
+}
@@ -0,0 +1,118 @@
+use crate::auth::{bad_request, current_claims, resolve_user_id, ErrorResponse};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::query::get_feed::{Feed, GetFeed};
+use chrono::NaiveDateTime;
+use infra::repository::postgres::query::feed::FeedDaoImpl;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const DEFAULT_FEED_LIMIT: i32 = 100;
+
+/// 注册增量同步原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/feeds", consts::URL_PATH_NATIVE_API))
+            .route("/{kind}/{event}", web::get().to(get_feed)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    /// 上次同步返回的 nextToken，为空时从头开始
+    pub since: Option<String>,
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedItemView {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist_id: Option<String>,
+    pub at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedView {
+    pub items: Vec<FeedItemView>,
+    pub next_token: String,
+    pub has_more: bool,
+}
+
+fn parse_feed(kind: &str, event: &str) -> Option<Feed> {
+    match (kind, event) {
+        ("albums", "added") => Some(Feed::AddedAlbums),
+        ("songs", "added") => Some(Feed::AddedSongs),
+        ("albums", "played") => Some(Feed::PlayedAlbums),
+        ("songs", "played") => Some(Feed::PlayedSongs),
+        _ => None,
+    }
+}
+
+/// 增量同步：/api/feeds/{albums|songs}/{added|played}?since=
+pub async fn get_feed(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    query: web::Query<FeedQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let (kind, event) = path.into_inner();
+    let Some(feed) = parse_feed(&kind, &event) else {
+        return bad_request(format!("Unknown feed: {}/{}", kind, event));
+    };
+    let since = match query.since.as_deref().filter(|s| !s.is_empty()) {
+        Some(raw) => match raw.parse::<i64>() {
+            Ok(token) => token,
+            Err(_) => return bad_request(format!("Invalid sync token: {}", raw)),
+        },
+        None => 0,
+    };
+    // 新增条目是全库范围的，只有播放记录需要用户
+    let user_id = match feed {
+        Feed::PlayedAlbums | Feed::PlayedSongs => match resolve_user_id(&state, &claims).await {
+            Ok(id) => id,
+            Err(rsp) => return rsp,
+        },
+        Feed::AddedAlbums | Feed::AddedSongs => 0,
+    };
+
+    let get_feed = GetFeed::new(Arc::new(FeedDaoImpl::new(state.db.clone())));
+    match get_feed
+        .handle(
+            feed,
+            user_id,
+            since,
+            query.limit.unwrap_or(DEFAULT_FEED_LIMIT),
+        )
+        .await
+    {
+        Ok(page) => HttpResponse::Ok().json(FeedView {
+            items: page
+                .items
+                .into_iter()
+                .map(|item| FeedItemView {
+                    id: item.item_id.to_string(),
+                    name: item.name,
+                    album_id: item.album_id.map(|id| id.to_string()),
+                    artist_id: item.artist_id.map(|id| id.to_string()),
+                    at: item.at,
+                })
+                .collect(),
+            next_token: page.next_token.to_string(),
+            has_more: page.has_more,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
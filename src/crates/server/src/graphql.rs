@@ -0,0 +1,74 @@
+mod loaders;
+mod query;
+mod types;
+
+use crate::auth::{current_claims, resolve_user_id};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, Either, HttpRequest, HttpResponse};
+use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use infra::config::GraphqlConfig;
+use log::info;
+
+pub type RhythmSchema = Schema<query::QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// 当前请求的用户，收藏、播放列表和个人排行都以它为准
+#[derive(Debug, Clone)]
+pub(crate) struct Viewer {
+    pub user_id: i64,
+    pub username: String,
+}
+
+/// 未启用时返回 None，不注册路由
+pub fn build_schema(cfg: &GraphqlConfig) -> Option<RhythmSchema> {
+    if !cfg.enabled {
+        return None;
+    }
+    info!(
+        "GraphQL enabled at {} (max depth {}, max complexity {})",
+        consts::URL_PATH_GRAPHQL,
+        cfg.max_depth,
+        cfg.max_complexity
+    );
+    Some(
+        Schema::build(query::QueryRoot, EmptyMutation, EmptySubscription)
+            .limit_depth(cfg.max_depth)
+            .limit_complexity(cfg.max_complexity)
+            .finish(),
+    )
+}
+
+/// 注册 GraphQL 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig, schema: Option<RhythmSchema>) {
+    let Some(schema) = schema else {
+        return;
+    };
+    cfg.app_data(web::Data::new(schema))
+        .route(consts::URL_PATH_GRAPHQL, web::post().to(execute));
+}
+
+/// POST /graphql，DataLoader 按请求创建，同一请求内相同 ID 只查询一次
+pub async fn execute(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    schema: web::Data<RhythmSchema>,
+    request: GraphQLRequest,
+) -> Either<GraphQLResponse, HttpResponse> {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return Either::Right(rsp),
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return Either::Right(rsp),
+    };
+    let viewer = Viewer {
+        user_id,
+        username: claims.user_name,
+    };
+    let request = loaders::register(request.into_inner(), &state)
+        .data(state.clone())
+        .data(viewer);
+    Either::Left(schema.execute(request).await.into())
+}
@@ -0,0 +1,149 @@
+use crate::AppState;
+use application::query::dao::{AlbumDao, ArtistDao, AudioFileDao, PlaylistDao};
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::{Error, Request};
+use futures::future::try_join_all;
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::artist::ArtistDaoImpl;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use infra::repository::postgres::query::playlist::PlaylistDaoImpl;
+use model::album::Album;
+use model::artist::Artist;
+use model::audio_file::AudioFile;
+use model::playlist::Playlist;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 为一次请求注册所有 DataLoader
+pub(super) fn register(request: Request, state: &AppState) -> Request {
+    let db = || state.db.clone();
+    request
+        .data(DataLoader::new(
+            AlbumLoader(AlbumDaoImpl::new(db())),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            ArtistLoader(ArtistDaoImpl::new(db())),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            SongLoader(AudioFileDaoImpl::new(db())),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            AlbumSongsLoader(AudioFileDaoImpl::new(db())),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            ArtistAlbumsLoader(AlbumDaoImpl::new(db())),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            PlaylistLoader(PlaylistDaoImpl::new(db())),
+            tokio::spawn,
+        ))
+}
+
+pub(super) struct AlbumLoader(AlbumDaoImpl);
+
+impl Loader<i64> for AlbumLoader {
+    type Value = Arc<Album>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        let albums = self.0.get_by_ids(keys).await?;
+        Ok(albums
+            .into_iter()
+            .map(|album| (album.id, Arc::new(album)))
+            .collect())
+    }
+}
+
+pub(super) struct ArtistLoader(ArtistDaoImpl);
+
+impl Loader<i64> for ArtistLoader {
+    type Value = Arc<Artist>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        let artists = self.0.get_by_ids(keys).await?;
+        Ok(artists
+            .into_iter()
+            .map(|artist| (artist.id, Arc::new(artist)))
+            .collect())
+    }
+}
+
+pub(super) struct SongLoader(AudioFileDaoImpl);
+
+impl Loader<i64> for SongLoader {
+    type Value = Arc<AudioFile>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        let songs = self.0.get_by_ids(keys).await?;
+        Ok(songs
+            .into_iter()
+            .map(|song| (song.id, Arc::new(song)))
+            .collect())
+    }
+}
+
+/// 专辑 ID -> 按碟号、音轨排序的歌曲
+pub(super) struct AlbumSongsLoader(AudioFileDaoImpl);
+
+impl Loader<i64> for AlbumSongsLoader {
+    type Value = Vec<Arc<AudioFile>>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        let mut songs = self.0.get_by_album_ids(keys).await?;
+        songs.sort_by_key(|song| (song.album_id, song.disc_number, song.track_number));
+        let mut grouped: HashMap<i64, Self::Value> = HashMap::new();
+        for song in songs {
+            grouped
+                .entry(song.album_id)
+                .or_default()
+                .push(Arc::new(song));
+        }
+        Ok(grouped)
+    }
+}
+
+/// 艺术家 ID -> 专辑，DAO 没有批量接口，同一批次内并发查询
+pub(super) struct ArtistAlbumsLoader(AlbumDaoImpl);
+
+impl Loader<i64> for ArtistAlbumsLoader {
+    type Value = Vec<Arc<Album>>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        let albums = try_join_all(keys.iter().map(|id| self.0.get_by_artist_id(*id))).await?;
+        Ok(keys
+            .iter()
+            .copied()
+            .zip(albums.into_iter().map(|albums| {
+                let mut albums: Vec<_> = albums.into_iter().map(Arc::new).collect();
+                albums.sort_by_key(|album| album.year);
+                albums
+            }))
+            .collect())
+    }
+}
+
+/// 播放列表 ID -> 包含歌曲的播放列表
+pub(super) struct PlaylistLoader(PlaylistDaoImpl);
+
+impl Loader<i64> for PlaylistLoader {
+    type Value = Arc<Playlist>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        let playlists = try_join_all(keys.iter().map(|id| self.0.get_by_id(*id))).await?;
+        Ok(playlists
+            .into_iter()
+            .flatten()
+            .map(|playlist| (playlist.id, Arc::new(playlist)))
+            .collect())
+    }
+}
@@ -0,0 +1,207 @@
+use super::loaders::{AlbumLoader, ArtistLoader, PlaylistLoader, SongLoader};
+use super::types::{AlbumNode, ArtistNode, ChartItemNode, PlaylistNode, SongNode};
+use super::Viewer;
+use crate::AppState;
+use actix_web::web;
+use application::query::dao::{AlbumDao, ArtistDao, AudioFileDao, PlaylistDao};
+use application::query::get_charts::{ChartKind, GetCharts};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Enum, Error, Object, Result, ID};
+use chrono::{Duration, Local, NaiveDate};
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::artist::ArtistDaoImpl;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use infra::repository::postgres::query::play_stats::ChartDaoImpl;
+use infra::repository::postgres::query::playlist::PlaylistDaoImpl;
+use std::sync::Arc;
+
+const DEFAULT_LIMIT: i32 = 50;
+const MAX_LIMIT: i32 = 500;
+const DEFAULT_CHART_DAYS: i64 = 30;
+
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlbumSort {
+    Name,
+    Artist,
+    Newest,
+    Recent,
+    Frequent,
+    Rating,
+    Random,
+    /// 原始发行年份，没有时使用发行年份
+    OriginalYear,
+    /// 当前用户收藏的专辑
+    Starred,
+}
+
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "ChartKind")]
+pub enum ChartKindInput {
+    Artists,
+    Albums,
+    Songs,
+    Genres,
+}
+
+impl From<ChartKindInput> for ChartKind {
+    fn from(kind: ChartKindInput) -> Self {
+        match kind {
+            ChartKindInput::Artists => ChartKind::Artists,
+            ChartKindInput::Albums => ChartKind::Albums,
+            ChartKindInput::Songs => ChartKind::Songs,
+            ChartKindInput::Genres => ChartKind::Genres,
+        }
+    }
+}
+
+fn parse_id(id: &ID) -> Result<i64> {
+    id.parse::<i64>()
+        .map_err(|_| Error::new(format!("Invalid id: {}", id.as_str())))
+}
+
+fn paging(offset: Option<i32>, limit: Option<i32>) -> (i32, i32) {
+    (
+        offset.unwrap_or(0).max(0),
+        limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+    )
+}
+
+fn app_state<'a>(ctx: &Context<'a>) -> Result<&'a web::Data<AppState>> {
+    ctx.data::<web::Data<AppState>>()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn album(&self, ctx: &Context<'_>, id: ID) -> Result<Option<AlbumNode>> {
+        let loader = ctx.data::<DataLoader<AlbumLoader>>()?;
+        Ok(loader.load_one(parse_id(&id)?).await?.map(AlbumNode))
+    }
+
+    async fn albums(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default_with = "AlbumSort::Name")] sort: AlbumSort,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<AlbumNode>> {
+        let (offset, limit) = paging(offset, limit);
+        let dao = AlbumDaoImpl::new(app_state(ctx)?.db.clone());
+        let (albums, _) = match sort {
+            AlbumSort::Name => dao.get_by_name(offset, limit).await?,
+            AlbumSort::Artist => dao.get_by_artist(offset, limit).await?,
+            AlbumSort::Newest => dao.get_by_newest(offset, limit).await?,
+            AlbumSort::Recent => dao.get_by_recent(offset, limit).await?,
+            AlbumSort::Frequent => dao.get_by_frequent(offset, limit).await?,
+            AlbumSort::Rating => dao.get_by_rating(offset, limit).await?,
+            AlbumSort::Random => dao.get_by_random(offset, limit).await?,
+            AlbumSort::OriginalYear => dao.get_by_original_year(offset, limit).await?,
+            AlbumSort::Starred => {
+                let viewer = ctx.data::<Viewer>()?;
+                dao.get_by_starred(viewer.user_id, offset, limit).await?
+            }
+        };
+        Ok(albums
+            .into_iter()
+            .map(|album| AlbumNode(Arc::new(album)))
+            .collect())
+    }
+
+    async fn artist(&self, ctx: &Context<'_>, id: ID) -> Result<Option<ArtistNode>> {
+        let loader = ctx.data::<DataLoader<ArtistLoader>>()?;
+        Ok(loader.load_one(parse_id(&id)?).await?.map(ArtistNode))
+    }
+
+    /// 提供 query 时按名称搜索，否则按名称排序列出
+    async fn artists(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<String>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<ArtistNode>> {
+        let (offset, limit) = paging(offset, limit);
+        let dao = ArtistDaoImpl::new(app_state(ctx)?.db.clone());
+        let artists = match query.filter(|q| !q.trim().is_empty()) {
+            Some(query) => dao.search(&query, offset, limit).await?,
+            None => dao
+                .get_all()
+                .await?
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect(),
+        };
+        Ok(artists
+            .into_iter()
+            .map(|artist| ArtistNode(Arc::new(artist)))
+            .collect())
+    }
+
+    async fn song(&self, ctx: &Context<'_>, id: ID) -> Result<Option<SongNode>> {
+        let loader = ctx.data::<DataLoader<SongLoader>>()?;
+        Ok(loader.load_one(parse_id(&id)?).await?.map(SongNode))
+    }
+
+    /// 在标题、艺术家和专辑中搜索，不提供 query 时按标题列出
+    async fn songs(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<String>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<SongNode>> {
+        let (offset, limit) = paging(offset, limit);
+        let (songs, _) = AudioFileDaoImpl::new(app_state(ctx)?.db.clone())
+            .search(query.as_deref(), None, None, None, None, offset, limit)
+            .await?;
+        Ok(songs
+            .into_iter()
+            .map(|song| SongNode(Arc::new(song)))
+            .collect())
+    }
+
+    /// 所有者、协作者可见，公开播放列表所有人可见
+    async fn playlist(&self, ctx: &Context<'_>, id: ID) -> Result<Option<PlaylistNode>> {
+        let viewer = ctx.data::<Viewer>()?;
+        let loader = ctx.data::<DataLoader<PlaylistLoader>>()?;
+        let Some(playlist) = loader.load_one(parse_id(&id)?).await? else {
+            return Ok(None);
+        };
+        let visible = playlist.public
+            || playlist.owner_id == viewer.user_id
+            || playlist.collaborators.contains(&viewer.username);
+        Ok(visible.then(|| PlaylistNode::from(playlist.as_ref())))
+    }
+
+    /// 当前用户拥有或协作的播放列表
+    async fn playlists(&self, ctx: &Context<'_>) -> Result<Vec<PlaylistNode>> {
+        let viewer = ctx.data::<Viewer>()?;
+        let playlists = PlaylistDaoImpl::new(app_state(ctx)?.db.clone())
+            .get_editable_by(viewer.user_id)
+            .await?;
+        Ok(playlists.into_iter().map(PlaylistNode).collect())
+    }
+
+    /// 播放次数排行，默认为当前用户最近 30 天
+    async fn charts(
+        &self,
+        ctx: &Context<'_>,
+        kind: ChartKindInput,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        #[graphql(default)] server: bool,
+        limit: Option<i32>,
+    ) -> Result<Vec<ChartItemNode>> {
+        let viewer = ctx.data::<Viewer>()?;
+        let to = to.unwrap_or_else(|| Local::now().date_naive());
+        let from = from.unwrap_or_else(|| to - Duration::days(DEFAULT_CHART_DAYS - 1));
+        let user_id = (!server).then_some(viewer.user_id);
+        let (_, limit) = paging(None, limit);
+        let entries = GetCharts::new(Arc::new(ChartDaoImpl::new(app_state(ctx)?.db.clone())))
+            .handle(kind.into(), from, to, user_id, limit)
+            .await?;
+        Ok(entries.into_iter().map(ChartItemNode::from).collect())
+    }
+}
@@ -0,0 +1,351 @@
+use super::loaders::{
+    AlbumLoader, AlbumSongsLoader, ArtistAlbumsLoader, ArtistLoader, PlaylistLoader, SongLoader,
+};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Object, Result, SimpleObject, ID};
+use chrono::NaiveDateTime;
+use model::album::Album;
+use model::artist::Artist;
+use model::audio_file::AudioFile;
+use model::play_stats::ChartEntry;
+use model::playlist::{Playlist, PlaylistSummary, PlaylistTrack};
+use model::shared::{Annotation, Contributor};
+use std::sync::Arc;
+
+/// 播放和收藏信息
+#[derive(SimpleObject)]
+#[graphql(name = "Annotation")]
+pub struct AnnotationNode {
+    pub play_count: i32,
+    pub played_at: Option<NaiveDateTime>,
+    pub rating: i32,
+    pub starred: bool,
+    pub starred_at: Option<NaiveDateTime>,
+}
+
+impl From<&Annotation> for AnnotationNode {
+    fn from(annotation: &Annotation) -> Self {
+        Self {
+            play_count: annotation.play_count,
+            played_at: annotation.play_date,
+            rating: annotation.rating,
+            starred: annotation.starred,
+            starred_at: annotation.starred_at,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Contributor")]
+pub struct ContributorNode {
+    pub artist_id: ID,
+    pub artist_name: String,
+    pub role: String,
+    pub sub_role: Option<String>,
+}
+
+impl From<&Contributor> for ContributorNode {
+    fn from(contributor: &Contributor) -> Self {
+        Self {
+            artist_id: contributor.artist_id.into(),
+            artist_name: contributor.artist_name.clone(),
+            role: contributor.role.clone(),
+            sub_role: contributor.sub_role.clone(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "ChartItem")]
+pub struct ChartItemNode {
+    pub id: ID,
+    pub name: String,
+    pub play_count: i64,
+}
+
+impl From<ChartEntry> for ChartItemNode {
+    fn from(entry: ChartEntry) -> Self {
+        Self {
+            id: entry.item_id.into(),
+            name: entry.name,
+            play_count: entry.play_count,
+        }
+    }
+}
+
+pub struct AlbumNode(pub Arc<Album>);
+
+#[Object(name = "Album")]
+impl AlbumNode {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn year(&self) -> Option<i32> {
+        self.0.year
+    }
+
+    /// 原始发行年份，再版专辑早于 year
+    async fn original_year(&self) -> Option<i32> {
+        self.0.original_year
+    }
+
+    async fn genre(&self) -> Option<&str> {
+        self.0.genre.as_ref().map(|genre| genre.name.as_str())
+    }
+
+    async fn song_count(&self) -> i32 {
+        self.0.song_count
+    }
+
+    /// 秒
+    async fn duration(&self) -> i64 {
+        self.0.duration
+    }
+
+    async fn created_at(&self) -> NaiveDateTime {
+        self.0.created_at
+    }
+
+    async fn annotation(&self) -> AnnotationNode {
+        (&self.0.annotation).into()
+    }
+
+    async fn contributors(&self) -> Vec<ContributorNode> {
+        self.0.contributors.iter().map(Into::into).collect()
+    }
+
+    async fn artist(&self, ctx: &Context<'_>) -> Result<Option<ArtistNode>> {
+        let loader = ctx.data::<DataLoader<ArtistLoader>>()?;
+        Ok(loader.load_one(self.0.artist.id).await?.map(ArtistNode))
+    }
+
+    /// 按碟号和音轨排序
+    async fn songs(&self, ctx: &Context<'_>) -> Result<Vec<SongNode>> {
+        let loader = ctx.data::<DataLoader<AlbumSongsLoader>>()?;
+        let songs = loader.load_one(self.0.id).await?.unwrap_or_default();
+        Ok(songs.into_iter().map(SongNode).collect())
+    }
+}
+
+pub struct ArtistNode(pub Arc<Artist>);
+
+#[Object(name = "Artist")]
+impl ArtistNode {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn album_count(&self) -> i32 {
+        self.0.album_count
+    }
+
+    async fn song_count(&self) -> i32 {
+        self.0.song_count
+    }
+
+    async fn annotation(&self) -> AnnotationNode {
+        AnnotationNode {
+            play_count: self.0.played_count,
+            played_at: self.0.played_at,
+            rating: self.0.rating,
+            starred: self.0.starred,
+            starred_at: self.0.starred_at,
+        }
+    }
+
+    /// 按年份排序
+    async fn albums(&self, ctx: &Context<'_>) -> Result<Vec<AlbumNode>> {
+        let loader = ctx.data::<DataLoader<ArtistAlbumsLoader>>()?;
+        let albums = loader.load_one(self.0.id).await?.unwrap_or_default();
+        Ok(albums.into_iter().map(AlbumNode).collect())
+    }
+}
+
+pub struct SongNode(pub Arc<AudioFile>);
+
+#[Object(name = "Song")]
+impl SongNode {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn track_number(&self) -> i32 {
+        self.0.track_number
+    }
+
+    async fn disc_number(&self) -> i32 {
+        self.0.disc_number
+    }
+
+    async fn year(&self) -> Option<i32> {
+        self.0.year
+    }
+
+    async fn genre(&self) -> Option<&str> {
+        self.0.genre.as_ref().map(|genre| genre.name.as_str())
+    }
+
+    /// 秒
+    async fn duration(&self) -> i64 {
+        self.0.duration
+    }
+
+    async fn bit_rate(&self) -> i32 {
+        self.0.bit_rate
+    }
+
+    async fn size(&self) -> i64 {
+        self.0.size
+    }
+
+    async fn suffix(&self) -> &str {
+        &self.0.suffix
+    }
+
+    async fn annotation(&self) -> AnnotationNode {
+        (&self.0.annotation).into()
+    }
+
+    async fn contributors(&self) -> Vec<ContributorNode> {
+        self.0.contributors.iter().map(Into::into).collect()
+    }
+
+    async fn album(&self, ctx: &Context<'_>) -> Result<Option<AlbumNode>> {
+        let loader = ctx.data::<DataLoader<AlbumLoader>>()?;
+        Ok(loader.load_one(self.0.album_id).await?.map(AlbumNode))
+    }
+
+    async fn artist(&self, ctx: &Context<'_>) -> Result<Option<ArtistNode>> {
+        let loader = ctx.data::<DataLoader<ArtistLoader>>()?;
+        Ok(loader.load_one(self.0.artist.id).await?.map(ArtistNode))
+    }
+}
+
+/// 列表查询只有基本信息，条目和协作者在请求时才加载
+pub struct PlaylistNode(pub PlaylistSummary);
+
+impl From<&Playlist> for PlaylistNode {
+    fn from(playlist: &Playlist) -> Self {
+        Self(PlaylistSummary {
+            id: playlist.id,
+            name: playlist.name.clone(),
+            comment: playlist.comment.clone(),
+            duration: playlist.duration,
+            song_count: playlist.song_count,
+            owner_name: playlist.owner_name.clone(),
+            owner_id: playlist.owner_id,
+            public: playlist.public,
+            tags: playlist.tags.clone(),
+            created_at: playlist.created_at,
+            updated_at: playlist.updated_at,
+            deleted_at: None,
+        })
+    }
+}
+
+impl PlaylistNode {
+    async fn detail(&self, ctx: &Context<'_>) -> Result<Option<Arc<Playlist>>> {
+        let loader = ctx.data::<DataLoader<PlaylistLoader>>()?;
+        Ok(loader.load_one(self.0.id).await?)
+    }
+}
+
+#[Object(name = "Playlist")]
+impl PlaylistNode {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn comment(&self) -> Option<&str> {
+        self.0.comment.as_deref()
+    }
+
+    async fn owner(&self) -> &str {
+        &self.0.owner_name
+    }
+
+    async fn public(&self) -> bool {
+        self.0.public
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.0.tags
+    }
+
+    async fn song_count(&self) -> i32 {
+        self.0.song_count
+    }
+
+    /// 秒
+    async fn duration(&self) -> i32 {
+        self.0.duration
+    }
+
+    /// 秒级时间戳
+    async fn created_at(&self) -> i64 {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> i64 {
+        self.0.updated_at
+    }
+
+    async fn collaborators(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        Ok(self
+            .detail(ctx)
+            .await?
+            .map(|playlist| playlist.collaborators.clone())
+            .unwrap_or_default())
+    }
+
+    async fn entries(&self, ctx: &Context<'_>) -> Result<Vec<PlaylistEntryNode>> {
+        Ok(self
+            .detail(ctx)
+            .await?
+            .map(|playlist| {
+                playlist
+                    .tracks
+                    .iter()
+                    .cloned()
+                    .map(PlaylistEntryNode)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+pub struct PlaylistEntryNode(pub PlaylistTrack);
+
+#[Object(name = "PlaylistEntry")]
+impl PlaylistEntryNode {
+    /// 条目 ID，同一首歌可以出现多次
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn added_by(&self) -> Option<&str> {
+        self.0.added_by.as_deref()
+    }
+
+    async fn song(&self, ctx: &Context<'_>) -> Result<Option<SongNode>> {
+        let loader = ctx.data::<DataLoader<SongLoader>>()?;
+        Ok(loader.load_one(self.0.audio_file.id).await?.map(SongNode))
+    }
+}
@@ -0,0 +1,665 @@
+use crate::AppState;
+use anyhow::{anyhow, Context};
+use application::command::media_annotation::{ImportAnnotationCmd, MediaAnnotationService};
+use application::command::playlist::{CreatePlaylistCmd, UpdatePlaylistCmd};
+use application::command::user::CreateUserCmd;
+use application::context::AppContext;
+use application::error::AppError;
+use application::query::dao::{AlbumDao, AnnotationDao, ArtistDao, AudioFileDao, PlaylistDao};
+use domain::annotation::Kind;
+use domain::user::UserRoles;
+use domain::value::UserId;
+use infra::import::{
+    AnnotationRecord, CatalogMatcher, CatalogSong, ItunesLibrary, MatchKind, NavidromeAnnotation,
+    NavidromeDb, NavidromeItem, NavidromePlaylist, NavidromeSong,
+};
+use infra::repository::postgres::command::album::AlbumRepositoryImpl;
+use infra::repository::postgres::command::annotation::AnnotationRepositoryImpl;
+use infra::repository::postgres::command::artist::ArtistRepositoryImpl;
+use infra::repository::postgres::command::audio_file::AudioFileRepositoryImpl;
+use infra::repository::postgres::command::player::PlayerRepositoryImpl;
+use infra::repository::postgres::query::album::AlbumDaoImpl;
+use infra::repository::postgres::query::annotation::AnnotationDaoImpl;
+use infra::repository::postgres::query::artist::ArtistDaoImpl;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use infra::repository::postgres::query::playlist::PlaylistDaoImpl;
+use log::{info, warn};
+use rand::distributions::{Alphanumeric, DistString};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 无法解密原密码时生成的随机密码长度
+const GENERATED_PASSWORD_LENGTH: usize = 16;
+/// 报告中最多列出的未匹配条目
+const MAX_REPORTED_UNMATCHED: usize = 100;
+/// 导出注解时每次读取的用户数
+const EXPORT_USER_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    Navidrome(NavidromeImportOptions),
+    Itunes(ItunesImportOptions),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavidromeImportOptions {
+    pub db_path: PathBuf,
+    /// Navidrome 的 `PasswordEncryptionKey`
+    pub key: String,
+    /// (Navidrome 中的路径前缀, 本库的路径前缀)
+    pub path_maps: Vec<(String, String)>,
+}
+
+/// 导入注解文件时歌曲的匹配方式，专辑和艺术家总是按名称匹配
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchStrategy {
+    /// 完整路径或路径末尾几级目录
+    Path,
+    /// 规范化后的艺术家/专辑/标题
+    Metadata,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItunesImportOptions {
+    pub library_path: PathBuf,
+    /// 导入到的本地用户
+    pub username: String,
+    /// (资料库中的路径前缀, 本库的路径前缀)
+    pub path_maps: Vec<(String, String)>,
+}
+
+/// 待创建的播放列表，歌曲已对应到本库
+struct PlaylistImport {
+    owner_id: i64,
+    owner_name: String,
+    name: String,
+    comment: String,
+    public: bool,
+    song_ids: Vec<i64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub users_created: usize,
+    pub users_existing: usize,
+    /// 无法解密原密码的用户，使用随机密码创建
+    pub generated_passwords: BTreeMap<String, String>,
+    pub playlists_created: usize,
+    /// 所有者已有同名播放列表，跳过
+    pub playlists_skipped: usize,
+    pub annotations_imported: usize,
+    /// 各匹配方式命中的歌曲数
+    pub songs_matched: BTreeMap<String, usize>,
+    pub songs_unmatched: usize,
+    pub unmatched: Vec<String>,
+}
+
+impl ImportReport {
+    fn record_song(
+        &mut self,
+        found: Option<(i64, MatchKind)>,
+        describe: impl FnOnce() -> String,
+    ) -> Option<i64> {
+        match found {
+            Some((id, kind)) => {
+                *self.songs_matched.entry(format!("{:?}", kind)).or_default() += 1;
+                Some(id)
+            }
+            None => {
+                self.songs_unmatched += 1;
+                self.record_unmatched(describe());
+                None
+            }
+        }
+    }
+
+    fn record_unmatched(&mut self, item: String) {
+        if self.unmatched.len() < MAX_REPORTED_UNMATCHED {
+            self.unmatched.push(item);
+        }
+    }
+}
+
+pub async fn import(state: &AppState, source: ImportSource) -> anyhow::Result<ImportReport> {
+    match source {
+        ImportSource::Navidrome(options) => import_navidrome(state, options).await,
+        ImportSource::Itunes(options) => import_itunes(state, options).await,
+    }
+}
+
+/// 从 Navidrome 导入用户、播放列表、收藏、评分和播放次数。
+/// 已存在的用户直接复用，重复执行不会创建重复的用户和播放列表，播放次数取两边的较大值
+async fn import_navidrome(
+    state: &AppState,
+    options: NavidromeImportOptions,
+) -> anyhow::Result<ImportReport> {
+    let source = NavidromeDb::open(&options.db_path, &options.key)
+        .await
+        .with_context(|| format!("failed to open {}", options.db_path.display()))?;
+    let matcher = load_catalog(state, options.path_maps).await?;
+    let mut report = ImportReport::default();
+
+    let users = import_users(state, &source, &mut report).await?;
+    let playlists = navidrome_playlists(source.playlists().await?, &matcher, &users, &mut report);
+    create_playlists(state, playlists, &mut report).await?;
+    let annotations =
+        navidrome_annotations(source.annotations().await?, &matcher, &users, &mut report);
+    apply_annotations(state, annotations, &mut report).await?;
+    Ok(report)
+}
+
+async fn load_catalog(
+    state: &AppState,
+    path_maps: Vec<(String, String)>,
+) -> anyhow::Result<CatalogMatcher> {
+    let mut matcher = CatalogMatcher::new(path_maps);
+    let songs = AudioFileDaoImpl::new(state.db.clone()).get_all().await?;
+    info!("Matching against {} songs", songs.len());
+    for song in songs {
+        // 多艺术家的歌曲按每个艺术家各建一条索引
+        for artist in &song.artists {
+            matcher.add_song(CatalogSong {
+                id: song.id,
+                path: song.path.clone(),
+                title: song.title.clone(),
+                album: song.album.clone(),
+                artist: artist.name.clone(),
+            });
+        }
+    }
+    for album in AlbumDaoImpl::new(state.db.clone()).get_all().await? {
+        matcher.add_album(album.id, &album.name, &album.artist.name);
+    }
+    for artist in ArtistDaoImpl::new(state.db.clone()).get_all().await? {
+        matcher.add_artist(artist.id, &artist.name);
+    }
+    Ok(matcher)
+}
+
+/// 返回 Navidrome 用户 ID 到本地用户的映射
+async fn import_users(
+    state: &AppState,
+    source: &NavidromeDb,
+    report: &mut ImportReport,
+) -> anyhow::Result<HashMap<String, (UserId, String)>> {
+    let svc = crate::cli::user_service(state);
+    let mut users = HashMap::new();
+    for user in source.users().await? {
+        let local = match svc.find_user(&user.username).await {
+            Ok(local) => {
+                report.users_existing += 1;
+                local
+            }
+            Err(AppError::AggregateNotFound(..)) => {
+                let password = match user.password {
+                    Some(password) => password,
+                    None => {
+                        let password = Alphanumeric
+                            .sample_string(&mut rand::thread_rng(), GENERATED_PASSWORD_LENGTH);
+                        report
+                            .generated_passwords
+                            .insert(user.username.clone(), password.clone());
+                        password
+                    }
+                };
+                let (password, encrypted_password) =
+                    crate::cli::protect_password(state, &password)?;
+                svc.create_user(CreateUserCmd {
+                    username: user.username.clone(),
+                    password,
+                    encrypted_password,
+                    email: user.email,
+                    is_admin: user.is_admin,
+                    roles: UserRoles::default(),
+                    max_bit_rate: None,
+                    download_quota_mb: None,
+                    hide_explicit: false,
+                })
+                .await?;
+                report.users_created += 1;
+                svc.find_user(&user.username).await?
+            }
+            Err(e) => return Err(e.into()),
+        };
+        users.insert(user.id, (local.id, local.username));
+    }
+    Ok(users)
+}
+
+fn navidrome_playlists(
+    playlists: Vec<NavidromePlaylist>,
+    matcher: &CatalogMatcher,
+    users: &HashMap<String, (UserId, String)>,
+    report: &mut ImportReport,
+) -> Vec<PlaylistImport> {
+    let mut imports = Vec::new();
+    for playlist in playlists {
+        let Some((owner_id, owner_name)) = users.get(&playlist.owner_id) else {
+            warn!("Skipping playlist {} of unknown owner", playlist.name);
+            continue;
+        };
+        let song_ids = playlist
+            .tracks
+            .iter()
+            .filter_map(|song| match_navidrome_song(matcher, song, report))
+            .collect();
+        imports.push(PlaylistImport {
+            owner_id: owner_id.as_i64(),
+            owner_name: owner_name.clone(),
+            name: playlist.name,
+            comment: playlist.comment,
+            public: playlist.public,
+            song_ids,
+        });
+    }
+    imports
+}
+
+fn navidrome_annotations(
+    annotations: Vec<NavidromeAnnotation>,
+    matcher: &CatalogMatcher,
+    users: &HashMap<String, (UserId, String)>,
+    report: &mut ImportReport,
+) -> Vec<ImportAnnotationCmd> {
+    let mut cmds = Vec::new();
+    for annotation in annotations {
+        let Some((user_id, _)) = users.get(&annotation.user_id) else {
+            continue;
+        };
+        let target = match &annotation.item {
+            NavidromeItem::Song(song) => {
+                match_navidrome_song(matcher, song, report).map(|id| (Kind::AudioFile, id))
+            }
+            NavidromeItem::Album { name, artist } => {
+                let found = matcher.match_album(name, artist);
+                if found.is_none() {
+                    report.record_unmatched(format!("album {} - {}", artist, name));
+                }
+                found.map(|id| (Kind::Album, id))
+            }
+            NavidromeItem::Artist { name } => {
+                let found = matcher.match_artist(name);
+                if found.is_none() {
+                    report.record_unmatched(format!("artist {}", name));
+                }
+                found.map(|id| (Kind::Artist, id))
+            }
+        };
+        let Some((kind, item_id)) = target else {
+            continue;
+        };
+        cmds.push(ImportAnnotationCmd {
+            user_id: user_id.clone(),
+            kind,
+            item_id,
+            starred_at: annotation.starred_at,
+            rating: annotation.rating,
+            play_count: annotation.play_count,
+            played_at: annotation.played_at,
+        });
+    }
+    cmds
+}
+
+fn match_navidrome_song(
+    matcher: &CatalogMatcher,
+    song: &NavidromeSong,
+    report: &mut ImportReport,
+) -> Option<i64> {
+    let found = matcher.match_song(&song.path, &song.title, &song.album, &song.artist);
+    report.record_song(found, || {
+        format!("{} - {} ({})", song.artist, song.title, song.path)
+    })
+}
+
+/// 从 iTunes 资料库 XML 导入评分、播放次数、喜爱和普通播放列表。
+/// 资料库只属于一个人，全部导入到指定的已有用户
+async fn import_itunes(
+    state: &AppState,
+    options: ItunesImportOptions,
+) -> anyhow::Result<ImportReport> {
+    let library = ItunesLibrary::open(&options.library_path)
+        .with_context(|| format!("failed to read {}", options.library_path.display()))?;
+    let user = crate::cli::user_service(state)
+        .find_user(&options.username)
+        .await?;
+    let matcher = load_catalog(state, options.path_maps).await?;
+    let mut report = ImportReport::default();
+
+    // 只匹配有数据或在播放列表中的曲目，资料库里其余的曲目与导入无关
+    let listed: HashSet<i64> = library
+        .playlists
+        .iter()
+        .flat_map(|playlist| playlist.track_ids.iter().copied())
+        .collect();
+    let mut track_ids: Vec<i64> = library.tracks.keys().copied().collect();
+    track_ids.sort_unstable();
+
+    let mut song_ids = HashMap::new();
+    let mut rated_albums = HashSet::new();
+    let mut cmds = Vec::new();
+    for track_id in track_ids {
+        let track = &library.tracks[&track_id];
+        let annotated = track.play_count > 0 || track.rating > 0 || track.loved;
+        if !annotated && track.album_rating == 0 && !listed.contains(&track_id) {
+            continue;
+        }
+        let path = track.path.as_deref().unwrap_or_default();
+        let found = matcher.match_song(path, &track.title, &track.album, &track.artist);
+        let Some(song_id) = report.record_song(found, || {
+            format!("{} - {} ({})", track.artist, track.title, path)
+        }) else {
+            continue;
+        };
+        song_ids.insert(track_id, song_id);
+        if annotated {
+            cmds.push(ImportAnnotationCmd {
+                user_id: user.id.clone(),
+                kind: Kind::AudioFile,
+                item_id: song_id,
+                // 资料库不记录标记喜爱的时间
+                starred_at: track.loved.then(|| chrono::Utc::now().naive_utc()),
+                rating: track.rating,
+                play_count: track.play_count,
+                played_at: track.played_at,
+            });
+        }
+        if track.album_rating > 0 {
+            match matcher.match_album(&track.album, &track.album_artist) {
+                Some(album_id) if rated_albums.insert(album_id) => {
+                    cmds.push(ImportAnnotationCmd {
+                        user_id: user.id.clone(),
+                        kind: Kind::Album,
+                        item_id: album_id,
+                        starred_at: None,
+                        rating: track.album_rating,
+                        play_count: 0,
+                        played_at: None,
+                    });
+                }
+                Some(_) => {}
+                None => report
+                    .record_unmatched(format!("album {} - {}", track.album_artist, track.album)),
+            }
+        }
+    }
+
+    let playlists = library
+        .playlists
+        .into_iter()
+        .map(|playlist| PlaylistImport {
+            owner_id: user.id.as_i64(),
+            owner_name: user.username.clone(),
+            name: playlist.name,
+            comment: String::new(),
+            public: false,
+            song_ids: playlist
+                .track_ids
+                .iter()
+                .filter_map(|track_id| song_ids.get(track_id).copied())
+                .collect(),
+        })
+        .collect();
+    create_playlists(state, playlists, &mut report).await?;
+    apply_annotations(state, cmds, &mut report).await?;
+    Ok(report)
+}
+
+/// 导出所有用户的收藏、评分和播放次数。条目以路径和标签记录，
+/// 只取消过收藏、没有其他数据的注解不导出
+pub async fn export_annotations(state: &AppState) -> anyhow::Result<Vec<AnnotationRecord>> {
+    let songs: HashMap<i64, _> = AudioFileDaoImpl::new(state.db.clone())
+        .get_all()
+        .await?
+        .into_iter()
+        .map(|song| (song.id, song))
+        .collect();
+    let albums: HashMap<i64, _> = AlbumDaoImpl::new(state.db.clone())
+        .get_all()
+        .await?
+        .into_iter()
+        .map(|album| (album.id, album))
+        .collect();
+    let artists: HashMap<i64, _> = ArtistDaoImpl::new(state.db.clone())
+        .get_all()
+        .await?
+        .into_iter()
+        .map(|artist| (artist.id, artist))
+        .collect();
+    let annotation_dao = AnnotationDaoImpl::new(state.db.clone());
+    let svc = crate::cli::user_service(state);
+
+    let mut records = Vec::new();
+    let mut offset = 0;
+    loop {
+        let (users, _) = svc.list_users(offset, EXPORT_USER_PAGE_SIZE).await?;
+        if users.is_empty() {
+            break;
+        }
+        offset += users.len() as u64;
+        for user in users {
+            for annotation in annotation_dao.get_by_user(user.id.as_i64(), None).await? {
+                if !annotation.starred && annotation.rating == 0 && annotation.play_count == 0 {
+                    continue;
+                }
+                let mut record = AnnotationRecord {
+                    username: user.username.clone(),
+                    kind: String::new(),
+                    path: String::new(),
+                    title: String::new(),
+                    album: String::new(),
+                    artist: String::new(),
+                    starred: annotation.starred,
+                    starred_at: annotation.starred.then_some(annotation.starred_at),
+                    rating: annotation.rating,
+                    play_count: annotation.play_count,
+                    played_at: (annotation.play_count > 0).then_some(annotation.played_at),
+                };
+                // 已删除的条目没有可对应的信息，跳过
+                match annotation.item_kind.parse::<Kind>() {
+                    Ok(Kind::AudioFile) => {
+                        let Some(song) = songs.get(&annotation.item_id) else {
+                            continue;
+                        };
+                        record.kind = "song".to_string();
+                        record.path = song.path.clone();
+                        record.title = song.title.clone();
+                        record.album = song.album.clone();
+                        record.artist = song
+                            .artists
+                            .first()
+                            .map(|artist| artist.name.clone())
+                            .unwrap_or_default();
+                    }
+                    Ok(Kind::Album) => {
+                        let Some(album) = albums.get(&annotation.item_id) else {
+                            continue;
+                        };
+                        record.kind = "album".to_string();
+                        record.album = album.name.clone();
+                        record.artist = album.artist.name.clone();
+                    }
+                    Ok(Kind::Artist) => {
+                        let Some(artist) = artists.get(&annotation.item_id) else {
+                            continue;
+                        };
+                        record.kind = "artist".to_string();
+                        record.artist = artist.name.clone();
+                    }
+                    _ => continue,
+                }
+                records.push(record);
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// 导入 `export_annotations` 导出的注解，按用户名对应本地用户，不存在的用户跳过。
+/// 与其他导入一样不会取消已有的收藏或覆盖评分，播放次数取较大值
+pub async fn import_annotation_records(
+    state: &AppState,
+    records: Vec<AnnotationRecord>,
+    strategy: MatchStrategy,
+) -> anyhow::Result<ImportReport> {
+    let matcher = load_catalog(state, Vec::new()).await?;
+    let svc = crate::cli::user_service(state);
+    let mut report = ImportReport::default();
+    let mut users: HashMap<String, Option<UserId>> = HashMap::new();
+    let mut cmds = Vec::new();
+    for record in records {
+        let user_id = match users.get(&record.username) {
+            Some(user_id) => user_id.clone(),
+            None => {
+                let user_id = match svc.find_user(&record.username).await {
+                    Ok(user) => {
+                        report.users_existing += 1;
+                        Some(user.id)
+                    }
+                    Err(AppError::AggregateNotFound(..)) => {
+                        report.record_unmatched(format!("user {}", record.username));
+                        None
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                users.insert(record.username.clone(), user_id.clone());
+                user_id
+            }
+        };
+        let Some(user_id) = user_id else {
+            continue;
+        };
+        let target = match record.kind.as_str() {
+            "song" => {
+                let found = match strategy {
+                    MatchStrategy::Path => matcher.match_song_by_path(&record.path),
+                    MatchStrategy::Metadata => {
+                        matcher.match_song_by_tags(&record.title, &record.album, &record.artist)
+                    }
+                };
+                report
+                    .record_song(found, || {
+                        format!("{} - {} ({})", record.artist, record.title, record.path)
+                    })
+                    .map(|id| (Kind::AudioFile, id))
+            }
+            "album" => {
+                let found = matcher.match_album(&record.album, &record.artist);
+                if found.is_none() {
+                    report.record_unmatched(format!("album {} - {}", record.artist, record.album));
+                }
+                found.map(|id| (Kind::Album, id))
+            }
+            "artist" => {
+                let found = matcher.match_artist(&record.artist);
+                if found.is_none() {
+                    report.record_unmatched(format!("artist {}", record.artist));
+                }
+                found.map(|id| (Kind::Artist, id))
+            }
+            kind => {
+                report.record_unmatched(format!("unknown kind {}", kind));
+                None
+            }
+        };
+        let Some((kind, item_id)) = target else {
+            continue;
+        };
+        cmds.push(ImportAnnotationCmd {
+            user_id,
+            kind,
+            item_id,
+            starred_at: record.starred.then(|| {
+                record
+                    .starred_at
+                    .unwrap_or_else(|| chrono::Utc::now().naive_utc())
+            }),
+            rating: record.rating.clamp(0, 5),
+            play_count: record.play_count.max(0),
+            played_at: record.played_at,
+        });
+    }
+    apply_annotations(state, cmds, &mut report).await?;
+    Ok(report)
+}
+
+/// 所有者已有同名播放列表时跳过，重复导入不会产生重复的播放列表
+async fn create_playlists(
+    state: &AppState,
+    playlists: Vec<PlaylistImport>,
+    report: &mut ImportReport,
+) -> anyhow::Result<()> {
+    let svc = crate::playlist_app_service(state);
+    let playlist_dao = PlaylistDaoImpl::new(state.db.clone());
+    for playlist in playlists {
+        let exists = playlist_dao
+            .get_by_owner_id(playlist.owner_id)
+            .await?
+            .iter()
+            .any(|existing| existing.name == playlist.name);
+        if exists {
+            report.playlists_skipped += 1;
+            continue;
+        }
+
+        let created = svc
+            .create_playlist(CreatePlaylistCmd {
+                playlist_id: None,
+                name: Some(playlist.name),
+                owner_id: playlist.owner_id,
+                owner_name: playlist.owner_name,
+                song_ids: playlist.song_ids,
+            })
+            .await?;
+        if !playlist.comment.is_empty() || playlist.public {
+            svc.update_playlist(UpdatePlaylistCmd {
+                playlist_id: created.id.as_i64(),
+                user_id: playlist.owner_id,
+                name: None,
+                comment: Some(playlist.comment).filter(|comment| !comment.is_empty()),
+                public: Some(playlist.public),
+                song_ids_to_add: Vec::new(),
+                song_indexes_to_remove: Vec::new(),
+            })
+            .await?;
+        }
+        report.playlists_created += 1;
+    }
+    Ok(())
+}
+
+async fn apply_annotations(
+    state: &AppState,
+    cmds: Vec<ImportAnnotationCmd>,
+    report: &mut ImportReport,
+) -> anyhow::Result<()> {
+    let annotation_repo = Arc::new(AnnotationRepositoryImpl::new(state.db.clone()));
+    let svc = MediaAnnotationService::new(
+        annotation_repo.clone(),
+        annotation_repo,
+        Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+        Arc::new(AlbumRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(ArtistRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(PlayerRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+        state.clock.clone(),
+    );
+    let ctx = AppContext::new();
+    for cmd in cmds {
+        let item_id = cmd.item_id;
+        svc.import_annotation(&ctx, cmd)
+            .await
+            .map_err(|e| anyhow!("failed to import annotation of {}: {}", item_id, e))?;
+        report.annotations_imported += 1;
+    }
+    Ok(())
+}
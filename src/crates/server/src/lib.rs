@@ -1,17 +1,45 @@
+pub mod admin;
+pub mod annotations;
+pub mod api_v1;
+pub mod app_passwords;
 pub mod auth;
+pub mod cli;
+pub mod client_ip;
 pub mod consts;
+pub mod dlna;
+pub mod events;
+pub mod feeds;
+pub mod graphql;
+pub mod import;
+pub mod listening_sessions;
 pub mod middleware;
+pub mod notes;
+pub mod players;
+pub mod playlists;
 pub mod resources;
+pub mod scan;
+pub mod signed_urls;
+pub mod stats;
 pub mod subsonic;
+pub mod tls;
+pub mod upload;
 
 use application::auth::AuthService;
 use application::command::album::AlbumService;
 use application::command::artist::ArtistService;
 use application::command::audio_file::AudioFileService;
 use application::command::cover_art::CoverArtService;
+use application::command::file_check::FileCheckService;
 use application::command::genre::GenreService;
+use application::command::library::LibraryCommandService;
+use application::command::listening_session::ListeningSessionService;
+use application::command::media_asset::MediaAssetService;
 use application::command::media_parse::MediaFileParseService;
-use application::command::shared::IdGenerator;
+use application::command::parse_pool::{ParsePoolOptions, ParseWorkerPool};
+use application::command::playlist::PlaylistAppService;
+use application::command::settings::SettingsService;
+use application::command::shared::{Clock, IdGenerator, SystemClock};
+use application::command::silence_analysis::SilenceAnalysisService;
 use application::event::coordinator::register::register_coordinators;
 use application::event::event_bus::EventBus;
 use application::event::handler::album::registry::register_handlers as register_album_handlers;
@@ -19,15 +47,39 @@ use application::event::handler::artist::registry::register_handlers as register
 use application::event::handler::audio_file::registry::register_handlers as register_audio_file_handlers;
 use application::event::handler::cover_art::registry::register_handlers as register_cover_art_handlers;
 use application::event::handler::genre::registry::register_handlers as register_genre_handlers;
+use application::event::handler::media_asset::registry::register_handlers as register_media_asset_handlers;
 use application::event::handler::on_library_file_added::OnLibraryFileAddedHandler;
 use application::event::handler::projector::registry::register_handlers;
+use application::event::handler::push::register_handlers as register_push_handlers;
+use application::event::handler::silence_analysis::registry::register_handlers as register_silence_analysis_handlers;
+use application::event::handler::trash::registry::register_handlers as register_trash_handlers;
+use application::event::push::ServerEventHub;
+use application::query::get_home::HomeRowCache;
+use application::query::get_library_stats::LibraryStatsCache;
 use application::shared::SystemConfigStore;
-use domain::library::LibraryEvent;
-use infra::auth::{BcryptPasswordHasher, JwtTokenService};
-use infra::config::AppConfigImpl;
+use domain::library::{LibraryCredentialsRepository, LibraryEvent};
+use infra::auth::{ConfiguredPasswordHasher, JwtTokenService};
+use infra::config::{AppConfigImpl, BufferedRepository};
+use infra::event_bus::dead_letter::DeadLetterQueue;
 use infra::event_bus::in_memory::InMemoryEventBus;
+use infra::event_bus::outbox::OutboxRelay;
+use infra::file_type_detector::DefaultFileTypeDetector;
 use infra::id_generator::SnowflakeIdGenerator;
 use infra::metadata::audio_metadata_reader::AudioMetadataReaderImpl;
+use infra::metadata::rule_config::ReloadableRuleEngine;
+use infra::metadata::silence_detector::FfmpegSilenceDetector;
+use infra::musicbrainz::MusicBrainzClient;
+use infra::repository::postgres::command::audible_range::AudibleRangeStoreImpl;
+use infra::repository::postgres::command::audio_file_availability::AudioFileAvailabilityStoreImpl;
+use infra::repository::postgres::command::dead_letter::DeadLetterRepositoryImpl;
+use infra::repository::postgres::command::event_outbox::OutboxRepositoryImpl;
+use infra::repository::postgres::command::processed_event::ProcessedEventRepositoryImpl;
+use infra::repository::postgres::command::library::{
+    LibraryCredentialsRepositoryImpl, LibraryRepositoryImpl,
+};
+use infra::repository::postgres::command::playlist::PlaylistRepositoryImpl;
+use infra::repository::postgres::command::scan_checkpoint::ScanCheckpointRepositoryImpl;
+use infra::repository::postgres::command::scan_error::ScanErrorRepositoryImpl;
 use infra::repository::postgres::command::system_config::SystemConfigStoreImpl;
 use infra::repository::postgres::command::user::UserRepositoryImpl;
 use infra::Aes256GcmEncryptor;
@@ -37,39 +89,86 @@ use infra::repository::buffered::command::{
     audio_file::BufferedAudioFileRepository, cover_art::BufferedCoverArtRepository,
     genre::BufferedGenreRepository,
 };
+use infra::repository::buffered::memtable::MemtableRegistry;
 use infra::repository::buffered::query::{
-    album_stats::BufferedAlbumStatsRepository, genre_stats::BufferedGenreStatsRepository,
+    album_stats::BufferedAlbumStatsRepository, format_stats::BufferedFormatStatsRepository,
+    genre_stats::BufferedGenreStatsRepository,
     participant_stats::BufferedParticipantStatsRepository,
 };
+use infra::repository::in_memory::processed_event::InMemoryProcessedEventLedger;
 use infra::repository::in_memory::scan_status::InMemoryScanStatusRepository;
 use infra::repository::postgres::command::{
-    album::AlbumRepositoryImpl, artist::ArtistRepositoryImpl, audio_file::AudioFileRepositoryImpl,
-    cover_art::CoverArtRepositoryImpl, genre::GenreRepositoryImpl,
+    album::AlbumRepositoryImpl,
+    artist::{ArtistAliasRepositoryImpl, ArtistRepositoryImpl},
+    audio_file::AudioFileRepositoryImpl,
+    cover_art::CoverArtRepositoryImpl,
+    genre::{GenreAliasRepositoryImpl, GenreRepositoryImpl},
+    media_asset::MediaAssetRepositoryImpl,
 };
+use infra::repository::postgres::query::format_stats::FormatStatsRepositoryImpl;
 use infra::repository::postgres::query::genre::GenreStatsRepositoryImpl;
+use infra::repository::postgres::query::listening_report::ListeningClockRepositoryImpl;
+use infra::repository::postgres::query::listening_streak::ListeningStreakRepositoryImpl;
+use infra::repository::postgres::query::play_stats::PlayStatsRepositoryImpl;
+use infra::repository::postgres::query::star_stats::StarStatsRepositoryImpl;
 use infra::repository::postgres::query::{
     album_location::MysqlAlbumLocationRepository, album_stats::MysqlAlbumStatsRepository,
     artist_location::MysqlArtistLocationRepository,
     participant_stats::MysqlParticipantStatsRepository,
     playback_history::PlaybackHistoryRepositoryImpl,
 };
+use infra::repository::postgres::query_metrics::QueryMetrics;
 use infra::storage::factory::StorageClientFactoryImpl;
-use infra::{CoverArtCacheImpl, FfmpegStreamer, StreamCacheImpl};
+use infra::{
+    CoverArtCacheImpl, FfmpegStreamer, FsPlaylistCoverStore, FsThumbnailStore, StreamCacheImpl,
+};
+use middleware::rate_limit::AuthLimiter;
 use model::scan_status::ScanStatusRepository;
 use sea_orm::DatabaseConnection;
 use sea_orm::{ActiveModelTrait, ConnectOptions, ConnectionTrait, Database, DbBackend, Statement};
 use std::sync::Arc;
 use tokio::time::Duration;
 
+/// 每个推送连接最多积压的事件数
+const PUSH_HUB_CAPACITY: usize = 256;
+/// outbox relay 的轮询间隔
+const OUTBOX_RELAY_INTERVAL: Duration = Duration::from_secs(1);
+/// 事件去重记录的保留天数，需覆盖 outbox 和死信重放的时间窗口
+const PROCESSED_EVENT_RETENTION_DAYS: i64 = 7;
+/// 写 memtable 的投影在进程内保留的去重记录数
+const BUFFERED_PROCESSED_EVENT_CAPACITY: usize = 200_000;
+
 pub struct AppState {
     pub app_cfg: AppConfigImpl,
     pub db: DatabaseConnection,
     pub id_generator: Arc<dyn IdGenerator>,
+    pub clock: Arc<dyn Clock>,
     pub event_bus: InMemoryEventBus,
     pub scan_repo: Arc<dyn ScanStatusRepository + Send + Sync>,
     pub cover_art_cache: Arc<CoverArtCacheImpl>,
     pub stream_cache: Arc<StreamCacheImpl>,
     pub transcoder: Arc<FfmpegStreamer>,
+    pub rule_engine: Arc<ReloadableRuleEngine>,
+    pub push_hub: ServerEventHub,
+    /// 缓冲仓库的 memtable，用于指标采集和关闭时 flush
+    pub memtables: MemtableRegistry,
+    pub outbox_relay: Arc<OutboxRelay>,
+    /// 扫描文件的解析工作池，订阅事件时创建
+    pub parse_pool: Option<Arc<ParseWorkerPool>>,
+    /// 登录和 Subsonic 认证的限流状态，所有 worker 共享
+    pub auth_limiter: Arc<AuthLimiter>,
+    /// 数据库查询次数和耗时
+    pub query_metrics: Arc<QueryMetrics>,
+    /// 未启用 MusicBrainz 时为 None
+    pub musicbrainz: Option<Arc<MusicBrainzClient>>,
+    /// 首页推荐行的缓存
+    pub home_cache: Arc<HomeRowCache>,
+    /// 媒体库统计的短时缓存
+    pub library_stats_cache: Arc<LibraryStatsCache>,
+    /// 进行中的一起听会话
+    pub listening_sessions: Arc<ListeningSessionService>,
+    /// 首尾静音分析，同一时间只运行一次
+    pub silence_analysis: Arc<SilenceAnalysisService>,
 }
 
 impl AppState {
@@ -100,9 +199,22 @@ impl AppState {
         db
     }
 
-    pub async fn new(db: DatabaseConnection, app_cfg: AppConfigImpl) -> Self {
-        let id_generator: Arc<dyn IdGenerator> = Arc::new(SnowflakeIdGenerator::new(1).unwrap());
-        let event_bus = InMemoryEventBus::new();
+    pub async fn new(mut db: DatabaseConnection, app_cfg: AppConfigImpl) -> Self {
+        // 在克隆连接之前安装，所有仓库的查询都会被统计
+        let query_metrics = Arc::new(QueryMetrics::new());
+        query_metrics.install(&mut db, app_cfg.clone());
+        let node_id = app_cfg.node_id();
+        log::info!("Using snowflake node id {}", node_id);
+        let id_generator: Arc<dyn IdGenerator> =
+            Arc::new(SnowflakeIdGenerator::new(node_id).expect("Invalid snowflake node id"));
+        let dead_letters = Arc::new(DeadLetterQueue::new(Arc::new(
+            DeadLetterRepositoryImpl::new(db.clone()),
+        )));
+        let event_bus = InMemoryEventBus::new().with_dead_letter_queue(dead_letters);
+        let outbox_relay = Arc::new(OutboxRelay::new(
+            Arc::new(OutboxRepositoryImpl::new(db.clone())),
+            event_bus.clone(),
+        ));
 
         // 初始化封面缓存
         let cache_cfg = app_cfg.cache();
@@ -125,19 +237,109 @@ impl AppState {
             transcoding_cfg.chunk_size,
         ));
 
+        // 加载元数据规则，并在规则文件变化时热加载
+        let metadata_cfg = app_cfg.metadata();
+        let rule_engine = Arc::new(ReloadableRuleEngine::from_file(&metadata_cfg.rules_file));
+        if metadata_cfg.reload_interval_secs > 0 {
+            rule_engine.start_watcher(Duration::from_secs(metadata_cfg.reload_interval_secs));
+        }
+        let config_store = SystemConfigStoreImpl::new(db.clone());
+        let settings_service = SettingsService::new(
+            Arc::new(config_store.clone()),
+            vec![Arc::new(app_cfg.clone())],
+        );
+        if let Err(e) = settings_service.reload().await {
+            log::error!("Failed to apply system settings: {}", e);
+        }
+        let protected_artists = admin::metadata::load_protected_artists(&config_store).await;
+        if !protected_artists.is_empty() {
+            if let Err(e) = rule_engine.set_protected_artists(protected_artists) {
+                log::error!("Failed to apply protected artists: {}", e);
+            }
+        }
+        let auth_limiter = Arc::new(AuthLimiter::new(app_cfg.rate_limit()));
+        let musicbrainz_cfg = app_cfg.musicbrainz();
+        let musicbrainz = musicbrainz_cfg.enabled.then(|| {
+            Arc::new(
+                MusicBrainzClient::new(&musicbrainz_cfg)
+                    .expect("Failed to create MusicBrainz client"),
+            )
+        });
+        let genre_aliases =
+            admin::genre::load_genre_aliases(&GenreAliasRepositoryImpl::new(db.clone())).await;
+        if !genre_aliases.is_empty() {
+            if let Err(e) = rule_engine.set_genre_aliases(genre_aliases) {
+                log::error!("Failed to apply genre aliases: {}", e);
+            }
+        }
+
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let push_hub = ServerEventHub::new(PUSH_HUB_CAPACITY);
+        let listening_sessions = Arc::new(ListeningSessionService::new(
+            push_hub.clone(),
+            clock.clone(),
+        ));
+        let silence_analysis = Arc::new(SilenceAnalysisService::new(
+            Arc::new(AudibleRangeStoreImpl::new(db.clone())),
+            Arc::new(FfmpegSilenceDetector::new(
+                transcoding_cfg.ffmpeg_path.clone(),
+            )),
+            clock.clone(),
+        ));
+
         Self {
             app_cfg,
             db,
             id_generator,
+            clock,
             event_bus,
             scan_repo: Arc::new(InMemoryScanStatusRepository::new()),
             cover_art_cache,
             stream_cache,
             transcoder,
+            rule_engine,
+            push_hub,
+            memtables: MemtableRegistry::new(),
+            outbox_relay,
+            parse_pool: None,
+            auth_limiter,
+            query_metrics,
+            musicbrainz,
+            home_cache: Arc::new(HomeRowCache::new()),
+            library_stats_cache: Arc::new(LibraryStatsCache::new()),
+            listening_sessions,
+            silence_analysis,
         }
     }
 }
 
+/// 服务器停止接收请求后调用：排空事件总线，flush 缓冲仓库，最后关闭数据库连接池
+pub async fn shutdown(state: &AppState, deadline: Duration) {
+    let graceful = async {
+        // 先把 outbox 中已提交的事件发布出去
+        state.outbox_relay.relay_pending().await;
+        state.event_bus.drain().await;
+        // 解析产生的事件会再次进入事件总线
+        if let Some(parse_pool) = &state.parse_pool {
+            parse_pool.drain().await;
+            state.event_bus.drain().await;
+        }
+        // 事件处理器会写入统计类 memtable，所以要在事件排空后再 flush
+        let flushed = state.memtables.flush_all().await;
+        log::info!("Flushed {} buffered items before shutdown", flushed);
+    };
+    if tokio::time::timeout(deadline, graceful).await.is_err() {
+        log::warn!(
+            "Shutdown deadline of {:?} exceeded, {} events still in flight; pending writes may be lost",
+            deadline,
+            state.event_bus.in_flight()
+        );
+    }
+    if let Err(e) = state.db.clone().close().await {
+        log::warn!("Failed to close database pool: {}", e);
+    }
+}
+
 pub async fn init_admin_user(state: &AppState) {
     use log::{info, warn};
     use rand::Rng;
@@ -173,8 +375,7 @@ pub async fn init_admin_user(state: &AppState) {
 
     let user_repo: Arc<dyn domain::user::UserRepository> =
         Arc::new(UserRepositoryImpl::new(state.db.clone()));
-    let hasher: Arc<dyn application::auth::PasswordHasher> =
-        Arc::new(BcryptPasswordHasher::new(10));
+    let hasher: Arc<dyn application::auth::PasswordHasher> = Arc::new(password_hasher(state));
     let encryptor: Arc<dyn application::auth::PasswordEncryptor> = Arc::new(
         Aes256GcmEncryptor::new(&state.app_cfg.password_encryption_key())
             .expect("Failed to create password encryptor"),
@@ -252,6 +453,14 @@ pub async fn init_music_folders(state: &AppState) {
             scan_status: Set(1_i32), // Idle
             last_scan_at: Set(zero_time),
             version: Set(1_i64),
+            enabled: Set(true),
+            scan_interval_minutes: Set(None),
+            kind: Set("music".to_string()),
+            include_patterns: Set(serde_json::json!([])),
+            exclude_patterns: Set(serde_json::json!([])),
+            scan_hidden: Set(false),
+            unavailable_since: Set(None),
+            hide_unavailable: Set(false),
         };
 
         match library_model.insert(&state.db).await {
@@ -278,57 +487,82 @@ pub async fn init_music_folders(state: &AppState) {
 pub async fn setup_event_bus(state: &mut AppState) {
     setup_application_handlers(state).await;
 
-    // 创建共享的 buffered repositories
+    // 创建共享的 buffered repositories，参数来自 [ingest] 配置
+    let ingest = state.app_cfg.ingest();
     let album_repository_impl =
         AlbumRepositoryImpl::new(state.db.clone(), state.id_generator.clone());
-    let album_repository: Arc<dyn domain::album::AlbumRepository> = BufferedAlbumRepository::new(
+    let buffer = ingest.get(BufferedRepository::Album);
+    let album_repository = BufferedAlbumRepository::new(
         album_repository_impl,
-        100,                    // cache_capacity: 缓存容量
-        3,                      // concurrency: 并发数
-        Duration::from_secs(5), // flush_timeout: 超时时间（即使未达到容量也 flush）
+        buffer.cache_capacity,
+        buffer.concurrency,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
     );
+    state.memtables.register(album_repository.memtable_handle());
+    let album_repository: Arc<dyn domain::album::AlbumRepository> = album_repository;
 
     let artist_repository_impl =
         ArtistRepositoryImpl::new(state.db.clone(), state.id_generator.clone());
-    let artist_repository: Arc<dyn domain::artist::ArtistRepository> =
-        BufferedArtistRepository::new(
-            artist_repository_impl,
-            100,                    // cache_capacity: 缓存容量
-            5,                      // concurrency: 并发数
-            Duration::from_secs(5), // flush_timeout: 超时时间（即使未达到容量也 flush）
-        );
+    let buffer = ingest.get(BufferedRepository::Artist);
+    let artist_repository = BufferedArtistRepository::new(
+        artist_repository_impl,
+        buffer.cache_capacity,
+        buffer.concurrency,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
+    );
+    state
+        .memtables
+        .register(artist_repository.memtable_handle());
+    let artist_repository: Arc<dyn domain::artist::ArtistRepository> = artist_repository;
 
     let genre_repository_impl = GenreRepositoryImpl::new(state.db.clone());
-    let genre_repository: Arc<dyn domain::genre::GenreRepository> = BufferedGenreRepository::new(
+    let buffer = ingest.get(BufferedRepository::Genre);
+    let genre_repository = BufferedGenreRepository::new(
         genre_repository_impl,
-        50,                     // cache_capacity: 缓存容量
-        3,                      // concurrency: 并发数
-        Duration::from_secs(5), // flush_timeout: 超时时间（即使未达到容量也 flush）
+        buffer.cache_capacity,
+        buffer.concurrency,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
     );
+    state.memtables.register(genre_repository.memtable_handle());
+    let genre_repository: Arc<dyn domain::genre::GenreRepository> = genre_repository;
 
     let audio_file_repository = AudioFileRepositoryImpl::new(state.db.clone());
     /*
     let audio_file_repository: Arc<dyn domain::audio_file::AudioFileRepository> =
         Arc::new(audio_file_repository);
         */
+    let buffer = ingest.get(BufferedRepository::AudioFile);
+    let audio_file_repository = BufferedAudioFileRepository::new(
+        audio_file_repository,
+        buffer.cache_capacity,
+        buffer.concurrency,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
+    );
+    state
+        .memtables
+        .register(audio_file_repository.memtable_handle());
     let audio_file_repository: Arc<dyn domain::audio_file::AudioFileRepository> =
-        BufferedAudioFileRepository::new(
-            audio_file_repository,
-            1000,                   // cache_capacity: 缓存容量
-            10,                     // concurrency: 并发数
-            Duration::from_secs(5), // flush_timeout: 超时时间（即使未达到容量也 flush）
-        );
+        audio_file_repository;
 
     // 创建共享的其他 repositories
     let cover_art_repository_impl =
         CoverArtRepositoryImpl::new(state.db.clone(), state.id_generator.clone());
-    let cover_art_repository: Arc<dyn domain::cover_art::CoverArtRepository> =
-        BufferedCoverArtRepository::new(
-            cover_art_repository_impl,
-            100,                    // cache_capacity: 缓存容量
-            3,                      // concurrency: 并发数
-            Duration::from_secs(5), // flush_timeout: 超时时间（即使未达到容量也 flush）
-        );
+    let buffer = ingest.get(BufferedRepository::CoverArt);
+    let cover_art_repository = BufferedCoverArtRepository::new(
+        cover_art_repository_impl,
+        buffer.cache_capacity,
+        buffer.concurrency,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
+    );
+    state
+        .memtables
+        .register(cover_art_repository.memtable_handle());
+    let cover_art_repository: Arc<dyn domain::cover_art::CoverArtRepository> = cover_art_repository;
 
     setup_domain_handlers(
         state,
@@ -340,6 +574,12 @@ pub async fn setup_event_bus(state: &mut AppState) {
     )
     .await;
     setup_projector_handlers(state).await;
+    register_push_handlers(
+        &mut state.event_bus,
+        state.push_hub.clone(),
+        state.scan_repo.clone(),
+    )
+    .await;
     setup_coordinators(
         state,
         album_repository,
@@ -348,19 +588,119 @@ pub async fn setup_event_bus(state: &mut AppState) {
         cover_art_repository,
     )
     .await;
+    // 处理器全部订阅后再开始发布 outbox，避免事件无人处理就被标记为已投递
+    state.outbox_relay.start(OUTBOX_RELAY_INTERVAL);
 }
 
-pub async fn setup_application_handlers(state: &mut AppState) {
-    let media_file_parse_service = MediaFileParseService::new(
+/// 库凭据仓库，密码使用与用户密码相同的密钥加密
+pub(crate) fn library_credentials_repository(
+    state: &AppState,
+) -> Option<Arc<dyn LibraryCredentialsRepository>> {
+    match Aes256GcmEncryptor::new(&state.app_cfg.password_encryption_key()) {
+        Ok(encryptor) => Some(Arc::new(LibraryCredentialsRepositoryImpl::new(
+            state.db.clone(),
+            encryptor,
+        ))),
+        Err(e) => {
+            log::warn!("Failed to create credentials encryptor: {}", e);
+            None
+        }
+    }
+}
+
+/// 存储客户端工厂，远程库按路径加载各自的凭据
+pub(crate) fn storage_client_factory(state: &AppState) -> StorageClientFactoryImpl {
+    let factory = match library_credentials_repository(state) {
+        Some(repo) => StorageClientFactoryImpl::with_credentials(repo),
+        None => StorageClientFactoryImpl::new(),
+    };
+    factory.with_read_ahead(state.app_cfg.storage().read_ahead)
+}
+
+/// 媒体文件解析服务，扫描和管理员重试解析失败文件共用
+pub(crate) fn media_file_parse_service(state: &AppState) -> MediaFileParseService<InMemoryEventBus> {
+    MediaFileParseService::new(
         Arc::new(state.event_bus.clone()),
-        Arc::new(StorageClientFactoryImpl::new()),
-        Arc::new(AudioMetadataReaderImpl::new()),
-    );
-    let on_library_file_added_handler = OnLibraryFileAddedHandler::new(media_file_parse_service);
+        Arc::new(storage_client_factory(state)),
+        Arc::new(AudioMetadataReaderImpl::with_reloadable_rule_engine(
+            state.rule_engine.clone(),
+        )),
+        Arc::new(ScanErrorRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+        Arc::new(FsThumbnailStore::new(
+            state.app_cfg.cache().cover_thumbnail_path(),
+        )),
+    )
+}
+
+/// 按当前配置生成密码哈希，配置重新加载后立即生效
+pub(crate) fn password_hasher(state: &AppState) -> ConfiguredPasswordHasher {
+    ConfiguredPasswordHasher::new(state.app_cfg.password_hashing())
+}
+
+pub(crate) fn playlist_app_service(state: &AppState) -> PlaylistAppService {
+    PlaylistAppService::new(
+        Arc::new(PlaylistRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+        Arc::new(FsPlaylistCoverStore::new(
+            state.app_cfg.cache().playlist_cover_path(),
+        )),
+    )
+}
+
+pub(crate) fn library_command_service(
+    state: &AppState,
+) -> LibraryCommandService<LibraryRepositoryImpl, InMemoryEventBus> {
+    LibraryCommandService::new(
+        Arc::new(LibraryRepositoryImpl::new(state.db.clone())),
+        Arc::new(storage_client_factory(state)),
+        Arc::new(DefaultFileTypeDetector::new()),
+        Arc::new(state.event_bus.clone()),
+        state.id_generator.clone(),
+        Arc::new(ScanCheckpointRepositoryImpl::new(state.db.clone())),
+        Arc::new(AudioFileAvailabilityStoreImpl::new(state.db.clone())),
+        state.clock.clone(),
+    )
+}
+
+pub(crate) fn file_check_service(state: &AppState) -> FileCheckService {
+    FileCheckService::new(
+        Arc::new(AudioFileAvailabilityStoreImpl::new(state.db.clone())),
+        Arc::new(LibraryRepositoryImpl::new(state.db.clone())),
+        Arc::new(storage_client_factory(state)),
+    )
+}
+
+pub async fn setup_application_handlers(state: &mut AppState) {
+    let scan_cfg = state.app_cfg.scan();
+    let parse_pool = Arc::new(ParseWorkerPool::start(
+        media_file_parse_service(state),
+        ParsePoolOptions {
+            workers: scan_cfg.parse_workers,
+            queue_capacity: scan_cfg.queue_capacity,
+            io_limits: scan_cfg.io_limits,
+        },
+    ));
+    state.parse_pool = Some(parse_pool.clone());
+    let on_library_file_added_handler = OnLibraryFileAddedHandler::new(parse_pool);
     state
         .event_bus
         .subscribe::<LibraryEvent>(Arc::new(on_library_file_added_handler))
         .await;
+
+    let media_asset_service = MediaAssetService::new(
+        Arc::new(MediaAssetRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+    );
+    register_media_asset_handlers(&mut state.event_bus, media_asset_service).await;
+
+    if scan_cfg.analyze_silence {
+        register_silence_analysis_handlers(&mut state.event_bus, state.silence_analysis.clone())
+            .await;
+    }
+
+    let trash_service = Arc::new(admin::trash::trash_service(state));
+    register_trash_handlers(&mut state.event_bus, trash_service).await;
 }
 
 async fn setup_coordinators(
@@ -370,12 +710,12 @@ async fn setup_coordinators(
     audio_file_repository: Arc<dyn domain::audio_file::AudioFileRepository>,
     cover_art_repository: Arc<dyn domain::cover_art::CoverArtRepository>,
 ) {
-    let ignored_articles = state.app_cfg.ignored_articles();
-    let artist_name_normalizer = Arc::new(infra::normalize::ArtistNameNormalizerImpl::new(
-        &ignored_articles,
+    let ignored_articles = state.app_cfg.ignored_articles.clone();
+    let artist_name_normalizer = Arc::new(infra::normalize::ArtistNameNormalizerImpl::with_shared(
+        ignored_articles.clone(),
     ));
-    let album_name_normalizer = Arc::new(infra::normalize::AlbumNameNormalizerImpl::new(
-        &ignored_articles,
+    let album_name_normalizer = Arc::new(infra::normalize::AlbumNameNormalizerImpl::with_shared(
+        ignored_articles,
     ));
 
     register_coordinators(
@@ -399,12 +739,12 @@ async fn setup_domain_handlers(
     audio_file_repository: Arc<dyn domain::audio_file::AudioFileRepository>,
     cover_art_repository: Arc<dyn domain::cover_art::CoverArtRepository>,
 ) {
-    let ignored_articles = state.app_cfg.ignored_articles();
-    let artist_name_normalizer = Arc::new(infra::normalize::ArtistNameNormalizerImpl::new(
-        &ignored_articles,
+    let ignored_articles = state.app_cfg.ignored_articles.clone();
+    let artist_name_normalizer = Arc::new(infra::normalize::ArtistNameNormalizerImpl::with_shared(
+        ignored_articles.clone(),
     ));
-    let album_name_normalizer = Arc::new(infra::normalize::AlbumNameNormalizerImpl::new(
-        &ignored_articles,
+    let album_name_normalizer = Arc::new(infra::normalize::AlbumNameNormalizerImpl::with_shared(
+        ignored_articles,
     ));
 
     // Create services
@@ -419,14 +759,16 @@ async fn setup_domain_handlers(
         album_repository.clone(),
         album_name_normalizer,
         Arc::new(state.event_bus.clone()),
-    );
+    )
+    .with_separate_editions(state.app_cfg.metadata().separate_editions);
 
     let artist_service = ArtistService::new(
         state.id_generator.clone(),
         artist_repository.clone(),
         artist_name_normalizer,
         Arc::new(state.event_bus.clone()),
-    );
+    )
+    .with_alias_repository(Arc::new(ArtistAliasRepositoryImpl::new(state.db.clone())));
 
     let cover_art_service = CoverArtService::new(
         cover_art_repository.clone(),
@@ -450,29 +792,61 @@ async fn setup_domain_handlers(
 
 async fn setup_projector_handlers(state: &mut AppState) {
     // Create all repositories
+    let ingest = state.app_cfg.ingest();
     let album_location_repository = Arc::new(MysqlAlbumLocationRepository::new(state.db.clone()));
     let album_stats_repository = MysqlAlbumStatsRepository::new(state.db.clone());
+    let buffer = ingest.get(BufferedRepository::AlbumStats);
     let album_stats_repository = BufferedAlbumStatsRepository::new(
         album_stats_repository,
-        1000,                    // cache_capacity
-        Duration::from_secs(30), // flush_timeout
+        buffer.cache_capacity,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
     );
     let artist_location_repository = Arc::new(MysqlArtistLocationRepository::new(state.db.clone()));
+    let format_stats_repository = FormatStatsRepositoryImpl::new(state.db.clone());
+    let buffer = ingest.get(BufferedRepository::FormatStats);
+    let format_stats_repository = BufferedFormatStatsRepository::new(
+        format_stats_repository,
+        buffer.cache_capacity,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
+    );
     let genre_stats_repository = GenreStatsRepositoryImpl::new(state.db.clone());
+    let buffer = ingest.get(BufferedRepository::GenreStats);
     let genre_stats_repository = BufferedGenreStatsRepository::new(
         genre_stats_repository,
-        100,                     // cache_capacity
-        Duration::from_secs(30), // flush_timeout
+        buffer.cache_capacity,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
     );
     let participant_stats_repository = MysqlParticipantStatsRepository::new(state.db.clone());
+    let buffer = ingest.get(BufferedRepository::ParticipantStats);
     let participant_stats_repository = BufferedParticipantStatsRepository::new(
         participant_stats_repository,
-        2000,                    // cache_capacity
-        Duration::from_secs(30), // flush_timeout
+        buffer.cache_capacity,
+        buffer.flush_timeout,
+        buffer.max_pending_flushes,
     );
+    for handle in [
+        album_stats_repository.memtable_handle(),
+        format_stats_repository.memtable_handle(),
+        genre_stats_repository.memtable_handle(),
+        participant_stats_repository.memtable_handle(),
+    ] {
+        state.memtables.register(handle);
+    }
     let playback_history_repository =
         Arc::new(PlaybackHistoryRepositoryImpl::new(state.db.clone()));
     let scan_status_repository = state.scan_repo.clone();
+    let star_stats_repository = Arc::new(StarStatsRepositoryImpl::new(state.db.clone()));
+    let play_stats_repository = Arc::new(PlayStatsRepositoryImpl::new(state.db.clone()));
+    let listening_clock_repository =
+        Arc::new(ListeningClockRepositoryImpl::new(state.db.clone()));
+    let listening_streak_repository =
+        Arc::new(ListeningStreakRepositoryImpl::new(state.db.clone()));
+    let audio_file_repository = Arc::new(AudioFileRepositoryImpl::new(state.db.clone()));
+    let processed_events = Arc::new(ProcessedEventRepositoryImpl::new(state.db.clone()));
+    processed_events.start_purger(PROCESSED_EVENT_RETENTION_DAYS, Duration::from_secs(3600));
 
     // Register all projector handlers using the centralized function
     register_handlers(
@@ -480,11 +854,23 @@ async fn setup_projector_handlers(state: &mut AppState) {
         album_location_repository,
         album_stats_repository,
         artist_location_repository,
+        format_stats_repository,
         genre_stats_repository,
         participant_stats_repository,
         playback_history_repository,
         scan_status_repository,
+        star_stats_repository,
+        play_stats_repository,
+        listening_clock_repository,
+        listening_streak_repository,
+        audio_file_repository,
         state.id_generator.clone(),
+        state.clock.clone(),
+        processed_events,
+        Arc::new(InMemoryProcessedEventLedger::new(
+            BUFFERED_PROCESSED_EVENT_CAPACITY,
+        )),
+        state.push_hub.clone(),
     )
     .await;
 }
@@ -0,0 +1,274 @@
+use crate::auth::{current_claims, error_response, parse_ids, resolve_user_id};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::listening_session::{ListeningSession, SessionMember, SessionPlayback};
+use application::error::AppError;
+use chrono::{DateTime, Utc};
+use domain::value::{AudioFileId, UserId};
+use serde::{Deserialize, Serialize};
+
+/// 注册一起听原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!(
+            "{}/listening-sessions",
+            consts::URL_PATH_NATIVE_API
+        ))
+        .route("", web::get().to(list_sessions))
+        .route("", web::post().to(create_session))
+        .route("/{code}", web::get().to(get_session))
+        .route("/{code}", web::delete().to(end_session))
+        .route("/{code}/join", web::post().to(join_session))
+        .route("/{code}/leave", web::post().to(leave_session))
+        .route("/{code}/playback", web::put().to(update_playback))
+        .route("/{code}/controllers", web::put().to(set_controllers)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackRequest {
+    #[serde(default)]
+    pub song_ids: Vec<String>,
+    pub current_index: Option<usize>,
+    #[serde(default)]
+    pub position_ms: i64,
+    #[serde(default)]
+    pub playing: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControllersRequest {
+    pub user_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMemberView {
+    pub id: String,
+    pub username: String,
+    pub can_control: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningSessionView {
+    pub code: String,
+    pub host: SessionMemberView,
+    pub guests: Vec<SessionMemberView>,
+    pub song_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_index: Option<usize>,
+    /// serverTime 时刻的位置，播放中时客户端按本地时间继续推算
+    pub position_ms: i64,
+    pub playing: bool,
+    pub updated_at: DateTime<Utc>,
+    pub server_time: DateTime<Utc>,
+}
+
+impl ListeningSessionView {
+    pub fn new(session: &ListeningSession, now: DateTime<Utc>) -> Self {
+        let member = |m: &SessionMember| SessionMemberView {
+            id: m.user_id.to_string(),
+            username: m.username.clone(),
+            can_control: session.can_control(&m.user_id),
+        };
+        Self {
+            code: session.code.clone(),
+            host: member(&session.host),
+            guests: session.guests.iter().map(member).collect(),
+            song_ids: session
+                .playback
+                .song_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+            current_index: session.playback.current_index,
+            position_ms: session.position_at(now),
+            playing: session.playback.playing,
+            updated_at: session.updated_at,
+            server_time: now,
+        }
+    }
+}
+
+fn parse_playback(body: PlaybackRequest) -> Result<SessionPlayback, HttpResponse> {
+    Ok(SessionPlayback {
+        song_ids: parse_ids(&body.song_ids)?
+            .into_iter()
+            .map(AudioFileId::from)
+            .collect(),
+        current_index: body.current_index,
+        position_ms: body.position_ms,
+        playing: body.playing,
+    })
+}
+
+/// 当前用户，用户名随会话一起推送给其他成员
+async fn current_member(
+    req: &HttpRequest,
+    state: &AppState,
+) -> Result<SessionMember, HttpResponse> {
+    let claims = current_claims(req)?;
+    let user_id = resolve_user_id(state, &claims).await?;
+    Ok(SessionMember {
+        user_id: UserId::from(user_id),
+        username: claims.user_name,
+    })
+}
+
+fn session_response(state: &AppState, result: Result<ListeningSession, AppError>) -> HttpResponse {
+    match result {
+        Ok(session) => HttpResponse::Ok().json(ListeningSessionView::new(
+            &session,
+            state.listening_sessions.now(),
+        )),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 当前用户主持或加入的会话：GET /api/listening-sessions
+pub async fn list_sessions(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    let now = state.listening_sessions.now();
+    let sessions: Vec<ListeningSessionView> = state
+        .listening_sessions
+        .find_by_member(&member.user_id)
+        .iter()
+        .map(|session| ListeningSessionView::new(session, now))
+        .collect();
+    HttpResponse::Ok().json(sessions)
+}
+
+/// 以当前队列创建会话并成为主持人：POST /api/listening-sessions，
+/// 请求体 {"songIds": [...], "currentIndex": 0, "positionMs": 0, "playing": true}
+pub async fn create_session(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<PlaybackRequest>,
+) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    let playback = match parse_playback(body.into_inner()) {
+        Ok(playback) => playback,
+        Err(rsp) => return rsp,
+    };
+    match state.listening_sessions.create(member, playback) {
+        Ok(session) => HttpResponse::Created().json(ListeningSessionView::new(
+            &session,
+            state.listening_sessions.now(),
+        )),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 会话当前状态，只有成员可以查看：GET /api/listening-sessions/{code}
+pub async fn get_session(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    let result = state.listening_sessions.find(&path, &member.user_id);
+    session_response(&state, result)
+}
+
+/// 用邀请码加入会话：POST /api/listening-sessions/{code}/join
+pub async fn join_session(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    let result = state.listening_sessions.join(&path, member);
+    session_response(&state, result)
+}
+
+/// 离开会话，主持人离开时会话结束：POST /api/listening-sessions/{code}/leave
+pub async fn leave_session(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    match state.listening_sessions.leave(&path, &member.user_id) {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 主持人结束会话：DELETE /api/listening-sessions/{code}
+pub async fn end_session(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    match state.listening_sessions.end(&path, &member.user_id) {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 主持人或被授权的听众同步队列和进度：PUT /api/listening-sessions/{code}/playback，
+/// 请求体与创建会话相同
+pub async fn update_playback(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<PlaybackRequest>,
+) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    let playback = match parse_playback(body.into_inner()) {
+        Ok(playback) => playback,
+        Err(rsp) => return rsp,
+    };
+    let result = state
+        .listening_sessions
+        .update_playback(&path, &member.user_id, playback);
+    session_response(&state, result)
+}
+
+/// 主持人授权听众控制播放：PUT /api/listening-sessions/{code}/controllers，
+/// 请求体 {"userIds": [...]}，空列表收回所有授权
+pub async fn set_controllers(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<ControllersRequest>,
+) -> HttpResponse {
+    let member = match current_member(&req, &state).await {
+        Ok(member) => member,
+        Err(rsp) => return rsp,
+    };
+    let controllers = match parse_ids(&body.user_ids) {
+        Ok(ids) => ids.into_iter().map(UserId::from).collect(),
+        Err(rsp) => return rsp,
+    };
+    let result = state
+        .listening_sessions
+        .set_controllers(&path, &member.user_id, controllers);
+    session_response(&state, result)
+}
@@ -1,2 +1,5 @@
 pub mod jwt_verify;
 pub mod other;
+pub mod query_budget;
+pub mod rate_limit;
+pub mod security_headers;
@@ -1,19 +1,24 @@
 use crate::{consts, AppState};
 use actix_cors::Cors;
 use application::auth::{PasswordEncryptor, UserClaims};
-use domain::user::UserError;
+use domain::user::{User, UserError, UserRepository};
+use domain::value::{PlayerId, UserId};
+use infra::auth::{AuthConfig, JwtTokenService};
+use infra::config::CorsConfig;
 use infra::repository::postgres::command::user::UserRepositoryImpl;
 use infra::Aes256GcmEncryptor;
 use log::{info, warn};
 
 use actix_web::{
-    body::MessageBody,
+    body::{BoxBody, MessageBody},
     cookie::Cookie,
     dev::{ServiceRequest, ServiceResponse},
     http::header::HeaderName,
     middleware::Next,
-    web, HttpMessage,
+    web, HttpMessage, HttpRequest,
 };
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use url::Url;
 
 pub async fn auth_header_mapper(
@@ -35,6 +40,19 @@ pub async fn auth_header_mapper(
 #[derive(Clone)]
 pub struct ClientUniqueID(pub String);
 
+/// 请求对应的播放器：同一个客户端 ID 总是得到相同的 player_id，
+/// 客户端未提供 ID 时退回到用户 ID
+pub fn request_player_id(req: &HttpRequest, user_id: &UserId) -> PlayerId {
+    match req.extensions().get::<ClientUniqueID>() {
+        Some(cid) => {
+            let mut hasher = DefaultHasher::new();
+            cid.0.hash(&mut hasher);
+            PlayerId::from(hasher.finish() as i64)
+        }
+        None => PlayerId::from(user_id.as_i64()),
+    }
+}
+
 /// client_unique_id middleware sets a unique client ID as a cookie if it's provided in the request header.
 /// If the unique client ID is not in the header but present as a cookie, it adds the ID to the request context.
 pub async fn client_unique_id(
@@ -112,12 +130,26 @@ pub async fn client_unique_id(
     Ok(rsp)
 }
 
-pub fn cors() -> Cors {
-    Cors::default()
-        .allow_any_origin()
+/// 按 `[cors]` 配置构建，来源或请求头列表为空时不做限制
+pub fn cors(cfg: &CorsConfig) -> Cors {
+    let mut cors = Cors::default()
         .allowed_methods(vec!["GET", "POST", "PATCH", "PUT", "DELETE", "HEAD"])
-        .allow_any_header()
-        .max_age(3600)
+        .max_age(cfg.max_age_secs);
+    if cfg.allowed_origins.is_empty() {
+        cors = cors.allow_any_origin();
+    }
+    for origin in &cfg.allowed_origins {
+        cors = cors.allowed_origin(origin);
+    }
+    cors = if cfg.allowed_headers.is_empty() {
+        cors.allow_any_header()
+    } else {
+        cors.allowed_headers(cfg.allowed_headers.iter().map(String::as_str))
+    };
+    if cfg.allow_credentials {
+        cors = cors.supports_credentials();
+    }
+    cors
 }
 
 type UsernameFinder = fn(&ServiceRequest) -> Option<Username>;
@@ -182,7 +214,7 @@ fn username_from_reverse_proxy_header(req: &ServiceRequest) -> Option<String> {
 }
 
 /// Parse query string and extract parameter value
-fn get_query_param(query_string: &str, param_name: &str) -> Option<String> {
+pub(crate) fn get_query_param(query_string: &str, param_name: &str) -> Option<String> {
     let url = format!("http://localhost/?{}", query_string);
     Url::parse(&url)
         .ok()?
@@ -191,6 +223,19 @@ fn get_query_param(query_string: &str, param_name: &str) -> Option<String> {
         .map(|(_, value)| value.to_string())
 }
 
+/// 可以用签名 URL（st 参数）代替 Subsonic 认证参数的接口
+const SIGNED_URL_ENDPOINTS: [&str; 3] = ["stream", "download", "getCoverArt"];
+
+/// 签名 URL 请求的 st 参数；其他接口带 st 参数时忽略
+fn signed_url_token(req: &ServiceRequest) -> Option<String> {
+    let endpoint = req.path().rsplit('/').next().unwrap_or_default();
+    let endpoint = endpoint.strip_suffix(".view").unwrap_or(endpoint);
+    if !SIGNED_URL_ENDPOINTS.contains(&endpoint) {
+        return None;
+    }
+    get_query_param(req.query_string(), "st")
+}
+
 /// check_required_parameters middleware checks for required query parameters.
 /// If username is found in reverse proxy header, only "v" and "c" are required.
 /// Otherwise, "u", "v", and "c" are required.
@@ -201,6 +246,11 @@ pub async fn check_required_parameters(
     use crate::subsonic::response::error::SubsonicError;
     //info!("check_required_parameters: {:?}", req);
 
+    // 签名 URL 只需要 id 和 st，由 subsonic_authenticator 校验
+    if signed_url_token(&req).is_some() {
+        return next.call(req).await;
+    }
+
     // Try to get username from reverse proxy header
     let username_from_header = username_from_reverse_proxy_header(&req);
 
@@ -256,6 +306,31 @@ pub async fn check_required_parameters(
     next.call(req).await
 }
 
+/// subsonic_error_format middleware 按 `f` 参数把处理函数返回的 SubsonicError 重新生成为 XML 或 JSONP
+pub async fn subsonic_error_format(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    use crate::subsonic::response::error::SubsonicError;
+    use crate::subsonic::response::{ResponseFormat, Subsonic};
+
+    let format = ResponseFormat::from_query(req.query_string());
+    let res = next.call(req).await?;
+    if format != ResponseFormat::Json {
+        let error = res
+            .response()
+            .error()
+            .and_then(|e| e.as_error::<SubsonicError>())
+            .map(|e| SubsonicError::new(e.code, e.message.clone()));
+        if let Some(error) = error {
+            let (req, _) = res.into_parts();
+            let subsonic: Subsonic = error.into();
+            return Ok(ServiceResponse::new(req, subsonic.into_response(format)));
+        }
+    }
+    Ok(res.map_into_boxed_body())
+}
+
 pub async fn authenticator(
     req: ServiceRequest,
     next: Next<impl MessageBody>,
@@ -290,6 +365,8 @@ pub async fn authenticator(
             _ => actix_web::error::ErrorInternalServerError(e.to_string()),
         })?
         .ok_or_else(|| actix_web::error::ErrorUnauthorized("User not found"))?;
+    user.is_active()
+        .map_err(|e| actix_web::error::ErrorUnauthorized(e.to_string()))?;
 
     req.extensions_mut().insert(user);
     next.call(req).await
@@ -314,6 +391,34 @@ pub async fn subsonic_authenticator(
         .ok_or_else(|| actix_web::error::ErrorInternalServerError("Missing AppState"))?
         .clone();
 
+    // 签名 URL：token 绑定了 id 参数和签发对象的用户
+    if let Some(token) = signed_url_token(&req) {
+        let media_id = get_query_param(&query_string, "id").unwrap_or_default();
+        let token_service =
+            JwtTokenService::new(state.app_cfg.jwt_secret(), state.app_cfg.jwt_expire_secs());
+        let user_id = token_service
+            .verify_stream_token(&token, &media_id)
+            .map_err(|e| {
+                let error = SubsonicError::error_authentication_fail().wrap(e.to_string());
+                actix_web::error::ErrorUnauthorized(error)
+            })?;
+        let user = UserRepositoryImpl::new(state.db.clone())
+            .find_by_id(UserId::from(user_id))
+            .await
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+            .ok_or_else(|| {
+                let error =
+                    SubsonicError::error_authentication_fail().wrap("User not found".to_string());
+                actix_web::error::ErrorUnauthorized(error)
+            })?;
+        if let Err(e) = user.is_active() {
+            let error = SubsonicError::error_authorization_fail().wrap(e.to_string());
+            return Err(actix_web::error::ErrorUnauthorized(error));
+        }
+        req.extensions_mut().insert(user);
+        return next.call(req).await;
+    }
+
     // Try reverse proxy header first
     if let Some(username) = username_from_reverse_proxy_header(&req) {
         let repo = UserRepositoryImpl::new(state.db.clone());
@@ -355,6 +460,10 @@ pub async fn subsonic_authenticator(
                 SubsonicError::error_authentication_fail().wrap("User not found".to_string());
             actix_web::error::ErrorUnauthorized(error)
         })?;
+    if let Err(e) = user.is_active() {
+        let error = SubsonicError::error_authorization_fail().wrap(e.to_string());
+        return Err(actix_web::error::ErrorUnauthorized(error));
+    }
 
     // Try token authentication first (t + s)
     // Subsonic token: t = md5(password + s) where password is the original plain password
@@ -392,6 +501,15 @@ pub async fn subsonic_authenticator(
             return next.call(req).await;
         }
 
+        let token_matches = |password: &str| {
+            let expected = format!("{:x}", md5::compute(format!("{}{}", password, salt)));
+            token.eq_ignore_ascii_case(&expected)
+        };
+        if matches_app_password(&state, &user, token_matches).await {
+            req.extensions_mut().insert(user);
+            return next.call(req).await;
+        }
+
         let error = SubsonicError::error_authentication_fail().wrap("Invalid token".to_string());
         return Err(actix_web::error::ErrorUnauthorized(error));
     }
@@ -431,6 +549,11 @@ pub async fn subsonic_authenticator(
             return next.call(req).await;
         }
 
+        if matches_app_password(&state, &user, |password| password == plain_password).await {
+            req.extensions_mut().insert(user);
+            return next.call(req).await;
+        }
+
         let error = SubsonicError::error_authentication_fail().wrap("Invalid password".to_string());
         return Err(actix_web::error::ErrorUnauthorized(error));
     }
@@ -441,6 +564,36 @@ pub async fn subsonic_authenticator(
     Err(actix_web::error::ErrorBadRequest(error))
 }
 
+/// 主密码不匹配时依次尝试用户的应用密码
+async fn matches_app_password(
+    state: &AppState,
+    user: &User,
+    matches: impl Fn(&str) -> bool,
+) -> bool {
+    let result = match crate::app_passwords::app_password_service(state) {
+        Ok(service) => service.authenticate(&user.id, matches).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(Some(app_password)) => {
+            log::debug!(
+                "User {} authenticated with app password '{}'",
+                user.username,
+                app_password.label
+            );
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            warn!(
+                "Failed to check app passwords for user {}: {}",
+                user.username, e
+            );
+            false
+        }
+    }
+}
+
 /// Decode Subsonic password parameter
 /// Supports: plain text, or enc:hexEncodedPassword
 fn decode_subsonic_password(password: &str) -> String {
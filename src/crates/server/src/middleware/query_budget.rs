@@ -0,0 +1,41 @@
+use crate::AppState;
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web,
+};
+use log::warn;
+
+/// query_budget middleware 统计每个请求执行的数据库查询，
+/// 查询次数或总耗时超出预算时记录执行最多和最慢的语句，用于发现 N+1 查询。
+pub async fn query_budget(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(state) = req.app_data::<web::Data<AppState>>().cloned() else {
+        return next.call(req).await;
+    };
+    let method = req.method().clone();
+    let path = req.path().to_string();
+
+    let (result, queries) = state.query_metrics.track(next.call(req)).await;
+
+    if queries.exceeds(&state.app_cfg.query_budget()) {
+        state.query_metrics.record_over_budget();
+        let (repeated_sql, repeated) = queries.most_repeated.unwrap_or_default();
+        let (slowest_sql, slowest) = queries.slowest.unwrap_or_default();
+        warn!(
+            "{} {} exceeded query budget: {} queries in {} ms; most repeated ({}x): {}; slowest ({} ms): {}",
+            method,
+            path,
+            queries.count,
+            queries.total.as_millis(),
+            repeated,
+            repeated_sql,
+            slowest.as_millis(),
+            slowest_sql
+        );
+    }
+    result
+}
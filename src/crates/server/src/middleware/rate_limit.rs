@@ -0,0 +1,311 @@
+use crate::auth::ErrorResponse;
+use crate::client_ip::client_ip_string;
+use crate::middleware::other::{get_query_param, RequestUsername};
+use crate::subsonic::response::error::SubsonicError;
+use crate::{consts, AppState};
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::{header, StatusCode},
+    middleware::Next,
+    web, HttpMessage, HttpResponse,
+};
+use infra::config::RateLimitConfig;
+use log::warn;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// 超过该数量时清理已回满的令牌桶和过期的失败记录
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// 审计日志使用独立的 target，便于在 log4rs 中单独输出
+const AUDIT_TARGET: &str = "audit";
+
+/// 限流的维度
+#[derive(Debug, Clone, Copy)]
+pub enum RateKey<'a> {
+    Ip(&'a str),
+    User(&'a str),
+}
+
+impl RateKey<'_> {
+    fn id(&self) -> String {
+        match self {
+            RateKey::Ip(ip) => format!("ip:{}", ip),
+            RateKey::User(user) => format!("user:{}", user),
+        }
+    }
+}
+
+impl fmt::Display for RateKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateKey::Ip(ip) => write!(f, "ip {}", ip),
+            RateKey::User(user) => write!(f, "user {}", user),
+        }
+    }
+}
+
+struct TokenBucket {
+    capacity: f64,
+    /// 每秒补充的令牌数
+    refill_rate: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(burst: u32, refill_per_minute: u32, now: Instant) -> Self {
+        Self {
+            capacity: burst as f64,
+            refill_rate: refill_per_minute as f64 / 60.0,
+            tokens: burst as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.capacity
+    }
+
+    /// 取一个令牌，不足时返回需要等待的时间
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_rate,
+            ))
+        }
+    }
+}
+
+struct FailureRecord {
+    count: u32,
+    window_start: Instant,
+    locked_until: Option<Instant>,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    buckets: HashMap<String, TokenBucket>,
+    failures: HashMap<String, FailureRecord>,
+}
+
+/// 登录和 Subsonic 认证共用的限流器
+///
+/// 每个 IP、每个用户名各有一个令牌桶；认证失败按同样的维度计数，
+/// 窗口内达到 `max_failures` 次后锁定 `lockout` 时长，锁定期间请求直接返回 429
+pub struct AuthLimiter {
+    cfg: RateLimitConfig,
+    state: Mutex<LimiterState>,
+}
+
+impl AuthLimiter {
+    pub fn new(cfg: RateLimitConfig) -> Self {
+        Self {
+            cfg,
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.cfg.enabled
+    }
+
+    fn new_bucket(&self, key: &RateKey, now: Instant) -> TokenBucket {
+        match key {
+            RateKey::Ip(_) => {
+                TokenBucket::new(self.cfg.ip_burst, self.cfg.ip_refill_per_minute, now)
+            }
+            RateKey::User(_) => {
+                TokenBucket::new(self.cfg.user_burst, self.cfg.user_refill_per_minute, now)
+            }
+        }
+    }
+
+    /// 检查锁定并从每个维度的令牌桶取一个令牌，被拒绝时返回建议的重试间隔
+    pub fn check(&self, keys: &[RateKey]) -> Result<(), Duration> {
+        if !self.cfg.enabled {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        for key in keys {
+            let locked_until = state
+                .failures
+                .get(&key.id())
+                .and_then(|record| record.locked_until)
+                .filter(|until| *until > now);
+            if let Some(until) = locked_until {
+                return Err(until - now);
+            }
+        }
+        for key in keys {
+            state
+                .buckets
+                .entry(key.id())
+                .or_insert_with(|| self.new_bucket(key, now))
+                .take(now)?;
+        }
+        if state.buckets.len() > PRUNE_THRESHOLD || state.failures.len() > PRUNE_THRESHOLD {
+            self.prune(&mut state, now);
+        }
+        Ok(())
+    }
+
+    /// 记录一次认证失败并写审计日志，达到阈值时锁定对应的维度
+    pub fn record_failure(&self, keys: &[RateKey], client_ip: &str, path: &str) {
+        if !self.cfg.enabled {
+            return;
+        }
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        for key in keys {
+            let record = state
+                .failures
+                .entry(key.id())
+                .or_insert_with(|| FailureRecord {
+                    count: 0,
+                    window_start: now,
+                    locked_until: None,
+                });
+            if now.duration_since(record.window_start) > self.cfg.failure_window {
+                record.count = 0;
+                record.window_start = now;
+            }
+            record.count += 1;
+            warn!(
+                target: AUDIT_TARGET,
+                "Authentication failure for {} from {} on {} ({}/{})",
+                key,
+                client_ip,
+                path,
+                record.count,
+                self.cfg.max_failures
+            );
+            if record.count >= self.cfg.max_failures {
+                record.count = 0;
+                record.window_start = now;
+                record.locked_until = Some(now + self.cfg.lockout);
+                warn!(
+                    target: AUDIT_TARGET,
+                    "Locked {} for {}s after {} failed attempts (last from {})",
+                    key,
+                    self.cfg.lockout.as_secs(),
+                    self.cfg.max_failures,
+                    client_ip
+                );
+            }
+        }
+    }
+
+    /// 认证成功后清空失败计数（锁定中的维度不会走到这里）
+    pub fn record_success(&self, keys: &[RateKey]) {
+        if !self.cfg.enabled {
+            return;
+        }
+        let mut state = self.state.lock();
+        for key in keys {
+            state.failures.remove(&key.id());
+        }
+    }
+
+    fn prune(&self, state: &mut LimiterState, now: Instant) {
+        state.buckets.retain(|_, bucket| {
+            bucket.refill(now);
+            !bucket.is_full()
+        });
+        let window = self.cfg.failure_window;
+        state.failures.retain(|_, record| {
+            record.locked_until.is_some_and(|until| until > now)
+                || now.duration_since(record.window_start) <= window
+        });
+    }
+}
+
+fn rate_keys<'a>(ip: &'a str, user: Option<&'a str>) -> Vec<RateKey<'a>> {
+    let mut keys = vec![RateKey::Ip(ip)];
+    keys.extend(user.map(RateKey::User));
+    keys
+}
+
+/// 429 响应，Subsonic 接口返回 Subsonic 错误，其余返回 JSON
+pub fn too_many_requests(path: &str, retry_after: Duration) -> HttpResponse {
+    let secs = retry_after.as_secs().max(1);
+    let message = format!("Too many requests, retry in {}s", secs);
+    let mut builder = HttpResponse::TooManyRequests();
+    builder.insert_header((header::RETRY_AFTER, secs.to_string()));
+    if path.starts_with(consts::URL_PATH_SUBSONIC_API) {
+        builder.body(
+            SubsonicError::error_authentication_fail()
+                .wrap(message)
+                .to_string(),
+        )
+    } else {
+        builder.json(ErrorResponse { error: message })
+    }
+}
+
+/// rate_limit middleware 按客户端 IP 和用户名限流，并根据 401 响应统计认证失败。
+/// 用户名取自 Subsonic 的 `u` 参数；登录接口的用户名在请求体中，
+/// 由处理函数写入 `RequestUsername` 后在响应阶段读取。
+pub async fn rate_limit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(limiter) = req
+        .app_data::<web::Data<AppState>>()
+        .map(|state| state.auth_limiter.clone())
+        .filter(|limiter| limiter.enabled())
+    else {
+        return next.call(req).await;
+    };
+
+    let ip = client_ip_string(req.request());
+    let path = req.path().to_string();
+    let query_user = get_query_param(req.query_string(), "u");
+
+    if let Err(retry_after) = limiter.check(&rate_keys(&ip, query_user.as_deref())) {
+        warn!(
+            target: AUDIT_TARGET,
+            "Rejected {} from {}: rate limited or locked out",
+            path,
+            ip
+        );
+        let rsp = too_many_requests(&path, retry_after);
+        return Err(InternalError::from_response("Too many requests", rsp).into());
+    }
+
+    let result = next.call(req).await;
+
+    let (status, body_user) = match &result {
+        Ok(rsp) => (
+            rsp.status(),
+            rsp.request()
+                .extensions()
+                .get::<RequestUsername>()
+                .map(|user| user.0.clone()),
+        ),
+        Err(e) => (e.as_response_error().status_code(), None),
+    };
+    let user = query_user.or(body_user);
+    let keys = rate_keys(&ip, user.as_deref());
+    if status == StatusCode::UNAUTHORIZED {
+        limiter.record_failure(&keys, &ip, &path);
+    } else if status.is_success() {
+        limiter.record_success(&keys);
+    }
+    result
+}
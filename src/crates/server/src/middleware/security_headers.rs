@@ -0,0 +1,54 @@
+use crate::AppState;
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderValue},
+    middleware::Next,
+    web,
+};
+
+/// security_headers middleware 按 `[security_headers]` 配置添加 HSTS 和 nosniff，
+/// Content-Security-Policy 只加在 Web UI 页面上，API 响应不受影响。处理器已设置的同名头不覆盖
+pub async fn security_headers(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(state) = req.app_data::<web::Data<AppState>>().cloned() else {
+        return next.call(req).await;
+    };
+    let cfg = state.app_cfg.security_headers();
+    let ui_base_path = state.app_cfg.server().ui_base_path;
+    let is_ui = is_ui_path(req.path(), &ui_base_path);
+
+    let mut rsp = next.call(req).await?;
+    let headers = rsp.headers_mut();
+    if let Some(hsts) = cfg.hsts.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        if !headers.contains_key(header::STRICT_TRANSPORT_SECURITY) {
+            headers.insert(header::STRICT_TRANSPORT_SECURITY, hsts);
+        }
+    }
+    if cfg.content_type_options && !headers.contains_key(header::X_CONTENT_TYPE_OPTIONS) {
+        headers.insert(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        );
+    }
+    if is_ui {
+        if let Some(csp) = cfg
+            .ui_content_security_policy
+            .and_then(|v| HeaderValue::from_str(&v).ok())
+        {
+            if !headers.contains_key(header::CONTENT_SECURITY_POLICY) {
+                headers.insert(header::CONTENT_SECURITY_POLICY, csp);
+            }
+        }
+    }
+    Ok(rsp)
+}
+
+fn is_ui_path(path: &str, ui_base_path: &str) -> bool {
+    path == "/"
+        || path
+            .strip_prefix(ui_base_path)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
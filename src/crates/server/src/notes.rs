@@ -0,0 +1,247 @@
+use crate::api_v1::{Page, Paging};
+use crate::auth::{
+    bad_request, current_claims, error_response, parse_id, resolve_user_id, ErrorResponse,
+};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::user_note::{SaveUserNoteCmd, UserNoteService};
+use application::query::dao::UserNoteDao;
+use chrono::NaiveDateTime;
+use domain::annotation::Kind;
+use domain::user_note;
+use domain::value::UserId;
+use infra::repository::postgres::command::album::AlbumRepositoryImpl;
+use infra::repository::postgres::command::artist::ArtistRepositoryImpl;
+use infra::repository::postgres::command::audio_file::AudioFileRepositoryImpl;
+use infra::repository::postgres::command::playlist::PlaylistRepositoryImpl;
+use infra::repository::postgres::command::user_note::UserNoteRepositoryImpl;
+use infra::repository::postgres::query::user_note::UserNoteDaoImpl;
+use model::user_note::UserNote;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 注册个人备注原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/notes", consts::URL_PATH_NATIVE_API))
+            .route("", web::get().to(list_notes))
+            .service(
+                web::resource("/{kind}/{id}")
+                    .route(web::get().to(get_note))
+                    .route(web::put().to(save_note))
+                    .route(web::delete().to(delete_note)),
+            ),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NoteListQuery {
+    /// song、album、artist 或 playlist，为空时返回全部
+    pub kind: Option<String>,
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveNoteRequest {
+    pub note: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteView {
+    /// song、album、artist 或 playlist
+    pub kind: &'static str,
+    pub id: String,
+    pub note: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<UserNote> for NoteView {
+    fn from(value: UserNote) -> Self {
+        let kind = value.item_kind.parse::<Kind>().unwrap_or_default();
+        Self {
+            kind: kind_name(&kind),
+            id: value.item_id.to_string(),
+            note: value.note,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl From<user_note::UserNote> for NoteView {
+    fn from(value: user_note::UserNote) -> Self {
+        Self {
+            kind: kind_name(&value.kind),
+            id: value.item_id.to_string(),
+            note: value.note,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+fn parse_kind(raw: &str) -> Option<Kind> {
+    match raw {
+        "song" => Some(Kind::AudioFile),
+        "album" => Some(Kind::Album),
+        "artist" => Some(Kind::Artist),
+        "playlist" => Some(Kind::Playlist),
+        _ => None,
+    }
+}
+
+fn kind_name(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::AudioFile => "song",
+        Kind::Album => "album",
+        Kind::Artist => "artist",
+        Kind::Playlist => "playlist",
+    }
+}
+
+fn parse_item(raw_kind: &str, raw_id: &str) -> Result<(Kind, i64), HttpResponse> {
+    let kind =
+        parse_kind(raw_kind).ok_or_else(|| bad_request(format!("Unknown kind: {}", raw_kind)))?;
+    let item_id = parse_id(raw_id)?;
+    Ok((kind, item_id))
+}
+
+/// 解析路径并找到当前用户
+async fn note_context(
+    req: &HttpRequest,
+    state: &AppState,
+    path: &(String, String),
+) -> Result<(UserId, Kind, i64), HttpResponse> {
+    let claims = current_claims(req)?;
+    let (kind, item_id) = parse_item(&path.0, &path.1)?;
+    let user_id = resolve_user_id(state, &claims).await?;
+    Ok((UserId::from(user_id), kind, item_id))
+}
+
+fn user_note_service(state: &AppState) -> UserNoteService {
+    UserNoteService::new(
+        Arc::new(UserNoteRepositoryImpl::new(state.db.clone())),
+        Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+        Arc::new(AlbumRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(ArtistRepositoryImpl::new(
+            state.db.clone(),
+            state.id_generator.clone(),
+        )),
+        Arc::new(PlaylistRepositoryImpl::new(state.db.clone())),
+    )
+}
+
+/// 当前用户的备注，最近修改的在前：/api/notes?kind=&offset=&limit=
+pub async fn list_notes(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<NoteListQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let kind = match query.kind.as_deref().filter(|s| !s.is_empty()) {
+        Some(raw) => match parse_kind(raw) {
+            Some(kind) => Some(kind),
+            None => return bad_request(format!("Unknown kind: {}", raw)),
+        },
+        None => None,
+    };
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    match UserNoteDaoImpl::new(state.db.clone())
+        .get_by_user(
+            user_id,
+            kind.as_ref().map(Kind::name),
+            paging.offset,
+            paging.limit,
+        )
+        .await
+    {
+        Ok((items, total)) => {
+            let page: Page<NoteView> = paging.page(items, total);
+            HttpResponse::Ok().json(page)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 单个条目的备注：GET /api/notes/{kind}/{id}
+pub async fn get_note(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> HttpResponse {
+    let (user_id, kind, item_id) = match note_context(&req, &state, &path).await {
+        Ok(context) => context,
+        Err(rsp) => return rsp,
+    };
+    match user_note_service(&state)
+        .find_note(&user_id, &kind, item_id)
+        .await
+    {
+        Ok(Some(note)) => HttpResponse::Ok().json(NoteView::from(note)),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Note not found: {}", item_id),
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 创建或修改备注：PUT /api/notes/{kind}/{id}，请求体 {"note": "..."}
+pub async fn save_note(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    body: web::Json<SaveNoteRequest>,
+) -> HttpResponse {
+    let (user_id, kind, item_id) = match note_context(&req, &state, &path).await {
+        Ok(context) => context,
+        Err(rsp) => return rsp,
+    };
+    let cmd = SaveUserNoteCmd {
+        user_id,
+        kind,
+        item_id,
+        note: body.into_inner().note,
+    };
+    match user_note_service(&state).save_note(cmd).await {
+        Ok(note) => HttpResponse::Ok().json(NoteView::from(note)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 删除备注：DELETE /api/notes/{kind}/{id}
+pub async fn delete_note(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> HttpResponse {
+    let (user_id, kind, item_id) = match note_context(&req, &state, &path).await {
+        Ok(context) => context,
+        Err(rsp) => return rsp,
+    };
+    match user_note_service(&state)
+        .delete_note(&user_id, &kind, item_id)
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
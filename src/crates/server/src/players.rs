@@ -0,0 +1,127 @@
+use crate::auth::{current_claims, resolve_user_id, ErrorResponse};
+use crate::client_ip::client_ip_string;
+use crate::consts;
+use crate::middleware::other::request_player_id;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::player::{PlayerService, SetScrobbleCmd};
+use application::error::AppError;
+use domain::player::Player;
+use domain::value::UserId;
+use infra::repository::postgres::command::player::PlayerRepositoryImpl;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 注册播放器设置原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource(format!("{}/players/current", consts::URL_PATH_NATIVE_API))
+            .route(web::get().to(get_current_player))
+            .route(web::put().to(update_current_player)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePlayerRequest {
+    /// false 时进入私密收听
+    pub scrobble_enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerView {
+    pub id: String,
+    /// 播放器还没有创建时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+    pub scrobble_enabled: bool,
+}
+
+impl From<Player> for PlayerView {
+    fn from(player: Player) -> Self {
+        Self {
+            id: player.id.to_string(),
+            name: Some(player.name),
+            client: Some(player.client),
+            scrobble_enabled: player.scrobble_enabled,
+        }
+    }
+}
+
+fn player_service(state: &AppState) -> PlayerService {
+    PlayerService::new(Arc::new(PlayerRepositoryImpl::new(state.db.clone())))
+}
+
+/// 当前会话对应的播放器（按客户端 ID 区分）：/api/players/current
+pub async fn get_current_player(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => UserId::from(id),
+        Err(rsp) => return rsp,
+    };
+    let player_id = request_player_id(&req, &user_id);
+    match player_service(&state)
+        .find(player_id.clone(), &user_id)
+        .await
+    {
+        Ok(Some(player)) => HttpResponse::Ok().json(PlayerView::from(player)),
+        // 还没有播放过，按默认设置返回
+        Ok(None) => HttpResponse::Ok().json(PlayerView {
+            id: player_id.to_string(),
+            name: None,
+            client: None,
+            scrobble_enabled: true,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 开关当前播放器的播放记录。关闭后仍可正常播放，但 scrobble 不再计入播放次数、
+/// 播放历史和统计，也不显示为正在播放：/api/players/current
+pub async fn update_current_player(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<UpdatePlayerRequest>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => UserId::from(id),
+        Err(rsp) => return rsp,
+    };
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string();
+    let player_id = request_player_id(&req, &user_id);
+    let cmd = SetScrobbleCmd {
+        player_id,
+        user_id,
+        // 原生 API 的客户端是 Web UI
+        client: "web".to_string(),
+        ip: client_ip_string(&req),
+        user_agent,
+        enabled: body.scrobble_enabled,
+    };
+    match player_service(&state).set_scrobble_enabled(cmd).await {
+        Ok(player) => HttpResponse::Ok().json(PlayerView::from(player)),
+        Err(AppError::AggregateNotFound(_, id)) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Player not found: {}", id),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,527 @@
+use crate::auth::{
+    current_claims, error_response, parse_id, parse_ids, resolve_user_id, ErrorResponse,
+};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::playlist::{
+    AddPlaylistEntriesCmd, MovePlaylistEntryCmd, SetPlaylistCollaboratorsCmd, SetPlaylistCoverCmd,
+    SetPlaylistTagsCmd, MAX_PLAYLIST_COVER_SIZE,
+};
+use application::query::get_playlist::{GetPlaylist, PlaylistTagCount};
+use application::query::QueryError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use domain::user::UserRepository;
+use infra::repository::postgres::command::user::UserRepositoryImpl;
+use infra::repository::postgres::query::playlist::PlaylistDaoImpl;
+use log::{info, warn};
+use model::playlist::PlaylistSummary;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 清理过期播放列表的周期
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 批量编辑请求体上限，足够容纳数千个条目 ID
+const MAX_JSON_BODY_SIZE: usize = 1024 * 1024;
+
+/// 注册播放列表原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/playlists", consts::URL_PATH_NATIVE_API))
+            .app_data(web::PayloadConfig::new(MAX_PLAYLIST_COVER_SIZE))
+            .app_data(web::JsonConfig::default().limit(MAX_JSON_BODY_SIZE))
+            .route("/tags", web::get().to(get_tags))
+            .route("/deleted", web::get().to(get_deleted))
+            .route("/{id}/restore", web::post().to(restore_playlist))
+            .route("/{id}/tags", web::put().to(set_tags))
+            .route("/{id}/cover", web::put().to(set_cover))
+            .route("/{id}/cover", web::delete().to(remove_cover))
+            .route("/{id}/collaborators", web::get().to(get_collaborators))
+            .route("/{id}/collaborators", web::put().to(set_collaborators))
+            .route("/{id}/entries", web::post().to(add_entries))
+            .route("/{id}/entries/remove", web::post().to(remove_entries))
+            .route("/{id}/entries/move", web::post().to(move_entry))
+            .route(
+                "/{id}/entries/deduplicate",
+                web::post().to(deduplicate_entries),
+            )
+            .route("/{id}/entries/order", web::put().to(reorder_entries)),
+    );
+}
+
+/// 批量添加歌曲，index 为空时追加到末尾
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddEntriesRequest {
+    pub song_ids: Vec<String>,
+    #[serde(default)]
+    pub index: Option<usize>,
+}
+
+/// 条目 ID 列表，用于批量删除和整体排序
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryIdsRequest {
+    pub entry_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveEntryRequest {
+    pub entry_id: String,
+    pub index: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RemovedView {
+    pub removed: usize,
+}
+
+/// 协作者列表，以用户名表示
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollaboratorsBody {
+    pub usernames: Vec<String>,
+}
+
+/// 播放列表标签，按设置时的顺序
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagsBody {
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagCountView {
+    pub tag: String,
+    pub playlists: usize,
+}
+
+impl From<PlaylistTagCount> for TagCountView {
+    fn from(count: PlaylistTagCount) -> Self {
+        Self {
+            tag: count.tag,
+            playlists: count.playlists,
+        }
+    }
+}
+
+/// 最近删除的播放列表
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedPlaylistView {
+    pub id: String,
+    pub name: String,
+    pub song_count: i32,
+    pub deleted_at: NaiveDateTime,
+    /// 超过该时间后永久删除，未配置保留期时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+fn deleted_view(state: &AppState, playlist: PlaylistSummary) -> Option<DeletedPlaylistView> {
+    let deleted_at = DateTime::from_timestamp(playlist.deleted_at?, 0)?.naive_utc();
+    let retention = state.app_cfg.trash().retention;
+    Some(DeletedPlaylistView {
+        id: playlist.id.to_string(),
+        name: playlist.name,
+        song_count: playlist.song_count,
+        deleted_at,
+        expires_at: retention.map(|retention| deleted_at + retention),
+    })
+}
+
+/// 解析路径中的播放列表 ID 和当前用户 ID
+async fn playlist_and_user(
+    req: &HttpRequest,
+    state: &AppState,
+    raw_id: &str,
+) -> Result<(i64, i64), HttpResponse> {
+    let claims = current_claims(req)?;
+    let playlist_id = parse_id(raw_id)?;
+    let user_id = resolve_user_id(state, &claims).await?;
+    Ok((playlist_id, user_id))
+}
+
+/// 上传播放列表封面：PUT /api/playlists/{id}/cover，请求体为图片数据
+pub async fn set_cover(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Bytes,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let playlist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    match crate::playlist_app_service(&state)
+        .set_playlist_cover(SetPlaylistCoverCmd {
+            playlist_id,
+            user_id,
+            data: body.to_vec(),
+        })
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 删除播放列表封面，恢复自动生成的封面
+pub async fn remove_cover(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let playlist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    match crate::playlist_app_service(&state)
+        .remove_playlist_cover(playlist_id, user_id)
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 查看协作者：GET /api/playlists/{id}/collaborators
+pub async fn get_collaborators(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let playlist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    let get_playlist = GetPlaylist::new(Arc::new(PlaylistDaoImpl::new(state.db.clone())));
+    match get_playlist.get_by_id(playlist_id).await {
+        Ok(playlist) => HttpResponse::Ok().json(CollaboratorsBody {
+            usernames: playlist.collaborators,
+        }),
+        Err(QueryError::InvalidInput(e)) => {
+            HttpResponse::NotFound().json(ErrorResponse { error: e })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 替换协作者：PUT /api/playlists/{id}/collaborators，仅所有者可用
+pub async fn set_collaborators(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<CollaboratorsBody>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let playlist_id = match parse_id(&path) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    let user_repository = UserRepositoryImpl::new(state.db.clone());
+    let mut collaborator_ids = Vec::with_capacity(body.usernames.len());
+    for username in &body.usernames {
+        match user_repository.find_by_username(username).await {
+            Ok(Some(user)) => collaborator_ids.push(user.id.as_i64()),
+            Ok(None) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    error: format!("User not found: {}", username),
+                })
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(ErrorResponse {
+                    error: e.to_string(),
+                })
+            }
+        }
+    }
+
+    match crate::playlist_app_service(&state)
+        .set_collaborators(SetPlaylistCollaboratorsCmd {
+            playlist_id,
+            user_id,
+            collaborator_ids,
+        })
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 当前用户拥有或协作的播放列表用到的标签及数量：GET /api/playlists/tags
+pub async fn get_tags(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    let get_playlist = GetPlaylist::new(Arc::new(PlaylistDaoImpl::new(state.db.clone())));
+    match get_playlist.get_tags(user_id).await {
+        Ok(tags) => {
+            let tags: Vec<TagCountView> = tags.into_iter().map(Into::into).collect();
+            HttpResponse::Ok().json(tags)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 当前用户最近删除的播放列表：GET /api/playlists/deleted
+pub async fn get_deleted(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    let get_playlist = GetPlaylist::new(Arc::new(PlaylistDaoImpl::new(state.db.clone())));
+    match get_playlist.get_deleted(user_id).await {
+        Ok(playlists) => {
+            let playlists: Vec<DeletedPlaylistView> = playlists
+                .into_iter()
+                .filter_map(|playlist| deleted_view(&state, playlist))
+                .collect();
+            HttpResponse::Ok().json(playlists)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 从最近删除中恢复：POST /api/playlists/{id}/restore，仅所有者可用
+pub async fn restore_playlist(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let (playlist_id, user_id) = match playlist_and_user(&req, &state, &path).await {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+    match crate::playlist_app_service(&state)
+        .restore_playlist(playlist_id, user_id)
+        .await
+    {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 定期永久删除超过保留期的播放列表，保留期与回收站相同
+pub fn start_deleted_playlist_purge(state: web::Data<AppState>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let Some(retention) = state.app_cfg.trash().retention else {
+                continue;
+            };
+            let before = Utc::now().naive_utc() - retention;
+            match crate::playlist_app_service(&state)
+                .purge_deleted(before)
+                .await
+            {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} deleted playlists", purged),
+                Err(e) => warn!("Failed to purge deleted playlists: {}", e),
+            }
+        }
+    });
+}
+
+/// 替换标签：PUT /api/playlists/{id}/tags，仅所有者可用，返回整理后的标签
+pub async fn set_tags(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<TagsBody>,
+) -> HttpResponse {
+    let (playlist_id, user_id) = match playlist_and_user(&req, &state, &path).await {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+    match crate::playlist_app_service(&state)
+        .set_tags(SetPlaylistTagsCmd {
+            playlist_id,
+            user_id,
+            tags: body.into_inner().tags,
+        })
+        .await
+    {
+        Ok(playlist) => HttpResponse::Ok().json(TagsBody {
+            tags: playlist.tags,
+        }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 批量添加歌曲：POST /api/playlists/{id}/entries
+pub async fn add_entries(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<AddEntriesRequest>,
+) -> HttpResponse {
+    let (playlist_id, user_id) = match playlist_and_user(&req, &state, &path).await {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+    let song_ids = match parse_ids(&body.song_ids) {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+
+    match crate::playlist_app_service(&state)
+        .add_entries(AddPlaylistEntriesCmd {
+            playlist_id,
+            user_id,
+            song_ids,
+            index: body.index,
+        })
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 批量删除条目：POST /api/playlists/{id}/entries/remove
+pub async fn remove_entries(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<EntryIdsRequest>,
+) -> HttpResponse {
+    let (playlist_id, user_id) = match playlist_and_user(&req, &state, &path).await {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+    let entry_ids = match parse_ids(&body.entry_ids) {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+
+    match crate::playlist_app_service(&state)
+        .remove_entries(playlist_id, user_id, entry_ids)
+        .await
+    {
+        Ok(removed) => HttpResponse::Ok().json(RemovedView { removed }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 移动条目：POST /api/playlists/{id}/entries/move
+pub async fn move_entry(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<MoveEntryRequest>,
+) -> HttpResponse {
+    let (playlist_id, user_id) = match playlist_and_user(&req, &state, &path).await {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+    let entry_id = match parse_id(&body.entry_id) {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    match crate::playlist_app_service(&state)
+        .move_entry(MovePlaylistEntryCmd {
+            playlist_id,
+            user_id,
+            entry_id,
+            index: body.index,
+        })
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 删除重复歌曲：POST /api/playlists/{id}/entries/deduplicate
+pub async fn deduplicate_entries(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let (playlist_id, user_id) = match playlist_and_user(&req, &state, &path).await {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+
+    match crate::playlist_app_service(&state)
+        .deduplicate_entries(playlist_id, user_id)
+        .await
+    {
+        Ok(removed) => HttpResponse::Ok().json(RemovedView { removed }),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 保存整体顺序（如随机排序后保存）：PUT /api/playlists/{id}/entries/order
+pub async fn reorder_entries(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<EntryIdsRequest>,
+) -> HttpResponse {
+    let (playlist_id, user_id) = match playlist_and_user(&req, &state, &path).await {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+    let entry_ids = match parse_ids(&body.entry_ids) {
+        Ok(ids) => ids,
+        Err(rsp) => return rsp,
+    };
+
+    match crate::playlist_app_service(&state)
+        .reorder_entries(playlist_id, user_id, entry_ids)
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
@@ -1,50 +1,188 @@
-use actix_files::Files;
-use actix_web::{web, HttpResponse};
+use actix_files::{Files, NamedFile};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::{web, HttpRequest, HttpResponse};
 use infra::config::ServerConfig;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+const INDEX_FILE: &str = "index.html";
+/// 文件名带内容哈希的资源，内容变化时文件名也会变化
+const IMMUTABLE_CACHE: &str = "public, max-age=31536000, immutable";
+/// index.html 每次都需要校验，保证发布新版本后能引用到新的资源
+const REVALIDATE_CACHE: &str = "no-cache";
 
 /// 配置静态资源路由，映射 /resources 到静态文件目录
 pub fn configure_service(cfg: &mut web::ServiceConfig) {
     cfg.service(Files::new("/resources", "resources").show_files_listing());
 }
 
-/// 配置 UI 静态文件服务
-pub fn configure_ui_service(cfg: &mut web::ServiceConfig, server_config: &ServerConfig) {
-    let ui_path = server_config.ui_path.clone();
-    let ui_base_path = server_config.ui_base_path.clone();
-    let index_file = Path::new(&ui_path).join("index.html");
+#[cfg(feature = "embedded-ui")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "../../../ui/dist"]
+struct EmbeddedUi;
+
+/// UI 文件来源，磁盘目录优先，便于替换内嵌的版本
+#[derive(Clone)]
+enum UiSource {
+    Disk(PathBuf),
+    #[cfg(feature = "embedded-ui")]
+    Embedded,
+}
 
-    // 检查 UI 目录是否存在
-    if !Path::new(&ui_path).exists() {
+impl UiSource {
+    fn detect(server_config: &ServerConfig) -> Option<Self> {
+        let ui_path = Path::new(&server_config.ui_path);
+        if !server_config.ui_path.is_empty() && ui_path.join(INDEX_FILE).is_file() {
+            log::info!("Serving UI from {}", ui_path.display());
+            return Some(UiSource::Disk(ui_path.to_path_buf()));
+        }
+        #[cfg(feature = "embedded-ui")]
+        if EmbeddedUi::get(INDEX_FILE).is_some() {
+            log::info!("Serving embedded UI");
+            return Some(UiSource::Embedded);
+        }
         log::warn!(
-            "UI directory '{}' not found, UI service disabled",
-            ui_path
+            "UI index file not found in '{}' and no embedded UI, UI service disabled",
+            server_config.ui_path
         );
+        None
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        match self {
+            UiSource::Disk(root) => root.join(path).is_file(),
+            #[cfg(feature = "embedded-ui")]
+            UiSource::Embedded => EmbeddedUi::get(path).is_some(),
+        }
+    }
+
+    async fn respond(&self, req: &HttpRequest, path: &str) -> HttpResponse {
+        match self {
+            UiSource::Disk(root) => match NamedFile::open_async(root.join(path)).await {
+                Ok(file) => file.into_response(req),
+                Err(_) => HttpResponse::NotFound().finish(),
+            },
+            #[cfg(feature = "embedded-ui")]
+            UiSource::Embedded => {
+                let Some(file) = EmbeddedUi::get(path) else {
+                    return HttpResponse::NotFound().finish();
+                };
+                let etag = format!("\"{}\"", hex::encode(file.metadata.sha256_hash()));
+                let not_modified = req
+                    .headers()
+                    .get(header::IF_NONE_MATCH)
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+                if not_modified {
+                    return HttpResponse::NotModified()
+                        .insert_header((header::ETAG, etag))
+                        .finish();
+                }
+                HttpResponse::Ok()
+                    .content_type(file.metadata.mimetype())
+                    .insert_header((header::ETAG, etag))
+                    .body(file.data.into_owned())
+            }
+        }
+    }
+}
+
+/// 配置 UI 静态文件服务：访问 / 重定向到 UI，未匹配的页面路径回退到 index.html（前端路由）
+pub fn configure_ui_service(cfg: &mut web::ServiceConfig, server_config: &ServerConfig) {
+    if !server_config.ui_enabled {
+        log::info!("UI service disabled by config");
         return;
     }
+    let Some(source) = UiSource::detect(server_config) else {
+        return;
+    };
+    let ui_base_path = server_config.ui_base_path.clone();
 
     // 根路径重定向到 UI
-    let redirect_path = ui_base_path.clone();
+    let redirect_path = format!("{}/", ui_base_path);
     cfg.route(
         "/",
         web::get().to(move || {
             let path = redirect_path.clone();
-            async move { HttpResponse::Found().insert_header(("Location", path)).finish() }
+            async move {
+                HttpResponse::Found()
+                    .insert_header(("Location", path))
+                    .finish()
+            }
         }),
     );
 
-    // UI 静态文件服务
-    if let Ok(named_file) = actix_files::NamedFile::open(&index_file) {
-        cfg.service(
-            Files::new(&ui_base_path, &ui_path)
-                .index_file("index.html")
-                // SPA fallback: 对于所有未匹配的路由，返回 index.html
-                .default_handler(named_file),
-        );
+    let source = web::Data::new(source);
+    cfg.service(
+        web::resource(ui_base_path.clone())
+            .app_data(source.clone())
+            .route(web::get().to(serve_ui)),
+    )
+    .service(
+        web::resource(format!("{}/{{tail:.*}}", ui_base_path))
+            .app_data(source)
+            .route(web::get().to(serve_ui)),
+    );
+}
+
+async fn serve_ui(req: HttpRequest, source: web::Data<UiSource>) -> HttpResponse {
+    let tail = req.match_info().get("tail").unwrap_or("");
+    let Some(path) = sanitize(tail) else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    let (path, cache_control) = if !path.is_empty() && source.exists(&path) {
+        let cache_control = if is_hashed_asset(&path) {
+            IMMUTABLE_CACHE
+        } else {
+            REVALIDATE_CACHE
+        };
+        (path, cache_control)
+    } else if path.is_empty() || !has_extension(&path) {
+        // 前端路由（history 模式）的页面路径
+        (INDEX_FILE.to_string(), REVALIDATE_CACHE)
     } else {
-        log::warn!(
-            "UI index file '{}' not found, UI service disabled",
-            index_file.display()
+        // 缺失的 js/css 等资源不回退到 index.html，避免浏览器按脚本解析 HTML
+        return HttpResponse::NotFound().finish();
+    };
+
+    let mut rsp = source.respond(&req, &path).await;
+    if rsp.status().is_success() || rsp.status().is_redirection() {
+        rsp.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
         );
     }
+    rsp
+}
+
+/// 拒绝 `..`、隐藏文件和反斜杠，返回相对路径
+fn sanitize(tail: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    for segment in tail.split('/').filter(|segment| !segment.is_empty()) {
+        if segment.starts_with('.') || segment.contains('\\') {
+            return None;
+        }
+        segments.push(segment);
+    }
+    Some(segments.join("/"))
+}
+
+fn has_extension(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.contains('.')
+}
+
+/// Vite 等构建工具生成的 `name-[hash].ext` / `name.[hash].ext`
+fn is_hashed_asset(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let Some((stem, _)) = name.rsplit_once('.') else {
+        return false;
+    };
+    let hash = stem.rsplit(['-', '.']).next().unwrap_or("");
+    hash.len() >= 8
+        && hash.len() < stem.len()
+        && hash.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && hash
+            .chars()
+            .any(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
 }
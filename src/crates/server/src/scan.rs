@@ -0,0 +1,157 @@
+use crate::auth::ErrorResponse;
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+use application::command::library::ScanLibraryCmd;
+use application::context::AppContext;
+use application::query::dao::MusicFolderDao;
+use chrono::NaiveDateTime;
+use domain::library::LibraryRepository;
+use infra::repository::postgres::command::library::LibraryRepositoryImpl;
+use infra::repository::postgres::query::music_folder::MusicFolderDaoImpl;
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 定时扫描的检查周期
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 注册扫描状态原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/scan", consts::URL_PATH_NATIVE_API))
+            .route("/status", web::get().to(get_scan_status)),
+    );
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryScanView {
+    pub library_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub scanning: bool,
+    pub phase: &'static str,
+    pub total_files: i64,
+    pub enumerated_files: i64,
+    pub parsed_files: i64,
+    pub processed_files: i64,
+    pub error_count: i64,
+    pub progress: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+/// 各库的扫描阶段、计数与预计剩余时间：/api/scan/status
+pub async fn get_scan_status(state: web::Data<AppState>) -> HttpResponse {
+    let statuses = match state.scan_repo.get_all_scan_statuses().await {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: e.to_string(),
+            })
+        }
+    };
+    // 库名只用于展示，查询失败时省略
+    let names: HashMap<i64, String> = MusicFolderDaoImpl::new(state.db.clone())
+        .get_all()
+        .await
+        .map(|folders| folders.into_iter().map(|f| (f.id, f.name)).collect())
+        .unwrap_or_default();
+
+    // 扫描状态中的时间是本地时间
+    let now = state
+        .clock
+        .now()
+        .with_timezone(&chrono::Local)
+        .naive_local();
+    let mut views: Vec<LibraryScanView> = statuses
+        .into_values()
+        .map(|status| LibraryScanView {
+            library_id: status.library_id.to_string(),
+            name: names.get(&status.library_id.as_i64()).cloned(),
+            scanning: status.scanning,
+            phase: status.phase.as_str(),
+            total_files: status.total_files,
+            enumerated_files: status.enumerated_files,
+            parsed_files: status.parsed_files,
+            processed_files: status.processed_files,
+            error_count: status.error_count,
+            progress: status.get_progress_percentage(),
+            eta_seconds: status.eta_seconds(now),
+            started_at: status.started_at,
+            finished_at: status.finished_at,
+        })
+        .collect();
+    views.sort_by(|a, b| a.library_id.cmp(&b.library_id));
+    HttpResponse::Ok().json(views)
+}
+
+/// 继续服务上次停止时未完成的扫描
+pub async fn resume_interrupted_scans(state: &AppState) {
+    let svc = crate::library_command_service(state);
+    if let Err(e) = svc.resume_interrupted_scans(&AppContext::new()).await {
+        warn!("Failed to resume interrupted scans: {}", e);
+    }
+}
+
+/// 按各库配置的扫描间隔定时触发增量扫描
+pub fn start_scan_scheduler(state: web::Data<AppState>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            run_due_scans(&state).await;
+        }
+    });
+}
+
+async fn run_due_scans(state: &AppState) {
+    // 存储恢复访问的库立即扫描，同步离线期间的变化
+    let restored = match crate::file_check_service(state).restore_libraries().await {
+        Ok(restored) => restored,
+        Err(e) => {
+            warn!("Failed to check unavailable libraries: {}", e);
+            Vec::new()
+        }
+    };
+    let library_repo = Arc::new(LibraryRepositoryImpl::new(state.db.clone()));
+    let libraries = match library_repo.find_all().await {
+        Ok(libraries) => libraries,
+        Err(e) => {
+            warn!("Failed to load libraries for scheduled scan: {}", e);
+            return;
+        }
+    };
+    let now = state.clock.now().naive_utc();
+    let default_interval = state.app_cfg.settings().scan_interval_minutes;
+    let due: Vec<_> = libraries
+        .into_iter()
+        .filter(|library| {
+            restored.contains(&library.id) || library.is_scan_due(now, default_interval)
+        })
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    let svc = crate::library_command_service(state);
+    let ctx = AppContext::new();
+    for library in due {
+        info!("Scheduled scan for library {}", library.name);
+        let cmd = ScanLibraryCmd {
+            library_id: library.id.clone(),
+            is_full_scan: false,
+            force: false,
+        };
+        if let Err(e) = svc.scan_library(&ctx, cmd).await {
+            warn!("Scheduled scan for library {} failed: {}", library.name, e);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::auth::{current_claims, resolve_user_id, ErrorResponse};
+use crate::consts;
+use crate::subsonic::helper::absolute_url;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use infra::auth::{AuthConfig, JwtTokenService};
+use serde::{Deserialize, Serialize};
+
+/// 可以签名的 Subsonic 接口
+const ENDPOINTS: [&str; 3] = ["stream", "download", "getCoverArt"];
+
+/// 注册签名媒体 URL 原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        &format!("{}/signed-urls", consts::URL_PATH_NATIVE_API),
+        web::post().to(create_signed_url),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedUrlRequest {
+    /// 歌曲 ID，getCoverArt 时为封面 ID
+    pub id: String,
+    /// stream（默认）、download 或 getCoverArt
+    pub endpoint: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedUrlView {
+    pub url: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 为无法携带认证参数的客户端（投屏设备、HTML audio）生成短期有效的媒体 URL：/api/signed-urls
+pub async fn create_signed_url(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<SignedUrlRequest>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let endpoint = body.endpoint.as_deref().unwrap_or("stream");
+    if !ENDPOINTS.contains(&endpoint) {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Unknown endpoint: {}", endpoint),
+        });
+    }
+    if body.id.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "Missing id".to_string(),
+        });
+    }
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+
+    let expires_at =
+        Utc::now() + chrono::Duration::seconds(state.app_cfg.stream_token_expire_secs());
+    let token_service =
+        JwtTokenService::new(state.app_cfg.jwt_secret(), state.app_cfg.jwt_expire_secs());
+    let token = match token_service.issue_stream_token(&body.id, user_id, expires_at.timestamp()) {
+        Ok(token) => token,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: e.to_string(),
+            })
+        }
+    };
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("id", &body.id)
+        .append_pair("st", &token)
+        .finish();
+    let url = format!(
+        "{}/{}?{}",
+        absolute_url(&state.app_cfg.base_url(), consts::URL_PATH_SUBSONIC_API),
+        endpoint,
+        query
+    );
+    HttpResponse::Ok().json(SignedUrlView {
+        url,
+        token,
+        expires_at,
+    })
+}
@@ -0,0 +1,619 @@
+use crate::admin::require_admin;
+use crate::api_v1::{query_error, Page, Paging};
+use crate::auth::{bad_request, current_claims, resolve_user_id, ErrorResponse};
+use crate::consts;
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::bandwidth::BandwidthUsageStore;
+use application::command::playback_history::PlaybackHistoryStore;
+use application::query::dao::{ListeningStreakDao, PlaybackHistoryDao};
+use application::query::get_charts::{ChartKind, GetCharts};
+use application::query::get_library_stats::{GetLibraryStats, LibraryStats, DEFAULT_GROWTH_MONTHS};
+use application::query::get_listening_report::GetListeningReport;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use infra::repository::postgres::command::bandwidth::BandwidthUsageStoreImpl;
+use infra::repository::postgres::command::playback_history::PlaybackHistoryStoreImpl;
+use infra::repository::postgres::query::library_stats::LibraryStatsDaoImpl;
+use infra::repository::postgres::query::listening_report::ListeningReportDaoImpl;
+use infra::repository::postgres::query::listening_streak::ListeningStreakDaoImpl;
+use infra::repository::postgres::query::play_stats::ChartDaoImpl;
+use infra::repository::postgres::query::playback_history::PlaybackHistoryDaoImpl;
+use log::{info, warn};
+use model::listening_streak::{AchievedMilestone, ListeningMilestone, MilestoneKind};
+use model::play_stats::ChartEntry;
+use model::playback_history::PlaybackHistoryItem;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const DEFAULT_CHART_DAYS: i64 = 30;
+const DEFAULT_CHART_LIMIT: i32 = 50;
+const MAX_CHART_LIMIT: i32 = 500;
+const DEFAULT_REPORT_LIMIT: i32 = 10;
+const DEFAULT_MILESTONE_LIMIT: i32 = 20;
+/// 播放历史的清理周期
+const PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// 注册播放统计原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/stats", consts::URL_PATH_NATIVE_API))
+            .route("/charts/{kind}", web::get().to(get_chart))
+            .route("/report/{year}", web::get().to(get_listening_report))
+            .route("/bandwidth", web::get().to(get_bandwidth))
+            .route("/history", web::get().to(get_history))
+            .route("/library", web::get().to(get_library_stats))
+            .route("/streak", web::get().to(get_listening_streak)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChartQuery {
+    /// 起始日期（含），YYYY-MM-DD，默认 to 之前 30 天
+    pub from: Option<NaiveDate>,
+    /// 结束日期（含），YYYY-MM-DD，默认今天
+    pub to: Option<NaiveDate>,
+    /// user（默认）或 server
+    pub scope: Option<String>,
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartItemView {
+    pub id: String,
+    pub name: String,
+    pub play_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportQuery {
+    /// 每个排行榜的条目数
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningReportView {
+    pub year: i32,
+    pub total_plays: i64,
+    pub total_minutes: i64,
+    pub top_artists: Vec<ChartItemView>,
+    pub top_albums: Vec<ChartItemView>,
+    pub top_songs: Vec<ChartItemView>,
+    pub top_genres: Vec<ChartItemView>,
+    /// 7 x 24 的播放次数，行为周一到周日，列为小时
+    pub clock: Vec<Vec<i64>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BandwidthQuery {
+    /// 起始日期（含），YYYY-MM-DD，默认 to 之前 30 天
+    pub from: Option<NaiveDate>,
+    /// 结束日期（含），YYYY-MM-DD，默认今天
+    pub to: Option<NaiveDate>,
+    /// user（默认）或 server，server 需要管理员
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthDayView {
+    pub day: NaiveDate,
+    pub user_id: String,
+    pub user_name: String,
+    pub bytes: i64,
+    pub streams: i32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthView {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub scope: String,
+    pub total_bytes: i64,
+    pub days: Vec<BandwidthDayView>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub offset: Option<i32>,
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItemView {
+    pub id: String,
+    pub song_id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    pub duration: i64,
+    /// 服务器本地时间
+    pub played_at: NaiveDateTime,
+}
+
+impl From<PlaybackHistoryItem> for HistoryItemView {
+    fn from(item: PlaybackHistoryItem) -> Self {
+        Self {
+            id: item.id.to_string(),
+            song_id: item.audio_file_id.to_string(),
+            title: item.title,
+            album_id: item.album_id.map(|id| id.to_string()),
+            album: item.album,
+            artist_id: item.artist_id.map(|id| id.to_string()),
+            artist: item.artist,
+            duration: item.duration,
+            played_at: item.scrobbled_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LibraryStatsQuery {
+    /// 增长曲线覆盖的月数（含当月），默认 12
+    pub months: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryTotalsView {
+    pub songs: i64,
+    pub albums: i64,
+    pub artists: i64,
+    pub genres: i64,
+    /// 秒
+    pub duration: i64,
+    /// 字节
+    pub size: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatCountView {
+    pub suffix: String,
+    pub songs: i64,
+    pub size: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitRateBucketView {
+    /// kbps，包含
+    pub min: i32,
+    /// kbps，不包含；最后一段没有上限
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<i32>,
+    pub songs: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleRateCountView {
+    pub sample_rate: i32,
+    pub songs: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrowthPointView {
+    /// YYYY-MM
+    pub month: String,
+    pub songs_added: i64,
+    pub albums_added: i64,
+    pub total_songs: i64,
+    pub total_albums: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStatsView {
+    pub totals: LibraryTotalsView,
+    pub formats: Vec<FormatCountView>,
+    pub bit_rates: Vec<BitRateBucketView>,
+    pub sample_rates: Vec<SampleRateCountView>,
+    pub growth: Vec<GrowthPointView>,
+}
+
+impl From<LibraryStats> for LibraryStatsView {
+    fn from(stats: LibraryStats) -> Self {
+        Self {
+            totals: LibraryTotalsView {
+                songs: stats.totals.songs,
+                albums: stats.totals.albums,
+                artists: stats.totals.artists,
+                genres: stats.totals.genres,
+                duration: stats.totals.duration,
+                size: stats.totals.size,
+            },
+            formats: stats
+                .formats
+                .into_iter()
+                .map(|f| FormatCountView {
+                    suffix: f.suffix,
+                    songs: f.songs,
+                    size: f.size,
+                })
+                .collect(),
+            bit_rates: stats
+                .bit_rates
+                .into_iter()
+                .map(|b| BitRateBucketView {
+                    min: b.min,
+                    max: b.max,
+                    songs: b.songs,
+                })
+                .collect(),
+            sample_rates: stats
+                .sample_rates
+                .into_iter()
+                .map(|s| SampleRateCountView {
+                    sample_rate: s.value,
+                    songs: s.songs,
+                })
+                .collect(),
+            growth: stats
+                .growth
+                .into_iter()
+                .map(|g| GrowthPointView {
+                    month: g.month.format("%Y-%m").to_string(),
+                    songs_added: g.songs_added,
+                    albums_added: g.albums_added,
+                    total_songs: g.total_songs,
+                    total_albums: g.total_albums,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreakQuery {
+    /// 返回最近的多少个里程碑
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneView {
+    /// plays、streak 或 firstArtistPlay
+    pub kind: &'static str,
+    /// 播放次数或连续天数；艺术家里程碑为 0
+    pub value: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist_name: Option<String>,
+    pub achieved_at: DateTime<Utc>,
+}
+
+impl MilestoneView {
+    pub fn new(milestone: &ListeningMilestone, artist_name: Option<String>) -> Self {
+        let (kind, value, artist_id) = match milestone.kind {
+            MilestoneKind::Plays => ("plays", milestone.value, None),
+            MilestoneKind::Streak => ("streak", milestone.value, None),
+            MilestoneKind::FirstArtistPlay => {
+                ("firstArtistPlay", 0, Some(milestone.value.to_string()))
+            }
+        };
+        Self {
+            kind,
+            value,
+            artist_id,
+            artist_name,
+            achieved_at: milestone.achieved_at.and_utc(),
+        }
+    }
+}
+
+impl From<AchievedMilestone> for MilestoneView {
+    fn from(achieved: AchievedMilestone) -> Self {
+        Self::new(&achieved.milestone, achieved.name)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningStreakView {
+    /// 昨天或今天没有播放时为 0
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_played_on: Option<NaiveDate>,
+    pub total_plays: i64,
+    /// 最近达成的在前
+    pub milestones: Vec<MilestoneView>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartView {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub scope: String,
+    pub items: Vec<ChartItemView>,
+}
+
+impl From<ChartEntry> for ChartItemView {
+    fn from(entry: ChartEntry) -> Self {
+        Self {
+            id: entry.item_id.to_string(),
+            name: entry.name,
+            play_count: entry.play_count,
+        }
+    }
+}
+
+fn parse_kind(raw: &str) -> Option<ChartKind> {
+    match raw {
+        "artists" => Some(ChartKind::Artists),
+        "albums" => Some(ChartKind::Albums),
+        "songs" => Some(ChartKind::Songs),
+        "genres" => Some(ChartKind::Genres),
+        _ => None,
+    }
+}
+
+/// 播放次数排行榜：/api/stats/charts/{artists|albums|songs|genres}
+pub async fn get_chart(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<ChartQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let Some(kind) = parse_kind(&path) else {
+        return bad_request(format!("Unknown chart: {}", path));
+    };
+
+    let to = query.to.unwrap_or_else(|| Local::now().date_naive());
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Duration::days(DEFAULT_CHART_DAYS - 1));
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_CHART_LIMIT)
+        .clamp(1, MAX_CHART_LIMIT);
+    let scope = query.scope.as_deref().unwrap_or("user");
+    let user_id = match scope {
+        "user" => match resolve_user_id(&state, &claims).await {
+            Ok(id) => Some(id),
+            Err(rsp) => return rsp,
+        },
+        "server" => None,
+        other => return bad_request(format!("Unknown scope: {}", other)),
+    };
+
+    let get_charts = GetCharts::new(Arc::new(ChartDaoImpl::new(state.db.clone())));
+    match get_charts.handle(kind, from, to, user_id, limit).await {
+        Ok(entries) => HttpResponse::Ok().json(ChartView {
+            from,
+            to,
+            scope: scope.to_string(),
+            items: entries.into_iter().map(Into::into).collect(),
+        }),
+        Err(e) => query_error(e),
+    }
+}
+
+/// 当前用户的年度收听报告：/api/stats/report/{year}
+pub async fn get_listening_report(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<ReportQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let year = match path.as_str() {
+        "current" => Local::now().year(),
+        raw => match raw.parse::<i32>() {
+            Ok(year) => year,
+            Err(_) => return bad_request(format!("Invalid year: {}", raw)),
+        },
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_REPORT_LIMIT)
+        .clamp(1, MAX_CHART_LIMIT);
+
+    let get_report = GetListeningReport::new(
+        Arc::new(ListeningReportDaoImpl::new(state.db.clone())),
+        Arc::new(ChartDaoImpl::new(state.db.clone())),
+    );
+    match get_report.handle(user_id, year, limit).await {
+        Ok(report) => HttpResponse::Ok().json(ListeningReportView {
+            year: report.year,
+            total_plays: report.total_plays,
+            total_minutes: report.total_minutes,
+            top_artists: report.top_artists.into_iter().map(Into::into).collect(),
+            top_albums: report.top_albums.into_iter().map(Into::into).collect(),
+            top_songs: report.top_songs.into_iter().map(Into::into).collect(),
+            top_genres: report.top_genres.into_iter().map(Into::into).collect(),
+            clock: report.clock,
+        }),
+        Err(e) => query_error(e),
+    }
+}
+
+/// stream 流量，按用户和日期：/api/stats/bandwidth
+pub async fn get_bandwidth(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<BandwidthQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let to = query.to.unwrap_or_else(|| Local::now().date_naive());
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Duration::days(DEFAULT_CHART_DAYS - 1));
+    if from > to {
+        return bad_request(format!("from ({}) must not be after to ({})", from, to));
+    }
+    let scope = query.scope.as_deref().unwrap_or("user");
+    let user_id = match scope {
+        "user" => match resolve_user_id(&state, &claims).await {
+            Ok(id) => Some(id),
+            Err(rsp) => return rsp,
+        },
+        "server" if claims.is_admin => None,
+        "server" => {
+            return HttpResponse::Forbidden().json(ErrorResponse {
+                error: "Only administrators can access this resource".to_string(),
+            })
+        }
+        other => return bad_request(format!("Unknown scope: {}", other)),
+    };
+
+    let store = BandwidthUsageStoreImpl::new(state.db.clone());
+    match store.usage(user_id, from, to).await {
+        Ok(usage) => HttpResponse::Ok().json(BandwidthView {
+            from,
+            to,
+            scope: scope.to_string(),
+            total_bytes: usage.iter().map(|u| u.bytes).sum(),
+            days: usage
+                .into_iter()
+                .map(|u| BandwidthDayView {
+                    day: u.day,
+                    user_id: u.user_id.to_string(),
+                    user_name: u.user_name,
+                    bytes: u.bytes,
+                    streams: u.streams,
+                })
+                .collect(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// 当前用户的播放历史，最近的在前：/api/stats/history。
+/// 超过 playback_history.retention_days 的记录已汇总进统计并删除
+pub async fn get_history(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<HistoryQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let paging = match Paging::new(query.offset, query.limit) {
+        Ok(paging) => paging,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let dao = PlaybackHistoryDaoImpl::new(state.db.clone());
+    match dao.get_by_user(user_id, paging.offset, paging.limit).await {
+        Ok((items, total)) => {
+            let page: Page<HistoryItemView> = paging.page(items, total);
+            HttpResponse::Ok().json(page)
+        }
+        Err(e) => query_error(e),
+    }
+}
+
+/// 管理仪表盘的媒体库统计，结果缓存一分钟：/api/stats/library
+pub async fn get_library_stats(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<LibraryStatsQuery>,
+) -> HttpResponse {
+    if let Err(rsp) = require_admin(&req) {
+        return rsp;
+    }
+    let today = state.clock.now().with_timezone(&Local).date_naive();
+    let months = query.months.unwrap_or(DEFAULT_GROWTH_MONTHS);
+
+    let get_stats = GetLibraryStats::new(
+        Arc::new(LibraryStatsDaoImpl::new(state.db.clone())),
+        state.library_stats_cache.clone(),
+    );
+    match get_stats.handle(today, months).await {
+        Ok(stats) => HttpResponse::Ok().json(LibraryStatsView::from(stats)),
+        Err(e) => query_error(e),
+    }
+}
+
+/// 当前用户的连续收听天数和最近的里程碑：/api/stats/streak
+pub async fn get_listening_streak(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<StreakQuery>,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(rsp) => return rsp,
+    };
+    let user_id = match resolve_user_id(&state, &claims).await {
+        Ok(id) => id,
+        Err(rsp) => return rsp,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_MILESTONE_LIMIT)
+        .clamp(1, MAX_CHART_LIMIT);
+    let today = state.clock.now().with_timezone(&Local).date_naive();
+
+    let dao = ListeningStreakDaoImpl::new(state.db.clone());
+    let streak = match dao.get_streak(user_id).await {
+        Ok(streak) => streak,
+        Err(e) => return query_error(e),
+    };
+    let milestones = match dao.get_milestones(user_id, limit).await {
+        Ok(milestones) => milestones,
+        Err(e) => return query_error(e),
+    };
+    HttpResponse::Ok().json(ListeningStreakView {
+        current_streak: streak.as_ref().map_or(0, |s| s.current_on(today)),
+        longest_streak: streak.as_ref().map_or(0, |s| s.longest_streak),
+        last_played_on: streak.as_ref().and_then(|s| s.last_played_on),
+        total_plays: streak.as_ref().map_or(0, |s| s.total_plays),
+        milestones: milestones.into_iter().map(Into::into).collect(),
+    })
+}
+
+/// 定期把超过保留期的播放历史汇总进统计后删除
+pub fn start_playback_history_prune(state: web::Data<AppState>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let Some(retention) = state.app_cfg.playback_history().retention else {
+                continue;
+            };
+            // scrobbled_at 是服务器本地时间
+            let before = Local::now().naive_local() - retention;
+            let store = PlaybackHistoryStoreImpl::new(state.db.clone());
+            match store.prune(before).await {
+                Ok(0) => {}
+                Ok(pruned) => info!("Pruned {} playback history entries", pruned),
+                Err(e) => warn!("Failed to prune playback history: {}", e),
+            }
+        }
+    });
+}
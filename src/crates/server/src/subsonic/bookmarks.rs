@@ -1,12 +1,24 @@
-use crate::subsonic::response::directory::Child;
+use crate::middleware::other::{ClientUniqueID, RequestClient};
+use crate::subsonic::helper::{check_non_negative, SubsonicQuery, ValidateParams};
+use crate::subsonic::response::directory::{Child, OpenSubsonicChild};
 use crate::subsonic::response::error::SubsonicError;
-use crate::subsonic::response::play::PlayQueue as PlayQueueResponse;
+use crate::subsonic::response::play::{
+    Bookmark as BookmarkResponse, Bookmarks as BookmarksResponse, PlayQueue as PlayQueueResponse,
+    PlayQueueByIndex as PlayQueueByIndexResponse,
+};
 use crate::subsonic::response::Subsonic;
 use crate::AppState;
 use actix_web::{web, HttpMessage, HttpRequest};
-use application::command::play_queue::{PlayQueueAppService, SavePlayQueueCmd};
+use application::command::bookmark::{BookmarkAppService, SaveBookmarkCmd};
+use application::command::play_queue::{queue_client_id, PlayQueueAppService, SavePlayQueueCmd};
+use application::query::get_bookmarks::GetBookmarks;
 use application::query::get_play_queue::GetPlayQueue;
+use infra::repository::postgres::command::audio_file::AudioFileRepositoryImpl;
+use infra::repository::postgres::command::bookmark::BookmarkRepositoryImpl;
+use infra::repository::postgres::command::library::LibraryRepositoryImpl;
 use infra::repository::postgres::command::play_queue::PlayQueueRepositoryImpl;
+use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
+use infra::repository::postgres::query::bookmark::BookmarkDaoImpl;
 use infra::repository::postgres::query::play_queue::PlayQueueDaoImpl;
 use model::playlist::PlaylistAudioFile;
 use serde::Deserialize;
@@ -78,6 +90,172 @@ pub struct SavePlayQueueQuery {
     /// 当前歌曲的播放位置（毫秒）
     #[serde(default)]
     pub position: Option<i64>,
+
+    /// 客户端产生该状态的时间（毫秒），用于多设备同时保存时以最新的为准
+    #[serde(default)]
+    pub changed: Option<i64>,
+}
+
+impl ValidateParams for SavePlayQueueQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("position", self.position)
+    }
+}
+
+/// 请求对应的设备标识：优先客户端唯一 ID，没有时退回客户端名称（c 参数）
+fn client_unique_id(req: &HttpRequest) -> Option<String> {
+    let extensions = req.extensions();
+    extensions
+        .get::<ClientUniqueID>()
+        .map(|id| id.0.clone())
+        .or_else(|| extensions.get::<RequestClient>().map(|c| c.0.clone()))
+}
+
+/// savePlayQueueByIndex API 请求参数
+///
+/// 与 savePlayQueue 相同，但当前歌曲用队列中的索引表示，
+/// 队列中有重复歌曲时也能准确定位
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePlayQueueByIndexQuery {
+    /// 歌曲 ID 列表
+    #[serde(default, deserialize_with = "deserialize_string_or_vec")]
+    pub id: Vec<String>,
+
+    /// 当前播放歌曲在队列中的索引（从 0 开始）
+    #[serde(default)]
+    pub current_index: Option<usize>,
+
+    /// 当前歌曲的播放位置（毫秒）
+    #[serde(default)]
+    pub position: Option<i64>,
+
+    /// 客户端产生该状态的时间（毫秒）
+    #[serde(default)]
+    pub changed: Option<i64>,
+}
+
+impl ValidateParams for SavePlayQueueByIndexQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("position", self.position)
+    }
+}
+
+/// createBookmark API 请求参数
+#[derive(Deserialize)]
+pub struct CreateBookmarkQuery {
+    /// 歌曲 ID
+    pub id: String,
+
+    /// 书签位置（毫秒）
+    pub position: i64,
+
+    /// 备注
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+impl ValidateParams for CreateBookmarkQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("position", Some(self.position))
+    }
+}
+
+/// deleteBookmark API 请求参数
+#[derive(Deserialize)]
+pub struct DeleteBookmarkQuery {
+    /// 歌曲 ID
+    pub id: String,
+}
+
+fn current_user(req: &HttpRequest) -> Result<domain::user::User, SubsonicError> {
+    req.extensions()
+        .get::<domain::user::User>()
+        .cloned()
+        .ok_or_else(|| SubsonicError::error_generic().wrap("User not found".to_string()))
+}
+
+fn parse_song_ids(ids: &[String]) -> Vec<i64> {
+    ids.iter().filter_map(|id| id.parse::<i64>().ok()).collect()
+}
+
+/// 客户端名称，记录为队列的 changedBy
+fn changed_by(req: &HttpRequest) -> String {
+    req.headers()
+        .get("X-Subsonic-Client")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn parse_song_id(raw: &str) -> Result<i64, SubsonicError> {
+    raw.parse::<i64>()
+        .map_err(|_| SubsonicError::error_generic().wrap(format!("Invalid id: {}", raw)))
+}
+
+fn bookmark_service(state: &AppState) -> BookmarkAppService {
+    BookmarkAppService::new(
+        Arc::new(BookmarkRepositoryImpl::new(state.db.clone())),
+        Arc::new(AudioFileRepositoryImpl::new(state.db.clone())),
+        Arc::new(LibraryRepositoryImpl::new(state.db.clone())),
+    )
+}
+
+async fn save_queue(state: &AppState, cmd: SavePlayQueueCmd) -> Result<Subsonic, SubsonicError> {
+    let play_queue_repo: Arc<dyn domain::play_queue::PlayQueueRepository> =
+        Arc::new(PlayQueueRepositoryImpl::new(state.db.clone()));
+    let play_queue_app_service = PlayQueueAppService::new(
+        play_queue_repo,
+        state.id_generator.clone(),
+        state.app_cfg.settings().play_queue_mode.unwrap_or_default(),
+    );
+
+    // 有声书的播放位置同时记为书签，换客户端也能续播
+    let progress = cmd
+        .current_id
+        .map(|id| (cmd.user_id, id, cmd.position, cmd.changed_by.clone()));
+
+    play_queue_app_service
+        .save_play_queue(cmd)
+        .await
+        .map_err(SubsonicError::from)?;
+
+    if let Some((user_id, current_id, position, changed_by)) = progress {
+        if let Err(e) = bookmark_service(state)
+            .record_progress(&user_id.into(), &current_id.into(), position, &changed_by)
+            .await
+        {
+            log::warn!("Failed to record progress for {}: {}", current_id, e);
+        }
+    }
+
+    Ok(Subsonic::default())
+}
+
+/// 读取当前用户的播放队列，按设备保存时优先取当前设备的队列
+async fn load_queue(
+    state: &AppState,
+    req: &HttpRequest,
+    user: &domain::user::User,
+) -> Result<Option<model::play_queue::PlayQueue>, SubsonicError> {
+    let play_queue_dao = Arc::new(PlayQueueDaoImpl::new(state.db.clone()));
+    let get_play_queue_svc = GetPlayQueue::new(play_queue_dao);
+
+    let mode = state.app_cfg.settings().play_queue_mode.unwrap_or_default();
+    let client_id = queue_client_id(mode, client_unique_id(req).as_deref());
+
+    get_play_queue_svc
+        .get_by_user_id(user.id.as_i64(), &client_id, &user.name)
+        .await
+        .map_err(SubsonicError::from)
+}
+
+fn queue_entries(pq: &model::play_queue::PlayQueue) -> Option<Vec<Child>> {
+    if pq.entries.is_empty() {
+        None
+    } else {
+        Some(pq.entries.iter().map(audio_file_to_child).collect())
+    }
 }
 
 /// savePlayQueue - 保存播放队列状态
@@ -90,58 +268,57 @@ pub struct SavePlayQueueQuery {
 /// - 如果不传任何参数，则清空当前保存的队列
 /// - 如果 id 为空，current 也不是必需的
 /// - 如果 position 为空，服务器应将位置视为 0
+///
+/// 扩展:
+/// - 设置为按设备保存时，队列以客户端唯一 ID 区分
+/// - changed 早于已保存状态的请求会被忽略
 pub async fn save_play_queue(
     state: web::Data<AppState>,
     req: HttpRequest,
-    query: web::Query<SavePlayQueueQuery>,
+    query: SubsonicQuery<SavePlayQueueQuery>,
 ) -> Result<Subsonic, SubsonicError> {
-    // 从 request extensions 中获取用户
-    let user = req
-        .extensions()
-        .get::<domain::user::User>()
-        .ok_or_else(|| SubsonicError::error_generic().wrap("User not found".to_string()))?
-        .clone();
-
-    // 解析歌曲 ID 列表
-    let song_ids: Vec<i64> = query
-        .id
-        .iter()
-        .filter_map(|id| id.parse::<i64>().ok())
-        .collect();
-
-    // 解析当前播放的歌曲 ID
-    let current_id: Option<i64> = query.current.as_ref().and_then(|id| id.parse::<i64>().ok());
+    let user = current_user(&req)?;
 
-    // 获取播放位置，默认为 0
-    let position = query.position.unwrap_or(0);
-
-    // 获取客户端名称
-    let changed_by = req
-        .headers()
-        .get("X-Subsonic-Client")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| "unknown".to_string());
-
-    // 创建 Command 服务
-    let play_queue_repo: Arc<dyn domain::play_queue::PlayQueueRepository> =
-        Arc::new(PlayQueueRepositoryImpl::new(state.db.clone()));
-    let play_queue_app_service =
-        PlayQueueAppService::new(play_queue_repo, state.id_generator.clone());
+    let cmd = SavePlayQueueCmd {
+        user_id: user.id.as_i64(),
+        song_ids: parse_song_ids(&query.id),
+        current_id: query.current.as_ref().and_then(|id| id.parse::<i64>().ok()),
+        current_index: None,
+        position: query.position.unwrap_or(0),
+        changed_by: changed_by(&req),
+        client_id: client_unique_id(&req),
+        changed_at: query.changed,
+    };
+    save_queue(&state, cmd).await
+}
 
-    // 保存播放队列
-    play_queue_app_service
-        .save_play_queue(SavePlayQueueCmd {
-            user_id: user.id.as_i64(),
-            song_ids,
-            current_id,
-            position,
-            changed_by,
-        })
-        .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+/// savePlayQueueByIndex - 按索引保存播放队列状态
+///
+/// OpenSubsonic indexBasedQueue 扩展:
+/// - currentIndex 为当前歌曲在 id 列表中的索引，越界时视为没有当前歌曲
+/// - 同时保存当前歌曲 ID，旧的 getPlayQueue 仍能返回 current
+pub async fn save_play_queue_by_index(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: SubsonicQuery<SavePlayQueueByIndexQuery>,
+) -> Result<Subsonic, SubsonicError> {
+    let user = current_user(&req)?;
 
-    Ok(Subsonic::default())
+    let song_ids = parse_song_ids(&query.id);
+    let current_id = query
+        .current_index
+        .and_then(|idx| song_ids.get(idx).copied());
+    let cmd = SavePlayQueueCmd {
+        user_id: user.id.as_i64(),
+        song_ids,
+        current_id,
+        current_index: query.current_index,
+        position: query.position.unwrap_or(0),
+        changed_by: changed_by(&req),
+        client_id: client_unique_id(&req),
+        changed_at: query.changed,
+    };
+    save_queue(&state, cmd).await
 }
 
 /// getPlayQueue - 获取播放队列状态
@@ -154,56 +331,127 @@ pub async fn get_play_queue(
     state: web::Data<AppState>,
     req: HttpRequest,
 ) -> Result<Subsonic, SubsonicError> {
-    // 从 request extensions 中获取用户
-    let user = req
-        .extensions()
-        .get::<domain::user::User>()
-        .ok_or_else(|| SubsonicError::error_generic().wrap("User not found".to_string()))?
-        .clone();
+    let user = current_user(&req)?;
 
-    // 创建 Query 服务
-    let play_queue_dao = Arc::new(PlayQueueDaoImpl::new(state.db.clone()));
-    let get_play_queue_svc = GetPlayQueue::new(play_queue_dao);
+    let response = match load_queue(&state, &req, &user).await? {
+        Some(pq) => PlayQueueResponse {
+            entry: queue_entries(&pq),
+            current: pq.current_id.map(|id| id.to_string()),
+            position: Some(pq.position),
+            username: pq.username,
+            changed: Some(pq.changed),
+            changed_by: pq.changed_by,
+        },
+        // 返回空的播放队列
+        None => PlayQueueResponse {
+            entry: None,
+            current: None,
+            position: None,
+            username: user.name,
+            changed: None,
+            changed_by: String::new(),
+        },
+    };
+    Ok(response.into())
+}
+
+/// getPlayQueueByIndex - 获取播放队列状态，当前歌曲以索引表示
+///
+/// 与 getPlayQueue 读取同一份队列，由 savePlayQueue 保存的旧数据
+/// 按当前歌曲 ID 在队列中的位置换算索引
+pub async fn get_play_queue_by_index(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<Subsonic, SubsonicError> {
+    let user = current_user(&req)?;
+
+    let response = match load_queue(&state, &req, &user).await? {
+        Some(pq) => PlayQueueByIndexResponse {
+            entry: queue_entries(&pq),
+            current_index: pq.current_index,
+            position: Some(pq.position),
+            username: pq.username,
+            changed: Some(pq.changed),
+            changed_by: pq.changed_by,
+        },
+        None => PlayQueueByIndexResponse {
+            entry: None,
+            current_index: None,
+            position: None,
+            username: user.name,
+            changed: None,
+            changed_by: String::new(),
+        },
+    };
+    Ok(response.into())
+}
+
+/// createBookmark - 创建或更新书签
+///
+/// 根据 Subsonic 规范 (Since 1.9.0):
+/// - 每个用户每首歌只有一个书签，重复创建时移动到新位置
+/// - position 为毫秒
+pub async fn create_bookmark(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: SubsonicQuery<CreateBookmarkQuery>,
+) -> Result<Subsonic, SubsonicError> {
+    let user = current_user(&req)?;
+    let cmd = SaveBookmarkCmd {
+        user_id: user.id.clone(),
+        audio_file_id: parse_song_id(&query.id)?.into(),
+        position: query.position,
+        comment: query.comment.clone(),
+        changed_by: changed_by(&req),
+    };
+    bookmark_service(&state).save_bookmark(cmd).await?;
+    Ok(Subsonic::default())
+}
 
-    // 获取播放队列
-    let play_queue = get_play_queue_svc
-        .get_by_user_id(user.id.as_i64(), &user.name)
+/// getBookmarks - 获取当前用户的全部书签，最近修改的在前
+pub async fn get_bookmarks(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<Subsonic, SubsonicError> {
+    let user = current_user(&req)?;
+    let get_bookmarks = GetBookmarks::new(
+        Arc::new(BookmarkDaoImpl::new(state.db.clone())),
+        Arc::new(AudioFileDaoImpl::new(state.db.clone())),
+    );
+    let bookmarks = get_bookmarks
+        .handle(user.id.as_i64())
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
-
-    match play_queue {
-        Some(pq) => {
-            let entries: Vec<Child> = pq.entries.iter().map(audio_file_to_child).collect();
-
-            let response = PlayQueueResponse {
-                entry: if entries.is_empty() {
-                    None
-                } else {
-                    Some(entries)
-                },
-                current: pq.current_id.map(|id| id.to_string()),
-                position: Some(pq.position),
-                username: pq.username,
-                changed: Some(pq.changed),
-                changed_by: pq.changed_by,
-            };
-
-            Ok(response.into())
-        }
-        None => {
-            // 返回空的播放队列
-            let response = PlayQueueResponse {
-                entry: None,
-                current: None,
-                position: None,
-                username: user.name,
-                changed: None,
-                changed_by: String::new(),
-            };
-
-            Ok(response.into())
-        }
+        .map_err(SubsonicError::from)?;
+
+    let bookmark: Vec<BookmarkResponse> = bookmarks
+        .into_iter()
+        .map(|b| BookmarkResponse {
+            entry: Child::from(b.entry),
+            position: b.position,
+            username: user.name.clone(),
+            comment: Some(b.comment).filter(|c| !c.is_empty()),
+            created: b.created_at,
+            changed: b.updated_at,
+        })
+        .collect();
+    Ok(BookmarksResponse {
+        bookmark: Some(bookmark).filter(|b| !b.is_empty()),
     }
+    .into())
+}
+
+/// deleteBookmark - 删除书签
+pub async fn delete_bookmark(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<DeleteBookmarkQuery>,
+) -> Result<Subsonic, SubsonicError> {
+    let user = current_user(&req)?;
+    let audio_file_id = parse_song_id(&query.id)?;
+    bookmark_service(&state)
+        .delete_bookmark(&user.id, &audio_file_id.into())
+        .await?;
+    Ok(Subsonic::default())
 }
 
 /// 将 PlaylistAudioFile 转换为 Child
@@ -222,6 +470,8 @@ fn audio_file_to_child(af: &PlaylistAudioFile) -> Child {
         year: af.year,
         genre: af.genre.clone(),
         cover_art: af.cover_art_id.map(|_| audio_file_cover_art_id(af.id)),
+        cover_art_blurhash: None,
+        cover_art_color: None,
         size: af.size,
         content_type: af.content_type.clone(),
         suffix: af.suffix.clone(),
@@ -240,5 +490,6 @@ fn audio_file_to_child(af: &PlaylistAudioFile) -> Child {
         user_rating: af.rating,
         song_count: 0,
         is_video: false,
+        os_child: OpenSubsonicChild::song(),
     }
 }
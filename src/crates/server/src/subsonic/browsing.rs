@@ -1,10 +1,14 @@
 use crate::consts;
+use crate::subsonic::helper::{
+    check_non_negative, current_user_id, hide_explicit, user_notes, SubsonicQuery, ValidateParams,
+    MAX_LIST_SIZE,
+};
 use crate::subsonic::response::error::SubsonicError;
 use crate::subsonic::response::{artist::Indexes, music_folder::MusicFolders, Subsonic};
 use crate::AppState;
 use actix_web::{
     error::ResponseError, http::StatusCode, middleware::from_fn, web, web::Json, web::Path,
-    HttpResponse, Responder, Scope,
+    HttpRequest, HttpResponse, Responder, Scope,
 };
 use application::query::artist::ArtistIndexRule;
 use application::query::artist::ArtistService;
@@ -18,7 +22,9 @@ use application::query::get_music_folders::GetMusicFolders;
 use application::query::get_similar_songs::GetSimilarSongs;
 use application::query::get_song::GetSong;
 use application::query::get_top_songs::GetTopSongs;
+use domain::annotation::Kind;
 use infra::auth::{AuthConfig, JwtTokenService};
+use infra::normalize::ArtistNameNormalizerImpl;
 use infra::repository::postgres::query::album::AlbumDaoImpl;
 use infra::repository::postgres::query::artist::ArtistDaoImpl;
 use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
@@ -26,19 +32,30 @@ use infra::repository::postgres::query::music_folder::MusicFolderDaoImpl;
 use serde::Deserialize;
 use std::sync::Arc;
 
+/// 索引分组使用当前生效的冠词列表
+fn index_rule(state: &AppState) -> ArtistIndexRule {
+    ArtistIndexRule::new(
+        &state.app_cfg.indexgroups(),
+        true,
+        Arc::new(ArtistNameNormalizerImpl::with_shared(
+            state.app_cfg.ignored_articles.clone(),
+        )),
+        state.app_cfg.index_romanize(),
+    )
+}
+
 pub async fn get_music_folders(state: web::Data<AppState>) -> Subsonic {
     let media_folder_dao = MusicFolderDaoImpl::new(state.db.clone());
     let usecase = GetMusicFolders::new(Arc::new(media_folder_dao));
     match usecase.handle().await {
         Ok(folders) => MusicFolders::new(folders).into(),
-        Err(e) => SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => SubsonicError::from(e).into(),
     }
 }
 
-pub async fn get_indexes(state: web::Data<AppState>) -> Subsonic {
-    let artist_dao = ArtistDaoImpl::new(state.db.clone());
-    let index_groups = state.app_cfg.indexgroups();
-    let index_rule = ArtistIndexRule::new(&index_groups, true);
+pub async fn get_indexes(req: HttpRequest, state: web::Data<AppState>) -> Subsonic {
+    let artist_dao = ArtistDaoImpl::new(state.db.clone()).with_user(current_user_id(&req));
+    let index_rule = index_rule(&state);
     let token_service = Arc::new(JwtTokenService::new(
         &state.app_cfg.jwt_secret(),
         state.app_cfg.jwt_expire_secs(),
@@ -49,7 +66,7 @@ pub async fn get_indexes(state: web::Data<AppState>) -> Subsonic {
     let base_url = state.app_cfg.base_url();
     match usecase.get_indexes_with_tokens().await {
         Ok(indexes) => Indexes::new(ignored_articles, indexes, &base_url).into(),
-        Err(e) => SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => SubsonicError::from(e).into(),
     }
 }
 #[derive(Deserialize, Default)]
@@ -61,6 +78,7 @@ pub struct GetArtistsQuery {
 
 use log::info;
 pub async fn get_artists(
+    req: HttpRequest,
     state: web::Data<AppState>,
     query: web::Query<GetArtistsQuery>,
 ) -> Subsonic {
@@ -74,11 +92,11 @@ pub async fn get_artists(
         match music_folder_dao.get_by_id(folder_id).await {
             Ok(Some(folder)) => folder.last_scan_at,
             Ok(None) => {
-                return SubsonicError::error_generic()
+                return SubsonicError::error_data_not_found()
                     .wrap("Music folder not found".to_string())
                     .into();
             }
-            Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+            Err(e) => return SubsonicError::from(e).into(),
         }
     } else {
         // 没有指定文件夹时，获取所有文件夹中最新的 last_scan_at
@@ -93,9 +111,8 @@ pub async fn get_artists(
         }
     };
 
-    let artist_dao = ArtistDaoImpl::new(state.db.clone());
-    let index_groups = state.app_cfg.indexgroups();
-    let index_rule = ArtistIndexRule::new(&index_groups, true);
+    let artist_dao = ArtistDaoImpl::new(state.db.clone()).with_user(current_user_id(&req));
+    let index_rule = index_rule(&state);
     let token_service = Arc::new(JwtTokenService::new(
         &state.app_cfg.jwt_secret(),
         state.app_cfg.jwt_expire_secs(),
@@ -106,7 +123,7 @@ pub async fn get_artists(
     let base_url = state.app_cfg.base_url();
     match usecase.get_artists_with_tokens().await {
         Ok(artists) => Artists::new(artists, last_scan_at, ignored_articles, &base_url).into(),
-        Err(e) => SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => SubsonicError::from(e).into(),
     }
 }
 
@@ -118,9 +135,16 @@ pub struct GetArtistQuery {
 
 use crate::subsonic::response::album::AlbumID3;
 use crate::subsonic::response::artist::{ArtistID3, ArtistWithAlbumsID3};
-pub async fn get_artist(state: web::Data<AppState>, query: web::Query<GetArtistQuery>) -> Subsonic {
-    let artist_dao = ArtistDaoImpl::new(state.db.clone());
-    let album_dao = AlbumDaoImpl::new(state.db.clone());
+pub async fn get_artist(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<GetArtistQuery>,
+) -> Subsonic {
+    let user_id = current_user_id(&req);
+    let artist_dao = ArtistDaoImpl::new(state.db.clone()).with_user(user_id);
+    let album_dao = AlbumDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(user_id);
     let token_service = Arc::new(JwtTokenService::new(
         &state.app_cfg.jwt_secret(),
         state.app_cfg.jwt_expire_secs(),
@@ -128,7 +152,7 @@ pub async fn get_artist(state: web::Data<AppState>, query: web::Query<GetArtistQ
     let usecase = GetArtist::new(Arc::new(artist_dao), Arc::new(album_dao), token_service);
     let artist_dto = match usecase.handle(query.id).await {
         Ok(dto) => dto,
-        Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => return SubsonicError::from(e).into(),
     };
 
     // 接口层负责 URL 生成（展示层关注点）
@@ -160,7 +184,7 @@ pub async fn get_genres(state: web::Data<AppState>) -> Subsonic {
     let usecase = GetGenres::new(Arc::new(genre_dao));
     match usecase.handle().await {
         Ok(genres) => Genres::new(genres).into(),
-        Err(e) => SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => SubsonicError::from(e).into(),
     }
 }
 
@@ -172,13 +196,20 @@ pub struct GetAlbumQuery {
 
 use crate::subsonic::response::album::AlbumWithSongsID3;
 use crate::subsonic::response::directory::Child;
-pub async fn get_album(state: web::Data<AppState>, query: web::Query<GetAlbumQuery>) -> Subsonic {
-    let album_dao = AlbumDaoImpl::new(state.db.clone());
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+pub async fn get_album(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<GetAlbumQuery>,
+) -> Subsonic {
+    let user_id = current_user_id(&req);
+    let album_dao = AlbumDaoImpl::new(state.db.clone()).with_user(user_id);
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(user_id);
     let usecase = GetAlbum::new(Arc::new(album_dao), Arc::new(audio_file_dao));
     let (album, audio_files) = match usecase.handle(query.id).await {
         Ok(result) => result,
-        Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => return SubsonicError::from(e).into(),
     };
 
     let songs: Vec<Child> = audio_files
@@ -186,7 +217,9 @@ pub async fn get_album(state: web::Data<AppState>, query: web::Query<GetAlbumQue
         .map(|audio_file| Child::from(audio_file))
         .collect();
 
-    let album_id3 = AlbumID3::new(album);
+    let mut notes = user_notes(&state, &req, Kind::Album, &[album.id]).await;
+    let mut album_id3 = AlbumID3::new(album);
+    album_id3.comment = notes.remove(&query.id);
     AlbumWithSongsID3 {
         album: album_id3,
         song: songs,
@@ -200,12 +233,16 @@ pub struct GetSongQuery {
     pub id: i64,
 }
 
-pub async fn get_song(state: web::Data<AppState>, query: web::Query<GetSongQuery>) -> Subsonic {
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+pub async fn get_song(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<GetSongQuery>,
+) -> Subsonic {
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone()).with_user(current_user_id(&req));
     let usecase = GetSong::new(Arc::new(audio_file_dao));
     let audio_file = match usecase.handle(query.id).await {
         Ok(audio_file) => audio_file,
-        Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => return SubsonicError::from(e).into(),
     };
 
     Song {
@@ -231,7 +268,7 @@ pub async fn get_artist_info(
     let usecase = GetArtistInfo::new(Arc::new(artist_dao), token_service);
     let artist_info_dto = match usecase.handle(query.id).await {
         Ok(dto) => dto,
-        Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => return SubsonicError::from(e).into(),
     };
 
     // 接口层负责 URL 生成（展示层关注点）
@@ -266,7 +303,7 @@ pub async fn get_album_info(
     let usecase = GetAlbumInfo::new(Arc::new(album_dao), token_service);
     let album_info_dto = match usecase.handle(query.id).await {
         Ok(dto) => dto,
-        Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => return SubsonicError::from(e).into(),
     };
 
     // 接口层负责 URL 生成（展示层关注点）
@@ -291,22 +328,32 @@ pub struct GetTopSongsQuery {
     pub count: Option<i32>,
 }
 
+impl ValidateParams for GetTopSongsQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("count", self.count)
+    }
+}
+
 use crate::subsonic::response::song::TopSongs;
 pub async fn get_top_songs(
+    req: HttpRequest,
     state: web::Data<AppState>,
-    query: web::Query<GetTopSongsQuery>,
+    query: SubsonicQuery<GetTopSongsQuery>,
 ) -> Subsonic {
-    let artist_dao = ArtistDaoImpl::new(state.db.clone());
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let user_id = current_user_id(&req);
+    let artist_dao = ArtistDaoImpl::new(state.db.clone()).with_user(user_id);
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(user_id);
     let usecase = GetTopSongs::new(Arc::new(artist_dao), Arc::new(audio_file_dao));
 
     // query the top songs by artist (按播放次数排序，限制数量)
     let songs = match usecase
-        .handle(&query.artist, query.count.unwrap_or(50))
+        .handle(&query.artist, query.count.unwrap_or(50).min(MAX_LIST_SIZE))
         .await
     {
         Ok(songs) => songs,
-        Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => return SubsonicError::from(e).into(),
     };
 
     // 将 AudioFile 转换为 Child
@@ -321,10 +368,17 @@ pub struct GetSimilarSongsQuery {
     pub count: Option<i32>,
 }
 
+impl ValidateParams for GetSimilarSongsQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("count", self.count)
+    }
+}
+
 use crate::subsonic::response::song::SimilarSongs;
 pub async fn get_similar_songs(
+    req: HttpRequest,
     state: web::Data<AppState>,
-    query: web::Query<GetSimilarSongsQuery>,
+    query: SubsonicQuery<GetSimilarSongsQuery>,
 ) -> Subsonic {
     // 解析 artist_id（从字符串转换为 i64）
     let artist_id: i64 = match query.id.parse() {
@@ -336,14 +390,18 @@ pub async fn get_similar_songs(
         }
     };
 
-    let artist_dao = ArtistDaoImpl::new(state.db.clone());
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let user_id = current_user_id(&req);
+    let artist_dao = ArtistDaoImpl::new(state.db.clone()).with_user(user_id);
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(user_id);
     let usecase = GetSimilarSongs::new(Arc::new(artist_dao), Arc::new(audio_file_dao));
 
     // query the similar artist' songs
-    let songs = match usecase.handle(artist_id, query.count.unwrap_or(50)).await {
+    let count = query.count.unwrap_or(50).min(MAX_LIST_SIZE);
+    let songs = match usecase.handle(artist_id, count).await {
         Ok(songs) => songs,
-        Err(e) => return SubsonicError::error_generic().wrap(e.to_string()).into(),
+        Err(e) => return SubsonicError::from(e).into(),
     };
 
     // 将 AudioFile 转换为 Child
@@ -1,6 +1,14 @@
 use crate::consts;
-use actix_web::{FromRequest, HttpRequest};
+use crate::subsonic::response::error::SubsonicError;
+use crate::AppState;
+use actix_web::error::QueryPayloadError;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use application::query::dao::UserNoteDao;
+use domain::annotation::Kind;
+use infra::repository::postgres::query::user_note::UserNoteDaoImpl;
 use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt;
 use std::future::{ready, Ready};
 use std::ops::Deref;
 
@@ -58,10 +66,132 @@ where
         let config = serde_qs::Config::new(5, false);
         match config.deserialize_str::<T>(query_string) {
             Ok(value) => ready(Ok(QsQuery(value))),
-            Err(e) => ready(Err(actix_web::error::ErrorBadRequest(format!(
-                "Query string parse error: {}",
-                e
-            )))),
+            Err(e) => ready(Err(parameter_error(&e.to_string()).into())),
         }
     }
 }
+
+/// 列表接口单次返回的最大条数
+pub const MAX_LIST_SIZE: i32 = 500;
+
+/// Subsonic 参数的取值校验，由 SubsonicQuery 在提取参数后调用
+pub trait ValidateParams {
+    fn validate(&self) -> Result<(), SubsonicError>;
+}
+
+/// 解析并校验 Subsonic 查询参数。缺少参数返回错误 10，类型或取值不对返回错误 0，
+/// 不再落到 actix 的纯文本 400 或处理器中的默认值
+#[derive(Debug, Clone)]
+pub struct SubsonicQuery<T>(pub T);
+
+impl<T> Deref for SubsonicQuery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for SubsonicQuery<T>
+where
+    T: DeserializeOwned + ValidateParams,
+{
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let result = match web::Query::<T>::from_query(req.query_string()) {
+            Ok(query) => query.validate().map(|_| SubsonicQuery(query.into_inner())),
+            Err(QueryPayloadError::Deserialize(e)) => Err(parameter_error(&e.to_string())),
+            Err(e) => Err(parameter_error(&e.to_string())),
+        };
+        ready(result.map_err(Into::into))
+    }
+}
+
+/// 缺少参数和参数无效都返回错误 10，与 `From<QueryError>` 中 InvalidParameter 的映射一致；
+/// serde 对缺少的字段只报告 `missing field`，其余错误在消息中注明参数无效
+fn parameter_error(message: &str) -> SubsonicError {
+    if message.contains("missing field") {
+        SubsonicError::error_missing_parameter().wrap(message.to_string())
+    } else {
+        SubsonicError::error_missing_parameter().wrap(format!("Invalid parameter: {}", message))
+    }
+}
+
+/// 当前用户开启了家长控制，列表、搜索和随机歌曲中不返回 explicit 内容
+pub fn hide_explicit(req: &HttpRequest) -> bool {
+    req.extensions()
+        .get::<domain::user::User>()
+        .is_some_and(|user| user.hide_explicit)
+}
+
+/// 当前用户的 ID，查询播放次数、评分和收藏时只取该用户的注解
+pub fn current_user_id(req: &HttpRequest) -> Option<i64> {
+    req.extensions()
+        .get::<domain::user::User>()
+        .map(|user| user.id.as_i64())
+}
+
+/// 当前用户对这些条目的备注，用于填充 comment。查询失败时不影响列表本身
+pub async fn user_notes(
+    state: &AppState,
+    req: &HttpRequest,
+    kind: Kind,
+    item_ids: &[i64],
+) -> HashMap<i64, String> {
+    let Some(user_id) = current_user_id(req) else {
+        return HashMap::new();
+    };
+    UserNoteDaoImpl::new(state.db.clone())
+        .get_notes(user_id, kind.name(), item_ids)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load notes of user {}: {}", user_id, e);
+            HashMap::new()
+        })
+}
+
+/// 可选参数为负数时返回错误
+pub fn check_non_negative<N>(name: &str, value: Option<N>) -> Result<(), SubsonicError>
+where
+    N: PartialOrd + Default + fmt::Display,
+{
+    match value {
+        Some(value) if value < N::default() => Err(SubsonicError::error_missing_parameter().wrap(
+            format!("Parameter '{}' must not be negative, got {}", name, value),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 可选参数超出 [min, max] 时返回错误
+pub fn check_range(
+    name: &str,
+    value: Option<i32>,
+    min: i32,
+    max: i32,
+) -> Result<(), SubsonicError> {
+    match value {
+        Some(value) if value < min || value > max => Err(SubsonicError::error_missing_parameter()
+            .wrap(format!(
+                "Parameter '{}' must be between {} and {}, got {}",
+                name, min, max, value
+            ))),
+        _ => Ok(()),
+    }
+}
+
+/// 参数取值不在 allowed 中时返回错误
+pub fn check_one_of(name: &str, value: &str, allowed: &[&str]) -> Result<(), SubsonicError> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(SubsonicError::error_missing_parameter().wrap(format!(
+            "Unknown {} '{}', expected one of: {}",
+            name,
+            value,
+            allowed.join(", ")
+        )))
+    }
+}
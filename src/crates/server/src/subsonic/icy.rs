@@ -0,0 +1,193 @@
+use actix_web::body::{BodySize, BoxBody, MessageBody};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::web::Bytes;
+use actix_web::{HttpRequest, HttpResponse};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// 两个元数据块之间的音频字节数，与 SHOUTcast 默认值相同
+const ICY_METAINT: usize = 16000;
+
+/// 元数据块长度以 16 字节为单位，用一个字节表示
+const MAX_METADATA_LEN: usize = 255 * 16;
+
+/// 客户端发送 `Icy-MetaData: 1` 时才插入元数据
+pub fn wants_icy_metadata(req: &HttpRequest) -> bool {
+    req.headers()
+        .get("icy-metadata")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim() == "1")
+}
+
+/// 在响应体中每隔 ICY_METAINT 字节插入一个 ICY 元数据块，第一个块带 StreamTitle；
+/// 插入后长度改变，不能再声明 Content-Length 或支持 Range
+pub fn with_icy_metadata(response: HttpResponse, artist: &str, title: &str) -> HttpResponse {
+    let stream_title = if artist.is_empty() {
+        title.to_string()
+    } else {
+        format!("{} - {}", artist, title)
+    };
+    let metadata = metadata_block(&stream_title);
+    response.map_body(|head, body| {
+        head.headers.remove(header::CONTENT_LENGTH);
+        head.headers.remove(header::ACCEPT_RANGES);
+        head.headers.insert(
+            HeaderName::from_static("icy-metaint"),
+            HeaderValue::from(ICY_METAINT),
+        );
+        if let Ok(name) = HeaderValue::from_str(&stream_title) {
+            head.headers
+                .insert(HeaderName::from_static("icy-name"), name);
+        }
+        BoxBody::new(IcyBody {
+            inner: body,
+            pending: Bytes::new(),
+            until_metadata: ICY_METAINT,
+            metadata: Some(metadata),
+        })
+    })
+}
+
+/// 长度字节加上补零到 16 字节整数倍的 `StreamTitle='...';`
+fn metadata_block(stream_title: &str) -> Bytes {
+    // 单引号会提前结束标题，换成右单引号
+    let stream_title = stream_title.replace('\'', "\u{2019}");
+    let mut text = format!("StreamTitle='{}';", stream_title);
+    if text.len() > MAX_METADATA_LEN {
+        let mut end = MAX_METADATA_LEN - 2;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("';");
+    }
+    let blocks = text.len().div_ceil(16);
+    let mut block = Vec::with_capacity(1 + blocks * 16);
+    block.push(blocks as u8);
+    block.extend_from_slice(text.as_bytes());
+    block.resize(1 + blocks * 16, 0);
+    Bytes::from(block)
+}
+
+struct IcyBody {
+    inner: BoxBody,
+    /// 上游已返回、还没发出的音频数据
+    pending: Bytes,
+    until_metadata: usize,
+    /// 第一个元数据块，发出后只发送表示无变化的空块
+    metadata: Option<Bytes>,
+}
+
+impl MessageBody for IcyBody {
+    type Error = Box<dyn std::error::Error>;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.get_mut();
+        while this.pending.is_empty() {
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => this.pending = chunk,
+                other => return other,
+            }
+        }
+        if this.until_metadata == 0 {
+            this.until_metadata = ICY_METAINT;
+            let block = this
+                .metadata
+                .take()
+                .unwrap_or_else(|| Bytes::from_static(&[0]));
+            return Poll::Ready(Some(Ok(block)));
+        }
+        let n = this.until_metadata.min(this.pending.len());
+        this.until_metadata -= n;
+        Poll::Ready(Some(Ok(this.pending.split_to(n))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::{to_bytes, BodyStream};
+    use std::convert::Infallible;
+
+    /// 按给定大小分块发送递增的音频字节，返回插入元数据后的完整响应体
+    fn stream(chunk_sizes: &[usize], metadata: &Bytes) -> (Vec<u8>, Bytes) {
+        let total: usize = chunk_sizes.iter().sum();
+        let audio: Vec<u8> = (0..total).map(|i| (i % 251) as u8).collect();
+        let mut offset = 0;
+        let chunks: Vec<Result<Bytes, Infallible>> = chunk_sizes
+            .iter()
+            .map(|size| {
+                let chunk = Bytes::copy_from_slice(&audio[offset..offset + size]);
+                offset += size;
+                Ok(chunk)
+            })
+            .collect();
+        let body = IcyBody {
+            inner: BoxBody::new(BodyStream::new(futures::stream::iter(chunks))),
+            pending: Bytes::new(),
+            until_metadata: ICY_METAINT,
+            metadata: Some(metadata.clone()),
+        };
+        let output = futures::executor::block_on(to_bytes(body)).unwrap();
+        (audio, output)
+    }
+
+    #[test]
+    fn metadata_block_is_padded_to_16_bytes() {
+        let block = metadata_block("Artist - It's");
+        // StreamTitle='Artist - It’s'; 共 30 字节，补零到 32
+        assert_eq!(block[0], 2);
+        assert_eq!(block.len(), 1 + 32);
+        let text = "StreamTitle='Artist - It\u{2019}s';";
+        assert_eq!(&block[1..1 + text.len()], text.as_bytes());
+        assert!(block[1 + text.len()..].iter().all(|b| *b == 0));
+
+        let block = metadata_block("");
+        assert_eq!(block[0], 1);
+        assert_eq!(&block[1..], b"StreamTitle='';\0");
+    }
+
+    #[test]
+    fn long_titles_are_truncated_on_a_char_boundary() {
+        let block = metadata_block(&"é".repeat(MAX_METADATA_LEN));
+        assert_eq!(block[0], 255);
+        assert_eq!(block.len(), 1 + MAX_METADATA_LEN);
+        let text = std::str::from_utf8(&block[1..])
+            .unwrap()
+            .trim_end_matches('\0');
+        assert!(text.starts_with("StreamTitle='é"));
+        assert!(text.ends_with("é';"));
+    }
+
+    #[test]
+    fn inserts_a_block_every_metaint_bytes_across_chunks() {
+        let metadata = metadata_block("Artist - Title");
+        let (audio, output) = stream(&[7000, 7000, 7000, 15000], &metadata);
+
+        let mut expected = audio[..ICY_METAINT].to_vec();
+        expected.extend_from_slice(&metadata);
+        expected.extend_from_slice(&audio[ICY_METAINT..2 * ICY_METAINT]);
+        // 之后的块为空，表示标题没有变化
+        expected.push(0);
+        expected.extend_from_slice(&audio[2 * ICY_METAINT..]);
+        assert_eq!(output.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn no_trailing_block_when_the_stream_ends_on_a_boundary() {
+        let metadata = metadata_block("Title");
+        let (audio, output) = stream(&[ICY_METAINT, ICY_METAINT], &metadata);
+
+        let mut expected = audio[..ICY_METAINT].to_vec();
+        expected.extend_from_slice(&metadata);
+        expected.extend_from_slice(&audio[ICY_METAINT..]);
+        assert_eq!(output.as_ref(), expected.as_slice());
+    }
+}
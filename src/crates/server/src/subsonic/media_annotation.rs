@@ -1,17 +1,23 @@
+use crate::client_ip::client_ip_string;
+use crate::subsonic::helper::{check_range, SubsonicQuery, ValidateParams};
 use crate::subsonic::response::error::SubsonicError;
 use crate::subsonic::response::Subsonic;
 use crate::AppState;
 use actix_web::{web, HttpMessage, HttpRequest};
 use application::command::media_annotation::{
-    MediaAnnotationService, ScrobbleCmd, SetRatingCmd, StarCmd, StarItem, UnstarCmd, UnstarItem,
+    MediaAnnotationService, ScrobbleCmd, ScrobbleItem, SetRatingCmd, StarCmd, StarItem, UnstarCmd,
+    UnstarItem,
 };
 use application::context::AppContext;
-use domain::value::{AudioFileId, PlayerId};
+use domain::annotation::Kind;
+use domain::value::AudioFileId;
 use infra::repository::postgres::command::album::AlbumRepositoryImpl;
 use infra::repository::postgres::command::annotation::AnnotationRepositoryImpl;
 use infra::repository::postgres::command::artist::ArtistRepositoryImpl;
 use infra::repository::postgres::command::audio_file::AudioFileRepositoryImpl;
+use infra::repository::postgres::command::library::LibraryRepositoryImpl;
 use infra::repository::postgres::command::player::PlayerRepositoryImpl;
+use infra::repository::postgres::command::scrobble_submission::ScrobbleSubmissionStoreImpl;
 use serde::Deserialize;
 use std::sync::Arc;
 
@@ -90,21 +96,27 @@ pub async fn star(
     // 添加 song ids
     for id in &query.id {
         if let Ok(item_id) = id.parse::<i64>() {
-            items.push(StarItem { item_id });
+            items.push(StarItem { item_id, kind: None });
         }
     }
     
     // 添加 album ids
     for id in &query.album_id {
         if let Ok(item_id) = id.parse::<i64>() {
-            items.push(StarItem { item_id });
+            items.push(StarItem {
+                item_id,
+                kind: Some(Kind::Album),
+            });
         }
     }
     
     // 添加 artist ids
     for id in &query.artist_id {
         if let Ok(item_id) = id.parse::<i64>() {
-            items.push(StarItem { item_id });
+            items.push(StarItem {
+                item_id,
+                kind: Some(Kind::Artist),
+            });
         }
     }
 
@@ -120,17 +132,17 @@ pub async fn star(
         state.id_generator.clone(),
     ));
     let player_repo = Arc::new(PlayerRepositoryImpl::new(state.db.clone()));
-    let event_bus = Arc::new(state.event_bus.clone());
     let id_generator = state.id_generator.clone();
 
     let svc = MediaAnnotationService::new(
+        annotation_repo.clone(),
         annotation_repo,
         audio_file_repo,
         album_repo,
         artist_repo,
         player_repo,
         id_generator,
-        event_bus,
+        state.clock.clone(),
     );
 
     let ctx = AppContext::new();
@@ -145,7 +157,7 @@ pub async fn star(
         .await
     {
         Ok(_) => Ok(Subsonic::default()),
-        Err(e) => Err(SubsonicError::error_generic().wrap(e.to_string())),
+        Err(e) => Err(SubsonicError::from(e)),
     }
 }
 
@@ -178,21 +190,27 @@ pub async fn unstar(
     // 添加 song ids
     for id in &query.id {
         if let Ok(item_id) = id.parse::<i64>() {
-            items.push(UnstarItem { item_id });
+            items.push(UnstarItem { item_id, kind: None });
         }
     }
     
     // 添加 album ids
     for id in &query.album_id {
         if let Ok(item_id) = id.parse::<i64>() {
-            items.push(UnstarItem { item_id });
+            items.push(UnstarItem {
+                item_id,
+                kind: Some(Kind::Album),
+            });
         }
     }
     
     // 添加 artist ids
     for id in &query.artist_id {
         if let Ok(item_id) = id.parse::<i64>() {
-            items.push(UnstarItem { item_id });
+            items.push(UnstarItem {
+                item_id,
+                kind: Some(Kind::Artist),
+            });
         }
     }
 
@@ -208,17 +226,17 @@ pub async fn unstar(
         state.id_generator.clone(),
     ));
     let player_repo = Arc::new(PlayerRepositoryImpl::new(state.db.clone()));
-    let event_bus = Arc::new(state.event_bus.clone());
     let id_generator = state.id_generator.clone();
 
     let svc = MediaAnnotationService::new(
+        annotation_repo.clone(),
         annotation_repo,
         audio_file_repo,
         album_repo,
         artist_repo,
         player_repo,
         id_generator,
-        event_bus,
+        state.clock.clone(),
     );
 
     let ctx = AppContext::new();
@@ -233,7 +251,7 @@ pub async fn unstar(
         .await
     {
         Ok(_) => Ok(Subsonic::default()),
-        Err(e) => Err(SubsonicError::error_generic().wrap(e.to_string())),
+        Err(e) => Err(SubsonicError::from(e)),
     }
 }
 
@@ -244,10 +262,17 @@ pub struct SetRatingQuery {
     pub rating: i32,
 }
 
+impl ValidateParams for SetRatingQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        // 0 表示清除评分
+        check_range("rating", Some(self.rating), 0, 5)
+    }
+}
+
 pub async fn set_rating(
     state: web::Data<AppState>,
     req: HttpRequest,
-    query: web::Query<SetRatingQuery>,
+    query: SubsonicQuery<SetRatingQuery>,
 ) -> Result<Subsonic, SubsonicError> {
     // 从 request extensions 中获取用户
     let user = req
@@ -274,17 +299,17 @@ pub async fn set_rating(
         state.id_generator.clone(),
     ));
     let player_repo = Arc::new(PlayerRepositoryImpl::new(state.db.clone()));
-    let event_bus = Arc::new(state.event_bus.clone());
     let id_generator = state.id_generator.clone();
 
     let svc = MediaAnnotationService::new(
+        annotation_repo.clone(),
         annotation_repo,
         audio_file_repo,
         album_repo,
         artist_repo,
         player_repo,
         id_generator,
-        event_bus,
+        state.clock.clone(),
     );
 
     let ctx = AppContext::new();
@@ -294,45 +319,69 @@ pub async fn set_rating(
             SetRatingCmd {
                 user_id: user.id.clone(),
                 item_id,
+                kind: None,
                 rating: query.rating,
             },
         )
         .await
     {
         Ok(_) => Ok(Subsonic::default()),
-        Err(e) => Err(SubsonicError::error_generic().wrap(e.to_string())),
+        Err(e) => Err(SubsonicError::from(e)),
     }
 }
 
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ScrobbleQuery {
-    pub id: String,
-    #[serde(default)]
-    pub time: Option<i64>,
-    #[serde(default = "default_submission")]
-    pub submission: bool,
-}
-
-fn default_submission() -> bool {
-    true
+/// id 和 time 都可以重复（离线队列一次提交），按出现顺序配对；time 为毫秒时间戳
+fn scrobble_params(query_string: &str) -> Result<(Vec<ScrobbleItem>, bool), SubsonicError> {
+    let invalid = |msg: String| SubsonicError::error_generic().wrap(msg);
+    let mut ids = Vec::new();
+    let mut times = Vec::new();
+    let mut submission = true;
+    for (key, value) in url::form_urlencoded::parse(query_string.as_bytes()) {
+        match key.as_ref() {
+            "id" => ids.push(
+                value
+                    .parse::<i64>()
+                    .map_err(|_| invalid("Invalid id format".to_string()))?,
+            ),
+            "time" => {
+                let played_at = value
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .ok_or_else(|| invalid(format!("Invalid time: {}", value)))?;
+                times.push(played_at.naive_utc());
+            }
+            "submission" => submission = value != "false",
+            _ => {}
+        }
+    }
+    if ids.is_empty() {
+        return Err(SubsonicError::error_missing_parameter().wrap("id".to_string()));
+    }
+    if !times.is_empty() && times.len() != ids.len() {
+        return Err(invalid(
+            "Number of time and id parameters differ".to_string(),
+        ));
+    }
+    let items = ids
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| ScrobbleItem {
+            audio_file_id: AudioFileId::from(id),
+            played_at: times.get(i).copied(),
+        })
+        .collect();
+    Ok((items, submission))
 }
 
 pub async fn scrobble(
     state: web::Data<AppState>,
     req: HttpRequest,
-    query: web::Query<ScrobbleQuery>,
 ) -> Result<Subsonic, SubsonicError> {
-    use crate::middleware::other::{ClientUniqueID, RequestClient};
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
+    use crate::middleware::other::{request_player_id, RequestClient};
     use url::Url;
 
-    // 解析 id
-    let audio_file_id: i64 = query
-        .id
-        .parse()
-        .map_err(|_| SubsonicError::error_generic().wrap("Invalid id format".to_string()))?;
+    let (items, submission) = scrobble_params(req.query_string())?;
 
     // 从 request extensions 中获取用户
     let user = req
@@ -366,12 +415,8 @@ pub async fn scrobble(
                 .to_string()
         });
 
-    // 从 request 中获取 IP 地址
-    let ip = req
-        .connection_info()
-        .peer_addr()
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| "unknown".to_string());
+    // 从 request 中获取 IP 地址（经过受信代理时取转发的客户端地址）
+    let ip = client_ip_string(&req);
 
     // 从 User-Agent header 获取 user_agent
     let user_agent = req
@@ -393,38 +438,24 @@ pub async fn scrobble(
         state.id_generator.clone(),
     ));
     let player_repo = Arc::new(PlayerRepositoryImpl::new(state.db.clone()));
-    let event_bus = Arc::new(state.event_bus.clone());
     let id_generator = state.id_generator.clone();
 
     let svc = MediaAnnotationService::new(
+        annotation_repo.clone(),
         annotation_repo,
         audio_file_repo,
         album_repo,
         artist_repo,
         player_repo,
         id_generator,
-        event_bus,
-    );
+        state.clock.clone(),
+    )
+    .with_submission_store(Arc::new(ScrobbleSubmissionStoreImpl::new(state.db.clone())))
+    .with_library_repository(Arc::new(LibraryRepositoryImpl::new(state.db.clone())));
 
-    // 从客户端生成的 client_id (UUID 字符串) 生成 player_id
-    // client_id 由客户端生成并通过 client_unique_id 中间件设置到 request extensions
-    // 使用 client_id 的 hash 值生成稳定的 player_id，确保同一客户端总是得到相同的 player_id
-    let player_id = req
-        .extensions()
-        .get::<ClientUniqueID>()
-        .map(|cid| {
-            // client_id 是客户端生成的 UUID 字符串，使用 hash 值生成 player_id
-            // 这样可以确保相同的 client_id 总是生成相同的 player_id
-            let mut hasher = DefaultHasher::new();
-            cid.0.hash(&mut hasher);
-            let hash = hasher.finish() as i64;
-            PlayerId::from(hash)
-        })
-        .unwrap_or_else(|| {
-            // 如果没有 ClientUniqueID（客户端未提供），使用用户ID作为后备
-            // 注意：这种情况下，应用服务可能会创建新的 player
-            PlayerId::from(user.id.as_i64())
-        });
+    // client_id 由客户端生成并通过 client_unique_id 中间件设置到 request extensions，
+    // 没有 client_id 时应用服务可能会以用户 ID 创建新的 player
+    let player_id = request_player_id(&req, &user.id);
 
     let ctx = AppContext::new();
     match svc
@@ -436,13 +467,13 @@ pub async fn scrobble(
                 ip,
                 user_agent,
                 user_id: user.id.clone(),
-                audio_file_id: AudioFileId::from(audio_file_id),
-                submission: query.submission,
+                items,
+                submission,
             },
         )
         .await
     {
         Ok(_) => Ok(Subsonic::default()),
-        Err(e) => Err(SubsonicError::error_generic().wrap(e.to_string())),
+        Err(e) => Err(SubsonicError::from(e)),
     }
 }
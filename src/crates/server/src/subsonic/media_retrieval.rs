@@ -1,21 +1,38 @@
+use crate::middleware::other::request_player_id;
+use crate::subsonic::helper::{check_non_negative, SubsonicQuery, ValidateParams};
+use crate::subsonic::icy;
 use crate::subsonic::response::error::SubsonicError;
 use crate::subsonic::response::Subsonic;
 use crate::AppState;
-use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use actix_web::body::{BodySize, BoxBody, MessageBody};
+use actix_web::http::header::TryIntoHeaderValue;
+use actix_web::web::Bytes;
+use actix_web::{http::header, web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use application::command::bandwidth::BandwidthUsageStore;
 use application::query::config::CoverArtConfig;
 use application::query::dao::{AudioFileDao, CoverArtDao};
-use application::query::get_cover_art::{CoverArtCache, CoverArtReader, GetCoverArt};
+use application::query::get_cover_art::{
+    CoverArtCache, CoverArtReader, CoverOutputFormat, GetCoverArt,
+};
 use application::query::stream_cache::{StreamCache, StreamCacheConfig};
-use application::query::stream_media::{StreamMedia, StreamRequest, TranscodeStream};
+use application::query::stream_media::{
+    StreamInfo, StreamMedia, StreamRequest, TranscodeStream,
+};
+use domain::player::PlayerRepository;
 use domain::transcoding::TranscodingStreamer;
+use domain::user::User;
 use futures::StreamExt;
 use infra::auth::AuthConfig;
 use infra::config::TranscodingConfig;
+use infra::repository::postgres::command::bandwidth::BandwidthUsageStoreImpl;
+use infra::repository::postgres::command::player::PlayerRepositoryImpl;
 use infra::repository::postgres::query::audio_file::AudioFileDaoImpl;
 use infra::repository::postgres::query::cover_art::CoverArtDaoImpl;
 use infra::{CoverArtCacheImpl, CoverArtReaderImpl};
 use serde::Deserialize;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
 
@@ -26,26 +43,39 @@ pub struct GetCoverArtQuery {
     pub id: String,
     #[serde(default)]
     pub size: Option<u32>,
+    /// 缩放后的输出格式（jpeg/webp/avif），未指定时按 Accept 头协商
+    #[serde(default)]
+    pub format: Option<String>,
 }
 
-/// getCoverArt - 获取封面艺术图片
-pub async fn get_cover_art(
-    state: web::Data<AppState>,
-    query: web::Query<GetCoverArtQuery>,
-) -> HttpResponse {
+/// 封面 URL 带更新时间，内容不会变化
+const COVER_ART_CACHE_CONTROL: &str = "public, max-age=315360000";
+
+/// 存储无法访问时建议客户端重试的间隔
+const STORAGE_RETRY_AFTER_SECS: u64 = 60;
+
+fn cover_art_usecase(state: &AppState) -> GetCoverArt {
     let cover_art_dao: Arc<dyn CoverArtDao + Send + Sync> =
         Arc::new(CoverArtDaoImpl::new(state.db.clone()));
-    let cover_art_config: Arc<dyn CoverArtConfig + Send + Sync> =
-        Arc::new(state.app_cfg.clone());
+    let cover_art_config: Arc<dyn CoverArtConfig + Send + Sync> = Arc::new(state.app_cfg.clone());
     let cover_art_reader: Arc<dyn CoverArtReader + Send + Sync> =
         Arc::new(CoverArtReaderImpl::new());
     let cover_art_cache: Arc<dyn CoverArtCache + Send + Sync> = state.cover_art_cache.clone();
-    let usecase = GetCoverArt::new(
+    GetCoverArt::new(
         cover_art_dao,
         cover_art_config,
         cover_art_reader,
         cover_art_cache,
-    );
+    )
+}
+
+/// getCoverArt - 获取封面艺术图片
+pub async fn get_cover_art(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<GetCoverArtQuery>,
+) -> HttpResponse {
+    let usecase = cover_art_usecase(&state);
 
     let token_service = infra::auth::JwtTokenService::new(
         &state.app_cfg.jwt_secret(),
@@ -53,12 +83,38 @@ pub async fn get_cover_art(
     );
 
     let cover_art_id = parse_cover_art_id(&query.id, &token_service);
+    let accept = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok());
+    let format = CoverOutputFormat::negotiate(query.format.as_deref(), accept);
+
+    // 客户端已缓存当前版本时不生成图片
+    if let Some(if_none_match) = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+    {
+        if let Ok(etag) = usecase.etag(&cover_art_id, query.size, format).await {
+            if etag_matches(if_none_match, &etag) {
+                return HttpResponse::NotModified()
+                    .insert_header((header::CACHE_CONTROL, COVER_ART_CACHE_CONTROL))
+                    .insert_header((header::ETAG, format!("\"{}\"", etag)))
+                    .insert_header((header::VARY, "Accept"))
+                    .finish();
+            }
+        }
+    }
 
-    match usecase.get_or_placeholder(&cover_art_id, query.size).await {
+    match usecase
+        .get_or_placeholder(&cover_art_id, query.size, format)
+        .await
+    {
         Ok(cover_data) => HttpResponse::Ok()
             .insert_header((header::CONTENT_TYPE, cover_data.mime_type))
-            .insert_header((header::CACHE_CONTROL, "public, max-age=315360000"))
+            .insert_header((header::CACHE_CONTROL, COVER_ART_CACHE_CONTROL))
             .insert_header((header::ETAG, format!("\"{}\"", cover_data.cache_key)))
+            .insert_header((header::VARY, "Accept"))
             .body(cover_data.data),
         Err(e) => {
             log::warn!("Failed to get cover art for {}: {}", query.id, e);
@@ -67,6 +123,14 @@ pub async fn get_cover_art(
     }
 }
 
+/// If-None-Match 可能包含多个 ETag、弱校验前缀或 *
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/").trim_matches('"') == etag)
+}
+
 fn parse_cover_art_id(id: &str, token_service: &infra::auth::JwtTokenService) -> String {
     if id.contains('.') {
         match token_service.verify_cover_art_token(id) {
@@ -96,6 +160,13 @@ pub struct StreamQuery {
     pub estimate_content_length: Option<bool>,
 }
 
+impl ValidateParams for StreamQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("maxBitRate", self.max_bit_rate)?;
+        check_non_negative("timeOffset", self.time_offset)
+    }
+}
+
 /// TranscodingConfig 的 StreamCacheConfig 适配器
 struct TranscodingConfigAdapter {
     config: TranscodingConfig,
@@ -123,12 +194,37 @@ impl StreamCacheConfig for TranscodingConfigAdapter {
     fn is_lossless(&self, format: &str) -> bool {
         self.config.is_lossless(format)
     }
+
+    fn embeds_metadata(&self, format: &str) -> bool {
+        self.config.embeds_metadata(format)
+    }
+}
+
+/// 用户和当前播放器比特率上限中较小的一个，0 或未设置表示不限制
+async fn bit_rate_cap(state: &AppState, req: &HttpRequest, user: &User) -> Option<i32> {
+    let player_id = request_player_id(req, &user.id);
+    let player_cap = match PlayerRepositoryImpl::new(state.db.clone())
+        .find_by_id(player_id)
+        .await
+    {
+        Ok(Some(player)) if player.user_id == user.id => Some(player.max_bit_rate),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("[Stream] Failed to load player: {}", e);
+            None
+        }
+    };
+    [user.max_bit_rate, player_cap]
+        .into_iter()
+        .flatten()
+        .filter(|v| *v > 0)
+        .min()
 }
 
 /// stream - 流式传输媒体文件
 pub async fn stream(
     state: web::Data<AppState>,
-    query: web::Query<StreamQuery>,
+    query: SubsonicQuery<StreamQuery>,
     req: HttpRequest,
 ) -> StreamResponse {
     log::info!(
@@ -155,14 +251,32 @@ pub async fn stream(
     let usecase = StreamMedia::new(audio_file_dao)
         .with_cache(stream_cache)
         .with_config(config_adapter)
-        .with_transcoder(transcoder);
+        .with_transcoder(transcoder)
+        .with_storage(Arc::new(crate::storage_client_factory(&state)))
+        .with_cover_art(Arc::new(cover_art_usecase(&state)));
+
+    // 用户或播放器设置了比特率上限时，收紧客户端请求的比特率，超过上限的源文件必须转码
+    let user = req.extensions().get::<domain::user::User>().cloned();
+    let (max_bit_rate, bit_rate_cap) = match user {
+        Some(user) => {
+            let cap = bit_rate_cap(&state, &req, &user).await;
+            let requested = user.limit_bit_rate(query.max_bit_rate);
+            let max_bit_rate = match cap {
+                Some(cap) => Some(requested.filter(|v| *v > 0).map_or(cap, |v| v.min(cap))),
+                None => requested,
+            };
+            (max_bit_rate, cap)
+        }
+        None => (query.max_bit_rate, None),
+    };
 
     let request = StreamRequest {
         id: query.id,
-        max_bit_rate: query.max_bit_rate,
+        max_bit_rate,
         format: query.format.clone(),
         time_offset: query.time_offset,
         estimate_content_length: query.estimate_content_length.unwrap_or(false),
+        bit_rate_cap,
     };
 
     // 获取流媒体信息
@@ -196,6 +310,16 @@ pub async fn stream(
         query.id, decision.needs_transcoding, range_header
     );
 
+    // 硬件播放器通过 ICY 元数据显示歌曲信息，Range 请求不插入
+    let icy = range_header.is_none() && icy::wants_icy_metadata(&req);
+    let with_icy = |response: HttpResponse| {
+        if icy {
+            icy::with_icy_metadata(response, &stream_info.tags.artist, &stream_info.tags.title)
+        } else {
+            response
+        }
+    };
+
     // 如果需要转码或没有 Range 请求，使用流处理逻辑
     if decision.needs_transcoding || range_header.is_none() {
         // 1. 先检查缓存
@@ -216,13 +340,13 @@ pub async fn stream(
                 ));
             }
 
-            return StreamResponse::Binary(
+            return StreamResponse::Binary(with_icy(
                 HttpResponse::Ok()
                     .insert_header((header::CONTENT_TYPE, cached.content_type))
                     .insert_header((header::CONTENT_LENGTH, cached.size))
                     .insert_header((header::ACCEPT_RANGES, "bytes"))
                     .body(cached.data),
-            );
+            ));
         }
 
         // 2. 需要转码：使用流式响应
@@ -294,7 +418,7 @@ pub async fn stream(
                         }
                     }
 
-                    return StreamResponse::Binary(response.streaming(body_stream));
+                    return StreamResponse::Binary(with_icy(response.streaming(body_stream)));
                 }
                 Err(e) => {
                     log::error!("[Stream] Failed to create transcode stream: {}", e);
@@ -325,21 +449,26 @@ pub async fn stream(
                     ));
                 }
 
-                StreamResponse::Binary(
+                StreamResponse::Binary(with_icy(
                     HttpResponse::Ok()
                         .insert_header((header::CONTENT_TYPE, stream_data.content_type))
                         .insert_header((header::CONTENT_LENGTH, stream_data.size))
                         .insert_header((header::ACCEPT_RANGES, "bytes"))
                         .body(stream_data.data),
-                )
+                ))
             }
             Err(e) => {
                 log::error!("[Stream] Failed to get stream data for {}: {}", query.id, e);
-                StreamResponse::Error(
-                    SubsonicError::error_generic().wrap(format!("Failed to stream: {}", e)),
-                )
+                storage_unavailable(format!("Failed to stream: {}", e))
             }
         }
+    } else if !stream_info.is_local() {
+        // 远程存储上的原始文件：从 Range 起点打开，只读取需要的部分
+        log::debug!(
+            "[Stream] id={}, serving remote file with range support",
+            query.id
+        );
+        handle_source_range_request(&usecase, &stream_info, range_header.unwrap()).await
     } else {
         // 原始文件 + Range 请求：直接从文件系统读取以支持 seek
         log::debug!("[Stream] id={}, serving raw file with range support", query.id);
@@ -347,9 +476,7 @@ pub async fn stream(
             Ok(f) => f,
             Err(e) => {
                 log::error!("[Stream] Failed to open file {}: {}", stream_info.path, e);
-                return StreamResponse::Error(
-                    SubsonicError::error_generic().wrap(format!("Failed to open file: {}", e)),
-                );
+                return storage_unavailable(format!("Failed to open file: {}", e));
             }
         };
 
@@ -372,6 +499,118 @@ pub async fn stream(
     }
 }
 
+/// download - 下载原始文件，不转码也不受比特率上限限制，需要下载权限
+pub async fn download(
+    state: web::Data<AppState>,
+    query: SubsonicQuery<StreamQuery>,
+    req: HttpRequest,
+) -> StreamResponse {
+    let allowed = req
+        .extensions()
+        .get::<User>()
+        .is_some_and(|user| user.is_admin || user.roles.download);
+    if !allowed {
+        return StreamResponse::Error(SubsonicError::error_authorization_fail());
+    }
+
+    let usecase = StreamMedia::new(Arc::new(AudioFileDaoImpl::new(state.db.clone())))
+        .with_storage(Arc::new(crate::storage_client_factory(&state)));
+    let request = StreamRequest {
+        id: query.id,
+        max_bit_rate: None,
+        format: Some("raw".to_string()),
+        time_offset: None,
+        estimate_content_length: false,
+        bit_rate_cap: None,
+    };
+    let stream_info = match usecase.get_stream_info(&request).await {
+        Ok(info) => info,
+        Err(e) => {
+            log::warn!("[Download] Song {} not found: {}", query.id, e);
+            return StreamResponse::Error(
+                SubsonicError::error_data_not_found().wrap(format!("Song not found: {}", query.id)),
+            );
+        }
+    };
+    let file_name = std::path::Path::new(&stream_info.path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("{}.{}", query.id, stream_info.suffix));
+    let disposition = header::ContentDisposition {
+        disposition: header::DispositionType::Attachment,
+        parameters: vec![header::DispositionParam::Filename(file_name)],
+    };
+
+    let range_header = req.headers().get(header::RANGE);
+    let Some(range_str) = range_header.and_then(|v| v.to_str().ok()) else {
+        return match usecase.get_stream_data(&request, &stream_info).await {
+            Ok(stream_data) => StreamResponse::Binary(
+                HttpResponse::Ok()
+                    .insert_header((header::CONTENT_TYPE, stream_data.content_type))
+                    .insert_header((header::CONTENT_LENGTH, stream_data.size))
+                    .insert_header((header::ACCEPT_RANGES, "bytes"))
+                    .insert_header(disposition)
+                    .body(stream_data.data),
+            ),
+            Err(e) => {
+                log::error!("[Download] Failed to read {}: {}", stream_info.path, e);
+                StreamResponse::Error(
+                    SubsonicError::error_generic().wrap(format!("Failed to download: {}", e)),
+                )
+            }
+        };
+    };
+
+    if !stream_info.is_local() {
+        return match handle_source_range_request(&usecase, &stream_info, range_str).await {
+            StreamResponse::Binary(mut response) => {
+                if let Ok(value) = disposition.try_into_value() {
+                    response
+                        .headers_mut()
+                        .insert(header::CONTENT_DISPOSITION, value);
+                }
+                StreamResponse::Binary(response)
+            }
+            error => error,
+        };
+    }
+
+    let file = match File::open(&stream_info.path).await {
+        Ok(f) => f,
+        Err(e) => {
+            log::error!("[Download] Failed to open file {}: {}", stream_info.path, e);
+            return StreamResponse::Error(
+                SubsonicError::error_generic().wrap(format!("Failed to open file: {}", e)),
+            );
+        }
+    };
+    let file_size = match file.metadata().await {
+        Ok(m) => m.len(),
+        Err(e) => {
+            return StreamResponse::Error(
+                SubsonicError::error_generic().wrap(format!("Failed to get file metadata: {}", e)),
+            );
+        }
+    };
+    let mut response =
+        handle_range_request(file, file_size, range_str, &stream_info.content_type).await;
+    if let Ok(value) = disposition.try_into_value() {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    StreamResponse::Binary(response)
+}
+
+/// 原始文件读取失败通常是存储暂时无法访问（如 SMB 共享离线），返回 503 让客户端稍后重试
+fn storage_unavailable(message: String) -> StreamResponse {
+    StreamResponse::Binary(
+        HttpResponse::ServiceUnavailable()
+            .insert_header((header::RETRY_AFTER, STORAGE_RETRY_AFTER_SECS))
+            .body(message),
+    )
+}
+
 /// Stream 响应类型
 pub enum StreamResponse {
     Binary(HttpResponse),
@@ -383,7 +622,7 @@ impl Responder for StreamResponse {
 
     fn respond_to(self, req: &actix_web::HttpRequest) -> HttpResponse<Self::Body> {
         match self {
-            StreamResponse::Binary(response) => response,
+            StreamResponse::Binary(response) => count_bandwidth(req, response),
             StreamResponse::Error(error) => {
                 let subsonic: Subsonic = error.into();
                 subsonic.respond_to(req)
@@ -392,6 +631,69 @@ impl Responder for StreamResponse {
     }
 }
 
+/// 成功的响应按实际发出的字节数记入用户当天的流量，客户端中途断开时只计已发出的部分
+fn count_bandwidth(req: &HttpRequest, response: HttpResponse) -> HttpResponse {
+    let user_id = req.extensions().get::<User>().map(|user| user.id.as_i64());
+    match (user_id, req.app_data::<web::Data<AppState>>()) {
+        (Some(user_id), Some(state)) if response.status().is_success() => {
+            let store = Arc::new(BandwidthUsageStoreImpl::new(state.db.clone()));
+            response.map_body(|_, body| {
+                BoxBody::new(CountedBody {
+                    inner: body,
+                    sent: 0,
+                    user_id,
+                    store,
+                })
+            })
+        }
+        _ => response,
+    }
+}
+
+/// 统计发出字节数的响应体，响应结束（或连接断开）时写入 user_bandwidth
+struct CountedBody {
+    inner: BoxBody,
+    sent: u64,
+    user_id: i64,
+    store: Arc<dyn BandwidthUsageStore>,
+}
+
+impl MessageBody for CountedBody {
+    type Error = Box<dyn std::error::Error>;
+
+    fn size(&self) -> BodySize {
+        self.inner.size()
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.get_mut();
+        let next = Pin::new(&mut this.inner).poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &next {
+            this.sent += chunk.len() as u64;
+        }
+        next
+    }
+}
+
+impl Drop for CountedBody {
+    fn drop(&mut self) {
+        if self.sent == 0 {
+            return;
+        }
+        let store = self.store.clone();
+        let (user_id, bytes) = (self.user_id, self.sent as i64);
+        tokio::spawn(async move {
+            let day = chrono::Local::now().date_naive();
+            if let Err(e) = store.record(user_id, day, bytes).await {
+                log::warn!("[Stream] Failed to record bandwidth: {}", e);
+            }
+        });
+    }
+}
+
 /// 从内存数据处理 Range 请求
 fn handle_range_request_from_bytes(data: &[u8], range_str: &str, content_type: &str) -> HttpResponse {
     let file_size = data.len() as u64;
@@ -462,6 +764,56 @@ async fn handle_range_request(
         .body(buffer)
 }
 
+/// 通过存储客户端读取远程文件的一个范围，文件大小使用索引时记录的大小
+async fn handle_source_range_request(
+    usecase: &StreamMedia,
+    info: &StreamInfo,
+    range_str: &str,
+) -> StreamResponse {
+    let file_size = info.size.max(0) as u64;
+    let Some((start, end)) = parse_range(range_str, file_size) else {
+        return StreamResponse::Binary(
+            HttpResponse::RangeNotSatisfiable()
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", file_size)))
+                .finish(),
+        );
+    };
+    let length = end - start + 1;
+
+    let reader = match usecase.open_source(info, start).await {
+        Ok(reader) => reader,
+        Err(e) => {
+            log::error!("[Stream] Failed to open {}: {}", info.path, e);
+            return storage_unavailable(format!("Failed to open file: {}", e));
+        }
+    };
+    let mut buffer = Vec::with_capacity(length as usize);
+    if let Err(e) = reader.take(length).read_to_end(&mut buffer).await {
+        log::error!("[Stream] Failed to read {}: {}", info.path, e);
+        return storage_unavailable(format!("Failed to read file: {}", e));
+    }
+    if buffer.len() as u64 != length {
+        log::error!(
+            "[Stream] {} is shorter than indexed size {}",
+            info.path,
+            file_size
+        );
+        return storage_unavailable("File changed on storage".to_string());
+    }
+
+    StreamResponse::Binary(
+        HttpResponse::PartialContent()
+            .insert_header((header::CONTENT_TYPE, info.content_type.clone()))
+            .insert_header((header::CONTENT_LENGTH, length))
+            .insert_header((
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, file_size),
+            ))
+            .insert_header((header::ACCEPT_RANGES, "bytes"))
+            .body(buffer),
+    )
+}
+
 /// 解析 Range 请求头
 fn parse_range(range_str: &str, file_size: u64) -> Option<(u64, u64)> {
     let range_str = range_str.strip_prefix("bytes=")?;
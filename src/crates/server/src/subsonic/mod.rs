@@ -1,6 +1,7 @@
 pub mod bookmarks;
 pub mod browsing;
 pub mod helper;
+pub mod icy;
 pub mod media_annotation;
 pub mod media_retrieval;
 pub mod playlists;
@@ -11,22 +12,28 @@ pub mod song_album_list;
 pub mod system;
 pub mod users;
 use crate::consts;
-use crate::middleware::other;
+use crate::middleware::{other, rate_limit};
 use actix_web::{middleware::from_fn, web};
 
 pub fn configure_service(svc: &mut web::ServiceConfig) {
     // 中间件执行顺序：从下到上包装，从上到下执行
     // 1. check_required_parameters - 验证必需参数 (u, v, c)
     // 2. subsonic_authenticator - 用户认证
+    // 3. client_unique_id - 读取客户端唯一 ID（按设备保存播放队列等）
+    // 4. subsonic_error_format - 按 f 参数输出 XML / JSONP 格式的错误
+    // rate_limit 在最外层，锁定期间的请求不会进入认证，认证失败的 401 也能被它统计
     svc.service(
         web::scope(consts::URL_PATH_SUBSONIC_API)
             .configure(configure_routes)
+            .wrap(from_fn(other::subsonic_error_format))
+            .wrap(from_fn(other::client_unique_id))
             .wrap(from_fn(move |req, next| {
                 other::subsonic_authenticator(req, next)
             }))
             .wrap(from_fn(move |req, next| {
                 other::check_required_parameters(req, next)
-            })),
+            }))
+            .wrap(from_fn(rate_limit::rate_limit)),
     );
 }
 
@@ -75,8 +82,13 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
     register_get_post("deletePlaylist", playlists::delete_playlist, cfg);
 
     // Bookmarks
+    register_get_post("createBookmark", bookmarks::create_bookmark, cfg);
+    register_get("getBookmarks", bookmarks::get_bookmarks, cfg);
+    register_get_post("deleteBookmark", bookmarks::delete_bookmark, cfg);
     register_get_post("savePlayQueue", bookmarks::save_play_queue, cfg);
     register_get("getPlayQueue", bookmarks::get_play_queue, cfg);
+    register_get_post("savePlayQueueByIndex", bookmarks::save_play_queue_by_index, cfg);
+    register_get("getPlayQueueByIndex", bookmarks::get_play_queue_by_index, cfg);
 
     // User Management
     register_get_post("createUser", users::create_user, cfg);
@@ -92,6 +104,7 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
     // Media Retrieval
     register_get("getCoverArt", media_retrieval::get_cover_art, cfg);
     register_get("stream", media_retrieval::stream, cfg);
+    register_get("download", media_retrieval::download, cfg);
 
     // Scanning (OpenSubsonic standard - no library id parameter)
     register_get_post("startScan", scan::start_library_scan, cfg);
@@ -1,5 +1,5 @@
-use crate::subsonic::helper::QsQuery;
-use crate::subsonic::response::directory::Child;
+use crate::subsonic::helper::{current_user_id, user_notes, QsQuery};
+use crate::subsonic::response::directory::{Child, OpenSubsonicChild};
 use crate::subsonic::response::error::SubsonicError;
 use crate::subsonic::response::play::{
     Playlist as PlaylistResponse, PlaylistWithSongs, Playlists,
@@ -7,9 +7,12 @@ use crate::subsonic::response::play::{
 use crate::subsonic::response::Subsonic;
 use crate::AppState;
 use actix_web::{web, HttpMessage, HttpRequest};
-use application::command::playlist::{CreatePlaylistCmd, PlaylistAppService, UpdatePlaylistCmd};
-use application::query::get_playlist::GetPlaylist;
-use infra::repository::postgres::command::playlist::PlaylistRepositoryImpl;
+use application::command::playlist::{CreatePlaylistCmd, UpdatePlaylistCmd};
+use application::error::AppError;
+use application::event::push::ServerEvent;
+use application::query::dto::cover_art::playlist_cover_art_id;
+use application::query::get_playlist::{GetPlaylist, PlaylistOrder};
+use domain::annotation::Kind;
 use infra::repository::postgres::query::playlist::PlaylistDaoImpl;
 use model::playlist::{Playlist, PlaylistAudioFile, PlaylistSummary};
 use serde::Deserialize;
@@ -56,12 +59,11 @@ pub async fn create_playlist(
         .clone();
 
     // 创建 Command 服务
-    let playlist_repo: Arc<dyn domain::playlist::PlaylistRepository> =
-        Arc::new(PlaylistRepositoryImpl::new(state.db.clone()));
-    let playlist_app_service = PlaylistAppService::new(playlist_repo, state.id_generator.clone());
+    let playlist_app_service = crate::playlist_app_service(&state);
 
     // 创建 Query 服务
-    let playlist_dao = Arc::new(PlaylistDaoImpl::new(state.db.clone()));
+    let playlist_dao =
+        Arc::new(PlaylistDaoImpl::new(state.db.clone()).with_user(Some(user.id.as_i64())));
     let get_playlist = GetPlaylist::new(playlist_dao);
 
     // 解析参数
@@ -86,13 +88,14 @@ pub async fn create_playlist(
             song_ids,
         })
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(playlist_error)?;
+    publish_playlist_updated(&state, &req, playlist.id.as_i64(), false);
 
     // 通过 Query 服务获取完整的播放列表信息
     let playlist_detail = get_playlist
         .get_by_id(playlist.id.as_i64())
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
 
     // 构建响应
     let response = PlaylistWithSongs {
@@ -106,8 +109,9 @@ pub async fn create_playlist(
             duration: playlist_detail.duration,
             created: format_timestamp(playlist_detail.created_at),
             changed: format_timestamp(playlist_detail.updated_at),
-            cover_art: None,
-            allowed_user: None,
+            cover_art: Some(playlist_cover_art_id(playlist_detail.id)),
+            allowed_user: allowed_users(playlist_detail.collaborators),
+            tags: playlist_detail.tags,
         },
         entry: None, // 暂不返回歌曲详情，可根据需要添加
     };
@@ -115,6 +119,33 @@ pub async fn create_playlist(
     Ok(response.into())
 }
 
+/// 权限不足返回 50（未授权），而不是认证失败
+fn playlist_error(e: AppError) -> SubsonicError {
+    match e {
+        AppError::AuthError(message) => SubsonicError::error_authorization_fail().wrap(message),
+        e => SubsonicError::from(e),
+    }
+}
+
+fn allowed_users(collaborators: Vec<String>) -> Option<Vec<String>> {
+    if collaborators.is_empty() {
+        None
+    } else {
+        Some(collaborators)
+    }
+}
+
+/// 通知当前用户的其他客户端播放列表已变更
+fn publish_playlist_updated(state: &AppState, req: &HttpRequest, playlist_id: i64, deleted: bool) {
+    if let Some(user) = req.extensions().get::<domain::user::User>() {
+        state.push_hub.publish(ServerEvent::PlaylistUpdated {
+            playlist_id: playlist_id.into(),
+            user_id: user.id.clone(),
+            deleted,
+        });
+    }
+}
+
 /// 格式化时间戳为 ISO 8601 格式
 fn format_timestamp(timestamp: i64) -> String {
     use chrono::{TimeZone, Utc};
@@ -131,6 +162,12 @@ pub struct GetPlaylistsQuery {
     /// 用户名（需要 admin 权限）
     #[serde(default)]
     pub username: Option<String>,
+    /// 只返回带该标签的播放列表（扩展参数）
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// changed（默认，最近修改的在前）或 name（扩展参数）
+    #[serde(default)]
+    pub sort: Option<String>,
 }
 
 /// getPlaylists - 获取用户的所有播放列表
@@ -159,9 +196,8 @@ pub async fn get_playlists(
             );
         }
         // TODO: 根据 username 查找用户 ID，暂时返回错误
-        return Err(
-            SubsonicError::error_generic().wrap("Username lookup not implemented".to_string())
-        );
+        return Err(SubsonicError::error_server_too_old()
+            .wrap("Username lookup not implemented".to_string()));
     } else {
         user.id.as_i64()
     };
@@ -170,15 +206,35 @@ pub async fn get_playlists(
     let playlist_dao = Arc::new(PlaylistDaoImpl::new(state.db.clone()));
     let get_playlist = GetPlaylist::new(playlist_dao);
 
-    // 获取播放列表
+    let order = match query.sort.as_deref() {
+        None | Some("") | Some("changed") => PlaylistOrder::Changed,
+        Some("name") => PlaylistOrder::Name,
+        Some(sort) => {
+            return Err(SubsonicError::error_generic().wrap(format!("Invalid sort: {}", sort)))
+        }
+    };
+    let tag = query.tag.as_deref().filter(|tag| !tag.trim().is_empty());
+
+    // 获取自己的以及作为协作者的播放列表
     let playlists = get_playlist
-        .get_by_owner_id(target_owner_id)
+        .list_editable(target_owner_id, tag, order)
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
+
+    // 播放列表没有描述时，comment 使用当前用户的备注
+    let ids: Vec<i64> = playlists.iter().map(|p| p.id).collect();
+    let mut notes = user_notes(&state, &req, Kind::Playlist, &ids).await;
 
     // 转换为响应格式
-    let playlist_responses: Vec<PlaylistResponse> =
-        playlists.into_iter().map(playlist_to_response).collect();
+    let playlist_responses: Vec<PlaylistResponse> = playlists
+        .into_iter()
+        .map(|p| {
+            let note = notes.remove(&p.id);
+            let mut response = playlist_to_response(p);
+            response.comment = playlist_comment(response.comment, note);
+            response
+        })
+        .collect();
 
     let response = Playlists {
         playlist: if playlist_responses.is_empty() {
@@ -191,6 +247,11 @@ pub async fn get_playlists(
     Ok(response.into())
 }
 
+/// 播放列表自身的描述优先，为空时使用备注
+fn playlist_comment(comment: Option<String>, note: Option<String>) -> Option<String> {
+    comment.filter(|c| !c.is_empty()).or(note)
+}
+
 /// 将 PlaylistSummary 转换为 API 响应
 fn playlist_to_response(p: PlaylistSummary) -> PlaylistResponse {
     PlaylistResponse {
@@ -203,8 +264,9 @@ fn playlist_to_response(p: PlaylistSummary) -> PlaylistResponse {
         duration: p.duration,
         created: format_timestamp(p.created_at),
         changed: format_timestamp(p.updated_at),
-        cover_art: None,
+        cover_art: Some(playlist_cover_art_id(p.id)),
         allowed_user: None,
+        tags: p.tags,
     }
 }
 
@@ -222,7 +284,7 @@ pub struct GetPlaylistQuery {
 /// - 返回播放列表中的文件列表
 pub async fn get_playlist(
     state: web::Data<AppState>,
-    _req: HttpRequest,
+    req: HttpRequest,
     query: web::Query<GetPlaylistQuery>,
 ) -> Result<Subsonic, SubsonicError> {
     // 解析播放列表 ID
@@ -232,17 +294,22 @@ pub async fn get_playlist(
         .map_err(|_| SubsonicError::error_generic().wrap("Invalid playlist ID".to_string()))?;
 
     // 创建 Query 服务
-    let playlist_dao = Arc::new(PlaylistDaoImpl::new(state.db.clone()));
+    let playlist_dao =
+        Arc::new(PlaylistDaoImpl::new(state.db.clone()).with_user(current_user_id(&req)));
     let get_playlist_svc = GetPlaylist::new(playlist_dao);
 
     // 获取播放列表详情
     let playlist = get_playlist_svc
         .get_by_id(playlist_id)
         .await
-        .map_err(|e| SubsonicError::error_data_not_found().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
+
+    let mut notes = user_notes(&state, &req, Kind::Playlist, &[playlist.id]).await;
+    let note = notes.remove(&playlist.id);
 
     // 转换为响应格式
-    let response = playlist_detail_to_response(playlist);
+    let mut response = playlist_detail_to_response(playlist);
+    response.playlist.comment = playlist_comment(response.playlist.comment, note);
 
     Ok(response.into())
 }
@@ -266,8 +333,9 @@ fn playlist_detail_to_response(p: Playlist) -> PlaylistWithSongs {
             duration: p.duration,
             created: format_timestamp(p.created_at),
             changed: format_timestamp(p.updated_at),
-            cover_art: None,
-            allowed_user: None,
+            cover_art: Some(playlist_cover_art_id(p.id)),
+            allowed_user: allowed_users(p.collaborators),
+            tags: p.tags,
         },
         entry: if entries.is_empty() {
             None
@@ -293,6 +361,8 @@ fn audio_file_to_child(af: &PlaylistAudioFile) -> Child {
         year: af.year,
         genre: af.genre.clone(),
         cover_art: af.cover_art_id.map(|_| audio_file_cover_art_id(af.id)),
+        cover_art_blurhash: None,
+        cover_art_color: None,
         size: af.size,
         content_type: af.content_type.clone(),
         suffix: af.suffix.clone(),
@@ -311,6 +381,7 @@ fn audio_file_to_child(af: &PlaylistAudioFile) -> Child {
         user_rating: af.rating,
         song_count: 0,
         is_video: false,
+        os_child: OpenSubsonicChild::song(),
     }
 }
 
@@ -328,7 +399,7 @@ pub struct DeletePlaylistQuery {
 /// - 删除指定的播放列表
 pub async fn delete_playlist(
     state: web::Data<AppState>,
-    _req: HttpRequest,
+    req: HttpRequest,
     query: web::Query<DeletePlaylistQuery>,
 ) -> Result<Subsonic, SubsonicError> {
     // 解析播放列表 ID
@@ -337,16 +408,21 @@ pub async fn delete_playlist(
         .parse()
         .map_err(|_| SubsonicError::error_generic().wrap("Invalid playlist ID".to_string()))?;
 
+    let user_id = req
+        .extensions()
+        .get::<domain::user::User>()
+        .map(|user| user.id.as_i64())
+        .ok_or_else(|| SubsonicError::error_generic().wrap("User not found".to_string()))?;
+
     // 创建 Command 服务
-    let playlist_repo: Arc<dyn domain::playlist::PlaylistRepository> =
-        Arc::new(PlaylistRepositoryImpl::new(state.db.clone()));
-    let playlist_app_service = PlaylistAppService::new(playlist_repo, state.id_generator.clone());
+    let playlist_app_service = crate::playlist_app_service(&state);
 
     // 删除播放列表
     playlist_app_service
-        .delete_playlist(playlist_id)
+        .delete_playlist(playlist_id, user_id)
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(playlist_error)?;
+    publish_playlist_updated(&state, &req, playlist_id, true);
 
     Ok(Subsonic::default())
 }
@@ -383,9 +459,10 @@ pub struct UpdatePlaylistQuery {
 ///
 /// 根据 OpenSubsonic 规范 (Since 1.8.0):
 /// - 只有播放列表的所有者可以更新它
+/// - 公开播放列表的协作者可以增删歌曲，但不能修改名称、备注和公开状态
 pub async fn update_playlist(
     state: web::Data<AppState>,
-    _req: HttpRequest,
+    req: HttpRequest,
     query: QsQuery<UpdatePlaylistQuery>,
 ) -> Result<Subsonic, SubsonicError> {
     // 解析播放列表 ID
@@ -401,15 +478,20 @@ pub async fn update_playlist(
         .filter_map(|id| id.parse::<i64>().ok())
         .collect();
 
+    let user_id = req
+        .extensions()
+        .get::<domain::user::User>()
+        .map(|user| user.id.as_i64())
+        .ok_or_else(|| SubsonicError::error_generic().wrap("User not found".to_string()))?;
+
     // 创建 Command 服务
-    let playlist_repo: Arc<dyn domain::playlist::PlaylistRepository> =
-        Arc::new(PlaylistRepositoryImpl::new(state.db.clone()));
-    let playlist_app_service = PlaylistAppService::new(playlist_repo, state.id_generator.clone());
+    let playlist_app_service = crate::playlist_app_service(&state);
 
     // 更新播放列表
     playlist_app_service
         .update_playlist(UpdatePlaylistCmd {
             playlist_id,
+            user_id,
             name: query.name.clone(),
             comment: query.comment.clone(),
             public: query.public,
@@ -417,7 +499,8 @@ pub async fn update_playlist(
             song_indexes_to_remove: query.song_index_to_remove.clone(),
         })
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(playlist_error)?;
+    publish_playlist_updated(&state, &req, playlist_id, false);
 
     Ok(Subsonic::default())
 }
@@ -1,5 +1,5 @@
-use crate::subsonic::response::artist::ArtistID3Ref;
-use crate::subsonic::response::directory::Child;
+use crate::subsonic::response::artist::{ArtistID3Ref, ItemContributor};
+use crate::subsonic::response::directory::{explicit_status, Child};
 use crate::subsonic::response::genre::ItemGenre;
 use application::query::dto::cover_art::album_cover_art_id;
 use chrono::NaiveDateTime;
@@ -9,8 +9,32 @@ use serde::Serialize;
 #[serde(rename_all = "camelCase")]
 
 pub struct DiscTitle {
+    disc: i32,
     title: String,
-    disc_number: i32,
+}
+
+/// OpenSubsonic ItemDate，只知道年份时省略月和日
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ItemDate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    month: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day: Option<u32>,
+}
+
+impl ItemDate {
+    /// 解析 YYYY[-MM[-DD]]，日期缺失时退回到年份
+    fn new(date: Option<&str>, year: Option<i32>) -> Option<Self> {
+        let mut parts = date.unwrap_or_default().split('-');
+        let date_year = parts.next().and_then(|y| y.parse::<i32>().ok());
+        date_year.or(year).filter(|y| *y > 0).map(|year| Self {
+            year: Some(year),
+            month: parts.next().and_then(|m| m.parse().ok()),
+            day: parts.next().and_then(|d| d.parse().ok()),
+        })
+    }
 }
 
 #[derive(Serialize, Debug)]
@@ -21,12 +45,32 @@ pub struct OpenSubsonicAlbumID3 {
     genres: Vec<ItemGenre>,
     is_compilation: bool,
     pub sort_name: String,
-    disk_titles: Vec<DiscTitle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_release_date: Option<ItemDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release_date: Option<ItemDate>,
+    disc_titles: Vec<DiscTitle>,
     artists: Vec<ArtistID3Ref>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    contributors: Vec<ItemContributor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explicit_status: Option<String>,
 }
 
 impl OpenSubsonicAlbumID3 {
     pub fn new(album: model::album::Album) -> Self {
+        let mut disc_titles: Vec<DiscTitle> = album
+            .discs
+            .iter()
+            .map(|(disc_number, title)| DiscTitle {
+                disc: *disc_number,
+                title: title.clone(),
+            })
+            .collect();
+        disc_titles.sort_by_key(|d| d.disc);
+
         Self {
             played: album.annotation.play_date,
             user_rating: album.annotation.rating,
@@ -37,17 +81,21 @@ impl OpenSubsonicAlbumID3 {
                 .collect(),
             is_compilation: album.compilation,
             sort_name: album.sort_name,
-            disk_titles: album
-                .discs
-                .iter()
-                .map(|(disc_number, title)| DiscTitle {
-                    title: title.clone(),
-                    disc_number: *disc_number,
-                })
-                .collect(),
+            version: album.edition,
+            original_release_date: ItemDate::new(
+                album.original_date.as_deref(),
+                album.original_year,
+            ),
+            release_date: ItemDate::new(album.release_date.as_deref(), album.year),
+            disc_titles,
+            explicit_status: explicit_status(album.explicit),
+            contributors: ItemContributor::from_contributors(&album.contributors),
             artists: album
                 .contributors
                 .into_iter()
+                .filter(|contributor| {
+                    contributor.role == "Artist" || contributor.role == "AlbumArtist"
+                })
                 .map(|contributor| ArtistID3Ref {
                     id: contributor.artist_id.to_string(),
                     name: contributor.artist_name,
@@ -72,6 +120,13 @@ pub struct AlbumID3 {
     #[serde(skip_serializing_if = "String::is_empty")]
     pub cover_art: String,
 
+    /// 封面加载前的占位（扩展字段）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_art_blurhash: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_art_color: Option<String>,
+
     pub song_count: i32,
 
     pub duration: i64,
@@ -88,18 +143,25 @@ pub struct AlbumID3 {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub genre: Option<String>,
 
+    /// 当前用户的备注（扩展字段）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
     #[serde(flatten)]
     pub os_album_id3: OpenSubsonicAlbumID3,
 }
 
 impl AlbumID3 {
     pub fn new(album: model::album::Album) -> Self {
+        let placeholder = album.cover_placeholder.clone();
         Self {
             id: album.id.to_string(),
             name: album.name.clone(),
             artist: album.artist.name.clone(),
             artist_id: album.artist.id.to_string(),
             cover_art: album_cover_art_id(album.id),
+            cover_art_blurhash: placeholder.as_ref().map(|p| p.blurhash.clone()),
+            cover_art_color: placeholder.and_then(|p| p.dominant_color),
             song_count: album.song_count,
             duration: album.duration,
             play_count: album.annotation.play_count,
@@ -111,6 +173,7 @@ impl AlbumID3 {
                 .as_ref()
                 .map(|genre| genre.name.clone())
                 .or(None),
+            comment: None,
             os_album_id3: OpenSubsonicAlbumID3::new(album),
         }
     }
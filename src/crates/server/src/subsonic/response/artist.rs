@@ -194,6 +194,34 @@ pub struct ArtistID3Ref {
     pub name: String,
 }
 
+/// OpenSubsonic Contributor：作曲、指挥等艺术家以外的参与者，role 为小写角色名
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemContributor {
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_role: Option<String>,
+    pub artist: ArtistID3Ref,
+}
+
+impl ItemContributor {
+    /// 艺术家本身在 artists 中返回，不重复列为 contributor
+    pub fn from_contributors(contributors: &[model::shared::Contributor]) -> Vec<Self> {
+        contributors
+            .iter()
+            .filter(|c| c.role != "Artist" && c.role != "AlbumArtist")
+            .map(|c| Self {
+                role: c.role.to_lowercase(),
+                sub_role: c.sub_role.clone(),
+                artist: ArtistID3Ref {
+                    id: c.artist_id.to_string(),
+                    name: c.artist_name.clone(),
+                },
+            })
+            .collect()
+    }
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ArtistsID3 {
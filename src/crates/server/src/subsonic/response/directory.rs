@@ -1,4 +1,4 @@
-use super::artist::ArtistID3Ref;
+use super::artist::{ArtistID3Ref, ItemContributor};
 use super::genre::ItemGenre;
 use application::query::dto::cover_art::{album_cover_art_id, audio_file_cover_art_id};
 use chrono::NaiveDateTime;
@@ -7,6 +7,59 @@ use serde::Serialize;
 impl From<model::audio_file::AudioFile> for Child {
     fn from(audio_file: model::audio_file::AudioFile) -> Self {
         let cover_art = audio_file_cover_art_id(audio_file.id);
+        let placeholder = audio_file.cover_placeholder.clone();
+        let os_child = OpenSubsonicChild {
+            played: audio_file.annotation.play_date,
+            bpm: Some(audio_file.bpm).filter(|bpm| *bpm > 0),
+            comment: audio_file.comment.clone(),
+            sort_name: Some(audio_file.sort_name.clone()),
+            media_type: Some("song".to_string()),
+            music_brainz_id: audio_file.mbz_track_id.clone(),
+            genres: audio_file
+                .genres
+                .iter()
+                .map(|genre| ItemGenre {
+                    name: genre.name.clone(),
+                })
+                .collect(),
+            channel_count: Some(audio_file.channels).filter(|c| *c > 0),
+            sampling_rate: Some(audio_file.sample_rate).filter(|r| *r > 0),
+            artists: audio_file
+                .contributors
+                .iter()
+                .filter(|contributor| contributor.role == "Artist")
+                .map(|contributor| ArtistID3Ref {
+                    id: contributor.artist_id.to_string(),
+                    name: contributor.artist_name.clone(),
+                })
+                .collect(),
+            contributors: ItemContributor::from_contributors(&audio_file.contributors),
+            display_composer: Some(
+                audio_file
+                    .contributors
+                    .iter()
+                    .filter(|contributor| contributor.role == "Composer")
+                    .map(|contributor| contributor.artist_name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+            .filter(|composer| !composer.is_empty()),
+            replay_gain: ReplayGain::new(audio_file.replay_gain.clone()),
+            chapters: audio_file
+                .chapters
+                .iter()
+                .map(|chapter| ItemChapter {
+                    title: chapter.title.clone(),
+                    start: chapter.start,
+                    end: chapter.end,
+                })
+                .collect(),
+            explicit_status: explicit_status(audio_file.explicit),
+            audible_range: audio_file.audible_range.map(|range| ItemAudibleRange {
+                start: range.start,
+                end: range.end,
+            }),
+        };
 
         Self {
             id: audio_file.id.to_string(),
@@ -24,6 +77,8 @@ impl From<model::audio_file::AudioFile> for Child {
             year: audio_file.year,
             genre: audio_file.genre.as_ref().map(|g| g.name.clone()),
             cover_art: Some(cover_art),
+            cover_art_blurhash: placeholder.as_ref().map(|p| p.blurhash.clone()),
+            cover_art_color: placeholder.and_then(|p| p.dominant_color),
             size: Some(audio_file.size),
             content_type: Some(format!("audio/{}", audio_file.suffix)),
             suffix: Some(audio_file.suffix.clone()),
@@ -42,12 +97,27 @@ impl From<model::audio_file::AudioFile> for Child {
             user_rating: Some(audio_file.annotation.rating),
             song_count: audio_file.song_count,
             is_video: false,
+            os_child,
         }
     }
 }
 
 impl From<model::album::Album> for Child {
     fn from(album: model::album::Album) -> Self {
+        let os_child = OpenSubsonicChild {
+            played: album.annotation.play_date,
+            sort_name: Some(album.sort_name.clone()),
+            media_type: Some("album".to_string()),
+            genres: album
+                .genres
+                .iter()
+                .map(|genre| ItemGenre {
+                    name: genre.name.clone(),
+                })
+                .collect(),
+            explicit_status: explicit_status(album.explicit),
+            ..Default::default()
+        };
         Self {
             id: format!("al-{}", album.id),
             parent: None,
@@ -60,6 +130,11 @@ impl From<model::album::Album> for Child {
             year: album.year,
             genre: album.genre.as_ref().map(|g| g.name.clone()),
             cover_art: Some(album_cover_art_id(album.id)),
+            cover_art_blurhash: album.cover_placeholder.as_ref().map(|p| p.blurhash.clone()),
+            cover_art_color: album
+                .cover_placeholder
+                .as_ref()
+                .and_then(|p| p.dominant_color.clone()),
             size: Some(album.size),
             content_type: None,
             suffix: None,
@@ -78,6 +153,7 @@ impl From<model::album::Album> for Child {
             user_rating: Some(album.annotation.rating),
             song_count: album.song_count,
             is_video: false,
+            os_child,
         }
     }
 }
@@ -107,22 +183,121 @@ pub struct Directory {
     pub child: Option<Vec<Child>>,
 }
 
-#[derive(Serialize, Debug)]
+/// OpenSubsonic 扩展的歌曲字段，读模型中没有的值省略
+#[derive(Serialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenSubsonicChild {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub played: Option<NaiveDateTime>,
-    pub bpm: i32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<i32>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
-    pub sort_name: String,
-    pub media_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub music_brainz_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub genres: Vec<ItemGenre>,
 
-    pub channel_count: i32,
-    pub sampling_rate: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_count: Option<i32>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling_rate: Option<i32>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub artists: Vec<ArtistID3Ref>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<ItemContributor>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_composer: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_gain: Option<ReplayGain>,
+
+    /// 章节（扩展字段）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<ItemChapter>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explicit_status: Option<String>,
+
+    /// 去掉首尾静音后的范围（扩展字段），用于交叉淡入淡出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audible_range: Option<ItemAudibleRange>,
+}
+
+/// 标签只区分是否 explicit，无法区分 clean 和未分级，因此只输出 "explicit"
+pub fn explicit_status(explicit: bool) -> Option<String> {
+    explicit.then(|| "explicit".to_string())
+}
+
+impl OpenSubsonicChild {
+    /// 播放列表、书签等只有部分字段的歌曲
+    pub fn song() -> Self {
+        Self {
+            media_type: Some("song".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// 位置单位为毫秒，与书签的 position 一致
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemChapter {
+    pub title: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// 毫秒，与章节相同
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemAudibleRange {
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayGain {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_gain: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album_gain: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_peak: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album_peak: Option<f64>,
+}
+
+impl ReplayGain {
+    fn new(value: domain::value::ReplayGain) -> Option<Self> {
+        if value == domain::value::ReplayGain::default() {
+            return None;
+        }
+        Some(Self {
+            track_gain: value.track_gain,
+            album_gain: value.album_gain,
+            track_peak: value.track_peak,
+            album_peak: value.album_peak,
+        })
+    }
 }
 
 #[derive(Serialize, Debug)]
@@ -157,6 +332,13 @@ pub struct Child {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cover_art: Option<String>,
 
+    /// 封面加载前的占位（扩展字段）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_art_blurhash: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_art_color: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub size: Option<i64>,
 
@@ -205,4 +387,7 @@ pub struct Child {
     pub song_count: i32,
 
     pub is_video: bool,
+
+    #[serde(flatten)]
+    pub os_child: OpenSubsonicChild,
 }
@@ -1,9 +1,13 @@
 use actix_web::{HttpResponse, ResponseError};
 use application::error::AppError;
+use application::query::QueryError;
+use domain::annotation::AnnotationError;
+use domain::audio_file::AudioFileError;
+use domain::user::UserError;
 use serde::Serialize;
 use std::fmt;
 
-use super::{JsonWrapper, Subsonic};
+use super::{ResponseFormat, Subsonic};
 
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -40,6 +44,7 @@ macro_rules! rhythm_error {
     }
 }
 
+/// 应用层错误到 Subsonic 错误码的统一映射，处理器直接用 `?` 或 `SubsonicError::from` 转换
 impl From<AppError> for SubsonicError {
     fn from(err: AppError) -> Self {
         match err {
@@ -49,7 +54,34 @@ impl From<AppError> for SubsonicError {
             AppError::AuthError(message) => {
                 SubsonicError::error_authentication_fail().wrap(message)
             }
-            AppError::AggregateNotFound(_, _) => SubsonicError::error_data_not_found(),
+            AppError::AggregateNotFound(_, _)
+            | AppError::AudioFileError(AudioFileError::NotFound(_))
+            | AppError::AnnotationError(AnnotationError::NotFound(_))
+            | AppError::UserError(UserError::UserNotFound(_)) => {
+                SubsonicError::error_data_not_found().wrap(err.to_string())
+            }
+            AppError::UserError(
+                UserError::InvalidUserOrPassword(_)
+                | UserError::UserDeleted
+                | UserError::UserDisabled
+                | UserError::AuthError(_),
+            ) => SubsonicError::error_authentication_fail().wrap(err.to_string()),
+            AppError::NotSupported(message) => SubsonicError::error_server_too_old().wrap(message),
+            _ => SubsonicError::error_generic().wrap(err.to_string()),
+        }
+    }
+}
+
+impl From<QueryError> for SubsonicError {
+    fn from(err: QueryError) -> Self {
+        match err {
+            QueryError::NotFound(message) => SubsonicError::error_data_not_found().wrap(message),
+            QueryError::InvalidInput(message) | QueryError::InvalidParameter(message) => {
+                SubsonicError::error_missing_parameter().wrap(message)
+            }
+            QueryError::NotSupported(message) => {
+                SubsonicError::error_server_too_old().wrap(message)
+            }
             _ => SubsonicError::error_generic().wrap(err.to_string()),
         }
     }
@@ -74,11 +106,51 @@ impl fmt::Display for SubsonicError {
 
 impl ResponseError for SubsonicError {
     fn error_response(&self) -> HttpResponse {
-        // 将错误转换为 Subsonic 响应，并使用 JsonWrapper 包装
+        // 这里拿不到请求，先按 JSON 输出，XML / JSONP 由 subsonic_error_format 中间件重新生成
         let subsonic: Subsonic = SubsonicError::new(self.code, self.message.clone()).into();
-        let wrapper = JsonWrapper {
-            subsonic_response: subsonic,
-        };
-        HttpResponse::Ok().json(wrapper)
+        subsonic.into_response(ResponseFormat::Json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_errors_map_to_subsonic_codes() {
+        let code = |err: QueryError| SubsonicError::from(err).code;
+        assert_eq!(code(QueryError::InvalidInput("id".to_string())), 10);
+        assert_eq!(code(QueryError::InvalidParameter("size".to_string())), 10);
+        assert_eq!(code(QueryError::NotFound("album 1".to_string())), 70);
+        assert_eq!(code(QueryError::NotSupported("lyrics".to_string())), 30);
+        assert_eq!(code(QueryError::ExecutionError("timeout".to_string())), 0);
+        assert_eq!(code(QueryError::DbError("closed".to_string())), 0);
+    }
+
+    #[test]
+    fn app_errors_map_to_subsonic_codes() {
+        let code = |err: AppError| SubsonicError::from(err).code;
+        assert_eq!(code(AppError::InvalidInput("name".to_string())), 10);
+        assert_eq!(code(AppError::AuthError("bad token".to_string())), 40);
+        assert_eq!(
+            code(AppError::AggregateNotFound(
+                "playlist".to_string(),
+                "1".to_string()
+            )),
+            70
+        );
+        assert_eq!(code(UserError::UserNotFound("bob".to_string()).into()), 70);
+        assert_eq!(code(UserError::UserDisabled.into()), 40);
+        assert_eq!(code(AppError::NotSupported("jukebox".to_string())), 30);
+        assert_eq!(code(AppError::UnknownError("boom".to_string())), 0);
+    }
+
+    #[test]
+    fn mapped_errors_keep_the_original_message() {
+        let error = SubsonicError::from(QueryError::InvalidParameter("size".to_string()));
+        assert_eq!(
+            error.message,
+            "Required parameter is missing: size".to_string()
+        );
     }
 }
@@ -1,10 +1,12 @@
 use crate::consts;
+use crate::middleware::other::get_query_param;
 use error::SubsonicError;
 
 use actix_web::body::BoxBody;
 use actix_web::http::header::TryIntoHeaderValue;
 use actix_web::http::{header, StatusCode};
-use actix_web::Responder;
+use actix_web::{HttpRequest, HttpResponse, Responder};
+use log::error;
 use serde::Serialize;
 
 pub mod album;
@@ -21,6 +23,7 @@ pub mod share;
 pub mod song;
 pub mod star;
 pub mod user;
+pub mod xml;
 
 const VERSION: &str = "1.0";
 const STATUS_OK: &str = "ok";
@@ -134,6 +137,12 @@ pub struct Subsonic {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub play_queue: Option<play::PlayQueue>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub play_queue_by_index: Option<play::PlayQueueByIndex>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bookmarks: Option<play::Bookmarks>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub shares: Option<share::Shares>,
 
@@ -207,6 +216,8 @@ impl Default for Subsonic {
             similar_songs2: None,
             top_songs: None,
             play_queue: None,
+            play_queue_by_index: None,
+            bookmarks: None,
             shares: None,
             scan_status: None,
             lyrics: None,
@@ -263,6 +274,8 @@ to_subsonic_ok!(similar_songs, song::SimilarSongs);
 to_subsonic_ok!(similar_songs2, song::SimilarSongs2);
 to_subsonic_ok!(top_songs, song::TopSongs);
 to_subsonic_ok!(play_queue, play::PlayQueue);
+to_subsonic_ok!(play_queue_by_index, play::PlayQueueByIndex);
+to_subsonic_ok!(bookmarks, play::Bookmarks);
 to_subsonic_ok!(shares, share::Shares);
 to_subsonic_ok!(scan_status, scan::ScanStatus);
 to_subsonic_ok!(lyrics, lyric::Lyrics);
@@ -306,19 +319,83 @@ impl From<SubsonicError> for Subsonic {
     }
 }
 
-impl Responder for Subsonic {
-    type Body = BoxBody;
+/// 响应格式，由 `f` 参数决定；按 Subsonic 规范，没有 `f` 参数时返回 XML
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    Xml,
+    /// 回调函数名
+    Jsonp(String),
+}
+
+impl ResponseFormat {
+    pub fn from_query(query_string: &str) -> Self {
+        match get_query_param(query_string, "f").as_deref() {
+            Some("jsonp") => get_query_param(query_string, "callback")
+                .filter(|callback| is_valid_callback(callback))
+                .map(ResponseFormat::Jsonp)
+                .unwrap_or(ResponseFormat::Json),
+            Some(f) if f.starts_with("json") => ResponseFormat::Json,
+            _ => ResponseFormat::Xml,
+        }
+    }
+}
 
-    fn respond_to(self, _req: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+/// 回调名只允许 JavaScript 标识符和点号路径（如 `app.cb`），避免注入脚本
+fn is_valid_callback(callback: &str) -> bool {
+    !callback.is_empty()
+        && callback.len() <= 128
+        && callback.split('.').all(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        })
+}
+
+impl Subsonic {
+    pub fn into_response(self, format: ResponseFormat) -> HttpResponse {
         // 包装为 { "subsonic-response": { ... } } 格式
         let wrapper = JsonWrapper {
             subsonic_response: self,
         };
-        let jsonstr = serde_json::to_string(&wrapper).unwrap();
-        let mime = mime::APPLICATION_JSON.try_into_value().unwrap();
-        let mut res = actix_web::HttpResponse::new(StatusCode::OK);
-        res.headers_mut().insert(header::CONTENT_TYPE, mime);
-        res.set_body(BoxBody::new(jsonstr))
+        let (body, mime) = match format {
+            ResponseFormat::Json => (
+                serde_json::to_string(&wrapper).unwrap(),
+                mime::APPLICATION_JSON,
+            ),
+            ResponseFormat::Jsonp(callback) => (
+                format!(
+                    "{}({});",
+                    callback,
+                    serde_json::to_string(&wrapper).unwrap()
+                ),
+                mime::APPLICATION_JAVASCRIPT,
+            ),
+            ResponseFormat::Xml => {
+                let value = serde_json::to_value(&wrapper.subsonic_response).unwrap();
+                match xml::to_xml("subsonic-response", &value) {
+                    Ok(body) => (body, mime::TEXT_XML),
+                    Err(e) => {
+                        error!("Failed to render XML response: {}", e);
+                        return HttpResponse::InternalServerError().finish();
+                    }
+                }
+            }
+        };
+        let mut res = HttpResponse::new(StatusCode::OK);
+        res.headers_mut()
+            .insert(header::CONTENT_TYPE, mime.try_into_value().unwrap());
+        res.set_body(BoxBody::new(body))
+    }
+}
+
+impl Responder for Subsonic {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        self.into_response(ResponseFormat::from_query(req.query_string()))
     }
 }
 
@@ -337,3 +414,77 @@ pub struct DiscTitle {
     pub title: String,
 }
     */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::MessageBody;
+
+    #[test]
+    fn accepts_dotted_identifier_callbacks() {
+        assert!(is_valid_callback("a.b_c$1"));
+        assert!(is_valid_callback("_cb"));
+        assert_eq!(
+            ResponseFormat::from_query("u=a&f=jsonp&callback=a.b_c$1"),
+            ResponseFormat::Jsonp("a.b_c$1".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_callbacks_that_are_not_identifiers() {
+        for callback in ["alert(1)//", "a;b", "", "a..b", "1a", "a.", "a b"] {
+            assert!(!is_valid_callback(callback), "{:?}", callback);
+        }
+        assert!(!is_valid_callback(&"a".repeat(129)));
+        // 非法的回调名退回为普通 JSON，不会原样输出到响应里
+        assert_eq!(
+            ResponseFormat::from_query("f=jsonp&callback=alert(1)//"),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_query("f=jsonp&callback=a%3Bb"),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_query("f=jsonp&callback="),
+            ResponseFormat::Json
+        );
+    }
+
+    #[test]
+    fn format_defaults() {
+        assert_eq!(ResponseFormat::from_query("f=jsonp"), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_query("f=json"), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_query("f=xml"), ResponseFormat::Xml);
+        assert_eq!(ResponseFormat::from_query(""), ResponseFormat::Xml);
+    }
+
+    fn render(format: ResponseFormat) -> (String, String) {
+        let res = Subsonic::default().into_response(format);
+        let content_type = res
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = res.into_body().try_into_bytes().unwrap();
+        (content_type, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn jsonp_wraps_body_in_callback() {
+        let (content_type, body) = render(ResponseFormat::Jsonp("app.cb".to_string()));
+        assert_eq!(content_type, "application/javascript");
+        assert!(body.starts_with(r#"app.cb({"subsonic-response":{"#));
+        assert!(body.ends_with("});"));
+
+        let (content_type, body) = render(ResponseFormat::Json);
+        assert_eq!(content_type, "application/json");
+        assert!(body.starts_with(r#"{"subsonic-response":"#));
+
+        let (content_type, body) = render(ResponseFormat::Xml);
+        assert_eq!(content_type, "text/xml");
+        assert!(body.contains("<subsonic-response xmlns="));
+    }
+}
@@ -1,4 +1,5 @@
 use super::directory::Child;
+use chrono::NaiveDateTime;
 use serde::Serialize;
 
 #[derive(Serialize, Debug)]
@@ -60,6 +61,10 @@ pub struct Playlist {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_user: Option<Vec<String>>, // Added to match the sequence element
+
+    /// 所有者设置的标签（扩展字段）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -99,6 +104,50 @@ pub struct PlayQueue {
     pub changed_by: String,
 }
 
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayQueueByIndex {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<Vec<Child>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_index: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<i64>,
+
+    pub username: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed: Option<String>,
+
+    pub changed_by: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmarks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<Vec<Bookmark>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub entry: Child,
+
+    pub position: i64,
+
+    pub username: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    pub created: NaiveDateTime,
+
+    pub changed: NaiveDateTime,
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RandomSongs {
@@ -12,6 +12,21 @@ pub struct ScanStatus {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_scan: Option<NaiveDateTime>,
+
+    /// 扩展字段：当前阶段（enumerating / parsing / projecting）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+
+    /// 扩展字段：已遍历到的音频文件总数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_count: Option<i64>,
+
+    /// 扩展字段：预计剩余秒数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta: Option<i64>,
 }
 
 impl ScanStatus {
@@ -21,6 +36,10 @@ impl ScanStatus {
             count: 0,
             folder_count: 0,
             last_scan: None,
+            phase: None,
+            total: None,
+            error_count: None,
+            eta: None,
         }
     }
 }
@@ -0,0 +1,161 @@
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde_json::{Map, Value};
+use std::io;
+
+const XMLNS: &str = "http://subsonic.org/restapi";
+
+/// 按 Subsonic XML 的约定把 JSON 响应转换为 XML：
+/// 标量字段是属性，对象是子元素，数组展开为同名的多个子元素，`value` 字段是元素文本
+pub fn to_xml(root: &str, body: &Value) -> io::Result<String> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    let empty = Map::new();
+    let fields = body.as_object().unwrap_or(&empty);
+    write_element(&mut writer, root, fields, Some(XMLNS))?;
+    String::from_utf8(writer.into_inner())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn write_element(
+    writer: &mut Writer<Vec<u8>>,
+    name: &str,
+    fields: &Map<String, Value>,
+    xmlns: Option<&str>,
+) -> io::Result<()> {
+    let mut start = BytesStart::new(name);
+    if let Some(xmlns) = xmlns {
+        start.push_attribute(("xmlns", xmlns));
+    }
+    for (key, value) in fields {
+        if key == "value" {
+            continue;
+        }
+        if let Some(text) = scalar_text(value) {
+            start.push_attribute((key.as_str(), text.as_str()));
+        }
+    }
+
+    let text = fields.get("value").and_then(scalar_text);
+    let has_children = fields
+        .values()
+        .any(|value| value.is_object() || value.is_array());
+    if text.is_none() && !has_children {
+        return writer.write_event(Event::Empty(start));
+    }
+
+    writer.write_event(Event::Start(start))?;
+    if let Some(text) = text {
+        writer.write_event(Event::Text(BytesText::new(&text)))?;
+    }
+    for (key, value) in fields {
+        write_child(writer, key, value)?;
+    }
+    writer.write_event(Event::End(BytesEnd::new(name)))
+}
+
+fn write_child(writer: &mut Writer<Vec<u8>>, name: &str, value: &Value) -> io::Result<()> {
+    match value {
+        Value::Object(fields) => write_element(writer, name, fields, None),
+        Value::Array(items) => {
+            for item in items {
+                match item {
+                    Value::Object(fields) => write_element(writer, name, fields, None)?,
+                    Value::Array(_) | Value::Null => {}
+                    _ => {
+                        let text = scalar_text(item).unwrap_or_default();
+                        writer.write_event(Event::Start(BytesStart::new(name)))?;
+                        writer.write_event(Event::Text(BytesText::new(&text)))?;
+                        writer.write_event(Event::End(BytesEnd::new(name)))?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn scalars_are_attributes_and_objects_are_children() {
+        let xml = to_xml(
+            "subsonic-response",
+            &json!({
+                "status": "ok",
+                "openSubsonic": true,
+                "user": {"username": "alice", "folder": {"id": 1}},
+            }),
+        )
+        .unwrap();
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains(r#"status="ok""#));
+        assert!(xml.contains(r#"openSubsonic="true""#));
+        assert!(xml.contains(r#"<user username="alice"><folder id="1"/></user>"#));
+        assert!(xml.ends_with("</subsonic-response>"));
+    }
+
+    #[test]
+    fn root_element_has_xmlns() {
+        let xml = to_xml("subsonic-response", &json!({"status": "ok"})).unwrap();
+        assert!(
+            xml.contains(r#"<subsonic-response xmlns="http://subsonic.org/restapi" status="ok"/>"#)
+        );
+        // 只有根元素带命名空间
+        let xml = to_xml("subsonic-response", &json!({"user": {"username": "a"}})).unwrap();
+        assert_eq!(xml.matches("xmlns=").count(), 1);
+    }
+
+    #[test]
+    fn arrays_become_repeated_elements() {
+        let xml = to_xml(
+            "subsonic-response",
+            &json!({
+                "musicFolders": {"musicFolder": [{"id": 1}, {"id": 2}]},
+                "genres": {"genre": []},
+                "roles": {"role": ["admin", "stream"]},
+            }),
+        )
+        .unwrap();
+        assert!(xml.contains(
+            r#"<musicFolders><musicFolder id="1"/><musicFolder id="2"/></musicFolders>"#
+        ));
+        assert!(xml.contains("<genres></genres>"));
+        assert!(xml.contains("<roles><role>admin</role><role>stream</role></roles>"));
+    }
+
+    #[test]
+    fn value_field_is_element_text() {
+        let xml = to_xml(
+            "subsonic-response",
+            &json!({"lyrics": {"artist": "Queen", "value": "Is this the real life?"}}),
+        )
+        .unwrap();
+        assert!(xml.contains(r#"<lyrics artist="Queen">Is this the real life?</lyrics>"#));
+        assert!(!xml.contains("value="));
+    }
+
+    #[test]
+    fn escapes_attributes_and_text() {
+        let xml = to_xml(
+            "subsonic-response",
+            &json!({"song": {"title": r#"Rock & "Roll" <Live>"#, "value": "a < b & c"}}),
+        )
+        .unwrap();
+        assert!(xml.contains(r#"title="Rock &amp; &quot;Roll&quot; &lt;Live&gt;""#));
+        assert!(xml.contains(">a &lt; b &amp; c</song>"));
+    }
+}
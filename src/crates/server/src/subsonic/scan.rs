@@ -3,17 +3,27 @@ use crate::subsonic::response::scan::ScanStatus as ScanStatusResponse;
 use crate::subsonic::response::Subsonic;
 use crate::AppState;
 use actix_web::web;
-use application::command::library::{LibraryCommandService, ScanLibraryCmd};
+use application::command::library::ScanLibraryCmd;
 use application::context::AppContext;
 use application::query::dao::MusicFolderDao;
-use infra::file_type_detector::DefaultFileTypeDetector;
-use infra::repository::postgres::command::library::LibraryRepositoryImpl;
 use infra::repository::postgres::query::music_folder::MusicFolderDaoImpl;
-use infra::storage::factory::StorageClientFactoryImpl;
-use std::sync::Arc;
+use model::scan_status::ScanPhase;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct StartScanQuery {
+    /// 为 true 时忽略中断扫描的检查点，从头扫描
+    #[serde(default)]
+    pub force: bool,
+}
 
 /// OpenSubsonic startScan API - scans all music folders
-pub async fn start_library_scan(state: web::Data<AppState>) -> Result<Subsonic, SubsonicError> {
+///
+/// 中断的扫描默认从检查点继续，`force=true` 时重新开始
+pub async fn start_library_scan(
+    state: web::Data<AppState>,
+    query: web::Query<StartScanQuery>,
+) -> Result<Subsonic, SubsonicError> {
     let state = state.into_inner();
 
     // Query all music folders
@@ -21,31 +31,19 @@ pub async fn start_library_scan(state: web::Data<AppState>) -> Result<Subsonic,
     let folders = music_folder_dao
         .get_all()
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
 
     if folders.is_empty() {
         return Ok(ScanStatusResponse {
             scanning: false,
             count: 0,
             folder_count: 0,
-            last_scan: None,
+            ..ScanStatusResponse::new()
         }
         .into());
     }
 
-    let library_repo = Arc::new(LibraryRepositoryImpl::new(state.db.clone()));
-    let scanner_factory = Arc::new(StorageClientFactoryImpl::new());
-    let file_type_detector = Arc::new(DefaultFileTypeDetector::new());
-    let event_bus = Arc::new(state.event_bus.clone());
-    let id_generator = state.id_generator.clone();
-
-    let svc = LibraryCommandService::new(
-        library_repo,
-        scanner_factory,
-        file_type_detector,
-        event_bus,
-        id_generator,
-    );
+    let svc = crate::library_command_service(&state);
 
     let ctx = AppContext::new();
 
@@ -58,6 +56,7 @@ pub async fn start_library_scan(state: web::Data<AppState>) -> Result<Subsonic,
                 ScanLibraryCmd {
                     library_id,
                     is_full_scan: true,
+                    force: query.force,
                 },
             )
             .await;
@@ -67,7 +66,7 @@ pub async fn start_library_scan(state: web::Data<AppState>) -> Result<Subsonic,
         scanning: true,
         count: 0,
         folder_count: folders.len() as i32,
-        last_scan: None,
+        ..ScanStatusResponse::new()
     }
     .into())
 }
@@ -88,27 +87,47 @@ pub async fn get_scan_status(state: web::Data<AppState>) -> Result<Subsonic, Sub
             scanning: false,
             count: 0,
             folder_count: 0,
-            last_scan: None,
+            ..ScanStatusResponse::new()
         }
         .into());
     }
 
     // Aggregate status from all libraries
+    let now = state
+        .clock
+        .now()
+        .with_timezone(&chrono::Local)
+        .naive_local();
     let mut scanning = false;
     let mut total_count: i64 = 0;
+    let mut total_files: i64 = 0;
+    let mut error_count: i64 = 0;
+    let mut phase: Option<ScanPhase> = None;
+    let mut eta: Option<i64> = None;
+    let mut last_scan = None;
 
     for status in all_statuses.values() {
+        total_count += status.processed_files;
+        last_scan = last_scan.max(status.finished_at);
         if status.scanning {
             scanning = true;
+            total_files += status.total_files;
+            error_count += status.error_count;
+            // 多个库同时扫描时报告最靠前的阶段和最晚的完成时间
+            phase = Some(phase.map_or(status.phase, |p| p.min(status.phase)));
+            eta = eta.max(status.eta_seconds(now));
         }
-        total_count += status.processed_files;
     }
 
     Ok(ScanStatusResponse {
         scanning,
         count: total_count as i32,
         folder_count: all_statuses.len() as i32,
-        last_scan: Some(chrono::Utc::now().naive_utc()),
+        last_scan,
+        phase: phase.map(|p| p.as_str().to_string()),
+        total: scanning.then_some(total_files),
+        error_count: scanning.then_some(error_count),
+        eta,
     }
     .into())
 }
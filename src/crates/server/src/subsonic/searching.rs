@@ -1,3 +1,6 @@
+use crate::subsonic::helper::{
+    check_non_negative, current_user_id, hide_explicit, SubsonicQuery, ValidateParams,
+};
 use crate::subsonic::response::album::AlbumID3;
 use crate::subsonic::response::artist::{Artist as ArtistResponse, ArtistID3};
 use crate::subsonic::response::directory::Child;
@@ -5,7 +8,7 @@ use crate::subsonic::response::error::SubsonicError;
 use crate::subsonic::response::search::{SearchResult, SearchResult2, SearchResult3};
 use crate::subsonic::response::Subsonic;
 use crate::AppState;
-use actix_web::web;
+use actix_web::{web, HttpRequest};
 use application::query::dao::{AlbumDao, ArtistDao, AudioFileDao};
 use infra::repository::postgres::query::album::AlbumDaoImpl;
 use infra::repository::postgres::query::artist::ArtistDaoImpl;
@@ -58,6 +61,20 @@ fn default_count() -> i32 {
     20
 }
 
+impl ValidateParams for SearchQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("count", Some(self.count))?;
+        check_non_negative("offset", Some(self.offset))
+    }
+}
+
+/// search2 和 search3 的分页参数不能为负数
+fn check_search_pages(pages: [(&str, i32); 6]) -> Result<(), SubsonicError> {
+    pages
+        .into_iter()
+        .try_for_each(|(name, value)| check_non_negative(name, Some(value)))
+}
+
 /// search - 搜索文件
 ///
 /// 根据 Subsonic 规范 (Since 1.0.0, Deprecated since 1.4.0):
@@ -65,10 +82,13 @@ fn default_count() -> i32 {
 /// - 支持分页
 /// - 已弃用，推荐使用 search2
 pub async fn search(
+    req: HttpRequest,
     state: web::Data<AppState>,
-    query: web::Query<SearchQuery>,
+    query: SubsonicQuery<SearchQuery>,
 ) -> Result<Subsonic, SubsonicError> {
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(current_user_id(&req));
 
     // 执行搜索
     let (audio_files, total) = audio_file_dao
@@ -82,7 +102,7 @@ pub async fn search(
             query.count,
         )
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
 
     // 转换为 Child 列表
     let matches: Vec<Child> = audio_files.into_iter().map(Child::from).collect();
@@ -146,18 +166,38 @@ pub struct Search2Query {
     pub music_folder_id: Option<i64>,
 }
 
+impl ValidateParams for Search2Query {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_search_pages([
+            ("artistCount", self.artist_count),
+            ("artistOffset", self.artist_offset),
+            ("albumCount", self.album_count),
+            ("albumOffset", self.album_offset),
+            ("songCount", self.song_count),
+            ("songOffset", self.song_offset),
+        ])
+    }
+}
+
 /// search2 - 搜索艺术家、专辑和歌曲
 ///
 /// 根据 Subsonic 规范 (Since 1.4.0):
 /// - 返回匹配搜索条件的艺术家、专辑和歌曲列表
 /// - 支持分页
 pub async fn search2(
+    req: HttpRequest,
     state: web::Data<AppState>,
-    query: web::Query<Search2Query>,
+    query: SubsonicQuery<Search2Query>,
 ) -> Result<Subsonic, SubsonicError> {
-    let artist_dao = ArtistDaoImpl::new(state.db.clone());
-    let album_dao = AlbumDaoImpl::new(state.db.clone());
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let hide_explicit = hide_explicit(&req);
+    let user_id = current_user_id(&req);
+    let artist_dao = ArtistDaoImpl::new(state.db.clone()).with_user(user_id);
+    let album_dao = AlbumDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit)
+        .with_user(user_id);
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit)
+        .with_user(user_id);
 
     // 并行执行三个搜索
     let (artists_result, albums_result, songs_result) = tokio::join!(
@@ -176,7 +216,7 @@ pub async fn search2(
 
     // 处理艺术家结果
     let artists = artists_result
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
     let artist_responses: Vec<ArtistResponse> = artists
         .into_iter()
         .map(|artist| {
@@ -194,12 +234,12 @@ pub async fn search2(
 
     // 处理专辑结果
     let albums = albums_result
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
     let album_responses: Vec<Child> = albums.into_iter().map(Child::from).collect();
 
     // 处理歌曲结果
     let (songs, _total) = songs_result
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
     let song_responses: Vec<Child> = songs.into_iter().map(Child::from).collect();
 
     let search_result = SearchResult2 {
@@ -270,6 +310,19 @@ pub struct Search3Query {
     pub music_folder_id: Option<i64>,
 }
 
+impl ValidateParams for Search3Query {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_search_pages([
+            ("artistCount", self.artist_count),
+            ("artistOffset", self.artist_offset),
+            ("albumCount", self.album_count),
+            ("albumOffset", self.album_offset),
+            ("songCount", self.song_count),
+            ("songOffset", self.song_offset),
+        ])
+    }
+}
+
 /// search3 - 搜索艺术家、专辑和歌曲（ID3 标签）
 ///
 /// 根据 Subsonic 规范 (Since 1.8.0):
@@ -278,12 +331,19 @@ pub struct Search3Query {
 /// - 支持分页
 /// - OpenSubsonic: 支持空查询返回所有数据用于离线同步
 pub async fn search3(
+    req: HttpRequest,
     state: web::Data<AppState>,
-    query: web::Query<Search3Query>,
+    query: SubsonicQuery<Search3Query>,
 ) -> Result<Subsonic, SubsonicError> {
-    let artist_dao = ArtistDaoImpl::new(state.db.clone());
-    let album_dao = AlbumDaoImpl::new(state.db.clone());
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let hide_explicit = hide_explicit(&req);
+    let user_id = current_user_id(&req);
+    let artist_dao = ArtistDaoImpl::new(state.db.clone()).with_user(user_id);
+    let album_dao = AlbumDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit)
+        .with_user(user_id);
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit)
+        .with_user(user_id);
 
     // 处理空查询 - OpenSubsonic 要求支持空查询返回所有数据
     let search_query = if query.query.is_empty() || query.query == "\"\"" {
@@ -317,7 +377,7 @@ pub async fn search3(
 
     // 处理艺术家结果 - 使用 ArtistID3 格式
     let artists = artists_result
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
     let artist_responses: Vec<ArtistID3> = artists
         .into_iter()
         .map(|artist| {
@@ -328,12 +388,12 @@ pub async fn search3(
 
     // 处理专辑结果 - 使用 AlbumID3 格式
     let albums = albums_result
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
     let album_responses: Vec<AlbumID3> = albums.into_iter().map(AlbumID3::new).collect();
 
     // 处理歌曲结果
     let (songs, _total) = songs_result
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
     let song_responses: Vec<Child> = songs.into_iter().map(Child::from).collect();
 
     let search_result = SearchResult3 {
@@ -1,11 +1,16 @@
+use crate::subsonic::helper::{
+    check_non_negative, check_one_of, current_user_id, hide_explicit, user_notes, SubsonicQuery,
+    ValidateParams, MAX_LIST_SIZE,
+};
 use crate::subsonic::response::album::{AlbumID3, AlbumList, AlbumList2};
 use crate::subsonic::response::artist::{Artist, ArtistID3, ArtistList};
 use crate::subsonic::response::directory::Child;
 use crate::subsonic::response::error::SubsonicError;
 use crate::subsonic::response::song::{RandomSongs, SongList, Songs, SongsByGenre};
 use crate::subsonic::response::star::{Starred, Starred2};
-use crate::subsonic::response::{JsonWrapper, Subsonic};
+use crate::subsonic::response::Subsonic;
 use crate::AppState;
+use actix_web::http::header::{HeaderName, HeaderValue};
 use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
 use application::query::get_album_list::GetAlbumList;
 use application::query::get_artist_list::GetArtistList;
@@ -13,6 +18,7 @@ use application::query::get_random_songs::GetRandomSongs;
 use application::query::get_songs_by_genre::GetSongsByGenre;
 use application::query::get_songs_list::GetSongsList;
 use application::query::get_starred::GetStarred;
+use domain::annotation::Kind;
 use infra::auth::{AuthConfig, JwtTokenService};
 use infra::repository::postgres::query::album::AlbumDaoImpl;
 use infra::repository::postgres::query::artist::ArtistDaoImpl;
@@ -31,80 +37,148 @@ pub struct GetAlbumListQuery {
     pub size: Option<i32>,
 }
 
+/// getAlbumList 支持的列表类型，byOriginalYear 和 mostStarred 是扩展类型
+const ALBUM_LIST_TYPES: &[&str] = &[
+    "random",
+    "newest",
+    "highest",
+    "frequent",
+    "recent",
+    "alphabeticalByName",
+    "alphabeticalByArtist",
+    "starred",
+    "byYear",
+    "byGenre",
+    "byOriginalYear",
+    "mostStarred",
+];
+
+impl ValidateParams for GetAlbumListQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_one_of("type", &self.r#type, ALBUM_LIST_TYPES)?;
+        let missing = |name: &str| {
+            Err(SubsonicError::error_missing_parameter().wrap(format!(
+                "Parameter '{}' is required for type {}",
+                name, self.r#type
+            )))
+        };
+        match self.r#type.as_str() {
+            "byGenre" if self.genre.is_none() => return missing("genre"),
+            "byYear" if self.from_year.is_none() => return missing("fromYear"),
+            "byYear" if self.to_year.is_none() => return missing("toYear"),
+            _ => {}
+        }
+        check_non_negative("offset", self.offset)?;
+        check_non_negative("size", self.size)
+    }
+}
+
+/// 列表响应，总数放在 x-total-count 头中
+fn with_total_count(req: &HttpRequest, response: Subsonic, count: i64) -> HttpResponse {
+    let mut rsp = response.respond_to(req);
+    rsp.headers_mut().insert(
+        HeaderName::from_static("x-total-count"),
+        HeaderValue::from(count),
+    );
+    rsp
+}
+
 pub async fn get_album_list(
     state: web::Data<AppState>,
-    query: web::Query<GetAlbumListQuery>,
+    req: HttpRequest,
+    query: SubsonicQuery<GetAlbumListQuery>,
 ) -> impl Responder {
-    let album_dao = AlbumDaoImpl::new(state.db.clone());
+    let user_id = current_user_id(&req);
+    let album_dao = AlbumDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(user_id);
     let usecase = GetAlbumList::new(Arc::new(album_dao));
 
     let offset = query.offset.unwrap_or(0);
     let size = query.size.unwrap_or(10);
 
     let (albums, count) = match usecase
-        .handle(
+        .handle_with_user(
             &query.r#type,
             query.genre.as_deref(),
             query.from_year,
             query.to_year,
             offset,
             size,
+            user_id.unwrap_or(0),
         )
         .await
     {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
-            let wrapper = JsonWrapper { subsonic_response: error };
-            return HttpResponse::Ok().json(wrapper);
+            let error: Subsonic = SubsonicError::from(e).into();
+            return error.respond_to(&req);
         }
     };
 
-    let album_children: Vec<Child> = albums.into_iter().map(|album| Child::from(album)).collect();
+    let ids: Vec<i64> = albums.iter().map(|album| album.id).collect();
+    let mut notes = user_notes(&state, &req, Kind::Album, &ids).await;
+    let album_children: Vec<Child> = albums
+        .into_iter()
+        .map(|album| {
+            let note = notes.remove(&album.id);
+            let mut child = Child::from(album);
+            child.os_child.comment = note;
+            child
+        })
+        .collect();
 
     let response: Subsonic = AlbumList {
         album: album_children,
     }
     .into();
 
-    let wrapper = JsonWrapper { subsonic_response: response };
-    HttpResponse::Ok()
-        .insert_header(("x-total-count", count.to_string()))
-        .json(wrapper)
+    with_total_count(&req, response, count)
 }
 
 pub async fn get_album_list2(
     state: web::Data<AppState>,
-    query: web::Query<GetAlbumListQuery>,
+    req: HttpRequest,
+    query: SubsonicQuery<GetAlbumListQuery>,
 ) -> impl Responder {
-    let album_dao = AlbumDaoImpl::new(state.db.clone());
+    let user_id = current_user_id(&req);
+    let album_dao = AlbumDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(user_id);
     let usecase = GetAlbumList::new(Arc::new(album_dao));
 
     let offset = query.offset.unwrap_or(0);
     let size = query.size.unwrap_or(10);
 
     let (albums, count) = match usecase
-        .handle(
+        .handle_with_user(
             &query.r#type,
             query.genre.as_deref(),
             query.from_year,
             query.to_year,
             offset,
             size,
+            user_id.unwrap_or(0),
         )
         .await
     {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
-            let wrapper = JsonWrapper { subsonic_response: error };
-            return HttpResponse::Ok().json(wrapper);
+            let error: Subsonic = SubsonicError::from(e).into();
+            return error.respond_to(&req);
         }
     };
 
+    let ids: Vec<i64> = albums.iter().map(|album| album.id).collect();
+    let mut notes = user_notes(&state, &req, Kind::Album, &ids).await;
     let album_id3_list: Vec<AlbumID3> = albums
         .into_iter()
-        .map(|album| AlbumID3::new(album))
+        .map(|album| {
+            let note = notes.remove(&album.id);
+            let mut album_id3 = AlbumID3::new(album);
+            album_id3.comment = note;
+            album_id3
+        })
         .collect();
 
     let response: Subsonic = AlbumList2 {
@@ -112,10 +186,7 @@ pub async fn get_album_list2(
     }
     .into();
 
-    let wrapper = JsonWrapper { subsonic_response: response };
-    HttpResponse::Ok()
-        .insert_header(("x-total-count", count.to_string()))
-        .json(wrapper)
+    with_total_count(&req, response, count)
 }
 
 #[derive(Deserialize)]
@@ -127,14 +198,23 @@ pub struct GetRandomSongsQuery {
     pub to_year: Option<i32>,
 }
 
+impl ValidateParams for GetRandomSongsQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("size", self.size)
+    }
+}
+
 pub async fn get_random_songs(
     state: web::Data<AppState>,
-    query: web::Query<GetRandomSongsQuery>,
+    req: HttpRequest,
+    query: SubsonicQuery<GetRandomSongsQuery>,
 ) -> impl Responder {
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(current_user_id(&req));
     let usecase = GetRandomSongs::new(Arc::new(audio_file_dao));
 
-    let size = query.size.unwrap_or(10).min(500);
+    let size = query.size.unwrap_or(10).min(MAX_LIST_SIZE);
 
     let songs = match usecase
         .handle(query.genre.as_deref(), query.from_year, query.to_year, size)
@@ -142,7 +222,7 @@ pub async fn get_random_songs(
     {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
+            let error: Subsonic = SubsonicError::from(e).into();
             return error;
         }
     };
@@ -165,20 +245,30 @@ pub struct GetSongsByGenreQuery {
     pub offset: Option<i32>,
 }
 
+impl ValidateParams for GetSongsByGenreQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_non_negative("count", self.count)?;
+        check_non_negative("offset", self.offset)
+    }
+}
+
 pub async fn get_songs_by_genre(
     state: web::Data<AppState>,
-    query: web::Query<GetSongsByGenreQuery>,
+    req: HttpRequest,
+    query: SubsonicQuery<GetSongsByGenreQuery>,
 ) -> impl Responder {
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(current_user_id(&req));
     let usecase = GetSongsByGenre::new(Arc::new(audio_file_dao));
 
-    let count = query.count.unwrap_or(10).min(500);
+    let count = query.count.unwrap_or(10).min(MAX_LIST_SIZE);
     let offset = query.offset.unwrap_or(0);
 
     let songs = match usecase.handle(&query.genre, offset, count).await {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
+            let error: Subsonic = SubsonicError::from(e).into();
             return error;
         }
     };
@@ -221,7 +311,7 @@ pub async fn get_starred(state: web::Data<AppState>, req: HttpRequest) -> impl R
     let (artists_with_tokens, albums, audio_files) = match usecase.handle_with_tokens(user.id.as_i64()).await {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
+            let error: Subsonic = SubsonicError::from(e).into();
             return error;
         }
     };
@@ -300,7 +390,7 @@ pub async fn get_starred2(state: web::Data<AppState>, req: HttpRequest) -> impl
     let (artists_with_tokens, albums, audio_files) = match usecase.handle_with_tokens(user.id.as_i64()).await {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
+            let error: Subsonic = SubsonicError::from(e).into();
             return error;
         }
     };
@@ -354,19 +444,30 @@ pub struct GetArtistListQuery {
     pub size: Option<i32>,
 }
 
+impl ValidateParams for GetArtistListQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        check_one_of(
+            "type",
+            &self.r#type,
+            &["frequent", "mostPlayed", "recent", "recentlyPlayed"],
+        )?;
+        check_non_negative("size", self.size)
+    }
+}
+
 pub async fn get_artist_list(
     state: web::Data<AppState>,
-    query: web::Query<GetArtistListQuery>,
+    query: SubsonicQuery<GetArtistListQuery>,
 ) -> impl Responder {
     let artist_dao = ArtistDaoImpl::new(state.db.clone());
     let usecase = GetArtistList::new(Arc::new(artist_dao));
 
-    let size = query.size.unwrap_or(10).min(500);
+    let size = query.size.unwrap_or(10).min(MAX_LIST_SIZE);
 
     let artists = match usecase.handle(&query.r#type, size).await {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
+            let error: Subsonic = SubsonicError::from(e).into();
             return error;
         }
     };
@@ -405,19 +506,34 @@ pub struct GetSongsListQuery {
     pub size: Option<i32>,
 }
 
+impl ValidateParams for GetSongsListQuery {
+    fn validate(&self) -> Result<(), SubsonicError> {
+        // 列表类型不区分大小写
+        check_one_of(
+            "type",
+            &self.r#type.to_lowercase(),
+            &["frequent", "mostplayed", "recent", "recentlyplayed"],
+        )?;
+        check_non_negative("size", self.size)
+    }
+}
+
 pub async fn get_songs_list(
     state: web::Data<AppState>,
-    query: web::Query<GetSongsListQuery>,
+    req: HttpRequest,
+    query: SubsonicQuery<GetSongsListQuery>,
 ) -> impl Responder {
-    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone());
+    let audio_file_dao = AudioFileDaoImpl::new(state.db.clone())
+        .with_hide_explicit(hide_explicit(&req))
+        .with_user(current_user_id(&req));
     let usecase = GetSongsList::new(Arc::new(audio_file_dao));
 
-    let size = query.size.unwrap_or(10).min(500);
+    let size = query.size.unwrap_or(10).min(MAX_LIST_SIZE);
 
     let songs = match usecase.handle(&query.r#type, size).await {
         Ok(result) => result,
         Err(e) => {
-            let error: Subsonic = SubsonicError::error_generic().wrap(e.to_string()).into();
+            let error: Subsonic = SubsonicError::from(e).into();
             return error;
         }
     };
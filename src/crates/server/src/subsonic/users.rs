@@ -2,10 +2,12 @@ use crate::subsonic::response::error::SubsonicError;
 use crate::subsonic::response::Subsonic;
 use crate::AppState;
 use actix_web::{web, HttpMessage, HttpRequest};
-use application::auth::PasswordEncryptor;
-use application::command::user::{ChangePasswordCmd, CreateUserCmd, DeleteUserCmd, UpdateUserCmd, UserAppService};
-use infra::auth::BcryptPasswordHasher;
-use infra::repository::postgres::command::user::UserRepositoryImpl;
+use application::auth::{PasswordEncryptor, PasswordHasher};
+use application::command::user::{
+    ChangePasswordCmd, CreateUserCmd, DeleteUserCmd, UpdateUserCmd, UserAppService, UserRolesPatch,
+};
+use domain::user::UserRoles;
+use infra::repository::postgres::command::user::{UserPreferenceRepositoryImpl, UserRepositoryImpl};
 use infra::Aes256GcmEncryptor;
 use serde::Deserialize;
 use std::sync::Arc;
@@ -103,6 +105,14 @@ fn default_true() -> bool {
     true
 }
 
+fn user_service(state: &AppState) -> UserAppService {
+    UserAppService::new(
+        Arc::new(UserRepositoryImpl::new(state.db.clone())),
+        Arc::new(UserPreferenceRepositoryImpl::new(state.db.clone())),
+        state.id_generator.clone(),
+    )
+}
+
 /// 解码密码
 ///
 /// 根据 Subsonic 规范，密码可以是明文或以 "enc:" 前缀的 hex 编码
@@ -145,8 +155,8 @@ pub async fn create_user(
     // 解码密码
     let plain_password = decode_password(&query.password)?;
 
-    // 哈希密码 (bcrypt for normal auth)
-    let hasher = BcryptPasswordHasher::new(12);
+    // 哈希密码（登录认证用）
+    let hasher = crate::password_hasher(&state);
     let hashed_password = hasher
         .hash(&plain_password)
         .map_err(|e| SubsonicError::error_generic().wrap(format!("Failed to hash password: {}", e)))?;
@@ -158,22 +168,32 @@ pub async fn create_user(
         .encrypt(&plain_password)
         .map_err(|e| SubsonicError::error_generic().wrap(format!("Failed to encrypt password: {}", e)))?;
 
-    // 创建用户服务
-    let user_repo: Arc<dyn domain::user::UserRepository> =
-        Arc::new(UserRepositoryImpl::new(state.db.clone()));
-    let user_service = UserAppService::new(user_repo, state.id_generator.clone());
-
     // 创建用户
-    user_service
+    user_service(&state)
         .create_user(CreateUserCmd {
             username: query.username.clone(),
             password: hashed_password,
             encrypted_password,
             email: query.email.clone(),
             is_admin: query.admin_role,
+            roles: UserRoles {
+                settings: query.settings_role,
+                stream: query.stream_role,
+                download: query.download_role,
+                upload: query.upload_role,
+                playlist: query.playlist_role,
+                cover_art: query.cover_art_role,
+                comment: query.comment_role,
+                podcast: query.podcast_role,
+                jukebox: query.jukebox_role,
+                share: query.share_role,
+            },
+            max_bit_rate: None,
+            download_quota_mb: None,
+            hide_explicit: false,
         })
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
 
     Ok(Subsonic::default())
 }
@@ -295,7 +315,7 @@ pub async fn update_user(
     // 解码并处理密码（如果提供）
     let (hashed_password, encrypted_password) = if let Some(ref password) = query.password {
         let plain_password = decode_password(password)?;
-        let hasher = BcryptPasswordHasher::new(12);
+        let hasher = crate::password_hasher(&state);
         let hashed = hasher
             .hash(&plain_password)
             .map_err(|e| SubsonicError::error_generic().wrap(format!("Failed to hash password: {}", e)))?;
@@ -311,22 +331,32 @@ pub async fn update_user(
         (None, None)
     };
 
-    // 创建用户服务
-    let user_repo: Arc<dyn domain::user::UserRepository> =
-        Arc::new(UserRepositoryImpl::new(state.db.clone()));
-    let user_service = UserAppService::new(user_repo, state.id_generator.clone());
-
     // 更新用户
-    user_service
+    user_service(&state)
         .update_user(UpdateUserCmd {
             username: query.username.clone(),
             password: hashed_password,
             encrypted_password,
             email: query.email.clone(),
             is_admin: query.admin_role,
+            roles: UserRolesPatch {
+                settings: query.settings_role,
+                stream: query.stream_role,
+                download: query.download_role,
+                upload: query.upload_role,
+                playlist: None,
+                cover_art: query.cover_art_role,
+                comment: query.comment_role,
+                podcast: query.podcast_role,
+                jukebox: query.jukebox_role,
+                share: query.share_role,
+            },
+            max_bit_rate: query.max_bit_rate,
+            download_quota_mb: None,
+            hide_explicit: None,
         })
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
 
     Ok(Subsonic::default())
 }
@@ -371,18 +401,13 @@ pub async fn delete_user(
             .wrap("Cannot delete yourself".to_string()));
     }
 
-    // 创建用户服务
-    let user_repo: Arc<dyn domain::user::UserRepository> =
-        Arc::new(UserRepositoryImpl::new(state.db.clone()));
-    let user_service = UserAppService::new(user_repo, state.id_generator.clone());
-
     // 删除用户
-    user_service
+    user_service(&state)
         .delete_user(DeleteUserCmd {
             username: query.username.clone(),
         })
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
 
     Ok(Subsonic::default())
 }
@@ -428,8 +453,8 @@ pub async fn change_password(
     // 解码密码
     let plain_password = decode_password(&query.password)?;
 
-    // 哈希密码 (bcrypt for normal auth)
-    let hasher = BcryptPasswordHasher::new(12);
+    // 哈希密码（登录认证用）
+    let hasher = crate::password_hasher(&state);
     let hashed_password = hasher
         .hash(&plain_password)
         .map_err(|e| SubsonicError::error_generic().wrap(format!("Failed to hash password: {}", e)))?;
@@ -441,20 +466,15 @@ pub async fn change_password(
         .encrypt(&plain_password)
         .map_err(|e| SubsonicError::error_generic().wrap(format!("Failed to encrypt password: {}", e)))?;
 
-    // 创建用户服务
-    let user_repo: Arc<dyn domain::user::UserRepository> =
-        Arc::new(UserRepositoryImpl::new(state.db.clone()));
-    let user_service = UserAppService::new(user_repo, state.id_generator.clone());
-
     // 修改密码
-    user_service
+    user_service(&state)
         .change_password(ChangePasswordCmd {
             username: query.username.clone(),
             password: hashed_password,
             encrypted_password,
         })
         .await
-        .map_err(|e| SubsonicError::error_generic().wrap(e.to_string()))?;
+        .map_err(SubsonicError::from)?;
 
     Ok(Subsonic::default())
 }
@@ -0,0 +1,254 @@
+use actix_web::dev::ServerHandle;
+use actix_web::http::header;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use anyhow::{anyhow, Context};
+use futures::StreamExt;
+use infra::config::{AcmeConfig, ListenAddress, TlsConfig};
+use log::{error, info};
+use parking_lot::RwLock;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::CertificateDer;
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::ServerConfig;
+use rustls_acme::acme::ACME_TLS_ALPN_NAME;
+use rustls_acme::caches::DirCache;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_HTTPS_PORT: u16 = 443;
+
+/// 构建 rustls 配置，未启用 TLS 时返回 None。
+/// 证书来自文件时按间隔检查修改时间并热加载，来自 ACME 时由后台任务申请和续期。
+pub fn server_config(cfg: &TlsConfig) -> anyhow::Result<Option<ServerConfig>> {
+    if !cfg.enabled {
+        return Ok(None);
+    }
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth();
+
+    let config = match &cfg.acme {
+        Some(acme) => {
+            let mut config = builder.with_cert_resolver(start_acme(acme));
+            // TLS-ALPN-01 验证通过 ALPN 协商完成
+            config.alpn_protocols.push(ACME_TLS_ALPN_NAME.to_vec());
+            config
+        }
+        None => {
+            let resolver = Arc::new(FileCertResolver::load(
+                cfg.cert_path.clone(),
+                cfg.key_path.clone(),
+                provider,
+            )?);
+            if let Some(interval) = cfg.reload_interval {
+                resolver.clone().start_watcher(interval);
+            }
+            info!("TLS enabled with certificate {}", cfg.cert_path.display());
+            builder.with_cert_resolver(resolver)
+        }
+    };
+    Ok(Some(config))
+}
+
+fn start_acme(cfg: &AcmeConfig) -> Arc<dyn ResolvesServerCert> {
+    info!(
+        "TLS enabled with ACME certificates for {:?} ({})",
+        cfg.domains,
+        if cfg.production {
+            "production"
+        } else {
+            "staging"
+        }
+    );
+    let mut state = rustls_acme::AcmeConfig::new(cfg.domains.clone())
+        .contact(cfg.contact.iter().map(|email| format!("mailto:{}", email)))
+        .cache(DirCache::new(cfg.cache_dir.clone()))
+        .directory_lets_encrypt(cfg.production)
+        .state();
+    let resolver = state.resolver();
+    actix_web::rt::spawn(async move {
+        while let Some(event) = state.next().await {
+            match event {
+                Ok(event) => info!("ACME: {:?}", event),
+                Err(e) => error!("ACME error: {:?}", e),
+            }
+        }
+    });
+    resolver
+}
+
+/// 从文件加载的证书，文件修改后在下次检查时替换，已建立的连接不受影响
+struct FileCertResolver {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    provider: Arc<CryptoProvider>,
+    current: RwLock<Arc<CertifiedKey>>,
+    modified: RwLock<Option<(SystemTime, SystemTime)>>,
+}
+
+impl FileCertResolver {
+    fn load(
+        cert_path: PathBuf,
+        key_path: PathBuf,
+        provider: Arc<CryptoProvider>,
+    ) -> anyhow::Result<Self> {
+        let certified_key = load_certified_key(&cert_path, &key_path, &provider)?;
+        let modified = modified_times(&cert_path, &key_path);
+        Ok(Self {
+            cert_path,
+            key_path,
+            provider,
+            current: RwLock::new(Arc::new(certified_key)),
+            modified: RwLock::new(modified),
+        })
+    }
+
+    fn start_watcher(self: Arc<Self>, interval: Duration) {
+        actix_web::rt::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let modified = modified_times(&self.cert_path, &self.key_path);
+                if modified == *self.modified.read() {
+                    continue;
+                }
+                // 记录修改时间，证书和私钥分两次写入时，下一次检查会再加载一次
+                *self.modified.write() = modified;
+                match load_certified_key(&self.cert_path, &self.key_path, &self.provider) {
+                    Ok(certified_key) => {
+                        *self.current.write() = Arc::new(certified_key);
+                        info!("Reloaded TLS certificate {}", self.cert_path.display());
+                    }
+                    Err(e) => error!("Failed to reload TLS certificate: {:#}", e),
+                }
+            }
+        });
+    }
+}
+
+impl fmt::Debug for FileCertResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileCertResolver")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .finish()
+    }
+}
+
+impl ResolvesServerCert for FileCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().clone())
+    }
+}
+
+fn modified_times(cert_path: &Path, key_path: &Path) -> Option<(SystemTime, SystemTime)> {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    Some((modified(cert_path)?, modified(key_path)?))
+}
+
+fn load_certified_key(
+    cert_path: &Path,
+    key_path: &Path,
+    provider: &CryptoProvider,
+) -> anyhow::Result<CertifiedKey> {
+    let cert_file =
+        File::open(cert_path).with_context(|| format!("failed to open {}", cert_path.display()))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(cert_file))
+        .collect::<Result<Vec<CertificateDer<'static>>, _>>()
+        .with_context(|| format!("failed to parse {}", cert_path.display()))?;
+    if certs.is_empty() {
+        return Err(anyhow!("no certificate found in {}", cert_path.display()));
+    }
+
+    let key_file =
+        File::open(key_path).with_context(|| format!("failed to open {}", key_path.display()))?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(key_file))
+        .with_context(|| format!("failed to parse {}", key_path.display()))?
+        .ok_or_else(|| anyhow!("no private key found in {}", key_path.display()))?;
+    let signing_key = provider
+        .key_provider
+        .load_private_key(key)
+        .with_context(|| format!("unsupported private key in {}", key_path.display()))?;
+    Ok(CertifiedKey::new(certs, signing_key))
+}
+
+/// HTTPS 对外端口，取第一个 TCP 监听地址的端口
+pub fn https_port(addresses: &[ListenAddress]) -> u16 {
+    addresses
+        .iter()
+        .find_map(|address| match address {
+            ListenAddress::Tcp(address) => address.rsplit_once(':')?.1.parse().ok(),
+            ListenAddress::Unix(_) => None,
+        })
+        .unwrap_or(DEFAULT_HTTPS_PORT)
+}
+
+#[derive(Clone, Copy)]
+struct HttpsPort(u16);
+
+/// 在 HTTP 端口上把所有请求重定向到 HTTPS，返回的 handle 用于随主服务一起停止
+pub fn start_http_redirect(
+    addresses: &[ListenAddress],
+    https_port: u16,
+) -> std::io::Result<ServerHandle> {
+    let mut server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(HttpsPort(https_port)))
+            .default_service(web::to(redirect_to_https))
+    })
+    .workers(1);
+    for address in addresses {
+        if let ListenAddress::Tcp(address) = address {
+            info!("Redirecting HTTP on {} to HTTPS", address);
+            server = server.bind(address)?;
+        }
+    }
+    let server = server.run();
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+    Ok(handle)
+}
+
+/// 使用 308，POST 等请求重定向后保持原方法
+async fn redirect_to_https(req: HttpRequest, https_port: web::Data<HttpsPort>) -> HttpResponse {
+    let Some(host) = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(strip_port)
+    else {
+        return HttpResponse::BadRequest().body("Missing Host header");
+    };
+    let authority = match https_port.0 {
+        DEFAULT_HTTPS_PORT => host.to_string(),
+        port => format!("{}:{}", host, port),
+    };
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    HttpResponse::PermanentRedirect()
+        .insert_header((header::LOCATION, format!("https://{}{}", authority, path)))
+        .finish()
+}
+
+/// `example.com:80` -> `example.com`，`[::1]:80` -> `[::1]`
+fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        return match host.find(']') {
+            Some(end) => &host[..=end],
+            None => host,
+        };
+    }
+    match host.rsplit_once(':') {
+        Some((name, _)) => name,
+        None => host,
+    }
+}
@@ -0,0 +1,332 @@
+use crate::auth::{current_claims, ErrorResponse};
+use crate::consts;
+use crate::AppState;
+use actix_multipart::{Field, Multipart};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse};
+use application::command::library::{AddLibraryFilesCmd, FileTypeDetector};
+use application::context::AppContext;
+use domain::library::{Library, LibraryRepository, ScanStatus};
+use domain::user::UserRepository;
+use domain::value::{FileType, LibraryId};
+use futures::TryStreamExt;
+use infra::config::UploadConfig;
+use infra::file_type_detector::DefaultFileTypeDetector;
+use infra::repository::postgres::command::library::LibraryRepositoryImpl;
+use infra::repository::postgres::command::user::UserRepositoryImpl;
+use infra::storage::local::file_meta;
+use log::{info, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// 注册上传原生 API 路由（需挂在 JWT 验证的 scope 下）
+pub fn configure_service(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope(&format!("{}/uploads", consts::URL_PATH_NATIVE_API))
+            .route("", web::post().to(upload_files)),
+    );
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedFileView {
+    pub name: String,
+    /// 相对于库根目录的路径，失败时为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadView {
+    pub files: Vec<UploadedFileView>,
+    /// 已写入但未能立即入库，会在下一次扫描时入库
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+fn error_response(status: StatusCode, error: &str) -> HttpResponse {
+    HttpResponse::build(status).json(ErrorResponse {
+        error: error.to_string(),
+    })
+}
+
+/// 上传音频和封面到上传库：/api/uploads（multipart，文件名可带子目录）。
+/// 文件写入 `<库根目录>/<upload.directory>/<用户名>/` 下，已存在的文件不会被覆盖
+pub async fn upload_files(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    mut payload: Multipart,
+) -> HttpResponse {
+    let claims = match current_claims(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+    let cfg = state.app_cfg.upload();
+    let library_id = match cfg.library_id {
+        Some(library_id) if cfg.enabled => LibraryId::from(library_id),
+        _ => return error_response(StatusCode::NOT_FOUND, "Upload is disabled"),
+    };
+    match UserRepositoryImpl::new(state.db.clone())
+        .find_by_username(&claims.user_name)
+        .await
+    {
+        Ok(Some(user)) if user.is_admin || user.roles.upload => {}
+        Ok(Some(_)) => return error_response(StatusCode::FORBIDDEN, "Upload is not allowed"),
+        Ok(None) => return error_response(StatusCode::UNAUTHORIZED, "User not found"),
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+    let library = match upload_library(&state, &library_id).await {
+        Ok(library) => library,
+        Err(resp) => return resp,
+    };
+    let library_root = PathBuf::from(&library.path.path);
+    let user_dir = match sanitize_component(&claims.user_name) {
+        Some(name) => library_root.join(&cfg.directory).join(name),
+        None => return error_response(StatusCode::BAD_REQUEST, "Invalid user name"),
+    };
+
+    let detector = DefaultFileTypeDetector::new();
+    let mut views = Vec::new();
+    let mut files = Vec::new();
+    loop {
+        let mut field = match payload.try_next().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let Some(name) = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let mut view = UploadedFileView {
+            name: name.clone(),
+            path: None,
+            size: 0,
+            error: None,
+        };
+        match save_field(&mut field, &name, &user_dir, &cfg, &detector).await {
+            Ok((target, size)) => {
+                view.size = size;
+                view.path = target
+                    .strip_prefix(&library_root)
+                    .ok()
+                    .map(|p| p.to_string_lossy().to_string());
+                match tokio::fs::metadata(&target).await {
+                    Ok(meta) => files.push(file_meta(&target, &meta)),
+                    Err(e) => warn!("Failed to stat uploaded file {}: {}", target.display(), e),
+                }
+            }
+            Err(e) => {
+                // 丢弃剩余内容，继续处理下一个文件
+                while let Ok(Some(_)) = field.try_next().await {}
+                view.error = Some(e);
+            }
+        }
+        views.push(view);
+    }
+
+    let mut warning = None;
+    if !files.is_empty() {
+        info!(
+            "User {} uploaded {} file(s) to library {}",
+            claims.user_name,
+            files.len(),
+            library_id
+        );
+        let cmd = AddLibraryFilesCmd { library_id, files };
+        if let Err(e) = crate::library_command_service(&state)
+            .add_files(&AppContext::new(), cmd)
+            .await
+        {
+            warn!("Failed to add uploaded files: {}", e);
+            warning = Some(format!(
+                "Files were saved but will be indexed by the next scan: {}",
+                e
+            ));
+        }
+    }
+    HttpResponse::Ok().json(UploadView {
+        files: views,
+        warning,
+    })
+}
+
+/// 上传库必须是已启用的本地库，扫描期间不接受上传
+async fn upload_library(state: &AppState, library_id: &LibraryId) -> Result<Library, HttpResponse> {
+    let library = LibraryRepositoryImpl::new(state.db.clone())
+        .find_all()
+        .await
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?
+        .into_iter()
+        .find(|library| &library.id == library_id)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Upload library does not exist"))?;
+    if !library.path.is_local() || !library.enabled {
+        return Err(error_response(
+            StatusCode::NOT_FOUND,
+            "Upload library is not an enabled local library",
+        ));
+    }
+    if library.scan_status == ScanStatus::Scanning {
+        return Err(error_response(
+            StatusCode::CONFLICT,
+            "Library is being scanned, try again later",
+        ));
+    }
+    Ok(library)
+}
+
+/// 先写入同目录下的隐藏临时文件，完整后再链接到目标路径，扫描不会读到写了一半的文件。
+/// 硬链接在目标已存在时失败，同时上传同名文件时只有一个成功，已有的歌曲不会被覆盖
+async fn save_field(
+    field: &mut Field,
+    name: &str,
+    user_dir: &Path,
+    cfg: &UploadConfig,
+    detector: &DefaultFileTypeDetector,
+) -> Result<(PathBuf, u64), String> {
+    let relative = sanitize_relative_path(name).ok_or("Invalid file name")?;
+    let suffix = relative
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    if !matches!(detector.detect(suffix), FileType::Audio | FileType::Image) {
+        return Err("Only audio and image files can be uploaded".to_string());
+    }
+    let target = user_dir.join(&relative);
+    if tokio::fs::try_exists(&target).await.unwrap_or(true) {
+        return Err("File already exists".to_string());
+    }
+    let dir = target.parent().unwrap_or(user_dir);
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // 每次上传使用不同的临时文件，并发上传不会写到同一个文件里
+    let part = dir.join(format!(
+        ".{}.{:016x}.part",
+        file_name,
+        rand::random::<u64>()
+    ));
+    let file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&part)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result = match write_part(field, file, cfg.max_file_size).await {
+        Ok(size) => match tokio::fs::hard_link(&part, &target).await {
+            Ok(()) => Ok(size),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                Err("File already exists".to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        },
+        Err(e) => Err(e),
+    };
+    // 成功时临时文件是目标文件的另一个链接，同样需要删除
+    let _ = tokio::fs::remove_file(&part).await;
+    result.map(|size| (target, size))
+}
+
+async fn write_part(
+    field: &mut Field,
+    mut file: tokio::fs::File,
+    max_size: u64,
+) -> Result<u64, String> {
+    let mut size = 0u64;
+    while let Some(chunk) = field.try_next().await.map_err(|e| e.to_string())? {
+        size += chunk.len() as u64;
+        if size > max_size {
+            return Err(format!("File exceeds {} bytes", max_size));
+        }
+        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+    }
+    file.flush().await.map_err(|e| e.to_string())?;
+    Ok(size)
+}
+
+/// 浏览器上传文件夹时文件名带相对路径，逐级检查，拒绝 `..`、隐藏文件和盘符；
+/// 开头的分隔符被忽略，绝对路径也写在上传目录中
+fn sanitize_relative_path(name: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in name.split(['/', '\\']).filter(|c| !c.is_empty()) {
+        path.push(sanitize_component(component)?);
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+fn sanitize_component(component: &str) -> Option<&str> {
+    let valid = !component.starts_with('.')
+        && !component.contains(':')
+        && !component.chars().any(char::is_control);
+    valid.then_some(component)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_folder_structure() {
+        assert_eq!(
+            sanitize_relative_path("Album/CD1/01 Song.flac"),
+            Some(PathBuf::from("Album/CD1/01 Song.flac"))
+        );
+        assert_eq!(
+            sanitize_relative_path("Album\\01 Song.flac"),
+            Some(PathBuf::from("Album/01 Song.flac"))
+        );
+        // 空的路径段被忽略
+        assert_eq!(
+            sanitize_relative_path("Album//01 Song.flac/"),
+            Some(PathBuf::from("Album/01 Song.flac"))
+        );
+    }
+
+    #[test]
+    fn absolute_paths_stay_inside_the_upload_directory() {
+        assert_eq!(
+            sanitize_relative_path("/etc/passwd"),
+            Some(PathBuf::from("etc/passwd"))
+        );
+        assert_eq!(
+            sanitize_relative_path("\\\\server\\share\\a.mp3"),
+            Some(PathBuf::from("server/share/a.mp3"))
+        );
+        assert_eq!(sanitize_relative_path("C:\\Windows\\a.mp3"), None);
+    }
+
+    #[test]
+    fn rejects_parent_and_hidden_components() {
+        assert_eq!(sanitize_relative_path("../a.mp3"), None);
+        assert_eq!(sanitize_relative_path("Album/../../a.mp3"), None);
+        assert_eq!(sanitize_relative_path("Album\\..\\a.mp3"), None);
+        assert_eq!(sanitize_relative_path("./a.mp3"), None);
+        assert_eq!(sanitize_relative_path("Album/.hidden.mp3"), None);
+    }
+
+    #[test]
+    fn rejects_control_characters_and_empty_names() {
+        assert_eq!(sanitize_relative_path("a\0.mp3"), None);
+        assert_eq!(sanitize_relative_path("Album/a\n.mp3"), None);
+        assert_eq!(sanitize_relative_path(""), None);
+        assert_eq!(sanitize_relative_path("//\\"), None);
+
+        assert_eq!(sanitize_component("Song.mp3"), Some("Song.mp3"));
+        assert_eq!(sanitize_component(".."), None);
+        assert_eq!(sanitize_component("a:b"), None);
+        assert_eq!(sanitize_component("a\0b"), None);
+    }
+}
@@ -0,0 +1,282 @@
+//! 集成测试工具：启动临时 Postgres，执行迁移，扫描生成的音频文件，
+//! 然后在进程内调用 Subsonic 接口
+use actix_web::dev::ServiceResponse;
+use actix_web::{test, web, App};
+use infra::config::{AppConfigImpl, ConfigSources};
+use server::cli::{self, Command};
+use server::consts;
+use server::AppState;
+use std::path::{Path, PathBuf};
+use std::process::Command as Process;
+use tempfile::TempDir;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::ContainerAsync;
+
+pub const USERNAME: &str = "admin";
+pub const PASSWORD: &str = "fixture-password";
+pub const API_VERSION: &str = "1.16.1";
+
+/// 测试库中的一首歌，文件由 ffmpeg 生成，标签写入文件本身
+pub struct FixtureTrack {
+    /// 相对于库根目录的路径，扩展名决定编码
+    pub path: &'static str,
+    pub title: &'static str,
+    pub artist: &'static str,
+    pub album: &'static str,
+    pub genre: &'static str,
+    pub year: u32,
+    pub track: u32,
+}
+
+/// 两位艺术家、三张专辑，包含 mp3 和 flac
+pub const FIXTURE_LIBRARY: &[FixtureTrack] = &[
+    FixtureTrack {
+        path: "Aurora Lane/Morning Tides/01 Sunrise Over Water.mp3",
+        title: "Sunrise Over Water",
+        artist: "Aurora Lane",
+        album: "Morning Tides",
+        genre: "Ambient",
+        year: 2019,
+        track: 1,
+    },
+    FixtureTrack {
+        path: "Aurora Lane/Morning Tides/02 Harbour Lights.mp3",
+        title: "Harbour Lights",
+        artist: "Aurora Lane",
+        album: "Morning Tides",
+        genre: "Ambient",
+        year: 2019,
+        track: 2,
+    },
+    FixtureTrack {
+        path: "Aurora Lane/Evening Static/01 Low Frequency.flac",
+        title: "Low Frequency",
+        artist: "Aurora Lane",
+        album: "Evening Static",
+        genre: "Electronic",
+        year: 2021,
+        track: 1,
+    },
+    FixtureTrack {
+        path: "Copper Fields/Dust Roads/01 Dry Creek.mp3",
+        title: "Dry Creek",
+        artist: "Copper Fields",
+        album: "Dust Roads",
+        genre: "Folk",
+        year: 2015,
+        track: 1,
+    },
+    FixtureTrack {
+        path: "Copper Fields/Dust Roads/02 Sunrise Rail.flac",
+        title: "Sunrise Rail",
+        artist: "Copper Fields",
+        album: "Dust Roads",
+        genre: "Folk",
+        year: 2015,
+        track: 2,
+    },
+];
+
+/// 已完成扫描的服务端状态。容器和临时目录随它一起释放
+pub struct TestServer {
+    pub state: web::Data<AppState>,
+    pub library: PathBuf,
+    _postgres: ContainerAsync<Postgres>,
+    _dir: TempDir,
+}
+
+impl TestServer {
+    /// 生成测试库、启动数据库并完成一次完整扫描。需要 Docker 和 ffmpeg
+    pub async fn start() -> TestServer {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let library = dir.path().join("library");
+        generate_fixture_library(&library);
+
+        let postgres = Postgres::default()
+            .start()
+            .await
+            .expect("Failed to start Postgres, is Docker running?");
+        let host = postgres.get_host().await.expect("Failed to get host");
+        let port = postgres
+            .get_host_port_ipv4(5432)
+            .await
+            .expect("Failed to get Postgres port");
+        let database_url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+        let cfg = load_config(dir.path(), &library, &database_url);
+
+        let db = AppState::init_db(&database_url).await;
+        cli::migrate(&db).await.expect("Failed to run migrations");
+        let state = AppState::new(db.clone(), cfg.clone()).await;
+        server::init_music_folders(&state).await;
+        cli::run(
+            Command::UserAdd {
+                username: USERNAME.to_string(),
+                email: "admin@example.com".to_string(),
+                admin: true,
+                password: Some(PASSWORD.to_string()),
+            },
+            state,
+        )
+        .await
+        .expect("Failed to create user");
+        // 与 `rhythm scan --full` 相同，返回时解析和入库都已完成，连接池也已关闭
+        cli::run(
+            Command::Scan {
+                library_id: None,
+                full: true,
+                force: false,
+            },
+            AppState::new(db.clone(), cfg.clone()).await,
+        )
+        .await
+        .expect("Failed to scan fixture library");
+
+        let db = AppState::init_db(&database_url).await;
+        let mut state = AppState::new(db, cfg).await;
+        server::setup_event_bus(&mut state).await;
+        TestServer {
+            state: web::Data::new(state),
+            library,
+            _postgres: postgres,
+            _dir: dir,
+        }
+    }
+
+    /// 以测试用户调用 Subsonic 接口，使用 token 认证
+    pub async fn call(&self, endpoint: &str, params: &[(&str, &str)]) -> ServiceResponse {
+        self.call_as(USERNAME, PASSWORD, endpoint, params).await
+    }
+
+    pub async fn call_as(
+        &self,
+        username: &str,
+        password: &str,
+        endpoint: &str,
+        params: &[(&str, &str)],
+    ) -> ServiceResponse {
+        let salt = "fixture";
+        let token = format!("{:x}", md5::compute(format!("{}{}", password, salt)));
+        let mut query = vec![
+            ("u", username),
+            ("t", token.as_str()),
+            ("s", salt),
+            ("v", API_VERSION),
+            ("c", "rhythm-tests"),
+        ];
+        query.extend_from_slice(params);
+        let query = query
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let app = test::init_service(
+            App::new()
+                .app_data(self.state.clone())
+                .configure(server::subsonic::configure_service),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "{}/{}?{}",
+                consts::URL_PATH_SUBSONIC_API,
+                endpoint,
+                query
+            ))
+            .to_request();
+        // 中间件返回的错误（如认证失败）在真实服务中由 actix 转换为响应
+        match test::try_call_service(&app, req).await {
+            Ok(rsp) => rsp.map_into_boxed_body(),
+            Err(e) => ServiceResponse::new(
+                test::TestRequest::default().to_http_request(),
+                e.error_response(),
+            ),
+        }
+    }
+
+    /// 以 JSON 格式调用并返回 `subsonic-response` 的内容
+    pub async fn json(&self, endpoint: &str, params: &[(&str, &str)]) -> serde_json::Value {
+        let mut params = params.to_vec();
+        params.push(("f", "json"));
+        let rsp = self.call(endpoint, &params).await;
+        let body: serde_json::Value = test::read_body_json(rsp).await;
+        body["subsonic-response"].clone()
+    }
+
+    /// 与 `json` 相同，但要求响应状态为 ok
+    pub async fn ok(&self, endpoint: &str, params: &[(&str, &str)]) -> serde_json::Value {
+        let rsp = self.json(endpoint, params).await;
+        assert_eq!(rsp["status"], "ok", "{} failed: {}", endpoint, rsp);
+        rsp
+    }
+}
+
+fn load_config(dir: &Path, library: &Path, database_url: &str) -> AppConfigImpl {
+    let file = dir.join("config.toml");
+    let content = format!(
+        r#"
+jwt_secret_key = "integration-test-jwt-secret"
+password_encryption_key = "integration-test-encryption-key"
+database_url = "{database_url}"
+
+[[music_folders]]
+name = "Fixtures"
+protocol = "local"
+path = "{library}"
+
+[cache]
+data_dir = "{cache}"
+"#,
+        database_url = database_url,
+        library = library.display(),
+        cache = dir.join("cache").display(),
+    );
+    std::fs::write(&file, content).expect("Failed to write config");
+    AppConfigImpl::load_from(ConfigSources {
+        file: file.to_string_lossy().to_string(),
+        overrides: Vec::new(),
+    })
+    .expect("Invalid test config")
+}
+
+/// 用 ffmpeg 生成几秒的正弦波并写入标签，每首歌频率不同，避免内容重复
+pub fn generate_fixture_library(root: &Path) {
+    for (i, track) in FIXTURE_LIBRARY.iter().enumerate() {
+        let path = root.join(track.path);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("Failed to create fixture dir");
+        let codec = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("flac") => "flac",
+            _ => "libmp3lame",
+        };
+        let status = Process::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error", "-y", "-f", "lavfi"])
+            .arg("-i")
+            .arg(format!("sine=frequency={}:duration=3", 220 + i * 110))
+            .args(["-metadata", &format!("title={}", track.title)])
+            .args(["-metadata", &format!("artist={}", track.artist)])
+            .args(["-metadata", &format!("album_artist={}", track.artist)])
+            .args(["-metadata", &format!("album={}", track.album)])
+            .args(["-metadata", &format!("genre={}", track.genre)])
+            .args(["-metadata", &format!("date={}", track.year)])
+            .args(["-metadata", &format!("track={}", track.track)])
+            .args(["-codec:a", codec])
+            .arg(&path)
+            .status()
+            .expect("ffmpeg is required to generate the fixture library");
+        assert!(status.success(), "ffmpeg failed for {}", track.path);
+    }
+}
+
+/// 查询参数的百分号编码，测试数据只包含 ASCII
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,169 @@
+//! Subsonic 接口端到端测试。需要 Docker 和 ffmpeg，默认忽略，
+//! 在 src/crates/tests 下运行 `cargo test -- --ignored`
+use actix_web::http::StatusCode;
+use actix_web::test;
+use serde_json::Value;
+use tests::{TestServer, FIXTURE_LIBRARY, PASSWORD, USERNAME};
+
+fn names(items: &Value, key: &str) -> Vec<String> {
+    let mut names: Vec<String> = items
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item[key].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[actix_web::test]
+#[ignore = "requires Docker and ffmpeg"]
+async fn authenticates_with_token_and_rejects_wrong_password() {
+    let server = TestServer::start().await;
+    server.ok("ping", &[]).await;
+
+    let rsp = server
+        .call_as(USERNAME, "wrong-password", "ping", &[("f", "json")])
+        .await;
+    assert_eq!(rsp.status(), StatusCode::UNAUTHORIZED);
+
+    let rsp = server.call_as(USERNAME, PASSWORD, "ping", &[]).await;
+    assert_eq!(rsp.status(), StatusCode::OK);
+    let body = test::read_body(rsp).await;
+    assert!(String::from_utf8_lossy(&body).contains("status=\"ok\""));
+}
+
+#[actix_web::test]
+#[ignore = "requires Docker and ffmpeg"]
+async fn scanned_fixtures_are_browsable() {
+    let server = TestServer::start().await;
+
+    let rsp = server.ok("getArtists", &[]).await;
+    let artists: Vec<Value> = rsp["artists"]["index"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|index| index["artist"].as_array().unwrap().clone())
+        .collect();
+    assert_eq!(
+        names(&Value::Array(artists), "name"),
+        ["Aurora Lane", "Copper Fields"]
+    );
+
+    let rsp = server
+        .ok(
+            "getAlbumList2",
+            &[("type", "alphabeticalByName"), ("size", "10")],
+        )
+        .await;
+    let albums = &rsp["albumList2"]["album"];
+    assert_eq!(
+        names(albums, "name"),
+        ["Dust Roads", "Evening Static", "Morning Tides"]
+    );
+
+    let morning_tides = albums
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|album| album["name"] == "Morning Tides")
+        .unwrap();
+    assert_eq!(morning_tides["artist"], "Aurora Lane");
+    assert_eq!(morning_tides["songCount"], 2);
+
+    let id = morning_tides["id"].as_str().unwrap();
+    let rsp = server.ok("getAlbum", &[("id", id)]).await;
+    let songs = rsp["album"]["song"].as_array().unwrap();
+    let titles: Vec<&str> = songs
+        .iter()
+        .map(|song| song["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Sunrise Over Water", "Harbour Lights"]);
+    assert_eq!(songs[0]["track"], 1);
+    assert_eq!(songs[0]["year"], 2019);
+    assert_eq!(songs[0]["genre"], "Ambient");
+
+    let rsp = server
+        .ok("getAlbumList2", &[("type", "byGenre"), ("genre", "Folk")])
+        .await;
+    assert_eq!(names(&rsp["albumList2"]["album"], "name"), ["Dust Roads"]);
+}
+
+#[actix_web::test]
+#[ignore = "requires Docker and ffmpeg"]
+async fn search3_finds_songs_albums_and_artists() {
+    let server = TestServer::start().await;
+
+    let rsp = server.ok("search3", &[("query", "Sunrise")]).await;
+    assert_eq!(
+        names(&rsp["searchResult3"]["song"], "title"),
+        ["Sunrise Over Water", "Sunrise Rail"]
+    );
+
+    let rsp = server.ok("search3", &[("query", "Copper")]).await;
+    let result = &rsp["searchResult3"];
+    assert_eq!(names(&result["artist"], "name"), ["Copper Fields"]);
+    assert_eq!(names(&result["album"], "name"), ["Dust Roads"]);
+    assert_eq!(
+        names(&result["song"], "title"),
+        ["Dry Creek", "Sunrise Rail"]
+    );
+
+    let rsp = server.ok("search3", &[("query", "Evening")]).await;
+    assert_eq!(
+        names(&rsp["searchResult3"]["album"], "name"),
+        ["Evening Static"]
+    );
+}
+
+#[actix_web::test]
+#[ignore = "requires Docker and ffmpeg"]
+async fn streams_original_files() {
+    let server = TestServer::start().await;
+
+    for fixture in FIXTURE_LIBRARY {
+        let rsp = server.ok("search3", &[("query", fixture.title)]).await;
+        let song = rsp["searchResult3"]["song"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|song| song["title"] == fixture.title)
+            .unwrap()
+            .clone();
+        let id = song["id"].as_str().unwrap();
+
+        let rsp = server
+            .call("stream", &[("id", id), ("format", "raw")])
+            .await;
+        assert_eq!(rsp.status(), StatusCode::OK, "{}", fixture.path);
+        let body = test::read_body(rsp).await;
+        let original = std::fs::read(server.library.join(fixture.path)).unwrap();
+        assert_eq!(body.len(), original.len(), "{}", fixture.path);
+        assert!(body == original, "{} differs from the file", fixture.path);
+    }
+}
+
+#[actix_web::test]
+#[ignore = "requires Docker and ffmpeg"]
+async fn invalid_requests_return_subsonic_error_codes() {
+    let server = TestServer::start().await;
+
+    let rsp = server.json("getAlbum", &[("id", "999999")]).await;
+    assert_eq!(rsp["status"], "failed");
+    assert_eq!(rsp["error"]["code"], 70);
+
+    let rsp = server.json("getAlbumList2", &[("type", "bogus")]).await;
+    assert_eq!(rsp["status"], "failed");
+    assert_eq!(rsp["error"]["code"], 0);
+
+    let rsp = server.json("getAlbumList2", &[]).await;
+    assert_eq!(rsp["error"]["code"], 10);
+
+    let rsp = server
+        .json("search3", &[("query", "x"), ("songCount", "-1")])
+        .await;
+    assert_eq!(rsp["error"]["code"], 0);
+}
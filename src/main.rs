@@ -1,20 +1,69 @@
 use actix_web::middleware::Logger;
 use actix_web::{middleware::from_fn, web, App, HttpServer};
 
-use infra::config::AppConfigImpl;
+use infra::config::{AppConfigImpl, ConfigSources, ListenAddress};
 use log4rs::{
+    append::console::{ConsoleAppender, Target},
     append::file::FileAppender,
     config::{Appender, Config, Root},
     encode::pattern::PatternEncoder,
 };
 
-use server::middleware::{jwt_verify, other};
+use sea_orm::DatabaseConnection;
+use server::cli::{self, Command};
+use server::middleware::{jwt_verify, other, query_budget, security_headers};
+use std::time::Duration;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // 配置日志同时输出到控制台和文件
-    let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+    let (command, config_args) = match Command::parse(std::env::args().skip(1).collect()) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if command == Command::Help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    let cfg = match ConfigSources::from_args(config_args).and_then(AppConfigImpl::load_from) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
+    // 管理命令的结果输出到标准输出（export 可能输出 JSON），日志改写到标准错误
+    let console_target = match command {
+        Command::Serve => Target::Stdout,
+        _ => Target::Stderr,
+    };
+    init_logging(&cfg, console_target);
+    let db = server::AppState::init_db(&cfg.database_url()).await;
+
+    match command {
+        Command::Serve => serve(db, cfg).await,
+        // 迁移前表结构可能不完整，只使用数据库连接
+        Command::Migrate => exit_on_error(cli::migrate(&db).await),
+        command => {
+            let app_state = server::AppState::new(db, cfg).await;
+            exit_on_error(cli::run(command, app_state).await)
+        }
+    }
+}
+
+fn exit_on_error<E: std::fmt::Display>(result: Result<(), E>) -> std::io::Result<()> {
+    if let Err(e) = result {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 配置日志同时输出到控制台和文件
+fn init_logging(cfg: &AppConfigImpl, console_target: Target) {
     // 创建文件 appender
     let file_appender = FileAppender::builder()
         .encoder(Box::new(PatternEncoder::new(
@@ -27,30 +76,51 @@ async fn main() -> std::io::Result<()> {
     let config = Config::builder()
         .appender(Appender::builder().build("file", Box::new(file_appender)))
         .appender(Appender::builder().build(
-            "stdout",
-            Box::new(log4rs::append::console::ConsoleAppender::builder().build()),
+            "console",
+            Box::new(ConsoleAppender::builder().target(console_target).build()),
         ))
         .build(
             Root::builder()
                 .appender("file")
-                .appender("stdout")
-                .build(log_level.parse().unwrap_or(log::LevelFilter::Info)),
+                .appender("console")
+                .build(log::LevelFilter::Trace),
         )
         .unwrap();
 
     log4rs::init_config(config).unwrap();
-    let cfg = AppConfigImpl::load().unwrap();
+    // 实际级别由全局最大级别控制，重新加载配置时可调整
+    log::set_max_level(cfg.log_level());
+}
+
+async fn serve(db: DatabaseConnection, cfg: AppConfigImpl) -> std::io::Result<()> {
     let server_cfg = cfg.server();
     let ui_server_cfg = server_cfg.clone();
-    let db = server::AppState::init_db(&cfg.database_url()).await;
-
-    let mut app_state = server::AppState::new(db.clone(), cfg).await;
+    let cors_cfg = cfg.cors();
+    let graphql_schema = server::graphql::build_schema(&cfg.graphql());
+    let tls_cfg = cfg.tls();
+    let tls_server_config = match server::tls::server_config(&tls_cfg) {
+        Ok(config) => config,
+        Err(e) => {
+            log::error!("Failed to set up TLS: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut app_state = server::AppState::new(db, cfg).await;
     server::init_admin_user(&app_state).await;
     server::init_music_folders(&app_state).await;
     server::setup_event_bus(&mut app_state).await;
     let app_state = web::Data::new(app_state);
-    HttpServer::new(move || {
+    let shutdown_state = app_state.clone();
+    server::scan::resume_interrupted_scans(&app_state).await;
+    server::scan::start_scan_scheduler(app_state.clone());
+    server::admin::trash::start_trash_purge(app_state.clone());
+    server::playlists::start_deleted_playlist_purge(app_state.clone());
+    server::stats::start_playback_history_prune(app_state.clone());
+    server::dlna::ssdp::start_ssdp(app_state.clone());
+    server::admin::config::start_reload_on_sighup(app_state.clone());
+    let mut http_server = HttpServer::new(move || {
         let ui_cfg = ui_server_cfg.clone();
+        let graphql_schema = graphql_schema.clone();
         App::new()
             .app_data(app_state.clone())
             .wrap(Logger::default())
@@ -58,20 +128,91 @@ async fn main() -> std::io::Result<()> {
             // UI 静态文件服务（无需认证）
             .configure(move |cfg| server::resources::configure_ui_service(cfg, &ui_cfg))
             // auth API 不需要 JWT 验证
-            .service(server::auth::configure_service())
+            .configure(server::auth::configure_service)
             // Subsonic API 使用自己的认证方式，不需要 JWT
             .configure(server::subsonic::configure_service)
+            // DLNA 设备无法认证，只对局域网开放
+            .configure(server::dlna::configure_service)
+            // OpenAPI 文档公开，便于生成客户端
+            .configure(server::api_v1::configure_public_service)
             // 需要 JWT 验证的路由
             .service(
                 web::scope("")
                     .configure(server::resources::configure_service)
+                    .configure(server::admin::configure_service)
+                    .configure(server::api_v1::configure_service)
+                    .configure(move |cfg| server::graphql::configure_service(cfg, graphql_schema))
+                    .configure(server::stats::configure_service)
+                    .configure(server::feeds::configure_service)
+                    .configure(server::annotations::configure_service)
+                    .configure(server::notes::configure_service)
+                    .configure(server::app_passwords::configure_service)
+                    .configure(server::listening_sessions::configure_service)
+                    .configure(server::players::configure_service)
+                    .configure(server::playlists::configure_service)
+                    .configure(server::events::configure_service)
+                    .configure(server::scan::configure_service)
+                    .configure(server::upload::configure_service)
+                    .configure(server::signed_urls::configure_service)
                     .wrap(jwt_verify::JwtVerifier {})
                     .wrap(from_fn(other::auth_header_mapper))
                     .wrap(from_fn(other::client_unique_id)),
             )
-            .wrap(other::cors())
+            .wrap(from_fn(query_budget::query_budget))
+            .wrap(from_fn(security_headers::security_headers))
+            .wrap(other::cors(&cors_cfg))
     })
-    .bind((server_cfg.host.as_str(), server_cfg.port))?
-    .run()
-    .await
+    .shutdown_timeout(server_cfg.shutdown_timeout_secs);
+    for address in server_cfg.listen_addresses() {
+        log::info!("Listening on {}", address);
+        http_server = match address {
+            ListenAddress::Tcp(address) => match &tls_server_config {
+                Some(config) => http_server.bind_rustls_0_23(address, config.clone())?,
+                None => http_server.bind(address)?,
+            },
+            #[cfg(unix)]
+            ListenAddress::Unix(path) => {
+                remove_stale_socket(&path)?;
+                http_server.bind_uds(path)?
+            }
+            #[cfg(not(unix))]
+            ListenAddress::Unix(path) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    format!("unix sockets are not supported: {}", path.display()),
+                ));
+            }
+        };
+    }
+    let redirect_server = match &tls_server_config {
+        Some(_) if !tls_cfg.redirect_http_listen.is_empty() => {
+            Some(server::tls::start_http_redirect(
+                &tls_cfg.redirect_http_listen,
+                server::tls::https_port(&server_cfg.listen_addresses()),
+            )?)
+        }
+        _ => None,
+    };
+    let result = http_server.run().await;
+    if let Some(redirect_server) = redirect_server {
+        redirect_server.stop(true).await;
+    }
+    // HTTP 服务停止后（已等待进行中的请求），再处理后台写入
+    server::shutdown(
+        &shutdown_state,
+        Duration::from_secs(server_cfg.shutdown_timeout_secs),
+    )
+    .await;
+    result
+}
+
+/// 上次未正常退出时遗留的套接字文件会导致绑定失败，只删除套接字，避免误删普通文件
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path),
+        _ => Ok(()),
+    }
 }